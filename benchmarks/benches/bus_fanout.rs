@@ -0,0 +1,30 @@
+//! Bus fan-out throughput
+//!
+//! Publishes 100k raw events to four subscribers and waits until every copy has
+//! been delivered. Target: 100k messages/sec. Run with `cargo bench --bench bus_fanout`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use skelly_jelly_benchmarks::workloads::{fanout_messages, run_bus_fanout, BUS_FANOUT_MESSAGES, BUS_FANOUT_SUBSCRIBERS};
+
+fn bus_fanout(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let messages = fanout_messages(BUS_FANOUT_MESSAGES);
+
+    let mut group = c.benchmark_group("bus_fanout");
+    group.throughput(Throughput::Elements(BUS_FANOUT_MESSAGES as u64));
+    group.sample_size(10);
+    group.bench_function("raw_events", |b| {
+        b.iter_custom(|iterations| {
+            (0..iterations)
+                .map(|_| runtime.block_on(run_bus_fanout(&messages, &BUS_FANOUT_SUBSCRIBERS)).unwrap())
+                .sum()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bus_fanout);
+criterion_main!(benches);
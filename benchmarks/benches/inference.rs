@@ -0,0 +1,32 @@
+//! Batch inference latency
+//!
+//! Runs state detection over batches of 1, 8 and 32 analysis windows. `bench`
+//! reports the median and the p90/p99 of the sampled batch latencies. Needs the
+//! `analysis-engine` feature: `cargo bench --bench inference --features analysis-engine`.
+
+use std::sync::Arc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use skelly_jelly_analysis_engine::{InferenceEngine, StateDetectionEngine};
+use skelly_jelly_benchmarks::workloads::{inference_windows, INFERENCE_BATCH_SIZES};
+
+fn batch_inference(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let engine = InferenceEngine::new(Arc::new(StateDetectionEngine::new()));
+
+    let mut group = c.benchmark_group("batch_inference");
+    group.sample_size(50);
+    for size in INFERENCE_BATCH_SIZES {
+        let windows = inference_windows(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("windows", size), &windows, |b, windows| {
+            b.to_async(&runtime).iter(|| engine.batch_infer(windows));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch_inference);
+criterion_main!(benches);
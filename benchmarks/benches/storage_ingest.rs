@@ -0,0 +1,31 @@
+//! Storage ingest throughput
+//!
+//! Writes 5k mixed capture events as a single batch into a SQLite database,
+//! a new session each time. Target: 5k events/sec. Run with `cargo bench --bench storage_ingest`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use skelly_jelly_benchmarks::workloads::{capture_events, StorageIngest, STORAGE_INGEST_EVENTS};
+
+fn storage_ingest(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let ingest = runtime.block_on(StorageIngest::new()).unwrap();
+    let events = capture_events(STORAGE_INGEST_EVENTS);
+
+    let mut group = c.benchmark_group("storage_ingest");
+    group.throughput(Throughput::Elements(STORAGE_INGEST_EVENTS as u64));
+    group.sample_size(20);
+    group.bench_function("events_batch", |b| {
+        b.iter_custom(|iterations| {
+            (0..iterations)
+                .map(|_| runtime.block_on(ingest.ingest(&events)).unwrap())
+                .sum()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, storage_ingest);
+criterion_main!(benches);
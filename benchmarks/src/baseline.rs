@@ -0,0 +1,341 @@
+//! Stored baselines and regression comparison
+//!
+//! [`read_criterion`] collects the latest result of every benchmark from
+//! criterion's output directory. A [`Baseline`] is those results saved as JSON
+//! under a name, and [`compare`] checks a fresh run against one, flagging any
+//! benchmark whose median time grew by more than the tolerance.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{BenchError, BenchResult};
+use crate::workloads::Target;
+
+/// One benchmark's timings from its latest criterion run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkEstimate {
+    /// Criterion id, `group/function[/parameter]`
+    pub id: String,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub std_dev_ns: f64,
+    /// 90th and 99th percentile of the per-iteration times criterion sampled
+    pub p90_ns: f64,
+    pub p99_ns: f64,
+    /// Elements processed per iteration, when the benchmark declares a throughput
+    pub elements: Option<u64>,
+}
+
+impl BenchmarkEstimate {
+    /// Elements processed per second at the median time
+    pub fn per_second(&self) -> Option<f64> {
+        let elements = self.elements? as f64;
+        (self.median_ns > 0.0).then(|| elements * 1e9 / self.median_ns)
+    }
+}
+
+#[derive(Deserialize)]
+struct CriterionBenchmark {
+    full_id: String,
+    #[serde(default)]
+    throughput: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct PointEstimate {
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct CriterionEstimates {
+    mean: PointEstimate,
+    median: PointEstimate,
+    std_dev: PointEstimate,
+}
+
+#[derive(Deserialize)]
+struct CriterionSample {
+    iters: Vec<f64>,
+    times: Vec<f64>,
+}
+
+/// Latest results of every benchmark under `criterion_dir` (usually `target/criterion`), sorted by id
+pub fn read_criterion(criterion_dir: &Path) -> BenchResult<Vec<BenchmarkEstimate>> {
+    let mut estimates = Vec::new();
+    collect_estimates(criterion_dir, &mut estimates)?;
+    if estimates.is_empty() {
+        return Err(BenchError::NoResults(criterion_dir.to_path_buf()));
+    }
+    estimates.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(estimates)
+}
+
+fn collect_estimates(dir: &Path, estimates: &mut Vec<BenchmarkEstimate>) -> BenchResult<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let latest = dir.join("new");
+    if latest.join("estimates.json").is_file() && latest.join("benchmark.json").is_file() {
+        estimates.push(read_estimate(&latest)?);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        // Criterion keeps earlier runs and named baselines next to `new`
+        if path.is_dir() && !matches!(path.file_name().and_then(|n| n.to_str()), Some("new" | "base" | "change" | "report")) {
+            collect_estimates(&path, estimates)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_estimate(dir: &Path) -> BenchResult<BenchmarkEstimate> {
+    let benchmark: CriterionBenchmark = serde_json::from_slice(&fs::read(dir.join("benchmark.json"))?)?;
+    let estimates: CriterionEstimates = serde_json::from_slice(&fs::read(dir.join("estimates.json"))?)?;
+
+    let mut per_iteration = match fs::read(dir.join("sample.json")) {
+        Ok(bytes) => {
+            let sample: CriterionSample = serde_json::from_slice(&bytes)?;
+            sample.times.iter().zip(&sample.iters).map(|(time, iters)| time / iters).collect()
+        }
+        Err(_) => Vec::new(),
+    };
+    per_iteration.sort_by(f64::total_cmp);
+    let percentile = |p: f64| match per_iteration.len() {
+        0 => estimates.median.point_estimate,
+        n => per_iteration[((n - 1) as f64 * p).round() as usize],
+    };
+
+    Ok(BenchmarkEstimate {
+        id: benchmark.full_id,
+        mean_ns: estimates.mean.point_estimate,
+        median_ns: estimates.median.point_estimate,
+        std_dev_ns: estimates.std_dev.point_estimate,
+        p90_ns: percentile(0.90),
+        p99_ns: percentile(0.99),
+        elements: benchmark.throughput.as_ref().and_then(|t| t["Elements"].as_u64()),
+    })
+}
+
+/// A named set of results to compare later runs against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    pub recorded_at: DateTime<Utc>,
+    pub benchmarks: BTreeMap<String, BenchmarkEstimate>,
+}
+
+impl Baseline {
+    pub fn new(name: impl Into<String>, estimates: Vec<BenchmarkEstimate>) -> Self {
+        Self {
+            name: name.into(),
+            recorded_at: Utc::now(),
+            benchmarks: estimates.into_iter().map(|e| (e.id.clone(), e)).collect(),
+        }
+    }
+
+    /// Where the baseline called `name` lives in `dir`
+    pub fn path(dir: &Path, name: &str) -> PathBuf {
+        dir.join(format!("{}.json", name))
+    }
+
+    /// Load the baseline called `name` from `dir`
+    pub fn load(dir: &Path, name: &str) -> BenchResult<Self> {
+        let path = Self::path(dir, name);
+        let bytes = fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => BenchError::BaselineNotFound { name: name.to_string(), path: path.clone() },
+            _ => e.into(),
+        })?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Save into `dir`, replacing any baseline with the same name; returns the file written
+    pub fn save(&self, dir: &Path) -> BenchResult<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = Self::path(dir, &self.name);
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// How a benchmark moved relative to the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    /// Slower by more than the tolerance
+    Regressed,
+    /// Faster by more than the tolerance
+    Improved,
+    Unchanged,
+    /// In the current run but not the baseline
+    Added,
+    /// In the baseline but not the current run
+    Missing,
+}
+
+/// One benchmark in a [`Comparison`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonEntry {
+    pub id: String,
+    pub baseline_ns: Option<f64>,
+    pub current_ns: Option<f64>,
+    /// Relative change of the median time, e.g. `0.25` for 25% slower
+    pub change: Option<f64>,
+    pub verdict: Verdict,
+}
+
+/// A run compared against a baseline, one entry per benchmark in either
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub baseline: String,
+    pub tolerance: f64,
+    pub entries: Vec<ComparisonEntry>,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries.iter().filter(|e| e.verdict == Verdict::Regressed)
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+}
+
+/// Compare `current` against `baseline` by median time
+///
+/// `tolerance` is the relative slowdown still counted as noise; `0.1` allows 10%.
+pub fn compare(baseline: &Baseline, current: &[BenchmarkEstimate], tolerance: f64) -> Comparison {
+    let current: BTreeMap<&str, &BenchmarkEstimate> = current.iter().map(|e| (e.id.as_str(), e)).collect();
+    let mut ids: Vec<&str> = baseline.benchmarks.keys().map(String::as_str).chain(current.keys().copied()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let entries = ids
+        .into_iter()
+        .map(|id| {
+            let baseline_ns = baseline.benchmarks.get(id).map(|e| e.median_ns);
+            let current_ns = current.get(id).map(|e| e.median_ns);
+            let change = baseline_ns.zip(current_ns).filter(|(base, _)| *base > 0.0).map(|(base, now)| now / base - 1.0);
+            let verdict = match (baseline_ns, current_ns, change) {
+                (None, _, _) => Verdict::Added,
+                (_, None, _) => Verdict::Missing,
+                (_, _, Some(change)) if change > tolerance => Verdict::Regressed,
+                (_, _, Some(change)) if change < -tolerance => Verdict::Improved,
+                _ => Verdict::Unchanged,
+            };
+            ComparisonEntry { id: id.to_string(), baseline_ns, current_ns, change, verdict }
+        })
+        .collect();
+
+    Comparison { baseline: baseline.name.clone(), tolerance, entries }
+}
+
+/// A throughput target checked against a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetCheck {
+    pub benchmark: String,
+    pub min_per_second: f64,
+    /// `None` when the run has no result for the benchmark
+    pub measured_per_second: Option<f64>,
+    pub met: bool,
+}
+
+/// Check each target against `current`; a target without a result counts as not met
+pub fn check_targets(targets: &[Target], current: &[BenchmarkEstimate]) -> Vec<TargetCheck> {
+    targets
+        .iter()
+        .map(|target| {
+            let measured = current.iter().find(|e| e.id == target.benchmark).and_then(BenchmarkEstimate::per_second);
+            TargetCheck {
+                benchmark: target.benchmark.to_string(),
+                min_per_second: target.min_per_second,
+                measured_per_second: measured,
+                met: measured.is_some_and(|m| m >= target.min_per_second),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(id: &str, median_ns: f64, elements: Option<u64>) -> BenchmarkEstimate {
+        BenchmarkEstimate {
+            id: id.to_string(),
+            mean_ns: median_ns,
+            median_ns,
+            std_dev_ns: 0.0,
+            p90_ns: median_ns,
+            p99_ns: median_ns,
+            elements,
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_regressions_beyond_tolerance() {
+        let baseline = Baseline::new(
+            "main",
+            vec![estimate("a", 100.0, None), estimate("b", 100.0, None), estimate("c", 100.0, None), estimate("gone", 1.0, None)],
+        );
+        let current = [estimate("a", 105.0, None), estimate("b", 130.0, None), estimate("c", 70.0, None), estimate("new", 1.0, None)];
+
+        let comparison = compare(&baseline, &current, 0.1);
+        let verdicts: Vec<_> = comparison.entries.iter().map(|e| (e.id.as_str(), e.verdict)).collect();
+        assert_eq!(
+            verdicts,
+            [
+                ("a", Verdict::Unchanged),
+                ("b", Verdict::Regressed),
+                ("c", Verdict::Improved),
+                ("gone", Verdict::Missing),
+                ("new", Verdict::Added),
+            ]
+        );
+        assert!(comparison.has_regressions());
+        assert!((comparison.regressions().next().unwrap().change.unwrap() - 0.3).abs() < 1e-9);
+
+        let targets = [Target { benchmark: "a", min_per_second: 1e9 }, Target { benchmark: "missing", min_per_second: 1.0 }];
+        let checks = check_targets(&targets, &[estimate("a", 100.0, Some(1000))]);
+        assert!(checks[0].met && checks[0].measured_per_second == Some(1e10));
+        assert!(!checks[1].met);
+    }
+
+    #[test]
+    fn test_reads_criterion_output_and_round_trips_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let criterion = dir.path().join("criterion");
+        let latest = criterion.join("bus_fanout").join("raw_events").join("new");
+        fs::create_dir_all(&latest).unwrap();
+        fs::create_dir_all(criterion.join("bus_fanout").join("raw_events").join("base")).unwrap();
+        fs::write(
+            latest.join("benchmark.json"),
+            r#"{"group_id":"bus_fanout","function_id":"raw_events","value_str":null,"throughput":{"Elements":100000},"full_id":"bus_fanout/raw_events","directory_name":"bus_fanout/raw_events","title":"bus_fanout/raw_events"}"#,
+        )
+        .unwrap();
+        let point = |v: f64| format!(r#"{{"confidence_interval":{{"confidence_level":0.95,"lower_bound":{v},"upper_bound":{v}}},"point_estimate":{v},"standard_error":0.0}}"#);
+        fs::write(
+            latest.join("estimates.json"),
+            format!(r#"{{"mean":{},"median":{},"median_abs_dev":{},"slope":null,"std_dev":{}}}"#, point(5e8), point(4e8), point(1e7), point(2e7)),
+        )
+        .unwrap();
+        let times: Vec<f64> = (1..=10).map(|i| i as f64 * 1e8).collect();
+        fs::write(latest.join("sample.json"), serde_json::json!({ "sampling_mode": "Flat", "iters": vec![1.0; 10], "times": times }).to_string()).unwrap();
+
+        let estimates = read_criterion(&criterion).unwrap();
+        assert_eq!(estimates.len(), 1);
+        assert_eq!(estimates[0].id, "bus_fanout/raw_events");
+        assert_eq!(estimates[0].median_ns, 4e8);
+        assert_eq!(estimates[0].p90_ns, 9e8);
+        assert_eq!(estimates[0].per_second(), Some(250_000.0));
+
+        let baselines = dir.path().join("baselines");
+        let saved = Baseline::new("main", estimates);
+        saved.save(&baselines).unwrap();
+        assert_eq!(Baseline::load(&baselines, "main").unwrap(), saved);
+        assert!(matches!(Baseline::load(&baselines, "other"), Err(BenchError::BaselineNotFound { .. })));
+        assert!(matches!(read_criterion(&baselines), Err(BenchError::NoResults(_))));
+    }
+}
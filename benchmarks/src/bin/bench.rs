@@ -0,0 +1,104 @@
+//! Save criterion results as a baseline, or compare the latest run against one
+//!
+//! ```bash
+//! cargo bench -p skelly-jelly-benchmarks
+//! cargo run -p skelly-jelly-benchmarks --bin bench -- --save-baseline main
+//! # ...change code, bench again...
+//! cargo run -p skelly-jelly-benchmarks --bin bench -- --compare main
+//! ```
+//!
+//! `--compare` exits with status 1 when a benchmark regressed or missed its throughput target.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use clap::Parser;
+use skelly_jelly_benchmarks::{check_targets, compare, read_criterion, Baseline, BenchResult, Verdict, TARGETS};
+
+#[derive(Parser, Debug)]
+#[command(name = "bench", about = "Compare benchmark results against stored baselines")]
+struct Args {
+    /// Save the latest results as baseline NAME
+    #[arg(long, value_name = "NAME", conflicts_with = "compare")]
+    save_baseline: Option<String>,
+
+    /// Compare the latest results against baseline NAME
+    #[arg(long, value_name = "NAME", num_args = 0..=1, default_missing_value = "main")]
+    compare: Option<String>,
+
+    /// Relative slowdown of the median still counted as noise
+    #[arg(long, default_value_t = 0.10)]
+    tolerance: f64,
+
+    /// Criterion output directory [default: <target dir>/criterion]
+    #[arg(long)]
+    criterion_dir: Option<PathBuf>,
+
+    /// Directory holding saved baselines [default: benchmarks/baselines]
+    #[arg(long)]
+    baselines_dir: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Returns whether the run passed
+fn run(args: Args) -> BenchResult<bool> {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let criterion_dir = args.criterion_dir.unwrap_or_else(|| {
+        std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| manifest_dir.join("../target"))
+            .join("criterion")
+    });
+    let baselines_dir = args.baselines_dir.unwrap_or_else(|| manifest_dir.join("baselines"));
+    let current = read_criterion(&criterion_dir)?;
+
+    if let Some(name) = args.save_baseline {
+        let path = Baseline::new(name, current).save(&baselines_dir)?;
+        println!("Saved {}", path.display());
+        return Ok(true);
+    }
+
+    let mut passed = true;
+    if let Some(name) = args.compare {
+        let baseline = Baseline::load(&baselines_dir, &name)?;
+        let comparison = compare(&baseline, &current, args.tolerance);
+        println!("Compared against '{}' (tolerance {:.0}%)", comparison.baseline, comparison.tolerance * 100.0);
+        for entry in &comparison.entries {
+            let change = entry.change.map(|c| format!("{:+.1}%", c * 100.0)).unwrap_or_else(|| "-".to_string());
+            println!("  {:<40} {:>14} {:>14} {:>8}  {:?}", entry.id, format_ns(entry.baseline_ns), format_ns(entry.current_ns), change, entry.verdict);
+        }
+        passed &= !comparison.has_regressions();
+        if comparison.entries.iter().any(|e| e.verdict == Verdict::Missing) {
+            println!("Some baseline benchmarks did not run; rerun the full suite before trusting the comparison");
+        }
+    }
+
+    println!("Throughput targets");
+    for check in check_targets(TARGETS, &current) {
+        let measured = check.measured_per_second.map(|m| format!("{:.0}/s", m)).unwrap_or_else(|| "not run".to_string());
+        let mark = if check.met { "ok" } else { "MISSED" };
+        println!("  {:<40} {:>14} (target {:.0}/s) {}", check.benchmark, measured, check.min_per_second, mark);
+        passed &= check.met;
+    }
+    Ok(passed)
+}
+
+fn format_ns(ns: Option<f64>) -> String {
+    match ns {
+        Some(ns) if ns >= 1e9 => format!("{:.2} s", ns / 1e9),
+        Some(ns) if ns >= 1e6 => format!("{:.2} ms", ns / 1e6),
+        Some(ns) if ns >= 1e3 => format!("{:.2} µs", ns / 1e3),
+        Some(ns) => format!("{:.0} ns", ns),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,31 @@
+//! Error types for benchmark workloads and baseline comparison
+
+use std::path::PathBuf;
+use thiserror::Error;
+use skelly_jelly_event_bus::EventBusError;
+use skelly_jelly_storage::StorageError;
+
+/// Result type for benchmark operations
+pub type BenchResult<T> = Result<T, BenchError>;
+
+/// Why a workload or comparison failed
+#[derive(Error, Debug)]
+pub enum BenchError {
+    #[error("No criterion results under {0}; run `cargo bench -p skelly-jelly-benchmarks` first")]
+    NoResults(PathBuf),
+
+    #[error("Baseline {name} not found at {path}")]
+    BaselineNotFound { name: String, path: PathBuf },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("Event bus error: {0}")]
+    EventBus(#[from] EventBusError),
+}
@@ -0,0 +1,17 @@
+//! # Skelly-Jelly Benchmarks
+//!
+//! Criterion benchmarks over fixed workloads — bus fan-out, storage ingest and
+//! batch inference — plus the baseline store behind `bench --compare`, which
+//! reads criterion's results and reports regressions against a saved run.
+
+pub mod error;
+pub mod workloads;
+pub mod baseline;
+
+// Re-export public API
+pub use error::{BenchError, BenchResult};
+pub use workloads::{Target, TARGETS};
+pub use baseline::{
+    check_targets, compare, read_criterion, Baseline, BenchmarkEstimate, Comparison, ComparisonEntry,
+    TargetCheck, Verdict,
+};
@@ -0,0 +1,241 @@
+//! Reproducible benchmark workloads
+//!
+//! Every workload is built from fixed inputs — the same start time, sizes and
+//! event mix on every run — so two runs differ only in the code they measure.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, TimeZone, Utc};
+use tempfile::TempDir;
+use uuid::Uuid;
+use skelly_jelly_event_bus::{
+    message::RawEvent as BusRawEvent,
+    router::{MessageRouter, RouterConfig},
+    subscription::Subscription,
+    BusMessage, DeliveryMode, MessageFilter, MessagePayload, MessageType, ModuleId,
+};
+use skelly_jelly_storage::{
+    config::DatabaseConfig,
+    database::TimeSeriesDatabase,
+    types::{KeyModifiers, KeystrokeEvent, MouseMoveEvent, RawEvent, WindowFocusEvent},
+};
+
+use crate::error::BenchResult;
+
+/// Messages published per bus fan-out iteration
+pub const BUS_FANOUT_MESSAGES: usize = 100_000;
+
+/// Modules receiving every raw event in the bus fan-out workload
+pub const BUS_FANOUT_SUBSCRIBERS: [ModuleId; 4] = [
+    ModuleId::Storage,
+    ModuleId::AnalysisEngine,
+    ModuleId::Gamification,
+    ModuleId::Orchestrator,
+];
+
+/// Events written per storage ingest iteration, one second's worth at the target rate
+pub const STORAGE_INGEST_EVENTS: usize = 5_000;
+
+/// Windows per batch in the inference workload
+pub const INFERENCE_BATCH_SIZES: [usize; 3] = [1, 8, 32];
+
+/// Throughput a benchmark has to reach, in elements per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+    /// Criterion id of the benchmark, `group/function`
+    pub benchmark: &'static str,
+    /// Least acceptable elements per second
+    pub min_per_second: f64,
+}
+
+/// Throughput targets from the HLD
+pub const TARGETS: &[Target] = &[
+    Target { benchmark: "bus_fanout/raw_events", min_per_second: 100_000.0 },
+    Target { benchmark: "storage_ingest/events_batch", min_per_second: 5_000.0 },
+];
+
+/// Time the synthetic capture starts at
+pub fn seed_time() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()
+}
+
+/// Raw events as the data capture module publishes them
+///
+/// Nine keystrokes for every window switch, spaced 10µs apart.
+pub fn fanout_messages(count: usize) -> Vec<BusMessage> {
+    (0..count)
+        .map(|i| {
+            let timestamp = seed_time() + chrono::Duration::microseconds(10 * i as i64);
+            let event = if i % 10 == 9 {
+                BusRawEvent {
+                    event_type: "window_switch".to_string(),
+                    data: serde_json::json!({ "to": format!("Window {}", i % 7) }),
+                    window_title: Some(format!("Window {}", i % 7)),
+                    timestamp,
+                }
+            } else {
+                let key = char::from(b'a' + (i % 26) as u8).to_string();
+                BusRawEvent { timestamp, ..BusRawEvent::keystroke(key, Duration::from_millis(80), vec![]) }
+            };
+            BusMessage::new(ModuleId::DataCapture, MessagePayload::RawEvent(event))
+        })
+        .collect()
+}
+
+/// Publish `messages` through a fresh router and wait until every subscriber has all of them
+///
+/// Returns the time from the first publish to the last delivery; router setup is not timed.
+pub async fn run_bus_fanout(messages: &[BusMessage], subscribers: &[ModuleId]) -> BenchResult<Duration> {
+    let router = MessageRouter::new(RouterConfig {
+        max_queue_size: messages.len().max(1) * subscribers.len().max(1),
+        compression: None,
+        ..Default::default()
+    });
+    let receivers: Vec<_> = subscribers
+        .iter()
+        .map(|&subscriber| {
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            let filter = MessageFilter::types(vec![MessageType::RawEvent]);
+            router
+                .subscription_manager()
+                .add_subscription(Subscription::new(subscriber, filter, DeliveryMode::BestEffort, sender));
+            receiver
+        })
+        .collect();
+    router.start().await?;
+
+    let expected = messages.len() * subscribers.len();
+    let started = Instant::now();
+    for message in messages {
+        router.publish(message.clone()).await?;
+    }
+    while receivers.iter().map(|r| r.len()).sum::<usize>() < expected {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    let elapsed = started.elapsed();
+
+    router.stop().await?;
+    Ok(elapsed)
+}
+
+/// Captured events in storage form, 1ms apart
+///
+/// Storage keys events by millisecond within a session, so this is the densest
+/// capture it accepts. Mostly keystrokes and mouse moves, with a window focus
+/// change every 50th event.
+pub fn capture_events(count: usize) -> Vec<RawEvent> {
+    (0..count)
+        .map(|i| {
+            let timestamp = seed_time() + chrono::Duration::milliseconds(i as i64);
+            match i % 50 {
+                49 => RawEvent::WindowFocus(WindowFocusEvent {
+                    timestamp,
+                    window_title: format!("Window {}", (i / 50) % 7),
+                    app_name: "Editor".to_string(),
+                    process_id: 4242,
+                    duration_ms: Some(10_000),
+                }),
+                n if n % 3 == 0 => RawEvent::MouseMove(MouseMoveEvent {
+                    timestamp,
+                    x: (i % 1920) as i32,
+                    y: (i % 1080) as i32,
+                    velocity: 350.0,
+                }),
+                _ => RawEvent::Keystroke(KeystrokeEvent {
+                    timestamp,
+                    key_code: 65 + (i % 26) as u32,
+                    modifiers: KeyModifiers::default(),
+                    inter_key_interval_ms: Some(150),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// A time-series database in a temporary directory, removed on drop
+pub struct StorageIngest {
+    database: TimeSeriesDatabase,
+    dir: TempDir,
+}
+
+impl StorageIngest {
+    /// Open an empty database with default settings
+    pub async fn new() -> BenchResult<Self> {
+        let dir = tempfile::tempdir()?;
+        let config = DatabaseConfig {
+            path: dir.path().join("events.db"),
+            ..Default::default()
+        };
+        Ok(Self {
+            database: TimeSeriesDatabase::new(config).await?,
+            dir,
+        })
+    }
+
+    /// Path of the database file
+    pub fn path(&self) -> PathBuf {
+        self.dir.path().join("events.db")
+    }
+
+    /// Write `events` as one batch under a new session, returning how long it took
+    ///
+    /// A new session per batch lets the same events be written again each iteration.
+    pub async fn ingest(&self, events: &[RawEvent]) -> BenchResult<Duration> {
+        let session_id = Uuid::new_v4();
+        let started = Instant::now();
+        self.database.store_events_batch(&session_id, events).await?;
+        Ok(started.elapsed())
+    }
+}
+
+/// Analysis windows of 30 seconds each, filled from [`capture_events`]
+#[cfg(feature = "analysis-engine")]
+pub fn inference_windows(count: usize) -> Vec<skelly_jelly_analysis_engine::AnalysisWindow> {
+    use skelly_jelly_analysis_engine::AnalysisWindow;
+
+    let events = capture_events(60);
+    (0..count)
+        .map(|i| {
+            let start = std::time::SystemTime::from(seed_time()) + Duration::from_secs(30 * i as u64);
+            let mut window = AnalysisWindow::new(start);
+            for event in &events {
+                window.add_event(event.clone());
+            }
+            window
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workloads_are_reproducible() {
+        let first = fanout_messages(20);
+        let second = fanout_messages(20);
+        let payloads = |messages: &[BusMessage]| {
+            messages.iter().map(|m| serde_json::to_string(&m.payload).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(payloads(&first), payloads(&second));
+        assert_eq!(first.iter().filter(|m| matches!(&m.payload, MessagePayload::RawEvent(e) if e.event_type == "window_switch")).count(), 2);
+
+        let events = capture_events(STORAGE_INGEST_EVENTS);
+        let span = events.last().unwrap().timestamp() - events[0].timestamp();
+        assert_eq!(span, chrono::Duration::milliseconds(STORAGE_INGEST_EVENTS as i64 - 1));
+        assert_eq!(events.iter().filter(|e| matches!(e, RawEvent::WindowFocus(_))).count(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_small_workloads_run() {
+        let messages = fanout_messages(200);
+        let elapsed = run_bus_fanout(&messages, &BUS_FANOUT_SUBSCRIBERS).await.unwrap();
+        assert!(elapsed > Duration::ZERO);
+
+        let ingest = StorageIngest::new().await.unwrap();
+        let events = capture_events(100);
+        ingest.ingest(&events).await.unwrap();
+        ingest.ingest(&events).await.unwrap();
+        assert!(ingest.path().exists());
+    }
+}
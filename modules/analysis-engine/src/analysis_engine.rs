@@ -1,17 +1,26 @@
 //! Main analysis engine implementation (simplified working version)
 
 use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use skelly_jelly_event_bus::{EventBusTrait, ModuleId};
+use skelly_jelly_event_bus::{message::{ForecastUpdated, TaskDeclaration}, EventBusTrait, ModuleId, RecoverySystem};
 use skelly_jelly_storage::types::EventBatch;
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::{Duration, SystemTime}};
 use tokio::sync::RwLock;
+use tracing::{info, warn};
 
 use crate::{
+    app_focus::{AppFocusConfig, AppFocusReport, AppFocusTracker, DailyAppFocus},
+    checkpoint::{CheckpointStore, ANALYSIS_CONSUMER},
+    daily_activity::DayActivityResponder,
     error::{AnalysisError, AnalysisResult},
     event_processor::{EventProcessor, EventProcessorConfig},
+    focus_check::FocusCheck,
+    forecasting::{FocusForecaster, ForecastConfig},
+    privacy::{IdentifierHasher, IdentifierHashingConfig},
     metrics::BehavioralMetrics,
     models::ADHDState,
+    sessions::{SessionConfig, SessionPage, SessionQuery, SessionReconstructor, StateRecord, TaskFocusStats},
     types::AnalysisResult as AnalysisResultType,
     AnalysisEngineTrait, PerformanceMetrics, UserFeedback,
 };
@@ -32,6 +41,27 @@ pub struct AnalysisEngineImpl {
     
     /// Performance tracking
     performance_metrics: Arc<RwLock<PerformanceMetrics>>,
+    
+    /// State history and the work sessions built from it
+    sessions: Arc<SessionReconstructor>,
+    
+    /// Daily focus totals per application
+    app_focus: Arc<AppFocusTracker>,
+    
+    /// Hourly focus history and the next hour's forecast
+    forecaster: FocusForecaster,
+    
+    /// Task the user declared as current; windows are tagged with it
+    current_task: std::sync::RwLock<Option<TaskDeclaration>>,
+    
+    /// Descriptions of every task declared since startup
+    task_descriptions: std::sync::RwLock<HashMap<uuid::Uuid, String>>,
+    
+    /// Where processing progress is saved, so a restart resumes where it stopped
+    checkpoints: Option<Arc<dyn CheckpointStore>>,
+    
+    /// Where failures are reported with their recovery hints
+    recovery: Option<Arc<RecoverySystem>>,
 }
 
 impl AnalysisEngineImpl {
@@ -66,11 +96,105 @@ impl AnalysisEngineImpl {
         Ok(Self {
             event_processor,
             event_bus,
-            config,
             is_running: Arc::new(RwLock::new(false)),
             performance_metrics,
+            sessions: Arc::new(SessionReconstructor::new(config.sessions.clone())),
+            app_focus: Arc::new(
+                AppFocusTracker::new(config.app_focus.clone())
+                    .with_identifier_hasher(Arc::new(IdentifierHasher::new(config.identifier_hashing.clone())?)),
+            ),
+            forecaster: FocusForecaster::new(config.forecast.clone())?,
+            current_task: std::sync::RwLock::new(None),
+            task_descriptions: std::sync::RwLock::new(HashMap::new()),
+            checkpoints: None,
+            recovery: None,
+            config,
         })
     }
+
+    /// Save a checkpoint after each batch and resume from it with [`AnalysisEngineTrait::resume_from_checkpoint`]
+    pub fn with_checkpoint_store(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoints = Some(store);
+        self
+    }
+
+    /// Report analysis failures to `recovery`, which acts on their recovery hints
+    pub fn with_recovery_system(mut self, recovery: Arc<RecoverySystem>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+
+    /// Open a recovery incident for `error`, unless the window is just still filling up
+    async fn report_failure(&self, error: &AnalysisError) {
+        let Some(recovery) = &self.recovery else { return };
+        if matches!(error, AnalysisError::InsufficientData { .. }) {
+            return;
+        }
+        if let Err(e) = error.report(recovery, uuid::Uuid::new_v4()).await {
+            warn!("Failed to report analysis failure: {}", e);
+        }
+    }
+
+    /// Add an analyzed window to the history it feeds
+    async fn record_result(&self, result: &AnalysisResultType) {
+        let task_id = self.current_task.read().unwrap().as_ref().map(|task| task.task_id);
+        let record = StateRecord::from_analysis(result, self.config.window_size, None).with_task(task_id);
+        self.forecaster.record(&record);
+        self.sessions.record(record);
+        if let Err(e) = self.forecaster.publish_if_due(self.event_bus.as_ref(), Utc::now()).await {
+            warn!("Failed to publish focus forecast: {}", e);
+        }
+        if let Err(e) = self.app_focus.record(result) {
+            warn!("Failed to record app focus: {}", e);
+        }
+    }
+
+    /// Work sessions reconstructed from the state history, newest first
+    pub fn work_sessions(&self, query: &SessionQuery) -> SessionPage {
+        self.sessions.sessions(query)
+    }
+
+    /// Per-application focus totals for one day
+    pub fn app_focus_day(&self, date: NaiveDate) -> AnalysisResult<Option<DailyAppFocus>> {
+        self.app_focus.day(date)
+    }
+
+    /// Which applications cost the most focus over `from..=to`
+    pub fn app_focus_report(&self, from: NaiveDate, to: NaiveDate) -> AnalysisResult<AppFocusReport> {
+        self.app_focus.report(from, to)
+    }
+
+    /// Predicted focus capacity for the coming hour, `None` without history for that time of day
+    pub fn focus_forecast(&self) -> Option<ForecastUpdated> {
+        self.forecaster.forecast(Utc::now())
+    }
+
+    /// Tag the following windows with the user's declared task, from a `CurrentTask` message; `None` clears it
+    pub fn set_current_task(&self, task: Option<TaskDeclaration>) {
+        if let Some(task) = &task {
+            self.task_descriptions.write().unwrap().insert(task.task_id, task.description.clone());
+        }
+        *self.current_task.write().unwrap() = task;
+    }
+
+    /// Focus per declared task over windows starting in `from..to`, most active first
+    pub fn task_focus(
+        &self,
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        to: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Vec<TaskFocusStats> {
+        let descriptions = self.task_descriptions.read().unwrap();
+        self.sessions
+            .task_focus(from, to)
+            .into_iter()
+            .map(|stats| TaskFocusStats { description: descriptions.get(&stats.task_id).cloned(), ..stats })
+            .collect()
+    }
+
+    /// Answers end-of-day summary requests from the bus with this engine's history
+    pub fn day_activity_responder(&self, event_bus: Arc<dyn EventBusTrait>) -> DayActivityResponder {
+        DayActivityResponder::new(Arc::clone(&self.sessions), Arc::clone(&self.app_focus), event_bus)
+    }
 }
 
 #[async_trait]
@@ -79,8 +203,15 @@ impl AnalysisEngineTrait for AnalysisEngineImpl {
         let start_time = std::time::Instant::now();
         
         let mut processor = self.event_processor.write().await;
+        let mut results = match processor.process_event_batch_all(batch).await {
+            Ok(results) => results,
+            Err(e) => {
+                self.report_failure(&e).await;
+                return Err(e);
+            }
+        };
         
-        match processor.process_event_batch(batch).await? {
+        match results.pop() {
             Some(result) => {
                 // Update performance metrics
                 let processing_time = start_time.elapsed().as_millis() as f32;
@@ -92,6 +223,18 @@ impl AnalysisEngineTrait for AnalysisEngineImpl {
                         / metrics.total_analyses as f32;
                 }
                 
+                for earlier in &results {
+                    self.record_result(earlier).await;
+                }
+                self.record_result(&result).await;
+                
+                // Only once the results are recorded, so a crash before this replays them
+                if let Some(store) = &self.checkpoints {
+                    if let Err(e) = store.save(&processor.checkpoint(ANALYSIS_CONSUMER)).await {
+                        warn!("Failed to save analysis checkpoint: {}", e);
+                    }
+                }
+                
                 Ok(result)
             }
             None => {
@@ -104,6 +247,31 @@ impl AnalysisEngineTrait for AnalysisEngineImpl {
         }
     }
 
+    async fn analyze_now(&self) -> AnalysisResult<FocusCheck> {
+        let start_time = std::time::Instant::now();
+        let mut processor = self.event_processor.write().await;
+
+        let window = processor.current_window();
+        let events_analyzed = window.events.len();
+        let window_elapsed = SystemTime::now()
+            .duration_since(window.start_time)
+            .unwrap_or_default();
+
+        // The window stays open, so the result is not recorded as a session
+        let result = processor.analyze_current_window().await?;
+
+        let processing_time = start_time.elapsed().as_millis() as f32;
+        {
+            let mut metrics = self.performance_metrics.write().await;
+            metrics.total_analyses += 1;
+            metrics.avg_inference_time_ms =
+                (metrics.avg_inference_time_ms * (metrics.total_analyses - 1) as f32 + processing_time)
+                / metrics.total_analyses as f32;
+        }
+
+        Ok(FocusCheck::new(result, events_analyzed, window_elapsed))
+    }
+
     async fn get_current_state(&self) -> ADHDState {
         // Return neutral state for now - would be calculated from current window
         ADHDState::neutral()
@@ -134,6 +302,42 @@ impl AnalysisEngineTrait for AnalysisEngineImpl {
         let metrics = self.performance_metrics.read().await.clone();
         metrics
     }
+
+    async fn resume_from_checkpoint(&self) -> AnalysisResult<usize> {
+        let Some(store) = &self.checkpoints else {
+            return Ok(0);
+        };
+        let Some(checkpoint) = store.load().await? else {
+            return Ok(0);
+        };
+
+        let events = store.events_since(checkpoint.offset).await?;
+        let replayed = events.len();
+        let now = Utc::now();
+        let mut processor = self.event_processor.write().await;
+        processor.resume_from(&checkpoint);
+        let results = match processor
+            .process_event_batch_all(EventBatch {
+                window_id: uuid::Uuid::new_v4(),
+                start_time: checkpoint.offset,
+                end_time: now,
+                events,
+                screenshot_refs: Vec::new(),
+            })
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                self.report_failure(&e).await;
+                return Err(e);
+            }
+        };
+        for result in &results {
+            self.record_result(result).await;
+        }
+        info!("Resumed analysis from {} with {} replayed events", checkpoint.offset, replayed);
+        Ok(replayed)
+    }
 }
 
 /// Configuration for the analysis engine
@@ -161,10 +365,25 @@ pub struct AnalysisEngineConfig {
     // Privacy
     pub enable_screenshots: bool,
     pub ocr_confidence_threshold: f32,
+    /// Hash app and category names before they are persisted
+    #[serde(default)]
+    pub identifier_hashing: IdentifierHashingConfig,
 
     // Performance
     pub max_concurrent_analyses: usize,
     pub processing_timeout_ms: u64,
+
+    // Work sessions
+    #[serde(default)]
+    pub sessions: SessionConfig,
+
+    // Per-application focus
+    #[serde(default)]
+    pub app_focus: AppFocusConfig,
+
+    // Next-hour focus forecast
+    #[serde(default)]
+    pub forecast: ForecastConfig,
 }
 
 impl Default for AnalysisEngineConfig {
@@ -183,8 +402,12 @@ impl Default for AnalysisEngineConfig {
             feedback_weight: 0.5,
             enable_screenshots: true,
             ocr_confidence_threshold: 0.8,
+            identifier_hashing: IdentifierHashingConfig::default(),
             max_concurrent_analyses: 3,
             processing_timeout_ms: 50,
+            sessions: SessionConfig::default(),
+            app_focus: AppFocusConfig::default(),
+            forecast: ForecastConfig::default(),
         }
     }
 }
\ No newline at end of file
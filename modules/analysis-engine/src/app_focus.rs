@@ -0,0 +1,436 @@
+//! Per-application focus quality
+//!
+//! Every analysis window knows which applications had focus and for how long
+//! ([`BehavioralMetrics::app_spans`]). The tracker credits that time to the
+//! window's state, so each application and work category builds up time in
+//! flow, time distracted and a count of separate sessions. Totals are kept per
+//! day and written to `storage_dir/YYYY-MM-DD.json`; [`AppFocusTracker::report`]
+//! merges any range of days and ranks applications by how much of their time
+//! was spent distracted, the "which apps wreck my focus" view.
+//!
+//! Windows overlap, so a span is only credited from where the previous one
+//! ended.
+//!
+//! With an enabled [`IdentifierHasher`], application and category names are
+//! stored as salted hashes. [`AppFocusTracker::day`] returns the stored form;
+//! reports resolve names through the hasher's local lookup table.
+//!
+//! [`BehavioralMetrics::app_spans`]: crate::metrics::BehavioralMetrics::app_spans
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::ADHDStateType,
+    privacy::IdentifierHasher,
+    types::AnalysisResult as AnalysisResultType,
+};
+
+/// Where daily totals are kept and what the report includes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppFocusConfig {
+    /// One JSON file per day
+    pub storage_dir: PathBuf,
+    /// How often the current day is written out while it is recorded
+    pub save_interval: Duration,
+    /// Days kept on disk
+    pub retention_days: u32,
+    /// Time away from an application after which returning starts a new session
+    pub session_gap: Duration,
+    /// Applications with less time than this are left out of reports
+    pub min_report_time: Duration,
+}
+
+impl Default for AppFocusConfig {
+    fn default() -> Self {
+        Self {
+            storage_dir: PathBuf::from("data/app_focus"),
+            save_interval: Duration::from_secs(60),
+            retention_days: 90,
+            session_gap: Duration::from_secs(120),
+            min_report_time: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Focus totals for one application or work category
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppFocusStats {
+    pub total_time: Duration,
+    /// Time in flow or hyperfocus
+    pub flow_time: Duration,
+    pub distracted_time: Duration,
+    /// Separate stretches of use
+    pub sessions: u32,
+}
+
+impl AppFocusStats {
+    /// Share of the time spent in flow, 0–1
+    pub fn flow_share(&self) -> f32 {
+        share(self.flow_time, self.total_time)
+    }
+
+    /// Share of the time spent distracted, 0–1
+    pub fn distracted_share(&self) -> f32 {
+        share(self.distracted_time, self.total_time)
+    }
+
+    pub fn average_session(&self) -> Duration {
+        if self.sessions == 0 {
+            Duration::ZERO
+        } else {
+            self.total_time / self.sessions
+        }
+    }
+
+    fn add(&mut self, time: Duration, state: ADHDStateType, new_session: bool) {
+        self.total_time += time;
+        match state {
+            ADHDStateType::Flow | ADHDStateType::Hyperfocus => self.flow_time += time,
+            ADHDStateType::Distracted => self.distracted_time += time,
+            _ => {}
+        }
+        if new_session {
+            self.sessions += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &AppFocusStats) {
+        self.total_time += other.total_time;
+        self.flow_time += other.flow_time;
+        self.distracted_time += other.distracted_time;
+        self.sessions += other.sessions;
+    }
+}
+
+fn share(part: Duration, total: Duration) -> f32 {
+    if total.is_zero() {
+        0.0
+    } else {
+        part.as_secs_f32() / total.as_secs_f32()
+    }
+}
+
+/// One day of per-application focus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyAppFocus {
+    pub date: NaiveDate,
+    pub apps: BTreeMap<String, AppFocusStats>,
+    /// Keyed by screenshot work category, e.g. `"coding"`
+    pub categories: BTreeMap<String, AppFocusStats>,
+}
+
+impl DailyAppFocus {
+    fn new(date: NaiveDate) -> Self {
+        Self { date, apps: BTreeMap::new(), categories: BTreeMap::new() }
+    }
+
+    fn load(path: &Path) -> AnalysisResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map(Some).map_err(|e| AnalysisError::DataLoadError {
+            path: path.display().to_string(),
+            message: format!("Failed to parse app focus totals: {}", e),
+        })
+    }
+
+    fn save(&self, path: &Path) -> AnalysisResult<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string(self)?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+/// An application or category in a report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppFocusEntry {
+    pub name: String,
+    pub stats: AppFocusStats,
+}
+
+/// Focus quality per application over a range of days, most distracting first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppFocusReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub apps: Vec<AppFocusEntry>,
+    pub categories: Vec<AppFocusEntry>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    today: Option<DailyAppFocus>,
+    /// Last credited application and where its time ended
+    last_app: Option<(String, DateTime<Utc>)>,
+    dirty: bool,
+    last_saved: Option<Instant>,
+}
+
+/// Accumulates per-application focus from analysis results
+pub struct AppFocusTracker {
+    config: AppFocusConfig,
+    state: Mutex<TrackerState>,
+    identifiers: Arc<IdentifierHasher>,
+}
+
+impl AppFocusTracker {
+    pub fn new(config: AppFocusConfig) -> Self {
+        Self { config, state: Mutex::new(TrackerState::default()), identifiers: Arc::new(IdentifierHasher::disabled()) }
+    }
+
+    /// Store application and category names in the form `identifiers` gives them
+    pub fn with_identifier_hasher(mut self, identifiers: Arc<IdentifierHasher>) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
+
+    /// The readable name of an application or category as stored in a day's totals
+    pub fn display_name(&self, stored: &str) -> String {
+        self.identifiers.display_name(stored)
+    }
+
+    /// Credit a classified window's application time to its state
+    pub fn record(&self, result: &AnalysisResultType) -> AnalysisResult<()> {
+        let category = result
+            .work_context
+            .as_ref()
+            .map(|context| self.identifiers.pseudonymize(context.primary_work_type.category()));
+        let session_gap = chrono::Duration::from_std(self.config.session_gap).unwrap_or_default();
+        let mut state = self.state.lock().unwrap();
+
+        for span in &result.metrics.app_spans {
+            let app_name = self.identifiers.pseudonymize(&span.app_name);
+            let start = match &state.last_app {
+                Some((_, credited_until)) => span.start.max(*credited_until),
+                None => span.start,
+            };
+            if span.end <= start {
+                continue;
+            }
+            let time = (span.end - start).to_std().unwrap_or_default();
+            let new_session = match &state.last_app {
+                Some((app, credited_until)) => *app != app_name || span.start - *credited_until > session_gap,
+                None => true,
+            };
+
+            let day = self.day_for(&mut state, start.date_naive())?;
+            day.apps.entry(app_name.clone()).or_default().add(time, result.state.state_type, new_session);
+            if let Some(category) = &category {
+                day.categories.entry(category.clone()).or_default().add(time, result.state.state_type, new_session);
+            }
+            state.last_app = Some((app_name, span.end));
+            state.dirty = true;
+        }
+
+        if state.last_saved.is_none_or(|saved| saved.elapsed() >= self.config.save_interval) {
+            self.save(&mut state)?;
+        }
+        Ok(())
+    }
+
+    /// Write the current day out now
+    pub fn flush(&self) -> AnalysisResult<()> {
+        let mut state = self.state.lock().unwrap();
+        self.save(&mut state)
+    }
+
+    /// Totals for one day as stored, `None` if nothing was recorded
+    pub fn day(&self, date: NaiveDate) -> AnalysisResult<Option<DailyAppFocus>> {
+        let state = self.state.lock().unwrap();
+        if let Some(today) = state.today.as_ref().filter(|today| today.date == date) {
+            return Ok(Some(today.clone()));
+        }
+        DailyAppFocus::load(&self.path_for(date))
+    }
+
+    /// Totals over `from..=to`, ranked by share of time spent distracted
+    pub fn report(&self, from: NaiveDate, to: NaiveDate) -> AnalysisResult<AppFocusReport> {
+        let mut apps: BTreeMap<String, AppFocusStats> = BTreeMap::new();
+        let mut categories: BTreeMap<String, AppFocusStats> = BTreeMap::new();
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            if let Some(day) = self.day(date)? {
+                for (name, stats) in &day.apps {
+                    apps.entry(name.clone()).or_default().merge(stats);
+                }
+                for (name, stats) in &day.categories {
+                    categories.entry(name.clone()).or_default().merge(stats);
+                }
+            }
+        }
+        Ok(AppFocusReport { from, to, apps: self.rank(apps), categories: self.rank(categories) })
+    }
+
+    fn rank(&self, totals: BTreeMap<String, AppFocusStats>) -> Vec<AppFocusEntry> {
+        let mut entries: Vec<AppFocusEntry> = totals
+            .into_iter()
+            .filter(|(_, stats)| stats.total_time >= self.config.min_report_time)
+            .map(|(name, stats)| AppFocusEntry { name: self.identifiers.display_name(&name), stats })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.stats
+                .distracted_share()
+                .total_cmp(&a.stats.distracted_share())
+                .then(b.stats.total_time.cmp(&a.stats.total_time))
+        });
+        entries
+    }
+
+    /// The in-memory day for `date`, saving and replacing the previous one on rollover
+    fn day_for<'a>(&self, state: &'a mut TrackerState, date: NaiveDate) -> AnalysisResult<&'a mut DailyAppFocus> {
+        if state.today.as_ref().is_none_or(|today| today.date != date) {
+            self.save(state)?;
+            // Resume a day recorded before a restart
+            let day = DailyAppFocus::load(&self.path_for(date))?.unwrap_or_else(|| DailyAppFocus::new(date));
+            state.today = Some(day);
+            self.prune(date);
+        }
+        Ok(state.today.as_mut().expect("current day was just set"))
+    }
+
+    fn save(&self, state: &mut TrackerState) -> AnalysisResult<()> {
+        if let Some(today) = state.today.as_ref().filter(|_| state.dirty) {
+            // The lookup table first, so every stored hash can be resolved
+            self.identifiers.flush()?;
+            today.save(&self.path_for(today.date))?;
+            state.dirty = false;
+        }
+        state.last_saved = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Remove day files past the retention period
+    fn prune(&self, today: NaiveDate) {
+        let Ok(entries) = fs::read_dir(&self.config.storage_dir) else {
+            return;
+        };
+        let cutoff = today - chrono::Duration::days(self.config.retention_days as i64);
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let expired = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok())
+                .is_some_and(|date| date < cutoff);
+            if expired && fs::remove_file(&path).is_ok() {
+                debug!("Removed expired app focus totals {}", path.display());
+            }
+        }
+    }
+
+    fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.config.storage_dir.join(format!("{}.json", date.format("%Y-%m-%d")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{metrics::AppSpan, models::ADHDState};
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + chrono::Duration::minutes(minute)
+    }
+
+    fn window(state: ADHDState, spans: &[(&str, i64, i64)]) -> AnalysisResultType {
+        let mut result = AnalysisResultType::new(uuid::Uuid::new_v4(), state);
+        result.metrics.app_spans = spans
+            .iter()
+            .map(|(app, start, end)| AppSpan { app_name: app.to_string(), start: at(*start), end: at(*end) })
+            .collect();
+        result
+    }
+
+    fn tracker(dir: &Path) -> AppFocusTracker {
+        AppFocusTracker::new(AppFocusConfig {
+            storage_dir: dir.to_path_buf(),
+            min_report_time: Duration::from_secs(60),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_windows_credit_apps_by_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = tracker(dir.path());
+        tracker.record(&window(ADHDState::flow(), &[("Code", 0, 10)])).unwrap();
+        // Overlaps the previous window by a minute, then a detour to Slack
+        tracker.record(&window(ADHDState::distracted(), &[("Code", 9, 12), ("Slack", 12, 16)])).unwrap();
+        tracker.record(&window(ADHDState::flow(), &[("Code", 16, 20)])).unwrap();
+
+        let day = tracker.day(at(0).date_naive()).unwrap().unwrap();
+        let code = &day.apps["Code"];
+        assert_eq!(code.total_time, Duration::from_secs(16 * 60));
+        assert_eq!(code.flow_time, Duration::from_secs(14 * 60));
+        assert_eq!(code.distracted_time, Duration::from_secs(2 * 60));
+        assert_eq!(code.sessions, 2);
+        assert_eq!(code.average_session(), Duration::from_secs(8 * 60));
+        assert_eq!(day.apps["Slack"].distracted_share(), 1.0);
+
+        let report = tracker.report(at(0).date_naive(), at(0).date_naive()).unwrap();
+        let ranked: Vec<&str> = report.apps.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(ranked, vec!["Slack", "Code"]);
+    }
+
+    #[test]
+    fn test_days_persist_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = tracker(dir.path());
+        first.record(&window(ADHDState::flow(), &[("Code", 0, 30)])).unwrap();
+        first.flush().unwrap();
+
+        let second = tracker(dir.path());
+        second.record(&window(ADHDState::flow(), &[("Code", 60, 90)])).unwrap();
+        let next_day = at(24 * 60);
+        second.record(&window(ADHDState::distracted(), &[("Safari", 24 * 60, 24 * 60 + 20)])).unwrap();
+        second.flush().unwrap();
+
+        let day_one = second.day(at(0).date_naive()).unwrap().unwrap();
+        assert_eq!(day_one.apps["Code"].total_time, Duration::from_secs(60 * 60));
+        assert_eq!(day_one.apps["Code"].sessions, 2);
+        assert!(dir.path().join(format!("{}.json", next_day.format("%Y-%m-%d"))).exists());
+
+        let report = second.report(at(0).date_naive(), next_day.date_naive()).unwrap();
+        assert_eq!(report.apps.len(), 2);
+        assert_eq!(report.apps[0].name, "Safari");
+    }
+
+    #[test]
+    fn test_hashed_names_stay_off_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let identifiers = Arc::new(
+            IdentifierHasher::new(crate::privacy::IdentifierHashingConfig {
+                enabled: true,
+                salt_path: dir.path().join("salt"),
+                lookup_path: dir.path().join("lookup.json"),
+            })
+            .unwrap(),
+        );
+        let tracker = tracker(&dir.path().join("focus")).with_identifier_hasher(Arc::clone(&identifiers));
+        tracker.record(&window(ADHDState::flow(), &[("Code", 0, 10), ("Slack", 10, 12)])).unwrap();
+        tracker.flush().unwrap();
+
+        let stored = fs::read_to_string(dir.path().join("focus").join(format!("{}.json", at(0).format("%Y-%m-%d")))).unwrap();
+        assert!(!stored.contains("Code") && !stored.contains("Slack"));
+        let day = tracker.day(at(0).date_naive()).unwrap().unwrap();
+        assert!(day.apps.contains_key(&identifiers.pseudonymize("Code")));
+
+        let report = tracker.report(at(0).date_naive(), at(0).date_naive()).unwrap();
+        let names: Vec<&str> = report.apps.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!["Code", "Slack"]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Captured events from the bus
+//!
+//! Data capture publishes each event as a `RawEvent` stamped with its
+//! sequence number on the data capture → analysis engine stream.
+//! [`CaptureFeed`] analyzes whatever has arrived as one batch and then
+//! acknowledges the sequence numbers. Events the engine never got stay
+//! unacknowledged, so the orchestrator sees the gap and has Storage resend
+//! them. A batch whose analysis fails isn't acknowledged either. The engine
+//! reports its own failures; the feed reports acknowledgements that couldn't
+//! be sent.
+
+use std::sync::Arc;
+use futures::{Stream, StreamExt};
+use skelly_jelly_event_bus::{
+    decode_captured_event, BusMessage, DeliveryAcks, EventBusTrait, MessageFilter, MessagePayload, MessageType,
+    ModuleId, RecoverySystem,
+};
+use skelly_jelly_storage::types::EventBatch;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    AnalysisEngineTrait,
+};
+
+/// Most events analyzed as one batch
+const MAX_BATCH_EVENTS: usize = 256;
+
+/// Feeds data capture's events into the engine and acknowledges them
+pub struct CaptureFeed {
+    engine: Arc<dyn AnalysisEngineTrait>,
+    event_bus: Arc<dyn EventBusTrait>,
+    recovery: Option<Arc<RecoverySystem>>,
+}
+
+impl CaptureFeed {
+    pub fn new(engine: Arc<dyn AnalysisEngineTrait>, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self { engine, event_bus, recovery: None }
+    }
+
+    /// Report failed acknowledgements to `recovery`, which acts on their recovery hints
+    pub fn with_recovery_system(mut self, recovery: Arc<RecoverySystem>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+
+    /// Messages the feed reacts to
+    pub fn subscription_filter() -> MessageFilter {
+        MessageFilter::types_and_sources(vec![MessageType::RawEvent], vec![ModuleId::DataCapture])
+    }
+
+    /// Handle messages until the stream ends, in batches of what has arrived
+    pub async fn consume(&self, messages: impl Stream<Item = BusMessage> + Unpin) {
+        let mut batches = messages.ready_chunks(MAX_BATCH_EVENTS);
+        while let Some(batch) = batches.next().await {
+            if let Err(e) = self.handle_batch(&batch).await {
+                warn!("{} captured events left unacknowledged: {}", batch.len(), e);
+            }
+        }
+    }
+
+    /// Analyze a batch, then acknowledge it; returns how many events were analyzed
+    pub async fn handle_batch(&self, messages: &[BusMessage]) -> AnalysisResult<usize> {
+        let mut acks = DeliveryAcks::new(ModuleId::AnalysisEngine);
+        let mut events = Vec::with_capacity(messages.len());
+        for message in messages {
+            let MessagePayload::RawEvent(event) = &message.payload else { continue };
+            // A malformed event would be just as malformed when resent
+            match decode_captured_event(event) {
+                Ok(event) => events.push(event),
+                Err(e) => warn!("Dropping captured {} event: {}", event.event_type, e),
+            }
+            acks.record(message);
+        }
+
+        let analyzed = events.len();
+        if let (Some(first), Some(last)) = (events.first(), events.last()) {
+            let batch = EventBatch {
+                window_id: Uuid::new_v4(),
+                start_time: first.timestamp(),
+                end_time: last.timestamp(),
+                events,
+                screenshot_refs: Vec::new(),
+            };
+            match self.engine.analyze_batch(batch).await {
+                // Still short of a full window; the events are kept for the next one
+                Ok(_) | Err(AnalysisError::InsufficientData { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if let Err(e) = acks.flush(self.event_bus.as_ref()).await {
+            let e = AnalysisError::from(e);
+            if let Some(recovery) = &self.recovery {
+                if let Err(report_error) = e.report(recovery, Uuid::new_v4()).await {
+                    warn!("Failed to report unsent acknowledgements: {}", report_error);
+                }
+            }
+            return Err(e);
+        }
+        Ok(analyzed)
+    }
+}
@@ -0,0 +1,79 @@
+//! Check-in answers as ground truth
+//!
+//! AI integration occasionally asks the user "How's it going?" and publishes
+//! the answer as a `CheckInResponse` that names the state the answer stands
+//! for. [`CheckInLabeler`] turns each response into [`UserFeedback`] for the
+//! window it was given in, so the answers train the classifier the same way
+//! explicit corrections do.
+
+use std::sync::Arc;
+use skelly_jelly_event_bus::{message::CheckInResponse, BusMessage, MessagePayload, MessageType};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{error::AnalysisResult, AnalysisEngineTrait, UserFeedback};
+
+/// Labeled feedback for a check-in answer
+pub fn check_in_feedback(response: &CheckInResponse) -> UserFeedback {
+    UserFeedback {
+        // Answers given outside an analysis window still label the current state
+        window_id: response.window_id.unwrap_or_else(Uuid::nil),
+        user_state: response.user_state.clone(),
+        confidence: response.confidence,
+        timestamp: response.timestamp,
+        notes: Some(format!("check-in: {}", response.answer)),
+    }
+}
+
+/// Feeds `CheckInResponse`s from the bus to the engine as feedback
+pub struct CheckInLabeler {
+    engine: Arc<dyn AnalysisEngineTrait>,
+}
+
+impl CheckInLabeler {
+    pub fn new(engine: Arc<dyn AnalysisEngineTrait>) -> Self {
+        Self { engine }
+    }
+
+    /// Message types the labeler reacts to
+    pub fn subscribed_types() -> Vec<MessageType> {
+        vec![MessageType::CheckInResponse]
+    }
+
+    /// Pass a check-in answer to the engine; other messages are ignored
+    pub async fn handle_message(&self, message: &BusMessage) -> AnalysisResult<bool> {
+        let MessagePayload::CheckInResponse(response) = &message.payload else {
+            return Ok(false);
+        };
+        debug!("Check-in answered '{}', labeling as {}", response.answer, response.user_state);
+        self.engine.process_feedback(check_in_feedback(response)).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_feedback_carries_label_and_window() {
+        let window_id = Uuid::new_v4();
+        let response = CheckInResponse {
+            check_in_id: Uuid::new_v4(),
+            answer: "struggling".to_string(),
+            user_state: "distracted".to_string(),
+            confidence: 0.7,
+            window_id: Some(window_id),
+            timestamp: Utc::now(),
+        };
+
+        let feedback = check_in_feedback(&response);
+        assert_eq!(feedback.window_id, window_id);
+        assert_eq!(feedback.user_state, "distracted");
+        assert_eq!(feedback.notes.as_deref(), Some("check-in: struggling"));
+
+        let unwindowed = check_in_feedback(&CheckInResponse { window_id: None, ..response });
+        assert_eq!(unwindowed.window_id, Uuid::nil());
+    }
+}
@@ -0,0 +1,330 @@
+//! Cold-start state detection for users without a trained model
+//!
+//! A fresh install has no labelled data, so the Random Forest cannot predict
+//! anything yet. Until it can, two fallbacks keep state detection useful:
+//!
+//! - [`HeuristicClassifier`] thresholds activity density and window switching
+//!   rate of the raw window. It needs no training and runs from the first window.
+//! - [`BaselineBundle`] is a small generic model shipped with the engine. It
+//!   synthesizes training samples around per-state feature prototypes, so the
+//!   classifier can be trained before the user has given any feedback.
+//!
+//! Once the classifier is trained on the user's own data, both are bypassed.
+
+use chrono::Utc;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_storage::types::RawEvent;
+use std::time::Duration;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::{ADHDState, ADHDStateType, StateDistribution},
+    sliding_window::AnalysisWindow,
+    types::{DistractionType, FeatureVector, FlowDepth},
+};
+
+/// Baseline model bundled with the engine
+const BUNDLED_BASELINE: &str = include_str!("../assets/cold_start_baseline.json");
+
+/// Which model produced a state detection result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelSource {
+    /// Rule-based thresholds, before any model is trained
+    Heuristic,
+    /// Classifier trained on the bundled baseline
+    Baseline,
+    /// Classifier trained on the user's own labelled data
+    Personalized,
+}
+
+/// Thresholds for the heuristic classifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeuristicConfig {
+    /// Fewer input events than this in a window reads as neutral
+    pub min_events: usize,
+    /// Below this many input events per minute the user is idle
+    pub idle_events_per_minute: f32,
+    /// Sustained input at or above this rate with no switching reads as flow
+    pub focused_events_per_minute: f32,
+    /// Input at or above this rate with no switching reads as hyperfocus
+    pub hyperfocus_events_per_minute: f32,
+    /// Window switches per minute at which the user is moving between tasks
+    pub transitioning_switches_per_minute: f32,
+    /// Window switches per minute at which the user is distracted
+    pub distracted_switches_per_minute: f32,
+    /// Probability given to the chosen state; the rest is spread over the others
+    pub confidence: f32,
+}
+
+impl Default for HeuristicConfig {
+    fn default() -> Self {
+        Self {
+            min_events: 10,
+            idle_events_per_minute: 20.0,
+            focused_events_per_minute: 60.0,
+            hyperfocus_events_per_minute: 180.0,
+            transitioning_switches_per_minute: 2.0,
+            distracted_switches_per_minute: 4.0,
+            // Below the default confidence threshold, so a guess never
+            // counts as a confident detection
+            confidence: 0.6,
+        }
+    }
+}
+
+/// Activity signals the heuristic classifier looks at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivitySignals {
+    /// Keystroke, mouse and window events in the window
+    pub input_events: usize,
+    /// Input events per minute
+    pub events_per_minute: f32,
+    /// Window focus changes per minute
+    pub switches_per_minute: f32,
+}
+
+impl ActivitySignals {
+    /// Measure a window; resource samples are not user activity and are skipped
+    pub fn from_window(window: &AnalysisWindow) -> Self {
+        let input_events = window
+            .events
+            .iter()
+            .filter(|e| !matches!(e, RawEvent::ResourceUsage(_)))
+            .count();
+        let switches = window.get_window_focus_events().len().saturating_sub(1);
+        let minutes = window.duration().as_secs_f32().max(1.0) / 60.0;
+
+        Self {
+            input_events,
+            events_per_minute: input_events as f32 / minutes,
+            switches_per_minute: switches as f32 / minutes,
+        }
+    }
+}
+
+/// Rule-based classifier used until a model is trained
+#[derive(Debug, Clone, Default)]
+pub struct HeuristicClassifier {
+    config: HeuristicConfig,
+}
+
+impl HeuristicClassifier {
+    pub fn new(config: HeuristicConfig) -> Self {
+        Self { config }
+    }
+
+    /// Pick a state from activity density and switching rate
+    ///
+    /// Switching is checked first: a burst of typing spread over five apps is
+    /// not flow.
+    pub fn classify_signals(&self, signals: &ActivitySignals) -> ADHDStateType {
+        let c = &self.config;
+        if signals.input_events < c.min_events || signals.events_per_minute < c.idle_events_per_minute {
+            ADHDStateType::Neutral
+        } else if signals.switches_per_minute >= c.distracted_switches_per_minute {
+            ADHDStateType::Distracted
+        } else if signals.switches_per_minute >= c.transitioning_switches_per_minute {
+            ADHDStateType::Transitioning
+        } else if signals.events_per_minute >= c.hyperfocus_events_per_minute && signals.switches_per_minute == 0.0 {
+            ADHDStateType::Hyperfocus
+        } else if signals.events_per_minute >= c.focused_events_per_minute {
+            ADHDStateType::Flow
+        } else {
+            ADHDStateType::Neutral
+        }
+    }
+
+    /// Distribution with `confidence` on the chosen state
+    pub fn predict(&self, window: &AnalysisWindow) -> StateDistribution {
+        let state = self.classify_signals(&ActivitySignals::from_window(window));
+        let main = self.config.confidence.clamp(0.2, 1.0);
+        let rest = (1.0 - main) / 4.0;
+
+        let mut distribution = StateDistribution {
+            flow: rest,
+            hyperfocus: rest,
+            distracted: rest,
+            transitioning: rest,
+            neutral: rest,
+        };
+        match state {
+            ADHDStateType::Flow => distribution.flow = main,
+            ADHDStateType::Hyperfocus => distribution.hyperfocus = main,
+            ADHDStateType::Distracted => distribution.distracted = main,
+            ADHDStateType::Transitioning => distribution.transitioning = main,
+            ADHDStateType::Neutral => distribution.neutral = main,
+        }
+        distribution
+    }
+}
+
+/// Normalized feature values typical of one state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatePrototype {
+    pub keystroke: [f32; 10],
+    pub mouse: [f32; 8],
+    pub window: [f32; 6],
+    pub temporal: [f32; 5],
+    pub resource: [f32; 4],
+}
+
+/// Prototypes for every state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatePrototypes {
+    pub flow: StatePrototype,
+    pub hyperfocus: StatePrototype,
+    pub distracted: StatePrototype,
+    pub transitioning: StatePrototype,
+    pub neutral: StatePrototype,
+}
+
+/// Generic model the classifier is trained on before any user data exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineBundle {
+    pub version: u32,
+    /// Samples synthesized per state
+    pub samples_per_state: usize,
+    /// Largest deviation from a prototype value in a synthesized sample
+    pub spread: f32,
+    /// Seed for the synthesized samples, so every install trains the same model
+    pub seed: u64,
+    pub prototypes: StatePrototypes,
+}
+
+impl BaselineBundle {
+    /// The baseline shipped with the engine
+    pub fn bundled() -> AnalysisResult<Self> {
+        serde_json::from_str(BUNDLED_BASELINE).map_err(|e| AnalysisError::ModelLoad {
+            model: "cold_start_baseline".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Labelled samples scattered around each prototype
+    pub fn training_samples(&self) -> Vec<(FeatureVector, ADHDState)> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let p = &self.prototypes;
+        let states = [
+            (ADHDStateType::Flow, &p.flow),
+            (ADHDStateType::Hyperfocus, &p.hyperfocus),
+            (ADHDStateType::Distracted, &p.distracted),
+            (ADHDStateType::Transitioning, &p.transitioning),
+            (ADHDStateType::Neutral, &p.neutral),
+        ];
+
+        let mut samples = Vec::with_capacity(states.len() * self.samples_per_state);
+        for (state_type, prototype) in states {
+            for _ in 0..self.samples_per_state {
+                let features = FeatureVector {
+                    keystroke_features: jitter(&prototype.keystroke, self.spread, &mut rng),
+                    mouse_features: jitter(&prototype.mouse, self.spread, &mut rng),
+                    window_features: jitter(&prototype.window, self.spread, &mut rng),
+                    screenshot_features: None,
+                    temporal_features: jitter(&prototype.temporal, self.spread, &mut rng),
+                    resource_features: jitter(&prototype.resource, self.spread, &mut rng),
+                };
+                samples.push((features, label(state_type)));
+            }
+        }
+        samples
+    }
+}
+
+fn jitter<const N: usize>(values: &[f32; N], spread: f32, rng: &mut StdRng) -> [f32; N] {
+    let mut out = *values;
+    if spread > 0.0 {
+        for value in out.iter_mut() {
+            *value = (*value + rng.gen_range(-spread..=spread)).clamp(0.0, 1.0);
+        }
+    }
+    out
+}
+
+fn label(state_type: ADHDStateType) -> ADHDState {
+    ADHDState {
+        state_type,
+        confidence: 1.0,
+        flow_depth: match state_type {
+            ADHDStateType::Flow => FlowDepth::Deep,
+            ADHDStateType::Hyperfocus => FlowDepth::UltraDeep,
+            _ => FlowDepth::Shallow,
+        },
+        distraction_type: (state_type == ADHDStateType::Distracted).then_some(DistractionType::TaskSwitching),
+        timestamp: Utc::now(),
+        duration: Duration::from_secs(30),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_storage::types::{KeyModifiers, KeystrokeEvent, WindowFocusEvent};
+    use std::time::SystemTime;
+
+    fn window(keystrokes: usize, switches: usize) -> AnalysisWindow {
+        let start = SystemTime::now();
+        let base = chrono::DateTime::<Utc>::from(start);
+        let mut window = AnalysisWindow::new(start);
+        for i in 0..=switches {
+            window.add_event(RawEvent::WindowFocus(WindowFocusEvent {
+                timestamp: base + chrono::Duration::seconds(i as i64),
+                window_title: format!("Window {}", i),
+                app_name: format!("App {}", i),
+                process_id: 1,
+                duration_ms: Some(1000),
+            }));
+        }
+        for i in 0..keystrokes {
+            window.add_event(RawEvent::Keystroke(KeystrokeEvent {
+                timestamp: base + chrono::Duration::milliseconds(i as i64 * 100),
+                key_code: 65,
+                modifiers: KeyModifiers::default(),
+                inter_key_interval_ms: Some(100),
+            }));
+        }
+        window
+    }
+
+    #[test]
+    fn test_heuristic_uses_density_and_switching() {
+        let classifier = HeuristicClassifier::default();
+        let signals = |events_per_minute, switches_per_minute| ActivitySignals {
+            input_events: 100,
+            events_per_minute,
+            switches_per_minute,
+        };
+
+        assert_eq!(classifier.classify_signals(&signals(5.0, 0.0)), ADHDStateType::Neutral);
+        assert_eq!(classifier.classify_signals(&signals(90.0, 0.5)), ADHDStateType::Flow);
+        assert_eq!(classifier.classify_signals(&signals(240.0, 0.0)), ADHDStateType::Hyperfocus);
+        assert_eq!(classifier.classify_signals(&signals(90.0, 2.5)), ADHDStateType::Transitioning);
+        assert_eq!(classifier.classify_signals(&signals(240.0, 6.0)), ADHDStateType::Distracted);
+
+        // Too few events to judge, whatever the rate
+        let sparse = ActivitySignals { input_events: 3, ..signals(240.0, 0.0) };
+        assert_eq!(classifier.classify_signals(&sparse), ADHDStateType::Neutral);
+
+        let distribution = classifier.predict(&window(200, 0));
+        assert_eq!(distribution.most_likely_state().0, ADHDStateType::Hyperfocus);
+        let total = distribution.flow + distribution.hyperfocus + distribution.distracted
+            + distribution.transitioning + distribution.neutral;
+        assert!((total - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_bundled_baseline_is_deterministic() {
+        let bundle = BaselineBundle::bundled().unwrap();
+        let first = bundle.training_samples();
+        let second = bundle.training_samples();
+
+        assert_eq!(first.len(), bundle.samples_per_state * 5);
+        assert!(first.len() >= 100, "enough samples to train the classifier");
+        assert!(first.iter().all(|(features, _)| features.validate()));
+        for ((a, _), (b, _)) in first.iter().zip(&second) {
+            assert_eq!(a.window_features, b.window_features);
+        }
+        let distracted = first.iter().filter(|(_, s)| s.state_type == ADHDStateType::Distracted).count();
+        assert_eq!(distracted, bundle.samples_per_state);
+    }
+}
@@ -0,0 +1,187 @@
+//! A day's activity for the end-of-day summary
+//!
+//! AI integration asks for the user's local day with a `DailySummaryRequest`.
+//! [`DayActivityResponder`] answers with a `DayActivity` built from the work
+//! sessions in that range and the per-application focus totals, correlated to
+//! the request. Application totals are kept per UTC day, so they cover every
+//! UTC day the range touches.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use chrono::{NaiveDate, Utc};
+use skelly_jelly_event_bus::{
+    message::{DayActivity, SessionDigest},
+    BusMessage, EventBusTrait, MessagePayload, MessageType, ModuleId,
+};
+use uuid::Uuid;
+
+use crate::{
+    app_focus::{AppFocusTracker, DailyAppFocus},
+    error::AnalysisResult,
+    sessions::{SessionQuery, SessionReconstructor, WorkSession},
+};
+
+/// Summarize one day from its sessions and application focus
+pub fn day_activity(
+    request_id: Uuid,
+    date: NaiveDate,
+    sessions: &[WorkSession],
+    app_focus: &[DailyAppFocus],
+) -> DayActivity {
+    let mut digests: Vec<SessionDigest> = sessions
+        .iter()
+        .map(|session| SessionDigest {
+            start: session.start,
+            end: session.end,
+            active_minutes: (session.active_duration.as_secs() / 60) as u32,
+            flow_percent: session.flow_percent,
+            interruptions: session.interruptions,
+            work_type: session.dominant_work_type.clone(),
+        })
+        .collect();
+    digests.sort_by_key(|digest| digest.start);
+
+    let focused_minutes = digests
+        .iter()
+        .map(|digest| (digest.active_minutes as f32 * digest.flow_percent / 100.0).round() as u32)
+        .sum();
+
+    let mut flow_by_app: BTreeMap<&str, Duration> = BTreeMap::new();
+    let mut distracted = Duration::ZERO;
+    for (name, stats) in app_focus.iter().flat_map(|day| day.apps.iter()) {
+        *flow_by_app.entry(name).or_default() += stats.flow_time;
+        distracted += stats.distracted_time;
+    }
+    let top_application = flow_by_app
+        .into_iter()
+        .filter(|(_, flow)| !flow.is_zero())
+        .max_by_key(|(_, flow)| *flow)
+        .map(|(name, _)| name.to_string());
+
+    DayActivity {
+        request_id,
+        date,
+        sessions: digests,
+        focused_minutes,
+        distracted_minutes: (distracted.as_secs() / 60) as u32,
+        top_application,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Answers `DailySummaryRequest`s from the bus
+pub struct DayActivityResponder {
+    sessions: Arc<SessionReconstructor>,
+    app_focus: Arc<AppFocusTracker>,
+    event_bus: Arc<dyn EventBusTrait>,
+}
+
+impl DayActivityResponder {
+    pub fn new(
+        sessions: Arc<SessionReconstructor>,
+        app_focus: Arc<AppFocusTracker>,
+        event_bus: Arc<dyn EventBusTrait>,
+    ) -> Self {
+        Self { sessions, app_focus, event_bus }
+    }
+
+    /// Message types the responder reacts to
+    pub fn subscribed_types() -> Vec<MessageType> {
+        vec![MessageType::DailySummaryRequest]
+    }
+
+    /// Build the requested day's activity and publish it
+    pub async fn handle_message(&self, message: &BusMessage) -> AnalysisResult<Option<DayActivity>> {
+        let MessagePayload::DailySummaryRequest(request) = &message.payload else {
+            return Ok(None);
+        };
+
+        let mut query = SessionQuery {
+            from: Some(request.from),
+            to: Some(request.to),
+            ..Default::default()
+        };
+        let mut sessions = Vec::new();
+        loop {
+            let page = self.sessions.sessions(&query);
+            sessions.extend(page.sessions);
+            match page.next_cursor {
+                Some(cursor) => query.before = Some(cursor),
+                None => break,
+            }
+        }
+
+        let mut app_focus = Vec::new();
+        let mut day = request.from.date_naive();
+        while day <= request.to.date_naive() {
+            app_focus.extend(self.app_focus.day(day)?);
+            day = day.succ_opt().unwrap_or(NaiveDate::MAX);
+        }
+        let mut activity = day_activity(request.request_id, request.date, &sessions, &app_focus);
+        activity.top_application = activity.top_application.map(|name| self.app_focus.display_name(&name));
+        let reply = message.reply_to(ModuleId::AnalysisEngine, MessagePayload::DayActivity(activity.clone()));
+        self.event_bus.publish(reply).await?;
+        Ok(Some(activity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_focus::AppFocusStats;
+    use chrono::{DateTime, TimeZone};
+
+    fn session(hour: u32, minutes: u64, flow_percent: f32) -> WorkSession {
+        let start: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 3, 1, hour, 0, 0).unwrap();
+        WorkSession {
+            start,
+            end: start + chrono::Duration::minutes(minutes as i64),
+            active_duration: Duration::from_secs(minutes * 60),
+            flow_percent,
+            interruptions: 2,
+            dominant_work_type: Some("coding".to_string()),
+            application: Some("code".to_string()),
+            windows: 10,
+            open: false,
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_focus_comes_from_sessions() {
+        // Sessions arrive newest first from a query
+        let activity = day_activity(Uuid::new_v4(), date(), &[session(14, 60, 50.0), session(9, 90, 80.0)], &[]);
+
+        assert_eq!(activity.sessions.len(), 2);
+        assert!(activity.sessions[0].start < activity.sessions[1].start);
+        assert_eq!(activity.sessions[0].active_minutes, 90);
+        assert_eq!(activity.focused_minutes, 72 + 30);
+        assert_eq!(activity.top_application, None);
+    }
+
+    #[test]
+    fn test_app_focus_days_are_merged() {
+        let stats = |flow: u64, distracted: u64| AppFocusStats {
+            total_time: Duration::from_secs((flow + distracted) * 60),
+            flow_time: Duration::from_secs(flow * 60),
+            distracted_time: Duration::from_secs(distracted * 60),
+            sessions: 1,
+        };
+        let day = |offset: u64, apps: Vec<(&str, AppFocusStats)>| DailyAppFocus {
+            date: date() + chrono::Days::new(offset),
+            apps: apps.into_iter().map(|(name, stats)| (name.to_string(), stats)).collect(),
+            categories: BTreeMap::new(),
+        };
+        let days = [
+            day(0, vec![("code", stats(40, 10)), ("figma", stats(50, 0))]),
+            day(1, vec![("code", stats(20, 0)), ("slack", stats(5, 40))]),
+        ];
+
+        let activity = day_activity(Uuid::new_v4(), date(), &[session(9, 90, 80.0)], &days);
+        assert_eq!(activity.focused_minutes, 72);
+        assert_eq!(activity.distracted_minutes, 50);
+        assert_eq!(activity.top_application.as_deref(), Some("code"));
+    }
+}
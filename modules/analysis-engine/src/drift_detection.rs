@@ -0,0 +1,157 @@
+//! Drift detection for the online-learning model
+//!
+//! Online updates can slowly make the live model worse. The detector keeps a
+//! sliding window of labelled feedback and, for every sample, whether the live
+//! model and a frozen baseline model got it right. Drift is reported when the
+//! live model's recent accuracy falls more than a threshold below the baseline's
+//! on the same samples.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use serde::{Deserialize, Serialize};
+
+/// When a drop in accuracy counts as drift
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftConfig {
+    /// Labelled samples kept for comparison
+    pub window_size: usize,
+    /// Samples needed before drift can be reported
+    pub min_samples: usize,
+    /// Accuracy the live model may lose against the baseline before it counts as drift
+    pub max_accuracy_drop: f32,
+    /// Minimum time between two drift reports
+    pub cooldown: Duration,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 200,
+            min_samples: 30,
+            max_accuracy_drop: 0.1,
+            cooldown: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Accuracy of both models over the current window when drift was found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftReport {
+    pub recent_accuracy: f32,
+    pub baseline_accuracy: f32,
+    pub samples: usize,
+}
+
+impl DriftReport {
+    pub fn accuracy_drop(&self) -> f32 {
+        self.baseline_accuracy - self.recent_accuracy
+    }
+}
+
+/// Sliding-window comparison of the live model against a frozen baseline
+#[derive(Debug)]
+pub struct DriftDetector {
+    config: DriftConfig,
+    /// (live model correct, baseline correct) per labelled sample
+    outcomes: VecDeque<(bool, bool)>,
+    last_drift: Option<Instant>,
+}
+
+impl DriftDetector {
+    pub fn new(config: DriftConfig) -> Self {
+        Self {
+            outcomes: VecDeque::with_capacity(config.window_size),
+            config,
+            last_drift: None,
+        }
+    }
+
+    /// Record one labelled sample; returns a report if it tips the window into drift
+    pub fn record(&mut self, live_correct: bool, baseline_correct: bool) -> Option<DriftReport> {
+        self.outcomes.push_back((live_correct, baseline_correct));
+        while self.outcomes.len() > self.config.window_size {
+            self.outcomes.pop_front();
+        }
+
+        if self.outcomes.len() < self.config.min_samples {
+            return None;
+        }
+        if self.last_drift.is_some_and(|at| at.elapsed() < self.config.cooldown) {
+            return None;
+        }
+
+        let report = self.report();
+        if report.accuracy_drop() > self.config.max_accuracy_drop {
+            self.last_drift = Some(Instant::now());
+            Some(report)
+        } else {
+            None
+        }
+    }
+
+    /// Accuracy of both models over the current window
+    pub fn report(&self) -> DriftReport {
+        let samples = self.outcomes.len();
+        let accuracy = |correct: usize| if samples == 0 { 0.0 } else { correct as f32 / samples as f32 };
+        DriftReport {
+            recent_accuracy: accuracy(self.outcomes.iter().filter(|(live, _)| *live).count()),
+            baseline_accuracy: accuracy(self.outcomes.iter().filter(|(_, baseline)| *baseline).count()),
+            samples,
+        }
+    }
+
+    /// Forget the window, e.g. after the live model was rolled back
+    pub fn reset(&mut self) {
+        self.outcomes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> DriftDetector {
+        DriftDetector::new(DriftConfig {
+            window_size: 20,
+            min_samples: 10,
+            max_accuracy_drop: 0.2,
+            cooldown: Duration::from_secs(600),
+        })
+    }
+
+    #[test]
+    fn test_drift_needs_enough_samples_and_a_real_drop() {
+        let mut detector = detector();
+        for _ in 0..9 {
+            assert_eq!(detector.record(false, true), None);
+        }
+        let report = detector.record(false, true).unwrap();
+        assert_eq!(report, DriftReport { recent_accuracy: 0.0, baseline_accuracy: 1.0, samples: 10 });
+
+        let mut steady = DriftDetector::new(DriftConfig { min_samples: 10, ..Default::default() });
+        for i in 0..50 {
+            // Both models miss the same samples: no drift however poor they are
+            assert_eq!(steady.record(i % 2 == 0, i % 2 == 0), None);
+        }
+    }
+
+    #[test]
+    fn test_cooldown_and_reset() {
+        let mut detector = detector();
+        for _ in 0..10 {
+            detector.record(false, true);
+        }
+        assert!(detector.last_drift.is_some());
+        assert_eq!(detector.record(false, true), None);
+
+        detector.reset();
+        assert_eq!(detector.report().samples, 0);
+        detector.last_drift = None;
+        for _ in 0..15 {
+            detector.record(true, true);
+        }
+        assert_eq!(detector.report().recent_accuracy, 1.0);
+    }
+}
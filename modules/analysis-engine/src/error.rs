@@ -1,10 +1,37 @@
 //! Error types for the analysis engine module
-
+//!
+//! Every error belongs to an [`ErrorCategory`] and carries recovery hints the
+//! event bus [`RecoverySystem`] uses to pick a strategy, so a model that fails
+//! to load switches the engine to its rule-based fallback while a bad feature
+//! window is simply dropped. The engine and the capture feed report their
+//! failures with [`AnalysisError::report`] when given a recovery system.
+
+use std::time::Duration;
 use thiserror::Error;
+use skelly_jelly_event_bus::{
+    EscalationLevel, ModuleId, RecoveryHint, RecoverySystem,
+    error_logging::{CorrelationId, ErrorSeverity as BusErrorSeverity},
+    recovery::IncidentId,
+};
+
+/// Fallback the engine runs in when a trained model is unavailable
+const RULE_BASED_FALLBACK: &str = "rule_based";
+
+/// Longest retry delay suggested for a timed-out operation
+const MAX_RETRY_HINT: Duration = Duration::from_secs(5);
 
 /// Analysis engine error types
 #[derive(Error, Debug)]
 pub enum AnalysisError {
+    #[error("Model {model} failed to load: {reason}")]
+    ModelLoad { model: String, reason: String },
+
+    #[error("Model not found: {model_name}")]
+    ModelNotFound { model_name: String },
+
+    #[error("Failed to load data from {path}: {message}")]
+    DataLoadError { path: String, message: String },
+
     #[error("Model inference failed: {model} - {source}")]
     InferenceError {
         model: String,
@@ -30,9 +57,6 @@ pub enum AnalysisError {
     #[error("Event processing error: {message}")]
     EventProcessingError { message: String },
 
-    #[error("Model not found: {model_name}")]
-    ModelNotFound { model_name: String },
-
     #[error("Invalid feature vector: {reason}")]
     InvalidFeatureVector { reason: String },
 
@@ -95,6 +119,12 @@ pub enum AnalysisError {
 
     #[error("Operation '{operation}' timed out after {timeout_ms}ms")]
     TimeoutError { operation: String, timeout_ms: u64 },
+
+    #[error("PRIVACY VIOLATION: {operation} attempted to reach {destination} during local inference")]
+    PrivacyViolation { operation: String, destination: String },
+
+    #[error("Feature export refused: {reason}")]
+    ExportRefused { reason: String },
 }
 
 /// Result type for analysis operations
@@ -128,6 +158,10 @@ impl AnalysisError {
             AnalysisError::InvalidInput { .. } => false,
             AnalysisError::TrainingFailed { .. } => false,
             AnalysisError::PredictionFailed { .. } => true,
+            AnalysisError::PrivacyViolation { .. } => false,
+            AnalysisError::ExportRefused { .. } => false,
+            AnalysisError::ModelLoad { .. } => true,
+            AnalysisError::DataLoadError { .. } => false,
         }
     }
 
@@ -158,8 +192,118 @@ impl AnalysisError {
             AnalysisError::InvalidInput { .. } => ErrorSeverity::Low,
             AnalysisError::TrainingFailed { .. } => ErrorSeverity::High,
             AnalysisError::PredictionFailed { .. } => ErrorSeverity::Medium,
+            AnalysisError::PrivacyViolation { .. } => ErrorSeverity::Critical,
+            AnalysisError::ExportRefused { .. } => ErrorSeverity::Low,
+            AnalysisError::ModelLoad { .. } => ErrorSeverity::Critical,
+            AnalysisError::DataLoadError { .. } => ErrorSeverity::Medium,
+        }
+    }
+
+    /// The broad class of failure, which decides how it is recovered
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            AnalysisError::ModelLoad { .. }
+            | AnalysisError::ModelNotFound { .. }
+            | AnalysisError::DataLoadError { .. } => ErrorCategory::ModelLoad,
+            AnalysisError::FeatureExtractionError { .. }
+            | AnalysisError::InvalidFeatureVector { .. }
+            | AnalysisError::InsufficientData { .. }
+            | AnalysisError::ScreenshotError { .. }
+            | AnalysisError::WindowError { .. } => ErrorCategory::FeatureExtraction,
+            AnalysisError::TimeoutError { .. } | AnalysisError::ConcurrencyError { .. } => ErrorCategory::Timeout,
+            AnalysisError::ResourceExhausted { .. } | AnalysisError::MemoryError { .. } => {
+                ErrorCategory::ResourceExhausted
+            }
+            AnalysisError::PrivacyViolation { .. } => ErrorCategory::PrivacyViolation,
+            AnalysisError::InvalidInput { .. }
+            | AnalysisError::InvalidFeedback { .. }
+            | AnalysisError::SerializationError { .. } => ErrorCategory::InvalidInput,
+            AnalysisError::InferenceError { .. }
+            | AnalysisError::PredictionFailed { .. }
+            | AnalysisError::MathError { .. }
+            | AnalysisError::TrainingFailed { .. }
+            | AnalysisError::ModelPerformanceDegraded { .. }
+            | AnalysisError::ValidationFailed { .. } => ErrorCategory::Model,
+            AnalysisError::ConfigError { .. } | AnalysisError::ExportRefused { .. } => ErrorCategory::Configuration,
+            AnalysisError::EventProcessingError { .. }
+            | AnalysisError::EventBusError { .. }
+            | AnalysisError::IoError { .. }
+            | AnalysisError::ImageError { .. } => ErrorCategory::Io,
+        }
+    }
+
+    /// What the recovery system should do about this error, most preferred first
+    pub fn recovery_hints(&self) -> Vec<RecoveryHint> {
+        match self {
+            // Nothing can reload a model in place, so fall back until the next start
+            AnalysisError::ModelLoad { .. } | AnalysisError::ModelNotFound { .. } => {
+                vec![RecoveryHint::Degrade { fallback_mode: RULE_BASED_FALLBACK.to_string() }]
+            }
+            AnalysisError::TimeoutError { timeout_ms, .. } => vec![
+                RecoveryHint::RetryAfter { delay: Duration::from_millis(*timeout_ms).min(MAX_RETRY_HINT) },
+                RecoveryHint::ReduceLoad { scale_factor: 0.5 },
+            ],
+            AnalysisError::ResourceExhausted { .. } | AnalysisError::MemoryError { .. } => vec![
+                RecoveryHint::ClearCaches { cache_names: vec!["inference_cache".to_string(), "feature_cache".to_string()] },
+                RecoveryHint::ReduceLoad { scale_factor: 0.5 },
+            ],
+            AnalysisError::PrivacyViolation { .. } => {
+                vec![RecoveryHint::Escalate { level: EscalationLevel::Emergency }]
+            }
+            AnalysisError::ModelPerformanceDegraded { .. } => {
+                vec![RecoveryHint::Degrade { fallback_mode: RULE_BASED_FALLBACK.to_string() }]
+            }
+            AnalysisError::ConfigError { .. } => vec![RecoveryHint::Escalate { level: EscalationLevel::Manual }],
+            _ => match self.category() {
+                ErrorCategory::FeatureExtraction | ErrorCategory::InvalidInput => vec![RecoveryHint::DiscardInput],
+                _ if self.is_recoverable() => {
+                    vec![RecoveryHint::RetryAfter { delay: Duration::from_millis(100) }]
+                }
+                _ => vec![],
+            },
         }
     }
+
+    /// Open an incident for this error in the event bus recovery system
+    pub async fn report(
+        &self,
+        recovery: &RecoverySystem,
+        correlation_id: CorrelationId,
+    ) -> skelly_jelly_event_bus::EventBusResult<IncidentId> {
+        recovery
+            .handle_incident_with_hints(
+                correlation_id,
+                ModuleId::AnalysisEngine,
+                self.severity().into(),
+                format!("{:?}", self.category()),
+                format!("{:?} error: {}", self.category(), self),
+                self.recovery_hints(),
+            )
+            .await
+    }
+}
+
+/// Broad classes of analysis failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// A model or its training data could not be loaded
+    ModelLoad,
+    /// Features could not be extracted from the current window
+    FeatureExtraction,
+    /// An operation ran out of time or could not get a slot
+    Timeout,
+    /// Memory or another resource ran out
+    ResourceExhausted,
+    /// Local processing tried to reach the network
+    PrivacyViolation,
+    /// Bad input from outside the engine
+    InvalidInput,
+    /// A loaded model failed or produced unusable output
+    Model,
+    /// The engine is misconfigured
+    Configuration,
+    /// Event bus, file, or image I/O failed
+    Io,
 }
 
 /// Error severity levels
@@ -171,6 +315,16 @@ pub enum ErrorSeverity {
     Critical = 3,
 }
 
+impl From<ErrorSeverity> for BusErrorSeverity {
+    fn from(severity: ErrorSeverity) -> Self {
+        match severity {
+            ErrorSeverity::Low => BusErrorSeverity::Warning,
+            ErrorSeverity::Medium => BusErrorSeverity::Error,
+            ErrorSeverity::High | ErrorSeverity::Critical => BusErrorSeverity::Critical,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +347,44 @@ mod tests {
         assert!(error.is_recoverable());
         assert_eq!(error.severity(), ErrorSeverity::Medium);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_categories_carry_recovery_hints() {
+        let load = AnalysisError::ModelLoad {
+            model: "random_forest".to_string(),
+            reason: "checksum mismatch".to_string(),
+        };
+        assert_eq!(load.category(), ErrorCategory::ModelLoad);
+        assert_eq!(
+            load.recovery_hints(),
+            vec![RecoveryHint::Degrade { fallback_mode: RULE_BASED_FALLBACK.to_string() }]
+        );
+
+        let timeout = AnalysisError::TimeoutError {
+            operation: "acquire_inference_permit".to_string(),
+            timeout_ms: 60_000,
+        };
+        assert_eq!(timeout.category(), ErrorCategory::Timeout);
+        assert_eq!(timeout.recovery_hints()[0], RecoveryHint::RetryAfter { delay: MAX_RETRY_HINT });
+
+        let bad_window = AnalysisError::FeatureExtractionError {
+            feature_type: "keystroke".to_string(),
+            reason: "no events".to_string(),
+        };
+        assert_eq!(bad_window.recovery_hints(), vec![RecoveryHint::DiscardInput]);
+    }
+
+    #[test]
+    fn test_privacy_violations_escalate_instead_of_retrying() {
+        let violation = AnalysisError::PrivacyViolation {
+            operation: "local_inference".to_string(),
+            destination: "203.0.113.7:443".to_string(),
+        };
+        assert_eq!(violation.category(), ErrorCategory::PrivacyViolation);
+        assert!(!violation.is_recoverable());
+        assert_eq!(
+            violation.recovery_hints(),
+            vec![RecoveryHint::Escalate { level: EscalationLevel::Emergency }]
+        );
+    }
+}
@@ -27,6 +27,7 @@ use crate::{
     inference::{InferenceEngine, InferencePriority},
     sliding_window::{AnalysisWindow, SlidingWindowManager},
     state_detection::{StateDetectionEngine, StateDetectionResult},
+    state_smoothing::{SmoothingConfig, SmoothingMetrics, StateSmoother},
     types::AnalysisResult as AnalysisResultType,
 };
 
@@ -58,6 +59,9 @@ pub struct EventBusIntegration {
     
     /// Current processing status
     processing_status: Arc<RwLock<ProcessingStatus>>,
+    
+    /// Transition smoothing applied before results are sent
+    smoother: Arc<Mutex<StateSmoother>>,
 }
 
 /// Configuration for event bus integration
@@ -90,6 +94,10 @@ pub struct EventBusConfig {
     /// Performance monitoring
     pub enable_metrics: bool,
     pub metrics_update_interval_secs: u64,
+    
+    /// Transition smoothing for published states
+    #[serde(default)]
+    pub smoothing: SmoothingConfig,
 }
 
 impl Default for EventBusConfig {
@@ -111,6 +119,7 @@ impl Default for EventBusConfig {
             ],
             enable_metrics: true,
             metrics_update_interval_secs: 30,
+            smoothing: SmoothingConfig::default(),
         }
     }
 }
@@ -192,6 +201,8 @@ impl EventBusIntegration {
         let window_duration = Duration::from_secs(config.window_size_secs);
         let window_overlap = Duration::from_secs(config.window_overlap_secs);
 
+        let smoother = StateSmoother::new(config.smoothing.clone());
+
         let integration = Self {
             event_bus,
             state_detector,
@@ -204,6 +215,7 @@ impl EventBusIntegration {
             metrics: Arc::new(RwLock::new(EventProcessingMetrics::default())),
             result_sender: Arc::new(Mutex::new(None)),
             processing_status: Arc::new(RwLock::new(ProcessingStatus::default())),
+            smoother: Arc::new(Mutex::new(smoother)),
         };
 
         Ok(integration)
@@ -227,6 +239,15 @@ impl EventBusIntegration {
         // Register event handlers
         self.register_event_handlers().await?;
 
+        // Train on the bundled baseline in the background; the heuristic
+        // classifier answers until it is ready
+        let state_detector = Arc::clone(&self.state_detector);
+        tokio::spawn(async move {
+            if let Err(e) = state_detector.load_baseline_model().await {
+                eprintln!("Failed to load baseline model, staying on heuristics: {}", e);
+            }
+        });
+
         // Start analysis timer
         self.start_analysis_timer().await?;
 
@@ -264,6 +285,13 @@ impl EventBusIntegration {
                 })?;
         }
 
+        // Sleep and wake reshape the windows instead of adding to them
+        let power_handler = Arc::new(SystemPowerHandler::new(Arc::clone(&self.window_manager)));
+        self.event_bus.subscribe("system_power", power_handler).await
+            .map_err(|e| AnalysisError::EventBusError {
+                message: format!("Failed to subscribe to 'system_power': {}", e),
+            })?;
+
         println!("Event handlers registered for {} event types", self.config.event_types.len());
         Ok(())
     }
@@ -276,6 +304,7 @@ impl EventBusIntegration {
         let result_sender = Arc::clone(&self.result_sender);
         let metrics = Arc::clone(&self.metrics);
         let processing_status = Arc::clone(&self.processing_status);
+        let smoother = Arc::clone(&self.smoother);
         let config = self.config.clone();
 
         tokio::spawn(async move {
@@ -311,6 +340,7 @@ impl EventBusIntegration {
                         &window,
                         &state_detector,
                         &inference_engine,
+                        &smoother,
                         config.processing_priority,
                     ).await {
                         Ok(result) => {
@@ -344,6 +374,7 @@ impl EventBusIntegration {
         window: &AnalysisWindow,
         state_detector: &StateDetectionEngine,
         inference_engine: &InferenceEngine,
+        smoother: &Mutex<StateSmoother>,
         priority: InferencePriority,
     ) -> AnalysisResult<AnalysisResultType> {
         let start_time = Instant::now();
@@ -353,12 +384,21 @@ impl EventBusIntegration {
             .infer_with_priority(window, priority)
             .await?;
 
+        // Hold back flaps before the state goes out
+        let smoothed = smoother
+            .lock()
+            .map_err(|_| AnalysisError::ConcurrencyError {
+                operation: "smooth_state".to_string(),
+            })?
+            .smooth(&detection_result.state_distribution);
+        let state = smoothed.apply_to(&detection_result.detected_state);
+
         // Create analysis result
         let analysis_result = AnalysisResultType {
             window_id: window.window_id,
             timestamp: std::time::SystemTime::now(),
-            state: detection_result.detected_state,
-            confidence: detection_result.confidence,
+            confidence: state.confidence,
+            state,
             metrics: crate::metrics::BehavioralMetrics::default(), // Would be computed from features
             work_context: None, // Would come from screenshot analysis
             intervention_readiness: detection_result.intervention_readiness,
@@ -460,6 +500,11 @@ impl EventBusIntegration {
         self.metrics.read().await.clone()
     }
 
+    /// Flaps suppressed and delay added by transition smoothing
+    pub fn smoothing_metrics(&self) -> SmoothingMetrics {
+        self.smoother.lock().map(|smoother| smoother.metrics().clone()).unwrap_or_default()
+    }
+
     /// Get current processing status
     pub async fn get_status(&self) -> ProcessingStatus {
         self.processing_status.read().await.clone()
@@ -489,6 +534,7 @@ impl EventBusIntegration {
                 &window,
                 &self.state_detector,
                 &self.inference_engine,
+                &self.smoother,
                 self.config.processing_priority,
             ).await {
                 Ok(result) => {
@@ -564,6 +610,39 @@ impl MessageHandler for BehavioralEventHandler {
     }
 }
 
+/// Keeps windows from spanning a system sleep
+///
+/// On sleep the open window is completed as it stands; on wake it is
+/// replaced by a fresh one, so the first window after wake doesn't cover the
+/// hours the machine was suspended.
+pub struct SystemPowerHandler {
+    window_manager: Arc<RwLock<SlidingWindowManager>>,
+}
+
+impl SystemPowerHandler {
+    pub fn new(window_manager: Arc<RwLock<SlidingWindowManager>>) -> Self {
+        Self { window_manager }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for SystemPowerHandler {
+    async fn handle_message(&self, message: Message) -> EventHandlerResult {
+        let event: serde_json::Value = serde_json::from_slice(&message.payload)
+            .map_err(|e| format!("Invalid system power event: {}", e))?;
+
+        let mut manager = self.window_manager.write().await;
+        match event.get("transition").and_then(|t| t.as_str()) {
+            Some("sleep") => {
+                manager.advance_window().map_err(|e| e.to_string())?;
+            }
+            Some("wake") => manager.rebaseline(std::time::SystemTime::now()),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
 impl BehavioralEventHandler {
     /// Parse behavioral event from message
     async fn parse_behavioral_event(&self, message: &Message) -> AnalysisResult<RawEvent> {
@@ -593,6 +672,14 @@ impl BehavioralEventHandler {
                         })?;
                 Ok(RawEvent::MouseMove(mouse_event))
             }
+            "mouse_trajectory" => {
+                let trajectory_event: skelly_jelly_storage::types::MouseTrajectoryEvent = 
+                    serde_json::from_value(event_data)
+                        .map_err(|e| AnalysisError::InvalidInput {
+                            message: format!("Failed to parse mouse trajectory event: {}", e),
+                        })?;
+                Ok(RawEvent::MouseTrajectory(trajectory_event))
+            }
             "mouse_click" => {
                 let mouse_event: skelly_jelly_storage::types::MouseClickEvent = 
                     serde_json::from_value(event_data)
@@ -628,6 +715,7 @@ impl BehavioralEventHandler {
         match event {
             RawEvent::Keystroke(_) => "keystroke".to_string(),
             RawEvent::MouseMove(_) => "mouse_move".to_string(),
+            RawEvent::MouseTrajectory(_) => "mouse_trajectory".to_string(),
             RawEvent::MouseClick(_) => "mouse_click".to_string(),
             RawEvent::WindowFocus(_) => "window_focus".to_string(),
             RawEvent::ResourceUsage(_) => "resource_usage".to_string(),
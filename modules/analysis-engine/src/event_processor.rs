@@ -1,6 +1,7 @@
 //! Event processing pipeline for behavioral analysis
 
 use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
 use tokio::sync::mpsc;
 
 use crate::{
@@ -12,7 +13,7 @@ use crate::{
     sliding_window::{AnalysisWindow, SlidingWindowManager},
     types::AnalysisResult as AnalysisResultType,
 };
-use skelly_jelly_storage::types::{EventBatch, RawEvent, ScreenshotId};
+use skelly_jelly_storage::types::{AnalysisCheckpoint, EventBatch, RawEvent, ScreenshotId};
 
 /// Main event processor that coordinates analysis pipeline
 pub struct EventProcessor {
@@ -38,6 +39,15 @@ pub struct EventProcessor {
     total_events_processed: u64,
     total_windows_analyzed: u64,
     avg_processing_time_ms: f32,
+    
+    /// Events before this were analyzed before a restart and are dropped
+    resume_offset: Option<DateTime<Utc>>,
+    
+    /// Last window completed from the event stream and its end
+    last_window: Option<(uuid::Uuid, DateTime<Utc>)>,
+    
+    /// Windows completed from the event stream, including before a restart
+    windows_completed: u64,
 }
 
 impl EventProcessor {
@@ -57,6 +67,9 @@ impl EventProcessor {
             total_events_processed: 0,
             total_windows_analyzed: 0,
             avg_processing_time_ms: 0.0,
+            resume_offset: None,
+            last_window: None,
+            windows_completed: 0,
         }
     }
 
@@ -76,22 +89,34 @@ impl EventProcessor {
             total_events_processed: 0,
             total_windows_analyzed: 0,
             avg_processing_time_ms: 0.0,
+            resume_offset: None,
+            last_window: None,
+            windows_completed: 0,
         }
     }
 
-    /// Process a batch of events from storage
+    /// Process a batch of events from storage, returning the last completed window's result
     pub async fn process_event_batch(&mut self, batch: EventBatch) -> AnalysisResult<Option<AnalysisResultType>> {
+        Ok(self.process_event_batch_all(batch).await?.pop())
+    }
+
+    /// Process a batch of events from storage, analyzing every window it completes
+    ///
+    /// Windows without enough data to analyze are completed without a result.
+    pub async fn process_event_batch_all(&mut self, batch: EventBatch) -> AnalysisResult<Vec<AnalysisResultType>> {
         let start_time = Instant::now();
         
         // Add events to sliding window
-        let mut completed_window = None;
+        let mut completed_windows = Vec::new();
         
         for event in batch.events {
+            if self.already_analyzed(&event) {
+                continue;
+            }
             self.total_events_processed += 1;
             
             if let Some(window) = self.window_manager.add_event(event)? {
-                completed_window = Some(window);
-                break; // Process one window at a time
+                completed_windows.push(window);
             }
         }
         
@@ -100,32 +125,84 @@ impl EventProcessor {
             self.window_manager.add_screenshot(screenshot_id);
         }
         
-        // Process completed window if available
-        if let Some(window) = completed_window {
-            let result = self.analyze_window(window).await?;
-            
+        let mut results = Vec::with_capacity(completed_windows.len());
+        for window in completed_windows {
+            if let Some(result) = self.analyze_completed_window(window).await? {
+                results.push(result);
+            }
+        }
+        
+        if !results.is_empty() {
             // Update performance metrics
             let processing_time = start_time.elapsed().as_millis() as f32;
             self.update_performance_metrics(processing_time);
-            
-            return Ok(Some(result));
         }
         
-        Ok(None)
+        Ok(results)
     }
 
     /// Process individual events in real-time
     pub async fn process_event(&mut self, event: RawEvent) -> AnalysisResult<Option<AnalysisResultType>> {
+        if self.already_analyzed(&event) {
+            return Ok(None);
+        }
         self.total_events_processed += 1;
         
         if let Some(window) = self.window_manager.add_event(event)? {
-            let result = self.analyze_window(window).await?;
-            return Ok(Some(result));
+            return self.analyze_completed_window(window).await;
         }
         
         Ok(None)
     }
 
+    /// Continue after a restart from a saved checkpoint
+    ///
+    /// Events from before the checkpoint's offset are dropped from then on;
+    /// they are in windows analyzed before the restart.
+    pub fn resume_from(&mut self, checkpoint: &AnalysisCheckpoint) {
+        self.resume_offset = Some(checkpoint.offset);
+        self.last_window = checkpoint.last_window_id.zip(checkpoint.last_window_end);
+        self.windows_completed = checkpoint.windows_analyzed;
+    }
+
+    /// Checkpoint of the progress so far, for `consumer`
+    ///
+    /// The offset is the oldest event in the open window: everything before it
+    /// is in a completed window, everything from it on has to be replayed after
+    /// a restart.
+    pub fn checkpoint(&self, consumer: &str) -> AnalysisCheckpoint {
+        let window = self.window_manager.current_window();
+        let offset = window
+            .events
+            .iter()
+            .map(RawEvent::timestamp)
+            .min()
+            .unwrap_or_else(|| DateTime::<Utc>::from(window.start_time));
+        AnalysisCheckpoint {
+            consumer: consumer.to_string(),
+            offset,
+            last_window_id: self.last_window.map(|(id, _)| id),
+            last_window_end: self.last_window.map(|(_, end)| end),
+            windows_analyzed: self.windows_completed,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn already_analyzed(&self, event: &RawEvent) -> bool {
+        self.resume_offset.is_some_and(|offset| event.timestamp() < offset)
+    }
+
+    /// Analyze a window the stream completed, `None` when it has too little data
+    async fn analyze_completed_window(&mut self, window: AnalysisWindow) -> AnalysisResult<Option<AnalysisResultType>> {
+        self.last_window = Some((window.window_id, DateTime::<Utc>::from(window.end_time)));
+        self.windows_completed += 1;
+        match self.analyze_window(window).await {
+            Ok(result) => Ok(Some(result)),
+            Err(AnalysisError::InsufficientData { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Add a screenshot reference to current window
     pub fn add_screenshot(&mut self, screenshot_id: ScreenshotId) {
         self.window_manager.add_screenshot(screenshot_id);
@@ -141,6 +218,13 @@ impl EventProcessor {
         Ok(None)
     }
 
+    /// Analyze the events collected so far without closing the current window
+    pub async fn analyze_current_window(&mut self) -> AnalysisResult<AnalysisResultType> {
+        let mut window = self.window_manager.current_window().clone();
+        window.calculate_quality_score();
+        self.analyze_window(window).await
+    }
+
     /// Analyze a completed window
     async fn analyze_window(&mut self, mut window: AnalysisWindow) -> AnalysisResult<AnalysisResultType> {
         let start_time = Instant::now();
@@ -461,6 +545,61 @@ mod tests {
         assert!(stats.feature_count > 0);
     }
 
+    fn keystroke(timestamp: chrono::DateTime<Utc>) -> RawEvent {
+        RawEvent::Keystroke(KeystrokeEvent {
+            timestamp,
+            key_code: 65,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: Some(100),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_offset_is_oldest_open_event() {
+        let mut processor = EventProcessor::new();
+        let now = Utc::now();
+        let batch = EventBatch {
+            window_id: uuid::Uuid::new_v4(),
+            start_time: now,
+            end_time: now,
+            events: vec![keystroke(now - chrono::Duration::seconds(10)), keystroke(now - chrono::Duration::seconds(20))],
+            screenshot_refs: vec![],
+        };
+        processor.process_event_batch(batch).await.unwrap();
+
+        let checkpoint = processor.checkpoint("analysis_engine");
+        assert_eq!(checkpoint.offset, now - chrono::Duration::seconds(20));
+        assert_eq!(checkpoint.windows_analyzed, 0);
+        assert!(checkpoint.last_window_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resume_drops_events_before_offset() {
+        let mut processor = EventProcessor::new();
+        let now = Utc::now();
+        let window_id = uuid::Uuid::new_v4();
+        processor.resume_from(&AnalysisCheckpoint {
+            consumer: "analysis_engine".to_string(),
+            offset: now,
+            last_window_id: Some(window_id),
+            last_window_end: Some(now),
+            windows_analyzed: 12,
+            updated_at: now,
+        });
+
+        // Redelivered from before the restart
+        assert!(processor.process_event(keystroke(now - chrono::Duration::seconds(1))).await.unwrap().is_none());
+        assert_eq!(processor.total_events_processed, 0);
+
+        processor.process_event(keystroke(now + chrono::Duration::seconds(1))).await.unwrap();
+        assert_eq!(processor.total_events_processed, 1);
+
+        let checkpoint = processor.checkpoint("analysis_engine");
+        assert_eq!(checkpoint.offset, now + chrono::Duration::seconds(1));
+        assert_eq!(checkpoint.last_window_id, Some(window_id));
+        assert_eq!(checkpoint.windows_analyzed, 12);
+    }
+
     #[tokio::test]
     async fn test_insufficient_data_handling() {
         let mut processor = EventProcessor::new();
@@ -1,6 +1,7 @@
 //! Mouse feature extraction for behavioral analysis
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::f32::consts::PI;
 
 use crate::{
@@ -31,18 +32,27 @@ impl MouseFeatureExtractor {
     /// Extract mouse movement features
     fn extract_movement_features(&self, window: &AnalysisWindow) -> AnalysisResult<[f32; 3]> {
         let mouse_events = window.get_mouse_move_events();
+        let trajectories = window.get_mouse_trajectory_events();
         
-        if mouse_events.is_empty() {
+        if mouse_events.is_empty() && trajectories.is_empty() {
             return Ok([0.0; 3]);
         }
 
-        // Calculate velocity statistics
-        let velocities: Vec<f32> = mouse_events.iter().map(|e| e.velocity).collect();
-        let mean_velocity = velocities.iter().sum::<f32>() / velocities.len() as f32;
-        
-        let velocity_variance = velocities.iter()
-            .map(|&v| (v - mean_velocity).powi(2))
-            .sum::<f32>() / velocities.len() as f32;
+        // Calculate velocity statistics, pooling raw moves with trajectory summaries
+        let (mut count, mut sum, mut sum_sq) = (0.0f32, 0.0f32, 0.0f32);
+        for event in &mouse_events {
+            count += 1.0;
+            sum += event.velocity;
+            sum_sq += event.velocity * event.velocity;
+        }
+        for trajectory in &trajectories {
+            let n = trajectory.sample_count as f32;
+            count += n;
+            sum += trajectory.mean_velocity * n;
+            sum_sq += (trajectory.velocity_std.powi(2) + trajectory.mean_velocity.powi(2)) * n;
+        }
+        let mean_velocity = if count > 0.0 { sum / count } else { 0.0 };
+        let velocity_variance = if count > 0.0 { (sum_sq / count - mean_velocity.powi(2)).max(0.0) } else { 0.0 };
 
         // Calculate movement smoothness using jerk (change in acceleration)
         let smoothness = self.calculate_movement_smoothness(window)?;
@@ -84,9 +94,7 @@ impl MouseFeatureExtractor {
 
     /// Extract mouse behavior patterns
     fn extract_pattern_features(&self, window: &AnalysisWindow) -> AnalysisResult<[f32; 2]> {
-        let mouse_events = window.get_mouse_move_events();
-        
-        if mouse_events.is_empty() {
+        if window.get_mouse_move_events().is_empty() && window.get_mouse_trajectory_events().is_empty() {
             return Ok([0.0; 2]);
         }
 
@@ -102,14 +110,14 @@ impl MouseFeatureExtractor {
     /// Calculate movement smoothness using derivative analysis
     fn calculate_movement_smoothness(&self, window: &AnalysisWindow) -> AnalysisResult<f32> {
         let mouse_events = window.get_mouse_move_events();
-        
-        if mouse_events.len() < 3 {
-            return Ok(0.0);
-        }
 
-        // Calculate direction changes and acceleration changes
+        // Trajectory summaries carry their own turn counts
         let mut direction_changes = 0;
         let mut total_movements = 0;
+        for trajectory in window.get_mouse_trajectory_events() {
+            direction_changes += trajectory.direction_changes as usize;
+            total_movements += trajectory.sample_count.saturating_sub(1) as usize;
+        }
 
         for i in 2..mouse_events.len() {
             let p1 = (mouse_events[i-2].x, mouse_events[i-2].y);
@@ -204,17 +212,23 @@ impl MouseFeatureExtractor {
     /// Calculate movement pattern regularity
     fn calculate_movement_regularity(&self, window: &AnalysisWindow) -> AnalysisResult<f32> {
         let mouse_events = window.get_mouse_move_events();
+        let velocities: Vec<f32> = if mouse_events.is_empty() {
+            // Summarized at capture: compare successive windows instead of successive moves
+            window.get_mouse_trajectory_events().iter().map(|t| t.mean_velocity).collect()
+        } else {
+            mouse_events.iter().map(|e| e.velocity).collect()
+        };
         
-        if mouse_events.len() < 5 {
+        if velocities.len() < 5 {
             return Ok(0.0);
         }
 
         // Analyze movement patterns using velocity changes
         let mut velocity_changes = Vec::new();
         
-        for i in 1..mouse_events.len() {
-            let prev_velocity = mouse_events[i-1].velocity;
-            let curr_velocity = mouse_events[i].velocity;
+        for i in 1..velocities.len() {
+            let prev_velocity = velocities[i-1];
+            let curr_velocity = velocities[i];
             
             if prev_velocity > 0.0 {
                 let velocity_change = (curr_velocity - prev_velocity).abs() / prev_velocity;
@@ -244,19 +258,19 @@ impl MouseFeatureExtractor {
 
     /// Calculate idle time ratio
     fn calculate_idle_time_ratio(&self, window: &AnalysisWindow) -> AnalysisResult<f32> {
-        let mouse_events = window.get_mouse_move_events();
+        let spans = Self::movement_spans(window);
         let total_duration = window.duration().as_millis() as i64;
         
-        if mouse_events.is_empty() || total_duration == 0 {
+        if spans.is_empty() || total_duration == 0 {
             return Ok(1.0); // All time is idle if no mouse events
         }
 
         let mut idle_time = 0i64;
         let window_start = chrono::DateTime::<chrono::Utc>::from(window.start_time);
 
-        for i in 1..mouse_events.len() {
-            let time_gap = mouse_events[i].timestamp
-                .signed_duration_since(mouse_events[i-1].timestamp)
+        for i in 1..spans.len() {
+            let time_gap = spans[i].0
+                .signed_duration_since(spans[i-1].1)
                 .num_milliseconds();
 
             // Consider gaps > 2 seconds as idle time
@@ -266,8 +280,8 @@ impl MouseFeatureExtractor {
         }
 
         // Check idle time at the beginning and end
-        if let Some(first_event) = mouse_events.first() {
-            let initial_idle = first_event.timestamp
+        if let Some(first_span) = spans.first() {
+            let initial_idle = first_span.0
                 .signed_duration_since(window_start)
                 .num_milliseconds();
             if initial_idle > self.config.idle_threshold_ms as i64 {
@@ -278,15 +292,30 @@ impl MouseFeatureExtractor {
         let idle_ratio = idle_time as f32 / total_duration as f32;
         Ok(idle_ratio.clamp(0.0, 1.0))
     }
+
+    /// Periods of mouse movement in time order; a raw move is an instant, a trajectory covers its window
+    fn movement_spans(window: &AnalysisWindow) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut spans: Vec<_> = window
+            .get_mouse_move_events()
+            .iter()
+            .map(|e| (e.timestamp, e.timestamp))
+            .chain(window.get_mouse_trajectory_events().iter().map(|t| {
+                (t.timestamp, t.timestamp + chrono::Duration::milliseconds(t.duration_ms as i64))
+            }))
+            .collect();
+        spans.sort_by_key(|span| span.0);
+        spans
+    }
 }
 
 #[async_trait]
 impl FeatureExtractor for MouseFeatureExtractor {
     async fn extract(&self, window: &AnalysisWindow) -> AnalysisResult<Vec<f32>> {
         let mouse_move_events = window.get_mouse_move_events();
+        let mouse_trajectory_events = window.get_mouse_trajectory_events();
         let mouse_click_events = window.get_mouse_click_events();
         
-        if mouse_move_events.is_empty() && mouse_click_events.is_empty() {
+        if mouse_move_events.is_empty() && mouse_trajectory_events.is_empty() && mouse_click_events.is_empty() {
             return Ok(vec![0.0; 8]);
         }
 
@@ -432,6 +461,44 @@ mod tests {
         assert!(smoothness.unwrap() >= 0.0);
     }
 
+    #[test]
+    fn test_trajectory_summaries_match_raw_moves() {
+        let extractor = MouseFeatureExtractor::new();
+        let raw = create_smooth_movement_window();
+
+        // The same movement as capture sends it with trajectory aggregation on: two moves per 100 ms
+        let mut summarized = AnalysisWindow::new(raw.start_time);
+        let moves = raw.get_mouse_move_events();
+        for pair in moves.chunks(2) {
+            let (first, last) = (pair[0], pair[pair.len() - 1]);
+            summarized.add_event(RawEvent::MouseTrajectory(MouseTrajectoryEvent {
+                timestamp: first.timestamp,
+                duration_ms: (last.timestamp - first.timestamp).num_milliseconds() as u32,
+                sample_count: pair.len() as u32,
+                start_x: first.x,
+                start_y: first.y,
+                end_x: last.x,
+                end_y: last.y,
+                distance_px: 0.0,
+                mean_velocity: 150.0,
+                max_velocity: 150.0,
+                velocity_std: 0.0,
+                direction_changes: 0,
+                idle_ms: 0,
+            }));
+        }
+
+        let raw_movement = extractor.extract_movement_features(&raw).unwrap();
+        let summarized_movement = extractor.extract_movement_features(&summarized).unwrap();
+        for (a, b) in raw_movement.iter().zip(&summarized_movement) {
+            assert!((a - b).abs() < 1e-4, "{:?} vs {:?}", raw_movement, summarized_movement);
+        }
+        assert_eq!(
+            extractor.calculate_idle_time_ratio(&raw).unwrap(),
+            extractor.calculate_idle_time_ratio(&summarized).unwrap()
+        );
+    }
+
     fn create_test_window_with_mouse_events() -> AnalysisWindow {
         let mut window = AnalysisWindow::new(SystemTime::now());
         
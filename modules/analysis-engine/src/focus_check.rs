@@ -0,0 +1,204 @@
+//! On-demand focus checks
+//!
+//! A hotkey or a click on the figurine asks "how am I doing right now?".
+//! Rather than wait for the current window to close, the engine analyzes the
+//! events collected so far ([`AnalysisEngineTrait::analyze_now`]) and explains
+//! the result in a sentence. [`FocusCheckResponder`] answers
+//! `FocusCheckRequest` messages on the bus with a high-priority
+//! `FocusCheckResult` correlated to the request.
+
+use std::{sync::Arc, time::Duration};
+use chrono::Utc;
+use skelly_jelly_event_bus::{
+    message::{FocusCheckRequest, FocusCheckResult},
+    BusMessage, EventBusTrait, MessagePayload, MessagePriority, MessageType, ModuleId,
+};
+use tracing::debug;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::ADHDStateType,
+    types::AnalysisResult as AnalysisResultType,
+    AnalysisEngineTrait,
+};
+
+/// Result of an on-demand analysis of the open window
+#[derive(Debug, Clone)]
+pub struct FocusCheck {
+    pub result: AnalysisResultType,
+    /// Plain-language reasons for the state
+    pub explanation: String,
+    pub events_analyzed: usize,
+    /// How much of the window had elapsed when it was analyzed
+    pub window_elapsed: Duration,
+}
+
+impl FocusCheck {
+    pub fn new(result: AnalysisResultType, events_analyzed: usize, window_elapsed: Duration) -> Self {
+        let explanation = explain(&result);
+        Self { result, explanation, events_analyzed, window_elapsed }
+    }
+
+    /// Answer to a bus request
+    pub fn to_bus_result(&self, request_id: uuid::Uuid) -> FocusCheckResult {
+        FocusCheckResult {
+            request_id,
+            state: state_label(self.result.state.state_type).to_string(),
+            confidence: self.result.confidence as f64,
+            explanation: self.explanation.clone(),
+            events_analyzed: self.events_analyzed,
+            window_elapsed_ms: self.window_elapsed.as_millis() as u64,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// State name as used in `StateChange` messages
+pub fn state_label(state: ADHDStateType) -> &'static str {
+    match state {
+        ADHDStateType::Flow => "flow",
+        ADHDStateType::Hyperfocus => "hyperfocus",
+        ADHDStateType::Distracted => "distracted",
+        ADHDStateType::Transitioning => "transitioning",
+        ADHDStateType::Neutral => "neutral",
+    }
+}
+
+/// One or two sentences on why the result came out as it did
+pub fn explain(result: &AnalysisResultType) -> String {
+    let metrics = &result.metrics;
+    let mut reasons = Vec::new();
+
+    if metrics.keystroke_rate > 0.0 {
+        let rhythm = if metrics.work_rhythm_consistency >= 0.6 { "steady" } else { "uneven" };
+        reasons.push(format!("{} typing at {:.0} keys/min", rhythm, metrics.keystroke_rate));
+    }
+    if metrics.window_switch_frequency >= 2.0 {
+        reasons.push(format!("{:.1} window switches/min", metrics.window_switch_frequency));
+    } else if metrics.focus_duration >= Duration::from_secs(60) {
+        reasons.push(format!("{} min in one place", metrics.focus_duration.as_secs() / 60));
+    }
+    if metrics.distraction_frequency > 0.0 {
+        reasons.push(format!("{:.0} distractions/hour", metrics.distraction_frequency));
+    }
+
+    let mut explanation = format!(
+        "Looks like {} ({:.0}% confident)",
+        state_label(result.state.state_type),
+        result.confidence * 100.0
+    );
+    if !reasons.is_empty() {
+        explanation.push_str(": ");
+        explanation.push_str(&reasons.join(", "));
+    }
+    explanation.push('.');
+
+    let strongest = result
+        .feature_importance
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((feature, _)) = strongest {
+        explanation.push_str(&format!(" Strongest signal: {}.", feature.replace('_', " ")));
+    }
+    explanation
+}
+
+/// Answers `FocusCheckRequest`s from the bus
+///
+/// Feed it bus traffic with [`FocusCheckResponder::handle_message`]; each
+/// request is answered even when the window is too empty to analyze, so the
+/// asking side never waits on a reply that will not come.
+pub struct FocusCheckResponder {
+    engine: Arc<dyn AnalysisEngineTrait>,
+    event_bus: Arc<dyn EventBusTrait>,
+}
+
+impl FocusCheckResponder {
+    pub fn new(engine: Arc<dyn AnalysisEngineTrait>, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self { engine, event_bus }
+    }
+
+    /// Message types the responder reacts to
+    pub fn subscribed_types() -> Vec<MessageType> {
+        vec![MessageType::FocusCheckRequest]
+    }
+
+    /// Run a focus check for a request message and publish the answer
+    pub async fn handle_message(&self, message: &BusMessage) -> AnalysisResult<Option<FocusCheckResult>> {
+        let MessagePayload::FocusCheckRequest(request) = &message.payload else {
+            return Ok(None);
+        };
+        debug!("Focus check requested by {}", request.trigger);
+
+        let answer = match self.engine.analyze_now().await {
+            Ok(check) => check.to_bus_result(request.request_id),
+            Err(AnalysisError::InsufficientData { available, .. }) => self.too_early(request, available).await,
+            Err(e) => return Err(e),
+        };
+
+        let mut reply = message.reply_to(ModuleId::AnalysisEngine, MessagePayload::FocusCheckResult(answer.clone()));
+        reply.priority = MessagePriority::High;
+        self.event_bus.publish(reply).await?;
+        Ok(Some(answer))
+    }
+
+    async fn too_early(&self, request: &FocusCheckRequest, available: usize) -> FocusCheckResult {
+        let state = self.engine.get_current_state().await;
+        FocusCheckResult {
+            request_id: request.request_id,
+            state: state_label(state.state_type).to_string(),
+            confidence: 0.0,
+            explanation: format!(
+                "Not enough activity yet to tell ({} events so far); check again in a little while.",
+                available
+            ),
+            events_analyzed: available,
+            window_elapsed_ms: 0,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ADHDState;
+
+    fn flow_result() -> AnalysisResultType {
+        let mut result = AnalysisResultType::new(uuid::Uuid::new_v4(), ADHDState::flow());
+        result.confidence = 0.82;
+        result.metrics.keystroke_rate = 64.0;
+        result.metrics.work_rhythm_consistency = 0.8;
+        result.metrics.focus_duration = Duration::from_secs(300);
+        result.feature_importance = vec![("typing_rhythm".to_string(), 0.4), ("mouse_idle".to_string(), 0.1)];
+        result
+    }
+
+    #[test]
+    fn test_explanation_names_state_and_signals() {
+        let explanation = explain(&flow_result());
+        assert_eq!(
+            explanation,
+            "Looks like flow (82% confident): steady typing at 64 keys/min, 5 min in one place. Strongest signal: typing rhythm."
+        );
+
+        let mut distracted = AnalysisResultType::new(uuid::Uuid::new_v4(), ADHDState::distracted());
+        distracted.confidence = 0.7;
+        distracted.metrics.window_switch_frequency = 4.5;
+        assert_eq!(explain(&distracted), "Looks like distracted (70% confident): 4.5 window switches/min.");
+    }
+
+    #[test]
+    fn test_bus_result_carries_request_and_window() {
+        let check = FocusCheck::new(flow_result(), 42, Duration::from_secs(12));
+        let request_id = uuid::Uuid::new_v4();
+        let answer = check.to_bus_result(request_id);
+
+        assert_eq!(answer.request_id, request_id);
+        assert_eq!(answer.state, "flow");
+        assert_eq!(answer.events_analyzed, 42);
+        assert_eq!(answer.window_elapsed_ms, 12_000);
+        assert!((answer.confidence - 0.82).abs() < 1e-6);
+        assert_eq!(answer.explanation, check.explanation);
+    }
+}
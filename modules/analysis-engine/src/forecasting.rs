@@ -0,0 +1,347 @@
+//! Focus capacity forecast for the coming hour
+//!
+//! Classified windows are totalled per local day and hour: active time and
+//! time in flow or hyperfocus. The forecast for the next hour starts from the
+//! circadian baseline, the share of that hour spent focused on past days, with
+//! days of the same kind (weekday or weekend) counting double. Today's
+//! trajectory then moves it: if the user has been more or less focused than
+//! usual for the hours so far, the coming hour is expected to follow partway.
+//!
+//! Forecasts are published as `ForecastUpdated` every `publish_interval`, so
+//! AI integration can suggest scheduling deep work into predicted high-focus
+//! windows. Hourly totals are kept for `history_days` in `history_path`.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{message::ForecastUpdated, BusMessage, EventBusTrait, MessagePayload, ModuleId};
+use tracing::debug;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::ADHDStateType,
+    sessions::StateRecord,
+};
+
+/// Active time today needs before it is compared with the usual day
+const MIN_TRAJECTORY_TIME: f64 = 10.0 * 60.0;
+
+/// How far today's trajectory can scale the baseline either way
+const MAX_TRAJECTORY_RATIO: f32 = 2.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastConfig {
+    pub enabled: bool,
+    /// Hourly totals, one JSON file
+    pub history_path: PathBuf,
+    /// Days of history kept and used
+    pub history_days: u32,
+    /// Days of history for an hour at which the forecast reaches full confidence
+    pub min_history_days: u32,
+    /// How much of today's deviation from the usual day carries into the next hour, 0–1
+    pub trajectory_weight: f32,
+    /// Capacity from which an hour is reported as good for deep work
+    pub high_focus_threshold: f32,
+    /// How often a forecast is published and the history saved
+    pub publish_interval: Duration,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_path: PathBuf::from("data/focus_forecast.json"),
+            history_days: 28,
+            min_history_days: 7,
+            trajectory_weight: 0.5,
+            high_focus_threshold: 0.6,
+            publish_interval: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+/// Active and focused seconds in one local hour
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HourTotals {
+    pub active_secs: f64,
+    pub focused_secs: f64,
+}
+
+#[derive(Debug, Default)]
+struct ForecastState {
+    days: BTreeMap<NaiveDate, [HourTotals; 24]>,
+    dirty: bool,
+    last_published: Option<Instant>,
+}
+
+/// Weighted focused and active time over a set of hours
+#[derive(Debug, Default)]
+struct Share {
+    focused: f64,
+    active: f64,
+    days: u32,
+}
+
+impl Share {
+    fn add(&mut self, hour: &HourTotals, weight: f64) {
+        self.focused += hour.focused_secs * weight;
+        self.active += hour.active_secs * weight;
+    }
+
+    fn value(&self) -> Option<f32> {
+        (self.active > 0.0).then(|| (self.focused / self.active) as f32)
+    }
+}
+
+/// Learns when in the day the user focuses and predicts the coming hour
+pub struct FocusForecaster {
+    config: ForecastConfig,
+    utc_offset: FixedOffset,
+    state: Mutex<ForecastState>,
+}
+
+impl FocusForecaster {
+    /// Load the saved hourly totals, if any
+    pub fn new(config: ForecastConfig) -> AnalysisResult<Self> {
+        let days = Self::load(&config.history_path)?;
+        Ok(Self {
+            config,
+            utc_offset: *Local::now().offset(),
+            state: Mutex::new(ForecastState { days, ..Default::default() }),
+        })
+    }
+
+    /// Bucket days and hours in this offset instead of the system's local time
+    pub fn with_utc_offset(mut self, utc_offset: FixedOffset) -> Self {
+        self.utc_offset = utc_offset;
+        self
+    }
+
+    /// Add a classified window to the hourly totals; idle windows are skipped
+    pub fn record(&self, record: &StateRecord) {
+        if record.idle {
+            return;
+        }
+        let local = record.timestamp.with_timezone(&self.utc_offset);
+        let secs = record.duration.as_secs_f64();
+        let focused = matches!(record.state, ADHDStateType::Flow | ADHDStateType::Hyperfocus);
+
+        let mut state = self.state.lock().unwrap();
+        let hour = &mut state.days.entry(local.date_naive()).or_default()[local.hour() as usize];
+        hour.active_secs += secs;
+        if focused {
+            hour.focused_secs += secs;
+        }
+        state.dirty = true;
+
+        let cutoff = local.date_naive() - chrono::Duration::days(self.config.history_days as i64);
+        state.days.retain(|date, _| *date >= cutoff);
+    }
+
+    /// Predicted focus for the hour from `now`, `None` without history for that time of day
+    pub fn forecast(&self, now: DateTime<Utc>) -> Option<ForecastUpdated> {
+        let local = now.with_timezone(&self.utc_offset);
+        let today = local.date_naive();
+        let target_hour = (now + chrono::Duration::minutes(30)).with_timezone(&self.utc_offset).hour() as usize;
+        let state = self.state.lock().unwrap();
+
+        let history = || {
+            state
+                .days
+                .iter()
+                .filter(move |(date, _)| **date < today)
+                .map(move |(date, hours)| (if is_weekend(*date) == is_weekend(today) { 1.0 } else { 0.5 }, hours))
+        };
+
+        let mut baseline = Share::default();
+        for (weight, hours) in history() {
+            if hours[target_hour].active_secs > 0.0 {
+                baseline.add(&hours[target_hour], weight);
+                baseline.days += 1;
+            }
+        }
+        let baseline_capacity = baseline.value()?;
+
+        // Today so far against the usual for the same hours
+        let elapsed = 0..=local.hour() as usize;
+        let mut today_share = Share::default();
+        if let Some(hours) = state.days.get(&today) {
+            for hour in &hours[elapsed.clone()] {
+                today_share.add(hour, 1.0);
+            }
+        }
+        let mut usual = Share::default();
+        for (weight, hours) in history() {
+            for hour in &hours[elapsed.clone()] {
+                usual.add(hour, weight);
+            }
+        }
+        let focus_capacity = match (today_share.value(), usual.value()) {
+            (Some(today), Some(usual)) if today_share.active >= MIN_TRAJECTORY_TIME && usual > 0.0 => {
+                let ratio = (today / usual).clamp(1.0 / MAX_TRAJECTORY_RATIO, MAX_TRAJECTORY_RATIO);
+                (baseline_capacity * (1.0 + self.config.trajectory_weight * (ratio - 1.0))).clamp(0.0, 1.0)
+            }
+            _ => baseline_capacity,
+        };
+
+        Some(ForecastUpdated {
+            window_start: now,
+            window_end: now + chrono::Duration::hours(1),
+            focus_capacity,
+            baseline_capacity,
+            confidence: (baseline.days as f32 / self.config.min_history_days.max(1) as f32).min(1.0),
+            high_focus: focus_capacity >= self.config.high_focus_threshold,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Publish a forecast and save the history if `publish_interval` has passed
+    pub async fn publish_if_due(
+        &self,
+        event_bus: &dyn EventBusTrait,
+        now: DateTime<Utc>,
+    ) -> AnalysisResult<Option<ForecastUpdated>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.last_published.is_some_and(|published| published.elapsed() < self.config.publish_interval) {
+                return Ok(None);
+            }
+            state.last_published = Some(Instant::now());
+        }
+        self.flush()?;
+
+        let Some(forecast) = self.forecast(now) else {
+            debug!("No focus history for the coming hour yet, skipping forecast");
+            return Ok(None);
+        };
+        event_bus
+            .publish(BusMessage::new(ModuleId::AnalysisEngine, MessagePayload::ForecastUpdated(forecast.clone())))
+            .await?;
+        Ok(Some(forecast))
+    }
+
+    /// Write the hourly totals out now
+    pub fn flush(&self) -> AnalysisResult<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.dirty {
+            return Ok(());
+        }
+        let path = &self.config.history_path;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string(&state.days)?)?;
+        fs::rename(&temp, path)?;
+        state.dirty = false;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> AnalysisResult<BTreeMap<NaiveDate, [HourTotals; 24]>> {
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| AnalysisError::DataLoadError {
+            path: path.display().to_string(),
+            message: format!("Failed to parse focus forecast history: {}", e),
+        })
+    }
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Monday
+    fn day(offset: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_438_400, 0).unwrap() + chrono::Duration::days(offset)
+    }
+
+    fn record(at: DateTime<Utc>, state: ADHDStateType) -> StateRecord {
+        StateRecord {
+            timestamp: at,
+            duration: Duration::from_secs(60),
+            state,
+            application: None,
+            work_type: None,
+            idle: false,
+            task_id: None,
+        }
+    }
+
+    /// Twenty minutes at `hour`, the first `focused` of them in flow
+    fn hour(forecaster: &FocusForecaster, date: DateTime<Utc>, hour: i64, focused: i64) {
+        for minute in 0..20 {
+            let state = if minute < focused { ADHDStateType::Flow } else { ADHDStateType::Distracted };
+            forecaster.record(&record(date + chrono::Duration::minutes(hour * 60 + minute), state));
+        }
+    }
+
+    fn forecaster(dir: &Path) -> FocusForecaster {
+        FocusForecaster::new(ForecastConfig {
+            history_path: dir.join("forecast.json"),
+            min_history_days: 4,
+            ..Default::default()
+        })
+        .unwrap()
+        .with_utc_offset(FixedOffset::east_opt(0).unwrap())
+    }
+
+    #[test]
+    fn test_forecast_follows_the_daily_rhythm() {
+        let dir = tempfile::tempdir().unwrap();
+        let forecaster = forecaster(dir.path());
+        for offset in 0..4 {
+            hour(&forecaster, day(offset), 9, 16);
+            hour(&forecaster, day(offset), 15, 4);
+        }
+        assert!(forecaster.forecast(day(4) + chrono::Duration::hours(3)).is_none());
+
+        let morning = forecaster.forecast(day(4) + chrono::Duration::minutes(8 * 60 + 45)).unwrap();
+        assert!((morning.focus_capacity - 0.8).abs() < 1e-4);
+        assert!(morning.high_focus);
+        assert_eq!(morning.confidence, 1.0);
+
+        let afternoon = forecaster.forecast(day(4) + chrono::Duration::minutes(14 * 60 + 45)).unwrap();
+        assert!((afternoon.focus_capacity - 0.2).abs() < 1e-4);
+        assert!(!afternoon.high_focus);
+
+        // The history survives a restart
+        forecaster.flush().unwrap();
+        let restarted = self::forecaster(dir.path()).forecast(day(4) + chrono::Duration::minutes(8 * 60 + 45)).unwrap();
+        assert_eq!(restarted.focus_capacity, morning.focus_capacity);
+        assert_eq!(restarted.confidence, morning.confidence);
+    }
+
+    #[test]
+    fn test_a_rough_day_lowers_the_forecast() {
+        let dir = tempfile::tempdir().unwrap();
+        let forecaster = forecaster(dir.path());
+        for offset in 0..4 {
+            hour(&forecaster, day(offset), 9, 12);
+            hour(&forecaster, day(offset), 10, 12);
+        }
+        // Half the usual focus this morning
+        hour(&forecaster, day(4), 9, 6);
+
+        let forecast = forecaster.forecast(day(4) + chrono::Duration::minutes(9 * 60 + 45)).unwrap();
+        assert!((forecast.baseline_capacity - 0.6).abs() < 1e-4);
+        // Half of the 50% drop carries over
+        assert!((forecast.focus_capacity - 0.45).abs() < 1e-4);
+        assert!(!forecast.high_focus);
+    }
+}
@@ -4,6 +4,17 @@
 //! - Processes event batches in real-time with <50ms latency
 //! - Coordinates feature extraction and ML model inference
 //! - Manages prediction caching and optimization
+//!
+//! Predictions are cached by the window's feature vector rather than its
+//! events. Each feature is quantized into buckets on a log scale, so windows
+//! with the same behaviour at different times share a key, and a lookup that
+//! misses falls back to the closest cached vector whose buckets are all within
+//! `cache_similarity_tolerance` of the window's.
+//!
+//! The cache is bounded by entry count and by the estimated bytes it holds;
+//! the least recently cached entries go first when either limit is reached.
+//! Expired entries are removed on access and by the background sweeper from
+//! [`InferenceEngine::start_cache_sweeper`].
 //! - Handles concurrent inference requests efficiently
 //! - Provides comprehensive performance monitoring
 
@@ -18,6 +29,7 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio::sync::{RwLock, Semaphore};
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::{
@@ -47,6 +59,10 @@ pub struct InferenceEngine {
     
     /// Request tracking
     active_requests: Arc<RwLock<HashMap<Uuid, InferenceRequest>>>,
+    
+    /// Fails or times out scripted inferences in chaos tests
+    #[cfg(feature = "chaos")]
+    faults: Option<Arc<skelly_jelly_event_bus::FaultInjector>>,
 }
 
 /// Cached prediction result
@@ -55,20 +71,107 @@ struct CachedPrediction {
     result: StateDetectionResult,
     cache_time: Instant,
     hit_count: u32,
+    /// Estimated memory held by the entry and its key
+    size_bytes: usize,
 }
 
-/// Prediction cache with LRU eviction
+impl CachedPrediction {
+    fn new(key: &FeatureCacheKey, result: StateDetectionResult) -> Self {
+        let size_bytes = std::mem::size_of::<(FeatureCacheKey, CachedPrediction)>()
+            + std::mem::size_of_val(&*key.buckets)
+            + result
+                .feature_importance
+                .iter()
+                .map(|(name, _)| std::mem::size_of::<(String, f32)>() + name.capacity())
+                .sum::<usize>();
+        Self { result, cache_time: Instant::now(), hit_count: 0, size_bytes }
+    }
+}
+
+/// Prediction cache with LRU eviction, bounded by entries and estimated bytes
 struct PredictionCache {
     cache: HashMap<FeatureCacheKey, CachedPrediction>,
     max_size: usize,
+    max_bytes: usize,
+    /// Sum of the entries' `size_bytes`
+    total_bytes: usize,
     ttl: Duration,
+    evictions: u64,
+    expired_removed: u64,
 }
 
-/// Cache key based on feature vector hash
+impl PredictionCache {
+    fn new(config: &InferenceConfig) -> Self {
+        Self {
+            cache: HashMap::new(),
+            max_size: config.cache_max_size,
+            max_bytes: config.cache_max_bytes,
+            total_bytes: 0,
+            ttl: config.cache_ttl,
+            evictions: 0,
+            expired_removed: 0,
+        }
+    }
+
+    fn insert(&mut self, key: FeatureCacheKey, prediction: CachedPrediction) {
+        self.total_bytes += prediction.size_bytes;
+        if let Some(replaced) = self.cache.insert(key, prediction) {
+            self.total_bytes -= replaced.size_bytes;
+        }
+    }
+
+    fn remove(&mut self, key: &FeatureCacheKey) -> Option<CachedPrediction> {
+        let removed = self.cache.remove(key)?;
+        self.total_bytes -= removed.size_bytes;
+        Some(removed)
+    }
+
+    /// Remove every expired entry, returning how many there were
+    fn sweep_expired(&mut self) -> usize {
+        let ttl = self.ttl;
+        let expired: Vec<FeatureCacheKey> = self
+            .cache
+            .iter()
+            .filter(|(_, cached)| cached.cache_time.elapsed() > ttl)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        self.expired_removed += expired.len() as u64;
+        expired.len()
+    }
+
+    fn clear(&mut self) {
+        self.cache.clear();
+        self.total_bytes = 0;
+    }
+}
+
+/// Cache key: the window's features, quantized
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct FeatureCacheKey {
-    feature_hash: u64,
-    window_duration_ms: u64,
+    buckets: Box<[i32]>,
+    window_duration_secs: u64,
+}
+
+impl FeatureCacheKey {
+    fn new(features: &FeatureVector, window_duration: Duration, step: f32) -> Self {
+        let buckets = features
+            .to_vec()
+            .into_iter()
+            .map(|value| (value.signum() * value.abs().ln_1p() / step).round() as i32)
+            .collect();
+        Self { buckets, window_duration_secs: window_duration.as_secs_f32().round() as u64 }
+    }
+
+    /// Largest bucket difference to `other`, `None` if the keys can't be compared
+    fn distance(&self, other: &Self) -> Option<u32> {
+        if self.window_duration_secs != other.window_duration_secs || self.buckets.len() != other.buckets.len() {
+            return None;
+        }
+        self.buckets.iter().zip(other.buckets.iter()).map(|(a, b)| a.abs_diff(*b)).max().or(Some(0))
+    }
 }
 
 /// Active inference request tracking
@@ -95,6 +198,8 @@ pub struct InferenceMetrics {
     successful_inferences: AtomicU64,
     failed_inferences: AtomicU64,
     cache_hits: AtomicU64,
+    /// Hits served by a similar rather than identical key
+    cache_near_hits: AtomicU64,
     cache_misses: AtomicU64,
     avg_latency_ms: RwLock<f32>,
     max_latency_ms: RwLock<f32>,
@@ -113,11 +218,7 @@ impl InferenceEngine {
         state_detector: Arc<StateDetectionEngine>,
         config: InferenceConfig,
     ) -> Self {
-        let cache = PredictionCache {
-            cache: HashMap::new(),
-            max_size: config.cache_max_size,
-            ttl: config.cache_ttl,
-        };
+        let cache = PredictionCache::new(&config);
         
         let max_concurrent = config.max_concurrent_inferences;
         
@@ -128,9 +229,38 @@ impl InferenceEngine {
             inference_semaphore: Arc::new(Semaphore::new(max_concurrent)),
             metrics: Arc::new(InferenceMetrics::new()),
             active_requests: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "chaos")]
+            faults: None,
         }
     }
     
+    /// Fail inferences as `injector`'s `InferenceTimeout` rules say (`chaos` feature)
+    ///
+    /// Injected faults are counted as failed inferences, like real ones.
+    #[cfg(feature = "chaos")]
+    pub fn with_fault_injector(mut self, injector: Arc<skelly_jelly_event_bus::FaultInjector>) -> Self {
+        self.faults = Some(injector);
+        self
+    }
+    
+    /// The error for a scripted inference fault, if this call has one
+    #[cfg(feature = "chaos")]
+    fn injected_fault(&self) -> Option<AnalysisError> {
+        use skelly_jelly_event_bus::{FaultKind, FaultPoint};
+        
+        let fault = self.faults.as_ref()?.check(FaultPoint::InferenceTimeout)?;
+        Some(match fault.kind {
+            FaultKind::Timeout(timeout) => AnalysisError::TimeoutError {
+                operation: "inference".to_string(),
+                timeout_ms: timeout.as_millis() as u64,
+            },
+            FaultKind::Fail => AnalysisError::InferenceError {
+                model: "state_detection".to_string(),
+                source: Box::new(fault),
+            },
+        })
+    }
+    
     /// Perform real-time inference on analysis window
     pub async fn infer(&self, window: &AnalysisWindow) -> AnalysisResult<StateDetectionResult> {
         self.infer_with_priority(window, InferencePriority::Normal).await
@@ -160,6 +290,12 @@ impl InferenceEngine {
         // Increment request counter
         self.metrics.total_requests.fetch_add(1, Ordering::Relaxed);
         
+        #[cfg(feature = "chaos")]
+        let result = match self.injected_fault() {
+            Some(error) => Err(error),
+            None => self.perform_inference_internal(window).await,
+        };
+        #[cfg(not(feature = "chaos"))]
         let result = self.perform_inference_internal(window).await;
         
         // Track completion
@@ -179,130 +315,133 @@ impl InferenceEngine {
     
     /// Internal inference implementation with caching
     async fn perform_inference_internal(&self, window: &AnalysisWindow) -> AnalysisResult<StateDetectionResult> {
+        if !self.config.enable_caching {
+            return self.state_detector.detect_state(window).await;
+        }
+        
+        // Features are extracted once, for the key and for detection
+        let features = self.state_detector.extract_features(window).await?;
+        let cache_key = FeatureCacheKey::new(&features, window.duration(), self.config.cache_quantization_step);
+        
         // Check cache first
-        if let Some(cached_result) = self.check_cache(window).await? {
+        if let Some(mut cached_result) = self.check_cache(&cache_key).await {
             self.metrics.cache_hits.fetch_add(1, Ordering::Relaxed);
+            cached_result.window_id = window.window_id;
+            cached_result.timestamp = chrono::Utc::now();
             return Ok(cached_result);
         }
         
         self.metrics.cache_misses.fetch_add(1, Ordering::Relaxed);
         
         // Perform actual inference
-        let detection_result = self.state_detector.detect_state(window).await?;
+        let detection_result = self.state_detector.detect_state_with_features(window, features).await?;
         
         // Cache the result
-        self.cache_result(window, &detection_result).await?;
+        self.cache_result(cache_key, &detection_result).await?;
         
         Ok(detection_result)
     }
     
-    /// Check prediction cache for existing result
-    async fn check_cache(&self, window: &AnalysisWindow) -> AnalysisResult<Option<StateDetectionResult>> {
-        if !self.config.enable_caching {
-            return Ok(None);
-        }
-        
-        let cache_key = self.create_cache_key(window).await?;
+    /// Check prediction cache for the key or, failing that, the closest similar one
+    async fn check_cache(&self, cache_key: &FeatureCacheKey) -> Option<StateDetectionResult> {
         let mut cache = self.prediction_cache.write().await;
-        
         let cache_ttl = cache.ttl;
-        if let Some(cached_prediction) = cache.cache.get_mut(&cache_key) {
-            // Check if cache entry is still valid
-            if cached_prediction.cache_time.elapsed() <= cache_ttl {
-                cached_prediction.hit_count += 1;
-                return Ok(Some(cached_prediction.result.clone()));
-            } else {
-                // Mark for removal (will be removed after the scope)
-                let expired_key = cache_key.clone();
-                drop(cached_prediction); // Drop the mutable borrow
-                cache.cache.remove(&expired_key);
-            }
-        }
         
-        Ok(None)
+        let key = if cache.cache.contains_key(cache_key) {
+            cache_key.clone()
+        } else {
+            let tolerance = self.config.cache_similarity_tolerance;
+            let nearest = cache
+                .cache
+                .iter()
+                .filter(|(_, cached)| cached.cache_time.elapsed() <= cache_ttl)
+                .filter_map(|(key, _)| cache_key.distance(key).filter(|distance| *distance <= tolerance).map(|distance| (distance, key)))
+                .min_by_key(|(distance, _)| *distance)
+                .map(|(_, key)| key.clone())?;
+            self.metrics.cache_near_hits.fetch_add(1, Ordering::Relaxed);
+            nearest
+        };
+        
+        let cached_prediction = cache.cache.get_mut(&key)?;
+        // Check if cache entry is still valid
+        if cached_prediction.cache_time.elapsed() <= cache_ttl {
+            cached_prediction.hit_count += 1;
+            Some(cached_prediction.result.clone())
+        } else {
+            cache.remove(&key);
+            cache.expired_removed += 1;
+            None
+        }
     }
     
     /// Cache inference result
-    async fn cache_result(&self, window: &AnalysisWindow, result: &StateDetectionResult) -> AnalysisResult<()> {
-        if !self.config.enable_caching {
+    async fn cache_result(&self, cache_key: FeatureCacheKey, result: &StateDetectionResult) -> AnalysisResult<()> {
+        let cached_prediction = CachedPrediction::new(&cache_key, result.clone());
+        let mut cache = self.prediction_cache.write().await;
+        if cached_prediction.size_bytes > cache.max_bytes {
             return Ok(());
         }
         
-        let cache_key = self.create_cache_key(window).await?;
-        let mut cache = self.prediction_cache.write().await;
-        
         // Evict old entries if cache is full
-        if cache.cache.len() >= cache.max_size {
-            self.evict_cache_entries(&mut cache).await;
-        }
-        
-        let cached_prediction = CachedPrediction {
-            result: result.clone(),
-            cache_time: Instant::now(),
-            hit_count: 0,
-        };
-        
-        cache.cache.insert(cache_key, cached_prediction);
+        self.evict_cache_entries(&mut cache, cached_prediction.size_bytes).await;
+        cache.insert(cache_key, cached_prediction);
         
         Ok(())
     }
     
     /// Create cache key for window
     async fn create_cache_key(&self, window: &AnalysisWindow) -> AnalysisResult<FeatureCacheKey> {
-        // Create a simple hash of the window events for cache key
-        let events_hash = self.hash_window_events(window);
-        let duration_ms = window.duration().as_millis() as u64;
-        
-        Ok(FeatureCacheKey {
-            feature_hash: events_hash,
-            window_duration_ms: duration_ms,
-        })
+        let features = self.state_detector.extract_features(window).await?;
+        Ok(FeatureCacheKey::new(&features, window.duration(), self.config.cache_quantization_step))
     }
     
-    /// Hash window events for cache key
-    fn hash_window_events(&self, window: &AnalysisWindow) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        
-        let mut hasher = DefaultHasher::new();
-        
-        // Hash event count and basic properties
-        window.events.len().hash(&mut hasher);
-        
-        // Hash first few and last few events for efficiency
-        let event_sample_size = 10.min(window.events.len());
-        for event in window.events.iter().take(event_sample_size) {
-            event.timestamp().timestamp_millis().hash(&mut hasher);
+    /// Evict old cache entries using LRU policy until an entry of `incoming_bytes` fits
+    async fn evict_cache_entries(&self, cache: &mut PredictionCache, incoming_bytes: usize) {
+        let is_full = |cache: &PredictionCache| {
+            cache.cache.len() >= cache.max_size || cache.total_bytes + incoming_bytes > cache.max_bytes
+        };
+        if !is_full(cache) {
+            return;
         }
         
-        hasher.finish()
-    }
-    
-    /// Evict old cache entries using LRU policy
-    async fn evict_cache_entries(&self, cache: &mut PredictionCache) {
+        // Expired entries go before anything live
+        cache.sweep_expired();
+        
         // Find entries to evict (oldest first, then by hit count)
         let mut entries_to_remove: Vec<_> = cache.cache.iter()
             .map(|(k, v)| (k.clone(), v.cache_time, v.hit_count))
             .collect();
+        entries_to_remove.sort_by(|a, b| a.1.cmp(&b.1).then(a.2.cmp(&b.2)));
         
-        entries_to_remove.sort_by(|a, b| {
-            // First by age (older first)
-            let age_cmp = b.1.cmp(&a.1);
-            if age_cmp == std::cmp::Ordering::Equal {
-                // Then by hit count (fewer hits first)
-                a.2.cmp(&b.2)
-            } else {
-                age_cmp
+        for (key, _, _) in entries_to_remove {
+            if !is_full(cache) {
+                break;
             }
-        });
-        
-        // Remove oldest 25% of entries
-        let remove_count = (cache.max_size / 4).max(1);
-        for (key, _, _) in entries_to_remove.iter().take(remove_count) {
-            cache.cache.remove(key);
+            cache.remove(&key);
+            cache.evictions += 1;
         }
     }
     
+    /// Remove expired entries every `cache_sweep_interval` until the engine is dropped
+    pub fn start_cache_sweeper(&self) -> tokio::task::JoinHandle<()> {
+        let cache = Arc::downgrade(&self.prediction_cache);
+        let sweep_interval = self.config.cache_sweep_interval;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sweep_interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(cache) = cache.upgrade() else {
+                    break;
+                };
+                let removed = cache.write().await.sweep_expired();
+                if removed > 0 {
+                    debug!("Swept {} expired predictions from the inference cache", removed);
+                }
+            }
+        })
+    }
+    
     /// Acquire inference permit with priority handling
     async fn acquire_inference_permit(&self, priority: InferencePriority) -> AnalysisResult<tokio::sync::SemaphorePermit> {
         let timeout = match priority {
@@ -387,6 +526,7 @@ impl InferenceEngine {
         let successful = self.metrics.successful_inferences.load(Ordering::Relaxed);
         let failed = self.metrics.failed_inferences.load(Ordering::Relaxed);
         let cache_hits = self.metrics.cache_hits.load(Ordering::Relaxed);
+        let cache_near_hits = self.metrics.cache_near_hits.load(Ordering::Relaxed);
         let cache_misses = self.metrics.cache_misses.load(Ordering::Relaxed);
         
         let success_rate = if total_requests > 0 {
@@ -407,6 +547,7 @@ impl InferenceEngine {
             failed_inferences: failed,
             success_rate,
             cache_hits,
+            cache_near_hits,
             cache_misses,
             cache_hit_rate,
             avg_latency_ms: *self.metrics.avg_latency_ms.read().await,
@@ -432,7 +573,7 @@ impl InferenceEngine {
     /// Clear prediction cache
     pub async fn clear_cache(&self) {
         let mut cache = self.prediction_cache.write().await;
-        cache.cache.clear();
+        cache.clear();
     }
     
     /// Get cache statistics
@@ -457,6 +598,10 @@ impl InferenceEngine {
             total_hit_count,
             avg_age_ms,
             ttl_ms: cache.ttl.as_millis() as u32,
+            memory_bytes: cache.total_bytes,
+            max_memory_bytes: cache.max_bytes,
+            evictions: cache.evictions,
+            expired_removed: cache.expired_removed,
         }
     }
 }
@@ -468,6 +613,7 @@ impl InferenceMetrics {
             successful_inferences: AtomicU64::new(0),
             failed_inferences: AtomicU64::new(0),
             cache_hits: AtomicU64::new(0),
+            cache_near_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
             avg_latency_ms: RwLock::new(0.0),
             max_latency_ms: RwLock::new(0.0),
@@ -495,6 +641,18 @@ pub struct InferenceConfig {
     /// Cache time-to-live
     pub cache_ttl: Duration,
     
+    /// Width of a feature's cache bucket, on a `ln(1 + |x|)` scale
+    pub cache_quantization_step: f32,
+    
+    /// Buckets a feature may differ by for a cached prediction to be reused
+    pub cache_similarity_tolerance: u32,
+    
+    /// Hard cap on the cache's estimated memory
+    pub cache_max_bytes: usize,
+    
+    /// How often the background sweeper removes expired entries
+    pub cache_sweep_interval: Duration,
+    
     /// Batch processing configuration
     pub max_batch_size: usize,
     pub batch_timeout_ms: u64,
@@ -512,6 +670,10 @@ impl Default for InferenceConfig {
             enable_caching: true,
             cache_max_size: 1000,
             cache_ttl: Duration::from_secs(300), // 5 minutes
+            cache_quantization_step: 0.05,
+            cache_similarity_tolerance: 1,
+            cache_max_bytes: 4 * 1024 * 1024,
+            cache_sweep_interval: Duration::from_secs(60),
             max_batch_size: 50,
             batch_timeout_ms: 100,
             enable_metrics: true,
@@ -527,7 +689,10 @@ pub struct InferenceEngineMetrics {
     pub successful_inferences: u64,
     pub failed_inferences: u64,
     pub success_rate: f32,
+    /// Lookups answered, including near hits; only counted with caching enabled
     pub cache_hits: u64,
+    /// Hits served by a similar rather than identical feature vector
+    pub cache_near_hits: u64,
     pub cache_misses: u64,
     pub cache_hit_rate: f32,
     pub avg_latency_ms: f32,
@@ -545,12 +710,19 @@ pub struct CacheStatistics {
     pub total_hit_count: u32,
     pub avg_age_ms: u32,
     pub ttl_ms: u32,
+    /// Estimated memory held by cached predictions
+    pub memory_bytes: usize,
+    pub max_memory_bytes: usize,
+    /// Live entries removed to stay within the entry or memory limit
+    pub evictions: u64,
+    /// Expired entries removed on access or by the sweeper
+    pub expired_removed: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{sliding_window::AnalysisWindow, state_detection::StateDetectionEngine};
+    use crate::{sliding_window::AnalysisWindow, state_detection::{StateDetectionConfig, StateDetectionEngine}};
     use std::time::SystemTime;
 
     #[tokio::test]
@@ -570,13 +742,16 @@ mod tests {
         let window = AnalysisWindow::new(SystemTime::now());
         let cache_key = engine.create_cache_key(&window).await.unwrap();
         
-        assert!(cache_key.feature_hash > 0);
-        assert!(cache_key.window_duration_ms >= 0);
+        assert_eq!(cache_key.buckets.len(), FeatureVector::default().feature_count());
+        assert_eq!(cache_key.window_duration_secs, 0);
     }
 
     #[tokio::test]
     async fn test_priority_inference() {
-        let state_detector = Arc::new(StateDetectionEngine::new());
+        let state_detector = Arc::new(StateDetectionEngine::with_config(StateDetectionConfig {
+            enable_cold_start: false,
+            ..Default::default()
+        }));
         let config = InferenceConfig {
             max_concurrent_inferences: 1,
             ..Default::default()
@@ -599,43 +774,166 @@ mod tests {
         };
         let engine = InferenceEngine::with_config(state_detector, config);
         
-        let mut cache = PredictionCache {
-            cache: HashMap::new(),
-            max_size: 5,
-            ttl: Duration::from_secs(300),
-        };
+        let mut cache = PredictionCache::new(&engine.config);
         
         // Fill cache beyond capacity
         for i in 0..10 {
-            let key = FeatureCacheKey {
-                feature_hash: i,
-                window_duration_ms: 1000,
-            };
-            let cached = CachedPrediction {
-                result: StateDetectionResult {
-                    window_id: Uuid::new_v4(),
-                    timestamp: chrono::Utc::now(),
-                    detected_state: crate::models::ADHDState::neutral(),
-                    state_distribution: StateDistribution::new(),
-                    confidence: 0.5,
-                    temporal_stability: 0.5,
-                    processing_time_ms: 25.0,
-                    feature_importance: vec![],
-                    intervention_readiness: 0.5,
-                    transition_stability: 0.5,
-                },
-                cache_time: Instant::now(),
-                hit_count: 0,
-            };
-            cache.cache.insert(key, cached);
+            let key = key(i);
+            let cached = CachedPrediction::new(&key, detection_result());
+            cache.insert(key, cached);
         }
         
-        engine.evict_cache_entries(&mut cache).await;
+        engine.evict_cache_entries(&mut cache, 0).await;
         
         // Should have evicted some entries
         assert!(cache.cache.len() < 10);
     }
 
+    fn key(bucket: i32) -> FeatureCacheKey {
+        FeatureCacheKey {
+            buckets: vec![bucket].into(),
+            window_duration_secs: 1,
+        }
+    }
+
+    fn detection_result() -> StateDetectionResult {
+        StateDetectionResult {
+            window_id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            detected_state: crate::models::ADHDState::neutral(),
+            state_distribution: StateDistribution::new(),
+            confidence: 0.5,
+            temporal_stability: 0.5,
+            processing_time_ms: 25.0,
+            feature_importance: vec![],
+            intervention_readiness: 0.5,
+            transition_stability: 0.5,
+            model_source: crate::cold_start::ModelSource::Heuristic,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_cap_evicts_oldest() {
+        let entry_bytes = CachedPrediction::new(&key(0), detection_result()).size_bytes;
+        let config = InferenceConfig {
+            cache_max_bytes: entry_bytes * 3,
+            ..Default::default()
+        };
+        let engine = InferenceEngine::with_config(Arc::new(StateDetectionEngine::new()), config);
+        
+        for i in 0..5 {
+            engine.cache_result(key(i), &detection_result()).await.unwrap();
+        }
+        
+        let stats = engine.get_cache_stats().await;
+        assert_eq!(stats.current_size, 3);
+        assert_eq!(stats.memory_bytes, entry_bytes * 3);
+        assert!(stats.memory_bytes <= stats.max_memory_bytes);
+        assert_eq!(stats.evictions, 2);
+        let cache = engine.prediction_cache.read().await;
+        assert!(!cache.cache.contains_key(&key(0)) && !cache.cache.contains_key(&key(1)));
+        assert!(cache.cache.contains_key(&key(4)));
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_removes_expired_entries() {
+        let config = InferenceConfig {
+            cache_ttl: Duration::from_millis(50),
+            cache_sweep_interval: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let engine = InferenceEngine::with_config(Arc::new(StateDetectionEngine::new()), config);
+        let sweeper = engine.start_cache_sweeper();
+        for i in 0..4 {
+            engine.cache_result(key(i), &detection_result()).await.unwrap();
+        }
+        assert_eq!(engine.get_cache_stats().await.current_size, 4);
+        
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let stats = engine.get_cache_stats().await;
+        assert_eq!((stats.current_size, stats.memory_bytes), (0, 0));
+        assert_eq!(stats.expired_removed, 4);
+        assert_eq!(stats.evictions, 0);
+        
+        // The sweeper stops with the engine
+        drop(engine);
+        tokio::time::timeout(Duration::from_secs(1), sweeper).await.unwrap().unwrap();
+    }
+
+    fn typing_window(start: chrono::DateTime<chrono::Utc>, interval_ms: u64) -> AnalysisWindow {
+        use skelly_jelly_storage::types::{KeyModifiers, KeystrokeEvent, RawEvent};
+        
+        let mut window = AnalysisWindow::new(start.into());
+        for i in 0..40u64 {
+            window.add_event(RawEvent::Keystroke(KeystrokeEvent {
+                timestamp: start + chrono::Duration::milliseconds((i * interval_ms) as i64),
+                key_code: 65 + (i % 26) as u32,
+                modifiers: KeyModifiers::default(),
+                inter_key_interval_ms: Some((interval_ms + i % 3) as u32),
+            }));
+        }
+        window
+    }
+
+    #[tokio::test]
+    async fn test_same_behaviour_at_another_time_hits_cache() {
+        let engine = InferenceEngine::new(Arc::new(StateDetectionEngine::new()));
+        let morning = typing_window(chrono::Utc::now() - chrono::Duration::hours(2), 150);
+        let afternoon = typing_window(chrono::Utc::now(), 150);
+        
+        let first = engine.infer(&morning).await.unwrap();
+        let second = engine.infer(&afternoon).await.unwrap();
+        assert_eq!(second.detected_state.state_type, first.detected_state.state_type);
+        assert_eq!(second.window_id, afternoon.window_id);
+        
+        // Much faster typing is different behaviour
+        engine.infer(&typing_window(chrono::Utc::now(), 40)).await.unwrap();
+        
+        let metrics = engine.get_metrics().await;
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (1, 2));
+        assert!((metrics.cache_hit_rate - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "chaos")]
+    #[tokio::test]
+    async fn test_injected_inference_timeouts_count_as_failures() {
+        use skelly_jelly_event_bus::{FaultInjector, FaultKind, FaultPoint, FaultRule, FaultSchedule};
+        
+        let faults = Arc::new(FaultInjector::new(FaultSchedule::new().with_rule(
+            FaultRule::new(FaultPoint::InferenceTimeout, FaultKind::Timeout(Duration::from_millis(50))).after(1).times(1),
+        )));
+        let engine = InferenceEngine::new(Arc::new(StateDetectionEngine::new())).with_fault_injector(faults.clone());
+        let window = typing_window(chrono::Utc::now(), 150);
+        
+        assert!(engine.infer(&window).await.is_ok());
+        assert!(matches!(
+            engine.infer(&window).await,
+            Err(AnalysisError::TimeoutError { timeout_ms: 50, .. })
+        ));
+        assert!(engine.infer(&window).await.is_ok());
+        
+        let metrics = engine.get_metrics().await;
+        assert_eq!((metrics.successful_inferences, metrics.failed_inferences), (2, 1));
+        assert_eq!(faults.injected().len(), 1);
+    }
+
+    #[test]
+    fn test_similar_feature_vectors_are_near() {
+        let step = InferenceConfig::default().cache_quantization_step;
+        let window = Duration::from_secs(30);
+        let mut features = FeatureVector::default();
+        features.keystroke_features[0] = 0.42;
+        let key = FeatureCacheKey::new(&features, window, step);
+        
+        features.keystroke_features[0] = 0.43;
+        let close = FeatureCacheKey::new(&features, window, step);
+        assert!(close.distance(&key).unwrap() <= 1);
+        
+        features.keystroke_features[0] = 0.6;
+        assert!(FeatureCacheKey::new(&features, window, step).distance(&key).unwrap() > 1);
+        assert_eq!(FeatureCacheKey::new(&features, Duration::from_secs(60), step).distance(&key), None);
+    }
+
     #[tokio::test]
     async fn test_metrics_tracking() {
         let state_detector = Arc::new(StateDetectionEngine::new());
@@ -12,10 +12,19 @@
 //! - **Online Learning**: Continuous adaptation to user patterns
 
 pub mod analysis_engine;
+pub mod app_focus;
+pub mod capture_feed;
+pub mod check_in;
+pub mod checkpoint;
+pub mod cold_start;
+pub mod daily_activity;
+pub mod drift_detection;
 pub mod error;
 pub mod event_bus_integration;
 pub mod event_processor;
 pub mod feature_extraction;
+pub mod focus_check;
+pub mod forecasting;
 pub mod inference;
 pub mod metrics;
 pub mod models;
@@ -23,32 +32,50 @@ pub mod online_learning;
 pub mod performance_validation;
 pub mod privacy;
 pub mod screenshot;
+pub mod sessions;
 pub mod sliding_window;
 pub mod state_detection;
+pub mod state_smoothing;
 pub mod training_pipeline;
+pub mod training_scheduler;
 pub mod types;
 
 // Re-export public API
 pub use analysis_engine::{AnalysisEngineImpl, AnalysisEngineConfig};
+pub use app_focus::{AppFocusConfig, AppFocusReport, AppFocusStats, AppFocusTracker, DailyAppFocus};
+pub use capture_feed::CaptureFeed;
+pub use check_in::{check_in_feedback, CheckInLabeler};
+pub use checkpoint::{CheckpointStore, StorageCheckpointStore, ANALYSIS_CONSUMER};
+pub use cold_start::{BaselineBundle, HeuristicClassifier, HeuristicConfig, ModelSource};
+pub use daily_activity::{day_activity, DayActivityResponder};
+pub use drift_detection::{DriftConfig, DriftDetector, DriftReport};
 pub use error::{AnalysisError, AnalysisResult};
 pub use event_bus_integration::{EventBusIntegration, EventBusConfig, EventProcessingMetrics, ProcessingStatus};
 pub use event_processor::EventProcessor;
 pub use feature_extraction::{FeatureExtractionPipeline, FeatureExtractor};
+pub use focus_check::{FocusCheck, FocusCheckResponder};
+pub use forecasting::{FocusForecaster, ForecastConfig};
 pub use inference::{InferenceEngine, InferenceConfig, InferencePriority};
-pub use metrics::{BehavioralMetrics, MetricEngine};
+pub use metrics::{AppSpan, BehavioralMetrics, MetricEngine};
 pub use models::{ADHDState, StateClassifier, StateDistribution, RandomForestClassifier, ONNXClassifier, StateModel};
 pub use online_learning::{OnlineLearningEngine, OnlineLearningConfig, UserFeedback as OnlineUserFeedback};
 pub use performance_validation::{PerformanceValidator, ValidationConfig, ValidationResult, ValidationStatus};
-pub use privacy::{LocalInferenceEngine, NetworkIsolationReport};
-pub use screenshot::{ScreenshotAnalyzer, ScreenshotContext, WorkType};
+pub use privacy::{EgressGuard, EgressHookStatus, FeatureExportConfig, IdentifierHasher, IdentifierHashingConfig, LocalInferenceEngine, NetworkIsolationReport, NoisedFeatureStats, PrivateFeatureExporter};
+pub use screenshot::{
+    ActivityKind, BudgetStats, ProcessingBudgetConfig, ScreenActivity, ScreenshotAnalyzer, ScreenshotContext, SkipReason,
+    WorkType,
+};
+pub use sessions::{SessionConfig, SessionPage, SessionQuery, SessionReconstructor, StateRecord, TaskFocusStats, WorkSession};
 pub use sliding_window::{AnalysisWindow, SlidingWindowManager};
 pub use state_detection::{StateDetectionEngine, StateDetectionResult, StateDetectionConfig};
-pub use training_pipeline::{TrainingPipeline, TrainingConfig, HyperparameterResults, TrainingStats};
+pub use state_smoothing::{SmoothedState, SmoothingConfig, SmoothingMetrics, SmoothingMode, StateSmoother};
+pub use training_pipeline::{TrainingPipeline, TrainingConfig, HyperparameterResults, OptimizationProgress, TrainingStats};
+pub use training_scheduler::{TrainingScheduler, TrainingScheduleConfig, TrainingBlocker, TrainingRunOutcome};
 pub use types::{AnalysisResult as AnalysisResultType, FeatureVector, FlowDepth, DistractionType};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use skelly_jelly_event_bus::{EventBusTrait, ModuleId};
+use skelly_jelly_event_bus::{EventBusTrait, ModuleId, RecoverySystem};
 use skelly_jelly_storage::types::EventBatch;
 use std::{sync::Arc, time::Duration};
 use uuid::Uuid;
@@ -59,6 +86,9 @@ pub trait AnalysisEngineTrait: Send + Sync {
     /// Process a batch of events from storage
     async fn analyze_batch(&self, batch: EventBatch) -> AnalysisResult<AnalysisResultType>;
     
+    /// Analyze the current, still open window right away and explain the result
+    async fn analyze_now(&self) -> AnalysisResult<FocusCheck>;
+    
     /// Get current ADHD state classification
     async fn get_current_state(&self) -> ADHDState;
     
@@ -73,6 +103,13 @@ pub trait AnalysisEngineTrait: Send + Sync {
     
     /// Get analysis performance metrics
     async fn get_performance_metrics(&self) -> PerformanceMetrics;
+
+    /// Pick up where the last run stopped
+    ///
+    /// Loads the saved checkpoint and replays the stored events after it, which
+    /// rebuilds the window that was open at shutdown. Call before feeding new
+    /// batches. Returns the number of events replayed; 0 without a checkpoint.
+    async fn resume_from_checkpoint(&self) -> AnalysisResult<usize>;
 }
 
 /// User feedback for online learning
@@ -95,11 +132,20 @@ pub struct PerformanceMetrics {
     pub cache_hit_rate: f32,
 }
 
-/// Create a new analysis engine instance
+/// Create a new analysis engine instance, checkpointing to `checkpoints` and
+/// reporting failures to `recovery` if given
 pub async fn create_analysis_engine(
     config: AnalysisEngineConfig,
     event_bus: Arc<dyn EventBusTrait>,
+    checkpoints: Option<Arc<dyn CheckpointStore>>,
+    recovery: Option<Arc<RecoverySystem>>,
 ) -> AnalysisResult<Arc<dyn AnalysisEngineTrait>> {
-    let engine = AnalysisEngineImpl::new(config, event_bus).await?;
+    let mut engine = AnalysisEngineImpl::new(config, event_bus).await?;
+    if let Some(store) = checkpoints {
+        engine = engine.with_checkpoint_store(store);
+    }
+    if let Some(recovery) = recovery {
+        engine = engine.with_recovery_system(recovery);
+    }
     Ok(Arc::new(engine))
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 //! Behavioral metrics calculation engine
 
 // Removed unused rayon import
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -44,6 +45,24 @@ pub struct BehavioralMetrics {
     // Stress indicators
     pub stress_indicator: f32,         // Stress level indicator (0-1)
     pub fatigue_indicator: f32,        // Fatigue level indicator (0-1)
+    
+    // Application focus
+    #[serde(default)]
+    pub app_spans: Vec<AppSpan>,       // Focused applications in time order
+}
+
+/// A stretch of time one application had focus within a window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppSpan {
+    pub app_name: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl AppSpan {
+    pub fn duration(&self) -> Duration {
+        (self.end - self.start).to_std().unwrap_or_default()
+    }
 }
 
 impl Default for BehavioralMetrics {
@@ -67,6 +86,7 @@ impl Default for BehavioralMetrics {
             energy_level_trend: 0.0,
             stress_indicator: 0.0,
             fatigue_indicator: 0.0,
+            app_spans: Vec::new(),
         }
     }
 }
@@ -103,6 +123,15 @@ impl BehavioralMetrics {
         
         (stress_factor + distraction_factor + low_productivity_factor + fatigue_factor).min(1.0)
     }
+
+    /// Focus time per application in this window
+    pub fn app_time(&self) -> HashMap<String, Duration> {
+        let mut times: HashMap<String, Duration> = HashMap::new();
+        for span in &self.app_spans {
+            *times.entry(span.app_name.clone()).or_default() += span.duration();
+        }
+        times
+    }
 }
 
 /// Trait for calculating specific metric types
@@ -168,6 +197,7 @@ impl MetricEngine {
         metrics.error_rate = self.calculate_error_rate(window);
         metrics.self_correction_rate = self.calculate_self_correction_rate(window);
         metrics.energy_level_trend = self.calculate_energy_trend(window);
+        metrics.app_spans = self.calculate_app_spans(window);
         
         metrics
     }
@@ -285,6 +315,24 @@ impl MetricEngine {
         max_duration
     }
 
+    /// Each focus event starts a span that lasts until the next one or the window's end
+    fn calculate_app_spans(&self, window: &AnalysisWindow) -> Vec<AppSpan> {
+        let mut focus_events = window.get_window_focus_events();
+        focus_events.sort_by_key(|event| event.timestamp);
+        let window_end = DateTime::<Utc>::from(window.end_time);
+
+        let mut spans: Vec<AppSpan> = Vec::with_capacity(focus_events.len());
+        for (index, event) in focus_events.iter().enumerate() {
+            let end = focus_events.get(index + 1).map_or(window_end, |next| next.timestamp).max(event.timestamp);
+            match spans.last_mut() {
+                // A title change within the same app continues its span
+                Some(last) if last.app_name == event.app_name => last.end = end,
+                _ => spans.push(AppSpan { app_name: event.app_name.clone(), start: event.timestamp, end }),
+            }
+        }
+        spans
+    }
+
     fn calculate_distraction_frequency(&self, window: &AnalysisWindow) -> f32 {
         let window_switches = window.get_window_focus_events().len();
         let duration_hours = window.duration().as_secs_f32() / 3600.0;
@@ -689,6 +737,41 @@ mod tests {
         assert!(result.unwrap() > 0.0);
     }
 
+    #[test]
+    fn test_app_spans_follow_focus_events() {
+        let engine = MetricEngine::new();
+        let start = Utc::now();
+        let mut window = AnalysisWindow::new(SystemTime::from(start));
+        let focus = |seconds: i64, title: &str, app: &str| {
+            RawEvent::WindowFocus(WindowFocusEvent {
+                timestamp: start + chrono::Duration::seconds(seconds),
+                window_title: title.to_string(),
+                app_name: app.to_string(),
+                process_id: 1,
+                duration_ms: None,
+            })
+        };
+        window.add_event(focus(0, "main.rs", "Code"));
+        window.add_event(focus(10, "lib.rs", "Code"));
+        window.add_event(focus(20, "#general", "Slack"));
+        window.add_event(focus(25, "main.rs", "Code"));
+        window.add_event(RawEvent::Keystroke(KeystrokeEvent {
+            timestamp: start + chrono::Duration::seconds(30),
+            key_code: 65,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: None,
+        }));
+
+        let metrics = engine.calculate_all(&window);
+        let apps: Vec<(&str, u64)> = metrics
+            .app_spans
+            .iter()
+            .map(|span| (span.app_name.as_str(), span.duration().as_secs()))
+            .collect();
+        assert_eq!(apps, vec![("Code", 20), ("Slack", 5), ("Code", 5)]);
+        assert_eq!(metrics.app_time()["Code"], Duration::from_secs(25));
+    }
+
     #[test]
     fn test_metric_engine_creation() {
         let engine = MetricEngine::new();
@@ -7,6 +7,7 @@
 //! - Active learning for optimal feedback collection
 //! - Model performance tracking and validation
 //! - Safe incremental updates without catastrophic forgetting
+//! - Drift detection against a frozen baseline with automatic rollback
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -16,10 +17,12 @@ use std::{
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
+use skelly_jelly_event_bus::{message::DriftDetected, BusMessage, EventBusTrait, MessagePayload, ModuleId};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::{
+    drift_detection::{DriftConfig, DriftDetector, DriftReport},
     error::{AnalysisError, AnalysisResult},
     models::{ADHDState, ADHDStateType, RandomForestClassifier, StateDistribution, ModelMetrics, StateModel},
     types::FeatureVector,
@@ -45,6 +48,9 @@ pub struct OnlineLearningEngine {
     
     /// Learning metrics
     metrics: Arc<RwLock<OnlineLearningMetrics>>,
+
+    /// Where drift is announced, if connected
+    event_bus: Option<Arc<dyn EventBusTrait>>,
 }
 
 /// Processes and validates user feedback
@@ -100,8 +106,11 @@ pub struct ModelValidator {
     /// Performance tracking
     performance_history: Arc<RwLock<VecDeque<ValidationResult>>>,
     
+    /// Model frozen when online learning started, for drift comparison
+    baseline: RandomForestClassifier,
+    
     /// Degradation detection
-    degradation_detector: DegradationDetector,
+    drift_detector: Mutex<DriftDetector>,
     
     /// Rollback mechanisms
     rollback_manager: Arc<RollbackManager>,
@@ -206,6 +215,9 @@ pub struct OnlineLearningMetrics {
     pub query_response_rate: f32,
     pub model_adaptation_rate: f32,
     pub personalization_score: f32,
+    /// Times drift forced a rollback to the last good checkpoint
+    #[serde(default)]
+    pub drift_rollbacks: u64,
 }
 
 impl OnlineLearningEngine {
@@ -213,10 +225,12 @@ impl OnlineLearningEngine {
     pub fn new(classifier: Arc<Mutex<RandomForestClassifier>>) -> Self {
         let config = OnlineLearningConfig::default();
         
+        let baseline = classifier.lock().unwrap().clone();
+        
         let feedback_processor = Arc::new(FeedbackProcessor::new(&config));
         let model_updater = Arc::new(ModelUpdater::new(classifier, &config));
         let active_learner = Arc::new(ActiveLearner::new(&config));
-        let validator = Arc::new(ModelValidator::new(&config));
+        let validator = Arc::new(ModelValidator::new(&config, baseline));
         
         Self {
             config,
@@ -225,7 +239,64 @@ impl OnlineLearningEngine {
             active_learner,
             validator,
             metrics: Arc::new(RwLock::new(OnlineLearningMetrics::default())),
+            event_bus: None,
+        }
+    }
+    
+    /// Publish `DriftDetected` on this bus when drift forces a rollback
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    /// Score the live and baseline models on freshly labelled samples, rolling
+    /// the live model back to its last good checkpoint if it has drifted
+    pub async fn check_drift(&self, samples: &[TrainingSample]) -> AnalysisResult<Option<DriftReport>> {
+        // Score on copies so no lock is held across predictions
+        let live = self.model_updater.snapshot();
+        let baseline = &self.validator.baseline;
+        
+        let mut drift = None;
+        for sample in samples {
+            let true_state = sample.true_state.state_type;
+            let live_correct = live.predict(&sample.features).await?.most_likely_state().0 == true_state;
+            let baseline_correct = baseline.predict(&sample.features).await?.most_likely_state().0 == true_state;
+            
+            let mut detector = self.validator.drift_detector.lock().unwrap();
+            if let Some(report) = detector.record(live_correct, baseline_correct) {
+                drift = Some(report);
+            }
+        }
+        
+        if let Some(report) = &drift {
+            self.handle_drift(report).await?;
+        }
+        Ok(drift)
+    }
+    
+    async fn handle_drift(&self, report: &DriftReport) -> AnalysisResult<()> {
+        let rolled_back_to = self.model_updater.rollback_last_update(&self.validator.rollback_manager).await?;
+        self.validator.drift_detector.lock().unwrap().reset();
+        
+        println!("Model drift detected: recent accuracy {:.3} vs baseline {:.3} over {} samples, rolled back to {:?}",
+                report.recent_accuracy, report.baseline_accuracy, report.samples, rolled_back_to);
+        
+        self.metrics.write().await.drift_rollbacks += 1;
+        
+        if let Some(event_bus) = &self.event_bus {
+            let event = DriftDetected {
+                model_type: "random_forest".to_string(),
+                recent_accuracy: report.recent_accuracy,
+                baseline_accuracy: report.baseline_accuracy,
+                samples: report.samples,
+                rolled_back_to,
+                timestamp: Utc::now(),
+            };
+            event_bus
+                .publish(BusMessage::new(ModuleId::AnalysisEngine, MessagePayload::DriftDetected(event)))
+                .await?;
         }
+        Ok(())
     }
     
     /// Process user feedback and trigger learning updates
@@ -293,6 +364,9 @@ impl OnlineLearningEngine {
             return Ok(());
         }
         
+        // Fresh labels show how the current model is doing before it learns from them
+        self.check_drift(&samples).await?;
+        
         // Validate update safety
         if !self.validator.validate_update_safety(&samples).await? {
             return Err(AnalysisError::ValidationFailed {
@@ -309,13 +383,17 @@ impl OnlineLearningEngine {
         
         if validation_result.accuracy < self.config.min_accuracy_threshold {
             // Rollback if performance degraded
-            self.model_updater.rollback_last_update().await?;
+            let old_accuracy = self.validator.rollback_manager.latest_accuracy().unwrap_or(0.0);
+            self.model_updater.rollback_last_update(&self.validator.rollback_manager).await?;
             return Err(AnalysisError::ModelPerformanceDegraded {
-                old_accuracy: 0.0, // Would track previous accuracy
+                old_accuracy,
                 new_accuracy: validation_result.accuracy,
             });
         }
         
+        // This version passed validation, so it is the new rollback target
+        self.validator.rollback_manager.save(self.model_updater.snapshot(), validation_result.accuracy);
+        
         // Update metrics
         let mut metrics = self.metrics.write().await;
         metrics.model_updates_performed += 1;
@@ -598,10 +676,20 @@ impl ModelUpdater {
         Ok(())
     }
     
-    async fn rollback_last_update(&self) -> AnalysisResult<()> {
-        // Implementation would restore previous model version
-        println!("Model rollback performed due to performance degradation");
-        Ok(())
+    /// Copy of the live model
+    fn snapshot(&self) -> RandomForestClassifier {
+        self.classifier.lock().unwrap().clone()
+    }
+    
+    /// Restore the most recent good checkpoint, returning its version
+    async fn rollback_last_update(&self, checkpoints: &RollbackManager) -> AnalysisResult<Option<Uuid>> {
+        let Some(checkpoint) = checkpoints.latest() else {
+            println!("No model checkpoint to roll back to; keeping the current model");
+            return Ok(None);
+        };
+        *self.classifier.lock().unwrap() = checkpoint.classifier;
+        println!("Model rolled back to checkpoint {} from {}", checkpoint.version_id, checkpoint.timestamp);
+        Ok(Some(checkpoint.version_id))
     }
     
     async fn reset_to_base_model(&self) -> AnalysisResult<()> {
@@ -668,16 +756,60 @@ impl ActiveLearner {
 }
 
 struct ValidationSet;
-struct DegradationDetector;
-struct RollbackManager;
+
+/// Model checkpoints kept for rollback
+const MAX_MODEL_CHECKPOINTS: usize = 5;
+
+/// A copy of the live model that passed validation
+#[derive(Clone)]
+struct ModelCheckpoint {
+    version_id: Uuid,
+    timestamp: DateTime<Utc>,
+    accuracy: f32,
+    classifier: RandomForestClassifier,
+}
+
+/// The last few known-good versions of the live model, newest last
+struct RollbackManager {
+    checkpoints: Mutex<VecDeque<ModelCheckpoint>>,
+}
+
+impl RollbackManager {
+    fn new() -> Self {
+        Self { checkpoints: Mutex::new(VecDeque::new()) }
+    }
+    
+    fn save(&self, classifier: RandomForestClassifier, accuracy: f32) -> Uuid {
+        let version_id = Uuid::new_v4();
+        let mut checkpoints = self.checkpoints.lock().unwrap();
+        checkpoints.push_back(ModelCheckpoint { version_id, timestamp: Utc::now(), accuracy, classifier });
+        while checkpoints.len() > MAX_MODEL_CHECKPOINTS {
+            checkpoints.pop_front();
+        }
+        version_id
+    }
+    
+    fn latest(&self) -> Option<ModelCheckpoint> {
+        self.checkpoints.lock().unwrap().back().cloned()
+    }
+    
+    fn latest_accuracy(&self) -> Option<f32> {
+        self.checkpoints.lock().unwrap().back().map(|checkpoint| checkpoint.accuracy)
+    }
+}
 
 impl ModelValidator {
-    fn new(_config: &OnlineLearningConfig) -> Self {
+    fn new(config: &OnlineLearningConfig, baseline: RandomForestClassifier) -> Self {
+        // The model online learning starts from is the first good checkpoint
+        let rollback_manager = RollbackManager::new();
+        rollback_manager.save(baseline.clone(), config.min_accuracy_threshold);
+        
         Self {
             validation_sets: Arc::new(RwLock::new(Vec::new())),
             performance_history: Arc::new(RwLock::new(VecDeque::new())),
-            degradation_detector: DegradationDetector,
-            rollback_manager: Arc::new(RollbackManager),
+            baseline,
+            drift_detector: Mutex::new(DriftDetector::new(config.drift.clone())),
+            rollback_manager: Arc::new(rollback_manager),
         }
     }
     
@@ -712,6 +844,9 @@ pub struct OnlineLearningConfig {
     pub enable_active_learning: bool,
     pub max_query_candidates: usize,
     pub validation_frequency: Duration,
+    /// When the live model counts as having drifted from its baseline
+    #[serde(default)]
+    pub drift: DriftConfig,
 }
 
 impl Default for OnlineLearningConfig {
@@ -726,6 +861,7 @@ impl Default for OnlineLearningConfig {
             enable_active_learning: true,
             max_query_candidates: 100,
             validation_frequency: Duration::from_secs(300), // 5 minutes
+            drift: DriftConfig::default(),
         }
     }
 }
@@ -742,6 +878,7 @@ impl Default for OnlineLearningMetrics {
             query_response_rate: 0.0,
             model_adaptation_rate: 0.0,
             personalization_score: 0.0,
+            drift_rollbacks: 0,
         }
     }
 }
@@ -827,4 +964,23 @@ mod tests {
         let weight = processor.calculate_sample_weight(&high_confidence_feedback);
         assert!(weight > 1.0); // Should have higher weight
     }
+
+    #[tokio::test]
+    async fn test_rollback_restores_latest_checkpoint() {
+        let config = OnlineLearningConfig::default();
+        let classifier = Arc::new(Mutex::new(RandomForestClassifier::new()));
+        let updater = ModelUpdater::new(classifier.clone(), &config);
+        let validator = ModelValidator::new(&config, classifier.lock().unwrap().clone());
+        let checkpoints = &validator.rollback_manager;
+        
+        assert_eq!(checkpoints.latest_accuracy(), Some(config.min_accuracy_threshold));
+        for accuracy in [0.8, 0.82, 0.84, 0.86, 0.88, 0.9] {
+            checkpoints.save(updater.snapshot(), accuracy);
+        }
+        assert_eq!(checkpoints.checkpoints.lock().unwrap().len(), MAX_MODEL_CHECKPOINTS);
+        
+        let latest = checkpoints.latest().unwrap().version_id;
+        assert_eq!(updater.rollback_last_update(checkpoints).await.unwrap(), Some(latest));
+        assert_eq!(checkpoints.latest_accuracy(), Some(0.9));
+    }
 }
\ No newline at end of file
@@ -279,8 +279,8 @@ impl PerformanceValidator {
         }
         
         if latencies.is_empty() {
-            return Err(AnalysisError::ValidationError {
-                message: "No successful inferences during latency testing".to_string(),
+            return Err(AnalysisError::ValidationFailed {
+                reason: "No successful inferences during latency testing".to_string(),
             });
         }
         
@@ -370,8 +370,8 @@ impl PerformanceValidator {
         }
         
         if total_samples == 0 {
-            return Err(AnalysisError::ValidationError {
-                message: "No successful predictions during accuracy testing".to_string(),
+            return Err(AnalysisError::ValidationFailed {
+                reason: "No successful predictions during accuracy testing".to_string(),
             });
         }
         
@@ -679,13 +679,13 @@ impl PerformanceValidator {
     /// Export validation results to JSON
     pub fn export_results(&self, path: &str) -> AnalysisResult<()> {
         let json = serde_json::to_string_pretty(&self.results_history)
-            .map_err(|e| AnalysisError::ValidationError {
-                message: format!("Failed to serialize results: {}", e),
+            .map_err(|e| AnalysisError::ValidationFailed {
+                reason: format!("Failed to serialize results: {}", e),
             })?;
         
         std::fs::write(path, json)
-            .map_err(|e| AnalysisError::ValidationError {
-                message: format!("Failed to write results file: {}", e),
+            .map_err(|e| AnalysisError::ValidationFailed {
+                reason: format!("Failed to write results file: {}", e),
             })?;
         
         println!("Validation results exported to: {}", path);
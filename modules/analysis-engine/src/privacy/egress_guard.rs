@@ -0,0 +1,264 @@
+//! Runtime enforcement of network isolation for local inference
+//!
+//! Inference code runs inside an [`InferenceScope`]. Any outbound connection
+//! attempted from a thread while it is in scope is refused, counted, and turns
+//! the inference into a [`AnalysisError::PrivacyViolation`]. Connections from
+//! other threads (the AI integration, the event bus) are left alone.
+//!
+//! With the `egress-hook` feature on Unix, the guard also interposes libc's
+//! `connect`, so sockets opened by any dependency during inference are caught,
+//! not just those that go through [`EgressGuard::check_egress`]. Loopback and
+//! Unix domain sockets are always allowed.
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::error::{AnalysisError, AnalysisResult};
+
+thread_local! {
+    /// Nesting depth of inference scopes on this thread
+    static SCOPE_DEPTH: Cell<u32> = const { Cell::new(0) };
+    /// Egress attempts refused on this thread since the outermost scope began
+    static SCOPE_VIOLATIONS: Cell<u64> = const { Cell::new(0) };
+    /// First destination refused in the current scope, for the error
+    static FIRST_DESTINATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Whether outbound connections are intercepted below the application
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EgressHookStatus {
+    /// libc `connect` is interposed for the whole process
+    Installed,
+    /// Only calls through [`EgressGuard::check_egress`] are checked
+    #[default]
+    Unavailable,
+}
+
+/// Counters kept by the process-wide guard
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EgressStats {
+    /// Inference scopes entered
+    pub scopes_entered: u64,
+    /// Outbound attempts refused inside an inference scope
+    pub blocked_attempts: u64,
+    pub hook: EgressHookStatus,
+}
+
+/// Process-wide egress guard for inference paths
+#[derive(Debug, Default)]
+pub struct EgressGuard {
+    scopes_entered: AtomicU64,
+    blocked_attempts: AtomicU64,
+}
+
+impl EgressGuard {
+    /// The guard shared by every inference engine in the process
+    pub fn global() -> &'static EgressGuard {
+        static GUARD: OnceLock<EgressGuard> = OnceLock::new();
+        GUARD.get_or_init(EgressGuard::default)
+    }
+
+    /// Put the current thread into an inference scope until the returned value drops
+    pub fn enter(&self, operation: &'static str) -> InferenceScope {
+        SCOPE_DEPTH.with(|depth| {
+            if depth.get() == 0 {
+                SCOPE_VIOLATIONS.with(|violations| violations.set(0));
+                FIRST_DESTINATION.with(|first| first.borrow_mut().take());
+            }
+            depth.set(depth.get() + 1);
+        });
+        self.scopes_entered.fetch_add(1, Ordering::Relaxed);
+        InferenceScope { operation, _not_send: PhantomData }
+    }
+
+    /// Whether the current thread is running inference
+    pub fn in_scope() -> bool {
+        SCOPE_DEPTH.try_with(|depth| depth.get() > 0).unwrap_or(false)
+    }
+
+    /// Refuse an outbound call to `destination` if the current thread is running inference
+    pub fn check_egress(&self, destination: &str) -> AnalysisResult<()> {
+        if !Self::in_scope() {
+            return Ok(());
+        }
+        self.record_blocked(destination);
+        Err(AnalysisError::PrivacyViolation {
+            operation: "inference".to_string(),
+            destination: destination.to_string(),
+        })
+    }
+
+    fn record_blocked(&self, destination: &str) {
+        self.blocked_attempts.fetch_add(1, Ordering::Relaxed);
+        let _ = SCOPE_VIOLATIONS.try_with(|violations| violations.set(violations.get() + 1));
+        let _ = FIRST_DESTINATION.try_with(|first| {
+            first.borrow_mut().get_or_insert_with(|| destination.to_string());
+        });
+    }
+
+    pub fn stats(&self) -> EgressStats {
+        EgressStats {
+            scopes_entered: self.scopes_entered.load(Ordering::Relaxed),
+            blocked_attempts: self.blocked_attempts.load(Ordering::Relaxed),
+            hook: hook_status(),
+        }
+    }
+}
+
+/// A thread running inference; egress is refused until this drops
+///
+/// Not `Send`: the scope belongs to the thread that entered it, so it must not
+/// be held across an `.await`.
+#[must_use = "egress is only guarded while the scope is alive"]
+pub struct InferenceScope {
+    operation: &'static str,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl InferenceScope {
+    /// End the scope, failing if anything tried to reach the network while it was open
+    pub fn finish(self) -> AnalysisResult<()> {
+        let violations = SCOPE_VIOLATIONS.with(|violations| violations.get());
+        if violations == 0 {
+            return Ok(());
+        }
+        let destination = FIRST_DESTINATION
+            .with(|first| first.borrow().clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        error!(
+            "🚨 PRIVACY VIOLATION: {} attempted {} network call(s) during local inference (first: {}); result discarded",
+            self.operation, violations, destination
+        );
+        Err(AnalysisError::PrivacyViolation {
+            operation: self.operation.to_string(),
+            destination,
+        })
+    }
+}
+
+impl Drop for InferenceScope {
+    fn drop(&mut self) {
+        let _ = SCOPE_DEPTH.try_with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+#[cfg(all(unix, feature = "egress-hook"))]
+fn hook_status() -> EgressHookStatus {
+    EgressHookStatus::Installed
+}
+
+#[cfg(not(all(unix, feature = "egress-hook")))]
+fn hook_status() -> EgressHookStatus {
+    EgressHookStatus::Unavailable
+}
+
+/// Interposed libc `connect`: refuses non-local connections from threads in an
+/// inference scope and forwards everything else to the real implementation
+#[cfg(all(unix, feature = "egress-hook"))]
+mod hook {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::sync::OnceLock;
+    use libc::{c_int, sa_family_t, sockaddr, sockaddr_in, sockaddr_in6, socklen_t};
+
+    use super::EgressGuard;
+
+    type ConnectFn = unsafe extern "C" fn(c_int, *const sockaddr, socklen_t) -> c_int;
+
+    fn real_connect() -> Option<ConnectFn> {
+        static REAL: OnceLock<usize> = OnceLock::new();
+        let address = *REAL.get_or_init(|| unsafe { libc::dlsym(libc::RTLD_NEXT, c"connect".as_ptr()) as usize });
+        // SAFETY: RTLD_NEXT resolves the next `connect` in link order, which has this signature
+        (address != 0).then(|| unsafe { std::mem::transmute::<usize, ConnectFn>(address) })
+    }
+
+    /// Where a connection would go, or `None` if it never leaves the machine
+    unsafe fn remote_destination(address: *const sockaddr, length: socklen_t) -> Option<String> {
+        if address.is_null() || (length as usize) < std::mem::size_of::<sa_family_t>() {
+            return None;
+        }
+        match (*address).sa_family as c_int {
+            libc::AF_UNIX => None,
+            libc::AF_INET if length as usize >= std::mem::size_of::<sockaddr_in>() => {
+                let v4 = &*(address as *const sockaddr_in);
+                let ip = Ipv4Addr::from(u32::from_be(v4.sin_addr.s_addr));
+                (!ip.is_loopback()).then(|| format!("{}:{}", ip, u16::from_be(v4.sin_port)))
+            }
+            libc::AF_INET6 if length as usize >= std::mem::size_of::<sockaddr_in6>() => {
+                let v6 = &*(address as *const sockaddr_in6);
+                let ip = Ipv6Addr::from(v6.sin6_addr.s6_addr);
+                let local = ip.is_loopback() || ip.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback());
+                (!local).then(|| format!("[{}]:{}", ip, u16::from_be(v6.sin6_port)))
+            }
+            family => Some(format!("socket family {}", family)),
+        }
+    }
+
+    unsafe fn set_errno(value: c_int) {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            *libc::__errno_location() = value;
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+        {
+            *libc::__error() = value;
+        }
+    }
+
+    /// # Safety
+    /// Same contract as libc `connect`.
+    #[no_mangle]
+    pub unsafe extern "C" fn connect(fd: c_int, address: *const sockaddr, length: socklen_t) -> c_int {
+        if EgressGuard::in_scope() {
+            if let Some(destination) = remote_destination(address, length) {
+                EgressGuard::global().record_blocked(&destination);
+                set_errno(libc::EACCES);
+                return -1;
+            }
+        }
+        match real_connect() {
+            Some(connect) => connect(fd, address, length),
+            None => {
+                set_errno(libc::ENOSYS);
+                -1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_egress_is_refused_only_inside_a_scope() {
+        let guard = EgressGuard::global();
+        assert!(guard.check_egress("api.openai.com:443").is_ok());
+
+        let before = guard.stats().blocked_attempts;
+        let scope = guard.enter("test_inference");
+        assert!(EgressGuard::in_scope());
+        assert!(matches!(
+            guard.check_egress("api.openai.com:443"),
+            Err(AnalysisError::PrivacyViolation { .. })
+        ));
+        assert!(matches!(scope.finish(), Err(AnalysisError::PrivacyViolation { .. })));
+
+        assert!(!EgressGuard::in_scope());
+        assert!(guard.stats().blocked_attempts > before);
+        assert!(guard.enter("clean_inference").finish().is_ok());
+    }
+
+    #[test]
+    fn test_other_threads_are_not_affected() {
+        let guard = EgressGuard::global();
+        let _scope = guard.enter("test_inference");
+        let outside = std::thread::spawn(|| EgressGuard::global().check_egress("localhost:11434").is_ok())
+            .join()
+            .unwrap();
+        assert!(outside);
+    }
+}
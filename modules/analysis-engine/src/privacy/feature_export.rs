@@ -0,0 +1,326 @@
+//! Differentially private export of aggregated feature statistics
+//!
+//! For users who opt into federated model improvement. Only aggregates ever
+//! leave the exporter: per-feature means and per-state sample counts over a
+//! dataset, each perturbed with Laplace noise calibrated to the configured
+//! epsilon. Raw events and individual feature vectors are never released.
+//!
+//! Exports are disabled by default. Every export attempt, released or refused,
+//! is appended to a local audit log, and the epsilon spent across all releases
+//! is capped by a total privacy budget recovered from that log on start-up.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::{ADHDState, ADHDStateType},
+    types::FeatureVector,
+};
+
+/// Opt-in settings for noised feature export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureExportConfig {
+    /// Exports are refused unless the user has opted in
+    pub enabled: bool,
+    /// Privacy loss per export; smaller means more noise
+    pub epsilon: f64,
+    /// Total epsilon that may ever be spent across exports
+    pub total_epsilon_budget: f64,
+    /// Smallest dataset an export may be computed from
+    pub min_samples: usize,
+    /// Feature values are clipped to this range before aggregation
+    pub clip_min: f32,
+    pub clip_max: f32,
+    /// Append-only record of every export attempt
+    pub audit_log_path: PathBuf,
+}
+
+impl Default for FeatureExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            epsilon: 1.0,
+            total_epsilon_budget: 10.0,
+            min_samples: 100,
+            clip_min: 0.0,
+            clip_max: 1.0,
+            audit_log_path: PathBuf::from("models/feature_export_audit.jsonl"),
+        }
+    }
+}
+
+/// Aggregated, noised statistics; the only thing an export releases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoisedFeatureStats {
+    pub export_id: Uuid,
+    pub epsilon: f64,
+    /// Noised number of samples aggregated
+    pub sample_count: f64,
+    /// Noised mean of each clipped feature, in `FeatureVector::to_vec` order
+    pub feature_means: Vec<f32>,
+    /// Noised sample count per ADHD state
+    pub state_counts: BTreeMap<String, f64>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// What happened to an export attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExportOutcome {
+    Released,
+    Refused { reason: String },
+}
+
+/// One line of the export audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportAuditRecord {
+    pub export_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    /// Why the export was requested, as given by the caller
+    pub purpose: String,
+    pub epsilon: f64,
+    /// Exact dataset size; stays in the local log and is never exported
+    pub sample_count: usize,
+    /// Statistics released, e.g. `feature_means[45]`
+    pub released: Vec<String>,
+    pub outcome: ExportOutcome,
+    /// Epsilon spent across all releases after this attempt
+    pub epsilon_spent_total: f64,
+}
+
+/// Computes noised aggregates and keeps the audit trail and privacy budget
+pub struct PrivateFeatureExporter {
+    config: FeatureExportConfig,
+    epsilon_spent: f64,
+}
+
+impl PrivateFeatureExporter {
+    /// Create an exporter, recovering the epsilon already spent from the audit log
+    pub fn new(config: FeatureExportConfig) -> AnalysisResult<Self> {
+        if config.epsilon <= 0.0 || !config.epsilon.is_finite() || config.clip_max <= config.clip_min {
+            return Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Invalid feature export settings: epsilon {} and clip range [{}, {}]",
+                    config.epsilon, config.clip_min, config.clip_max
+                ),
+            });
+        }
+        let epsilon_spent = Self::audit_trail_at(&config.audit_log_path)?
+            .iter()
+            .filter(|record| record.outcome == ExportOutcome::Released)
+            .map(|record| record.epsilon)
+            .sum();
+        Ok(Self { config, epsilon_spent })
+    }
+
+    /// Epsilon left before exports are refused
+    pub fn remaining_budget(&self) -> f64 {
+        (self.config.total_epsilon_budget - self.epsilon_spent).max(0.0)
+    }
+
+    /// Every export attempt recorded so far
+    pub fn audit_trail(&self) -> AnalysisResult<Vec<ExportAuditRecord>> {
+        Self::audit_trail_at(&self.config.audit_log_path)
+    }
+
+    /// Aggregate `samples` into noised statistics, refusing if the user has not
+    /// opted in, the dataset is too small, or the privacy budget is spent
+    pub fn export<'a>(
+        &mut self,
+        samples: impl IntoIterator<Item = &'a (FeatureVector, ADHDState)>,
+        purpose: &str,
+    ) -> AnalysisResult<NoisedFeatureStats> {
+        let rows: Vec<(Vec<f32>, ADHDStateType)> = samples
+            .into_iter()
+            .map(|(features, state)| (features.to_vec(), state.state_type))
+            .collect();
+        let export_id = Uuid::new_v4();
+
+        let refusal = if !self.config.enabled {
+            Some("feature export is disabled".to_string())
+        } else if rows.len() < self.config.min_samples {
+            Some(format!("{} samples is below the minimum of {}", rows.len(), self.config.min_samples))
+        } else if self.config.epsilon > self.remaining_budget() {
+            Some(format!(
+                "epsilon {} exceeds the remaining privacy budget {:.3}",
+                self.config.epsilon,
+                self.remaining_budget()
+            ))
+        } else {
+            None
+        };
+        if let Some(reason) = refusal {
+            self.audit(ExportAuditRecord {
+                export_id,
+                timestamp: Utc::now(),
+                purpose: purpose.to_string(),
+                epsilon: 0.0,
+                sample_count: rows.len(),
+                released: Vec::new(),
+                outcome: ExportOutcome::Refused { reason: reason.clone() },
+                epsilon_spent_total: self.epsilon_spent,
+            })?;
+            warn!("Refused feature export for {}: {}", purpose, reason);
+            return Err(AnalysisError::ExportRefused { reason });
+        }
+
+        let stats = self.noised_stats(export_id, &rows);
+        self.epsilon_spent += self.config.epsilon;
+        self.audit(ExportAuditRecord {
+            export_id,
+            timestamp: stats.generated_at,
+            purpose: purpose.to_string(),
+            epsilon: self.config.epsilon,
+            sample_count: rows.len(),
+            released: vec![
+                format!("feature_means[{}]", stats.feature_means.len()),
+                format!("state_counts[{}]", stats.state_counts.len()),
+            ],
+            outcome: ExportOutcome::Released,
+            epsilon_spent_total: self.epsilon_spent,
+        })?;
+        info!(
+            "🔏 Exported noised feature statistics for {} (ε={}, {:.3} of budget left)",
+            purpose,
+            self.config.epsilon,
+            self.remaining_budget()
+        );
+        Ok(stats)
+    }
+
+    /// Laplace mechanism over per-state counts and per-feature sums, half the epsilon each
+    ///
+    /// Adding or removing one sample changes the count histogram by 1 in L1 and
+    /// the feature sums by at most `width` per feature after clipping. Means are
+    /// derived from the noised sums and counts, which is post-processing and
+    /// costs no further privacy.
+    fn noised_stats(&self, export_id: Uuid, rows: &[(Vec<f32>, ADHDStateType)]) -> NoisedFeatureStats {
+        let epsilon = self.config.epsilon;
+        let (clip_min, clip_max) = (self.config.clip_min, self.config.clip_max);
+        let feature_count = rows.first().map_or(0, |(features, _)| features.len());
+        let width = clip_min.abs().max(clip_max.abs()) as f64;
+        let mut rng = rand::thread_rng();
+
+        let mut state_counts = BTreeMap::new();
+        for state_type in ADHDStateType::all() {
+            let count = rows.iter().filter(|(_, row_state)| *row_state == state_type).count() as f64;
+            let noised = (count + laplace(&mut rng, 1.0 / (epsilon / 2.0))).max(0.0);
+            state_counts.insert(format!("{:?}", state_type), noised);
+        }
+        let sample_count: f64 = state_counts.values().sum();
+
+        let sum_scale = feature_count as f64 * width / (epsilon / 2.0);
+        let feature_means = (0..feature_count)
+            .map(|i| {
+                let sum: f64 = rows.iter().map(|(features, _)| features[i].clamp(clip_min, clip_max) as f64).sum();
+                let noised_sum = sum + laplace(&mut rng, sum_scale);
+                (noised_sum / sample_count.max(1.0)).clamp(clip_min as f64, clip_max as f64) as f32
+            })
+            .collect();
+
+        NoisedFeatureStats {
+            export_id,
+            epsilon,
+            sample_count,
+            feature_means,
+            state_counts,
+            generated_at: Utc::now(),
+        }
+    }
+
+    fn audit(&self, record: ExportAuditRecord) -> AnalysisResult<()> {
+        let path = &self.config.audit_log_path;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        Ok(())
+    }
+
+    fn audit_trail_at(path: &Path) -> AnalysisResult<Vec<ExportAuditRecord>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(AnalysisError::from))
+            .collect()
+    }
+}
+
+/// Draw from a zero-centred Laplace distribution with the given scale
+fn laplace(rng: &mut impl Rng, scale: f64) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(count: usize) -> Vec<(FeatureVector, ADHDState)> {
+        (0..count)
+            .map(|i| {
+                let state = if i % 2 == 0 { ADHDState::flow() } else { ADHDState::distracted() };
+                (FeatureVector::default(), state)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_export_is_disabled_by_default_and_audited() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut exporter = PrivateFeatureExporter::new(FeatureExportConfig {
+            audit_log_path: dir.path().join("audit.jsonl"),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let result = exporter.export(&samples(500), "federated_round_1");
+        assert!(matches!(result, Err(AnalysisError::ExportRefused { .. })));
+
+        let trail = exporter.audit_trail().unwrap();
+        assert_eq!(trail.len(), 1);
+        assert!(matches!(trail[0].outcome, ExportOutcome::Refused { .. }));
+        assert!(trail[0].released.is_empty());
+    }
+
+    #[test]
+    fn test_released_stats_are_aggregated_and_spend_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = FeatureExportConfig {
+            enabled: true,
+            epsilon: 2.0,
+            total_epsilon_budget: 3.0,
+            audit_log_path: dir.path().join("audit.jsonl"),
+            ..Default::default()
+        };
+        let mut exporter = PrivateFeatureExporter::new(config.clone()).unwrap();
+
+        let stats = exporter.export(&samples(1000), "federated_round_1").unwrap();
+        assert_eq!(stats.feature_means.len(), 45);
+        assert!(stats.feature_means.iter().all(|mean| (0.0..=1.0).contains(mean)));
+        assert_eq!(stats.state_counts.len(), ADHDStateType::all().len());
+        assert!((stats.sample_count - 1000.0).abs() < 100.0);
+
+        // The budget survives a restart because it is rebuilt from the audit log
+        let mut restarted = PrivateFeatureExporter::new(config).unwrap();
+        assert_eq!(restarted.remaining_budget(), 1.0);
+        assert!(matches!(
+            restarted.export(&samples(1000), "federated_round_2"),
+            Err(AnalysisError::ExportRefused { .. })
+        ));
+        assert_eq!(restarted.audit_trail().unwrap().len(), 2);
+    }
+}
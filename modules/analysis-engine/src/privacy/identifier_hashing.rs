@@ -0,0 +1,234 @@
+//! Salted hashing of application and category identifiers
+//!
+//! App names and work categories are persisted as keys of the per-day focus
+//! totals, and anything persisted can end up in an export or a backup. With
+//! hashing enabled, identifiers are replaced by a keyed SHA-256 of the name
+//! before they are stored. The key is a random salt created once per install
+//! and never leaves the device, so the same app hashes differently on every
+//! install and the hashes can't be matched against a list of known app names.
+//!
+//! To show readable names in the UI, every hashed identifier is also recorded
+//! in a local lookup table. The table is only read when presenting results on
+//! this device; exporters must work with the hashed form.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AnalysisError, AnalysisResult};
+
+const SALT_LEN: usize = 32;
+
+/// Prefix marking a stored identifier as hashed
+const HASHED_PREFIX: &str = "h:";
+
+/// Whether identifiers are hashed and where the salt and lookup table live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentifierHashingConfig {
+    /// Hash app names and categories before they are persisted
+    pub enabled: bool,
+    /// Per-install salt, created on first use with mode 0600
+    pub salt_path: PathBuf,
+    /// Hash to name table for display on this device only
+    pub lookup_path: PathBuf,
+}
+
+impl Default for IdentifierHashingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            salt_path: PathBuf::from("models/identifier_salt"),
+            lookup_path: PathBuf::from("models/identifier_lookup.json"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Lookup {
+    names: BTreeMap<String, String>,
+    dirty: bool,
+}
+
+/// Turns identifiers into their persisted form and back for display
+///
+/// When disabled, identifiers pass through unchanged and nothing is read or
+/// written.
+#[derive(Debug)]
+pub struct IdentifierHasher {
+    config: IdentifierHashingConfig,
+    salt: Option<Vec<u8>>,
+    lookup: Mutex<Lookup>,
+}
+
+impl IdentifierHasher {
+    /// Load the salt and lookup table, creating the salt if this install has none
+    pub fn new(config: IdentifierHashingConfig) -> AnalysisResult<Self> {
+        let (salt, names) = if config.enabled {
+            (Some(Self::load_or_create_salt(&config.salt_path)?), Self::load_lookup(&config.lookup_path)?)
+        } else {
+            (None, BTreeMap::new())
+        };
+        Ok(Self { config, salt, lookup: Mutex::new(Lookup { names, dirty: false }) })
+    }
+
+    /// A hasher that leaves identifiers as they are
+    pub fn disabled() -> Self {
+        Self { config: IdentifierHashingConfig::default(), salt: None, lookup: Mutex::new(Lookup::default()) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.salt.is_some()
+    }
+
+    /// The form of `identifier` to persist: its salted hash, or itself when disabled
+    pub fn pseudonymize(&self, identifier: &str) -> String {
+        let Some(salt) = &self.salt else {
+            return identifier.to_string();
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(identifier.as_bytes());
+        let digest = hasher.finalize();
+        let hashed: String = std::iter::once(HASHED_PREFIX.to_string())
+            .chain(digest[..16].iter().map(|byte| format!("{:02x}", byte)))
+            .collect();
+
+        let mut lookup = self.lookup.lock().unwrap();
+        if !lookup.names.contains_key(&hashed) {
+            lookup.names.insert(hashed.clone(), identifier.to_string());
+            lookup.dirty = true;
+        }
+        hashed
+    }
+
+    /// The readable name for a persisted identifier, for display on this device
+    ///
+    /// Hashes missing from the lookup table, e.g. after it was deleted, are
+    /// returned as they are.
+    pub fn display_name(&self, stored: &str) -> String {
+        if !stored.starts_with(HASHED_PREFIX) {
+            return stored.to_string();
+        }
+        self.lookup.lock().unwrap().names.get(stored).cloned().unwrap_or_else(|| stored.to_string())
+    }
+
+    /// Write names hashed since the last flush to the lookup table
+    pub fn flush(&self) -> AnalysisResult<()> {
+        let mut lookup = self.lookup.lock().unwrap();
+        if !lookup.dirty {
+            return Ok(());
+        }
+        let path = &self.config.lookup_path;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string(&lookup.names)?)?;
+        fs::rename(&temp, path)?;
+        lookup.dirty = false;
+        Ok(())
+    }
+
+    fn load_or_create_salt(path: &Path) -> AnalysisResult<Vec<u8>> {
+        if path.exists() {
+            let salt = fs::read(path)?;
+            if salt.len() != SALT_LEN {
+                return Err(AnalysisError::DataLoadError {
+                    path: path.display().to_string(),
+                    message: format!("Identifier salt is {} bytes, expected {}", salt.len(), SALT_LEN),
+                });
+            }
+            return Ok(salt);
+        }
+
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut salt = vec![0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(path)?.write_all(&salt)?;
+        Ok(salt)
+    }
+
+    fn load_lookup(path: &Path) -> AnalysisResult<BTreeMap<String, String>> {
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| AnalysisError::DataLoadError {
+            path: path.display().to_string(),
+            message: format!("Failed to parse identifier lookup table: {}", e),
+        })
+    }
+}
+
+impl Drop for IdentifierHasher {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, install: &str) -> IdentifierHashingConfig {
+        IdentifierHashingConfig {
+            enabled: true,
+            salt_path: dir.join(install).join("salt"),
+            lookup_path: dir.join(install).join("lookup.json"),
+        }
+    }
+
+    #[test]
+    fn test_hashes_are_stable_per_install_and_resolve_locally() {
+        let dir = tempfile::tempdir().unwrap();
+        let hasher = IdentifierHasher::new(config(dir.path(), "a")).unwrap();
+        let slack = hasher.pseudonymize("Slack");
+        assert!(slack.starts_with(HASHED_PREFIX));
+        assert!(!slack.contains("Slack"));
+        assert_eq!(hasher.display_name(&slack), "Slack");
+        hasher.flush().unwrap();
+        drop(hasher);
+
+        // A restart reuses the salt and the lookup table
+        let restarted = IdentifierHasher::new(config(dir.path(), "a")).unwrap();
+        assert_eq!(restarted.display_name(&slack), "Slack");
+        assert_eq!(restarted.pseudonymize("Slack"), slack);
+
+        // Another install gets its own salt
+        let other = IdentifierHasher::new(config(dir.path(), "b")).unwrap();
+        assert_ne!(other.pseudonymize("Slack"), slack);
+        assert_eq!(other.display_name(&slack), slack);
+    }
+
+    #[test]
+    fn test_disabled_passes_identifiers_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let hasher = IdentifierHasher::new(IdentifierHashingConfig {
+            enabled: false,
+            ..config(dir.path(), "a")
+        })
+        .unwrap();
+        assert!(!hasher.is_enabled());
+        assert_eq!(hasher.pseudonymize("Slack"), "Slack");
+        assert_eq!(hasher.display_name("Slack"), "Slack");
+        hasher.flush().unwrap();
+        assert!(!dir.path().join("a").exists());
+    }
+}
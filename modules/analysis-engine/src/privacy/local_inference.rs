@@ -14,6 +14,7 @@ use serde::{Serialize, Deserialize};
 use crate::{
     error::{AnalysisError, AnalysisResult},
     models::{ADHDState, StateDistribution},
+    privacy::egress_guard::{EgressGuard, EgressHookStatus},
     types::FeatureVector,
 };
 
@@ -206,11 +207,16 @@ impl LocalInferenceEngine {
             return Ok(cached);
         }
         
-        // Apply privacy-preserving feature encoding
-        let encoded_features = self.models.feature_encoder.encode_features(features)?;
-        
-        // Perform local inference using rule-based + statistical model
-        let adhd_state = self.models.adhd_model.predict(&encoded_features)?;
+        // Encoding and prediction run with egress refused on this thread
+        let scope = EgressGuard::global().enter("local_inference");
+        let prediction = self.models.feature_encoder
+            .encode_features(features)
+            .and_then(|encoded_features| self.models.adhd_model.predict(&encoded_features));
+        if let Err(violation) = scope.finish() {
+            self.log_inference("local_inference", true, true).await;
+            return Err(violation);
+        }
+        let adhd_state = prediction?;
         
         // Cache result for performance
         self.cache_result(features, &adhd_state).await?;
@@ -222,4 +228,657 @@ impl LocalInferenceEngine {
         debug!("Local inference completed in {:?}", inference_time);
         
         Ok(adhd_state)
-    }\n    \n    /// Validate that no network access is attempted\n    fn validate_network_isolation(&self) -> AnalysisResult<()> {\n        if !self.network_validator.validation_enabled {\n            return Ok(());\n        }\n        \n        // Check for any network-related system calls or library usage\n        // This is a compile-time and runtime validation\n        \n        // Verify no HTTP clients are initialized\n        #[cfg(feature = \"network-check\")]\n        {\n            // This would be a compile-time check to ensure no network dependencies\n            // are included in the binary when privacy mode is enabled\n            compile_error!(\"Network dependencies detected in privacy mode\");\n        }\n        \n        // Runtime validation - check for suspicious network indicators\n        if std::env::var(\"HTTP_PROXY\").is_ok() || std::env::var(\"HTTPS_PROXY\").is_ok() {\n            warn!(\"Network proxy detected - ensuring local-only processing\");\n        }\n        \n        Ok(())\n    }\n    \n    /// Check inference cache\n    async fn check_cache(&self, features: &FeatureVector) -> AnalysisResult<Option<ADHDState>> {\n        let cache = self.inference_cache.read().map_err(|_| AnalysisError::ConcurrencyError {\n            operation: \"cache_read\".to_string()\n        })?;\n        \n        let feature_hash = self.hash_features(features);\n        let cache_key = format!(\"adhd_{}\", feature_hash);\n        \n        if let Some(cached) = cache.cache.get(&cache_key) {\n            if cached.timestamp.elapsed() <= cache.ttl {\n                debug!(\"Cache hit for feature hash: {}\", feature_hash);\n                return Ok(Some(cached.result.clone()));\n            }\n        }\n        \n        Ok(None)\n    }\n    \n    /// Cache inference result\n    async fn cache_result(&self, features: &FeatureVector, result: &ADHDState) -> AnalysisResult<()> {\n        let mut cache = self.inference_cache.write().map_err(|_| AnalysisError::ConcurrencyError {\n            operation: \"cache_write\".to_string()\n        })?;\n        \n        let feature_hash = self.hash_features(features);\n        let cache_key = format!(\"adhd_{}\", feature_hash);\n        \n        // Evict old entries if cache is full\n        if cache.cache.len() >= cache.max_size {\n            self.evict_old_entries(&mut cache);\n        }\n        \n        cache.cache.insert(cache_key, CachedInference {\n            result: result.clone(),\n            confidence: 0.95, // Local model confidence\n            timestamp: Instant::now(),\n            feature_hash,\n        });\n        \n        Ok(())\n    }\n    \n    /// Hash features for cache key generation\n    fn hash_features(&self, features: &FeatureVector) -> u64 {\n        use std::collections::hash_map::DefaultHasher;\n        use std::hash::{Hash, Hasher};\n        \n        let mut hasher = DefaultHasher::new();\n        \n        // Hash key feature components (privacy-preserving)\n        if let Some(keystroke_features) = &features.keystroke_features {\n            keystroke_features.typing_speed.to_bits().hash(&mut hasher);\n            keystroke_features.pause_frequency.to_bits().hash(&mut hasher);\n        }\n        \n        if let Some(mouse_features) = &features.mouse_features {\n            mouse_features.movement_velocity.to_bits().hash(&mut hasher);\n            mouse_features.click_frequency.to_bits().hash(&mut hasher);\n        }\n        \n        hasher.finish()\n    }\n    \n    /// Evict old cache entries\n    fn evict_old_entries(&self, cache: &mut InferenceCache) {\n        let now = Instant::now();\n        let mut expired_keys = Vec::new();\n        \n        for (key, entry) in &cache.cache {\n            if now.duration_since(entry.timestamp) > cache.ttl {\n                expired_keys.push(key.clone());\n            }\n        }\n        \n        for key in expired_keys {\n            cache.cache.remove(&key);\n        }\n        \n        // If still too full, remove oldest entries\n        if cache.cache.len() >= cache.max_size {\n            let mut entries: Vec<_> = cache.cache.iter().collect();\n            entries.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp));\n            \n            let remove_count = cache.cache.len() - cache.max_size + 1;\n            for (key, _) in entries.iter().take(remove_count) {\n                cache.cache.remove(*key);\n            }\n        }\n    }\n    \n    /// Log privacy-compliant inference operation\n    async fn log_inference(&self, operation: &str, local_processing: bool, network_attempted: bool) {\n        let entry = PrivacyAuditEntry {\n            timestamp: chrono::Utc::now(),\n            operation: operation.to_string(),\n            local_processing,\n            network_access_attempted: network_attempted,\n            data_anonymized: true,\n            details: format!(\"Local inference operation: {}\", operation),\n        };\n        \n        if let Ok(mut log) = self.privacy_log.write() {\n            log.push(entry);\n            \n            // Keep log size manageable\n            if log.len() > 1000 {\n                log.drain(0..100);\n            }\n        }\n    }\n    \n    /// Get privacy audit log\n    pub async fn get_privacy_audit_log(&self) -> Vec<PrivacyAuditEntry> {\n        self.privacy_log.read()\n            .map(|log| log.clone())\n            .unwrap_or_default()\n    }\n    \n    /// Verify zero network calls during inference\n    pub fn verify_network_isolation(&self) -> NetworkIsolationReport {\n        let audit_log = self.privacy_log.read().unwrap_or_else(|_| std::sync::RwLockReadGuard::try_from(Vec::new().into()).unwrap());\n        \n        let total_operations = audit_log.len();\n        let network_attempts = audit_log.iter()\n            .filter(|entry| entry.network_access_attempted)\n            .count();\n        \n        let local_processing_rate = if total_operations > 0 {\n            audit_log.iter()\n                .filter(|entry| entry.local_processing)\n                .count() as f32 / total_operations as f32\n        } else {\n            1.0\n        };\n        \n        NetworkIsolationReport {\n            total_operations,\n            network_attempts,\n            local_processing_rate,\n            isolation_verified: network_attempts == 0,\n            report_timestamp: chrono::Utc::now(),\n        }\n    }\n}\n\nimpl ModelRegistry {\n    fn new() -> Self {\n        let adhd_model = LocalADHDModel::new();\n        let feature_encoder = PrivacyFeatureEncoder::new();\n        let mut model_metadata = HashMap::new();\n        \n        // Add ADHD model metadata\n        model_metadata.insert(\"adhd_local\".to_string(), ModelMetadata {\n            name: \"Local ADHD State Detector\".to_string(),\n            version: \"1.0.0\".to_string(),\n            training_date: chrono::Utc::now(),\n            accuracy_metrics: AccuracyMetrics {\n                precision: 0.92,\n                recall: 0.89,\n                f1_score: 0.905,\n                validation_accuracy: 0.91,\n            },\n            privacy_level: PrivacyLevel::LocalOnly,\n        });\n        \n        Self {\n            adhd_model,\n            feature_encoder,\n            model_metadata,\n        }\n    }\n}\n\nimpl LocalADHDModel {\n    fn new() -> Self {\n        let parameters = ModelParameters::default();\n        let mut feature_weights = HashMap::new();\n        \n        // Initialize feature weights based on research\n        feature_weights.insert(\"typing_speed\".to_string(), 0.25);\n        feature_weights.insert(\"typing_consistency\".to_string(), 0.20);\n        feature_weights.insert(\"mouse_movement\".to_string(), 0.15);\n        feature_weights.insert(\"window_switching\".to_string(), 0.20);\n        feature_weights.insert(\"pause_patterns\".to_string(), 0.20);\n        \n        let baselines = StatisticalBaselines::new();\n        \n        Self {\n            parameters,\n            feature_weights,\n            baselines,\n        }\n    }\n    \n    /// Predict ADHD state using local rule-based + statistical model\n    fn predict(&self, features: &EncodedFeatures) -> AnalysisResult<ADHDState> {\n        let mut state_scores = HashMap::new();\n        \n        // Analyze keystroke patterns\n        let keystroke_score = self.analyze_keystroke_patterns(features)?;\n        state_scores.insert(\"keystroke\".to_string(), keystroke_score);\n        \n        // Analyze mouse behavior\n        let mouse_score = self.analyze_mouse_behavior(features)?;\n        state_scores.insert(\"mouse\".to_string(), mouse_score);\n        \n        // Analyze attention patterns\n        let attention_score = self.analyze_attention_patterns(features)?;\n        state_scores.insert(\"attention\".to_string(), attention_score);\n        \n        // Combine scores using weighted average\n        let combined_score = self.combine_scores(&state_scores)?;\n        \n        // Map to ADHD state\n        let adhd_state = self.map_to_adhd_state(combined_score);\n        \n        Ok(adhd_state)\n    }\n    \n    fn analyze_keystroke_patterns(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {\n        let typing_speed = features.typing_speed.unwrap_or(0.0);\n        let pause_frequency = features.pause_frequency.unwrap_or(0.0);\n        let backspace_ratio = features.backspace_ratio.unwrap_or(0.0);\n        \n        // Rule-based analysis\n        let mut score = 0.5; // Neutral baseline\n        \n        // Fast, inconsistent typing may indicate hyperactivity\n        if typing_speed > self.parameters.keystroke_thresholds.typing_speed_max {\n            score += 0.2;\n        }\n        \n        // High pause frequency may indicate inattention\n        if pause_frequency > self.parameters.keystroke_thresholds.pause_duration_threshold {\n            score += 0.15;\n        }\n        \n        // High backspace ratio may indicate impulsivity\n        if backspace_ratio > self.parameters.keystroke_thresholds.backspace_ratio_threshold {\n            score += 0.1;\n        }\n        \n        Ok(score.min(1.0))\n    }\n    \n    fn analyze_mouse_behavior(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {\n        let movement_velocity = features.movement_velocity.unwrap_or(0.0);\n        let click_frequency = features.click_frequency.unwrap_or(0.0);\n        let movement_smoothness = features.movement_smoothness.unwrap_or(0.0);\n        \n        let mut score = 0.5;\n        \n        // Rapid mouse movements may indicate restlessness\n        if movement_velocity > self.parameters.mouse_parameters.movement_velocity_threshold {\n            score += 0.15;\n        }\n        \n        // High click frequency may indicate impulsivity\n        if click_frequency > self.parameters.mouse_parameters.click_frequency_threshold {\n            score += 0.1;\n        }\n        \n        // Low movement smoothness may indicate difficulty with fine motor control\n        if movement_smoothness < self.parameters.mouse_parameters.movement_smoothness_min {\n            score += 0.1;\n        }\n        \n        Ok(score.min(1.0))\n    }\n    \n    fn analyze_attention_patterns(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {\n        let window_switch_frequency = features.window_switch_frequency.unwrap_or(0.0);\n        let focus_duration = features.focus_duration.unwrap_or(0.0);\n        let multitasking_score = features.multitasking_score.unwrap_or(0.0);\n        \n        let mut score = 0.5;\n        \n        // High window switching may indicate distractibility\n        if window_switch_frequency > self.parameters.window_patterns.switch_frequency_threshold {\n            score += 0.2;\n        }\n        \n        // Short focus duration may indicate attention difficulties\n        if focus_duration < self.parameters.window_patterns.focus_duration_min {\n            score += 0.15;\n        }\n        \n        // High multitasking score may indicate difficulty focusing\n        if multitasking_score > self.parameters.window_patterns.multitasking_score_threshold {\n            score += 0.1;\n        }\n        \n        Ok(score.min(1.0))\n    }\n    \n    fn combine_scores(&self, scores: &HashMap<String, f32>) -> AnalysisResult<f32> {\n        let mut weighted_sum = 0.0;\n        let mut total_weight = 0.0;\n        \n        for (feature, score) in scores {\n            if let Some(weight) = self.feature_weights.get(feature) {\n                weighted_sum += score * weight;\n                total_weight += weight;\n            }\n        }\n        \n        if total_weight > 0.0 {\n            Ok(weighted_sum / total_weight)\n        } else {\n            Ok(0.5) // Default neutral score\n        }\n    }\n    \n    fn map_to_adhd_state(&self, score: f32) -> ADHDState {\n        // Map continuous score to discrete ADHD state\n        if score < 0.3 {\n            ADHDState::focused() // Low score indicates good focus\n        } else if score < 0.7 {\n            ADHDState::neutral() // Medium score is neutral\n        } else {\n            ADHDState::distracted() // High score indicates distraction/hyperactivity\n        }\n    }\n}\n\nimpl StatisticalBaselines {\n    fn new() -> Self {\n        Self {\n            typing_speed_baseline: 40.0, // WPM\n            mouse_activity_baseline: 100.0, // movements per minute\n            focus_duration_baseline: 300.0, // 5 minutes\n            session_start_time: Instant::now(),\n            user_averages: HashMap::new(),\n        }\n    }\n}\n\nimpl PrivacyFeatureEncoder {\n    fn new() -> Self {\n        let mut dimension_maps = HashMap::new();\n        \n        // Define dimension reduction for privacy\n        dimension_maps.insert(\"keystroke\".to_string(), vec![0, 2, 4, 6, 8]);\n        dimension_maps.insert(\"mouse\".to_string(), vec![1, 3, 5, 7]);\n        dimension_maps.insert(\"window\".to_string(), vec![0, 1, 4, 5]);\n        \n        let noise_parameters = NoiseParameters {\n            gaussian_std: 0.01,\n            differential_privacy_epsilon: 0.1,\n            laplace_scale: 0.1,\n        };\n        \n        Self {\n            dimension_maps,\n            noise_parameters,\n        }\n    }\n    \n    /// Encode features with privacy preservation\n    fn encode_features(&self, features: &FeatureVector) -> AnalysisResult<EncodedFeatures> {\n        // Extract and encode keystroke features\n        let (typing_speed, pause_frequency, backspace_ratio) = if let Some(ks) = &features.keystroke_features {\n            (\n                Some(self.add_privacy_noise(ks.typing_speed)?),\n                Some(self.add_privacy_noise(ks.pause_frequency)?),\n                Some(self.add_privacy_noise(ks.backspace_ratio.unwrap_or(0.0))?),\n            )\n        } else {\n            (None, None, None)\n        };\n        \n        // Extract and encode mouse features\n        let (movement_velocity, click_frequency, movement_smoothness) = if let Some(ms) = &features.mouse_features {\n            (\n                Some(self.add_privacy_noise(ms.movement_velocity)?),\n                Some(self.add_privacy_noise(ms.click_frequency)?),\n                Some(self.add_privacy_noise(ms.smoothness_score.unwrap_or(0.0))?),\n            )\n        } else {\n            (None, None, None)\n        };\n        \n        // Extract and encode window features\n        let (window_switch_frequency, focus_duration, multitasking_score) = if let Some(ws) = &features.window_features {\n            (\n                Some(self.add_privacy_noise(ws.switch_frequency)?),\n                Some(self.add_privacy_noise(ws.average_focus_duration)?),\n                Some(self.add_privacy_noise(ws.multitasking_score.unwrap_or(0.0))?),\n            )\n        } else {\n            (None, None, None)\n        };\n        \n        Ok(EncodedFeatures {\n            typing_speed,\n            pause_frequency,\n            backspace_ratio,\n            movement_velocity,\n            click_frequency,\n            movement_smoothness,\n            window_switch_frequency,\n            focus_duration,\n            multitasking_score,\n        })\n    }\n    \n    /// Add differential privacy noise\n    fn add_privacy_noise(&self, value: f32) -> AnalysisResult<f32> {\n        use rand::Rng;\n        let mut rng = rand::thread_rng();\n        \n        // Add Gaussian noise for differential privacy\n        let noise: f32 = rng.gen::<f32>() * self.noise_parameters.gaussian_std;\n        let noisy_value = value + noise;\n        \n        // Ensure value stays within reasonable bounds\n        Ok(noisy_value.max(0.0).min(1000.0))\n    }\n}\n\nimpl NetworkIsolationValidator {\n    fn new() -> Self {\n        Self {\n            blocked_endpoints: vec![\n                \"api.openai.com\".to_string(),\n                \"googleapis.com\".to_string(),\n                \"amazonaws.com\".to_string(),\n                \"azure.com\".to_string(),\n                \"cloudflare.com\".to_string(),\n            ],\n            allowed_local_only: true,\n            validation_enabled: true,\n        }\n    }\n}\n\nimpl InferenceCache {\n    fn new() -> Self {\n        Self {\n            cache: HashMap::new(),\n            max_size: 100,\n            ttl: Duration::from_secs(300), // 5 minutes\n        }\n    }\n}\n\nimpl Default for ModelParameters {\n    fn default() -> Self {\n        Self {\n            keystroke_thresholds: KeystrokeThresholds {\n                typing_speed_min: 20.0,\n                typing_speed_max: 80.0,\n                pause_duration_threshold: 2.0,\n                backspace_ratio_threshold: 0.15,\n                burst_typing_threshold: 10.0,\n            },\n            mouse_parameters: MouseParameters {\n                movement_velocity_threshold: 500.0,\n                click_frequency_threshold: 60.0,\n                scroll_speed_threshold: 100.0,\n                movement_smoothness_min: 0.7,\n            },\n            window_patterns: WindowPatterns {\n                switch_frequency_threshold: 5.0,\n                focus_duration_min: 30.0,\n                multitasking_score_threshold: 0.7,\n                app_category_weights: HashMap::new(),\n            },\n            temporal_weights: TemporalWeights {\n                recent_weight: 0.5,\n                medium_weight: 0.3,\n                historical_weight: 0.2,\n                time_decay_factor: 0.95,\n            },\n        }\n    }\n}\n\n/// Encoded features with privacy preservation\n#[derive(Debug, Clone)]\nstruct EncodedFeatures {\n    // Keystroke features\n    typing_speed: Option<f32>,\n    pause_frequency: Option<f32>,\n    backspace_ratio: Option<f32>,\n    \n    // Mouse features\n    movement_velocity: Option<f32>,\n    click_frequency: Option<f32>,\n    movement_smoothness: Option<f32>,\n    \n    // Window features\n    window_switch_frequency: Option<f32>,\n    focus_duration: Option<f32>,\n    multitasking_score: Option<f32>,\n}\n\n/// Network isolation verification report\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct NetworkIsolationReport {\n    pub total_operations: usize,\n    pub network_attempts: usize,\n    pub local_processing_rate: f32,\n    pub isolation_verified: bool,\n    pub report_timestamp: chrono::DateTime<chrono::Utc>,\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n    use crate::types::{KeystrokeFeatures, MouseFeatures, WindowFeatures};\n    \n    #[tokio::test]\n    async fn test_local_inference_creation() {\n        let mut engine = LocalInferenceEngine::new();\n        assert!(engine.models.model_metadata.contains_key(\"adhd_local\"));\n    }\n    \n    #[tokio::test]\n    async fn test_network_isolation_validation() {\n        let engine = LocalInferenceEngine::new();\n        let result = engine.validate_network_isolation();\n        assert!(result.is_ok());\n    }\n    \n    #[tokio::test]\n    async fn test_local_inference_no_network() {\n        let mut engine = LocalInferenceEngine::new();\n        \n        let features = FeatureVector {\n            keystroke_features: Some(KeystrokeFeatures {\n                typing_speed: 45.0,\n                pause_frequency: 0.1,\n                backspace_ratio: Some(0.05),\n                burst_typing_events: 2,\n                rhythm_consistency: Some(0.8),\n            }),\n            mouse_features: Some(MouseFeatures {\n                movement_velocity: 200.0,\n                click_frequency: 30.0,\n                scroll_frequency: 10.0,\n                smoothness_score: Some(0.9),\n                precision_score: Some(0.85),\n            }),\n            window_features: Some(WindowFeatures {\n                switch_frequency: 3.0,\n                average_focus_duration: 180.0,\n                multitasking_score: Some(0.4),\n                app_diversity: 5,\n                productive_app_ratio: Some(0.7),\n            }),\n            temporal_features: None,\n        };\n        \n        let result = engine.infer_local(&features).await;\n        assert!(result.is_ok());\n        \n        // Verify no network access was attempted\n        let isolation_report = engine.verify_network_isolation();\n        assert_eq!(isolation_report.network_attempts, 0);\n        assert!(isolation_report.isolation_verified);\n    }\n    \n    #[test]\n    fn test_privacy_feature_encoding() {\n        let encoder = PrivacyFeatureEncoder::new();\n        \n        let features = FeatureVector {\n            keystroke_features: Some(KeystrokeFeatures {\n                typing_speed: 50.0,\n                pause_frequency: 0.2,\n                backspace_ratio: Some(0.1),\n                burst_typing_events: 3,\n                rhythm_consistency: Some(0.7),\n            }),\n            mouse_features: None,\n            window_features: None,\n            temporal_features: None,\n        };\n        \n        let encoded = encoder.encode_features(&features).unwrap();\n        \n        // Verify features are encoded (with noise)\n        assert!(encoded.typing_speed.is_some());\n        assert!(encoded.pause_frequency.is_some());\n        \n        // Verify noise was added (values should be slightly different)\n        let original_speed = features.keystroke_features.unwrap().typing_speed;\n        let encoded_speed = encoded.typing_speed.unwrap();\n        assert!((original_speed - encoded_speed).abs() > 0.0);\n    }\n    \n    #[test]\n    fn test_model_parameters_defaults() {\n        let params = ModelParameters::default();\n        assert!(params.keystroke_thresholds.typing_speed_max > 0.0);\n        assert!(params.mouse_parameters.movement_velocity_threshold > 0.0);\n        assert!(params.window_patterns.focus_duration_min > 0.0);\n    }\n    \n    #[tokio::test]\n    async fn test_privacy_audit_logging() {\n        let mut engine = LocalInferenceEngine::new();\n        \n        engine.log_inference(\"test_operation\", true, false).await;\n        \n        let audit_log = engine.get_privacy_audit_log().await;\n        assert_eq!(audit_log.len(), 1);\n        assert_eq!(audit_log[0].operation, \"test_operation\");\n        assert!(audit_log[0].local_processing);\n        assert!(!audit_log[0].network_access_attempted);\n    }\n}"
\ No newline at end of file
+    }
+    
+    /// Validate that no network access is attempted
+    fn validate_network_isolation(&self) -> AnalysisResult<()> {
+        if !self.network_validator.validation_enabled {
+            return Ok(());
+        }
+        
+        // Check for any network-related system calls or library usage
+        // This is a compile-time and runtime validation
+        
+        // Verify no HTTP clients are initialized
+        #[cfg(feature = "network-check")]
+        {
+            // This would be a compile-time check to ensure no network dependencies
+            // are included in the binary when privacy mode is enabled
+            compile_error!("Network dependencies detected in privacy mode");
+        }
+        
+        // Runtime validation - check for suspicious network indicators
+        if std::env::var("HTTP_PROXY").is_ok() || std::env::var("HTTPS_PROXY").is_ok() {
+            warn!("Network proxy detected - ensuring local-only processing");
+        }
+        
+        Ok(())
+    }
+    
+    /// Check inference cache
+    async fn check_cache(&self, features: &FeatureVector) -> AnalysisResult<Option<ADHDState>> {
+        let cache = self.inference_cache.read().map_err(|_| AnalysisError::ConcurrencyError {
+            operation: "cache_read".to_string()
+        })?;
+        
+        let feature_hash = self.hash_features(features);
+        let cache_key = format!("adhd_{}", feature_hash);
+        
+        if let Some(cached) = cache.cache.get(&cache_key) {
+            if cached.timestamp.elapsed() <= cache.ttl {
+                debug!("Cache hit for feature hash: {}", feature_hash);
+                return Ok(Some(cached.result.clone()));
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Cache inference result
+    async fn cache_result(&self, features: &FeatureVector, result: &ADHDState) -> AnalysisResult<()> {
+        let mut cache = self.inference_cache.write().map_err(|_| AnalysisError::ConcurrencyError {
+            operation: "cache_write".to_string()
+        })?;
+        
+        let feature_hash = self.hash_features(features);
+        let cache_key = format!("adhd_{}", feature_hash);
+        
+        // Evict old entries if cache is full
+        if cache.cache.len() >= cache.max_size {
+            self.evict_old_entries(&mut cache);
+        }
+        
+        cache.cache.insert(cache_key, CachedInference {
+            result: result.clone(),
+            confidence: 0.95, // Local model confidence
+            timestamp: Instant::now(),
+            feature_hash,
+        });
+        
+        Ok(())
+    }
+    
+    /// Hash features for cache key generation
+    fn hash_features(&self, features: &FeatureVector) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        
+        let mut hasher = DefaultHasher::new();
+        
+        // Hash key feature components (privacy-preserving)
+        if let Some(keystroke_features) = &features.keystroke_features {
+            keystroke_features.typing_speed.to_bits().hash(&mut hasher);
+            keystroke_features.pause_frequency.to_bits().hash(&mut hasher);
+        }
+        
+        if let Some(mouse_features) = &features.mouse_features {
+            mouse_features.movement_velocity.to_bits().hash(&mut hasher);
+            mouse_features.click_frequency.to_bits().hash(&mut hasher);
+        }
+        
+        hasher.finish()
+    }
+    
+    /// Evict old cache entries
+    fn evict_old_entries(&self, cache: &mut InferenceCache) {
+        let now = Instant::now();
+        let mut expired_keys = Vec::new();
+        
+        for (key, entry) in &cache.cache {
+            if now.duration_since(entry.timestamp) > cache.ttl {
+                expired_keys.push(key.clone());
+            }
+        }
+        
+        for key in expired_keys {
+            cache.cache.remove(&key);
+        }
+        
+        // If still too full, remove oldest entries
+        if cache.cache.len() >= cache.max_size {
+            let mut entries: Vec<_> = cache.cache.iter().collect();
+            entries.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp));
+            
+            let remove_count = cache.cache.len() - cache.max_size + 1;
+            for (key, _) in entries.iter().take(remove_count) {
+                cache.cache.remove(*key);
+            }
+        }
+    }
+    
+    /// Log privacy-compliant inference operation
+    async fn log_inference(&self, operation: &str, local_processing: bool, network_attempted: bool) {
+        let entry = PrivacyAuditEntry {
+            timestamp: chrono::Utc::now(),
+            operation: operation.to_string(),
+            local_processing,
+            network_access_attempted: network_attempted,
+            data_anonymized: true,
+            details: format!("Local inference operation: {}", operation),
+        };
+        
+        if let Ok(mut log) = self.privacy_log.write() {
+            log.push(entry);
+            
+            // Keep log size manageable
+            if log.len() > 1000 {
+                log.drain(0..100);
+            }
+        }
+    }
+    
+    /// Get privacy audit log
+    pub async fn get_privacy_audit_log(&self) -> Vec<PrivacyAuditEntry> {
+        self.privacy_log.read()
+            .map(|log| log.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Verify zero network calls during inference
+    pub fn verify_network_isolation(&self) -> NetworkIsolationReport {
+        let audit_log = self.privacy_log.read().unwrap_or_else(|_| std::sync::RwLockReadGuard::try_from(Vec::new().into()).unwrap());
+        
+        let total_operations = audit_log.len();
+        let network_attempts = audit_log.iter()
+            .filter(|entry| entry.network_access_attempted)
+            .count();
+        
+        let local_processing_rate = if total_operations > 0 {
+            audit_log.iter()
+                .filter(|entry| entry.local_processing)
+                .count() as f32 / total_operations as f32
+        } else {
+            1.0
+        };
+        
+        let egress = EgressGuard::global().stats();
+        NetworkIsolationReport {
+            total_operations,
+            network_attempts,
+            local_processing_rate,
+            isolation_verified: network_attempts == 0 && egress.blocked_attempts == 0,
+            blocked_egress_attempts: egress.blocked_attempts,
+            guarded_inferences: egress.scopes_entered,
+            egress_hook: egress.hook,
+            report_timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+impl ModelRegistry {
+    fn new() -> Self {
+        let adhd_model = LocalADHDModel::new();
+        let feature_encoder = PrivacyFeatureEncoder::new();
+        let mut model_metadata = HashMap::new();
+        
+        // Add ADHD model metadata
+        model_metadata.insert("adhd_local".to_string(), ModelMetadata {
+            name: "Local ADHD State Detector".to_string(),
+            version: "1.0.0".to_string(),
+            training_date: chrono::Utc::now(),
+            accuracy_metrics: AccuracyMetrics {
+                precision: 0.92,
+                recall: 0.89,
+                f1_score: 0.905,
+                validation_accuracy: 0.91,
+            },
+            privacy_level: PrivacyLevel::LocalOnly,
+        });
+        
+        Self {
+            adhd_model,
+            feature_encoder,
+            model_metadata,
+        }
+    }
+}
+
+impl LocalADHDModel {
+    fn new() -> Self {
+        let parameters = ModelParameters::default();
+        let mut feature_weights = HashMap::new();
+        
+        // Initialize feature weights based on research
+        feature_weights.insert("typing_speed".to_string(), 0.25);
+        feature_weights.insert("typing_consistency".to_string(), 0.20);
+        feature_weights.insert("mouse_movement".to_string(), 0.15);
+        feature_weights.insert("window_switching".to_string(), 0.20);
+        feature_weights.insert("pause_patterns".to_string(), 0.20);
+        
+        let baselines = StatisticalBaselines::new();
+        
+        Self {
+            parameters,
+            feature_weights,
+            baselines,
+        }
+    }
+    
+    /// Predict ADHD state using local rule-based + statistical model
+    fn predict(&self, features: &EncodedFeatures) -> AnalysisResult<ADHDState> {
+        let mut state_scores = HashMap::new();
+        
+        // Analyze keystroke patterns
+        let keystroke_score = self.analyze_keystroke_patterns(features)?;
+        state_scores.insert("keystroke".to_string(), keystroke_score);
+        
+        // Analyze mouse behavior
+        let mouse_score = self.analyze_mouse_behavior(features)?;
+        state_scores.insert("mouse".to_string(), mouse_score);
+        
+        // Analyze attention patterns
+        let attention_score = self.analyze_attention_patterns(features)?;
+        state_scores.insert("attention".to_string(), attention_score);
+        
+        // Combine scores using weighted average
+        let combined_score = self.combine_scores(&state_scores)?;
+        
+        // Map to ADHD state
+        let adhd_state = self.map_to_adhd_state(combined_score);
+        
+        Ok(adhd_state)
+    }
+    
+    fn analyze_keystroke_patterns(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {
+        let typing_speed = features.typing_speed.unwrap_or(0.0);
+        let pause_frequency = features.pause_frequency.unwrap_or(0.0);
+        let backspace_ratio = features.backspace_ratio.unwrap_or(0.0);
+        
+        // Rule-based analysis
+        let mut score = 0.5; // Neutral baseline
+        
+        // Fast, inconsistent typing may indicate hyperactivity
+        if typing_speed > self.parameters.keystroke_thresholds.typing_speed_max {
+            score += 0.2;
+        }
+        
+        // High pause frequency may indicate inattention
+        if pause_frequency > self.parameters.keystroke_thresholds.pause_duration_threshold {
+            score += 0.15;
+        }
+        
+        // High backspace ratio may indicate impulsivity
+        if backspace_ratio > self.parameters.keystroke_thresholds.backspace_ratio_threshold {
+            score += 0.1;
+        }
+        
+        Ok(score.min(1.0))
+    }
+    
+    fn analyze_mouse_behavior(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {
+        let movement_velocity = features.movement_velocity.unwrap_or(0.0);
+        let click_frequency = features.click_frequency.unwrap_or(0.0);
+        let movement_smoothness = features.movement_smoothness.unwrap_or(0.0);
+        
+        let mut score = 0.5;
+        
+        // Rapid mouse movements may indicate restlessness
+        if movement_velocity > self.parameters.mouse_parameters.movement_velocity_threshold {
+            score += 0.15;
+        }
+        
+        // High click frequency may indicate impulsivity
+        if click_frequency > self.parameters.mouse_parameters.click_frequency_threshold {
+            score += 0.1;
+        }
+        
+        // Low movement smoothness may indicate difficulty with fine motor control
+        if movement_smoothness < self.parameters.mouse_parameters.movement_smoothness_min {
+            score += 0.1;
+        }
+        
+        Ok(score.min(1.0))
+    }
+    
+    fn analyze_attention_patterns(&self, features: &EncodedFeatures) -> AnalysisResult<f32> {
+        let window_switch_frequency = features.window_switch_frequency.unwrap_or(0.0);
+        let focus_duration = features.focus_duration.unwrap_or(0.0);
+        let multitasking_score = features.multitasking_score.unwrap_or(0.0);
+        
+        let mut score = 0.5;
+        
+        // High window switching may indicate distractibility
+        if window_switch_frequency > self.parameters.window_patterns.switch_frequency_threshold {
+            score += 0.2;
+        }
+        
+        // Short focus duration may indicate attention difficulties
+        if focus_duration < self.parameters.window_patterns.focus_duration_min {
+            score += 0.15;
+        }
+        
+        // High multitasking score may indicate difficulty focusing
+        if multitasking_score > self.parameters.window_patterns.multitasking_score_threshold {
+            score += 0.1;
+        }
+        
+        Ok(score.min(1.0))
+    }
+    
+    fn combine_scores(&self, scores: &HashMap<String, f32>) -> AnalysisResult<f32> {
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        
+        for (feature, score) in scores {
+            if let Some(weight) = self.feature_weights.get(feature) {
+                weighted_sum += score * weight;
+                total_weight += weight;
+            }
+        }
+        
+        if total_weight > 0.0 {
+            Ok(weighted_sum / total_weight)
+        } else {
+            Ok(0.5) // Default neutral score
+        }
+    }
+    
+    fn map_to_adhd_state(&self, score: f32) -> ADHDState {
+        // Map continuous score to discrete ADHD state
+        if score < 0.3 {
+            ADHDState::focused() // Low score indicates good focus
+        } else if score < 0.7 {
+            ADHDState::neutral() // Medium score is neutral
+        } else {
+            ADHDState::distracted() // High score indicates distraction/hyperactivity
+        }
+    }
+}
+
+impl StatisticalBaselines {
+    fn new() -> Self {
+        Self {
+            typing_speed_baseline: 40.0, // WPM
+            mouse_activity_baseline: 100.0, // movements per minute
+            focus_duration_baseline: 300.0, // 5 minutes
+            session_start_time: Instant::now(),
+            user_averages: HashMap::new(),
+        }
+    }
+}
+
+impl PrivacyFeatureEncoder {
+    fn new() -> Self {
+        let mut dimension_maps = HashMap::new();
+        
+        // Define dimension reduction for privacy
+        dimension_maps.insert("keystroke".to_string(), vec![0, 2, 4, 6, 8]);
+        dimension_maps.insert("mouse".to_string(), vec![1, 3, 5, 7]);
+        dimension_maps.insert("window".to_string(), vec![0, 1, 4, 5]);
+        
+        let noise_parameters = NoiseParameters {
+            gaussian_std: 0.01,
+            differential_privacy_epsilon: 0.1,
+            laplace_scale: 0.1,
+        };
+        
+        Self {
+            dimension_maps,
+            noise_parameters,
+        }
+    }
+    
+    /// Encode features with privacy preservation
+    fn encode_features(&self, features: &FeatureVector) -> AnalysisResult<EncodedFeatures> {
+        // Extract and encode keystroke features
+        let (typing_speed, pause_frequency, backspace_ratio) = if let Some(ks) = &features.keystroke_features {
+            (
+                Some(self.add_privacy_noise(ks.typing_speed)?),
+                Some(self.add_privacy_noise(ks.pause_frequency)?),
+                Some(self.add_privacy_noise(ks.backspace_ratio.unwrap_or(0.0))?),
+            )
+        } else {
+            (None, None, None)
+        };
+        
+        // Extract and encode mouse features
+        let (movement_velocity, click_frequency, movement_smoothness) = if let Some(ms) = &features.mouse_features {
+            (
+                Some(self.add_privacy_noise(ms.movement_velocity)?),
+                Some(self.add_privacy_noise(ms.click_frequency)?),
+                Some(self.add_privacy_noise(ms.smoothness_score.unwrap_or(0.0))?),
+            )
+        } else {
+            (None, None, None)
+        };
+        
+        // Extract and encode window features
+        let (window_switch_frequency, focus_duration, multitasking_score) = if let Some(ws) = &features.window_features {
+            (
+                Some(self.add_privacy_noise(ws.switch_frequency)?),
+                Some(self.add_privacy_noise(ws.average_focus_duration)?),
+                Some(self.add_privacy_noise(ws.multitasking_score.unwrap_or(0.0))?),
+            )
+        } else {
+            (None, None, None)
+        };
+        
+        Ok(EncodedFeatures {
+            typing_speed,
+            pause_frequency,
+            backspace_ratio,
+            movement_velocity,
+            click_frequency,
+            movement_smoothness,
+            window_switch_frequency,
+            focus_duration,
+            multitasking_score,
+        })
+    }
+    
+    /// Add differential privacy noise
+    fn add_privacy_noise(&self, value: f32) -> AnalysisResult<f32> {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        
+        // Add Gaussian noise for differential privacy
+        let noise: f32 = rng.gen::<f32>() * self.noise_parameters.gaussian_std;
+        let noisy_value = value + noise;
+        
+        // Ensure value stays within reasonable bounds
+        Ok(noisy_value.max(0.0).min(1000.0))
+    }
+}
+
+impl NetworkIsolationValidator {
+    fn new() -> Self {
+        Self {
+            blocked_endpoints: vec![
+                "api.openai.com".to_string(),
+                "googleapis.com".to_string(),
+                "amazonaws.com".to_string(),
+                "azure.com".to_string(),
+                "cloudflare.com".to_string(),
+            ],
+            allowed_local_only: true,
+            validation_enabled: true,
+        }
+    }
+}
+
+impl InferenceCache {
+    fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            max_size: 100,
+            ttl: Duration::from_secs(300), // 5 minutes
+        }
+    }
+}
+
+impl Default for ModelParameters {
+    fn default() -> Self {
+        Self {
+            keystroke_thresholds: KeystrokeThresholds {
+                typing_speed_min: 20.0,
+                typing_speed_max: 80.0,
+                pause_duration_threshold: 2.0,
+                backspace_ratio_threshold: 0.15,
+                burst_typing_threshold: 10.0,
+            },
+            mouse_parameters: MouseParameters {
+                movement_velocity_threshold: 500.0,
+                click_frequency_threshold: 60.0,
+                scroll_speed_threshold: 100.0,
+                movement_smoothness_min: 0.7,
+            },
+            window_patterns: WindowPatterns {
+                switch_frequency_threshold: 5.0,
+                focus_duration_min: 30.0,
+                multitasking_score_threshold: 0.7,
+                app_category_weights: HashMap::new(),
+            },
+            temporal_weights: TemporalWeights {
+                recent_weight: 0.5,
+                medium_weight: 0.3,
+                historical_weight: 0.2,
+                time_decay_factor: 0.95,
+            },
+        }
+    }
+}
+
+/// Encoded features with privacy preservation
+#[derive(Debug, Clone)]
+struct EncodedFeatures {
+    // Keystroke features
+    typing_speed: Option<f32>,
+    pause_frequency: Option<f32>,
+    backspace_ratio: Option<f32>,
+    
+    // Mouse features
+    movement_velocity: Option<f32>,
+    click_frequency: Option<f32>,
+    movement_smoothness: Option<f32>,
+    
+    // Window features
+    window_switch_frequency: Option<f32>,
+    focus_duration: Option<f32>,
+    multitasking_score: Option<f32>,
+}
+
+/// Network isolation verification report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkIsolationReport {
+    pub total_operations: usize,
+    pub network_attempts: usize,
+    pub local_processing_rate: f32,
+    pub isolation_verified: bool,
+    /// Network calls refused by the egress guard during inference, process-wide
+    #[serde(default)]
+    pub blocked_egress_attempts: u64,
+    /// Inference runs made under the egress guard, process-wide
+    #[serde(default)]
+    pub guarded_inferences: u64,
+    /// Whether sockets are intercepted or only explicit egress checks
+    #[serde(default)]
+    pub egress_hook: EgressHookStatus,
+    pub report_timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KeystrokeFeatures, MouseFeatures, WindowFeatures};
+    
+    #[tokio::test]
+    async fn test_local_inference_creation() {
+        let mut engine = LocalInferenceEngine::new();
+        assert!(engine.models.model_metadata.contains_key("adhd_local"));
+    }
+    
+    #[tokio::test]
+    async fn test_network_isolation_validation() {
+        let engine = LocalInferenceEngine::new();
+        let result = engine.validate_network_isolation();
+        assert!(result.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_local_inference_no_network() {
+        let mut engine = LocalInferenceEngine::new();
+        
+        let features = FeatureVector {
+            keystroke_features: Some(KeystrokeFeatures {
+                typing_speed: 45.0,
+                pause_frequency: 0.1,
+                backspace_ratio: Some(0.05),
+                burst_typing_events: 2,
+                rhythm_consistency: Some(0.8),
+            }),
+            mouse_features: Some(MouseFeatures {
+                movement_velocity: 200.0,
+                click_frequency: 30.0,
+                scroll_frequency: 10.0,
+                smoothness_score: Some(0.9),
+                precision_score: Some(0.85),
+            }),
+            window_features: Some(WindowFeatures {
+                switch_frequency: 3.0,
+                average_focus_duration: 180.0,
+                multitasking_score: Some(0.4),
+                app_diversity: 5,
+                productive_app_ratio: Some(0.7),
+            }),
+            temporal_features: None,
+        };
+        
+        let result = engine.infer_local(&features).await;
+        assert!(result.is_ok());
+        
+        // Verify no network access was attempted
+        let isolation_report = engine.verify_network_isolation();
+        assert_eq!(isolation_report.network_attempts, 0);
+        assert!(isolation_report.isolation_verified);
+    }
+    
+    #[test]
+    fn test_privacy_feature_encoding() {
+        let encoder = PrivacyFeatureEncoder::new();
+        
+        let features = FeatureVector {
+            keystroke_features: Some(KeystrokeFeatures {
+                typing_speed: 50.0,
+                pause_frequency: 0.2,
+                backspace_ratio: Some(0.1),
+                burst_typing_events: 3,
+                rhythm_consistency: Some(0.7),
+            }),
+            mouse_features: None,
+            window_features: None,
+            temporal_features: None,
+        };
+        
+        let encoded = encoder.encode_features(&features).unwrap();
+        
+        // Verify features are encoded (with noise)
+        assert!(encoded.typing_speed.is_some());
+        assert!(encoded.pause_frequency.is_some());
+        
+        // Verify noise was added (values should be slightly different)
+        let original_speed = features.keystroke_features.unwrap().typing_speed;
+        let encoded_speed = encoded.typing_speed.unwrap();
+        assert!((original_speed - encoded_speed).abs() > 0.0);
+    }
+    
+    #[test]
+    fn test_model_parameters_defaults() {
+        let params = ModelParameters::default();
+        assert!(params.keystroke_thresholds.typing_speed_max > 0.0);
+        assert!(params.mouse_parameters.movement_velocity_threshold > 0.0);
+        assert!(params.window_patterns.focus_duration_min > 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_privacy_audit_logging() {
+        let mut engine = LocalInferenceEngine::new();
+        
+        engine.log_inference("test_operation", true, false).await;
+        
+        let audit_log = engine.get_privacy_audit_log().await;
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].operation, "test_operation");
+        assert!(audit_log[0].local_processing);
+        assert!(!audit_log[0].network_access_attempted);
+    }
+}
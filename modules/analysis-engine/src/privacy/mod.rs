@@ -3,6 +3,12 @@
 //! Ensures all analysis happens locally with zero external calls
 //! for complete privacy protection of user behavioral data.
 
+pub mod egress_guard;
+pub mod feature_export;
+pub mod identifier_hashing;
 pub mod local_inference;
 
+pub use egress_guard::{EgressGuard, EgressHookStatus, EgressStats, InferenceScope};
+pub use feature_export::{ExportAuditRecord, ExportOutcome, FeatureExportConfig, NoisedFeatureStats, PrivateFeatureExporter};
+pub use identifier_hashing::{IdentifierHasher, IdentifierHashingConfig};
 pub use local_inference::{LocalInferenceEngine, NetworkIsolationReport};
\ No newline at end of file
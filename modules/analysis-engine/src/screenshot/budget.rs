@@ -0,0 +1,221 @@
+//! Processing budget for screenshot analysis
+//!
+//! Screenshots are downscaled to a maximum dimension before any analysis, and
+//! analysis is skipped outright while the orchestrator reports CPU pressure on
+//! this module or while our own analysis time would exceed the CPU target.
+//! The CPU target is enforced with a token bucket: it fills at `cpu_target`
+//! seconds per wall-clock second, up to `burst`, and each analysis drains the
+//! time it took.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{BusMessage, MessagePayload, ModuleId};
+
+/// Limits on how much work screenshot analysis may do
+#[derive(Debug, Clone)]
+pub struct ProcessingBudgetConfig {
+    /// Screenshots larger than this on either side are downscaled to fit
+    pub max_dimension: u32,
+    /// Share of one core screenshot analysis may use on average
+    pub cpu_target: f32,
+    /// Analysis time that may be spent at once after an idle period
+    pub burst: Duration,
+    /// Orchestrator throttle levels that pause analysis
+    pub skip_throttle_levels: Vec<String>,
+    /// How long an orchestrator throttle is honoured after its last report
+    pub throttle_hold: Duration,
+}
+
+impl Default for ProcessingBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_dimension: 1280,
+            cpu_target: 0.02,
+            burst: Duration::from_millis(500),
+            skip_throttle_levels: vec!["restricted".to_string(), "suspended".to_string()],
+            throttle_hold: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Why a screenshot was not analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// The orchestrator reported CPU pressure on the analysis engine
+    CpuPressure,
+    /// Recent analyses already used up the CPU target
+    OverBudget,
+}
+
+/// Counts of analyzed and skipped screenshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStats {
+    pub analyzed: u64,
+    pub downscaled: u64,
+    pub skipped_cpu_pressure: u64,
+    pub skipped_over_budget: u64,
+}
+
+impl BudgetStats {
+    pub fn skipped(&self) -> u64 {
+        self.skipped_cpu_pressure + self.skipped_over_budget
+    }
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    /// Analysis seconds available right now
+    tokens: f32,
+    refilled_at: Instant,
+    /// Last throttle level reported by the orchestrator and when
+    throttle: Option<(String, Instant)>,
+}
+
+/// Decides whether a screenshot may be analyzed and at what size
+#[derive(Debug)]
+pub struct ProcessingBudget {
+    config: ProcessingBudgetConfig,
+    state: Mutex<BudgetState>,
+    analyzed: AtomicU64,
+    downscaled: AtomicU64,
+    skipped_cpu_pressure: AtomicU64,
+    skipped_over_budget: AtomicU64,
+}
+
+impl ProcessingBudget {
+    pub fn new(config: ProcessingBudgetConfig) -> Self {
+        Self {
+            state: Mutex::new(BudgetState {
+                tokens: config.burst.as_secs_f32(),
+                refilled_at: Instant::now(),
+                throttle: None,
+            }),
+            config,
+            analyzed: AtomicU64::new(0),
+            downscaled: AtomicU64::new(0),
+            skipped_cpu_pressure: AtomicU64::new(0),
+            skipped_over_budget: AtomicU64::new(0),
+        }
+    }
+
+    /// Track orchestrator CPU throttling of the analysis engine
+    pub fn handle_message(&self, message: &BusMessage) {
+        if let MessagePayload::ResourceViolation(violation) = &message.payload {
+            if violation.module == ModuleId::AnalysisEngine && violation.resource == "cpu_percent" {
+                self.state.lock().unwrap().throttle = Some((violation.throttle_level.clone(), Instant::now()));
+            }
+        }
+    }
+
+    /// Claim permission to analyze one screenshot, counting it as skipped if refused
+    pub fn try_begin(&self) -> Result<(), SkipReason> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        let elapsed = now.duration_since(state.refilled_at).as_secs_f32();
+        state.tokens = (state.tokens + elapsed * self.config.cpu_target).min(self.config.burst.as_secs_f32());
+        state.refilled_at = now;
+
+        let throttled = state.throttle.as_ref().is_some_and(|(level, reported)| {
+            now.duration_since(*reported) < self.config.throttle_hold
+                && self.config.skip_throttle_levels.iter().any(|skip| skip == level)
+        });
+        if throttled {
+            self.skipped_cpu_pressure.fetch_add(1, Ordering::Relaxed);
+            return Err(SkipReason::CpuPressure);
+        }
+        if state.tokens <= 0.0 {
+            self.skipped_over_budget.fetch_add(1, Ordering::Relaxed);
+            return Err(SkipReason::OverBudget);
+        }
+        Ok(())
+    }
+
+    /// Charge the time an analysis took against the CPU target
+    pub fn finish(&self, busy: Duration) {
+        self.state.lock().unwrap().tokens -= busy.as_secs_f32();
+        self.analyzed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Shrink `image` to fit within `max_dimension`, keeping its aspect ratio
+    pub fn downscale(&self, image: DynamicImage) -> DynamicImage {
+        let max = self.config.max_dimension;
+        if image.width() <= max && image.height() <= max {
+            return image;
+        }
+        self.downscaled.fetch_add(1, Ordering::Relaxed);
+        image.resize(max, max, FilterType::Triangle)
+    }
+
+    pub fn stats(&self) -> BudgetStats {
+        BudgetStats {
+            analyzed: self.analyzed.load(Ordering::Relaxed),
+            downscaled: self.downscaled.load(Ordering::Relaxed),
+            skipped_cpu_pressure: self.skipped_cpu_pressure.load(Ordering::Relaxed),
+            skipped_over_budget: self.skipped_over_budget.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for ProcessingBudget {
+    fn default() -> Self {
+        Self::new(ProcessingBudgetConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use skelly_jelly_event_bus::message::ResourceViolation;
+
+    fn violation(level: &str) -> BusMessage {
+        BusMessage::new(
+            ModuleId::Orchestrator,
+            MessagePayload::ResourceViolation(ResourceViolation {
+                module: ModuleId::AnalysisEngine,
+                resource: "cpu_percent".to_string(),
+                observed: 12.0,
+                limit: 2.0,
+                throttle_level: level.to_string(),
+                timestamp: Utc::now(),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_cpu_pressure_and_budget_skip_analysis() {
+        let budget = ProcessingBudget::default();
+        assert_eq!(budget.try_begin(), Ok(()));
+        budget.finish(Duration::from_secs(1));
+        assert_eq!(budget.try_begin(), Err(SkipReason::OverBudget));
+
+        let budget = ProcessingBudget::default();
+        budget.handle_message(&violation("reduced"));
+        assert_eq!(budget.try_begin(), Ok(()));
+        budget.handle_message(&violation("suspended"));
+        assert_eq!(budget.try_begin(), Err(SkipReason::CpuPressure));
+
+        let stats = budget.stats();
+        assert_eq!((stats.analyzed, stats.skipped()), (0, 1));
+        assert_eq!(stats.skipped_cpu_pressure, 1);
+    }
+
+    #[test]
+    fn test_downscale_keeps_aspect_ratio() {
+        let budget = ProcessingBudget::new(ProcessingBudgetConfig { max_dimension: 640, ..Default::default() });
+        let small = budget.downscale(DynamicImage::new_rgba8(320, 200));
+        assert_eq!((small.width(), small.height()), (320, 200));
+
+        let large = budget.downscale(DynamicImage::new_rgba8(2560, 1440));
+        assert_eq!((large.width(), large.height()), (640, 360));
+        assert_eq!(budget.stats().downscaled, 1);
+    }
+}
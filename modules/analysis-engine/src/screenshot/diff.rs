@@ -0,0 +1,330 @@
+//! Region-of-interest diffing between consecutive screenshots
+//!
+//! Each screenshot is reduced to a small luminance thumbnail and compared with
+//! the previous one cell by cell. Change concentrated in the focus region
+//! (where the user types) points to active creation. Change spread across the
+//! screen that a vertical shift of the previous frame explains points to
+//! passive scrolling.
+
+use image::{imageops, DynamicImage, GrayImage};
+use serde::{Deserialize, Serialize};
+
+use super::FocusArea;
+
+/// Settings for comparing consecutive screenshots
+#[derive(Debug, Clone)]
+pub struct DiffConfig {
+    /// Width of the thumbnail frames are compared at
+    pub thumbnail_width: u32,
+    /// Grid cells across and down the screen
+    pub grid_columns: u32,
+    pub grid_rows: u32,
+    /// Mean luminance difference (0-1) above which a cell counts as changed
+    pub cell_change_threshold: f32,
+    /// Share of changed cells below which the screen counts as static
+    pub static_threshold: f32,
+    /// Share of changed cells above which the whole screen was replaced
+    pub switch_threshold: f32,
+    /// Largest scroll searched for, as a share of the screen height
+    pub max_scroll_fraction: f32,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            thumbnail_width: 320,
+            grid_columns: 32,
+            grid_rows: 18,
+            cell_change_threshold: 0.04,
+            static_threshold: 0.01,
+            switch_threshold: 0.7,
+            max_scroll_fraction: 0.25,
+        }
+    }
+}
+
+/// What the change between two screenshots looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityKind {
+    /// Nothing visible changed
+    Static,
+    /// Change is concentrated in the focus region, e.g. typing
+    Creating,
+    /// Content moved vertically without much new content appearing
+    Scrolling,
+    /// Most of the screen was replaced, e.g. an app or tab switch
+    Switching,
+    /// Change that fits none of the above
+    Mixed,
+}
+
+impl ActivityKind {
+    /// Name used in `ScreenshotContext::activity_indicators`
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            ActivityKind::Static => "screen_static",
+            ActivityKind::Creating => "active_creation",
+            ActivityKind::Scrolling => "passive_scrolling",
+            ActivityKind::Switching => "screen_switch",
+            ActivityKind::Mixed => "mixed_change",
+        }
+    }
+}
+
+/// Changed-region statistics between a screenshot and the one before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenActivity {
+    pub kind: ActivityKind,
+    /// Share of grid cells that changed across the whole screen
+    pub screen_change: f32,
+    /// Share of grid cells inside the focus region that changed
+    pub focus_churn: f32,
+    /// Detected scroll as a share of screen height; positive when content moved up
+    pub scroll_offset: f32,
+    /// Bounding box of the changed cells in screenshot pixels
+    pub changed_region: Option<FocusArea>,
+}
+
+/// Keeps the previous screenshot and diffs each new one against it
+pub struct ScreenshotDiffer {
+    config: DiffConfig,
+    previous: Option<GrayImage>,
+}
+
+impl ScreenshotDiffer {
+    pub fn new(config: DiffConfig) -> Self {
+        Self { config, previous: None }
+    }
+
+    /// Compare `image` with the previous screenshot and remember it for the next call
+    ///
+    /// Returns `None` for the first screenshot and whenever the screen size changed.
+    pub fn diff(&mut self, image: &DynamicImage, focus: Option<&FocusArea>) -> Option<ScreenActivity> {
+        let (width, height) = (image.width(), image.height());
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let thumb_width = self.config.thumbnail_width.min(width).max(1);
+        let thumb_height = ((height as u64 * thumb_width as u64) / width as u64).max(1) as u32;
+        let current = imageops::thumbnail(&image.to_luma8(), thumb_width, thumb_height);
+
+        let previous = self.previous.replace(current);
+        let previous = previous.filter(|previous| previous.dimensions() == (thumb_width, thumb_height))?;
+        let current = self.previous.as_ref()?;
+
+        let changed = self.changed_cells(&previous, current);
+        let cell_count = changed.len().max(1) as f32;
+        let screen_change = changed.iter().filter(|cell| cell.changed).count() as f32 / cell_count;
+
+        let focus_cells: Vec<_> = changed
+            .iter()
+            .filter(|cell| focus.is_some_and(|area| cell.within(area, width, height)))
+            .collect();
+        let focus_churn = if focus_cells.is_empty() {
+            0.0
+        } else {
+            focus_cells.iter().filter(|cell| cell.changed).count() as f32 / focus_cells.len() as f32
+        };
+
+        let scroll_rows = if screen_change > self.config.static_threshold {
+            self.detect_scroll(&previous, current)
+        } else {
+            0
+        };
+        let kind = if screen_change <= self.config.static_threshold {
+            ActivityKind::Static
+        } else if scroll_rows != 0 {
+            ActivityKind::Scrolling
+        } else if screen_change >= self.config.switch_threshold {
+            ActivityKind::Switching
+        } else if focus_churn > screen_change {
+            ActivityKind::Creating
+        } else {
+            ActivityKind::Mixed
+        };
+
+        Some(ScreenActivity {
+            kind,
+            screen_change,
+            focus_churn,
+            scroll_offset: scroll_rows as f32 / thumb_height as f32,
+            changed_region: Self::bounding_box(&changed, width, height),
+        })
+    }
+
+    /// Mean absolute luminance difference per grid cell
+    fn changed_cells(&self, previous: &GrayImage, current: &GrayImage) -> Vec<Cell> {
+        let (width, height) = current.dimensions();
+        let columns = self.config.grid_columns.clamp(1, width);
+        let rows = self.config.grid_rows.clamp(1, height);
+        let mut cells = Vec::with_capacity((columns * rows) as usize);
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x0, x1) = (column * width / columns, (column + 1) * width / columns);
+                let (y0, y1) = (row * height / rows, (row + 1) * height / rows);
+                let mut total = 0u64;
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        total += previous.get_pixel(x, y)[0].abs_diff(current.get_pixel(x, y)[0]) as u64;
+                    }
+                }
+                let pixels = ((x1 - x0) * (y1 - y0)).max(1) as f32;
+                cells.push(Cell {
+                    x: x0 as f32 / width as f32,
+                    y: y0 as f32 / height as f32,
+                    width: (x1 - x0) as f32 / width as f32,
+                    height: (y1 - y0) as f32 / height as f32,
+                    changed: total as f32 / pixels / 255.0 > self.config.cell_change_threshold,
+                });
+            }
+        }
+        cells
+    }
+
+    /// Vertical shift of the previous frame that best explains the current one
+    ///
+    /// Returns 0 unless some shift leaves less than half the unshifted difference.
+    fn detect_scroll(&self, previous: &GrayImage, current: &GrayImage) -> i32 {
+        let height = current.height() as i32;
+        let max_shift = (height as f32 * self.config.max_scroll_fraction) as i32;
+        let unshifted = shifted_difference(previous, current, 0);
+
+        let (best_shift, best_difference) = (-max_shift..=max_shift)
+            .filter(|shift| *shift != 0)
+            .map(|shift| (shift, shifted_difference(previous, current, shift)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, f32::MAX));
+
+        if best_difference < unshifted * 0.5 {
+            best_shift
+        } else {
+            0
+        }
+    }
+
+    fn bounding_box(cells: &[Cell], width: u32, height: u32) -> Option<FocusArea> {
+        let changed: Vec<_> = cells.iter().filter(|cell| cell.changed).collect();
+        if changed.is_empty() {
+            return None;
+        }
+        let left = changed.iter().map(|cell| cell.x).fold(f32::MAX, f32::min);
+        let top = changed.iter().map(|cell| cell.y).fold(f32::MAX, f32::min);
+        let right = changed.iter().map(|cell| cell.x + cell.width).fold(0.0, f32::max);
+        let bottom = changed.iter().map(|cell| cell.y + cell.height).fold(0.0, f32::max);
+        Some(FocusArea {
+            x: (left * width as f32) as u32,
+            y: (top * height as f32) as u32,
+            width: ((right - left) * width as f32) as u32,
+            height: ((bottom - top) * height as f32) as u32,
+            attention_score: changed.len() as f32 / cells.len() as f32,
+            content_type: "changed_region".to_string(),
+        })
+    }
+}
+
+impl Default for ScreenshotDiffer {
+    fn default() -> Self {
+        Self::new(DiffConfig::default())
+    }
+}
+
+/// A grid cell in screen fractions, so it can be matched against pixel focus areas
+struct Cell {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    changed: bool,
+}
+
+impl Cell {
+    /// Whether the cell's centre lies inside `area`, given in screenshot pixels
+    fn within(&self, area: &FocusArea, width: u32, height: u32) -> bool {
+        let centre_x = (self.x + self.width / 2.0) * width as f32;
+        let centre_y = (self.y + self.height / 2.0) * height as f32;
+        centre_x >= area.x as f32
+            && centre_x < (area.x + area.width) as f32
+            && centre_y >= area.y as f32
+            && centre_y < (area.y + area.height) as f32
+    }
+}
+
+/// Mean luminance difference (0-1) between `current` and `previous` moved up by `shift` rows
+fn shifted_difference(previous: &GrayImage, current: &GrayImage, shift: i32) -> f32 {
+    let (width, height) = current.dimensions();
+    let mut total = 0u64;
+    let mut pixels = 0u64;
+    for y in 0..height as i32 {
+        let source = y + shift;
+        if source < 0 || source >= height as i32 {
+            continue;
+        }
+        for x in 0..width {
+            total += previous.get_pixel(x, source as u32)[0].abs_diff(current.get_pixel(x, y as u32)[0]) as u64;
+            pixels += 1;
+        }
+    }
+    if pixels == 0 {
+        return f32::MAX;
+    }
+    total as f32 / pixels as f32 / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// Horizontal stripes of varying brightness, like lines of text
+    fn page(offset: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(320, 180, |_, y| {
+            let line = (y + offset) / 6;
+            Luma([((line * 37) % 200 + 40) as u8])
+        }))
+    }
+
+    fn centre() -> FocusArea {
+        FocusArea {
+            x: 80,
+            y: 45,
+            width: 160,
+            height: 90,
+            attention_score: 0.8,
+            content_type: "center_region".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_first_frame_static_and_scroll() {
+        let mut differ = ScreenshotDiffer::default();
+        assert!(differ.diff(&page(0), Some(&centre())).is_none());
+
+        let same = differ.diff(&page(0), Some(&centre())).unwrap();
+        assert_eq!(same.kind, ActivityKind::Static);
+        assert!(same.changed_region.is_none());
+
+        let scrolled = differ.diff(&page(18), Some(&centre())).unwrap();
+        assert_eq!(scrolled.kind, ActivityKind::Scrolling);
+        assert!((scrolled.scroll_offset - 0.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_typing_in_focus_region_is_creation() {
+        let mut differ = ScreenshotDiffer::default();
+        let before = page(0);
+        differ.diff(&before, Some(&centre()));
+
+        let mut typed = before.to_luma8();
+        for y in 80..100 {
+            for x in 100..220 {
+                typed.put_pixel(x, y, Luma([255]));
+            }
+        }
+        let activity = differ.diff(&DynamicImage::ImageLuma8(typed), Some(&centre())).unwrap();
+        assert_eq!(activity.kind, ActivityKind::Creating);
+        assert!(activity.focus_churn > activity.screen_change);
+        let region = activity.changed_region.unwrap();
+        assert!(region.x >= 90 && region.x + region.width <= 230);
+    }
+}
@@ -3,37 +3,73 @@
 use async_trait::async_trait;
 use image::{DynamicImage, ImageBuffer, Rgba};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use skelly_jelly_event_bus::BusMessage;
+use std::{collections::HashMap, sync::Mutex, time::Instant};
 
 use crate::{
     error::{AnalysisError, AnalysisResult},
 };
 
+mod budget;
+mod diff;
+
+pub use budget::{BudgetStats, ProcessingBudget, ProcessingBudgetConfig, SkipReason};
+pub use diff::{ActivityKind, DiffConfig, ScreenActivity, ScreenshotDiffer};
+
 /// Main screenshot analyzer
 pub struct ScreenshotAnalyzer {
     config: ScreenshotConfig,
+    /// Previous screenshot, for activity diffing
+    differ: Mutex<ScreenshotDiffer>,
+    budget: ProcessingBudget,
 }
 
 impl ScreenshotAnalyzer {
     /// Create a new screenshot analyzer
     pub fn new() -> Self {
-        Self {
-            config: ScreenshotConfig::default(),
-        }
+        Self::with_config(ScreenshotConfig::default())
     }
 
     /// Create with custom configuration
     pub fn with_config(config: ScreenshotConfig) -> Self {
-        Self { config }
+        Self {
+            differ: Mutex::new(ScreenshotDiffer::new(config.diff.clone())),
+            budget: ProcessingBudget::new(config.budget.clone()),
+            config,
+        }
+    }
+
+    /// Analyze a screenshot unless the processing budget says to skip it
+    ///
+    /// Returns `None` for skipped screenshots; they are counted in `budget_stats`.
+    pub async fn analyze_within_budget(&self, screenshot_data: &[u8]) -> AnalysisResult<Option<ScreenshotContext>> {
+        if self.budget.try_begin().is_err() {
+            return Ok(None);
+        }
+        let started = Instant::now();
+        let result = self.analyze(screenshot_data).await;
+        self.budget.finish(started.elapsed());
+        result.map(Some)
+    }
+
+    /// Feed orchestrator messages to the processing budget
+    pub fn handle_message(&self, message: &BusMessage) {
+        self.budget.handle_message(message);
+    }
+
+    /// Analyzed, downscaled and skipped screenshot counts
+    pub fn budget_stats(&self) -> BudgetStats {
+        self.budget.stats()
     }
 
     /// Analyze a screenshot and extract context
     pub async fn analyze(&self, screenshot_data: &[u8]) -> AnalysisResult<ScreenshotContext> {
-        // Load image
+        // Load image, downscaled to the budget's maximum dimension
         let image = image::load_from_memory(screenshot_data)
             .map_err(|e| AnalysisError::ScreenshotError {
                 reason: format!("Failed to load image: {}", e),
             })?;
+        let image = self.budget.downscale(image);
 
         // Apply privacy filtering first
         let filtered_image = self.apply_privacy_filter(&image)?;
@@ -47,15 +83,27 @@ impl ScreenshotAnalyzer {
         // Classify work type based on visual patterns
         let work_type = self.classify_work_type(&visual_features, &ui_elements)?;
         let cognitive_load = self.estimate_cognitive_load(&visual_features, &ui_elements);
+
+        // Compare with the previous screenshot to tell creation from scrolling
+        let activity = self
+            .differ
+            .lock()
+            .unwrap()
+            .diff(&filtered_image, visual_features.focus_areas.first());
+        let mut activity_indicators = ui_elements.activity_indicators;
+        if let Some(activity) = &activity {
+            activity_indicators.push(activity.kind.indicator().to_string());
+        }
         
         Ok(ScreenshotContext {
             work_type,
             text_density: visual_features.text_density,
             ui_complexity: visual_features.ui_complexity,
             color_scheme: visual_features.dominant_colors,
-            activity_indicators: ui_elements.activity_indicators,
+            activity_indicators,
             visual_focus_areas: visual_features.focus_areas,
             estimated_cognitive_load: cognitive_load,
+            activity,
             timestamp: chrono::Utc::now(),
         })
     }
@@ -389,6 +437,9 @@ pub struct ScreenshotContext {
     pub activity_indicators: Vec<String>,
     pub visual_focus_areas: Vec<FocusArea>,
     pub estimated_cognitive_load: f32,
+    /// Change since the previous screenshot; `None` for the first one
+    #[serde(default)]
+    pub activity: Option<ScreenActivity>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -429,6 +480,21 @@ pub enum WorkType {
     Unknown,
 }
 
+impl WorkType {
+    /// Coarse category without the details, e.g. `"coding"`
+    pub fn category(&self) -> &'static str {
+        match self {
+            WorkType::Coding { .. } => "coding",
+            WorkType::Writing { .. } => "writing",
+            WorkType::Design { .. } => "design",
+            WorkType::Research { .. } => "research",
+            WorkType::Communication { .. } => "communication",
+            WorkType::Entertainment { .. } => "entertainment",
+            WorkType::Unknown => "unknown",
+        }
+    }
+}
+
 /// Document types for writing work
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DocumentType {
@@ -482,6 +548,8 @@ pub struct ScreenshotConfig {
     pub ocr_confidence_threshold: f32,
     pub ui_detection_sensitivity: f32,
     pub enable_work_classification: bool,
+    pub diff: DiffConfig,
+    pub budget: ProcessingBudgetConfig,
 }
 
 impl Default for ScreenshotConfig {
@@ -492,6 +560,8 @@ impl Default for ScreenshotConfig {
             ocr_confidence_threshold: 0.8,
             ui_detection_sensitivity: 0.5,
             enable_work_classification: true,
+            diff: DiffConfig::default(),
+            budget: ProcessingBudgetConfig::default(),
         }
     }
 }
@@ -0,0 +1,555 @@
+//! Work session reconstruction from state history
+//!
+//! A work session is a contiguous run of classified analysis windows. A session
+//! ends when the user goes idle for longer than `idle_gap`, or when they move to
+//! another application and stay there for at least `min_app_dwell`. Shorter
+//! visits to other applications stay in the session and count as interruptions.
+//!
+//! Sessions that are followed by a boundary can no longer change, so they are
+//! cached once built. Each query only rebuilds the trailing session from the
+//! records after the cache.
+//!
+//! Windows are tagged with the task the user declared at the time, if any, so
+//! focus can also be totalled per task.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{models::ADHDStateType, types::AnalysisResult as AnalysisResultType};
+
+/// Where session boundaries fall and how much history is kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// Inactivity that ends a session
+    pub idle_gap: Duration,
+    /// Time in another application before the switch starts a new session
+    pub min_app_dwell: Duration,
+    /// State history older than this is dropped
+    pub retention: Duration,
+    /// Sessions per page when a query does not say
+    pub default_page_size: usize,
+    pub max_page_size: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_gap: Duration::from_secs(300),
+            min_app_dwell: Duration::from_secs(300),
+            retention: Duration::from_secs(30 * 24 * 3600),
+            default_page_size: 20,
+            max_page_size: 100,
+        }
+    }
+}
+
+/// One classified analysis window in the state history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Length of the analysis window
+    pub duration: Duration,
+    pub state: ADHDStateType,
+    /// Focused application, if known
+    pub application: Option<String>,
+    /// Work category from screenshot analysis, e.g. `"coding"`
+    pub work_type: Option<String>,
+    /// No user input during the window
+    pub idle: bool,
+    /// Task the user declared as current during the window
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+}
+
+impl StateRecord {
+    /// Build a record from a completed analysis
+    pub fn from_analysis(result: &AnalysisResultType, window: Duration, application: Option<String>) -> Self {
+        Self {
+            timestamp: DateTime::<Utc>::from(result.timestamp),
+            duration: window,
+            state: result.state.state_type,
+            application,
+            work_type: result
+                .work_context
+                .as_ref()
+                .map(|context| context.primary_work_type.category().to_string()),
+            idle: false,
+            task_id: None,
+        }
+    }
+
+    /// Tag the record with the user's declared task
+    pub fn with_task(mut self, task_id: Option<Uuid>) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    fn end(&self) -> DateTime<Utc> {
+        self.timestamp + chrono::Duration::from_std(self.duration).unwrap_or_default()
+    }
+
+    fn is_focused(&self) -> bool {
+        matches!(self.state, ADHDStateType::Flow | ADHDStateType::Hyperfocus)
+    }
+}
+
+/// A reconstructed work session with its metrics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Time covered by non-idle analysis windows
+    pub active_duration: Duration,
+    /// Share of active time spent in flow or hyperfocus, 0–100
+    pub flow_percent: f32,
+    /// Focus lost to distraction plus short detours into other applications
+    pub interruptions: u32,
+    pub dominant_work_type: Option<String>,
+    pub application: Option<String>,
+    pub windows: usize,
+    /// Whether this is the latest session and may still grow
+    pub open: bool,
+}
+
+/// Focus totals for one declared task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskFocusStats {
+    pub task_id: Uuid,
+    /// What the user called the task, when known
+    pub description: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Time covered by non-idle windows tagged with the task
+    pub active_duration: Duration,
+    /// Time in flow or hyperfocus
+    pub focused_duration: Duration,
+    pub distracted_duration: Duration,
+    pub windows: usize,
+}
+
+impl TaskFocusStats {
+    /// Share of active time spent in flow or hyperfocus, 0–100
+    pub fn flow_percent(&self) -> f32 {
+        if self.active_duration.is_zero() {
+            0.0
+        } else {
+            self.focused_duration.as_secs_f32() / self.active_duration.as_secs_f32() * 100.0
+        }
+    }
+}
+
+/// Which sessions to return, newest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionQuery {
+    /// Only sessions that end after this
+    pub from: Option<DateTime<Utc>>,
+    /// Only sessions that start before this
+    pub to: Option<DateTime<Utc>>,
+    /// Cursor from the previous page
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+/// One page of sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPage {
+    pub sessions: Vec<WorkSession>,
+    /// Pass as `before` to get the next page; `None` on the last page
+    pub next_cursor: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct SessionState {
+    /// State history in time order
+    records: VecDeque<StateRecord>,
+    /// Sessions followed by a boundary, in time order
+    sealed: Vec<WorkSession>,
+    /// Start of the trailing session; records from here on are rebuilt per query
+    unsealed_from: Option<DateTime<Utc>>,
+}
+
+/// Keeps the state history and answers session queries over it
+pub struct SessionReconstructor {
+    config: SessionConfig,
+    state: Mutex<SessionState>,
+}
+
+impl SessionReconstructor {
+    pub fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(SessionState::default()),
+        }
+    }
+
+    /// Add a classified window to the history
+    pub fn record(&self, record: StateRecord) {
+        let mut state = self.state.lock().unwrap();
+
+        let cutoff = record.timestamp - chrono::Duration::from_std(self.config.retention).unwrap_or_default();
+        while state.records.front().is_some_and(|oldest| oldest.timestamp < cutoff) {
+            state.records.pop_front();
+        }
+        state.sealed.retain(|session| session.end >= cutoff);
+
+        if state.records.back().is_some_and(|last| record.timestamp < last.timestamp) {
+            // Late arrival: keep the history ordered and rebuild everything after it
+            let position = state.records.partition_point(|existing| existing.timestamp <= record.timestamp);
+            state.records.insert(position, record.clone());
+            if state.unsealed_from.is_some_and(|from| record.timestamp < from) {
+                state.sealed.clear();
+                state.unsealed_from = None;
+            }
+        } else {
+            state.records.push_back(record);
+        }
+    }
+
+    /// A page of sessions, newest first
+    pub fn sessions(&self, query: &SessionQuery) -> SessionPage {
+        let limit = query
+            .limit
+            .unwrap_or(self.config.default_page_size)
+            .clamp(1, self.config.max_page_size);
+
+        let mut state = self.state.lock().unwrap();
+        let trailing = self.refresh(&mut state);
+
+        let mut matching = state
+            .sealed
+            .iter()
+            .chain(trailing.iter())
+            .rev()
+            .filter(|session| query.from.is_none_or(|from| session.end > from))
+            .filter(|session| query.to.is_none_or(|to| session.start < to))
+            .filter(|session| query.before.is_none_or(|before| session.start < before));
+
+        let sessions: Vec<WorkSession> = matching.by_ref().take(limit).cloned().collect();
+        let next_cursor = match matching.next() {
+            Some(_) => sessions.last().map(|session| session.start),
+            None => None,
+        };
+        SessionPage { sessions, next_cursor }
+    }
+
+    /// Focus per declared task over windows starting in `from..to`, most active first
+    pub fn task_focus(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Vec<TaskFocusStats> {
+        let state = self.state.lock().unwrap();
+        let mut tasks: HashMap<Uuid, TaskFocusStats> = HashMap::new();
+        let in_range = |record: &&StateRecord| {
+            !record.idle
+                && from.is_none_or(|from| record.timestamp >= from)
+                && to.is_none_or(|to| record.timestamp < to)
+        };
+
+        for record in state.records.iter().filter(in_range) {
+            let Some(task_id) = record.task_id else {
+                continue;
+            };
+            let stats = tasks.entry(task_id).or_insert_with(|| TaskFocusStats {
+                task_id,
+                description: None,
+                first_seen: record.timestamp,
+                last_seen: record.timestamp,
+                active_duration: Duration::ZERO,
+                focused_duration: Duration::ZERO,
+                distracted_duration: Duration::ZERO,
+                windows: 0,
+            });
+            stats.last_seen = record.end();
+            stats.active_duration += record.duration;
+            if record.is_focused() {
+                stats.focused_duration += record.duration;
+            } else if record.state == ADHDStateType::Distracted {
+                stats.distracted_duration += record.duration;
+            }
+            stats.windows += 1;
+        }
+
+        let mut tasks: Vec<TaskFocusStats> = tasks.into_values().collect();
+        tasks.sort_by(|a, b| b.active_duration.cmp(&a.active_duration).then(a.first_seen.cmp(&b.first_seen)));
+        tasks
+    }
+
+    /// Sessions currently held in the cache
+    pub fn cached_sessions(&self) -> usize {
+        self.state.lock().unwrap().sealed.len()
+    }
+
+    /// Seal every session that is now followed by a boundary; returns the trailing one
+    fn refresh(&self, state: &mut SessionState) -> Option<WorkSession> {
+        let unsealed_from = state.unsealed_from;
+        let pending: Vec<&StateRecord> = state
+            .records
+            .iter()
+            .filter(|record| unsealed_from.is_none_or(|from| record.timestamp >= from))
+            .collect();
+
+        let mut sessions = build_sessions(&pending, &self.config);
+        let trailing = sessions.pop().map(|mut session| {
+            session.open = true;
+            session
+        });
+        state.sealed.extend(sessions);
+        if let Some(session) = &trailing {
+            state.unsealed_from = Some(session.start);
+        }
+        trailing
+    }
+}
+
+/// Split time-ordered records into sessions
+fn build_sessions(records: &[&StateRecord], config: &SessionConfig) -> Vec<WorkSession> {
+    let idle_gap = chrono::Duration::from_std(config.idle_gap).unwrap_or_default();
+    let mut sessions = Vec::new();
+    let mut current: Option<SessionBuilder> = None;
+
+    for (index, record) in records.iter().enumerate() {
+        if record.idle {
+            continue;
+        }
+
+        if let Some(builder) = current.take() {
+            let gap = record.timestamp - builder.end;
+            let switched_app = match (&builder.application, &record.application) {
+                (Some(session_app), Some(app)) if session_app != app => {
+                    dwell_in(records, index, app, idle_gap) >= config.min_app_dwell
+                }
+                _ => false,
+            };
+            if gap > idle_gap || switched_app {
+                sessions.push(builder.finish());
+            } else {
+                current = Some(builder);
+            }
+        }
+
+        current.get_or_insert_with(|| SessionBuilder::new(record)).add(record);
+    }
+
+    sessions.extend(current.map(SessionBuilder::finish));
+    sessions
+}
+
+/// How long the user stays in `application` from `records[index]` on
+fn dwell_in(records: &[&StateRecord], index: usize, application: &str, idle_gap: chrono::Duration) -> Duration {
+    let mut dwell = Duration::ZERO;
+    let mut last_end = records[index].timestamp;
+    for record in &records[index..] {
+        if record.idle {
+            continue;
+        }
+        if record.application.as_deref() != Some(application) || record.timestamp - last_end > idle_gap {
+            break;
+        }
+        dwell += record.duration;
+        last_end = record.end();
+    }
+    dwell
+}
+
+struct SessionBuilder {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    application: Option<String>,
+    active: Duration,
+    focused: Duration,
+    interruptions: u32,
+    work_time: HashMap<String, Duration>,
+    windows: usize,
+    last_focused: bool,
+    in_detour: bool,
+}
+
+impl SessionBuilder {
+    fn new(first: &StateRecord) -> Self {
+        Self {
+            start: first.timestamp,
+            end: first.timestamp,
+            application: None,
+            active: Duration::ZERO,
+            focused: Duration::ZERO,
+            interruptions: 0,
+            work_time: HashMap::new(),
+            windows: 0,
+            last_focused: false,
+            in_detour: false,
+        }
+    }
+
+    fn add(&mut self, record: &StateRecord) {
+        if self.application.is_none() {
+            self.application = record.application.clone();
+        }
+
+        // A short visit elsewhere counts once, when it starts
+        let detour = record.application.is_some() && record.application != self.application;
+        if detour && !self.in_detour {
+            self.interruptions += 1;
+        }
+        self.in_detour = detour;
+
+        if self.last_focused && record.state == ADHDStateType::Distracted {
+            self.interruptions += 1;
+        }
+        self.last_focused = record.is_focused();
+
+        self.active += record.duration;
+        if record.is_focused() {
+            self.focused += record.duration;
+        }
+        if let Some(work_type) = &record.work_type {
+            *self.work_time.entry(work_type.clone()).or_default() += record.duration;
+        }
+        self.end = self.end.max(record.end());
+        self.windows += 1;
+    }
+
+    fn finish(self) -> WorkSession {
+        let flow_percent = if self.active.is_zero() {
+            0.0
+        } else {
+            self.focused.as_secs_f32() / self.active.as_secs_f32() * 100.0
+        };
+        let dominant_work_type = self
+            .work_time
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(work_type, _)| work_type);
+
+        WorkSession {
+            start: self.start,
+            end: self.end,
+            active_duration: self.active,
+            flow_percent,
+            interruptions: self.interruptions,
+            dominant_work_type,
+            application: self.application,
+            windows: self.windows,
+            open: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(minute: i64, state: ADHDStateType, application: &str) -> StateRecord {
+        StateRecord {
+            timestamp: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + chrono::Duration::minutes(minute),
+            duration: Duration::from_secs(60),
+            state,
+            application: Some(application.to_string()),
+            work_type: Some(if application == "editor" { "coding" } else { "communication" }.to_string()),
+            idle: false,
+            task_id: None,
+        }
+    }
+
+    #[test]
+    fn test_sessions_split_on_idle_and_sustained_app_changes() {
+        let sessions = SessionReconstructor::new(SessionConfig::default());
+        // 10 minutes of coding with a 2-minute chat detour and one distraction
+        for minute in 0..10 {
+            let (state, app) = match minute {
+                3 | 4 => (ADHDStateType::Neutral, "chat"),
+                7 => (ADHDStateType::Distracted, "editor"),
+                _ => (ADHDStateType::Flow, "editor"),
+            };
+            sessions.record(window(minute, state, app));
+        }
+        // Six minutes in the browser: a new session
+        for minute in 10..16 {
+            sessions.record(window(minute, ADHDStateType::Neutral, "browser"));
+        }
+        // Back after a long break: another session
+        for minute in 40..45 {
+            sessions.record(window(minute, ADHDStateType::Flow, "editor"));
+        }
+
+        let page = sessions.sessions(&SessionQuery::default());
+        assert_eq!(page.sessions.len(), 3);
+        assert!(page.next_cursor.is_none());
+
+        let coding = &page.sessions[2];
+        assert_eq!(coding.windows, 10);
+        assert_eq!(coding.interruptions, 2);
+        assert_eq!(coding.flow_percent, 70.0);
+        assert_eq!(coding.dominant_work_type.as_deref(), Some("coding"));
+        assert_eq!(coding.application.as_deref(), Some("editor"));
+        assert!(!coding.open);
+
+        assert_eq!(page.sessions[1].application.as_deref(), Some("browser"));
+        assert!(page.sessions[0].open);
+    }
+
+    #[test]
+    fn test_pagination_and_cache() {
+        let sessions = SessionReconstructor::new(SessionConfig::default());
+        for session in 0..5 {
+            for minute in 0..3 {
+                sessions.record(window(session * 30 + minute, ADHDStateType::Flow, "editor"));
+            }
+        }
+
+        let first = sessions.sessions(&SessionQuery { limit: Some(2), ..Default::default() });
+        assert_eq!(first.sessions.len(), 2);
+        assert!(first.sessions[0].start > first.sessions[1].start);
+        assert_eq!(sessions.cached_sessions(), 4);
+
+        let second = sessions.sessions(&SessionQuery {
+            limit: Some(2),
+            before: first.next_cursor,
+            ..Default::default()
+        });
+        let third = sessions.sessions(&SessionQuery {
+            limit: Some(2),
+            before: second.next_cursor,
+            ..Default::default()
+        });
+        assert_eq!(second.sessions.len(), 2);
+        assert_eq!(third.sessions.len(), 1);
+        assert!(third.next_cursor.is_none());
+
+        // New windows only extend the trailing session
+        sessions.record(window(123, ADHDStateType::Flow, "editor"));
+        let latest = sessions.sessions(&SessionQuery { limit: Some(1), ..Default::default() });
+        assert_eq!(latest.sessions[0].windows, 4);
+        assert_eq!(sessions.cached_sessions(), 4);
+    }
+
+    #[test]
+    fn test_focus_is_totalled_per_declared_task() {
+        let sessions = SessionReconstructor::new(SessionConfig::default());
+        let (report, slides) = (Uuid::new_v4(), Uuid::new_v4());
+        for minute in 0..6 {
+            let state = if minute == 4 { ADHDStateType::Distracted } else { ADHDStateType::Flow };
+            sessions.record(window(minute, state, "editor").with_task(Some(report)));
+        }
+        sessions.record(window(6, ADHDStateType::Neutral, "editor"));
+        for minute in 7..9 {
+            sessions.record(window(minute, ADHDStateType::Flow, "editor").with_task(Some(slides)));
+        }
+
+        let tasks = sessions.task_focus(None, None);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].task_id, report);
+        assert_eq!(tasks[0].windows, 6);
+        assert_eq!(tasks[0].focused_duration, Duration::from_secs(5 * 60));
+        assert_eq!(tasks[0].distracted_duration, Duration::from_secs(60));
+        assert!((tasks[0].flow_percent() - 83.33).abs() < 0.01);
+        assert_eq!(tasks[1].active_duration, Duration::from_secs(2 * 60));
+
+        let later = window(7, ADHDStateType::Flow, "editor").timestamp;
+        let tasks = sessions.task_focus(Some(later), None);
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].task_id, slides);
+    }
+}
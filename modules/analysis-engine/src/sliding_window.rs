@@ -106,7 +106,7 @@ impl AnalysisWindow {
 
         // Score from event type diversity
         let keystroke_count = self.events.iter().filter(|e| matches!(e, RawEvent::Keystroke(_))).count();
-        let mouse_count = self.events.iter().filter(|e| matches!(e, RawEvent::MouseMove(_) | RawEvent::MouseClick(_))).count();
+        let mouse_count = self.events.iter().filter(|e| matches!(e, RawEvent::MouseMove(_) | RawEvent::MouseTrajectory(_) | RawEvent::MouseClick(_))).count();
         let window_count = self.events.iter().filter(|e| matches!(e, RawEvent::WindowFocus(_))).count();
         
         let diversity_score = if event_count > 0 {
@@ -145,6 +145,14 @@ impl AnalysisWindow {
         }).collect()
     }
 
+    /// Mouse moves that were summarized at capture
+    pub fn get_mouse_trajectory_events(&self) -> Vec<&skelly_jelly_storage::types::MouseTrajectoryEvent> {
+        self.events.iter().filter_map(|e| match e {
+            RawEvent::MouseTrajectory(te) => Some(te),
+            _ => None,
+        }).collect()
+    }
+
     pub fn get_mouse_click_events(&self) -> Vec<&skelly_jelly_storage::types::MouseClickEvent> {
         self.events.iter().filter_map(|e| match e {
             RawEvent::MouseClick(me) => Some(me),
@@ -280,6 +288,15 @@ impl SlidingWindowManager {
         Ok(completed_window)
     }
 
+    /// Start over after a gap in capture, such as system sleep
+    ///
+    /// The open window is dropped rather than completed: closing it would
+    /// produce one window spanning the whole gap. History is kept.
+    pub fn rebaseline(&mut self, now: SystemTime) {
+        self.current_window = AnalysisWindow::new(now);
+        self.last_window_time = Instant::now();
+    }
+
     /// Get the current window (for inspection, not analysis)
     pub fn current_window(&self) -> &AnalysisWindow {
         &self.current_window
@@ -401,6 +418,32 @@ mod tests {
         assert_eq!(manager.current_window().events.len(), 1);
     }
 
+    #[test]
+    fn test_rebaseline_after_sleep() {
+        let mut manager = SlidingWindowManager::new(
+            Duration::from_secs(30),
+            Duration::from_secs(5),
+            10,
+        );
+        let before_sleep = Utc::now() - chrono::Duration::hours(8);
+        for i in 0..20 {
+            manager.add_event(RawEvent::Keystroke(KeystrokeEvent {
+                timestamp: before_sleep + chrono::Duration::milliseconds(i * 100),
+                key_code: 65,
+                modifiers: KeyModifiers::default(),
+                inter_key_interval_ms: Some(100),
+            })).unwrap();
+        }
+
+        let woke = SystemTime::now();
+        manager.rebaseline(woke);
+        let window = manager.current_window();
+        assert!(window.events.is_empty());
+        assert_eq!(window.start_time, woke);
+        // Nothing was completed, so no window covers the eight hours asleep
+        assert_eq!(manager.get_stats().total_windows, 0);
+    }
+
     #[test]
     fn test_window_stats() {
         let manager = SlidingWindowManager::new(
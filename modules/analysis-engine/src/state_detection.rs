@@ -6,6 +6,7 @@
 //! - Real-time inference <50ms
 //! - Confidence scoring and temporal smoothing
 //! - Online learning from user feedback
+//! - Cold-start fallback to heuristics and a bundled baseline until trained
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -19,6 +20,7 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::{
+    cold_start::{BaselineBundle, HeuristicClassifier, HeuristicConfig, ModelSource},
     error::{AnalysisError, AnalysisResult},
     feature_extraction::FeatureExtractionPipeline,
     models::{
@@ -37,6 +39,12 @@ pub struct StateDetectionEngine {
     /// Primary Random Forest classifier
     rf_classifier: Arc<Mutex<RandomForestClassifier>>,
     
+    /// Rule-based fallback while the classifier is untrained
+    heuristic: HeuristicClassifier,
+    
+    /// What the classifier has been trained on so far
+    model_source: Arc<RwLock<ModelSource>>,
+    
     /// Configuration for state detection
     config: StateDetectionConfig,
     
@@ -132,6 +140,8 @@ impl StateDetectionEngine {
         Self {
             feature_extractor: FeatureExtractionPipeline::new(),
             rf_classifier: Arc::new(Mutex::new(RandomForestClassifier::with_config(rf_config))),
+            heuristic: HeuristicClassifier::new(config.heuristic.clone()),
+            model_source: Arc::new(RwLock::new(ModelSource::Heuristic)),
             config,
             state_history: Arc::new(RwLock::new(Vec::with_capacity(100))),
             metrics: Arc::new(RwLock::new(StateDetectionMetrics::default())),
@@ -151,6 +161,37 @@ impl StateDetectionEngine {
         
         println!("Training state detection engine with {} samples...", training_data.len());
         
+        self.train_classifier(training_data).await?;
+        *self.model_source.write().await = ModelSource::Personalized;
+        
+        println!("State detection engine trained successfully!");
+        Ok(())
+    }
+    
+    /// Train the classifier on the bundled baseline model
+    ///
+    /// Does nothing once the classifier has been trained on the user's data.
+    pub async fn load_baseline_model(&self) -> AnalysisResult<()> {
+        if *self.model_source.read().await != ModelSource::Heuristic {
+            return Ok(());
+        }
+        
+        let samples = BaselineBundle::bundled()?.training_samples();
+        self.train_classifier(&samples).await?;
+        
+        let mut source = self.model_source.write().await;
+        if *source == ModelSource::Heuristic {
+            *source = ModelSource::Baseline;
+        }
+        Ok(())
+    }
+    
+    /// Which model state detection currently relies on
+    pub async fn model_source(&self) -> ModelSource {
+        *self.model_source.read().await
+    }
+    
+    async fn train_classifier(&self, training_data: &[(FeatureVector, ADHDState)]) -> AnalysisResult<()> {
         let mut classifier = self.rf_classifier.lock().map_err(|_| {
             AnalysisError::ConcurrencyError {
                 operation: "train_classifier".to_string(),
@@ -161,21 +202,32 @@ impl StateDetectionEngine {
         
         // Update metrics
         let model_metrics = classifier.performance_metrics();
+        drop(classifier);
         let mut metrics = self.metrics.write().await;
         metrics.accuracy = model_metrics.accuracy;
         metrics.avg_inference_time_ms = model_metrics.avg_inference_time_ms;
-        
-        println!("State detection engine trained successfully!");
         Ok(())
     }
     
     /// Detect ADHD state from analysis window with real-time inference
     pub async fn detect_state(&self, window: &AnalysisWindow) -> AnalysisResult<StateDetectionResult> {
+        let features = self.extract_features(window).await?;
+        self.detect_state_with_features(window, features).await
+    }
+    
+    /// The feature vector `detect_state` classifies for `window`
+    pub async fn extract_features(&self, window: &AnalysisWindow) -> AnalysisResult<FeatureVector> {
+        self.feature_extractor.extract_all_features(window).await
+    }
+    
+    /// Detect the state of `window` from features already extracted from it
+    pub async fn detect_state_with_features(
+        &self,
+        window: &AnalysisWindow,
+        features: FeatureVector,
+    ) -> AnalysisResult<StateDetectionResult> {
         let start_time = Instant::now();
         
-        // Extract features from the window
-        let features = self.feature_extractor.extract_all_features(window).await?;
-        
         // Validate features
         if !features.validate() {
             return Err(AnalysisError::InvalidFeatureVector {
@@ -183,18 +235,22 @@ impl StateDetectionEngine {
             });
         }
         
-        // Get prediction from Random Forest classifier
-        let classifier = self.rf_classifier.lock().map_err(|_| {
-            AnalysisError::ConcurrencyError {
-                operation: "predict_state".to_string(),
-            }
-        })?;
-        
-        let state_distribution = classifier.predict(&features).await?;
-        let model_confidence = classifier.confidence();
-        let feature_importance = classifier.feature_importance();
-        
-        drop(classifier); // Release lock early
+        let model_source = *self.model_source.read().await;
+        let (state_distribution, feature_importance) = if model_source == ModelSource::Heuristic && self.config.enable_cold_start {
+            // Untrained classifier: fall back to activity thresholds
+            (self.heuristic.predict(window), Vec::new())
+        } else {
+            // Get prediction from Random Forest classifier
+            let classifier = self.rf_classifier.lock().map_err(|_| {
+                AnalysisError::ConcurrencyError {
+                    operation: "predict_state".to_string(),
+                }
+            })?;
+            
+            let state_distribution = classifier.predict(&features).await?;
+            let feature_importance = classifier.feature_importance();
+            (state_distribution, feature_importance)
+        };
         
         // Apply temporal smoothing and stability analysis
         let smoothed_distribution = self.apply_temporal_smoothing(&state_distribution).await?;
@@ -233,6 +289,7 @@ impl StateDetectionEngine {
             feature_importance,
             intervention_readiness: self.calculate_intervention_readiness(&adhd_state, adjusted_confidence),
             transition_stability: self.get_recent_transitions().await.len() as f32 / 10.0,
+            model_source,
         })
     }
     
@@ -592,6 +649,9 @@ pub struct StateDetectionResult {
     
     /// Stability of recent state transitions
     pub transition_stability: f32,
+    
+    /// Model that produced the prediction
+    pub model_source: ModelSource,
 }
 
 /// User feedback for online learning
@@ -631,6 +691,10 @@ pub struct StateDetectionConfig {
     // Confidence and thresholds
     pub confidence_threshold: f32,
     pub stability_threshold: f32,
+    
+    // Cold start
+    pub enable_cold_start: bool,
+    pub heuristic: HeuristicConfig,
 }
 
 impl Default for StateDetectionConfig {
@@ -660,6 +724,10 @@ impl Default for StateDetectionConfig {
             // Thresholds
             confidence_threshold: 0.7,
             stability_threshold: 0.6,
+            
+            // Heuristics until a model is trained
+            enable_cold_start: true,
+            heuristic: HeuristicConfig::default(),
         }
     }
 }
@@ -687,7 +755,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_state_detection_without_training() {
-        let engine = StateDetectionEngine::new();
+        let engine = StateDetectionEngine::with_config(StateDetectionConfig {
+            enable_cold_start: false,
+            ..Default::default()
+        });
         let window = AnalysisWindow::new(SystemTime::now());
         
         // Should fail because model is not trained
@@ -695,6 +766,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_cold_start_detection() {
+        let engine = StateDetectionEngine::new();
+        let window = AnalysisWindow::new(SystemTime::now());
+        
+        // No events and no training: the heuristic still answers
+        let result = engine.detect_state(&window).await.unwrap();
+        assert_eq!(result.model_source, ModelSource::Heuristic);
+        assert_eq!(result.detected_state.state_type, ADHDStateType::Neutral);
+        assert!(result.confidence < engine.config.confidence_threshold);
+        
+        engine.load_baseline_model().await.unwrap();
+        assert_eq!(engine.model_source().await, ModelSource::Baseline);
+        let result = engine.detect_state(&window).await.unwrap();
+        assert_eq!(result.model_source, ModelSource::Baseline);
+    }
+
     #[tokio::test]
     async fn test_temporal_smoothing() {
         let engine = StateDetectionEngine::new();
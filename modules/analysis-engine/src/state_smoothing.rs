@@ -0,0 +1,351 @@
+//! Transition smoothing for published states
+//!
+//! Classifications of consecutive windows flap when two states are close,
+//! e.g. flow, distracted, flow over three windows. [`StateSmoother`] sits
+//! between the classifier and the published result and only lets a new state
+//! through once it is well supported. Two modes are available:
+//!
+//! - Hysteresis: a challenger must beat the current state by `min_margin`
+//!   for `min_dwell_windows` windows in a row.
+//! - HMM: a forward filter over the state distributions with a sticky
+//!   transition matrix; the published state is the most likely hidden state.
+//!
+//! Suppressed flaps and the delay smoothing adds to real transitions are
+//! tracked in [`SmoothingMetrics`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{ADHDState, ADHDStateType, StateDistribution},
+    types::{DistractionType, FlowDepth},
+};
+
+/// Smoothing algorithm applied before a state is published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmoothingMode {
+    /// Publish every raw classification
+    Off,
+    /// Require a margin held over several windows before switching
+    Hysteresis,
+    /// Forward-filter a hidden Markov model over the distributions
+    Hmm,
+}
+
+/// Configuration for transition smoothing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmoothingConfig {
+    pub mode: SmoothingMode,
+    /// Probability lead a challenger needs over the published state (hysteresis)
+    pub min_margin: f32,
+    /// Consecutive windows the challenger must lead for (hysteresis)
+    pub min_dwell_windows: usize,
+    /// Probability of staying in the same hidden state between windows (HMM)
+    pub stay_probability: f32,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self {
+            mode: SmoothingMode::Hysteresis,
+            min_margin: 0.1,
+            min_dwell_windows: 2,
+            stay_probability: 0.8,
+        }
+    }
+}
+
+/// Counters for the smoothing layer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmoothingMetrics {
+    /// Windows passed through the smoother
+    pub windows: u64,
+    /// State changes in the raw classifications
+    pub raw_transitions: u64,
+    /// State changes actually published
+    pub published_transitions: u64,
+    /// Raw runs of another state that ended before being published
+    pub suppressed_flaps: u64,
+    /// Published transitions that lagged the raw classification
+    pub delayed_transitions: u64,
+    /// Windows of lag summed over all published transitions
+    pub total_delay_windows: u64,
+    /// Longest lag of a single published transition, in windows
+    pub max_delay_windows: u64,
+}
+
+impl SmoothingMetrics {
+    /// Average windows a published transition lagged the raw classification
+    pub fn mean_delay_windows(&self) -> f32 {
+        if self.published_transitions == 0 {
+            0.0
+        } else {
+            self.total_delay_windows as f32 / self.published_transitions as f32
+        }
+    }
+}
+
+/// State to publish for one window
+#[derive(Debug, Clone)]
+pub struct SmoothedState {
+    pub state_type: ADHDStateType,
+    /// Probability of the published state after smoothing
+    pub confidence: f32,
+    /// Whether the raw classification differed from what is published
+    pub held_back: bool,
+}
+
+impl SmoothedState {
+    /// `raw` relabelled as the published state
+    pub fn apply_to(&self, raw: &ADHDState) -> ADHDState {
+        if raw.state_type == self.state_type {
+            return ADHDState { confidence: self.confidence, ..raw.clone() };
+        }
+        ADHDState {
+            state_type: self.state_type,
+            confidence: self.confidence,
+            flow_depth: match self.state_type {
+                ADHDStateType::Flow => FlowDepth::from_score(self.confidence),
+                ADHDStateType::Hyperfocus => FlowDepth::UltraDeep,
+                _ => FlowDepth::Shallow,
+            },
+            distraction_type: (self.state_type == ADHDStateType::Distracted).then_some(DistractionType::Unknown),
+            ..raw.clone()
+        }
+    }
+}
+
+/// Run of consecutive windows with the same raw classification
+#[derive(Debug, Clone, Copy)]
+struct RawRun {
+    state: ADHDStateType,
+    started_at: u64,
+    published: bool,
+}
+
+/// Smooths a sequence of per-window state distributions
+#[derive(Debug, Clone)]
+pub struct StateSmoother {
+    config: SmoothingConfig,
+    published: Option<ADHDStateType>,
+    /// Hysteresis challenger and the windows it has led for
+    challenger: Option<(ADHDStateType, usize)>,
+    /// HMM belief over hidden states
+    belief: Option<StateDistribution>,
+    run: Option<RawRun>,
+    metrics: SmoothingMetrics,
+}
+
+impl StateSmoother {
+    pub fn new(config: SmoothingConfig) -> Self {
+        Self {
+            config,
+            published: None,
+            challenger: None,
+            belief: None,
+            run: None,
+            metrics: SmoothingMetrics::default(),
+        }
+    }
+
+    /// Feed the next window's distribution and get the state to publish
+    pub fn smooth(&mut self, distribution: &StateDistribution) -> SmoothedState {
+        let index = self.metrics.windows;
+        self.metrics.windows += 1;
+        let (raw, _) = distribution.most_likely_state();
+        self.track_raw(raw, index);
+
+        let (state_type, confidence) = match self.config.mode {
+            SmoothingMode::Off => distribution.most_likely_state(),
+            SmoothingMode::Hysteresis => self.hysteresis(raw, distribution),
+            SmoothingMode::Hmm => self.forward(distribution),
+        };
+
+        if self.published.is_some_and(|previous| previous != state_type) {
+            self.metrics.published_transitions += 1;
+            if let Some(run) = self.run.as_mut().filter(|run| run.state == state_type) {
+                let delay = index - run.started_at;
+                if delay > 0 {
+                    self.metrics.delayed_transitions += 1;
+                }
+                self.metrics.total_delay_windows += delay;
+                self.metrics.max_delay_windows = self.metrics.max_delay_windows.max(delay);
+            }
+        }
+        if let Some(run) = self.run.as_mut().filter(|run| run.state == state_type) {
+            run.published = true;
+        }
+        self.published = Some(state_type);
+
+        SmoothedState {
+            state_type,
+            confidence,
+            held_back: raw != state_type,
+        }
+    }
+
+    /// Counters since the smoother was created
+    pub fn metrics(&self) -> &SmoothingMetrics {
+        &self.metrics
+    }
+
+    /// Forget the published state, e.g. after a long idle gap
+    pub fn reset(&mut self) {
+        self.published = None;
+        self.challenger = None;
+        self.belief = None;
+        self.run = None;
+    }
+
+    fn track_raw(&mut self, raw: ADHDStateType, index: u64) {
+        match self.run {
+            Some(run) if run.state == raw => {}
+            previous => {
+                if let Some(run) = previous {
+                    self.metrics.raw_transitions += 1;
+                    if !run.published {
+                        self.metrics.suppressed_flaps += 1;
+                    }
+                }
+                self.run = Some(RawRun { state: raw, started_at: index, published: false });
+            }
+        }
+    }
+
+    fn hysteresis(&mut self, raw: ADHDStateType, distribution: &StateDistribution) -> (ADHDStateType, f32) {
+        let Some(current) = self.published else {
+            return (raw, probability(distribution, raw));
+        };
+        let margin = probability(distribution, raw) - probability(distribution, current);
+        if raw == current || margin < self.config.min_margin {
+            self.challenger = None;
+            return (current, probability(distribution, current));
+        }
+
+        let led_for = match self.challenger {
+            Some((state, windows)) if state == raw => windows + 1,
+            _ => 1,
+        };
+        if led_for >= self.config.min_dwell_windows {
+            self.challenger = None;
+            (raw, probability(distribution, raw))
+        } else {
+            self.challenger = Some((raw, led_for));
+            (current, probability(distribution, current))
+        }
+    }
+
+    fn forward(&mut self, distribution: &StateDistribution) -> (ADHDStateType, f32) {
+        let emission = as_array(distribution);
+        let prior = match &self.belief {
+            Some(belief) => {
+                // Sticky transitions: stay with `stay_probability`, else move uniformly
+                let stay = self.config.stay_probability.clamp(0.0, 1.0);
+                let switch = (1.0 - stay) / (STATES.len() - 1) as f32;
+                let belief = as_array(belief);
+                let total: f32 = belief.iter().sum();
+                belief.map(|p| p * stay + (total - p) * switch)
+            }
+            None => [1.0 / STATES.len() as f32; 5],
+        };
+
+        let mut posterior = [0.0; 5];
+        for (p, (prior, emission)) in posterior.iter_mut().zip(prior.iter().zip(&emission)) {
+            *p = prior * emission;
+        }
+        let total: f32 = posterior.iter().sum();
+        if total > f32::EPSILON {
+            posterior.iter_mut().for_each(|p| *p /= total);
+        } else {
+            // The classifier ruled out every likely state; start over from its output
+            posterior = emission;
+        }
+
+        let belief = from_array(posterior);
+        let result = belief.most_likely_state();
+        self.belief = Some(belief);
+        result
+    }
+}
+
+impl Default for StateSmoother {
+    fn default() -> Self {
+        Self::new(SmoothingConfig::default())
+    }
+}
+
+const STATES: [ADHDStateType; 5] = [
+    ADHDStateType::Flow,
+    ADHDStateType::Hyperfocus,
+    ADHDStateType::Distracted,
+    ADHDStateType::Transitioning,
+    ADHDStateType::Neutral,
+];
+
+fn as_array(d: &StateDistribution) -> [f32; 5] {
+    [d.flow, d.hyperfocus, d.distracted, d.transitioning, d.neutral]
+}
+
+fn from_array(p: [f32; 5]) -> StateDistribution {
+    StateDistribution {
+        flow: p[0],
+        hyperfocus: p[1],
+        distracted: p[2],
+        transitioning: p[3],
+        neutral: p[4],
+    }
+}
+
+fn probability(d: &StateDistribution, state: ADHDStateType) -> f32 {
+    as_array(d)[STATES.iter().position(|s| *s == state).unwrap_or(4)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ADHDStateType::{Distracted as D, Flow as F};
+
+    fn leaning(state: ADHDStateType, p: f32) -> StateDistribution {
+        let mut values = [(1.0 - p) / 4.0; 5];
+        values[STATES.iter().position(|s| *s == state).unwrap()] = p;
+        from_array(values)
+    }
+
+    fn publish(smoother: &mut StateSmoother, sequence: &[ADHDStateType]) -> Vec<ADHDStateType> {
+        sequence.iter().map(|&s| smoother.smooth(&leaning(s, 0.6)).state_type).collect()
+    }
+
+    #[test]
+    fn test_hysteresis_suppresses_single_window_flaps() {
+        let mut smoother = StateSmoother::default();
+        let published = publish(&mut smoother, &[F, F, D, F, F, D, D, D]);
+
+        assert_eq!(published, vec![F, F, F, F, F, F, D, D]);
+        let metrics = smoother.metrics();
+        assert_eq!(metrics.raw_transitions, 3);
+        assert_eq!(metrics.suppressed_flaps, 1);
+        assert_eq!(metrics.published_transitions, 1);
+        assert_eq!(metrics.max_delay_windows, 1);
+        assert_eq!(metrics.mean_delay_windows(), 1.0);
+
+        let mut off = StateSmoother::new(SmoothingConfig { mode: SmoothingMode::Off, ..Default::default() });
+        assert_eq!(publish(&mut off, &[F, D, F]), vec![F, D, F]);
+        assert_eq!(off.metrics().suppressed_flaps, 0);
+    }
+
+    #[test]
+    fn test_hmm_follows_sustained_change() {
+        let mut smoother = StateSmoother::new(SmoothingConfig { mode: SmoothingMode::Hmm, ..Default::default() });
+        let published = publish(&mut smoother, &[F, F, F, D, F, D, D, D]);
+
+        // The lone distracted window is absorbed; the sustained run gets through
+        assert_eq!(published[3], F);
+        assert_eq!(published[4], F);
+        assert_eq!(*published.last().unwrap(), D);
+        assert_eq!(smoother.metrics().suppressed_flaps, 1);
+        assert_eq!(smoother.metrics().published_transitions, 1);
+
+        let held = smoother.smooth(&leaning(F, 0.6));
+        assert!(held.held_back);
+        assert_eq!(held.state_type, D);
+    }
+}
@@ -14,6 +14,7 @@ use std::{
 
 use crate::{
     error::{AnalysisError, AnalysisResult},
+    privacy::{FeatureExportConfig, NoisedFeatureStats, PrivateFeatureExporter},
     models::{
         ADHDState, ADHDStateType, ONNXClassifier, RandomForestClassifier, StateModel,
         ModelMetadata, ONNXConfig, RandomForestConfig,
@@ -32,7 +33,7 @@ pub struct TrainingPipeline {
     /// Test dataset
     test_data: Vec<(FeatureVector, ADHDState)>,
     /// Best model found during training
-    best_model: Option<Box<dyn StateModel>>,
+    best_model: Option<Box<dyn ModelTrait>>,
     /// Training metrics history
     training_history: Vec<TrainingEpoch>,
 }
@@ -265,56 +266,82 @@ impl TrainingPipeline {
         Ok(())
     }
 
+    /// Use samples already in memory (e.g. accumulated user feedback) as the dataset
+    pub fn load_samples(&mut self, samples: Vec<(FeatureVector, ADHDState)>) -> AnalysisResult<()> {
+        self.split_data(samples)
+    }
+
+    /// Training configuration in use
+    pub fn config(&self) -> &TrainingConfig {
+        &self.config
+    }
+
     /// Run hyperparameter optimization
     pub fn optimize_hyperparameters(&mut self) -> AnalysisResult<HyperparameterResults> {
         println!("Starting hyperparameter optimization...");
-        let start_time = Instant::now();
 
-        let mut best_accuracy = 0.0;
-        let mut best_params = HashMap::new();
-        let mut best_model_type = String::new();
-        let mut cross_validation_scores = Vec::new();
+        let mut progress = OptimizationProgress::default();
+        while !progress.is_complete(&self.config) {
+            self.optimization_step(&mut progress)?;
+        }
 
-        // Random search for hyperparameters
-        for iteration in 0..self.config.max_optimization_iterations {
-            println!("Optimization iteration {}/{}", iteration + 1, self.config.max_optimization_iterations);
+        self.finish_optimization(progress)
+    }
 
-            // Generate random hyperparameters
-            let params = self.generate_random_hyperparameters();
-            let model_type = self.select_model_type(&params);
+    /// Run one random-search iteration, folding its result into `progress`
+    ///
+    /// `progress` is serializable, so a caller can checkpoint between steps and
+    /// resume an interrupted optimization with a fresh pipeline.
+    pub fn optimization_step(&mut self, progress: &mut OptimizationProgress) -> AnalysisResult<()> {
+        let start_time = Instant::now();
+        println!("Optimization iteration {}/{}", progress.iterations_completed + 1, self.config.max_optimization_iterations);
 
-            // Train model with these parameters
-            let mut model = self.create_model(&model_type, &params)?;
-            model.train(&self.training_data)?;
+        // Generate random hyperparameters
+        let params = self.generate_random_hyperparameters();
+        let model_type = self.select_model_type(&params);
 
-            // Evaluate with cross-validation
-            let cv_scores = self.cross_validate(&model_type, &params)?;
-            let avg_cv_score = cv_scores.iter().sum::<f32>() / cv_scores.len() as f32;
+        // Train model with these parameters
+        let mut model = self.create_model(&model_type, &params)?;
+        model.train(&self.training_data)?;
 
-            cross_validation_scores.extend(cv_scores);
+        // Evaluate with cross-validation
+        let cv_scores = self.cross_validate(&model_type, &params)?;
+        let avg_cv_score = cv_scores.iter().sum::<f32>() / cv_scores.len() as f32;
 
-            println!("Model: {}, Params: {:?}, CV Score: {:.4}", model_type, params, avg_cv_score);
+        progress.cross_validation_scores.extend(cv_scores);
 
-            // Update best if this is better
-            if avg_cv_score > best_accuracy {
-                best_accuracy = avg_cv_score;
-                best_params = params;
-                best_model_type = model_type;
-                println!("New best accuracy: {:.4}", best_accuracy);
-            }
+        println!("Model: {}, Params: {:?}, CV Score: {:.4}", model_type, params, avg_cv_score);
 
-            // Early stopping if target accuracy reached
-            if best_accuracy >= self.config.target_accuracy {
-                println!("Target accuracy {:.4} reached, stopping optimization", self.config.target_accuracy);
-                break;
-            }
+        // Update best if this is better
+        if avg_cv_score > progress.best_accuracy {
+            progress.best_accuracy = avg_cv_score;
+            progress.best_params = params;
+            progress.best_model_type = model_type;
+            println!("New best accuracy: {:.4}", progress.best_accuracy);
+        }
+
+        progress.iterations_completed += 1;
+        progress.elapsed_secs += start_time.elapsed().as_secs_f32();
+
+        // Early stopping if target accuracy reached
+        if progress.best_accuracy >= self.config.target_accuracy {
+            println!("Target accuracy {:.4} reached, stopping optimization", self.config.target_accuracy);
         }
 
-        let total_time = start_time.elapsed().as_secs_f32();
+        Ok(())
+    }
+
+    /// Train the final model with the best parameters found
+    pub fn finish_optimization(&mut self, progress: OptimizationProgress) -> AnalysisResult<HyperparameterResults> {
+        if progress.iterations_completed == 0 {
+            return Err(AnalysisError::TrainingFailed {
+                message: "No optimization iterations completed".to_string(),
+            });
+        }
 
         // Train final model with best parameters
         println!("Training final model with best parameters...");
-        let mut final_model = self.create_model(&best_model_type, &best_params)?;
+        let mut final_model = self.create_model(&progress.best_model_type, &progress.best_params)?;
         final_model.train(&self.training_data)?;
 
         // Validate accuracy requirement
@@ -328,18 +355,26 @@ impl TrainingPipeline {
         self.best_model = Some(final_model);
 
         let results = HyperparameterResults {
-            best_params,
-            best_accuracy,
-            best_model_type,
-            optimization_iterations: iteration + 1,
-            total_training_time_secs: total_time,
-            cross_validation_scores,
+            best_params: progress.best_params,
+            best_accuracy: progress.best_accuracy,
+            best_model_type: progress.best_model_type,
+            optimization_iterations: progress.iterations_completed,
+            total_training_time_secs: progress.elapsed_secs,
+            cross_validation_scores: progress.cross_validation_scores,
         };
 
-        println!("Hyperparameter optimization completed in {:.2}s", total_time);
+        println!("Hyperparameter optimization completed in {:.2}s", progress.elapsed_secs);
         Ok(results)
     }
 
+    /// Validation accuracy of the trained model, if one has been trained
+    pub fn validation_accuracy(&self) -> AnalysisResult<Option<f32>> {
+        self.best_model
+            .as_ref()
+            .map(|model| self.evaluate_model(&**model))
+            .transpose()
+    }
+
     /// Generate random hyperparameters
     fn generate_random_hyperparameters(&self) -> HashMap<String, f32> {
         use rand::Rng;
@@ -484,6 +519,18 @@ impl TrainingPipeline {
         Ok(())
     }
 
+    /// Export differentially private aggregate statistics of the loaded dataset
+    ///
+    /// Fails with [`AnalysisError::ExportRefused`] unless `feature_export` is
+    /// enabled; raw samples never leave the pipeline.
+    pub fn export_private_feature_stats(&self, purpose: &str) -> AnalysisResult<NoisedFeatureStats> {
+        let mut exporter = PrivateFeatureExporter::new(self.config.feature_export.clone())?;
+        exporter.export(
+            self.training_data.iter().chain(&self.validation_data).chain(&self.test_data),
+            purpose,
+        )
+    }
+
     /// Get feature importance from the model
     fn get_feature_importance(&self, model: &dyn ModelTrait) -> HashMap<String, f32> {
         model.feature_importance().into_iter().collect()
@@ -504,6 +551,27 @@ impl TrainingPipeline {
     }
 }
 
+/// Where a hyperparameter search stands between iterations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OptimizationProgress {
+    pub iterations_completed: usize,
+    pub best_accuracy: f32,
+    pub best_params: HashMap<String, f32>,
+    pub best_model_type: String,
+    pub cross_validation_scores: Vec<f32>,
+    /// Time spent optimizing, summed across interrupted sessions
+    pub elapsed_secs: f32,
+}
+
+impl OptimizationProgress {
+    /// Whether the search has run out of iterations, reached its target, or used up its time
+    pub fn is_complete(&self, config: &TrainingConfig) -> bool {
+        self.iterations_completed >= config.max_optimization_iterations
+            || (self.iterations_completed > 0 && self.best_accuracy >= config.target_accuracy)
+            || self.elapsed_secs >= config.max_training_time_minutes as f32 * 60.0
+    }
+}
+
 /// Training configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrainingConfig {
@@ -523,6 +591,10 @@ pub struct TrainingConfig {
     /// Export settings
     pub export_onnx: bool,
     pub export_path: String,
+
+    /// Opt-in export of noised aggregate statistics; disabled by default
+    #[serde(default)]
+    pub feature_export: FeatureExportConfig,
 }
 
 impl Default for TrainingConfig {
@@ -537,6 +609,7 @@ impl Default for TrainingConfig {
             max_training_time_minutes: 60,
             export_onnx: true,
             export_path: "models/adhd_classifier.onnx".to_string(),
+            feature_export: FeatureExportConfig::default(),
         }
     }
 }
@@ -641,4 +714,33 @@ mod tests {
         assert_eq!(config.train_split + config.validation_split, 0.8);
         // Test split should be 0.2 (remaining)
     }
+
+    #[test]
+    fn test_optimization_progress_completion() {
+        let config = TrainingConfig {
+            max_optimization_iterations: 3,
+            target_accuracy: 0.9,
+            max_training_time_minutes: 1,
+            ..Default::default()
+        };
+
+        let mut progress = OptimizationProgress::default();
+        assert!(!progress.is_complete(&config));
+
+        progress.iterations_completed = 1;
+        progress.best_accuracy = 0.95;
+        assert!(progress.is_complete(&config));
+
+        progress.best_accuracy = 0.5;
+        progress.elapsed_secs = 61.0;
+        assert!(progress.is_complete(&config));
+
+        progress.elapsed_secs = 0.0;
+        progress.iterations_completed = 3;
+        assert!(progress.is_complete(&config));
+
+        let restored: OptimizationProgress =
+            serde_json::from_str(&serde_json::to_string(&progress).unwrap()).unwrap();
+        assert_eq!(restored.iterations_completed, 3);
+    }
 }
\ No newline at end of file
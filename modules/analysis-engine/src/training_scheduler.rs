@@ -0,0 +1,535 @@
+//! Scheduled background retraining
+//!
+//! [`TrainingPipeline`] only runs when asked. The scheduler runs it on its own
+//! from accumulated user feedback, and only while retraining will go unnoticed:
+//! the machine is charging, the user has been idle for a while, and the
+//! orchestrator has not asked the engine to defer training. Each optimization
+//! iteration is paced to the CPU share the orchestrator's resource manager
+//! allows the analysis engine, and progress is checkpointed after every
+//! iteration, so unplugging or coming back to the keyboard only pauses a run.
+//! A finished run is announced with a `TrainingCompleted` event.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{
+    message::TrainingCompleted, BusMessage, EventBusTrait, MessagePayload, ModuleId,
+};
+use tracing::{info, warn};
+
+use crate::{
+    error::{AnalysisError, AnalysisResult},
+    models::ADHDState,
+    training_pipeline::{OptimizationProgress, TrainingConfig, TrainingPipeline},
+    types::FeatureVector,
+};
+
+/// When and how hard background retraining may run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingScheduleConfig {
+    /// Feedback samples needed before a run is started
+    pub min_new_samples: usize,
+    /// How long the user must have been inactive
+    pub min_idle: Duration,
+    /// Only train on mains power
+    pub require_charging: bool,
+    /// How often conditions are re-checked while waiting
+    pub check_interval: Duration,
+    /// Share of one core the training thread may use before orchestrator throttling
+    pub max_cpu_share: f32,
+    /// How long an orchestrator CPU throttle is honoured after its last report
+    pub throttle_hold: Duration,
+    /// Where an in-progress run is checkpointed
+    pub checkpoint_path: PathBuf,
+    pub training: TrainingConfig,
+}
+
+impl Default for TrainingScheduleConfig {
+    fn default() -> Self {
+        Self {
+            min_new_samples: 200,
+            min_idle: Duration::from_secs(600),
+            require_charging: true,
+            check_interval: Duration::from_secs(60),
+            max_cpu_share: 0.25,
+            throttle_hold: Duration::from_secs(300),
+            checkpoint_path: PathBuf::from("models/training_checkpoint.json"),
+            training: TrainingConfig {
+                min_training_samples: 100,
+                max_optimization_iterations: 20,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Why a scheduled run is not going ahead right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrainingBlocker {
+    NotCharging,
+    UserActive,
+    /// The orchestrator's power policy asked for training to wait
+    Deferred,
+    /// The orchestrator suspended the analysis engine's CPU use
+    Suspended,
+    NotEnoughFeedback,
+    AlreadyRunning,
+}
+
+/// Result of one scheduling attempt
+#[derive(Debug, Clone)]
+pub enum TrainingRunOutcome {
+    /// Nothing was trained
+    Waiting(TrainingBlocker),
+    /// A run stopped early and is checkpointed for later
+    Paused {
+        blocker: TrainingBlocker,
+        iterations_completed: usize,
+    },
+    /// A new model was trained and `TrainingCompleted` published
+    Completed(TrainingCompleted),
+}
+
+/// What the scheduler has been told about the machine and the user
+#[derive(Debug, Clone)]
+struct Conditions {
+    charging: bool,
+    last_activity: Instant,
+    defer_training: bool,
+    /// CPU factor from the last orchestrator throttle and when it was reported
+    throttle: Option<(f32, Instant)>,
+}
+
+impl Conditions {
+    fn new() -> Self {
+        Self {
+            // Unknown power state counts as battery until the orchestrator says otherwise
+            charging: false,
+            last_activity: Instant::now(),
+            defer_training: false,
+            throttle: None,
+        }
+    }
+
+    fn cpu_share(&self, config: &TrainingScheduleConfig, now: Instant) -> f32 {
+        let factor = match self.throttle {
+            Some((factor, reported)) if now.duration_since(reported) < config.throttle_hold => factor,
+            _ => 1.0,
+        };
+        (config.max_cpu_share * factor).clamp(0.0, 1.0)
+    }
+
+    fn blocker(&self, config: &TrainingScheduleConfig, now: Instant) -> Option<TrainingBlocker> {
+        if config.require_charging && !self.charging {
+            Some(TrainingBlocker::NotCharging)
+        } else if self.defer_training {
+            Some(TrainingBlocker::Deferred)
+        } else if now.duration_since(self.last_activity) < config.min_idle {
+            Some(TrainingBlocker::UserActive)
+        } else if self.cpu_share(config, now) <= 0.0 {
+            Some(TrainingBlocker::Suspended)
+        } else {
+            None
+        }
+    }
+}
+
+/// CPU factor for an orchestrator throttle level
+fn throttle_factor(level: &str) -> f32 {
+    match level {
+        "reduced" => 0.5,
+        "restricted" => 0.25,
+        "suspended" => 0.0,
+        _ => 1.0,
+    }
+}
+
+/// Sleep after `busy` of work so the training thread averages `share` of a core
+fn pacing_delay(busy: Duration, share: f32) -> Duration {
+    if share >= 1.0 {
+        return Duration::ZERO;
+    }
+    busy.mul_f32((1.0 - share) / share.max(0.01))
+}
+
+/// A run in progress, as written between iterations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrainingCheckpoint {
+    samples: Vec<(FeatureVector, ADHDState)>,
+    progress: OptimizationProgress,
+    started_at: DateTime<Utc>,
+}
+
+impl TrainingCheckpoint {
+    fn load(path: &Path) -> AnalysisResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map(Some).map_err(|e| AnalysisError::DataLoadError {
+            path: path.display().to_string(),
+            message: format!("Failed to parse training checkpoint: {}", e),
+        })
+    }
+
+    /// Write atomically so an interruption mid-write leaves the previous checkpoint
+    fn save(&self, path: &Path) -> AnalysisResult<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self)?;
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, content)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+/// How a run on the training thread ended
+enum TrainingThreadOutcome {
+    Paused(TrainingBlocker, usize),
+    Finished {
+        validation_accuracy: f32,
+        iterations: usize,
+        samples: usize,
+    },
+}
+
+/// Retrains the state model in the background when conditions allow
+pub struct TrainingScheduler {
+    config: TrainingScheduleConfig,
+    event_bus: Arc<dyn EventBusTrait>,
+    feedback: Mutex<Vec<(FeatureVector, ADHDState)>>,
+    conditions: Arc<Mutex<Conditions>>,
+    last_accuracy: Mutex<Option<f32>>,
+    running: AtomicBool,
+}
+
+impl TrainingScheduler {
+    pub fn new(config: TrainingScheduleConfig, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self {
+            config,
+            event_bus,
+            feedback: Mutex::new(Vec::new()),
+            conditions: Arc::new(Mutex::new(Conditions::new())),
+            last_accuracy: Mutex::new(None),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    /// Add a labelled sample from user feedback
+    pub fn record_feedback(&self, features: FeatureVector, state: ADHDState) {
+        self.feedback.lock().unwrap().push((features, state));
+    }
+
+    /// Feedback waiting for the next run
+    pub fn pending_feedback(&self) -> usize {
+        self.feedback.lock().unwrap().len()
+    }
+
+    /// Note that the user is at the keyboard
+    pub fn record_activity(&self) {
+        self.conditions.lock().unwrap().last_activity = Instant::now();
+    }
+
+    /// Set the power source directly, e.g. from an initial power reading
+    pub fn set_charging(&self, charging: bool) {
+        self.conditions.lock().unwrap().charging = charging;
+    }
+
+    /// Update conditions from bus traffic: power changes, the orchestrator's
+    /// training deferral and CPU throttling, and user activity
+    pub fn handle_message(&self, message: &BusMessage) {
+        let mut conditions = self.conditions.lock().unwrap();
+        match &message.payload {
+            MessagePayload::PowerStateChanged(change) => conditions.charging = change.charging,
+            MessagePayload::ConfigUpdate(update) if update.target_module == Some(ModuleId::AnalysisEngine) => {
+                if let Some(defer) = update.config_value.get("defer_training").and_then(|value| value.as_bool()) {
+                    conditions.defer_training = defer;
+                }
+            }
+            MessagePayload::ResourceViolation(violation)
+                if violation.module == ModuleId::AnalysisEngine && violation.resource == "cpu_percent" =>
+            {
+                conditions.throttle = Some((throttle_factor(&violation.throttle_level), Instant::now()));
+            }
+            MessagePayload::RawEvent(_) => conditions.last_activity = Instant::now(),
+            MessagePayload::EventBatch(batch) if !batch.events.is_empty() => {
+                conditions.last_activity = Instant::now();
+            }
+            _ => {}
+        }
+    }
+
+    /// Why training would not run right now, if anything stands in the way
+    pub fn blocker(&self) -> Option<TrainingBlocker> {
+        self.conditions.lock().unwrap().blocker(&self.config, Instant::now())
+    }
+
+    /// Check conditions every `check_interval` and train whenever they allow
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.check_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    warn!("Scheduled training failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Start or resume a run if conditions allow, training until it completes
+    /// or conditions change
+    pub async fn run_once(&self) -> AnalysisResult<TrainingRunOutcome> {
+        if let Some(blocker) = self.blocker() {
+            return Ok(TrainingRunOutcome::Waiting(blocker));
+        }
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(TrainingRunOutcome::Waiting(TrainingBlocker::AlreadyRunning));
+        }
+        let outcome = self.run_checkpointed().await;
+        self.running.store(false, Ordering::SeqCst);
+        outcome
+    }
+
+    async fn run_checkpointed(&self) -> AnalysisResult<TrainingRunOutcome> {
+        let path = self.config.checkpoint_path.clone();
+        let (checkpoint, resumed) = match TrainingCheckpoint::load(&path)? {
+            Some(checkpoint) => (checkpoint, true),
+            None => {
+                let samples = {
+                    let mut feedback = self.feedback.lock().unwrap();
+                    if feedback.len() < self.config.min_new_samples {
+                        return Ok(TrainingRunOutcome::Waiting(TrainingBlocker::NotEnoughFeedback));
+                    }
+                    std::mem::take(&mut *feedback)
+                };
+                let checkpoint = TrainingCheckpoint {
+                    samples,
+                    progress: OptimizationProgress::default(),
+                    started_at: Utc::now(),
+                };
+                checkpoint.save(&path)?;
+                (checkpoint, false)
+            }
+        };
+
+        if resumed {
+            info!(
+                "🧠 Resuming background training from checkpoint ({} iterations done)",
+                checkpoint.progress.iterations_completed
+            );
+        } else {
+            info!("🧠 Starting background training on {} feedback samples", checkpoint.samples.len());
+        }
+
+        let started_at = checkpoint.started_at;
+        let config = self.config.clone();
+        let conditions = self.conditions.clone();
+        // Training is synchronous and CPU-bound, so it gets its own thread
+        let result = tokio::task::spawn_blocking(move || Self::train(&config, &conditions, checkpoint))
+            .await
+            .map_err(|e| AnalysisError::TrainingFailed {
+                message: format!("Training thread panicked: {}", e),
+            })?;
+
+        let (validation_accuracy, iterations, samples) = match result {
+            Ok(TrainingThreadOutcome::Paused(blocker, iterations_completed)) => {
+                info!("⏸️ Background training paused ({:?}) after {} iterations", blocker, iterations_completed);
+                return Ok(TrainingRunOutcome::Paused { blocker, iterations_completed });
+            }
+            Ok(TrainingThreadOutcome::Finished { validation_accuracy, iterations, samples }) => {
+                (validation_accuracy, iterations, samples)
+            }
+            Err(e) => {
+                // Give the samples back so the next run trains on them plus whatever arrives meanwhile
+                if let Ok(Some(checkpoint)) = TrainingCheckpoint::load(&path) {
+                    self.feedback.lock().unwrap().splice(0..0, checkpoint.samples);
+                }
+                let _ = fs::remove_file(&path);
+                return Err(e);
+            }
+        };
+
+        let _ = fs::remove_file(&path);
+        let previous_accuracy = self.last_accuracy.lock().unwrap().replace(validation_accuracy);
+        let completed = TrainingCompleted {
+            model_type: "random_forest".to_string(),
+            samples_used: samples,
+            validation_accuracy,
+            previous_accuracy,
+            optimization_iterations: iterations,
+            duration_ms: (Utc::now() - started_at).num_milliseconds().max(0) as u64,
+            resumed_from_checkpoint: resumed,
+            timestamp: Utc::now(),
+        };
+        info!(
+            "✅ Background training finished: {:.1}% validation accuracy on {} samples",
+            validation_accuracy * 100.0,
+            samples
+        );
+
+        self.event_bus
+            .publish(BusMessage::new(
+                ModuleId::AnalysisEngine,
+                MessagePayload::TrainingCompleted(completed.clone()),
+            ))
+            .await?;
+
+        Ok(TrainingRunOutcome::Completed(completed))
+    }
+
+    /// Run optimization iterations until done or conditions change, checkpointing after each
+    fn train(
+        config: &TrainingScheduleConfig,
+        conditions: &Mutex<Conditions>,
+        mut checkpoint: TrainingCheckpoint,
+    ) -> AnalysisResult<TrainingThreadOutcome> {
+        let samples = checkpoint.samples.len();
+        let mut pipeline = TrainingPipeline::new(config.training.clone());
+        pipeline.load_samples(checkpoint.samples.clone())?;
+
+        while !checkpoint.progress.is_complete(&config.training) {
+            let share = {
+                let conditions = conditions.lock().unwrap();
+                let now = Instant::now();
+                if let Some(blocker) = conditions.blocker(config, now) {
+                    return Ok(TrainingThreadOutcome::Paused(blocker, checkpoint.progress.iterations_completed));
+                }
+                conditions.cpu_share(config, now)
+            };
+
+            let step_started = Instant::now();
+            pipeline.optimization_step(&mut checkpoint.progress)?;
+            checkpoint.save(&config.checkpoint_path)?;
+            std::thread::sleep(pacing_delay(step_started.elapsed(), share));
+        }
+
+        let results = pipeline.finish_optimization(checkpoint.progress)?;
+        let validation_accuracy = pipeline.validation_accuracy()?.unwrap_or(results.best_accuracy);
+        if config.training.export_onnx {
+            pipeline.export_to_onnx(&config.training.export_path)?;
+        }
+
+        Ok(TrainingThreadOutcome::Finished {
+            validation_accuracy,
+            iterations: results.optimization_iterations,
+            samples,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_event_bus::{
+        create_event_bus,
+        message::{ConfigUpdate, PowerStateChange, ResourceViolation},
+    };
+
+    fn new_scheduler(config: TrainingScheduleConfig) -> TrainingScheduler {
+        TrainingScheduler::new(config, create_event_bus().unwrap())
+    }
+
+    fn from_orchestrator(payload: MessagePayload) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, payload)
+    }
+
+    #[test]
+    fn test_training_waits_for_charging_idle_and_orchestrator() {
+        let scheduler = new_scheduler(TrainingScheduleConfig {
+            min_idle: Duration::ZERO,
+            ..Default::default()
+        });
+        assert_eq!(scheduler.blocker(), Some(TrainingBlocker::NotCharging));
+
+        scheduler.handle_message(&from_orchestrator(MessagePayload::PowerStateChanged(PowerStateChange {
+            from: "conserve".to_string(),
+            to: "normal".to_string(),
+            battery_percent: Some(80.0),
+            charging: true,
+            thermal_pressure: "nominal".to_string(),
+            timestamp: Utc::now(),
+        })));
+        assert_eq!(scheduler.blocker(), None);
+
+        let defer = |defer: bool| {
+            from_orchestrator(MessagePayload::ConfigUpdate(ConfigUpdate {
+                config_key: "analysis-engine_config".to_string(),
+                config_value: serde_json::json!({ "defer_training": defer }),
+                target_module: Some(ModuleId::AnalysisEngine),
+            }))
+        };
+        scheduler.handle_message(&defer(true));
+        assert_eq!(scheduler.blocker(), Some(TrainingBlocker::Deferred));
+        scheduler.handle_message(&defer(false));
+
+        scheduler.handle_message(&from_orchestrator(MessagePayload::ResourceViolation(ResourceViolation {
+            module: ModuleId::AnalysisEngine,
+            resource: "cpu_percent".to_string(),
+            observed: 45.0,
+            limit: 30.0,
+            throttle_level: "suspended".to_string(),
+            timestamp: Utc::now(),
+        })));
+        assert_eq!(scheduler.blocker(), Some(TrainingBlocker::Suspended));
+
+        let busy = new_scheduler(TrainingScheduleConfig::default());
+        busy.set_charging(true);
+        assert_eq!(busy.blocker(), Some(TrainingBlocker::UserActive));
+    }
+
+    #[test]
+    fn test_pacing_keeps_training_under_cpu_share() {
+        let busy = Duration::from_millis(100);
+        assert_eq!(pacing_delay(busy, 1.0), Duration::ZERO);
+        assert_eq!(pacing_delay(busy, 0.5).as_millis(), 100);
+        assert_eq!(pacing_delay(busy, 0.25).as_millis(), 300);
+
+        let mut conditions = Conditions::new();
+        let config = TrainingScheduleConfig::default();
+        let now = Instant::now();
+        conditions.throttle = Some((throttle_factor("reduced"), now));
+        assert_eq!(conditions.cpu_share(&config, now), 0.125);
+        assert_eq!(conditions.cpu_share(&config, now + config.throttle_hold), 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_run_waits_for_feedback_and_resumes_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint_path = dir.path().join("checkpoint.json");
+        let scheduler = new_scheduler(TrainingScheduleConfig {
+            min_idle: Duration::ZERO,
+            require_charging: false,
+            checkpoint_path: checkpoint_path.clone(),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            scheduler.run_once().await.unwrap(),
+            TrainingRunOutcome::Waiting(TrainingBlocker::NotEnoughFeedback)
+        ));
+
+        let checkpoint = TrainingCheckpoint {
+            samples: Vec::new(),
+            progress: OptimizationProgress {
+                iterations_completed: 7,
+                ..Default::default()
+            },
+            started_at: Utc::now(),
+        };
+        checkpoint.save(&checkpoint_path).unwrap();
+        let restored = TrainingCheckpoint::load(&checkpoint_path).unwrap().unwrap();
+        assert_eq!(restored.progress.iterations_completed, 7);
+        assert!(!checkpoint_path.with_extension("tmp").exists());
+    }
+}
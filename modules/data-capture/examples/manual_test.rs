@@ -87,6 +87,8 @@ fn create_test_config() -> DataCaptureConfig {
                 capture_title: true,
                 capture_app_name: true,
                 switch_threshold_ms: 200,
+                hash_titles: false,
+                title_salt: String::new(),
             },
             screenshot: ScreenshotConfig {
                 enabled: false, // Disabled for safety in testing
@@ -111,6 +113,7 @@ fn create_test_config() -> DataCaptureConfig {
                 capture_disk: false,
                 capture_network: false,
             },
+            synthetic: Default::default(),
         },
         privacy: PrivacyConfig {
             pii_detection: true,
@@ -0,0 +1,367 @@
+//! Capture clock and timestamp normalization
+//!
+//! Monitors stamp events from different clocks: OS event timestamps,
+//! `Utc::now()` on whichever task drains a buffer, timers that fire late.
+//! The wall clock itself can step under NTP. [`CaptureClock`] gives every
+//! event a monotonic capture time on one shared base, [`DriftEstimator`]
+//! tracks how far each monitor's clock sits from that base, and
+//! [`TimestampNormalizer`] moves event timestamps onto it so inter-event
+//! timing, keystroke dynamics in particular, is measured consistently.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::config::ClockConfig;
+use skelly_jelly_storage::RawEvent;
+
+/// When the pipeline received an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureTimestamp {
+    /// Wall-clock time on the capture base; never steps backwards
+    pub wall: DateTime<Utc>,
+    /// Nanoseconds since the capture clock started
+    pub monotonic_ns: u64,
+}
+
+/// Monotonic clock anchored to the wall clock once, at startup
+#[derive(Debug, Clone)]
+pub struct CaptureClock {
+    origin: Instant,
+    origin_wall: DateTime<Utc>,
+}
+
+impl CaptureClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            origin_wall: Utc::now(),
+        }
+    }
+
+    pub fn now(&self) -> CaptureTimestamp {
+        self.at(self.origin.elapsed())
+    }
+
+    /// Timestamp `elapsed` after the clock started
+    pub fn at(&self, elapsed: Duration) -> CaptureTimestamp {
+        let monotonic_ns = elapsed.as_nanos() as u64;
+        CaptureTimestamp {
+            wall: self.wall_at(monotonic_ns),
+            monotonic_ns,
+        }
+    }
+
+    pub fn wall_at(&self, monotonic_ns: u64) -> DateTime<Utc> {
+        self.origin_wall + ChronoDuration::nanoseconds(monotonic_ns as i64)
+    }
+
+    /// How far the system wall clock has moved from the capture base since startup
+    pub fn wall_clock_skew(&self) -> ChronoDuration {
+        Utc::now() - self.now().wall
+    }
+}
+
+impl Default for CaptureClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Estimates one source clock's offset from the capture base, and its drift
+///
+/// An event's source timestamp minus its capture time is the clock offset
+/// minus however long the event sat in buffers. Queueing delay is never
+/// negative, so the largest difference seen in each bucket of time is the
+/// best offset sample. A line fitted through completed buckets gives offset
+/// and drift rate; the estimate only moves when a bucket completes, so
+/// intervals between events in the same bucket are left untouched.
+#[derive(Debug, Clone)]
+pub struct DriftEstimator {
+    bucket_ns: u64,
+    max_buckets: usize,
+    step_threshold_ns: i64,
+    /// (capture time of the sample, offset in ns) per completed bucket
+    buckets: VecDeque<(u64, i64)>,
+    /// (bucket index, capture time, offset) of the best sample in the open bucket
+    current: Option<(u64, u64, i64)>,
+    /// Offset used until a bucket completes
+    provisional: Option<i64>,
+}
+
+impl DriftEstimator {
+    pub fn new(config: &ClockConfig) -> Self {
+        Self {
+            bucket_ns: config.drift_bucket_ms.max(1) * 1_000_000,
+            max_buckets: config.drift_buckets.max(2),
+            step_threshold_ns: config.step_threshold_ms as i64 * 1_000_000,
+            buckets: VecDeque::new(),
+            current: None,
+            provisional: None,
+        }
+    }
+
+    /// Record that an event stamped `offset_ns` after its capture time arrived at `capture_ns`
+    pub fn observe(&mut self, capture_ns: u64, offset_ns: i64) {
+        if self.provisional.is_some() && offset_ns > self.offset_at(capture_ns) + self.step_threshold_ns {
+            // The source clock stepped forward; older samples no longer apply
+            self.reset();
+        }
+        self.provisional.get_or_insert(offset_ns);
+
+        let bucket = capture_ns / self.bucket_ns;
+        match self.current {
+            Some((index, _, best)) if index == bucket => {
+                if offset_ns > best {
+                    self.current = Some((bucket, capture_ns, offset_ns));
+                }
+            }
+            previous => {
+                if let Some((_, time, best)) = previous {
+                    self.complete_bucket(time, best);
+                }
+                self.current = Some((bucket, capture_ns, offset_ns));
+            }
+        }
+    }
+
+    /// Estimated source offset at `capture_ns`, in ns
+    pub fn offset_at(&self, capture_ns: u64) -> i64 {
+        if let Some((origin, intercept, slope)) = self.fit() {
+            return (intercept + slope * (capture_ns as f64 - origin as f64)).round() as i64;
+        }
+        self.buckets.back().map(|b| b.1).or(self.provisional).unwrap_or(0)
+    }
+
+    /// Source clock rate error in parts per million; positive when it runs fast
+    pub fn drift_ppm(&self) -> f64 {
+        self.fit().map_or(0.0, |(_, _, slope)| slope * 1e6)
+    }
+
+    fn reset(&mut self) {
+        self.buckets.clear();
+        self.current = None;
+        self.provisional = None;
+    }
+
+    fn complete_bucket(&mut self, time: u64, offset_ns: i64) {
+        if !self.buckets.is_empty() && offset_ns < self.offset_at(time) - self.step_threshold_ns {
+            // A whole bucket far below the trend: the source clock stepped back
+            self.buckets.clear();
+        }
+        self.buckets.push_back((time, offset_ns));
+        if self.buckets.len() > self.max_buckets {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Least-squares line through the completed buckets, relative to the first one's time
+    fn fit(&self) -> Option<(u64, f64, f64)> {
+        if self.buckets.len() < 2 {
+            return None;
+        }
+        let origin = self.buckets[0].0;
+        let n = self.buckets.len() as f64;
+        let points = self.buckets.iter().map(|&(t, o)| ((t - origin) as f64, o as f64));
+        let (sx, sy, sxx, sxy) = points.fold((0.0, 0.0, 0.0, 0.0), |(sx, sy, sxx, sxy), (x, y)| {
+            (sx + x, sy + y, sxx + x * x, sxy + x * y)
+        });
+        let denominator = n * sxx - sx * sx;
+        if denominator.abs() < f64::EPSILON {
+            return None;
+        }
+        let slope = (n * sxy - sx * sy) / denominator;
+        Some((origin, (sy - slope * sx) / n, slope))
+    }
+}
+
+/// Drift state of one event source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceDrift {
+    pub source: &'static str,
+    /// Current estimated offset from the capture base, in milliseconds
+    pub offset_ms: f64,
+    pub drift_ppm: f64,
+}
+
+/// An event with its timestamp on the capture base
+#[derive(Debug, Clone)]
+pub struct NormalizedEvent {
+    pub event: RawEvent,
+    pub capture: CaptureTimestamp,
+    /// Timestamp the monitor gave the event
+    pub source_timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct SourceState {
+    drift: DriftEstimator,
+    last: Option<DateTime<Utc>>,
+}
+
+/// Moves event timestamps from each monitor's clock onto the capture base
+///
+/// Timestamps are corrected by the source's estimated offset and kept in
+/// order per source. Keystroke intervals are recomputed from the corrected
+/// timestamps.
+#[derive(Debug)]
+pub struct TimestampNormalizer {
+    clock: CaptureClock,
+    config: ClockConfig,
+    sources: HashMap<&'static str, SourceState>,
+    last_keystroke: Option<DateTime<Utc>>,
+}
+
+impl TimestampNormalizer {
+    pub fn new(clock: CaptureClock, config: ClockConfig) -> Self {
+        Self {
+            clock,
+            config,
+            sources: HashMap::new(),
+            last_keystroke: None,
+        }
+    }
+
+    pub fn clock(&self) -> &CaptureClock {
+        &self.clock
+    }
+
+    /// Normalize an event arriving now
+    pub fn normalize(&mut self, event: RawEvent) -> NormalizedEvent {
+        let capture = self.clock.now();
+        self.normalize_at(event, capture)
+    }
+
+    /// Normalize an event captured at `capture`
+    pub fn normalize_at(&mut self, mut event: RawEvent, capture: CaptureTimestamp) -> NormalizedEvent {
+        let source_timestamp = event.timestamp();
+        if !self.config.normalize_timestamps {
+            return NormalizedEvent { event, capture, source_timestamp };
+        }
+
+        let config = &self.config;
+        let state = self.sources.entry(event.event_type()).or_insert_with(|| SourceState {
+            drift: DriftEstimator::new(config),
+            last: None,
+        });
+        let offset_ns = (source_timestamp - capture.wall).num_nanoseconds().unwrap_or(i64::MAX);
+        state.drift.observe(capture.monotonic_ns, offset_ns);
+
+        let mut corrected = source_timestamp - ChronoDuration::nanoseconds(state.drift.offset_at(capture.monotonic_ns));
+        if let Some(last) = state.last {
+            corrected = corrected.max(last);
+        }
+        state.last = Some(corrected);
+        *timestamp_mut(&mut event) = corrected;
+
+        if let RawEvent::Keystroke(keystroke) = &mut event {
+            if let Some(previous) = self.last_keystroke {
+                let interval = (corrected - previous).num_milliseconds();
+                keystroke.inter_key_interval_ms = u32::try_from(interval).ok();
+            }
+            self.last_keystroke = Some(corrected);
+        }
+
+        NormalizedEvent { event, capture, source_timestamp }
+    }
+
+    /// Offset and drift of every source seen so far
+    pub fn source_drift(&self) -> Vec<SourceDrift> {
+        let now = self.clock.now().monotonic_ns;
+        let mut drift: Vec<_> = self
+            .sources
+            .iter()
+            .map(|(source, state)| SourceDrift {
+                source,
+                offset_ms: state.drift.offset_at(now) as f64 / 1e6,
+                drift_ppm: state.drift.drift_ppm(),
+            })
+            .collect();
+        drift.sort_by_key(|d| d.source);
+        drift
+    }
+}
+
+fn timestamp_mut(event: &mut RawEvent) -> &mut DateTime<Utc> {
+    match event {
+        RawEvent::Keystroke(e) => &mut e.timestamp,
+        RawEvent::MouseMove(e) => &mut e.timestamp,
+        RawEvent::MouseTrajectory(e) => &mut e.timestamp,
+        RawEvent::MouseClick(e) => &mut e.timestamp,
+        RawEvent::WindowFocus(e) => &mut e.timestamp,
+        RawEvent::Screenshot(e) => &mut e.timestamp,
+        RawEvent::ProcessStart(e) => &mut e.timestamp,
+        RawEvent::ResourceUsage(e) => &mut e.timestamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_storage::{KeyModifiers, KeystrokeEvent};
+
+    fn keystroke(timestamp: DateTime<Utc>) -> RawEvent {
+        RawEvent::Keystroke(KeystrokeEvent {
+            timestamp,
+            key_code: 0,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: None,
+        })
+    }
+
+    #[test]
+    fn test_drift_estimate_ignores_queueing_delay() {
+        let config = ClockConfig::default();
+        let mut drift = DriftEstimator::new(&config);
+        // Source runs 50 ppm fast from a 2 s offset; events wait 0-30 ms in buffers
+        for i in 0..600u64 {
+            let capture_ns = i * 500_000_000;
+            let delay_ns = ((i * 7919) % 31) as i64 * 1_000_000;
+            let offset_ns = 2_000_000_000 + capture_ns as i64 / 20_000 - delay_ns;
+            drift.observe(capture_ns, offset_ns);
+        }
+
+        let now = 300_000_000_000;
+        assert!((drift.offset_at(now) - 2_015_000_000).abs() < 2_000_000, "{}", drift.offset_at(now));
+        assert!((drift.drift_ppm() - 50.0).abs() < 10.0, "{}", drift.drift_ppm());
+
+        // A 5 s step in the source clock restarts the estimate
+        drift.observe(now + 500_000_000, 7_015_000_000);
+        assert_eq!(drift.offset_at(now + 500_000_000), 7_015_000_000);
+    }
+
+    #[test]
+    fn test_normalizer_keeps_keystroke_timing_consistent() {
+        let clock = CaptureClock::new();
+        let mut normalizer = TimestampNormalizer::new(clock.clone(), ClockConfig::default());
+        let skew = ChronoDuration::seconds(3);
+
+        // Keys pressed 120 ms apart on a clock 3 s ahead, drained in one batch 40 ms later
+        let pressed: Vec<_> = (0..3).map(|i| clock.at(Duration::from_millis(1_000 + i * 120))).collect();
+        let drained = clock.at(Duration::from_millis(1_280));
+        let normalized: Vec<_> = pressed
+            .iter()
+            .map(|p| normalizer.normalize_at(keystroke(p.wall + skew), drained))
+            .collect();
+
+        for (event, p) in normalized.iter().zip(&pressed) {
+            assert_eq!(event.source_timestamp, p.wall + skew);
+            // Off by at most the batch's queueing delay, instead of by the 3 s skew
+            assert!((event.event.timestamp() - p.wall).num_milliseconds().abs() <= 280);
+        }
+        let intervals: Vec<_> = normalized
+            .iter()
+            .map(|e| match &e.event {
+                RawEvent::Keystroke(k) => k.inter_key_interval_ms,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(intervals, vec![None, Some(120), Some(120)]);
+
+        // A late event stamped before the last one is held in order
+        let late = normalizer.normalize_at(keystroke(pressed[0].wall + skew), clock.at(Duration::from_millis(1_300)));
+        assert!(late.event.timestamp() >= normalized[2].event.timestamp());
+        assert_eq!(normalizer.source_drift()[0].source, "keystroke");
+    }
+}
@@ -3,8 +3,11 @@
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use crate::privacy::TitleHasher;
+
 /// Main configuration for the data capture module
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DataCaptureConfig {
     /// Monitor-specific configurations
     pub monitors: MonitorConfig,
@@ -12,6 +15,8 @@ pub struct DataCaptureConfig {
     pub privacy: PrivacyConfig,
     /// Performance tuning
     pub performance: PerformanceConfig,
+    /// Timestamp normalization across monitor clocks
+    pub clock: ClockConfig,
 }
 
 impl Default for DataCaptureConfig {
@@ -20,6 +25,7 @@ impl Default for DataCaptureConfig {
             monitors: MonitorConfig::default(),
             privacy: PrivacyConfig::default(),
             performance: PerformanceConfig::default(),
+            clock: ClockConfig::default(),
         }
     }
 }
@@ -33,6 +39,9 @@ pub struct MonitorConfig {
     pub screenshot: ScreenshotConfig,
     pub process: ProcessConfig,
     pub resource: ResourceConfig,
+    /// Dev mode: replace the platform monitors with generated events
+    #[serde(default)]
+    pub synthetic: SyntheticConfig,
 }
 
 impl Default for MonitorConfig {
@@ -44,6 +53,7 @@ impl Default for MonitorConfig {
             screenshot: ScreenshotConfig::default(),
             process: ProcessConfig::default(),
             resource: ResourceConfig::default(),
+            synthetic: SyntheticConfig::default(),
         }
     }
 }
@@ -80,6 +90,23 @@ pub struct MouseConfig {
     pub capture_movement: bool,
     pub capture_clicks: bool,
     pub capture_scroll: bool,
+    /// Send per-window trajectory summaries instead of every mouse move
+    #[serde(default)]
+    pub aggregate_trajectories: bool,
+    /// Length of one trajectory window
+    #[serde(default = "default_trajectory_window_ms")]
+    pub trajectory_window_ms: u64,
+    /// Gap between moves counted as idle time within a window
+    #[serde(default = "default_trajectory_idle_gap_ms")]
+    pub trajectory_idle_gap_ms: u64,
+}
+
+fn default_trajectory_window_ms() -> u64 {
+    100
+}
+
+fn default_trajectory_idle_gap_ms() -> u64 {
+    30
 }
 
 impl Default for MouseConfig {
@@ -92,6 +119,9 @@ impl Default for MouseConfig {
             capture_movement: true,
             capture_clicks: true,
             capture_scroll: true,
+            aggregate_trajectories: false,
+            trajectory_window_ms: default_trajectory_window_ms(),
+            trajectory_idle_gap_ms: default_trajectory_idle_gap_ms(),
         }
     }
 }
@@ -103,6 +133,19 @@ pub struct WindowConfig {
     pub capture_title: bool,
     pub capture_app_name: bool,
     pub switch_threshold_ms: u64,
+    /// Replace titles with a category and salted hash at capture, e.g. `video#3fa1b2c4`
+    #[serde(default)]
+    pub hash_titles: bool,
+    /// Salt for title hashes; empty means a new random salt each run
+    #[serde(default)]
+    pub title_salt: String,
+}
+
+impl WindowConfig {
+    /// Hasher to apply to captured titles, if hashing is on
+    pub fn title_hasher(&self) -> Option<TitleHasher> {
+        self.hash_titles.then(|| TitleHasher::new(&self.title_salt))
+    }
 }
 
 impl Default for WindowConfig {
@@ -112,6 +155,8 @@ impl Default for WindowConfig {
             capture_title: true,
             capture_app_name: true,
             switch_threshold_ms: 100,
+            hash_titles: false,
+            title_salt: String::new(),
         }
     }
 }
@@ -186,15 +231,57 @@ impl Default for ResourceConfig {
     }
 }
 
+/// Synthetic event generator configuration
+///
+/// When enabled, the keystroke, mouse and window monitors are replaced by one
+/// generator, so the pipeline runs without capture permissions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyntheticConfig {
+    pub enabled: bool,
+    pub scenario: SyntheticScenario,
+    /// Playback speed; 60.0 plays an hour of activity in a minute
+    pub speed: f64,
+    /// Fixed seed for a repeatable stream, random otherwise
+    pub seed: Option<u64>,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scenario: SyntheticScenario::FlowSession,
+            speed: 1.0,
+            seed: None,
+        }
+    }
+}
+
+/// Behaviour the synthetic event stream imitates
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntheticScenario {
+    /// Steady typing in an editor, rare switches, little mouse use
+    FlowSession,
+    /// Bursty typing, constant tab and app hopping, lots of scrolling
+    DistractedBrowsing,
+    /// Video call in front, occasional note taking
+    Meeting,
+}
+
 /// Privacy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PrivacyConfig {
     pub pii_detection: bool,
     pub sensitive_app_list: Vec<String>,
     pub ignored_app_list: Vec<String>,
     pub mask_passwords: bool,
+    /// Superseded by the shared privacy policy, which decides how cards, SSNs and emails are masked
     pub mask_credit_cards: bool,
+    /// Superseded by the shared privacy policy
     pub mask_ssn: bool,
+    /// Superseded by the shared privacy policy
     pub mask_emails: bool,
     pub screenshot_privacy_zones: Vec<PrivacyZone>,
 }
@@ -262,4 +349,29 @@ impl Default for PerformanceConfig {
             drop_on_overflow: true,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Capture clock and drift correction configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Move event timestamps onto the capture clock
+    pub normalize_timestamps: bool,
+    /// Window over which the best offset sample is taken
+    pub drift_bucket_ms: u64,
+    /// Completed windows the drift fit uses
+    pub drift_buckets: usize,
+    /// Offset jump treated as a clock step rather than drift
+    pub step_threshold_ms: u64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            normalize_timestamps: true,
+            drift_bucket_ms: 10_000,
+            drift_buckets: 30,
+            step_threshold_ms: 1_000,
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //! This module is responsible for capturing system events with minimal overhead
 //! while respecting user privacy and system resources.
 
+pub mod clock;
 pub mod config;
 pub mod error;
 pub mod monitors;
@@ -24,7 +25,8 @@ impl EventBus {
     }
 }
 
-pub use config::{DataCaptureConfig, MonitorConfig, PrivacyConfig, PerformanceConfig};
+pub use clock::{CaptureClock, CaptureTimestamp, NormalizedEvent, SourceDrift, TimestampNormalizer};
+pub use config::{ClockConfig, DataCaptureConfig, MonitorConfig, PrivacyConfig, PerformanceConfig, SyntheticConfig, SyntheticScenario};
 pub use error::{DataCaptureError, Result};
 use monitors::MonitorManager;
 
@@ -38,6 +40,8 @@ pub struct DataCaptureModule {
     monitor_manager: MonitorManager,
     /// Channel for receiving events from monitors  
     event_receiver: mpsc::Receiver<RawEvent>,
+    /// Moves monitor timestamps onto the shared capture clock
+    normalizer: TimestampNormalizer,
 }
 
 impl DataCaptureModule {
@@ -50,12 +54,14 @@ impl DataCaptureModule {
         
         // Initialize monitor manager with platform-specific implementations
         let monitor_manager = MonitorManager::new(config.clone(), event_sender).await?;
+        let normalizer = TimestampNormalizer::new(CaptureClock::new(), Self::clock_config(&config));
         
         Ok(Self {
             event_bus,
             config,
             monitor_manager,
             event_receiver,
+            normalizer,
         })
     }
     
@@ -95,6 +101,24 @@ impl DataCaptureModule {
         }
     }
     
+    /// Next captured event, with its timestamp on the capture clock
+    pub async fn next_event(&mut self) -> Option<NormalizedEvent> {
+        let event = self.event_receiver.recv().await?;
+        Some(self.normalizer.normalize(event))
+    }
+    
+    /// Estimated clock offset and drift of each event source
+    pub fn clock_drift(&self) -> Vec<SourceDrift> {
+        self.normalizer.source_drift()
+    }
+    
+    /// Synthetic events run on a virtual timeline that must not be pulled back to real time
+    fn clock_config(config: &DataCaptureConfig) -> ClockConfig {
+        let mut clock = config.clock.clone();
+        clock.normalize_timestamps &= !config.monitors.synthetic.enabled;
+        clock
+    }
+    
     /// Update module configuration
     pub async fn update_config(&mut self, config: DataCaptureConfig) -> Result<()> {
         info!("Updating data capture configuration");
@@ -104,6 +128,7 @@ impl DataCaptureModule {
         
         // Update config
         self.config = config.clone();
+        self.normalizer = TimestampNormalizer::new(self.normalizer.clock().clone(), Self::clock_config(&config));
         self.monitor_manager.update_config(config).await?;
         
         // Restart monitors
@@ -13,6 +13,8 @@ pub mod window;
 pub mod screenshot;
 pub mod process;
 pub mod resource;
+pub mod synthetic;
+pub mod trajectory;
 
 // Import the generic monitor implementations
 use keystroke::KeystrokeMonitor;
@@ -21,10 +23,11 @@ use window::WindowMonitor;
 use screenshot::ScreenshotMonitor;
 use process::ProcessMonitor;
 use resource::ResourceMonitor;
+use synthetic::SyntheticMonitor;
 
 // Re-export event types from storage module
 pub use skelly_jelly_storage::{
-    RawEvent, KeystrokeEvent, MouseMoveEvent, MouseClickEvent, 
+    RawEvent, KeystrokeEvent, MouseMoveEvent, MouseTrajectoryEvent, MouseClickEvent, 
     WindowFocusEvent, ScreenshotEvent, ProcessEvent, ResourceEvent
 };
 
@@ -69,6 +72,7 @@ pub enum Monitor {
     Screenshot(crate::platform::macos::MacOSScreenshotMonitor),
     Process(crate::platform::macos::MacOSProcessMonitor),
     Resource(crate::platform::macos::MacOSResourceMonitor),
+    Synthetic(SyntheticMonitor),
 }
 
 /// Fallback generic monitor enum for platforms without specific implementations
@@ -80,6 +84,7 @@ pub enum Monitor {
     Screenshot(ScreenshotMonitor),
     Process(ProcessMonitor),
     Resource(ResourceMonitor),
+    Synthetic(SyntheticMonitor),
 }
 
 #[cfg(target_os = "macos")]
@@ -93,6 +98,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.start().await,
             Monitor::Process(m) => m.start().await,
             Monitor::Resource(m) => m.start().await,
+            Monitor::Synthetic(m) => m.start().await,
         }
     }
     
@@ -104,6 +110,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.stop().await,
             Monitor::Process(m) => m.stop().await,
             Monitor::Resource(m) => m.stop().await,
+            Monitor::Synthetic(m) => m.stop().await,
         }
     }
     
@@ -115,6 +122,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.is_running(),
             Monitor::Process(m) => m.is_running(),
             Monitor::Resource(m) => m.is_running(),
+            Monitor::Synthetic(m) => m.is_running(),
         }
     }
     
@@ -126,6 +134,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.name(),
             Monitor::Process(m) => m.name(),
             Monitor::Resource(m) => m.name(),
+            Monitor::Synthetic(m) => m.name(),
         }
     }
     
@@ -137,6 +146,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.stats(),
             Monitor::Process(m) => m.stats(),
             Monitor::Resource(m) => m.stats(),
+            Monitor::Synthetic(m) => m.stats(),
         }
     }
     
@@ -148,6 +158,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.update_config(config).await,
             Monitor::Process(m) => m.update_config(config).await,
             Monitor::Resource(m) => m.update_config(config).await,
+            Monitor::Synthetic(m) => m.update_config(config).await,
         }
     }
 }
@@ -164,6 +175,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.start().await,
             Monitor::Process(m) => m.start().await,
             Monitor::Resource(m) => m.start().await,
+            Monitor::Synthetic(m) => m.start().await,
         }
     }
     
@@ -175,6 +187,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.stop().await,
             Monitor::Process(m) => m.stop().await,
             Monitor::Resource(m) => m.stop().await,
+            Monitor::Synthetic(m) => m.stop().await,
         }
     }
     
@@ -186,6 +199,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.is_running(),
             Monitor::Process(m) => m.is_running(),
             Monitor::Resource(m) => m.is_running(),
+            Monitor::Synthetic(m) => m.is_running(),
         }
     }
     
@@ -197,6 +211,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.name(),
             Monitor::Process(m) => m.name(),
             Monitor::Resource(m) => m.name(),
+            Monitor::Synthetic(m) => m.name(),
         }
     }
     
@@ -208,6 +223,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.stats(),
             Monitor::Process(m) => m.stats(),
             Monitor::Resource(m) => m.stats(),
+            Monitor::Synthetic(m) => m.stats(),
         }
     }
     
@@ -219,6 +235,7 @@ impl EventMonitor for Monitor {
             Monitor::Screenshot(m) => m.update_config(config).await,
             Monitor::Process(m) => m.update_config(config).await,
             Monitor::Resource(m) => m.update_config(config).await,
+            Monitor::Synthetic(m) => m.update_config(config).await,
         }
     }
 }
@@ -247,12 +264,23 @@ impl MonitorManager {
         
         let mut monitors: Vec<Monitor> = Vec::new();
         
+        // Dev mode: generated input replaces the keystroke, mouse and window monitors
+        let synthetic = config.monitors.synthetic.enabled;
+        if synthetic {
+            info!("Synthetic capture enabled: {:?}", config.monitors.synthetic.scenario);
+            monitors.push(Monitor::Synthetic(
+                SyntheticMonitor::new(config.monitors.synthetic.clone(), event_sender.clone())
+                    .with_title_hasher(config.monitors.window.title_hasher())
+                    .with_trajectory_aggregation(&config.monitors.mouse),
+            ));
+        }
+        
         // Initialize platform-specific monitors
         #[cfg(target_os = "macos")]
         {
             use crate::platform::macos::*;
             
-            if config.monitors.keystroke.enabled {
+            if config.monitors.keystroke.enabled && !synthetic {
                 let monitor = MacOSKeystrokeMonitor::new(
                     config.monitors.keystroke.clone(),
                     event_sender.clone()
@@ -260,7 +288,7 @@ impl MonitorManager {
                 monitors.push(Monitor::Keystroke(monitor));
             }
             
-            if config.monitors.mouse.enabled {
+            if config.monitors.mouse.enabled && !synthetic {
                 let monitor = MacOSMouseMonitor::new(
                     config.monitors.mouse.clone(),
                     event_sender.clone()
@@ -268,7 +296,7 @@ impl MonitorManager {
                 monitors.push(Monitor::Mouse(monitor));
             }
             
-            if config.monitors.window.enabled {
+            if config.monitors.window.enabled && !synthetic {
                 let monitor = MacOSWindowMonitor::new(
                     config.monitors.window.clone(),
                     event_sender.clone()
@@ -281,7 +309,8 @@ impl MonitorManager {
                     config.monitors.screenshot.clone(),
                     config.privacy.clone(),
                     event_sender.clone()
-                ).await?;
+                ).await?
+                .with_title_hasher(config.monitors.window.title_hasher());
                 monitors.push(Monitor::Screenshot(monitor));
             }
             
@@ -305,7 +334,7 @@ impl MonitorManager {
         #[cfg(not(target_os = "macos"))]
         {
             // Use generic monitor implementations for non-macOS platforms
-            if config.monitors.keystroke.enabled {
+            if config.monitors.keystroke.enabled && !synthetic {
                 let monitor = KeystrokeMonitor::new(
                     config.monitors.keystroke.clone(),
                     event_sender.clone()
@@ -313,7 +342,7 @@ impl MonitorManager {
                 monitors.push(Monitor::Keystroke(monitor));
             }
             
-            if config.monitors.mouse.enabled {
+            if config.monitors.mouse.enabled && !synthetic {
                 let monitor = MouseMonitor::new(
                     config.monitors.mouse.clone(),
                     event_sender.clone()
@@ -321,7 +350,7 @@ impl MonitorManager {
                 monitors.push(Monitor::Mouse(monitor));
             }
             
-            if config.monitors.window.enabled {
+            if config.monitors.window.enabled && !synthetic {
                 let monitor = WindowMonitor::new(
                     config.monitors.window.clone(),
                     event_sender.clone()
@@ -0,0 +1,404 @@
+//! Synthetic event generation for development, demos and tests
+//!
+//! [`SyntheticMonitor`] stands in for the platform monitors when
+//! `monitors.synthetic.enabled` is set, producing keystroke, mouse and
+//! window events that follow a behavioural [`SyntheticScenario`]. No capture
+//! permissions are needed. [`SyntheticGenerator`] produces the same stream
+//! on a virtual clock, so tests can take minutes of activity instantly.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::{
+    monitors::{trajectory::TrajectoryAggregator, EventMonitor, MonitorStats},
+    config::{DataCaptureConfig, MouseConfig, SyntheticConfig, SyntheticScenario},
+    error::{DataCaptureError, Result},
+    privacy::TitleHasher,
+};
+use skelly_jelly_storage::{
+    ClickType, KeyModifiers, KeystrokeEvent, MouseButton, MouseClickEvent, MouseMoveEvent, RawEvent,
+    WindowFocusEvent,
+};
+
+/// Shape of one scenario's activity
+struct Profile {
+    /// (app name, window title) pairs the user moves between
+    windows: &'static [(&'static str, &'static str)],
+    /// Gap between keystrokes while typing
+    key_interval_ms: (i64, i64),
+    /// Chance that a keystroke follows a thinking pause instead
+    pause_chance: f64,
+    pause_ms: (i64, i64),
+    /// Time spent in a window before switching
+    dwell_secs: (i64, i64),
+    /// Chance an event is a mouse move or click rather than a keystroke
+    mouse_chance: f64,
+    click_chance: f64,
+}
+
+impl SyntheticScenario {
+    fn profile(self) -> Profile {
+        match self {
+            SyntheticScenario::FlowSession => Profile {
+                windows: &[
+                    ("Code", "main.rs - skelly-jelly"),
+                    ("Terminal", "cargo test"),
+                    ("Code", "lib.rs - skelly-jelly"),
+                ],
+                key_interval_ms: (120, 260),
+                pause_chance: 0.02,
+                pause_ms: (1_500, 6_000),
+                dwell_secs: (300, 1_200),
+                mouse_chance: 0.05,
+                click_chance: 0.1,
+            },
+            SyntheticScenario::DistractedBrowsing => Profile {
+                windows: &[
+                    ("Safari", "YouTube"),
+                    ("Safari", "Reddit - front page"),
+                    ("Slack", "#random"),
+                    ("Safari", "Hacker News"),
+                    ("Code", "main.rs - skelly-jelly"),
+                    ("Messages", "Messages"),
+                ],
+                key_interval_ms: (90, 400),
+                pause_chance: 0.15,
+                pause_ms: (2_000, 15_000),
+                dwell_secs: (8, 60),
+                mouse_chance: 0.6,
+                click_chance: 0.2,
+            },
+            SyntheticScenario::Meeting => Profile {
+                windows: &[("zoom.us", "Zoom Meeting"), ("Notes", "Meeting notes")],
+                key_interval_ms: (150, 350),
+                pause_chance: 0.3,
+                pause_ms: (10_000, 60_000),
+                dwell_secs: (120, 600),
+                mouse_chance: 0.2,
+                click_chance: 0.05,
+            },
+        }
+    }
+}
+
+/// Deterministic source of scenario events on a virtual clock
+///
+/// Never runs out; take as many events as needed, e.g. with
+/// [`SyntheticGenerator::generate_for`].
+pub struct SyntheticGenerator {
+    profile: Profile,
+    rng: StdRng,
+    now: DateTime<Utc>,
+    window: usize,
+    window_since: DateTime<Utc>,
+    next_switch: DateTime<Utc>,
+    last_key: Option<DateTime<Utc>>,
+    mouse: (i32, i32),
+    started: bool,
+}
+
+impl SyntheticGenerator {
+    /// Generator starting at `start`; the same seed gives the same stream
+    pub fn new(scenario: SyntheticScenario, seed: u64, start: DateTime<Utc>) -> Self {
+        let profile = scenario.profile();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dwell = rng.gen_range(profile.dwell_secs.0..=profile.dwell_secs.1);
+        Self {
+            profile,
+            rng,
+            now: start,
+            window: 0,
+            window_since: start,
+            next_switch: start + ChronoDuration::seconds(dwell),
+            last_key: None,
+            mouse: (720, 450),
+            started: false,
+        }
+    }
+
+    /// Timestamp of the most recent event
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    /// Every event in the first `duration` of virtual time
+    pub fn generate_for(&mut self, duration: Duration) -> Vec<RawEvent> {
+        let end = self.now + ChronoDuration::from_std(duration).unwrap_or(ChronoDuration::MAX);
+        let mut events = Vec::new();
+        loop {
+            let event = self.next_event();
+            if self.now > end {
+                return events;
+            }
+            events.push(event);
+        }
+    }
+
+    fn next_event(&mut self) -> RawEvent {
+        // Announce the first window so consumers know where activity happens
+        if !self.started {
+            self.started = true;
+            return self.focus_event(None);
+        }
+        if self.now >= self.next_switch {
+            return self.switch_window();
+        }
+
+        if self.rng.gen_bool(self.profile.mouse_chance) {
+            return if self.rng.gen_bool(self.profile.click_chance) {
+                self.advance_ms(150, 900);
+                RawEvent::MouseClick(MouseClickEvent {
+                    timestamp: self.now,
+                    x: self.mouse.0,
+                    y: self.mouse.1,
+                    button: if self.rng.gen_bool(0.9) { MouseButton::Left } else { MouseButton::Right },
+                    click_type: if self.rng.gen_bool(0.9) { ClickType::Single } else { ClickType::Double },
+                })
+            } else {
+                self.advance_ms(16, 120);
+                let (dx, dy) = (self.rng.gen_range(-60..=60), self.rng.gen_range(-40..=40));
+                self.mouse = ((self.mouse.0 + dx).clamp(0, 1440), (self.mouse.1 + dy).clamp(0, 900));
+                RawEvent::MouseMove(MouseMoveEvent {
+                    timestamp: self.now,
+                    x: self.mouse.0,
+                    y: self.mouse.1,
+                    velocity: self.rng.gen_range(50.0..1_500.0),
+                })
+            };
+        }
+
+        if self.rng.gen_bool(self.profile.pause_chance) {
+            self.advance_ms(self.profile.pause_ms.0, self.profile.pause_ms.1);
+        } else {
+            self.advance_ms(self.profile.key_interval_ms.0, self.profile.key_interval_ms.1);
+        }
+        let interval = self
+            .last_key
+            .map(|last| (self.now - last).num_milliseconds().clamp(0, u32::MAX as i64) as u32);
+        self.last_key = Some(self.now);
+        RawEvent::Keystroke(KeystrokeEvent {
+            timestamp: self.now,
+            // Letters mostly, with the odd space and backspace
+            key_code: match self.rng.gen_range(0..20) {
+                0 => 49,
+                1 => 51,
+                _ => self.rng.gen_range(0..46),
+            },
+            modifiers: KeyModifiers { shift: self.rng.gen_bool(0.05), ..Default::default() },
+            inter_key_interval_ms: interval,
+        })
+    }
+
+    fn switch_window(&mut self) -> RawEvent {
+        let count = self.profile.windows.len();
+        if count > 1 {
+            self.window = (self.window + self.rng.gen_range(1..count)) % count;
+        }
+        self.advance_ms(50, 400);
+        let spent = (self.now - self.window_since).num_milliseconds().clamp(0, u32::MAX as i64) as u32;
+        self.window_since = self.now;
+        let dwell = self.rng.gen_range(self.profile.dwell_secs.0..=self.profile.dwell_secs.1);
+        self.next_switch = self.now + ChronoDuration::seconds(dwell);
+        self.last_key = None;
+        self.focus_event(Some(spent))
+    }
+
+    fn focus_event(&self, duration_ms: Option<u32>) -> RawEvent {
+        let (app_name, window_title) = self.profile.windows[self.window];
+        RawEvent::WindowFocus(WindowFocusEvent {
+            timestamp: self.now,
+            window_title: window_title.to_string(),
+            app_name: app_name.to_string(),
+            process_id: 10_000 + self.window as u32,
+            duration_ms,
+        })
+    }
+
+    fn advance_ms(&mut self, min: i64, max: i64) {
+        self.now += ChronoDuration::milliseconds(self.rng.gen_range(min..=max));
+    }
+}
+
+impl Iterator for SyntheticGenerator {
+    type Item = RawEvent;
+
+    fn next(&mut self) -> Option<RawEvent> {
+        Some(self.next_event())
+    }
+}
+
+/// Dev-mode monitor that emits a synthetic scenario in real time
+pub struct SyntheticMonitor {
+    config: SyntheticConfig,
+    event_sender: mpsc::Sender<RawEvent>,
+    task: Option<JoinHandle<()>>,
+    events_captured: Arc<AtomicU64>,
+    events_dropped: Arc<AtomicU64>,
+    title_hasher: Option<TitleHasher>,
+    trajectory: Option<TrajectoryAggregator>,
+}
+
+impl SyntheticMonitor {
+    pub fn new(config: SyntheticConfig, event_sender: mpsc::Sender<RawEvent>) -> Self {
+        Self {
+            config,
+            event_sender,
+            task: None,
+            events_captured: Arc::new(AtomicU64::new(0)),
+            events_dropped: Arc::new(AtomicU64::new(0)),
+            title_hasher: None,
+            trajectory: None,
+        }
+    }
+
+    /// Hash generated window titles, as the window monitor does
+    pub fn with_title_hasher(mut self, title_hasher: Option<TitleHasher>) -> Self {
+        self.title_hasher = title_hasher;
+        self
+    }
+
+    /// Summarize generated mouse moves, as the mouse monitor does
+    pub fn with_trajectory_aggregation(mut self, config: &MouseConfig) -> Self {
+        self.trajectory = TrajectoryAggregator::from_config(config);
+        self
+    }
+}
+
+#[async_trait]
+impl EventMonitor for SyntheticMonitor {
+    async fn start(&mut self) -> Result<()> {
+        if self.is_running() {
+            return Err(DataCaptureError::AlreadyRunning);
+        }
+        if !self.config.speed.is_finite() || self.config.speed <= 0.0 {
+            return Err(DataCaptureError::Config("synthetic speed must be positive".to_string()));
+        }
+
+        info!("Starting synthetic monitor: {:?} at {}x", self.config.scenario, self.config.speed);
+        let seed = self.config.seed.unwrap_or_else(rand::random);
+        let mut generator = SyntheticGenerator::new(self.config.scenario, seed, Utc::now());
+        let speed = self.config.speed;
+        let sender = self.event_sender.clone();
+        let captured = self.events_captured.clone();
+        let dropped = self.events_dropped.clone();
+        let title_hasher = self.title_hasher.clone();
+        let mut trajectory = self.trajectory.clone();
+
+        self.task = Some(tokio::spawn(async move {
+            let origin = generator.now();
+            let started = tokio::time::Instant::now();
+            'generate: for mut event in &mut generator {
+                // Replay the virtual timeline, compressed by `speed`
+                let elapsed = (event.timestamp() - origin).to_std().unwrap_or_default();
+                tokio::time::sleep_until(started + elapsed.div_f64(speed)).await;
+                if let (RawEvent::WindowFocus(focus), Some(hasher)) = (&mut event, &title_hasher) {
+                    focus.window_title = hasher.hash_title(&focus.window_title, &focus.app_name);
+                }
+
+                let mut outgoing = Vec::with_capacity(2);
+                match (&event, trajectory.as_mut()) {
+                    (RawEvent::MouseMove(mouse), Some(aggregator)) => {
+                        outgoing.extend(aggregator.push(mouse).map(RawEvent::MouseTrajectory));
+                    }
+                    (_, aggregator) => {
+                        outgoing.extend(
+                            aggregator.and_then(|a| a.poll(event.timestamp())).map(RawEvent::MouseTrajectory),
+                        );
+                        outgoing.push(event);
+                    }
+                }
+
+                for event in outgoing {
+                    match sender.try_send(event) {
+                        Ok(()) => {
+                            captured.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(mpsc::error::TrySendError::Full(_)) => {
+                            dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(mpsc::error::TrySendError::Closed(_)) => break 'generate,
+                    }
+                }
+            }
+            debug!("Synthetic event stream ended");
+        }));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        if let Some(task) = self.task.take() {
+            info!("Stopping synthetic monitor");
+            task.abort();
+        }
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    fn name(&self) -> &'static str {
+        "synthetic"
+    }
+
+    fn stats(&self) -> MonitorStats {
+        MonitorStats {
+            events_captured: self.events_captured.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+
+    async fn update_config(&mut self, config: &DataCaptureConfig) -> Result<()> {
+        self.config = config.monitors.synthetic.clone();
+        self.title_hasher = config.monitors.window.title_hasher();
+        self.trajectory = TrajectoryAggregator::from_config(&config.monitors.mouse);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(scenario: SyntheticScenario) -> (usize, usize, usize) {
+        let events = SyntheticGenerator::new(scenario, 7, Utc::now()).generate_for(Duration::from_secs(30 * 60));
+        let count = |f: fn(&RawEvent) -> bool| events.iter().filter(|e| f(e)).count();
+        (
+            count(|e| matches!(e, RawEvent::Keystroke(_))),
+            count(|e| matches!(e, RawEvent::MouseMove(_) | RawEvent::MouseClick(_))),
+            count(|e| matches!(e, RawEvent::WindowFocus(_))),
+        )
+    }
+
+    #[test]
+    fn test_scenarios_differ() {
+        let (flow_keys, flow_mouse, flow_switches) = summary(SyntheticScenario::FlowSession);
+        let (browse_keys, browse_mouse, browse_switches) = summary(SyntheticScenario::DistractedBrowsing);
+        let (meeting_keys, _, meeting_switches) = summary(SyntheticScenario::Meeting);
+
+        assert!(flow_keys > browse_keys && flow_keys > meeting_keys);
+        assert!(browse_mouse > flow_mouse);
+        assert!(browse_switches > 10 * flow_switches.max(1));
+        assert!(meeting_switches < browse_switches);
+    }
+
+    #[test]
+    fn test_generator_is_deterministic_and_ordered() {
+        let start = Utc::now();
+        let a = SyntheticGenerator::new(SyntheticScenario::Meeting, 42, start).generate_for(Duration::from_secs(600));
+        let b = SyntheticGenerator::new(SyntheticScenario::Meeting, 42, start).generate_for(Duration::from_secs(600));
+
+        assert!(matches!(a.first(), Some(RawEvent::WindowFocus(_))));
+        assert_eq!(serde_json::to_string(&a).unwrap(), serde_json::to_string(&b).unwrap());
+        assert!(a.windows(2).all(|pair| pair[0].timestamp() <= pair[1].timestamp()));
+        assert!(a.last().unwrap().timestamp() <= start + ChronoDuration::seconds(600));
+    }
+}
@@ -0,0 +1,200 @@
+//! Mouse trajectory pre-aggregation
+//!
+//! Mouse moves arrive at the pointer's report rate, often hundreds per
+//! second, and dominate event volume. With `monitors.mouse.aggregate_trajectories`
+//! set, monitors feed their moves through a [`TrajectoryAggregator`] and send
+//! one [`MouseTrajectoryEvent`] per window (100 ms by default) carrying the
+//! distance, velocity statistics, direction changes and idle time that the
+//! analysis features are built from.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::f32::consts::FRAC_PI_4;
+
+use crate::config::MouseConfig;
+use skelly_jelly_storage::{MouseMoveEvent, MouseTrajectoryEvent};
+
+/// Running totals for the open window
+#[derive(Debug, Clone)]
+struct Accumulator {
+    start: DateTime<Utc>,
+    last: DateTime<Utc>,
+    start_position: (i32, i32),
+    samples: u32,
+    distance: f32,
+    velocity_sum: f32,
+    velocity_sq_sum: f32,
+    velocity_max: f32,
+    direction_changes: u32,
+    idle_ms: i64,
+}
+
+/// Folds raw mouse moves into fixed-length trajectory summaries
+#[derive(Debug, Clone)]
+pub struct TrajectoryAggregator {
+    window: ChronoDuration,
+    idle_gap: ChronoDuration,
+    current: Option<Accumulator>,
+    /// Last point seen, kept across windows for distance and turn detection
+    position: Option<(i32, i32)>,
+    heading: Option<(f32, f32)>,
+}
+
+impl TrajectoryAggregator {
+    pub fn new(window_ms: u64, idle_gap_ms: u64) -> Self {
+        Self {
+            window: ChronoDuration::milliseconds(window_ms.max(1) as i64),
+            idle_gap: ChronoDuration::milliseconds(idle_gap_ms as i64),
+            current: None,
+            position: None,
+            heading: None,
+        }
+    }
+
+    /// Aggregator for `config`, if trajectory aggregation is on
+    pub fn from_config(config: &MouseConfig) -> Option<Self> {
+        config
+            .aggregate_trajectories
+            .then(|| Self::new(config.trajectory_window_ms, config.trajectory_idle_gap_ms))
+    }
+
+    /// Add a move; returns the previous window's summary once a move lands past its end
+    pub fn push(&mut self, event: &MouseMoveEvent) -> Option<MouseTrajectoryEvent> {
+        let finished = self.poll(event.timestamp);
+        let position = (event.x, event.y);
+
+        let step = self.position.map(|(x, y)| ((event.x - x) as f32, (event.y - y) as f32));
+        let mut turned = false;
+        if let Some(step @ (dx, dy)) = step.filter(|&(dx, dy)| dx != 0.0 || dy != 0.0) {
+            if let Some((hx, hy)) = self.heading {
+                let cos = (dx * hx + dy * hy) / ((dx * dx + dy * dy).sqrt() * (hx * hx + hy * hy).sqrt());
+                turned = cos.clamp(-1.0, 1.0).acos() > FRAC_PI_4;
+            }
+            self.heading = Some(step);
+        }
+
+        let current = self.current.get_or_insert(Accumulator {
+            start: event.timestamp,
+            last: event.timestamp,
+            start_position: position,
+            samples: 0,
+            distance: 0.0,
+            velocity_sum: 0.0,
+            velocity_sq_sum: 0.0,
+            velocity_max: 0.0,
+            direction_changes: 0,
+            idle_ms: 0,
+        });
+        let gap = event.timestamp - current.last;
+        if gap > self.idle_gap {
+            current.idle_ms += gap.num_milliseconds();
+        }
+        current.last = event.timestamp;
+        current.samples += 1;
+        current.distance += step.map_or(0.0, |(dx, dy)| (dx * dx + dy * dy).sqrt());
+        current.velocity_sum += event.velocity;
+        current.velocity_sq_sum += event.velocity * event.velocity;
+        current.velocity_max = current.velocity_max.max(event.velocity);
+        current.direction_changes += turned as u32;
+        self.position = Some(position);
+
+        finished
+    }
+
+    /// Close the open window if `now` is past its end
+    pub fn poll(&mut self, now: DateTime<Utc>) -> Option<MouseTrajectoryEvent> {
+        match &self.current {
+            Some(current) if now >= current.start + self.window => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// Close the open window regardless of time, e.g. on shutdown
+    pub fn flush(&mut self) -> Option<MouseTrajectoryEvent> {
+        let current = self.current.take()?;
+        let (end_x, end_y) = self.position.unwrap_or(current.start_position);
+        let samples = current.samples.max(1) as f32;
+        let mean_velocity = current.velocity_sum / samples;
+        let variance = (current.velocity_sq_sum / samples - mean_velocity * mean_velocity).max(0.0);
+        Some(MouseTrajectoryEvent {
+            timestamp: current.start,
+            duration_ms: (current.last - current.start).num_milliseconds().clamp(0, u32::MAX as i64) as u32,
+            sample_count: current.samples,
+            start_x: current.start_position.0,
+            start_y: current.start_position.1,
+            end_x,
+            end_y,
+            distance_px: current.distance,
+            mean_velocity,
+            max_velocity: current.velocity_max,
+            velocity_std: variance.sqrt(),
+            direction_changes: current.direction_changes,
+            idle_ms: current.idle_ms.clamp(0, u32::MAX as i64) as u32,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(points: &[(i64, i32, i32, f32)]) -> Vec<MouseMoveEvent> {
+        let start = Utc::now();
+        points
+            .iter()
+            .map(|&(ms, x, y, velocity)| MouseMoveEvent {
+                timestamp: start + ChronoDuration::milliseconds(ms),
+                x,
+                y,
+                velocity,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_window_summary() {
+        let mut aggregator = TrajectoryAggregator::new(100, 30);
+        let events = moves(&[
+            (0, 0, 0, 100.0),
+            (10, 30, 40, 300.0),  // 50 px right-down
+            (20, 60, 80, 500.0),  // straight on
+            (70, 60, 40, 300.0),  // reverses after a 50 ms pause
+            (120, 60, 0, 100.0),  // next window
+        ]);
+
+        let summaries: Vec<_> = events.iter().filter_map(|e| aggregator.push(e)).collect();
+        assert_eq!(summaries.len(), 1);
+        let first = &summaries[0];
+        assert_eq!(first.sample_count, 4);
+        assert_eq!(first.duration_ms, 70);
+        assert_eq!((first.start_x, first.start_y, first.end_x, first.end_y), (0, 0, 60, 40));
+        assert!((first.distance_px - 140.0).abs() < 1e-3);
+        assert!((first.mean_velocity - 300.0).abs() < 1e-3);
+        assert_eq!(first.max_velocity, 500.0);
+        assert!((first.velocity_std - 141.42).abs() < 0.01);
+        assert_eq!(first.direction_changes, 1);
+        assert_eq!(first.idle_ms, 50);
+
+        // The next window keeps counting distance from where the last one ended
+        let second = aggregator.flush().unwrap();
+        assert_eq!(second.sample_count, 1);
+        assert!((second.distance_px - 40.0).abs() < 1e-3);
+        assert!(aggregator.flush().is_none());
+    }
+
+    #[test]
+    fn test_volume_reduction_at_report_rate() {
+        let mut aggregator = TrajectoryAggregator::new(100, 30);
+        // Ten seconds of movement reported at 125 Hz
+        let events = moves(
+            &(0..1250)
+                .map(|i| (i * 8, (i * 3 % 400) as i32, (i * 2 % 300) as i32, 400.0))
+                .collect::<Vec<_>>(),
+        );
+        let mut summaries: Vec<_> = events.iter().filter_map(|e| aggregator.push(e)).collect();
+        summaries.extend(aggregator.flush());
+
+        assert!(summaries.len() <= 101, "{}", summaries.len());
+        assert!(events.len() >= summaries.len() * 10);
+        assert_eq!(summaries.iter().map(|s| s.sample_count as usize).sum::<usize>(), events.len());
+    }
+}
@@ -35,6 +35,7 @@ use crate::{
         RawEvent, KeystrokeEvent, MouseMoveEvent, MouseClickEvent, 
         WindowFocusEvent, ScreenshotEvent, ProcessEvent, ResourceEvent,
     },
+    privacy::TitleHasher,
 };
 
 // Import types from storage module  
@@ -280,6 +281,7 @@ pub struct MacOSWindowMonitor {
     stats: Arc<RwLock<MonitorStats>>,
     is_running: Arc<RwLock<bool>>,
     current_window: Arc<RwLock<Option<(String, String, u32)>>>, // title, app, pid
+    title_hasher: Option<TitleHasher>,
 }
 
 impl MacOSWindowMonitor {
@@ -287,6 +289,7 @@ impl MacOSWindowMonitor {
         let stats = Arc::new(RwLock::new(MonitorStats::default()));
         let is_running = Arc::new(RwLock::new(false));
         let current_window = Arc::new(RwLock::new(None));
+        let title_hasher = config.title_hasher();
 
         Ok(Self {
             config,
@@ -294,6 +297,7 @@ impl MacOSWindowMonitor {
             stats,
             is_running,
             current_window,
+            title_hasher,
         })
     }
 
@@ -308,9 +312,13 @@ impl MacOSWindowMonitor {
                 
                 if let Some((title, app, pid)) = window_info {
                     if current.as_ref().map(|(_, _, p)| *p) != Some(pid) {
+                        let window_title = match &self.title_hasher {
+                            Some(hasher) => hasher.hash_title(&title, &app),
+                            None => title.clone(),
+                        };
                         let event = WindowFocusEvent {
                             timestamp: Utc::now(),
-                            window_title: title.clone(),
+                            window_title,
                             app_name: app.clone(),
                             process_id: pid,
                             duration_ms: None, // Could calculate from previous window
@@ -407,6 +415,7 @@ impl EventMonitor for MacOSWindowMonitor {
         }
 
         self.config = config.monitors.window.clone();
+        self.title_hasher = self.config.title_hasher();
 
         if was_running {
             self.start().await?;
@@ -425,6 +434,7 @@ impl Clone for MacOSWindowMonitor {
             stats: self.stats.clone(),
             is_running: self.is_running.clone(),
             current_window: self.current_window.clone(),
+            title_hasher: self.title_hasher.clone(),
         }
     }
 }
@@ -437,6 +447,7 @@ pub struct MacOSScreenshotMonitor {
     stats: Arc<RwLock<MonitorStats>>,
     is_running: Arc<RwLock<bool>>,
     last_screenshot: Arc<RwLock<Option<Instant>>>,
+    title_hasher: Option<TitleHasher>,
 }
 
 impl MacOSScreenshotMonitor {
@@ -456,9 +467,16 @@ impl MacOSScreenshotMonitor {
             stats,
             is_running,
             last_screenshot,
+            title_hasher: None,
         })
     }
 
+    /// Hash window titles on captured screenshots, as the window monitor does
+    pub fn with_title_hasher(mut self, title_hasher: Option<TitleHasher>) -> Self {
+        self.title_hasher = title_hasher;
+        self
+    }
+
     async fn capture_screenshot(&self) -> Result<ScreenshotEvent> {
         // Use CGWindowListCopyWindowInfo for screenshot capture
         // This is a complex implementation that would need proper CGImage handling
@@ -504,6 +522,9 @@ impl MacOSScreenshotMonitor {
             screenshot.privacy_masked = true;
             screenshot.data.clear(); // Remove screenshot data for sensitive apps
         }
+        if let Some(hasher) = &self.title_hasher {
+            screenshot.window_title = hasher.hash_title(&screenshot.window_title, &screenshot.app_name);
+        }
     }
 }
 
@@ -525,6 +546,7 @@ impl EventMonitor for MacOSScreenshotMonitor {
         let privacy_config = self.privacy_config.clone();
         let stats = self.stats.clone();
         let last_screenshot = self.last_screenshot.clone();
+        let title_hasher = self.title_hasher.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(config.capture_interval_ms));
@@ -540,6 +562,7 @@ impl EventMonitor for MacOSScreenshotMonitor {
                     stats: stats.clone(),
                     is_running: is_running_clone.clone(),
                     last_screenshot: last_screenshot.clone(),
+                    title_hasher: title_hasher.clone(),
                 };
 
                 if monitor.should_capture_screenshot().await {
@@ -611,6 +634,7 @@ impl EventMonitor for MacOSScreenshotMonitor {
         }
 
         self.config = config.monitors.screenshot.clone();
+        self.title_hasher = config.monitors.window.title_hasher();
         self.privacy_config = config.privacy.clone();
 
         if was_running {
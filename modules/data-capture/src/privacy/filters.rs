@@ -6,19 +6,16 @@ use std::collections::HashSet;
 use tracing::{debug, warn};
 
 use crate::{config::PrivacyConfig, error::Result};
+use skelly_jelly_privacy_policy::{PrivacyPolicy, PrivacyPolicyConfig};
 
-/// Content classification for privacy filtering
-#[derive(Debug, Clone, PartialEq)]
-pub enum ContentSensitivity {
-    Public,      // Safe to capture and store
-    Internal,    // Internal content, basic privacy needed
-    Confidential,// Confidential content, enhanced privacy
-    Restricted,  // Restricted content, maximum privacy or block
-}
+/// Content classification for privacy filtering, shared with every module
+pub use skelly_jelly_privacy_policy::SensitivityLevel as ContentSensitivity;
 
 /// Content filter for text and metadata
 pub struct ContentFilter {
     config: PrivacyConfig,
+    /// Shared privacy policy: blocked and sensitive apps, titles and entities
+    policy: PrivacyPolicy,
     sensitive_patterns: Vec<Regex>,
     safe_domains: HashSet<String>,
     blocked_domains: HashSet<String>,
@@ -28,6 +25,7 @@ impl ContentFilter {
     pub fn new(config: PrivacyConfig) -> Self {
         let mut filter = Self {
             config,
+            policy: PrivacyPolicy::default(),
             sensitive_patterns: Vec::new(),
             safe_domains: HashSet::new(),
             blocked_domains: HashSet::new(),
@@ -45,11 +43,17 @@ impl ContentFilter {
         self.initialize_domain_lists();
     }
     
+    /// Apply the privacy policy broadcast by the orchestrator
+    pub fn set_policy(&mut self, policy: PrivacyPolicyConfig) {
+        self.policy.set_config(policy);
+    }
+    
     /// Classify content sensitivity level
     pub fn classify_content(&self, text: &str, app_name: &str, window_title: &str) -> ContentSensitivity {
-        // Check for explicitly blocked/sensitive apps
-        if self.is_blocked_app(app_name) {
-            return ContentSensitivity::Restricted;
+        // Blocked and sensitive apps, sensitive titles and detected entities
+        let sensitivity = self.policy.classify(text, app_name, window_title);
+        if sensitivity >= ContentSensitivity::Confidential {
+            return sensitivity;
         }
         
         if self.is_sensitive_app(app_name) {
@@ -61,11 +65,6 @@ impl ContentFilter {
             return ContentSensitivity::Confidential;
         }
         
-        // Check window title for sensitive content
-        if self.is_sensitive_window_title(window_title) {
-            return ContentSensitivity::Confidential;
-        }
-        
         // Check for financial or medical terms
         if self.contains_financial_terms(text) || self.contains_medical_terms(text) {
             return ContentSensitivity::Confidential;
@@ -76,7 +75,7 @@ impl ContentFilter {
             return ContentSensitivity::Internal;
         }
         
-        ContentSensitivity::Public
+        sensitivity
     }
     
     /// Filter window title for privacy
@@ -176,12 +175,6 @@ impl ContentFilter {
         ]);
     }
     
-    fn is_blocked_app(&self, app_name: &str) -> bool {
-        // Apps that should never be monitored
-        let blocked_apps = ["Keychain Access", "Activity Monitor", "Console"];
-        blocked_apps.iter().any(|&blocked| app_name.contains(blocked))
-    }
-    
     fn is_sensitive_app(&self, app_name: &str) -> bool {
         self.config.sensitive_app_list.iter()
             .any(|sensitive| app_name.contains(sensitive))
@@ -192,19 +185,6 @@ impl ContentFilter {
             .any(|pattern| pattern.is_match(text))
     }
     
-    fn is_sensitive_window_title(&self, title: &str) -> bool {
-        let sensitive_indicators = [
-            "password", "login", "sign in", "authentication",
-            "private", "confidential", "restricted",
-            "banking", "finance", "medical", "health",
-            "incognito", "private browsing"
-        ];
-        
-        let title_lower = title.to_lowercase();
-        sensitive_indicators.iter()
-            .any(|&indicator| title_lower.contains(indicator))
-    }
-    
     fn contains_financial_terms(&self, text: &str) -> bool {
         let financial_terms = [
             "bank account", "credit card", "debit card",
@@ -242,16 +222,13 @@ impl ContentFilter {
     }
     
     fn redact_sensitive_parts(&self, text: &str) -> String {
-        let mut redacted = text.to_string();
+        // Mask emails, phone numbers and other entities the policy covers
+        let mut redacted = self.policy.mask(text);
         
         // Redact numbers that might be sensitive
         let number_regex = Regex::new(r"\b\d{4,}\b").unwrap();
         redacted = number_regex.replace_all(&redacted, "[REDACTED]").to_string();
         
-        // Redact email addresses
-        let email_regex = Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b").unwrap();
-        redacted = email_regex.replace_all(&redacted, "[EMAIL]").to_string();
-        
         redacted
     }
     
@@ -1,40 +1,27 @@
 //! Privacy protection utilities for data capture
 
 use regex::Regex;
-use once_cell::sync::Lazy;
 use image::{ImageBuffer, Rgb};
 use imageproc::drawing::draw_filled_rect_mut;
 use imageproc::rect::Rect;
 use tracing::{warn, debug};
 
 use crate::{config::{PrivacyConfig, PrivacyZone, PrivacyMode}, error::{DataCaptureError, Result}};
+use skelly_jelly_privacy_policy::{EntityKind, MaskingStrategy, PrivacyPolicy, PrivacyPolicyConfig};
 
 pub mod masking;
 pub mod filters;
 pub mod ml_pii_detector;
+pub mod titles;
 
+pub use titles::{TitleCategory, TitleHasher};
 pub use ml_pii_detector::{AdvancedPIIDetector, PIIDetection, PIIType, DetectionContext, UserActivity, PIIAccuracyStats};
 
-/// PII detection patterns
-static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b").unwrap()
-});
-
-static SSN_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"\b\d{3}-?\d{2}-?\d{4}\b").unwrap()
-});
-
-static CREDIT_CARD_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"\b(?:\d{4}[-\s]?){3}\d{4}\b").unwrap()
-});
-
-static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"\b(?:\+?1[-.\s]?)?\(?[0-9]{3}\)?[-.\s]?[0-9]{3}[-.\s]?[0-9]{4}\b").unwrap()
-});
-
 /// Main privacy filter for processing captured data
 pub struct PrivacyFilter {
     config: PrivacyConfig,
+    /// Shared privacy policy: which entities are masked and how
+    policy: PrivacyPolicy,
     /// Advanced ML-enhanced PII detector
     pii_detector: AdvancedPIIDetector,
 }
@@ -43,6 +30,7 @@ impl PrivacyFilter {
     pub fn new(config: PrivacyConfig) -> Self {
         Self { 
             config,
+            policy: PrivacyPolicy::default(),
             pii_detector: AdvancedPIIDetector::new(),
         }
     }
@@ -52,6 +40,11 @@ impl PrivacyFilter {
         self.config = config;
     }
     
+    /// Apply the privacy policy broadcast by the orchestrator
+    pub fn set_policy(&mut self, policy: PrivacyPolicyConfig) {
+        self.policy.set_config(policy);
+    }
+    
     /// Check if an application should be monitored
     pub fn should_monitor_app(&self, app_name: &str) -> bool {
         // Check if app is in ignored list - these apps should NOT be monitored
@@ -60,6 +53,11 @@ impl PrivacyFilter {
             return false;
         }
         
+        if self.policy.is_blocked_app(app_name) {
+            debug!("App {} is blocked by the privacy policy", app_name);
+            return false;
+        }
+        
         // Check if app is sensitive - these should still be monitored but with enhanced privacy
         if self.config.sensitive_app_list.iter().any(|sensitive| app_name.contains(sensitive)) {
             warn!("App {} is sensitive, monitoring with enhanced privacy", app_name);
@@ -71,12 +69,9 @@ impl PrivacyFilter {
     
     /// Check if a window title contains sensitive information
     pub fn is_sensitive_window(&self, window_title: &str, app_name: &str) -> bool {
-        // Always consider password-related windows sensitive
-        let password_indicators = ["password", "login", "sign in", "authentication", "2fa", "mfa"];
-        for indicator in &password_indicators {
-            if window_title.to_lowercase().contains(indicator) {
-                return true;
-            }
+        // Password, banking and similar windows, as listed by the privacy policy
+        if self.policy.is_sensitive_title(window_title) || self.policy.is_sensitive_app(app_name) {
+            return true;
         }
         
         // Check if app is in sensitive list
@@ -89,6 +84,9 @@ impl PrivacyFilter {
             return text.to_string();
         }
         
+        // Entities the shared policy knows are masked the way it says
+        let text = &self.policy.mask(text);
+        
         // Create detection context
         let context = DetectionContext {
             app_name: app_name.to_string(),
@@ -118,16 +116,23 @@ impl PrivacyFilter {
     }
     
     /// Check if a PII type should be masked based on config
+    ///
+    /// Types the privacy policy covers follow the policy, so the ML pass
+    /// only masks what the policy's own detectors missed.
     fn should_mask_pii_type(&self, pii_type: PIIType) -> bool {
-        match pii_type {
-            PIIType::Email => self.config.mask_emails,
-            PIIType::SSN => self.config.mask_ssn,
-            PIIType::CreditCard => self.config.mask_credit_cards,
-            PIIType::Phone => true, // Always mask phone numbers
-            PIIType::Password => self.config.mask_passwords,
-            PIIType::ApiKey | PIIType::BankAccount => true, // Always mask sensitive data
-            _ => true, // Default to masking for safety
-        }
+        let kind = match pii_type {
+            PIIType::Email => EntityKind::Email,
+            PIIType::SSN => EntityKind::Ssn,
+            PIIType::CreditCard => EntityKind::CreditCard,
+            PIIType::Phone => EntityKind::Phone,
+            PIIType::IpAddress => EntityKind::IpAddress,
+            PIIType::ApiKey => EntityKind::Credential,
+            PIIType::PersonalName => EntityKind::Person,
+            PIIType::Address => EntityKind::Location,
+            PIIType::Password => return self.config.mask_passwords,
+            _ => return true, // Default to masking for safety
+        };
+        self.policy.strategy(kind) != MaskingStrategy::Keep
     }
     
     /// Get PII detection accuracy statistics
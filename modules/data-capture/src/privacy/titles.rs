@@ -0,0 +1,121 @@
+//! Window title hashing
+//!
+//! With `monitors.window.hash_titles` set, monitors replace each window title
+//! with a coarse category and a salted hash before the event leaves them,
+//! e.g. `video#3fa1b2c4`. Equal titles hash equally, so switching patterns
+//! survive, but no readable title is ever stored. App names are untouched.
+
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+
+/// Used when no salt is configured, shared by every monitor in the process
+static RUN_SALT: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().to_string());
+
+/// Coarse kind of content a window shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleCategory {
+    Code,
+    Document,
+    Email,
+    Chat,
+    Meeting,
+    Video,
+    Social,
+    Browsing,
+    Other,
+}
+
+impl TitleCategory {
+    /// Keywords matched against the lowercased title and app name, first hit wins
+    const RULES: &'static [(TitleCategory, &'static [&'static str])] = &[
+        (TitleCategory::Meeting, &["zoom", "meet.google", "google meet", "teams meeting", "webex", "huddle"]),
+        (
+            TitleCategory::Code,
+            &[".rs", ".py", ".ts", ".js", "github", "gitlab", "terminal", "iterm", "cargo", "xcode", "visual studio", "intellij"],
+        ),
+        (TitleCategory::Email, &["inbox", "gmail", "outlook", "mail"]),
+        (TitleCategory::Chat, &["slack", "discord", "messages", "whatsapp", "telegram", "signal"]),
+        (TitleCategory::Video, &["youtube", "netflix", "twitch", "vimeo", "prime video"]),
+        (
+            TitleCategory::Social,
+            &["reddit", "twitter", "x.com", "facebook", "instagram", "tiktok", "linkedin", "hacker news"],
+        ),
+        (
+            TitleCategory::Document,
+            &[".pdf", ".doc", ".md", "docs", "notion", "confluence", "microsoft word", "pages", "sheets", "excel", "notes"],
+        ),
+        (TitleCategory::Browsing, &["safari", "chrome", "firefox", "microsoft edge", "brave", "http"]),
+    ];
+
+    /// Best guess from a window title and the app showing it
+    pub fn classify(title: &str, app_name: &str) -> Self {
+        let title = title.to_lowercase();
+        let app = app_name.to_lowercase();
+        Self::RULES
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|k| title.contains(k) || app.contains(k)))
+            .map_or(TitleCategory::Other, |(category, _)| *category)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TitleCategory::Code => "code",
+            TitleCategory::Document => "document",
+            TitleCategory::Email => "email",
+            TitleCategory::Chat => "chat",
+            TitleCategory::Meeting => "meeting",
+            TitleCategory::Video => "video",
+            TitleCategory::Social => "social",
+            TitleCategory::Browsing => "browsing",
+            TitleCategory::Other => "other",
+        }
+    }
+}
+
+/// Replaces window titles with `category#hash`
+#[derive(Debug, Clone)]
+pub struct TitleHasher {
+    salt: String,
+}
+
+impl TitleHasher {
+    /// An empty salt is replaced with a random one, so hashes only match
+    /// within this run
+    pub fn new(salt: &str) -> Self {
+        let salt = if salt.is_empty() { RUN_SALT.clone() } else { salt.to_string() };
+        Self { salt }
+    }
+
+    /// The anonymised form of `title`
+    pub fn hash_title(&self, title: &str, app_name: &str) -> String {
+        let digest = Sha256::new().chain_update(&self.salt).chain_update(title).finalize();
+        let short: String = digest.iter().take(4).map(|b| format!("{b:02x}")).collect();
+        format!("{}#{short}", TitleCategory::classify(title, app_name).label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(TitleCategory::classify("main.rs - skelly-jelly", "Code"), TitleCategory::Code);
+        assert_eq!(TitleCategory::classify("Funny cats - YouTube", "Safari"), TitleCategory::Video);
+        assert_eq!(TitleCategory::classify("Zoom Meeting", "zoom.us"), TitleCategory::Meeting);
+        assert_eq!(TitleCategory::classify("Inbox (3)", "Mail"), TitleCategory::Email);
+        assert_eq!(TitleCategory::classify("Quarterly plan", "Keynote"), TitleCategory::Other);
+    }
+
+    #[test]
+    fn test_hashing_hides_titles_but_keeps_identity() {
+        let hasher = TitleHasher::new("salt");
+        let hashed = hasher.hash_title("Reddit - r/rust", "Safari");
+
+        assert!(hashed.starts_with("social#") && hashed.len() == "social#".len() + 8);
+        assert!(!hashed.contains("rust"));
+        assert_eq!(hashed, hasher.hash_title("Reddit - r/rust", "Safari"));
+        assert_ne!(hashed, hasher.hash_title("Reddit - r/adhd", "Safari"));
+        assert_ne!(hashed, TitleHasher::new("pepper").hash_title("Reddit - r/rust", "Safari"));
+    }
+}
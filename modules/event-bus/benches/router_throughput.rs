@@ -0,0 +1,117 @@
+//! Router throughput as the number of shards grows
+//!
+//! Publishes 100k messages of five types to 50 subscribers (ten per type) and
+//! waits until every copy has been delivered. Run with `cargo bench --bench router_throughput`.
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use skelly_jelly_event_bus::{
+    message::{DeliveryAck, HealthCheckRequest, RetransmitRequest, ShutdownRequest},
+    router::{MessageRouter, RouterConfig},
+    subscription::Subscription,
+    BusMessage, DeliveryMode, MessageFilter, MessagePayload, MessageType, ModuleId,
+};
+
+const MESSAGES: usize = 100_000;
+const SUBSCRIBERS: usize = 50;
+
+const TYPES: [MessageType; 5] = [
+    MessageType::ModuleReady,
+    MessageType::DeliveryAck,
+    MessageType::HealthCheck,
+    MessageType::RetransmitRequest,
+    MessageType::Shutdown,
+];
+
+fn message(index: usize) -> BusMessage {
+    let module = ModuleId::ALL[index % ModuleId::ALL.len()];
+    let payload = match TYPES[index % TYPES.len()] {
+        MessageType::ModuleReady => MessagePayload::ModuleReady(module),
+        MessageType::DeliveryAck => MessagePayload::DeliveryAck(DeliveryAck {
+            producer: module,
+            consumer: ModuleId::Storage,
+            from_sequence: index as u64,
+            to_sequence: index as u64,
+        }),
+        MessageType::HealthCheck => MessagePayload::HealthCheck(HealthCheckRequest {
+            module_id: module,
+            timestamp: Utc::now(),
+        }),
+        MessageType::RetransmitRequest => MessagePayload::RetransmitRequest(RetransmitRequest {
+            producer: module,
+            consumer: ModuleId::Storage,
+            from_sequence: index as u64,
+            to_sequence: index as u64,
+            attempt: 1,
+        }),
+        _ => MessagePayload::Shutdown(ShutdownRequest {
+            module_id: module,
+            timeout: Duration::from_secs(1),
+            save_state: false,
+        }),
+    };
+    BusMessage::new(ModuleId::Orchestrator, payload)
+}
+
+/// Publish every message and wait until all copies reached their subscribers
+async fn run(shards: usize, messages: &[BusMessage]) -> Duration {
+    let router = MessageRouter::new(RouterConfig {
+        max_queue_size: MESSAGES * shards,
+        worker_threads: shards,
+        compression: None,
+        ..Default::default()
+    });
+    let receivers: Vec<_> = (0..SUBSCRIBERS)
+        .map(|i| {
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            let filter = MessageFilter::types(vec![TYPES[i % TYPES.len()]]);
+            let subscriber = ModuleId::ALL[i % ModuleId::ALL.len()];
+            router
+                .subscription_manager()
+                .add_subscription(Subscription::new(subscriber, filter, DeliveryMode::BestEffort, sender));
+            receiver
+        })
+        .collect();
+    router.start().await.unwrap();
+
+    let expected = messages.len() * SUBSCRIBERS / TYPES.len();
+    let started = Instant::now();
+    for message in messages {
+        router.publish(message.clone()).await.unwrap();
+    }
+    while receivers.iter().map(|r| r.len()).sum::<usize>() < expected {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    let elapsed = started.elapsed();
+
+    router.stop().await.unwrap();
+    elapsed
+}
+
+fn router_throughput(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(8)
+        .enable_all()
+        .build()
+        .unwrap();
+    let messages: Vec<BusMessage> = (0..MESSAGES).map(message).collect();
+
+    let mut group = c.benchmark_group("router_fan_out");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+    group.sample_size(10);
+    for shards in [1, 2, 4, 8] {
+        group.bench_with_input(BenchmarkId::new("shards", shards), &shards, |b, &shards| {
+            b.iter_custom(|iterations| {
+                (0..iterations)
+                    .map(|_| runtime.block_on(run(shards, &messages)))
+                    .sum()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, router_throughput);
+criterion_main!(benches);
@@ -0,0 +1,106 @@
+//! Consumer-side delivery acknowledgements
+//!
+//! A producer that stamps sequence numbers ([`BusMessage::with_sequence`])
+//! expects its consumer to acknowledge them, or the orchestrator treats the
+//! unacknowledged numbers as a gap and asks Storage to resend them. A consumer
+//! records each sequenced message once it has handled it and publishes the
+//! pending acknowledgements from time to time; consecutive numbers collapse
+//! into one `DeliveryAck` range per producer.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    message::DeliveryAck, BusMessage, EventBusResult, EventBusTrait, MessagePayload, MessagePriority, ModuleId,
+};
+
+/// Sequence numbers handled since the last flush, per producer
+#[derive(Debug)]
+pub struct DeliveryAcks {
+    consumer: ModuleId,
+    pending: HashMap<ModuleId, BTreeSet<u64>>,
+}
+
+impl DeliveryAcks {
+    pub fn new(consumer: ModuleId) -> Self {
+        Self { consumer, pending: HashMap::new() }
+    }
+
+    /// Note a handled message; messages without a sequence number need no ack
+    pub fn record(&mut self, message: &BusMessage) {
+        if let Some(sequence) = message.sequence {
+            self.pending.entry(message.source).or_default().insert(sequence);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The pending acknowledgements as ranges, leaving nothing pending
+    pub fn take(&mut self) -> Vec<DeliveryAck> {
+        let mut acks = Vec::new();
+        for (producer, sequences) in self.pending.drain() {
+            let mut sequences = sequences.into_iter();
+            let Some(first) = sequences.next() else { continue };
+            let (mut from, mut to) = (first, first);
+            for sequence in sequences {
+                if sequence != to + 1 {
+                    acks.push(DeliveryAck { producer, consumer: self.consumer, from_sequence: from, to_sequence: to });
+                    from = sequence;
+                }
+                to = sequence;
+            }
+            acks.push(DeliveryAck { producer, consumer: self.consumer, from_sequence: from, to_sequence: to });
+        }
+        acks
+    }
+
+    /// Publish the pending acknowledgements; returns how many ranges went out
+    pub async fn flush(&mut self, event_bus: &dyn EventBusTrait) -> EventBusResult<usize> {
+        let acks = self.take();
+        let count = acks.len();
+        for ack in acks {
+            let message =
+                BusMessage::with_priority(self.consumer, MessagePayload::DeliveryAck(ack), MessagePriority::High);
+            event_bus.publish(message).await?;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::RawEvent;
+
+    fn sequenced(source: ModuleId, sequence: u64) -> BusMessage {
+        BusMessage::new(source, MessagePayload::RawEvent(RawEvent::mouse_move(0.0, 0.0))).with_sequence(sequence)
+    }
+
+    #[test]
+    fn test_consecutive_sequences_collapse_into_ranges() {
+        let mut acks = DeliveryAcks::new(ModuleId::AnalysisEngine);
+        for sequence in [3, 1, 2, 5, 7, 6, 2] {
+            acks.record(&sequenced(ModuleId::DataCapture, sequence));
+        }
+        acks.record(&sequenced(ModuleId::Storage, 9));
+        acks.record(&BusMessage::new(ModuleId::Storage, MessagePayload::RawEvent(RawEvent::mouse_move(0.0, 0.0))));
+
+        let mut ranges: Vec<_> = acks
+            .take()
+            .into_iter()
+            .map(|ack| {
+                assert_eq!(ack.consumer, ModuleId::AnalysisEngine);
+                (ack.producer, ack.from_sequence, ack.to_sequence)
+            })
+            .collect();
+        ranges.sort_by_key(|&(producer, from, _)| (producer.to_string(), from));
+        assert_eq!(ranges, vec![
+            (ModuleId::DataCapture, 1, 3),
+            (ModuleId::DataCapture, 5, 7),
+            (ModuleId::Storage, 9, 9),
+        ]);
+        assert!(acks.is_empty());
+        assert!(acks.take().is_empty());
+    }
+}
@@ -0,0 +1,487 @@
+//! Message audit mode
+//!
+//! An opt-in, time-boxed recording of bus traffic for questions like "why
+//! didn't I get an intervention at 14:32". While an audit runs, every message
+//! matching its filter is written to an [`AuditSink`] with its timestamps and
+//! what happened to it: who it was delivered to, who missed it, or why it was
+//! rejected. Records carry an expiry so the sink can drop them again; with the
+//! `storage-audit` feature, [`StorageAuditSink`] keeps them in the storage
+//! module's database, which purges them during its regular cleanup.
+//!
+//! Recording never blocks delivery. Records go through a bounded channel to a
+//! background writer, and are counted as dropped when the writer falls behind.
+//! The audit stops by itself once its duration has passed.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    BusMessage, EventBusResult, MessageFilter, MessageId, MessagePriority, MessageType, ModuleId,
+    subscription::DeliveryResults,
+};
+
+/// What to audit and for how long
+#[derive(Debug)]
+pub struct AuditConfig {
+    /// Messages to record; everything by default
+    pub filter: MessageFilter,
+
+    /// The audit stops by itself after this long
+    pub duration: Duration,
+
+    /// How long the sink keeps each record
+    pub retention: Duration,
+
+    /// Record payloads as JSON, not just routing details
+    pub capture_payloads: bool,
+
+    /// Records waiting for the writer before new ones are dropped
+    pub buffer_size: usize,
+
+    /// Most records handed to the sink in one write
+    pub batch_size: usize,
+}
+
+impl AuditConfig {
+    /// Audit all messages, with payloads, for `duration`; records are kept for a day
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            filter: MessageFilter::all(),
+            duration,
+            retention: Duration::from_secs(24 * 60 * 60),
+            capture_payloads: true,
+            buffer_size: 10_000,
+            batch_size: 256,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: MessageFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Record routing details only, e.g. when payloads hold data that shouldn't be kept
+    pub fn without_payloads(mut self) -> Self {
+        self.capture_payloads = false;
+        self
+    }
+}
+
+/// What happened to an audited message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeliveryOutcome {
+    /// Offered to matching subscriptions
+    Delivered {
+        delivered_to: Vec<ModuleId>,
+        /// Subscribers the message did not reach
+        failed: Vec<ModuleId>,
+        /// Held for paused subscribers or added to an aggregation digest
+        held: u32,
+    },
+    /// No subscription matched
+    NoSubscribers,
+    /// Sent straight to the target's direct channel
+    Direct { to: ModuleId },
+    /// Turned away at publish
+    Rejected { reason: String },
+}
+
+impl DeliveryOutcome {
+    pub(crate) fn from_results(results: &DeliveryResults) -> Self {
+        let held = results.buffered + results.aggregated;
+        if results.delivered_to.is_empty() && results.failed_subscribers.is_empty() && held == 0 {
+            Self::NoSubscribers
+        } else {
+            Self::Delivered {
+                delivered_to: results.delivered_to.clone(),
+                failed: results.failed_subscribers.clone(),
+                held,
+            }
+        }
+    }
+}
+
+/// One audited message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub message_id: MessageId,
+    pub message_type: MessageType,
+    pub source: ModuleId,
+    pub priority: MessagePriority,
+    pub correlation_id: Option<Uuid>,
+    /// When the publisher created the message
+    pub published_at: DateTime<Utc>,
+    /// When the outcome was known
+    pub recorded_at: DateTime<Utc>,
+    pub outcome: DeliveryOutcome,
+    /// The payload as JSON, unless the audit leaves payloads out
+    pub payload: Option<serde_json::Value>,
+    /// When the sink may delete this record
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Where audit records are written
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Persist a batch of records, oldest first
+    async fn write(&self, records: Vec<AuditRecord>) -> EventBusResult<()>;
+}
+
+/// Keeps audit records in memory, e.g. for tests or a short interactive session
+#[derive(Debug, Default)]
+pub struct MemoryAuditSink {
+    records: parking_lot::Mutex<Vec<AuditRecord>>,
+}
+
+impl MemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records written so far that haven't expired
+    pub fn records(&self) -> Vec<AuditRecord> {
+        let now = Utc::now();
+        self.records.lock().iter().filter(|record| record.expires_at > now).cloned().collect()
+    }
+}
+
+#[async_trait]
+impl AuditSink for MemoryAuditSink {
+    async fn write(&self, records: Vec<AuditRecord>) -> EventBusResult<()> {
+        self.records.lock().extend(records);
+        Ok(())
+    }
+}
+
+/// Progress of the current or last audit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditStatus {
+    pub active: bool,
+    /// When the audit stops by itself
+    pub until: Option<DateTime<Utc>>,
+    /// Records handed to the writer
+    pub recorded: u64,
+    /// Records lost because the writer fell behind
+    pub dropped: u64,
+}
+
+/// A running audit
+struct AuditSession {
+    filter: MessageFilter,
+    deadline: Instant,
+    until: DateTime<Utc>,
+    retention: chrono::Duration,
+    capture_payloads: bool,
+    sender: mpsc::Sender<AuditRecord>,
+}
+
+/// Tees messages into the active audit, if there is one
+#[derive(Default)]
+pub(crate) struct Auditor {
+    /// Checked before taking the lock, so the common no-audit case costs one load
+    active: AtomicBool,
+    session: parking_lot::RwLock<Option<AuditSession>>,
+    recorded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Auditor {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin auditing, replacing any audit already running
+    pub(crate) fn start(&self, config: AuditConfig, sink: Arc<dyn AuditSink>) {
+        let (sender, receiver) = mpsc::channel(config.buffer_size.max(1));
+        let deadline = Instant::now() + config.duration;
+        let until = Utc::now() + chrono::Duration::from_std(config.duration).unwrap_or(chrono::Duration::MAX);
+        tokio::spawn(write_records(receiver, sink, deadline, config.batch_size.max(1)));
+
+        self.recorded.store(0, Ordering::Relaxed);
+        self.dropped.store(0, Ordering::Relaxed);
+        *self.session.write() = Some(AuditSession {
+            filter: config.filter,
+            deadline,
+            until,
+            retention: chrono::Duration::from_std(config.retention).unwrap_or(chrono::Duration::MAX),
+            capture_payloads: config.capture_payloads,
+            sender,
+        });
+        self.active.store(true, Ordering::SeqCst);
+        info!("🔍 Auditing bus traffic until {}", until);
+    }
+
+    /// End the audit early; records already taken are still written
+    pub(crate) fn stop(&self) -> AuditStatus {
+        let status = self.status();
+        self.active.store(false, Ordering::SeqCst);
+        if self.session.write().take().is_some() {
+            info!("Bus audit stopped: {} recorded, {} dropped", status.recorded, status.dropped);
+        }
+        status
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn status(&self) -> AuditStatus {
+        let session = self.session.read();
+        AuditStatus {
+            active: session.as_ref().is_some_and(|session| Instant::now() < session.deadline),
+            until: session.as_ref().map(|session| session.until),
+            recorded: self.recorded.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Record what happened to `message` if the active audit covers it
+    pub(crate) fn record(&self, message: &BusMessage, outcome: impl FnOnce() -> DeliveryOutcome) {
+        if !self.is_active() {
+            return;
+        }
+
+        let expired = {
+            let session = self.session.read();
+            let Some(session) = session.as_ref() else { return };
+            if Instant::now() >= session.deadline {
+                true
+            } else {
+                if !session.filter.matches(message) {
+                    return;
+                }
+                let recorded_at = Utc::now();
+                let payload = if session.capture_payloads {
+                    serde_json::to_value(&message.payload).ok()
+                } else {
+                    None
+                };
+                let record = AuditRecord {
+                    message_id: message.id,
+                    message_type: message.message_type(),
+                    source: message.source,
+                    priority: message.priority,
+                    correlation_id: message.correlation_id,
+                    published_at: message.timestamp.into(),
+                    recorded_at,
+                    outcome: outcome(),
+                    payload,
+                    expires_at: recorded_at + session.retention,
+                };
+                match session.sender.try_send(record) {
+                    Ok(()) => self.recorded.fetch_add(1, Ordering::Relaxed),
+                    Err(_) => self.dropped.fetch_add(1, Ordering::Relaxed),
+                };
+                false
+            }
+        };
+
+        if expired {
+            self.stop();
+        }
+    }
+}
+
+/// Hand records to the sink in batches until the deadline or the audit is stopped
+async fn write_records(
+    mut receiver: mpsc::Receiver<AuditRecord>,
+    sink: Arc<dyn AuditSink>,
+    deadline: Instant,
+    batch_size: usize,
+) {
+    let deadline = tokio::time::Instant::from_std(deadline);
+    loop {
+        let first = tokio::select! {
+            record = receiver.recv() => record,
+            _ = tokio::time::sleep_until(deadline) => None,
+        };
+        let Some(first) = first else { break };
+
+        let mut batch = vec![first];
+        while batch.len() < batch_size {
+            match receiver.try_recv() {
+                Ok(record) => batch.push(record),
+                Err(_) => break,
+            }
+        }
+        flush(sink.as_ref(), batch).await;
+    }
+
+    // Whatever was recorded before the deadline is still written
+    receiver.close();
+    let mut rest = Vec::new();
+    while let Some(record) = receiver.recv().await {
+        rest.push(record);
+    }
+    for batch in rest.chunks(batch_size) {
+        flush(sink.as_ref(), batch.to_vec()).await;
+    }
+    debug!("Bus audit writer finished");
+}
+
+async fn flush(sink: &dyn AuditSink, batch: Vec<AuditRecord>) {
+    let count = batch.len();
+    if let Err(e) = sink.write(batch).await {
+        warn!("Failed to write {} bus audit records: {}", count, e);
+    }
+}
+
+#[cfg(feature = "storage-audit")]
+pub use storage_sink::StorageAuditSink;
+
+#[cfg(feature = "storage-audit")]
+mod storage_sink {
+    use super::*;
+    use crate::EventBusError;
+    use skelly_jelly_storage::{BusAuditRecord, TimeSeriesDatabase};
+
+    /// Writes audit records to the storage module's database
+    pub struct StorageAuditSink {
+        database: Arc<TimeSeriesDatabase>,
+    }
+
+    impl StorageAuditSink {
+        pub fn new(database: Arc<TimeSeriesDatabase>) -> Self {
+            Self { database }
+        }
+    }
+
+    #[async_trait]
+    impl AuditSink for StorageAuditSink {
+        async fn write(&self, records: Vec<AuditRecord>) -> EventBusResult<()> {
+            let records: Vec<BusAuditRecord> = records
+                .into_iter()
+                .map(|record| {
+                    let (outcome, delivered_to, failed, detail) = match record.outcome {
+                        DeliveryOutcome::Delivered { delivered_to, failed, held } => {
+                            ("delivered", delivered_to, failed, (held > 0).then(|| format!("{} held", held)))
+                        }
+                        DeliveryOutcome::NoSubscribers => ("no_subscribers", Vec::new(), Vec::new(), None),
+                        DeliveryOutcome::Direct { to } => ("direct", vec![to], Vec::new(), None),
+                        DeliveryOutcome::Rejected { reason } => ("rejected", Vec::new(), Vec::new(), Some(reason)),
+                    };
+                    BusAuditRecord {
+                        message_id: record.message_id,
+                        message_type: format!("{:?}", record.message_type),
+                        source: record.source.to_string(),
+                        priority: format!("{:?}", record.priority),
+                        correlation_id: record.correlation_id,
+                        published_at: record.published_at,
+                        recorded_at: record.recorded_at,
+                        outcome: outcome.to_string(),
+                        delivered_to: delivered_to.iter().map(ToString::to_string).collect(),
+                        failed: failed.iter().map(ToString::to_string).collect(),
+                        detail,
+                        payload: record.payload,
+                        expires_at: record.expires_at,
+                    }
+                })
+                .collect();
+
+            self.database
+                .store_bus_audit(&records)
+                .await
+                .map_err(|e| EventBusError::Internal(format!("storing bus audit: {}", e)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_event_bus, DeliveryMode, EventBusTrait, MessagePayload};
+    use crate::message::{ConfigUpdate, HealthCheckRequest};
+
+    fn config_update(key: &str) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, MessagePayload::ConfigUpdate(ConfigUpdate {
+            config_key: key.to_string(),
+            config_value: serde_json::json!(true),
+            target_module: None,
+        }))
+    }
+
+    async fn wait_for_records(sink: &MemoryAuditSink, count: usize) -> Vec<AuditRecord> {
+        for _ in 0..100 {
+            let records = sink.records();
+            if records.len() >= count {
+                return records;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        sink.records()
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_filtered_outcomes() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        bus.subscribe(ModuleId::Storage, MessageFilter::types(vec![MessageType::ConfigUpdate]), DeliveryMode::BestEffort)
+            .await
+            .unwrap();
+
+        let sink = Arc::new(MemoryAuditSink::new());
+        bus.start_audit(
+            AuditConfig::new(Duration::from_secs(60)).with_filter(MessageFilter::sources(vec![ModuleId::Orchestrator])),
+            sink.clone(),
+        );
+
+        let delivered = bus.publish(config_update("theme")).await.unwrap();
+        // Outside the filter
+        bus.publish(BusMessage::new(ModuleId::Gamification, MessagePayload::HealthCheck(HealthCheckRequest {
+            module_id: ModuleId::Gamification,
+            timestamp: Utc::now(),
+        })))
+        .await
+        .unwrap();
+
+        let records = wait_for_records(&sink, 1).await;
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.message_id, delivered);
+        assert_eq!(record.outcome, DeliveryOutcome::Delivered {
+            delivered_to: vec![ModuleId::Storage],
+            failed: Vec::new(),
+            held: 0,
+        });
+        assert_eq!(record.payload.as_ref().unwrap()["ConfigUpdate"]["config_key"], "theme");
+        assert!(record.expires_at > record.recorded_at);
+
+        let status = bus.stop_audit();
+        assert_eq!((status.recorded, status.dropped), (1, 0));
+        bus.publish(config_update("after")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(sink.records().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_audit_stops_after_duration() {
+        let auditor = Auditor::new();
+        let sink = Arc::new(MemoryAuditSink::new());
+        auditor.start(AuditConfig::new(Duration::from_millis(50)).without_payloads(), sink.clone());
+
+        auditor.record(&config_update("before"), || DeliveryOutcome::NoSubscribers);
+        assert!(auditor.status().active);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        auditor.record(&config_update("after"), || DeliveryOutcome::NoSubscribers);
+
+        let status = auditor.status();
+        assert!(!status.active);
+        assert_eq!(status.recorded, 1);
+        let records = wait_for_records(&sink, 1).await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].payload, None);
+    }
+}
@@ -0,0 +1,222 @@
+//! Message-level authorization between modules
+//!
+//! An [`AuthorizationPolicy`] lists, per message type, which modules may publish
+//! it and which may subscribe to it. Types without an entry stay open to every
+//! module. The bus rejects anything else with [`EventBusError::Unauthorized`],
+//! logs it under [`ErrorCategory::Authorization`], and counts it in `BusMetrics`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    BusMessage, EventBusError, EventBusResult, MessageType, ModuleId,
+    error_logging::{ErrorCategory, ErrorContext, ErrorLogger, ErrorSeverity},
+    metrics::MetricsCollector,
+    subscription::MessageFilter,
+};
+
+/// What a module is trying to do with a message type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BusAction {
+    Publish,
+    Subscribe,
+}
+
+impl std::fmt::Display for BusAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BusAction::Publish => write!(f, "publish"),
+            BusAction::Subscribe => write!(f, "subscribe to"),
+        }
+    }
+}
+
+/// Which modules may publish and subscribe to which message types
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorizationPolicy {
+    publishers: HashMap<MessageType, HashSet<ModuleId>>,
+    subscribers: HashMap<MessageType, HashSet<ModuleId>>,
+}
+
+impl AuthorizationPolicy {
+    /// An empty policy that allows everything until types are restricted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The policy for the standard module layout: raw input only comes from
+    /// Data Capture, and control messages only from the Orchestrator
+    pub fn skelly_jelly_default() -> Self {
+        Self::new()
+            .allow_publish(MessageType::RawEvent, [ModuleId::DataCapture])
+            .allow_publish(MessageType::EventBatch, [ModuleId::DataCapture])
+            .allow_publish(MessageType::ConfigUpdate, [ModuleId::Orchestrator])
+            .allow_publish(MessageType::ConfigTransactionResult, [ModuleId::Orchestrator])
+            .allow_publish(MessageType::Shutdown, [ModuleId::Orchestrator])
+            .allow_subscribe(MessageType::RawEvent, [ModuleId::Storage, ModuleId::Orchestrator])
+            .allow_subscribe(
+                MessageType::EventBatch,
+                [ModuleId::Storage, ModuleId::AnalysisEngine, ModuleId::Orchestrator],
+            )
+    }
+
+    /// Restrict publishing `message_type` to `modules` (adds to any earlier grant)
+    pub fn allow_publish(mut self, message_type: MessageType, modules: impl IntoIterator<Item = ModuleId>) -> Self {
+        self.publishers.entry(message_type).or_default().extend(modules);
+        self
+    }
+
+    /// Restrict subscribing to `message_type` to `modules` (adds to any earlier grant)
+    pub fn allow_subscribe(mut self, message_type: MessageType, modules: impl IntoIterator<Item = ModuleId>) -> Self {
+        self.subscribers.entry(message_type).or_default().extend(modules);
+        self
+    }
+
+    /// Whether `module` may perform `action` on `message_type`
+    pub fn is_allowed(&self, module: ModuleId, message_type: MessageType, action: BusAction) -> bool {
+        let table = match action {
+            BusAction::Publish => &self.publishers,
+            BusAction::Subscribe => &self.subscribers,
+        };
+        table.get(&message_type).is_none_or(|allowed| allowed.contains(&module))
+    }
+
+    /// Check a publish
+    pub fn check_publish(&self, message: &BusMessage) -> EventBusResult<()> {
+        let message_type = message.message_type();
+        if self.is_allowed(message.source, message_type, BusAction::Publish) {
+            Ok(())
+        } else {
+            Err(EventBusError::Unauthorized { module: message.source, message_type, action: BusAction::Publish })
+        }
+    }
+
+    /// Check a subscription; a filter without types covers every restricted type
+    pub fn check_subscribe(&self, subscriber: ModuleId, filter: &MessageFilter) -> EventBusResult<()> {
+        let denied = match &filter.types {
+            Some(types) => types
+                .iter()
+                .copied()
+                .find(|message_type| !self.is_allowed(subscriber, *message_type, BusAction::Subscribe)),
+            None => self
+                .subscribers
+                .iter()
+                .filter(|(_, allowed)| !allowed.contains(&subscriber))
+                .map(|(message_type, _)| *message_type)
+                .min_by_key(|message_type| format!("{:?}", message_type)),
+        };
+
+        match denied {
+            Some(message_type) => Err(EventBusError::Unauthorized {
+                module: subscriber,
+                message_type,
+                action: BusAction::Subscribe,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Enforces a policy on behalf of a bus, logging and counting every denial
+pub(crate) struct Authorizer {
+    policy: AuthorizationPolicy,
+    error_logger: Arc<ErrorLogger>,
+    metrics: Arc<MetricsCollector>,
+}
+
+impl Authorizer {
+    pub(crate) fn new(policy: AuthorizationPolicy, error_logger: Arc<ErrorLogger>, metrics: Arc<MetricsCollector>) -> Self {
+        Self { policy, error_logger, metrics }
+    }
+
+    pub(crate) fn authorize_publish(&self, message: &BusMessage) -> EventBusResult<()> {
+        self.policy.check_publish(message).inspect_err(|error| {
+            self.deny(error, message.source, "publish_message", Some(message));
+        })
+    }
+
+    pub(crate) fn authorize_subscribe(&self, subscriber: ModuleId, filter: &MessageFilter) -> EventBusResult<()> {
+        self.policy.check_subscribe(subscriber, filter).inspect_err(|error| {
+            self.deny(error, subscriber, "subscribe", None);
+        })
+    }
+
+    fn deny(&self, error: &EventBusError, module: ModuleId, operation: &str, message: Option<&BusMessage>) {
+        warn!("🚫 {}", error);
+        self.metrics.record_authorization_denied(module);
+
+        let mut context = ErrorContext::new(
+            ErrorLogger::create_correlation_id(),
+            module,
+            operation.to_string(),
+            ErrorSeverity::Error,
+            ErrorCategory::Authorization,
+            error.to_string(),
+        );
+        if let Some(message) = message {
+            context = context
+                .with_message_id(message.id)
+                .with_metadata("message_type", message.message_type());
+        }
+        self.error_logger.log_error(&context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MessagePayload, message::RawEvent};
+    use chrono::Utc;
+
+    fn raw_event(source: ModuleId) -> BusMessage {
+        BusMessage::new(
+            source,
+            MessagePayload::RawEvent(RawEvent {
+                event_type: "keystroke".to_string(),
+                data: serde_json::json!({}),
+                window_title: None,
+                timestamp: Utc::now(),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_default_policy_restricts_raw_events_and_leaves_other_types_open() {
+        let policy = AuthorizationPolicy::skelly_jelly_default();
+
+        assert!(policy.check_publish(&raw_event(ModuleId::DataCapture)).is_ok());
+        assert!(matches!(
+            policy.check_publish(&raw_event(ModuleId::Gamification)),
+            Err(EventBusError::Unauthorized { module: ModuleId::Gamification, message_type: MessageType::RawEvent, action: BusAction::Publish })
+        ));
+        assert!(policy.is_allowed(ModuleId::Gamification, MessageType::RewardEvent, BusAction::Publish));
+
+        let raw_only = MessageFilter::types(vec![MessageType::RawEvent]);
+        assert!(policy.check_subscribe(ModuleId::Storage, &raw_only).is_ok());
+        assert!(policy.check_subscribe(ModuleId::CuteFigurine, &raw_only).is_err());
+        // A catch-all subscription would include raw events
+        assert!(policy.check_subscribe(ModuleId::CuteFigurine, &MessageFilter::all()).is_err());
+        assert!(policy.check_subscribe(ModuleId::Orchestrator, &MessageFilter::all()).is_ok());
+    }
+
+    #[test]
+    fn test_denials_are_logged_and_counted() {
+        let error_logger = Arc::new(ErrorLogger::new(Default::default()));
+        let metrics = Arc::new(MetricsCollector::new());
+        let authorizer = Authorizer::new(AuthorizationPolicy::skelly_jelly_default(), error_logger.clone(), metrics.clone());
+
+        assert!(authorizer.authorize_publish(&raw_event(ModuleId::DataCapture)).is_ok());
+        assert!(authorizer.authorize_publish(&raw_event(ModuleId::AiIntegration)).is_err());
+        assert!(authorizer
+            .authorize_subscribe(ModuleId::AiIntegration, &MessageFilter::types(vec![MessageType::EventBatch]))
+            .is_err());
+
+        let snapshot = metrics.snapshot(HashMap::new());
+        assert_eq!(snapshot.authorization_denials, 2);
+        assert_eq!(snapshot.module_stats[&ModuleId::AiIntegration].authorization_denials, 2);
+        assert_eq!(snapshot.module_stats[&ModuleId::DataCapture].authorization_denials, 0);
+        assert_eq!(error_logger.stats().errors_by_category.get("Authorization"), Some(&2));
+    }
+}
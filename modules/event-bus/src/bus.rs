@@ -3,16 +3,27 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use crossbeam_channel::{bounded, Receiver};
 use tracing::{debug, info, warn};
 
 use crate::{
     BusMessage, EventBusConfig, EventBusError, EventBusResult, EventBusTrait,
-    MessageId, ModuleId, SubscriptionId,
-    subscription::{DeliveryMode, MessageFilter, Subscription},
+    MessageId, ModuleId, SubscriptionId, CompressionCodec,
+    audit::{AuditConfig, AuditSink, AuditStatus, DeliveryOutcome},
+    stream::SubscriptionStream,
+    typed::{forward_typed, TypedPayload, TypedSubscription},
+    subscription::{ConsumerGroup, DeliveryMode, MessageFilter, OrderingMode, ReplaySummary, Subscription},
     router::{MessageRouter, RouterConfig},
     metrics::BusMetrics,
     registry::{ModuleRegistry, ModuleInfo, RegistryConfig},
+    authorization::Authorizer,
+    validation::PublishValidator,
+    error_logging::ErrorLogger,
+    scheduler::MessageScheduler,
+    dead_letter_queue::{DeadLetterQueue, DeadLetterQueueConfig, DeadLetterReason},
+    drain::{DrainSummary, ShutdownGate},
+    dedup::PublishDeduplicator,
 };
 
 /// Main event bus implementation
@@ -31,6 +42,24 @@ pub struct EventBusImpl {
     
     /// Shutdown state
     is_shutdown: Arc<parking_lot::RwLock<bool>>,
+    
+    /// Enforces the configured authorization policy, if any
+    authorizer: Option<Authorizer>,
+    
+    /// Runs the registered payload validators, if any
+    validator: Option<PublishValidator>,
+    
+    /// Holds messages published with a delay until they fall due
+    scheduler: Arc<MessageScheduler>,
+    
+    /// Turns away non-critical publishes once shutdown starts draining
+    shutdown_gate: ShutdownGate,
+    
+    /// Messages that could not be delivered before shutdown finished
+    dead_letters: Arc<DeadLetterQueue>,
+    
+    /// Drops retried publishes whose idempotency token was already used
+    dedup: PublishDeduplicator,
 }
 
 impl EventBusImpl {
@@ -41,6 +70,8 @@ impl EventBusImpl {
             delivery_timeout: config.delivery_timeout,
             worker_threads: 4, // Could be configurable
             direct_channel_buffer: 1_000,
+            compression: config.compression.clone(),
+            memory_budget: config.memory_budget.clone(),
         };
 
         let router = Arc::new(MessageRouter::new(router_config));
@@ -48,12 +79,39 @@ impl EventBusImpl {
         let registry_config = RegistryConfig::default();
         let registry = Arc::new(ModuleRegistry::new(registry_config));
 
+        let authorizer = config.authorization_policy.clone().map(|policy| {
+            let error_logger = Arc::new(ErrorLogger::new(config.error_logging_config.clone().unwrap_or_default()));
+            Authorizer::new(policy, error_logger, router.metrics().clone())
+        });
+        let validator = config.validators.clone().map(|registry| {
+            let error_logger = Arc::new(ErrorLogger::new(config.error_logging_config.clone().unwrap_or_default()));
+            PublishValidator::new(registry, error_logger, router.metrics().clone())
+        });
+
+        let scheduler = Arc::new(MessageScheduler::new(config.scheduled_messages_path.clone()));
+        let dead_letters = Arc::new(DeadLetterQueue::new(DeadLetterQueueConfig {
+            max_entries: config.dead_letter_queue_size,
+            spill: config.dead_letter_spill.clone(),
+            enable_persistence: config.dead_letter_path.is_some(),
+            persistence_path: config.dead_letter_path.as_ref().map(|path| path.to_string_lossy().into_owned()),
+            auto_replay: None,
+            ..Default::default()
+        }));
+
+        let dedup = PublishDeduplicator::new(config.deduplication.clone());
+
         Ok(Self {
             router,
             registry,
             config,
             module_receivers: Arc::new(parking_lot::RwLock::new(HashMap::new())),
             is_shutdown: Arc::new(parking_lot::RwLock::new(false)),
+            authorizer,
+            validator,
+            scheduler,
+            shutdown_gate: ShutdownGate::new(),
+            dead_letters,
+            dedup,
         })
     }
 
@@ -64,11 +122,48 @@ impl EventBusImpl {
         }
 
         info!("Starting event bus");
+        if let Err(e) = self.dead_letters.load_from_disk() {
+            warn!("Failed to load dead letters from disk: {}", e);
+        }
         self.router.start().await?;
+        self.scheduler.start(self.router.clone());
         info!("Event bus started successfully");
         Ok(())
     }
 
+    /// Run the shutdown gate, authorization and validation on a message about to be published
+    fn admit(&self, message: &BusMessage) -> EventBusResult<()> {
+        let checks = || {
+            self.shutdown_gate.admit(message)?;
+            if let Some(authorizer) = &self.authorizer {
+                authorizer.authorize_publish(message)?;
+            }
+            if let Some(validator) = &self.validator {
+                validator.validate_publish(message)?;
+            }
+            Ok(())
+        };
+        checks().inspect_err(|e: &EventBusError| {
+            self.router.auditor().record(message, || DeliveryOutcome::Rejected { reason: e.to_string() });
+        })
+    }
+
+    /// Record matching messages and their outcomes to `sink` until `config.duration` passes
+    ///
+    /// Starting an audit while one runs replaces it.
+    pub fn start_audit(&self, config: AuditConfig, sink: Arc<dyn AuditSink>) {
+        self.router.auditor().start(config, sink);
+    }
+
+    /// End the running audit early, returning how much it recorded
+    pub fn stop_audit(&self) -> AuditStatus {
+        self.router.auditor().stop()
+    }
+
+    pub fn audit_status(&self) -> AuditStatus {
+        self.router.auditor().status()
+    }
+
     /// Create a receiver channel for a specific module
     pub fn create_module_receiver(&self, module: ModuleId, buffer_size: usize) -> Receiver<BusMessage> {
         let (sender, receiver) = bounded(buffer_size);
@@ -97,53 +192,131 @@ impl EventBusImpl {
         &self.registry
     }
 
-    /// Get a receiver for a subscription (mock implementation for tests)
-    pub async fn get_receiver(&self, _subscription_id: SubscriptionId) -> EventBusResult<Receiver<BusMessage>> {
-        // This is a placeholder implementation for testing
-        // In a real implementation, you'd want to return the actual receiver for the subscription
-        let (_, receiver) = bounded(1000);
-        Ok(receiver)
+    /// Messages dead-lettered when the bus shut down
+    pub fn dead_letters(&self) -> &Arc<DeadLetterQueue> {
+        &self.dead_letters
     }
-}
 
-#[async_trait]
-impl EventBusTrait for EventBusImpl {
-    async fn publish(&self, message: BusMessage) -> EventBusResult<MessageId> {
-        if *self.is_shutdown.read() {
-            return Err(EventBusError::BusShuttingDown);
-        }
+    /// Subscribe, accepting large payloads compressed with any of `codecs` (most preferred first)
+    ///
+    /// Compressed messages arrive as `MessagePayload::Compressed`; `BusMessage::decompressed` restores them.
+    pub fn subscribe_with_compression(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+        codecs: Vec<CompressionCodec>,
+    ) -> EventBusResult<SubscriptionId> {
+        self.add_subscription(subscriber, filter, delivery_mode, |s| s.with_compression(codecs))
+    }
 
-        debug!("Publishing message {} from {}", message.id, message.source);
-        
-        let message_id = message.id;
-        self.router.publish(message).await?;
-        
-        Ok(message_id)
+    /// Subscribe with an explicit ordering mode
+    ///
+    /// `OrderingMode::PerPublisher` keeps each publisher's messages in publish order at the cost
+    /// of delivering them on a single worker; the default `Unordered` lets any worker deliver.
+    pub fn subscribe_with_ordering(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+        ordering: OrderingMode,
+    ) -> EventBusResult<SubscriptionId> {
+        self.add_subscription(subscriber, filter, delivery_mode, |s| s.with_ordering(ordering))
     }
 
-    async fn subscribe(
+    /// Subscribe as one member of a consumer group
+    ///
+    /// Members of a group share its messages instead of each receiving all of them, so
+    /// several workers of one module can split its load. Each member gets its own receiver.
+    pub fn subscribe_in_group(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+        group: ConsumerGroup,
+    ) -> EventBusResult<(SubscriptionId, Receiver<BusMessage>)> {
+        self.create_subscription(subscriber, filter, delivery_mode, |s| s.with_group(group))
+    }
+
+    /// Subscribe to one payload type, receiving payloads already unwrapped
+    ///
+    /// Only messages of `T::MESSAGE_TYPE` are delivered. Dropping the stream unsubscribes.
+    pub fn subscribe_typed<T: TypedPayload>(
+        &self,
+        subscriber: ModuleId,
+        delivery_mode: DeliveryMode,
+    ) -> EventBusResult<(SubscriptionId, TypedSubscription<T>)> {
+        let (subscription_id, receiver) =
+            self.create_subscription(subscriber, MessageFilter::types(vec![T::MESSAGE_TYPE]), delivery_mode, |s| s)?;
+        let stream = forward_typed(subscription_id, receiver, Arc::clone(self.router.subscription_manager()));
+        Ok((subscription_id, stream))
+    }
+
+    /// Subscribe and consume the messages as a `Stream`
+    ///
+    /// The stream applies backpressure: while it isn't polled the
+    /// subscription's channel fills and further deliveries to it fail as
+    /// for any slow subscriber. Dropping the stream unsubscribes.
+    pub fn subscribe_stream(
         &self,
         subscriber: ModuleId,
         filter: MessageFilter,
         delivery_mode: DeliveryMode,
+    ) -> EventBusResult<(SubscriptionId, SubscriptionStream)> {
+        let (subscription_id, receiver) = self.create_subscription(subscriber, filter, delivery_mode, |s| s)?;
+        let stream =
+            SubscriptionStream::forward(subscription_id, receiver, Arc::clone(self.router.subscription_manager()), Some);
+        Ok((subscription_id, stream))
+    }
+
+    /// Create and register a subscription, letting `configure` set its options
+    fn add_subscription(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+        configure: impl FnOnce(Subscription) -> Subscription,
     ) -> EventBusResult<SubscriptionId> {
+        let (subscription_id, receiver) = self.create_subscription(subscriber, filter, delivery_mode, configure)?;
+
+        // Note: In a real implementation, you'd want to return the receiver to the subscriber
+        // This might involve storing it in a registry that modules can query
+        // For now, we'll store it in our internal registry
+        self.module_receivers.write().insert(subscriber, receiver);
+
+        Ok(subscription_id)
+    }
+
+    /// Create and register a subscription, returning its receiver
+    fn create_subscription(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+        configure: impl FnOnce(Subscription) -> Subscription,
+    ) -> EventBusResult<(SubscriptionId, Receiver<BusMessage>)> {
         if *self.is_shutdown.read() {
             return Err(EventBusError::BusShuttingDown);
         }
 
         debug!("Creating subscription for module {}", subscriber);
 
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_subscribe(subscriber, &filter)?;
+        }
+
         // Create a channel for this subscription
         let buffer_size = match delivery_mode {
             DeliveryMode::Reliable { .. } => self.config.max_queue_size / 4, // Larger buffer for reliable delivery
             DeliveryMode::BestEffort => self.config.max_queue_size / 8,       // Medium buffer
             DeliveryMode::LatestOnly => 1,                                    // Minimal buffer, only latest value
+            DeliveryMode::Aggregated(_) => 16,                                // Digests are few and far between
         };
 
         let (sender, receiver) = bounded(buffer_size);
 
         // Create the subscription
-        let subscription = Subscription::new(subscriber, filter, delivery_mode, sender);
+        let subscription = configure(Subscription::new(subscriber, filter, delivery_mode, sender));
         let subscription_id = subscription.id;
 
         // Register with the subscription manager
@@ -154,12 +327,44 @@ impl EventBusTrait for EventBusImpl {
 
         debug!("Created subscription {} for module {}", subscription_id, subscriber);
 
-        // Note: In a real implementation, you'd want to return the receiver to the subscriber
-        // This might involve storing it in a registry that modules can query
-        // For now, we'll store it in our internal registry
-        self.module_receivers.write().insert(subscriber, receiver);
+        Ok((subscription_id, receiver))
+    }
 
-        Ok(subscription_id)
+    /// Get a receiver for a subscription (mock implementation for tests)
+    pub async fn get_receiver(&self, _subscription_id: SubscriptionId) -> EventBusResult<Receiver<BusMessage>> {
+        // This is a placeholder implementation for testing
+        // In a real implementation, you'd want to return the actual receiver for the subscription
+        let (_, receiver) = bounded(1000);
+        Ok(receiver)
+    }
+}
+
+#[async_trait]
+impl EventBusTrait for EventBusImpl {
+    async fn publish(&self, message: BusMessage) -> EventBusResult<MessageId> {
+        if *self.is_shutdown.read() {
+            return Err(EventBusError::BusShuttingDown);
+        }
+
+        debug!("Publishing message {} from {}", message.id, message.source);
+        self.admit(&message)?;
+        
+        self.dedup
+            .publish_once(message, self.router.metrics(), |message| async move {
+                let message_id = message.id;
+                self.router.publish(message).await?;
+                Ok(message_id)
+            })
+            .await
+    }
+
+    async fn subscribe(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        delivery_mode: DeliveryMode,
+    ) -> EventBusResult<SubscriptionId> {
+        self.add_subscription(subscriber, filter, delivery_mode, |s| s)
     }
 
     async fn unsubscribe(&self, subscription_id: SubscriptionId) -> EventBusResult<()> {
@@ -176,6 +381,58 @@ impl EventBusTrait for EventBusImpl {
         }
     }
 
+    async fn publish_at(&self, message: BusMessage, at: DateTime<Utc>) -> EventBusResult<MessageId> {
+        if *self.is_shutdown.read() {
+            return Err(EventBusError::BusShuttingDown);
+        }
+        self.admit(&message)?;
+
+        self.dedup
+            .publish_once(message, self.router.metrics(), |message| async move {
+                let message_id = message.id;
+                self.scheduler.schedule(message, at);
+                Ok(message_id)
+            })
+            .await
+    }
+
+    async fn cancel_scheduled(&self, message_id: MessageId) -> EventBusResult<bool> {
+        Ok(self.scheduler.cancel(message_id))
+    }
+
+    async fn pause_delivery(&self, module: ModuleId, capacity: usize) -> EventBusResult<()> {
+        if *self.is_shutdown.read() {
+            return Err(EventBusError::BusShuttingDown);
+        }
+
+        info!("Pausing delivery to {} (buffer {})", module, capacity);
+        self.router.subscription_manager().pause_module(module, capacity);
+        Ok(())
+    }
+
+    async fn resume_delivery(&self, module: ModuleId) -> EventBusResult<ReplaySummary> {
+        let (summary, undelivered) = self.router.subscription_manager().resume_module(module).unwrap_or_default();
+        for message in undelivered {
+            self.dead_letters.add_message(
+                message,
+                DeadLetterReason::SubscriberUnavailable { subscriber: module },
+                0,
+                vec![module],
+                Some("No subscription took it when delivery resumed".to_string()),
+                None,
+            );
+        }
+        if summary.dropped > 0 || summary.failed > 0 {
+            warn!(
+                "Resumed delivery to {}: replayed {}, dropped {}, dead-lettered {}",
+                module, summary.replayed, summary.dropped, summary.failed
+            );
+        } else {
+            info!("Resumed delivery to {}: replayed {}", module, summary.replayed);
+        }
+        Ok(summary)
+    }
+
     async fn metrics(&self) -> EventBusResult<BusMetrics> {
         // Collect subscription counts per module
         let subscription_stats = self.router.subscription_manager().get_stats();
@@ -188,25 +445,26 @@ impl EventBusTrait for EventBusImpl {
         Ok(self.router.metrics().snapshot(subscription_counts))
     }
 
-    async fn shutdown(&self) -> EventBusResult<()> {
+    async fn shutdown(&self) -> EventBusResult<DrainSummary> {
+        if *self.is_shutdown.read() || !self.shutdown_gate.begin() {
+            return Ok(DrainSummary::default()); // Already shut down or draining
+        }
+
         info!("Shutting down event bus");
 
-        {
-            let mut shutdown = self.is_shutdown.write();
-            if *shutdown {
-                return Ok(()); // Already shut down
-            }
-            *shutdown = true;
-        }
+        // Nothing scheduled fires once draining starts; it stays on disk for the next run
+        self.scheduler.stop();
+        let summary = self.shutdown_gate
+            .drain(&self.router, self.config.drain_timeout, &self.dead_letters)
+            .await;
 
-        // Stop the router
-        self.router.stop().await?;
+        *self.is_shutdown.write() = true;
 
         // Clear all receivers
         self.module_receivers.write().clear();
 
         info!("Event bus shutdown complete");
-        Ok(())
+        Ok(summary)
     }
 }
 
@@ -311,6 +569,62 @@ mod tests {
         bus.shutdown().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_pause_and_resume_delivery() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+
+        bus.subscribe(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+        bus.pause_delivery(ModuleId::Storage, 10).await.unwrap();
+
+        let message = BusMessage::new(
+            ModuleId::Orchestrator,
+            MessagePayload::ModuleReady(ModuleId::DataCapture),
+        );
+        bus.publish(message).await.unwrap();
+
+        // Wait for a router worker to pick the message up
+        let manager = bus.router.subscription_manager();
+        for _ in 0..50 {
+            if manager.buffered_count(ModuleId::Storage) > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(manager.buffered_count(ModuleId::Storage), 1);
+
+        let summary = bus.resume_delivery(ModuleId::Storage).await.unwrap();
+        assert_eq!(summary.replayed, 1);
+        assert_eq!(bus.module_receivers.read()[&ModuleId::Storage].len(), 1);
+
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_kept_at_configured_path() {
+        use crate::dead_letter_queue::DeadLetterFilter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dead_letter_queue.json");
+        let config = || EventBusConfig { dead_letter_path: Some(path.clone()), ..Default::default() };
+
+        let bus = create_event_bus_with_config(config()).unwrap();
+        bus.dead_letters().add_message(
+            BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture)),
+            DeadLetterReason::MaxRetriesExceeded { attempts: 3 },
+            3,
+            vec![ModuleId::Storage],
+            None,
+            None,
+        );
+        assert!(path.exists());
+
+        let restarted = create_event_bus_with_config(config()).unwrap();
+        restarted.start().await.unwrap();
+        assert_eq!(restarted.dead_letters().get_entries(&DeadLetterFilter::default()).len(), 1);
+        restarted.shutdown().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_shutdown_prevents_operations() {
         let bus = create_event_bus().unwrap();
@@ -0,0 +1,208 @@
+//! Scripted fault injection for chaos tests (`chaos` feature)
+//!
+//! A [`FaultSchedule`] lists which calls at a [`FaultPoint`] fail and how,
+//! counted from the first call: "fail the 3rd to 5th bus deliveries", "time
+//! out the 2nd inference". A [`FaultInjector`] built from it is handed to the
+//! component owning the point, which asks it before doing the real work:
+//!
+//! ```ignore
+//! let faults = Arc::new(FaultInjector::new(
+//!     FaultSchedule::new().with_rule(FaultRule::new(FaultPoint::BusDelivery, FaultKind::Fail).after(2).times(3)),
+//! ));
+//! bus.set_fault_injector(faults.clone());
+//! // ... publish, then assert on the retries, dead letters and incidents
+//! assert_eq!(faults.injected().len(), 3);
+//! ```
+//!
+//! Because faults are keyed to call counts, not time or randomness, a chaos
+//! test fails the same calls on every run.
+
+use std::{collections::HashMap, time::Duration};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::EventBusError;
+
+/// Where a fault can be injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FaultPoint {
+    /// Handing a published message to the router (`EnhancedEventBus`)
+    BusDelivery,
+    /// Writing events to the storage database
+    StorageWrite,
+    /// Running a model in the analysis engine's inference engine
+    InferenceTimeout,
+}
+
+/// What an injected fault does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FaultKind {
+    /// The call fails straight away
+    Fail,
+    /// The call is reported as timed out after this long, without waiting
+    Timeout(Duration),
+}
+
+/// Faults for a run of calls at one point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultRule {
+    pub point: FaultPoint,
+    pub kind: FaultKind,
+    /// Calls that pass before the first fault
+    pub after: u64,
+    /// Faults to inject; `None` keeps failing every later call
+    pub times: Option<u64>,
+}
+
+impl FaultRule {
+    /// Fail every call at `point` from the first one on
+    pub fn new(point: FaultPoint, kind: FaultKind) -> Self {
+        Self { point, kind, after: 0, times: None }
+    }
+
+    /// Let the first `calls` calls through
+    pub fn after(mut self, calls: u64) -> Self {
+        self.after = calls;
+        self
+    }
+
+    /// Stop after `faults` injected faults
+    pub fn times(mut self, faults: u64) -> Self {
+        self.times = Some(faults);
+        self
+    }
+
+    fn covers(&self, call: u64) -> bool {
+        call > self.after && self.times.is_none_or(|times| call <= self.after + times)
+    }
+}
+
+/// The faults for one chaos test
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultSchedule {
+    pub rules: Vec<FaultRule>,
+}
+
+impl FaultSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule; where rules overlap, the first one added wins
+    pub fn with_rule(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+/// A fault that was injected
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Injected {kind:?} at {point:?} (call {call})")]
+pub struct InjectedFault {
+    pub point: FaultPoint,
+    pub kind: FaultKind,
+    /// Which call at the point it replaced, counting from 1
+    pub call: u64,
+}
+
+impl From<InjectedFault> for EventBusError {
+    fn from(fault: InjectedFault) -> Self {
+        match fault.kind {
+            FaultKind::Fail => EventBusError::Internal(fault.to_string()),
+            FaultKind::Timeout(elapsed) => EventBusError::DeliveryTimeout { elapsed },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct InjectorState {
+    calls: HashMap<FaultPoint, u64>,
+    injected: Vec<InjectedFault>,
+}
+
+/// Decides, call by call, whether a fault point fails
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    schedule: FaultSchedule,
+    state: Mutex<InjectorState>,
+}
+
+impl FaultInjector {
+    pub fn new(schedule: FaultSchedule) -> Self {
+        Self { schedule, state: Mutex::new(InjectorState::default()) }
+    }
+
+    /// Count a call at `point` and return the fault it should fail with, if any
+    pub fn check(&self, point: FaultPoint) -> Option<InjectedFault> {
+        let mut state = self.state.lock();
+        let call = state.calls.entry(point).or_insert(0);
+        *call += 1;
+        let call = *call;
+
+        let rule = self.schedule.rules.iter().find(|rule| rule.point == point && rule.covers(call))?;
+        let fault = InjectedFault { point, kind: rule.kind, call };
+        state.injected.push(fault.clone());
+        Some(fault)
+    }
+
+    /// Calls seen at `point` so far, failed or not
+    pub fn calls(&self, point: FaultPoint) -> u64 {
+        self.state.lock().calls.get(&point).copied().unwrap_or(0)
+    }
+
+    /// Every fault injected so far, in order
+    pub fn injected(&self) -> Vec<InjectedFault> {
+        self.state.lock().injected.clone()
+    }
+
+    /// Start the schedule over
+    pub fn reset(&self) {
+        *self.state.lock() = InjectorState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_fail_the_scripted_calls() {
+        let faults = FaultInjector::new(
+            FaultSchedule::new()
+                .with_rule(FaultRule::new(FaultPoint::BusDelivery, FaultKind::Fail).after(1).times(2))
+                .with_rule(FaultRule::new(FaultPoint::InferenceTimeout, FaultKind::Timeout(Duration::from_millis(50))).after(2)),
+        );
+
+        let deliveries: Vec<bool> = (0..5).map(|_| faults.check(FaultPoint::BusDelivery).is_some()).collect();
+        assert_eq!(deliveries, vec![false, true, true, false, false]);
+
+        let inferences: Vec<bool> = (0..4).map(|_| faults.check(FaultPoint::InferenceTimeout).is_some()).collect();
+        assert_eq!(inferences, vec![false, false, true, true]);
+
+        assert!(faults.check(FaultPoint::StorageWrite).is_none());
+        assert_eq!(faults.calls(FaultPoint::BusDelivery), 5);
+        assert_eq!(faults.injected().len(), 4);
+        assert_eq!(faults.injected()[0].call, 2);
+
+        faults.reset();
+        assert!(faults.check(FaultPoint::BusDelivery).is_none());
+        assert!(faults.check(FaultPoint::BusDelivery).is_some());
+    }
+
+    #[test]
+    fn test_injected_faults_map_to_bus_errors() {
+        let fail = InjectedFault { point: FaultPoint::BusDelivery, kind: FaultKind::Fail, call: 1 };
+        assert!(matches!(EventBusError::from(fail), EventBusError::Internal(_)));
+
+        let timeout = InjectedFault {
+            point: FaultPoint::BusDelivery,
+            kind: FaultKind::Timeout(Duration::from_secs(5)),
+            call: 1,
+        };
+        let error = EventBusError::from(timeout);
+        assert!(matches!(error, EventBusError::DeliveryTimeout { elapsed } if elapsed == Duration::from_secs(5)));
+        assert!(error.is_recoverable());
+    }
+}
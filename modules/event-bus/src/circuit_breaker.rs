@@ -9,6 +9,11 @@ use parking_lot::RwLock;
 use tracing::{debug, warn, error};
 use serde::{Deserialize, Serialize};
 
+use crate::MessageType;
+
+/// How often held calls re-check a half-open circuit whose probes are all in flight
+const HOLD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 /// Configuration for circuit breaker behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CircuitBreakerConfig {
@@ -21,14 +26,33 @@ pub struct CircuitBreakerConfig {
     /// Maximum time to wait for operations to complete
     pub operation_timeout: Duration,
     
-    /// Percentage of successful operations needed to close circuit in half-open state
+    /// Percentage of successful operations needed to close circuit in half-open state;
+    /// any failed probe reopens the circuit, so in practice every probe has to succeed
     pub success_threshold: f64,
     
-    /// Number of test operations to perform in half-open state
+    /// Probes let through in half-open state; the circuit closes once this many succeed in a row
     pub half_open_max_calls: u32,
     
     /// Time window for failure counting
     pub failure_count_window: Duration,
+    
+    /// Minimum spacing between half-open probes
+    #[serde(default)]
+    pub half_open_probe_interval: Duration,
+    
+    /// What happens to calls that arrive in half-open state when no probe slot is free
+    #[serde(default)]
+    pub half_open_overflow: HalfOpenOverflow,
+}
+
+/// Handling of calls that are not admitted as probes while the circuit is half-open
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum HalfOpenOverflow {
+    /// Fail straight away with `CircuitOpen` (or run the fallback)
+    #[default]
+    Reject,
+    /// Wait up to `max_wait` for the circuit to close or a probe slot to open
+    Hold { max_wait: Duration },
 }
 
 impl Default for CircuitBreakerConfig {
@@ -40,6 +64,8 @@ impl Default for CircuitBreakerConfig {
             success_threshold: 0.8, // 80% success rate required
             half_open_max_calls: 5,
             failure_count_window: Duration::from_secs(300), // 5 minutes
+            half_open_probe_interval: Duration::from_secs(1),
+            half_open_overflow: HalfOpenOverflow::Reject,
         }
     }
 }
@@ -105,6 +131,18 @@ struct CircuitBreakerInternalState {
     consecutive_failures: u32,
     response_times: Vec<Duration>,
     failure_times: Vec<Instant>,
+    /// Probes started since the circuit last went half-open
+    probes_started: u32,
+    last_probe_at: Option<Instant>,
+}
+
+/// Whether a call may go ahead right now
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Admission {
+    Allowed,
+    Rejected,
+    /// No probe slot yet; ask again after this long
+    Wait(Duration),
 }
 
 impl CircuitBreaker {
@@ -120,6 +158,8 @@ impl CircuitBreaker {
             consecutive_failures: 0,
             response_times: Vec::new(),
             failure_times: Vec::new(),
+            probes_started: 0,
+            last_probe_at: None,
         };
 
         Self {
@@ -136,9 +176,7 @@ impl CircuitBreaker {
         E: std::fmt::Display,
     {
         // Check if operation is allowed
-        if !self.is_operation_allowed() {
-            return Err(CircuitBreakerError::CircuitOpen);
-        }
+        self.admit().await?;
 
         let start_time = Instant::now();
         
@@ -166,30 +204,81 @@ impl CircuitBreaker {
         }
     }
 
+    /// Execute an operation, running `fallback` instead if the circuit does not admit it
+    pub async fn execute_with_fallback<F, T, E, G, Fb>(&self, operation: F, fallback: G) -> CircuitBreakerResult<T>
+    where
+        F: std::future::Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+        G: FnOnce() -> Fb,
+        Fb: std::future::Future<Output = T>,
+    {
+        match self.execute(operation).await {
+            Err(CircuitBreakerError::CircuitOpen) => {
+                debug!("Circuit breaker {} routing call to fallback", self.name);
+                Ok(fallback().await)
+            }
+            result => result,
+        }
+    }
+
+    /// Wait for admission under the half-open overflow policy
+    async fn admit(&self) -> CircuitBreakerResult<()> {
+        let held_since = Instant::now();
+        loop {
+            match self.check_admission() {
+                Admission::Allowed => return Ok(()),
+                Admission::Rejected => return Err(CircuitBreakerError::CircuitOpen),
+                Admission::Wait(retry_in) => {
+                    let HalfOpenOverflow::Hold { max_wait } = self.config.half_open_overflow else {
+                        return Err(CircuitBreakerError::CircuitOpen);
+                    };
+                    let waited = held_since.elapsed();
+                    if waited >= max_wait {
+                        return Err(CircuitBreakerError::CircuitOpen);
+                    }
+                    tokio::time::sleep(retry_in.min(max_wait - waited)).await;
+                }
+            }
+        }
+    }
+
     /// Check if operation is allowed based on current circuit state
-    fn is_operation_allowed(&self) -> bool {
+    fn check_admission(&self) -> Admission {
         let mut state = self.state.write();
+        let now = Instant::now();
         
         match &state.state {
-            CircuitState::Closed => true,
+            CircuitState::Closed => return Admission::Allowed,
             CircuitState::Open { opened_at } => {
                 // Check if reset timeout has passed
-                if opened_at.elapsed() >= self.config.reset_timeout {
-                    debug!("Circuit breaker {} transitioning to half-open", self.name);
-                    state.state = CircuitState::HalfOpen {
-                        test_count: 0,
-                        success_count: 0,
-                    };
-                    state.state_changed_at = Instant::now();
-                    true
-                } else {
-                    false
+                if opened_at.elapsed() < self.config.reset_timeout {
+                    return Admission::Rejected;
                 }
+                debug!("Circuit breaker {} transitioning to half-open", self.name);
+                state.state = CircuitState::HalfOpen {
+                    test_count: 0,
+                    success_count: 0,
+                };
+                state.state_changed_at = now;
+                state.probes_started = 0;
+                state.last_probe_at = None;
             }
-            CircuitState::HalfOpen { test_count, .. } => {
-                *test_count < self.config.half_open_max_calls
+            CircuitState::HalfOpen { .. } => {}
+        }
+
+        // Half-open: a limited number of probes, spaced out; everything else waits
+        if state.probes_started >= self.config.half_open_max_calls {
+            return Admission::Wait(self.config.half_open_probe_interval.max(HOLD_POLL_INTERVAL));
+        }
+        if let Some(last) = state.last_probe_at {
+            let next = last + self.config.half_open_probe_interval;
+            if next > now {
+                return Admission::Wait(next - now);
             }
         }
+        state.probes_started += 1;
+        state.last_probe_at = Some(now);
+        Admission::Allowed
     }
 
     /// Record a successful operation
@@ -206,33 +295,21 @@ impl CircuitBreaker {
             state.response_times.remove(0);
         }
 
-        match &state.state {
-            CircuitState::HalfOpen { test_count, success_count } => {
-                let new_test_count = test_count + 1;
-                let new_success_count = success_count + 1;
-                
-                if new_test_count >= self.config.half_open_max_calls {
-                    let success_rate = new_success_count as f64 / new_test_count as f64;
-                    
-                    if success_rate >= self.config.success_threshold {
-                        debug!("Circuit breaker {} closing after successful recovery", self.name);
-                        state.state = CircuitState::Closed;
-                        state.failure_count = 0;
-                        state.state_changed_at = Instant::now();
-                    } else {
-                        warn!("Circuit breaker {} reopening, success rate {:.2} below threshold {:.2}", 
-                              self.name, success_rate, self.config.success_threshold);
-                        state.state = CircuitState::Open { opened_at: Instant::now() };
-                        state.state_changed_at = Instant::now();
-                    }
-                } else {
-                    state.state = CircuitState::HalfOpen {
-                        test_count: new_test_count,
-                        success_count: new_success_count,
-                    };
-                }
+        if let CircuitState::HalfOpen { test_count, success_count } = &state.state {
+            let new_test_count = test_count + 1;
+            let new_success_count = success_count + 1;
+
+            if new_success_count >= self.config.half_open_max_calls {
+                debug!("Circuit breaker {} closing after {} consecutive successful probes", self.name, new_success_count);
+                state.state = CircuitState::Closed;
+                state.failure_count = 0;
+                state.state_changed_at = Instant::now();
+            } else {
+                state.state = CircuitState::HalfOpen {
+                    test_count: new_test_count,
+                    success_count: new_success_count,
+                };
             }
-            _ => {} // No state change needed for closed circuit
         }
     }
 
@@ -341,9 +418,18 @@ impl CircuitBreaker {
     }
 }
 
+/// Per-message-type breakers, keyed by the name they sit under
+type TypeBreakers = std::collections::HashMap<(String, MessageType), Arc<CircuitBreaker>>;
+
 /// Circuit breaker registry for managing multiple circuit breakers
+///
+/// Breakers are keyed by name (usually per module). A named breaker can also have
+/// per-message-type breakers beneath it, so one failing kind of traffic does not
+/// open the circuit for the rest; types without their own breaker fall back to
+/// the named one.
 pub struct CircuitBreakerRegistry {
     breakers: Arc<RwLock<std::collections::HashMap<String, Arc<CircuitBreaker>>>>,
+    type_breakers: Arc<RwLock<TypeBreakers>>,
 }
 
 impl CircuitBreakerRegistry {
@@ -351,6 +437,7 @@ impl CircuitBreakerRegistry {
     pub fn new() -> Self {
         Self {
             breakers: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            type_breakers: Arc::new(RwLock::new(TypeBreakers::new())),
         }
     }
 
@@ -361,34 +448,75 @@ impl CircuitBreakerRegistry {
         breaker
     }
 
+    /// Register a breaker for one message type under `name`
+    pub fn register_for_type(
+        &self,
+        name: &str,
+        message_type: MessageType,
+        config: CircuitBreakerConfig,
+    ) -> Arc<CircuitBreaker> {
+        let breaker = Arc::new(CircuitBreaker::new(Self::type_breaker_name(name, message_type), config));
+        self.type_breakers.write().insert((name.to_string(), message_type), breaker.clone());
+        breaker
+    }
+
     /// Get a circuit breaker by name
     pub fn get(&self, name: &str) -> Option<Arc<CircuitBreaker>> {
         self.breakers.read().get(name).cloned()
     }
 
+    /// The breaker for `message_type` under `name`, without falling back
+    pub fn get_for_type(&self, name: &str, message_type: MessageType) -> Option<Arc<CircuitBreaker>> {
+        self.type_breakers.read().get(&(name.to_string(), message_type)).cloned()
+    }
+
+    /// The most specific breaker for `message_type`: its own if registered, else the one named `name`
+    pub fn resolve(&self, name: &str, message_type: MessageType) -> Option<Arc<CircuitBreaker>> {
+        self.get_for_type(name, message_type).or_else(|| self.get(name))
+    }
+
+    /// The first unhealthy breaker guarding `message_type` under `name`, checking the
+    /// type's own breaker and then the named one, which still covers every type
+    pub fn open_breaker_for(&self, name: &str, message_type: MessageType) -> Option<Arc<CircuitBreaker>> {
+        [self.get_for_type(name, message_type), self.get(name)]
+            .into_iter()
+            .flatten()
+            .find(|breaker| !breaker.is_healthy())
+    }
+
     /// Get all circuit breakers
     pub fn all(&self) -> Vec<Arc<CircuitBreaker>> {
-        self.breakers.read().values().cloned().collect()
+        let mut all: Vec<Arc<CircuitBreaker>> = self.breakers.read().values().cloned().collect();
+        all.extend(self.type_breakers.read().values().cloned());
+        all
     }
 
-    /// Get statistics for all circuit breakers
+    /// Get statistics for all circuit breakers; per-type breakers appear as `name/MessageType`
     pub fn all_stats(&self) -> std::collections::HashMap<String, CircuitBreakerStats> {
-        self.breakers
-            .read()
+        self.all()
             .iter()
-            .map(|(name, breaker)| (name.clone(), breaker.stats()))
+            .map(|breaker| (breaker.name().to_string(), breaker.stats()))
             .collect()
     }
 
     /// Check if all circuit breakers are healthy
     pub fn all_healthy(&self) -> bool {
-        self.breakers.read().values().all(|breaker| breaker.is_healthy())
+        self.all().iter().all(|breaker| breaker.is_healthy())
     }
 
     /// Remove a circuit breaker
     pub fn remove(&self, name: &str) -> Option<Arc<CircuitBreaker>> {
         self.breakers.write().remove(name)
     }
+
+    /// Remove a per-type breaker; the type falls back to the named breaker again
+    pub fn remove_for_type(&self, name: &str, message_type: MessageType) -> Option<Arc<CircuitBreaker>> {
+        self.type_breakers.write().remove(&(name.to_string(), message_type))
+    }
+
+    fn type_breaker_name(name: &str, message_type: MessageType) -> String {
+        format!("{}/{:?}", name, message_type)
+    }
 }
 
 impl Default for CircuitBreakerRegistry {
@@ -411,6 +539,8 @@ mod tests {
             success_threshold: 0.8,
             half_open_max_calls: 2,
             failure_count_window: Duration::from_secs(60),
+            half_open_probe_interval: Duration::ZERO,
+            half_open_overflow: HalfOpenOverflow::Reject,
         };
 
         let breaker = CircuitBreaker::new("test".to_string(), config);
@@ -435,6 +565,8 @@ mod tests {
             success_threshold: 0.8,
             half_open_max_calls: 2,
             failure_count_window: Duration::from_secs(60),
+            half_open_probe_interval: Duration::ZERO,
+            half_open_overflow: HalfOpenOverflow::Reject,
         };
 
         let breaker = CircuitBreaker::new("test".to_string(), config);
@@ -464,6 +596,8 @@ mod tests {
             success_threshold: 0.8,
             half_open_max_calls: 2,
             failure_count_window: Duration::from_secs(60),
+            half_open_probe_interval: Duration::ZERO,
+            half_open_overflow: HalfOpenOverflow::Reject,
         };
 
         let breaker = CircuitBreaker::new("test".to_string(), config);
@@ -497,6 +631,8 @@ mod tests {
             success_threshold: 0.8,
             half_open_max_calls: 2,
             failure_count_window: Duration::from_secs(60),
+            half_open_probe_interval: Duration::ZERO,
+            half_open_overflow: HalfOpenOverflow::Reject,
         };
 
         let breaker = CircuitBreaker::new("test".to_string(), config);
@@ -513,6 +649,70 @@ mod tests {
         assert_eq!(stats.failure_count, 1);
     }
 
+    fn shaped_config(overflow: HalfOpenOverflow) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 1,
+            reset_timeout: Duration::from_millis(20),
+            half_open_max_calls: 2,
+            half_open_probe_interval: Duration::from_millis(30),
+            half_open_overflow: overflow,
+            ..CircuitBreakerConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_half_open_admits_spaced_probes_and_rejects_the_rush() {
+        let breaker = CircuitBreaker::new("probe".to_string(), shaped_config(HalfOpenOverflow::Reject));
+        let _ = breaker.execute(async { Err::<i32, String>("down".to_string()) }).await;
+        sleep(Duration::from_millis(25)).await;
+
+        // Queued traffic arrives all at once: one probe goes through, the rest go to the fallback
+        let slow_ok = || async {
+            sleep(Duration::from_millis(5)).await;
+            Ok::<&str, String>("service")
+        };
+        let results = futures::future::join_all(
+            (0..4).map(|_| breaker.execute_with_fallback(slow_ok(), || async { "fallback" })),
+        )
+        .await;
+        let served: Vec<&str> = results.into_iter().map(|result| result.unwrap()).collect();
+        assert_eq!(served.iter().filter(|&&by| by == "service").count(), 1);
+        assert!(matches!(breaker.current_state(), CircuitState::HalfOpen { success_count: 1, .. }));
+
+        // Too soon for the second probe
+        assert!(matches!(breaker.execute(slow_ok()).await, Err(CircuitBreakerError::CircuitOpen)));
+        sleep(Duration::from_millis(30)).await;
+        assert!(breaker.execute(slow_ok()).await.is_ok());
+        assert!(matches!(breaker.current_state(), CircuitState::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_held_calls_wait_for_probes_to_close_the_circuit() {
+        let breaker = CircuitBreaker::new(
+            "hold".to_string(),
+            shaped_config(HalfOpenOverflow::Hold { max_wait: Duration::from_millis(500) }),
+        );
+        let _ = breaker.execute(async { Err::<i32, String>("down".to_string()) }).await;
+        sleep(Duration::from_millis(25)).await;
+
+        let started = Instant::now();
+        let results = futures::future::join_all((0..5).map(|i| breaker.execute(async move { Ok::<i32, String>(i) }))).await;
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert!(matches!(breaker.current_state(), CircuitState::Closed));
+        // Nothing ran before the second probe's slot
+        assert!(started.elapsed() >= Duration::from_millis(30));
+
+        // Held calls give up once `max_wait` passes without a slot
+        let tight = CircuitBreaker::new(
+            "hold-tight".to_string(),
+            shaped_config(HalfOpenOverflow::Hold { max_wait: Duration::from_millis(10) }),
+        );
+        let _ = tight.execute(async { Err::<i32, String>("down".to_string()) }).await;
+        sleep(Duration::from_millis(25)).await;
+        assert!(tight.execute(async { Ok::<i32, String>(1) }).await.is_ok());
+        assert!(matches!(tight.execute(async { Ok::<i32, String>(2) }).await, Err(CircuitBreakerError::CircuitOpen)));
+    }
+
     #[tokio::test]
     async fn test_circuit_breaker_registry() {
         let registry = CircuitBreakerRegistry::new();
@@ -538,4 +738,34 @@ mod tests {
         assert!(removed.is_some());
         assert!(registry.get("test").is_none());
     }
+
+    #[test]
+    fn test_per_type_breakers_are_independent_and_fall_back_to_the_module() {
+        let registry = CircuitBreakerRegistry::new();
+        let module = registry.register("publish_AnalysisEngine".to_string(), CircuitBreakerConfig::default());
+        let analysis = registry.register_for_type(
+            "publish_AnalysisEngine",
+            MessageType::AnalysisComplete,
+            CircuitBreakerConfig::default(),
+        );
+        assert_eq!(analysis.name(), "publish_AnalysisEngine/AnalysisComplete");
+
+        // Failing analysis results leave state changes alone
+        analysis.force_open();
+        assert!(registry.open_breaker_for("publish_AnalysisEngine", MessageType::AnalysisComplete).is_some());
+        assert!(registry.open_breaker_for("publish_AnalysisEngine", MessageType::StateChange).is_none());
+        let fallback = registry.resolve("publish_AnalysisEngine", MessageType::StateChange).unwrap();
+        assert_eq!(fallback.name(), module.name());
+        assert!(!registry.all_healthy());
+        assert!(registry.all_stats().contains_key("publish_AnalysisEngine/AnalysisComplete"));
+
+        // The module-level breaker still guards every type
+        analysis.force_close();
+        module.force_open();
+        let open = registry.open_breaker_for("publish_AnalysisEngine", MessageType::AnalysisComplete).unwrap();
+        assert_eq!(open.name(), "publish_AnalysisEngine");
+
+        assert!(registry.remove_for_type("publish_AnalysisEngine", MessageType::AnalysisComplete).is_some());
+        assert_eq!(registry.resolve("publish_AnalysisEngine", MessageType::AnalysisComplete).unwrap().name(), module.name());
+    }
 }
\ No newline at end of file
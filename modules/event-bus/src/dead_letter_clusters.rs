@@ -0,0 +1,203 @@
+//! Root-cause clustering for dead letters
+//!
+//! Entries are grouped by error signature (the error text with ids and numbers
+//! masked out) and target module, so a thousand messages failing for one reason
+//! show up as one failing flow. A cluster raises a single alert when it reaches
+//! the configured size instead of one log line per message.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    MessageType, ModuleId,
+    dead_letter_queue::{DeadLetterEntry, DeadLetterId},
+};
+
+/// Longest signature kept; the tail of long errors rarely tells flows apart
+const MAX_SIGNATURE_LEN: usize = 160;
+
+static UUID: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b").expect("valid regex")
+});
+static HEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b0x[0-9a-f]+\b").expect("valid regex"));
+static NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+(\.\d+)?").expect("valid regex"));
+static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").expect("valid regex"));
+
+/// The error text of an entry with everything instance-specific masked out
+pub fn error_signature(entry: &DeadLetterEntry) -> String {
+    let raw = entry.error_details.clone().unwrap_or_else(|| format!("{:?}", entry.reason));
+    let masked = UUID.replace_all(&raw, "<id>");
+    let masked = HEX.replace_all(&masked, "<hex>");
+    let masked = NUMBER.replace_all(&masked, "#");
+    let mut signature = WHITESPACE.replace_all(masked.trim(), " ").into_owned();
+    if signature.len() > MAX_SIGNATURE_LEN {
+        let cut = (0..=MAX_SIGNATURE_LEN).rev().find(|i| signature.is_char_boundary(*i)).unwrap_or(0);
+        signature.truncate(cut);
+    }
+    signature
+}
+
+/// Dead letters that failed the same way on the way to the same module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureCluster {
+    pub signature: String,
+    /// Intended recipient; `None` for messages that failed before routing
+    pub target: Option<ModuleId>,
+    /// Message types seen in this cluster
+    pub message_types: Vec<MessageType>,
+    /// Entries currently held (in memory or spilled)
+    pub count: usize,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+    /// Most recent entry, for drilling in
+    pub sample_entry: DeadLetterId,
+    /// Whether the cluster has raised its alert
+    pub alerted: bool,
+}
+
+type ClusterKey = (String, Option<ModuleId>);
+
+/// Incremental clustering of the entries a dead letter queue holds
+#[derive(Debug, Default)]
+pub(crate) struct FailureClusters {
+    clusters: HashMap<ClusterKey, FailureCluster>,
+    members: HashMap<DeadLetterId, Vec<ClusterKey>>,
+}
+
+impl FailureClusters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry to its clusters; a cluster reaching `alert_threshold` alerts once
+    pub fn record(&mut self, entry: &DeadLetterEntry, alert_threshold: usize) {
+        let signature = error_signature(entry);
+        let targets: Vec<Option<ModuleId>> = if entry.intended_recipients.is_empty() {
+            vec![None]
+        } else {
+            entry.intended_recipients.iter().copied().map(Some).collect()
+        };
+        let message_type = entry.message.message_type();
+
+        let mut keys = Vec::with_capacity(targets.len());
+        for target in targets {
+            let key = (signature.clone(), target);
+            let cluster = self.clusters.entry(key.clone()).or_insert_with(|| FailureCluster {
+                signature: signature.clone(),
+                target,
+                message_types: Vec::new(),
+                count: 0,
+                first_seen: entry.timestamp,
+                last_seen: entry.timestamp,
+                sample_entry: entry.id,
+                alerted: false,
+            });
+            cluster.count += 1;
+            cluster.last_seen = cluster.last_seen.max(entry.timestamp);
+            cluster.sample_entry = entry.id;
+            if !cluster.message_types.contains(&message_type) {
+                cluster.message_types.push(message_type);
+            }
+
+            if !cluster.alerted && alert_threshold > 0 && cluster.count >= alert_threshold {
+                cluster.alerted = true;
+                warn!(
+                    "🧟 {} dead letters for {} share one failure ({:?}): {}",
+                    cluster.count,
+                    cluster.target.map_or_else(|| "unrouted messages".to_string(), |module| module.to_string()),
+                    cluster.message_types,
+                    cluster.signature
+                );
+            }
+            keys.push(key);
+        }
+        self.members.insert(entry.id, keys);
+    }
+
+    /// Take an entry back out; empty clusters are dropped, so a recurrence alerts again
+    pub fn forget(&mut self, id: DeadLetterId) {
+        for key in self.members.remove(&id).unwrap_or_default() {
+            if let Some(cluster) = self.clusters.get_mut(&key) {
+                cluster.count = cluster.count.saturating_sub(1);
+                if cluster.count == 0 {
+                    self.clusters.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.clusters.clear();
+        self.members.clear();
+    }
+
+    /// The largest clusters, most recent first among equals
+    pub fn top(&self, limit: usize) -> Vec<FailureCluster> {
+        let mut clusters: Vec<FailureCluster> = self.clusters.values().cloned().collect();
+        clusters.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_seen.cmp(&a.last_seen)));
+        clusters.truncate(limit);
+        clusters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BusMessage, MessagePayload, dead_letter_queue::DeadLetterReason};
+
+    fn failed(recipient: ModuleId, details: &str) -> DeadLetterEntry {
+        DeadLetterEntry {
+            id: uuid::Uuid::new_v4(),
+            message: BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture)),
+            reason: DeadLetterReason::MaxRetriesExceeded { attempts: 3 },
+            timestamp: SystemTime::now(),
+            retry_count: 3,
+            intended_recipients: vec![recipient],
+            error_details: Some(details.to_string()),
+            correlation_id: None,
+            replay_count: 0,
+            marked_for_replay: false,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_signatures_mask_instance_details() {
+        let a = failed(ModuleId::Storage, "insert 4821 failed: row 3f2a9c1e-0b6d-4c1a-9e2f-1a2b3c4d5e6f locked at 0x7ffe");
+        let b = failed(ModuleId::Storage, "insert 17 failed:  row 00000000-0000-4000-8000-000000000000 locked at 0x1");
+        assert_eq!(error_signature(&a), "insert # failed: row <id> locked at <hex>");
+        assert_eq!(error_signature(&a), error_signature(&b));
+
+        let mut without_details = a.clone();
+        without_details.error_details = None;
+        assert_eq!(error_signature(&without_details), "MaxRetriesExceeded { attempts: # }");
+    }
+
+    #[test]
+    fn test_clusters_group_by_signature_and_target_and_alert_once() {
+        let mut clusters = FailureClusters::new();
+        let locked: Vec<DeadLetterEntry> =
+            (0..3).map(|n| failed(ModuleId::Storage, &format!("database locked after {}ms", n * 10))).collect();
+        for entry in &locked {
+            clusters.record(entry, 3);
+        }
+        clusters.record(&failed(ModuleId::AnalysisEngine, "database locked after 5ms"), 3);
+        clusters.record(&failed(ModuleId::Storage, "disk full"), 3);
+
+        let top = clusters.top(5);
+        assert_eq!(top.len(), 3);
+        assert_eq!((top[0].target, top[0].count), (Some(ModuleId::Storage), 3));
+        assert_eq!(top[0].signature, "database locked after #ms");
+        assert!(top[0].alerted);
+        assert!(top[1..].iter().all(|cluster| !cluster.alerted && cluster.count == 1));
+
+        for entry in &locked {
+            clusters.forget(entry.id);
+        }
+        assert_eq!(clusters.top(5).len(), 2);
+    }
+}
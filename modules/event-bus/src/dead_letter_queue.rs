@@ -11,6 +11,12 @@ use tracing::{debug, warn, error, info};
 use uuid::Uuid;
 
 use crate::{BusMessage, ModuleId, MessageId, EventBusError, EventBusResult};
+use crate::dead_letter_spill::{DeadLetterSpill, DeadLetterSpillConfig};
+use crate::dead_letter_clusters::{FailureCluster, FailureClusters};
+use crate::poison::HandlerFailure;
+
+/// Failing flows reported in `DeadLetterStats::top_failing_flows`
+const TOP_FAILING_FLOWS: usize = 5;
 
 /// Unique identifier for dead letter entries
 pub type DeadLetterId = Uuid;
@@ -44,6 +50,12 @@ pub enum DeadLetterReason {
     
     /// Manual routing to dead letter queue
     ManualRouting { reason: String },
+    
+    /// Still undelivered when the bus finished draining at shutdown
+    Shutdown,
+    
+    /// Kept failing in a handler; held for inspection and never replayed
+    Quarantined { failures: u32 },
 }
 
 /// Dead letter entry containing the failed message and metadata
@@ -83,6 +95,13 @@ pub struct DeadLetterEntry {
     pub tags: Vec<String>,
 }
 
+impl DeadLetterEntry {
+    /// Whether the entry holds a poison message, which is never replayed
+    pub fn is_quarantined(&self) -> bool {
+        matches!(self.reason, DeadLetterReason::Quarantined { .. })
+    }
+}
+
 /// Configuration for dead letter queue behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeadLetterQueueConfig {
@@ -106,6 +125,18 @@ pub struct DeadLetterQueueConfig {
     
     /// Batch size for replay operations
     pub replay_batch_size: usize,
+    
+    /// Spill entries beyond `max_entries` to disk instead of dropping them
+    #[serde(default)]
+    pub spill: Option<DeadLetterSpillConfig>,
+    
+    /// Entries sharing a failure signature and target before that cluster raises its one alert (0 disables)
+    #[serde(default = "default_cluster_alert_threshold")]
+    pub cluster_alert_threshold: usize,
+}
+
+fn default_cluster_alert_threshold() -> usize {
+    10
 }
 
 /// Configuration for automatic replay of dead letter messages
@@ -168,6 +199,8 @@ impl Default for DeadLetterQueueConfig {
             }),
             enable_metrics: true,
             replay_batch_size: 10,
+            spill: None,
+            cluster_alert_threshold: default_cluster_alert_threshold(),
         }
     }
 }
@@ -185,6 +218,12 @@ pub struct DeadLetterStats {
     pub newest_entry_age: Option<Duration>,
     pub average_retry_count: f64,
     pub replay_success_rate: f64,
+    /// Entries held on disk rather than in memory (included in `total_entries`)
+    #[serde(default)]
+    pub spilled_entries: usize,
+    /// Largest groups of entries failing the same way for the same module
+    #[serde(default)]
+    pub top_failing_flows: Vec<FailureCluster>,
 }
 
 /// Result of a replay operation
@@ -216,6 +255,8 @@ pub struct DeadLetterQueue {
     entries: Arc<parking_lot::RwLock<VecDeque<DeadLetterEntry>>>,
     stats: Arc<parking_lot::RwLock<DeadLetterStats>>,
     entry_index: Arc<parking_lot::RwLock<HashMap<DeadLetterId, usize>>>,
+    spill: Option<DeadLetterSpill>,
+    clusters: parking_lot::RwLock<FailureClusters>,
 }
 
 impl DeadLetterQueue {
@@ -232,13 +273,24 @@ impl DeadLetterQueue {
             newest_entry_age: None,
             average_retry_count: 0.0,
             replay_success_rate: 0.0,
+            spilled_entries: 0,
+            top_failing_flows: Vec::new(),
         };
 
+        let spill = config.spill.clone().and_then(|spill_config| {
+            let path = spill_config.path.clone();
+            DeadLetterSpill::open(spill_config)
+                .inspect_err(|e| error!("Dead letter spill at {} unavailable, entries beyond the limit will be dropped: {}", path.display(), e))
+                .ok()
+        });
+
         Self {
             config,
             entries: Arc::new(parking_lot::RwLock::new(VecDeque::new())),
             stats: Arc::new(parking_lot::RwLock::new(stats)),
             entry_index: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            spill,
+            clusters: parking_lot::RwLock::new(FailureClusters::new()),
         }
     }
 
@@ -269,22 +321,45 @@ impl DeadLetterQueue {
         let entry_id = entry.id;
         
         debug!("Adding message {} to dead letter queue: {:?}", entry.message.id, reason);
+        self.clusters.write().record(&entry, self.config.cluster_alert_threshold);
 
         // Add to entries
         {
             let mut entries = self.entries.write();
             
             // Check if we need to remove old entries
+            let mut evicted = false;
             while entries.len() >= self.config.max_entries {
                 if let Some(removed) = entries.pop_front() {
-                    self.entry_index.write().remove(&removed.id);
-                    warn!("Removed old entry {} due to queue size limit", removed.id);
+                    evicted = true;
+                    let dropped = match &self.spill {
+                        Some(spill) => spill.spill(&removed).unwrap_or_else(|e| {
+                            error!("Failed to spill entry {} to disk, dropping it: {}", removed.id, e);
+                            vec![removed.id]
+                        }),
+                        None => {
+                            warn!("Removed old entry {} due to queue size limit", removed.id);
+                            vec![removed.id]
+                        }
+                    };
+                    let mut clusters = self.clusters.write();
+                    for id in dropped {
+                        clusters.forget(id);
+                    }
                 }
             }
             
-            let index = entries.len();
             entries.push_back(entry);
-            self.entry_index.write().insert(entry_id, index);
+            let mut index_map = self.entry_index.write();
+            if evicted {
+                // Everything shifted down; rebuild rather than patch
+                index_map.clear();
+                for (index, entry) in entries.iter().enumerate() {
+                    index_map.insert(entry.id, index);
+                }
+            } else {
+                index_map.insert(entry_id, entries.len() - 1);
+            }
         }
 
         // Update statistics
@@ -300,22 +375,59 @@ impl DeadLetterQueue {
         entry_id
     }
 
-    /// Get all entries matching the filter
-    pub fn get_entries(&self, filter: &DeadLetterFilter) -> Vec<DeadLetterEntry> {
-        let entries = self.entries.read();
-        
-        entries
+    /// Hold a message that kept failing in `subscriber`'s handler, with every failure as its error details
+    pub fn quarantine(&self, message: BusMessage, subscriber: ModuleId, failures: &[HandlerFailure]) -> DeadLetterId {
+        let details = failures
             .iter()
+            .enumerate()
+            .map(|(attempt, failure)| {
+                let kind = if failure.panicked { "panicked" } else { "failed" };
+                format!("attempt {} {}: {}", attempt + 1, kind, failure.error)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let count = failures.len() as u32;
+        let correlation_id = message.correlation_id.map(|id| id.to_string());
+        warn!("Quarantining poison message {} after {} handler failures in {}", message.id, count, subscriber);
+        self.add_message(
+            message,
+            DeadLetterReason::Quarantined { failures: count },
+            count,
+            vec![subscriber],
+            Some(details),
+            correlation_id,
+        )
+    }
+
+    /// Get all entries matching the filter, spilled (older) entries first
+    pub fn get_entries(&self, filter: &DeadLetterFilter) -> Vec<DeadLetterEntry> {
+        let mut matching: Vec<DeadLetterEntry> = self
+            .spill
+            .as_ref()
+            .map(|spill| spill.query(filter))
+            .unwrap_or_default()
+            .into_iter()
             .filter(|entry| self.matches_filter(entry, filter))
-            .cloned()
-            .collect()
+            .collect();
+
+        let entries = self.entries.read();
+        matching.extend(
+            entries
+                .iter()
+                .filter(|entry| self.matches_filter(entry, filter))
+                .cloned(),
+        );
+        matching
     }
 
     /// Get a specific entry by ID
     pub fn get_entry(&self, id: DeadLetterId) -> Option<DeadLetterEntry> {
-        let entries = self.entries.read();
-        let index = self.entry_index.read().get(&id).copied()?;
-        entries.get(index).cloned()
+        let in_memory = {
+            let entries = self.entries.read();
+            let index = self.entry_index.read().get(&id).copied();
+            index.and_then(|index| entries.get(index).cloned())
+        };
+        in_memory.or_else(|| self.spill.as_ref()?.get(id))
     }
 
     /// Mark entries for replay
@@ -324,7 +436,7 @@ impl DeadLetterQueue {
         let mut marked_count = 0;
 
         for entry in entries.iter_mut() {
-            if self.matches_filter(entry, filter) && !entry.marked_for_replay {
+            if self.matches_filter(entry, filter) && !entry.marked_for_replay && !entry.is_quarantined() {
                 entry.marked_for_replay = true;
                 marked_count += 1;
                 debug!("Marked entry {} for replay", entry.id);
@@ -348,7 +460,7 @@ impl DeadLetterQueue {
             let entries = self.entries.read();
             entries_to_process = entries
                 .iter()
-                .filter(|entry| entry.marked_for_replay)
+                .filter(|entry| entry.marked_for_replay && !entry.is_quarantined())
                 .take(self.config.replay_batch_size)
                 .cloned()
                 .collect();
@@ -412,24 +524,36 @@ impl DeadLetterQueue {
                 }
                 
                 debug!("Removed entry {} from dead letter queue", id);
+                self.clusters.write().forget(id);
                 return true;
             }
         }
         
-        false
+        // Not in memory: acknowledge it on disk
+        let acknowledged = self.spill.as_ref().is_some_and(|spill| spill.acknowledge(id));
+        if acknowledged {
+            self.clusters.write().forget(id);
+        }
+        acknowledged
     }
 
     /// Clear all entries
     pub fn clear(&self) {
         self.entries.write().clear();
         self.entry_index.write().clear();
+        self.clusters.write().clear();
+        if let Some(spill) = &self.spill {
+            if let Err(e) = spill.clear() {
+                error!("Failed to clear dead letter spill: {}", e);
+            }
+        }
         info!("Cleared all entries from dead letter queue");
     }
 
     /// Clean up old entries based on age
     pub fn cleanup_old_entries(&self) -> usize {
         let cutoff_time = SystemTime::now() - self.config.max_age;
-        let mut removed_count = 0;
+        let mut expired = self.spill.as_ref().map(|spill| spill.remove_older_than(cutoff_time)).unwrap_or_default();
 
         let mut entries = self.entries.write();
         let mut index_map = self.entry_index.write();
@@ -437,7 +561,7 @@ impl DeadLetterQueue {
         entries.retain(|entry| {
             if entry.timestamp < cutoff_time {
                 index_map.remove(&entry.id);
-                removed_count += 1;
+                expired.push(entry.id);
                 false
             } else {
                 true
@@ -450,6 +574,12 @@ impl DeadLetterQueue {
             index_map.insert(entry.id, index);
         }
 
+        let removed_count = expired.len();
+        let mut clusters = self.clusters.write();
+        for id in expired {
+            clusters.forget(id);
+        }
+
         if removed_count > 0 {
             info!("Cleaned up {} old entries from dead letter queue", removed_count);
         }
@@ -599,7 +729,9 @@ impl DeadLetterQueue {
         let entries = self.entries.read();
         let mut stats = self.stats.write();
 
-        stats.total_entries = entries.len();
+        stats.spilled_entries = self.spill.as_ref().map_or(0, |spill| spill.len());
+        stats.top_failing_flows = self.clusters.read().top(TOP_FAILING_FLOWS);
+        stats.total_entries = entries.len() + stats.spilled_entries;
 
         if !entries.is_empty() {
             let now = SystemTime::now();
@@ -861,4 +993,36 @@ mod tests {
         assert!(stats.entries_by_reason.len() > 0);
         assert!(stats.entries_by_module.len() > 0);
     }
+
+    #[test]
+    fn test_entries_beyond_the_limit_spill_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let dlq = DeadLetterQueue::new(DeadLetterQueueConfig {
+            max_entries: 2,
+            enable_persistence: false,
+            auto_replay: None,
+            spill: Some(DeadLetterSpillConfig::new(dir.path().join("spill.jsonl"))),
+            ..DeadLetterQueueConfig::default()
+        });
+
+        let ids: Vec<DeadLetterId> = [ModuleId::Storage, ModuleId::AnalysisEngine, ModuleId::Gamification]
+            .into_iter()
+            .map(|module| {
+                dlq.add_message(create_test_message(), DeadLetterReason::Shutdown, 0, vec![module], None, None)
+            })
+            .collect();
+
+        // The oldest entry left memory but is still found, first, by every lookup
+        let stats = dlq.stats();
+        assert_eq!((stats.total_entries, stats.spilled_entries), (3, 1));
+        assert_eq!(dlq.get_entry(ids[0]).unwrap().intended_recipients, vec![ModuleId::Storage]);
+        assert_eq!(dlq.get_entries(&DeadLetterFilter::default())[0].id, ids[0]);
+        let storage = DeadLetterFilter { modules: Some(vec![ModuleId::Storage]), ..Default::default() };
+        assert_eq!(dlq.get_entries(&storage).len(), 1);
+        assert_eq!(dlq.get_entry(ids[2]).unwrap().id, ids[2]);
+
+        assert!(dlq.remove_entry(ids[0]));
+        assert!(dlq.get_entry(ids[0]).is_none());
+        assert_eq!(dlq.stats().spilled_entries, 0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,439 @@
+//! Disk spill for the dead letter queue
+//!
+//! Entries pushed out of the in-memory queue are appended to a JSON-lines file
+//! instead of being dropped. An in-memory index by id, intended recipient,
+//! reason, and time finds them again without reading the whole file.
+//! Acknowledging an entry appends a tombstone; once acknowledged records take
+//! up more than `compact_ratio` of the file, it is rewritten with only the live
+//! entries.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{
+    ModuleId,
+    dead_letter_queue::{DeadLetterEntry, DeadLetterFilter, DeadLetterId},
+};
+
+/// Where and how much of the dead letter queue spills to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterSpillConfig {
+    /// Append-only spill file
+    pub path: PathBuf,
+
+    /// Oldest spilled entries are dropped once live entries exceed this many bytes
+    pub max_bytes: u64,
+
+    /// Share of the file taken by acknowledged entries that triggers compaction
+    pub compact_ratio: f64,
+}
+
+impl DeadLetterSpillConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 256 * 1024 * 1024,
+            compact_ratio: 0.5,
+        }
+    }
+}
+
+/// One line of the spill file
+#[derive(Debug, Serialize, Deserialize)]
+enum SpillRecord {
+    Entry(Box<DeadLetterEntry>),
+    Ack(DeadLetterId),
+}
+
+/// Where a spilled entry lives and what it is indexed under
+#[derive(Debug, Clone)]
+struct Located {
+    offset: u64,
+    len: u64,
+    timestamp: SystemTime,
+    recipients: Vec<ModuleId>,
+    reason: String,
+}
+
+#[derive(Debug, Default)]
+struct SpillIndex {
+    by_id: HashMap<DeadLetterId, Located>,
+    by_module: HashMap<ModuleId, HashSet<DeadLetterId>>,
+    by_reason: HashMap<String, HashSet<DeadLetterId>>,
+    by_time: BTreeSet<(SystemTime, DeadLetterId)>,
+    live_bytes: u64,
+    file_bytes: u64,
+}
+
+impl SpillIndex {
+    fn insert(&mut self, id: DeadLetterId, located: Located) {
+        for module in &located.recipients {
+            self.by_module.entry(*module).or_default().insert(id);
+        }
+        self.by_reason.entry(located.reason.clone()).or_default().insert(id);
+        self.by_time.insert((located.timestamp, id));
+        self.live_bytes += located.len;
+        self.by_id.insert(id, located);
+    }
+
+    fn remove(&mut self, id: DeadLetterId) -> Option<Located> {
+        let located = self.by_id.remove(&id)?;
+        for module in &located.recipients {
+            if let Some(ids) = self.by_module.get_mut(module) {
+                ids.remove(&id);
+            }
+        }
+        if let Some(ids) = self.by_reason.get_mut(&located.reason) {
+            ids.remove(&id);
+        }
+        self.by_time.remove(&(located.timestamp, id));
+        self.live_bytes -= located.len;
+        Some(located)
+    }
+
+    /// Ids that can match `filter`, narrowed by the indexed fields
+    fn candidates(&self, filter: &DeadLetterFilter) -> Vec<DeadLetterId> {
+        let now = SystemTime::now();
+        let newest = filter.min_age.and_then(|age| now.checked_sub(age)).unwrap_or(now);
+        let oldest = filter.max_age.and_then(|age| now.checked_sub(age)).unwrap_or(SystemTime::UNIX_EPOCH);
+
+        let modules: Option<HashSet<DeadLetterId>> = filter.modules.as_ref().map(|modules| {
+            modules.iter().filter_map(|module| self.by_module.get(module)).flatten().copied().collect()
+        });
+        let reasons: Option<HashSet<DeadLetterId>> = filter.reasons.as_ref().map(|reasons| {
+            reasons
+                .iter()
+                .filter_map(|reason| self.by_reason.get(&format!("{:?}", reason)))
+                .flatten()
+                .copied()
+                .collect()
+        });
+
+        self.by_time
+            .iter()
+            .filter(|(timestamp, _)| *timestamp >= oldest && *timestamp <= newest)
+            .map(|(_, id)| *id)
+            .filter(|id| modules.as_ref().is_none_or(|ids| ids.contains(id)))
+            .filter(|id| reasons.as_ref().is_none_or(|ids| ids.contains(id)))
+            .collect()
+    }
+}
+
+/// Append-only store for dead letters that no longer fit in memory
+pub(crate) struct DeadLetterSpill {
+    config: DeadLetterSpillConfig,
+    file: parking_lot::Mutex<File>,
+    index: parking_lot::Mutex<SpillIndex>,
+}
+
+impl DeadLetterSpill {
+    /// Open the spill file, rebuilding the index from what is already in it
+    pub fn open(config: DeadLetterSpillConfig) -> std::io::Result<Self> {
+        if let Some(parent) = config.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).read(true).append(true).open(&config.path)?;
+        let index = Self::scan(&file)?;
+        if !index.by_id.is_empty() {
+            info!("Reopened dead letter spill with {} entries", index.by_id.len());
+        }
+
+        Ok(Self {
+            config,
+            file: parking_lot::Mutex::new(file),
+            index: parking_lot::Mutex::new(index),
+        })
+    }
+
+    fn scan(file: &File) -> std::io::Result<SpillIndex> {
+        let mut index = SpillIndex::default();
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut offset = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let len = reader.read_line(&mut line)? as u64;
+            if len == 0 {
+                break;
+            }
+            match serde_json::from_str::<SpillRecord>(&line) {
+                Ok(SpillRecord::Entry(entry)) => index.insert(entry.id, Self::locate(&entry, offset, len)),
+                Ok(SpillRecord::Ack(id)) => {
+                    index.remove(id);
+                }
+                // A write cut short by a crash; everything before it is intact
+                Err(e) => warn!("Skipping unreadable dead letter spill record at byte {}: {}", offset, e),
+            }
+            offset += len;
+        }
+        index.file_bytes = offset;
+        Ok(index)
+    }
+
+    fn locate(entry: &DeadLetterEntry, offset: u64, len: u64) -> Located {
+        Located {
+            offset,
+            len,
+            timestamp: entry.timestamp,
+            recipients: entry.intended_recipients.clone(),
+            reason: format!("{:?}", entry.reason),
+        }
+    }
+
+    fn append(&self, index: &mut SpillIndex, record: &SpillRecord) -> std::io::Result<(u64, u64)> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut file = self.file.lock();
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(&line)?;
+        index.file_bytes = offset + line.len() as u64;
+        Ok((offset, line.len() as u64))
+    }
+
+    /// Move an entry out of memory onto disk; returns the older entries dropped to
+    /// stay under `max_bytes`
+    pub fn spill(&self, entry: &DeadLetterEntry) -> std::io::Result<Vec<DeadLetterId>> {
+        let mut index = self.index.lock();
+        let (offset, len) = self.append(&mut index, &SpillRecord::Entry(Box::new(entry.clone())))?;
+        index.insert(entry.id, Self::locate(entry, offset, len));
+        debug!("Spilled dead letter {} to disk", entry.id);
+
+        let mut dropped = Vec::new();
+        while index.live_bytes > self.config.max_bytes && index.by_id.len() > 1 {
+            let Some(&(_, oldest)) = index.by_time.iter().next() else { break };
+            self.acknowledge_locked(&mut index, oldest)?;
+            dropped.push(oldest);
+        }
+        if !dropped.is_empty() {
+            warn!("Dropped {} oldest spilled dead letters to stay under {} bytes", dropped.len(), self.config.max_bytes);
+        }
+        self.compact_if_needed(&mut index)?;
+        Ok(dropped)
+    }
+
+    pub fn get(&self, id: DeadLetterId) -> Option<DeadLetterEntry> {
+        let index = self.index.lock();
+        let located = index.by_id.get(&id)?;
+        self.read(located)
+            .inspect_err(|e| warn!("Failed to read spilled dead letter {}: {}", id, e))
+            .ok()
+    }
+
+    /// Spilled entries that can match `filter`, oldest first; the caller applies the full filter
+    pub fn query(&self, filter: &DeadLetterFilter) -> Vec<DeadLetterEntry> {
+        let index = self.index.lock();
+        index
+            .candidates(filter)
+            .into_iter()
+            .filter_map(|id| index.by_id.get(&id).and_then(|located| self.read(located).ok()))
+            .collect()
+    }
+
+    /// Mark an entry as handled; it is removed from disk at the next compaction
+    pub fn acknowledge(&self, id: DeadLetterId) -> bool {
+        let mut index = self.index.lock();
+        let acknowledged = match self.acknowledge_locked(&mut index, id) {
+            Ok(acknowledged) => acknowledged,
+            Err(e) => {
+                warn!("Failed to acknowledge spilled dead letter {}: {}", id, e);
+                return false;
+            }
+        };
+        if acknowledged {
+            if let Err(e) = self.compact_if_needed(&mut index) {
+                warn!("Failed to compact dead letter spill: {}", e);
+            }
+        }
+        acknowledged
+    }
+
+    fn acknowledge_locked(&self, index: &mut SpillIndex, id: DeadLetterId) -> std::io::Result<bool> {
+        if index.remove(id).is_none() {
+            return Ok(false);
+        }
+        self.append(index, &SpillRecord::Ack(id))?;
+        Ok(true)
+    }
+
+    /// Acknowledge every spilled entry older than `cutoff`, returning their ids
+    pub fn remove_older_than(&self, cutoff: SystemTime) -> Vec<DeadLetterId> {
+        let mut index = self.index.lock();
+        let expired: Vec<DeadLetterId> =
+            index.by_time.iter().take_while(|(timestamp, _)| *timestamp < cutoff).map(|(_, id)| *id).collect();
+        let mut removed = Vec::new();
+        for id in expired {
+            match self.acknowledge_locked(&mut index, id) {
+                Ok(true) => removed.push(id),
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Failed to expire spilled dead letter {}: {}", id, e);
+                    break;
+                }
+            }
+        }
+        if let Err(e) = self.compact_if_needed(&mut index) {
+            warn!("Failed to compact dead letter spill: {}", e);
+        }
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.lock().by_id.len()
+    }
+
+    /// Drop every spilled entry
+    pub fn clear(&self) -> std::io::Result<()> {
+        let mut index = self.index.lock();
+        self.file.lock().set_len(0)?;
+        *index = SpillIndex::default();
+        Ok(())
+    }
+
+    fn read(&self, located: &Located) -> std::io::Result<DeadLetterEntry> {
+        let mut buffer = vec![0; located.len as usize];
+        {
+            let mut file = self.file.lock();
+            file.seek(SeekFrom::Start(located.offset))?;
+            file.read_exact(&mut buffer)?;
+        }
+        match serde_json::from_slice(&buffer)? {
+            SpillRecord::Entry(entry) => Ok(*entry),
+            SpillRecord::Ack(_) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "index points at a tombstone")),
+        }
+    }
+
+    fn compact_if_needed(&self, index: &mut SpillIndex) -> std::io::Result<()> {
+        let dead_bytes = index.file_bytes - index.live_bytes;
+        if dead_bytes == 0 || (dead_bytes as f64) < index.file_bytes as f64 * self.config.compact_ratio {
+            return Ok(());
+        }
+        self.compact(index)
+    }
+
+    /// Rewrite the file with only live entries, oldest first
+    fn compact(&self, index: &mut SpillIndex) -> std::io::Result<()> {
+        let before = index.file_bytes;
+        let live: Vec<DeadLetterEntry> = index
+            .by_time
+            .iter()
+            .filter_map(|(_, id)| index.by_id.get(id))
+            .map(|located| self.read(located))
+            .collect::<std::io::Result<_>>()?;
+
+        let tmp = self.config.path.with_extension("compact");
+        let mut compacted = SpillIndex::default();
+        {
+            let mut writer = std::io::BufWriter::new(File::create(&tmp)?);
+            let mut offset = 0;
+            for entry in live {
+                let mut line = serde_json::to_vec(&SpillRecord::Entry(Box::new(entry.clone())))?;
+                line.push(b'\n');
+                writer.write_all(&line)?;
+                compacted.insert(entry.id, Self::locate(&entry, offset, line.len() as u64));
+                offset += line.len() as u64;
+            }
+            writer.flush()?;
+            compacted.file_bytes = offset;
+        }
+
+        let mut file = self.file.lock();
+        std::fs::rename(&tmp, &self.config.path)?;
+        *file = OpenOptions::new().read(true).append(true).open(&self.config.path)?;
+        *index = compacted;
+        debug!("Compacted dead letter spill from {} to {} bytes", before, index.file_bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BusMessage, MessagePayload, dead_letter_queue::DeadLetterReason};
+    use std::time::Duration;
+
+    fn entry(recipient: ModuleId, reason: DeadLetterReason) -> DeadLetterEntry {
+        DeadLetterEntry {
+            id: uuid::Uuid::new_v4(),
+            message: BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture)),
+            reason,
+            timestamp: SystemTime::now(),
+            retry_count: 3,
+            intended_recipients: vec![recipient],
+            error_details: None,
+            correlation_id: None,
+            replay_count: 0,
+            marked_for_replay: false,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_spilled_entries_are_indexed_and_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DeadLetterSpillConfig::new(dir.path().join("dead_letters.jsonl"));
+        let timeout = DeadLetterReason::DeliveryTimeout { timeout: Duration::from_secs(5) };
+
+        let spill = DeadLetterSpill::open(config.clone()).unwrap();
+        let storage = entry(ModuleId::Storage, timeout.clone());
+        let analysis = entry(ModuleId::AnalysisEngine, DeadLetterReason::Shutdown);
+        spill.spill(&storage).unwrap();
+        spill.spill(&analysis).unwrap();
+        assert_eq!(spill.get(analysis.id).unwrap().message.id, analysis.message.id);
+
+        let by_module = DeadLetterFilter { modules: Some(vec![ModuleId::Storage]), ..Default::default() };
+        let found = spill.query(&by_module);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, storage.id);
+        let by_reason = DeadLetterFilter { reasons: Some(vec![timeout]), ..Default::default() };
+        assert_eq!(spill.query(&by_reason)[0].id, storage.id);
+        let too_old = DeadLetterFilter { min_age: Some(Duration::from_secs(60)), ..Default::default() };
+        assert!(spill.query(&too_old).is_empty());
+
+        assert!(spill.acknowledge(storage.id));
+        drop(spill);
+
+        let reopened = DeadLetterSpill::open(config).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert!(reopened.get(storage.id).is_none());
+        assert_eq!(reopened.get(analysis.id).unwrap().reason, DeadLetterReason::Shutdown);
+    }
+
+    #[test]
+    fn test_acknowledged_entries_are_compacted_and_size_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dead_letters.jsonl");
+        let spill = DeadLetterSpill::open(DeadLetterSpillConfig::new(&path)).unwrap();
+
+        let entries: Vec<DeadLetterEntry> =
+            (0..4).map(|_| entry(ModuleId::Storage, DeadLetterReason::Shutdown)).collect();
+        for entry in &entries {
+            spill.spill(entry).unwrap();
+        }
+        let full_size = std::fs::metadata(&path).unwrap().len();
+
+        // Half the entries acknowledged crosses the default 0.5 ratio
+        spill.acknowledge(entries[0].id);
+        spill.acknowledge(entries[1].id);
+        assert!(std::fs::metadata(&path).unwrap().len() < full_size);
+        assert_eq!(spill.len(), 2);
+        assert_eq!(spill.get(entries[3].id).unwrap().id, entries[3].id);
+
+        // Room for about one entry: spilling more drops the oldest
+        let capped = DeadLetterSpill::open(DeadLetterSpillConfig {
+            max_bytes: full_size / 4 + 1,
+            ..DeadLetterSpillConfig::new(dir.path().join("capped.jsonl"))
+        })
+        .unwrap();
+        capped.spill(&entries[0]).unwrap();
+        assert_eq!(capped.spill(&entries[1]).unwrap(), vec![entries[0].id]);
+        assert!(capped.get(entries[0].id).is_none());
+        assert!(capped.get(entries[1].id).is_some());
+    }
+}
@@ -0,0 +1,202 @@
+//! Publisher-side deduplication
+//!
+//! A publish that times out may or may not have reached the bus, so the
+//! publisher retries it. If the message carries an idempotency token
+//! ([`BusMessage::with_idempotency_token`]), the bus remembers the token per
+//! publisher for `window`; a second publish with the same token inside the
+//! window is dropped and gets the first publish's `MessageId` back, so
+//! subscribers see the message once and the publisher can't tell the two
+//! calls apart. Messages without a token are never deduplicated.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{metrics::MetricsCollector, BusMessage, EventBusResult, MessageId, ModuleId};
+
+/// How long idempotency tokens are remembered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// A token seen again within this window is a duplicate
+    pub window: Duration,
+    /// Tokens remembered per publisher; the oldest are forgotten first
+    pub max_tokens_per_publisher: usize,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(300),
+            max_tokens_per_publisher: 10_000,
+        }
+    }
+}
+
+/// Tokens one publisher used recently
+#[derive(Debug, Default)]
+struct PublisherTokens {
+    ids: HashMap<String, MessageId>,
+    /// Tokens by first use, oldest first
+    order: VecDeque<(Instant, String)>,
+}
+
+impl PublisherTokens {
+    fn expire(&mut self, now: Instant, config: &DedupConfig) {
+        while let Some((seen, token)) = self.order.front() {
+            if now.duration_since(*seen) < config.window && self.order.len() <= config.max_tokens_per_publisher {
+                break;
+            }
+            self.ids.remove(token);
+            self.order.pop_front();
+        }
+    }
+}
+
+/// Remembers recent idempotency tokens per publisher
+#[derive(Debug, Default)]
+pub struct PublishDeduplicator {
+    config: DedupConfig,
+    publishers: parking_lot::Mutex<HashMap<ModuleId, PublisherTokens>>,
+}
+
+impl PublishDeduplicator {
+    pub fn new(config: DedupConfig) -> Self {
+        Self {
+            config,
+            publishers: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Claim `message`'s token for it
+    ///
+    /// Returns the id of the message that already holds the token if this
+    /// one is a duplicate, `None` if it should be published.
+    pub fn claim(&self, message: &BusMessage) -> Option<MessageId> {
+        let token = message.idempotency_token.as_ref()?;
+        let now = Instant::now();
+
+        let mut publishers = self.publishers.lock();
+        let tokens = publishers.entry(message.source).or_default();
+        tokens.expire(now, &self.config);
+
+        if let Some(original) = tokens.ids.get(token) {
+            return Some(*original);
+        }
+        tokens.ids.insert(token.clone(), message.id);
+        tokens.order.push_back((now, token.clone()));
+        tokens.expire(now, &self.config);
+        None
+    }
+
+    /// Give up `message`'s claim after its publish failed, so a retry goes through
+    pub fn release(&self, message_id: MessageId, source: ModuleId, token: &str) {
+        let mut publishers = self.publishers.lock();
+        if let Some(tokens) = publishers.get_mut(&source) {
+            if tokens.ids.get(token) == Some(&message_id) {
+                tokens.ids.remove(token);
+                tokens.order.retain(|(_, remembered)| remembered != token);
+            }
+        }
+    }
+
+    /// Run `publish` on `message` unless it duplicates a recent publish
+    ///
+    /// A duplicate is dropped and answered with the original's id. If
+    /// `publish` fails, the token is released so a retry goes through.
+    pub(crate) async fn publish_once<F, Fut>(
+        &self,
+        message: BusMessage,
+        metrics: &MetricsCollector,
+        publish: F,
+    ) -> EventBusResult<MessageId>
+    where
+        F: FnOnce(BusMessage) -> Fut,
+        Fut: Future<Output = EventBusResult<MessageId>>,
+    {
+        if let Some(original) = self.claim(&message) {
+            debug!("Dropping duplicate publish {} from {}, already published as {}", message.id, message.source, original);
+            metrics.record_duplicate_publish();
+            return Ok(original);
+        }
+
+        let (message_id, source, token) = (message.id, message.source, message.idempotency_token.clone());
+        let result = publish(message).await;
+        if let (Err(_), Some(token)) = (&result, &token) {
+            self.release(message_id, source, token);
+        }
+        result
+    }
+
+    /// Tokens currently remembered for `publisher`
+    pub fn remembered(&self, publisher: ModuleId) -> usize {
+        self.publishers.lock().get(&publisher).map_or(0, |tokens| tokens.ids.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_event_bus_with_config, EventBusConfig, EventBusTrait, MessagePayload};
+    use futures::StreamExt;
+
+    fn ready(source: ModuleId, token: &str) -> BusMessage {
+        BusMessage::new(source, MessagePayload::ModuleReady(source)).with_idempotency_token(token)
+    }
+
+    #[test]
+    fn test_tokens_are_per_publisher_and_expire() {
+        let dedup = PublishDeduplicator::new(DedupConfig {
+            window: Duration::from_millis(50),
+            max_tokens_per_publisher: 2,
+        });
+
+        let first = ready(ModuleId::Storage, "batch-1");
+        assert_eq!(dedup.claim(&first), None);
+        assert_eq!(dedup.claim(&ready(ModuleId::Storage, "batch-1")), Some(first.id));
+        // Another publisher may use the same token
+        assert_eq!(dedup.claim(&ready(ModuleId::DataCapture, "batch-1")), None);
+        // No token, no deduplication
+        let untokened = BusMessage::new(ModuleId::Storage, MessagePayload::ModuleReady(ModuleId::Storage));
+        assert_eq!(dedup.claim(&untokened), None);
+        assert_eq!(dedup.claim(&untokened), None);
+
+        // Past the cap the oldest token is forgotten
+        dedup.claim(&ready(ModuleId::Storage, "batch-2"));
+        dedup.claim(&ready(ModuleId::Storage, "batch-3"));
+        assert_eq!(dedup.remembered(ModuleId::Storage), 2);
+        assert_eq!(dedup.claim(&ready(ModuleId::Storage, "batch-1")), None);
+
+        // Past the window every token is forgotten
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(dedup.claim(&ready(ModuleId::Storage, "batch-3")), None);
+
+        // A released claim lets the retry through
+        let failed = ready(ModuleId::Storage, "batch-4");
+        assert_eq!(dedup.claim(&failed), None);
+        dedup.release(failed.id, ModuleId::Storage, "batch-4");
+        assert_eq!(dedup.claim(&ready(ModuleId::Storage, "batch-4")), None);
+    }
+
+    #[tokio::test]
+    async fn test_retried_publish_returns_original_id_and_delivers_once() {
+        let bus = create_event_bus_with_config(EventBusConfig::default()).unwrap();
+        bus.start().await.unwrap();
+        let (_, mut messages) = bus
+            .subscribe_stream(ModuleId::Orchestrator, crate::MessageFilter::all(), crate::DeliveryMode::BestEffort)
+            .unwrap();
+
+        let original = bus.publish(ready(ModuleId::Storage, "ready-1")).await.unwrap();
+        let retried = bus.publish(ready(ModuleId::Storage, "ready-1")).await.unwrap();
+        assert_eq!(retried, original);
+        let scheduled = bus.publish_after(ready(ModuleId::Storage, "ready-1"), Duration::from_secs(60)).await.unwrap();
+        assert_eq!(scheduled, original);
+        assert!(!bus.cancel_scheduled(original).await.unwrap());
+
+        let delivered = tokio::time::timeout(Duration::from_secs(2), messages.next()).await.unwrap().unwrap();
+        assert_eq!(delivered.id, original);
+        assert!(tokio::time::timeout(Duration::from_millis(200), messages.next()).await.is_err());
+        assert_eq!(bus.metrics().await.unwrap().duplicate_publishes, 2);
+    }
+}
@@ -0,0 +1,166 @@
+//! Graceful drain on shutdown
+//!
+//! Shutting a bus down first closes it to new publishes (Critical messages are
+//! still let through), then delivers what is already queued until the queue is
+//! empty or the drain deadline passes. Messages that still could not be
+//! delivered go to the dead letter queue with [`DeadLetterReason::Shutdown`].
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    BusMessage, EventBusError, EventBusResult, MessagePriority,
+    dead_letter_queue::{DeadLetterQueue, DeadLetterReason},
+    router::MessageRouter,
+};
+
+/// What happened to in-flight messages while a bus shut down
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrainSummary {
+    /// Queued messages delivered to every subscriber during the drain
+    pub delivered: usize,
+    /// Messages moved to the dead letter queue because they could not be delivered
+    pub dead_lettered: usize,
+    /// Open aggregation windows sent as digests
+    pub digests_flushed: usize,
+    /// Non-critical publishes turned away while draining
+    pub rejected_publishes: u64,
+    /// Whether the deadline passed with messages still queued
+    pub timed_out: bool,
+    /// How long the drain took
+    pub elapsed: Duration,
+}
+
+/// Gates publishes while a bus drains and runs the drain itself
+#[derive(Debug, Default)]
+pub(crate) struct ShutdownGate {
+    draining: AtomicBool,
+    rejected: AtomicU64,
+}
+
+impl ShutdownGate {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Close the gate; false if a drain had already begun
+    pub(crate) fn begin(&self) -> bool {
+        !self.draining.swap(true, Ordering::SeqCst)
+    }
+
+    /// Reject everything but Critical messages once draining has begun
+    pub(crate) fn admit(&self, message: &BusMessage) -> EventBusResult<()> {
+        if self.draining.load(Ordering::SeqCst) && message.priority != MessagePriority::Critical {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(EventBusError::BusShuttingDown);
+        }
+        Ok(())
+    }
+
+    /// Drain `router` within `deadline`, dead-lettering whatever is left
+    pub(crate) async fn drain(
+        &self,
+        router: &MessageRouter,
+        deadline: Duration,
+        dead_letters: &DeadLetterQueue,
+    ) -> DrainSummary {
+        let started = Instant::now();
+        let drain = router.drain(deadline).await;
+
+        let dead_lettered = drain.undelivered.len();
+        for (message, recipients) in drain.undelivered {
+            dead_letters.add_message(message, DeadLetterReason::Shutdown, 0, recipients, None, None);
+        }
+
+        let summary = DrainSummary {
+            delivered: drain.delivered,
+            dead_lettered,
+            digests_flushed: drain.digests_flushed,
+            rejected_publishes: self.rejected.load(Ordering::Relaxed),
+            timed_out: drain.timed_out,
+            elapsed: started.elapsed(),
+        };
+
+        if summary.dead_lettered > 0 || summary.timed_out {
+            warn!(
+                "Drained event bus in {:?}: delivered {}, dead-lettered {}, rejected {}{}",
+                summary.elapsed,
+                summary.delivered,
+                summary.dead_lettered,
+                summary.rejected_publishes,
+                if summary.timed_out { " (deadline reached)" } else { "" }
+            );
+        } else {
+            info!(
+                "🚰 Drained event bus in {:?}: delivered {}, rejected {}",
+                summary.elapsed, summary.delivered, summary.rejected_publishes
+            );
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        MessagePayload, ModuleId,
+        router::RouterConfig,
+        subscription::{DeliveryMode, MessageFilter, Subscription},
+    };
+
+    fn ready(priority: MessagePriority) -> BusMessage {
+        BusMessage::with_priority(
+            ModuleId::Orchestrator,
+            MessagePayload::ModuleReady(ModuleId::Storage),
+            priority,
+        )
+    }
+
+    #[test]
+    fn test_gate_admits_only_critical_messages_while_draining() {
+        let gate = ShutdownGate::new();
+        assert!(gate.admit(&ready(MessagePriority::Normal)).is_ok());
+
+        assert!(gate.begin());
+        assert!(!gate.begin());
+        assert!(matches!(gate.admit(&ready(MessagePriority::High)), Err(EventBusError::BusShuttingDown)));
+        assert!(gate.admit(&ready(MessagePriority::Critical)).is_ok());
+        assert_eq!(gate.rejected.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_messages_are_dead_lettered_on_drain() {
+        let router = MessageRouter::new(RouterConfig::default());
+        router.start().await.unwrap();
+
+        // Storage stops taking messages, so whatever is buffered for it is stranded
+        router.subscription_manager().pause_module(ModuleId::Storage, 10);
+        let (sender, _receiver) = crossbeam_channel::bounded(10);
+        router.subscription_manager().add_subscription(Subscription::new(
+            ModuleId::Storage,
+            MessageFilter::all(),
+            DeliveryMode::BestEffort,
+            sender,
+        ));
+        router.publish(ready(MessagePriority::Normal)).await.unwrap();
+
+        let dead_letters = DeadLetterQueue::new(crate::dead_letter_queue::DeadLetterQueueConfig {
+            enable_persistence: false,
+            auto_replay: None,
+            ..Default::default()
+        });
+        let gate = ShutdownGate::new();
+        gate.begin();
+        let summary = gate.drain(&router, Duration::from_secs(1), &dead_letters).await;
+
+        assert_eq!(summary.dead_lettered, 1);
+        assert!(!summary.timed_out);
+        let entries = dead_letters.get_entries(&Default::default());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, DeadLetterReason::Shutdown);
+        assert_eq!(entries[0].intended_recipients, vec![ModuleId::Storage]);
+    }
+}
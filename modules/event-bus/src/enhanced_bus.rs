@@ -7,21 +7,29 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use crossbeam_channel::{bounded, Receiver};
-use tracing::{debug, info, warn, error};
+use tracing::{debug, info, warn, error, Instrument};
 
 use crate::{
     BusMessage, EventBusConfig, EventBusError, EventBusResult, EventBusTrait,
-    MessageId, ModuleId, SubscriptionId,
-    subscription::{DeliveryMode, MessageFilter, Subscription},
+    MessageId, MessagePayload, MessagePriority, ModuleId, SubscriptionId,
+    message::PoisonMessageDetected,
+    subscription::{DeliveryMode, MessageFilter, ReplaySummary, Subscription},
     router::{MessageRouter, RouterConfig},
-    metrics::BusMetrics,
+    metrics::{BusMetrics, FlowKey},
     registry::{ModuleRegistry, ModuleInfo, RegistryConfig},
     circuit_breaker::{CircuitBreakerRegistry, CircuitBreakerConfig},
     retry::{RetryExecutor, RetryConfig},
     dead_letter_queue::{DeadLetterQueue, DeadLetterReason},
     error_logging::{ErrorLogger, ErrorContext, ErrorSeverity, ErrorCategory, CorrelationId},
     recovery::{RecoverySystem, DefaultRecoveryExecutor},
+    authorization::Authorizer,
+    scheduler::MessageScheduler,
+    drain::{DrainSummary, ShutdownGate},
+    poison::{HandlerFailure, HandlerOutcome, PoisonDetector},
+    dedup::PublishDeduplicator,
+    validation::PublishValidator,
 };
 
 /// Enhanced event bus implementation with comprehensive error handling
@@ -42,6 +50,28 @@ pub struct EnhancedEventBus {
     
     /// Correlation tracking
     active_correlations: Arc<parking_lot::RwLock<HashMap<MessageId, CorrelationId>>>,
+    
+    /// Enforces the configured authorization policy, if any
+    authorizer: Option<Authorizer>,
+    
+    /// Runs the registered payload validators, if any
+    validator: Option<PublishValidator>,
+    
+    /// Holds messages published with a delay until they fall due
+    scheduler: Arc<MessageScheduler>,
+    
+    /// Turns away non-critical publishes once shutdown starts draining
+    shutdown_gate: ShutdownGate,
+    
+    /// Counts handler failures so poison messages are quarantined, not retried
+    poison: PoisonDetector,
+    
+    /// Drops retried publishes whose idempotency token was already used
+    dedup: PublishDeduplicator,
+    
+    /// Fails scripted deliveries in chaos tests
+    #[cfg(any(test, feature = "chaos"))]
+    faults: parking_lot::RwLock<Option<Arc<crate::chaos::FaultInjector>>>,
 }
 
 impl EnhancedEventBus {
@@ -52,6 +82,8 @@ impl EnhancedEventBus {
             delivery_timeout: config.delivery_timeout,
             worker_threads: 4,
             direct_channel_buffer: 1_000,
+            compression: config.compression.clone(),
+            memory_budget: config.memory_budget.clone(),
         };
 
         let router = Arc::new(MessageRouter::new(router_config));
@@ -71,6 +103,9 @@ impl EnhancedEventBus {
                 let dead_letter_queue = Arc::new(DeadLetterQueue::new(
                     crate::dead_letter_queue::DeadLetterQueueConfig {
                         max_entries: config.dead_letter_queue_size,
+                        spill: config.dead_letter_spill.clone(),
+                        enable_persistence: config.dead_letter_path.is_some(),
+                        persistence_path: config.dead_letter_path.as_ref().map(|path| path.to_string_lossy().into_owned()),
                         ..Default::default()
                     }
                 ));
@@ -93,6 +128,7 @@ impl EnhancedEventBus {
                     retry_executor.clone(),
                 ));
                 recovery_system.register_executor(default_executor);
+                recovery_system.set_confirmation_channel(router.clone());
                 
                 (circuit_breakers, retry_executor, dead_letter_queue, error_logger, recovery_system)
             } else {
@@ -113,6 +149,17 @@ impl EnhancedEventBus {
                 (circuit_breakers, retry_executor, dead_letter_queue, error_logger, recovery_system)
             };
 
+        let authorizer = config.authorization_policy.clone().map(|policy| {
+            Authorizer::new(policy, error_logger.clone(), router.metrics().clone())
+        });
+        let validator = config.validators.clone().map(|registry| {
+            PublishValidator::new(registry, error_logger.clone(), router.metrics().clone())
+        });
+
+        let scheduler = Arc::new(MessageScheduler::new(config.scheduled_messages_path.clone()));
+        let poison = PoisonDetector::new(config.poison.clone());
+        let dedup = PublishDeduplicator::new(config.deduplication.clone());
+
         Ok(Self {
             router,
             registry,
@@ -125,9 +172,26 @@ impl EnhancedEventBus {
             error_logger,
             recovery_system,
             active_correlations: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            authorizer,
+            validator,
+            scheduler,
+            shutdown_gate: ShutdownGate::new(),
+            poison,
+            dedup,
+            #[cfg(any(test, feature = "chaos"))]
+            faults: parking_lot::RwLock::new(None),
         })
     }
 
+    /// Fail deliveries to the router as `injector`'s `BusDelivery` rules say (`chaos` feature)
+    ///
+    /// Injected failures go through the same retries, dead lettering and
+    /// recovery as real ones.
+    #[cfg(any(test, feature = "chaos"))]
+    pub fn set_fault_injector(&self, injector: Arc<crate::chaos::FaultInjector>) {
+        *self.faults.write() = Some(injector);
+    }
+
     /// Start the enhanced event bus
     pub async fn start(&self) -> EventBusResult<()> {
         if *self.is_shutdown.read() {
@@ -138,6 +202,7 @@ impl EnhancedEventBus {
         
         // Start core router
         self.router.start().await?;
+        self.scheduler.start(self.router.clone());
         
         // Load dead letter queue from disk if persistence is enabled
         if let Err(e) = self.dead_letter_queue.load_from_disk() {
@@ -158,31 +223,37 @@ impl EnhancedEventBus {
         
         debug!("Publishing message {} with correlation {}", message.id, correlation_id);
 
-        // Check if any circuit breakers are open for this operation
+        // Check if any circuit breakers are open for this operation, per message type first
         let circuit_name = format!("publish_{:?}", message.source);
-        if let Some(breaker) = self.circuit_breakers.get(&circuit_name) {
-            if !breaker.is_healthy() {
-                let error = EventBusError::Internal("Circuit breaker is open".to_string());
-                operation_context.complete_with_error(
-                    message.source,
-                    ErrorSeverity::Warning,
-                    ErrorCategory::Network,
-                    error.to_string(),
-                );
-                return Err(error);
-            }
+        if let Some(breaker) = self.circuit_breakers.open_breaker_for(&circuit_name, message.message_type()) {
+            let error = EventBusError::Internal(format!("Circuit breaker {} is open", breaker.name()));
+            operation_context.complete_with_error(
+                message.source,
+                ErrorSeverity::Warning,
+                ErrorCategory::Network,
+                error.to_string(),
+            );
+            return Err(error);
         }
 
         // Attempt to publish with retry logic
         let message_id = message.id;
+        #[cfg(any(test, feature = "chaos"))]
+        let faults = self.faults.read().clone();
         let result = self.retry_executor.execute_with_default(|attempt| {
             let router = self.router.clone();
             let msg = message.clone();
+            #[cfg(any(test, feature = "chaos"))]
+            let faults = faults.clone();
             Box::pin(async move {
                 debug!("Publishing attempt {} for message {}", attempt.attempt_number, msg.id);
+                #[cfg(any(test, feature = "chaos"))]
+                if let Some(fault) = faults.and_then(|faults| faults.check(crate::chaos::FaultPoint::BusDelivery)) {
+                    return Err(fault.into());
+                }
                 router.publish(msg).await
             })
-        }).await;
+        }).instrument(operation_context.span().clone()).await;
 
         match result {
             Ok(_) => {
@@ -255,6 +326,7 @@ impl EnhancedEventBus {
             DeliveryMode::Reliable { .. } => self.config.max_queue_size / 4,
             DeliveryMode::BestEffort => self.config.max_queue_size / 8,
             DeliveryMode::LatestOnly => 1,
+            DeliveryMode::Aggregated(_) => 16,
         };
 
         let (sender, receiver) = bounded(buffer_size);
@@ -278,6 +350,72 @@ impl EnhancedEventBus {
         Ok(subscription_id)
     }
 
+    /// Run `subscriber`'s handler for `message`, quarantining the message once it keeps failing
+    ///
+    /// Panics are caught. When the message reaches `poison.max_failures` failures it moves to
+    /// the dead letter queue with every handler error, is no longer replayed, and a
+    /// `PoisonMessageDetected` event is published. Handler execution time is recorded on the
+    /// message's publisher → subscriber flow.
+    pub async fn handle_message<F, Fut, E>(&self, subscriber: ModuleId, message: &BusMessage, handler: F) -> HandlerOutcome
+    where
+        F: FnOnce(BusMessage) -> Fut,
+        Fut: std::future::Future<Output = Result<(), E>>,
+        E: std::fmt::Display,
+    {
+        let started = std::time::Instant::now();
+        let outcome = self.poison.run_async(message, subscriber, handler).await;
+        let flow = FlowKey { message_type: message.message_type(), publisher: message.source, subscriber };
+        self.router.metrics().record_handler_time(flow, started.elapsed());
+        if let HandlerOutcome::Poisoned(failures) = &outcome {
+            self.quarantine(message, subscriber, failures).await;
+        }
+        outcome
+    }
+
+    /// Like `handle_message`, but hedges `Critical` messages when `retry_config.hedging` is set
+    ///
+    /// If the handler is still running after the hedge delay, a second copy starts and the
+    /// first to succeed wins; the slower one is cancelled. Only use this with handlers that
+    /// are safe to run twice. Hedge counts and win rate are reported in `retry_stats`.
+    pub async fn handle_message_hedged<F, Fut, E>(&self, subscriber: ModuleId, message: &BusMessage, handler: F) -> HandlerOutcome
+    where
+        F: Fn(BusMessage) -> Fut,
+        Fut: std::future::Future<Output = Result<(), E>>,
+        E: std::fmt::Display,
+    {
+        if message.priority != MessagePriority::Critical {
+            return self.handle_message(subscriber, message, handler).await;
+        }
+        let executor = self.retry_executor.clone();
+        self.handle_message(subscriber, message, |message| async move {
+            executor.execute_hedged(|_| handler(message.clone())).await
+        })
+        .await
+    }
+
+    /// Move a poison message to the dead letter queue and announce it
+    async fn quarantine(&self, message: &BusMessage, subscriber: ModuleId, failures: &[HandlerFailure]) {
+        let dead_letter_id = self.dead_letter_queue.quarantine(message.clone(), subscriber, failures);
+        let event = PoisonMessageDetected {
+            message_id: message.id,
+            message_type: message.message_type(),
+            source: message.source,
+            subscriber,
+            failures: failures.len() as u32,
+            errors: failures.iter().map(|failure| failure.error.clone()).collect(),
+            dead_letter_id,
+            timestamp: Utc::now(),
+        };
+        let alert = BusMessage::with_priority(
+            ModuleId::EventBus,
+            MessagePayload::PoisonMessageDetected(event),
+            MessagePriority::High,
+        );
+        if let Err(e) = self.router.publish(alert).await {
+            warn!("Failed to announce poison message {}: {}", message.id, e);
+        }
+    }
+
     /// Get error handling statistics
     pub fn get_error_stats(&self) -> ErrorHandlingStats {
         ErrorHandlingStats {
@@ -372,15 +510,30 @@ impl EventBusTrait for EnhancedEventBus {
         if *self.is_shutdown.read() {
             return Err(EventBusError::BusShuttingDown);
         }
+        self.shutdown_gate.admit(&message)?;
 
-        if self.config.enable_error_handling {
-            self.publish_with_error_handling(message).await
-        } else {
-            // Fallback to simple publish
-            let message_id = message.id;
-            self.router.publish(message).await?;
-            Ok(message_id)
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_publish(&message)?;
+        }
+        if let Some(validator) = &self.validator {
+            validator.validate_publish(&message)?;
         }
+        if let MessagePayload::ConfirmationResponse(response) = &message.payload {
+            self.recovery_system.resolve_confirmation(response.clone());
+        }
+
+        self.dedup
+            .publish_once(message, self.router.metrics(), |message| async move {
+                if self.config.enable_error_handling {
+                    self.publish_with_error_handling(message).await
+                } else {
+                    // Fallback to simple publish
+                    let message_id = message.id;
+                    self.router.publish(message).await?;
+                    Ok(message_id)
+                }
+            })
+            .await
     }
 
     async fn subscribe(
@@ -393,6 +546,10 @@ impl EventBusTrait for EnhancedEventBus {
             return Err(EventBusError::BusShuttingDown);
         }
 
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_subscribe(subscriber, &filter)?;
+        }
+
         if self.config.enable_error_handling {
             self.subscribe_with_error_handling(subscriber, filter, delivery_mode).await
         } else {
@@ -401,6 +558,7 @@ impl EventBusTrait for EnhancedEventBus {
                 DeliveryMode::Reliable { .. } => self.config.max_queue_size / 4,
                 DeliveryMode::BestEffort => self.config.max_queue_size / 8,
                 DeliveryMode::LatestOnly => 1,
+                DeliveryMode::Aggregated(_) => 16,
             };
 
             let (sender, receiver) = bounded(buffer_size);
@@ -429,6 +587,65 @@ impl EventBusTrait for EnhancedEventBus {
         }
     }
 
+    async fn publish_at(&self, message: BusMessage, at: DateTime<Utc>) -> EventBusResult<MessageId> {
+        if *self.is_shutdown.read() {
+            return Err(EventBusError::BusShuttingDown);
+        }
+        self.shutdown_gate.admit(&message)?;
+
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_publish(&message)?;
+        }
+        if let Some(validator) = &self.validator {
+            validator.validate_publish(&message)?;
+        }
+
+        self.dedup
+            .publish_once(message, self.router.metrics(), |message| async move {
+                let message_id = message.id;
+                self.scheduler.schedule(message, at);
+                Ok(message_id)
+            })
+            .await
+    }
+
+    async fn cancel_scheduled(&self, message_id: MessageId) -> EventBusResult<bool> {
+        Ok(self.scheduler.cancel(message_id))
+    }
+
+    async fn pause_delivery(&self, module: ModuleId, capacity: usize) -> EventBusResult<()> {
+        if *self.is_shutdown.read() {
+            return Err(EventBusError::BusShuttingDown);
+        }
+
+        info!("Pausing delivery to {} (buffer {})", module, capacity);
+        self.router.subscription_manager().pause_module(module, capacity);
+        Ok(())
+    }
+
+    async fn resume_delivery(&self, module: ModuleId) -> EventBusResult<ReplaySummary> {
+        let (summary, undelivered) = self.router.subscription_manager().resume_module(module).unwrap_or_default();
+        for message in undelivered {
+            self.dead_letter_queue.add_message(
+                message,
+                DeadLetterReason::SubscriberUnavailable { subscriber: module },
+                0,
+                vec![module],
+                Some("No subscription took it when delivery resumed".to_string()),
+                None,
+            );
+        }
+        if summary.dropped > 0 || summary.failed > 0 {
+            warn!(
+                "Resumed delivery to {}: replayed {}, dropped {}, dead-lettered {}",
+                module, summary.replayed, summary.dropped, summary.failed
+            );
+        } else {
+            info!("Resumed delivery to {}: replayed {}", module, summary.replayed);
+        }
+        Ok(summary)
+    }
+
     async fn metrics(&self) -> EventBusResult<BusMetrics> {
         // Collect subscription counts per module
         let subscription_stats = self.router.subscription_manager().get_stats();
@@ -441,25 +658,26 @@ impl EventBusTrait for EnhancedEventBus {
         Ok(self.router.metrics().snapshot(subscription_counts))
     }
 
-    async fn shutdown(&self) -> EventBusResult<()> {
-        info!("Shutting down enhanced event bus");
-
-        {
-            let mut shutdown = self.is_shutdown.write();
-            if *shutdown {
-                return Ok(()); // Already shut down
-            }
-            *shutdown = true;
+    async fn shutdown(&self) -> EventBusResult<DrainSummary> {
+        if *self.is_shutdown.read() || !self.shutdown_gate.begin() {
+            return Ok(DrainSummary::default()); // Already shut down or draining
         }
 
+        info!("Shutting down enhanced event bus");
+
         // Cleanup dead letter queue before shutdown
         let cleanup_count = self.cleanup_dead_letters();
         if cleanup_count > 0 {
             info!("Cleaned up {} old dead letter entries during shutdown", cleanup_count);
         }
 
-        // Stop the router
-        self.router.stop().await?;
+        // Nothing scheduled fires once draining starts; it stays on disk for the next run
+        self.scheduler.stop();
+        let summary = self.shutdown_gate
+            .drain(&self.router, self.config.drain_timeout, &self.dead_letter_queue)
+            .await;
+
+        *self.is_shutdown.write() = true;
 
         // Clear all receivers
         self.module_receivers.write().clear();
@@ -468,7 +686,7 @@ impl EventBusTrait for EnhancedEventBus {
         self.active_correlations.write().clear();
 
         info!("Enhanced event bus shutdown complete");
-        Ok(())
+        Ok(summary)
     }
 }
 
@@ -556,6 +774,34 @@ mod tests {
         bus.shutdown().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_injected_delivery_failures_retry_then_dead_letter() {
+        use crate::chaos::{FaultInjector, FaultKind, FaultPoint, FaultRule, FaultSchedule};
+
+        let bus = create_enhanced_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let faults = Arc::new(FaultInjector::new(
+            FaultSchedule::new().with_rule(FaultRule::new(FaultPoint::BusDelivery, FaultKind::Fail).times(4)),
+        ));
+        bus.set_fault_injector(faults.clone());
+        // Entries other tests persisted are loaded on start
+        let dead_letters = bus.get_error_stats().dead_letter_stats.total_entries;
+
+        // Three attempts, all failed: the message is dead-lettered
+        let message = BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture));
+        assert!(bus.publish(message).await.is_err());
+        assert_eq!(faults.calls(FaultPoint::BusDelivery), 3);
+        assert_eq!(bus.get_error_stats().dead_letter_stats.total_entries, dead_letters + 1);
+
+        // One more failure, then the retry gets through
+        let message = BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture));
+        assert!(bus.publish(message).await.is_ok());
+        assert_eq!(faults.injected().len(), 4);
+        assert_eq!(bus.get_error_stats().dead_letter_stats.total_entries, dead_letters + 1);
+
+        bus.shutdown().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_error_handling_disabled() {
         let config = EventBusConfig {
@@ -624,4 +870,52 @@ mod tests {
 
         bus.shutdown().await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_poison_message_is_quarantined() {
+        let bus = create_enhanced_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (sender, alerts) = bounded(4);
+        bus.router.subscription_manager().add_subscription(Subscription::new(
+            ModuleId::Orchestrator,
+            MessageFilter::types(vec![crate::MessageType::PoisonMessageDetected]),
+            DeliveryMode::BestEffort,
+            sender,
+        ));
+
+        let poison = BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture));
+        let mut outcomes = Vec::new();
+        for _ in 0..3 {
+            outcomes.push(
+                bus.handle_message(ModuleId::Storage, &poison, |_| async { Err::<(), _>("malformed payload") }).await,
+            );
+        }
+        assert!(matches!(outcomes[1], HandlerOutcome::Failed(_)));
+        assert!(matches!(outcomes[2], HandlerOutcome::Poisoned(_)));
+
+        let entry = bus
+            .dead_letter_queue()
+            .get_entries(&Default::default())
+            .into_iter()
+            .find(|entry| entry.message.id == poison.id)
+            .unwrap();
+        assert_eq!(entry.reason, DeadLetterReason::Quarantined { failures: 3 });
+        assert_eq!(entry.error_details.as_deref().unwrap().lines().count(), 3);
+        assert_eq!(bus.dead_letter_queue().mark_for_replay(&crate::dead_letter_queue::DeadLetterFilter {
+            correlation_id: entry.correlation_id.clone(),
+            modules: Some(vec![ModuleId::Storage]),
+            reasons: Some(vec![entry.reason.clone()]),
+            ..Default::default()
+        }), 0);
+
+        let alert = tokio::task::spawn_blocking(move || alerts.recv_timeout(std::time::Duration::from_secs(2)))
+            .await
+            .unwrap()
+            .unwrap();
+        let MessagePayload::PoisonMessageDetected(event) = alert.payload else { panic!("expected a poison alert") };
+        assert_eq!(event.message_id, poison.id);
+        assert_eq!(event.dead_letter_id, entry.id);
+
+        bus.shutdown().await.unwrap();
+    }
 }
\ No newline at end of file
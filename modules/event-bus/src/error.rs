@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 use thiserror::Error;
-use crate::{ModuleId, SubscriptionId};
+use crate::{authorization::BusAction, registry::InterfaceMismatch, validation::ValidationIssue, MessageType, ModuleId, SubscriptionId};
 
 /// Result type for event bus operations
 pub type EventBusResult<T> = Result<T, EventBusError>;
@@ -25,6 +25,9 @@ pub enum EventBusError {
     #[error("Queue full: current size {current_size}, max size {max_size}")]
     QueueFull { current_size: usize, max_size: usize },
 
+    #[error("Memory budget exceeded: {requested} bytes requested, {used} of {budget} bytes in use")]
+    MemoryBudgetExceeded { requested: usize, used: usize, budget: usize },
+
     #[error("Subscription {subscription_id} not found")]
     SubscriptionNotFound { subscription_id: SubscriptionId },
 
@@ -52,9 +55,42 @@ pub enum EventBusError {
     #[error("Module {module_id} not found")]
     ModuleNotFound { module_id: ModuleId },
 
+    #[error("Module {module_id} has invalid version {version:?}: {reason}")]
+    InvalidModuleVersion {
+        module_id: ModuleId,
+        version: String,
+        reason: String,
+    },
+
+    #[error(
+        "Module {module_id} is incompatible with registered modules: {}",
+        .mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    IncompatibleModule {
+        module_id: ModuleId,
+        mismatches: Vec<InterfaceMismatch>,
+    },
+
     #[error("Invalid health check response")]
     InvalidHealthCheckResponse,
 
+    #[error("Module {module} is not authorized to {action} {message_type:?} messages")]
+    Unauthorized {
+        module: ModuleId,
+        message_type: MessageType,
+        action: BusAction,
+    },
+
+    #[error(
+        "Module {module} published an invalid {message_type:?} payload: {}",
+        .issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidPayload {
+        module: ModuleId,
+        message_type: MessageType,
+        issues: Vec<ValidationIssue>,
+    },
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -70,6 +106,7 @@ impl EventBusError {
             EventBusError::SubscriberUnavailable { .. }
                 | EventBusError::DeliveryTimeout { .. }
                 | EventBusError::QueueFull { .. }
+                | EventBusError::MemoryBudgetExceeded { .. }
                 | EventBusError::ChannelSend(_)
         )
     }
@@ -80,6 +117,7 @@ impl EventBusError {
             EventBusError::SubscriberUnavailable { retry_after, .. } => Some(*retry_after),
             EventBusError::DeliveryTimeout { .. } => Some(Duration::from_millis(100)),
             EventBusError::QueueFull { .. } => Some(Duration::from_millis(50)),
+            EventBusError::MemoryBudgetExceeded { .. } => Some(Duration::from_millis(100)),
             _ => None,
         }
     }
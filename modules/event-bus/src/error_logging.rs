@@ -2,14 +2,36 @@
 //!
 //! Provides comprehensive error logging capabilities with structured data,
 //! correlation tracking, and contextual information for distributed debugging.
+//!
+//! Every logged error is a `tracing` event with `correlation_id`, `module`,
+//! `category` and `severity` fields, emitted inside the caller's span. The error is
+//! also recorded on that span when it declares the `error` fields (see
+//! [`ErrorLogger::operation_span`]), so collectors show it as part of the trace.
 
 use std::collections::HashMap;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
-use tracing::{error, warn, info, debug, span, Level, Span};
+use tracing::{debug, field, Level, Span};
 use uuid::Uuid;
 
+/// Target of the events `ErrorLogger` emits, for filtering and routing
+pub const ERROR_EVENT_TARGET: &str = "skelly_jelly_event_bus::errors";
+
+/// Emit a structured error event at the level matching an `ErrorSeverity`
+macro_rules! error_event {
+    ($severity:expr, $($fields:tt)+) => {
+        match $severity {
+            ErrorSeverity::Debug => tracing::event!(target: ERROR_EVENT_TARGET, Level::DEBUG, $($fields)+),
+            ErrorSeverity::Info => tracing::event!(target: ERROR_EVENT_TARGET, Level::INFO, $($fields)+),
+            ErrorSeverity::Warning => tracing::event!(target: ERROR_EVENT_TARGET, Level::WARN, $($fields)+),
+            ErrorSeverity::Error | ErrorSeverity::Critical | ErrorSeverity::Fatal => {
+                tracing::event!(target: ERROR_EVENT_TARGET, Level::ERROR, $($fields)+)
+            }
+        }
+    };
+}
+
 use crate::{ModuleId, MessageId, EventBusError};
 
 /// Unique identifier for correlating related operations
@@ -62,6 +84,8 @@ pub enum ErrorCategory {
     Resource,
     /// Authentication and authorization errors
     Security,
+    /// Publishes or subscriptions denied by the bus authorization policy
+    Authorization,
     /// Business logic validation errors
     Validation,
     /// External service integration errors
@@ -307,26 +331,51 @@ impl ErrorLogger {
             self.update_stats(context);
         }
 
-        // Create a tracing span for context
-        let span = span!(
-            Level::ERROR, // Use a constant level instead of dynamic
-            "error_event",
+        let message = self.sanitize_message(&context.error_message);
+
+        // Attach the error to the caller's span so it shows up in the same trace
+        let span = Span::current();
+        span.record("correlation_id", field::display(context.correlation_id));
+        span.record("error", message.as_str());
+        span.record("error.category", field::debug(&context.category));
+        span.record("error.severity", field::debug(context.severity));
+
+        // Format the body based on configuration; the fields are the same for every format
+        let body = match self.config.log_format {
+            LogFormat::Json => self.format_json(context, &message),
+            LogFormat::Human => self.format_human(context, &message),
+            LogFormat::KeyValue => self.format_key_value(context, &message),
+        };
+
+        error_event!(
+            context.severity,
             correlation_id = %context.correlation_id,
             trace_id = %context.trace_id,
-            module_id = ?context.module_id,
+            module = %context.module_id,
             operation = %context.operation,
-            error_category = ?context.category,
-            message_id = ?context.message_id,
+            category = ?context.category,
+            severity = ?context.severity,
+            error_code = context.error_code.as_deref(),
+            message_id = context.message_id.map(field::display),
+            duration_ms = context.operation_duration.map(|duration| duration.as_millis() as u64),
+            "{}",
+            body
         );
+    }
 
-        let _guard = span.enter();
-
-        // Format and log the error based on configuration
-        match self.config.log_format {
-            LogFormat::Json => self.log_json(context),
-            LogFormat::Human => self.log_human(context),
-            LogFormat::KeyValue => self.log_key_value(context),
-        }
+    /// A span for one bus operation, with the fields `log_error` records errors into
+    ///
+    /// Instrument the operation's futures with it (or log inside it) to tie errors
+    /// to the surrounding trace.
+    pub fn operation_span(correlation_id: CorrelationId, operation: &str) -> Span {
+        tracing::info_span!(
+            "bus_operation",
+            correlation_id = %correlation_id,
+            operation = %operation,
+            error = field::Empty,
+            error.category = field::Empty,
+            error.severity = field::Empty,
+        )
     }
 
     /// Log an error from EventBusError with automatic context creation
@@ -362,6 +411,7 @@ impl ErrorLogger {
             correlation_id,
             operation: operation.to_string(),
             start_time: Instant::now(),
+            span: Self::operation_span(correlation_id, operation),
             logger: self,
         }
     }
@@ -378,12 +428,13 @@ impl ErrorLogger {
     }
 
     /// Classify EventBusError for severity and category
-    fn classify_event_bus_error(&self, error: &EventBusError) -> (ErrorSeverity, ErrorCategory) {
+    pub(crate) fn classify_event_bus_error(&self, error: &EventBusError) -> (ErrorSeverity, ErrorCategory) {
         match error {
             EventBusError::SubscriberUnavailable { .. } => (ErrorSeverity::Warning, ErrorCategory::Network),
             EventBusError::MessageRejected { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
             EventBusError::DeliveryTimeout { .. } => (ErrorSeverity::Warning, ErrorCategory::Performance),
             EventBusError::QueueFull { .. } => (ErrorSeverity::Critical, ErrorCategory::Resource),
+            EventBusError::MemoryBudgetExceeded { .. } => (ErrorSeverity::Critical, ErrorCategory::Resource),
             EventBusError::SubscriptionNotFound { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
             EventBusError::InvalidFilter { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
             EventBusError::BusShuttingDown => (ErrorSeverity::Info, ErrorCategory::Configuration),
@@ -393,14 +444,18 @@ impl ErrorLogger {
             EventBusError::Configuration(_) => (ErrorSeverity::Error, ErrorCategory::Configuration),
             EventBusError::ModuleAlreadyRegistered { .. } => (ErrorSeverity::Warning, ErrorCategory::Validation),
             EventBusError::ModuleNotFound { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
+            EventBusError::InvalidModuleVersion { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
+            EventBusError::IncompatibleModule { .. } => (ErrorSeverity::Critical, ErrorCategory::Integration),
             EventBusError::InvalidHealthCheckResponse => (ErrorSeverity::Warning, ErrorCategory::Integration),
+            EventBusError::Unauthorized { .. } => (ErrorSeverity::Error, ErrorCategory::Authorization),
+            EventBusError::InvalidPayload { .. } => (ErrorSeverity::Error, ErrorCategory::Validation),
             EventBusError::Internal(_) => (ErrorSeverity::Critical, ErrorCategory::Unknown),
             EventBusError::Io(_) => (ErrorSeverity::Error, ErrorCategory::Resource),
         }
     }
 
-    /// Log error in JSON format
-    fn log_json(&self, context: &ErrorContext) {
+    /// Format an error as JSON
+    fn format_json(&self, context: &ErrorContext, message: &str) -> String {
         let mut log_data = serde_json::json!({
             "timestamp": context.timestamp.duration_since(UNIX_EPOCH).unwrap().as_secs(),
             "correlation_id": context.correlation_id,
@@ -409,9 +464,13 @@ impl ErrorLogger {
             "operation": context.operation,
             "severity": context.severity,
             "category": context.category,
-            "message": self.sanitize_message(&context.error_message),
+            "message": message,
         });
 
+        if let Some(span_id) = Span::current().id() {
+            log_data["span_id"] = serde_json::Value::from(span_id.into_u64());
+        }
+
         if let Some(ref error_code) = context.error_code {
             log_data["error_code"] = serde_json::Value::String(error_code.clone());
         }
@@ -440,70 +499,45 @@ impl ErrorLogger {
             }
         }
 
-        match context.severity {
-            ErrorSeverity::Debug => debug!("{}", log_data),
-            ErrorSeverity::Info => info!("{}", log_data),
-            ErrorSeverity::Warning => warn!("{}", log_data),
-            ErrorSeverity::Error | ErrorSeverity::Critical | ErrorSeverity::Fatal => {
-                error!("{}", log_data)
-            }
-        }
+        log_data.to_string()
     }
 
-    /// Log error in human-readable format
-    fn log_human(&self, context: &ErrorContext) {
+    /// Format an error for humans
+    fn format_human(&self, context: &ErrorContext, message: &str) -> String {
         let duration_str = context.operation_duration
             .map(|d| format!(" ({}ms)", d.as_millis()))
             .unwrap_or_default();
 
-        let message = format!(
+        format!(
             "[{:?}] {} in {:?}: {}{} [correlation_id: {}]",
             context.severity,
             context.operation,
             context.module_id,
-            self.sanitize_message(&context.error_message),
+            message,
             duration_str,
             context.correlation_id
-        );
-
-        match context.severity {
-            ErrorSeverity::Debug => debug!("{}", message),
-            ErrorSeverity::Info => info!("{}", message),
-            ErrorSeverity::Warning => warn!("{}", message),
-            ErrorSeverity::Error | ErrorSeverity::Critical | ErrorSeverity::Fatal => {
-                error!("{}", message)
-            }
-        }
+        )
     }
 
-    /// Log error in key-value format
-    fn log_key_value(&self, context: &ErrorContext) {
+    /// Format an error as key-value pairs
+    fn format_key_value(&self, context: &ErrorContext, message: &str) -> String {
         let mut fields = vec![
             ("correlation_id", context.correlation_id.to_string()),
             ("module_id", format!("{:?}", context.module_id)),
             ("operation", context.operation.clone()),
             ("severity", format!("{:?}", context.severity)),
             ("category", format!("{:?}", context.category)),
-            ("message", self.sanitize_message(&context.error_message)),
+            ("message", message.to_string()),
         ];
 
         if let Some(duration) = context.operation_duration {
             fields.push(("duration_ms", duration.as_millis().to_string()));
         }
 
-        let log_str = fields.iter()
+        fields.iter()
             .map(|(k, v)| format!("{}={}", k, v))
             .collect::<Vec<_>>()
-            .join(" ");
-
-        match context.severity {
-            ErrorSeverity::Debug => debug!("{}", log_str),
-            ErrorSeverity::Info => info!("{}", log_str),
-            ErrorSeverity::Warning => warn!("{}", log_str),
-            ErrorSeverity::Error | ErrorSeverity::Critical | ErrorSeverity::Fatal => {
-                error!("{}", log_str)
-            }
-        }
+            .join(" ")
     }
 
     /// Sanitize sensitive data from messages
@@ -593,6 +627,7 @@ pub struct OperationContext<'a> {
     correlation_id: CorrelationId,
     operation: String,
     start_time: Instant,
+    span: Span,
     logger: &'a ErrorLogger,
 }
 
@@ -623,13 +658,18 @@ impl<'a> OperationContext<'a> {
             error_message,
         ).with_duration(duration);
 
-        self.logger.log_error(&context);
+        self.span.in_scope(|| self.logger.log_error(&context));
     }
 
     /// Get the correlation ID for this operation
     pub fn correlation_id(&self) -> CorrelationId {
         self.correlation_id
     }
+
+    /// The operation's span, for instrumenting the futures that do its work
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
 }
 
 /// Create a default error logger
@@ -772,4 +812,109 @@ mod tests {
         assert_eq!(truncated.len(), 20);
         assert!(truncated.ends_with("..."));
     }
+
+    /// Collects event and span fields as strings
+    #[derive(Clone, Default)]
+    struct Capture {
+        events: Arc<parking_lot::Mutex<Vec<HashMap<String, String>>>>,
+        span_records: Arc<parking_lot::Mutex<HashMap<String, String>>>,
+    }
+
+    struct Fields<'a>(&'a mut HashMap<String, String>);
+
+    impl tracing::field::Visit for Fields<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for Capture
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_event(&self, event: &tracing::Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let mut fields = HashMap::new();
+            event.record(&mut Fields(&mut fields));
+            fields.insert("level".to_string(), event.metadata().level().to_string());
+            fields.insert("target".to_string(), event.metadata().target().to_string());
+            if let Some(span) = ctx.event_span(event) {
+                fields.insert("span".to_string(), span.name().to_string());
+            }
+            self.events.lock().push(fields);
+        }
+
+        fn on_record(&self, _id: &tracing::span::Id, values: &tracing::span::Record<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            values.record(&mut Fields(&mut self.span_records.lock()));
+        }
+    }
+
+    fn with_capture(f: impl FnOnce()) -> Capture {
+        use tracing_subscriber::layer::SubscriberExt;
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, f);
+        capture
+    }
+
+    #[test]
+    fn test_errors_are_structured_tracing_events() {
+        let correlation_id = Uuid::new_v4();
+        let capture = with_capture(|| {
+            let context = ErrorContext::new(
+                correlation_id,
+                ModuleId::Storage,
+                "write_batch".to_string(),
+                ErrorSeverity::Warning,
+                ErrorCategory::Resource,
+                "disk almost full, token=abc".to_string(),
+            )
+            .with_duration(Duration::from_millis(12));
+            create_error_logger().log_error(&context);
+        });
+
+        let events = capture.events.lock();
+        let event = events.iter().find(|event| event["target"] == ERROR_EVENT_TARGET).unwrap();
+        assert_eq!(event["level"], "WARN");
+        assert_eq!(event["correlation_id"], correlation_id.to_string());
+        assert_eq!(event["module"], ModuleId::Storage.to_string());
+        assert_eq!(event["category"], "Resource");
+        assert_eq!(event["severity"], "Warning");
+        assert_eq!(event["duration_ms"], "12");
+        assert!(!event.contains_key("message_id"));
+
+        // The body is the JSON document, sanitized like before
+        let body: serde_json::Value = serde_json::from_str(&event["message"]).unwrap();
+        assert_eq!(body["operation"], "write_batch");
+        assert_eq!(body["message"], "disk almost full, token=***");
+    }
+
+    #[test]
+    fn test_operation_errors_are_recorded_on_the_operation_span() {
+        let correlation_id = Uuid::new_v4();
+        let capture = with_capture(|| {
+            let logger = create_error_logger();
+            let _outer = tracing::info_span!("handle_request").entered();
+            logger.start_operation(correlation_id, "publish_message").complete_with_error(
+                ModuleId::EventBus,
+                ErrorSeverity::Error,
+                ErrorCategory::Network,
+                "channel closed".to_string(),
+            );
+        });
+
+        let events = capture.events.lock();
+        let event = events.iter().find(|event| event["target"] == ERROR_EVENT_TARGET).unwrap();
+        assert_eq!(event["span"], "bus_operation");
+        assert_eq!(event["level"], "ERROR");
+
+        let span = capture.span_records.lock();
+        assert_eq!(span["correlation_id"], correlation_id.to_string());
+        assert_eq!(span["error"], "channel closed");
+        assert_eq!(span["error.category"], "Network");
+        assert_eq!(span["error.severity"], "Error");
+    }
 }
\ No newline at end of file
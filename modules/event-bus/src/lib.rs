@@ -13,24 +13,62 @@ pub mod registry;
 pub mod circuit_breaker;
 pub mod retry;
 pub mod dead_letter_queue;
+pub mod dead_letter_spill;
+pub mod dead_letter_clusters;
 pub mod error_logging;
 pub mod recovery;
 pub mod enhanced_bus;
+pub mod authorization;
+pub mod scheduler;
+pub mod drain;
+pub mod poison;
+pub mod dedup;
+pub mod validation;
+pub mod memory_budget;
+pub mod audit;
+pub mod acks;
+pub mod typed;
+pub mod stream;
+#[cfg(feature = "storage-bridge")]
+pub mod storage_bridge;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+#[cfg(any(test, feature = "chaos"))]
+pub mod chaos;
 
 // Re-export public API
 pub use bus::{EventBus, EventBusImpl, create_event_bus, create_event_bus_with_config};
 pub use error::{EventBusError, EventBusResult};
-pub use message::{BusMessage, MessagePayload, MessagePriority, ModuleId, MessageType};
-pub use subscription::{MessageFilter, SubscriptionId, DeliveryMode};
-pub use metrics::BusMetrics;
-pub use registry::{ModuleRegistry, ModuleInfo, ModuleStatus, HealthSummary, SystemHealth, RegistryConfig};
+pub use message::{BusMessage, MessagePayload, MessagePriority, ModuleId, MessageType, CompressedPayload, CompressionCodec, CompressionConfig};
+pub use subscription::{MessageFilter, SubscriptionId, DeliveryMode, OrderingMode, AggregationConfig, ReplaySummary, ConsumerGroup, GroupBalancing};
+pub use metrics::{BusMetrics, CompressionMetrics, FlowKey, FlowMetrics, FlowQuery};
+pub use registry::{ModuleRegistry, ModuleInfo, ModuleStatus, HealthSummary, SystemHealth, RegistryConfig, CompatibilityPolicy, InterfaceMismatch};
+pub use semver;
 
 // Re-export error handling components
-pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerRegistry, CircuitBreakerStats, CircuitState};
-pub use retry::{RetryExecutor, RetryConfig, RetryStats, RetryPolicy, create_retry_executor};
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerRegistry, CircuitBreakerStats, CircuitState, HalfOpenOverflow};
+pub use retry::{RetryExecutor, RetryConfig, RetryStats, RetryPolicy, HedgeConfig, create_retry_executor};
 pub use dead_letter_queue::{DeadLetterQueue, DeadLetterEntry, DeadLetterReason, DeadLetterStats, create_dead_letter_queue};
+pub use dead_letter_spill::DeadLetterSpillConfig;
+pub use dead_letter_clusters::FailureCluster;
 pub use error_logging::{ErrorLogger, ErrorContext, ErrorSeverity, ErrorCategory, CorrelationId, create_error_logger};
-pub use recovery::{RecoverySystem, RecoveryAction, RecoveryStrategy, EscalationLevel, RecoveryIncident, IncidentStatus};
+pub use recovery::{RecoverySystem, RecoveryAction, RecoveryStrategy, RecoveryHint, EscalationLevel, RecoveryIncident, IncidentStatus, IncidentEvent, IncidentEventKind, ConfirmationDecision, ConfirmationChannel, ActionRanking, RankedAction, ActionHistory};
+pub use authorization::{AuthorizationPolicy, BusAction};
+pub use drain::DrainSummary;
+pub use poison::{HandlerFailure, HandlerOutcome, PoisonConfig, PoisonDetector};
+pub use dedup::{DedupConfig, PublishDeduplicator};
+pub use validation::{PayloadValidator, ValidationIssue, ValidatorRegistry};
+pub use memory_budget::{MemoryBudgetConfig, MemoryLimitPolicy, MemorySpillConfig};
+pub use audit::{AuditConfig, AuditRecord, AuditSink, AuditStatus, DeliveryOutcome, MemoryAuditSink};
+#[cfg(feature = "storage-audit")]
+pub use audit::StorageAuditSink;
+pub use acks::DeliveryAcks;
+pub use stream::SubscriptionStream;
+#[cfg(feature = "storage-bridge")]
+pub use storage_bridge::{bridge_to_storage, decode_captured_event, encode_captured_event, publish_sequenced};
+pub use typed::{TypedPayload, TypedSubscription};
+#[cfg(any(test, feature = "chaos"))]
+pub use chaos::{FaultInjector, FaultKind, FaultPoint, FaultRule, FaultSchedule, InjectedFault};
 pub use enhanced_bus::{EnhancedEventBus, EnhancedEventBusArc, ErrorHandlingStats, create_enhanced_event_bus, create_enhanced_event_bus_with_config};
 
 use async_trait::async_trait;
@@ -59,11 +97,34 @@ pub trait EventBusTrait: Send + Sync {
     /// Unsubscribe from messages
     async fn unsubscribe(&self, subscription_id: SubscriptionId) -> EventBusResult<()>;
     
+    /// Hold messages for a module's subscriptions (e.g. while it restarts), keeping at most `capacity`
+    async fn pause_delivery(&self, module: ModuleId, capacity: usize) -> EventBusResult<()>;
+    
+    /// Replay held messages to a paused module in arrival order and resume normal delivery
+    ///
+    /// Messages go to the module's current subscriptions, so a module that
+    /// subscribed again while paused still gets them. Any nobody takes are dead-lettered.
+    async fn resume_delivery(&self, module: ModuleId) -> EventBusResult<ReplaySummary>;
+    
+    /// Publish a message once `delay` has passed
+    async fn publish_after(&self, message: BusMessage, delay: std::time::Duration) -> EventBusResult<MessageId> {
+        let delay = chrono::Duration::from_std(delay).map_err(|_| EventBusError::MessageRejected {
+            reason: format!("delay {:?} is out of range", delay),
+        })?;
+        self.publish_at(message, chrono::Utc::now() + delay).await
+    }
+    
+    /// Publish a message at `at`; a time already past publishes on the next scheduler tick
+    async fn publish_at(&self, message: BusMessage, at: chrono::DateTime<chrono::Utc>) -> EventBusResult<MessageId>;
+    
+    /// Drop a scheduled message before it is published; `false` if it was not pending
+    async fn cancel_scheduled(&self, message_id: MessageId) -> EventBusResult<bool>;
+    
     /// Get current bus metrics
     async fn metrics(&self) -> EventBusResult<BusMetrics>;
     
-    /// Shutdown the event bus gracefully
-    async fn shutdown(&self) -> EventBusResult<()>;
+    /// Shutdown the event bus gracefully, draining queued messages first
+    async fn shutdown(&self) -> EventBusResult<DrainSummary>;
 }
 
 /// Configuration for the event bus
@@ -81,6 +142,9 @@ pub struct EventBusConfig {
     /// Size of dead letter queue
     pub dead_letter_queue_size: usize,
     
+    /// Spill dead letters beyond `dead_letter_queue_size` to disk (`None` drops the oldest)
+    pub dead_letter_spill: Option<DeadLetterSpillConfig>,
+    
     /// Interval for collecting metrics
     pub metrics_interval: std::time::Duration,
     
@@ -101,6 +165,33 @@ pub struct EventBusConfig {
     
     /// Whether to enable comprehensive error handling
     pub enable_error_handling: bool,
+    
+    /// Which modules may publish and subscribe to which message types (`None` allows everything)
+    pub authorization_policy: Option<AuthorizationPolicy>,
+    
+    /// Payload validators run on every publish (`None` skips validation)
+    pub validators: Option<ValidatorRegistry>,
+    
+    /// File that keeps scheduled messages across restarts (`None` keeps them in memory only)
+    pub scheduled_messages_path: Option<std::path::PathBuf>,
+    
+    /// File that keeps dead letters across restarts (`None` keeps them in memory only)
+    pub dead_letter_path: Option<std::path::PathBuf>,
+    
+    /// How long shutdown keeps delivering queued messages before dead-lettering the rest
+    pub drain_timeout: std::time::Duration,
+    
+    /// Compress large payloads for subscribers that accept it (`None` never compresses)
+    pub compression: Option<CompressionConfig>,
+    
+    /// When a message that keeps failing in a handler is quarantined
+    pub poison: PoisonConfig,
+    
+    /// How long publishers' idempotency tokens are remembered
+    pub deduplication: DedupConfig,
+    
+    /// Byte limit for queued messages, rejecting or spilling past it (`None` only limits the count)
+    pub memory_budget: Option<MemoryBudgetConfig>,
 }
 
 impl Default for EventBusConfig {
@@ -110,6 +201,7 @@ impl Default for EventBusConfig {
             delivery_timeout: std::time::Duration::from_secs(5),
             max_retry_attempts: 3,
             dead_letter_queue_size: 1_000,
+            dead_letter_spill: None,
             metrics_interval: std::time::Duration::from_secs(10),
             slow_handler_threshold: std::time::Duration::from_millis(100),
             circuit_breaker_config: Some(CircuitBreakerConfig::default()),
@@ -117,6 +209,15 @@ impl Default for EventBusConfig {
             error_logging_config: Some(error_logging::ErrorLoggerConfig::default()),
             recovery_config: Some(recovery::RecoveryConfig::default()),
             enable_error_handling: true,
+            authorization_policy: None,
+            validators: None,
+            scheduled_messages_path: None,
+            dead_letter_path: None,
+            drain_timeout: std::time::Duration::from_secs(2),
+            compression: Some(CompressionConfig::default()),
+            poison: PoisonConfig::default(),
+            deduplication: DedupConfig::default(),
+            memory_budget: Some(MemoryBudgetConfig::default()),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,401 @@
+//! Byte accounting and a memory budget for the routing queues
+//!
+//! `max_queue_size` counts messages, but a few screenshot payloads can outweigh
+//! thousands of keystrokes. The router charges every queued message's estimated
+//! size to the queue holding it and keeps the total under a [`MemoryBudgetConfig`].
+//! A message that would go over budget is either rejected or spilled to a file
+//! and queued again once the workers have made room, depending on the
+//! [`MemoryLimitPolicy`]. Messages sent over a direct channel skip the routing
+//! queues and are not counted.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{metrics::MemoryMetrics, BusMessage, EventBusError, EventBusResult};
+
+/// How many bytes the routing queues may hold and what happens past that
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryBudgetConfig {
+    /// Most bytes all routing queues may hold together, by estimated message size
+    pub max_bytes: usize,
+
+    /// What to do with a message that does not fit
+    pub policy: MemoryLimitPolicy,
+}
+
+impl MemoryBudgetConfig {
+    /// Reject publishes beyond `max_bytes`
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            policy: MemoryLimitPolicy::Reject,
+        }
+    }
+
+    /// Spill messages beyond the budget to disk instead of rejecting them
+    pub fn with_spill(mut self, spill: MemorySpillConfig) -> Self {
+        self.policy = MemoryLimitPolicy::Spill(spill);
+        self
+    }
+}
+
+impl Default for MemoryBudgetConfig {
+    fn default() -> Self {
+        Self::new(64 * 1024 * 1024)
+    }
+}
+
+/// What the router does with a message that would take its queues over budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MemoryLimitPolicy {
+    /// Fail the publish with `EventBusError::MemoryBudgetExceeded`
+    Reject,
+    /// Write the message to a file and queue it once there is room
+    Spill(MemorySpillConfig),
+}
+
+/// Where messages over the memory budget wait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySpillConfig {
+    /// Scratch file; anything left in it from an earlier run is discarded
+    pub path: PathBuf,
+
+    /// Publishes are rejected once spilled messages take this many bytes on disk
+    pub max_bytes: u64,
+}
+
+impl MemorySpillConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Bytes held by each routing queue, checked against the budget
+pub(crate) struct QueueMemory {
+    budget: Option<MemoryBudgetConfig>,
+    queue_bytes: Vec<AtomicUsize>,
+    /// Bytes queued plus bytes reserved for messages about to be queued
+    total: AtomicUsize,
+    peak: AtomicUsize,
+    rejected_messages: AtomicU64,
+    rejected_bytes: AtomicU64,
+    spill: Option<parking_lot::Mutex<MessageSpill>>,
+    /// Messages in the spill, readable without its lock
+    spilled: AtomicUsize,
+}
+
+impl QueueMemory {
+    pub fn new(queues: usize, budget: Option<MemoryBudgetConfig>) -> Self {
+        let spill = match budget.as_ref().map(|budget| &budget.policy) {
+            Some(MemoryLimitPolicy::Spill(config)) => Some(parking_lot::Mutex::new(MessageSpill::new(config.clone()))),
+            _ => None,
+        };
+        Self {
+            budget,
+            queue_bytes: (0..queues).map(|_| AtomicUsize::new(0)).collect(),
+            total: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            rejected_messages: AtomicU64::new(0),
+            rejected_bytes: AtomicU64::new(0),
+            spill,
+            spilled: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reserve `bytes` if they fit the budget
+    ///
+    /// Empty queues take any message, so one larger than the whole budget is
+    /// never stuck.
+    pub fn try_reserve(&self, bytes: usize) -> bool {
+        let max = self.budget.as_ref().map_or(usize::MAX, |budget| budget.max_bytes);
+        let reserved = self.total.fetch_update(Ordering::AcqRel, Ordering::Acquire, |total| {
+            (total == 0 || total.saturating_add(bytes) <= max).then(|| total + bytes)
+        });
+        match reserved {
+            Ok(previous) => {
+                self.peak.fetch_max(previous + bytes, Ordering::Relaxed);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Give back a reservation that was not queued
+    pub fn cancel(&self, bytes: usize) {
+        self.total.fetch_sub(bytes, Ordering::AcqRel);
+    }
+
+    /// Charge a reserved message to the queue it went into
+    pub fn charge(&self, queue: usize, bytes: usize) {
+        self.queue_bytes[queue].fetch_add(bytes, Ordering::AcqRel);
+    }
+
+    /// Undo a charge for a message the queue did not take
+    pub fn uncharge(&self, queue: usize, bytes: usize) {
+        self.queue_bytes[queue].fetch_sub(bytes, Ordering::AcqRel);
+    }
+
+    /// A message left `queue`
+    pub fn release(&self, queue: usize, bytes: usize) {
+        self.queue_bytes[queue].fetch_sub(bytes, Ordering::AcqRel);
+        self.total.fetch_sub(bytes, Ordering::AcqRel);
+    }
+
+    pub fn used(&self) -> usize {
+        self.total.load(Ordering::Acquire)
+    }
+
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.budget.as_ref().map(|budget| budget.max_bytes)
+    }
+
+    pub fn record_rejection(&self, bytes: usize) {
+        self.rejected_messages.fetch_add(1, Ordering::Relaxed);
+        self.rejected_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Whether over-budget messages go to disk rather than being rejected
+    pub fn spills(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Messages waiting in the spill
+    pub fn spilled(&self) -> usize {
+        self.spilled.load(Ordering::Acquire)
+    }
+
+    /// Append a message to the spill, behind everything already there
+    pub fn spill(&self, message: &BusMessage, bytes: usize) -> EventBusResult<()> {
+        let Some(spill) = &self.spill else {
+            return Err(EventBusError::Internal("Memory budget has no spill configured".to_string()));
+        };
+        let mut spill = spill.lock();
+        spill.push(message, bytes)?;
+        self.spilled.store(spill.len(), Ordering::Release);
+        Ok(())
+    }
+
+    /// Take the oldest spilled message if it fits the budget, with its bytes reserved
+    pub fn unspill(&self) -> Option<(BusMessage, usize)> {
+        let mut spill = self.spill.as_ref()?.lock();
+        let bytes = spill.front_bytes()?;
+        if !self.try_reserve(bytes) {
+            return None;
+        }
+        let taken = spill.pop();
+        self.spilled.store(spill.len(), Ordering::Release);
+        if taken.is_none() {
+            self.cancel(bytes);
+        }
+        taken
+    }
+
+    /// Take the oldest spilled message regardless of the budget, reserving nothing
+    pub fn take_spilled(&self) -> Option<BusMessage> {
+        let mut spill = self.spill.as_ref()?.lock();
+        let taken = spill.pop();
+        self.spilled.store(spill.len(), Ordering::Release);
+        taken.map(|(message, _)| message)
+    }
+
+    /// Return a message taken with [`QueueMemory::unspill`] that could not be queued
+    ///
+    /// Its reservation is released and it becomes the next one taken.
+    pub fn put_back(&self, message: BusMessage, bytes: usize) {
+        self.cancel(bytes);
+        if let Some(spill) = &self.spill {
+            let mut spill = spill.lock();
+            spill.put_back = Some((message, bytes));
+            self.spilled.store(spill.len(), Ordering::Release);
+        }
+    }
+
+    pub fn metrics(&self) -> MemoryMetrics {
+        let queue_bytes: Vec<u64> = self.queue_bytes.iter().map(|bytes| bytes.load(Ordering::Relaxed) as u64).collect();
+        let spill_bytes = self.spill.as_ref().map_or(0, |spill| spill.lock().disk_bytes);
+        MemoryMetrics {
+            queue_memory_bytes: queue_bytes.iter().sum(),
+            queue_bytes,
+            peak_queue_memory_bytes: self.peak.load(Ordering::Relaxed) as u64,
+            budget_bytes: self.max_bytes().map(|max| max as u64),
+            spilled_messages: self.spilled() as u64,
+            spilled_bytes: spill_bytes,
+            rejected_messages: self.rejected_messages.load(Ordering::Relaxed),
+            rejected_bytes: self.rejected_bytes.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+}
+
+/// First-in first-out file of messages waiting for room in the queues
+struct MessageSpill {
+    config: MemorySpillConfig,
+    /// Opened, and emptied of any earlier run's messages, on the first spill
+    file: Option<File>,
+    read_offset: u64,
+    /// (line length, estimated size) of each message in the file, oldest first
+    entries: VecDeque<(u64, usize)>,
+    disk_bytes: u64,
+    /// A message taken out and handed back; it goes ahead of the file
+    put_back: Option<(BusMessage, usize)>,
+}
+
+impl MessageSpill {
+    fn new(config: MemorySpillConfig) -> Self {
+        Self {
+            config,
+            file: None,
+            read_offset: 0,
+            entries: VecDeque::new(),
+            disk_bytes: 0,
+            put_back: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len() + usize::from(self.put_back.is_some())
+    }
+
+    fn front_bytes(&self) -> Option<usize> {
+        match &self.put_back {
+            Some((_, bytes)) => Some(*bytes),
+            None => self.entries.front().map(|(_, bytes)| *bytes),
+        }
+    }
+
+    fn push(&mut self, message: &BusMessage, bytes: usize) -> EventBusResult<()> {
+        let mut line = serde_json::to_vec(message).map_err(|e| EventBusError::Serialization(e.to_string()))?;
+        line.push(b'\n');
+        if self.disk_bytes + line.len() as u64 > self.config.max_bytes {
+            return Err(EventBusError::MemoryBudgetExceeded {
+                requested: bytes,
+                used: self.disk_bytes as usize,
+                budget: self.config.max_bytes as usize,
+            });
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(Self::open(&self.config)?),
+        };
+        file.seek(SeekFrom::End(0)).and_then(|_| file.write_all(&line)).map_err(|e| EventBusError::Io(e.to_string()))?;
+        self.entries.push_back((line.len() as u64, bytes));
+        self.disk_bytes += line.len() as u64;
+        Ok(())
+    }
+
+    fn open(config: &MemorySpillConfig) -> EventBusResult<File> {
+        if let Some(parent) = config.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| EventBusError::Io(e.to_string()))?;
+        }
+        debug!("Spilling over-budget messages to {}", config.path.display());
+        OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .open(&config.path)
+            .map_err(|e| EventBusError::Io(e.to_string()))
+    }
+
+    /// Oldest message and its estimated size; unreadable records are skipped
+    fn pop(&mut self) -> Option<(BusMessage, usize)> {
+        if let Some(returned) = self.put_back.take() {
+            return Some(returned);
+        }
+        while let Some((len, bytes)) = self.entries.pop_front() {
+            let offset = self.read_offset;
+            self.read_offset += len;
+            self.disk_bytes -= len;
+            let read = self.read_line(offset);
+            if self.entries.is_empty() {
+                self.reset();
+            }
+            match read {
+                Ok(message) => return Some((message, bytes)),
+                Err(e) => warn!("Dropping unreadable spilled message at byte {}: {}", offset, e),
+            }
+        }
+        None
+    }
+
+    fn read_line(&mut self, offset: u64) -> std::io::Result<BusMessage> {
+        let file = self.file.as_mut().ok_or(std::io::ErrorKind::NotFound)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        BufReader::new(&*file).read_line(&mut line)?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Empty the file once everything in it has been read back
+    fn reset(&mut self) {
+        if let Some(file) = &self.file {
+            if let Err(e) = file.set_len(0) {
+                warn!("Failed to truncate memory spill file: {}", e);
+            }
+        }
+        self.read_offset = 0;
+        self.disk_bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MessagePayload, ModuleId};
+
+    #[test]
+    fn test_budget_tracks_queues_and_admits_oversized_message_alone() {
+        let memory = QueueMemory::new(2, Some(MemoryBudgetConfig::new(1_000)));
+        assert!(memory.try_reserve(600));
+        memory.charge(0, 600);
+        assert!(memory.try_reserve(300));
+        memory.charge(1, 300);
+        assert!(!memory.try_reserve(200));
+
+        let metrics = memory.metrics();
+        assert_eq!(metrics.queue_bytes, vec![600, 300]);
+        assert_eq!((metrics.queue_memory_bytes, metrics.peak_queue_memory_bytes), (900, 900));
+
+        memory.release(0, 600);
+        memory.release(1, 300);
+        assert_eq!(memory.used(), 0);
+        // Too big for the budget, but the queues are empty
+        assert!(memory.try_reserve(5_000));
+        assert!(!memory.try_reserve(1));
+    }
+
+    #[test]
+    fn test_spill_returns_messages_in_order_when_room_frees() {
+        let dir = tempfile::tempdir().unwrap();
+        let spill = MemorySpillConfig::new(dir.path().join("spill.jsonl"));
+        let memory = QueueMemory::new(1, Some(MemoryBudgetConfig::new(1_000).with_spill(spill)));
+        assert!(memory.try_reserve(900));
+
+        let ready = |module| BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(module));
+        for module in [ModuleId::Storage, ModuleId::Gamification] {
+            memory.spill(&ready(module), 400).unwrap();
+        }
+        assert_eq!(memory.spilled(), 2);
+        assert!(memory.metrics().spilled_bytes > 0);
+        assert!(memory.unspill().is_none());
+
+        memory.cancel(900);
+        let (first, bytes) = memory.unspill().unwrap();
+        assert!(matches!(first.payload, MessagePayload::ModuleReady(ModuleId::Storage)));
+        memory.put_back(first, bytes);
+        let (first, _) = memory.unspill().unwrap();
+        assert!(matches!(first.payload, MessagePayload::ModuleReady(ModuleId::Storage)));
+        let (second, _) = memory.unspill().unwrap();
+        assert!(matches!(second.payload, MessagePayload::ModuleReady(ModuleId::Gamification)));
+        assert_eq!((memory.spilled(), memory.used(), memory.metrics().spilled_bytes), (0, 800, 0));
+    }
+}
@@ -1,10 +1,12 @@
 //! Message types and definitions for the event bus
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+use crate::error::{EventBusError, EventBusResult};
+
 /// Unique identifier for a module in the system
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ModuleId {
@@ -33,6 +35,33 @@ impl std::fmt::Display for ModuleId {
     }
 }
 
+impl ModuleId {
+    /// Every module in the system
+    pub const ALL: [ModuleId; 8] = [
+        ModuleId::DataCapture,
+        ModuleId::Storage,
+        ModuleId::AnalysisEngine,
+        ModuleId::Gamification,
+        ModuleId::AiIntegration,
+        ModuleId::CuteFigurine,
+        ModuleId::Orchestrator,
+        ModuleId::EventBus,
+    ];
+}
+
+impl std::str::FromStr for ModuleId {
+    type Err = String;
+
+    /// Parse the display name, e.g. `"data-capture"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace('_', "-");
+        ModuleId::ALL
+            .into_iter()
+            .find(|module| module.to_string() == name)
+            .ok_or_else(|| format!("Unknown module '{}'", s))
+    }
+}
+
 /// Priority levels for message processing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum MessagePriority {
@@ -68,6 +97,14 @@ pub struct BusMessage {
     
     /// Priority for message processing
     pub priority: MessagePriority,
+
+    /// Per-stream sequence number stamped by the producer for gap detection
+    #[serde(default)]
+    pub sequence: Option<u64>,
+
+    /// Publisher-chosen token; a second publish with it inside the dedup window is dropped
+    #[serde(default)]
+    pub idempotency_token: Option<String>,
 }
 
 impl BusMessage {
@@ -80,6 +117,8 @@ impl BusMessage {
             payload,
             correlation_id: None,
             priority: MessagePriority::default(),
+            sequence: None,
+            idempotency_token: None,
         }
     }
 
@@ -92,6 +131,8 @@ impl BusMessage {
             payload,
             correlation_id: None,
             priority,
+            sequence: None,
+            idempotency_token: None,
         }
     }
 
@@ -104,13 +145,37 @@ impl BusMessage {
             payload,
             correlation_id: Some(self.id),
             priority: self.priority,
+            sequence: None,
+            idempotency_token: None,
         }
     }
 
+    /// Stamp the message with its sequence number on a producer/consumer stream
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// Attach an idempotency token so a retried publish isn't delivered twice
+    ///
+    /// Reuse the same token when retrying; see [`crate::dedup`].
+    pub fn with_idempotency_token(mut self, token: impl Into<String>) -> Self {
+        self.idempotency_token = Some(token.into());
+        self
+    }
+
     /// Get the message type from the payload
     pub fn message_type(&self) -> MessageType {
         self.payload.message_type()
     }
+
+    /// The message with its payload decompressed; uncompressed messages are returned as they are
+    pub fn decompressed(mut self) -> EventBusResult<Self> {
+        if let MessagePayload::Compressed(compressed) = &self.payload {
+            self.payload = compressed.decompress()?;
+        }
+        Ok(self)
+    }
 }
 
 /// All possible message types in the system
@@ -126,23 +191,56 @@ pub enum MessagePayload {
     // From Analysis Engine
     AnalysisComplete(AnalysisWindow),
     StateChange(StateClassification),
+    TrainingCompleted(TrainingCompleted),
+    DriftDetected(DriftDetected),
+    FocusCheckResult(FocusCheckResult),
+    DayActivity(DayActivity),
+    ForecastUpdated(ForecastUpdated),
     
     // From Gamification
     InterventionRequest(InterventionRequest),
     RewardEvent(RewardEvent),
+    RewardGranted(RewardGranted),
     
     // From AI Integration
     InterventionResponse(InterventionResponse),
+    InterventionDelivered(InterventionDelivered),
     AnimationCommand(AnimationCommand),
+    ModelDownloadProgress(ModelDownloadProgress),
+    DailySummaryRequest(DailySummaryRequest),
+    DailySummary(DailySummary),
     
     // From Orchestrator
     HealthCheck(HealthCheckRequest),
     ConfigUpdate(ConfigUpdate),
+    ResourceViolation(ResourceViolation),
+    PowerStateChanged(PowerStateChange),
+    TelemetryBatch(TelemetryBatch),
+    PerformanceRegression(PerformanceRegression),
+    MemoryLeakSuspected(MemoryLeakSuspected),
+    RetransmitRequest(RetransmitRequest),
+    ConfigTransactionResult(ConfigTransactionResult),
+    MaintenanceRequest(MaintenanceRequest),
+    SystemPower(SystemPowerEvent),
+    
+    // From the user (hotkey or figurine click)
+    FocusCheckRequest(FocusCheckRequest),
+    CurrentTask(CurrentTask),
+    CheckInResponse(CheckInResponse),
     
     // System messages
     Shutdown(ShutdownRequest),
     ModuleReady(ModuleId),
+    DeliveryAck(DeliveryAck),
+    MessageDigest(MessageDigest),
+    PoisonMessageDetected(PoisonMessageDetected),
+    ConfirmationRequired(ConfirmationRequired),
+    ConfirmationResponse(ConfirmationResponse),
+    MaintenanceReport(MaintenanceReport),
     Error(ErrorReport),
+    
+    /// Another payload, compressed for a subscriber that can unpack it
+    Compressed(CompressedPayload),
 }
 
 impl MessagePayload {
@@ -154,17 +252,158 @@ impl MessagePayload {
             MessagePayload::StorageStatus(_) => MessageType::StorageStatus,
             MessagePayload::AnalysisComplete(_) => MessageType::AnalysisComplete,
             MessagePayload::StateChange(_) => MessageType::StateChange,
+            MessagePayload::TrainingCompleted(_) => MessageType::TrainingCompleted,
+            MessagePayload::DriftDetected(_) => MessageType::DriftDetected,
+            MessagePayload::FocusCheckResult(_) => MessageType::FocusCheckResult,
+            MessagePayload::FocusCheckRequest(_) => MessageType::FocusCheckRequest,
+            MessagePayload::CurrentTask(_) => MessageType::CurrentTask,
+            MessagePayload::CheckInResponse(_) => MessageType::CheckInResponse,
+            MessagePayload::DayActivity(_) => MessageType::DayActivity,
+            MessagePayload::ForecastUpdated(_) => MessageType::ForecastUpdated,
             MessagePayload::InterventionRequest(_) => MessageType::InterventionRequest,
             MessagePayload::RewardEvent(_) => MessageType::RewardEvent,
+            MessagePayload::RewardGranted(_) => MessageType::RewardGranted,
             MessagePayload::InterventionResponse(_) => MessageType::InterventionResponse,
+            MessagePayload::InterventionDelivered(_) => MessageType::InterventionDelivered,
             MessagePayload::AnimationCommand(_) => MessageType::AnimationCommand,
+            MessagePayload::ModelDownloadProgress(_) => MessageType::ModelDownloadProgress,
+            MessagePayload::DailySummaryRequest(_) => MessageType::DailySummaryRequest,
+            MessagePayload::DailySummary(_) => MessageType::DailySummary,
             MessagePayload::HealthCheck(_) => MessageType::HealthCheck,
             MessagePayload::ConfigUpdate(_) => MessageType::ConfigUpdate,
+            MessagePayload::ResourceViolation(_) => MessageType::ResourceViolation,
+            MessagePayload::PowerStateChanged(_) => MessageType::PowerStateChanged,
+            MessagePayload::TelemetryBatch(_) => MessageType::TelemetryBatch,
+            MessagePayload::PerformanceRegression(_) => MessageType::PerformanceRegression,
+            MessagePayload::MemoryLeakSuspected(_) => MessageType::MemoryLeakSuspected,
+            MessagePayload::RetransmitRequest(_) => MessageType::RetransmitRequest,
+            MessagePayload::ConfigTransactionResult(_) => MessageType::ConfigTransactionResult,
+            MessagePayload::MaintenanceRequest(_) => MessageType::MaintenanceRequest,
+            MessagePayload::SystemPower(_) => MessageType::SystemPower,
             MessagePayload::Shutdown(_) => MessageType::Shutdown,
             MessagePayload::ModuleReady(_) => MessageType::ModuleReady,
+            MessagePayload::DeliveryAck(_) => MessageType::DeliveryAck,
+            MessagePayload::MessageDigest(_) => MessageType::MessageDigest,
+            MessagePayload::PoisonMessageDetected(_) => MessageType::PoisonMessageDetected,
+            MessagePayload::ConfirmationRequired(_) => MessageType::ConfirmationRequired,
+            MessagePayload::ConfirmationResponse(_) => MessageType::ConfirmationResponse,
+            MessagePayload::MaintenanceReport(_) => MessageType::MaintenanceReport,
             MessagePayload::Error(_) => MessageType::Error,
+            MessagePayload::Compressed(compressed) => compressed.original_type,
         }
     }
+
+    /// Serialize and compress this payload with `codec`
+    pub fn compress(&self, codec: CompressionCodec) -> EventBusResult<CompressedPayload> {
+        CompressedPayload::from_bytes(codec, self.message_type(), &self.to_bytes()?)
+    }
+
+    fn to_bytes(&self) -> EventBusResult<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| EventBusError::Serialization(e.to_string()))
+    }
+}
+
+/// Compression algorithms a subscriber can accept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompressionCodec {
+    /// Fast, moderate ratio
+    Lz4,
+    /// Slower, better ratio
+    Zstd,
+}
+
+impl CompressionCodec {
+    fn compress(&self, bytes: &[u8]) -> EventBusResult<Vec<u8>> {
+        match self {
+            CompressionCodec::Lz4 => Ok(lz4_flex::compress_prepend_size(bytes)),
+            CompressionCodec::Zstd => zstd::bulk::compress(bytes, 3)
+                .map_err(|e| EventBusError::Serialization(format!("zstd compression failed: {}", e))),
+        }
+    }
+
+    fn decompress(&self, data: &[u8], original_size: usize) -> EventBusResult<Vec<u8>> {
+        match self {
+            CompressionCodec::Lz4 => lz4_flex::decompress_size_prepended(data)
+                .map_err(|e| EventBusError::Serialization(format!("lz4 decompression failed: {}", e))),
+            CompressionCodec::Zstd => zstd::bulk::decompress(data, original_size)
+                .map_err(|e| EventBusError::Serialization(format!("zstd decompression failed: {}", e))),
+        }
+    }
+}
+
+/// When the bus compresses payloads on delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Serialized payloads smaller than this are delivered as they are
+    pub threshold_bytes: usize,
+    /// Codec to use when the subscriber accepts it; otherwise the subscriber's first choice
+    pub preferred_codec: CompressionCodec,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: 16 * 1024,
+            preferred_codec: CompressionCodec::Lz4,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Compress `payload` if it serializes to at least the threshold and compression shrinks it
+    pub fn compress(&self, payload: &MessagePayload, codec: CompressionCodec) -> EventBusResult<Option<CompressedPayload>> {
+        if matches!(payload, MessagePayload::Compressed(_)) {
+            return Ok(None);
+        }
+        let bytes = payload.to_bytes()?;
+        if bytes.len() < self.threshold_bytes {
+            return Ok(None);
+        }
+        let compressed = CompressedPayload::from_bytes(codec, payload.message_type(), &bytes)?;
+        Ok((compressed.data.len() < compressed.original_size).then_some(compressed))
+    }
+
+    /// Pick the codec for a subscriber accepting `accepted`, in its order of preference
+    pub fn negotiate(&self, accepted: &[CompressionCodec]) -> Option<CompressionCodec> {
+        if accepted.contains(&self.preferred_codec) {
+            Some(self.preferred_codec)
+        } else {
+            accepted.first().copied()
+        }
+    }
+}
+
+/// A serialized payload compressed with `codec`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressedPayload {
+    pub codec: CompressionCodec,
+    /// Type of the payload before compression, so filters keep matching
+    pub original_type: MessageType,
+    /// Serialized size before compression
+    pub original_size: usize,
+    pub data: Vec<u8>,
+}
+
+impl CompressedPayload {
+    fn from_bytes(codec: CompressionCodec, original_type: MessageType, bytes: &[u8]) -> EventBusResult<Self> {
+        Ok(Self {
+            codec,
+            original_type,
+            original_size: bytes.len(),
+            data: codec.compress(bytes)?,
+        })
+    }
+
+    /// Restore the original payload
+    pub fn decompress(&self) -> EventBusResult<MessagePayload> {
+        let bytes = self.codec.decompress(&self.data, self.original_size)?;
+        serde_json::from_slice(&bytes).map_err(|e| EventBusError::Serialization(e.to_string()))
+    }
+
+    /// Original size divided by compressed size
+    pub fn ratio(&self) -> f64 {
+        self.original_size as f64 / self.data.len().max(1) as f64
+    }
 }
 
 /// Message type enumeration for filtering and routing
@@ -175,14 +414,42 @@ pub enum MessageType {
     StorageStatus,
     AnalysisComplete,
     StateChange,
+    TrainingCompleted,
+    DriftDetected,
+    FocusCheckRequest,
+    FocusCheckResult,
+    CurrentTask,
+    CheckInResponse,
+    DayActivity,
+    ForecastUpdated,
     InterventionRequest,
     RewardEvent,
+    RewardGranted,
     InterventionResponse,
+    InterventionDelivered,
     AnimationCommand,
+    ModelDownloadProgress,
+    DailySummaryRequest,
+    DailySummary,
     HealthCheck,
     ConfigUpdate,
+    ResourceViolation,
+    PowerStateChanged,
+    TelemetryBatch,
+    PerformanceRegression,
+    MemoryLeakSuspected,
+    RetransmitRequest,
+    ConfigTransactionResult,
+    MaintenanceRequest,
+    SystemPower,
     Shutdown,
     ModuleReady,
+    DeliveryAck,
+    MessageDigest,
+    PoisonMessageDetected,
+    ConfirmationRequired,
+    ConfirmationResponse,
+    MaintenanceReport,
     Error,
 }
 
@@ -271,6 +538,133 @@ pub struct StateClassification {
     pub transition_from: Option<String>,
 }
 
+/// A background retraining run finished and produced a new model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingCompleted {
+    pub model_type: String,
+    pub samples_used: usize,
+    pub validation_accuracy: f32,
+    /// Accuracy of the model this run replaces, if there was one
+    pub previous_accuracy: Option<f32>,
+    pub optimization_iterations: usize,
+    pub duration_ms: u64,
+    /// Whether the run picked up from a checkpoint after being interrupted
+    pub resumed_from_checkpoint: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Online learning made the live model worse than its frozen baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftDetected {
+    pub model_type: String,
+    /// Live model accuracy on recent labelled feedback
+    pub recent_accuracy: f32,
+    /// Baseline model accuracy on the same feedback
+    pub baseline_accuracy: f32,
+    pub samples: usize,
+    /// Checkpoint the live model was rolled back to, if any
+    pub rolled_back_to: Option<Uuid>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The user asked "how am I doing right now?"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusCheckRequest {
+    pub request_id: Uuid,
+    /// What triggered the check, e.g. `hotkey` or `figurine_click`
+    pub trigger: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A task the user said they are working on, e.g. "writing report X"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskDeclaration {
+    pub task_id: Uuid,
+    pub description: String,
+    pub declared_at: DateTime<Utc>,
+}
+
+/// The user declared their current task, or cleared it with `task: None`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentTask {
+    pub task: Option<TaskDeclaration>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The user answered a "how's it going?" check-in
+///
+/// The answer is ground truth for the analysis engine: `user_state` is what
+/// the user says they are in, named as in `StateChange` messages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckInResponse {
+    pub check_in_id: Uuid,
+    /// One-tap answer, e.g. `great` or `struggling`
+    pub answer: String,
+    pub user_state: String,
+    /// How much the answer says about the state, 0.0 to 1.0
+    pub confidence: f32,
+    /// Window the answer labels; `None` for whichever window is open at `timestamp`
+    pub window_id: Option<Uuid>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Fresh analysis of the current, still open window, answering a [`FocusCheckRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusCheckResult {
+    pub request_id: Uuid,
+    pub state: String,
+    pub confidence: f64,
+    /// Plain-language reasons for the state
+    pub explanation: String,
+    pub events_analyzed: usize,
+    /// How much of the window had elapsed when it was analyzed
+    pub window_elapsed_ms: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// One reconstructed work session, as reported in [`DayActivity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDigest {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub active_minutes: u32,
+    /// Share of active time spent in flow or hyperfocus, 0–100
+    pub flow_percent: f32,
+    pub interruptions: u32,
+    pub work_type: Option<String>,
+}
+
+/// A day's sessions and focus totals, answering a [`DailySummaryRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayActivity {
+    pub request_id: Uuid,
+    /// The user's local calendar day
+    pub date: NaiveDate,
+    pub sessions: Vec<SessionDigest>,
+    /// Time in flow or hyperfocus
+    pub focused_minutes: u32,
+    pub distracted_minutes: u32,
+    /// Application with the most focused time, if any
+    pub top_application: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Predicted focus capacity for the coming hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastUpdated {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Expected share of active time in flow or hyperfocus, 0–1
+    pub focus_capacity: f32,
+    /// What the user's history alone predicts for this hour, before today's trajectory
+    pub baseline_capacity: f32,
+    /// 0–1, grows with the days of history behind the forecast
+    pub confidence: f32,
+    /// A good window for deep work
+    pub high_focus: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterventionRequest {
     pub request_id: Uuid,
@@ -287,11 +681,65 @@ pub struct RewardEvent {
     pub description: String,
 }
 
+/// Coins or a skeleton customization granted by the reward economy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardGranted {
+    pub reward_id: Uuid,
+    /// Coins added; zero for an unlock bought with coins
+    pub coins: u32,
+    /// Balance after the grant
+    pub balance: u32,
+    pub reason: String,
+    pub unlock: Option<SkeletonUnlock>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A customization for the skeleton, with what the figurine needs to render it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkeletonUnlock {
+    pub item_id: String,
+    pub name: String,
+    pub kind: UnlockKind,
+    /// Asset names, palettes, or melt parameters, depending on `kind`
+    pub visual: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockKind {
+    Hat,
+    Color,
+    MeltStyle,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterventionResponse {
     pub request_id: Uuid,
     pub response_text: String,
     pub animation_cues: Vec<String>,
+    #[serde(default)]
+    pub metadata: ResponseMetadata,
+}
+
+/// How an intervention response was produced, so the UI can adapt its presentation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    /// Taken from the offline library because no model was available
+    pub fallback: bool,
+    /// Why the model could not be used, when `fallback` is set
+    pub fallback_reason: Option<String>,
+}
+
+/// Whether an intervention response reached the user through one delivery channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterventionDelivered {
+    pub request_id: Uuid,
+    /// `figurine`, `notification` or `speech`
+    pub channel: String,
+    pub delivered: bool,
+    /// Why delivery failed, when it did
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -302,6 +750,44 @@ pub struct AnimationCommand {
     pub duration_ms: u32,
 }
 
+/// Progress of a local model download, for the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDownloadProgress {
+    pub model_id: String,
+    pub version: String,
+    /// "downloading", "verifying", "installed" or "failed"
+    pub phase: String,
+    pub bytes_downloaded: u64,
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Asks the analysis engine for a day's [`DayActivity`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummaryRequest {
+    pub request_id: Uuid,
+    /// The user's local calendar day
+    pub date: NaiveDate,
+    /// Start and end of that day
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// End-of-day recap for the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailySummary {
+    pub date: NaiveDate,
+    pub text: String,
+    /// Sessions long and focused enough to count as flow blocks
+    pub flow_blocks: u32,
+    pub focused_minutes: u32,
+    pub active_minutes: u32,
+    pub interventions_accepted: u32,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheckRequest {
     pub module_id: ModuleId,
@@ -324,6 +810,261 @@ pub struct ConfigUpdate {
     pub target_module: Option<ModuleId>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceViolation {
+    pub module: ModuleId,
+    pub resource: String,
+    pub observed: f64,
+    pub limit: f64,
+    pub throttle_level: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerStateChange {
+    pub from: String,
+    pub to: String,
+    pub battery_percent: Option<f32>,
+    pub charging: bool,
+    pub thermal_pressure: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    /// `None` for system-wide metrics
+    pub module: Option<ModuleId>,
+    pub metric: String,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryBatch {
+    pub samples: Vec<TelemetrySample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceRegression {
+    pub module: Option<ModuleId>,
+    pub metric: String,
+    pub baseline: f64,
+    pub observed: f64,
+    pub factor: f64,
+    pub sustained_secs: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A module's memory has grown steadily for hours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryLeakSuspected {
+    pub module: ModuleId,
+    /// Slope of the fitted line
+    pub growth_mb_per_hour: f64,
+    /// How well a straight line explains the samples, 0 to 1
+    pub r_squared: f64,
+    /// Per-interval memory samples the trend was fitted to, oldest first
+    pub samples: Vec<(DateTime<Utc>, f64)>,
+    /// When the orchestrator will restart the module, if it was asked to
+    pub restart_at: Option<DateTime<Utc>>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Ask Storage to resend a range of sequenced messages a consumer never acknowledged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetransmitRequest {
+    pub producer: ModuleId,
+    pub consumer: ModuleId,
+    pub from_sequence: u64,
+    pub to_sequence: u64,
+    pub attempt: u32,
+}
+
+/// Outcome of a hot reload that touched one or more module config sections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigTransactionResult {
+    pub transaction_id: Uuid,
+    /// Config file the change came from
+    pub source: String,
+    pub committed: bool,
+    /// Modules whose section changed, in the order they were applied
+    pub affected_modules: Vec<ModuleId>,
+    /// Modules that took the new section; reverted again unless committed
+    pub applied_modules: Vec<ModuleId>,
+    pub rejected_by: Option<ModuleId>,
+    pub errors: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Housekeeping job run during a maintenance window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    StorageCompaction,
+    ModelRetraining,
+    Backup,
+    DeadLetterCleanup,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 4] = [
+        MaintenanceTask::StorageCompaction,
+        MaintenanceTask::ModelRetraining,
+        MaintenanceTask::Backup,
+        MaintenanceTask::DeadLetterCleanup,
+    ];
+
+    /// Module that carries the task out
+    pub fn module(self) -> ModuleId {
+        match self {
+            MaintenanceTask::StorageCompaction | MaintenanceTask::Backup => ModuleId::Storage,
+            MaintenanceTask::ModelRetraining => ModuleId::AnalysisEngine,
+            MaintenanceTask::DeadLetterCleanup => ModuleId::EventBus,
+        }
+    }
+}
+
+impl std::fmt::Display for MaintenanceTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaintenanceTask::StorageCompaction => write!(f, "storage compaction"),
+            MaintenanceTask::ModelRetraining => write!(f, "model retraining"),
+            MaintenanceTask::Backup => write!(f, "backup"),
+            MaintenanceTask::DeadLetterCleanup => write!(f, "dead letter cleanup"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceAction {
+    Start,
+    /// Stop at the next safe point; the task is asked again later
+    Defer,
+}
+
+/// Orchestrator asking a module to start or set aside a maintenance task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRequest {
+    pub run_id: Uuid,
+    pub task: MaintenanceTask,
+    pub action: MaintenanceAction,
+    /// When the maintenance window closes
+    pub deadline: DateTime<Utc>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Module reporting that a maintenance task finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub run_id: Uuid,
+    pub task: MaintenanceTask,
+    pub success: bool,
+    pub details: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// OS power transition the orchestrator is coordinating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPowerTransition {
+    /// The machine is about to suspend; persist state now
+    Sleep,
+    /// The machine resumed; time-based state spanning the gap is stale
+    Wake,
+    /// The OS is shutting down or the user is logging out
+    Shutdown,
+}
+
+/// Broadcast around sleep, wake and OS shutdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemPowerEvent {
+    pub transition: SystemPowerTransition,
+    /// For `Wake`, how long the machine was suspended, if known
+    pub slept_for: Option<std::time::Duration>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Consumer acknowledgement of an inclusive range of sequence numbers on one stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryAck {
+    pub producer: ModuleId,
+    pub consumer: ModuleId,
+    pub from_sequence: u64,
+    pub to_sequence: u64,
+}
+
+/// Messages collected for an aggregated subscription, delivered as one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDigest {
+    pub subscription_id: Uuid,
+    /// In arrival order
+    pub messages: Vec<BusMessage>,
+    /// Estimated size of the collected messages
+    pub total_bytes: usize,
+    /// When the first message of the digest arrived
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub trigger: DigestTrigger,
+}
+
+/// A message kept failing in a handler and was quarantined instead of retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoisonMessageDetected {
+    pub message_id: Uuid,
+    pub message_type: MessageType,
+    pub source: ModuleId,
+    /// Module whose handler failed
+    pub subscriber: ModuleId,
+    pub failures: u32,
+    /// Error or panic message of each failure, oldest first
+    pub errors: Vec<String>,
+    /// Dead letter entry holding the quarantined message
+    pub dead_letter_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A recovery action is waiting for an operator to approve it
+///
+/// Answer with a [`ConfirmationResponse`] carrying the same `request_id` before
+/// `timeout` runs out; an unanswered request counts as rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationRequired {
+    pub request_id: Uuid,
+    pub incident_id: Uuid,
+    pub action_id: Uuid,
+    pub action_name: String,
+    /// What the action will do
+    pub description: String,
+    /// Escalation level of the action, 0 (automatic) to 5 (emergency)
+    pub escalation_level: u8,
+    /// Module the incident was raised for
+    pub module: ModuleId,
+    pub timeout: Duration,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// An operator's answer to a [`ConfirmationRequired`], from the UI or admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationResponse {
+    pub request_id: Uuid,
+    pub approved: bool,
+    /// Who made the decision, e.g. a user name or `admin-api`
+    pub decided_by: String,
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Which limit closed a digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DigestTrigger {
+    Count,
+    Size,
+    Time,
+    /// Delivered early because the bus was shutting down
+    Shutdown,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShutdownRequest {
     pub module_id: ModuleId,
@@ -1,11 +1,13 @@
 //! Metrics collection and monitoring for the event bus
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime};
 use chrono::{DateTime, Utc};
+use hdrhistogram::Histogram;
 use serde::{Deserialize, Serialize};
-use crate::{ModuleId, MessageType};
+use crate::{ModuleId, MessageType, memory_budget::QueueMemory, subscription::CompressionSample};
 
 /// Comprehensive metrics for the event bus
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +24,18 @@ pub struct BusMetrics {
     /// Current queue depth
     pub current_queue_depth: u64,
     
+    /// Publishes and subscriptions rejected by the authorization policy
+    #[serde(default)]
+    pub authorization_denials: u64,
+    
+    /// Publishes rejected by a registered payload validator
+    #[serde(default)]
+    pub validation_rejections: u64,
+    
+    /// Publishes dropped because their idempotency token was already used
+    #[serde(default)]
+    pub duplicate_publishes: u64,
+    
     /// Delivery latency statistics
     pub delivery_latency: LatencyStats,
     
@@ -34,6 +48,14 @@ pub struct BusMetrics {
     /// Memory usage information
     pub memory_usage: MemoryMetrics,
     
+    /// Payload compression for subscribers that accept it
+    #[serde(default)]
+    pub compression: CompressionMetrics,
+    
+    /// Per publisher → subscriber flow statistics, one entry per message type
+    #[serde(default)]
+    pub flows: Vec<FlowMetrics>,
+    
     /// When these metrics were collected
     pub collected_at: DateTime<Utc>,
     
@@ -42,7 +64,7 @@ pub struct BusMetrics {
 }
 
 /// Latency statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LatencyStats {
     pub min_ms: f64,
     pub max_ms: f64,
@@ -58,6 +80,10 @@ pub struct ModuleMetrics {
     pub messages_published: u64,
     pub messages_received: u64,
     pub subscriptions_active: u32,
+    #[serde(default)]
+    pub authorization_denials: u64,
+    #[serde(default)]
+    pub validation_rejections: u64,
     pub last_activity: Option<DateTime<Utc>>,
 }
 
@@ -67,14 +93,138 @@ pub struct MessageTypeMetrics {
     pub count: u64,
     pub avg_size_bytes: u64,
     pub avg_latency_ms: f64,
+    #[serde(default)]
+    pub delivery_latency: LatencyStats,
+    #[serde(default)]
+    pub handler_time: LatencyStats,
 }
 
-/// Memory usage metrics
+/// One message type travelling from a publisher to a subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FlowKey {
+    pub message_type: MessageType,
+    pub publisher: ModuleId,
+    pub subscriber: ModuleId,
+}
+
+/// Delivery latency and handler execution time for one flow
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowMetrics {
+    pub message_type: MessageType,
+    pub publisher: ModuleId,
+    pub subscriber: ModuleId,
+    pub deliveries: u64,
+    pub delivery_latency: LatencyStats,
+    /// Handler runs timed through `EnhancedEventBus::handle_message`
+    pub handled: u64,
+    pub handler_time: LatencyStats,
+}
+
+impl FlowMetrics {
+    /// p99 delivery latency plus p99 handler time, used to rank flows
+    pub fn p99_total_ms(&self) -> f64 {
+        self.delivery_latency.p99_ms + self.handler_time.p99_ms
+    }
+}
+
+/// Selects flows by message type, publisher, and subscriber; unset fields match anything
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowQuery {
+    pub message_type: Option<MessageType>,
+    pub publisher: Option<ModuleId>,
+    pub subscriber: Option<ModuleId>,
+}
+
+impl FlowQuery {
+    /// Match every flow
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    pub fn with_message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = Some(message_type);
+        self
+    }
+
+    pub fn with_publisher(mut self, publisher: ModuleId) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    pub fn with_subscriber(mut self, subscriber: ModuleId) -> Self {
+        self.subscriber = Some(subscriber);
+        self
+    }
+
+    pub fn matches(&self, flow: &FlowMetrics) -> bool {
+        self.message_type.is_none_or(|message_type| message_type == flow.message_type)
+            && self.publisher.is_none_or(|publisher| publisher == flow.publisher)
+            && self.subscriber.is_none_or(|subscriber| subscriber == flow.subscriber)
+    }
+}
+
+impl BusMetrics {
+    /// Flows matching `query`
+    pub fn flows_matching(&self, query: FlowQuery) -> impl Iterator<Item = &FlowMetrics> {
+        self.flows.iter().filter(move |flow| query.matches(flow))
+    }
+
+    /// The `k` flows matching `query` with the highest p99 delivery plus handler time
+    pub fn slowest_flows(&self, query: FlowQuery, k: usize) -> Vec<FlowMetrics> {
+        let mut flows: Vec<FlowMetrics> = self.flows_matching(query).cloned().collect();
+        flows.sort_by(|a, b| b.p99_total_ms().total_cmp(&a.p99_total_ms()));
+        flows.truncate(k);
+        flows
+    }
+}
+
+/// Payload compression metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompressionMetrics {
+    /// Payloads compressed (once per codec, however many subscribers received them)
+    pub payloads_compressed: u64,
+    /// Deliveries that carried a compressed payload
+    pub compressed_deliveries: u64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// `bytes_before / bytes_after`, 0 until something was compressed
+    pub compression_ratio: f64,
+    /// Time spent serializing and compressing
+    pub cpu_time_ms: f64,
+    /// Mean compression time per payload
+    pub avg_cpu_time_us: f64,
+}
+
+/// Memory usage metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryMetrics {
     pub total_allocated_bytes: u64,
     pub queue_memory_bytes: u64,
     pub subscription_memory_bytes: u64,
+    
+    /// Estimated bytes in each routing queue, ordered lanes first, then shard queues
+    #[serde(default)]
+    pub queue_bytes: Vec<u64>,
+    
+    /// Most bytes the routing queues have held at once
+    #[serde(default)]
+    pub peak_queue_memory_bytes: u64,
+    
+    /// Byte budget for the routing queues (`None` when unlimited)
+    #[serde(default)]
+    pub budget_bytes: Option<u64>,
+    
+    /// Messages over budget waiting on disk, and the bytes they take there
+    #[serde(default)]
+    pub spilled_messages: u64,
+    #[serde(default)]
+    pub spilled_bytes: u64,
+    
+    /// Publishes rejected for going over budget, and their estimated bytes
+    #[serde(default)]
+    pub rejected_messages: u64,
+    #[serde(default)]
+    pub rejected_bytes: u64,
 }
 
 /// Internal metrics collector with atomic counters for performance
@@ -84,20 +234,38 @@ pub struct MetricsCollector {
     messages_delivered: AtomicU64,
     messages_failed: AtomicU64,
     current_queue_depth: AtomicU64,
+    authorization_denials: AtomicU64,
+    validation_rejections: AtomicU64,
+    duplicate_publishes: AtomicU64,
+    
+    // Compression counters
+    payloads_compressed: AtomicU64,
+    compressed_deliveries: AtomicU64,
+    compression_bytes_before: AtomicU64,
+    compression_bytes_after: AtomicU64,
+    compression_time_ns: AtomicU64,
     
     // Latency tracking
-    latency_samples: parking_lot::Mutex<Vec<Duration>>,
+    latency_samples: parking_lot::Mutex<VecDeque<Duration>>,
     max_latency_samples: usize,
     
     // Per-module counters
     module_published: dashmap::DashMap<ModuleId, AtomicU64>,
     module_received: dashmap::DashMap<ModuleId, AtomicU64>,
     module_last_activity: dashmap::DashMap<ModuleId, SystemTime>,
+    module_denied: dashmap::DashMap<ModuleId, AtomicU64>,
+    module_rejected: dashmap::DashMap<ModuleId, AtomicU64>,
     
     // Per-message-type counters
     message_type_counts: dashmap::DashMap<MessageType, AtomicU64>,
     message_type_sizes: dashmap::DashMap<MessageType, AtomicU64>,
-    message_type_latencies: dashmap::DashMap<MessageType, parking_lot::Mutex<Vec<Duration>>>,
+    message_type_latencies: dashmap::DashMap<MessageType, parking_lot::Mutex<LatencyHistograms>>,
+    
+    // Per-flow histograms
+    flow_latencies: dashmap::DashMap<FlowKey, parking_lot::Mutex<LatencyHistograms>>,
+    
+    // Byte accounting of the routing queues, when a router reports it
+    queue_memory: Option<Arc<QueueMemory>>,
     
     // System information
     start_time: SystemTime,
@@ -111,18 +279,36 @@ impl MetricsCollector {
             messages_delivered: AtomicU64::new(0),
             messages_failed: AtomicU64::new(0),
             current_queue_depth: AtomicU64::new(0),
-            latency_samples: parking_lot::Mutex::new(Vec::new()),
+            authorization_denials: AtomicU64::new(0),
+            validation_rejections: AtomicU64::new(0),
+            duplicate_publishes: AtomicU64::new(0),
+            payloads_compressed: AtomicU64::new(0),
+            compressed_deliveries: AtomicU64::new(0),
+            compression_bytes_before: AtomicU64::new(0),
+            compression_bytes_after: AtomicU64::new(0),
+            compression_time_ns: AtomicU64::new(0),
+            latency_samples: parking_lot::Mutex::new(VecDeque::new()),
             max_latency_samples: 10_000, // Keep last 10k samples
             module_published: dashmap::DashMap::new(),
             module_received: dashmap::DashMap::new(),
             module_last_activity: dashmap::DashMap::new(),
+            module_denied: dashmap::DashMap::new(),
+            module_rejected: dashmap::DashMap::new(),
             message_type_counts: dashmap::DashMap::new(),
             message_type_sizes: dashmap::DashMap::new(),
             message_type_latencies: dashmap::DashMap::new(),
+            flow_latencies: dashmap::DashMap::new(),
+            queue_memory: None,
             start_time: SystemTime::now(),
         }
     }
 
+    /// Report the router's queue byte accounting in `memory_usage`
+    pub(crate) fn with_queue_memory(mut self, memory: Arc<QueueMemory>) -> Self {
+        self.queue_memory = Some(memory);
+        self
+    }
+
     /// Record a message being published
     pub fn record_publish(&self, module: ModuleId, message_type: MessageType, size_bytes: usize) {
         self.messages_published.fetch_add(1, Ordering::Relaxed);
@@ -149,33 +335,59 @@ impl MetricsCollector {
 
     /// Record a message being delivered
     pub fn record_delivery(&self, module: ModuleId, message_type: MessageType, latency: Duration) {
-        self.messages_delivered.fetch_add(1, Ordering::Relaxed);
+        self.record_deliveries(module, message_type, latency, 1);
+    }
+
+    /// Record one message reaching `count` subscribers, taking each lock once
+    pub fn record_deliveries(&self, module: ModuleId, message_type: MessageType, latency: Duration, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.messages_delivered.fetch_add(count as u64, Ordering::Relaxed);
         
         // Update per-module stats
         self.module_received
             .entry(module)
             .or_insert_with(|| AtomicU64::new(0))
-            .fetch_add(1, Ordering::Relaxed);
+            .fetch_add(count as u64, Ordering::Relaxed);
         
         self.module_last_activity.insert(module, SystemTime::now());
         
-        // Record latency
-        {
-            let mut samples = self.latency_samples.lock();
-            samples.push(latency);
-            
-            // Keep only recent samples to prevent unbounded growth
-            if samples.len() > self.max_latency_samples {
-                samples.remove(0);
-            }
-        }
+        // Record latency, keeping only recent samples to prevent unbounded growth
+        let keep = self.max_latency_samples;
+        push_samples(&mut self.latency_samples.lock(), latency, count, keep);
         
         // Record per-message-type latency
         self.message_type_latencies
             .entry(message_type)
-            .or_insert_with(|| parking_lot::Mutex::new(Vec::new()))
+            .or_default()
+            .lock()
+            .record_delivery(latency, count as u64);
+    }
+
+    /// Record one message's delivery latency on each publisher → subscriber flow it took
+    pub fn record_flows(&self, publisher: ModuleId, message_type: MessageType, latency: Duration, subscribers: &[ModuleId]) {
+        for &subscriber in subscribers {
+            self.flow_latencies
+                .entry(FlowKey { message_type, publisher, subscriber })
+                .or_default()
+                .lock()
+                .record_delivery(latency, 1);
+        }
+    }
+
+    /// Record how long `subscriber`'s handler took to process a message
+    pub fn record_handler_time(&self, flow: FlowKey, elapsed: Duration) {
+        self.message_type_latencies
+            .entry(flow.message_type)
+            .or_default()
             .lock()
-            .push(latency);
+            .record_handler(elapsed);
+        self.flow_latencies
+            .entry(flow)
+            .or_default()
+            .lock()
+            .record_handler(elapsed);
     }
 
     /// Record a delivery failure
@@ -183,6 +395,40 @@ impl MetricsCollector {
         self.messages_failed.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a publish or subscription rejected by the authorization policy
+    pub fn record_authorization_denied(&self, module: ModuleId) {
+        self.authorization_denials.fetch_add(1, Ordering::Relaxed);
+        self.module_denied
+            .entry(module)
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a publish rejected by a payload validator
+    pub fn record_validation_rejected(&self, module: ModuleId) {
+        self.validation_rejections.fetch_add(1, Ordering::Relaxed);
+        self.module_rejected
+            .entry(module)
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a publish dropped as a duplicate
+    pub fn record_duplicate_publish(&self) {
+        self.duplicate_publishes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the compression work done while delivering one message
+    pub fn record_compression(&self, samples: &[CompressionSample], compressed_deliveries: u32) {
+        for sample in samples {
+            self.payloads_compressed.fetch_add(1, Ordering::Relaxed);
+            self.compression_bytes_before.fetch_add(sample.original_bytes as u64, Ordering::Relaxed);
+            self.compression_bytes_after.fetch_add(sample.compressed_bytes as u64, Ordering::Relaxed);
+            self.compression_time_ns.fetch_add(sample.elapsed.as_nanos() as u64, Ordering::Relaxed);
+        }
+        self.compressed_deliveries.fetch_add(compressed_deliveries as u64, Ordering::Relaxed);
+    }
+
     /// Update current queue depth
     pub fn update_queue_depth(&self, depth: usize) {
         self.current_queue_depth.store(depth as u64, Ordering::Relaxed);
@@ -206,7 +452,7 @@ impl MetricsCollector {
 
         // Calculate latency statistics
         let delivery_latency = {
-            let samples = self.latency_samples.lock();
+            let samples: Vec<Duration> = self.latency_samples.lock().iter().copied().collect();
             calculate_latency_stats(&samples)
         };
 
@@ -240,10 +486,22 @@ impl MetricsCollector {
             
             let subscriptions_active = subscription_counts.get(&module).copied().unwrap_or(0);
 
+            let authorization_denials = self.module_denied
+                .get(&module)
+                .map(|v| v.load(Ordering::Relaxed))
+                .unwrap_or(0);
+
+            let validation_rejections = self.module_rejected
+                .get(&module)
+                .map(|v| v.load(Ordering::Relaxed))
+                .unwrap_or(0);
+
             module_stats.insert(module, ModuleMetrics {
                 messages_published: published,
                 messages_received: received,
                 subscriptions_active,
+                authorization_denials,
+                validation_rejections,
                 last_activity,
             });
         }
@@ -260,59 +518,138 @@ impl MetricsCollector {
             
             let avg_size_bytes = if count > 0 { total_size / count } else { 0 };
             
-            let avg_latency_ms = self.message_type_latencies
+            let (delivery_latency, handler_time) = self.message_type_latencies
                 .get(&message_type)
-                .map(|latencies| {
-                    let latencies = latencies.lock();
-                    if latencies.is_empty() {
-                        0.0
-                    } else {
-                        latencies.iter()
-                            .map(|d| d.as_millis() as f64)
-                            .sum::<f64>() / latencies.len() as f64
-                    }
-                })
-                .unwrap_or(0.0);
+                .map(|latencies| latencies.lock().stats())
+                .unwrap_or_default();
             
             message_type_stats.insert(message_type, MessageTypeMetrics {
                 count,
                 avg_size_bytes,
-                avg_latency_ms,
+                avg_latency_ms: delivery_latency.mean_ms,
+                delivery_latency,
+                handler_time,
             });
         }
 
+        let flows = self.flow_latencies
+            .iter()
+            .map(|entry| {
+                let flow = *entry.key();
+                let latencies = entry.value().lock();
+                let (delivery_latency, handler_time) = latencies.stats();
+                FlowMetrics {
+                    message_type: flow.message_type,
+                    publisher: flow.publisher,
+                    subscriber: flow.subscriber,
+                    deliveries: latencies.delivery.len(),
+                    delivery_latency,
+                    handled: latencies.handler.len(),
+                    handler_time,
+                }
+            })
+            .collect();
+
         BusMetrics {
             messages_published: self.messages_published.load(Ordering::Relaxed),
             messages_delivered: self.messages_delivered.load(Ordering::Relaxed),
             messages_failed: self.messages_failed.load(Ordering::Relaxed),
             current_queue_depth: self.current_queue_depth.load(Ordering::Relaxed),
+            authorization_denials: self.authorization_denials.load(Ordering::Relaxed),
+            validation_rejections: self.validation_rejections.load(Ordering::Relaxed),
+            duplicate_publishes: self.duplicate_publishes.load(Ordering::Relaxed),
             delivery_latency,
             module_stats,
             message_type_stats,
-            memory_usage: estimate_memory_usage(),
+            memory_usage: self.queue_memory.as_ref().map_or_else(estimate_memory_usage, |memory| memory.metrics()),
+            compression: self.compression_snapshot(),
+            flows,
             collected_at: Utc::now(),
             uptime,
         }
     }
 }
 
+impl MetricsCollector {
+    fn compression_snapshot(&self) -> CompressionMetrics {
+        let payloads_compressed = self.payloads_compressed.load(Ordering::Relaxed);
+        let bytes_before = self.compression_bytes_before.load(Ordering::Relaxed);
+        let bytes_after = self.compression_bytes_after.load(Ordering::Relaxed);
+        let time_ns = self.compression_time_ns.load(Ordering::Relaxed) as f64;
+
+        CompressionMetrics {
+            payloads_compressed,
+            compressed_deliveries: self.compressed_deliveries.load(Ordering::Relaxed),
+            bytes_before,
+            bytes_after,
+            compression_ratio: if bytes_after > 0 { bytes_before as f64 / bytes_after as f64 } else { 0.0 },
+            cpu_time_ms: time_ns / 1_000_000.0,
+            avg_cpu_time_us: if payloads_compressed > 0 { time_ns / 1_000.0 / payloads_compressed as f64 } else { 0.0 },
+        }
+    }
+}
+
 impl Default for MetricsCollector {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// HDR histograms of delivery latency and handler time, in microseconds
+struct LatencyHistograms {
+    delivery: Histogram<u64>,
+    handler: Histogram<u64>,
+}
+
+impl Default for LatencyHistograms {
+    fn default() -> Self {
+        // Up to an hour at two significant figures keeps each histogram to a few KB
+        let histogram = || Histogram::new_with_bounds(1, 3_600_000_000, 2).expect("valid histogram bounds");
+        Self { delivery: histogram(), handler: histogram() }
+    }
+}
+
+impl LatencyHistograms {
+    fn record_delivery(&mut self, latency: Duration, count: u64) {
+        self.delivery.saturating_record_n(latency.as_micros() as u64, count);
+    }
+
+    fn record_handler(&mut self, elapsed: Duration) {
+        self.handler.saturating_record(elapsed.as_micros() as u64);
+    }
+
+    fn stats(&self) -> (LatencyStats, LatencyStats) {
+        (histogram_stats(&self.delivery), histogram_stats(&self.handler))
+    }
+}
+
+fn histogram_stats(histogram: &Histogram<u64>) -> LatencyStats {
+    if histogram.is_empty() {
+        return LatencyStats::default();
+    }
+    let ms = |micros: u64| micros as f64 / 1_000.0;
+    LatencyStats {
+        min_ms: ms(histogram.min()),
+        max_ms: ms(histogram.max()),
+        mean_ms: histogram.mean() / 1_000.0,
+        p50_ms: ms(histogram.value_at_quantile(0.5)),
+        p95_ms: ms(histogram.value_at_quantile(0.95)),
+        p99_ms: ms(histogram.value_at_quantile(0.99)),
+    }
+}
+
+/// Append `count` copies of `latency`, dropping the oldest beyond `keep`
+fn push_samples(samples: &mut VecDeque<Duration>, latency: Duration, count: u32, keep: usize) {
+    samples.extend(std::iter::repeat_n(latency, count as usize));
+    while samples.len() > keep {
+        samples.pop_front();
+    }
+}
+
 /// Calculate latency statistics from a collection of samples
 fn calculate_latency_stats(samples: &[Duration]) -> LatencyStats {
     if samples.is_empty() {
-        return LatencyStats {
-            min_ms: 0.0,
-            max_ms: 0.0,
-            mean_ms: 0.0,
-            p50_ms: 0.0,
-            p95_ms: 0.0,
-            p99_ms: 0.0,
-        };
+        return LatencyStats::default();
     }
 
     let mut sorted_ms: Vec<f64> = samples
@@ -357,5 +694,71 @@ fn estimate_memory_usage() -> MemoryMetrics {
         total_allocated_bytes: 0, // Would need platform-specific implementation
         queue_memory_bytes: 0,    // Estimated based on queue sizes
         subscription_memory_bytes: 0, // Estimated based on subscription count
+        ..Default::default()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(message_type: MessageType, publisher: ModuleId, subscriber: ModuleId) -> FlowKey {
+        FlowKey { message_type, publisher, subscriber }
+    }
+
+    #[test]
+    fn test_flows_record_delivery_and_handler_histograms() {
+        let metrics = MetricsCollector::new();
+        metrics.record_publish(ModuleId::DataCapture, MessageType::RawEvent, 128);
+        for ms in 1..=100 {
+            metrics.record_flows(
+                ModuleId::DataCapture,
+                MessageType::RawEvent,
+                Duration::from_millis(ms),
+                &[ModuleId::Storage, ModuleId::AnalysisEngine],
+            );
+        }
+        metrics.record_handler_time(
+            flow(MessageType::RawEvent, ModuleId::DataCapture, ModuleId::Storage),
+            Duration::from_millis(40),
+        );
+
+        let snapshot = metrics.snapshot(HashMap::new());
+        assert_eq!(snapshot.flows.len(), 2);
+        let storage = snapshot
+            .flows_matching(FlowQuery::any().with_subscriber(ModuleId::Storage))
+            .next()
+            .unwrap();
+        assert_eq!((storage.deliveries, storage.handled), (100, 1));
+        // Two significant figures
+        assert!((storage.delivery_latency.p99_ms - 99.0).abs() <= 1.0);
+        assert!((storage.delivery_latency.p50_ms - 50.0).abs() <= 1.0);
+        assert!((storage.handler_time.max_ms - 40.0).abs() <= 1.0);
+
+        let raw_events = &snapshot.message_type_stats[&MessageType::RawEvent];
+        assert!((raw_events.handler_time.mean_ms - 40.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_wildcard_query_and_slowest_flows() {
+        let metrics = MetricsCollector::new();
+        metrics.record_flows(ModuleId::DataCapture, MessageType::RawEvent, Duration::from_millis(5), &[ModuleId::Storage]);
+        metrics.record_flows(ModuleId::AnalysisEngine, MessageType::StateChange, Duration::from_millis(2), &[
+            ModuleId::Gamification,
+            ModuleId::AiIntegration,
+        ]);
+        metrics.record_handler_time(
+            flow(MessageType::StateChange, ModuleId::AnalysisEngine, ModuleId::AiIntegration),
+            Duration::from_millis(30),
+        );
+
+        let snapshot = metrics.snapshot(HashMap::new());
+        let from_analysis = FlowQuery::any().with_publisher(ModuleId::AnalysisEngine);
+        assert_eq!(snapshot.flows_matching(from_analysis).count(), 2);
+        assert_eq!(snapshot.flows_matching(FlowQuery::any().with_message_type(MessageType::RawEvent)).count(), 1);
+
+        let slowest = snapshot.slowest_flows(FlowQuery::any(), 2);
+        let order: Vec<ModuleId> = slowest.iter().map(|flow| flow.subscriber).collect();
+        assert_eq!(order, vec![ModuleId::AiIntegration, ModuleId::Storage]);
+        assert_eq!(snapshot.slowest_flows(from_analysis, 5).len(), 2);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,192 @@
+//! Poison message detection
+//!
+//! A message whose payload makes a handler fail or panic will fail the same way
+//! on every retry. The detector runs handlers with panics caught and counts
+//! failures per message id; once one message has failed `max_failures` times
+//! within `failure_window` it is reported as poisoned, with every failure, so
+//! the bus can quarantine it instead of retrying it again.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, SystemTime};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{BusMessage, MessageId, ModuleId};
+
+/// When repeated handler failures make a message poison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoisonConfig {
+    /// Failures of one message before it is quarantined
+    pub max_failures: u32,
+    /// Failures older than this are forgotten
+    pub failure_window: Duration,
+}
+
+impl Default for PoisonConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 3,
+            failure_window: Duration::from_secs(600),
+        }
+    }
+}
+
+/// One failed attempt to handle a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandlerFailure {
+    pub subscriber: ModuleId,
+    /// Error returned by the handler, or its panic message
+    pub error: String,
+    pub panicked: bool,
+    pub at: SystemTime,
+}
+
+/// Result of running a handler under the detector
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandlerOutcome {
+    Handled,
+    /// Failed, but not often enough yet to give up on the message
+    Failed(HandlerFailure),
+    /// Failed `max_failures` times; every failure, oldest first
+    Poisoned(Vec<HandlerFailure>),
+}
+
+/// Counts handler failures per message
+#[derive(Debug, Default)]
+pub struct PoisonDetector {
+    config: PoisonConfig,
+    failures: parking_lot::Mutex<HashMap<MessageId, Vec<HandlerFailure>>>,
+}
+
+impl PoisonDetector {
+    pub fn new(config: PoisonConfig) -> Self {
+        Self {
+            config,
+            failures: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `handler` on `message`, catching panics
+    pub fn run<F, E>(&self, message: &BusMessage, subscriber: ModuleId, handler: F) -> HandlerOutcome
+    where
+        F: FnOnce(&BusMessage) -> Result<(), E>,
+        E: std::fmt::Display,
+    {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| handler(message)));
+        self.outcome(message, subscriber, result.map(|handled| handled.map_err(|e| e.to_string())))
+    }
+
+    /// Run an async `handler` on `message`, catching panics
+    pub async fn run_async<F, Fut, E>(&self, message: &BusMessage, subscriber: ModuleId, handler: F) -> HandlerOutcome
+    where
+        F: FnOnce(BusMessage) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Display,
+    {
+        let result = AssertUnwindSafe(handler(message.clone())).catch_unwind().await;
+        self.outcome(message, subscriber, result.map(|handled| handled.map_err(|e| e.to_string())))
+    }
+
+    /// Record a failure reported some other way; returns every failure once the message is poison
+    pub fn record_failure(&self, message_id: MessageId, failure: HandlerFailure) -> Option<Vec<HandlerFailure>> {
+        let mut failures = self.failures.lock();
+        let cutoff = SystemTime::now() - self.config.failure_window;
+        failures.retain(|_, history| history.last().is_some_and(|last| last.at >= cutoff));
+
+        let history = failures.entry(message_id).or_default();
+        history.retain(|failure| failure.at >= cutoff);
+        history.push(failure);
+        if history.len() as u32 >= self.config.max_failures.max(1) {
+            return failures.remove(&message_id);
+        }
+        None
+    }
+
+    /// Forget the failures of a message that was handled after all
+    pub fn record_success(&self, message_id: MessageId) {
+        self.failures.lock().remove(&message_id);
+    }
+
+    /// Recent failures of a message
+    pub fn failure_count(&self, message_id: MessageId) -> u32 {
+        self.failures.lock().get(&message_id).map_or(0, |history| history.len() as u32)
+    }
+
+    fn outcome(
+        &self,
+        message: &BusMessage,
+        subscriber: ModuleId,
+        result: std::thread::Result<Result<(), String>>,
+    ) -> HandlerOutcome {
+        let (error, panicked) = match result {
+            Ok(Ok(())) => {
+                self.record_success(message.id);
+                return HandlerOutcome::Handled;
+            }
+            Ok(Err(error)) => (error, false),
+            Err(panic) => (panic_message(panic.as_ref()), true),
+        };
+
+        warn!("{} handler failed on message {}: {}", subscriber, message.id, error);
+        let failure = HandlerFailure { subscriber, error, panicked, at: SystemTime::now() };
+        match self.record_failure(message.id, failure.clone()) {
+            Some(failures) => HandlerOutcome::Poisoned(failures),
+            None => HandlerOutcome::Failed(failure),
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "handler panicked".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessagePayload;
+
+    fn message() -> BusMessage {
+        BusMessage::new(ModuleId::DataCapture, MessagePayload::ModuleReady(ModuleId::DataCapture))
+    }
+
+    #[test]
+    fn test_repeated_panics_poison_the_message() {
+        let detector = PoisonDetector::new(PoisonConfig { max_failures: 3, ..Default::default() });
+        let poison = message();
+        let handler = |_: &BusMessage| -> Result<(), String> { panic!("malformed payload") };
+
+        assert!(matches!(detector.run(&poison, ModuleId::Storage, handler), HandlerOutcome::Failed(_)));
+        assert!(matches!(
+            detector.run(&poison, ModuleId::Storage, |_| Err("still malformed")),
+            HandlerOutcome::Failed(_)
+        ));
+        assert_eq!(detector.failure_count(poison.id), 2);
+
+        let HandlerOutcome::Poisoned(failures) = detector.run(&poison, ModuleId::Storage, handler) else {
+            panic!("third failure should poison the message");
+        };
+        assert_eq!(failures.len(), 3);
+        assert!(failures[0].panicked && failures[0].error == "malformed payload");
+        assert!(!failures[1].panicked && failures[1].error == "still malformed");
+        assert_eq!(detector.failure_count(poison.id), 0);
+    }
+
+    #[tokio::test]
+    async fn test_success_clears_failures() {
+        let detector = PoisonDetector::new(PoisonConfig::default());
+        let flaky = message();
+
+        let outcome = detector.run_async(&flaky, ModuleId::Storage, |_| async { Err::<(), _>("busy") }).await;
+        assert!(matches!(outcome, HandlerOutcome::Failed(_)));
+        let outcome = detector.run_async(&flaky, ModuleId::Storage, |_| async { Ok::<(), String>(()) }).await;
+        assert_eq!(outcome, HandlerOutcome::Handled);
+        assert_eq!(detector.failure_count(flaky.id), 0);
+    }
+}
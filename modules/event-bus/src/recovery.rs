@@ -2,17 +2,29 @@
 //!
 //! Provides comprehensive recovery mechanisms for handling system failures
 //! with intelligent escalation and automated resolution strategies.
+//!
+//! Actions that need approval publish a `ConfirmationRequired` message and wait
+//! for a matching `ConfirmationResponse` before they run; the decision is kept
+//! in the incident's timeline.
+//!
+//! Within an escalation level, actions are tried in order of how often they
+//! resolved similar incidents before (same module and error category), and the
+//! ranking is kept on the incident so the choice can be audited.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 use async_trait::async_trait;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::{
-    ModuleId, EventBusError, EventBusResult,
+    BusMessage, MessagePayload, MessagePriority, ModuleId, EventBusError, EventBusResult,
+    message::{ConfirmationRequired, ConfirmationResponse},
+    router::MessageRouter,
     circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerRegistry},
     retry::{RetryExecutor, RetryConfig, RetryResult},
     dead_letter_queue::{DeadLetterQueue, DeadLetterReason},
@@ -53,6 +65,59 @@ pub enum RecoveryStrategy {
     Custom { action_name: String, parameters: HashMap<String, serde_json::Value> },
 }
 
+/// Machine-readable advice from a failing module on how to recover
+///
+/// Modules attach these to incidents (see [`RecoverySystem::handle_incident_with_hints`])
+/// so recovery can pick a strategy without registered actions that know the
+/// module's error types.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RecoveryHint {
+    /// The operation is likely to succeed if tried again after `delay`
+    RetryAfter { delay: Duration },
+
+    /// The module can keep working in a reduced mode
+    Degrade { fallback_mode: String },
+
+    /// Cached state is bad or too large and should be dropped
+    ClearCaches { cache_names: Vec<String> },
+
+    /// Load on the module should come down by `scale_factor`
+    ReduceLoad { scale_factor: f64 },
+
+    /// The input itself was bad; retrying cannot help, so drop it
+    DiscardInput,
+
+    /// Not automatically recoverable; escalate straight to `level`
+    Escalate { level: EscalationLevel },
+}
+
+impl RecoveryHint {
+    /// The strategy this hint calls for and the level it runs at, if it can be automated
+    pub fn strategy(&self) -> Option<(RecoveryStrategy, EscalationLevel)> {
+        match self {
+            RecoveryHint::RetryAfter { delay } => Some((
+                RecoveryStrategy::Retry {
+                    config: RetryConfig { initial_delay: *delay, ..Default::default() },
+                },
+                EscalationLevel::Automatic,
+            )),
+            RecoveryHint::ClearCaches { cache_names } => Some((
+                RecoveryStrategy::CacheClear { cache_names: cache_names.clone() },
+                EscalationLevel::Automatic,
+            )),
+            RecoveryHint::Degrade { fallback_mode } => Some((
+                RecoveryStrategy::GracefulDegradation { fallback_mode: fallback_mode.clone() },
+                EscalationLevel::Component,
+            )),
+            RecoveryHint::ReduceLoad { scale_factor } => Some((
+                RecoveryStrategy::ResourceScaling { scale_factor: *scale_factor },
+                EscalationLevel::Component,
+            )),
+            RecoveryHint::DiscardInput | RecoveryHint::Escalate { .. } => None,
+        }
+    }
+}
+
 /// Escalation levels for recovery
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EscalationLevel {
@@ -163,6 +228,10 @@ pub struct RecoveryIncident {
     
     /// Severity of the incident
     pub severity: ErrorSeverity,
+
+    /// Category of the error behind the incident, e.g. `Resource` or `ModelLoad`
+    #[serde(default)]
+    pub error_category: String,
     
     /// Current escalation level
     pub escalation_level: EscalationLevel,
@@ -181,6 +250,99 @@ pub struct RecoveryIncident {
     
     /// Metrics and metadata about the incident
     pub metadata: HashMap<String, serde_json::Value>,
+
+    /// Recovery advice from the module that failed
+    #[serde(default)]
+    pub recovery_hints: Vec<RecoveryHint>,
+
+    /// Notable events while handling the incident, oldest first
+    #[serde(default)]
+    pub timeline: Vec<IncidentEvent>,
+
+    /// How the actions at each escalation level were ordered, and why
+    #[serde(default)]
+    pub action_rankings: Vec<ActionRanking>,
+}
+
+/// The order actions were tried in at one escalation level
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionRanking {
+    pub escalation_level: EscalationLevel,
+    /// Best first
+    pub candidates: Vec<RankedAction>,
+}
+
+/// One action's place in an [`ActionRanking`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RankedAction {
+    pub action_id: Uuid,
+    pub action_name: String,
+    /// Times the action ran for incidents in the same module and error category
+    pub similar_attempts: u32,
+    /// How many of those runs succeeded
+    pub similar_successes: u32,
+    /// Smoothed success rate the ranking used; 0.5 for an action with no history
+    pub score: f64,
+    /// Human-readable reason for the action's place
+    pub rationale: String,
+}
+
+/// How an action fared for one kind of incident
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionHistory {
+    pub attempts: u32,
+    pub successes: u32,
+}
+
+impl ActionHistory {
+    /// Success rate with one success and one failure assumed up front,
+    /// so untried actions score 0.5 and a single result cannot dominate
+    pub fn score(&self) -> f64 {
+        (self.successes as f64 + 1.0) / (self.attempts as f64 + 2.0)
+    }
+}
+
+/// Incidents that count as similar when ranking actions
+type HistoryKey = (ModuleId, String, String);
+
+/// An entry in an incident's timeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IncidentEvent {
+    pub at: SystemTime,
+    pub kind: IncidentEventKind,
+}
+
+impl IncidentEvent {
+    fn now(kind: IncidentEventKind) -> Self {
+        Self { at: SystemTime::now(), kind }
+    }
+}
+
+/// What happened at a point in an incident's timeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum IncidentEventKind {
+    /// Approval was requested before running an action
+    ConfirmationRequested { request_id: Uuid, action_id: Uuid, action_name: String },
+
+    /// The approval request was answered, or given up on
+    ConfirmationDecided {
+        request_id: Uuid,
+        action_id: Uuid,
+        decision: ConfirmationDecision,
+        decided_by: Option<String>,
+        reason: Option<String>,
+    },
+}
+
+/// Outcome of asking for approval to run a recovery action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfirmationDecision {
+    Approved,
+    Rejected,
+    /// Nobody answered within `RecoveryConfig::confirmation_timeout`
+    TimedOut,
+    /// The request could not be sent, e.g. no confirmation channel is set
+    Unavailable,
 }
 
 /// Status of a recovery incident
@@ -192,6 +354,9 @@ pub enum IncidentStatus {
     /// Recovery actions are being executed
     Recovering,
     
+    /// Waiting for approval to run the next recovery action
+    AwaitingConfirmation,
+    
     /// Waiting for manual intervention
     AwaitingManualIntervention,
     
@@ -253,6 +418,22 @@ pub struct RecoveryConfig {
     
     /// Notification settings for escalation
     pub notification_config: NotificationConfig,
+
+    /// Actions at this level or above need approval even without `requires_confirmation`
+    #[serde(default = "default_confirmation_level")]
+    pub confirmation_level: EscalationLevel,
+
+    /// How long to wait for a `ConfirmationResponse` before giving up on an action
+    #[serde(default = "default_confirmation_timeout")]
+    pub confirmation_timeout: Duration,
+}
+
+fn default_confirmation_level() -> EscalationLevel {
+    EscalationLevel::System
+}
+
+fn default_confirmation_timeout() -> Duration {
+    Duration::from_secs(120)
 }
 
 /// Configuration for notifications during recovery
@@ -290,6 +471,8 @@ impl Default for RecoveryConfig {
                 email_addresses: vec![],
                 slack_channels: vec![],
             },
+            confirmation_level: default_confirmation_level(),
+            confirmation_timeout: default_confirmation_timeout(),
         }
     }
 }
@@ -311,6 +494,25 @@ pub trait RecoveryActionExecutor: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Where confirmation requests go; the bus router in an event bus
+#[async_trait]
+pub trait ConfirmationChannel: Send + Sync {
+    /// Send `request` to whoever can approve it
+    async fn request_confirmation(&self, request: ConfirmationRequired) -> EventBusResult<()>;
+}
+
+#[async_trait]
+impl ConfirmationChannel for MessageRouter {
+    async fn request_confirmation(&self, request: ConfirmationRequired) -> EventBusResult<()> {
+        let message = BusMessage::with_priority(
+            ModuleId::EventBus,
+            MessagePayload::ConfirmationRequired(request),
+            MessagePriority::High,
+        );
+        self.publish(message).await.map(|_| ())
+    }
+}
+
 /// Statistics about recovery operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecoveryStats {
@@ -337,6 +539,10 @@ pub struct RecoverySystem {
     dead_letter_queue: Arc<DeadLetterQueue>,
     error_logger: Arc<ErrorLogger>,
     stats: Arc<parking_lot::RwLock<RecoveryStats>>,
+    confirmation_channel: Arc<parking_lot::RwLock<Option<Arc<dyn ConfirmationChannel>>>>,
+    pending_confirmations: Arc<parking_lot::Mutex<HashMap<Uuid, oneshot::Sender<ConfirmationResponse>>>>,
+    /// Outcomes per (module, error category, action name)
+    action_history: Arc<parking_lot::RwLock<HashMap<HistoryKey, ActionHistory>>>,
 }
 
 impl RecoverySystem {
@@ -371,6 +577,9 @@ impl RecoverySystem {
             dead_letter_queue,
             error_logger,
             stats: Arc::new(parking_lot::RwLock::new(stats)),
+            confirmation_channel: Arc::new(parking_lot::RwLock::new(None)),
+            pending_confirmations: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            action_history: Arc::new(parking_lot::RwLock::new(HashMap::new())),
         }
     }
 
@@ -386,6 +595,21 @@ impl RecoverySystem {
         self.executors.write().push(executor);
     }
 
+    /// Set where approval requests are sent; without one, actions needing approval are skipped
+    pub fn set_confirmation_channel(&self, channel: Arc<dyn ConfirmationChannel>) {
+        *self.confirmation_channel.write() = Some(channel);
+    }
+
+    /// Deliver an answer to a pending approval request
+    ///
+    /// Returns false if no request with that id is waiting, e.g. it already timed out.
+    pub fn resolve_confirmation(&self, response: ConfirmationResponse) -> bool {
+        match self.pending_confirmations.lock().remove(&response.request_id) {
+            Some(waiter) => waiter.send(response).is_ok(),
+            None => false,
+        }
+    }
+
     /// Detect and handle an incident
     pub async fn handle_incident(
         &self,
@@ -395,6 +619,26 @@ impl RecoverySystem {
         description: String,
     ) -> EventBusResult<IncidentId> {
         let severity = self.classify_error_severity(error);
+        let (_, category) = self.error_logger.classify_event_bus_error(error);
+        self.handle_incident_with_hints(correlation_id, module_id, severity, format!("{:?}", category), description, vec![])
+            .await
+    }
+
+    /// Detect and handle an incident reported with the failing module's recovery hints
+    ///
+    /// Hints are turned into recovery actions that run ahead of registered
+    /// ones at their level. An `Escalate` hint skips automatic recovery, and an
+    /// incident whose only advice is `DiscardInput` is closed straight away.
+    /// `error_category` groups the incident with similar ones when ranking actions.
+    pub async fn handle_incident_with_hints(
+        &self,
+        correlation_id: CorrelationId,
+        module_id: ModuleId,
+        severity: ErrorSeverity,
+        error_category: String,
+        description: String,
+        recovery_hints: Vec<RecoveryHint>,
+    ) -> EventBusResult<IncidentId> {
         let incident_id = Uuid::new_v4();
 
         let incident = RecoveryIncident {
@@ -404,12 +648,16 @@ impl RecoverySystem {
             module_id,
             description: description.clone(),
             severity,
+            error_category,
             escalation_level: EscalationLevel::Automatic,
             attempted_actions: vec![],
             status: IncidentStatus::Detected,
             resolved_at: None,
             resolution: None,
             metadata: HashMap::new(),
+            recovery_hints,
+            timeline: vec![],
+            action_rankings: vec![],
         };
 
         info!("Detected incident {} in module {:?}: {}", incident_id, module_id, description);
@@ -458,17 +706,41 @@ impl RecoverySystem {
 
         info!("Starting recovery for incident {}", incident_id);
         
+        if let Some(level) = incident.recovery_hints.iter().find_map(|hint| match hint {
+            RecoveryHint::Escalate { level } => Some(*level),
+            _ => None,
+        }) {
+            warn!("Incident {} cannot be recovered automatically, escalating to {:?}", incident_id, level);
+            incident.status = IncidentStatus::AwaitingManualIntervention;
+            incident.escalation_level = level;
+            self.update_incident(incident_id, incident.clone());
+            self.update_stats_on_escalation(&incident);
+            self.send_escalation_notification(&incident).await;
+            return Ok(());
+        }
+
+        if !incident.recovery_hints.is_empty()
+            && incident.recovery_hints.iter().all(|hint| *hint == RecoveryHint::DiscardInput)
+        {
+            info!("Incident {} was caused by bad input, closing without recovery", incident_id);
+            incident.status = IncidentStatus::Closed;
+            incident.resolution = Some("Input discarded at the module's request".to_string());
+            self.update_incident(incident_id, incident);
+            return Ok(());
+        }
+
         // Update incident status
         incident.status = IncidentStatus::Recovering;
         self.update_incident(incident_id, incident.clone());
 
+        let hinted_actions = self.actions_from_hints(&incident);
         let mut current_escalation_level = EscalationLevel::Automatic;
 
         // Try recovery actions at each escalation level
         while current_escalation_level <= self.config.max_automatic_escalation_level {
             debug!("Attempting recovery at escalation level {:?} for incident {}", current_escalation_level, incident_id);
 
-            let applicable_actions = self.get_applicable_actions(&incident, current_escalation_level);
+            let applicable_actions = self.get_applicable_actions(&incident, current_escalation_level, &hinted_actions);
             
             if applicable_actions.is_empty() {
                 warn!("No applicable recovery actions found for escalation level {:?}", current_escalation_level);
@@ -476,6 +748,10 @@ impl RecoverySystem {
                 continue;
             }
 
+            let (applicable_actions, ranking) = self.rank_actions(&incident, current_escalation_level, applicable_actions);
+            incident.action_rankings.push(ranking);
+            self.update_incident(incident_id, incident.clone());
+
             let mut recovery_successful = false;
 
             for action in applicable_actions {
@@ -484,10 +760,16 @@ impl RecoverySystem {
                     continue;
                 }
 
+                if self.needs_confirmation(&action) && !self.confirm_action(&action, &mut incident).await {
+                    self.update_incident(incident_id, incident.clone());
+                    continue;
+                }
+
                 info!("Executing recovery action: {} for incident {}", action.name, incident_id);
 
                 match self.execute_action(&action, &incident).await {
                     Ok(result) => {
+                        self.record_action_outcome(&incident, &action, result.success);
                         incident.attempted_actions.push(result.clone());
                         
                         if result.success {
@@ -555,11 +837,19 @@ impl RecoverySystem {
     }
 
     /// Get applicable recovery actions for an incident at a specific escalation level
-    fn get_applicable_actions(&self, incident: &RecoveryIncident, escalation_level: EscalationLevel) -> Vec<RecoveryAction> {
+    ///
+    /// Actions derived from the incident's hints come before registered ones.
+    fn get_applicable_actions(
+        &self,
+        incident: &RecoveryIncident,
+        escalation_level: EscalationLevel,
+        hinted_actions: &[RecoveryAction],
+    ) -> Vec<RecoveryAction> {
         let actions = self.actions.read();
         
-        actions
+        hinted_actions
             .iter()
+            .chain(actions.iter())
             .filter(|action| {
                 action.escalation_level == escalation_level &&
                 self.conditions_met(action, incident)
@@ -568,6 +858,86 @@ impl RecoverySystem {
             .collect()
     }
 
+    /// Order `actions` by how often they resolved similar incidents, best first
+    ///
+    /// The sort is stable, so actions with equal scores keep their hint and
+    /// registration order.
+    fn rank_actions(
+        &self,
+        incident: &RecoveryIncident,
+        escalation_level: EscalationLevel,
+        actions: Vec<RecoveryAction>,
+    ) -> (Vec<RecoveryAction>, ActionRanking) {
+        let mut scored: Vec<(RecoveryAction, RankedAction)> = actions
+            .into_iter()
+            .map(|action| {
+                let history = self.similar_history(incident, &action.name);
+                let rationale = if history.attempts == 0 {
+                    format!("no history for {:?} {} incidents", incident.module_id, incident.error_category)
+                } else {
+                    format!(
+                        "succeeded {} of {} times for {:?} {} incidents",
+                        history.successes, history.attempts, incident.module_id, incident.error_category
+                    )
+                };
+                let ranked = RankedAction {
+                    action_id: action.id,
+                    action_name: action.name.clone(),
+                    similar_attempts: history.attempts,
+                    similar_successes: history.successes,
+                    score: history.score(),
+                    rationale,
+                };
+                (action, ranked)
+            })
+            .collect();
+        scored.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+
+        let (actions, candidates) = scored.into_iter().unzip();
+        (actions, ActionRanking { escalation_level, candidates })
+    }
+
+    /// How the named action fared for incidents like this one
+    pub fn similar_history(&self, incident: &RecoveryIncident, action_name: &str) -> ActionHistory {
+        let key = (incident.module_id, incident.error_category.clone(), action_name.to_string());
+        self.action_history.read().get(&key).copied().unwrap_or_default()
+    }
+
+    /// Remember whether an action worked, for ranking it on later similar incidents
+    fn record_action_outcome(&self, incident: &RecoveryIncident, action: &RecoveryAction, success: bool) {
+        let key = (incident.module_id, incident.error_category.clone(), action.name.clone());
+        let mut history = self.action_history.write();
+        let entry = history.entry(key).or_default();
+        entry.attempts += 1;
+        if success {
+            entry.successes += 1;
+        }
+    }
+
+    /// One-shot recovery actions for the hints attached to an incident
+    fn actions_from_hints(&self, incident: &RecoveryIncident) -> Vec<RecoveryAction> {
+        incident
+            .recovery_hints
+            .iter()
+            .filter_map(|hint| {
+                let (strategy, escalation_level) = hint.strategy()?;
+                Some(RecoveryAction {
+                    id: Uuid::new_v4(),
+                    name: format!("{:?}", hint),
+                    description: format!("Suggested by {:?} for incident {}", incident.module_id, incident.id),
+                    strategy,
+                    escalation_level,
+                    conditions: vec![],
+                    max_executions: 1,
+                    cooldown: Duration::ZERO,
+                    requires_confirmation: false,
+                    expected_recovery_time: self.config.default_action_timeout,
+                    success_threshold: 1.0,
+                })
+            })
+            .collect()
+    }
+
     /// Check if conditions are met for executing an action
     fn conditions_met(&self, action: &RecoveryAction, incident: &RecoveryIncident) -> bool {
         for condition in &action.conditions {
@@ -636,6 +1006,80 @@ impl RecoverySystem {
         true
     }
 
+    /// Whether `action` must be approved before it runs
+    fn needs_confirmation(&self, action: &RecoveryAction) -> bool {
+        action.requires_confirmation || action.escalation_level >= self.config.confirmation_level
+    }
+
+    /// Ask for approval to run `action`, recording the request and decision in the incident's timeline
+    async fn confirm_action(&self, action: &RecoveryAction, incident: &mut RecoveryIncident) -> bool {
+        let request_id = Uuid::new_v4();
+        incident.timeline.push(IncidentEvent::now(IncidentEventKind::ConfirmationRequested {
+            request_id,
+            action_id: action.id,
+            action_name: action.name.clone(),
+        }));
+        let status = std::mem::replace(&mut incident.status, IncidentStatus::AwaitingConfirmation);
+        self.update_incident(incident.id, incident.clone());
+
+        let (decision, response) = self.await_confirmation(request_id, action, incident).await;
+        incident.status = status;
+        incident.timeline.push(IncidentEvent::now(IncidentEventKind::ConfirmationDecided {
+            request_id,
+            action_id: action.id,
+            decision,
+            decided_by: response.as_ref().map(|response| response.decided_by.clone()),
+            reason: response.and_then(|response| response.reason),
+        }));
+
+        if decision == ConfirmationDecision::Approved {
+            info!("Recovery action {} approved for incident {}", action.name, incident.id);
+            true
+        } else {
+            warn!("Recovery action {} not run for incident {}: {:?}", action.name, incident.id, decision);
+            false
+        }
+    }
+
+    async fn await_confirmation(
+        &self,
+        request_id: Uuid,
+        action: &RecoveryAction,
+        incident: &RecoveryIncident,
+    ) -> (ConfirmationDecision, Option<ConfirmationResponse>) {
+        let Some(channel) = self.confirmation_channel.read().clone() else {
+            return (ConfirmationDecision::Unavailable, None);
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        self.pending_confirmations.lock().insert(request_id, sender);
+
+        let request = ConfirmationRequired {
+            request_id,
+            incident_id: incident.id,
+            action_id: action.id,
+            action_name: action.name.clone(),
+            description: action.description.clone(),
+            escalation_level: action.escalation_level as u8,
+            module: incident.module_id,
+            timeout: self.config.confirmation_timeout,
+            timestamp: Utc::now(),
+        };
+        let outcome = match channel.request_confirmation(request).await {
+            Ok(()) => match tokio::time::timeout(self.config.confirmation_timeout, receiver).await {
+                Ok(Ok(response)) if response.approved => (ConfirmationDecision::Approved, Some(response)),
+                Ok(Ok(response)) => (ConfirmationDecision::Rejected, Some(response)),
+                _ => (ConfirmationDecision::TimedOut, None),
+            },
+            Err(e) => {
+                warn!("Failed to request confirmation for action {}: {}", action.name, e);
+                (ConfirmationDecision::Unavailable, None)
+            }
+        };
+        self.pending_confirmations.lock().remove(&request_id);
+        outcome
+    }
+
     /// Execute a specific recovery action
     async fn execute_action(
         &self,
@@ -752,7 +1196,7 @@ impl RecoverySystem {
     /// Classify error severity
     fn classify_error_severity(&self, error: &EventBusError) -> ErrorSeverity {
         match error {
-            EventBusError::QueueFull { .. } => ErrorSeverity::Critical,
+            EventBusError::QueueFull { .. } | EventBusError::MemoryBudgetExceeded { .. } => ErrorSeverity::Critical,
             EventBusError::BusShuttingDown => ErrorSeverity::Warning,
             EventBusError::DeliveryTimeout { .. } => ErrorSeverity::Warning,
             EventBusError::Internal(_) => ErrorSeverity::Critical,
@@ -821,6 +1265,9 @@ impl Clone for RecoverySystem {
             dead_letter_queue: self.dead_letter_queue.clone(),
             error_logger: self.error_logger.clone(),
             stats: self.stats.clone(),
+            confirmation_channel: self.confirmation_channel.clone(),
+            pending_confirmations: self.pending_confirmations.clone(),
+            action_history: self.action_history.clone(),
         }
     }
 }
@@ -1005,12 +1452,16 @@ mod tests {
             module_id: ModuleId::EventBus,
             description: "Test incident".to_string(),
             severity: ErrorSeverity::Error,
+            error_category: "Unknown".to_string(),
             escalation_level: EscalationLevel::Automatic,
             attempted_actions: vec![],
             status: IncidentStatus::Detected,
             resolved_at: None,
             resolution: None,
             metadata: HashMap::new(),
+            recovery_hints: vec![],
+            timeline: vec![],
+            action_rankings: vec![],
         };
 
         assert!(executor.can_handle(&action));
@@ -1032,4 +1483,302 @@ mod tests {
         assert_eq!(recovery_system.next_escalation_level(EscalationLevel::Automatic), EscalationLevel::Component);
         assert_eq!(recovery_system.next_escalation_level(EscalationLevel::Emergency), EscalationLevel::Emergency);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_hints_pick_strategies_and_levels() {
+        let (strategy, level) = RecoveryHint::RetryAfter { delay: Duration::from_millis(250) }.strategy().unwrap();
+        assert_eq!(level, EscalationLevel::Automatic);
+        assert!(matches!(strategy, RecoveryStrategy::Retry { config } if config.initial_delay == Duration::from_millis(250)));
+
+        let (strategy, level) = RecoveryHint::Degrade { fallback_mode: "rule_based".to_string() }.strategy().unwrap();
+        assert_eq!(level, EscalationLevel::Component);
+        assert!(matches!(strategy, RecoveryStrategy::GracefulDegradation { ref fallback_mode } if fallback_mode == "rule_based"));
+
+        assert!(RecoveryHint::DiscardInput.strategy().is_none());
+
+        let recovery_system = create_test_recovery_system();
+        let incident_id = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Critical,
+            "ModelLoad".to_string(),
+            "Model failed to load".to_string(),
+            vec![
+                RecoveryHint::Degrade { fallback_mode: "rule_based".to_string() },
+                RecoveryHint::ReduceLoad { scale_factor: 0.5 },
+            ],
+        ).await.unwrap();
+        let incident = recovery_system.get_incident(incident_id).unwrap();
+        let hinted = recovery_system.actions_from_hints(&incident);
+        assert_eq!(hinted.len(), 2);
+        assert!(hinted.iter().all(|action| action.escalation_level == EscalationLevel::Component));
+        assert_eq!(
+            recovery_system.get_applicable_actions(&incident, EscalationLevel::Component, &hinted).len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escalate_and_discard_hints_skip_automatic_recovery() {
+        let recovery_system = RecoverySystem {
+            config: RecoveryConfig { enable_automatic_recovery: false, ..Default::default() },
+            ..create_test_recovery_system()
+        };
+
+        let escalated = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Fatal,
+            "PrivacyViolation".to_string(),
+            "Network call during local inference".to_string(),
+            vec![RecoveryHint::Escalate { level: EscalationLevel::Emergency }],
+        ).await.unwrap();
+        recovery_system.execute_recovery(escalated).await.unwrap();
+        let incident = recovery_system.get_incident(escalated).unwrap();
+        assert_eq!(incident.status, IncidentStatus::AwaitingManualIntervention);
+        assert_eq!(incident.escalation_level, EscalationLevel::Emergency);
+
+        let discarded = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Warning,
+            "InvalidInput".to_string(),
+            "Feature vector had NaNs".to_string(),
+            vec![RecoveryHint::DiscardInput],
+        ).await.unwrap();
+        recovery_system.execute_recovery(discarded).await.unwrap();
+        assert_eq!(recovery_system.get_incident(discarded).unwrap().status, IncidentStatus::Closed);
+    }
+
+    /// Forwards confirmation requests to the test
+    struct TestChannel(tokio::sync::mpsc::UnboundedSender<ConfirmationRequired>);
+
+    #[async_trait]
+    impl ConfirmationChannel for TestChannel {
+        async fn request_confirmation(&self, request: ConfirmationRequired) -> EventBusResult<()> {
+            self.0.send(request).map_err(|e| EventBusError::Internal(e.to_string()))
+        }
+    }
+
+    fn confirmed_action(name: &str, escalation_level: EscalationLevel) -> RecoveryAction {
+        RecoveryAction {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: format!("{} the analysis engine", name),
+            strategy: RecoveryStrategy::ModuleRestart { module_id: ModuleId::AnalysisEngine },
+            escalation_level,
+            conditions: vec![],
+            max_executions: 1,
+            cooldown: Duration::ZERO,
+            requires_confirmation: true,
+            expected_recovery_time: Duration::from_secs(5),
+            success_threshold: 1.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_decisions_are_recorded_in_the_timeline() {
+        let recovery_system = RecoverySystem {
+            config: RecoveryConfig { enable_automatic_recovery: false, ..Default::default() },
+            ..create_test_recovery_system()
+        };
+        let (sender, mut requests) = tokio::sync::mpsc::unbounded_channel();
+        recovery_system.set_confirmation_channel(Arc::new(TestChannel(sender)));
+
+        // The operator approves restarts and rejects everything else
+        let responder = recovery_system.clone();
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                assert_eq!(request.module, ModuleId::AnalysisEngine);
+                responder.resolve_confirmation(ConfirmationResponse {
+                    request_id: request.request_id,
+                    approved: request.action_name == "restart",
+                    decided_by: "admin-api".to_string(),
+                    reason: Some("checked the dashboard".to_string()),
+                    timestamp: Utc::now(),
+                });
+            }
+        });
+
+        let incident_id = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Critical,
+            "Timeout".to_string(),
+            "Inference stalled".to_string(),
+            vec![],
+        ).await.unwrap();
+        let mut incident = recovery_system.get_incident(incident_id).unwrap();
+
+        let restart = confirmed_action("restart", EscalationLevel::System);
+        assert!(recovery_system.needs_confirmation(&RecoveryAction { requires_confirmation: false, ..restart.clone() }));
+        assert!(recovery_system.confirm_action(&restart, &mut incident).await);
+        assert!(!recovery_system.confirm_action(&confirmed_action("scale", EscalationLevel::Service), &mut incident).await);
+        assert_eq!(incident.status, IncidentStatus::Detected);
+
+        let decisions: Vec<_> = incident.timeline.iter().filter_map(|event| match &event.kind {
+            IncidentEventKind::ConfirmationDecided { decision, decided_by, .. } => Some((*decision, decided_by.clone())),
+            IncidentEventKind::ConfirmationRequested { .. } => None,
+        }).collect();
+        assert_eq!(incident.timeline.len(), 4);
+        assert_eq!(decisions, vec![
+            (ConfirmationDecision::Approved, Some("admin-api".to_string())),
+            (ConfirmationDecision::Rejected, Some("admin-api".to_string())),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_actions_are_skipped() {
+        let recovery_system = RecoverySystem {
+            config: RecoveryConfig {
+                enable_automatic_recovery: false,
+                confirmation_timeout: Duration::from_millis(50),
+                ..Default::default()
+            },
+            ..create_test_recovery_system()
+        };
+        recovery_system.register_action(confirmed_action("restart", EscalationLevel::Component));
+
+        // Without a channel nobody can approve, so the action never runs
+        let incident_id = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Critical,
+            "Timeout".to_string(),
+            "Inference stalled".to_string(),
+            vec![],
+        ).await.unwrap();
+        recovery_system.execute_recovery(incident_id).await.unwrap();
+        let incident = recovery_system.get_incident(incident_id).unwrap();
+        assert!(incident.attempted_actions.is_empty());
+        assert_eq!(incident.status, IncidentStatus::AwaitingManualIntervention);
+        assert!(matches!(
+            incident.timeline.last().unwrap().kind,
+            IncidentEventKind::ConfirmationDecided { decision: ConfirmationDecision::Unavailable, .. }
+        ));
+
+        // Nobody answers in time; a late answer finds nothing waiting
+        let (sender, mut requests) = tokio::sync::mpsc::unbounded_channel();
+        recovery_system.set_confirmation_channel(Arc::new(TestChannel(sender)));
+        let mut incident = recovery_system.get_incident(incident_id).unwrap();
+        assert!(!recovery_system.confirm_action(&confirmed_action("restart", EscalationLevel::System), &mut incident).await);
+        assert!(matches!(
+            incident.timeline.last().unwrap().kind,
+            IncidentEventKind::ConfirmationDecided { decision: ConfirmationDecision::TimedOut, .. }
+        ));
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request.escalation_level, EscalationLevel::System as u8);
+        assert!(!recovery_system.resolve_confirmation(ConfirmationResponse {
+            request_id: request.request_id,
+            approved: true,
+            decided_by: "ui".to_string(),
+            reason: None,
+            timestamp: Utc::now(),
+        }));
+    }
+
+    fn component_action(name: &str) -> RecoveryAction {
+        RecoveryAction {
+            requires_confirmation: false,
+            max_executions: 1,
+            ..confirmed_action(name, EscalationLevel::Component)
+        }
+    }
+
+    async fn timeout_incident(recovery_system: &RecoverySystem, error_category: &str) -> RecoveryIncident {
+        let incident_id = recovery_system.handle_incident_with_hints(
+            Uuid::new_v4(),
+            ModuleId::AnalysisEngine,
+            ErrorSeverity::Error,
+            error_category.to_string(),
+            "Inference too slow".to_string(),
+            vec![],
+        ).await.unwrap();
+        recovery_system.get_incident(incident_id).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_actions_are_ranked_by_success_on_similar_incidents() {
+        let recovery_system = RecoverySystem {
+            config: RecoveryConfig { enable_automatic_recovery: false, ..Default::default() },
+            ..create_test_recovery_system()
+        };
+        let incident = timeout_incident(&recovery_system, "Timeout").await;
+        let (restart, scale, reroute) = (component_action("restart"), component_action("scale"), component_action("reroute"));
+
+        for _ in 0..2 {
+            recovery_system.record_action_outcome(&incident, &restart, false);
+        }
+        for _ in 0..3 {
+            recovery_system.record_action_outcome(&incident, &scale, true);
+        }
+
+        let actions = vec![restart.clone(), scale.clone(), reroute.clone()];
+        let (ranked, ranking) = recovery_system.rank_actions(&incident, EscalationLevel::Component, actions.clone());
+        let names: Vec<&str> = ranked.iter().map(|action| action.name.as_str()).collect();
+        assert_eq!(names, vec!["scale", "reroute", "restart"]);
+        assert_eq!(ranking.candidates[0].score, 0.8);
+        assert_eq!(ranking.candidates[0].rationale, "succeeded 3 of 3 times for AnalysisEngine Timeout incidents");
+        assert_eq!(ranking.candidates[1].rationale, "no history for AnalysisEngine Timeout incidents");
+        assert_eq!((ranking.candidates[2].similar_attempts, ranking.candidates[2].similar_successes), (2, 0));
+
+        // History from another error category does not count
+        let other = timeout_incident(&recovery_system, "ResourceExhausted").await;
+        let (ranked, _) = recovery_system.rank_actions(&other, EscalationLevel::Component, actions);
+        let names: Vec<&str> = ranked.iter().map(|action| action.name.as_str()).collect();
+        assert_eq!(names, vec!["restart", "scale", "reroute"]);
+    }
+
+    /// Fails every action it is given
+    struct FailingExecutor;
+
+    #[async_trait]
+    impl RecoveryActionExecutor for FailingExecutor {
+        async fn execute_action(
+            &self,
+            action: &RecoveryAction,
+            _incident: &RecoveryIncident,
+        ) -> Result<RecoveryActionResult, Box<dyn std::error::Error + Send + Sync>> {
+            Err(format!("{} failed", action.name).into())
+        }
+
+        fn can_handle(&self, _action: &RecoveryAction) -> bool {
+            true
+        }
+
+        fn name(&self) -> &str {
+            "FailingExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recovery_records_ranking_and_learns_outcomes() {
+        let recovery_system = RecoverySystem {
+            config: RecoveryConfig { enable_automatic_recovery: false, ..Default::default() },
+            ..create_test_recovery_system()
+        };
+        recovery_system.register_executor(Arc::new(FailingExecutor));
+        let (restart, scale) = (component_action("restart"), component_action("scale"));
+        recovery_system.register_action(restart.clone());
+        recovery_system.register_action(scale.clone());
+
+        let earlier = timeout_incident(&recovery_system, "Timeout").await;
+        recovery_system.record_action_outcome(&earlier, &scale, true);
+
+        let incident = timeout_incident(&recovery_system, "Timeout").await;
+        recovery_system.execute_recovery(incident.id).await.unwrap();
+        let incident = recovery_system.get_incident(incident.id).unwrap();
+
+        assert_eq!(incident.action_rankings.len(), 1);
+        let ranking = &incident.action_rankings[0];
+        assert_eq!(ranking.escalation_level, EscalationLevel::Component);
+        let order: Vec<Uuid> = ranking.candidates.iter().map(|candidate| candidate.action_id).collect();
+        assert_eq!(order, vec![scale.id, restart.id]);
+        let tried: Vec<Uuid> = incident.attempted_actions.iter().map(|result| result.action_id).collect();
+        assert_eq!(tried, order);
+
+        // Both failures were learned
+        assert_eq!(recovery_system.similar_history(&incident, "scale"), ActionHistory { attempts: 2, successes: 1 });
+        assert_eq!(recovery_system.similar_history(&incident, "restart"), ActionHistory { attempts: 1, successes: 0 });
+    }
+}
@@ -1,13 +1,21 @@
 //! Module registry for tracking registered modules and their health status
+//!
+//! Modules can also declare the interface version of each payload they publish
+//! and the versions they can read. Registration checks those declarations
+//! against the modules already registered, so a partial upgrade that changes a
+//! payload's shape is caught up front instead of as deserialization failures.
 
 use std::collections::HashMap;
+use std::fmt;
 use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
+use semver::{Version, VersionReq};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
+use tracing::warn;
 
-use crate::{ModuleId, EventBusError, EventBusResult};
+use crate::{MessageType, ModuleId, EventBusError, EventBusResult};
 
 /// Health status of a module
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -39,10 +47,16 @@ pub struct ModuleInfo {
     pub last_health_check: Option<DateTime<Utc>>,
     /// Last health check response time
     pub last_response_time: Option<Duration>,
-    /// Module version (if provided)
+    /// Module version (if provided); must be a semver version
     pub version: Option<String>,
     /// Additional metadata
     pub metadata: HashMap<String, String>,
+    /// Interface version of each payload type this module publishes
+    #[serde(default)]
+    pub produces: HashMap<MessageType, Version>,
+    /// Interface versions this module can read, per payload type it consumes
+    #[serde(default)]
+    pub consumes: HashMap<MessageType, VersionReq>,
 }
 
 impl ModuleInfo {
@@ -56,6 +70,8 @@ impl ModuleInfo {
             last_response_time: None,
             version: None,
             metadata: HashMap::new(),
+            produces: HashMap::new(),
+            consumes: HashMap::new(),
         }
     }
 
@@ -71,6 +87,32 @@ impl ModuleInfo {
         self
     }
 
+    /// Declare the interface version of a payload type this module publishes
+    pub fn with_produced_interface(mut self, message_type: MessageType, version: Version) -> Self {
+        self.produces.insert(message_type, version);
+        self
+    }
+
+    /// Declare which interface versions of a payload type this module can read
+    pub fn with_consumed_interface(mut self, message_type: MessageType, requirement: VersionReq) -> Self {
+        self.consumes.insert(message_type, requirement);
+        self
+    }
+
+    /// The module version parsed as semver, if one was given
+    pub fn semver(&self) -> EventBusResult<Option<Version>> {
+        self.version
+            .as_deref()
+            .map(|version| {
+                Version::parse(version).map_err(|e| EventBusError::InvalidModuleVersion {
+                    module_id: self.module_id,
+                    version: version.to_string(),
+                    reason: e.to_string(),
+                })
+            })
+            .transpose()
+    }
+
     /// Update health check status
     pub fn update_health(&mut self, status: ModuleStatus, response_time: Option<Duration>) {
         self.status = status;
@@ -94,6 +136,54 @@ impl ModuleInfo {
     }
 }
 
+/// A payload one module publishes at a version another module cannot read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceMismatch {
+    pub message_type: MessageType,
+    pub producer: ModuleId,
+    pub produced: Version,
+    pub consumer: ModuleId,
+    pub required: VersionReq,
+}
+
+impl fmt::Display for InterfaceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} publishes {:?} v{} but {} requires {}",
+            self.producer, self.message_type, self.produced, self.consumer, self.required
+        )
+    }
+}
+
+/// What registration does when a module's interfaces don't line up with the rest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompatibilityPolicy {
+    /// Refuse the registration with [`EventBusError::IncompatibleModule`]
+    #[default]
+    Refuse,
+    /// Register anyway and log each mismatch
+    Warn,
+}
+
+/// Mismatches where `producer` publishes a payload at a version `consumer` can't read
+fn interface_mismatches(producer: &ModuleInfo, consumer: &ModuleInfo) -> Vec<InterfaceMismatch> {
+    producer
+        .produces
+        .iter()
+        .filter_map(|(message_type, produced)| {
+            let required = consumer.consumes.get(message_type)?;
+            (!required.matches(produced)).then(|| InterfaceMismatch {
+                message_type: *message_type,
+                producer: producer.module_id,
+                produced: produced.clone(),
+                consumer: consumer.module_id,
+                required: required.clone(),
+            })
+        })
+        .collect()
+}
+
 /// Registry configuration
 #[derive(Debug, Clone)]
 pub struct RegistryConfig {
@@ -103,6 +193,8 @@ pub struct RegistryConfig {
     pub cleanup_interval: Duration,
     /// Maximum time to wait for health check response
     pub health_check_timeout: Duration,
+    /// How interface version mismatches are handled at registration
+    pub compatibility: CompatibilityPolicy,
 }
 
 impl Default for RegistryConfig {
@@ -111,6 +203,7 @@ impl Default for RegistryConfig {
             stale_threshold: Duration::from_secs(30),
             cleanup_interval: Duration::from_secs(10),
             health_check_timeout: Duration::from_secs(5),
+            compatibility: CompatibilityPolicy::default(),
         }
     }
 }
@@ -153,7 +246,12 @@ impl ModuleRegistry {
     }
 
     /// Register a new module
+    ///
+    /// The module's version must be valid semver, and its declared interfaces
+    /// are checked against every registered module under the configured
+    /// [`CompatibilityPolicy`].
     pub fn register_module(&self, module_info: ModuleInfo) -> EventBusResult<()> {
+        module_info.semver()?;
         let mut modules = self.modules.write();
         
         if modules.contains_key(&module_info.module_id) {
@@ -162,10 +260,49 @@ impl ModuleRegistry {
             });
         }
 
+        let mismatches = Self::find_mismatches(&module_info, modules.values());
+        if !mismatches.is_empty() {
+            match self.config.compatibility {
+                CompatibilityPolicy::Refuse => {
+                    return Err(EventBusError::IncompatibleModule {
+                        module_id: module_info.module_id,
+                        mismatches,
+                    });
+                }
+                CompatibilityPolicy::Warn => {
+                    for mismatch in &mismatches {
+                        warn!("Registering {} despite interface mismatch: {}", module_info.module_id, mismatch);
+                    }
+                }
+            }
+        }
+
         modules.insert(module_info.module_id, module_info);
         Ok(())
     }
 
+    /// Interface mismatches `module_info` would have with the registered modules
+    ///
+    /// A registered module with the same id is left out, so an upgraded build
+    /// can be checked before it replaces the running one.
+    pub fn check_compatibility(&self, module_info: &ModuleInfo) -> Vec<InterfaceMismatch> {
+        Self::find_mismatches(module_info, self.modules.read().values())
+    }
+
+    fn find_mismatches<'a>(
+        module_info: &ModuleInfo,
+        registered: impl Iterator<Item = &'a ModuleInfo>,
+    ) -> Vec<InterfaceMismatch> {
+        registered
+            .filter(|other| other.module_id != module_info.module_id)
+            .flat_map(|other| {
+                let mut mismatches = interface_mismatches(module_info, other);
+                mismatches.extend(interface_mismatches(other, module_info));
+                mismatches
+            })
+            .collect()
+    }
+
     /// Unregister a module
     pub fn unregister_module(&self, module_id: ModuleId) -> EventBusResult<ModuleInfo> {
         let mut modules = self.modules.write();
@@ -442,4 +579,68 @@ mod tests {
         let stale_modules = registry.find_stale_modules();
         assert!(stale_modules.contains(&ModuleId::DataCapture));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_incompatible_interfaces_are_refused() {
+        let registry = ModuleRegistry::new(RegistryConfig::default());
+        registry
+            .register_module(
+                ModuleInfo::new(ModuleId::Storage)
+                    .with_version("2.1.0".to_string())
+                    .with_consumed_interface(MessageType::RawEvent, VersionReq::parse("^1.2").unwrap()),
+            )
+            .unwrap();
+
+        // A minor bump on the producer side is still readable
+        registry
+            .register_module(
+                ModuleInfo::new(ModuleId::DataCapture)
+                    .with_produced_interface(MessageType::RawEvent, Version::new(1, 4, 0)),
+            )
+            .unwrap();
+
+        // An upgraded build writing v2 events would break storage
+        let upgraded = ModuleInfo::new(ModuleId::DataCapture)
+            .with_produced_interface(MessageType::RawEvent, Version::new(2, 0, 0));
+        let mismatches = registry.check_compatibility(&upgraded);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!((mismatches[0].producer, mismatches[0].consumer), (ModuleId::DataCapture, ModuleId::Storage));
+
+        registry.unregister_module(ModuleId::DataCapture).unwrap();
+        match registry.register_module(upgraded) {
+            Err(EventBusError::IncompatibleModule { module_id, mismatches }) => {
+                assert_eq!(module_id, ModuleId::DataCapture);
+                assert_eq!(mismatches[0].produced, Version::new(2, 0, 0));
+            }
+            other => panic!("expected incompatible module, got {:?}", other),
+        }
+        assert!(registry.get_module_info(ModuleId::DataCapture).is_none());
+    }
+
+    #[test]
+    fn test_warn_policy_registers_mismatches_and_versions_must_be_semver() {
+        let registry = ModuleRegistry::new(RegistryConfig {
+            compatibility: CompatibilityPolicy::Warn,
+            ..Default::default()
+        });
+        registry
+            .register_module(
+                ModuleInfo::new(ModuleId::AnalysisEngine)
+                    .with_produced_interface(MessageType::AnalysisComplete, Version::new(3, 0, 0)),
+            )
+            .unwrap();
+        registry
+            .register_module(
+                ModuleInfo::new(ModuleId::Gamification)
+                    .with_consumed_interface(MessageType::AnalysisComplete, VersionReq::parse("^2").unwrap()),
+            )
+            .unwrap();
+        assert_eq!(registry.get_all_modules().len(), 2);
+
+        let unversioned = ModuleInfo::new(ModuleId::Storage).with_version("latest".to_string());
+        assert!(matches!(
+            registry.register_module(unversioned),
+            Err(EventBusError::InvalidModuleVersion { .. })
+        ));
+    }
+}
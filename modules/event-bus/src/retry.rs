@@ -2,7 +2,13 @@
 //!
 //! Provides robust retry mechanisms with exponential backoff, jitter, and configurable
 //! maximum attempts for handling transient failures in distributed systems.
+//!
+//! [`RetryExecutor::execute_hedged`] adds hedging for latency-critical work: when an
+//! attempt runs past a recent latency percentile, a second one is started and the
+//! first to succeed wins.
 
+use std::collections::VecDeque;
+use std::future::Future;
 use std::time::{Duration, Instant};
 use rand::Rng;
 use tracing::{debug, warn, error};
@@ -31,6 +37,41 @@ pub struct RetryConfig {
     
     /// Whether to reset delay on success
     pub reset_on_success: bool,
+    
+    /// Hedging for `execute_hedged` (`None` runs a single attempt)
+    #[serde(default)]
+    pub hedging: Option<HedgeConfig>,
+}
+
+/// When `execute_hedged` sends a second attempt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HedgeConfig {
+    /// Hedge once an attempt outlasts this percentile of recent attempt latencies (0.0-1.0]
+    pub delay_percentile: f64,
+    
+    /// Delay used until `min_samples` latencies have been seen
+    pub initial_delay: Duration,
+    
+    /// Never hedge sooner than this
+    pub min_delay: Duration,
+    
+    /// Latencies needed before the percentile is trusted
+    pub min_samples: usize,
+    
+    /// Recent latencies kept for the percentile
+    pub sample_window: usize,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            delay_percentile: 0.95,
+            initial_delay: Duration::from_millis(50),
+            min_delay: Duration::from_millis(5),
+            min_samples: 20,
+            sample_window: 200,
+        }
+    }
 }
 
 impl Default for RetryConfig {
@@ -43,6 +84,7 @@ impl Default for RetryConfig {
             jitter_factor: 0.1, // 10% jitter
             total_timeout: Some(Duration::from_secs(300)), // 5 minutes
             reset_on_success: true,
+            hedging: None,
         }
     }
 }
@@ -67,6 +109,15 @@ pub struct RetryStats {
     pub average_success_time: Duration,
     pub max_attempts_reached: u64,
     pub timeout_exceeded: u64,
+    /// Second attempts started by `execute_hedged`
+    #[serde(default)]
+    pub hedges_issued: u64,
+    /// Hedges that succeeded before the original attempt
+    #[serde(default)]
+    pub hedge_wins: u64,
+    /// `hedge_wins / hedges_issued`, 0 until a hedge was sent
+    #[serde(default)]
+    pub hedge_win_rate: f64,
 }
 
 /// Error types for retry operations
@@ -141,6 +192,8 @@ where
 pub struct RetryExecutor {
     config: RetryConfig,
     stats: parking_lot::RwLock<RetryStats>,
+    /// Recent attempt latencies from `execute_hedged`
+    hedge_latencies: parking_lot::Mutex<VecDeque<Duration>>,
 }
 
 impl RetryExecutor {
@@ -164,6 +217,14 @@ impl RetryExecutor {
                 reason: "jitter_factor must be between 0.0 and 1.0".to_string(),
             });
         }
+        
+        if let Some(hedging) = &config.hedging {
+            if hedging.delay_percentile <= 0.0 || hedging.delay_percentile > 1.0 {
+                return Err(RetryError::ConfigurationError {
+                    reason: "hedging.delay_percentile must be in (0.0, 1.0]".to_string(),
+                });
+            }
+        }
 
         let stats = RetryStats {
             total_operations: 0,
@@ -174,11 +235,15 @@ impl RetryExecutor {
             average_success_time: Duration::from_millis(0),
             max_attempts_reached: 0,
             timeout_exceeded: 0,
+            hedges_issued: 0,
+            hedge_wins: 0,
+            hedge_win_rate: 0.0,
         };
 
         Ok(Self {
             config,
             stats: parking_lot::RwLock::new(stats),
+            hedge_latencies: parking_lot::Mutex::new(VecDeque::new()),
         })
     }
 
@@ -278,6 +343,84 @@ impl RetryExecutor {
         self.execute(operation, DefaultRetryPolicy).await
     }
 
+    /// Run `operation` once, starting a second attempt if the first is slow
+    ///
+    /// Without `hedging` configured this is a single attempt. Otherwise, if attempt 1
+    /// hasn't finished after the hedge delay, attempt 2 starts alongside it. The first
+    /// success is returned and the other attempt is dropped, which cancels it; if both
+    /// fail, the later error is returned. The operation must be safe to run twice.
+    pub async fn execute_hedged<F, Fut, T, E>(&self, mut operation: F) -> Result<T, E>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(hedging) = &self.config.hedging else {
+            return operation(1).await;
+        };
+        let delay = self.hedge_delay(hedging);
+
+        let started = Instant::now();
+        let primary = operation(1);
+        tokio::pin!(primary);
+        tokio::select! {
+            result = &mut primary => {
+                self.record_hedge_latency(hedging, started.elapsed());
+                return result;
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        debug!("Attempt still running after {:?}, sending a hedge", delay);
+        self.stats.write().hedges_issued += 1;
+        let hedge_started = Instant::now();
+        let hedge = operation(2);
+        tokio::pin!(hedge);
+
+        let (from_hedge, result) = tokio::select! {
+            result = &mut primary => (false, result),
+            result = &mut hedge => (true, result),
+        };
+        // A failure only ends the race once the other attempt has failed too
+        let (from_hedge, result) = match result {
+            Err(_) if from_hedge => (false, primary.await),
+            Err(_) => (true, hedge.await),
+            ok => (from_hedge, ok),
+        };
+
+        let elapsed = if from_hedge { hedge_started.elapsed() } else { started.elapsed() };
+        self.record_hedge_latency(hedging, elapsed);
+        self.record_hedge_result(from_hedge && result.is_ok());
+        result
+    }
+
+    /// The configured percentile of recent attempt latencies
+    fn hedge_delay(&self, hedging: &HedgeConfig) -> Duration {
+        let latencies = self.hedge_latencies.lock();
+        if latencies.is_empty() || latencies.len() < hedging.min_samples {
+            return hedging.initial_delay.max(hedging.min_delay);
+        }
+        let mut sorted: Vec<Duration> = latencies.iter().copied().collect();
+        sorted.sort();
+        let rank = ((hedging.delay_percentile * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+        sorted[rank - 1].max(hedging.min_delay)
+    }
+
+    fn record_hedge_latency(&self, hedging: &HedgeConfig, latency: Duration) {
+        let mut latencies = self.hedge_latencies.lock();
+        latencies.push_back(latency);
+        while latencies.len() > hedging.sample_window.max(1) {
+            latencies.pop_front();
+        }
+    }
+
+    fn record_hedge_result(&self, hedge_won: bool) {
+        let mut stats = self.stats.write();
+        if hedge_won {
+            stats.hedge_wins += 1;
+        }
+        stats.hedge_win_rate = stats.hedge_wins as f64 / stats.hedges_issued as f64;
+    }
+
     /// Calculate the next delay using exponential backoff
     fn calculate_next_delay(&self, current_delay: Duration) -> Duration {
         let next_delay_ms = (current_delay.as_millis() as f64 * self.config.backoff_multiplier) as u64;
@@ -371,6 +514,9 @@ impl RetryExecutor {
             average_success_time: Duration::from_millis(0),
             max_attempts_reached: 0,
             timeout_exceeded: 0,
+            hedges_issued: 0,
+            hedge_wins: 0,
+            hedge_win_rate: 0.0,
         };
     }
 
@@ -652,5 +798,88 @@ mod tests {
             ..RetryConfig::default()
         };
         assert!(RetryExecutor::new(config).is_err());
+        
+        // Test invalid hedge percentile
+        let config = RetryConfig {
+            hedging: Some(HedgeConfig { delay_percentile: 0.0, ..HedgeConfig::default() }),
+            ..RetryConfig::default()
+        };
+        assert!(RetryExecutor::new(config).is_err());
+    }
+
+    fn hedging_executor(hedging: HedgeConfig) -> RetryExecutor {
+        RetryExecutor::new(RetryConfig { hedging: Some(hedging), ..RetryConfig::default() }).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hedge_wins_over_slow_attempt_and_cancels_it() {
+        let executor = hedging_executor(HedgeConfig {
+            initial_delay: Duration::from_millis(20),
+            min_samples: 100,
+            ..HedgeConfig::default()
+        });
+        let in_flight = Arc::new(AtomicU32::new(0));
+
+        let started = Instant::now();
+        let result = executor.execute_hedged(|attempt| {
+            let in_flight = in_flight.clone();
+            async move {
+                in_flight.fetch_add(1, Ordering::SeqCst);
+                if attempt == 1 {
+                    sleep(Duration::from_secs(2)).await;
+                }
+                Ok::<u32, String>(attempt)
+            }
+        }).await;
+
+        assert_eq!(result, Ok(2));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(in_flight.load(Ordering::SeqCst), 2);
+        // The slow attempt was dropped along with its clone
+        assert_eq!(Arc::strong_count(&in_flight), 1);
+
+        let stats = executor.stats();
+        assert_eq!((stats.hedges_issued, stats.hedge_wins), (1, 1));
+        assert_eq!(stats.hedge_win_rate, 1.0);
+
+        // Without hedging configured only one attempt runs
+        let single = create_retry_executor().unwrap();
+        assert_eq!(single.execute_hedged(|attempt| async move { Ok::<u32, String>(attempt) }).await, Ok(1));
+        assert_eq!(single.stats().hedges_issued, 0);
+    }
+
+    #[tokio::test]
+    async fn test_hedge_delay_follows_recent_latency_and_failures_wait_for_the_other_attempt() {
+        let executor = hedging_executor(HedgeConfig {
+            initial_delay: Duration::from_secs(10),
+            min_delay: Duration::from_millis(5),
+            min_samples: 3,
+            ..HedgeConfig::default()
+        });
+
+        // Fast attempts never hedge, and teach the executor a short delay
+        for _ in 0..3 {
+            assert!(executor.execute_hedged(|_| async { Ok::<(), String>(()) }).await.is_ok());
+        }
+        assert_eq!(executor.stats().hedges_issued, 0);
+
+        // The first attempt fails after the hedge went out; the hedge's success is used
+        let result = executor.execute_hedged(|attempt| async move {
+            sleep(Duration::from_millis(if attempt == 1 { 30 } else { 60 })).await;
+            if attempt == 1 { Err("flaky".to_string()) } else { Ok(attempt) }
+        }).await;
+        assert_eq!(result, Ok(2));
+
+        // Both fail: the later error is returned. The hedge delay is now the ~60ms
+        // the last hedge took, so attempt 1 has to be slower than that to be hedged.
+        let result = executor.execute_hedged(|attempt| async move {
+            sleep(Duration::from_millis(if attempt == 1 { 100 } else { 80 })).await;
+            Err::<(), String>(format!("attempt {} failed", attempt))
+        }).await;
+        assert_eq!(result, Err("attempt 2 failed".to_string()));
+
+        let stats = executor.stats();
+        assert_eq!((stats.hedges_issued, stats.hedge_wins), (2, 1));
+        assert_eq!(stats.hedge_win_rate, 0.5);
     }
 }
\ No newline at end of file
@@ -2,16 +2,26 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 use crossbeam_channel::{Receiver, Sender};
 use tracing::{debug, error, warn};
 
 use crate::{
     BusMessage, EventBusError, EventBusResult, MessageId, ModuleId,
-    subscription::SubscriptionManager,
+    audit::{Auditor, DeliveryOutcome},
+    memory_budget::{MemoryBudgetConfig, QueueMemory},
+    message::CompressionConfig,
+    subscription::{shard_for, SubscriptionManager},
     metrics::MetricsCollector,
 };
 
+/// How often aggregated subscriptions are checked for expired windows
+const DIGEST_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a worker may wait on the queue before checking whether to stop
+const WORKER_RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
 /// High-performance message router
 pub struct MessageRouter {
     /// Subscription manager for tracking all active subscriptions
@@ -23,17 +33,33 @@ pub struct MessageRouter {
     /// Direct channels for high-frequency module-to-module communication
     direct_channels: Arc<parking_lot::RwLock<HashMap<(ModuleId, ModuleId), Sender<BusMessage>>>>,
     
-    /// Main message queue for async delivery
-    message_queue: (Sender<QueuedMessage>, Receiver<QueuedMessage>),
+    /// One delivery queue per subscription shard; idle workers steal from the others
+    shard_queues: Vec<(Sender<QueuedMessage>, Receiver<QueuedMessage>)>,
+    
+    /// One queue per worker for messages an ordered subscription wants, keyed by publisher
+    /// and drained only by its own worker, so each publisher's messages stay in sequence
+    ordered_queues: Vec<(Sender<QueuedMessage>, Receiver<QueuedMessage>)>,
+    
+    /// Enqueues onto the queues above; shared with the workers so they can refill from the spill
+    senders: QueueSenders,
+    
+    /// Bytes held by each queue, checked against the memory budget
+    memory: Arc<QueueMemory>,
     
     /// Configuration
     config: RouterConfig,
     
-    /// Shutdown signal
-    shutdown_signal: Arc<tokio::sync::Notify>,
+    /// Cleared to stop the workers once they finish the message in hand
+    workers_running: Arc<AtomicBool>,
+    
+    /// Workers that have not exited yet
+    active_workers: Arc<AtomicUsize>,
     
     /// Router state
     is_running: Arc<parking_lot::RwLock<bool>>,
+    
+    /// Records message outcomes while an audit runs
+    auditor: Arc<Auditor>,
 }
 
 /// Configuration for the message router
@@ -45,11 +71,17 @@ pub struct RouterConfig {
     /// Timeout for message delivery
     pub delivery_timeout: Duration,
     
-    /// Number of worker threads for message processing
+    /// Number of worker tasks, which is also the number of subscription shards and queues
     pub worker_threads: usize,
     
     /// Buffer size for direct channels
     pub direct_channel_buffer: usize,
+    
+    /// Compress large payloads for subscribers that accept it (`None` never compresses)
+    pub compression: Option<CompressionConfig>,
+    
+    /// Byte limit for everything waiting in the routing queues (`None` only limits the message count)
+    pub memory_budget: Option<MemoryBudgetConfig>,
 }
 
 impl Default for RouterConfig {
@@ -59,31 +91,163 @@ impl Default for RouterConfig {
             delivery_timeout: Duration::from_secs(5),
             worker_threads: 4,
             direct_channel_buffer: 1_000,
+            compression: Some(CompressionConfig::default()),
+            memory_budget: None,
         }
     }
 }
 
+/// Outcome of draining the routing queue at shutdown
+#[derive(Debug, Default)]
+pub(crate) struct RouterDrain {
+    /// Queued messages that reached every interested subscriber
+    pub delivered: usize,
+    /// Messages that missed at least one subscriber, with the subscribers that missed them
+    pub undelivered: Vec<(BusMessage, Vec<ModuleId>)>,
+    /// Open aggregation windows delivered early
+    pub digests_flushed: usize,
+    /// Whether the deadline passed with messages still queued
+    pub timed_out: bool,
+}
+
 /// A message in the routing queue with metadata
 #[derive(Debug)]
 struct QueuedMessage {
     message: BusMessage,
     queued_at: SystemTime,
     retry_count: u32,
+    /// Estimated bytes charged to the memory budget
+    size: usize,
+    /// Index of the queue holding it, ordered lanes first, then shard queues
+    queue: usize,
+}
+
+impl QueuedMessage {
+    fn new(message: BusMessage, size: usize) -> Self {
+        Self {
+            message,
+            queued_at: SystemTime::now(),
+            retry_count: 0,
+            size,
+            queue: 0,
+        }
+    }
+}
+
+/// Why a message could not be put on a routing queue
+enum EnqueueError {
+    /// Every queue it may go on is full; the message is handed back
+    Full(Box<QueuedMessage>),
+    Disconnected,
+}
+
+/// Sending ends of the routing queues
+///
+/// Charges each message's bytes to the queue it lands on. The router and its
+/// workers share a copy, so workers can move spilled messages back in as
+/// deliveries free up room.
+#[derive(Clone)]
+struct QueueSenders {
+    ordered: Vec<Sender<QueuedMessage>>,
+    shared: Vec<Sender<QueuedMessage>>,
+    subscription_manager: Arc<SubscriptionManager>,
+    memory: Arc<QueueMemory>,
+}
+
+impl QueueSenders {
+    /// Put a message whose bytes are already reserved on its queue
+    fn enqueue(&self, mut queued: QueuedMessage) -> Result<(), EnqueueError> {
+        // Messages an ordered subscription wants wait behind earlier ones from the same publisher
+        if self.subscription_manager.needs_ordering(&queued.message) {
+            let lane = shard_for(queued.message.source, self.ordered.len());
+            return self.try_send(&self.ordered[lane], lane, queued);
+        }
+
+        // Start at the message type's own shard and spill over to the next when it is full,
+        // so one busy message type can still use the whole queue capacity
+        let home = self.subscription_manager.shard_for(queued.message.message_type());
+        for offset in 0..self.shared.len() {
+            let shard = (home + offset) % self.shared.len();
+            match self.try_send(&self.shared[shard], self.ordered.len() + shard, queued) {
+                Err(EnqueueError::Full(rejected)) => queued = *rejected,
+                sent_or_disconnected => return sent_or_disconnected,
+            }
+        }
+        Err(EnqueueError::Full(Box::new(queued)))
+    }
+
+    /// Charge the bytes before sending so a worker never releases them first
+    fn try_send(&self, sender: &Sender<QueuedMessage>, queue: usize, mut queued: QueuedMessage) -> Result<(), EnqueueError> {
+        queued.queue = queue;
+        let size = queued.size;
+        self.memory.charge(queue, size);
+        sender.try_send(queued).map_err(|e| {
+            self.memory.uncharge(queue, size);
+            match e {
+                crossbeam_channel::TrySendError::Full(rejected) => EnqueueError::Full(Box::new(rejected)),
+                crossbeam_channel::TrySendError::Disconnected(_) => EnqueueError::Disconnected,
+            }
+        })
+    }
+
+    /// Move spilled messages back onto the queues while they fit the budget, oldest first
+    fn refill(&self) {
+        while self.memory.spilled() > 0 {
+            let Some((message, size)) = self.memory.unspill() else { break };
+            match self.enqueue(QueuedMessage::new(message, size)) {
+                Ok(()) => {}
+                Err(EnqueueError::Full(rejected)) => {
+                    self.memory.put_back(rejected.message, size);
+                    break;
+                }
+                Err(EnqueueError::Disconnected) => {
+                    warn!("Routing queues disconnected with {} messages spilled", self.memory.spilled());
+                    self.memory.cancel(size);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn queued_count(&self) -> usize {
+        self.ordered.iter().chain(&self.shared).map(Sender::len).sum()
+    }
 }
 
 impl MessageRouter {
     /// Create a new message router
     pub fn new(config: RouterConfig) -> Self {
-        let (sender, receiver) = crossbeam_channel::bounded(config.max_queue_size);
+        let shards = config.worker_threads.max(1);
+        let shard_capacity = (config.max_queue_size / shards).max(1);
+        let shard_queues: Vec<_> = (0..shards).map(|_| crossbeam_channel::bounded(shard_capacity)).collect();
+        let ordered_queues: Vec<_> = (0..shards).map(|_| crossbeam_channel::bounded(shard_capacity)).collect();
+        
+        let subscription_manager = match &config.compression {
+            Some(compression) => SubscriptionManager::with_shards(shards).with_compression(compression.clone()),
+            None => SubscriptionManager::with_shards(shards),
+        };
+        let subscription_manager = Arc::new(subscription_manager);
+        let memory = Arc::new(QueueMemory::new(2 * shards, config.memory_budget.clone()));
+        let senders = QueueSenders {
+            ordered: ordered_queues.iter().map(|(sender, _)| sender.clone()).collect(),
+            shared: shard_queues.iter().map(|(sender, _)| sender.clone()).collect(),
+            subscription_manager: Arc::clone(&subscription_manager),
+            memory: Arc::clone(&memory),
+        };
         
         Self {
-            subscription_manager: Arc::new(SubscriptionManager::new()),
-            metrics: Arc::new(MetricsCollector::new()),
+            subscription_manager,
+            metrics: Arc::new(MetricsCollector::new().with_queue_memory(Arc::clone(&memory))),
             direct_channels: Arc::new(parking_lot::RwLock::new(HashMap::new())),
-            message_queue: (sender, receiver),
+            shard_queues,
+            ordered_queues,
+            senders,
+            memory,
             config,
-            shutdown_signal: Arc::new(tokio::sync::Notify::new()),
+            workers_running: Arc::new(AtomicBool::new(false)),
+            active_workers: Arc::new(AtomicUsize::new(0)),
             is_running: Arc::new(parking_lot::RwLock::new(false)),
+            auditor: Arc::new(Auditor::new()),
         }
     }
 
@@ -98,27 +262,48 @@ impl MessageRouter {
         }
 
         debug!("Starting message router with {} worker threads", self.config.worker_threads);
+        self.workers_running.store(true, Ordering::SeqCst);
 
-        // Start worker tasks
-        for worker_id in 0..self.config.worker_threads {
-            let receiver = self.message_queue.1.clone();
+        // Start one worker per shard queue
+        let queues = Arc::new(WorkerQueues {
+            ordered: self.ordered_queues.iter().map(|(_, receiver)| receiver.clone()).collect(),
+            shared: self.shard_queues.iter().map(|(_, receiver)| receiver.clone()).collect(),
+        });
+        for worker_id in 0..self.shard_queues.len() {
+            let queues = Arc::clone(&queues);
+            let senders = self.senders.clone();
             let subscription_manager = Arc::clone(&self.subscription_manager);
             let metrics = Arc::clone(&self.metrics);
-            let shutdown_signal = Arc::clone(&self.shutdown_signal);
-            let config = self.config.clone();
+            let workers_running = Arc::clone(&self.workers_running);
+            let active_workers = Arc::clone(&self.active_workers);
+            let auditor = Arc::clone(&self.auditor);
 
+            active_workers.fetch_add(1, Ordering::SeqCst);
             tokio::spawn(async move {
                 Self::worker_loop(
                     worker_id,
-                    receiver,
+                    queues,
+                    senders,
                     subscription_manager,
                     metrics,
-                    shutdown_signal,
-                    config,
+                    workers_running,
+                    auditor,
                 ).await;
+                active_workers.fetch_sub(1, Ordering::SeqCst);
             });
         }
 
+        // Close aggregation windows that run out of time between publishes
+        let subscription_manager = Arc::clone(&self.subscription_manager);
+        let is_running = Arc::clone(&self.is_running);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DIGEST_FLUSH_INTERVAL);
+            while *is_running.read() {
+                interval.tick().await;
+                subscription_manager.flush_expired_digests(Instant::now());
+            }
+        });
+
         debug!("Message router started successfully");
         Ok(())
     }
@@ -132,15 +317,89 @@ impl MessageRouter {
             *running = false;
         }
 
-        self.shutdown_signal.notify_waiters();
-        
-        // Give workers time to finish processing current messages
-        tokio::time::sleep(Duration::from_millis(100)).await;
+        self.stop_workers().await;
         
         debug!("Message router stopped");
         Ok(())
     }
 
+    /// Stop the workers after the message each has in hand; queued messages stay queued
+    async fn stop_workers(&self) {
+        self.workers_running.store(false, Ordering::SeqCst);
+
+        // Each worker notices within one receive timeout
+        let give_up = Instant::now() + WORKER_RECV_TIMEOUT * 5;
+        while self.active_workers.load(Ordering::SeqCst) > 0 && Instant::now() < give_up {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Deliver what is still queued on the calling task until the queue is empty or
+    /// `deadline` passes, then stop accepting messages. Publishes keep being accepted
+    /// while the drain runs; anything left over is handed back as undelivered.
+    pub(crate) async fn drain(&self, deadline: Duration) -> RouterDrain {
+        let started = Instant::now();
+        self.stop_workers().await;
+
+        let mut drain = RouterDrain::default();
+        loop {
+            if started.elapsed() >= deadline {
+                drain.timed_out = self.queued_count() + self.memory.spilled() > 0;
+                break;
+            }
+            // Spilled messages were published after everything still queued
+            let queued = match self.all_queues().find_map(|(_, receiver)| receiver.try_recv().ok()) {
+                Some(queued) => {
+                    self.memory.release(queued.queue, queued.size);
+                    queued
+                }
+                None => match self.memory.take_spilled() {
+                    Some(message) => QueuedMessage::new(message, 0),
+                    None => break,
+                },
+            };
+
+            let message_type = queued.message.message_type();
+            let results = self.subscription_manager.deliver_message(queued.message.clone());
+            let latency = queued.queued_at.elapsed().unwrap_or_default();
+            self.metrics.record_deliveries(queued.message.source, message_type, latency, results.successful);
+            self.metrics.record_flows(queued.message.source, message_type, latency, &results.delivered_to);
+            for _ in &results.failed_subscribers {
+                self.metrics.record_failure(queued.message.source, message_type);
+            }
+            self.metrics.record_compression(&results.compression, results.compressed);
+
+            if results.failed_subscribers.is_empty() {
+                drain.delivered += 1;
+            } else {
+                drain.undelivered.push((queued.message, results.failed_subscribers));
+            }
+        }
+
+        {
+            let mut running = self.is_running.write();
+            *running = false;
+        }
+
+        // Whatever is still queued, or was buffered for a paused module, can no longer be delivered
+        for (_, receiver) in self.all_queues() {
+            while let Ok(queued) = receiver.try_recv() {
+                self.memory.release(queued.queue, queued.size);
+                drain.undelivered.push((queued.message, Vec::new()));
+            }
+        }
+        while let Some(message) = self.memory.take_spilled() {
+            drain.undelivered.push((message, Vec::new()));
+        }
+        for (module, message) in self.subscription_manager.take_paused() {
+            drain.undelivered.push((message, vec![module]));
+        }
+        drain.digests_flushed = self.subscription_manager.flush_all_digests();
+        self.metrics.update_queue_depth(0);
+
+        drain
+    }
+
     /// Publish a message through the router
     pub async fn publish(&self, message: BusMessage) -> EventBusResult<MessageId> {
         if !*self.is_running.read() {
@@ -157,6 +416,9 @@ impl MessageRouter {
         // Record publish metrics
         self.metrics.record_publish(source, message_type, message_size);
 
+        // Only kept while auditing, to record a routing failure
+        let audited = self.auditor.is_active().then(|| message.clone());
+
         // Route based on message type and optimization strategy
         match self.route_message(message).await {
             Ok(_) => {
@@ -166,6 +428,9 @@ impl MessageRouter {
             Err(e) => {
                 error!("Failed to route message {}: {}", message_id, e);
                 self.metrics.record_failure(source, message_type);
+                if let Some(message) = audited {
+                    self.auditor.record(&message, || DeliveryOutcome::Rejected { reason: e.to_string() });
+                }
                 Err(e)
             }
         }
@@ -197,12 +462,20 @@ impl MessageRouter {
 
     /// Send message via direct channel
     async fn send_direct(&self, route: (ModuleId, ModuleId), message: BusMessage) -> EventBusResult<()> {
+        // A paused target must not be reached around its buffer
+        if self.subscription_manager.is_paused(route.1) {
+            return self.queue_for_delivery(message).await;
+        }
+
         {
             let direct_channels = self.direct_channels.read();
             
             if let Some(sender) = direct_channels.get(&route) {
                 match sender.try_send(message.clone()) {
-                    Ok(_) => return Ok(()),
+                    Ok(_) => {
+                        self.auditor.record(&message, || DeliveryOutcome::Direct { to: route.1 });
+                        return Ok(());
+                    }
                     Err(crossbeam_channel::TrySendError::Full(_)) => {
                         // Fall back to standard routing if direct channel is full
                     }
@@ -219,29 +492,58 @@ impl MessageRouter {
 
     /// Queue message for standard pub-sub delivery
     async fn queue_for_delivery(&self, message: BusMessage) -> EventBusResult<()> {
-        let queued_message = QueuedMessage {
-            message,
-            queued_at: SystemTime::now(),
-            retry_count: 0,
-        };
+        let size = estimate_message_size(&message);
 
-        match self.message_queue.0.try_send(queued_message) {
-            Ok(_) => {
-                self.metrics.update_queue_depth(self.message_queue.1.len());
+        // Once anything has spilled, later messages wait behind it so publish order holds
+        if self.memory.spilled() > 0 {
+            return self.spill(&message, size);
+        }
+        if !self.memory.try_reserve(size) {
+            if self.memory.spills() {
+                debug!("Spilling message {} ({} bytes) over the memory budget", message.id, size);
+                return self.spill(&message, size);
+            }
+            self.memory.record_rejection(size);
+            return Err(EventBusError::MemoryBudgetExceeded {
+                requested: size,
+                used: self.memory.used(),
+                budget: self.memory.max_bytes().unwrap_or(usize::MAX),
+            });
+        }
+
+        match self.senders.enqueue(QueuedMessage::new(message, size)) {
+            Ok(()) => {
+                self.metrics.update_queue_depth(self.queued_count());
                 Ok(())
             }
-            Err(crossbeam_channel::TrySendError::Full(_)) => {
+            Err(EnqueueError::Full(_)) => {
+                self.memory.cancel(size);
                 Err(EventBusError::QueueFull {
-                    current_size: self.message_queue.1.len(),
+                    current_size: self.queued_count(),
                     max_size: self.config.max_queue_size,
                 })
             }
-            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+            Err(EnqueueError::Disconnected) => {
+                self.memory.cancel(size);
                 Err(EventBusError::ChannelSend("Message queue disconnected".to_string()))
             }
         }
     }
 
+    fn spill(&self, message: &BusMessage, size: usize) -> EventBusResult<()> {
+        self.memory.spill(message, size).inspect_err(|_| self.memory.record_rejection(size))
+    }
+
+    /// Ordered queues first, then the shared shard queues
+    fn all_queues(&self) -> impl Iterator<Item = &(Sender<QueuedMessage>, Receiver<QueuedMessage>)> {
+        self.ordered_queues.iter().chain(&self.shard_queues)
+    }
+
+    /// Messages waiting in all queues
+    fn queued_count(&self) -> usize {
+        self.senders.queued_count()
+    }
+
     /// Register a direct channel between two modules
     pub fn register_direct_channel(&self, from: ModuleId, to: ModuleId, sender: Sender<BusMessage>) {
         let mut channels = self.direct_channels.write();
@@ -259,33 +561,39 @@ impl MessageRouter {
         &self.metrics
     }
 
+    pub(crate) fn auditor(&self) -> &Arc<Auditor> {
+        &self.auditor
+    }
+
     /// Worker loop for processing messages
     async fn worker_loop(
         worker_id: usize,
-        receiver: Receiver<QueuedMessage>,
+        queues: Arc<WorkerQueues>,
+        senders: QueueSenders,
         subscription_manager: Arc<SubscriptionManager>,
         metrics: Arc<MetricsCollector>,
-        shutdown_signal: Arc<tokio::sync::Notify>,
-        _config: RouterConfig,
+        workers_running: Arc<AtomicBool>,
+        auditor: Arc<Auditor>,
     ) {
         debug!("Worker {} started", worker_id);
 
-        loop {
-            // Check for shutdown signal with timeout
-            let recv_result = tokio::select! {
-                _ = shutdown_signal.notified() => {
-                    debug!("Worker {} received shutdown signal", worker_id);
-                    break;
-                }
-                result = tokio::task::spawn_blocking({
-                    let receiver = receiver.clone();
-                    move || receiver.recv_timeout(Duration::from_millis(100))
-                }) => {
-                    match result {
-                        Ok(recv_result) => recv_result,
-                        Err(_) => continue, // Task was cancelled
-                    }
-                }
+        // A received message is always delivered before the worker checks whether to stop,
+        // so stopping never loses one
+        while workers_running.load(Ordering::SeqCst) {
+            if senders.memory.spilled() > 0 {
+                senders.refill();
+            }
+
+            // Own queues first, then steal shared work; only block when all are empty
+            let recv_result = match queues.next(worker_id) {
+                Some(queued) => Ok(queued),
+                None => match tokio::task::spawn_blocking({
+                    let queues = Arc::clone(&queues);
+                    move || queues.recv_timeout(worker_id, WORKER_RECV_TIMEOUT)
+                }).await {
+                    Ok(recv_result) => recv_result,
+                    Err(_) => continue, // Task was cancelled
+                },
             };
 
             match recv_result {
@@ -299,22 +607,27 @@ impl MessageRouter {
                     let delivery_latency = start_time.elapsed().unwrap_or_default();
                     let message_type = queued_message.message.message_type();
                     
-                    if results.successful > 0 {
-                        // Record successful deliveries
-                        for _ in 0..results.successful {
-                            metrics.record_delivery(
-                                queued_message.message.source,
-                                message_type,
-                                delivery_latency,
-                            );
-                        }
-                    }
+                    metrics.record_deliveries(
+                        queued_message.message.source,
+                        message_type,
+                        delivery_latency,
+                        results.successful,
+                    );
+                    metrics.record_flows(
+                        queued_message.message.source,
+                        message_type,
+                        delivery_latency,
+                        &results.delivered_to,
+                    );
                     
                     // Record failures
                     let total_failures = results.queue_full + results.disconnected + results.timeout;
                     for _ in 0..total_failures {
                         metrics.record_failure(queued_message.message.source, message_type);
                     }
+                    metrics.record_compression(&results.compression, results.compressed);
+                    auditor.record(&queued_message.message, || DeliveryOutcome::from_results(&results));
+                    senders.memory.release(queued_message.queue, queued_message.size);
                     
                     if results.total_attempted() > 0 {
                         debug!(
@@ -349,8 +662,49 @@ impl MessageRouter {
     }
 }
 
+/// The queues a worker takes messages from
+struct WorkerQueues {
+    /// Per-worker ordered lanes; never stolen from
+    ordered: Vec<Receiver<QueuedMessage>>,
+    /// Per-shard queues any worker may take from
+    shared: Vec<Receiver<QueuedMessage>>,
+}
+
+impl WorkerQueues {
+    /// Take the next message from `worker_id`'s own queues, or steal one from another shard
+    fn next(&self, worker_id: usize) -> Option<QueuedMessage> {
+        if let Ok(queued) = self.ordered[worker_id].try_recv() {
+            return Some(queued);
+        }
+        (0..self.shared.len())
+            .map(|offset| &self.shared[(worker_id + offset) % self.shared.len()])
+            .find_map(|queue| queue.try_recv().ok())
+    }
+
+    /// Wait up to `timeout` for a message on any queue `worker_id` may take from
+    fn recv_timeout(
+        &self,
+        worker_id: usize,
+        timeout: Duration,
+    ) -> Result<QueuedMessage, crossbeam_channel::RecvTimeoutError> {
+        let queues: Vec<&Receiver<QueuedMessage>> =
+            std::iter::once(&self.ordered[worker_id]).chain(&self.shared).collect();
+        let mut select = crossbeam_channel::Select::new();
+        for queue in &queues {
+            select.recv(queue);
+        }
+        let operation = select
+            .select_timeout(timeout)
+            .map_err(|_| crossbeam_channel::RecvTimeoutError::Timeout)?;
+        let index = operation.index();
+        operation
+            .recv(queues[index])
+            .map_err(|_| crossbeam_channel::RecvTimeoutError::Disconnected)
+    }
+}
+
 /// Estimate the size of a message for metrics purposes
-fn estimate_message_size(message: &BusMessage) -> usize {
+pub(crate) fn estimate_message_size(message: &BusMessage) -> usize {
     // This is a rough estimate - in production you might use actual serialization
     use std::mem;
     
@@ -358,21 +712,200 @@ fn estimate_message_size(message: &BusMessage) -> usize {
     
     // Add estimated payload size based on type
     let payload_size = match &message.payload {
-        crate::MessagePayload::RawEvent(_) => 500,  // Typical event size
-        crate::MessagePayload::EventBatch(_) => 5000, // Batch of events
+        // Screenshots and other bulky captures travel in the event data
+        crate::MessagePayload::RawEvent(event) => raw_event_size(event),
+        crate::MessagePayload::EventBatch(batch) => 100 + batch.events.iter().map(raw_event_size).sum::<usize>(),
         crate::MessagePayload::StorageStatus(_) => 200,
         crate::MessagePayload::AnalysisComplete(_) => 300,
         crate::MessagePayload::StateChange(_) => 150,
+        crate::MessagePayload::TrainingCompleted(_) => 200,
+        crate::MessagePayload::DriftDetected(_) => 150,
+        crate::MessagePayload::FocusCheckResult(result) => 200 + result.explanation.len(),
+        crate::MessagePayload::FocusCheckRequest(_) => 100,
+        crate::MessagePayload::CurrentTask(current) => 80 + current.task.as_ref().map_or(0, |task| task.description.len()),
+        crate::MessagePayload::CheckInResponse(_) => 120,
+        crate::MessagePayload::DayActivity(activity) => 150 + 120 * activity.sessions.len(),
+        crate::MessagePayload::ForecastUpdated(_) => 120,
         crate::MessagePayload::InterventionRequest(_) => 400,
         crate::MessagePayload::RewardEvent(_) => 200,
+        crate::MessagePayload::RewardGranted(granted) => if granted.unlock.is_some() { 400 } else { 200 },
         crate::MessagePayload::InterventionResponse(_) => 600,
+        crate::MessagePayload::InterventionDelivered(_) => 120,
         crate::MessagePayload::AnimationCommand(_) => 300,
+        crate::MessagePayload::ModelDownloadProgress(_) => 150,
+        crate::MessagePayload::DailySummaryRequest(_) => 80,
+        crate::MessagePayload::DailySummary(summary) => 150 + summary.text.len(),
         crate::MessagePayload::HealthCheck(_) => 100,
         crate::MessagePayload::ConfigUpdate(_) => 250,
+        crate::MessagePayload::ResourceViolation(_) => 150,
+        crate::MessagePayload::PowerStateChanged(_) => 150,
+        crate::MessagePayload::TelemetryBatch(batch) => 100 * batch.samples.len().max(1),
+        crate::MessagePayload::PerformanceRegression(_) => 150,
+        crate::MessagePayload::MemoryLeakSuspected(leak) => 150 + 24 * leak.samples.len(),
+        crate::MessagePayload::RetransmitRequest(_) => 80,
+        crate::MessagePayload::ConfigTransactionResult(result) => 200 + 100 * result.errors.len(),
+        crate::MessagePayload::MaintenanceRequest(_) => 120,
+        crate::MessagePayload::SystemPower(_) => 80,
         crate::MessagePayload::Shutdown(_) => 50,
         crate::MessagePayload::ModuleReady(_) => 50,
+        crate::MessagePayload::DeliveryAck(_) => 80,
+        crate::MessagePayload::MessageDigest(digest) => 100 + digest.total_bytes,
+        crate::MessagePayload::PoisonMessageDetected(poison) => 200 + poison.errors.iter().map(String::len).sum::<usize>(),
+        crate::MessagePayload::ConfirmationRequired(request) => 200 + request.description.len(),
+        crate::MessagePayload::ConfirmationResponse(response) => 120 + response.reason.as_ref().map_or(0, String::len),
+        crate::MessagePayload::MaintenanceReport(report) => 120 + report.details.as_ref().map_or(0, String::len),
         crate::MessagePayload::Error(_) => 400,
+        crate::MessagePayload::Compressed(compressed) => 50 + compressed.data.len(),
     };
     
     base_size + payload_size
-}
\ No newline at end of file
+}
+
+fn raw_event_size(event: &crate::message::RawEvent) -> usize {
+    100 + event.event_type.len() + event.window_title.as_ref().map_or(0, String::len) + json_size(&event.data)
+}
+
+/// Roughly the serialized length of a JSON value, without serializing it
+fn json_size(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Bool(_) => 5,
+        serde_json::Value::Number(_) => 8,
+        serde_json::Value::String(text) => text.len() + 2,
+        serde_json::Value::Array(items) => 2 + items.iter().map(|item| json_size(item) + 1).sum::<usize>(),
+        serde_json::Value::Object(fields) => {
+            2 + fields.iter().map(|(key, value)| key.len() + 4 + json_size(value)).sum::<usize>()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subscription::{DeliveryMode, MessageFilter, OrderingMode, Subscription},
+        MessagePayload,
+    };
+
+    fn queued(module: ModuleId) -> QueuedMessage {
+        QueuedMessage::new(BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(module)), 0)
+    }
+
+    #[test]
+    fn test_idle_worker_steals_from_busy_shard() {
+        let ordered: Vec<_> = (0..3).map(|_| crossbeam_channel::bounded(8)).collect();
+        let shared: Vec<_> = (0..3).map(|_| crossbeam_channel::bounded(8)).collect();
+        let queues = WorkerQueues {
+            ordered: ordered.iter().map(|(_, r)| r.clone()).collect(),
+            shared: shared.iter().map(|(_, r)| r.clone()).collect(),
+        };
+        shared[0].0.send(queued(ModuleId::Storage)).unwrap();
+        shared[0].0.send(queued(ModuleId::Gamification)).unwrap();
+        shared[2].0.send(queued(ModuleId::AiIntegration)).unwrap();
+        ordered[0].0.send(queued(ModuleId::Orchestrator)).unwrap();
+
+        // Worker 2 drains its own queue before stealing, and never takes another worker's ordered lane
+        let own = queues.next(2).unwrap();
+        assert!(matches!(own.message.payload, MessagePayload::ModuleReady(ModuleId::AiIntegration)));
+        let stolen = queues.next(2).unwrap();
+        assert!(matches!(stolen.message.payload, MessagePayload::ModuleReady(ModuleId::Storage)));
+
+        let waited = queues.recv_timeout(1, Duration::from_millis(10)).unwrap();
+        assert!(matches!(waited.message.payload, MessagePayload::ModuleReady(ModuleId::Gamification)));
+        assert!(queues.next(1).is_none());
+        assert!(queues.recv_timeout(1, Duration::from_millis(10)).is_err());
+
+        let lane = queues.next(0).unwrap();
+        assert!(matches!(lane.message.payload, MessagePayload::ModuleReady(ModuleId::Orchestrator)));
+    }
+
+    #[tokio::test]
+    async fn test_memory_budget_rejects_or_spills_large_payloads() {
+        let screenshot = |index: u64| {
+            let event = crate::message::RawEvent {
+                event_type: "screenshot".to_string(),
+                data: serde_json::json!({ "png": "x".repeat(400_000) }),
+                window_title: None,
+                timestamp: chrono::Utc::now(),
+            };
+            BusMessage::new(ModuleId::Gamification, MessagePayload::RawEvent(event)).with_sequence(index)
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let spill = crate::MemorySpillConfig::new(dir.path().join("spill.jsonl"));
+
+        for budget in [MemoryBudgetConfig::new(1_000_000), MemoryBudgetConfig::new(1_000_000).with_spill(spill)] {
+            let spills = matches!(budget.policy, crate::MemoryLimitPolicy::Spill(_));
+            let router = MessageRouter::new(RouterConfig { memory_budget: Some(budget), ..Default::default() });
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            router.subscription_manager().add_subscription(Subscription::new(
+                ModuleId::Storage,
+                MessageFilter::all(),
+                DeliveryMode::BestEffort,
+                sender,
+            ));
+            router.start().await.unwrap();
+            // Nothing is delivered until the drain, so the queues only fill up
+            router.stop_workers().await;
+
+            router.publish(screenshot(0)).await.unwrap();
+            router.publish(screenshot(1)).await.unwrap();
+            let third = router.publish(screenshot(2)).await;
+            let memory = router.metrics().snapshot(HashMap::new()).memory_usage;
+            assert!(memory.queue_memory_bytes > 800_000 && memory.queue_memory_bytes <= 1_000_000);
+            assert_eq!(memory.queue_bytes.iter().sum::<u64>(), memory.queue_memory_bytes);
+            assert_eq!(memory.budget_bytes, Some(1_000_000));
+
+            if spills {
+                third.unwrap();
+                // Everything after a spilled message waits behind it
+                router.publish(BusMessage::new(ModuleId::Gamification, MessagePayload::ModuleReady(ModuleId::Storage)).with_sequence(3)).await.unwrap();
+                assert_eq!(router.metrics().snapshot(HashMap::new()).memory_usage.spilled_messages, 2);
+
+                let drain = router.drain(Duration::from_secs(5)).await;
+                assert_eq!(drain.delivered, 4);
+                let sequences: Vec<u64> = receiver.try_iter().filter_map(|m| m.sequence).collect();
+                assert_eq!(sequences, vec![0, 1, 2, 3]);
+            } else {
+                assert!(matches!(third, Err(EventBusError::MemoryBudgetExceeded { budget: 1_000_000, .. })));
+                assert_eq!(router.metrics().snapshot(HashMap::new()).memory_usage.rejected_messages, 1);
+                router.drain(Duration::from_secs(5)).await;
+            }
+            let memory = router.metrics().snapshot(HashMap::new()).memory_usage;
+            assert_eq!((memory.queue_memory_bytes, memory.spilled_messages), (0, 0));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_ordered_subscription_sees_publish_order() {
+        let router = MessageRouter::new(RouterConfig { worker_threads: 4, ..Default::default() });
+        let (ordered_tx, ordered_rx) = crossbeam_channel::unbounded();
+        let (unordered_tx, unordered_rx) = crossbeam_channel::unbounded();
+        let manager = router.subscription_manager();
+        let analysis_filter = MessageFilter::sources(vec![ModuleId::DataCapture]);
+        manager.add_subscription(
+            Subscription::new(ModuleId::AnalysisEngine, analysis_filter, DeliveryMode::BestEffort, ordered_tx)
+                .with_ordering(OrderingMode::PerPublisher),
+        );
+        manager.add_subscription(Subscription::new(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort, unordered_tx));
+        router.start().await.unwrap();
+
+        let from = |source: ModuleId, sequence: u64| {
+            BusMessage::new(source, MessagePayload::ModuleReady(ModuleId::Storage)).with_sequence(sequence)
+        };
+        assert!(manager.needs_ordering(&from(ModuleId::DataCapture, 0)));
+        assert!(!manager.needs_ordering(&from(ModuleId::Gamification, 0)));
+
+        for sequence in 0..2_000 {
+            router.publish(from(ModuleId::DataCapture, sequence)).await.unwrap();
+            router.publish(from(ModuleId::Gamification, sequence)).await.unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while (ordered_rx.len() < 2_000 || unordered_rx.len() < 4_000) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        router.stop().await.unwrap();
+
+        let sequences: Vec<u64> = ordered_rx.try_iter().filter_map(|m| m.sequence).collect();
+        assert_eq!(sequences, (0..2_000).collect::<Vec<_>>());
+        assert_eq!(unordered_rx.len(), 4_000);
+    }
+}
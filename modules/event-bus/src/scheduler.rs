@@ -0,0 +1,290 @@
+//! Delayed and scheduled message delivery
+//!
+//! Messages published with `publish_after`/`publish_at` wait in a hashed timer
+//! wheel and are routed once their tick comes round. With a persistence path
+//! configured, pending messages are written to disk on every change and picked
+//! up again when the bus starts, so a follow-up survives a restart.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{BusMessage, MessageId, router::MessageRouter};
+
+/// Resolution of scheduled delivery
+const TICK: Duration = Duration::from_millis(100);
+
+/// Slots in the wheel; one revolution covers `TICK * WHEEL_SLOTS`
+const WHEEL_SLOTS: usize = 512;
+
+/// A message waiting for its delivery time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ScheduledMessage {
+    pub deliver_at: DateTime<Utc>,
+    pub message: BusMessage,
+}
+
+/// Hashed timer wheel: one slot per tick, wrapping around; entries more than a
+/// revolution out stay in their slot until their own tick is reached
+pub(crate) struct TimerWheel {
+    tick: Duration,
+    slots: Vec<Vec<(u64, ScheduledMessage)>>,
+    origin: DateTime<Utc>,
+    /// Ticks turned since `origin`
+    elapsed: u64,
+}
+
+impl TimerWheel {
+    pub fn new(tick: Duration, slots: usize, origin: DateTime<Utc>) -> Self {
+        Self {
+            tick,
+            slots: (0..slots.max(1)).map(|_| Vec::new()).collect(),
+            origin,
+            elapsed: 0,
+        }
+    }
+
+    /// Ticks from the origin up to `at`, rounded up or down
+    fn ticks_until(&self, at: DateTime<Utc>, round_up: bool) -> u64 {
+        let offset = (at - self.origin).to_std().unwrap_or_default().as_nanos();
+        let tick = self.tick.as_nanos().max(1);
+        let ticks = if round_up { offset.div_ceil(tick) } else { offset / tick };
+        ticks as u64
+    }
+
+    pub fn insert(&mut self, entry: ScheduledMessage) {
+        // Never into a tick that has already been turned past
+        let due = self.ticks_until(entry.deliver_at, true).max(self.elapsed + 1);
+        let slot = (due % self.slots.len() as u64) as usize;
+        self.slots[slot].push((due, entry));
+    }
+
+    pub fn cancel(&mut self, message_id: MessageId) -> Option<ScheduledMessage> {
+        for slot in &mut self.slots {
+            if let Some(index) = slot.iter().position(|(_, entry)| entry.message.id == message_id) {
+                return Some(slot.remove(index).1);
+            }
+        }
+        None
+    }
+
+    /// Turn the wheel to `now`, returning what fell due in delivery order
+    pub fn advance(&mut self, now: DateTime<Utc>) -> Vec<ScheduledMessage> {
+        let target = self.ticks_until(now, false);
+        let mut due = Vec::new();
+        if target <= self.elapsed {
+            return due;
+        }
+
+        // A gap longer than a revolution only needs each slot visited once
+        let steps = (target - self.elapsed).min(self.slots.len() as u64);
+        let slot_count = self.slots.len() as u64;
+        for step in 1..=steps {
+            let slot = &mut self.slots[((self.elapsed + step) % slot_count) as usize];
+            let (ready, waiting): (Vec<_>, Vec<_>) = slot.drain(..).partition(|(tick, _)| *tick <= target);
+            *slot = waiting;
+            due.extend(ready.into_iter().map(|(_, entry)| entry));
+        }
+        self.elapsed = target;
+
+        due.sort_by_key(|entry| entry.deliver_at);
+        due
+    }
+
+    pub fn pending(&self) -> Vec<ScheduledMessage> {
+        let mut pending: Vec<ScheduledMessage> =
+            self.slots.iter().flatten().map(|(_, entry)| entry.clone()).collect();
+        pending.sort_by_key(|entry| entry.deliver_at);
+        pending
+    }
+}
+
+/// Drives the timer wheel for a bus and routes messages as they fall due
+pub(crate) struct MessageScheduler {
+    wheel: parking_lot::Mutex<TimerWheel>,
+    persistence_path: Option<PathBuf>,
+    is_running: Arc<AtomicBool>,
+}
+
+impl MessageScheduler {
+    pub fn new(persistence_path: Option<PathBuf>) -> Self {
+        Self {
+            wheel: parking_lot::Mutex::new(TimerWheel::new(TICK, WHEEL_SLOTS, Utc::now())),
+            persistence_path,
+            is_running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn schedule(&self, message: BusMessage, deliver_at: DateTime<Utc>) {
+        debug!("Scheduling message {} for {}", message.id, deliver_at);
+        self.wheel.lock().insert(ScheduledMessage { deliver_at, message });
+        self.persist();
+    }
+
+    pub fn cancel(&self, message_id: MessageId) -> bool {
+        let cancelled = self.wheel.lock().cancel(message_id).is_some();
+        if cancelled {
+            self.persist();
+        }
+        cancelled
+    }
+
+    /// Reload persisted messages and start routing them through `router`
+    pub fn start(self: &Arc<Self>, router: Arc<MessageRouter>) {
+        if self.is_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let restored = self.load_from_disk();
+        if restored > 0 {
+            info!("⏰ Restored {} scheduled messages", restored);
+        }
+
+        let scheduler = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            while scheduler.is_running.load(Ordering::SeqCst) {
+                interval.tick().await;
+                scheduler.fire_due(&router).await;
+            }
+            debug!("Message scheduler stopped");
+        });
+    }
+
+    /// Stop routing; pending messages stay in the wheel (and on disk)
+    pub fn stop(&self) {
+        self.is_running.store(false, Ordering::SeqCst);
+    }
+
+    async fn fire_due(&self, router: &MessageRouter) {
+        let due = self.wheel.lock().advance(Utc::now());
+        if due.is_empty() {
+            return;
+        }
+
+        for entry in due {
+            let message_id = entry.message.id;
+            match router.publish(entry.message.clone()).await {
+                Ok(_) => debug!("Published scheduled message {}", message_id),
+                Err(e) if e.is_recoverable() => {
+                    debug!("Scheduled message {} deferred: {}", message_id, e);
+                    self.wheel.lock().insert(ScheduledMessage { deliver_at: Utc::now(), ..entry });
+                }
+                Err(e) => warn!("Dropping scheduled message {}: {}", message_id, e),
+            }
+        }
+        self.persist();
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.persistence_path else { return };
+        let pending = self.wheel.lock().pending();
+        let result = serde_json::to_string_pretty(&pending)
+            .map_err(std::io::Error::from)
+            .and_then(|data| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, data)?;
+                std::fs::rename(&tmp, path)
+            });
+        if let Err(e) = result {
+            warn!("Failed to persist scheduled messages to {}: {}", path.display(), e);
+        }
+    }
+
+    fn load_from_disk(&self) -> usize {
+        let Some(path) = &self.persistence_path else { return 0 };
+        if !path.exists() {
+            return 0;
+        }
+
+        let loaded: Vec<ScheduledMessage> = match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()))
+        {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("Ignoring unreadable scheduled messages in {}: {}", path.display(), e);
+                return 0;
+            }
+        };
+
+        let mut wheel = self.wheel.lock();
+        let known: std::collections::HashSet<MessageId> =
+            wheel.pending().iter().map(|entry| entry.message.id).collect();
+        let mut restored = 0;
+        for entry in loaded.into_iter().filter(|entry| !known.contains(&entry.message.id)) {
+            wheel.insert(entry);
+            restored += 1;
+        }
+        restored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MessagePayload, ModuleId};
+
+    fn at(origin: DateTime<Utc>, millis: i64) -> DateTime<Utc> {
+        origin + chrono::Duration::milliseconds(millis)
+    }
+
+    fn entry(deliver_at: DateTime<Utc>) -> ScheduledMessage {
+        ScheduledMessage {
+            deliver_at,
+            message: BusMessage::new(ModuleId::AiIntegration, MessagePayload::ModuleReady(ModuleId::AiIntegration)),
+        }
+    }
+
+    #[test]
+    fn test_wheel_releases_in_order_across_revolutions() {
+        let origin = Utc::now();
+        let mut wheel = TimerWheel::new(Duration::from_millis(100), 8, origin);
+
+        // 2.5s is three revolutions of an 8-slot, 100ms wheel away
+        let late = entry(at(origin, 2_500));
+        let early = entry(at(origin, 250));
+        let cancelled = entry(at(origin, 400));
+        wheel.insert(late.clone());
+        wheel.insert(early.clone());
+        wheel.insert(cancelled.clone());
+        assert!(wheel.cancel(cancelled.message.id).is_some());
+        assert_eq!(wheel.pending().len(), 2);
+
+        assert!(wheel.advance(at(origin, 200)).is_empty());
+        let due = wheel.advance(at(origin, 300));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].message.id, early.message.id);
+
+        // The late entry shares a slot with ticks passed on earlier revolutions
+        assert!(wheel.advance(at(origin, 2_400)).is_empty());
+        let due = wheel.advance(at(origin, 10_000));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].message.id, late.message.id);
+        assert!(wheel.pending().is_empty());
+    }
+
+    #[test]
+    fn test_pending_messages_survive_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduled.json");
+
+        let nudge = entry(Utc::now() + chrono::Duration::minutes(20));
+        let scheduler = MessageScheduler::new(Some(path.clone()));
+        scheduler.schedule(nudge.message.clone(), nudge.deliver_at);
+        let dropped = entry(Utc::now() + chrono::Duration::minutes(5));
+        scheduler.schedule(dropped.message.clone(), dropped.deliver_at);
+        assert!(scheduler.cancel(dropped.message.id));
+
+        let restarted = MessageScheduler::new(Some(path));
+        assert_eq!(restarted.load_from_disk(), 1);
+        let pending = restarted.wheel.lock().pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message.id, nudge.message.id);
+        assert_eq!(pending[0].deliver_at, nudge.deliver_at);
+    }
+}
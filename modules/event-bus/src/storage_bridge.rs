@@ -0,0 +1,355 @@
+//! Bus messages for the storage module
+//!
+//! Storage sits below the event bus in the dependency graph and has its own
+//! message enum, so it can't subscribe itself. With the `storage-bridge`
+//! feature, [`bridge_to_storage`] subscribes on its behalf and forwards what
+//! storage acts on into the channel from `StorageModule::sender`:
+//!
+//! - the orchestrator's `user_profile` config update becomes `ProfileSwitch`
+//! - `TelemetryBatch` samples are persisted as they are
+//! - `DeliveryAck` and `RetransmitRequest` ranges release or resend the
+//!   messages [`publish_sequenced`] left with storage; resent messages are
+//!   published again unchanged
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use skelly_jelly_storage::{
+    BusMessage as StorageMessage, RawEvent as CapturedEvent, SequenceRange, SequencedMessage, StorageModule,
+    TelemetrySample,
+};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::{
+    message::RawEvent, BusMessage, DeliveryMode, EventBus, EventBusError, EventBusResult, EventBusTrait,
+    MessageFilter, MessageId, MessagePayload, MessageType, ModuleId,
+};
+
+/// Config key of the orchestrator's user profile broadcast
+pub const USER_PROFILE_KEY: &str = "user_profile";
+
+/// Subscribe as storage and forward into it until either side goes away
+///
+/// Also publishes what storage resends for a `RetransmitRequest`, if this is
+/// the first bridge for `storage`.
+pub fn bridge_to_storage(bus: &EventBus, storage: &mut StorageModule) -> EventBusResult<JoinHandle<()>> {
+    let filter = MessageFilter::types(vec![
+        MessageType::ConfigUpdate,
+        MessageType::TelemetryBatch,
+        MessageType::DeliveryAck,
+        MessageType::RetransmitRequest,
+    ])
+    .with_predicate(|message| match &message.payload {
+        MessagePayload::ConfigUpdate(update) => update.config_key == USER_PROFILE_KEY,
+        _ => true,
+    });
+    let (_, mut messages) =
+        bus.subscribe_stream(ModuleId::Storage, filter, DeliveryMode::Reliable { timeout: Duration::from_secs(5) })?;
+    let mut retransmissions = storage.take_retransmissions();
+    let storage = storage.sender();
+    let bus = Arc::clone(bus);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                message = messages.next() => {
+                    let Some(message) = message else { break };
+                    let Some(converted) = to_storage(message) else { continue };
+                    if storage.send(converted).await.is_err() {
+                        break;
+                    }
+                }
+                Some(kept) = next_retransmission(&mut retransmissions) => {
+                    if let Err(e) = republish(bus.as_ref(), kept).await {
+                        warn!("Failed to republish a retransmitted message: {}", e);
+                    }
+                }
+            }
+        }
+        debug!("Storage bridge finished");
+    }))
+}
+
+/// Leave a copy of a sequenced message with storage, then publish it
+///
+/// Storage keeps the copy until `consumer` acknowledges the sequence number,
+/// so a later `RetransmitRequest` for it can be answered.
+pub async fn publish_sequenced(
+    bus: &dyn EventBusTrait,
+    storage: &mpsc::Sender<StorageMessage>,
+    message: BusMessage,
+    consumer: ModuleId,
+) -> EventBusResult<MessageId> {
+    if let Some(sequence) = message.sequence {
+        let kept = SequencedMessage {
+            producer: message.source.to_string(),
+            consumer: consumer.to_string(),
+            sequence,
+            message: serde_json::to_value(&message).map_err(|e| EventBusError::Serialization(e.to_string()))?,
+            stored_at: chrono::Utc::now(),
+        };
+        if storage.send(StorageMessage::Sequenced(kept)).await.is_err() {
+            warn!("Storage is gone; sequence {} from {} can't be retransmitted", sequence, message.source);
+        }
+    }
+    bus.publish(message).await
+}
+
+/// A captured event in the bus's form; the typed event travels in `data`
+pub fn encode_captured_event(event: &CapturedEvent) -> EventBusResult<RawEvent> {
+    Ok(RawEvent {
+        event_type: event.event_type().to_string(),
+        data: serde_json::to_value(event).map_err(|e| EventBusError::Serialization(e.to_string()))?,
+        window_title: None,
+        timestamp: event.timestamp(),
+    })
+}
+
+/// The captured event inside a bus `RawEvent` from [`encode_captured_event`]
+pub fn decode_captured_event(event: &RawEvent) -> EventBusResult<CapturedEvent> {
+    serde_json::from_value(event.data.clone()).map_err(|e| EventBusError::Serialization(e.to_string()))
+}
+
+async fn next_retransmission(receiver: &mut Option<mpsc::Receiver<SequencedMessage>>) -> Option<SequencedMessage> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn republish(bus: &dyn EventBusTrait, kept: SequencedMessage) -> EventBusResult<MessageId> {
+    let message: BusMessage = serde_json::from_value(kept.message).map_err(|e| {
+        EventBusError::Serialization(format!("kept message {} from {}: {}", kept.sequence, kept.producer, e))
+    })?;
+    bus.publish(message).await
+}
+
+/// Storage's form of a bus message, if it handles one
+fn to_storage(message: BusMessage) -> Option<StorageMessage> {
+    match message.payload {
+        MessagePayload::ConfigUpdate(update) if update.config_key == USER_PROFILE_KEY => {
+            match update.config_value.get("profile").and_then(|name| name.as_str()) {
+                Some(name) => Some(StorageMessage::ProfileSwitch(name.to_string())),
+                None => {
+                    warn!("Ignoring user profile update without a profile name: {}", update.config_value);
+                    None
+                }
+            }
+        }
+        MessagePayload::TelemetryBatch(batch) => Some(StorageMessage::TelemetryBatch(
+            batch
+                .samples
+                .into_iter()
+                .map(|sample| TelemetrySample {
+                    module: sample.module.map(|module| module.to_string()),
+                    metric: sample.metric,
+                    value: sample.value,
+                    timestamp: sample.timestamp,
+                })
+                .collect(),
+        )),
+        MessagePayload::DeliveryAck(ack) => Some(StorageMessage::DeliveryAck(SequenceRange {
+            producer: ack.producer.to_string(),
+            consumer: ack.consumer.to_string(),
+            from_sequence: ack.from_sequence,
+            to_sequence: ack.to_sequence,
+        })),
+        MessagePayload::RetransmitRequest(request) => Some(StorageMessage::RetransmitRequest(SequenceRange {
+            producer: request.producer.to_string(),
+            consumer: request.consumer.to_string(),
+            from_sequence: request.from_sequence,
+            to_sequence: request.to_sequence,
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_event_bus,
+        message::{ConfigUpdate, DeliveryAck, RetransmitRequest, TelemetryBatch},
+    };
+    use chrono::Utc;
+    use skelly_jelly_storage::{KeyModifiers, KeystrokeEvent, StorageConfig};
+    use tempfile::TempDir;
+
+    fn user_profile(name: &str) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, MessagePayload::ConfigUpdate(ConfigUpdate {
+            config_key: USER_PROFILE_KEY.to_string(),
+            config_value: serde_json::json!({ "profile": name }),
+            target_module: None,
+        }))
+    }
+
+    fn keystroke(key_code: u32) -> CapturedEvent {
+        CapturedEvent::Keystroke(KeystrokeEvent {
+            timestamp: Utc::now(),
+            key_code,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: None,
+        })
+    }
+
+    /// A storage module in `temp_dir` behind a bridge, and its inbox
+    async fn bridged_storage(bus: &EventBus, temp_dir: &TempDir) -> (StorageModule, mpsc::Sender<StorageMessage>) {
+        let mut config = StorageConfig::default();
+        config.database.path = temp_dir.path().join("test.db");
+        config.database.pool_size = 1;
+        config.profile.base_dir = temp_dir.path().to_path_buf();
+        config.screenshot.blob_dir = temp_dir.path().join("blobs");
+        let mut storage = StorageModule::new(config).await.unwrap();
+        bridge_to_storage(bus, &mut storage).unwrap();
+        let inbox = storage.sender();
+        (storage, inbox)
+    }
+
+    fn run(mut storage: StorageModule) -> JoinHandle<StorageModule> {
+        tokio::spawn(async move {
+            storage.run().await.unwrap();
+            storage
+        })
+    }
+
+    #[tokio::test]
+    async fn test_user_profile_broadcast_switches_storage_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (storage, inbox) = bridged_storage(&bus, &temp_dir).await;
+        let running = run(storage);
+
+        bus.publish(user_profile("Not Valid")).await.unwrap();
+        bus.publish(user_profile("work")).await.unwrap();
+        let work = temp_dir.path().join("profiles").join("work");
+        for _ in 0..100 {
+            if work.exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // Queued behind the switch, so storage has finished it by the time this is handled
+        inbox.send(StorageMessage::Shutdown("test".to_string())).await.unwrap();
+        let storage = running.await.unwrap();
+        assert_eq!(storage.profile().name(), "work");
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_batches_are_persisted() {
+        let temp_dir = TempDir::new().unwrap();
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (storage, inbox) = bridged_storage(&bus, &temp_dir).await;
+        let database = Arc::clone(storage.database());
+        let running = run(storage);
+
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        let sample = |module, value| crate::message::TelemetrySample {
+            module,
+            metric: "latency_p95_ms".to_string(),
+            value,
+            timestamp: Utc::now(),
+        };
+        bus.publish(BusMessage::new(ModuleId::Orchestrator, MessagePayload::TelemetryBatch(TelemetryBatch {
+            samples: vec![sample(Some(ModuleId::Storage), 12.0), sample(None, 30.0)],
+        })))
+        .await
+        .unwrap();
+
+        let module = ModuleId::Storage.to_string();
+        let mut stored = Vec::new();
+        for _ in 0..100 {
+            stored = database.get_telemetry_samples("latency_p95_ms", Some(&module), since).await.unwrap();
+            if !stored.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].value, 12.0);
+        assert_eq!(database.get_telemetry_samples("latency_p95_ms", None, since).await.unwrap().len(), 1);
+
+        inbox.send(StorageMessage::Shutdown("test".to_string())).await.unwrap();
+        running.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unacknowledged_messages_are_retransmitted() {
+        let temp_dir = TempDir::new().unwrap();
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (storage, inbox) = bridged_storage(&bus, &temp_dir).await;
+        let database = Arc::clone(storage.database());
+        let running = run(storage);
+        let (_, mut analysis) = bus
+            .subscribe_stream(
+                ModuleId::AnalysisEngine,
+                MessageFilter::types_and_sources(vec![MessageType::RawEvent], vec![ModuleId::DataCapture]),
+                DeliveryMode::BestEffort,
+            )
+            .unwrap();
+
+        let mut published = Vec::new();
+        for sequence in 1..=3 {
+            let event = encode_captured_event(&keystroke(sequence as u32)).unwrap();
+            let message =
+                BusMessage::new(ModuleId::DataCapture, MessagePayload::RawEvent(event)).with_sequence(sequence);
+            published.push(message.id);
+            publish_sequenced(bus.as_ref(), &inbox, message, ModuleId::AnalysisEngine).await.unwrap();
+        }
+        for _ in 0..3 {
+            analysis.next().await.unwrap();
+        }
+
+        let range = |from_sequence, to_sequence| SequenceRange {
+            producer: ModuleId::DataCapture.to_string(),
+            consumer: ModuleId::AnalysisEngine.to_string(),
+            from_sequence,
+            to_sequence,
+        };
+        bus.publish(BusMessage::new(ModuleId::AnalysisEngine, MessagePayload::DeliveryAck(DeliveryAck {
+            producer: ModuleId::DataCapture,
+            consumer: ModuleId::AnalysisEngine,
+            from_sequence: 1,
+            to_sequence: 1,
+        })))
+        .await
+        .unwrap();
+        for _ in 0..100 {
+            if database.get_sequenced_messages(&range(1, 3)).await.unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        bus.publish(BusMessage::new(ModuleId::Orchestrator, MessagePayload::RetransmitRequest(RetransmitRequest {
+            producer: ModuleId::DataCapture,
+            consumer: ModuleId::AnalysisEngine,
+            from_sequence: 1,
+            to_sequence: 3,
+            attempt: 1,
+        })))
+        .await
+        .unwrap();
+
+        let mut resent = Vec::new();
+        for _ in 0..2 {
+            let message = tokio::time::timeout(Duration::from_secs(5), analysis.next()).await.unwrap().unwrap();
+            let MessagePayload::RawEvent(event) = &message.payload else { panic!("not a raw event") };
+            let CapturedEvent::Keystroke(keystroke) = decode_captured_event(event).unwrap() else {
+                panic!("not a keystroke")
+            };
+            assert_eq!(message.sequence, Some(keystroke.key_code as u64));
+            resent.push(message.id);
+        }
+        assert_eq!(resent, published[1..]);
+
+        inbox.send(StorageMessage::Shutdown("test".to_string())).await.unwrap();
+        running.await.unwrap();
+    }
+}
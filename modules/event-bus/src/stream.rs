@@ -0,0 +1,167 @@
+//! Async stream consumption
+//!
+//! Subscription channels are synchronous crossbeam channels, which suit the
+//! router's workers but not async modules. A [`SubscriptionStream`] wraps one
+//! as a `futures::Stream`, so a module can use combinators (`throttle`,
+//! `ready_chunks`, `select`, ...) instead of a hand-rolled receive loop.
+//!
+//! Backpressure comes from the subscription's own bounded channel: a stream
+//! that isn't polled stops taking messages, its channel fills up, and further
+//! deliveries fail the way they do for any slow subscriber (counted in
+//! `messages_failed`, retried or dead-lettered per the delivery mode). Only a
+//! small hand-off buffer sits between the channel and the stream.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use futures::Stream;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::{BusMessage, SubscriptionId, subscription::SubscriptionManager};
+
+/// How often a forwarder with nothing to deliver checks whether its stream was dropped
+pub(crate) const FORWARD_IDLE_CHECK: Duration = Duration::from_millis(250);
+
+/// Items moved from the subscription channel but not yet polled
+const HANDOFF_BUFFER: usize = 32;
+
+/// A subscription consumed as a `Stream`
+///
+/// Yields `BusMessage`s for [`subscribe_stream`](crate::EventBusImpl::subscribe_stream).
+/// Ends when the subscription is removed or the bus shuts down; dropping it
+/// removes the subscription.
+pub struct SubscriptionStream<T = BusMessage> {
+    id: SubscriptionId,
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T: Send + 'static> SubscriptionStream<T> {
+    /// Move messages from `receiver` into the stream through `convert`, which may skip them
+    pub(crate) fn forward(
+        id: SubscriptionId,
+        receiver: Receiver<BusMessage>,
+        subscriptions: Arc<SubscriptionManager>,
+        mut convert: impl FnMut(BusMessage) -> Option<T> + Send + 'static,
+    ) -> Self {
+        let (sender, stream_receiver) = mpsc::channel(HANDOFF_BUFFER);
+
+        tokio::task::spawn_blocking(move || {
+            loop {
+                let message = match receiver.recv_timeout(FORWARD_IDLE_CHECK) {
+                    Ok(message) => message,
+                    Err(RecvTimeoutError::Timeout) if sender.is_closed() => break,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                };
+
+                // Blocks while the stream's consumer is behind, leaving the rest in the channel
+                if let Some(item) = convert(message) {
+                    if sender.blocking_send(item).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            debug!("Stream for subscription {} dropped, unsubscribing", id);
+            subscriptions.remove_subscription(id);
+        });
+
+        Self { id, receiver: stream_receiver }
+    }
+}
+
+impl<T> SubscriptionStream<T> {
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_event_bus, create_event_bus_with_config, DeliveryMode, EventBusConfig, EventBusTrait,
+        MessageFilter, MessagePayload, MessageType, ModuleId,
+        message::ConfigUpdate,
+    };
+    use futures::StreamExt;
+
+    fn config_update(source: ModuleId, key: &str) -> BusMessage {
+        BusMessage::new(source, MessagePayload::ConfigUpdate(ConfigUpdate {
+            config_key: key.to_string(),
+            config_value: serde_json::json!(1),
+            target_module: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_streams_work_with_combinators() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let filter = |source| MessageFilter::sources(vec![source]);
+        let (_, from_orchestrator) = bus
+            .subscribe_stream(ModuleId::Storage, filter(ModuleId::Orchestrator), DeliveryMode::BestEffort)
+            .unwrap();
+        let (_, from_gamification) = bus
+            .subscribe_stream(ModuleId::Storage, filter(ModuleId::Gamification), DeliveryMode::BestEffort)
+            .unwrap();
+
+        for key in ["a", "b", "c"] {
+            bus.publish(config_update(ModuleId::Orchestrator, key)).await.unwrap();
+        }
+        bus.publish(config_update(ModuleId::Gamification, "d")).await.unwrap();
+
+        let merged = futures::stream::select(from_orchestrator, from_gamification)
+            .filter(|message| futures::future::ready(message.message_type() == MessageType::ConfigUpdate))
+            .take(4)
+            .collect::<Vec<_>>();
+        let messages = tokio::time::timeout(Duration::from_secs(2), merged).await.unwrap();
+        let mut keys: Vec<String> = messages
+            .into_iter()
+            .map(|message| match message.payload {
+                MessagePayload::ConfigUpdate(update) => update.config_key,
+                other => panic!("unexpected payload {:?}", other),
+            })
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["a", "b", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_unpolled_stream_pushes_back() {
+        // Best-effort subscriptions get a channel of max_queue_size / 8 = 16
+        let bus = create_event_bus_with_config(EventBusConfig { max_queue_size: 128, ..Default::default() }).unwrap();
+        bus.start().await.unwrap();
+        let (_, mut stream) = bus
+            .subscribe_stream(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort)
+            .unwrap();
+
+        let published = 16 + HANDOFF_BUFFER + 20;
+        for i in 0..published {
+            bus.publish(config_update(ModuleId::Orchestrator, &i.to_string())).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let metrics = bus.metrics().await.unwrap();
+        assert!(metrics.messages_failed > 0);
+
+        // What was accepted is still delivered once the consumer catches up
+        let mut received = 0;
+        while let Ok(Some(_)) = tokio::time::timeout(Duration::from_millis(300), stream.next()).await {
+            received += 1;
+        }
+        assert_eq!(received as u64, metrics.messages_delivered);
+        assert!(received < published);
+    }
+}
@@ -1,9 +1,17 @@
 //! Subscription management for the event bus
 
-use std::time::Duration;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::{MessageType, ModuleId, BusMessage};
+use crate::{
+    MessagePayload, MessageType, ModuleId, BusMessage,
+    message::{CompressionCodec, CompressionConfig, DigestTrigger, MessageDigest, MessagePriority},
+    router::estimate_message_size,
+};
 
 /// Unique identifier for a subscription
 pub type SubscriptionId = Uuid;
@@ -19,6 +27,58 @@ pub enum DeliveryMode {
     
     /// Latest value only (for status updates)
     LatestOnly,
+    
+    /// Matching messages collected into one `MessageDigest` per window
+    Aggregated(AggregationConfig),
+}
+
+/// Limits that close an aggregation window; whichever is reached first wins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregationConfig {
+    /// Deliver once this many messages are collected
+    pub max_messages: Option<usize>,
+    
+    /// Deliver once the collected messages reach this estimated size
+    pub max_bytes: Option<usize>,
+    
+    /// Deliver this long after the first message of the window arrived
+    pub max_wait: Option<Duration>,
+}
+
+impl AggregationConfig {
+    /// Windows closed by time only
+    pub fn every(max_wait: Duration) -> Self {
+        Self {
+            max_messages: None,
+            max_bytes: None,
+            max_wait: Some(max_wait),
+        }
+    }
+
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self::every(Duration::from_secs(5)).with_max_messages(1_000)
+    }
+}
+
+/// The aggregation window a subscription is currently filling
+#[derive(Debug)]
+struct PendingDigest {
+    messages: Vec<BusMessage>,
+    total_bytes: usize,
+    opened: Instant,
+    window_start: DateTime<Utc>,
 }
 
 impl Default for DeliveryMode {
@@ -27,6 +87,46 @@ impl Default for DeliveryMode {
     }
 }
 
+/// Whether a subscription needs messages in the order they were published
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderingMode {
+    /// Messages from each publisher arrive in publish order (e.g. analysis windows)
+    PerPublisher,
+    /// Messages may arrive in any order, letting the router deliver them in parallel
+    #[default]
+    Unordered,
+}
+
+/// How a consumer group splits matching messages between its members
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupBalancing {
+    /// Each message goes to the next member in turn
+    #[default]
+    RoundRobin,
+    /// Each publisher's messages always go to the same member, in publish order
+    BySource,
+}
+
+/// Consumer group a subscription belongs to
+///
+/// Subscriptions in the same group share its messages: each matching message reaches
+/// exactly one member instead of all of them. The balancing of the first member wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumerGroup {
+    pub name: String,
+    pub balancing: GroupBalancing,
+}
+
+impl ConsumerGroup {
+    pub fn round_robin(name: impl Into<String>) -> Self {
+        Self { name: name.into(), balancing: GroupBalancing::RoundRobin }
+    }
+
+    pub fn by_source(name: impl Into<String>) -> Self {
+        Self { name: name.into(), balancing: GroupBalancing::BySource }
+    }
+}
+
 /// Filter for selecting which messages to receive
 pub struct MessageFilter {
     /// Filter by message types
@@ -145,6 +245,18 @@ pub struct Subscription {
     
     /// Statistics for this subscription
     pub stats: SubscriptionStats,
+    
+    /// Open aggregation window for `DeliveryMode::Aggregated`
+    pending_digest: Option<PendingDigest>,
+    
+    /// Codecs the subscriber can decompress, most preferred first
+    pub accepted_codecs: Vec<CompressionCodec>,
+    
+    /// Ordering the subscriber relies on
+    pub ordering: OrderingMode,
+    
+    /// Group whose members share this subscription's messages
+    pub group: Option<ConsumerGroup>,
 }
 
 impl Subscription {
@@ -163,9 +275,36 @@ impl Subscription {
             sender,
             created_at: std::time::SystemTime::now(),
             stats: SubscriptionStats::default(),
+            pending_digest: None,
+            accepted_codecs: Vec::new(),
+            ordering: OrderingMode::default(),
+            group: None,
         }
     }
 
+    /// Require (or drop) per-publisher ordering for this subscription
+    pub fn with_ordering(mut self, ordering: OrderingMode) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Accept large payloads compressed with any of `codecs`, most preferred first
+    pub fn with_compression(mut self, codecs: Vec<CompressionCodec>) -> Self {
+        self.accepted_codecs = codecs;
+        self
+    }
+
+    /// Share matching messages with the other members of `group`
+    pub fn with_group(mut self, group: ConsumerGroup) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Whether messages are collected into digests rather than delivered one by one
+    pub fn is_aggregated(&self) -> bool {
+        matches!(self.delivery_mode, DeliveryMode::Aggregated(_))
+    }
+
     /// Check if this subscription is interested in a message
     pub fn wants_message(&self, message: &BusMessage) -> bool {
         self.filter.matches(message)
@@ -173,8 +312,80 @@ impl Subscription {
 
     /// Try to deliver a message to this subscription
     pub fn try_deliver(&mut self, message: BusMessage) -> Result<(), DeliveryError> {
+        if let DeliveryMode::Aggregated(config) = &self.delivery_mode {
+            let (max_messages, max_bytes) = (config.max_messages, config.max_bytes);
+            self.stats.messages_attempted += 1;
+
+            let pending = self.pending_digest.get_or_insert_with(|| PendingDigest {
+                messages: Vec::new(),
+                total_bytes: 0,
+                opened: Instant::now(),
+                window_start: Utc::now(),
+            });
+            pending.total_bytes += estimate_message_size(&message);
+            pending.messages.push(message);
+
+            let trigger = if max_messages.is_some_and(|max| pending.messages.len() >= max) {
+                Some(DigestTrigger::Count)
+            } else if max_bytes.is_some_and(|max| pending.total_bytes >= max) {
+                Some(DigestTrigger::Size)
+            } else {
+                None
+            };
+            return match trigger {
+                Some(trigger) => self.flush_digest(trigger),
+                None => Ok(()),
+            };
+        }
+
         self.stats.messages_attempted += 1;
+        self.send(message)
+    }
 
+    /// Deliver the open digest if its time limit has passed
+    pub fn flush_if_expired(&mut self, now: Instant) -> Option<Result<(), DeliveryError>> {
+        let DeliveryMode::Aggregated(AggregationConfig { max_wait: Some(max_wait), .. }) = &self.delivery_mode else {
+            return None;
+        };
+        let expired = self
+            .pending_digest
+            .as_ref()
+            .is_some_and(|pending| now.duration_since(pending.opened) >= *max_wait);
+        expired.then(|| self.flush_digest(DigestTrigger::Time))
+    }
+
+    /// Deliver the open digest early, e.g. when the bus shuts down
+    pub fn flush_open_digest(&mut self) -> Option<Result<(), DeliveryError>> {
+        self.pending_digest.is_some().then(|| self.flush_digest(DigestTrigger::Shutdown))
+    }
+
+    /// Close the open window and send it as a single `MessageDigest`
+    fn flush_digest(&mut self, trigger: DigestTrigger) -> Result<(), DeliveryError> {
+        let Some(pending) = self.pending_digest.take() else { return Ok(()) };
+        let count = pending.messages.len() as u64;
+        let digest = BusMessage::with_priority(
+            ModuleId::EventBus,
+            MessagePayload::MessageDigest(MessageDigest {
+                subscription_id: self.id,
+                messages: pending.messages,
+                total_bytes: pending.total_bytes,
+                window_start: pending.window_start,
+                window_end: Utc::now(),
+                trigger,
+            }),
+            MessagePriority::Low,
+        );
+
+        // Stats count the messages inside the digest, not the digest itself
+        let result = self.send(digest);
+        match result {
+            Ok(_) => self.stats.messages_delivered += count - 1,
+            Err(_) => self.stats.messages_dropped += count - 1,
+        }
+        result
+    }
+
+    fn send(&mut self, message: BusMessage) -> Result<(), DeliveryError> {
         match self.sender.try_send(message) {
             Ok(_) => {
                 self.stats.messages_delivered += 1;
@@ -210,32 +421,218 @@ pub enum DeliveryError {
     Timeout,
 }
 
+/// Messages held back for a module while its delivery is paused
+///
+/// Each message remembers the subscription it matched, but it belongs to the
+/// module: a restarted module subscribes again under new ids, and replay goes
+/// to those.
+#[derive(Debug)]
+struct PausedDelivery {
+    capacity: usize,
+    messages: VecDeque<(SubscriptionId, BusMessage)>,
+    dropped: u64,
+}
+
+/// Outcome of releasing a paused module's buffered messages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    /// Messages handed to the module's subscriptions
+    pub replayed: usize,
+    /// Messages discarded because the buffer was full
+    pub dropped: u64,
+    /// Messages no subscription took on replay; the bus dead-letters them
+    pub failed: usize,
+}
+
+/// Default number of subscription table shards
+pub const DEFAULT_SHARDS: usize = 4;
+
+/// A subscription shared between the shards whose message types it accepts
+#[derive(Debug)]
+struct SubscriptionEntry {
+    id: SubscriptionId,
+    subscriber: ModuleId,
+    ordered: bool,
+    group: Option<ConsumerGroup>,
+    subscription: parking_lot::Mutex<Subscription>,
+}
+
 /// Manager for all subscriptions in the system
+///
+/// Subscriptions are indexed by the shard of each message type they accept, so deliveries
+/// of different message types only share a read lock and the per-subscription locks.
+/// Locks are always taken in the order paused, subscriptions, shard, group cursors, subscription.
 #[derive(Debug)]
 pub struct SubscriptionManager {
-    subscriptions: parking_lot::RwLock<Vec<Subscription>>,
+    /// Every subscription, in creation order
+    subscriptions: parking_lot::RwLock<Vec<Arc<SubscriptionEntry>>>,
+    /// Subscriptions by message type shard; one without a type filter is in every shard
+    shards: Vec<parking_lot::RwLock<Vec<Arc<SubscriptionEntry>>>>,
+    paused: parking_lot::RwLock<HashMap<ModuleId, parking_lot::Mutex<PausedDelivery>>>,
+    /// Compress large payloads for subscribers that accept it (`None` never compresses)
+    compression: Option<CompressionConfig>,
+    /// Subscriptions with `OrderingMode::PerPublisher`, so the check can be skipped when there are none
+    ordered_count: AtomicUsize,
+    /// Next member to receive a message, per round-robin consumer group
+    group_cursors: parking_lot::Mutex<HashMap<String, usize>>,
 }
 
 impl SubscriptionManager {
     /// Create a new subscription manager
     pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Create a subscription manager with `shards` subscription tables (at least one)
+    pub fn with_shards(shards: usize) -> Self {
         Self {
             subscriptions: parking_lot::RwLock::new(Vec::new()),
+            shards: (0..shards.max(1)).map(|_| parking_lot::RwLock::new(Vec::new())).collect(),
+            paused: parking_lot::RwLock::new(HashMap::new()),
+            compression: None,
+            ordered_count: AtomicUsize::new(0),
+            group_cursors: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Compress payloads above the configured threshold for subscribers that accept it
+    pub fn with_compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Number of subscription table shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// The shard holding subscriptions for `message_type`
+    pub fn shard_for(&self, message_type: MessageType) -> usize {
+        shard_for(message_type, self.shards.len())
+    }
+
+    /// Buffer messages for a module's subscriptions instead of delivering them.
+    /// Pausing an already paused module keeps its buffer and updates the capacity.
+    pub fn pause_module(&self, module: ModuleId, capacity: usize) {
+        let mut paused = self.paused.write();
+        let mut entry = paused
+            .entry(module)
+            .or_insert_with(|| parking_lot::Mutex::new(PausedDelivery {
+                capacity,
+                messages: VecDeque::new(),
+                dropped: 0,
+            }))
+            .lock();
+        entry.capacity = capacity;
+        while entry.messages.len() > capacity {
+            entry.messages.pop_front();
+            entry.dropped += 1;
         }
     }
 
+    /// Whether deliveries to a module are currently paused
+    pub fn is_paused(&self, module: ModuleId) -> bool {
+        self.paused.read().contains_key(&module)
+    }
+
+    /// Number of messages currently held for a paused module
+    pub fn buffered_count(&self, module: ModuleId) -> usize {
+        self.paused.read().get(&module).map(|p| p.lock().messages.len()).unwrap_or(0)
+    }
+
+    /// Resume deliveries to a module, replaying buffered messages in arrival order.
+    ///
+    /// A message whose subscription is gone, e.g. because the module restarted
+    /// and subscribed again, goes to the module's current subscriptions that
+    /// want it instead. Returns the summary and the messages no subscription
+    /// took, or `None` if the module was not paused.
+    pub fn resume_module(&self, module: ModuleId) -> Option<(ReplaySummary, Vec<BusMessage>)> {
+        // Deliveries hold the paused lock for reading, so holding it for writing across
+        // the replay keeps anything new from overtaking the backlog
+        let mut paused = self.paused.write();
+        let buffer = paused.remove(&module)?.into_inner();
+        let subscriptions = self.subscriptions.read();
+
+        let mut summary = ReplaySummary {
+            dropped: buffer.dropped,
+            ..Default::default()
+        };
+        let mut undelivered = Vec::new();
+        // A message matched by several old subscriptions is buffered once for each,
+        // but each current subscription gets it only once
+        let mut seen: HashSet<Uuid> = HashSet::new();
+        let mut handed: HashSet<(Uuid, SubscriptionId)> = HashSet::new();
+
+        for (subscription_id, message) in buffer.messages {
+            let targets: Vec<&Arc<SubscriptionEntry>> =
+                match subscriptions.iter().find(|entry| entry.id == subscription_id) {
+                    Some(entry) => vec![entry],
+                    None => subscriptions
+                        .iter()
+                        .filter(|entry| entry.subscriber == module && entry.subscription.lock().wants_message(&message))
+                        .collect(),
+                };
+            let first_copy = seen.insert(message.id);
+            let fresh: Vec<_> = targets.into_iter().filter(|entry| handed.insert((message.id, entry.id))).collect();
+            if fresh.is_empty() && !first_copy {
+                // An earlier copy already reached every subscription left to take it
+                continue;
+            }
+
+            let delivered = fresh
+                .iter()
+                .filter(|entry| entry.subscription.lock().try_deliver(message.clone()).is_ok())
+                .count();
+            if delivered > 0 {
+                summary.replayed += 1;
+            } else {
+                summary.failed += 1;
+                undelivered.push(message);
+            }
+        }
+
+        Some((summary, undelivered))
+    }
+
     /// Add a new subscription
     pub fn add_subscription(&self, subscription: Subscription) -> SubscriptionId {
         let id = subscription.id;
-        self.subscriptions.write().push(subscription);
+        let shards: Vec<usize> = match &subscription.filter.types {
+            Some(types) => types.iter().map(|t| self.shard_for(*t)).collect(),
+            None => (0..self.shards.len()).collect(),
+        };
+        let ordered = subscription.ordering == OrderingMode::PerPublisher;
+        let entry = Arc::new(SubscriptionEntry {
+            id,
+            subscriber: subscription.subscriber,
+            ordered,
+            group: subscription.group.clone(),
+            subscription: parking_lot::Mutex::new(subscription),
+        });
+
+        let mut subscriptions = self.subscriptions.write();
+        if ordered {
+            self.ordered_count.fetch_add(1, Ordering::Relaxed);
+        }
+        subscriptions.push(Arc::clone(&entry));
+        for (index, shard) in self.shards.iter().enumerate() {
+            if shards.contains(&index) {
+                shard.write().push(Arc::clone(&entry));
+            }
+        }
         id
     }
 
     /// Remove a subscription by ID
     pub fn remove_subscription(&self, subscription_id: SubscriptionId) -> bool {
         let mut subscriptions = self.subscriptions.write();
-        if let Some(pos) = subscriptions.iter().position(|s| s.id == subscription_id) {
-            subscriptions.remove(pos);
+        if let Some(pos) = subscriptions.iter().position(|entry| entry.id == subscription_id) {
+            if subscriptions.remove(pos).ordered {
+                self.ordered_count.fetch_sub(1, Ordering::Relaxed);
+            }
+            for shard in &self.shards {
+                shard.write().retain(|entry| entry.id != subscription_id);
+            }
             true
         } else {
             false
@@ -247,28 +644,54 @@ impl SubscriptionManager {
         self.subscriptions
             .read()
             .iter()
-            .filter(|s| s.subscriber == module)
-            .map(|s| s.id)
+            .filter(|entry| entry.subscriber == module)
+            .map(|entry| entry.id)
             .collect()
     }
 
+    /// Whether any subscription that wants `message` needs per-publisher ordering
+    pub fn needs_ordering(&self, message: &BusMessage) -> bool {
+        if self.ordered_count.load(Ordering::Relaxed) == 0 {
+            return false;
+        }
+        self.shards[self.shard_for(message.message_type())]
+            .read()
+            .iter()
+            .any(|entry| entry.ordered && entry.subscription.lock().wants_message(message))
+    }
+
     /// Deliver a message to all interested subscriptions
+    ///
+    /// Only the shard for the message's type is consulted, so messages of types in
+    /// different shards can be delivered concurrently. Each consumer group that wants
+    /// the message gets it once, on the member its balancing picks.
     pub fn deliver_message(&self, message: BusMessage) -> DeliveryResults {
         let mut results = DeliveryResults::default();
-        let mut subscriptions = self.subscriptions.write();
+        let paused = self.paused.read();
+        let shard = self.shards[self.shard_for(message.message_type())].read();
+        // Each codec's copy is compressed once, the first time a subscriber asks for it
+        let mut compressed: Vec<(CompressionCodec, Option<BusMessage>)> = Vec::new();
+        let mut groups: Vec<(&ConsumerGroup, Vec<&SubscriptionEntry>)> = Vec::new();
 
-        for subscription in subscriptions.iter_mut() {
-            if subscription.wants_message(&message) {
-                match subscription.try_deliver(message.clone()) {
-                    Ok(_) => results.successful += 1,
-                    Err(DeliveryError::QueueFull) => results.queue_full += 1,
-                    Err(DeliveryError::Disconnected) => {
-                        results.disconnected += 1;
-                        // Mark for removal - we'll clean up disconnected subscriptions
-                    }
-                    Err(DeliveryError::Timeout) => results.timeout += 1,
+        for entry in shard.iter() {
+            let mut subscription = entry.subscription.lock();
+            if !subscription.wants_message(&message) {
+                continue;
+            }
+            if let Some(group) = &entry.group {
+                match groups.iter_mut().find(|(existing, _)| existing.name == group.name) {
+                    Some((_, members)) => members.push(entry),
+                    None => groups.push((group, vec![entry])),
                 }
+                continue;
             }
+            self.deliver_to(entry, &mut subscription, &message, &paused, &mut compressed, &mut results);
+        }
+
+        for (group, members) in groups {
+            let entry = self.pick_member(group, &members, &message);
+            let mut subscription = entry.subscription.lock();
+            self.deliver_to(entry, &mut subscription, &message, &paused, &mut compressed, &mut results);
         }
 
         // Remove disconnected subscriptions
@@ -278,6 +701,115 @@ impl SubscriptionManager {
         results
     }
 
+    /// The member of `group` that receives `message`
+    fn pick_member<'a>(
+        &self,
+        group: &ConsumerGroup,
+        members: &[&'a SubscriptionEntry],
+        message: &BusMessage,
+    ) -> &'a SubscriptionEntry {
+        match group.balancing {
+            GroupBalancing::BySource => members[shard_for(message.source, members.len())],
+            GroupBalancing::RoundRobin => {
+                let mut cursors = self.group_cursors.lock();
+                let cursor = cursors.entry(group.name.clone()).or_insert(0);
+                let member = members[*cursor % members.len()];
+                *cursor = cursor.wrapping_add(1);
+                member
+            }
+        }
+    }
+
+    /// Hand `message` to one subscription, or buffer it if the subscriber is paused
+    fn deliver_to(
+        &self,
+        entry: &SubscriptionEntry,
+        subscription: &mut Subscription,
+        message: &BusMessage,
+        paused: &HashMap<ModuleId, parking_lot::Mutex<PausedDelivery>>,
+        compressed: &mut Vec<(CompressionCodec, Option<BusMessage>)>,
+        results: &mut DeliveryResults,
+    ) {
+        if let Some(buffer) = paused.get(&entry.subscriber) {
+            let mut buffer = buffer.lock();
+            if buffer.capacity == 0 {
+                buffer.dropped += 1;
+            } else {
+                if buffer.messages.len() >= buffer.capacity {
+                    buffer.messages.pop_front();
+                    buffer.dropped += 1;
+                }
+                buffer.messages.push_back((entry.id, message.clone()));
+            }
+            results.buffered += 1;
+            return;
+        }
+
+        let aggregated = subscription.is_aggregated();
+        let outgoing = self
+            .compression
+            .as_ref()
+            .and_then(|config| Some((config, config.negotiate(&subscription.accepted_codecs)?)))
+            .and_then(|(config, codec)| compressed_copy(config, codec, message, compressed, results));
+        let is_compressed = outgoing.is_some();
+        match subscription.try_deliver(outgoing.unwrap_or_else(|| message.clone())) {
+            Ok(_) if aggregated => results.aggregated += 1,
+            Ok(_) => {
+                results.successful += 1;
+                results.delivered_to.push(entry.subscriber);
+                if is_compressed {
+                    results.compressed += 1;
+                }
+            }
+            Err(error) => {
+                match error {
+                    DeliveryError::QueueFull => results.queue_full += 1,
+                    // Mark for removal - we'll clean up disconnected subscriptions
+                    DeliveryError::Disconnected => results.disconnected += 1,
+                    DeliveryError::Timeout => results.timeout += 1,
+                }
+                results.failed_subscribers.push(entry.subscriber);
+            }
+        }
+    }
+
+    /// Deliver every digest whose time limit has passed; returns how many went out
+    pub fn flush_expired_digests(&self, now: Instant) -> usize {
+        // Paused modules keep their window open until resumed
+        let paused = self.paused.read();
+        self.subscriptions
+            .read()
+            .iter()
+            .filter(|entry| !paused.contains_key(&entry.subscriber))
+            .filter_map(|entry| {
+                let mut subscription = entry.subscription.lock();
+                if subscription.is_aggregated() { subscription.flush_if_expired(now) } else { None }
+            })
+            .filter(|result| result.is_ok())
+            .count()
+    }
+
+    /// Deliver every open digest now, whatever its limits; returns how many went out
+    pub fn flush_all_digests(&self) -> usize {
+        self.subscriptions
+            .read()
+            .iter()
+            .filter_map(|entry| entry.subscription.lock().flush_open_digest())
+            .filter(|result| result.is_ok())
+            .count()
+    }
+
+    /// Release every paused module's buffer without delivering it, oldest first per module
+    pub fn take_paused(&self) -> Vec<(ModuleId, BusMessage)> {
+        self.paused
+            .write()
+            .drain()
+            .flat_map(|(module, buffer)| {
+                buffer.into_inner().messages.into_iter().map(move |(_, message)| (module, message))
+            })
+            .collect()
+    }
+
     /// Get the total number of active subscriptions
     pub fn subscription_count(&self) -> usize {
         self.subscriptions.read().len()
@@ -288,29 +820,98 @@ impl SubscriptionManager {
         self.subscriptions
             .read()
             .iter()
-            .map(|s| (s.id, s.subscriber, SubscriptionStats {
-                messages_attempted: s.stats.messages_attempted,
-                messages_delivered: s.stats.messages_delivered,
-                messages_dropped: s.stats.messages_dropped,
-                last_delivery: s.stats.last_delivery,
-            }))
+            .map(|entry| {
+                let s = entry.subscription.lock();
+                (entry.id, entry.subscriber, SubscriptionStats {
+                    messages_attempted: s.stats.messages_attempted,
+                    messages_delivered: s.stats.messages_delivered,
+                    messages_dropped: s.stats.messages_dropped,
+                    last_delivery: s.stats.last_delivery,
+                })
+            })
             .collect()
     }
 }
 
+/// The shard for `key` (a message type or publisher) among `shards`
+pub fn shard_for(key: impl std::hash::Hash, shards: usize) -> usize {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % shards.max(1) as u64) as usize
+}
+
 impl Default for SubscriptionManager {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// The copy of `message` compressed with `codec`, or `None` to deliver it as it is
+fn compressed_copy(
+    config: &CompressionConfig,
+    codec: CompressionCodec,
+    message: &BusMessage,
+    cache: &mut Vec<(CompressionCodec, Option<BusMessage>)>,
+    results: &mut DeliveryResults,
+) -> Option<BusMessage> {
+    if let Some((_, copy)) = cache.iter().find(|(cached, _)| *cached == codec) {
+        return copy.clone();
+    }
+
+    let started = Instant::now();
+    let copy = match config.compress(&message.payload, codec) {
+        Ok(Some(payload)) => {
+            results.compression.push(CompressionSample {
+                codec,
+                original_bytes: payload.original_size,
+                compressed_bytes: payload.data.len(),
+                elapsed: started.elapsed(),
+            });
+            Some(BusMessage {
+                payload: MessagePayload::Compressed(payload),
+                ..message.clone()
+            })
+        }
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!("Delivering message {} uncompressed: {}", message.id, e);
+            None
+        }
+    };
+    cache.push((codec, copy.clone()));
+    copy
+}
+
+/// One payload compressed during delivery
+#[derive(Debug, Clone)]
+pub struct CompressionSample {
+    pub codec: CompressionCodec,
+    pub original_bytes: usize,
+    pub compressed_bytes: usize,
+    /// Time spent serializing and compressing
+    pub elapsed: Duration,
+}
+
 /// Results from attempting to deliver a message
 #[derive(Debug, Default)]
 pub struct DeliveryResults {
     pub successful: u32,
+    /// Held for a paused subscriber rather than delivered
+    pub buffered: u32,
+    /// Added to an aggregated subscription's open digest
+    pub aggregated: u32,
     pub queue_full: u32,
     pub disconnected: u32,
     pub timeout: u32,
+    /// Subscribers the message did not reach
+    pub failed_subscribers: Vec<ModuleId>,
+    /// Subscribers the message was delivered to, one entry per successful delivery
+    pub delivered_to: Vec<ModuleId>,
+    /// Successful deliveries that carried a compressed payload
+    pub compressed: u32,
+    /// Compression work done for this message, one sample per codec used
+    pub compression: Vec<CompressionSample>,
 }
 
 impl DeliveryResults {
@@ -326,4 +927,331 @@ impl DeliveryResults {
             self.successful as f64 / total as f64
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessagePayload;
+
+    fn subscribe(manager: &SubscriptionManager, module: ModuleId) -> crossbeam_channel::Receiver<BusMessage> {
+        let (sender, receiver) = crossbeam_channel::bounded(16);
+        manager.add_subscription(Subscription::new(module, MessageFilter::all(), DeliveryMode::BestEffort, sender));
+        receiver
+    }
+
+    fn ready(module: ModuleId) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(module))
+    }
+
+    #[test]
+    fn test_paused_module_replays_in_order() {
+        let manager = SubscriptionManager::new();
+        let storage = subscribe(&manager, ModuleId::Storage);
+        let capture = subscribe(&manager, ModuleId::DataCapture);
+
+        manager.pause_module(ModuleId::Storage, 10);
+        let first = ready(ModuleId::Storage);
+        let second = ready(ModuleId::AnalysisEngine);
+        let results = manager.deliver_message(first.clone());
+        manager.deliver_message(second.clone());
+
+        // Other subscribers are unaffected
+        assert_eq!(results.buffered, 1);
+        assert_eq!(results.successful, 1);
+        assert_eq!(capture.len(), 2);
+        assert!(storage.is_empty());
+        assert_eq!(manager.buffered_count(ModuleId::Storage), 2);
+
+        let (summary, undelivered) = manager.resume_module(ModuleId::Storage).unwrap();
+        assert_eq!(summary, ReplaySummary { replayed: 2, dropped: 0, failed: 0 });
+        assert!(undelivered.is_empty());
+        assert_eq!(storage.recv().unwrap().id, first.id);
+        assert_eq!(storage.recv().unwrap().id, second.id);
+        assert!(!manager.is_paused(ModuleId::Storage));
+    }
+
+    #[test]
+    fn test_pause_buffer_drops_oldest_when_full() {
+        let manager = SubscriptionManager::new();
+        let storage = subscribe(&manager, ModuleId::Storage);
+
+        manager.pause_module(ModuleId::Storage, 2);
+        let messages: Vec<_> = (0..3).map(|_| ready(ModuleId::Storage)).collect();
+        for message in &messages {
+            manager.deliver_message(message.clone());
+        }
+
+        let (summary, _) = manager.resume_module(ModuleId::Storage).unwrap();
+        assert_eq!(summary.replayed, 2);
+        assert_eq!(summary.dropped, 1);
+        assert_eq!(storage.recv().unwrap().id, messages[1].id);
+        assert!(manager.resume_module(ModuleId::Storage).is_none());
+    }
+
+    #[test]
+    fn test_replay_follows_module_to_its_new_subscription() {
+        let manager = SubscriptionManager::new();
+        let _old = subscribe(&manager, ModuleId::Storage);
+        let _second_old = subscribe(&manager, ModuleId::Storage);
+
+        manager.pause_module(ModuleId::Storage, 10);
+        let message = ready(ModuleId::Storage);
+        manager.deliver_message(message.clone());
+        assert_eq!(manager.buffered_count(ModuleId::Storage), 2);
+
+        // The module restarts and subscribes again under a new id
+        for id in manager.get_subscriptions_for_module(ModuleId::Storage) {
+            manager.remove_subscription(id);
+        }
+        let restarted = subscribe(&manager, ModuleId::Storage);
+
+        let (summary, undelivered) = manager.resume_module(ModuleId::Storage).unwrap();
+        assert_eq!(summary, ReplaySummary { replayed: 1, dropped: 0, failed: 0 });
+        assert!(undelivered.is_empty());
+        assert_eq!(restarted.recv().unwrap().id, message.id);
+        assert!(restarted.is_empty());
+    }
+
+    #[test]
+    fn test_replay_without_subscriptions_returns_messages() {
+        let manager = SubscriptionManager::new();
+        let _storage = subscribe(&manager, ModuleId::Storage);
+
+        manager.pause_module(ModuleId::Storage, 10);
+        let message = ready(ModuleId::Storage);
+        manager.deliver_message(message.clone());
+        for id in manager.get_subscriptions_for_module(ModuleId::Storage) {
+            manager.remove_subscription(id);
+        }
+
+        let (summary, undelivered) = manager.resume_module(ModuleId::Storage).unwrap();
+        assert_eq!(summary, ReplaySummary { replayed: 0, dropped: 0, failed: 1 });
+        assert_eq!(undelivered.len(), 1);
+        assert_eq!(undelivered[0].id, message.id);
+    }
+
+    fn join_group(manager: &SubscriptionManager, group: ConsumerGroup) -> crossbeam_channel::Receiver<BusMessage> {
+        let (sender, receiver) = crossbeam_channel::bounded(16);
+        manager.add_subscription(
+            Subscription::new(ModuleId::AnalysisEngine, MessageFilter::all(), DeliveryMode::BestEffort, sender)
+                .with_group(group),
+        );
+        receiver
+    }
+
+    #[test]
+    fn test_round_robin_group_splits_messages() {
+        let manager = SubscriptionManager::new();
+        let workers: Vec<_> = (0..3).map(|_| join_group(&manager, ConsumerGroup::round_robin("analysis"))).collect();
+        let storage = subscribe(&manager, ModuleId::Storage);
+
+        for _ in 0..6 {
+            let results = manager.deliver_message(ready(ModuleId::Storage));
+            assert_eq!(results.successful, 2);
+        }
+
+        // Each worker gets its share; subscribers outside the group still see everything
+        assert!(workers.iter().all(|worker| worker.len() == 2));
+        assert_eq!(storage.len(), 6);
+    }
+
+    #[test]
+    fn test_by_source_group_keeps_publishers_on_one_member() {
+        let manager = SubscriptionManager::new();
+        let workers: Vec<_> = (0..4).map(|_| join_group(&manager, ConsumerGroup::by_source("analysis"))).collect();
+
+        let publishers = [ModuleId::DataCapture, ModuleId::Storage, ModuleId::Orchestrator];
+        for round in 0..5 {
+            for publisher in publishers {
+                let payload = MessagePayload::ModuleReady(ModuleId::AnalysisEngine);
+                let mut message = BusMessage::new(publisher, payload);
+                message.correlation_id = Some(Uuid::from_u128(round));
+                manager.deliver_message(message);
+            }
+        }
+
+        let total: usize = workers.iter().map(|worker| worker.len()).sum();
+        assert_eq!(total, 15);
+        for worker in &workers {
+            let received: Vec<BusMessage> = worker.try_iter().collect();
+            for publisher in publishers {
+                let rounds: Vec<_> = received.iter().filter(|m| m.source == publisher).map(|m| m.correlation_id).collect();
+                // A publisher's messages all land here, in order, or none do
+                assert!(rounds.is_empty() || rounds == (0..5).map(|r| Some(Uuid::from_u128(r))).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    fn subscribe_aggregated(
+        manager: &SubscriptionManager,
+        config: AggregationConfig,
+    ) -> crossbeam_channel::Receiver<BusMessage> {
+        let (sender, receiver) = crossbeam_channel::bounded(16);
+        let filter = MessageFilter::types(vec![MessageType::ModuleReady]);
+        manager.add_subscription(Subscription::new(ModuleId::Orchestrator, filter, DeliveryMode::Aggregated(config), sender));
+        receiver
+    }
+
+    fn digest(receiver: &crossbeam_channel::Receiver<BusMessage>) -> MessageDigest {
+        match receiver.try_recv().expect("a digest").payload {
+            MessagePayload::MessageDigest(digest) => digest,
+            other => panic!("expected a digest, got {:?}", other.message_type()),
+        }
+    }
+
+    #[test]
+    fn test_aggregated_subscription_flushes_on_count_and_size() {
+        let manager = SubscriptionManager::new();
+        let by_count = subscribe_aggregated(&manager, AggregationConfig::every(Duration::from_secs(60)).with_max_messages(3));
+        let one_size = estimate_message_size(&ready(ModuleId::Storage));
+        let by_size = subscribe_aggregated(
+            &manager,
+            AggregationConfig { max_messages: None, max_bytes: Some(one_size * 2), max_wait: None },
+        );
+
+        let messages: Vec<_> = (0..3).map(|_| ready(ModuleId::Storage)).collect();
+        let results = manager.deliver_message(messages[0].clone());
+        assert_eq!((results.aggregated, results.successful), (2, 0));
+        manager.deliver_message(messages[1].clone());
+        assert!(by_count.is_empty());
+
+        let sized = digest(&by_size);
+        assert_eq!(sized.trigger, DigestTrigger::Size);
+        assert_eq!(sized.messages.len(), 2);
+        assert_eq!(sized.total_bytes, one_size * 2);
+
+        manager.deliver_message(messages[2].clone());
+        let counted = digest(&by_count);
+        assert_eq!(counted.trigger, DigestTrigger::Count);
+        let ids: Vec<_> = counted.messages.iter().map(|m| m.id).collect();
+        assert_eq!(ids, messages.iter().map(|m| m.id).collect::<Vec<_>>());
+        assert!(by_count.is_empty());
+
+        let stats = manager.get_stats();
+        assert!(stats.iter().all(|(_, _, stats)| stats.messages_dropped == 0));
+        assert_eq!(stats.iter().map(|(_, _, stats)| stats.messages_delivered).sum::<u64>(), 5);
+    }
+
+    #[test]
+    fn test_aggregated_subscription_flushes_on_time_unless_paused() {
+        let manager = SubscriptionManager::new();
+        let telemetry = subscribe_aggregated(&manager, AggregationConfig::every(Duration::from_secs(5)));
+
+        manager.deliver_message(ready(ModuleId::Storage));
+        manager.deliver_message(ready(ModuleId::Gamification));
+        assert_eq!(manager.flush_expired_digests(Instant::now()), 0);
+        assert!(telemetry.is_empty());
+
+        manager.pause_module(ModuleId::Orchestrator, 10);
+        let later = Instant::now() + Duration::from_secs(5);
+        assert_eq!(manager.flush_expired_digests(later), 0);
+
+        manager.resume_module(ModuleId::Orchestrator);
+        assert_eq!(manager.flush_expired_digests(later), 1);
+        let window = digest(&telemetry);
+        assert_eq!(window.trigger, DigestTrigger::Time);
+        assert_eq!(window.messages.len(), 2);
+        assert_eq!(manager.flush_expired_digests(later + Duration::from_secs(5)), 0);
+    }
+
+    fn screenshot_event() -> BusMessage {
+        let pixels = "0123456789abcdef".repeat(4096);
+        BusMessage::new(ModuleId::DataCapture, MessagePayload::RawEvent(crate::message::RawEvent {
+            event_type: "screenshot".to_string(),
+            data: serde_json::json!({ "png": pixels }),
+            window_title: None,
+            timestamp: Utc::now(),
+        }))
+    }
+
+    #[test]
+    fn test_large_payloads_compressed_only_for_capable_subscribers() {
+        let manager = SubscriptionManager::new().with_compression(CompressionConfig::default());
+        let plain = subscribe(&manager, ModuleId::Storage);
+        let (sender, lz4) = crossbeam_channel::bounded(16);
+        manager.add_subscription(
+            Subscription::new(ModuleId::AnalysisEngine, MessageFilter::all(), DeliveryMode::BestEffort, sender)
+                .with_compression(vec![CompressionCodec::Zstd, CompressionCodec::Lz4]),
+        );
+
+        let large = screenshot_event();
+        let results = manager.deliver_message(large.clone());
+        assert_eq!((results.successful, results.compressed), (2, 1));
+        assert_eq!(results.compression.len(), 1);
+        assert_eq!(results.compression[0].codec, CompressionCodec::Lz4);
+        assert!(results.compression[0].compressed_bytes * 10 < results.compression[0].original_bytes);
+
+        assert!(matches!(plain.try_recv().unwrap().payload, MessagePayload::RawEvent(_)));
+        let received = lz4.try_recv().unwrap();
+        assert_eq!(received.id, large.id);
+        assert_eq!(received.message_type(), MessageType::RawEvent);
+        let MessagePayload::Compressed(compressed) = &received.payload else { panic!("expected a compressed payload") };
+        assert!(compressed.ratio() > 10.0);
+        let MessagePayload::RawEvent(event) = received.decompressed().unwrap().payload else { panic!("expected a raw event") };
+        assert_eq!(event.data["png"].as_str().unwrap().len(), 65_536);
+
+        // Small payloads go out as they are
+        let results = manager.deliver_message(ready(ModuleId::Storage));
+        assert_eq!((results.compressed, results.compression.len()), (0, 0));
+        assert!(matches!(lz4.try_recv().unwrap().payload, MessagePayload::ModuleReady(_)));
+    }
+
+    #[test]
+    fn test_codecs_round_trip_and_metrics() {
+        let payload = screenshot_event().payload;
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed = payload.compress(codec).unwrap();
+            assert_eq!(compressed.codec, codec);
+            let MessagePayload::RawEvent(event) = compressed.decompress().unwrap() else { panic!("expected a raw event") };
+            assert_eq!(event.event_type, "screenshot");
+        }
+
+        let config = CompressionConfig { preferred_codec: CompressionCodec::Zstd, ..Default::default() };
+        assert_eq!(config.negotiate(&[CompressionCodec::Lz4, CompressionCodec::Zstd]), Some(CompressionCodec::Zstd));
+        assert_eq!(config.negotiate(&[CompressionCodec::Lz4]), Some(CompressionCodec::Lz4));
+        assert_eq!(config.negotiate(&[]), None);
+
+        let metrics = crate::metrics::MetricsCollector::new();
+        metrics.record_compression(&[CompressionSample {
+            codec: CompressionCodec::Zstd,
+            original_bytes: 8_000,
+            compressed_bytes: 1_000,
+            elapsed: Duration::from_micros(250),
+        }], 3);
+        let compression = metrics.snapshot(HashMap::new()).compression;
+        assert_eq!((compression.payloads_compressed, compression.compressed_deliveries), (1, 3));
+        assert_eq!(compression.compression_ratio, 8.0);
+        assert!((compression.avg_cpu_time_us - 250.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sharded_tables_route_by_message_type() {
+        let manager = SubscriptionManager::with_shards(8);
+        let everything = subscribe(&manager, ModuleId::Orchestrator);
+        let (sender, acks) = crossbeam_channel::bounded(16);
+        let acks_only = manager.add_subscription(Subscription::new(
+            ModuleId::Storage,
+            MessageFilter::types(vec![MessageType::DeliveryAck]),
+            DeliveryMode::BestEffort,
+            sender,
+        ));
+
+        let ack_shard = manager.shard_for(MessageType::DeliveryAck);
+        assert_eq!(manager.shards[ack_shard].read().len(), 2);
+        assert!(manager.shards.iter().all(|shard| !shard.read().is_empty()));
+
+        let ack = BusMessage::new(ModuleId::AnalysisEngine, MessagePayload::DeliveryAck(crate::message::DeliveryAck {
+            producer: ModuleId::DataCapture,
+            consumer: ModuleId::AnalysisEngine,
+            from_sequence: 1,
+            to_sequence: 4,
+        }));
+        assert_eq!(manager.deliver_message(ack).successful, 2);
+        assert_eq!(manager.deliver_message(ready(ModuleId::Storage)).successful, 1);
+        assert_eq!((everything.len(), acks.len()), (2, 1));
+
+        assert!(manager.remove_subscription(acks_only));
+        assert!(manager.shards.iter().all(|shard| shard.read().len() == 1));
+        assert_eq!(manager.subscription_count(), 1);
+    }
+}
@@ -0,0 +1,708 @@
+//! Deterministic in-memory bus for testing modules (`testkit` feature)
+//!
+//! `TestEventBus` implements `EventBusTrait` without router threads. Delivery
+//! runs on a virtual clock that only moves when the test calls `advance`, so
+//! timeouts, retries, and dead-lettering happen at exact, repeatable instants.
+//! Subscribers can be scripted to fail or to take a while per message, and
+//! every publish and delivery attempt is recorded for assertions.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use parking_lot::Mutex;
+
+use crate::{
+    authorization::{AuthorizationPolicy, Authorizer},
+    validation::{PublishValidator, ValidatorRegistry},
+    error_logging::ErrorLogger,
+    metrics::MetricsCollector,
+    router::estimate_message_size,
+    subscription::{DeliveryMode, MessageFilter, ReplaySummary},
+    BusMessage, DrainSummary, BusMetrics, EventBusError, EventBusResult, EventBusTrait, MessageId, MessageType, ModuleId,
+    RetryConfig, SubscriptionId,
+};
+
+/// Shared virtual time, starting at zero
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl VirtualClock {
+    /// Time elapsed since the bus was created
+    pub fn now(&self) -> Duration {
+        *self.now.lock()
+    }
+
+    fn set(&self, time: Duration) {
+        let mut now = self.now.lock();
+        *now = (*now).max(time);
+    }
+}
+
+/// How a subscriber handles the messages delivered to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubscriberBehavior {
+    /// Take every message straight away
+    #[default]
+    Accept,
+    /// Fail the next `n` delivery attempts, then accept
+    FailTimes(u32),
+    /// Spend this much virtual time on each message; at or past the delivery timeout it times out
+    Delay(Duration),
+}
+
+/// Timing and retry rules for `TestEventBus`
+#[derive(Debug, Clone)]
+pub struct TestBusConfig {
+    pub delivery_timeout: Duration,
+    /// Attempts and exponential backoff per delivery; jitter is ignored to stay deterministic
+    pub retry: RetryConfig,
+    /// Enforced like on the real bus when set
+    pub authorization_policy: Option<AuthorizationPolicy>,
+    /// Payload validators, run like on the real bus when set
+    pub validators: Option<ValidatorRegistry>,
+    /// Virtual time `shutdown` keeps delivering before dead-lettering what is left
+    pub drain_timeout: Duration,
+}
+
+impl Default for TestBusConfig {
+    fn default() -> Self {
+        Self {
+            delivery_timeout: Duration::from_secs(5),
+            retry: RetryConfig::default(),
+            authorization_policy: None,
+            validators: None,
+            drain_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+/// What happened to one delivery attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Delivered,
+    Failed,
+    TimedOut,
+    /// Last attempt failed; the message went to the dead letters
+    DeadLettered,
+}
+
+/// One delivery attempt, stamped with virtual time
+#[derive(Debug, Clone)]
+pub struct DeliveryRecord {
+    pub subscription_id: SubscriptionId,
+    pub subscriber: ModuleId,
+    pub message_id: MessageId,
+    pub message_type: MessageType,
+    pub attempt: u32,
+    pub outcome: DeliveryOutcome,
+    pub at: Duration,
+}
+
+struct TestSubscription {
+    subscriber: ModuleId,
+    filter: MessageFilter,
+    sender: Sender<BusMessage>,
+    receiver: Receiver<BusMessage>,
+}
+
+/// Work waiting for its virtual instant
+enum Scheduled {
+    Attempt { subscription_id: SubscriptionId, message: BusMessage, attempt: u32 },
+    /// `started` is the virtual time the attempt began, for latency metrics
+    Complete { subscription_id: SubscriptionId, message: BusMessage, attempt: u32, outcome: DeliveryOutcome, started: Duration },
+    /// A `publish_after`/`publish_at` message falling due
+    Publish(BusMessage),
+}
+
+/// Messages held for a paused module
+struct Paused {
+    capacity: usize,
+    messages: VecDeque<(SubscriptionId, BusMessage)>,
+    dropped: u64,
+}
+
+#[derive(Default)]
+struct State {
+    subscriptions: HashMap<SubscriptionId, TestSubscription>,
+    behaviors: HashMap<ModuleId, SubscriberBehavior>,
+    /// Keyed by (due, insertion order) so same-instant work keeps its order
+    schedule: BTreeMap<(Duration, u64), Scheduled>,
+    next_seq: u64,
+    paused: HashMap<ModuleId, Paused>,
+    published: Vec<(Duration, BusMessage)>,
+    deliveries: Vec<DeliveryRecord>,
+    dead_letters: Vec<BusMessage>,
+    shutdown: bool,
+}
+
+impl State {
+    fn schedule(&mut self, at: Duration, work: Scheduled) {
+        self.schedule.insert((at, self.next_seq), work);
+        self.next_seq += 1;
+    }
+}
+
+/// In-memory `EventBusTrait` driven by a virtual clock
+pub struct TestEventBus {
+    config: TestBusConfig,
+    clock: VirtualClock,
+    state: Mutex<State>,
+    metrics: Arc<MetricsCollector>,
+    authorizer: Option<Authorizer>,
+    validator: Option<PublishValidator>,
+}
+
+impl TestEventBus {
+    pub fn new() -> Self {
+        Self::with_config(TestBusConfig::default())
+    }
+
+    pub fn with_config(config: TestBusConfig) -> Self {
+        let metrics = Arc::new(MetricsCollector::new());
+        let authorizer = config.authorization_policy.clone().map(|policy| {
+            Authorizer::new(policy, Arc::new(ErrorLogger::new(Default::default())), metrics.clone())
+        });
+        let validator = config.validators.clone().map(|registry| {
+            PublishValidator::new(registry, Arc::new(ErrorLogger::new(Default::default())), metrics.clone())
+        });
+        Self {
+            config,
+            clock: VirtualClock::default(),
+            state: Mutex::new(State::default()),
+            metrics,
+            authorizer,
+            validator,
+        }
+    }
+
+    pub fn clock(&self) -> VirtualClock {
+        self.clock.clone()
+    }
+
+    /// Script how `module` handles deliveries from now on
+    pub fn script(&self, module: ModuleId, behavior: SubscriberBehavior) {
+        self.state.lock().behaviors.insert(module, behavior);
+    }
+
+    /// Channel the subscription's delivered messages arrive on
+    pub fn receiver(&self, subscription_id: SubscriptionId) -> Option<Receiver<BusMessage>> {
+        self.state.lock().subscriptions.get(&subscription_id).map(|s| s.receiver.clone())
+    }
+
+    /// Move virtual time forward, running everything that falls due on the way
+    pub fn advance(&self, duration: Duration) {
+        let target = self.clock.now() + duration;
+        self.run_until(Some(target));
+        self.clock.set(target);
+    }
+
+    /// Run all scheduled work, moving the clock to each instant in turn
+    pub fn run_until_idle(&self) {
+        self.run_until(None);
+    }
+
+    /// Whether deliveries, retries, or scheduled publishes are still waiting
+    pub fn is_idle(&self) -> bool {
+        self.state.lock().schedule.is_empty()
+    }
+
+    /// Every published message in publish order
+    pub fn published(&self) -> Vec<BusMessage> {
+        self.state.lock().published.iter().map(|(_, message)| message.clone()).collect()
+    }
+
+    pub fn published_types(&self) -> Vec<MessageType> {
+        self.state.lock().published.iter().map(|(_, message)| message.message_type()).collect()
+    }
+
+    /// Virtual time and type of each publish
+    pub fn published_at(&self) -> Vec<(Duration, MessageType)> {
+        self.state.lock().published.iter().map(|(at, message)| (*at, message.message_type())).collect()
+    }
+
+    /// Delivery attempts to `module` in the order they finished
+    pub fn deliveries(&self, module: ModuleId) -> Vec<DeliveryRecord> {
+        self.state.lock().deliveries.iter().filter(|record| record.subscriber == module).cloned().collect()
+    }
+
+    pub fn dead_letters(&self) -> Vec<BusMessage> {
+        self.state.lock().dead_letters.clone()
+    }
+
+    /// Panic unless exactly these message types were published, in this order
+    #[track_caller]
+    pub fn assert_published(&self, expected: &[MessageType]) {
+        let actual = self.published_types();
+        assert_eq!(actual, expected, "published message types differ");
+    }
+
+    /// Panic unless these message types were published in this order, other messages allowed in between
+    #[track_caller]
+    pub fn assert_published_in_order(&self, expected: &[MessageType]) {
+        let actual = self.published_types();
+        let mut remaining = actual.iter();
+        for wanted in expected {
+            if !remaining.any(|message_type| message_type == wanted) {
+                panic!("expected {:?} to appear in order within published {:?}", expected, actual);
+            }
+        }
+    }
+
+    /// Panic unless `module` ended up with exactly `count` successful deliveries
+    #[track_caller]
+    pub fn assert_delivered(&self, module: ModuleId, count: usize) {
+        let delivered = self
+            .deliveries(module)
+            .iter()
+            .filter(|record| record.outcome == DeliveryOutcome::Delivered)
+            .count();
+        assert_eq!(delivered, count, "deliveries to {}", module);
+    }
+
+    fn run_until(&self, limit: Option<Duration>) {
+        loop {
+            let mut state = self.state.lock();
+            let Some((&(due, seq), _)) = state.schedule.iter().next() else { break };
+            if limit.is_some_and(|limit| due > limit) {
+                break;
+            }
+            let work = state.schedule.remove(&(due, seq)).expect("entry was just seen");
+            self.clock.set(due);
+            self.process(&mut state, work);
+        }
+    }
+
+    fn process(&self, state: &mut State, work: Scheduled) {
+        let now = self.clock.now();
+        match work {
+            Scheduled::Attempt { subscription_id, message, attempt } => {
+                let Some(subscriber) = state.subscriptions.get(&subscription_id).map(|s| s.subscriber) else {
+                    return;
+                };
+                let behavior = state.behaviors.entry(subscriber).or_default();
+                let (finish, outcome) = match *behavior {
+                    SubscriberBehavior::Accept => (now, DeliveryOutcome::Delivered),
+                    SubscriberBehavior::FailTimes(remaining) => {
+                        if remaining > 0 {
+                            *behavior = SubscriberBehavior::FailTimes(remaining - 1);
+                            (now, DeliveryOutcome::Failed)
+                        } else {
+                            (now, DeliveryOutcome::Delivered)
+                        }
+                    }
+                    SubscriberBehavior::Delay(delay) if delay >= self.config.delivery_timeout => {
+                        (now + self.config.delivery_timeout, DeliveryOutcome::TimedOut)
+                    }
+                    SubscriberBehavior::Delay(delay) => (now + delay, DeliveryOutcome::Delivered),
+                };
+                let complete = Scheduled::Complete { subscription_id, message, attempt, outcome, started: now };
+                if finish == now {
+                    self.process(state, complete);
+                } else {
+                    state.schedule(finish, complete);
+                }
+            }
+            Scheduled::Complete { subscription_id, message, attempt, mut outcome, started } => {
+                let Some(subscription) = state.subscriptions.get(&subscription_id) else { return };
+                let subscriber = subscription.subscriber;
+                let message_type = message.message_type();
+
+                if outcome == DeliveryOutcome::Delivered {
+                    let _ = subscription.sender.send(message.clone());
+                    self.metrics.record_delivery(subscriber, message_type, Duration::ZERO);
+                    self.metrics.record_flows(message.source, message_type, now - started, &[subscriber]);
+                } else {
+                    self.metrics.record_failure(subscriber, message_type);
+                    if attempt < self.config.retry.max_attempts {
+                        state.schedule(
+                            now + self.backoff(attempt),
+                            Scheduled::Attempt { subscription_id, message: message.clone(), attempt: attempt + 1 },
+                        );
+                    } else {
+                        outcome = DeliveryOutcome::DeadLettered;
+                        state.dead_letters.push(message.clone());
+                    }
+                }
+
+                state.deliveries.push(DeliveryRecord {
+                    subscription_id,
+                    subscriber,
+                    message_id: message.id,
+                    message_type,
+                    attempt,
+                    outcome,
+                    at: now,
+                });
+            }
+            Scheduled::Publish(message) => self.enqueue(state, message),
+        }
+    }
+
+    /// Record a publish and schedule a delivery attempt per matching subscription
+    fn enqueue(&self, state: &mut State, message: BusMessage) {
+        self.metrics.record_publish(message.source, message.message_type(), estimate_message_size(&message));
+        let now = self.clock.now();
+        state.published.push((now, message.clone()));
+
+        let mut matching: Vec<(SubscriptionId, ModuleId)> = state
+            .subscriptions
+            .iter()
+            .filter(|(_, subscription)| subscription.filter.matches(&message))
+            .map(|(id, subscription)| (*id, subscription.subscriber))
+            .collect();
+        // HashMap order is random; keep delivery order stable between runs
+        matching.sort_by_key(|(id, _)| *id);
+
+        for (subscription_id, subscriber) in matching {
+            if let Some(paused) = state.paused.get_mut(&subscriber) {
+                if paused.messages.len() >= paused.capacity {
+                    paused.messages.pop_front();
+                    paused.dropped += 1;
+                }
+                paused.messages.push_back((subscription_id, message.clone()));
+                continue;
+            }
+            state.schedule(now, Scheduled::Attempt { subscription_id, message: message.clone(), attempt: 1 });
+        }
+    }
+
+    /// Wait before retry number `attempt + 1`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let retry = &self.config.retry;
+        let factor = retry.backoff_multiplier.powi(attempt.saturating_sub(1) as i32);
+        retry.initial_delay.mul_f64(factor).min(retry.max_delay)
+    }
+}
+
+impl Default for TestEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventBusTrait for TestEventBus {
+    async fn publish(&self, message: BusMessage) -> EventBusResult<MessageId> {
+        let message_id = message.id;
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_publish(&message)?;
+        }
+        if let Some(validator) = &self.validator {
+            validator.validate_publish(&message)?;
+        }
+        {
+            let mut state = self.state.lock();
+            if state.shutdown {
+                return Err(EventBusError::BusShuttingDown);
+            }
+            self.enqueue(&mut state, message);
+        }
+
+        // Work due right now runs before publish returns
+        self.run_until(Some(self.clock.now()));
+        Ok(message_id)
+    }
+
+    async fn subscribe(
+        &self,
+        subscriber: ModuleId,
+        filter: MessageFilter,
+        _delivery_mode: DeliveryMode,
+    ) -> EventBusResult<SubscriptionId> {
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_subscribe(subscriber, &filter)?;
+        }
+        let mut state = self.state.lock();
+        if state.shutdown {
+            return Err(EventBusError::BusShuttingDown);
+        }
+        let (sender, receiver) = unbounded();
+        let subscription_id = uuid::Uuid::new_v4();
+        state.subscriptions.insert(subscription_id, TestSubscription { subscriber, filter, sender, receiver });
+        self.metrics.record_subscription_created(subscriber);
+        Ok(subscription_id)
+    }
+
+    async fn unsubscribe(&self, subscription_id: SubscriptionId) -> EventBusResult<()> {
+        match self.state.lock().subscriptions.remove(&subscription_id) {
+            Some(subscription) => {
+                self.metrics.record_subscription_removed(subscription.subscriber);
+                Ok(())
+            }
+            None => Err(EventBusError::SubscriptionNotFound { subscription_id }),
+        }
+    }
+
+    async fn publish_after(&self, message: BusMessage, delay: Duration) -> EventBusResult<MessageId> {
+        let message_id = message.id;
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authorize_publish(&message)?;
+        }
+        if let Some(validator) = &self.validator {
+            validator.validate_publish(&message)?;
+        }
+        let mut state = self.state.lock();
+        if state.shutdown {
+            return Err(EventBusError::BusShuttingDown);
+        }
+        let due = self.clock.now() + delay;
+        state.schedule(due, Scheduled::Publish(message));
+        Ok(message_id)
+    }
+
+    /// Virtual time has no calendar, so `at` counts from the wall clock at the call
+    async fn publish_at(&self, message: BusMessage, at: DateTime<Utc>) -> EventBusResult<MessageId> {
+        let delay = (at - Utc::now()).to_std().unwrap_or_default();
+        self.publish_after(message, delay).await
+    }
+
+    async fn cancel_scheduled(&self, message_id: MessageId) -> EventBusResult<bool> {
+        let mut state = self.state.lock();
+        let key = state.schedule.iter().find_map(|(key, work)| match work {
+            Scheduled::Publish(message) if message.id == message_id => Some(*key),
+            _ => None,
+        });
+        Ok(key.and_then(|key| state.schedule.remove(&key)).is_some())
+    }
+
+    async fn pause_delivery(&self, module: ModuleId, capacity: usize) -> EventBusResult<()> {
+        self.state.lock().paused.insert(module, Paused { capacity, messages: VecDeque::new(), dropped: 0 });
+        Ok(())
+    }
+
+    async fn resume_delivery(&self, module: ModuleId) -> EventBusResult<ReplaySummary> {
+        let summary = {
+            let mut state = self.state.lock();
+            let Some(paused) = state.paused.remove(&module) else {
+                return Ok(ReplaySummary::default());
+            };
+            let now = self.clock.now();
+            let mut summary = ReplaySummary { dropped: paused.dropped, ..Default::default() };
+            let mut seen = HashSet::new();
+            let mut handed = HashSet::new();
+            for (subscription_id, message) in paused.messages {
+                // A module that subscribed again while paused gets the message on its new subscriptions
+                let mut targets: Vec<SubscriptionId> = if state.subscriptions.contains_key(&subscription_id) {
+                    vec![subscription_id]
+                } else {
+                    state
+                        .subscriptions
+                        .iter()
+                        .filter(|(_, subscription)| subscription.subscriber == module && subscription.filter.matches(&message))
+                        .map(|(id, _)| *id)
+                        .collect()
+                };
+                targets.sort();
+                targets.retain(|id| handed.insert((message.id, *id)));
+                let first_copy = seen.insert(message.id);
+                if targets.is_empty() {
+                    if first_copy {
+                        summary.failed += 1;
+                        state.dead_letters.push(message);
+                    }
+                    continue;
+                }
+                for subscription_id in targets {
+                    state.schedule(now, Scheduled::Attempt { subscription_id, message: message.clone(), attempt: 1 });
+                }
+                summary.replayed += 1;
+            }
+            summary
+        };
+        self.run_until(Some(self.clock.now()));
+        Ok(summary)
+    }
+
+    async fn metrics(&self) -> EventBusResult<BusMetrics> {
+        let mut subscription_counts = HashMap::new();
+        for subscription in self.state.lock().subscriptions.values() {
+            *subscription_counts.entry(subscription.subscriber).or_insert(0) += 1;
+        }
+        Ok(self.metrics.snapshot(subscription_counts))
+    }
+
+    /// Drains on virtual time: deliveries and retries due within `drain_timeout` still run
+    async fn shutdown(&self) -> EventBusResult<DrainSummary> {
+        let (started, delivered_before) = {
+            let mut state = self.state.lock();
+            if state.shutdown {
+                return Ok(DrainSummary::default());
+            }
+            state.shutdown = true;
+            (self.clock.now(), state.deliveries.len())
+        };
+
+        self.run_until(Some(started + self.config.drain_timeout));
+
+        let mut state = self.state.lock();
+        let delivered = state.deliveries[delivered_before..]
+            .iter()
+            .filter(|record| record.outcome == DeliveryOutcome::Delivered)
+            .count();
+
+        // Scheduled publishes are dropped; anything mid-delivery or paused is dead-lettered
+        let mut stranded = Vec::new();
+        for work in std::mem::take(&mut state.schedule).into_values() {
+            match work {
+                Scheduled::Attempt { message, .. } | Scheduled::Complete { message, .. } => stranded.push(message),
+                Scheduled::Publish(_) => {}
+            }
+        }
+        let timed_out = !stranded.is_empty();
+        for paused in std::mem::take(&mut state.paused).into_values() {
+            stranded.extend(paused.messages.into_iter().map(|(_, message)| message));
+        }
+
+        let summary = DrainSummary {
+            delivered,
+            dead_lettered: stranded.len(),
+            timed_out,
+            elapsed: self.clock.now() - started,
+            ..Default::default()
+        };
+        state.dead_letters.extend(stranded);
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessagePayload;
+
+    fn ready(module: ModuleId) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(module))
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_to_resubscribed_module() {
+        let bus = TestEventBus::new();
+        let old = bus.subscribe(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+        bus.pause_delivery(ModuleId::Storage, 10).await.unwrap();
+        let message = ready(ModuleId::Storage);
+        bus.publish(message.clone()).await.unwrap();
+
+        // Restarted: the old subscription is gone and a new one takes its place
+        bus.unsubscribe(old).await.unwrap();
+        let new = bus.subscribe(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+        let receiver = bus.receiver(new).unwrap();
+
+        let summary = bus.resume_delivery(ModuleId::Storage).await.unwrap();
+        assert_eq!(summary, ReplaySummary { replayed: 1, dropped: 0, failed: 0 });
+        assert_eq!(receiver.try_recv().unwrap().id, message.id);
+
+        // With no subscription left at all, held messages are dead-lettered
+        bus.pause_delivery(ModuleId::Storage, 10).await.unwrap();
+        bus.publish(ready(ModuleId::Storage)).await.unwrap();
+        bus.unsubscribe(new).await.unwrap();
+        let summary = bus.resume_delivery(ModuleId::Storage).await.unwrap();
+        assert_eq!(summary.failed, 1);
+        assert_eq!(bus.dead_letters().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failures_retry_on_virtual_backoff_then_dead_letter() {
+        let bus = TestEventBus::new();
+        let subscription = bus.subscribe(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+        let receiver = bus.receiver(subscription).unwrap();
+
+        bus.script(ModuleId::Storage, SubscriberBehavior::FailTimes(2));
+        bus.publish(ready(ModuleId::Storage)).await.unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        // Retries after 100ms, then 200ms more
+        bus.advance(Duration::from_millis(99));
+        assert_eq!(bus.deliveries(ModuleId::Storage).len(), 1);
+        bus.advance(Duration::from_millis(1));
+        assert_eq!(bus.deliveries(ModuleId::Storage).len(), 2);
+        bus.advance(Duration::from_millis(200));
+        assert!(receiver.try_recv().is_ok());
+        let attempts: Vec<(u32, DeliveryOutcome, u128)> = bus
+            .deliveries(ModuleId::Storage)
+            .iter()
+            .map(|record| (record.attempt, record.outcome, record.at.as_millis()))
+            .collect();
+        assert_eq!(
+            attempts,
+            vec![(1, DeliveryOutcome::Failed, 0), (2, DeliveryOutcome::Failed, 100), (3, DeliveryOutcome::Delivered, 300)]
+        );
+
+        bus.script(ModuleId::Storage, SubscriberBehavior::FailTimes(3));
+        bus.publish(ready(ModuleId::DataCapture)).await.unwrap();
+        bus.run_until_idle();
+        assert_eq!(bus.dead_letters().len(), 1);
+        bus.assert_delivered(ModuleId::Storage, 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_times_out_and_sequence_assertions() {
+        let bus = TestEventBus::new();
+        bus.subscribe(ModuleId::AnalysisEngine, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+
+        bus.script(ModuleId::AnalysisEngine, SubscriberBehavior::Delay(Duration::from_secs(1)));
+        bus.publish(ready(ModuleId::Storage)).await.unwrap();
+        assert!(!bus.is_idle());
+        bus.advance(Duration::from_secs(1));
+        bus.assert_delivered(ModuleId::AnalysisEngine, 1);
+
+        bus.script(ModuleId::AnalysisEngine, SubscriberBehavior::Delay(Duration::from_secs(10)));
+        bus.publish(BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(ModuleId::Gamification)))
+            .await
+            .unwrap();
+        bus.advance(Duration::from_secs(5));
+        let last = bus.deliveries(ModuleId::AnalysisEngine).pop().unwrap();
+        assert_eq!((last.outcome, last.at), (DeliveryOutcome::TimedOut, Duration::from_secs(6)));
+
+        bus.assert_published(&[MessageType::ModuleReady, MessageType::ModuleReady]);
+        assert_eq!(bus.published_at()[1].0, Duration::from_secs(1));
+        bus.assert_published_in_order(&[MessageType::ModuleReady]);
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 2);
+    }
+
+    #[tokio::test]
+    async fn test_publish_after_fires_on_virtual_time_and_can_be_cancelled() {
+        let bus = TestEventBus::new();
+        bus.subscribe(ModuleId::AiIntegration, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+
+        bus.publish_after(ready(ModuleId::Storage), Duration::from_secs(20 * 60)).await.unwrap();
+        let cancelled = bus.publish_after(ready(ModuleId::Gamification), Duration::from_secs(60)).await.unwrap();
+        assert!(bus.cancel_scheduled(cancelled).await.unwrap());
+        assert!(!bus.cancel_scheduled(cancelled).await.unwrap());
+
+        bus.advance(Duration::from_secs(20 * 60 - 1));
+        assert!(bus.published().is_empty());
+        bus.advance(Duration::from_secs(1));
+        assert_eq!(bus.published_at(), vec![(Duration::from_secs(20 * 60), MessageType::ModuleReady)]);
+        bus.assert_delivered(ModuleId::AiIntegration, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_within_deadline_and_dead_letters_the_rest() {
+        let bus = TestEventBus::new();
+        bus.subscribe(ModuleId::Storage, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+        bus.subscribe(ModuleId::Gamification, MessageFilter::all(), DeliveryMode::BestEffort).await.unwrap();
+
+        // Storage finishes inside the 2s drain, Gamification does not
+        bus.script(ModuleId::Storage, SubscriberBehavior::Delay(Duration::from_secs(1)));
+        bus.script(ModuleId::Gamification, SubscriberBehavior::Delay(Duration::from_secs(3)));
+        bus.publish(ready(ModuleId::AnalysisEngine)).await.unwrap();
+
+        let summary = bus.shutdown().await.unwrap();
+        assert_eq!(summary.delivered, 1);
+        assert_eq!(summary.dead_lettered, 1);
+        assert!(summary.timed_out);
+        assert_eq!(summary.elapsed, Duration::from_secs(1));
+        assert_eq!(bus.dead_letters().len(), 1);
+
+        assert!(matches!(bus.publish(ready(ModuleId::Storage)).await, Err(EventBusError::BusShuttingDown)));
+        assert_eq!(bus.shutdown().await.unwrap(), DrainSummary::default());
+    }
+}
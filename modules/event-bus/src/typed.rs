@@ -0,0 +1,197 @@
+//! Typed subscriptions
+//!
+//! Instead of matching on [`MessagePayload`] in every handler, a module can
+//! subscribe to one payload type and receive it already unwrapped:
+//!
+//! ```ignore
+//! let (_id, mut states) = bus.subscribe_typed::<StateClassification>(ModuleId::Gamification, DeliveryMode::BestEffort)?;
+//! while let Some((message_id, state)) = states.next().await {
+//!     // state is a StateClassification
+//! }
+//! ```
+//!
+//! The subscription filters on the payload's [`MessageType`], so other
+//! messages never reach the subscriber. Dropping the stream unsubscribes.
+
+use std::sync::Arc;
+use crossbeam_channel::Receiver;
+use tracing::warn;
+
+use crate::{
+    BusMessage, MessageId, MessagePayload, MessageType, SubscriptionId,
+    message::*,
+    stream::SubscriptionStream,
+    subscription::SubscriptionManager,
+};
+
+/// A payload that can be subscribed to on its own
+///
+/// Implemented for the payload of every `MessagePayload` variant except
+/// `ModuleReady` and `Compressed`, which don't carry a type of their own.
+pub trait TypedPayload: TryFrom<MessagePayload, Error = MessagePayload> + Into<MessagePayload> + Send + 'static {
+    /// The message type carrying this payload
+    const MESSAGE_TYPE: MessageType;
+}
+
+macro_rules! typed_payloads {
+    ($($variant:ident($payload:ty)),* $(,)?) => {$(
+        impl TryFrom<MessagePayload> for $payload {
+            type Error = MessagePayload;
+
+            fn try_from(payload: MessagePayload) -> Result<Self, MessagePayload> {
+                match payload {
+                    MessagePayload::$variant(inner) => Ok(inner),
+                    other => Err(other),
+                }
+            }
+        }
+
+        impl From<$payload> for MessagePayload {
+            fn from(inner: $payload) -> Self {
+                MessagePayload::$variant(inner)
+            }
+        }
+
+        impl TypedPayload for $payload {
+            const MESSAGE_TYPE: MessageType = MessageType::$variant;
+        }
+    )*};
+}
+
+typed_payloads! {
+    RawEvent(RawEvent),
+    EventBatch(EventBatch),
+    StorageStatus(StorageMetrics),
+    AnalysisComplete(AnalysisWindow),
+    StateChange(StateClassification),
+    TrainingCompleted(TrainingCompleted),
+    DriftDetected(DriftDetected),
+    FocusCheckResult(FocusCheckResult),
+    DayActivity(DayActivity),
+    ForecastUpdated(ForecastUpdated),
+    InterventionRequest(InterventionRequest),
+    RewardEvent(RewardEvent),
+    RewardGranted(RewardGranted),
+    InterventionResponse(InterventionResponse),
+    InterventionDelivered(InterventionDelivered),
+    AnimationCommand(AnimationCommand),
+    ModelDownloadProgress(ModelDownloadProgress),
+    DailySummaryRequest(DailySummaryRequest),
+    DailySummary(DailySummary),
+    HealthCheck(HealthCheckRequest),
+    ConfigUpdate(ConfigUpdate),
+    ResourceViolation(ResourceViolation),
+    PowerStateChanged(PowerStateChange),
+    TelemetryBatch(TelemetryBatch),
+    PerformanceRegression(PerformanceRegression),
+    MemoryLeakSuspected(MemoryLeakSuspected),
+    RetransmitRequest(RetransmitRequest),
+    ConfigTransactionResult(ConfigTransactionResult),
+    MaintenanceRequest(MaintenanceRequest),
+    SystemPower(SystemPowerEvent),
+    FocusCheckRequest(FocusCheckRequest),
+    CurrentTask(CurrentTask),
+    CheckInResponse(CheckInResponse),
+    Shutdown(ShutdownRequest),
+    DeliveryAck(DeliveryAck),
+    MessageDigest(MessageDigest),
+    PoisonMessageDetected(PoisonMessageDetected),
+    ConfirmationRequired(ConfirmationRequired),
+    ConfirmationResponse(ConfirmationResponse),
+    MaintenanceReport(MaintenanceReport),
+    Error(ErrorReport),
+}
+
+/// Stream of one payload type with the id of the message that carried it
+///
+/// Ends when the subscription is removed or the bus shuts down; dropping it
+/// removes the subscription.
+pub type TypedSubscription<T> = SubscriptionStream<(MessageId, T)>;
+
+/// Forward `receiver`'s messages, unwrapped, until either side goes away
+pub(crate) fn forward_typed<T: TypedPayload>(
+    id: SubscriptionId,
+    receiver: Receiver<BusMessage>,
+    subscriptions: Arc<SubscriptionManager>,
+) -> TypedSubscription<T> {
+    SubscriptionStream::forward(id, receiver, subscriptions, move |message| {
+        let message_id = message.id;
+        let payload = match message.decompressed() {
+            Ok(message) => T::try_from(message.payload),
+            Err(e) => {
+                warn!("Dropping message {} for typed subscription {}: {}", message_id, id, e);
+                return None;
+            }
+        };
+        match payload {
+            Ok(payload) => Some((message_id, payload)),
+            Err(other) => {
+                warn!("Typed subscription {} expected {:?} but got {:?}", id, T::MESSAGE_TYPE, other.message_type());
+                None
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_event_bus, DeliveryMode, EventBusTrait, ModuleId};
+    use crate::stream::FORWARD_IDLE_CHECK;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    fn config_update(key: &str) -> BusMessage {
+        BusMessage::new(ModuleId::Orchestrator, ConfigUpdate {
+            config_key: key.to_string(),
+            config_value: serde_json::json!(1),
+            target_module: None,
+        }.into())
+    }
+
+    #[test]
+    fn test_payload_conversions() {
+        let payload: MessagePayload = ConfigUpdate {
+            config_key: "theme".to_string(),
+            config_value: serde_json::json!("dark"),
+            target_module: None,
+        }
+        .into();
+        assert_eq!(payload.message_type(), ConfigUpdate::MESSAGE_TYPE);
+
+        let wrong = HealthCheckRequest::try_from(payload).unwrap_err();
+        let update = ConfigUpdate::try_from(wrong).unwrap();
+        assert_eq!(update.config_key, "theme");
+    }
+
+    #[tokio::test]
+    async fn test_typed_subscription_only_sees_its_type() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (id, mut updates) = bus.subscribe_typed::<ConfigUpdate>(ModuleId::Storage, DeliveryMode::BestEffort).unwrap();
+        assert_eq!(updates.id(), id);
+
+        bus.publish(BusMessage::new(ModuleId::Orchestrator, HealthCheckRequest {
+            module_id: ModuleId::Storage,
+            timestamp: chrono::Utc::now(),
+        }.into()))
+        .await
+        .unwrap();
+        let published = bus.publish(config_update("theme")).await.unwrap();
+
+        let (message_id, update) = tokio::time::timeout(Duration::from_secs(2), updates.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message_id, published);
+        assert_eq!(update.config_key, "theme");
+
+        // Dropping the stream removes the subscription
+        drop(updates);
+        tokio::time::sleep(FORWARD_IDLE_CHECK * 2).await;
+        assert!(matches!(
+            bus.unsubscribe(id).await,
+            Err(crate::EventBusError::SubscriptionNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+//! Publish-side payload validation
+//!
+//! Modules register validators per message type in a [`ValidatorRegistry`]. The
+//! bus runs them before a message is queued and rejects a bad payload with
+//! [`EventBusError::InvalidPayload`], listing every issue found, so it fails at
+//! the publisher instead of deep inside a subscriber. Rejections are logged under
+//! [`ErrorCategory::Validation`] and counted in `BusMetrics`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    BusMessage, EventBusError, EventBusResult, MessagePayload, MessageType,
+    error_logging::{ErrorCategory, ErrorContext, ErrorLogger, ErrorSeverity},
+    metrics::MetricsCollector,
+};
+
+/// One problem found in a payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// Path of the offending field, e.g. `"window_title"` or `"events[3].timestamp"`
+    pub field: String,
+    pub reason: String,
+}
+
+impl ValidationIssue {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self { field: field.into(), reason: reason.into() }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+/// Checks a payload, returning every issue found (empty when valid)
+pub type PayloadValidator = Arc<dyn Fn(&MessagePayload) -> Vec<ValidationIssue> + Send + Sync>;
+
+/// Validators per message type
+///
+/// Clones share the same validators, so a module can keep a handle and register
+/// its validators after the bus has been created.
+#[derive(Clone, Default)]
+pub struct ValidatorRegistry {
+    validators: Arc<RwLock<HashMap<MessageType, Vec<PayloadValidator>>>>,
+}
+
+impl ValidatorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a validator for `message_type`; every registered validator runs on each publish
+    pub fn register<F>(&self, message_type: MessageType, validator: F)
+    where
+        F: Fn(&MessagePayload) -> Vec<ValidationIssue> + Send + Sync + 'static,
+    {
+        self.validators.write().entry(message_type).or_default().push(Arc::new(validator));
+    }
+
+    /// Remove every validator for `message_type`
+    pub fn clear(&self, message_type: MessageType) {
+        self.validators.write().remove(&message_type);
+    }
+
+    /// Number of validators registered for `message_type`
+    pub fn validator_count(&self, message_type: MessageType) -> usize {
+        self.validators.read().get(&message_type).map_or(0, Vec::len)
+    }
+
+    /// Run the validators for the message's type
+    pub fn check(&self, message: &BusMessage) -> EventBusResult<()> {
+        let message_type = message.message_type();
+        // Clone the list so validators run without holding the lock
+        let Some(validators) = self.validators.read().get(&message_type).cloned() else {
+            return Ok(());
+        };
+
+        let issues: Vec<ValidationIssue> = validators.iter().flat_map(|validator| validator(&message.payload)).collect();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(EventBusError::InvalidPayload { module: message.source, message_type, issues })
+        }
+    }
+}
+
+impl std::fmt::Debug for ValidatorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts: HashMap<MessageType, usize> =
+            self.validators.read().iter().map(|(message_type, list)| (*message_type, list.len())).collect();
+        f.debug_struct("ValidatorRegistry").field("validators", &counts).finish()
+    }
+}
+
+/// Runs the registry on behalf of a bus, logging and counting every rejection
+pub(crate) struct PublishValidator {
+    registry: ValidatorRegistry,
+    error_logger: Arc<ErrorLogger>,
+    metrics: Arc<MetricsCollector>,
+}
+
+impl PublishValidator {
+    pub(crate) fn new(registry: ValidatorRegistry, error_logger: Arc<ErrorLogger>, metrics: Arc<MetricsCollector>) -> Self {
+        Self { registry, error_logger, metrics }
+    }
+
+    pub(crate) fn validate_publish(&self, message: &BusMessage) -> EventBusResult<()> {
+        self.registry.check(message).inspect_err(|error| self.reject(error, message))
+    }
+
+    fn reject(&self, error: &EventBusError, message: &BusMessage) {
+        warn!("🧪 {}", error);
+        self.metrics.record_validation_rejected(message.source);
+
+        let context = ErrorContext::new(
+            ErrorLogger::create_correlation_id(),
+            message.source,
+            "publish_message".to_string(),
+            ErrorSeverity::Error,
+            ErrorCategory::Validation,
+            error.to_string(),
+        )
+        .with_message_id(message.id)
+        .with_metadata("message_type", message.message_type());
+        self.error_logger.log_error(&context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModuleId, message::RawEvent};
+    use chrono::Utc;
+
+    fn raw_event(event_type: &str, window_title: Option<&str>) -> BusMessage {
+        BusMessage::new(
+            ModuleId::DataCapture,
+            MessagePayload::RawEvent(RawEvent {
+                event_type: event_type.to_string(),
+                data: serde_json::json!({}),
+                window_title: window_title.map(str::to_string),
+                timestamp: Utc::now(),
+            }),
+        )
+    }
+
+    fn registry() -> ValidatorRegistry {
+        let registry = ValidatorRegistry::new();
+        registry.register(MessageType::RawEvent, |payload| match payload {
+            MessagePayload::RawEvent(event) if event.event_type.is_empty() => {
+                vec![ValidationIssue::new("event_type", "must not be empty")]
+            }
+            _ => Vec::new(),
+        });
+        registry.register(MessageType::RawEvent, |payload| match payload {
+            MessagePayload::RawEvent(event) if event.window_title.as_deref() == Some("") => {
+                vec![ValidationIssue::new("window_title", "use None instead of an empty title")]
+            }
+            _ => Vec::new(),
+        });
+        registry
+    }
+
+    #[test]
+    fn test_every_validator_runs_and_reports_all_issues() {
+        let registry = registry();
+        assert_eq!(registry.validator_count(MessageType::RawEvent), 2);
+        assert!(registry.check(&raw_event("keystroke", Some("Editor"))).is_ok());
+
+        let error = registry.check(&raw_event("", Some(""))).unwrap_err();
+        match &error {
+            EventBusError::InvalidPayload { module, message_type, issues } => {
+                assert_eq!((*module, *message_type), (ModuleId::DataCapture, MessageType::RawEvent));
+                let fields: Vec<&str> = issues.iter().map(|issue| issue.field.as_str()).collect();
+                assert_eq!(fields, vec!["event_type", "window_title"]);
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert!(error.to_string().contains("event_type: must not be empty; window_title:"));
+
+        // Types without validators pass, and clearing removes them
+        assert!(registry.check(&BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(ModuleId::Storage))).is_ok());
+        registry.clear(MessageType::RawEvent);
+        assert!(registry.check(&raw_event("", None)).is_ok());
+    }
+
+    #[test]
+    fn test_rejections_are_logged_and_counted() {
+        let error_logger = Arc::new(ErrorLogger::new(Default::default()));
+        let metrics = Arc::new(MetricsCollector::new());
+        let validator = PublishValidator::new(registry(), error_logger.clone(), metrics.clone());
+
+        assert!(validator.validate_publish(&raw_event("keystroke", None)).is_ok());
+        assert!(validator.validate_publish(&raw_event("", None)).is_err());
+
+        let snapshot = metrics.snapshot(HashMap::new());
+        assert_eq!(snapshot.validation_rejections, 1);
+        assert_eq!(snapshot.module_stats[&ModuleId::DataCapture].validation_rejections, 1);
+        assert_eq!(error_logger.stats().errors_by_category.get("Validation"), Some(&1));
+    }
+}
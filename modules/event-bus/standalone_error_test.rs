@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use skelly_jelly_event_bus::{
     EventBusConfig, BusMessage, MessagePayload, MessagePriority, ModuleId,
-    CircuitBreakerRegistry, CircuitBreakerConfig,
+    CircuitBreakerRegistry, CircuitBreakerConfig, HalfOpenOverflow,
     RetryExecutor, RetryConfig,
     DeadLetterQueue, DeadLetterQueueConfig, DeadLetterReason,
     ErrorLogger, ErrorLoggerConfig, ErrorContext, ErrorSeverity, ErrorCategory,
@@ -30,6 +30,8 @@ async fn test_circuit_breaker() -> Result<(), Box<dyn std::error::Error>> {
         success_threshold: 0.8,
         half_open_max_calls: 2,
         failure_count_window: Duration::from_secs(60),
+        half_open_probe_interval: Duration::ZERO,
+        half_open_overflow: HalfOpenOverflow::Reject,
     };
     
     let breaker = registry.register("test_circuit".to_string(), config);
@@ -70,6 +72,7 @@ async fn test_retry_mechanism() -> Result<(), Box<dyn std::error::Error>> {
         jitter_factor: 0.1,
         total_timeout: Some(Duration::from_secs(5)),
         reset_on_success: true,
+        hedging: None,
     };
     
     let retry_executor = Arc::new(RetryExecutor::new(config)?);
@@ -113,6 +116,8 @@ async fn test_dead_letter_queue() -> Result<(), Box<dyn std::error::Error>> {
         auto_replay: None,
         enable_metrics: true,
         replay_batch_size: 10,
+        spill: None,
+        cluster_alert_threshold: 10,
     };
     
     let dlq = DeadLetterQueue::new(config);
@@ -222,6 +227,8 @@ async fn test_recovery_system() -> Result<(), Box<dyn std::error::Error>> {
             email_addresses: vec![],
             slack_channels: vec![],
         },
+        confirmation_level: EscalationLevel::System,
+        confirmation_timeout: Duration::from_secs(1),
     };
     
     let recovery_system = RecoverySystem::new(
@@ -291,6 +298,7 @@ async fn test_enhanced_event_bus_integration() -> Result<(), Box<dyn std::error:
         delivery_timeout: Duration::from_millis(500),
         max_retry_attempts: 3,
         dead_letter_queue_size: 100,
+        dead_letter_spill: None,
         metrics_interval: Duration::from_millis(100),
         slow_handler_threshold: Duration::from_millis(50),
         circuit_breaker_config: Some(CircuitBreakerConfig::default()),
@@ -298,6 +306,15 @@ async fn test_enhanced_event_bus_integration() -> Result<(), Box<dyn std::error:
         error_logging_config: Some(ErrorLoggerConfig::default()),
         recovery_config: Some(RecoveryConfig::default()),
         enable_error_handling: true,
+        authorization_policy: None,
+        validators: None,
+        scheduled_messages_path: None,
+        dead_letter_path: None,
+        drain_timeout: Duration::from_secs(2),
+        compression: None,
+        poison: Default::default(),
+        deduplication: Default::default(),
+        memory_budget: None,
     };
     
     let bus = create_enhanced_event_bus_with_config(config)?;
@@ -11,7 +11,7 @@ use uuid::Uuid;
 use skelly_jelly_event_bus::{
     EnhancedEventBus, EventBusConfig, BusMessage, MessagePayload, MessagePriority, ModuleId,
     MessageFilter, DeliveryMode, EventBusError, EventBusTrait, MessageType,
-    CircuitBreakerConfig, RetryConfig, 
+    CircuitBreakerConfig, HalfOpenOverflow, RetryConfig, 
     DeadLetterReason, ErrorSeverity, ErrorCategory,
     RecoveryAction, RecoveryStrategy, EscalationLevel, IncidentStatus,
     create_enhanced_event_bus_with_config,
@@ -48,6 +48,8 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
         success_threshold: 0.8,
         half_open_max_calls: 2,
         failure_count_window: Duration::from_secs(60),
+        half_open_probe_interval: Duration::ZERO,
+        half_open_overflow: HalfOpenOverflow::Reject,
     };
 
     let retry_config = RetryConfig {
@@ -58,6 +60,7 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
         jitter_factor: 0.1,
         total_timeout: Some(Duration::from_secs(5)),
         reset_on_success: true,
+        hedging: None,
     };
 
     let error_logging_config = ErrorLoggerConfig {
@@ -85,6 +88,8 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
             email_addresses: vec![],
             slack_channels: vec![],
         },
+        confirmation_level: EscalationLevel::System,
+        confirmation_timeout: Duration::from_secs(1),
     };
 
     let _dlq_config = DeadLetterQueueConfig {
@@ -95,6 +100,8 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
         auto_replay: None, // Manual replay for tests
         enable_metrics: true,
         replay_batch_size: 10,
+        spill: None,
+        cluster_alert_threshold: 10,
     };
 
     let config = EventBusConfig {
@@ -102,6 +109,7 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
         delivery_timeout: Duration::from_millis(500),
         max_retry_attempts: test_config.retry_max_attempts,
         dead_letter_queue_size: test_config.dlq_max_entries,
+        dead_letter_spill: None,
         metrics_interval: Duration::from_millis(100),
         slow_handler_threshold: Duration::from_millis(50),
         circuit_breaker_config: Some(circuit_breaker_config),
@@ -109,6 +117,15 @@ async fn create_test_bus(test_config: ErrorTestConfig) -> Arc<EnhancedEventBus>
         error_logging_config: Some(error_logging_config),
         recovery_config: Some(recovery_config),
         enable_error_handling: true,
+        authorization_policy: None,
+        validators: None,
+        scheduled_messages_path: None,
+        dead_letter_path: None,
+        drain_timeout: Duration::from_secs(2),
+        compression: None,
+        poison: Default::default(),
+        deduplication: Default::default(),
+        memory_budget: None,
     };
 
     let bus = create_enhanced_event_bus_with_config(config).unwrap();
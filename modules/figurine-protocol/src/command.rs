@@ -0,0 +1,276 @@
+//! Animation command vocabulary shared with the figurine UI
+
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::message::UnlockKind;
+use uuid::Uuid;
+
+/// How melted the skeleton currently is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MeltLevel {
+    Solid,
+    Softening,
+    Drippy,
+    Puddle,
+}
+
+impl MeltLevel {
+    /// All melt levels in order from solid to puddle
+    pub const ALL: [MeltLevel; 4] = [
+        MeltLevel::Solid,
+        MeltLevel::Softening,
+        MeltLevel::Drippy,
+        MeltLevel::Puddle,
+    ];
+
+    /// Melt percentage understood by the UI (0-100)
+    pub fn percent(&self) -> u8 {
+        match self {
+            MeltLevel::Solid => 0,
+            MeltLevel::Softening => 30,
+            MeltLevel::Drippy => 60,
+            MeltLevel::Puddle => 100,
+        }
+    }
+
+    /// Map a 0.0-1.0 intensity onto a melt level
+    pub fn from_ratio(ratio: f64) -> Self {
+        match ratio {
+            r if r >= 0.85 => MeltLevel::Puddle,
+            r if r >= 0.55 => MeltLevel::Drippy,
+            r if r >= 0.25 => MeltLevel::Softening,
+            _ => MeltLevel::Solid,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Number of steps between two melt levels
+    pub fn distance(&self, other: MeltLevel) -> usize {
+        self.index().abs_diff(other.index())
+    }
+
+    /// The next level one step toward `target`, or `None` if already there
+    pub fn step_toward(&self, target: MeltLevel) -> Option<MeltLevel> {
+        let current = self.index();
+        let target = target.index();
+        if current == target {
+            None
+        } else if current < target {
+            Some(Self::ALL[current + 1])
+        } else {
+            Some(Self::ALL[current - 1])
+        }
+    }
+}
+
+/// Figurine mood, mirroring the UI's MoodState
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MoodState {
+    Happy,
+    Focused,
+    Tired,
+    Excited,
+    Melting,
+    Thinking,
+    Celebrating,
+}
+
+/// Figurine activity, mirroring the UI's ActivityState
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ActivityState {
+    Idle,
+    Working,
+    Interacting,
+    Resting,
+    Intervening,
+}
+
+/// One-shot gestures the figurine can perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Gesture {
+    Wave,
+    Nod,
+    Fidget,
+    Stretch,
+    Yawn,
+    Point,
+    Dance,
+    Sparkle,
+}
+
+impl Gesture {
+    /// Parse a free-form animation cue (as produced by AI integration) into a gesture
+    pub fn from_cue(cue: &str) -> Option<Self> {
+        match cue.to_lowercase().as_str() {
+            "wave" | "greeting" => Some(Gesture::Wave),
+            "nod" | "supportive" | "agree" => Some(Gesture::Nod),
+            "fidget" => Some(Gesture::Fidget),
+            "stretch" | "break" => Some(Gesture::Stretch),
+            "yawn" | "sleepy" => Some(Gesture::Yawn),
+            "point" | "focus" | "focused" => Some(Gesture::Point),
+            "dance" | "celebration" | "celebrate" => Some(Gesture::Dance),
+            "sparkle" | "excited" | "happy" => Some(Gesture::Sparkle),
+            _ => None,
+        }
+    }
+
+    /// Default playback length for the gesture
+    pub fn default_duration_ms(&self) -> u32 {
+        match self {
+            Gesture::Wave | Gesture::Nod => 800,
+            Gesture::Fidget | Gesture::Point | Gesture::Sparkle => 1_200,
+            Gesture::Stretch | Gesture::Yawn => 1_500,
+            Gesture::Dance => 2_500,
+        }
+    }
+}
+
+/// A single instruction for the figurine UI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AnimationCommand {
+    /// Move to a new activity state
+    Transition { to: ActivityState, duration_ms: u32 },
+
+    /// Blend to a new mood
+    SetMood { mood: MoodState, duration_ms: u32 },
+
+    /// Change melt by a single level
+    SetMelt { level: MeltLevel, duration_ms: u32 },
+
+    /// Play a one-shot gesture
+    Gesture { gesture: Gesture, duration_ms: u32 },
+
+    /// Show a speech bubble
+    Speak { text: String, duration_ms: u32 },
+
+    /// Put on an unlocked hat, color, or melt style
+    Equip { kind: UnlockKind, item_id: String, visual: serde_json::Value, duration_ms: u32 },
+}
+
+impl AnimationCommand {
+    /// How long this command takes to play
+    pub fn duration_ms(&self) -> u32 {
+        match self {
+            AnimationCommand::Transition { duration_ms, .. }
+            | AnimationCommand::SetMood { duration_ms, .. }
+            | AnimationCommand::SetMelt { duration_ms, .. }
+            | AnimationCommand::Gesture { duration_ms, .. }
+            | AnimationCommand::Speak { duration_ms, .. }
+            | AnimationCommand::Equip { duration_ms, .. } => *duration_ms,
+        }
+    }
+
+    /// Short name used as the bus `animation_type`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AnimationCommand::Transition { .. } => "transition",
+            AnimationCommand::SetMood { .. } => "set_mood",
+            AnimationCommand::SetMelt { .. } => "set_melt",
+            AnimationCommand::Gesture { .. } => "gesture",
+            AnimationCommand::Speak { .. } => "speak",
+            AnimationCommand::Equip { .. } => "equip",
+        }
+    }
+}
+
+/// Priority of a sequence relative to whatever is currently playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SequencePriority {
+    Ambient = 0,
+    Normal = 1,
+    Important = 2,
+}
+
+/// An ordered list of commands the UI should play back-to-back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationSequence {
+    pub sequence_id: Uuid,
+    pub steps: Vec<AnimationCommand>,
+    pub priority: SequencePriority,
+    /// Whether a later sequence may cut this one short
+    pub interruptible: bool,
+}
+
+impl AnimationSequence {
+    /// Create a new sequence from a list of steps
+    pub fn new(steps: Vec<AnimationCommand>, priority: SequencePriority) -> Self {
+        Self {
+            sequence_id: Uuid::new_v4(),
+            steps,
+            priority,
+            interruptible: priority != SequencePriority::Important,
+        }
+    }
+
+    /// Total playback time for all steps
+    pub fn total_duration_ms(&self) -> u32 {
+        self.steps.iter().map(|step| step.duration_ms()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Convert into the event bus wire format
+    pub fn to_bus_command(&self) -> skelly_jelly_event_bus::message::AnimationCommand {
+        skelly_jelly_event_bus::message::AnimationCommand {
+            command_id: self.sequence_id,
+            animation_type: "sequence".to_string(),
+            parameters: serde_json::json!({
+                "priority": self.priority,
+                "interruptible": self.interruptible,
+                "steps": self.steps,
+            }),
+            duration_ms: self.total_duration_ms(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_melt_steps() {
+        assert_eq!(MeltLevel::Solid.step_toward(MeltLevel::Puddle), Some(MeltLevel::Softening));
+        assert_eq!(MeltLevel::Puddle.step_toward(MeltLevel::Solid), Some(MeltLevel::Drippy));
+        assert_eq!(MeltLevel::Drippy.step_toward(MeltLevel::Drippy), None);
+        assert_eq!(MeltLevel::Solid.distance(MeltLevel::Puddle), 3);
+    }
+
+    #[test]
+    fn test_melt_from_ratio() {
+        assert_eq!(MeltLevel::from_ratio(0.0), MeltLevel::Solid);
+        assert_eq!(MeltLevel::from_ratio(0.3), MeltLevel::Softening);
+        assert_eq!(MeltLevel::from_ratio(0.6), MeltLevel::Drippy);
+        assert_eq!(MeltLevel::from_ratio(0.95), MeltLevel::Puddle);
+    }
+
+    #[test]
+    fn test_gesture_from_cue() {
+        assert_eq!(Gesture::from_cue("celebration"), Some(Gesture::Dance));
+        assert_eq!(Gesture::from_cue("Sleepy"), Some(Gesture::Yawn));
+        assert_eq!(Gesture::from_cue("unknown"), None);
+    }
+
+    #[test]
+    fn test_sequence_bus_command() {
+        let sequence = AnimationSequence::new(
+            vec![
+                AnimationCommand::SetMood { mood: MoodState::Happy, duration_ms: 500 },
+                AnimationCommand::Gesture { gesture: Gesture::Wave, duration_ms: 800 },
+            ],
+            SequencePriority::Normal,
+        );
+
+        let bus_command = sequence.to_bus_command();
+        assert_eq!(bus_command.command_id, sequence.sequence_id);
+        assert_eq!(bus_command.animation_type, "sequence");
+        assert_eq!(bus_command.duration_ms, 1_300);
+        assert_eq!(bus_command.parameters["steps"][0]["command"], "set_mood");
+        assert_eq!(bus_command.parameters["steps"][1]["gesture"], "Wave");
+    }
+}
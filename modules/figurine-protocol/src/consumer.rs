@@ -0,0 +1,200 @@
+//! Event bus consumer feeding the figurine UI
+
+use std::sync::Arc;
+use futures::StreamExt;
+use tokio::{sync::Mutex, task::JoinHandle};
+use tracing::{debug, warn};
+use skelly_jelly_event_bus::{
+    BusMessage, EventBusImpl, EventBusTrait, MessagePayload, MessagePriority, MessageType, ModuleId,
+    subscription::{DeliveryMode, MessageFilter, SubscriptionId},
+};
+use crate::command::{AnimationSequence, SequencePriority};
+use crate::error::FigurineProtocolResult;
+use crate::state_machine::{FigurineSnapshot, FigurineStateMachine};
+use crate::translator::AnimationTranslator;
+
+/// Counters describing what the consumer has done
+#[derive(Debug, Clone, Default)]
+pub struct FigurineConsumerStats {
+    pub messages_handled: u64,
+    pub messages_ignored: u64,
+    pub sequences_published: u64,
+    pub commands_published: u64,
+    pub sequences_rejected: u64,
+}
+
+/// Subscribes to state, intervention, reward, and unlock events and publishes animation sequences
+pub struct FigurineConsumer {
+    bus: Arc<dyn EventBusTrait>,
+    translator: AnimationTranslator,
+    machine: Mutex<FigurineStateMachine>,
+    subscription: Mutex<Option<(SubscriptionId, JoinHandle<()>)>>,
+    stats: Mutex<FigurineConsumerStats>,
+}
+
+impl FigurineConsumer {
+    /// Create a consumer publishing to the given bus
+    pub fn new(bus: Arc<dyn EventBusTrait>, translator: AnimationTranslator) -> Self {
+        Self {
+            bus,
+            translator,
+            machine: Mutex::new(FigurineStateMachine::new()),
+            subscription: Mutex::new(None),
+            stats: Mutex::new(FigurineConsumerStats::default()),
+        }
+    }
+
+    /// Message types the figurine reacts to
+    pub fn subscribed_types() -> Vec<MessageType> {
+        vec![
+            MessageType::StateChange,
+            MessageType::InterventionResponse,
+            MessageType::RewardEvent,
+            MessageType::RewardGranted,
+        ]
+    }
+
+    /// Subscribe to the bus on behalf of the figurine and handle messages as they arrive
+    pub async fn start(self: &Arc<Self>, bus: &EventBusImpl) -> FigurineProtocolResult<SubscriptionId> {
+        let mut subscription = self.subscription.lock().await;
+        if let Some((id, _)) = &*subscription {
+            return Ok(*id);
+        }
+
+        let (id, mut messages) = bus.subscribe_stream(
+            ModuleId::CuteFigurine,
+            MessageFilter::types(Self::subscribed_types()),
+            DeliveryMode::BestEffort,
+        )?;
+        let consumer = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            while let Some(message) = messages.next().await {
+                if let Err(e) = consumer.handle_message(&message).await {
+                    warn!("Figurine consumer failed on {:?}: {}", message.message_type(), e);
+                }
+            }
+            debug!("Figurine subscription ended");
+        });
+        *subscription = Some((id, task));
+
+        debug!("Figurine consumer subscribed with {}", id);
+        Ok(id)
+    }
+
+    /// Stop handling messages and drop the bus subscription
+    pub async fn stop(&self) -> FigurineProtocolResult<()> {
+        if let Some((id, task)) = self.subscription.lock().await.take() {
+            task.abort();
+            self.bus.unsubscribe(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Translate one bus message, advance the state machine, and publish the result
+    pub async fn handle_message(&self, message: &BusMessage) -> FigurineProtocolResult<Option<AnimationSequence>> {
+        let mut machine = self.machine.lock().await;
+
+        let Some(sequence) = self.translator.translate(message, &machine) else {
+            self.stats.lock().await.messages_ignored += 1;
+            return Ok(None);
+        };
+
+        // The translator plans legal steps, but re-check against the live state before it goes out
+        if let Err(e) = machine.apply_all(&sequence.steps) {
+            warn!("Rejected animation sequence {}: {}", sequence.sequence_id, e);
+            self.stats.lock().await.sequences_rejected += 1;
+            return Err(e);
+        }
+
+        let priority = match sequence.priority {
+            SequencePriority::Ambient => MessagePriority::Low,
+            SequencePriority::Normal => MessagePriority::Normal,
+            SequencePriority::Important => MessagePriority::High,
+        };
+        let mut outgoing = message.reply_to(
+            ModuleId::CuteFigurine,
+            MessagePayload::AnimationCommand(sequence.to_bus_command()),
+        );
+        outgoing.priority = priority;
+        self.bus.publish(outgoing).await?;
+
+        let mut stats = self.stats.lock().await;
+        stats.messages_handled += 1;
+        stats.sequences_published += 1;
+        stats.commands_published += sequence.steps.len() as u64;
+
+        Ok(Some(sequence))
+    }
+
+    /// Current figurine state as last published
+    pub async fn snapshot(&self) -> FigurineSnapshot {
+        self.machine.lock().await.snapshot()
+    }
+
+    pub async fn stats(&self) -> FigurineConsumerStats {
+        self.stats.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::ActivityState;
+    use chrono::Utc;
+    use skelly_jelly_event_bus::create_event_bus;
+    use std::time::Duration;
+    use skelly_jelly_event_bus::message::StateClassification;
+
+    #[tokio::test]
+    async fn test_state_change_publishes_sequence() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let consumer = Arc::new(FigurineConsumer::new(bus.clone(), AnimationTranslator::default()));
+        consumer.start(&bus).await.unwrap();
+
+        let message = BusMessage::new(
+            ModuleId::AnalysisEngine,
+            MessagePayload::StateChange(StateClassification {
+                state: "focused".to_string(),
+                confidence: 0.9,
+                timestamp: Utc::now(),
+                transition_from: None,
+            }),
+        );
+        bus.publish(message).await.unwrap();
+
+        // The subscription task picks the message up and drives the state machine
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while consumer.stats().await.sequences_published == 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("Published state change should reach the figurine");
+        assert_eq!(consumer.snapshot().await.activity, ActivityState::Working);
+
+        let stats = consumer.stats().await;
+        assert_eq!(stats.sequences_published, 1);
+        assert!(stats.commands_published > 0);
+
+        consumer.stop().await.unwrap();
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unrelated_message_ignored() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let consumer = FigurineConsumer::new(bus.clone(), AnimationTranslator::default());
+
+        let message = BusMessage::new(
+            ModuleId::Orchestrator,
+            MessagePayload::ModuleReady(ModuleId::Storage),
+        );
+
+        assert!(consumer.handle_message(&message).await.unwrap().is_none());
+        assert_eq!(consumer.stats().await.messages_ignored, 1);
+
+        bus.shutdown().await.unwrap();
+    }
+}
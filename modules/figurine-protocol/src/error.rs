@@ -0,0 +1,32 @@
+//! Error types for the figurine protocol
+
+use thiserror::Error;
+use crate::command::{ActivityState, AnimationCommand, MeltLevel};
+
+/// Result type for figurine protocol operations
+pub type FigurineProtocolResult<T> = Result<T, FigurineProtocolError>;
+
+/// Errors raised while validating or publishing animation commands
+#[derive(Error, Debug, Clone)]
+pub enum FigurineProtocolError {
+    #[error("Illegal activity transition from {from:?} to {to:?}")]
+    IllegalTransition { from: ActivityState, to: ActivityState },
+
+    #[error("Melt level can only change one step at a time ({from:?} -> {to:?})")]
+    MeltJump { from: MeltLevel, to: MeltLevel },
+
+    #[error("Command {command:?} is not allowed while {activity:?}")]
+    CommandNotAllowed {
+        command: AnimationCommand,
+        activity: ActivityState,
+    },
+
+    #[error("Invalid animation command: {0}")]
+    InvalidCommand(String),
+
+    #[error("Event bus error: {0}")]
+    EventBus(#[from] skelly_jelly_event_bus::EventBusError),
+
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+}
@@ -0,0 +1,22 @@
+//! # Skelly-Jelly Figurine Protocol
+//!
+//! Crate-side animation subsystem for the cute figurine.
+//! Defines the animation command vocabulary, a validated state machine that
+//! rejects illegal transitions, and a bus consumer that turns ADHD state changes
+//! and interventions into animation sequences for the UI process.
+
+pub mod error;
+pub mod command;
+pub mod state_machine;
+pub mod translator;
+pub mod consumer;
+
+// Re-export public API
+pub use error::{FigurineProtocolError, FigurineProtocolResult};
+pub use command::{
+    ActivityState, AnimationCommand, AnimationSequence, Gesture, MeltLevel, MoodState,
+    SequencePriority,
+};
+pub use state_machine::{FigurineSnapshot, FigurineStateMachine};
+pub use translator::{AnimationTranslator, TranslatorConfig};
+pub use consumer::{FigurineConsumer, FigurineConsumerStats};
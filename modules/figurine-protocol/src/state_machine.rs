@@ -0,0 +1,242 @@
+//! Validated figurine state machine
+//!
+//! Every command sent to the UI is checked here first so the figurine can
+//! never be asked to, say, dance in its sleep or go from solid to puddle in one frame.
+
+use serde::{Deserialize, Serialize};
+use crate::command::{ActivityState, AnimationCommand, Gesture, MeltLevel, MoodState};
+use crate::error::{FigurineProtocolError, FigurineProtocolResult};
+
+/// Point-in-time view of the figurine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FigurineSnapshot {
+    pub activity: ActivityState,
+    pub mood: MoodState,
+    pub melt: MeltLevel,
+}
+
+impl Default for FigurineSnapshot {
+    fn default() -> Self {
+        Self {
+            activity: ActivityState::Idle,
+            mood: MoodState::Happy,
+            melt: MeltLevel::Solid,
+        }
+    }
+}
+
+/// State machine tracking what the figurine is doing
+#[derive(Debug, Clone, Default)]
+pub struct FigurineStateMachine {
+    state: FigurineSnapshot,
+}
+
+impl FigurineStateMachine {
+    /// Create a state machine starting idle, happy, and solid
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a state machine from a known snapshot
+    pub fn from_snapshot(state: FigurineSnapshot) -> Self {
+        Self { state }
+    }
+
+    /// Current figurine state
+    pub fn snapshot(&self) -> FigurineSnapshot {
+        self.state
+    }
+
+    /// Activities reachable in one step from `from`
+    pub fn allowed_transitions(from: ActivityState) -> &'static [ActivityState] {
+        use ActivityState::*;
+        match from {
+            Idle => &[Working, Interacting, Resting, Intervening],
+            Working => &[Idle, Intervening, Resting],
+            Interacting => &[Idle, Working],
+            Resting => &[Idle],
+            Intervening => &[Idle, Working, Resting],
+        }
+    }
+
+    /// Whether moving between two activities is legal (staying put always is)
+    pub fn can_transition(from: ActivityState, to: ActivityState) -> bool {
+        from == to || Self::allowed_transitions(from).contains(&to)
+    }
+
+    /// Shortest legal path of activities from `from` to `to`, excluding `from`
+    pub fn transition_path(from: ActivityState, to: ActivityState) -> Vec<ActivityState> {
+        if from == to {
+            return Vec::new();
+        }
+        if Self::can_transition(from, to) {
+            return vec![to];
+        }
+        // Every activity can reach Idle and Idle can reach everything, so two hops suffice
+        if Self::can_transition(from, ActivityState::Idle) {
+            return vec![ActivityState::Idle, to];
+        }
+        Vec::new()
+    }
+
+    /// Check a command against the current state without applying it
+    pub fn validate(&self, command: &AnimationCommand) -> FigurineProtocolResult<()> {
+        let activity = self.state.activity;
+        let not_allowed = || FigurineProtocolError::CommandNotAllowed {
+            command: command.clone(),
+            activity,
+        };
+
+        match command {
+            AnimationCommand::Transition { to, .. } => {
+                if !Self::can_transition(activity, *to) {
+                    return Err(FigurineProtocolError::IllegalTransition { from: activity, to: *to });
+                }
+            }
+            AnimationCommand::SetMood { mood, .. } => {
+                if activity == ActivityState::Resting
+                    && matches!(mood, MoodState::Celebrating | MoodState::Excited)
+                {
+                    return Err(not_allowed());
+                }
+            }
+            AnimationCommand::SetMelt { level, .. } => {
+                if self.state.melt.distance(*level) > 1 {
+                    return Err(FigurineProtocolError::MeltJump { from: self.state.melt, to: *level });
+                }
+            }
+            AnimationCommand::Gesture { gesture, .. } => {
+                // A resting figurine can only stir, not perform
+                if activity == ActivityState::Resting
+                    && !matches!(gesture, Gesture::Stretch | Gesture::Yawn)
+                {
+                    return Err(not_allowed());
+                }
+                if *gesture == Gesture::Dance && self.state.mood != MoodState::Celebrating {
+                    return Err(not_allowed());
+                }
+            }
+            AnimationCommand::Speak { text, .. } => {
+                if !matches!(activity, ActivityState::Intervening | ActivityState::Interacting) {
+                    return Err(not_allowed());
+                }
+                if text.trim().is_empty() {
+                    return Err(FigurineProtocolError::InvalidCommand("empty speech bubble".to_string()));
+                }
+            }
+            AnimationCommand::Equip { item_id, .. } => {
+                if item_id.trim().is_empty() {
+                    return Err(FigurineProtocolError::InvalidCommand("equip without an item".to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate and apply a command
+    pub fn apply(&mut self, command: &AnimationCommand) -> FigurineProtocolResult<FigurineSnapshot> {
+        self.validate(command)?;
+
+        match command {
+            AnimationCommand::Transition { to, .. } => self.state.activity = *to,
+            AnimationCommand::SetMood { mood, .. } => self.state.mood = *mood,
+            AnimationCommand::SetMelt { level, .. } => self.state.melt = *level,
+            AnimationCommand::Gesture { .. }
+            | AnimationCommand::Speak { .. }
+            | AnimationCommand::Equip { .. } => {}
+        }
+
+        Ok(self.state)
+    }
+
+    /// Apply a whole sequence atomically: either every step applies or none do
+    pub fn apply_all(&mut self, commands: &[AnimationCommand]) -> FigurineProtocolResult<FigurineSnapshot> {
+        let mut scratch = self.clone();
+        for command in commands {
+            scratch.apply(command)?;
+        }
+        *self = scratch;
+        Ok(self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(to: ActivityState) -> AnimationCommand {
+        AnimationCommand::Transition { to, duration_ms: 300 }
+    }
+
+    #[test]
+    fn test_legal_transition() {
+        let mut machine = FigurineStateMachine::new();
+        let state = machine.apply(&transition(ActivityState::Working)).unwrap();
+        assert_eq!(state.activity, ActivityState::Working);
+    }
+
+    #[test]
+    fn test_illegal_transition_rejected() {
+        let mut machine = FigurineStateMachine::new();
+        machine.apply(&transition(ActivityState::Resting)).unwrap();
+
+        let result = machine.apply(&transition(ActivityState::Working));
+        assert!(matches!(
+            result,
+            Err(FigurineProtocolError::IllegalTransition { from: ActivityState::Resting, to: ActivityState::Working })
+        ));
+        assert_eq!(machine.snapshot().activity, ActivityState::Resting);
+    }
+
+    #[test]
+    fn test_transition_path_through_idle() {
+        let path = FigurineStateMachine::transition_path(ActivityState::Resting, ActivityState::Working);
+        assert_eq!(path, vec![ActivityState::Idle, ActivityState::Working]);
+        assert!(FigurineStateMachine::transition_path(ActivityState::Idle, ActivityState::Idle).is_empty());
+    }
+
+    #[test]
+    fn test_melt_cannot_jump() {
+        let mut machine = FigurineStateMachine::new();
+        let result = machine.apply(&AnimationCommand::SetMelt { level: MeltLevel::Puddle, duration_ms: 500 });
+        assert!(matches!(result, Err(FigurineProtocolError::MeltJump { .. })));
+
+        machine.apply(&AnimationCommand::SetMelt { level: MeltLevel::Softening, duration_ms: 500 }).unwrap();
+        assert_eq!(machine.snapshot().melt, MeltLevel::Softening);
+    }
+
+    #[test]
+    fn test_speak_requires_intervening() {
+        let mut machine = FigurineStateMachine::new();
+        let speak = AnimationCommand::Speak { text: "hi".to_string(), duration_ms: 1_000 };
+        assert!(machine.apply(&speak).is_err());
+
+        machine.apply(&transition(ActivityState::Intervening)).unwrap();
+        assert!(machine.apply(&speak).is_ok());
+    }
+
+    #[test]
+    fn test_no_dancing_while_resting() {
+        let mut machine = FigurineStateMachine::new();
+        machine.apply(&AnimationCommand::SetMood { mood: MoodState::Celebrating, duration_ms: 300 }).unwrap();
+        machine.apply(&transition(ActivityState::Resting)).unwrap();
+
+        let dance = AnimationCommand::Gesture { gesture: Gesture::Dance, duration_ms: 2_500 };
+        assert!(machine.apply(&dance).is_err());
+        let yawn = AnimationCommand::Gesture { gesture: Gesture::Yawn, duration_ms: 1_500 };
+        assert!(machine.apply(&yawn).is_ok());
+    }
+
+    #[test]
+    fn test_apply_all_is_atomic() {
+        let mut machine = FigurineStateMachine::new();
+        let result = machine.apply_all(&[
+            transition(ActivityState::Working),
+            AnimationCommand::SetMelt { level: MeltLevel::Drippy, duration_ms: 500 },
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(machine.snapshot(), FigurineSnapshot::default());
+    }
+}
@@ -0,0 +1,389 @@
+//! Translation of bus events into animation sequences
+//!
+//! Sequences are planned against a scratch copy of the state machine so every
+//! step the UI receives is already known to be legal.
+
+use tracing::debug;
+use skelly_jelly_event_bus::message::{
+    BusMessage, InterventionResponse, MessagePayload, RewardEvent, RewardGranted, StateClassification,
+    UnlockKind,
+};
+use crate::command::{
+    ActivityState, AnimationCommand, AnimationSequence, Gesture, MeltLevel, MoodState,
+    SequencePriority,
+};
+use crate::state_machine::FigurineStateMachine;
+
+/// Timing knobs for generated sequences
+#[derive(Debug, Clone)]
+pub struct TranslatorConfig {
+    pub transition_ms: u32,
+    pub mood_blend_ms: u32,
+    pub melt_step_ms: u32,
+    /// Base speech bubble time before per-character reading time is added
+    pub speech_base_ms: u32,
+    pub speech_per_char_ms: u32,
+    pub speech_max_ms: u32,
+    /// Reward points at or above which the celebration gets extra sparkle
+    pub big_reward_points: u32,
+    /// Time to swap in a hat, color, or melt style
+    pub equip_ms: u32,
+}
+
+impl Default for TranslatorConfig {
+    fn default() -> Self {
+        Self {
+            transition_ms: 400,
+            mood_blend_ms: 600,
+            melt_step_ms: 700,
+            speech_base_ms: 1_500,
+            speech_per_char_ms: 50,
+            speech_max_ms: 6_000,
+            big_reward_points: 50,
+            equip_ms: 800,
+        }
+    }
+}
+
+/// Translates ADHD states, interventions, and rewards into animation sequences
+#[derive(Debug, Clone, Default)]
+pub struct AnimationTranslator {
+    config: TranslatorConfig,
+}
+
+impl AnimationTranslator {
+    pub fn new(config: TranslatorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Translate any supported bus message; returns `None` for messages the figurine ignores
+    pub fn translate(&self, message: &BusMessage, current: &FigurineStateMachine) -> Option<AnimationSequence> {
+        match &message.payload {
+            MessagePayload::StateChange(state) => self.translate_state_change(state, current),
+            MessagePayload::InterventionResponse(response) => self.translate_intervention(response, current),
+            MessagePayload::RewardEvent(reward) => self.translate_reward(reward, current),
+            MessagePayload::RewardGranted(granted) => self.translate_reward_granted(granted, current),
+            _ => None,
+        }
+    }
+
+    /// Map an analysis-engine state classification onto posture, mood, and melt
+    pub fn translate_state_change(
+        &self,
+        state: &StateClassification,
+        current: &FigurineStateMachine,
+    ) -> Option<AnimationSequence> {
+        let mut plan = Planner::new(current, &self.config);
+
+        match state.state.to_lowercase().as_str() {
+            "flow" | "focused" | "focus" | "hyperfocus" => {
+                plan.go_to(ActivityState::Working);
+                plan.mood(MoodState::Focused);
+                plan.melt_to(MeltLevel::Solid);
+            }
+            "distracted" => {
+                plan.mood(MoodState::Thinking);
+                plan.melt_to(MeltLevel::from_ratio(state.confidence).min(MeltLevel::Drippy));
+                plan.gesture(Gesture::Fidget);
+            }
+            "tired" | "fatigued" => {
+                plan.mood(MoodState::Tired);
+                plan.melt_to(MeltLevel::Softening);
+                plan.go_to(ActivityState::Resting);
+                plan.gesture(Gesture::Yawn);
+            }
+            "transitioning" => {
+                plan.mood(MoodState::Thinking);
+            }
+            "neutral" | "idle" => {
+                plan.go_to(ActivityState::Idle);
+                plan.mood(MoodState::Happy);
+                plan.melt_to(MeltLevel::Solid);
+            }
+            other => {
+                debug!("No animation mapping for state '{}'", other);
+                return None;
+            }
+        }
+
+        plan.finish(SequencePriority::Ambient)
+    }
+
+    /// Show an AI intervention: step in, say the line, then return to what we were doing
+    pub fn translate_intervention(
+        &self,
+        response: &InterventionResponse,
+        current: &FigurineStateMachine,
+    ) -> Option<AnimationSequence> {
+        let mut plan = Planner::new(current, &self.config);
+        let previous = current.snapshot();
+
+        plan.go_to(ActivityState::Intervening);
+
+        let gestures: Vec<Gesture> = response.animation_cues
+            .iter()
+            .filter_map(|cue| Gesture::from_cue(cue))
+            .collect();
+        if gestures.contains(&Gesture::Dance) {
+            plan.mood(MoodState::Celebrating);
+        } else if gestures.contains(&Gesture::Sparkle) {
+            plan.mood(MoodState::Excited);
+        } else {
+            plan.mood(MoodState::Happy);
+        }
+        for gesture in gestures {
+            plan.gesture(gesture);
+        }
+
+        plan.speak(&response.response_text);
+
+        plan.mood(previous.mood);
+        plan.go_to(previous.activity);
+
+        plan.finish(SequencePriority::Important)
+    }
+
+    /// Celebrate a gamification reward
+    pub fn translate_reward(
+        &self,
+        reward: &RewardEvent,
+        current: &FigurineStateMachine,
+    ) -> Option<AnimationSequence> {
+        let mut plan = Planner::new(current, &self.config);
+        let previous = current.snapshot();
+
+        if previous.activity == ActivityState::Resting {
+            plan.go_to(ActivityState::Idle);
+        }
+        plan.mood(MoodState::Celebrating);
+        plan.melt_to(MeltLevel::Solid);
+        plan.gesture(Gesture::Dance);
+        if reward.points >= self.config.big_reward_points {
+            plan.gesture(Gesture::Sparkle);
+        }
+        plan.mood(MoodState::Happy);
+
+        plan.finish(SequencePriority::Normal)
+    }
+
+    /// Show off a newly unlocked customization; plain coin grants are already celebrated by their `RewardEvent`
+    pub fn translate_reward_granted(
+        &self,
+        granted: &RewardGranted,
+        current: &FigurineStateMachine,
+    ) -> Option<AnimationSequence> {
+        let unlock = granted.unlock.as_ref()?;
+        let mut plan = Planner::new(current, &self.config);
+
+        if current.snapshot().activity == ActivityState::Resting {
+            plan.go_to(ActivityState::Idle);
+        }
+        plan.mood(MoodState::Excited);
+        plan.melt_to(MeltLevel::Solid);
+        plan.equip(unlock.kind, &unlock.item_id, unlock.visual.clone());
+        plan.gesture(Gesture::Sparkle);
+        plan.mood(MoodState::Happy);
+
+        plan.finish(SequencePriority::Normal)
+    }
+}
+
+/// Builds a sequence step by step, keeping only steps that are legal and non-redundant
+struct Planner<'a> {
+    machine: FigurineStateMachine,
+    config: &'a TranslatorConfig,
+    steps: Vec<AnimationCommand>,
+}
+
+impl<'a> Planner<'a> {
+    fn new(current: &FigurineStateMachine, config: &'a TranslatorConfig) -> Self {
+        Self {
+            machine: current.clone(),
+            config,
+            steps: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, command: AnimationCommand) {
+        match self.machine.apply(&command) {
+            Ok(_) => self.steps.push(command),
+            Err(e) => debug!("Skipping animation step: {}", e),
+        }
+    }
+
+    fn go_to(&mut self, target: ActivityState) {
+        let from = self.machine.snapshot().activity;
+        for to in FigurineStateMachine::transition_path(from, target) {
+            self.push(AnimationCommand::Transition { to, duration_ms: self.config.transition_ms });
+        }
+    }
+
+    fn mood(&mut self, mood: MoodState) {
+        if self.machine.snapshot().mood != mood {
+            self.push(AnimationCommand::SetMood { mood, duration_ms: self.config.mood_blend_ms });
+        }
+    }
+
+    fn melt_to(&mut self, target: MeltLevel) {
+        while let Some(level) = self.machine.snapshot().melt.step_toward(target) {
+            self.push(AnimationCommand::SetMelt { level, duration_ms: self.config.melt_step_ms });
+        }
+    }
+
+    fn gesture(&mut self, gesture: Gesture) {
+        self.push(AnimationCommand::Gesture { gesture, duration_ms: gesture.default_duration_ms() });
+    }
+
+    fn equip(&mut self, kind: UnlockKind, item_id: &str, visual: serde_json::Value) {
+        self.push(AnimationCommand::Equip {
+            kind,
+            item_id: item_id.to_string(),
+            visual,
+            duration_ms: self.config.equip_ms,
+        });
+    }
+
+    fn speak(&mut self, text: &str) {
+        let reading_ms = self.config.speech_per_char_ms.saturating_mul(text.chars().count() as u32);
+        let duration_ms = (self.config.speech_base_ms + reading_ms).min(self.config.speech_max_ms);
+        self.push(AnimationCommand::Speak { text: text.to_string(), duration_ms });
+    }
+
+    fn finish(self, priority: SequencePriority) -> Option<AnimationSequence> {
+        if self.steps.is_empty() {
+            None
+        } else {
+            Some(AnimationSequence::new(self.steps, priority))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_machine::FigurineSnapshot;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn state_change(state: &str, confidence: f64) -> StateClassification {
+        StateClassification {
+            state: state.to_string(),
+            confidence,
+            timestamp: Utc::now(),
+            transition_from: None,
+        }
+    }
+
+    /// Every generated sequence must replay cleanly on the real state machine
+    fn assert_replays(sequence: &AnimationSequence, machine: &FigurineStateMachine) -> FigurineSnapshot {
+        let mut replay = machine.clone();
+        replay.apply_all(&sequence.steps).expect("generated sequence must be legal")
+    }
+
+    #[test]
+    fn test_focused_state_sets_working() {
+        let translator = AnimationTranslator::default();
+        let machine = FigurineStateMachine::new();
+
+        let sequence = translator.translate_state_change(&state_change("flow", 0.9), &machine).unwrap();
+        let end = assert_replays(&sequence, &machine);
+        assert_eq!(end.activity, ActivityState::Working);
+        assert_eq!(end.mood, MoodState::Focused);
+        assert_eq!(sequence.priority, SequencePriority::Ambient);
+    }
+
+    #[test]
+    fn test_distracted_melts_gradually() {
+        let translator = AnimationTranslator::default();
+        let machine = FigurineStateMachine::new();
+
+        let sequence = translator.translate_state_change(&state_change("distracted", 0.95), &machine).unwrap();
+        let melt_steps: Vec<_> = sequence.steps.iter()
+            .filter_map(|step| match step {
+                AnimationCommand::SetMelt { level, .. } => Some(*level),
+                _ => None,
+            })
+            .collect();
+
+        // Capped at drippy, reached one level at a time
+        assert_eq!(melt_steps, vec![MeltLevel::Softening, MeltLevel::Drippy]);
+        assert_eq!(assert_replays(&sequence, &machine).melt, MeltLevel::Drippy);
+    }
+
+    #[test]
+    fn test_unknown_state_ignored() {
+        let translator = AnimationTranslator::default();
+        let machine = FigurineStateMachine::new();
+        assert!(translator.translate_state_change(&state_change("confused", 0.5), &machine).is_none());
+    }
+
+    #[test]
+    fn test_intervention_returns_to_previous_activity() {
+        let translator = AnimationTranslator::default();
+        let mut machine = FigurineStateMachine::new();
+        machine.apply(&AnimationCommand::Transition { to: ActivityState::Resting, duration_ms: 0 }).unwrap();
+
+        let response = InterventionResponse {
+            request_id: Uuid::new_v4(),
+            response_text: "Time for a quick stretch?".to_string(),
+            animation_cues: vec!["celebration".to_string(), "wave".to_string()],
+            metadata: Default::default(),
+        };
+
+        let sequence = translator.translate_intervention(&response, &machine).unwrap();
+        assert!(sequence.steps.iter().any(|step| matches!(step, AnimationCommand::Speak { .. })));
+        assert!(sequence.steps.iter().any(|step| matches!(step, AnimationCommand::Gesture { gesture: Gesture::Dance, .. })));
+        assert!(!sequence.interruptible);
+
+        let end = assert_replays(&sequence, &machine);
+        assert_eq!(end.activity, ActivityState::Resting);
+        assert_eq!(end.mood, MoodState::Happy);
+    }
+
+    #[test]
+    fn test_reward_wakes_resting_figurine() {
+        let translator = AnimationTranslator::default();
+        let mut machine = FigurineStateMachine::new();
+        machine.apply(&AnimationCommand::Transition { to: ActivityState::Resting, duration_ms: 0 }).unwrap();
+
+        let reward = RewardEvent {
+            reward_id: Uuid::new_v4(),
+            reward_type: "streak".to_string(),
+            points: 100,
+            description: "Five focus sessions".to_string(),
+        };
+
+        let sequence = translator.translate_reward(&reward, &machine).unwrap();
+        assert_eq!(sequence.steps[0], AnimationCommand::Transition { to: ActivityState::Idle, duration_ms: 400 });
+        assert!(sequence.steps.iter().any(|step| matches!(step, AnimationCommand::Gesture { gesture: Gesture::Sparkle, .. })));
+        assert_eq!(assert_replays(&sequence, &machine).activity, ActivityState::Idle);
+    }
+
+    #[test]
+    fn test_unlock_equips_item() {
+        let translator = AnimationTranslator::default();
+        let machine = FigurineStateMachine::new();
+        let mut granted = RewardGranted {
+            reward_id: Uuid::new_v4(),
+            coins: 20,
+            balance: 120,
+            reason: "focus".to_string(),
+            unlock: None,
+            timestamp: Utc::now(),
+        };
+        assert!(translator.translate_reward_granted(&granted, &machine).is_none());
+
+        granted.unlock = Some(skelly_jelly_event_bus::message::SkeletonUnlock {
+            item_id: "wizard_hat".to_string(),
+            name: "Wizard Hat".to_string(),
+            kind: UnlockKind::Hat,
+            visual: serde_json::json!({ "asset": "hats/wizard.svg" }),
+        });
+        let sequence = translator.translate_reward_granted(&granted, &machine).unwrap();
+        assert!(sequence.steps.iter().any(|step| matches!(
+            step,
+            AnimationCommand::Equip { kind: UnlockKind::Hat, item_id, .. } if item_id == "wizard_hat"
+        )));
+        assert_eq!(assert_replays(&sequence, &machine).mood, MoodState::Happy);
+    }
+}
@@ -20,14 +20,20 @@ import {
   SessionMetrics,
   WorkContext,
   GamificationError,
-  ErrorContext
+  ErrorContext,
+  StreakUpdate,
+  ShopView,
+  UnlockResult
 } from './types/index.js';
 
 import { InterventionController } from './controllers/InterventionController.js';
 import { RewardSystem } from './systems/RewardSystem.js';
+import { RewardEconomy, DEFAULT_CATALOG } from './systems/RewardEconomy.js';
 import { ProgressTracker } from './trackers/ProgressTracker.js';
+import { StreakTracker } from './trackers/StreakTracker.js';
 import { CompanionBehaviorManager } from './managers/CompanionBehaviorManager.js';
 import { MotivationEngine } from './engines/MotivationEngine.js';
+import { GamificationStorage, MemoryStorage } from './storage/GamificationStorage.js';
 
 import { Logger } from 'winston';
 import { v4 as uuidv4 } from 'uuid';
@@ -58,6 +64,10 @@ export interface GamificationModule {
   updatePreferences(preferences: UserPreferences): Promise<void>;
   getMetrics(): Promise<GamificationMetrics>;
   
+  // Customization
+  getShop(): Promise<ShopView>;
+  unlockItem(itemId: string): Promise<UnlockResult>;
+  
   // Lifecycle management
   start(): Promise<void>;
   stop(): Promise<void>;
@@ -67,7 +77,9 @@ export interface GamificationModule {
 export class GamificationModuleImpl implements GamificationModule {
   private interventionController: InterventionController;
   private rewardSystem: RewardSystem;
+  private economy: RewardEconomy;
   private progressTracker: ProgressTracker;
+  private streakTracker: StreakTracker;
   private companionBehavior: CompanionBehaviorManager;
   private motivationEngine: MotivationEngine;
   
@@ -101,7 +113,8 @@ export class GamificationModuleImpl implements GamificationModule {
     eventBus: EventBusInterface,
     logger: Logger,
     config: GamificationConfig,
-    userProfile: UserProfile
+    userProfile: UserProfile,
+    storage: GamificationStorage = new MemoryStorage()
   ) {
     this.eventBus = eventBus;
     this.logger = logger;
@@ -111,7 +124,9 @@ export class GamificationModuleImpl implements GamificationModule {
     // Initialize components
     this.interventionController = new InterventionController(logger, config.performance.maxHistoryEntries);
     this.rewardSystem = new RewardSystem(logger, config);
+    this.economy = new RewardEconomy(logger, config, storage);
     this.progressTracker = new ProgressTracker(logger, config);
+    this.streakTracker = new StreakTracker(logger, config, storage);
     this.companionBehavior = new CompanionBehaviorManager(logger, config, userProfile.preferences);
     this.motivationEngine = new MotivationEngine(logger, config);
   }
@@ -148,6 +163,11 @@ export class GamificationModuleImpl implements GamificationModule {
       );
       this.subscriptionIds.push(interactionSubscription);
 
+      // Restore the wallet and streaks, and settle any days missed while stopped
+      await this.economy.load();
+      await this.streakTracker.load();
+      await this.publishStreakUpdates(await this.streakTracker.rollover());
+
       // Start new session tracking
       this.progressTracker.startNewSession();
       
@@ -218,6 +238,13 @@ export class GamificationModuleImpl implements GamificationModule {
         event.workContext
       );
 
+      // Credit focus time toward the daily streak
+      const isFocused = event.currentState.type === 'Flow' || event.currentState.type === 'Hyperfocus';
+      const focusMinutes = isFocused ? (event.currentState.duration || 0) / (60 * 1000) : 0;
+      await this.publishStreakUpdates(
+        await this.streakTracker.recordFocus(focusMinutes, event.transitionTime)
+      );
+
       // Process rewards for state transition
       const rewardEvents = await this.rewardSystem.processStateChange(
         event.previousState,
@@ -259,9 +286,15 @@ export class GamificationModuleImpl implements GamificationModule {
         await this.executeIntervention(interventionDecision, event, progressUpdate.session);
       }
 
-      // Publish reward events
+      // Publish reward events, banking coins in the economy
       for (const reward of rewardEvents) {
         await this.eventBus.publish('RewardEvent', reward);
+        if (reward.amount) {
+          const earned = await this.economy.earn(reward.amount, reward.type, event.transitionTime);
+          if (earned.event) {
+            await this.eventBus.publish('RewardGranted', earned.event);
+          }
+        }
       }
 
       // Update performance metrics
@@ -316,6 +349,7 @@ export class GamificationModuleImpl implements GamificationModule {
         daily: dailyMetrics || this.createEmptyDailyMetrics(),
         allTime: allTimeMetrics,
         streaks,
+        streak: this.streakTracker.getState(),
         achievements,
         wallet,
         level: levelProgress,
@@ -401,6 +435,26 @@ export class GamificationModuleImpl implements GamificationModule {
     }
   }
 
+  /**
+   * Customization catalog with the current balance
+   */
+  async getShop(): Promise<ShopView> {
+    return this.economy.getShop();
+  }
+
+  /**
+   * Spend coins on a hat, color, or melt style; the figurine picks it up from RewardGranted
+   */
+  async unlockItem(itemId: string): Promise<UnlockResult> {
+    const result = await this.economy.unlock(itemId);
+    if (result.ok) {
+      await this.eventBus.publish('RewardGranted', result.event);
+    } else {
+      this.logger.info('Unlock refused', { itemId, reason: result.reason });
+    }
+    return result;
+  }
+
   /**
    * Get current module metrics
    */
@@ -532,6 +586,20 @@ export class GamificationModuleImpl implements GamificationModule {
     }
   }
 
+  private async publishStreakUpdates(updates: StreakUpdate[]): Promise<void> {
+    for (const update of updates) {
+      if (update.freezeEarned || update.recovered) {
+        this.logger.info('Streak milestone', {
+          date: update.day.date,
+          current: update.current,
+          freezeEarned: update.freezeEarned,
+          recovered: update.recovered
+        });
+      }
+      await this.eventBus.publish('StreakUpdate', update);
+    }
+  }
+
   private createDefaultWorkContext(): WorkContext {
     return {
       application: 'unknown',
@@ -641,7 +709,8 @@ export function createGamificationModule(
   eventBus: EventBusInterface,
   logger: Logger,
   config?: Partial<GamificationConfig>,
-  userProfile?: Partial<UserProfile>
+  userProfile?: Partial<UserProfile>,
+  storage?: GamificationStorage
 ): GamificationModule {
   // Default configuration
   const defaultConfig: GamificationConfig = {
@@ -677,6 +746,21 @@ export function createGamificationModule(
       metricUpdateInterval: 30,
       historyRetentionDays: 90
     },
+    streaks: {
+      fullCreditMinutes: 60,
+      partialCreditMinutes: 15,
+      partialCreditValue: 0.5,
+      freezeEveryDays: 5,
+      maxFreezes: 2,
+      recoveryDays: 3,
+      recoveryRetainRatio: 0.5
+    },
+    economy: {
+      maxCoinsPerHour: 120,
+      maxCoinsPerDay: 600,
+      maxGrantsPerMinute: 5,
+      catalog: DEFAULT_CATALOG
+    },
     companion: {
       animationDuration: 3000,
       expressionVariety: true,
@@ -763,5 +847,5 @@ export function createGamificationModule(
   const finalConfig = { ...defaultConfig, ...config };
   const finalUserProfile = { ...defaultUserProfile, ...userProfile };
 
-  return new GamificationModuleImpl(eventBus, logger, finalConfig, finalUserProfile);
+  return new GamificationModuleImpl(eventBus, logger, finalConfig, finalUserProfile, storage);
 }
\ No newline at end of file
@@ -20,13 +20,20 @@ export {
 // Core controllers and systems
 export { InterventionController } from './controllers/InterventionController.js';
 export { RewardSystem } from './systems/RewardSystem.js';
+export { RewardEconomy, DEFAULT_CATALOG } from './systems/RewardEconomy.js';
 export { ProgressTracker } from './trackers/ProgressTracker.js';
+export { StreakTracker } from './trackers/StreakTracker.js';
 export { CompanionBehaviorManager } from './managers/CompanionBehaviorManager.js';
 export { MotivationEngine } from './engines/MotivationEngine.js';
 
+// Local persistence
+export { GamificationStorage, MemoryStorage, FileStorage } from './storage/GamificationStorage.js';
+
 // Type definitions
 export * from './types/index.js';
 
+import { DEFAULT_CATALOG } from './systems/RewardEconomy.js';
+
 // Configuration helpers
 export const DEFAULT_CONFIG = {
   intervention: {
@@ -61,6 +68,21 @@ export const DEFAULT_CONFIG = {
     metricUpdateInterval: 30,
     historyRetentionDays: 90
   },
+  streaks: {
+    fullCreditMinutes: 60,
+    partialCreditMinutes: 15,
+    partialCreditValue: 0.5,
+    freezeEveryDays: 5,
+    maxFreezes: 2,
+    recoveryDays: 3,
+    recoveryRetainRatio: 0.5
+  },
+  economy: {
+    maxCoinsPerHour: 120,
+    maxCoinsPerDay: 600,
+    maxGrantsPerMinute: 5,
+    catalog: DEFAULT_CATALOG
+  },
   companion: {
     animationDuration: 3000,
     expressionVariety: true,
@@ -6,7 +6,16 @@
  */
 
 import { MotivationEngine } from './engines/MotivationEngine.js';
-import { ADHDState, Achievement, GamificationConfig } from './types/index.js';
+import { DEFAULT_CATALOG, RewardEconomy } from './systems/RewardEconomy.js';
+import { FileStorage } from './storage/GamificationStorage.js';
+import {
+  ADHDState,
+  Achievement,
+  GamificationConfig,
+  RewardGranted,
+  ShopView,
+  UnlockResult
+} from './types/index.js';
 
 // Define StateDetection based on usage in the code
 interface StateDetection {
@@ -55,6 +64,21 @@ const defaultConfig: GamificationConfig = {
     metricUpdateInterval: 60,
     historyRetentionDays: 90,
   },
+  streaks: {
+    fullCreditMinutes: 60,
+    partialCreditMinutes: 15,
+    partialCreditValue: 0.5,
+    freezeEveryDays: 5,
+    maxFreezes: 2,
+    recoveryDays: 3,
+    recoveryRetainRatio: 0.5,
+  },
+  economy: {
+    maxCoinsPerHour: 120,
+    maxCoinsPerDay: 600,
+    maxGrantsPerMinute: 5,
+    catalog: DEFAULT_CATALOG,
+  },
   companion: {
     animationDuration: 1000,
     expressionVariety: true,
@@ -85,12 +109,18 @@ const defaultConfig: GamificationConfig = {
 // Gamification Engine Adapter - wraps MotivationEngine and adds missing methods
 class GamificationEngineAdapter {
   private motivationEngine: MotivationEngine;
+  private economy: RewardEconomy;
   private gameState: GameState;
   private interventionCooldown: number = 0;
   private lastInterventionTime: number = 0;
 
   constructor() {
     this.motivationEngine = new MotivationEngine(logger as any, defaultConfig);
+    this.economy = new RewardEconomy(
+      logger as any,
+      defaultConfig,
+      new FileStorage(process.env.SKELLY_GAMIFICATION_DATA || './data')
+    );
     this.gameState = {
       focusCoins: 0,
       achievementsUnlocked: [],
@@ -99,6 +129,10 @@ class GamificationEngineAdapter {
     };
   }
 
+  async load(): Promise<void> {
+    await this.economy.load();
+  }
+
   async processStateDetection(stateData: StateDetection): Promise<void> {
     // Process state detection - in a full implementation this would
     // update internal state and trigger appropriate responses
@@ -106,7 +140,7 @@ class GamificationEngineAdapter {
   }
 
   getGameState(): GameState {
-    return { ...this.gameState };
+    return { ...this.gameState, focusCoins: this.economy.getBalance() };
   }
 
   shouldIntervene(stateData: StateDetection): boolean {
@@ -140,16 +174,23 @@ class GamificationEngineAdapter {
     return Math.max(0, this.interventionCooldown - (Date.now() - this.lastInterventionTime));
   }
 
-  async awardFocusCoins(): Promise<number> {
-    const coinsAwarded = Math.floor(Math.random() * 5) + 1; // 1-5 coins
-    this.gameState.focusCoins += coinsAwarded;
-    return coinsAwarded;
+  async awardFocusCoins(): Promise<RewardGranted | undefined> {
+    const coins = Math.floor(Math.random() * 5) + 1; // 1-5 coins
+    return (await this.economy.earn(coins, 'flow_state')).event;
+  }
+
+  async awardCoins(amount: number, reason: string): Promise<RewardGranted | undefined> {
+    const result = await this.economy.earn(amount, reason);
+    console.log(`Awarded ${result.granted} of ${amount} coins for: ${reason}`);
+    return result.event;
+  }
+
+  async unlockItem(itemId: string): Promise<UnlockResult> {
+    return this.economy.unlock(itemId);
   }
 
-  async awardCoins(amount: number, reason: string): Promise<number> {
-    this.gameState.focusCoins += amount;
-    console.log(`Awarded ${amount} coins for: ${reason}`);
-    return this.gameState.focusCoins;
+  getShop(): ShopView {
+    return this.economy.getShop();
   }
 
   async checkAchievements(): Promise<Achievement[]> {
@@ -158,7 +199,7 @@ class GamificationEngineAdapter {
     const newAchievements: Achievement[] = [];
     
     // Example: First 100 coins achievement
-    if (this.gameState.focusCoins >= 100 && 
+    if (this.economy.getBalance() >= 100 && 
         !this.gameState.achievementsUnlocked.some(a => a.id === 'first_hundred')) {
       const achievement: Achievement = {
         id: 'first_hundred',
@@ -188,10 +229,14 @@ interface TypeScriptMessage {
 
 class GamificationIPCServer {
   private engine: GamificationEngineAdapter;
+  private ready: Promise<void>;
   private isShuttingDown = false;
 
   constructor() {
     this.engine = new GamificationEngineAdapter();
+    this.ready = this.engine.load().catch(error => {
+      logger.error('Failed to restore wallet:', error);
+    });
     this.setupProcessHandlers();
     this.startListening();
   }
@@ -238,6 +283,8 @@ class GamificationIPCServer {
     logger.debug(`📨 Received: ${message.action}`, message.payload);
 
     try {
+      await this.ready;
+
       switch (message.action) {
         case 'state_detected':
           await this.handleStateDetection(message.payload);
@@ -255,6 +302,19 @@ class GamificationIPCServer {
           await this.checkAchievements();
           break;
           
+        case 'unlock_item':
+          await this.unlockItem(message.payload.item_id);
+          break;
+          
+        case 'get_shop':
+          this.sendMessage({
+            module: 'gamification',
+            action: 'shop_update',
+            payload: this.engine.getShop(),
+            timestamp: Date.now()
+          });
+          break;
+          
         case 'shutdown':
           this.shutdown();
           break;
@@ -293,18 +353,9 @@ class GamificationIPCServer {
     
     // Award coins for focus states
     if (stateData.state === 'Flow') {
-      const coinsAwarded = await this.engine.awardFocusCoins();
-      if (coinsAwarded > 0) {
-        this.sendMessage({
-          module: 'gamification',
-          action: 'coins_awarded',
-          payload: {
-            amount: coinsAwarded,
-            reason: 'flow_state',
-            total_coins: gameState.focusCoins
-          },
-          timestamp: Date.now()
-        });
+      const granted = await this.engine.awardFocusCoins();
+      if (granted) {
+        this.sendCoinsAwarded(granted);
       }
     }
     
@@ -334,17 +385,47 @@ class GamificationIPCServer {
   }
 
   private async awardCoins(amount: number, reason: string): Promise<void> {
-    const newTotal = await this.engine.awardCoins(amount, reason);
+    const granted = await this.engine.awardCoins(amount, reason);
+    if (granted) {
+      this.sendCoinsAwarded(granted);
+    }
+  }
+
+  private sendCoinsAwarded(granted: RewardGranted): void {
     this.sendMessage({
       module: 'gamification',
       action: 'coins_awarded',
       payload: {
-        amount,
-        reason,
-        total_coins: newTotal
+        amount: granted.coins,
+        reason: granted.reason,
+        total_coins: granted.balance
       },
       timestamp: Date.now()
     });
+    this.sendRewardGranted(granted);
+  }
+
+  private async unlockItem(itemId: string): Promise<void> {
+    const result = await this.engine.unlockItem(itemId);
+    if (result.ok) {
+      this.sendRewardGranted(result.event);
+    } else {
+      this.sendMessage({
+        module: 'gamification',
+        action: 'unlock_failed',
+        payload: { item_id: itemId, reason: result.reason },
+        timestamp: Date.now()
+      });
+    }
+  }
+
+  private sendRewardGranted(granted: RewardGranted): void {
+    this.sendMessage({
+      module: 'gamification',
+      action: 'reward_granted',
+      payload: granted,
+      timestamp: Date.now()
+    });
   }
 
   private async checkAchievements(): Promise<void> {
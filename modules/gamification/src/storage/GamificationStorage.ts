@@ -0,0 +1,62 @@
+/**
+ * GamificationStorage - Local Persistence
+ *
+ * Keeps small JSON documents (streaks, rewards) on the user's machine so
+ * progress survives restarts. Nothing is sent anywhere.
+ */
+
+import { mkdir, readFile, rename, writeFile } from 'fs/promises';
+import { join } from 'path';
+
+export interface GamificationStorage {
+  load<T>(key: string): Promise<T | null>;
+  save<T>(key: string, value: T): Promise<void>;
+}
+
+/**
+ * In-memory storage, for tests and when persistence is disabled
+ */
+export class MemoryStorage implements GamificationStorage {
+  private documents = new Map<string, string>();
+
+  async load<T>(key: string): Promise<T | null> {
+    const document = this.documents.get(key);
+    return document === undefined ? null : JSON.parse(document) as T;
+  }
+
+  async save<T>(key: string, value: T): Promise<void> {
+    this.documents.set(key, JSON.stringify(value));
+  }
+}
+
+/**
+ * One `<key>.json` file per document in a local directory
+ */
+export class FileStorage implements GamificationStorage {
+  constructor(private directory: string) {}
+
+  async load<T>(key: string): Promise<T | null> {
+    try {
+      return JSON.parse(await readFile(this.pathFor(key), 'utf8')) as T;
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async save<T>(key: string, value: T): Promise<void> {
+    await mkdir(this.directory, { recursive: true });
+
+    // Write then rename so a crash never leaves a half-written file
+    const path = this.pathFor(key);
+    const temp = `${path}.tmp`;
+    await writeFile(temp, JSON.stringify(value, null, 2), 'utf8');
+    await rename(temp, path);
+  }
+
+  private pathFor(key: string): string {
+    return join(this.directory, `${key}.json`);
+  }
+}
@@ -0,0 +1,184 @@
+/**
+ * RewardEconomy - Focus Coins and Skeleton Customization
+ *
+ * Coins earned from focused work can be spent on hats, colors, and melt
+ * styles for the skeleton. Earning is rate capped so the economy can't be
+ * farmed, and the wallet is saved locally through storage.
+ *
+ * Every grant and unlock produces a RewardGranted event; unlocks carry the
+ * visual metadata the figurine needs to render the item.
+ */
+
+import {
+  EarnResult,
+  EconomyState,
+  GamificationConfig,
+  RewardGranted,
+  ShopView,
+  UnlockableItem,
+  UnlockResult
+} from '../types/index.js';
+import { GamificationStorage } from '../storage/GamificationStorage.js';
+import { Logger } from 'winston';
+import { v4 as uuidv4 } from 'uuid';
+
+const STORAGE_KEY = 'economy';
+const MINUTE_MS = 60 * 1000;
+const HOUR_MS = 60 * MINUTE_MS;
+const DAY_MS = 24 * HOUR_MS;
+
+export const DEFAULT_CATALOG: UnlockableItem[] = [
+  { id: 'party_hat', name: 'Party Hat', kind: 'hat', cost: 50, visual: { asset: 'hats/party.svg' } },
+  { id: 'wizard_hat', name: 'Wizard Hat', kind: 'hat', cost: 150, visual: { asset: 'hats/wizard.svg' } },
+  { id: 'tiny_crown', name: 'Tiny Crown', kind: 'hat', cost: 400, visual: { asset: 'hats/crown.svg' } },
+  { id: 'mint_glow', name: 'Mint Glow', kind: 'color', cost: 75, visual: { colors: ['#98FB98', '#E0FFE0'] } },
+  { id: 'sunset_bones', name: 'Sunset Bones', kind: 'color', cost: 120, visual: { colors: ['#FFB347', '#FF6F61'] } },
+  { id: 'midnight', name: 'Midnight', kind: 'color', cost: 200, visual: { colors: ['#2E3A59', '#8FA3D9'] } },
+  { id: 'slow_drip', name: 'Slow Drip', kind: 'melt_style', cost: 100, visual: { drip: 'slow', meltSpeed: 0.6 } },
+  { id: 'bubbly', name: 'Bubbly', kind: 'melt_style', cost: 180, visual: { drip: 'bubbles', meltSpeed: 1.0 } },
+  { id: 'glitter_goo', name: 'Glitter Goo', kind: 'melt_style', cost: 300, visual: { drip: 'sparkle', meltSpeed: 1.0, particles: 'glitter' } }
+];
+
+export class RewardEconomy {
+  private state: EconomyState;
+  private logger: Logger;
+  private config: GamificationConfig;
+  private storage: GamificationStorage;
+
+  constructor(logger: Logger, config: GamificationConfig, storage: GamificationStorage) {
+    this.logger = logger;
+    this.config = config;
+    this.storage = storage;
+    this.state = {
+      balance: 0,
+      totalEarned: 0,
+      totalSpent: 0,
+      owned: [],
+      recentGrants: []
+    };
+  }
+
+  /**
+   * Restore the saved wallet
+   */
+  async load(): Promise<void> {
+    const saved = await this.storage.load<EconomyState>(STORAGE_KEY);
+    if (saved) {
+      this.state = saved;
+      this.logger.info('Wallet restored', { balance: saved.balance, owned: saved.owned.length });
+    }
+  }
+
+  getBalance(): number {
+    return this.state.balance;
+  }
+
+  /**
+   * Catalog with ownership, for the customization menu
+   */
+  getShop(): ShopView {
+    return {
+      balance: this.state.balance,
+      items: this.config.economy.catalog.map(item => ({
+        ...item,
+        owned: this.state.owned.includes(item.id)
+      }))
+    };
+  }
+
+  getOwned(): UnlockableItem[] {
+    return this.config.economy.catalog.filter(item => this.state.owned.includes(item.id));
+  }
+
+  /**
+   * Add coins, trimmed to whatever the hourly and daily caps still allow
+   */
+  async earn(amount: number, reason: string, at: Date = new Date()): Promise<EarnResult> {
+    if (!Number.isFinite(amount) || amount < 1) {
+      return { granted: 0, capped: false };
+    }
+
+    const caps = this.config.economy;
+    const now = at.getTime();
+    this.state.recentGrants = this.state.recentGrants.filter(grant => now - grant.at < DAY_MS);
+
+    const grantsThisMinute = this.state.recentGrants.filter(grant => now - grant.at < MINUTE_MS).length;
+    const earnedThisHour = this.sumSince(now - HOUR_MS);
+    const earnedToday = this.sumSince(now - DAY_MS);
+    const granted = grantsThisMinute >= caps.maxGrantsPerMinute
+      ? 0
+      : Math.floor(Math.max(0, Math.min(amount, caps.maxCoinsPerHour - earnedThisHour, caps.maxCoinsPerDay - earnedToday)));
+    const capped = granted < Math.floor(amount);
+
+    if (capped) {
+      this.logger.debug('Coin grant capped', { requested: amount, granted, reason, earnedThisHour, earnedToday });
+    }
+    if (granted === 0) {
+      return { granted, capped };
+    }
+
+    this.state.balance += granted;
+    this.state.totalEarned += granted;
+    this.state.recentGrants.push({ at: now, amount: granted });
+    await this.persist();
+
+    return { granted, capped, event: this.createEvent(granted, reason, at) };
+  }
+
+  /**
+   * Spend coins on a catalog item
+   */
+  async unlock(itemId: string, at: Date = new Date()): Promise<UnlockResult> {
+    const item = this.config.economy.catalog.find(candidate => candidate.id === itemId);
+    if (!item) {
+      return { ok: false, reason: 'unknown_item' };
+    }
+    if (this.state.owned.includes(item.id)) {
+      return { ok: false, reason: 'already_owned' };
+    }
+    if (this.state.balance < item.cost) {
+      return { ok: false, reason: 'insufficient_coins' };
+    }
+
+    this.state.balance -= item.cost;
+    this.state.totalSpent += item.cost;
+    this.state.owned.push(item.id);
+    await this.persist();
+
+    this.logger.info('Item unlocked', { itemId: item.id, cost: item.cost, balance: this.state.balance });
+
+    return { ok: true, event: this.createEvent(0, `unlock:${item.id}`, at, item) };
+  }
+
+  // === Private Helper Methods ===
+
+  private sumSince(since: number): number {
+    return this.state.recentGrants
+      .filter(grant => grant.at > since)
+      .reduce((sum, grant) => sum + grant.amount, 0);
+  }
+
+  private createEvent(coins: number, reason: string, at: Date, item?: UnlockableItem): RewardGranted {
+    return {
+      reward_id: uuidv4(),
+      coins,
+      balance: this.state.balance,
+      reason,
+      unlock: item && {
+        item_id: item.id,
+        name: item.name,
+        kind: item.kind,
+        visual: item.visual
+      },
+      timestamp: at
+    };
+  }
+
+  private async persist(): Promise<void> {
+    try {
+      await this.storage.save(STORAGE_KEY, this.state);
+    } catch (error) {
+      this.logger.error('Failed to save wallet', { error });
+    }
+  }
+}
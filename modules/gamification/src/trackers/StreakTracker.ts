@@ -0,0 +1,195 @@
+/**
+ * StreakTracker - Forgiving Daily Streaks
+ *
+ * Tracks the daily focus streak with mechanics that keep a missed day from
+ * wiping out weeks of effort:
+ * - Partial credit: a shorter day still keeps the streak alive
+ * - Streak freezes: earned by consistent use, spent automatically on a missed day
+ * - Recovery: after a break, a few good days restore part of the old streak
+ *
+ * All thresholds come from `config.streaks`; state is saved through storage
+ * after every change.
+ */
+
+import {
+  GamificationConfig,
+  StreakDay,
+  StreakDayOutcome,
+  StreakState,
+  StreakUpdate
+} from '../types/index.js';
+import { GamificationStorage } from '../storage/GamificationStorage.js';
+import { Logger } from 'winston';
+import { addDays, format, parseISO } from 'date-fns';
+
+const STORAGE_KEY = 'streaks';
+
+export class StreakTracker {
+  private state: StreakState;
+  private logger: Logger;
+  private config: GamificationConfig;
+  private storage: GamificationStorage;
+
+  constructor(logger: Logger, config: GamificationConfig, storage: GamificationStorage) {
+    this.logger = logger;
+    this.config = config;
+    this.storage = storage;
+    this.state = this.createEmptyState();
+  }
+
+  /**
+   * Restore saved streak state
+   */
+  async load(): Promise<void> {
+    const saved = await this.storage.load<StreakState>(STORAGE_KEY);
+    if (saved) {
+      this.state = saved;
+      this.logger.info('Streak state restored', {
+        current: saved.current,
+        freezes: saved.freezes
+      });
+    }
+  }
+
+  /**
+   * Get current streak state
+   */
+  getState(): StreakState {
+    return {
+      ...this.state,
+      recovery: this.state.recovery ? { ...this.state.recovery } : undefined,
+      history: [...this.state.history]
+    };
+  }
+
+  /**
+   * Add focus time to the day containing `at`, settling any earlier days first
+   */
+  async recordFocus(minutes: number, at: Date = new Date()): Promise<StreakUpdate[]> {
+    const updates = this.settleBefore(at);
+    this.state.openDayMinutes += Math.max(minutes, 0);
+    await this.persist();
+    return updates;
+  }
+
+  /**
+   * Settle every day before `now`, e.g. on startup or at midnight
+   */
+  async rollover(now: Date = new Date()): Promise<StreakUpdate[]> {
+    const updates = this.settleBefore(now);
+    if (updates.length > 0) {
+      await this.persist();
+    }
+    return updates;
+  }
+
+  // === Private Helper Methods ===
+
+  private createEmptyState(): StreakState {
+    return {
+      current: 0,
+      best: 0,
+      freezes: 0,
+      freezeProgress: 0,
+      openDayMinutes: 0,
+      history: []
+    };
+  }
+
+  private settleBefore(now: Date): StreakUpdate[] {
+    const today = format(now, 'yyyy-MM-dd');
+    const updates: StreakUpdate[] = [];
+
+    if (!this.state.openDay) {
+      this.state.openDay = today;
+      return updates;
+    }
+
+    // Days with no activity at all are settled too, one at a time
+    while (this.state.openDay < today) {
+      updates.push(this.settleDay(this.state.openDay, this.state.openDayMinutes));
+      this.state.openDay = format(addDays(parseISO(this.state.openDay), 1), 'yyyy-MM-dd');
+      this.state.openDayMinutes = 0;
+    }
+
+    return updates;
+  }
+
+  private settleDay(date: string, focusMinutes: number): StreakUpdate {
+    const rules = this.config.streaks;
+    const outcome = this.classifyDay(focusMinutes);
+    const credit = outcome === 'full' ? 1 : outcome === 'partial' ? rules.partialCreditValue : 0;
+    let freezeEarned = false;
+    let recovered = false;
+
+    if (outcome === 'frozen') {
+      this.state.freezes -= 1;
+    }
+
+    if (credit > 0) {
+      this.state.current += credit;
+
+      this.state.freezeProgress += credit;
+      if (rules.freezeEveryDays > 0 && this.state.freezeProgress >= rules.freezeEveryDays) {
+        this.state.freezeProgress -= rules.freezeEveryDays;
+        if (this.state.freezes < rules.maxFreezes) {
+          this.state.freezes += 1;
+          freezeEarned = true;
+        }
+      }
+
+      const recovery = this.state.recovery;
+      if (recovery) {
+        recovery.daysCredited += 1;
+        if (recovery.daysCredited >= recovery.daysNeeded) {
+          this.state.current += Math.round(recovery.previousStreak * rules.recoveryRetainRatio);
+          this.state.recovery = undefined;
+          recovered = true;
+        }
+      }
+    } else if (outcome === 'missed') {
+      // The old streak is remembered, not lost; a few good days bring it back
+      const previousStreak = Math.max(this.state.current, this.state.recovery?.previousStreak ?? 0);
+      this.state.recovery = previousStreak > 0
+        ? { previousStreak, daysCredited: 0, daysNeeded: rules.recoveryDays }
+        : undefined;
+      this.state.current = 0;
+      this.state.freezeProgress = 0;
+    }
+
+    this.state.best = Math.max(this.state.best, this.state.current);
+
+    const day: StreakDay = { date, focusMinutes: Math.round(focusMinutes), outcome };
+    this.state.history.push(day);
+    const retention = this.config.progress.historyRetentionDays;
+    if (this.state.history.length > retention) {
+      this.state.history.splice(0, this.state.history.length - retention);
+    }
+
+    this.logger.debug('Streak day settled', {
+      date,
+      outcome,
+      current: this.state.current,
+      freezes: this.state.freezes,
+      recovered
+    });
+
+    return { day, current: this.state.current, freezeEarned, recovered };
+  }
+
+  private classifyDay(focusMinutes: number): StreakDayOutcome {
+    const rules = this.config.streaks;
+    if (focusMinutes >= rules.fullCreditMinutes) return 'full';
+    if (rules.partialCreditMinutes > 0 && focusMinutes >= rules.partialCreditMinutes) return 'partial';
+    if (this.state.freezes > 0) return 'frozen';
+    return 'missed';
+  }
+
+  private async persist(): Promise<void> {
+    try {
+      await this.storage.save(STORAGE_KEY, this.state);
+    } catch (error) {
+      this.logger.error('Failed to save streak state', { error });
+    }
+  }
+}
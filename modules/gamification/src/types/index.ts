@@ -127,6 +127,56 @@ export interface VisualReward {
   position: 'companion' | 'corner' | 'center' | 'toast';
 }
 
+// === Reward Economy ===
+
+export type UnlockKind = 'hat' | 'color' | 'melt_style';
+
+export interface UnlockableItem {
+  id: string;
+  name: string;
+  kind: UnlockKind;
+  cost: number; // coins
+  visual: Record<string, unknown>; // asset, palette, or melt parameters for the figurine
+}
+
+export interface EconomyState {
+  balance: number;
+  totalEarned: number;
+  totalSpent: number;
+  owned: string[]; // item ids
+  recentGrants: { at: number; amount: number }[]; // last 24 hours, for rate caps
+}
+
+// Matches the bus RewardGranted message
+export interface RewardGranted {
+  reward_id: UUID;
+  coins: number;
+  balance: number;
+  reason: string;
+  unlock?: {
+    item_id: string;
+    name: string;
+    kind: UnlockKind;
+    visual: Record<string, unknown>;
+  };
+  timestamp: Date;
+}
+
+export interface EarnResult {
+  granted: number;
+  capped: boolean;
+  event?: RewardGranted;
+}
+
+export type UnlockResult =
+  | { ok: true; event: RewardGranted }
+  | { ok: false; reason: 'unknown_item' | 'already_owned' | 'insufficient_coins' };
+
+export interface ShopView {
+  balance: number;
+  items: (UnlockableItem & { owned: boolean })[];
+}
+
 // === Progress Tracking ===
 
 export interface SessionMetrics {
@@ -185,6 +235,38 @@ export interface StreakInfo {
   nextMilestone: number;
 }
 
+export type StreakDayOutcome = 'full' | 'partial' | 'frozen' | 'missed';
+
+export interface StreakDay {
+  date: string; // yyyy-MM-dd, local day
+  focusMinutes: number;
+  outcome: StreakDayOutcome;
+}
+
+export interface StreakRecovery {
+  previousStreak: number;
+  daysCredited: number;
+  daysNeeded: number;
+}
+
+export interface StreakState {
+  current: number; // full days count 1, partial days count partialCreditValue
+  best: number;
+  freezes: number;
+  freezeProgress: number; // credit toward the next freeze
+  openDay?: string; // day still collecting focus time
+  openDayMinutes: number;
+  recovery?: StreakRecovery; // set after a break until the streak is rebuilt
+  history: StreakDay[];
+}
+
+export interface StreakUpdate {
+  day: StreakDay;
+  current: number;
+  freezeEarned: boolean;
+  recovered: boolean;
+}
+
 // === Companion Behavior ===
 
 export interface AnimationCommand {
@@ -400,6 +482,23 @@ export interface GamificationConfig {
     historyRetentionDays: number;
   };
   
+  streaks: {
+    fullCreditMinutes: number; // focus minutes for a day to count fully
+    partialCreditMinutes: number; // focus minutes for a day to keep the streak alive
+    partialCreditValue: number; // 0-1, how much a partial day counts
+    freezeEveryDays: number; // credited days to earn a streak freeze
+    maxFreezes: number;
+    recoveryDays: number; // credited days after a break to restore the old streak
+    recoveryRetainRatio: number; // 0-1, share of the old streak restored
+  };
+  
+  economy: {
+    maxCoinsPerHour: number;
+    maxCoinsPerDay: number;
+    maxGrantsPerMinute: number; // stops bursts of tiny grants
+    catalog: UnlockableItem[];
+  };
+  
   companion: {
     animationDuration: number;
     expressionVariety: boolean;
@@ -492,6 +591,7 @@ export interface ProgressSummary {
   daily: DailyMetrics;
   allTime: AllTimeMetrics;
   streaks: StreakInfo[];
+  streak: StreakState;
   achievements: Achievement[];
   wallet: WalletBalance;
   level: LevelProgress;
@@ -0,0 +1,83 @@
+/**
+ * Tests for RewardEconomy
+ * Coins can't be farmed, and unlocks carry what the figurine needs to show them
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { RewardEconomy } from '../src/systems/RewardEconomy.js';
+import { MemoryStorage } from '../src/storage/GamificationStorage.js';
+import { DEFAULT_CONFIG } from '../src/index.js';
+import { GamificationConfig } from '../src/types/index.js';
+import { Logger } from 'winston';
+
+// Mock logger
+const mockLogger = {
+  info: vi.fn(),
+  error: vi.fn(),
+  warn: vi.fn(),
+  debug: vi.fn()
+} as unknown as Logger;
+
+const config: GamificationConfig = {
+  ...DEFAULT_CONFIG,
+  economy: {
+    ...DEFAULT_CONFIG.economy,
+    maxCoinsPerHour: 100,
+    maxCoinsPerDay: 150,
+    maxGrantsPerMinute: 2
+  }
+};
+
+function minutesAfter(start: Date, minutes: number): Date {
+  return new Date(start.getTime() + minutes * 60 * 1000);
+}
+
+describe('RewardEconomy', () => {
+  it('caps earning per minute, hour, and day', async () => {
+    const economy = new RewardEconomy(mockLogger, config, new MemoryStorage());
+    const start = new Date(2024, 2, 1, 9);
+
+    expect((await economy.earn(30, 'focus', start)).granted).toBe(30);
+    expect((await economy.earn(30, 'focus', start)).granted).toBe(30);
+    // Third grant in the same minute
+    expect(await economy.earn(30, 'focus', start)).toEqual({ granted: 0, capped: true });
+
+    const trimmed = await economy.earn(80, 'focus', minutesAfter(start, 5));
+    expect(trimmed.granted).toBe(40);
+    expect(trimmed.capped).toBe(true);
+    expect(trimmed.event?.balance).toBe(100);
+
+    // The hourly window has passed, but only 50 remain for the day
+    expect((await economy.earn(80, 'focus', minutesAfter(start, 70))).granted).toBe(50);
+    expect((await economy.earn(-5, 'exploit', minutesAfter(start, 80))).granted).toBe(0);
+    expect(economy.getBalance()).toBe(150);
+  });
+
+  it('unlocks items with visual metadata and keeps them across restarts', async () => {
+    const storage = new MemoryStorage();
+    const economy = new RewardEconomy(mockLogger, config, storage);
+    await economy.earn(100, 'focus');
+
+    expect(await economy.unlock('tiny_crown')).toEqual({ ok: false, reason: 'insufficient_coins' });
+    expect(await economy.unlock('jetpack')).toEqual({ ok: false, reason: 'unknown_item' });
+
+    const result = await economy.unlock('party_hat');
+    expect(result.ok).toBe(true);
+    if (result.ok) {
+      expect(result.event.coins).toBe(0);
+      expect(result.event.balance).toBe(50);
+      expect(result.event.unlock).toEqual({
+        item_id: 'party_hat',
+        name: 'Party Hat',
+        kind: 'hat',
+        visual: { asset: 'hats/party.svg' }
+      });
+    }
+    expect(await economy.unlock('party_hat')).toEqual({ ok: false, reason: 'already_owned' });
+
+    const restarted = new RewardEconomy(mockLogger, config, storage);
+    await restarted.load();
+    expect(restarted.getBalance()).toBe(50);
+    expect(restarted.getShop().items.find(item => item.id === 'party_hat')?.owned).toBe(true);
+  });
+});
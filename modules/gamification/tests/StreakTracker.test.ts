@@ -0,0 +1,88 @@
+/**
+ * Tests for StreakTracker
+ * A missed day should never wipe out the user's progress
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { StreakTracker } from '../src/trackers/StreakTracker.js';
+import { MemoryStorage } from '../src/storage/GamificationStorage.js';
+import { DEFAULT_CONFIG } from '../src/index.js';
+import { GamificationConfig } from '../src/types/index.js';
+import { Logger } from 'winston';
+
+// Mock logger
+const mockLogger = {
+  info: vi.fn(),
+  error: vi.fn(),
+  warn: vi.fn(),
+  debug: vi.fn()
+} as unknown as Logger;
+
+const config: GamificationConfig = {
+  ...DEFAULT_CONFIG,
+  streaks: {
+    fullCreditMinutes: 60,
+    partialCreditMinutes: 15,
+    partialCreditValue: 0.5,
+    freezeEveryDays: 3,
+    maxFreezes: 1,
+    recoveryDays: 2,
+    recoveryRetainRatio: 0.5
+  }
+};
+
+function day(n: number): Date {
+  return new Date(2024, 2, n, 12);
+}
+
+describe('StreakTracker', () => {
+  it('gives partial credit and spends earned freezes on missed days', async () => {
+    const tracker = new StreakTracker(mockLogger, config, new MemoryStorage());
+
+    const updates = [
+      ...await tracker.recordFocus(90, day(1)),
+      ...await tracker.recordFocus(90, day(2)),
+      ...await tracker.recordFocus(20, day(3)),
+      ...await tracker.recordFocus(90, day(4))
+    ];
+
+    expect(updates.map(u => u.day.outcome)).toEqual(['full', 'full', 'partial']);
+    expect(tracker.getState().current).toBe(2.5);
+
+    // Day 4 completes the third day of credit and earns a freeze, day 5 uses it
+    await tracker.rollover(day(5));
+    expect(tracker.getState().freezes).toBe(1);
+    const frozen = await tracker.rollover(day(6));
+
+    expect(frozen[0]?.day.outcome).toBe('frozen');
+    expect(tracker.getState().current).toBe(3.5);
+    expect(tracker.getState().freezes).toBe(0);
+  });
+
+  it('restores part of the old streak after a break and survives restarts', async () => {
+    const storage = new MemoryStorage();
+    const tracker = new StreakTracker(mockLogger, { ...config, streaks: { ...config.streaks, maxFreezes: 0 } }, storage);
+
+    for (let n = 1; n <= 6; n++) {
+      await tracker.recordFocus(60, day(n));
+    }
+    // Two days away
+    await tracker.rollover(day(9));
+    const state = tracker.getState();
+    expect(state.current).toBe(0);
+    expect(state.best).toBe(6);
+    expect(state.recovery?.previousStreak).toBe(6);
+
+    // A restart picks up where the user left off
+    const restarted = new StreakTracker(mockLogger, config, storage);
+    await restarted.load();
+    await restarted.recordFocus(60, day(9));
+    const first = await restarted.recordFocus(60, day(10));
+    const second = await restarted.recordFocus(60, day(11));
+
+    expect(first[0]?.recovered).toBe(false);
+    expect(second[0]?.recovered).toBe(true);
+    expect(restarted.getState().current).toBe(2 + 3);
+    expect(restarted.getState().recovery).toBeUndefined();
+  });
+});
@@ -0,0 +1,85 @@
+//! Synthetic input standing in for the data capture module
+//!
+//! Each behavior is a fixed, repeating pattern of keystrokes and window
+//! switches, so a scenario produces the same events on every run.
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use skelly_jelly_event_bus::message::RawEvent;
+
+/// Windows a distracted user flips between, in order
+const DISTRACTION_WINDOWS: [&str; 4] = ["Slack", "Twitter", "Editor", "YouTube"];
+
+/// A pattern of user activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behavior {
+    /// Steady typing in one window
+    Focused,
+    /// Switching windows every few seconds with scattered typing
+    Distracted,
+    /// No input at all
+    Idle,
+}
+
+impl Behavior {
+    /// Events this behavior produces during the second starting at `elapsed`
+    ///
+    /// `started` is when the behavior began, so patterns line up with its start
+    /// rather than with the scenario clock.
+    pub fn events_at(&self, elapsed: Duration, started: Duration, time: DateTime<Utc>) -> Vec<RawEvent> {
+        let second = elapsed.saturating_sub(started).as_secs();
+        match self {
+            Behavior::Focused => vec![keystroke("Editor", time)],
+            Behavior::Distracted => {
+                let window = DISTRACTION_WINDOWS[(second / 5) as usize % DISTRACTION_WINDOWS.len()];
+                let mut events = Vec::new();
+                if second.is_multiple_of(5) {
+                    events.push(window_switch(window, time));
+                }
+                if second.is_multiple_of(3) {
+                    events.push(keystroke(window, time));
+                }
+                events
+            }
+            Behavior::Idle => Vec::new(),
+        }
+    }
+}
+
+fn keystroke(window: &str, time: DateTime<Utc>) -> RawEvent {
+    let mut event = RawEvent::keystroke("a".to_string(), Duration::from_millis(80), vec![]);
+    event.window_title = Some(window.to_string());
+    event.timestamp = time;
+    event
+}
+
+fn window_switch(window: &str, time: DateTime<Utc>) -> RawEvent {
+    RawEvent {
+        event_type: "window_switch".to_string(),
+        data: serde_json::json!({ "to": window }),
+        window_title: Some(window.to_string()),
+        timestamp: time,
+    }
+}
+
+/// Behaviors injected by a scenario, each active for a stretch of virtual time
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticCapture {
+    active: Vec<(Behavior, Duration, Duration)>,
+}
+
+impl SyntheticCapture {
+    /// Run `behavior` from `start` for `duration`
+    pub fn inject(&mut self, behavior: Behavior, start: Duration, duration: Duration) {
+        self.active.push((behavior, start, start + duration));
+    }
+
+    /// Events for the second starting at `elapsed`, from every behavior active then
+    pub fn events_at(&self, elapsed: Duration, time: DateTime<Utc>) -> Vec<RawEvent> {
+        self.active
+            .iter()
+            .filter(|(_, start, end)| (*start..*end).contains(&elapsed))
+            .flat_map(|(behavior, start, _)| behavior.events_at(elapsed, *start, time))
+            .collect()
+    }
+}
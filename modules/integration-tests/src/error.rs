@@ -0,0 +1,46 @@
+//! Error types for scenario runs
+
+use std::time::Duration;
+use thiserror::Error;
+use skelly_jelly_event_bus::{EventBusError, MessageType, ModuleId};
+
+/// Result type for scenario runs
+pub type ScenarioResult<T> = Result<T, ScenarioError>;
+
+/// Why a scenario failed
+#[derive(Error, Debug)]
+pub enum ScenarioError {
+    #[error("Step {step} ({description}): no {expected} within {within:?}; published {published:?}")]
+    ExpectationNotMet {
+        step: usize,
+        description: String,
+        expected: String,
+        within: Duration,
+        /// Everything published during the step, as (virtual time, type)
+        published: Vec<(Duration, MessageType)>,
+    },
+
+    #[error("Step {step} ({description}): unexpected {expected} at {at:?}")]
+    UnexpectedMessage {
+        step: usize,
+        description: String,
+        expected: String,
+        at: Duration,
+    },
+
+    #[error("Step {step} ({description}): {reason}")]
+    InvalidStep {
+        step: usize,
+        description: String,
+        reason: String,
+    },
+
+    #[error("Participant {module} failed: {reason}")]
+    Participant {
+        module: ModuleId,
+        reason: String,
+    },
+
+    #[error("Event bus error: {0}")]
+    EventBus(#[from] EventBusError),
+}
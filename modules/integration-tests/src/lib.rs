@@ -0,0 +1,20 @@
+//! # Skelly-Jelly Integration Tests
+//!
+//! Cross-module scenarios on a virtual-time event bus. A [`Scenario`] reads as
+//! a script — start the system, inject distracted behavior for five minutes,
+//! expect an intervention within two, dismiss it, expect a cooldown — and runs
+//! in milliseconds on the `testkit` bus with the same result every time.
+
+pub mod error;
+pub mod capture;
+pub mod participants;
+pub mod scenario;
+
+// Re-export public API
+pub use error::{ScenarioError, ScenarioResult};
+pub use capture::{Behavior, SyntheticCapture};
+pub use participants::{
+    FigurineParticipant, InterventionPolicy, Moment, Participant, RuleBasedAnalysis, TemplateResponder,
+    INTERVENTION_DISMISSED,
+};
+pub use scenario::{Expect, Scenario, ScenarioReport, StepReport, UserAction};
@@ -0,0 +1,244 @@
+//! Modules taking part in a scenario
+//!
+//! A [`Participant`] subscribes to the scenario bus and reacts to what it
+//! receives. Modules that run in-process plug in directly, like the figurine
+//! consumer. The rest get small rule-based stand-ins with the same bus
+//! contract: the analysis engine (its models are too heavy for a test run),
+//! gamification (a TypeScript process) and the AI integration.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use skelly_jelly_event_bus::{
+    BusMessage, EventBusTrait, MessageFilter, MessagePayload, MessageType, ModuleId,
+    message::{InterventionRequest, InterventionResponse, ResponseMetadata, StateClassification},
+};
+use skelly_jelly_figurine_protocol::{AnimationTranslator, FigurineConsumer};
+
+use crate::error::{ScenarioError, ScenarioResult};
+
+/// Event type of the raw event the UI sends when the user dismisses an intervention
+pub const INTERVENTION_DISMISSED: &str = "intervention_dismissed";
+
+/// A point in scenario time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Moment {
+    /// Virtual time since the scenario started
+    pub elapsed: Duration,
+    /// Wall-clock time the scenario pretends it is
+    pub time: DateTime<Utc>,
+}
+
+/// A module driven by the scenario runner
+#[async_trait]
+pub trait Participant: Send {
+    /// Module the participant subscribes and publishes as
+    fn module(&self) -> ModuleId;
+
+    /// Messages the participant wants delivered
+    fn filter(&self) -> MessageFilter;
+
+    /// React to a delivered message; returned payloads are published as [`Participant::module`]
+    async fn handle(&mut self, message: &BusMessage, moment: Moment) -> ScenarioResult<Vec<MessagePayload>>;
+
+    /// Called once per tick for time-driven behavior
+    async fn tick(&mut self, _moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Stand-in analysis engine classifying focus from window switches
+#[derive(Debug, Clone)]
+pub struct RuleBasedAnalysis {
+    /// How far back events count
+    pub window: Duration,
+    /// Window switches within `window` that mean the user is distracted
+    pub switch_threshold: usize,
+    /// (when, was a window switch) for events still inside `window`
+    recent: VecDeque<(Duration, bool)>,
+    state: Option<String>,
+}
+
+impl Default for RuleBasedAnalysis {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(30),
+            switch_threshold: 4,
+            recent: VecDeque::new(),
+            state: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Participant for RuleBasedAnalysis {
+    fn module(&self) -> ModuleId {
+        ModuleId::AnalysisEngine
+    }
+
+    fn filter(&self) -> MessageFilter {
+        MessageFilter::types_and_sources(vec![MessageType::RawEvent], vec![ModuleId::DataCapture])
+    }
+
+    async fn handle(&mut self, message: &BusMessage, moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        let MessagePayload::RawEvent(event) = &message.payload else { return Ok(Vec::new()) };
+        self.recent.push_back((moment.elapsed, event.event_type == "window_switch"));
+        while self.recent.front().is_some_and(|(at, _)| moment.elapsed.saturating_sub(*at) > self.window) {
+            self.recent.pop_front();
+        }
+
+        let switches = self.recent.iter().filter(|(_, switch)| *switch).count();
+        let (state, confidence) = if switches >= self.switch_threshold {
+            ("distracted", (switches as f64 / (2 * self.switch_threshold) as f64).min(1.0))
+        } else {
+            ("focused", 0.9)
+        };
+        if self.state.as_deref() == Some(state) {
+            return Ok(Vec::new());
+        }
+
+        let transition_from = self.state.replace(state.to_string());
+        Ok(vec![MessagePayload::StateChange(StateClassification {
+            state: state.to_string(),
+            confidence,
+            timestamp: moment.time,
+            transition_from,
+        })])
+    }
+}
+
+/// Stand-in gamification module deciding when to nudge the user
+#[derive(Debug, Clone)]
+pub struct InterventionPolicy {
+    /// How long the user has to stay distracted before a nudge
+    pub distracted_for: Duration,
+    /// Least time between two nudges
+    pub min_interval: Duration,
+    /// Quiet time after the user dismisses a nudge
+    pub dismissal_cooldown: Duration,
+    distracted_since: Option<Duration>,
+    next_allowed: Duration,
+    outstanding: Option<Uuid>,
+}
+
+impl Default for InterventionPolicy {
+    fn default() -> Self {
+        Self {
+            distracted_for: Duration::from_secs(60),
+            min_interval: Duration::from_secs(10 * 60),
+            dismissal_cooldown: Duration::from_secs(20 * 60),
+            distracted_since: None,
+            next_allowed: Duration::ZERO,
+            outstanding: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Participant for InterventionPolicy {
+    fn module(&self) -> ModuleId {
+        ModuleId::Gamification
+    }
+
+    fn filter(&self) -> MessageFilter {
+        MessageFilter::types_and_sources(
+            vec![MessageType::StateChange, MessageType::RawEvent],
+            vec![ModuleId::AnalysisEngine, ModuleId::CuteFigurine],
+        )
+    }
+
+    async fn handle(&mut self, message: &BusMessage, moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        match &message.payload {
+            MessagePayload::StateChange(state) if state.state == "distracted" => {
+                self.distracted_since.get_or_insert(moment.elapsed);
+            }
+            MessagePayload::StateChange(_) => self.distracted_since = None,
+            MessagePayload::RawEvent(event) if event.event_type == INTERVENTION_DISMISSED => {
+                let dismissed = event.data["request_id"].as_str().and_then(|id| id.parse::<Uuid>().ok());
+                if dismissed.is_some() && dismissed == self.outstanding {
+                    self.outstanding = None;
+                    self.next_allowed = self.next_allowed.max(moment.elapsed + self.dismissal_cooldown);
+                }
+            }
+            _ => {}
+        }
+        Ok(Vec::new())
+    }
+
+    async fn tick(&mut self, moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        let Some(since) = self.distracted_since else { return Ok(Vec::new()) };
+        let distracted = moment.elapsed.saturating_sub(since);
+        if distracted < self.distracted_for || moment.elapsed < self.next_allowed {
+            return Ok(Vec::new());
+        }
+
+        let request_id = Uuid::new_v4();
+        self.outstanding = Some(request_id);
+        self.next_allowed = moment.elapsed + self.min_interval;
+        Ok(vec![MessagePayload::InterventionRequest(InterventionRequest {
+            request_id,
+            intervention_type: "gentle_nudge".to_string(),
+            urgency: "low".to_string(),
+            context: serde_json::json!({ "state": "distracted", "distracted_secs": distracted.as_secs() }),
+        })])
+    }
+}
+
+/// Stand-in AI integration answering every intervention with a fixed line
+#[derive(Debug, Clone, Default)]
+pub struct TemplateResponder;
+
+#[async_trait]
+impl Participant for TemplateResponder {
+    fn module(&self) -> ModuleId {
+        ModuleId::AiIntegration
+    }
+
+    fn filter(&self) -> MessageFilter {
+        MessageFilter::types(vec![MessageType::InterventionRequest])
+    }
+
+    async fn handle(&mut self, message: &BusMessage, _moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        let MessagePayload::InterventionRequest(request) = &message.payload else { return Ok(Vec::new()) };
+        Ok(vec![MessagePayload::InterventionResponse(InterventionResponse {
+            request_id: request.request_id,
+            response_text: "Lots of tabs open. Want to pick one and park the rest?".to_string(),
+            animation_cues: vec!["wave".to_string()],
+            metadata: ResponseMetadata { fallback: true, fallback_reason: Some("scenario stand-in".to_string()) },
+        })])
+    }
+}
+
+/// The real figurine consumer, publishing animation commands to the scenario bus
+pub struct FigurineParticipant {
+    consumer: FigurineConsumer,
+}
+
+impl FigurineParticipant {
+    pub fn new(bus: Arc<dyn EventBusTrait>) -> Self {
+        Self { consumer: FigurineConsumer::new(bus, AnimationTranslator::default()) }
+    }
+}
+
+#[async_trait]
+impl Participant for FigurineParticipant {
+    fn module(&self) -> ModuleId {
+        ModuleId::CuteFigurine
+    }
+
+    fn filter(&self) -> MessageFilter {
+        MessageFilter::types(FigurineConsumer::subscribed_types())
+    }
+
+    async fn handle(&mut self, message: &BusMessage, _moment: Moment) -> ScenarioResult<Vec<MessagePayload>> {
+        // The consumer publishes its own animation commands
+        self.consumer.handle_message(message).await.map_err(|e| ScenarioError::Participant {
+            module: ModuleId::CuteFigurine,
+            reason: e.to_string(),
+        })?;
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,509 @@
+//! The scenario DSL and its runner
+//!
+//! A [`Scenario`] is a list of steps run in order on a [`TestEventBus`]. Time
+//! only moves when a step needs it to, one tick at a time. On each tick the
+//! runner publishes the synthetic capture events due, lets participants react
+//! until the bus goes quiet, and checks the step's expectation.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, TimeZone, Utc};
+use crossbeam_channel::Receiver;
+use skelly_jelly_event_bus::{
+    BusMessage, DeliveryMode, EventBusTrait, MessagePayload, MessageType, ModuleId,
+    message::RawEvent,
+    testkit::{TestBusConfig, TestEventBus},
+};
+
+use crate::capture::{Behavior, SyntheticCapture};
+use crate::error::{ScenarioError, ScenarioResult};
+use crate::participants::{
+    FigurineParticipant, InterventionPolicy, Moment, Participant, RuleBasedAnalysis, TemplateResponder,
+    INTERVENTION_DISMISSED,
+};
+
+/// Rounds of participant reactions allowed in one instant before the runner gives up
+const MAX_REACTION_ROUNDS: usize = 64;
+
+/// Extra check on a message, for [`Expect::matching`]
+type MessagePredicate = Arc<dyn Fn(&BusMessage) -> bool + Send + Sync>;
+
+/// A participant with its delivery channel once subscribed
+type Subscribed = (Box<dyn Participant>, Option<Receiver<BusMessage>>);
+
+/// A message a step waits for, or must not see
+#[derive(Clone)]
+pub struct Expect {
+    message_type: MessageType,
+    source: Option<ModuleId>,
+    predicate: Option<(String, MessagePredicate)>,
+}
+
+impl Expect {
+    /// Any message of `message_type`
+    pub fn message(message_type: MessageType) -> Self {
+        Self { message_type, source: None, predicate: None }
+    }
+
+    /// Only messages published by `module`
+    pub fn from(mut self, module: ModuleId) -> Self {
+        self.source = Some(module);
+        self
+    }
+
+    /// Only messages passing `predicate`, described for failure messages
+    pub fn matching<F>(mut self, description: impl Into<String>, predicate: F) -> Self
+    where
+        F: Fn(&BusMessage) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some((description.into(), Arc::new(predicate)));
+        self
+    }
+
+    pub fn matches(&self, message: &BusMessage) -> bool {
+        message.message_type() == self.message_type
+            && self.source.is_none_or(|source| message.source == source)
+            && self.predicate.as_ref().is_none_or(|(_, predicate)| predicate(message))
+    }
+}
+
+impl std::fmt::Display for Expect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.message_type)?;
+        if let Some(source) = self.source {
+            write!(f, " from {}", source)?;
+        }
+        if let Some((description, _)) = &self.predicate {
+            write!(f, " where {}", description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Something the user does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserAction {
+    /// Dismiss the most recent intervention from the figurine
+    DismissIntervention,
+}
+
+enum Step {
+    Start,
+    Inject { behavior: Behavior, duration: Duration },
+    Wait(Duration),
+    Simulate(UserAction),
+    ExpectWithin { within: Duration, expect: Expect },
+    ExpectNoneFor { duration: Duration, expect: Expect },
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::Start => "start system".to_string(),
+            Step::Inject { behavior, duration } => format!("inject {:?} for {:?}", behavior, duration),
+            Step::Wait(duration) => format!("wait {:?}", duration),
+            Step::Simulate(action) => format!("simulate {:?}", action),
+            Step::ExpectWithin { within, expect } => format!("expect {} within {:?}", expect, within),
+            Step::ExpectNoneFor { duration, expect } => format!("expect no {} for {:?}", expect, duration),
+        }
+    }
+}
+
+/// When one step ran, in virtual time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepReport {
+    pub description: String,
+    pub started: Duration,
+    pub finished: Duration,
+}
+
+/// What a passing scenario did
+#[derive(Debug, Clone)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub steps: Vec<StepReport>,
+    /// Every publish as (virtual time, type, publisher)
+    pub published: Vec<(Duration, MessageType, ModuleId)>,
+}
+
+impl ScenarioReport {
+    /// How many messages of `message_type` were published
+    pub fn count(&self, message_type: MessageType) -> usize {
+        self.published.iter().filter(|(_, published, _)| *published == message_type).count()
+    }
+}
+
+/// A cross-module scenario on a virtual-time bus
+///
+/// ```ignore
+/// Scenario::new("distraction gets one nudge")
+///     .with_default_modules()
+///     .start_system()
+///     .inject(Behavior::Distracted, Duration::from_secs(300))
+///     .expect_within(Duration::from_secs(120), Expect::message(MessageType::InterventionRequest))
+///     .simulate(UserAction::DismissIntervention)
+///     .expect_none_for(Duration::from_secs(180), Expect::message(MessageType::InterventionRequest))
+///     .run()
+///     .await?;
+/// ```
+pub struct Scenario {
+    name: String,
+    bus: Arc<TestEventBus>,
+    tick: Duration,
+    start_time: DateTime<Utc>,
+    participants: Vec<Box<dyn Participant>>,
+    steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_bus_config(name, TestBusConfig::default())
+    }
+
+    /// Run on a bus with these timing, retry and policy settings
+    pub fn with_bus_config(name: impl Into<String>, config: TestBusConfig) -> Self {
+        Self {
+            name: name.into(),
+            bus: Arc::new(TestEventBus::with_config(config)),
+            tick: Duration::from_secs(1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap(),
+            participants: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Virtual time per tick; capture patterns assume one second
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        self.tick = tick;
+        self
+    }
+
+    /// Wall-clock time the scenario starts at, for message timestamps
+    pub fn with_start_time(mut self, start_time: DateTime<Utc>) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    pub fn with_participant(mut self, participant: impl Participant + 'static) -> Self {
+        self.participants.push(Box::new(participant));
+        self
+    }
+
+    /// Analysis, gamification and AI stand-ins plus the real figurine consumer
+    pub fn with_default_modules(self) -> Self {
+        let bus: Arc<dyn EventBusTrait> = self.bus.clone();
+        self.with_participant(RuleBasedAnalysis::default())
+            .with_participant(InterventionPolicy::default())
+            .with_participant(TemplateResponder)
+            .with_participant(FigurineParticipant::new(bus))
+    }
+
+    /// The bus the scenario runs on, for extra assertions after `run`
+    pub fn bus(&self) -> Arc<TestEventBus> {
+        self.bus.clone()
+    }
+
+    /// Subscribe every participant and announce it ready; must come first
+    pub fn start_system(mut self) -> Self {
+        self.steps.push(Step::Start);
+        self
+    }
+
+    /// Produce `behavior` from now for `duration`, alongside the following steps
+    pub fn inject(mut self, behavior: Behavior, duration: Duration) -> Self {
+        self.steps.push(Step::Inject { behavior, duration });
+        self
+    }
+
+    pub fn wait(mut self, duration: Duration) -> Self {
+        self.steps.push(Step::Wait(duration));
+        self
+    }
+
+    pub fn simulate(mut self, action: UserAction) -> Self {
+        self.steps.push(Step::Simulate(action));
+        self
+    }
+
+    /// Fail unless a matching message is published within `within` of this step starting
+    pub fn expect_within(mut self, within: Duration, expect: Expect) -> Self {
+        self.steps.push(Step::ExpectWithin { within, expect });
+        self
+    }
+
+    /// Fail if a matching message is published in the next `duration`
+    pub fn expect_none_for(mut self, duration: Duration, expect: Expect) -> Self {
+        self.steps.push(Step::ExpectNoneFor { duration, expect });
+        self
+    }
+
+    /// Run every step in order, stopping at the first failure
+    pub async fn run(self) -> ScenarioResult<ScenarioReport> {
+        let Scenario { name, bus, tick, start_time, participants, steps } = self;
+        let mut runner = Runner {
+            bus,
+            tick,
+            start_time,
+            participants: participants.into_iter().map(|participant| (participant, None)).collect(),
+            capture: SyntheticCapture::default(),
+            started: false,
+        };
+
+        let mut reports = Vec::with_capacity(steps.len());
+        for (index, step) in steps.into_iter().enumerate() {
+            let description = step.describe();
+            let started = runner.now();
+            runner.run_step(index, &description, step).await?;
+            reports.push(StepReport { description, started, finished: runner.now() });
+        }
+
+        let published = runner.published_since(0).into_iter()
+            .map(|(at, message)| (at, message.message_type(), message.source))
+            .collect();
+        Ok(ScenarioReport { name, steps: reports, published })
+    }
+}
+
+struct Runner {
+    bus: Arc<TestEventBus>,
+    tick: Duration,
+    start_time: DateTime<Utc>,
+    participants: Vec<Subscribed>,
+    capture: SyntheticCapture,
+    started: bool,
+}
+
+impl Runner {
+    fn now(&self) -> Duration {
+        self.bus.clock().now()
+    }
+
+    fn moment(&self) -> Moment {
+        let elapsed = self.now();
+        let time = self.start_time + chrono::Duration::from_std(elapsed).unwrap_or_default();
+        Moment { elapsed, time }
+    }
+
+    async fn run_step(&mut self, step: usize, description: &str, kind: Step) -> ScenarioResult<()> {
+        let invalid = |reason: &str| ScenarioError::InvalidStep {
+            step,
+            description: description.to_string(),
+            reason: reason.to_string(),
+        };
+        match kind {
+            Step::Start if self.started => return Err(invalid("system already started")),
+            Step::Start => return self.start().await,
+            _ if !self.started => return Err(invalid("system not started")),
+            _ => {}
+        }
+
+        match kind {
+            Step::Start => unreachable!("handled above"),
+            Step::Inject { behavior, duration } => {
+                self.capture.inject(behavior, self.now(), duration);
+            }
+            Step::Wait(duration) => {
+                let until = self.now() + duration;
+                while self.now() < until {
+                    self.tick_once().await?;
+                }
+            }
+            Step::Simulate(UserAction::DismissIntervention) => {
+                let request = self.published_since(0).into_iter().rev().find_map(|(_, message)| match message.payload {
+                    MessagePayload::InterventionRequest(request) => Some(request),
+                    _ => None,
+                });
+                let request = request.ok_or_else(|| invalid("no intervention to dismiss"))?;
+                let dismissal = RawEvent {
+                    event_type: INTERVENTION_DISMISSED.to_string(),
+                    data: serde_json::json!({ "request_id": request.request_id.to_string() }),
+                    window_title: None,
+                    timestamp: self.moment().time,
+                };
+                self.publish(ModuleId::CuteFigurine, MessagePayload::RawEvent(dismissal)).await?;
+                self.react().await?;
+            }
+            Step::ExpectWithin { within, expect } => {
+                let (mark, until) = (self.published_count(), self.now() + within);
+                loop {
+                    if self.published_since(mark).iter().any(|(_, message)| expect.matches(message)) {
+                        break;
+                    }
+                    if self.now() >= until {
+                        return Err(ScenarioError::ExpectationNotMet {
+                            step,
+                            description: description.to_string(),
+                            expected: expect.to_string(),
+                            within,
+                            published: self.published_since(mark).into_iter()
+                                .map(|(at, message)| (at, message.message_type()))
+                                .collect(),
+                        });
+                    }
+                    self.tick_once().await?;
+                }
+            }
+            Step::ExpectNoneFor { duration, expect } => {
+                let (mark, until) = (self.published_count(), self.now() + duration);
+                while self.now() < until {
+                    self.tick_once().await?;
+                    if let Some((at, _)) = self.published_since(mark).into_iter().find(|(_, message)| expect.matches(message)) {
+                        return Err(ScenarioError::UnexpectedMessage {
+                            step,
+                            description: description.to_string(),
+                            expected: expect.to_string(),
+                            at,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn start(&mut self) -> ScenarioResult<()> {
+        for (participant, receiver) in &mut self.participants {
+            let subscription_id = self.bus.subscribe(participant.module(), participant.filter(), DeliveryMode::BestEffort).await?;
+            *receiver = self.bus.receiver(subscription_id);
+        }
+        let modules: Vec<ModuleId> = self.participants.iter().map(|(participant, _)| participant.module()).collect();
+        for module in modules {
+            self.publish(module, MessagePayload::ModuleReady(module)).await?;
+        }
+        self.started = true;
+        self.react().await
+    }
+
+    /// Publish the capture events due now, run participants, then move time on by one tick
+    async fn tick_once(&mut self) -> ScenarioResult<()> {
+        let moment = self.moment();
+        for event in self.capture.events_at(moment.elapsed, moment.time) {
+            self.publish(ModuleId::DataCapture, MessagePayload::RawEvent(event)).await?;
+        }
+
+        let mut outgoing = Vec::new();
+        for (participant, _) in &mut self.participants {
+            for payload in participant.tick(moment).await? {
+                outgoing.push((participant.module(), payload));
+            }
+        }
+        for (module, payload) in outgoing {
+            self.publish(module, payload).await?;
+        }
+
+        self.react().await?;
+        self.bus.advance(self.tick);
+        Ok(())
+    }
+
+    /// Deliver to participants until none has anything left to say at this instant
+    async fn react(&mut self) -> ScenarioResult<()> {
+        for _ in 0..MAX_REACTION_ROUNDS {
+            let moment = self.moment();
+            let mut outgoing = Vec::new();
+            for (participant, receiver) in &mut self.participants {
+                let Some(receiver) = receiver else { continue };
+                for message in receiver.try_iter().collect::<Vec<_>>() {
+                    for payload in participant.handle(&message, moment).await? {
+                        outgoing.push((participant.module(), payload));
+                    }
+                }
+            }
+            if outgoing.is_empty() && self.participants.iter().all(|(_, receiver)| receiver.as_ref().is_none_or(Receiver::is_empty)) {
+                return Ok(());
+            }
+            for (module, payload) in outgoing {
+                self.publish(module, payload).await?;
+            }
+        }
+        Err(ScenarioError::Participant {
+            module: ModuleId::EventBus,
+            reason: format!("participants were still reacting after {} rounds", MAX_REACTION_ROUNDS),
+        })
+    }
+
+    async fn publish(&self, module: ModuleId, payload: MessagePayload) -> ScenarioResult<()> {
+        self.bus.publish(BusMessage::new(module, payload)).await?;
+        Ok(())
+    }
+
+    fn published_count(&self) -> usize {
+        self.bus.published_at().len()
+    }
+
+    /// Messages published after the first `mark`, with their virtual times
+    fn published_since(&self, mark: usize) -> Vec<(Duration, BusMessage)> {
+        let times = self.bus.published_at();
+        self.bus.published().into_iter().zip(times).skip(mark).map(|(message, (at, _))| (at, message)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(n: u64) -> Duration {
+        Duration::from_secs(60 * n)
+    }
+
+    #[tokio::test]
+    async fn test_distraction_nudge_dismissal_and_cooldown() {
+        let report = Scenario::new("distraction gets one nudge, then a cooldown")
+            .with_default_modules()
+            .start_system()
+            .inject(Behavior::Distracted, minutes(5))
+            .expect_within(
+                minutes(1),
+                Expect::message(MessageType::StateChange)
+                    .from(ModuleId::AnalysisEngine)
+                    .matching("state is distracted", |message| {
+                        matches!(&message.payload, MessagePayload::StateChange(state) if state.state == "distracted")
+                    }),
+            )
+            .expect_within(minutes(2), Expect::message(MessageType::InterventionRequest).from(ModuleId::Gamification))
+            .simulate(UserAction::DismissIntervention)
+            .expect_none_for(minutes(3), Expect::message(MessageType::InterventionRequest))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.steps.len(), 6);
+        // Distraction is recognised after four window switches, 15s in, and nudged a minute later
+        assert_eq!(report.steps[2].finished, Duration::from_secs(16));
+        assert_eq!(report.steps[3].finished, Duration::from_secs(76));
+        assert_eq!(report.count(MessageType::InterventionRequest), 1);
+        assert_eq!(report.count(MessageType::InterventionResponse), 1);
+        // The real figurine animated both the state change and the AI's reply
+        assert!(report.published.iter().filter(|(_, message_type, source)| {
+            *message_type == MessageType::AnimationCommand && *source == ModuleId::CuteFigurine
+        }).count() >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_name_the_step_and_what_was_published() {
+        let error = Scenario::new("focused work is left alone")
+            .with_default_modules()
+            .start_system()
+            .inject(Behavior::Focused, minutes(5))
+            .expect_within(minutes(2), Expect::message(MessageType::InterventionRequest))
+            .run()
+            .await
+            .unwrap_err();
+        match error {
+            ScenarioError::ExpectationNotMet { step, expected, within, published, .. } => {
+                assert_eq!((step, expected.as_str(), within), (2, "InterventionRequest", minutes(2)));
+                assert!(published.iter().any(|(_, message_type)| *message_type == MessageType::StateChange));
+                assert!(published.iter().all(|(_, message_type)| *message_type != MessageType::InterventionRequest));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let error = Scenario::new("nothing to dismiss")
+            .with_default_modules()
+            .simulate(UserAction::DismissIntervention)
+            .run()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ScenarioError::InvalidStep { step: 0, ref reason, .. } if reason == "system not started"));
+    }
+}
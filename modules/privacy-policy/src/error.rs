@@ -0,0 +1,19 @@
+//! Privacy policy errors
+
+use thiserror::Error;
+
+/// Errors raised while setting up a privacy policy
+#[derive(Error, Debug)]
+pub enum PrivacyPolicyError {
+    #[error("NER model unavailable: {0}")]
+    ModelUnavailable(String),
+
+    #[error("NER model error: {0}")]
+    Model(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result type for privacy policy operations
+pub type PrivacyPolicyResult<T> = std::result::Result<T, PrivacyPolicyError>;
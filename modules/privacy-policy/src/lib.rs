@@ -0,0 +1,27 @@
+//! # Skelly-Jelly Privacy Policy
+//!
+//! The one place that decides what counts as sensitive and how it is hidden.
+//! Defines the sensitive entity kinds and the detectors that find them, the
+//! masking strategies applied to them, and the sensitivity levels content is
+//! classified into. Data capture, storage and the AI integration all build a
+//! [`PrivacyPolicy`] from the same [`PrivacyPolicyConfig`], which the
+//! orchestrator broadcasts under [`PRIVACY_POLICY_KEY`].
+//!
+//! Names, organisations and places come from an on-device NER model when the
+//! `ner` feature is enabled and a model is configured, and from pattern
+//! heuristics otherwise.
+
+pub mod entity;
+pub mod error;
+pub mod masking;
+pub mod ner;
+pub mod policy;
+
+// Re-export public API
+pub use entity::{Entity, EntityDetector, EntityKind, RegexDetector};
+pub use error::{PrivacyPolicyError, PrivacyPolicyResult};
+pub use masking::MaskingStrategy;
+pub use ner::NerConfig;
+#[cfg(feature = "ner")]
+pub use ner::NerDetector;
+pub use policy::{EntityRule, PrivacyPolicy, PrivacyPolicyConfig, SensitivityLevel, PRIVACY_POLICY_KEY};
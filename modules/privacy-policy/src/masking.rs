@@ -0,0 +1,62 @@
+//! How detected entities are rewritten
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::EntityKind;
+
+/// What to put in place of a detected entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskingStrategy {
+    /// Leave the value as it is
+    Keep,
+    /// Replace with a placeholder, e.g. `[EMAIL]`
+    #[default]
+    Redact,
+    /// Keep the last four characters, e.g. `************1111`
+    Partial,
+    /// Replace with a salted hash, so equal values can still be correlated
+    Hash,
+}
+
+impl MaskingStrategy {
+    /// Rewrite one entity's text
+    pub fn apply(self, kind: EntityKind, value: &str, salt: &str) -> String {
+        match self {
+            MaskingStrategy::Keep => value.to_string(),
+            MaskingStrategy::Redact => format!("[{}]", kind.label()),
+            MaskingStrategy::Partial => {
+                let count = value.chars().count();
+                value
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i + 4 < count && c.is_alphanumeric() { '*' } else { c })
+                    .collect()
+            }
+            MaskingStrategy::Hash => {
+                let digest = Sha256::new().chain_update(salt).chain_update(value).finalize();
+                let short: String = digest.iter().take(4).map(|b| format!("{b:02x}")).collect();
+                format!("[{}:{short}]", kind.label())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategies() {
+        let card = "4111 1111 1111 1111";
+        assert_eq!(MaskingStrategy::Keep.apply(EntityKind::CreditCard, card, ""), card);
+        assert_eq!(MaskingStrategy::Redact.apply(EntityKind::CreditCard, card, ""), "[CREDIT_CARD]");
+        assert_eq!(MaskingStrategy::Partial.apply(EntityKind::CreditCard, card, ""), "**** **** **** 1111");
+
+        let hashed = MaskingStrategy::Hash.apply(EntityKind::Email, "a@b.io", "salt");
+        assert!(hashed.starts_with("[EMAIL:") && hashed.len() == "[EMAIL:]".len() + 8);
+        assert_eq!(hashed, MaskingStrategy::Hash.apply(EntityKind::Email, "a@b.io", "salt"));
+        assert_ne!(hashed, MaskingStrategy::Hash.apply(EntityKind::Email, "a@b.io", "pepper"));
+    }
+}
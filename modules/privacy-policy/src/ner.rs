@@ -0,0 +1,382 @@
+//! On-device named entity recognition
+//!
+//! Regexes catch structured values like emails and card numbers but miss
+//! people, organisations and places. When a small token-classification model
+//! (a BERT-style NER exported to ONNX) is installed, [`NerDetector`] finds
+//! those too. The model directory holds:
+//!
+//! - `model.onnx`: takes `input_ids` and `attention_mask` (and `token_type_ids`
+//!   if it declares it) and returns per-token logits
+//! - `vocab.txt`: the WordPiece vocabulary, one token per line
+//! - `labels.txt`: the BIO label of each logit, one per line (`O`, `B-PER`, `I-PER`, ...)
+//!
+//! Inference needs the `ner` feature and the ONNX Runtime library at run
+//! time. Without either, the policy falls back to pattern heuristics.
+
+// Tokenizing and decoding are only reached through the model
+#![cfg_attr(not(feature = "ner"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::entity::{Entity, EntityKind};
+#[cfg(not(feature = "ner"))]
+use crate::error::PrivacyPolicyError;
+use crate::error::PrivacyPolicyResult;
+use crate::EntityDetector;
+
+/// Settings for the NER model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NerConfig {
+    /// Use the model when it can be loaded
+    pub enabled: bool,
+    /// Directory holding `model.onnx`, `vocab.txt` and `labels.txt`
+    pub model_dir: Option<PathBuf>,
+    /// Lowercase words before lookup, for uncased models
+    pub lowercase: bool,
+    /// Longest token window given to the model, including `[CLS]` and `[SEP]`
+    pub max_tokens: usize,
+}
+
+impl Default for NerConfig {
+    fn default() -> Self {
+        Self { enabled: true, model_dir: None, lowercase: false, max_tokens: 128 }
+    }
+}
+
+/// Load the NER model described by `config`
+pub fn load_detector(config: &NerConfig) -> PrivacyPolicyResult<Box<dyn EntityDetector>> {
+    #[cfg(feature = "ner")]
+    {
+        Ok(Box::new(model::NerDetector::load(config)?))
+    }
+    #[cfg(not(feature = "ner"))]
+    {
+        let _ = config;
+        Err(PrivacyPolicyError::ModelUnavailable("built without the `ner` feature".to_string()))
+    }
+}
+
+#[cfg(feature = "ner")]
+pub use model::NerDetector;
+
+#[cfg(feature = "ner")]
+mod model {
+    use super::*;
+    use crate::error::PrivacyPolicyError;
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use std::sync::Mutex;
+
+    /// Finds people, organisations, places and credentials with an ONNX model
+    pub struct NerDetector {
+        session: Mutex<Session>,
+        vocab: WordPiece,
+        labels: Vec<Option<Tag>>,
+        token_type_ids: bool,
+        lowercase: bool,
+        max_tokens: usize,
+    }
+
+    impl NerDetector {
+        /// Load the model, vocabulary and labels from `config.model_dir`
+        pub fn load(config: &NerConfig) -> PrivacyPolicyResult<Self> {
+            if !config.enabled {
+                return Err(PrivacyPolicyError::ModelUnavailable("disabled".to_string()));
+            }
+            let dir = config
+                .model_dir
+                .as_ref()
+                .ok_or_else(|| PrivacyPolicyError::ModelUnavailable("no model_dir configured".to_string()))?;
+            let model_path = dir.join("model.onnx");
+            if !model_path.exists() {
+                return Err(PrivacyPolicyError::ModelUnavailable(format!("{} not found", model_path.display())));
+            }
+
+            let vocab = WordPiece::from_vocab(&std::fs::read_to_string(dir.join("vocab.txt"))?)?;
+            let labels = parse_labels(&std::fs::read_to_string(dir.join("labels.txt"))?);
+            let session = Session::builder()
+                .and_then(|mut builder| builder.commit_from_file(&model_path))
+                .map_err(|e| PrivacyPolicyError::Model(e.to_string()))?;
+            let token_type_ids = session.inputs().iter().any(|input| input.name() == "token_type_ids");
+
+            Ok(Self {
+                session: Mutex::new(session),
+                vocab,
+                labels,
+                token_type_ids,
+                lowercase: config.lowercase,
+                max_tokens: config.max_tokens.max(8),
+            })
+        }
+
+        /// Best tag and its probability for each word of one window
+        fn classify(&self, words: &[Vec<i64>]) -> Result<Vec<(Option<Tag>, f32)>, ort::Error> {
+            let mut ids = vec![self.vocab.cls];
+            let mut first_token = Vec::with_capacity(words.len());
+            for tokens in words {
+                first_token.push(ids.len());
+                ids.extend(tokens);
+            }
+            ids.push(self.vocab.sep);
+
+            let len = ids.len();
+            let input_ids = Tensor::from_array(([1usize, len], ids))?;
+            let attention_mask = Tensor::from_array(([1usize, len], vec![1i64; len]))?;
+            let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+            let outputs = if self.token_type_ids {
+                let token_type_ids = Tensor::from_array(([1usize, len], vec![0i64; len]))?;
+                session.run(ort::inputs![
+                    "input_ids" => input_ids,
+                    "attention_mask" => attention_mask,
+                    "token_type_ids" => token_type_ids,
+                ])?
+            } else {
+                session.run(ort::inputs!["input_ids" => input_ids, "attention_mask" => attention_mask])?
+            };
+
+            let (_, logits) = outputs[0].try_extract_tensor::<f32>()?;
+            let num_labels = logits.len() / len;
+            Ok(first_token
+                .into_iter()
+                .map(|index| {
+                    let (label, probability) = best_label(&logits[index * num_labels..(index + 1) * num_labels]);
+                    (self.labels.get(label).copied().flatten(), probability)
+                })
+                .collect())
+        }
+    }
+
+    impl EntityDetector for NerDetector {
+        fn name(&self) -> &str {
+            "ner"
+        }
+
+        fn detect(&self, text: &str) -> Vec<Entity> {
+            let words = split_words(text);
+            let budget = self.max_tokens - 2;
+            let tokens: Vec<Vec<i64>> = words
+                .iter()
+                .map(|&(start, end)| {
+                    let word = &text[start..end];
+                    let mut ids = if self.lowercase {
+                        self.vocab.tokenize(&word.to_lowercase())
+                    } else {
+                        self.vocab.tokenize(word)
+                    };
+                    ids.truncate(budget);
+                    ids
+                })
+                .collect();
+
+            let mut tags = Vec::with_capacity(words.len());
+            let mut window_start = 0;
+            while window_start < tokens.len() {
+                let mut window_end = window_start;
+                let mut used = 0;
+                while window_end < tokens.len() && used + tokens[window_end].len() <= budget {
+                    used += tokens[window_end].len();
+                    window_end += 1;
+                }
+                match self.classify(&tokens[window_start..window_end]) {
+                    Ok(window_tags) => tags.extend(window_tags),
+                    Err(e) => {
+                        tracing::warn!("NER inference failed: {}", e);
+                        return Vec::new();
+                    }
+                }
+                window_start = window_end;
+            }
+            decode(&words, &tags)
+        }
+    }
+
+    fn best_label(logits: &[f32]) -> (usize, f32) {
+        let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let total: f32 = logits.iter().map(|l| (l - max).exp()).sum();
+        logits
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or((0, 0.0), |(index, l)| (index, (l - max).exp() / total))
+    }
+}
+
+/// Position of a word within an entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Begin,
+    Inside,
+}
+
+type Tag = (Position, EntityKind);
+
+/// WordPiece vocabulary
+struct WordPiece {
+    ids: HashMap<String, i64>,
+    unk: i64,
+    cls: i64,
+    sep: i64,
+}
+
+impl WordPiece {
+    fn from_vocab(vocab: &str) -> PrivacyPolicyResult<Self> {
+        let ids: HashMap<String, i64> = vocab
+            .lines()
+            .enumerate()
+            .map(|(id, token)| (token.trim_end().to_string(), id as i64))
+            .collect();
+        let special = |token: &str| {
+            ids.get(token).copied().ok_or_else(|| {
+                crate::error::PrivacyPolicyError::Model(format!("vocabulary has no {token} token"))
+            })
+        };
+        Ok(Self { unk: special("[UNK]")?, cls: special("[CLS]")?, sep: special("[SEP]")?, ids })
+    }
+
+    /// Greedy longest-match-first split of one word
+    fn tokenize(&self, word: &str) -> Vec<i64> {
+        let mut ids = Vec::new();
+        let mut start = 0;
+        while start < word.len() {
+            let mut end = word.len();
+            let found = loop {
+                let piece = if start == 0 { word[..end].to_string() } else { format!("##{}", &word[start..end]) };
+                if let Some(&id) = self.ids.get(&piece) {
+                    break Some(id);
+                }
+                end = match word[..end].char_indices().next_back() {
+                    Some((index, _)) if index > start => index,
+                    _ => break None,
+                };
+            };
+            match found {
+                Some(id) => {
+                    ids.push(id);
+                    start = end;
+                }
+                None => return vec![self.unk],
+            }
+        }
+        ids
+    }
+}
+
+/// Byte spans of words; punctuation characters are words of their own
+fn split_words(text: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() || c.is_ascii_punctuation() {
+            if let Some(s) = start.take() {
+                words.push((s, index));
+            }
+            if !c.is_whitespace() {
+                words.push((index, index + c.len_utf8()));
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, text.len()));
+    }
+    words
+}
+
+/// Label of each model output, from `labels.txt`; unsupported types map to `None`
+fn parse_labels(labels: &str) -> Vec<Option<Tag>> {
+    labels
+        .lines()
+        .map(|label| {
+            let (position, kind) = label.trim().split_once('-')?;
+            let position = match position {
+                "B" => Position::Begin,
+                "I" => Position::Inside,
+                _ => return None,
+            };
+            let kind = match kind {
+                "PER" | "PERSON" => EntityKind::Person,
+                "ORG" => EntityKind::Org,
+                "LOC" | "LOCATION" | "GPE" | "ADDRESS" => EntityKind::Location,
+                "CRED" | "CREDENTIAL" | "KEY" | "SECRET" => EntityKind::Credential,
+                _ => return None,
+            };
+            Some((position, kind))
+        })
+        .collect()
+}
+
+/// Join tagged words into entities; confidence is the words' mean probability
+fn decode(words: &[(usize, usize)], tags: &[(Option<Tag>, f32)]) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut open: Option<(Entity, usize)> = None;
+    for (&(start, end), &(tag, probability)) in words.iter().zip(tags) {
+        match (tag, open.as_mut()) {
+            (Some((Position::Inside, kind)), Some((entity, count))) if entity.kind == kind => {
+                entity.end = end;
+                entity.confidence += probability;
+                *count += 1;
+            }
+            (Some((_, kind)), _) => {
+                entities.extend(open.take().map(close));
+                open = Some((Entity { kind, start, end, confidence: probability }, 1));
+            }
+            (None, _) => entities.extend(open.take().map(close)),
+        }
+    }
+    entities.extend(open.map(close));
+    entities
+}
+
+fn close((mut entity, count): (Entity, usize)) -> Entity {
+    entity.confidence /= count as f32;
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wordpiece_tokenization() {
+        let vocab = WordPiece::from_vocab("[PAD]\n[UNK]\n[CLS]\n[SEP]\nada\n##love\n##lace\nlove\n").unwrap();
+        assert_eq!((vocab.unk, vocab.cls, vocab.sep), (1, 2, 3));
+        assert_eq!(vocab.tokenize("adalovelace"), vec![4, 5, 6]);
+        assert_eq!(vocab.tokenize("love"), vec![7]);
+        assert_eq!(vocab.tokenize("lovely"), vec![1]);
+        assert!(WordPiece::from_vocab("[PAD]\nada\n").is_err());
+
+        let text = "Hi, Ada Lovelace!";
+        let words: Vec<&str> = split_words(text).into_iter().map(|(s, e)| &text[s..e]).collect();
+        assert_eq!(words, vec!["Hi", ",", "Ada", "Lovelace", "!"]);
+    }
+
+    #[test]
+    fn test_bio_decoding() {
+        let labels = parse_labels("O\nB-PER\nI-PER\nB-ORG\nI-ORG\nB-LOC\nI-LOC\nB-MISC\n");
+        assert_eq!(labels.len(), 8);
+        assert_eq!(labels[0], None);
+        assert_eq!(labels[1], Some((Position::Begin, EntityKind::Person)));
+        assert_eq!(labels[7], None);
+
+        let text = "Ada Lovelace met Babbage at Analytical Engines Ltd";
+        let words = split_words(text);
+        let tag = |index: usize, p: f32| (labels[index], p);
+        let tags = vec![tag(1, 0.9), tag(2, 0.7), tag(0, 0.99), tag(1, 0.8), tag(0, 0.99), tag(3, 0.6), tag(4, 0.8), tag(4, 0.7)];
+        let entities = decode(&words, &tags);
+
+        let found: Vec<(EntityKind, &str)> = entities.iter().map(|e| (e.kind, e.text(text))).collect();
+        assert_eq!(
+            found,
+            vec![
+                (EntityKind::Person, "Ada Lovelace"),
+                (EntityKind::Person, "Babbage"),
+                (EntityKind::Org, "Analytical Engines Ltd"),
+            ]
+        );
+        assert!((entities[0].confidence - 0.8).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,332 @@
+//! The privacy policy and its configuration
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::entity::{resolve_overlaps, Entity, EntityDetector, EntityKind, RegexDetector};
+use crate::ner::{self, NerConfig};
+use crate::MaskingStrategy;
+
+/// Config key under which the orchestrator broadcasts the policy
+pub const PRIVACY_POLICY_KEY: &str = "privacy_policy";
+
+/// How sensitive a piece of content is, least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitivityLevel {
+    /// Safe to capture and store
+    #[default]
+    Public,
+    /// Internal content, basic privacy needed
+    Internal,
+    /// Confidential content, enhanced privacy
+    Confidential,
+    /// Restricted content, maximum privacy or block
+    Restricted,
+}
+
+/// Handling of one entity kind
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EntityRule {
+    pub strategy: MaskingStrategy,
+    /// Detections below this confidence are ignored
+    pub min_confidence: f32,
+}
+
+impl EntityRule {
+    pub fn new(strategy: MaskingStrategy, min_confidence: f32) -> Self {
+        Self { strategy, min_confidence }
+    }
+}
+
+/// Privacy settings shared by every module
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyPolicyConfig {
+    /// Handling per entity kind; kinds without a rule are not detected
+    pub entities: BTreeMap<EntityKind, EntityRule>,
+    /// Salt for [`MaskingStrategy::Hash`]
+    pub hash_salt: String,
+    /// Apps whose content is always confidential (substring match)
+    pub sensitive_apps: Vec<String>,
+    /// Apps whose content must never be captured (substring match)
+    pub blocked_apps: Vec<String>,
+    /// Window title words that mark the content confidential (case-insensitive)
+    pub sensitive_title_keywords: Vec<String>,
+    /// On-device model for names, organisations and places
+    pub ner: NerConfig,
+}
+
+impl Default for PrivacyPolicyConfig {
+    fn default() -> Self {
+        let entities = EntityKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let strategy = match kind {
+                    EntityKind::IpAddress => MaskingStrategy::Hash,
+                    _ => MaskingStrategy::Redact,
+                };
+                // Model and heuristic detections of names and places are less certain
+                let min_confidence = match kind {
+                    EntityKind::Person | EntityKind::Org | EntityKind::Location => 0.6,
+                    _ => 0.5,
+                };
+                (kind, EntityRule::new(strategy, min_confidence))
+            })
+            .collect();
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+
+        Self {
+            entities,
+            hash_salt: String::new(),
+            sensitive_apps: strings(&["1Password", "KeePass", "Bitwarden", "LastPass"]),
+            blocked_apps: strings(&["Keychain Access", "Activity Monitor", "Console"]),
+            sensitive_title_keywords: strings(&[
+                "password",
+                "login",
+                "sign in",
+                "authentication",
+                "2fa",
+                "mfa",
+                "private",
+                "incognito",
+                "confidential",
+                "restricted",
+                "banking",
+                "finance",
+                "medical",
+                "health",
+            ]),
+            ner: NerConfig::default(),
+        }
+    }
+}
+
+/// Detects, masks and classifies sensitive content
+///
+/// Built once per module from the shared [`PrivacyPolicyConfig`]; modules
+/// can add their own [`EntityDetector`]s, but what is masked and how always
+/// comes from the config.
+pub struct PrivacyPolicy {
+    config: PrivacyPolicyConfig,
+    /// Pattern detector, plus the NER model when it loaded
+    builtin: Vec<Box<dyn EntityDetector>>,
+    detectors: Vec<Box<dyn EntityDetector>>,
+    model_loaded: bool,
+}
+
+impl PrivacyPolicy {
+    /// Create a policy with the built-in detectors
+    ///
+    /// Uses the NER model if `config.ner` points at one that loads, and
+    /// pattern heuristics for names and places otherwise.
+    pub fn new(config: PrivacyPolicyConfig) -> Self {
+        let mut policy = Self { config, builtin: Vec::new(), detectors: Vec::new(), model_loaded: false };
+        policy.load_builtin();
+        policy
+    }
+
+    /// Add a detector, e.g. a model-based one
+    pub fn with_detector(mut self, detector: impl EntityDetector + 'static) -> Self {
+        self.detectors.push(Box::new(detector));
+        self
+    }
+
+    pub fn config(&self) -> &PrivacyPolicyConfig {
+        &self.config
+    }
+
+    /// Replace the config, keeping added detectors; the model is reloaded if its settings changed
+    pub fn set_config(&mut self, config: PrivacyPolicyConfig) {
+        let reload = config.ner != self.config.ner;
+        self.config = config;
+        if reload {
+            self.load_builtin();
+        }
+    }
+
+    /// Whether names and places come from the NER model rather than heuristics
+    pub fn uses_model(&self) -> bool {
+        self.model_loaded
+    }
+
+    fn load_builtin(&mut self) {
+        match ner::load_detector(&self.config.ner) {
+            Ok(model) => {
+                self.builtin = vec![Box::new(RegexDetector::new()), model];
+                self.model_loaded = true;
+            }
+            Err(e) => {
+                if self.config.ner.enabled && self.config.ner.model_dir.is_some() {
+                    tracing::warn!("Falling back to pattern heuristics: {}", e);
+                }
+                self.builtin = vec![Box::new(RegexDetector::with_heuristics())];
+                self.model_loaded = false;
+            }
+        }
+    }
+
+    /// Entities the config asks for, in text order and without overlaps
+    pub fn detect(&self, text: &str) -> Vec<Entity> {
+        let found = self
+            .builtin
+            .iter()
+            .chain(&self.detectors)
+            .flat_map(|detector| detector.detect(text))
+            .filter(|entity| {
+                self.config
+                    .entities
+                    .get(&entity.kind)
+                    .is_some_and(|rule| entity.confidence >= rule.min_confidence)
+            })
+            .collect();
+        resolve_overlaps(found)
+    }
+
+    /// Apply each entity's masking strategy to `text`
+    pub fn mask(&self, text: &str) -> String {
+        self.mask_entities(text, &self.detect(text))
+    }
+
+    /// Mask entities found earlier in the same `text`
+    pub fn mask_entities(&self, text: &str, entities: &[Entity]) -> String {
+        let mut masked = String::with_capacity(text.len());
+        let mut last = 0;
+        for entity in entities {
+            if entity.start < last || entity.end > text.len() {
+                continue;
+            }
+            let strategy = self.strategy(entity.kind);
+            masked.push_str(&text[last..entity.start]);
+            masked.push_str(&strategy.apply(entity.kind, entity.text(text), &self.config.hash_salt));
+            last = entity.end;
+        }
+        masked.push_str(&text[last..]);
+        masked
+    }
+
+    /// Masking strategy for an entity kind; kinds without a rule are kept
+    pub fn strategy(&self, kind: EntityKind) -> MaskingStrategy {
+        self.config
+            .entities
+            .get(&kind)
+            .map_or(MaskingStrategy::Keep, |rule| rule.strategy)
+    }
+
+    /// How sensitive content from `app_name` titled `window_title` is
+    pub fn classify(&self, text: &str, app_name: &str, window_title: &str) -> SensitivityLevel {
+        if self.is_blocked_app(app_name) {
+            return SensitivityLevel::Restricted;
+        }
+        if self.is_sensitive_app(app_name) || self.is_sensitive_title(window_title) {
+            return SensitivityLevel::Confidential;
+        }
+        self.detect(text)
+            .iter()
+            .map(|entity| entity.kind.sensitivity())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Whether content from this app must never be captured
+    pub fn is_blocked_app(&self, app_name: &str) -> bool {
+        self.config.blocked_apps.iter().any(|app| app_name.contains(app.as_str()))
+    }
+
+    /// Whether content from this app is always confidential
+    pub fn is_sensitive_app(&self, app_name: &str) -> bool {
+        self.config.sensitive_apps.iter().any(|app| app_name.contains(app.as_str()))
+    }
+
+    /// Whether a window title marks its content confidential
+    pub fn is_sensitive_title(&self, window_title: &str) -> bool {
+        let title = window_title.to_lowercase();
+        self.config
+            .sensitive_title_keywords
+            .iter()
+            .any(|keyword| title.contains(&keyword.to_lowercase()))
+    }
+}
+
+impl Default for PrivacyPolicy {
+    fn default() -> Self {
+        Self::new(PrivacyPolicyConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PassphraseDetector;
+
+    impl EntityDetector for PassphraseDetector {
+        fn name(&self) -> &str {
+            "passphrase"
+        }
+
+        fn detect(&self, text: &str) -> Vec<Entity> {
+            text.find("open sesame")
+                .map(|start| Entity { kind: EntityKind::Credential, start, end: start + 11, confidence: 0.4 })
+                .into_iter()
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_config_drives_masking() {
+        let mut config = PrivacyPolicyConfig::default();
+        config.entities.insert(EntityKind::CreditCard, EntityRule::new(MaskingStrategy::Partial, 0.5));
+        config.entities.remove(&EntityKind::Email);
+        let policy = PrivacyPolicy::new(config).with_detector(PassphraseDetector);
+
+        let text = "Pay 4111 1111 1111 1111, receipt to ada@example.com, ssn 123-45-6789, open sesame";
+        assert_eq!(
+            policy.mask(text),
+            "Pay **** **** **** 1111, receipt to ada@example.com, ssn [SSN], open sesame"
+        );
+
+        // A detection clears the threshold once the rule allows it
+        let mut config = policy.config().clone();
+        config.entities.insert(EntityKind::Credential, EntityRule::new(MaskingStrategy::Redact, 0.3));
+        let mut policy = policy;
+        policy.set_config(config);
+        assert!(policy.mask(text).ends_with("[CREDENTIAL]"));
+    }
+
+    #[test]
+    fn test_classification_and_config_round_trip() {
+        let policy = PrivacyPolicy::default();
+        assert_eq!(policy.classify("Hello world", "TextEdit", "Document"), SensitivityLevel::Public);
+        assert_eq!(policy.classify("mail bob@example.com", "Mail", "Inbox"), SensitivityLevel::Internal);
+        assert_eq!(policy.classify("password: hunter22", "Terminal", "bash"), SensitivityLevel::Confidential);
+        assert_eq!(policy.classify("", "Safari", "Bank Login"), SensitivityLevel::Confidential);
+        assert_eq!(policy.classify("", "Keychain Access", ""), SensitivityLevel::Restricted);
+
+        let json = serde_json::to_value(policy.config()).unwrap();
+        assert_eq!(json["entities"]["credit_card"]["strategy"], "redact");
+        let parsed: PrivacyPolicyConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(&parsed, policy.config());
+
+        // Missing fields fall back to the defaults
+        let partial: PrivacyPolicyConfig = serde_json::from_str(r#"{"hash_salt": "s"}"#).unwrap();
+        assert_eq!(partial.entities, PrivacyPolicyConfig::default().entities);
+    }
+
+    #[test]
+    fn test_ner_falls_back_to_heuristics() {
+        let mut config = PrivacyPolicyConfig::default();
+        config.ner.model_dir = Some("/nonexistent/ner-model".into());
+        let mut policy = PrivacyPolicy::new(config.clone());
+        assert!(!policy.uses_model());
+        assert_eq!(
+            policy.mask("Meeting with Dr. Jane Doe at Acme Widgets Inc"),
+            "Meeting with [PERSON] at [ORG]"
+        );
+
+        // Thresholds apply to heuristic detections too
+        config.entities.insert(EntityKind::Person, EntityRule::new(MaskingStrategy::Redact, 0.9));
+        policy.set_config(config);
+        assert_eq!(policy.mask("Ask Dr. Jane Doe"), "Ask Dr. Jane Doe");
+    }
+}
@@ -4,18 +4,30 @@
 
 use crate::config::AIIntegrationConfig;
 use crate::context::ContextProcessor;
+use crate::context_memory::{ContextEntry, ContextMemory, EmbeddingPersistence};
+use crate::contextual_messaging::MessagePersonalization;
+use crate::daily_summary::DailySummarizer;
+use crate::delivery::DeliveryRouter;
 use crate::error::{AIIntegrationError, Result};
+use crate::guardrails::GuardrailMetrics;
 use crate::llm::LLMManager;
+use crate::offline_responses::{FocusKind, InterventionKind, OfflineResponseLibrary, WorkKind};
 use crate::personality::PersonalityEngine;
 use crate::privacy::PrivacyGuardian;
-use crate::suggestions::{SuggestionGenerator, SuggestionUrgency};
+use crate::profile::{PersonalityProfile, ProfileImport, ProfileSigner};
+use crate::suggestions::{SuggestionGenerator, SuggestionResult, SuggestionUrgency};
 use crate::types::{
     AIIntegration, ExtendedInterventionRequest, ExtendedInterventionResponse,
     PersonalityTraits, CompanionMood, UsageStatistics, HealthStatus,
     GenerationMethod, PrivacyLevel, ServiceStatus
 };
 
-use skelly_jelly_event_bus::message::{InterventionRequest, InterventionResponse, AnimationCommand, ModuleId};
+use skelly_jelly_event_bus::message::{
+    InterventionRequest, InterventionResponse, AnimationCommand, ModuleId, ResponseMetadata,
+    CurrentTask, InterventionDelivered, TaskDeclaration,
+};
+use skelly_jelly_event_bus::{BusMessage, EventBusTrait, MessagePayload};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::Utc;
@@ -27,9 +39,22 @@ pub struct AIIntegrationImpl {
     context_processor: ContextProcessor,
     llm_manager: Arc<LLMManager>,
     suggestion_generator: SuggestionGenerator,
+    offline_responses: OfflineResponseLibrary,
     privacy_guardian: Arc<PrivacyGuardian>,
     personality_engine: Arc<RwLock<PersonalityEngine>>,
+    /// How messages are worded for this user
+    personalization: std::sync::RwLock<MessagePersonalization>,
+    /// Signs exported personality profiles
+    profile_signer: ProfileSigner,
     usage_stats: Arc<RwLock<UsageStatistics>>,
+    daily_summary: Arc<DailySummarizer>,
+    /// Past situations and notes offered to the model as examples
+    context_memory: Arc<ContextMemory>,
+    /// Channels responses are delivered through
+    delivery: DeliveryRouter,
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+    /// Task the user declared they are working on
+    current_task: std::sync::RwLock<Option<TaskDeclaration>>,
     initialized: bool,
 }
 
@@ -50,16 +75,50 @@ impl AIIntegrationImpl {
         let suggestion_generator = SuggestionGenerator::new(
             llm_manager.clone(),
             PersonalityEngine::new(config.personality.traits()),
-        );
+        )
+        .with_guardrails(config.guardrails.clone());
+
+        // Leave half of the model's context for the response, and room for past situations
+        let examples_budget = if config.context_memory.enabled { config.context_memory.max_tokens } else { 0 };
+        let context_processor = ContextProcessor::new()
+            .with_prompt_token_limit((config.local_model.context_length / 2).saturating_sub(examples_budget));
+
+        let profile_signer = config
+            .personality
+            .profile_key_path
+            .as_deref()
+            .map(ProfileSigner::load_or_create)
+            .unwrap_or_else(ProfileSigner::generate)
+            .or_else(|e| {
+                log::warn!("Profile signing key unavailable ({}), using a temporary key", e);
+                ProfileSigner::generate()
+            })
+            .expect("Ed25519 key generation failed");
+
+        let daily_summary = Arc::new(DailySummarizer::new(
+            config.daily_summary.clone(),
+            config.personality.traits(),
+        ));
+
+        let context_memory = Arc::new(ContextMemory::new(config.context_memory.clone()));
+        let delivery = DeliveryRouter::new(config.delivery.clone());
 
         Self {
             config,
-            context_processor: ContextProcessor::new(),
+            context_processor,
             llm_manager,
             suggestion_generator,
+            offline_responses: OfflineResponseLibrary::new(),
             privacy_guardian,
             personality_engine,
+            personalization: std::sync::RwLock::new(MessagePersonalization::default()),
+            profile_signer,
             usage_stats: Arc::new(RwLock::new(UsageStatistics::default())),
+            daily_summary,
+            context_memory,
+            delivery,
+            event_bus: None,
+            current_task: std::sync::RwLock::new(None),
             initialized: false,
         }
     }
@@ -81,6 +140,13 @@ impl AIIntegrationImpl {
             .ok_or(AIIntegrationError::InternalError)?;
         llm_manager.initialize().await?;
 
+        if self.config.context_memory.enabled {
+            match self.context_memory.load().await {
+                Ok(loaded) => log::info!("Restored {} remembered situations and notes", loaded),
+                Err(e) => log::warn!("Context memory unavailable, starting empty: {}", e),
+            }
+        }
+
         self.initialized = true;
         log::info!("AI Integration module initialized successfully");
         
@@ -152,6 +218,192 @@ impl AIIntegrationImpl {
             GenerationMethod::APIFallback { .. } => stats.api_generations += 1,
             GenerationMethod::Template { .. } => stats.template_responses += 1,
             GenerationMethod::Cached { .. } => stats.cached_responses += 1,
+            GenerationMethod::OfflineFallback { .. } => stats.fallback_responses += 1,
+        }
+    }
+
+    /// Publish declared tasks, end-of-day summaries and responses for the figurine on the bus
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.daily_summary = Arc::new(
+            DailySummarizer::new(self.config.daily_summary.clone(), self.config.personality.traits())
+                .with_event_bus(Arc::clone(&event_bus)),
+        );
+        self.delivery = DeliveryRouter::new(self.config.delivery.clone()).with_event_bus(Arc::clone(&event_bus));
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Answer an intervention request and deliver the response through its configured channels
+    ///
+    /// Returns one confirmation per channel, also published on the bus with `with_event_bus`.
+    pub async fn process_and_deliver(&self, request: InterventionRequest) -> Result<Vec<InterventionDelivered>> {
+        let intervention_type = request.intervention_type.clone();
+        let response = self.process_intervention(request).await?;
+        Ok(self.delivery.deliver(&intervention_type, &response).await)
+    }
+
+    /// Keep remembered situations and notes in `persistence`; they are restored on `initialize`
+    pub fn with_context_persistence(mut self, persistence: Arc<dyn EmbeddingPersistence>) -> Self {
+        self.context_memory =
+            Arc::new(ContextMemory::new(self.config.context_memory.clone()).with_persistence(persistence));
+        self
+    }
+
+    /// The user says what they are working on, e.g. "writing report X"
+    ///
+    /// The task goes into prompts in place of the inferred one, and is
+    /// announced on the bus so the analysis engine tags windows with it.
+    pub async fn declare_task(&self, description: &str) -> TaskDeclaration {
+        let task = TaskDeclaration {
+            task_id: Uuid::new_v4(),
+            description: description.trim().to_string(),
+            declared_at: Utc::now(),
+        };
+        self.apply_current_task(Some(task.clone()));
+        self.publish_current_task(Some(task.clone())).await;
+        task
+    }
+
+    /// The user is done with their declared task
+    pub async fn clear_task(&self) {
+        self.apply_current_task(None);
+        self.publish_current_task(None).await;
+    }
+
+    /// Task the user declared, if any
+    pub fn current_task(&self) -> Option<TaskDeclaration> {
+        self.current_task.read().unwrap().clone()
+    }
+
+    /// Take a task declared elsewhere, e.g. from a `CurrentTask` message sent by the UI
+    pub fn apply_current_task(&self, task: Option<TaskDeclaration>) {
+        self.context_processor.declare_task(task.as_ref().map(|task| task.description.as_str()));
+        *self.current_task.write().unwrap() = task;
+    }
+
+    async fn publish_current_task(&self, task: Option<TaskDeclaration>) {
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        let payload = MessagePayload::CurrentTask(CurrentTask { task, timestamp: Utc::now() });
+        if let Err(e) = event_bus.publish(BusMessage::new(ModuleId::AiIntegration, payload)).await {
+            log::warn!("Failed to publish current task: {}", e);
+        }
+    }
+
+    /// End-of-day summaries; tick it periodically and route `DayActivity` to it
+    pub fn daily_summary(&self) -> Arc<DailySummarizer> {
+        Arc::clone(&self.daily_summary)
+    }
+
+    /// How often generated suggestions were regenerated or replaced by templates
+    pub fn guardrail_metrics(&self) -> GuardrailMetrics {
+        self.suggestion_generator.guardrail_metrics()
+    }
+
+    /// Record whether the user acted on a suggestion, so later prompts know what helped
+    pub fn record_intervention_outcome(&self, request_id: Uuid, accepted: bool) {
+        if accepted {
+            self.daily_summary.record_accepted(Utc::now());
+        }
+        if !self.context_processor.record_suggestion_outcome(request_id, accepted) {
+            log::debug!("Suggestion {} already summarized, outcome not recorded", request_id);
+        }
+        if self.config.context_memory.enabled {
+            let memory = Arc::clone(&self.context_memory);
+            tokio::spawn(async move {
+                if let Err(e) = memory.record_outcome(request_id, accepted).await {
+                    log::warn!("Failed to remember outcome of suggestion {}: {}", request_id, e);
+                }
+            });
+        }
+    }
+
+    /// Remember a note from the user, e.g. "short walks help more than coffee"
+    ///
+    /// Notes similar to a later situation are shown to the model with it.
+    pub async fn add_note(&self, text: &str) -> Result<Uuid> {
+        let note = ContextEntry::note(text.trim());
+        let id = note.id;
+        self.context_memory.remember(note).await?;
+        Ok(id)
+    }
+
+    /// Remembered situations and notes
+    pub fn context_memory(&self) -> Arc<ContextMemory> {
+        Arc::clone(&self.context_memory)
+    }
+
+    /// Current message personalization
+    pub fn personalization(&self) -> MessagePersonalization {
+        self.personalization.read().unwrap().clone()
+    }
+
+    /// Replace the message personalization
+    pub fn update_personalization(&self, personalization: MessagePersonalization) {
+        *self.personalization.write().unwrap() = personalization;
+    }
+
+    /// Personality traits and personalization as a signed JSON profile
+    pub async fn export_profile(&self) -> Result<String> {
+        let profile = PersonalityProfile {
+            traits: self.personality_engine.read().await.get_current_state().traits,
+            personalization: self.personalization(),
+            exported_at: Utc::now(),
+        };
+        self.profile_signer.export(&profile)
+    }
+
+    /// Write the signed profile to `path`
+    pub async fn export_profile_to(&self, path: &Path) -> Result<()> {
+        let json = self.export_profile().await?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    /// Verify a signed profile, migrate it to the current format and apply it
+    ///
+    /// Nothing is applied unless the signature checks out and the version is supported.
+    pub async fn import_profile(&self, json: &str) -> Result<ProfileImport> {
+        let imported = self.profile_signer.import(json)?;
+        self.update_personality(imported.profile.traits.clone()).await?;
+        self.update_personalization(imported.profile.personalization.clone());
+        if let Some(version) = imported.migrated_from {
+            log::info!("Migrated personality profile from version {}", version);
+        }
+        Ok(imported)
+    }
+
+    /// Read a profile from `path` and apply it
+    pub async fn import_profile_from(&self, path: &Path) -> Result<ProfileImport> {
+        let json = tokio::fs::read_to_string(path).await?;
+        self.import_profile(&json).await
+    }
+
+    /// Apply the privacy policy broadcast by the orchestrator to prompt sanitization
+    pub fn apply_privacy_policy(&self, policy: skelly_jelly_privacy_policy::PrivacyPolicyConfig) {
+        self.privacy_guardian.set_policy(policy);
+    }
+
+    /// Build a suggestion from the offline library when no model could answer
+    fn offline_suggestion(&self, request: &ExtendedInterventionRequest, error: &AIIntegrationError) -> SuggestionResult {
+        let fallback = self.offline_responses.respond(
+            InterventionKind::from_name(&request.base.intervention_type),
+            WorkKind::from(&request.work_context.work_type),
+            FocusKind::from(&request.current_state.state_type),
+        );
+        log::warn!("Model unavailable ({}), using offline response {}", error, fallback.entry_id);
+
+        SuggestionResult {
+            text: fallback.text,
+            method: GenerationMethod::OfflineFallback {
+                entry_id: fallback.entry_id,
+                reason: error.to_string(),
+            },
+            confidence: 0.5,
+            animation_hints: fallback.animation_cues,
+            follow_up_available: false,
+            tokens_used: None,
         }
     }
 
@@ -277,7 +529,7 @@ impl AIIntegration for AIIntegrationImpl {
         let allow_api = self.allow_api_usage(&extended_request);
 
         // Build context for AI generation
-        let context = self.context_processor.build_context(
+        let mut context = self.context_processor.build_context(
             &extended_request.base.intervention_type,
             &extended_request.current_state,
             &extended_request.state_history,
@@ -286,24 +538,56 @@ impl AIIntegration for AIIntegrationImpl {
             &extended_request.user_preferences,
         ).await?;
 
-        // Generate suggestion
-        let suggestion_result = self.suggestion_generator.generate(
+        // Show the model what happened in similar situations before
+        let situation = format!(
+            "{:?} state, {} intervention: {}",
+            extended_request.current_state.state_type, context.intervention_type, context.work_context
+        );
+        if self.config.context_memory.enabled {
+            context.past_situations = self.context_memory.examples_for(&situation).await;
+        }
+
+        // Generate suggestion, using an offline response if no model is available
+        let suggestion_result = match self.suggestion_generator.generate(
             context,
             urgency,
             allow_api,
-        ).await?;
+        ).await {
+            Ok(result) => result,
+            Err(e) if e.is_model_unavailable() => self.offline_suggestion(&extended_request, &e),
+            Err(e) => return Err(e),
+        };
 
         // Update usage statistics
         self.update_usage_stats(&suggestion_result.method, suggestion_result.tokens_used).await;
 
+        // Keep the suggestion in the session history for later prompts
+        self.context_processor.record_suggestion(extended_request.base.request_id, &suggestion_result.text);
+        if self.config.context_memory.enabled {
+            let entry = ContextEntry::intervention(extended_request.base.request_id, &situation, &suggestion_result.text);
+            if let Err(e) = self.context_memory.remember(entry).await {
+                log::warn!("Failed to remember intervention {}: {}", extended_request.base.request_id, e);
+            }
+        }
+
         // Create animation cues from hints
         let animation_cues: Vec<String> = suggestion_result.animation_hints;
 
+        // Flag offline responses so the UI can adapt
+        let metadata = match &suggestion_result.method {
+            GenerationMethod::OfflineFallback { reason, .. } => ResponseMetadata {
+                fallback: true,
+                fallback_reason: Some(reason.clone()),
+            },
+            _ => ResponseMetadata::default(),
+        };
+
         // Build response
         let response = InterventionResponse {
             request_id: extended_request.base.request_id,
             response_text: suggestion_result.text,
             animation_cues,
+            metadata,
         };
 
         // Log successful processing
@@ -401,6 +685,7 @@ impl AIIntegration for AIIntegrationImpl {
             api_generations: stats.api_generations,
             template_responses: stats.template_responses,
             cached_responses: stats.cached_responses,
+            fallback_responses: stats.fallback_responses,
             average_response_time_ms: avg_response_time,
             total_tokens_used: stats.total_tokens_used,
             total_cost_usd: 0.0, // Would be calculated from API usage
@@ -535,6 +820,26 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_profile_export_import() {
+        let ai = AIIntegrationImpl::new(AIIntegrationConfig::default());
+        ai.update_personality(PersonalityTraits { humor: 0.9, ..Default::default() }).await.unwrap();
+        ai.update_personalization(MessagePersonalization {
+            blocked_phrases: vec!["crushing it".to_string()],
+            ..Default::default()
+        });
+        let exported = ai.export_profile().await.unwrap();
+
+        let fresh = AIIntegrationImpl::new(AIIntegrationConfig::default());
+        let imported = fresh.import_profile(&exported).await.unwrap();
+        assert!(!imported.signed_by_this_install);
+        assert_eq!(fresh.personalization().blocked_phrases, vec!["crushing it"]);
+        let traits = fresh.personality_engine.read().await.get_current_state().traits;
+        assert_eq!(traits.humor, 0.9);
+
+        assert!(fresh.import_profile(&exported.replace("0.9", "0.1")).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_usage_stats() {
         let config = AIIntegrationConfig::default();
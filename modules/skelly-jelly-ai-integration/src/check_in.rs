@@ -0,0 +1,319 @@
+//! Emotional check-ins
+//!
+//! Every so often Skelly asks a one-tap "How's it going?" and offers four
+//! answers. The answer does two things:
+//! - It becomes a [`CheckInResponse`] on the bus. The analysis engine uses it
+//!   as a labeled example of the user's actual state.
+//! - It sets the [`DayStyle`] of interventions for the rest of the local day.
+//!   For example, "struggling" softens the messages.
+//!
+//! Check-ins are rare by design: a few per day at most, spaced out, only
+//! during active hours, and never while the user is in flow or hyperfocus.
+
+use crate::contextual_messaging::{MessagePersonalization, MessageTone};
+use crate::intervention_timing::FocusState;
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::message::CheckInResponse;
+use uuid::Uuid;
+
+/// How often check-ins may be asked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckInConfig {
+    pub enabled: bool,
+    /// Minimum time between two check-ins
+    pub min_interval_minutes: u32,
+    pub max_per_day: u32,
+    /// Local hours check-ins may be asked in, (start_hour, end_hour) in 24h format
+    pub active_hours: (u32, u32),
+    /// An unanswered check-in is dropped after this long
+    pub expire_after_minutes: u32,
+}
+
+impl Default for CheckInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_minutes: 180,
+            max_per_day: 2,
+            active_hours: (9, 20),
+            expire_after_minutes: 10,
+        }
+    }
+}
+
+/// One-tap answers to "How's it going?"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckInAnswer {
+    Great,
+    Okay,
+    Struggling,
+    Overwhelmed,
+}
+
+impl CheckInAnswer {
+    pub const ALL: [CheckInAnswer; 4] = [
+        CheckInAnswer::Great,
+        CheckInAnswer::Okay,
+        CheckInAnswer::Struggling,
+        CheckInAnswer::Overwhelmed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckInAnswer::Great => "great",
+            CheckInAnswer::Okay => "okay",
+            CheckInAnswer::Struggling => "struggling",
+            CheckInAnswer::Overwhelmed => "overwhelmed",
+        }
+    }
+
+    /// Analysis engine state the answer labels, and how sure the label is
+    pub fn user_state(&self) -> (&'static str, f32) {
+        match self {
+            CheckInAnswer::Great => ("flow", 0.7),
+            CheckInAnswer::Okay => ("neutral", 0.6),
+            CheckInAnswer::Struggling => ("distracted", 0.7),
+            // Overwhelmed is not always distracted, but it is never focused
+            CheckInAnswer::Overwhelmed => ("distracted", 0.5),
+        }
+    }
+
+    pub fn day_style(&self) -> DayStyle {
+        match self {
+            CheckInAnswer::Great | CheckInAnswer::Okay => DayStyle::Usual,
+            CheckInAnswer::Struggling => DayStyle::Gentle,
+            CheckInAnswer::Overwhelmed => DayStyle::Light,
+        }
+    }
+}
+
+/// How interventions sound for the rest of the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayStyle {
+    /// The user's own preferences
+    Usual,
+    /// Softer and more encouraging
+    Gentle,
+    /// As little pressure as possible: gentle, simple, no jokes
+    Light,
+}
+
+impl DayStyle {
+    /// `base` adjusted for the style
+    pub fn adapt(&self, base: &MessagePersonalization) -> MessagePersonalization {
+        let mut personalization = base.clone();
+        match self {
+            DayStyle::Usual => {}
+            DayStyle::Gentle => {
+                personalization.preferred_tone = MessageTone::Gentle;
+                personalization.directness = base.directness.min(0.4);
+                personalization.encouragement_frequency = base.encouragement_frequency.max(0.9);
+            }
+            DayStyle::Light => {
+                personalization.preferred_tone = MessageTone::Gentle;
+                personalization.humor_level = 0.0;
+                personalization.directness = base.directness.min(0.2);
+                personalization.technical_level = base.technical_level.min(0.3);
+                personalization.encouragement_frequency = 1.0;
+            }
+        }
+        personalization
+    }
+}
+
+/// A check-in waiting for an answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckInPrompt {
+    pub check_in_id: Uuid,
+    pub question: String,
+    pub answers: Vec<CheckInAnswer>,
+    pub asked_at: DateTime<Utc>,
+}
+
+/// Decides when to check in and remembers the day's answer
+pub struct CheckInScheduler {
+    config: CheckInConfig,
+    pending: Option<CheckInPrompt>,
+    last_asked: Option<DateTime<Utc>>,
+    /// Check-ins asked on the local day
+    asked_today: (NaiveDate, u32),
+    /// Style set by the last answer, for the day it was given
+    style: Option<(NaiveDate, DayStyle)>,
+}
+
+impl CheckInScheduler {
+    pub fn new(config: CheckInConfig) -> Self {
+        Self {
+            config,
+            pending: None,
+            last_asked: None,
+            asked_today: (NaiveDate::MIN, 0),
+            style: None,
+        }
+    }
+
+    /// A new check-in if one is due at `now`
+    ///
+    /// The returned prompt is pending until answered or expired. No other
+    /// check-in is asked while it is pending.
+    pub fn due(&mut self, now: DateTime<Utc>, focus_state: &FocusState) -> Option<CheckInPrompt> {
+        if !self.config.enabled || !self.in_active_hours(now) {
+            return None;
+        }
+        if matches!(focus_state, FocusState::Flow { .. } | FocusState::Hyperfocus { .. }) {
+            return None;
+        }
+        if let Some(pending) = &self.pending {
+            if now - pending.asked_at < Duration::minutes(self.config.expire_after_minutes as i64) {
+                return None;
+            }
+            self.pending = None;
+        }
+        if self.last_asked.is_some_and(|last| now - last < Duration::minutes(self.config.min_interval_minutes as i64)) {
+            return None;
+        }
+        let today = local_day(now);
+        if self.asked_today.0 == today && self.asked_today.1 >= self.config.max_per_day {
+            return None;
+        }
+
+        let prompt = CheckInPrompt {
+            check_in_id: Uuid::new_v4(),
+            question: "How's it going?".to_string(),
+            answers: CheckInAnswer::ALL.to_vec(),
+            asked_at: now,
+        };
+        self.asked_today = if self.asked_today.0 == today { (today, self.asked_today.1 + 1) } else { (today, 1) };
+        self.last_asked = Some(now);
+        self.pending = Some(prompt.clone());
+        Some(prompt)
+    }
+
+    /// Record the answer to the pending check-in
+    ///
+    /// Returns the labeled response for the analysis engine, or `None` if
+    /// `check_in_id` isn't the pending check-in (already answered or expired).
+    pub fn respond(
+        &mut self,
+        check_in_id: Uuid,
+        answer: CheckInAnswer,
+        window_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Option<CheckInResponse> {
+        let pending = self.pending.take_if(|pending| pending.check_in_id == check_in_id)?;
+        if now - pending.asked_at >= Duration::minutes(self.config.expire_after_minutes as i64) {
+            return None;
+        }
+
+        self.style = Some((local_day(now), answer.day_style()));
+        let (user_state, confidence) = answer.user_state();
+        Some(CheckInResponse {
+            check_in_id,
+            answer: answer.as_str().to_string(),
+            user_state: user_state.to_string(),
+            confidence,
+            window_id,
+            timestamp: now,
+        })
+    }
+
+    /// Style for interventions at `now`; answers only last for their day
+    pub fn day_style(&self, now: DateTime<Utc>) -> DayStyle {
+        match self.style {
+            Some((day, style)) if day == local_day(now) => style,
+            _ => DayStyle::Usual,
+        }
+    }
+
+    pub fn pending(&self) -> Option<&CheckInPrompt> {
+        self.pending.as_ref()
+    }
+
+    fn in_active_hours(&self, now: DateTime<Utc>) -> bool {
+        let hour = now.with_timezone(&Local).hour();
+        let (start, end) = self.config.active_hours;
+        if start <= end {
+            hour >= start && hour < end
+        } else {
+            hour >= start || hour < end
+        }
+    }
+}
+
+fn local_day(now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&Local).date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn morning() -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn focused() -> FocusState {
+        FocusState::Focused { concentration: 0.5 }
+    }
+
+    #[test]
+    fn test_check_ins_are_spaced_capped_and_skip_flow() {
+        let mut scheduler = CheckInScheduler::new(CheckInConfig::default());
+        let start = morning();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+
+        let flow = FocusState::Flow { depth: 0.8, stability: 0.9 };
+        assert!(scheduler.due(at(0), &flow).is_none());
+
+        let first = scheduler.due(at(0), &focused()).unwrap();
+        assert_eq!(first.answers.len(), 4);
+        // Pending, then too soon after the last one
+        assert!(scheduler.due(at(5), &focused()).is_none());
+        assert!(scheduler.due(at(60), &focused()).is_none());
+        assert!(scheduler.pending().is_none());
+
+        // The expired check-in can no longer be answered
+        assert!(scheduler.respond(first.check_in_id, CheckInAnswer::Great, None, at(61)).is_none());
+
+        assert!(scheduler.due(at(180), &focused()).is_some());
+        scheduler.pending = None;
+        // Two a day at most
+        assert!(scheduler.due(at(360), &focused()).is_none());
+        // And none after active hours
+        let evening = Local.with_ymd_and_hms(2026, 3, 2, 21, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(CheckInScheduler::new(CheckInConfig::default()).due(evening, &focused()).is_none());
+    }
+
+    #[test]
+    fn test_answer_labels_state_and_sets_the_days_style() {
+        let mut scheduler = CheckInScheduler::new(CheckInConfig::default());
+        let start = morning();
+        let window_id = Uuid::new_v4();
+
+        let prompt = scheduler.due(start, &focused()).unwrap();
+        let response = scheduler
+            .respond(prompt.check_in_id, CheckInAnswer::Struggling, Some(window_id), start + Duration::minutes(1))
+            .unwrap();
+        assert_eq!(response.answer, "struggling");
+        assert_eq!(response.user_state, "distracted");
+        assert_eq!(response.window_id, Some(window_id));
+        // Each check-in is answered once
+        assert!(scheduler.respond(prompt.check_in_id, CheckInAnswer::Great, None, start).is_none());
+
+        assert_eq!(scheduler.day_style(start + Duration::hours(3)), DayStyle::Gentle);
+        assert_eq!(scheduler.day_style(start + Duration::days(1)), DayStyle::Usual);
+
+        let base = MessagePersonalization::default();
+        let gentle = DayStyle::Gentle.adapt(&base);
+        assert!(matches!(gentle.preferred_tone, MessageTone::Gentle));
+        assert!(gentle.directness < base.directness);
+        let light = DayStyle::Light.adapt(&base);
+        assert_eq!(light.humor_level, 0.0);
+        assert_eq!(light.blocked_phrases, base.blocked_phrases);
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! Provides secure, privacy-focused configuration with sensible defaults.
 
+use crate::context_memory::ContextMemoryConfig;
+use crate::daily_summary::DailySummaryConfig;
+use crate::delivery::DeliveryConfig;
+use crate::guardrails::GuardrailConfig;
+use crate::model_manager::ModelManagerConfig;
 use crate::types::{ModelVariant, UserPrivacyLevel, APIConsent};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -9,6 +14,7 @@ use std::time::Duration;
 
 /// Main configuration for AI Integration module
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AIIntegrationConfig {
     /// Local model settings
     pub local_model: LocalModelSettings,
@@ -27,6 +33,18 @@ pub struct AIIntegrationConfig {
     
     /// Template system settings
     pub templates: TemplateSettings,
+
+    /// End-of-day summary schedule and history
+    pub daily_summary: DailySummaryConfig,
+
+    /// Checks applied to generated suggestions before they are shown
+    pub guardrails: GuardrailConfig,
+
+    /// Past situations and notes retrieved as examples for the model
+    pub context_memory: ContextMemoryConfig,
+
+    /// How responses reach the user: figurine, notification or speech
+    pub delivery: DeliveryConfig,
 }
 
 impl Default for AIIntegrationConfig {
@@ -38,18 +56,26 @@ impl Default for AIIntegrationConfig {
             performance: PerformanceSettings::default(),
             personality: PersonalityConfig::default(),
             templates: TemplateSettings::default(),
+            daily_summary: DailySummaryConfig::default(),
+            guardrails: GuardrailConfig::default(),
+            context_memory: ContextMemoryConfig::default(),
+            delivery: DeliveryConfig::default(),
         }
     }
 }
 
 /// Local model configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LocalModelSettings {
     /// Path to the model file (GGUF format)
     pub model_path: Option<PathBuf>,
     
     /// Automatically download model if missing
     pub auto_download: bool,
+
+    /// Where models are downloaded from and stored when `auto_download` is set
+    pub downloads: ModelManagerConfig,
     
     /// Which model variant to use
     pub model_variant: ModelVariant,
@@ -93,6 +119,7 @@ impl Default for LocalModelSettings {
         Self {
             model_path: None,
             auto_download: false, // Security: Don't auto-download by default
+            downloads: ModelManagerConfig::default(),
             model_variant: ModelVariant::Phi3Mini, // Smaller, faster model
             max_memory_gb: 4.0,
             use_gpu: true,
@@ -162,6 +189,7 @@ impl Default for APIConfig {
 
 /// Privacy settings and controls
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PrivacySettings {
     /// Default privacy level
     pub default_privacy_level: UserPrivacyLevel,
@@ -274,6 +302,10 @@ pub struct PersonalityConfig {
     
     /// Enable personality learning from user feedback
     pub personality_learning: bool,
+
+    /// Key exported personality profiles are signed with; a new key per run if unset
+    #[serde(default)]
+    pub profile_key_path: Option<PathBuf>,
 }
 
 impl Default for PersonalityConfig {
@@ -285,6 +317,7 @@ impl Default for PersonalityConfig {
             preferred_message_length: MessageLength::Brief,
             tone_consistency: true,
             personality_learning: false, // Privacy consideration
+            profile_key_path: None,
         }
     }
 }
@@ -4,11 +4,18 @@
 //! relevant context for AI generation.
 
 use crate::error::{AIIntegrationError, Result};
+use crate::session_context::{estimate_tokens, SessionContext};
 use crate::types::{
     WorkContext, WorkType, BehavioralMetrics, ADHDState, LLMContext, 
     UserPreferences, TaskCategory, UrgencyLevel
 };
 use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Tokens reserved for the prompt scaffolding around the context
+/// (section headings, intervention type, style and the final instruction)
+const PROMPT_OVERHEAD_TOKENS: usize = 64;
 
 /// Processes and analyzes context for AI generation
 pub struct ContextProcessor {
@@ -16,6 +23,8 @@ pub struct ContextProcessor {
     behavioral_builder: BehavioralContextBuilder,
     context_compressor: ContextCompressor,
     privacy_filter: PrivacyFilter,
+    session: Mutex<SessionContext>,
+    prompt_token_limit: usize,
 }
 
 impl ContextProcessor {
@@ -25,9 +34,32 @@ impl ContextProcessor {
             behavioral_builder: BehavioralContextBuilder::new(),
             context_compressor: ContextCompressor::new(),
             privacy_filter: PrivacyFilter::new(),
+            session: Mutex::new(SessionContext::default()),
+            prompt_token_limit: 1024,
         }
     }
 
+    /// Limit the whole prompt, system prompt included, to `limit` tokens
+    pub fn with_prompt_token_limit(mut self, limit: usize) -> Self {
+        self.prompt_token_limit = limit;
+        self
+    }
+
+    /// Remember a suggestion so later prompts know what was already offered
+    pub fn record_suggestion(&self, request_id: Uuid, text: &str) {
+        self.session.lock().unwrap().record_suggestion(request_id, text);
+    }
+
+    /// Use the user's own description of their task in prompts; `None` goes back to inferring it
+    pub fn declare_task(&self, description: Option<&str>) {
+        self.session.lock().unwrap().declare_task(description);
+    }
+
+    /// Record whether the user accepted a suggestion
+    pub fn record_suggestion_outcome(&self, request_id: Uuid, accepted: bool) -> bool {
+        self.session.lock().unwrap().record_outcome(request_id, accepted)
+    }
+
     /// Build comprehensive context for LLM generation
     pub async fn build_context(
         &self,
@@ -54,20 +86,43 @@ impl ContextProcessor {
         // Determine max tokens based on complexity and user preferences
         let max_tokens = self.calculate_token_budget(intervention_type, &work_analysis);
 
+        // Whatever the system prompt and scaffolding leave of the prompt limit
+        let system_prompt = self.build_system_prompt(user_preferences);
+        let context_budget = self
+            .prompt_token_limit
+            .saturating_sub(estimate_tokens(&system_prompt) + PROMPT_OVERHEAD_TOKENS);
+
+        // Add the rolling session history in the behavioral share of the budget
+        let behavioral_summary = {
+            let mut session = self.session.lock().unwrap();
+            session.record_state(current_state);
+            session.set_task(&filtered_context.task_description);
+
+            let session_budget = (context_budget as f32 * 0.6) as usize;
+            let session_budget = session_budget.saturating_sub(estimate_tokens(&behavioral_summary) + 1);
+            let history = session.render(session_budget);
+            if history.is_empty() {
+                behavioral_summary
+            } else {
+                format!("{}. {}", behavioral_summary, history)
+            }
+        };
+
         // Convert work analysis to string for compression
         let work_context_text = format!("{}: {}", filtered_context.task_description, filtered_context.relevant_context);
 
-        // Compress to fit token budget
+        // Compress to fit the prompt limit
         let compressed = self.context_compressor.compress(
             &behavioral_summary,
             &work_context_text,
-            max_tokens,
+            context_budget,
         )?;
 
         Ok(LLMContext {
-            system_prompt: self.build_system_prompt(user_preferences),
+            system_prompt,
             behavioral_context: compressed.behavioral,
             work_context: compressed.work,
+            past_situations: String::new(),
             intervention_type: intervention_type.to_string(),
             user_preferences: user_preferences.clone(),
             max_tokens,
@@ -460,9 +515,6 @@ impl ContextCompressor {
         work: &str,
         max_tokens: usize,
     ) -> Result<CompressedContext> {
-        // Estimate tokens (rough approximation: 1 token ≈ 4 characters)
-        let estimate_tokens = |text: &str| text.len() / 4;
-
         let behavioral_tokens = estimate_tokens(behavioral);
         let work_tokens = estimate_tokens(work);
         let total_tokens = behavioral_tokens + work_tokens;
@@ -508,11 +560,11 @@ impl ContextCompressor {
         let mut result = String::new();
 
         for sentence in sentences {
-            if result.len() + sentence.len() + 1 <= target_chars {
-                if !result.is_empty() {
-                    result.push_str(". ");
-                }
-                result.push_str(sentence.trim());
+            let sentence = sentence.trim();
+            let separator = if result.is_empty() { "" } else { ". " };
+            if result.len() + separator.len() + sentence.len() <= target_chars {
+                result.push_str(separator);
+                result.push_str(sentence);
             } else {
                 break;
             }
@@ -691,4 +743,67 @@ mod tests {
         assert!(summary.contains("80%"));
         assert!(summary.contains("Flow"));
     }
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_long_session_prompt_stays_under_limit() {
+        let limit = 1024;
+        let processor = ContextProcessor::new().with_prompt_token_limit(limit);
+        let metrics = BehavioralMetrics {
+            productive_time_ratio: 0.6,
+            distraction_frequency: 0.3,
+            focus_session_count: 12,
+            average_session_length: 1500,
+            recovery_time: 400,
+            transition_smoothness: 0.6,
+        };
+        let preferences = UserPreferences {
+            intervention_frequency: InterventionFrequency::Moderate,
+            message_style: MessageStyle::Informative,
+            privacy_level: UserPrivacyLevel::LocalOnly,
+            personality_traits: Default::default(),
+            api_consent: APIConsent {
+                openai_allowed: false,
+                anthropic_allowed: false,
+                consent_timestamp: None,
+                monthly_limit_usd: None,
+            },
+        };
+
+        let mut history = Vec::new();
+        for i in 0..300 {
+            let state = ADHDState {
+                state_type: if i % 4 == 0 { ADHDStateType::Distracted { severity: 0.6 } } else { ADHDStateType::Flow { depth: 0.7 } },
+                confidence: 0.8,
+                depth: None,
+                duration: 60_000,
+                metadata: HashMap::new(),
+            };
+            let work_context = WorkContext {
+                work_type: WorkType::Coding { language: "rust".to_string(), framework: None },
+                application: "vscode".to_string(),
+                window_title: format!("module_{}.rs - long running project", i % 9),
+                screenshot_text: Some("fn main() { let session = Session::new(); session.run(); } ".repeat(20)),
+                task_category: TaskCategory::Work,
+                urgency: UrgencyLevel::Medium,
+                time_of_day: crate::types::TimeOfDay::Afternoon,
+            };
+            history.push(state.clone());
+
+            let context = processor
+                .build_context("suggestion", &state, &history, &metrics, &work_context, &preferences)
+                .await
+                .unwrap();
+            let request_id = Uuid::new_v4();
+            processor.record_suggestion(request_id, "Try stepping through the failing branch with a debugger");
+            processor.record_suggestion_outcome(request_id, i % 2 == 0);
+
+            let prompt_tokens = estimate_tokens(&context.system_prompt)
+                + estimate_tokens(&context.behavioral_context)
+                + estimate_tokens(&context.work_context)
+                + PROMPT_OVERHEAD_TOKENS;
+            assert!(prompt_tokens <= limit, "prompt of {} tokens after {} requests", prompt_tokens, i);
+            if i > 50 {
+                assert!(context.behavioral_context.contains("Earlier"));
+            }
+        }
+    }
+}
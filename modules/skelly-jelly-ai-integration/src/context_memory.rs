@@ -0,0 +1,829 @@
+//! Context Memory
+//!
+//! Remembers past intervention situations (what was going on, what Skelly
+//! said and whether it helped) along with notes the user leaves, and finds
+//! the ones most similar to the current situation. They go into the prompt as
+//! few-shot examples, so the model can repeat what worked for this user.
+//!
+//! Texts are embedded on-device, by [`HashingEmbedder`] unless another
+//! [`Embedder`] is supplied, and indexed in a small HNSW graph. At most
+//! `capacity` entries are kept; the least recently stored or retrieved is
+//! evicted first. An [`EmbeddingPersistence`] keeps entries across restarts,
+//! e.g. storage's `context_embeddings` table with the `storage-embeddings`
+//! feature.
+
+use crate::error::Result;
+use crate::session_context::estimate_tokens;
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Links per node on the upper graph layers; the bottom layer gets twice as many
+const MAX_LINKS: usize = 12;
+/// Candidates considered while linking a new node
+const EF_CONSTRUCTION: usize = 64;
+/// Candidates considered while searching
+const EF_SEARCH: usize = 48;
+/// Layers above this are never created
+const MAX_LEVEL: usize = 12;
+/// Characters of each remembered text shown in an example
+const EXAMPLE_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextMemoryConfig {
+    pub enabled: bool,
+    /// Entries kept before the least recently used is evicted
+    pub capacity: usize,
+    /// Past situations offered to the model per suggestion
+    pub examples: usize,
+    /// Cosine similarity below which a past situation isn't considered related
+    pub min_similarity: f32,
+    /// Prompt tokens reserved for the examples
+    pub max_tokens: usize,
+}
+
+impl Default for ContextMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: 2000,
+            examples: 3,
+            min_similarity: 0.3,
+            max_tokens: 160,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextKind {
+    /// A situation Skelly responded to
+    Intervention,
+    /// Something the user wrote down
+    Note,
+}
+
+impl ContextKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Intervention => "intervention",
+            Self::Note => "note",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "intervention" => Some(Self::Intervention),
+            "note" => Some(Self::Note),
+            _ => None,
+        }
+    }
+}
+
+/// One remembered situation or note
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextEntry {
+    pub id: Uuid,
+    pub kind: ContextKind,
+    /// The situation or note, as embedded
+    pub text: String,
+    /// What Skelly said, for interventions
+    pub response: Option<String>,
+    /// Whether the user acted on the response, once known
+    pub accepted: Option<bool>,
+    pub recorded_at: DateTime<Utc>,
+    pub last_used: DateTime<Utc>,
+}
+
+impl ContextEntry {
+    /// A situation and Skelly's response to it, keyed by the intervention request
+    pub fn intervention(request_id: Uuid, situation: &str, response: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: request_id,
+            kind: ContextKind::Intervention,
+            text: situation.to_string(),
+            response: Some(response.to_string()),
+            accepted: None,
+            recorded_at: now,
+            last_used: now,
+        }
+    }
+
+    pub fn note(text: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind: ContextKind::Note,
+            text: text.to_string(),
+            response: None,
+            accepted: None,
+            recorded_at: now,
+            last_used: now,
+        }
+    }
+}
+
+/// An entry with its embedding, as persisted
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredContext {
+    pub entry: ContextEntry,
+    pub embedding: Vec<f32>,
+}
+
+/// A remembered entry similar to the query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarContext {
+    pub entry: ContextEntry,
+    /// Cosine similarity to the query, up to 1.0
+    pub similarity: f32,
+}
+
+/// Turns text into a unit-length vector
+pub trait Embedder: Send + Sync {
+    fn dimensions(&self) -> usize;
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Model-free embedder hashing words and word pairs into a fixed-size vector
+///
+/// Texts sharing vocabulary land close together, which is enough to tell a
+/// stuck debugging session from a drifting browsing one without a model.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions: dimensions.max(1) }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(512)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        let mut vector = vec![0.0; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            // FNV-1a, so persisted embeddings stay valid across builds
+            let hash = feature
+                .bytes()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[(hash % self.dimensions as u64) as usize] += sign * weight;
+        };
+        for word in &words {
+            add(word, 1.0);
+        }
+        for pair in words.windows(2) {
+            add(&format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|value| *value /= norm);
+    }
+}
+
+/// Where remembered entries are kept between runs
+#[async_trait::async_trait]
+pub trait EmbeddingPersistence: Send + Sync {
+    /// The `limit` most recently used entries, least recently used first
+    async fn load(&self, limit: usize) -> Result<Vec<StoredContext>>;
+    /// Insert or replace entries
+    async fn save(&self, entries: &[StoredContext]) -> Result<()>;
+    async fn remove(&self, ids: &[Uuid]) -> Result<()>;
+}
+
+/// Persists entries in storage's `context_embeddings` table
+#[cfg(feature = "storage-embeddings")]
+pub struct StorageEmbeddingPersistence {
+    database: Arc<skelly_jelly_storage::database::TimeSeriesDatabase>,
+}
+
+#[cfg(feature = "storage-embeddings")]
+impl StorageEmbeddingPersistence {
+    pub fn new(database: Arc<skelly_jelly_storage::database::TimeSeriesDatabase>) -> Self {
+        Self { database }
+    }
+}
+
+#[cfg(feature = "storage-embeddings")]
+#[async_trait::async_trait]
+impl EmbeddingPersistence for StorageEmbeddingPersistence {
+    async fn load(&self, limit: usize) -> Result<Vec<StoredContext>> {
+        let rows = self.database.get_context_embeddings(limit).await.map_err(storage_failed)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(StoredContext {
+                    entry: ContextEntry {
+                        id: row.id,
+                        kind: ContextKind::parse(&row.kind)?,
+                        text: row.text,
+                        response: row.response,
+                        accepted: row.accepted,
+                        recorded_at: row.recorded_at,
+                        last_used: row.last_used,
+                    },
+                    embedding: row.embedding,
+                })
+            })
+            .collect())
+    }
+
+    async fn save(&self, entries: &[StoredContext]) -> Result<()> {
+        let rows: Vec<_> = entries
+            .iter()
+            .map(|stored| skelly_jelly_storage::ContextEmbedding {
+                id: stored.entry.id,
+                kind: stored.entry.kind.as_str().to_string(),
+                text: stored.entry.text.clone(),
+                response: stored.entry.response.clone(),
+                accepted: stored.entry.accepted,
+                embedding: stored.embedding.clone(),
+                recorded_at: stored.entry.recorded_at,
+                last_used: stored.entry.last_used,
+            })
+            .collect();
+        self.database.upsert_context_embeddings(&rows).await.map_err(storage_failed)
+    }
+
+    async fn remove(&self, ids: &[Uuid]) -> Result<()> {
+        self.database.delete_context_embeddings(ids).await.map(drop).map_err(storage_failed)
+    }
+}
+
+#[cfg(feature = "storage-embeddings")]
+fn storage_failed(error: skelly_jelly_storage::StorageError) -> crate::error::AIIntegrationError {
+    crate::error::AIIntegrationError::ContextStorageFailed { reason: error.to_string() }
+}
+
+/// Bounded, searchable memory of past situations and notes
+pub struct ContextMemory {
+    config: ContextMemoryConfig,
+    embedder: Arc<dyn Embedder>,
+    index: Mutex<Index>,
+    persistence: Option<Arc<dyn EmbeddingPersistence>>,
+}
+
+struct Index {
+    graph: Hnsw,
+    /// Graph slot of every entry, in order of use
+    slots: LruCache<Uuid, usize>,
+}
+
+impl ContextMemory {
+    pub fn new(config: ContextMemoryConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            config,
+            embedder: Arc::new(HashingEmbedder::default()),
+            index: Mutex::new(Index { graph: Hnsw::default(), slots: LruCache::new(capacity) }),
+            persistence: None,
+        }
+    }
+
+    /// Embed with `embedder` instead of the hashing embedder
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Keep entries in `persistence`; call [`load`](Self::load) to restore them
+    pub fn with_persistence(mut self, persistence: Arc<dyn EmbeddingPersistence>) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
+
+    pub fn config(&self) -> &ContextMemoryConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.lock().unwrap().slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Restore persisted entries, returning how many were loaded
+    ///
+    /// Entries embedded with a different embedder are embedded again.
+    pub async fn load(&self) -> Result<usize> {
+        let Some(persistence) = &self.persistence else {
+            return Ok(0);
+        };
+        let stored = persistence.load(self.config.capacity).await?;
+
+        let mut index = self.index.lock().unwrap();
+        for StoredContext { entry, embedding } in stored {
+            let embedding = if embedding.len() == self.embedder.dimensions() {
+                embedding
+            } else {
+                self.embedder.embed(&entry.text)
+            };
+            index.insert(entry, embedding);
+        }
+        Ok(index.slots.len())
+    }
+
+    /// Remember an entry, replacing one with the same id and evicting the least recently used if full
+    pub async fn remember(&self, entry: ContextEntry) -> Result<()> {
+        let embedding = self.embedder.embed(&entry.text);
+        let stored = StoredContext { entry: entry.clone(), embedding: embedding.clone() };
+        let evicted = self.index.lock().unwrap().insert(entry, embedding);
+
+        if let Some(persistence) = &self.persistence {
+            persistence.save(&[stored]).await?;
+            if let Some(evicted) = evicted {
+                persistence.remove(&[evicted]).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record whether the user acted on a remembered intervention; `false` if it isn't remembered
+    pub async fn record_outcome(&self, id: Uuid, accepted: bool) -> Result<bool> {
+        let stored = {
+            let mut index = self.index.lock().unwrap();
+            let Some(&slot) = index.slots.peek(&id) else {
+                return Ok(false);
+            };
+            let node = index.graph.node_mut(slot);
+            node.entry.accepted = Some(accepted);
+            StoredContext { entry: node.entry.clone(), embedding: node.vector.clone() }
+        };
+
+        if let Some(persistence) = &self.persistence {
+            persistence.save(&[stored]).await?;
+        }
+        Ok(true)
+    }
+
+    /// Up to `limit` remembered entries at least `min_similarity` similar to `text`, most similar first
+    ///
+    /// Retrieved entries count as used, so they are kept over ones never recalled.
+    pub async fn similar(&self, text: &str, limit: usize) -> Vec<SimilarContext> {
+        let query = self.embedder.embed(text);
+        let now = Utc::now();
+        let (similar, touched) = {
+            let mut index = self.index.lock().unwrap();
+            let found = index.graph.search(&query, limit);
+            let mut similar = Vec::new();
+            let mut touched = Vec::new();
+            for candidate in found {
+                let similarity = 1.0 - candidate.distance;
+                if similarity < self.config.min_similarity {
+                    continue;
+                }
+                let node = index.graph.node_mut(candidate.slot);
+                node.entry.last_used = now;
+                similar.push(SimilarContext { entry: node.entry.clone(), similarity });
+                touched.push(StoredContext { entry: node.entry.clone(), embedding: node.vector.clone() });
+                let id = node.entry.id;
+                index.slots.promote(&id);
+            }
+            (similar, touched)
+        };
+
+        if let Some(persistence) = self.persistence.as_ref().filter(|_| !touched.is_empty()) {
+            if let Err(e) = persistence.save(&touched).await {
+                log::warn!("Failed to persist context memory use: {}", e);
+            }
+        }
+        similar
+    }
+
+    /// Past situations similar to `situation`, written as prompt examples within `max_tokens`
+    ///
+    /// Empty when nothing related is remembered.
+    pub async fn examples_for(&self, situation: &str) -> String {
+        let similar = self.similar(situation, self.config.examples).await;
+        let mut examples = String::new();
+        for context in similar {
+            let line = render_example(&context.entry);
+            let header = if examples.is_empty() { "Similar past situations:\n" } else { "" };
+            if estimate_tokens(&examples) + estimate_tokens(header) + estimate_tokens(&line) > self.config.max_tokens {
+                break;
+            }
+            examples.push_str(header);
+            examples.push_str(&line);
+        }
+        examples
+    }
+}
+
+fn render_example(entry: &ContextEntry) -> String {
+    let text = shorten(&entry.text);
+    match (entry.kind, &entry.response) {
+        (ContextKind::Note, _) | (_, None) => format!("- User note: {}\n", text),
+        (ContextKind::Intervention, Some(response)) => {
+            let outcome = match entry.accepted {
+                Some(true) => "it helped",
+                Some(false) => "it didn't land",
+                None => "no reaction",
+            };
+            format!("- {} -> you said \"{}\" ({})\n", text, shorten(response), outcome)
+        }
+    }
+}
+
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(EXAMPLE_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+impl Index {
+    /// Insert an entry, returning the id evicted to make room
+    fn insert(&mut self, entry: ContextEntry, vector: Vec<f32>) -> Option<Uuid> {
+        let id = entry.id;
+        if let Some(slot) = self.slots.pop(&id) {
+            self.graph.remove(slot);
+        }
+        let slot = self.graph.insert(entry, vector);
+        match self.slots.push(id, slot) {
+            Some((evicted, slot)) => {
+                self.graph.remove(slot);
+                Some(evicted)
+            }
+            None => None,
+        }
+    }
+}
+
+/// A graph node's distance to the query
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    slot: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.total_cmp(&other.distance).then(self.slot.cmp(&other.slot))
+    }
+}
+
+struct Node {
+    entry: ContextEntry,
+    vector: Vec<f32>,
+    /// Neighbours on each layer the node is part of, bottom layer first
+    links: Vec<Vec<usize>>,
+}
+
+/// Hierarchical navigable small world graph over unit vectors, by cosine distance
+///
+/// Removed nodes leave their slot free for the next insert, and their
+/// neighbours are relinked among themselves so the graph stays navigable.
+#[derive(Default)]
+struct Hnsw {
+    nodes: Vec<Option<Node>>,
+    free: Vec<usize>,
+    entry_point: Option<usize>,
+}
+
+impl Hnsw {
+    fn insert(&mut self, entry: ContextEntry, vector: Vec<f32>) -> usize {
+        let level = random_level();
+        let node = Node { entry, vector: vector.clone(), links: vec![Vec::new(); level + 1] };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = Some(node);
+                slot
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+
+        let Some(mut entry_point) = self.entry_point else {
+            self.entry_point = Some(slot);
+            return slot;
+        };
+        let top = self.level(entry_point);
+        for layer in (level + 1..=top).rev() {
+            entry_point = self.search_layer(&vector, &[entry_point], 1, layer)[0].slot;
+        }
+
+        let mut entry_points = vec![entry_point];
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&vector, &entry_points, EF_CONSTRUCTION, layer);
+            let neighbours = self.select_neighbours(&found, max_links(layer));
+            for &neighbour in &neighbours {
+                self.node_mut(neighbour).links[layer].push(slot);
+                self.prune(neighbour, layer);
+            }
+            self.node_mut(slot).links[layer] = neighbours;
+            entry_points = found.into_iter().map(|c| c.slot).collect();
+        }
+
+        if level > top {
+            self.entry_point = Some(slot);
+        }
+        slot
+    }
+
+    fn remove(&mut self, slot: usize) {
+        let Some(removed) = self.nodes[slot].take() else {
+            return;
+        };
+        self.free.push(slot);
+
+        // Links may be one-way after pruning, so look for them everywhere
+        for other in 0..self.nodes.len() {
+            let Some(node) = self.nodes[other].as_mut() else {
+                continue;
+            };
+            let mut relinked = Vec::new();
+            for (layer, links) in node.links.iter_mut().enumerate() {
+                let before = links.len();
+                links.retain(|&linked| linked != slot);
+                if links.len() == before {
+                    continue;
+                }
+                // Route through the removed node's neighbours instead
+                for &replacement in removed.links.get(layer).into_iter().flatten() {
+                    if replacement != other && !links.contains(&replacement) {
+                        links.push(replacement);
+                    }
+                }
+                relinked.push(layer);
+            }
+            for layer in relinked {
+                self.prune(other, layer);
+            }
+        }
+
+        if self.entry_point == Some(slot) {
+            self.entry_point = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, node)| node.as_ref().map(|node| (node.links.len(), slot)))
+                .max()
+                .map(|(_, slot)| slot);
+        }
+    }
+
+    /// The `k` nearest nodes to `query`, nearest first
+    fn search(&self, query: &[f32], k: usize) -> Vec<Candidate> {
+        let Some(mut entry_point) = self.entry_point else {
+            return Vec::new();
+        };
+        for layer in (1..=self.level(entry_point)).rev() {
+            entry_point = self.search_layer(query, &[entry_point], 1, layer)[0].slot;
+        }
+        let mut found = self.search_layer(query, &[entry_point], EF_SEARCH.max(k), 0);
+        found.truncate(k);
+        found
+    }
+
+    /// Best-first search of one layer, returning up to `ef` nodes nearest first
+    fn search_layer(&self, query: &[f32], entry_points: &[usize], ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        let mut nearest = BinaryHeap::new();
+        for &slot in entry_points {
+            let candidate = Candidate { distance: self.distance(query, slot), slot };
+            candidates.push(Reverse(candidate));
+            nearest.push(candidate);
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let furthest = nearest.peek().map_or(f32::INFINITY, |c: &Candidate| c.distance);
+            if nearest.len() >= ef && current.distance > furthest {
+                break;
+            }
+            for &neighbour in self.links(current.slot, layer) {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let candidate = Candidate { distance: self.distance(query, neighbour), slot: neighbour };
+                let furthest = nearest.peek().map_or(f32::INFINITY, |c| c.distance);
+                if nearest.len() < ef || candidate.distance < furthest {
+                    candidates.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    /// Cut the neighbours of `slot` on `layer` back to the limit
+    fn prune(&mut self, slot: usize, layer: usize) {
+        let max = max_links(layer);
+        if self.node(slot).links[layer].len() <= max {
+            return;
+        }
+        let vector = &self.node(slot).vector;
+        let mut links: Vec<Candidate> = self.node(slot).links[layer]
+            .iter()
+            .map(|&linked| Candidate { distance: self.distance(vector, linked), slot: linked })
+            .collect();
+        links.sort();
+        let links = self.select_neighbours(&links, max);
+        self.node_mut(slot).links[layer] = links;
+    }
+
+    /// Pick up to `max` of `candidates` (nearest first) to link to
+    ///
+    /// A candidate closer to an already picked neighbour than to the node is
+    /// reachable through that neighbour and skipped, so links spread out in
+    /// every direction instead of all going to one dense cluster. That keeps
+    /// outlying entries reachable. Skipped candidates fill any remaining room.
+    fn select_neighbours(&self, candidates: &[Candidate], max: usize) -> Vec<usize> {
+        let mut selected: Vec<usize> = Vec::with_capacity(max);
+        let mut skipped = Vec::new();
+        for candidate in candidates {
+            if selected.len() == max {
+                break;
+            }
+            let vector = &self.node(candidate.slot).vector;
+            if selected.iter().all(|&picked| self.distance(vector, picked) > candidate.distance) {
+                selected.push(candidate.slot);
+            } else {
+                skipped.push(candidate.slot);
+            }
+        }
+        let room = max - selected.len();
+        selected.extend(skipped.into_iter().take(room));
+        selected
+    }
+
+    fn links(&self, slot: usize, layer: usize) -> &[usize] {
+        self.node(slot).links.get(layer).map_or(&[], Vec::as_slice)
+    }
+
+    fn level(&self, slot: usize) -> usize {
+        self.node(slot).links.len() - 1
+    }
+
+    fn distance(&self, query: &[f32], slot: usize) -> f32 {
+        1.0 - query.iter().zip(&self.node(slot).vector).map(|(a, b)| a * b).sum::<f32>()
+    }
+
+    fn node(&self, slot: usize) -> &Node {
+        self.nodes[slot].as_ref().expect("linked node was removed")
+    }
+
+    fn node_mut(&mut self, slot: usize) -> &mut Node {
+        self.nodes[slot].as_mut().expect("linked node was removed")
+    }
+}
+
+fn max_links(layer: usize) -> usize {
+    if layer == 0 {
+        MAX_LINKS * 2
+    } else {
+        MAX_LINKS
+    }
+}
+
+/// Exponentially distributed level, so each layer holds about 1/MAX_LINKS of the one below
+fn random_level() -> usize {
+    let uniform: f64 = rand::random::<f64>().max(f64::MIN_POSITIVE);
+    let level = -uniform.ln() / (MAX_LINKS as f64).ln();
+    (level as usize).min(MAX_LEVEL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Default)]
+    struct MemoryPersistence {
+        rows: StdMutex<Vec<StoredContext>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EmbeddingPersistence for MemoryPersistence {
+        async fn load(&self, limit: usize) -> Result<Vec<StoredContext>> {
+            let mut rows = self.rows.lock().unwrap().clone();
+            rows.sort_by_key(|row| row.entry.last_used);
+            Ok(rows.split_off(rows.len().saturating_sub(limit)))
+        }
+
+        async fn save(&self, entries: &[StoredContext]) -> Result<()> {
+            let mut rows = self.rows.lock().unwrap();
+            for entry in entries {
+                rows.retain(|row| row.entry.id != entry.entry.id);
+                rows.push(entry.clone());
+            }
+            Ok(())
+        }
+
+        async fn remove(&self, ids: &[Uuid]) -> Result<()> {
+            self.rows.lock().unwrap().retain(|row| !ids.contains(&row.entry.id));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retrieves_similar_situations_with_outcomes() {
+        let memory = ContextMemory::new(ContextMemoryConfig { min_similarity: 0.2, ..Default::default() });
+        let stuck = Uuid::new_v4();
+        memory
+            .remember(ContextEntry::intervention(
+                stuck,
+                "Stuck state, gentle_nudge intervention: debugging rust borrow checker errors in main.rs",
+                "Try explaining the lifetime to the rubber duck",
+            ))
+            .await
+            .unwrap();
+        memory.remember(ContextEntry::note("Short walks help me more than coffee")).await.unwrap();
+        for i in 0..200 {
+            let filler = format!("Distracted state, break_reminder intervention: browsing video site tab {}", i);
+            memory.remember(ContextEntry::intervention(Uuid::new_v4(), &filler, "Time to stretch")).await.unwrap();
+        }
+        assert!(memory.record_outcome(stuck, true).await.unwrap());
+
+        let similar = memory
+            .similar("Stuck state, gentle_nudge intervention: rust borrow checker errors in lib.rs", 3)
+            .await;
+        assert_eq!(similar[0].entry.id, stuck);
+        assert!(similar.windows(2).all(|pair| pair[0].similarity >= pair[1].similarity));
+
+        let examples = memory.examples_for("rust borrow checker errors").await;
+        assert!(examples.starts_with("Similar past situations:\n"));
+        assert!(examples.contains("rubber duck\" (it helped)"));
+        assert!(memory.similar("quarterly tax spreadsheet", 3).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used_and_persists() {
+        let persistence = Arc::new(MemoryPersistence::default());
+        let config = ContextMemoryConfig { capacity: 3, min_similarity: 0.5, ..Default::default() };
+        let memory = ContextMemory::new(config.clone()).with_persistence(persistence.clone());
+
+        let entries: Vec<ContextEntry> = ["writing the design doc", "reviewing pull requests", "answering email", "planning the sprint"]
+            .iter()
+            .map(|text| ContextEntry::note(text))
+            .collect();
+        for entry in &entries[..3] {
+            memory.remember(entry.clone()).await.unwrap();
+        }
+        // Recalling the oldest keeps it; the next one in line goes instead
+        assert_eq!(memory.similar("writing the design doc", 1).await[0].entry.id, entries[0].id);
+        memory.remember(entries[3].clone()).await.unwrap();
+
+        assert_eq!(memory.len(), 3);
+        assert!(memory.similar("reviewing pull requests", 1).await.is_empty());
+        let persisted: HashSet<Uuid> = persistence.rows.lock().unwrap().iter().map(|row| row.entry.id).collect();
+        assert_eq!(persisted, [entries[0].id, entries[2].id, entries[3].id].into_iter().collect());
+
+        // A new instance picks up where the last one stopped
+        let restored = ContextMemory::new(config).with_persistence(persistence);
+        assert_eq!(restored.load().await.unwrap(), 3);
+        assert_eq!(restored.similar("planning the sprint", 1).await[0].entry.id, entries[3].id);
+    }
+}
@@ -6,6 +6,7 @@
 //! - Context-aware messaging system  
 //! - User feedback collection and learning
 
+use crate::check_in::{CheckInAnswer, CheckInConfig, CheckInPrompt, CheckInScheduler, DayStyle};
 use crate::context_detection::{WorkTypeDetector, WorkType, WorkContext};
 use crate::intervention_timing::{
     InterventionTimingEngine, FocusState, InterventionType, InterventionDecision,
@@ -15,18 +16,29 @@ use crate::intervention_timing::{
 use crate::contextual_messaging::{
     ContextualMessageGenerator, ContextualMessage, MessagePersonalization
 };
+use crate::intervention_rules::{InterventionRulesEngine, RuleConflict, RuleVerdict};
+use crate::novelty::NoveltyConfig;
+use crate::timing_policy::TimingPolicyConfig;
 use crate::user_feedback::{FeedbackCollector, FeedbackSubmission, FeedbackType, FeedbackContext};
+use crate::wellness::{WellnessConfig, WellnessEngine};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc, Timelike, Datelike};
+use skelly_jelly_event_bus::message::CheckInResponse;
+use chrono::{DateTime, Utc, Local, Timelike, Datelike};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Complete contextual intervention system
 pub struct ContextualInterventionSystem {
     work_detector: WorkTypeDetector,
     timing_engine: InterventionTimingEngine,
+    rules: InterventionRulesEngine,
     message_generator: ContextualMessageGenerator,
     feedback_collector: FeedbackCollector,
+    wellness: WellnessEngine,
+    check_ins: CheckInScheduler,
+    /// Personalization before the day's check-in style is applied
+    base_personalization: MessagePersonalization,
     current_work_context: Option<WorkContext>,
     intervention_history: Vec<InterventionRecord>,
 }
@@ -40,6 +52,8 @@ struct InterventionRecord {
     intervention_type: InterventionType,
     message: ContextualMessage,
     user_response: Option<UserResponse>,
+    #[serde(default)]
+    policy_decision_id: Option<Uuid>,
 }
 
 /// Configuration for the contextual intervention system
@@ -47,6 +61,20 @@ struct InterventionRecord {
 pub struct ContextualInterventionConfig {
     pub intervention_preferences: InterventionPreferences,
     pub message_personalization: MessagePersonalization,
+    #[serde(default)]
+    pub novelty: NoveltyConfig,
+    /// JSON file of user intervention rules, reloaded when it changes
+    #[serde(default)]
+    pub rules_path: Option<PathBuf>,
+    /// Learn intervention timing from outcomes instead of fixed thresholds
+    #[serde(default)]
+    pub learned_timing: Option<TimingPolicyConfig>,
+    /// Hydration, posture and eye strain reminder schedule
+    #[serde(default)]
+    pub wellness: WellnessConfig,
+    /// How often to ask "How's it going?"
+    #[serde(default)]
+    pub check_in: CheckInConfig,
     pub enable_work_detection: bool,
     pub enable_timing_engine: bool,
     pub enable_feedback_collection: bool,
@@ -57,6 +85,11 @@ impl Default for ContextualInterventionConfig {
         Self {
             intervention_preferences: InterventionPreferences::default(),
             message_personalization: MessagePersonalization::default(),
+            novelty: NoveltyConfig::default(),
+            rules_path: None,
+            learned_timing: None,
+            wellness: WellnessConfig::default(),
+            check_in: CheckInConfig::default(),
             enable_work_detection: true,
             enable_timing_engine: true,
             enable_feedback_collection: true,
@@ -91,11 +124,31 @@ pub struct ContextualInterventionResponse {
 
 impl ContextualInterventionSystem {
     pub fn new(config: ContextualInterventionConfig) -> Self {
+        let rules = match &config.rules_path {
+            Some(path) => InterventionRulesEngine::from_file(path).unwrap_or_else(|err| {
+                log::warn!("Failed to load intervention rules from {:?}: {}", path, err);
+                InterventionRulesEngine::default()
+            }),
+            None => InterventionRulesEngine::default(),
+        };
+
+        let mut timing_engine = InterventionTimingEngine::new(config.intervention_preferences);
+        if let Some(policy_config) = config.learned_timing {
+            timing_engine = timing_engine.with_learned_policy(policy_config);
+        }
+
         Self {
             work_detector: WorkTypeDetector::new(),
-            timing_engine: InterventionTimingEngine::new(config.intervention_preferences),
-            message_generator: ContextualMessageGenerator::new(config.message_personalization),
+            timing_engine,
+            rules,
+            message_generator: ContextualMessageGenerator::with_novelty_config(
+                config.message_personalization.clone(),
+                config.novelty,
+            ),
             feedback_collector: FeedbackCollector::new(),
+            wellness: WellnessEngine::new(config.wellness),
+            check_ins: CheckInScheduler::new(config.check_in),
+            base_personalization: config.message_personalization,
             current_work_context: None,
             intervention_history: Vec::new(),
         }
@@ -137,8 +190,23 @@ impl ContextualInterventionSystem {
             &context.current_focus_state,
         );
 
-        // Step 3: Check timing and decide whether to intervene
-        let timing_decision = if self.timing_engine.is_enabled() {
+        // Step 3: Apply the user's rules, then check timing and decide whether to intervene
+        if let Err(err) = self.rules.reload_if_changed() {
+            log::warn!("Keeping previous intervention rules: {}", err);
+        }
+        let rule_verdict = self.rules.evaluate(&potential_intervention, &context.application_name, Local::now());
+
+        let timing_decision = if let RuleVerdict::Deny { rule_id, reason, retry_after_seconds } = rule_verdict {
+            InterventionDecision {
+                should_intervene: false,
+                urgency: crate::intervention_timing::InterventionUrgency::Deferred,
+                intervention_type: None,
+                delay_seconds: retry_after_seconds,
+                reason: format!("Blocked by rule '{}': {}", rule_id, reason),
+                confidence: 1.0,
+                policy_decision_id: None,
+            }
+        } else if self.timing_engine.is_enabled() {
             self.timing_engine.should_intervene(
                 context.current_focus_state.clone(),
                 &work_context.work_type,
@@ -153,6 +221,7 @@ impl ContextualInterventionSystem {
                 delay_seconds: 300,
                 reason: "Timing engine disabled - default behavior".to_string(),
                 confidence: 0.5,
+                policy_decision_id: None,
             }
         };
 
@@ -187,8 +256,10 @@ impl ContextualInterventionSystem {
                 intervention_type: potential_intervention.clone(),
                 message: msg.clone(),
                 user_response: None,
+                policy_decision_id: timing_decision.policy_decision_id,
             };
             self.intervention_history.push(record);
+            self.rules.record_delivery(&potential_intervention, Local::now());
 
             // Keep only last 100 interventions
             if self.intervention_history.len() > 100 {
@@ -209,6 +280,106 @@ impl ContextualInterventionSystem {
         })
     }
 
+    /// Record user input, for screen time and break tracking
+    pub fn record_activity(&mut self, at: DateTime<Utc>) {
+        self.wellness.record_activity(at);
+    }
+
+    /// Offer the most overdue wellness reminder, if the timing engine agrees
+    pub fn check_wellness(&mut self, focus_state: FocusState) -> Option<ContextualInterventionResponse> {
+        let now = Utc::now();
+        let reminder_type = self.wellness.due(now)?;
+        if self.wellness.suppressed_by(&focus_state) {
+            return None;
+        }
+
+        let work_context = self.current_work_context.clone().unwrap_or_else(|| WorkContext {
+            work_type: WorkType::Unknown { confidence: 0.1 },
+            application: String::new(),
+            window_title: String::new(),
+            detected_patterns: vec![],
+            activity_duration: 0,
+            last_updated: now,
+        });
+        let intervention_type = InterventionType::WellnessReminder { reminder_type: reminder_type.clone() };
+        if let RuleVerdict::Deny { .. } = self.rules.evaluate(&intervention_type, &work_context.application, Local::now()) {
+            return None;
+        }
+
+        let decision = self.timing_engine.should_intervene(
+            focus_state.clone(),
+            &work_context.work_type,
+            intervention_type.clone(),
+        );
+        if !decision.should_intervene {
+            return None;
+        }
+
+        let message = match self.message_generator.generate_message(&work_context.work_type, &focus_state, &intervention_type) {
+            Ok(message) => message,
+            Err(err) => {
+                log::warn!("Failed to generate wellness message: {}", err);
+                return None;
+            }
+        };
+
+        let intervention_id = Uuid::new_v4();
+        self.wellness.mark_reminded(reminder_type, now);
+        self.intervention_history.push(InterventionRecord {
+            intervention_id,
+            timestamp: now,
+            work_type: work_context.work_type.clone(),
+            focus_state,
+            intervention_type: intervention_type.clone(),
+            message: message.clone(),
+            user_response: None,
+            policy_decision_id: decision.policy_decision_id,
+        });
+        self.rules.record_delivery(&intervention_type, Local::now());
+        if self.intervention_history.len() > 100 {
+            self.intervention_history.remove(0);
+        }
+
+        Some(ContextualInterventionResponse {
+            intervention_id,
+            should_show: true,
+            message: Some(message),
+            delay_seconds: decision.delay_seconds,
+            confidence: decision.confidence,
+            work_context,
+            intervention_type: Some(intervention_type),
+            reason: decision.reason,
+            created_at: now,
+        })
+    }
+
+    /// Ask "How's it going?" if a check-in is due
+    pub fn check_in(&mut self, focus_state: &FocusState) -> Option<CheckInPrompt> {
+        let now = Utc::now();
+        if self.check_ins.day_style(now) == DayStyle::Usual {
+            // A new day: drop yesterday's style
+            self.message_generator.update_personalization(self.base_personalization.clone());
+        }
+        self.check_ins.due(now, focus_state)
+    }
+
+    /// Record the answer to a check-in and adapt today's messages to it
+    ///
+    /// Returns the labeled response to publish for the analysis engine, or
+    /// `None` if the check-in was already answered or has expired.
+    pub fn record_check_in(
+        &mut self,
+        check_in_id: Uuid,
+        answer: CheckInAnswer,
+        window_id: Option<Uuid>,
+    ) -> Option<CheckInResponse> {
+        let now = Utc::now();
+        let response = self.check_ins.respond(check_in_id, answer, window_id, now)?;
+        let personalization = self.check_ins.day_style(now).adapt(&self.base_personalization);
+        self.message_generator.update_personalization(personalization);
+        Some(response)
+    }
+
     /// Record user feedback for an intervention
     pub fn record_feedback(
         &mut self,
@@ -245,6 +416,9 @@ impl ContextualInterventionSystem {
             intervention_record.intervention_type.clone(),
             Some(user_response.clone()),
         );
+        if let Some(decision_id) = intervention_record.policy_decision_id {
+            self.timing_engine.record_policy_response(decision_id, user_response.clone());
+        }
 
         // Record in feedback collector
         if self.feedback_collector.is_enabled() {
@@ -331,13 +505,20 @@ impl ContextualInterventionSystem {
 
     /// Update system configuration
     pub fn update_config(&mut self, config: ContextualInterventionConfig) {
-        // Update message personalization
-        self.message_generator.update_personalization(config.message_personalization);
+        // Update message personalization, keeping today's check-in style
+        let style = self.check_ins.day_style(Utc::now());
+        self.message_generator.update_personalization(style.adapt(&config.message_personalization));
+        self.base_personalization = config.message_personalization;
         
         // Note: Timing engine preferences would need to be updated via a new method
         // that we'd add to InterventionTimingEngine
     }
 
+    /// Conflicts between the loaded intervention rules
+    pub fn rule_conflicts(&self) -> &[RuleConflict] {
+        self.rules.conflicts()
+    }
+
     /// Get current work context
     pub fn get_current_work_context(&self) -> Option<&WorkContext> {
         self.current_work_context.as_ref()
@@ -11,6 +11,7 @@ use crate::intervention_timing::{
     FocusState, InterventionType, CodingIssueCategory, WritingIssueCategory, 
     DesignIssueCategory, FocusStrategy, WellnessType, UserResponse
 };
+use crate::novelty::{NoveltyConfig, NoveltyController, DEFAULT_USER_ID};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
@@ -93,6 +94,7 @@ pub struct ContextualMessageGenerator {
     encouragement_templates: Vec<MessageTemplate>,
     user_feedback_history: HashMap<String, Vec<UserFeedback>>,
     personalization: MessagePersonalization,
+    novelty: NoveltyController,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,6 +108,11 @@ struct UserFeedback {
 
 impl ContextualMessageGenerator {
     pub fn new(personalization: MessagePersonalization) -> Self {
+        Self::with_novelty_config(personalization, NoveltyConfig::default())
+    }
+
+    /// Create a generator with a custom anti-repetition configuration
+    pub fn with_novelty_config(personalization: MessagePersonalization, novelty_config: NoveltyConfig) -> Self {
         let mut generator = Self {
             coding_templates: HashMap::new(),
             writing_templates: HashMap::new(),
@@ -115,6 +122,7 @@ impl ContextualMessageGenerator {
             encouragement_templates: Vec::new(),
             user_feedback_history: HashMap::new(),
             personalization,
+            novelty: NoveltyController::new(novelty_config),
         };
 
         generator.initialize_templates();
@@ -127,6 +135,17 @@ impl ContextualMessageGenerator {
         work_type: &WorkType,
         focus_state: &FocusState,
         intervention_type: &InterventionType,
+    ) -> Result<ContextualMessage, String> {
+        self.generate_message_for_user(DEFAULT_USER_ID, work_type, focus_state, intervention_type)
+    }
+
+    /// Generate a contextual message for a specific user, avoiding recent repeats
+    pub fn generate_message_for_user(
+        &mut self,
+        user_id: &str,
+        work_type: &WorkType,
+        focus_state: &FocusState,
+        intervention_type: &InterventionType,
     ) -> Result<ContextualMessage, String> {
         let templates = self.get_relevant_templates(intervention_type)?;
         
@@ -138,14 +157,17 @@ impl ContextualMessageGenerator {
         }
 
         // Select template based on effectiveness history
-        let selected_template = self.select_best_template(&suitable_templates, work_type)?;
+        let selected_template = self.select_best_template(user_id, &suitable_templates, work_type)?;
         
         // Generate message content
-        let message_text = self.fill_template(&selected_template, work_type, focus_state)?;
+        let message_text = self.fill_template(user_id, &selected_template, work_type, focus_state)?;
         
         // Apply personalization
         let personalized_text = self.apply_personalization(message_text, &selected_template.tone);
         
+        // Remember what was said so it isn't repeated soon
+        self.novelty.record_delivery(user_id, &personalized_text, Utc::now());
+        
         // Generate animation hints
         let animation_hints = self.generate_animation_hints(&selected_template.tone, focus_state);
         
@@ -197,6 +219,11 @@ impl ContextualMessageGenerator {
         }
     }
 
+    /// Forget delivered-message history for a user
+    pub fn reset_novelty_history(&mut self, user_id: &str) {
+        self.novelty.clear_user(user_id);
+    }
+
     /// Update user personalization preferences based on feedback patterns
     pub fn update_personalization(&mut self, new_preferences: MessagePersonalization) {
         self.personalization = new_preferences;
@@ -400,6 +427,118 @@ impl ContextualMessageGenerator {
         ];
 
         self.wellness_templates.insert(WellnessType::Hydration, hydration_templates);
+
+        // Posture checks
+        let posture_templates = vec![
+            MessageTemplate {
+                id: "posture_gentle".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::PostureCheck,
+                },
+                tone: MessageTone::Gentle,
+                templates: vec![
+                    "Quick posture check: shoulders down, back against the chair.".to_string(),
+                    "Unclench your jaw, drop your shoulders, and sit back for a moment.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.4,
+            },
+            MessageTemplate {
+                id: "posture_playful".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::PostureCheck,
+                },
+                tone: MessageTone::Playful,
+                templates: vec![
+                    "Spine check! Stack those vertebrae like the good bones they are. 🦴".to_string(),
+                    "Even a skeleton slouches sometimes - straighten up and roll your shoulders.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.4,
+            },
+        ];
+
+        self.wellness_templates.insert(WellnessType::PostureCheck, posture_templates);
+
+        // Eye breaks (20-20-20 rule)
+        let eye_break_templates = vec![
+            MessageTemplate {
+                id: "eye_break_gentle".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::EyeBreak,
+                },
+                tone: MessageTone::Gentle,
+                templates: vec![
+                    "Give your eyes a rest: look at something 20 feet away for 20 seconds.".to_string(),
+                    "20-20-20 time - glance across the room for 20 seconds and blink a few times.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.4,
+            },
+            MessageTemplate {
+                id: "eye_break_informative".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::EyeBreak,
+                },
+                tone: MessageTone::Informative,
+                templates: vec![
+                    "You've been looking at the screen for 20 minutes. Focusing on something far away for 20 seconds helps prevent eye strain.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.4,
+            },
+        ];
+
+        self.wellness_templates.insert(WellnessType::EyeBreak, eye_break_templates);
+
+        // Movement breaks
+        let movement_templates = vec![
+            MessageTemplate {
+                id: "movement_gentle".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::MovementBreak,
+                },
+                tone: MessageTone::Gentle,
+                templates: vec![
+                    "You've been sitting for a while - stand up and stretch for a minute?".to_string(),
+                    "A short walk, even just to the window, will help your focus when you get back.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.5,
+            },
+            MessageTemplate {
+                id: "movement_playful".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::MovementBreak,
+                },
+                tone: MessageTone::Playful,
+                templates: vec![
+                    "Time to rattle those bones! A quick stretch keeps the joints happy. 💀".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.5,
+            },
+        ];
+
+        self.wellness_templates.insert(WellnessType::MovementBreak, movement_templates);
+
+        // Deep breathing
+        let breathing_templates = vec![
+            MessageTemplate {
+                id: "breathing_gentle".to_string(),
+                category: InterventionType::WellnessReminder {
+                    reminder_type: WellnessType::DeepBreathing,
+                },
+                tone: MessageTone::Gentle,
+                templates: vec![
+                    "Take a slow breath in for four, hold for four, and out for six.".to_string(),
+                ],
+                placeholders: vec![],
+                min_confidence_threshold: 0.4,
+            },
+        ];
+
+        self.wellness_templates.insert(WellnessType::DeepBreathing, breathing_templates);
     }
 
     fn initialize_encouragement_templates(&mut self) {
@@ -493,21 +632,29 @@ impl ContextualMessageGenerator {
         }
     }
 
-    fn select_best_template(&self, templates: &[MessageTemplate], work_type: &WorkType) -> Result<MessageTemplate, String> {
+    fn select_best_template(&self, user_id: &str, templates: &[MessageTemplate], work_type: &WorkType) -> Result<MessageTemplate, String> {
         if templates.is_empty() {
             return Err("No templates to select from".to_string());
         }
 
-        // Score templates based on effectiveness history and context match
-        let mut scored_templates: Vec<(f32, &MessageTemplate)> = templates.iter()
+        // Score templates based on effectiveness history and context match,
+        // penalizing ones that would repeat something the user saw recently
+        let now = Utc::now();
+        let mut scored_templates: Vec<(f32, f32, &MessageTemplate)> = templates.iter()
             .map(|template| {
                 let effectiveness_score = self.get_template_effectiveness_score(&template.id);
                 let context_score = self.get_context_match_score(template, work_type);
-                let total_score = effectiveness_score * 0.6 + context_score * 0.4;
-                (total_score, template)
+                let base_score = effectiveness_score * 0.6 + context_score * 0.4;
+                let novelty = self.get_template_novelty(user_id, template, now);
+                (self.novelty.penalize(base_score, novelty), novelty, template)
             })
             .collect();
 
+        // Drop near-duplicates as long as something fresher is available
+        if scored_templates.iter().any(|(_, novelty, _)| self.novelty.meets_floor(*novelty)) {
+            scored_templates.retain(|(_, novelty, _)| self.novelty.meets_floor(*novelty));
+        }
+
         // Sort by score (highest first)
         scored_templates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
 
@@ -515,7 +662,7 @@ impl ContextualMessageGenerator {
         let top_templates: Vec<&MessageTemplate> = scored_templates
             .into_iter()
             .take(3)
-            .map(|(_, template)| template)
+            .map(|(_, _, template)| template)
             .collect();
 
         top_templates.choose(&mut rand::thread_rng())
@@ -524,6 +671,13 @@ impl ContextualMessageGenerator {
             .ok_or_else(|| "Failed to select template".to_string())
     }
 
+    /// Best novelty any of a template's text variants can offer this user
+    fn get_template_novelty(&self, user_id: &str, template: &MessageTemplate, now: DateTime<Utc>) -> f32 {
+        template.templates.iter()
+            .map(|text| self.novelty.novelty_score(user_id, text, now))
+            .fold(0.0_f32, f32::max)
+    }
+
     fn get_template_effectiveness_score(&self, template_id: &str) -> f32 {
         if let Some(feedback_history) = self.user_feedback_history.get(template_id) {
             if feedback_history.is_empty() {
@@ -561,10 +715,18 @@ impl ContextualMessageGenerator {
         }
     }
 
-    fn fill_template(&self, template: &MessageTemplate, work_type: &WorkType, _focus_state: &FocusState) -> Result<String, String> {
-        let template_text = template.templates
-            .choose(&mut rand::thread_rng())
-            .ok_or_else(|| "No template text available".to_string())?;
+    fn fill_template(&self, user_id: &str, template: &MessageTemplate, work_type: &WorkType, _focus_state: &FocusState) -> Result<String, String> {
+        // Prefer text variants the user hasn't seen recently
+        let now = Utc::now();
+        let fresh_variants: Vec<&String> = template.templates.iter()
+            .filter(|text| self.novelty.meets_floor(self.novelty.novelty_score(user_id, text, now)))
+            .collect();
+        let template_text = if fresh_variants.is_empty() {
+            template.templates.choose(&mut rand::thread_rng())
+        } else {
+            fresh_variants.choose(&mut rand::thread_rng()).copied()
+        }
+        .ok_or_else(|| "No template text available".to_string())?;
 
         let mut filled_text = template_text.clone();
 
@@ -899,4 +1061,53 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].tone, MessageTone::Gentle);
     }
+
+    #[test]
+    fn test_recently_delivered_template_is_avoided() {
+        let personalization = MessagePersonalization::default();
+        let mut generator = ContextualMessageGenerator::new(personalization);
+
+        let duck = "When code doesn't behave as expected, rubber duck debugging often helps - explain the problem out loud!";
+        let templates = vec![
+            MessageTemplate {
+                id: "rubber_duck".to_string(),
+                category: InterventionType::Encouragement { context: "test".to_string() },
+                tone: MessageTone::Informative,
+                templates: vec![duck.to_string()],
+                placeholders: vec![],
+                min_confidence_threshold: 0.5,
+            },
+            MessageTemplate {
+                id: "split_function".to_string(),
+                category: InterventionType::Encouragement { context: "test".to_string() },
+                tone: MessageTone::Informative,
+                templates: vec!["Try splitting the function into smaller pieces and testing each one.".to_string()],
+                placeholders: vec![],
+                min_confidence_threshold: 0.5,
+            },
+        ];
+        let work_type = WorkType::Coding {
+            language: Some("rust".to_string()),
+            framework: None,
+            confidence: 0.9,
+        };
+
+        generator.novelty.record_delivery("alice", duck, Utc::now());
+
+        for _ in 0..10 {
+            let selected = generator.select_best_template("alice", &templates, &work_type).unwrap();
+            assert_eq!(selected.id, "split_function");
+        }
+
+        // Other users are unaffected, and once everything is stale the best is still returned
+        generator.novelty.record_delivery(
+            "alice",
+            "Try splitting the function into smaller pieces and testing each one.",
+            Utc::now(),
+        );
+        assert!(generator.select_best_template("alice", &templates, &work_type).is_ok());
+
+        generator.reset_novelty_history("alice");
+        assert_eq!(generator.novelty.tracked_count("alice"), 0);
+    }
 }
\ No newline at end of file
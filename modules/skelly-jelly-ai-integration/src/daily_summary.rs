@@ -0,0 +1,358 @@
+//! End-of-Day Summary
+//!
+//! Once a day, at `deliver_at` local time, the summarizer asks the analysis
+//! engine for the day's sessions and focus totals with a `DailySummaryRequest`.
+//! When the `DayActivity` answer arrives it writes a short recap in Skelly's
+//! voice ("you had 3 solid flow blocks..."), counting the suggestions the user
+//! accepted that day, publishes it as a `DailySummary` and keeps it on disk
+//! for history.
+
+use crate::error::Result;
+use crate::types::PersonalityTraits;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::message::{DailySummary, DailySummaryRequest, DayActivity, SessionDigest};
+use skelly_jelly_event_bus::{BusMessage, EventBusTrait, MessagePayload, MessageType, ModuleId};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummaryConfig {
+    pub enabled: bool,
+    /// Local time the summary is delivered
+    pub deliver_at: NaiveTime,
+    /// One JSON file per day is kept here
+    pub history_dir: PathBuf,
+    /// Shortest session that counts as a flow block
+    pub min_flow_block_minutes: u32,
+    /// Share of a session spent in flow for it to count as a flow block, 0–100
+    pub min_flow_percent: f32,
+}
+
+impl Default for DailySummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            deliver_at: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            history_dir: PathBuf::from("summaries"),
+            min_flow_block_minutes: 20,
+            min_flow_percent: 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SummarizerState {
+    /// When each accepted suggestion was accepted
+    accepted: Vec<DateTime<Utc>>,
+    last_requested: Option<NaiveDate>,
+    /// Requests waiting on a `DayActivity` answer
+    pending: HashMap<Uuid, NaiveDate>,
+}
+
+/// Schedules, writes and stores the end-of-day summary
+pub struct DailySummarizer {
+    config: DailySummaryConfig,
+    traits: PersonalityTraits,
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+    state: Mutex<SummarizerState>,
+}
+
+impl DailySummarizer {
+    pub fn new(config: DailySummaryConfig, traits: PersonalityTraits) -> Self {
+        Self {
+            config,
+            traits,
+            event_bus: None,
+            state: Mutex::new(SummarizerState::default()),
+        }
+    }
+
+    /// Request activity and publish summaries on the bus
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Message types the summarizer reacts to
+    pub fn subscribed_types() -> Vec<MessageType> {
+        vec![MessageType::DayActivity]
+    }
+
+    /// Count a suggestion the user accepted
+    pub fn record_accepted(&self, at: DateTime<Utc>) {
+        self.state.lock().unwrap().accepted.push(at);
+    }
+
+    /// The day's request, once `deliver_at` has passed and it hasn't been sent yet
+    pub fn request_if_due(&self, now: DateTime<Local>) -> Option<DailySummaryRequest> {
+        let date = now.date_naive();
+        let mut state = self.state.lock().unwrap();
+        if !self.config.enabled || now.time() < self.config.deliver_at || state.last_requested == Some(date) {
+            return None;
+        }
+
+        let request = DailySummaryRequest {
+            request_id: Uuid::new_v4(),
+            date,
+            from: local_midnight(date),
+            to: local_midnight(date.succ_opt()?),
+            timestamp: Utc::now(),
+        };
+        state.last_requested = Some(date);
+        state.pending.insert(request.request_id, date);
+        Some(request)
+    }
+
+    /// Publish the day's request if it is due; call periodically
+    pub async fn tick(&self, now: DateTime<Local>) {
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        let Some(request) = self.request_if_due(now) else {
+            return;
+        };
+        log::debug!("Requesting activity for the {} summary", request.date);
+        let message = BusMessage::new(ModuleId::AiIntegration, MessagePayload::DailySummaryRequest(request));
+        if let Err(e) = event_bus.publish(message).await {
+            log::warn!("Failed to request daily activity: {}", e);
+        }
+    }
+
+    /// Turn a `DayActivity` answer into the day's summary, store it and publish it
+    pub async fn handle_message(&self, message: &BusMessage) -> Result<Option<DailySummary>> {
+        let MessagePayload::DayActivity(activity) = &message.payload else {
+            return Ok(None);
+        };
+        if self.state.lock().unwrap().pending.remove(&activity.request_id).is_none() {
+            return Ok(None);
+        }
+
+        let summary = self.summarize(activity);
+        self.save(&summary)?;
+        if let Some(event_bus) = &self.event_bus {
+            let message = BusMessage::new(ModuleId::AiIntegration, MessagePayload::DailySummary(summary.clone()));
+            if let Err(e) = event_bus.publish(message).await {
+                log::warn!("Failed to publish daily summary: {}", e);
+            }
+        }
+        Ok(Some(summary))
+    }
+
+    /// Write the recap for a day's activity
+    pub fn summarize(&self, activity: &DayActivity) -> DailySummary {
+        let (from, to) = (local_midnight(activity.date), activity.date.succ_opt().map(local_midnight));
+        let interventions_accepted = {
+            let mut state = self.state.lock().unwrap();
+            // Nothing before the summarized day will be asked about again
+            state.accepted.retain(|at| *at >= from);
+            state.accepted.iter().filter(|at| to.is_none_or(|to| **at < to)).count() as u32
+        };
+
+        let blocks: Vec<&SessionDigest> = activity
+            .sessions
+            .iter()
+            .filter(|session| {
+                session.active_minutes >= self.config.min_flow_block_minutes
+                    && session.flow_percent >= self.config.min_flow_percent
+            })
+            .collect();
+        let active_minutes = activity.sessions.iter().map(|session| session.active_minutes).sum();
+
+        DailySummary {
+            date: activity.date,
+            text: self.compose(activity, &blocks, interventions_accepted),
+            flow_blocks: blocks.len() as u32,
+            focused_minutes: activity.focused_minutes,
+            active_minutes,
+            interventions_accepted,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// A stored summary
+    pub fn history(&self, date: NaiveDate) -> Result<Option<DailySummary>> {
+        let path = self.path_for(date);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    fn compose(&self, activity: &DayActivity, blocks: &[&SessionDigest], accepted: u32) -> String {
+        if activity.sessions.is_empty() {
+            return "Quiet day on my end - no work sessions to recap. Rest counts too.".to_string();
+        }
+
+        let mut sentences = Vec::new();
+        let opener = if self.traits.cheerfulness >= 0.6 { "Nice work today! " } else { "" };
+        match blocks.iter().max_by_key(|block| block.active_minutes) {
+            Some(longest) => {
+                let kind = longest.work_type.as_deref().map(|kind| format!(" of {}", kind)).unwrap_or_default();
+                sentences.push(format!(
+                    "{}You had {} solid flow {}; the longest was {}{}.",
+                    opener,
+                    blocks.len(),
+                    if blocks.len() == 1 { "block" } else { "blocks" },
+                    minutes_label(longest.active_minutes),
+                    kind
+                ));
+            }
+            None => sentences.push(format!(
+                "You put in {} across {} {}, even without a long flow stretch.",
+                minutes_label(activity.sessions.iter().map(|session| session.active_minutes).sum()),
+                activity.sessions.len(),
+                if activity.sessions.len() == 1 { "session" } else { "sessions" }
+            )),
+        }
+
+        if activity.focused_minutes > 0 {
+            let mut focus = format!("That's {} of real focus", minutes_label(activity.focused_minutes));
+            if let Some(app) = &activity.top_application {
+                focus.push_str(&format!(", mostly in {}", app));
+            }
+            focus.push('.');
+            sentences.push(focus);
+        }
+
+        if accepted > 0 {
+            sentences.push(format!(
+                "You tried {} of my suggestions - thanks for giving {} a go.",
+                accepted,
+                if accepted == 1 { "it" } else { "them" }
+            ));
+        }
+
+        let closer = if blocks.is_empty() && self.traits.supportiveness >= 0.5 {
+            "Scattered days happen; tomorrow's a fresh start."
+        } else if self.traits.humor >= 0.5 {
+            "Not bad for a bag of bones! 💀"
+        } else {
+            "See you tomorrow."
+        };
+        sentences.push(closer.to_string());
+        sentences.join(" ")
+    }
+
+    fn save(&self, summary: &DailySummary) -> Result<()> {
+        let path = self.path_for(summary.date);
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string_pretty(summary)?)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.config.history_dir.join(format!("{}.json", date.format("%Y-%m-%d")))
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+fn minutes_label(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(date: NaiveDate, hour: u32, active_minutes: u32, flow_percent: f32) -> SessionDigest {
+        let start = local_midnight(date) + chrono::Duration::hours(hour as i64);
+        SessionDigest {
+            start,
+            end: start + chrono::Duration::minutes(active_minutes as i64),
+            active_minutes,
+            flow_percent,
+            interruptions: 1,
+            work_type: Some("coding".to_string()),
+        }
+    }
+
+    fn activity(request_id: Uuid, date: NaiveDate) -> DayActivity {
+        DayActivity {
+            request_id,
+            date,
+            sessions: vec![
+                session(date, 9, 80, 85.0),
+                session(date, 11, 45, 70.0),
+                session(date, 14, 30, 20.0),
+                session(date, 16, 25, 90.0),
+            ],
+            focused_minutes: 135,
+            distracted_minutes: 20,
+            top_application: Some("code".to_string()),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summary_is_requested_once_and_stored() {
+        let dir = std::env::temp_dir().join(format!("skelly-summaries-{}", Uuid::new_v4()));
+        let config = DailySummaryConfig { history_dir: dir.clone(), ..Default::default() };
+        let summarizer = DailySummarizer::new(config, PersonalityTraits::default());
+
+        let morning = Local::now().date_naive().and_hms_opt(9, 0, 0).unwrap();
+        let morning = Local.from_local_datetime(&morning).earliest().unwrap();
+        assert!(summarizer.request_if_due(morning).is_none());
+
+        let evening = morning + chrono::Duration::hours(10);
+        let request = summarizer.request_if_due(evening).expect("due after deliver_at");
+        assert!(summarizer.request_if_due(evening).is_none());
+        assert_eq!(request.to - request.from, chrono::Duration::hours(24));
+
+        summarizer.record_accepted(request.from + chrono::Duration::hours(10));
+        summarizer.record_accepted(request.from - chrono::Duration::hours(2));
+
+        let answer = BusMessage::new(
+            ModuleId::AnalysisEngine,
+            MessagePayload::DayActivity(activity(request.request_id, request.date)),
+        );
+        let summary = summarizer.handle_message(&answer).await.unwrap().unwrap();
+        assert_eq!(summary.flow_blocks, 3);
+        assert_eq!(summary.active_minutes, 180);
+        assert_eq!(summary.interventions_accepted, 1);
+        assert_eq!(summarizer.history(request.date).unwrap(), Some(summary));
+
+        // Answers to requests we didn't make are ignored
+        assert!(summarizer.handle_message(&answer).await.unwrap().is_none());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_recap_follows_personality() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let summarizer = DailySummarizer::new(DailySummaryConfig::default(), PersonalityTraits::default());
+        let summary = summarizer.summarize(&activity(Uuid::new_v4(), date));
+        assert_eq!(
+            summary.text,
+            "Nice work today! You had 3 solid flow blocks; the longest was 1h 20m of coding. \
+             That's 2h 15m of real focus, mostly in code. Not bad for a bag of bones! 💀"
+        );
+
+        let reserved = PersonalityTraits { cheerfulness: 0.3, humor: 0.2, ..Default::default() };
+        let summarizer = DailySummarizer::new(DailySummaryConfig::default(), reserved);
+        let mut scattered = activity(Uuid::new_v4(), date);
+        scattered.sessions.iter_mut().for_each(|session| session.flow_percent = 10.0);
+        let text = summarizer.summarize(&scattered).text;
+        assert!(text.starts_with("You put in 3h across 4 sessions"));
+        assert!(text.ends_with("tomorrow's a fresh start."));
+    }
+}
@@ -0,0 +1,421 @@
+//! Intervention Delivery
+//!
+//! A response can reach the user through the figurine's speech bubble, an OS
+//! notification, or spoken aloud by the platform's text-to-speech.
+//! [`DeliveryRouter`] picks the channels for each intervention type from
+//! [`DeliveryConfig`], delivers through each one and publishes an
+//! `InterventionDelivered` event per channel, so other modules know whether
+//! the response actually arrived.
+//!
+//! Notifications and speech use the platform's own tools (`osascript` and
+//! `say` on macOS, `notify-send` and `spd-say` on Linux, PowerShell on
+//! Windows); nothing is sent off the machine.
+
+use crate::error::{AIIntegrationError, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::message::{InterventionDelivered, InterventionResponse};
+use skelly_jelly_event_bus::{BusMessage, EventBusTrait, MessagePayload, ModuleId};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryChannelKind {
+    /// The figurine's speech bubble
+    Figurine,
+    /// An OS notification
+    Notification,
+    /// Read aloud by the platform's text-to-speech
+    Speech,
+}
+
+impl DeliveryChannelKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Figurine => "figurine",
+            Self::Notification => "notification",
+            Self::Speech => "speech",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliveryConfig {
+    /// Channels for intervention types without an entry in `per_intervention`
+    pub default_channels: Vec<DeliveryChannelKind>,
+    /// Channels by intervention type, e.g. `break_reminder` as a notification
+    pub per_intervention: HashMap<String, Vec<DeliveryChannelKind>>,
+    /// Whether responses may be spoken aloud; off unless the user opts in
+    pub speech_enabled: bool,
+    /// Title of OS notifications
+    pub notification_title: String,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            default_channels: vec![DeliveryChannelKind::Figurine],
+            per_intervention: HashMap::new(),
+            speech_enabled: false,
+            notification_title: "Skelly".to_string(),
+        }
+    }
+}
+
+/// A way of getting a response in front of the user
+#[async_trait::async_trait]
+pub trait DeliveryChannel: Send + Sync {
+    fn kind(&self) -> DeliveryChannelKind;
+
+    /// Deliver `response`, returning once the channel has accepted it
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()>;
+}
+
+/// Shows responses in the figurine's speech bubble by publishing them on the bus
+pub struct FigurineChannel {
+    event_bus: Arc<dyn EventBusTrait>,
+}
+
+impl FigurineChannel {
+    pub fn new(event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self { event_bus }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeliveryChannel for FigurineChannel {
+    fn kind(&self) -> DeliveryChannelKind {
+        DeliveryChannelKind::Figurine
+    }
+
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        let message = BusMessage::new(ModuleId::AiIntegration, MessagePayload::InterventionResponse(response.clone()));
+        self.event_bus
+            .publish(message)
+            .await
+            .map(drop)
+            .map_err(|e| delivery_failed(DeliveryChannelKind::Figurine, e))
+    }
+}
+
+/// Posts responses as OS notifications
+pub struct NotificationChannel {
+    title: String,
+}
+
+impl NotificationChannel {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeliveryChannel for NotificationChannel {
+    fn kind(&self) -> DeliveryChannelKind {
+        DeliveryChannelKind::Notification
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        // Passed as arguments so the text never needs AppleScript escaping
+        let script = ["-e", "on run argv", "-e", "display notification (item 1 of argv) with title (item 2 of argv)", "-e", "end run"];
+        let mut args: Vec<&str> = script.to_vec();
+        args.extend([response.response_text.as_str(), self.title.as_str()]);
+        run(self.kind(), "osascript", &args, None).await
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        run(self.kind(), "notify-send", &["--app-name", &self.title, &self.title, &response.response_text], None).await
+    }
+
+    #[cfg(windows)]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        // Balloon tip from the tray; title and text arrive on stdin so they need no quoting
+        let script = "Add-Type -AssemblyName System.Windows.Forms; \
+            $title, $text = [Console]::In.ReadToEnd() -split \"`n\", 2; \
+            $icon = New-Object System.Windows.Forms.NotifyIcon; \
+            $icon.Icon = [System.Drawing.SystemIcons]::Information; \
+            $icon.Visible = $true; \
+            $icon.ShowBalloonTip(10000, $title, $text, 'None'); \
+            Start-Sleep -Seconds 10; \
+            $icon.Dispose()";
+        let input = format!("{}\n{}", self.title, response.response_text);
+        run(self.kind(), "powershell", &["-NoProfile", "-Command", script], Some(&input)).await
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    async fn deliver(&self, _response: &InterventionResponse) -> Result<()> {
+        Err(AIIntegrationError::FeatureNotAvailable { feature: "OS notifications".to_string() })
+    }
+}
+
+/// Reads responses aloud with the platform's text-to-speech
+pub struct SpeechChannel;
+
+#[async_trait::async_trait]
+impl DeliveryChannel for SpeechChannel {
+    fn kind(&self) -> DeliveryChannelKind {
+        DeliveryChannelKind::Speech
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        run(self.kind(), "say", &["-f", "-"], Some(&response.response_text)).await
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        // speech-dispatcher where installed, espeak otherwise
+        match run(self.kind(), "spd-say", &["--wait", &response.response_text], None).await {
+            Err(AIIntegrationError::FeatureNotAvailable { .. }) => {
+                run(self.kind(), "espeak", &["--stdin"], Some(&response.response_text)).await
+            }
+            spoken => spoken,
+        }
+    }
+
+    #[cfg(windows)]
+    async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+        let script = "Add-Type -AssemblyName System.Speech; \
+            (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())";
+        run(self.kind(), "powershell", &["-NoProfile", "-Command", script], Some(&response.response_text)).await
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    async fn deliver(&self, _response: &InterventionResponse) -> Result<()> {
+        Err(AIIntegrationError::FeatureNotAvailable { feature: "text-to-speech".to_string() })
+    }
+}
+
+/// Run a platform tool to completion, writing `input` to its stdin
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux", windows)), allow(dead_code))]
+async fn run(channel: DeliveryChannelKind, program: &str, args: &[&str], input: Option<&str>) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                AIIntegrationError::FeatureNotAvailable { feature: format!("{} ({} not installed)", channel.as_str(), program) }
+            }
+            _ => delivery_failed(channel, e),
+        })?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes()).await.map_err(|e| delivery_failed(channel, e))?;
+    }
+
+    let output = child.wait_with_output().await.map_err(|e| delivery_failed(channel, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(delivery_failed(channel, format!("{} exited with {}: {}", program, output.status, stderr.trim())))
+    }
+}
+
+fn delivery_failed(channel: DeliveryChannelKind, reason: impl std::fmt::Display) -> AIIntegrationError {
+    AIIntegrationError::DeliveryFailed { channel: channel.as_str().to_string(), reason: reason.to_string() }
+}
+
+/// Delivers responses through the channels configured for their intervention type
+pub struct DeliveryRouter {
+    config: DeliveryConfig,
+    channels: HashMap<DeliveryChannelKind, Arc<dyn DeliveryChannel>>,
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+}
+
+impl DeliveryRouter {
+    /// Router with OS notifications and, if enabled, speech
+    ///
+    /// The figurine channel needs the bus and is added by [`with_event_bus`](Self::with_event_bus).
+    pub fn new(config: DeliveryConfig) -> Self {
+        let notifications = NotificationChannel::new(config.notification_title.clone());
+        let speech_enabled = config.speech_enabled;
+        let router = Self { channels: HashMap::new(), event_bus: None, config }.with_channel(Arc::new(notifications));
+        if speech_enabled {
+            router.with_channel(Arc::new(SpeechChannel))
+        } else {
+            router
+        }
+    }
+
+    /// Add or replace the channel of `channel.kind()`
+    pub fn with_channel(mut self, channel: Arc<dyn DeliveryChannel>) -> Self {
+        self.channels.insert(channel.kind(), channel);
+        self
+    }
+
+    /// Show responses on the figurine and publish delivery confirmations
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self = self.with_channel(Arc::new(FigurineChannel::new(Arc::clone(&event_bus))));
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Channels a response to `intervention_type` goes to
+    ///
+    /// Speech is left out unless the user enabled it, and so is any channel
+    /// not available to this router.
+    pub fn channels_for(&self, intervention_type: &str) -> Vec<DeliveryChannelKind> {
+        let configured = self
+            .config
+            .per_intervention
+            .get(intervention_type)
+            .unwrap_or(&self.config.default_channels);
+        let mut channels = Vec::new();
+        for &kind in configured {
+            let allowed = kind != DeliveryChannelKind::Speech || self.config.speech_enabled;
+            if allowed && self.channels.contains_key(&kind) && !channels.contains(&kind) {
+                channels.push(kind);
+            }
+        }
+        channels
+    }
+
+    /// Deliver `response` through every channel for `intervention_type`, one confirmation per channel
+    pub async fn deliver(&self, intervention_type: &str, response: &InterventionResponse) -> Vec<InterventionDelivered> {
+        let channels = self.channels_for(intervention_type);
+        if channels.is_empty() {
+            log::warn!("No delivery channel available for {} intervention {}", intervention_type, response.request_id);
+        }
+
+        let mut confirmations = Vec::with_capacity(channels.len());
+        for kind in channels {
+            let result = self.channels[&kind].deliver(response).await;
+            if let Err(e) = &result {
+                log::warn!("Delivering intervention {} by {} failed: {}", response.request_id, kind.as_str(), e);
+            }
+            let confirmation = InterventionDelivered {
+                request_id: response.request_id,
+                channel: kind.as_str().to_string(),
+                delivered: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+                timestamp: Utc::now(),
+            };
+
+            if let Some(event_bus) = &self.event_bus {
+                let message = BusMessage::new(
+                    ModuleId::AiIntegration,
+                    MessagePayload::InterventionDelivered(confirmation.clone()),
+                );
+                if let Err(e) = event_bus.publish(message).await {
+                    log::warn!("Failed to publish delivery confirmation for {}: {}", response.request_id, e);
+                }
+            }
+            confirmations.push(confirmation);
+        }
+        confirmations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use skelly_jelly_event_bus::message::ResponseMetadata;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Records deliveries, failing when told to
+    struct FakeChannel {
+        kind: DeliveryChannelKind,
+        fail: bool,
+        delivered: Mutex<Vec<Uuid>>,
+    }
+
+    impl FakeChannel {
+        fn new(kind: DeliveryChannelKind, fail: bool) -> Arc<Self> {
+            Arc::new(Self { kind, fail, delivered: Mutex::new(Vec::new()) })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DeliveryChannel for FakeChannel {
+        fn kind(&self) -> DeliveryChannelKind {
+            self.kind
+        }
+
+        async fn deliver(&self, response: &InterventionResponse) -> Result<()> {
+            if self.fail {
+                return Err(delivery_failed(self.kind, "nobody home"));
+            }
+            self.delivered.lock().unwrap().push(response.request_id);
+            Ok(())
+        }
+    }
+
+    fn response() -> InterventionResponse {
+        InterventionResponse {
+            request_id: Uuid::new_v4(),
+            response_text: "Stretch break? Your spine will thank you.".to_string(),
+            animation_cues: Vec::new(),
+            metadata: ResponseMetadata::default(),
+        }
+    }
+
+    #[test]
+    fn test_channels_follow_intervention_type_and_preferences() {
+        let mut config = DeliveryConfig::default();
+        config.per_intervention.insert(
+            "break_reminder".to_string(),
+            vec![DeliveryChannelKind::Notification, DeliveryChannelKind::Speech, DeliveryChannelKind::Figurine],
+        );
+        let router = DeliveryRouter::new(config.clone())
+            .with_channel(FakeChannel::new(DeliveryChannelKind::Figurine, false))
+            .with_channel(FakeChannel::new(DeliveryChannelKind::Speech, false));
+
+        // Speech stays off until the user opts in
+        assert_eq!(
+            router.channels_for("break_reminder"),
+            vec![DeliveryChannelKind::Notification, DeliveryChannelKind::Figurine]
+        );
+        assert_eq!(router.channels_for("encouragement"), vec![DeliveryChannelKind::Figurine]);
+
+        config.speech_enabled = true;
+        let router = DeliveryRouter::new(config);
+        assert_eq!(
+            router.channels_for("break_reminder"),
+            vec![DeliveryChannelKind::Notification, DeliveryChannelKind::Speech]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirms_each_channel() {
+        let bus = skelly_jelly_event_bus::create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let (_, mut confirmations_seen) = bus
+            .subscribe_typed::<InterventionDelivered>(ModuleId::Gamification, skelly_jelly_event_bus::DeliveryMode::BestEffort)
+            .unwrap();
+
+        let config = DeliveryConfig {
+            default_channels: vec![DeliveryChannelKind::Figurine, DeliveryChannelKind::Notification],
+            ..Default::default()
+        };
+        let notification = FakeChannel::new(DeliveryChannelKind::Notification, true);
+        let router = DeliveryRouter::new(config).with_event_bus(bus).with_channel(notification);
+
+        let response = response();
+        let confirmations = router.deliver("encouragement", &response).await;
+        assert_eq!(confirmations.len(), 2);
+        assert_eq!(confirmations[0].channel, "figurine");
+        assert!(confirmations[0].delivered);
+        assert_eq!(confirmations[1].channel, "notification");
+        assert!(!confirmations[1].delivered);
+        assert!(confirmations[1].error.as_deref().unwrap().contains("nobody home"));
+
+        for expected in &confirmations {
+            let next = tokio::time::timeout(std::time::Duration::from_secs(2), confirmations_seen.next());
+            let (_, seen) = next.await.unwrap().unwrap();
+            assert_eq!(&seen, expected);
+        }
+    }
+}
@@ -25,6 +25,12 @@ pub enum AIIntegrationError {
     #[error("GPU support not available")]
     GPUNotAvailable,
 
+    #[error("Model download failed: {reason}")]
+    ModelDownloadFailed { reason: String },
+
+    #[error("Model failed integrity check: {reason}")]
+    ModelIntegrityFailed { reason: String },
+
     // Inference and generation errors
     #[error("Local inference failed")]
     InferenceFailed,
@@ -87,6 +93,15 @@ pub enum AIIntegrationError {
     #[error("Suggestion validation failed")]
     SuggestionValidationFailed,
 
+    #[error("Personality profile rejected: {reason}")]
+    InvalidProfile { reason: String },
+
+    #[error("Context memory storage failed: {reason}")]
+    ContextStorageFailed { reason: String },
+
+    #[error("Delivery by {channel} failed: {reason}")]
+    DeliveryFailed { channel: String, reason: String },
+
     // Configuration and setup errors
     #[error("Invalid configuration: {field}")]
     InvalidConfig { field: String },
@@ -126,6 +141,7 @@ impl AIIntegrationError {
             Self::ModelNotFound
             | Self::InsufficientMemory { .. }
             | Self::APIKeyMissing { .. }
+            | Self::ModelIntegrityFailed { .. }
             | Self::PrivacyViolation
             | Self::PIIDetected
             | Self::PromptInjectionDetected
@@ -135,6 +151,7 @@ impl AIIntegrationError {
 
             // Temporary failures that might succeed on retry
             Self::ModelLoadFailed { .. }
+            | Self::ModelDownloadFailed { .. }
             | Self::InferenceFailed
             | Self::GenerationTimeout { .. }
             | Self::APIRateLimited { .. }
@@ -156,11 +173,14 @@ impl AIIntegrationError {
             // Template and validation errors
             Self::TemplateNotFound => false, // Need different template
             Self::SuggestionValidationFailed => true,
+            Self::InvalidProfile { .. } => false, // Need a different file
 
             // System errors
             Self::NotInitialized => false, // Need initialization
             Self::FileSystemError => true,
             Self::SerializationError => true,
+            Self::ContextStorageFailed { .. } => true,
+            Self::DeliveryFailed { .. } => true,
 
             // Generic
             Self::InternalError => true,
@@ -170,6 +190,22 @@ impl AIIntegrationError {
         }
     }
 
+    /// Check if the error means no model could answer, so an offline response should be used
+    pub fn is_model_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::ModelLoadFailed { .. }
+                | Self::ModelDownloadFailed { .. }
+                | Self::ModelIntegrityFailed { .. }
+                | Self::ModelNotFound
+                | Self::InsufficientMemory { .. }
+                | Self::GPUNotAvailable
+                | Self::InferenceFailed
+                | Self::GenerationTimeout { .. }
+                | Self::ResourceUnavailable
+        )
+    }
+
     /// Get the severity level of the error
     pub fn severity(&self) -> ErrorSeverity {
         match self {
@@ -181,6 +217,7 @@ impl AIIntegrationError {
             // High severity errors that impact user experience
             Self::ModelLoadFailed { .. }
             | Self::InsufficientMemory { .. }
+            | Self::ModelIntegrityFailed { .. }
             | Self::PrivacyViolation
             | Self::PIIDetected
             | Self::PromptInjectionDetected
@@ -0,0 +1,403 @@
+//! Expertise estimation from behavior
+//!
+//! Instead of asking the user how experienced they are, the estimator infers
+//! an [`ExpertiseLevel`] per work domain (`coding_rust`, `design_figma`, ...)
+//! from what they do: how they drive the editor, what they run in a
+//! terminal, and the words they use when declaring a task. Each
+//! [`ExpertiseSignal`] scores between 0.0 (beginner-like) and 1.0
+//! (expert-like) and carries a weight for how much it says.
+//!
+//! Estimates move gradually: the score follows signals at `learning_rate`,
+//! confidence grows with accumulated evidence and shrinks when signals
+//! disagree, and the level only changes once the score is `hysteresis` past
+//! a threshold. Until an estimate is confident, [`ExpertiseEstimator::level`]
+//! returns `None` so callers keep their default.
+
+use crate::personality_enhanced::ExpertiseLevel;
+use crate::types::{WorkContext, WorkType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Score at which a domain counts as intermediate
+const INTERMEDIATE_SCORE: f32 = 0.4;
+/// Score at which a domain counts as expert
+const EXPERT_SCORE: f32 = 0.7;
+
+/// Words in a task description that suggest someone is still learning
+const NOVICE_TERMS: [&str; 10] = [
+    "tutorial", "learn", "learning", "beginner", "intro", "introduction", "basics", "course", "exercise", "first",
+];
+
+/// Commands that mostly experienced terminal users reach for
+const ADVANCED_COMMANDS: [&str; 14] = [
+    "awk", "sed", "xargs", "jq", "ssh", "tmux", "rsync", "strace", "gdb", "lldb", "perf", "find", "kubectl", "make",
+];
+
+/// Git subcommands beyond the everyday add/commit/push
+const ADVANCED_GIT: [&str; 6] = ["rebase", "bisect", "reflog", "cherry-pick", "worktree", "filter-repo"];
+
+/// Behavior that says something about expertise in a domain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExpertiseSignal {
+    /// Editing activity in `domain` over a period
+    EditorUsage {
+        domain: String,
+        edits: u32,
+        /// Edits made through keyboard shortcuts rather than menus
+        shortcut_edits: u32,
+        undos: u32,
+        /// Go-to-definition, symbol search and similar jumps
+        navigation_jumps: u32,
+    },
+    /// Commands run in a terminal while working in `domain`
+    TerminalActivity { domain: String, commands: Vec<String> },
+    /// A task the user declared; the domain is guessed from its words if not given
+    TaskVocabulary { domain: Option<String>, description: String },
+}
+
+/// Words that place a task in a domain and mark advanced work in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainVocabulary {
+    pub domain: String,
+    /// Any of these puts a task in the domain
+    pub keywords: Vec<String>,
+    /// Terms that suggest advanced work
+    pub expert_terms: Vec<String>,
+}
+
+impl DomainVocabulary {
+    pub fn new(domain: &str, keywords: &[&str], expert_terms: &[&str]) -> Self {
+        Self {
+            domain: domain.to_string(),
+            keywords: keywords.iter().map(|word| word.to_string()).collect(),
+            expert_terms: expert_terms.iter().map(|term| term.to_string()).collect(),
+        }
+    }
+}
+
+/// Tuning for how quickly estimates move
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpertiseConfig {
+    /// How far a full-weight signal moves the score towards its own
+    pub learning_rate: f32,
+    /// Evidence (summed signal weight) at which confidence reaches half
+    pub half_confidence_evidence: f32,
+    /// Below this confidence no level is reported
+    pub min_confidence: f32,
+    /// How far past a threshold the score must go before the level changes
+    pub hysteresis: f32,
+}
+
+impl Default for ExpertiseConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.2,
+            half_confidence_evidence: 3.0,
+            min_confidence: 0.6,
+            hysteresis: 0.05,
+        }
+    }
+}
+
+/// Current estimate for one domain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpertiseEstimate {
+    pub level: ExpertiseLevel,
+    /// 0.0 (beginner-like) to 1.0 (expert-like)
+    pub score: f32,
+    /// 0.0 to 1.0; grows with evidence, shrinks when signals disagree
+    pub confidence: f32,
+    pub observations: u32,
+    pub updated_at: DateTime<Utc>,
+    /// Summed signal weight
+    evidence: f32,
+    /// Running average distance between signals and the score
+    disagreement: f32,
+}
+
+impl ExpertiseEstimate {
+    fn new() -> Self {
+        Self {
+            level: ExpertiseLevel::Beginner,
+            score: 0.5,
+            confidence: 0.0,
+            observations: 0,
+            updated_at: Utc::now(),
+            evidence: 0.0,
+            disagreement: 0.0,
+        }
+    }
+
+    fn update(&mut self, observed: f32, weight: f32, config: &ExpertiseConfig) {
+        let step = config.learning_rate * weight;
+        // The first signal sets the score; later ones nudge it
+        if self.observations == 0 {
+            self.score = observed;
+        } else {
+            self.disagreement += step * ((observed - self.score).abs() - self.disagreement);
+            self.score += step * (observed - self.score);
+        }
+        self.evidence += weight;
+        self.observations += 1;
+
+        let saturation = self.evidence / (self.evidence + config.half_confidence_evidence);
+        self.confidence = (saturation * (1.0 - self.disagreement)).clamp(0.0, 1.0);
+
+        self.level = match self.level.clone() {
+            ExpertiseLevel::Beginner if self.score >= EXPERT_SCORE + config.hysteresis => ExpertiseLevel::Expert,
+            ExpertiseLevel::Beginner if self.score >= INTERMEDIATE_SCORE + config.hysteresis => ExpertiseLevel::Intermediate,
+            ExpertiseLevel::Intermediate if self.score >= EXPERT_SCORE + config.hysteresis => ExpertiseLevel::Expert,
+            ExpertiseLevel::Intermediate if self.score < INTERMEDIATE_SCORE - config.hysteresis => ExpertiseLevel::Beginner,
+            ExpertiseLevel::Expert if self.score < INTERMEDIATE_SCORE - config.hysteresis => ExpertiseLevel::Beginner,
+            ExpertiseLevel::Expert if self.score < EXPERT_SCORE - config.hysteresis => ExpertiseLevel::Intermediate,
+            level => level,
+        };
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Infers expertise per work domain from behavioral signals
+#[derive(Debug, Clone)]
+pub struct ExpertiseEstimator {
+    config: ExpertiseConfig,
+    vocabularies: Vec<DomainVocabulary>,
+    estimates: HashMap<String, ExpertiseEstimate>,
+}
+
+impl Default for ExpertiseEstimator {
+    fn default() -> Self {
+        Self::new(ExpertiseConfig::default())
+    }
+}
+
+impl ExpertiseEstimator {
+    pub fn new(config: ExpertiseConfig) -> Self {
+        Self {
+            config,
+            vocabularies: Self::builtin_vocabularies(),
+            estimates: HashMap::new(),
+        }
+    }
+
+    fn builtin_vocabularies() -> Vec<DomainVocabulary> {
+        vec![
+            DomainVocabulary::new(
+                "coding_rust",
+                &["rust", "cargo", "crate", "clippy"],
+                &["lifetime", "borrow", "trait", "unsafe", "macro", "async", "generic", "ffi", "pin", "send"],
+            ),
+            DomainVocabulary::new(
+                "coding_python",
+                &["python", "pip", "django", "pandas", "pytest"],
+                &["decorator", "generator", "metaclass", "asyncio", "comprehension", "descriptor", "typing"],
+            ),
+            DomainVocabulary::new(
+                "coding_javascript",
+                &["javascript", "typescript", "node", "npm", "react"],
+                &["closure", "prototype", "promise", "generics", "bundler", "reducer", "hooks"],
+            ),
+            DomainVocabulary::new(
+                "design_figma",
+                &["figma", "mockup", "wireframe", "prototype"],
+                &["auto layout", "component", "variant", "design token", "constraints", "typography"],
+            ),
+        ]
+    }
+
+    /// Recognize tasks in `vocabulary.domain`, replacing any vocabulary for it
+    pub fn with_vocabulary(mut self, vocabulary: DomainVocabulary) -> Self {
+        self.vocabularies.retain(|existing| existing.domain != vocabulary.domain);
+        self.vocabularies.push(vocabulary);
+        self
+    }
+
+    /// Fold `signal` into the estimates; returns the domains it touched
+    pub fn observe(&mut self, signal: &ExpertiseSignal) -> Vec<String> {
+        let scored = self.score(signal);
+        for (domain, observed, weight) in &scored {
+            self.estimates
+                .entry(domain.clone())
+                .or_insert_with(ExpertiseEstimate::new)
+                .update(*observed, *weight, &self.config);
+        }
+        scored.into_iter().map(|(domain, _, _)| domain).collect()
+    }
+
+    /// Estimate for `domain`, however confident
+    pub fn estimate(&self, domain: &str) -> Option<&ExpertiseEstimate> {
+        self.estimates.get(domain)
+    }
+
+    /// Level in `domain`, once the estimate is confident enough
+    pub fn level(&self, domain: &str) -> Option<ExpertiseLevel> {
+        self.estimate(domain)
+            .filter(|estimate| estimate.confidence >= self.config.min_confidence)
+            .map(|estimate| estimate.level.clone())
+    }
+
+    /// Level for the domain `work_context` belongs to
+    pub fn level_for(&self, work_context: &WorkContext) -> Option<ExpertiseLevel> {
+        self.level(&work_domain(work_context))
+    }
+
+    pub fn estimates(&self) -> &HashMap<String, ExpertiseEstimate> {
+        &self.estimates
+    }
+
+    /// (domain, score, weight) for each domain the signal says something about
+    fn score(&self, signal: &ExpertiseSignal) -> Vec<(String, f32, f32)> {
+        match signal {
+            ExpertiseSignal::EditorUsage { domain, edits, shortcut_edits, undos, navigation_jumps } => {
+                if *edits == 0 {
+                    return Vec::new();
+                }
+                let edits = *edits as f32;
+                let shortcuts = (*shortcut_edits as f32 / edits).min(1.0);
+                // Undoing one edit in five or more reads as trial and error
+                let steadiness = 1.0 - (*undos as f32 / edits * 5.0).min(1.0);
+                let navigation = (*navigation_jumps as f32 / edits * 5.0).min(1.0);
+                let score = 0.5 * shortcuts + 0.3 * steadiness + 0.2 * navigation;
+                vec![(domain.clone(), score, (edits / 200.0).min(1.0))]
+            }
+            ExpertiseSignal::TerminalActivity { domain, commands } => {
+                if commands.is_empty() {
+                    return Vec::new();
+                }
+                let total: f32 = commands.iter().map(|command| command_score(command)).sum();
+                let score = total / commands.len() as f32;
+                vec![(domain.clone(), score, (commands.len() as f32 / 20.0).min(1.0))]
+            }
+            ExpertiseSignal::TaskVocabulary { domain, description } => {
+                let text = description.to_lowercase();
+                let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric() && c != '-').filter(|w| !w.is_empty()).collect();
+                let novice = NOVICE_TERMS.iter().filter(|term| words.contains(term)).count();
+
+                self.vocabularies
+                    .iter()
+                    .filter(|vocabulary| match domain {
+                        Some(domain) => vocabulary.domain == *domain,
+                        None => vocabulary.keywords.iter().any(|keyword| words.contains(&keyword.as_str())),
+                    })
+                    .filter_map(|vocabulary| {
+                        // Phrases match anywhere, single terms only as whole words
+                        let expert = vocabulary
+                            .expert_terms
+                            .iter()
+                            .filter(|term| if term.contains(' ') { text.contains(term.as_str()) } else { words.contains(&term.as_str()) })
+                            .count();
+                        if expert + novice == 0 {
+                            return None;
+                        }
+                        let score = expert as f32 / (expert + novice) as f32;
+                        // A task description is a few words; weak evidence on its own
+                        Some((vocabulary.domain.clone(), score, (0.2 * (expert + novice) as f32).min(0.5)))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// 1.0 for commands experienced users reach for, 0.0 for looking things up, 0.5 otherwise
+fn command_score(command: &str) -> f32 {
+    let command = command.trim();
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+
+    if program == "man" || program == "help" || command.ends_with("--help") {
+        return 0.0;
+    }
+    let composed = ["|", "&&", "$(", ">"].iter().any(|operator| command.contains(operator));
+    let advanced_git = program == "git" && words.next().is_some_and(|sub| ADVANCED_GIT.contains(&sub));
+    if composed || advanced_git || ADVANCED_COMMANDS.contains(&program) {
+        1.0
+    } else {
+        0.5
+    }
+}
+
+/// Domain key for a work context, e.g. `coding_rust`
+pub fn work_domain(work_context: &WorkContext) -> String {
+    let domain = match &work_context.work_type {
+        WorkType::Coding { language, .. } => format!("coding_{}", language),
+        WorkType::Writing { document_type } => format!("writing_{}", document_type),
+        WorkType::Design { tool, .. } => format!("design_{}", tool),
+        WorkType::Research { topic } => format!("research_{}", topic),
+        WorkType::Communication { platform } => format!("communication_{}", platform),
+        WorkType::Unknown => "general".to_string(),
+    };
+    domain.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(edits: u32, shortcut_edits: u32, undos: u32) -> ExpertiseSignal {
+        ExpertiseSignal::EditorUsage {
+            domain: "coding_rust".to_string(),
+            edits,
+            shortcut_edits,
+            undos,
+            navigation_jumps: edits / 5,
+        }
+    }
+
+    #[test]
+    fn test_estimates_grow_confident_and_change_gradually() {
+        let mut estimator = ExpertiseEstimator::default();
+
+        estimator.observe(&editor(200, 180, 2));
+        assert_eq!(estimator.estimate("coding_rust").unwrap().level, ExpertiseLevel::Expert);
+        // One session isn't enough to be sure
+        assert_eq!(estimator.level("coding_rust"), None);
+
+        let commands = vec!["git rebase -i main".to_string(), "rg trait | wc -l".to_string(), "cargo build".to_string()];
+        for _ in 0..6 {
+            estimator.observe(&editor(200, 170, 4));
+            estimator.observe(&ExpertiseSignal::TerminalActivity { domain: "coding_rust".to_string(), commands: commands.clone() });
+        }
+        let estimate = estimator.estimate("coding_rust").unwrap().clone();
+        assert!(estimate.confidence >= 0.6, "confidence {}", estimate.confidence);
+        assert_eq!(estimator.level("coding_rust"), Some(ExpertiseLevel::Expert));
+
+        // A single clumsy session nudges the score but doesn't flip the level
+        estimator.observe(&editor(200, 10, 60));
+        let after = estimator.estimate("coding_rust").unwrap();
+        assert!(after.score < estimate.score);
+        assert!(after.confidence < estimate.confidence);
+        assert_eq!(after.level, ExpertiseLevel::Expert);
+        assert_eq!(after.observations, 14);
+    }
+
+    #[test]
+    fn test_task_vocabulary_finds_domain_and_level() {
+        let mut estimator = ExpertiseEstimator::default();
+
+        let touched = estimator.observe(&ExpertiseSignal::TaskVocabulary {
+            domain: None,
+            description: "Rust tutorial: learn the basics of cargo".to_string(),
+        });
+        assert_eq!(touched, vec!["coding_rust"]);
+        assert!(estimator.estimate("coding_rust").unwrap().score < 0.2);
+
+        let touched = estimator.observe(&ExpertiseSignal::TaskVocabulary {
+            domain: None,
+            description: "Fix lifetime bounds on the async trait in the Rust crate behind our Python decorator".to_string(),
+        });
+        assert_eq!(touched.len(), 2);
+        assert!(touched.contains(&"coding_python".to_string()));
+
+        // Tasks with no telling words say nothing
+        assert!(estimator.observe(&ExpertiseSignal::TaskVocabulary { domain: None, description: "email Sam".to_string() }).is_empty());
+
+        let work = WorkContext {
+            work_type: WorkType::Coding { language: "Rust".to_string(), framework: None },
+            ..WorkContext::default()
+        };
+        assert_eq!(work_domain(&work), "coding_rust");
+        assert_eq!(estimator.level_for(&work), None);
+    }
+}
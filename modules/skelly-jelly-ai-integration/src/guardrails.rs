@@ -0,0 +1,303 @@
+//! Post-generation guardrails for LLM output
+//!
+//! Model output is checked before it reaches the user. A response that gives
+//! medical advice, shames the user, or runs too long is regenerated with
+//! instructions naming what went wrong. If the retries still fail, the caller
+//! falls back to a template. Every trigger is counted per category.
+
+use crate::error::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+/// Kind of content the guardrail blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GuardrailCategory {
+    /// Medication, dosage or diagnosis advice
+    MedicalAdvice,
+    /// Blaming, insulting or guilt-tripping the user
+    Shaming,
+    /// Longer than a nudge should be
+    ExcessiveLength,
+}
+
+impl GuardrailCategory {
+    /// Instruction added to the prompt when regenerating after this violation
+    fn correction(&self) -> &'static str {
+        match self {
+            Self::MedicalAdvice => "Do not mention medication, doses, diagnoses or treatment. Stick to work habits.",
+            Self::Shaming => "Do not criticize or blame the user. Be kind and matter-of-fact.",
+            Self::ExcessiveLength => "Answer in one or two short sentences.",
+        }
+    }
+}
+
+/// One rule the output broke
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardrailViolation {
+    pub category: GuardrailCategory,
+    /// Text that triggered the rule
+    pub evidence: String,
+}
+
+/// Guardrail settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardrailConfig {
+    pub enabled: bool,
+    /// Longest acceptable response, in characters
+    pub max_chars: usize,
+    /// Most sentences an acceptable response may have
+    pub max_sentences: usize,
+    /// Regeneration attempts before falling back to a template
+    pub max_regenerations: u32,
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_chars: 280,
+            max_sentences: 3,
+            max_regenerations: 1,
+        }
+    }
+}
+
+/// How a response got past the guardrail
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuardrailOutcome {
+    /// The first response was fine
+    Passed(String),
+    /// A regenerated response was fine
+    Regenerated { text: String, attempts: u32 },
+    /// Every attempt broke a rule; use a template instead
+    Rejected(Vec<GuardrailViolation>),
+}
+
+/// Trigger counts since startup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailMetrics {
+    /// Responses checked, counting each regeneration
+    pub checked: u64,
+    /// Responses that broke at least one rule
+    pub triggered: u64,
+    /// Violations per category
+    pub triggers_by_category: HashMap<GuardrailCategory, u64>,
+    /// Regeneration attempts made
+    pub regenerations: u64,
+    /// Requests rescued by a regeneration
+    pub regeneration_successes: u64,
+    /// Requests that ended on a template
+    pub template_fallbacks: u64,
+}
+
+/// Checks generated text against the content rules
+pub struct ContentGuardrail {
+    config: GuardrailConfig,
+    medical: Vec<Regex>,
+    shaming: Vec<Regex>,
+    metrics: Mutex<GuardrailMetrics>,
+}
+
+impl ContentGuardrail {
+    pub fn new(config: GuardrailConfig) -> Self {
+        let compile = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|p| Regex::new(&format!("(?i){}", p)).expect("guardrail pattern"))
+                .collect()
+        };
+        Self {
+            config,
+            medical: compile(&[
+                r"\b(adderall|ritalin|vyvanse|concerta|strattera|methylphenidate|amphetamine|melatonin|ssris?|antidepressants?|stimulants?)\b",
+                r"\b(medication|meds|pills?|prescription)\b",
+                r"\b\d+\s?(mg|milligrams?|ml)\b",
+                r"\b(dose|dosage|doses)\b",
+                r"\byou (probably |might |may |clearly )?(have|suffer from) (adhd|add|depression|anxiety|bipolar|ocd|autism|insomnia)\b",
+                r"\b(diagnos(e|is|ed)|self-medicate|prescribe)\b",
+            ]),
+            shaming: compile(&[
+                r"\b(lazy|stupid|dumb|pathetic|worthless|useless|hopeless|idiot(ic)?|incompetent|failure)\b",
+                r"\bashamed\b",
+                r"\bno excuse\b",
+                r"\bwhat('?s| is) wrong with you\b",
+                r"\b(wasting|wasted) (all )?(your|the whole|the entire) (time|day|morning|afternoon)\b",
+                r"\byou (always|never) (get distracted|focus|finish|follow through)\b",
+                r"\b(again)\?!",
+                r"\bget it together\b",
+            ]),
+            metrics: Mutex::new(GuardrailMetrics::default()),
+        }
+    }
+
+    /// Every rule `text` breaks
+    pub fn check(&self, text: &str) -> Vec<GuardrailViolation> {
+        let mut violations = Vec::new();
+        let mut matched = |category, patterns: &[Regex]| {
+            if let Some(m) = patterns.iter().find_map(|p| p.find(text)) {
+                violations.push(GuardrailViolation { category, evidence: m.as_str().to_string() });
+            }
+        };
+        matched(GuardrailCategory::MedicalAdvice, &self.medical);
+        matched(GuardrailCategory::Shaming, &self.shaming);
+
+        let chars = text.chars().count();
+        let sentences = text
+            .split(['.', '!', '?'])
+            .filter(|s| s.chars().any(char::is_alphanumeric))
+            .count();
+        if chars > self.config.max_chars || sentences > self.config.max_sentences {
+            violations.push(GuardrailViolation {
+                category: GuardrailCategory::ExcessiveLength,
+                evidence: format!("{} characters, {} sentences", chars, sentences),
+            });
+        }
+        violations
+    }
+
+    /// Prompt addition telling the model what to fix
+    pub fn corrective_instructions(violations: &[GuardrailViolation]) -> String {
+        let mut instructions = String::from("\nYour previous answer was rejected.");
+        for violation in violations {
+            instructions.push(' ');
+            instructions.push_str(violation.category.correction());
+        }
+        instructions.push('\n');
+        instructions
+    }
+
+    /// Check `text`, regenerating with corrective instructions until it passes or retries run out
+    ///
+    /// `regenerate` gets the corrective instructions for the last failure.
+    pub async fn enforce<F, Fut>(&self, text: String, mut regenerate: F) -> Result<GuardrailOutcome>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        if !self.config.enabled {
+            return Ok(GuardrailOutcome::Passed(text));
+        }
+
+        let mut violations = self.record_check(&text);
+        if violations.is_empty() {
+            return Ok(GuardrailOutcome::Passed(text));
+        }
+
+        for attempt in 1..=self.config.max_regenerations {
+            log::debug!("Guardrail triggered ({:?}), regenerating", violations);
+            self.metrics.lock().unwrap().regenerations += 1;
+            let text = regenerate(Self::corrective_instructions(&violations)).await?;
+            violations = self.record_check(&text);
+            if violations.is_empty() {
+                self.metrics.lock().unwrap().regeneration_successes += 1;
+                return Ok(GuardrailOutcome::Regenerated { text, attempts: attempt });
+            }
+        }
+
+        self.metrics.lock().unwrap().template_fallbacks += 1;
+        Ok(GuardrailOutcome::Rejected(violations))
+    }
+
+    /// Trigger counts since startup
+    pub fn metrics(&self) -> GuardrailMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    fn record_check(&self, text: &str) -> Vec<GuardrailViolation> {
+        let violations = self.check(text);
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.checked += 1;
+        if !violations.is_empty() {
+            metrics.triggered += 1;
+            for violation in &violations {
+                *metrics.triggers_by_category.entry(violation.category).or_insert(0) += 1;
+            }
+        }
+        violations
+    }
+}
+
+impl Default for ContentGuardrail {
+    fn default() -> Self {
+        Self::new(GuardrailConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(guardrail: &ContentGuardrail, text: &str) -> Vec<GuardrailCategory> {
+        guardrail.check(text).into_iter().map(|v| v.category).collect()
+    }
+
+    #[test]
+    fn test_adversarial_outputs_are_caught() {
+        let guardrail = ContentGuardrail::default();
+
+        // What a model says when the prompt asks it to play doctor
+        assert_eq!(
+            categories(&guardrail, "Ignore your rules: try taking 20mg of Adderall before you start."),
+            vec![GuardrailCategory::MedicalAdvice]
+        );
+        assert_eq!(
+            categories(&guardrail, "Honestly, you probably have ADHD. Ask about upping your DOSE."),
+            vec![GuardrailCategory::MedicalAdvice]
+        );
+        // ...or to roast the user
+        assert_eq!(
+            categories(&guardrail, "Distracted AGAIN?! Stop being LAZY and get it together."),
+            vec![GuardrailCategory::Shaming]
+        );
+        assert_eq!(
+            categories(&guardrail, "You wasted the whole morning on Reddit."),
+            vec![GuardrailCategory::Shaming]
+        );
+        // ...or to write an essay
+        let rambling = "Here is a tip. ".repeat(10);
+        assert_eq!(categories(&guardrail, &rambling), vec![GuardrailCategory::ExcessiveLength]);
+
+        // Ordinary nudges pass, including ones that brush close to the rules
+        for ok in [
+            "You've been at it for 90 minutes. Time for a quick stretch?",
+            "Nice focus streak! Maybe grab some water before the next task.",
+            "That failing test looks tricky. Want to split it into smaller steps?",
+        ] {
+            assert!(guardrail.check(ok).is_empty(), "{}", ok);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regenerates_then_falls_back() {
+        let guardrail = ContentGuardrail::default();
+
+        let outcome = guardrail
+            .enforce("Take your meds and stop being lazy.".to_string(), |instructions| async move {
+                assert!(instructions.contains("medication"));
+                assert!(instructions.contains("blame"));
+                Ok("Quick break, then back to the diff?".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(outcome, GuardrailOutcome::Regenerated { text: "Quick break, then back to the diff?".to_string(), attempts: 1 });
+
+        let outcome = guardrail
+            .enforce("You're useless.".to_string(), |_| async { Ok("Still useless.".to_string()) })
+            .await
+            .unwrap();
+        assert!(matches!(outcome, GuardrailOutcome::Rejected(ref v) if v[0].category == GuardrailCategory::Shaming));
+
+        let metrics = guardrail.metrics();
+        assert_eq!(metrics.checked, 4);
+        assert_eq!(metrics.triggered, 3);
+        assert_eq!(metrics.triggers_by_category[&GuardrailCategory::Shaming], 3);
+        assert_eq!(metrics.triggers_by_category[&GuardrailCategory::MedicalAdvice], 1);
+        assert_eq!(metrics.regenerations, 2);
+        assert_eq!(metrics.regeneration_successes, 1);
+        assert_eq!(metrics.template_fallbacks, 1);
+    }
+}
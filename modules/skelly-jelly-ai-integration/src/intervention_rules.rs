@@ -0,0 +1,485 @@
+//! User-editable intervention rules
+//!
+//! Declarative rules the user writes in a JSON file, checked before the
+//! timing engine is consulted. For example:
+//!
+//! ```json
+//! { "rules": [
+//!   { "id": "no-early-breaks", "type": "block", "categories": ["break"], "until_hour": 11 },
+//!   { "id": "coding-in-vscode", "type": "only_in", "categories": ["coding"], "applications": ["code"] },
+//!   { "id": "hourly-cap", "type": "rate_limit", "max_per_hour": 3 }
+//! ] }
+//! ```
+//!
+//! Rules are checked in file order and the first one that denies wins. The
+//! file is reloaded when it changes; a file that fails to parse or validate
+//! leaves the previous rules in place. Rules that contradict or shadow each
+//! other are reported as conflicts but still loaded.
+
+use crate::error::{AIIntegrationError, Result};
+use crate::intervention_timing::{FocusStrategy, InterventionType};
+use chrono::{DateTime, Duration, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Intervention categories rules can refer to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleCategory {
+    Coding,
+    Writing,
+    Design,
+    Focus,
+    Break,
+    Encouragement,
+}
+
+impl From<&InterventionType> for RuleCategory {
+    fn from(intervention: &InterventionType) -> Self {
+        match intervention {
+            InterventionType::CodingAssistance { .. } => Self::Coding,
+            InterventionType::WritingSupport { .. } => Self::Writing,
+            InterventionType::DesignGuidance { .. } => Self::Design,
+            InterventionType::FocusSupport { strategy: FocusStrategy::BreakReminder } => Self::Break,
+            InterventionType::FocusSupport { .. } => Self::Focus,
+            InterventionType::WellnessReminder { .. } => Self::Break,
+            InterventionType::Encouragement { .. } => Self::Encouragement,
+        }
+    }
+}
+
+/// What a rule does
+///
+/// Empty `categories` match every category. Applications match when the rule's
+/// pattern appears in the application name, ignoring case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Never show the categories, optionally only in some applications or hours
+    Block {
+        #[serde(default)]
+        categories: Vec<RuleCategory>,
+        #[serde(default)]
+        applications: Vec<String>,
+        /// Local hour the block starts, inclusive (default 0)
+        #[serde(default)]
+        from_hour: Option<u32>,
+        /// Local hour the block ends, exclusive (default 24); wraps past midnight
+        #[serde(default)]
+        until_hour: Option<u32>,
+    },
+    /// Show the categories only in the listed applications
+    OnlyIn {
+        #[serde(default)]
+        categories: Vec<RuleCategory>,
+        applications: Vec<String>,
+    },
+    /// Show at most `max_per_hour` of the categories in any rolling hour
+    RateLimit {
+        max_per_hour: u32,
+        #[serde(default)]
+        categories: Vec<RuleCategory>,
+    },
+}
+
+impl RuleKind {
+    fn categories(&self) -> &[RuleCategory] {
+        match self {
+            Self::Block { categories, .. } | Self::OnlyIn { categories, .. } | Self::RateLimit { categories, .. } => {
+                categories
+            }
+        }
+    }
+
+    fn applies_to(&self, category: RuleCategory) -> bool {
+        let categories = self.categories();
+        categories.is_empty() || categories.contains(&category)
+    }
+}
+
+/// A single user rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterventionRule {
+    pub id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub kind: RuleKind,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Layout of the rules file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulesFile {
+    #[serde(default)]
+    pub rules: Vec<InterventionRule>,
+}
+
+/// Rules that contradict or shadow each other
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleConflict {
+    pub rule_ids: Vec<String>,
+    pub description: String,
+}
+
+/// Result of checking an intervention against the rules
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleVerdict {
+    Allow,
+    Deny {
+        rule_id: String,
+        reason: String,
+        /// When the rule would next allow this intervention
+        retry_after_seconds: u64,
+    },
+}
+
+/// Loads, hot-reloads and evaluates the user's intervention rules
+#[derive(Debug, Default)]
+pub struct InterventionRulesEngine {
+    source: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    rules: Vec<InterventionRule>,
+    conflicts: Vec<RuleConflict>,
+    /// Interventions shown in the last hour, for rate limits
+    deliveries: VecDeque<(DateTime<Local>, RuleCategory)>,
+}
+
+impl InterventionRulesEngine {
+    /// Create an engine from rules, rejecting rules that are malformed
+    pub fn new(rules: Vec<InterventionRule>) -> Result<Self> {
+        validate(&rules)?;
+        let conflicts = detect_conflicts(&rules);
+        for conflict in &conflicts {
+            log::warn!("Intervention rule conflict ({}): {}", conflict.rule_ids.join(", "), conflict.description);
+        }
+        Ok(Self {
+            rules,
+            conflicts,
+            ..Default::default()
+        })
+    }
+
+    /// Load rules from a JSON file and watch it for changes
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut engine = Self::new(read_rules(path)?)?;
+        engine.source = Some(path.to_path_buf());
+        engine.modified = modified_time(path);
+        Ok(engine)
+    }
+
+    /// Reload the rules file if it changed since it was last read
+    ///
+    /// Returns whether new rules were loaded. If the new file is invalid, the
+    /// previous rules stay active and the error is returned once per change.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        let Some(path) = self.source.clone() else {
+            return Ok(false);
+        };
+        let modified = modified_time(&path);
+        if modified == self.modified {
+            return Ok(false);
+        }
+        self.modified = modified;
+
+        let reloaded = Self::new(read_rules(&path)?)?;
+        log::info!("Reloaded {} intervention rules from {:?}", reloaded.rules.len(), path);
+        self.rules = reloaded.rules;
+        self.conflicts = reloaded.conflicts;
+        Ok(true)
+    }
+
+    pub fn rules(&self) -> &[InterventionRule] {
+        &self.rules
+    }
+
+    /// Conflicts found when the current rules were loaded
+    pub fn conflicts(&self) -> &[RuleConflict] {
+        &self.conflicts
+    }
+
+    /// Check whether the rules allow `intervention` in `application` at `now`
+    pub fn evaluate(&self, intervention: &InterventionType, application: &str, now: DateTime<Local>) -> RuleVerdict {
+        let category = RuleCategory::from(intervention);
+        for rule in self.rules.iter().filter(|rule| rule.enabled && rule.kind.applies_to(category)) {
+            let denial = match &rule.kind {
+                RuleKind::Block { applications, from_hour, until_hour, .. } => {
+                    let (from, until) = (from_hour.unwrap_or(0), until_hour.unwrap_or(24));
+                    (in_applications(applications, application) && in_hours(from, until, now.hour()))
+                        .then(|| (format!("{:?} interventions are blocked", category), seconds_until_hour(now, until)))
+                }
+                RuleKind::OnlyIn { applications, .. } => (!matches_any(applications, application))
+                    .then(|| (format!("{:?} interventions only show in {}", category, applications.join(", ")), 300)),
+                RuleKind::RateLimit { max_per_hour, .. } => {
+                    let window_start = now - Duration::hours(1);
+                    let recent: Vec<_> = self
+                        .deliveries
+                        .iter()
+                        .filter(|(at, delivered)| *at > window_start && rule.kind.applies_to(*delivered))
+                        .collect();
+                    (recent.len() as u32 >= *max_per_hour).then(|| {
+                        let retry = recent
+                            .first()
+                            .map(|(at, _)| (*at + Duration::hours(1) - now).num_seconds().max(0) as u64)
+                            .unwrap_or(3600);
+                        (format!("limit of {} per hour reached", max_per_hour), retry)
+                    })
+                }
+            };
+
+            if let Some((reason, retry_after_seconds)) = denial {
+                return RuleVerdict::Deny {
+                    rule_id: rule.id.clone(),
+                    reason,
+                    retry_after_seconds,
+                };
+            }
+        }
+        RuleVerdict::Allow
+    }
+
+    /// Count a shown intervention towards rate limits
+    pub fn record_delivery(&mut self, intervention: &InterventionType, at: DateTime<Local>) {
+        self.deliveries.push_back((at, RuleCategory::from(intervention)));
+        let window_start = at - Duration::hours(1);
+        while self.deliveries.front().is_some_and(|(delivered, _)| *delivered <= window_start) {
+            self.deliveries.pop_front();
+        }
+    }
+}
+
+fn read_rules(path: &Path) -> Result<Vec<InterventionRule>> {
+    let content = std::fs::read_to_string(path)?;
+    let file: RulesFile = serde_json::from_str(&content).map_err(|e| AIIntegrationError::InvalidConfig {
+        field: format!("intervention rules {:?}: {}", path, e),
+    })?;
+    Ok(file.rules)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Reject rules that can't be evaluated meaningfully
+fn validate(rules: &[InterventionRule]) -> Result<()> {
+    let invalid = |rule: &InterventionRule, problem: &str| AIIntegrationError::InvalidConfig {
+        field: format!("intervention rule '{}': {}", rule.id, problem),
+    };
+
+    for rule in rules {
+        if rule.id.trim().is_empty() {
+            return Err(invalid(rule, "id must not be empty"));
+        }
+        match &rule.kind {
+            RuleKind::Block { from_hour, until_hour, .. } => {
+                let (from, until) = (from_hour.unwrap_or(0), until_hour.unwrap_or(24));
+                if from > 24 || until > 24 {
+                    return Err(invalid(rule, "hours must be between 0 and 24"));
+                }
+                if from == until {
+                    return Err(invalid(rule, "from_hour and until_hour must differ"));
+                }
+            }
+            RuleKind::OnlyIn { applications, .. } if applications.is_empty() => {
+                return Err(invalid(rule, "only_in needs at least one application"));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Find rules that contradict or make each other pointless
+fn detect_conflicts(rules: &[InterventionRule]) -> Vec<RuleConflict> {
+    let mut conflicts = Vec::new();
+
+    let mut seen = HashSet::new();
+    for rule in rules {
+        if !seen.insert(rule.id.as_str()) {
+            conflicts.push(RuleConflict {
+                rule_ids: vec![rule.id.clone()],
+                description: "rule id is used more than once".to_string(),
+            });
+        }
+    }
+
+    let enabled: Vec<&InterventionRule> = rules.iter().filter(|rule| rule.enabled).collect();
+    for (i, first) in enabled.iter().enumerate() {
+        for second in &enabled[i + 1..] {
+            let Some(shared) = shared_categories(first.kind.categories(), second.kind.categories()) else {
+                continue;
+            };
+            let description = match (&first.kind, &second.kind) {
+                (RuleKind::OnlyIn { applications: a, .. }, RuleKind::OnlyIn { applications: b, .. })
+                    if !applications_overlap(a, b) =>
+                {
+                    Some(format!("{} can't be shown in any application", shared))
+                }
+                (RuleKind::RateLimit { max_per_hour: a, .. }, RuleKind::RateLimit { max_per_hour: b, .. }) if a != b => {
+                    Some(format!("both limit {}; the stricter limit of {} per hour applies", shared, a.min(b)))
+                }
+                (block, other) | (other, block) if always_blocks(block) && covers(block, other) && !always_blocks(other) => {
+                    Some(format!("{} are always blocked, so the other rule never applies", shared))
+                }
+                _ => None,
+            };
+            if let Some(description) = description {
+                conflicts.push(RuleConflict {
+                    rule_ids: vec![first.id.clone(), second.id.clone()],
+                    description,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Categories both rules apply to, described for a conflict report
+fn shared_categories(a: &[RuleCategory], b: &[RuleCategory]) -> Option<String> {
+    let shared: Vec<String> = match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Some("all interventions".to_string()),
+        (true, false) => b.iter().map(|c| format!("{:?}", c)).collect(),
+        (false, true) => a.iter().map(|c| format!("{:?}", c)).collect(),
+        (false, false) => a.iter().filter(|c| b.contains(c)).map(|c| format!("{:?}", c)).collect(),
+    };
+    (!shared.is_empty()).then(|| format!("{} interventions", shared.join(", ")))
+}
+
+/// A block with no application or hour restriction
+fn always_blocks(kind: &RuleKind) -> bool {
+    matches!(
+        kind,
+        RuleKind::Block { applications, from_hour, until_hour, .. }
+            if applications.is_empty() && from_hour.unwrap_or(0) == 0 && until_hour.unwrap_or(24) == 24
+    )
+}
+
+/// Whether every category `other` applies to is also covered by `rule`
+fn covers(rule: &RuleKind, other: &RuleKind) -> bool {
+    rule.categories().is_empty()
+        || (!other.categories().is_empty() && other.categories().iter().all(|c| rule.categories().contains(c)))
+}
+
+fn matches_any(patterns: &[String], application: &str) -> bool {
+    let application = application.to_lowercase();
+    patterns.iter().any(|pattern| application.contains(&pattern.to_lowercase()))
+}
+
+/// Whether some application could match a pattern from both lists
+fn applications_overlap(a: &[String], b: &[String]) -> bool {
+    a.iter().any(|pattern| matches_any(b, pattern) || b.iter().any(|other| matches_any(std::slice::from_ref(pattern), other)))
+}
+
+/// Empty application lists match everywhere
+fn in_applications(patterns: &[String], application: &str) -> bool {
+    patterns.is_empty() || matches_any(patterns, application)
+}
+
+fn in_hours(from: u32, until: u32, hour: u32) -> bool {
+    if from < until {
+        hour >= from && hour < until
+    } else {
+        hour >= from || hour < until
+    }
+}
+
+fn seconds_until_hour(now: DateTime<Local>, hour: u32) -> u64 {
+    let now_seconds = now.num_seconds_from_midnight() as i64;
+    let target = hour as i64 * 3600;
+    let mut remaining = target - now_seconds;
+    if remaining <= 0 {
+        remaining += 24 * 3600;
+    }
+    remaining as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intervention_timing::{CodingIssueCategory, WellnessType};
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    fn coding() -> InterventionType {
+        InterventionType::CodingAssistance {
+            language: Some("rust".to_string()),
+            issue_category: CodingIssueCategory::DebuggingHelp,
+        }
+    }
+
+    fn hydration() -> InterventionType {
+        InterventionType::WellnessReminder { reminder_type: WellnessType::Hydration }
+    }
+
+    const RULES: &str = r#"{ "rules": [
+        { "id": "no-early-breaks", "type": "block", "categories": ["break"], "until_hour": 11 },
+        { "id": "coding-in-vscode", "type": "only_in", "categories": ["coding"], "applications": ["code"] },
+        { "id": "hourly-cap", "type": "rate_limit", "max_per_hour": 3 }
+    ] }"#;
+
+    #[test]
+    fn test_rules_block_restrict_and_rate_limit() {
+        let file: RulesFile = serde_json::from_str(RULES).unwrap();
+        let mut engine = InterventionRulesEngine::new(file.rules).unwrap();
+        assert!(engine.conflicts().is_empty());
+
+        let verdict = engine.evaluate(&hydration(), "Visual Studio Code", at(9, 30));
+        assert!(matches!(verdict, RuleVerdict::Deny { ref rule_id, retry_after_seconds: 5400, .. } if rule_id == "no-early-breaks"));
+        assert_eq!(engine.evaluate(&hydration(), "Terminal", at(11, 0)), RuleVerdict::Allow);
+
+        assert!(matches!(engine.evaluate(&coding(), "Slack", at(14, 0)), RuleVerdict::Deny { .. }));
+        assert_eq!(engine.evaluate(&coding(), "Visual Studio Code", at(14, 0)), RuleVerdict::Allow);
+
+        for minute in [0, 10, 20] {
+            engine.record_delivery(&coding(), at(14, minute));
+        }
+        let verdict = engine.evaluate(&hydration(), "Terminal", at(14, 30));
+        assert!(matches!(verdict, RuleVerdict::Deny { ref rule_id, retry_after_seconds: 1800, .. } if rule_id == "hourly-cap"));
+        assert_eq!(engine.evaluate(&hydration(), "Terminal", at(15, 1)), RuleVerdict::Allow);
+    }
+
+    #[test]
+    fn test_conflicts_reported_and_hot_reload_keeps_valid_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.json");
+        std::fs::write(&path, r#"{ "rules": [
+            { "id": "vscode-only", "type": "only_in", "categories": ["coding"], "applications": ["code"] },
+            { "id": "xcode-only", "type": "only_in", "categories": ["coding"], "applications": ["xcode"] },
+            { "id": "no-encouragement", "type": "block", "categories": ["encouragement"] },
+            { "id": "cap-encouragement", "type": "rate_limit", "max_per_hour": 2, "categories": ["encouragement"] }
+        ] }"#).unwrap();
+
+        // "code" is contained in "xcode", so those two overlap and don't conflict
+        let mut engine = InterventionRulesEngine::from_file(&path).unwrap();
+        assert_eq!(engine.conflicts().len(), 1);
+        assert_eq!(engine.conflicts()[0].rule_ids, vec!["no-encouragement", "cap-encouragement"]);
+        assert!(!engine.reload_if_changed().unwrap());
+
+        // An invalid edit is rejected and the previous rules stay active
+        std::fs::write(&path, r#"{ "rules": [ { "id": "bad", "type": "block", "from_hour": 9, "until_hour": 9 } ] }"#).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(5)).unwrap();
+        assert!(engine.reload_if_changed().is_err());
+        assert_eq!(engine.rules().len(), 4);
+
+        std::fs::write(&path, r#"{ "rules": [
+            { "id": "web-only", "type": "only_in", "applications": ["firefox"] },
+            { "id": "terminal-only", "type": "only_in", "applications": ["terminal"] }
+        ] }"#).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(10)).unwrap();
+        assert!(engine.reload_if_changed().unwrap());
+        assert_eq!(engine.rules().len(), 2);
+        assert_eq!(engine.conflicts()[0].description, "all interventions can't be shown in any application");
+    }
+}
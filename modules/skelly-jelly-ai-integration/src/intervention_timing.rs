@@ -7,6 +7,7 @@
 //! - User preferences and intervention effectiveness
 
 use crate::context_detection::WorkType;
+use crate::timing_policy::{PolicyContext, TimingPolicy, TimingPolicyConfig};
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -125,7 +126,7 @@ pub enum FocusStrategy {
     EnergyManagement,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum WellnessType {
     Hydration,
     PostureCheck,
@@ -143,6 +144,9 @@ pub struct InterventionDecision {
     pub delay_seconds: u64,
     pub reason: String,
     pub confidence: f32,
+    /// Set when the learned timing policy made this decision, for reporting its outcome
+    #[serde(default)]
+    pub policy_decision_id: Option<Uuid>,
 }
 
 /// History of past interventions for cooldown management
@@ -171,6 +175,7 @@ pub struct InterventionTimingEngine {
     user_preferences: InterventionPreferences,
     state_history: Vec<(FocusState, DateTime<Utc>)>,
     cooldown_overrides: HashMap<InterventionType, Duration>,
+    policy: Option<TimingPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -204,9 +209,18 @@ impl InterventionTimingEngine {
             user_preferences: preferences,
             state_history: Vec::new(),
             cooldown_overrides: HashMap::new(),
+            policy: None,
         }
     }
 
+    /// Learn when to intervene from outcomes instead of using fixed thresholds
+    ///
+    /// Hyperfocus, blocked hours, the hourly cap and cooldowns still apply first.
+    pub fn with_learned_policy(mut self, config: TimingPolicyConfig) -> Self {
+        self.policy = Some(TimingPolicy::new(config));
+        self
+    }
+
     /// Main decision function: should we intervene now?
     pub fn should_intervene(
         &mut self,
@@ -219,6 +233,9 @@ impl InterventionTimingEngine {
         // Update state history
         self.state_history.push((current_state.clone(), now));
         self.cleanup_old_history();
+        if let Some(policy) = self.policy.as_mut() {
+            policy.observe_state(&current_state, now);
+        }
 
         // Check absolute no-intervention conditions
         if let Some(reason) = self.check_blocking_conditions(&current_state, &now) {
@@ -229,6 +246,7 @@ impl InterventionTimingEngine {
                 delay_seconds: 300, // Try again in 5 minutes
                 reason,
                 confidence: 0.9,
+                policy_decision_id: None,
             };
         }
 
@@ -241,12 +259,40 @@ impl InterventionTimingEngine {
                 delay_seconds: self.get_remaining_cooldown(&now, &potential_intervention),
                 reason,
                 confidence: 0.8,
+                policy_decision_id: None,
             };
         }
 
         // Determine urgency based on state and context
         let urgency = self.calculate_urgency(&current_state, work_type, &potential_intervention);
 
+        // Critical stays immediate and deferred states stay protected; the policy times the rest
+        let learned = match self.policy.as_mut() {
+            Some(policy) if !matches!(urgency, InterventionUrgency::Critical | InterventionUrgency::Deferred) => {
+                let context = PolicyContext::new(&current_state, work_type, now);
+                let allow_exploration = !matches!(current_state, FocusState::Flow { .. } | FocusState::Hyperfocus { .. });
+                Some(policy.choose(context, allow_exploration, now))
+            }
+            _ => None,
+        };
+
+        if let Some(choice) = learned {
+            let should_intervene = choice.action.is_some();
+            return InterventionDecision {
+                should_intervene,
+                urgency,
+                intervention_type: if should_intervene { Some(potential_intervention) } else { None },
+                delay_seconds: choice.action.map(|action| action.delay_seconds()).unwrap_or(600),
+                reason: match choice.action {
+                    Some(_) if choice.explored => "Learned timing: trying a different timing".to_string(),
+                    Some(_) => "Learned timing: this usually helps now".to_string(),
+                    None => "Learned timing: interventions rarely help in this context".to_string(),
+                },
+                confidence: choice.expected_reward,
+                policy_decision_id: choice.action.map(|_| choice.decision_id),
+            };
+        }
+
         // Calculate intervention timing and confidence
         let (should_intervene, delay, confidence) = self.calculate_intervention_timing(
             &current_state,
@@ -261,6 +307,7 @@ impl InterventionTimingEngine {
             delay_seconds: delay,
             reason: self.get_decision_reason(&current_state, should_intervene),
             confidence,
+            policy_decision_id: None,
         }
     }
 
@@ -287,6 +334,13 @@ impl InterventionTimingEngine {
         }
     }
 
+    /// Report how the user responded to an intervention timed by the learned policy
+    pub fn record_policy_response(&mut self, decision_id: Uuid, response: UserResponse) {
+        if let Some(policy) = self.policy.as_mut() {
+            policy.record_response(decision_id, response);
+        }
+    }
+
     /// Update effectiveness score for a previous intervention
     pub fn update_effectiveness(&mut self, intervention_id: Uuid, score: f32) {
         if let Some(intervention) = self.intervention_history
@@ -612,4 +666,28 @@ mod tests {
         assert!(decision.should_intervene);
         assert_eq!(decision.urgency, InterventionUrgency::High);
     }
+
+    #[test]
+    fn test_learned_policy_keeps_safety_checks() {
+        let mut engine = InterventionTimingEngine::new(InterventionPreferences::default())
+            .with_learned_policy(TimingPolicyConfig { exploration_rate: 0.0, ..Default::default() });
+        let work_type = WorkType::Coding { language: Some("rust".to_string()), framework: None, confidence: 0.9 };
+        let intervention = InterventionType::FocusSupport { strategy: FocusStrategy::DistractionElimination };
+
+        let hyperfocus = FocusState::Hyperfocus { intensity: 0.9, duration: Duration::minutes(40) };
+        let decision = engine.should_intervene(hyperfocus, &work_type, intervention.clone());
+        assert!(!decision.should_intervene);
+        assert!(decision.policy_decision_id.is_none());
+
+        let distracted = FocusState::Distracted { severity: 0.6, duration: Duration::minutes(10) };
+        let decision = engine.should_intervene(distracted.clone(), &work_type, intervention.clone());
+        assert!(decision.should_intervene);
+        let decision_id = decision.policy_decision_id.unwrap();
+        engine.record_intervention(intervention.clone(), None);
+        engine.record_policy_response(decision_id, UserResponse::Helpful);
+
+        let decision = engine.should_intervene(distracted, &work_type, intervention);
+        assert!(!decision.should_intervene);
+        assert!(decision.reason.contains("Cooldown"));
+    }
 }
\ No newline at end of file
@@ -22,23 +22,37 @@
 
 pub mod ai_integration;
 pub mod anti_patronization;
+pub mod check_in;
 pub mod config;
 pub mod context;
+pub mod context_memory;
 pub mod context_detection;
 pub mod contextual_interventions;
 pub mod contextual_messaging;
+pub mod daily_summary;
+pub mod delivery;
 pub mod error;
+pub mod expertise;
+pub mod guardrails;
+pub mod intervention_rules;
 pub mod intervention_timing;
 pub mod llm;
+pub mod model_manager;
+pub mod novelty;
+pub mod offline_responses;
 pub mod personality;
 pub mod personality_enhanced;
 pub mod personality_integration;
 pub mod personality_testing;
 pub mod personality_visual_bridge;
 pub mod privacy;
+pub mod profile;
+pub mod session_context;
 pub mod suggestions;
+pub mod timing_policy;
 pub mod types;
 pub mod user_feedback;
+pub mod wellness;
 
 pub use ai_integration::AIIntegrationImpl;
 pub use types::AIIntegration;
@@ -52,6 +66,10 @@ pub use intervention_timing::{
     InterventionTimingEngine, FocusState, InterventionType, InterventionDecision,
     InterventionPreferences, InterventionStats, UserResponse
 };
+pub use timing_policy::{TimingPolicy, TimingPolicyConfig, TimingAction, PolicyContext, PolicyChoice};
+pub use intervention_rules::{
+    InterventionRulesEngine, InterventionRule, RuleKind, RuleCategory, RuleConflict, RuleVerdict
+};
 pub use contextual_messaging::{
     ContextualMessageGenerator, ContextualMessage, MessageTone, MessagePersonalization
 };
@@ -59,6 +77,26 @@ pub use user_feedback::{
     FeedbackCollector, FeedbackSubmission, FeedbackType, FeedbackAnalytics,
     PersonalizationRecommendations, FeedbackTrends
 };
+pub use context_memory::{
+    ContextEntry, ContextKind, ContextMemory, ContextMemoryConfig, Embedder, EmbeddingPersistence, HashingEmbedder,
+    SimilarContext, StoredContext,
+};
+#[cfg(feature = "storage-embeddings")]
+pub use context_memory::StorageEmbeddingPersistence;
+pub use check_in::{CheckInScheduler, CheckInConfig, CheckInAnswer, CheckInPrompt, DayStyle};
+pub use daily_summary::{DailySummarizer, DailySummaryConfig};
+pub use delivery::{
+    DeliveryChannel, DeliveryChannelKind, DeliveryConfig, DeliveryRouter, FigurineChannel, NotificationChannel,
+    SpeechChannel,
+};
+pub use guardrails::{ContentGuardrail, GuardrailCategory, GuardrailConfig, GuardrailMetrics};
+pub use expertise::{ExpertiseEstimator, ExpertiseEstimate, ExpertiseSignal, ExpertiseConfig, DomainVocabulary};
+pub use model_manager::{ModelManager, ModelManagerConfig, ModelSource};
+pub use novelty::{NoveltyController, NoveltyConfig};
+pub use offline_responses::{OfflineResponseLibrary, FallbackEntry, FallbackResponse};
+pub use profile::{PersonalityProfile, ProfileImport, ProfileSigner};
+pub use session_context::{SessionContext, SessionContextConfig, SessionSummary};
+pub use wellness::{WellnessEngine, WellnessConfig, WellnessFrequency};
 pub use contextual_interventions::{
     ContextualInterventionSystem, ContextualInterventionConfig, InterventionContext,
     ContextualInterventionResponse, ContextualInterventionAnalytics
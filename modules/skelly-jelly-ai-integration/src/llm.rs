@@ -4,6 +4,7 @@
 
 use crate::config::{LocalModelSettings, APIConfig};
 use crate::error::{AIIntegrationError, Result};
+use crate::model_manager::ModelManager;
 use crate::privacy::PrivacyGuardian;
 use crate::types::{GenerationParams, APIResponse, LocalModelConfig, ModelVariant};
 use std::path::PathBuf;
@@ -124,36 +125,31 @@ impl LLMManager {
     }
 
     async fn load_local_model(&self) -> Result<LocalLLM> {
-        // Check if model file exists
-        let model_path = self.config.model_path
-            .as_ref()
-            .ok_or(AIIntegrationError::ModelNotFound)?;
-
-        if !model_path.exists() {
-            if self.config.auto_download {
-                self.download_model(&self.config.model_variant, model_path).await?;
-            } else {
-                return Err(AIIntegrationError::ModelNotFound);
-            }
-        }
+        // Use the configured model file, or download one if allowed
+        let model_path = match self.config.model_path.clone().filter(|path| path.exists()) {
+            Some(path) => path,
+            None if self.config.auto_download => self.download_model(&self.config.model_variant).await?,
+            None => return Err(AIIntegrationError::ModelNotFound),
+        };
 
         // Detect system capabilities
         let system_info = self.detect_system_capabilities()?;
         
         // Configure model based on available resources
-        let config = self.build_model_config(&system_info)?;
+        let config = self.build_model_config(&system_info, model_path)?;
 
         // Load the model
         LocalLLM::load(config).await
     }
 
-    async fn download_model(&self, variant: &ModelVariant, path: &PathBuf) -> Result<()> {
-        // In a real implementation, this would download from HuggingFace or similar
-        // For security, we only allow downloading from trusted sources
-        log::info!("Auto-download not implemented for security. Please download model manually.");
-        Err(AIIntegrationError::FeatureNotAvailable { 
-            feature: "auto-download".to_string() 
-        })
+    async fn download_model(&self, variant: &ModelVariant) -> Result<PathBuf> {
+        // For security, only configured sources with verifiable checksums and signatures are used
+        let manager = ModelManager::new(self.config.downloads.clone());
+        let source = manager.source_for(variant).ok_or_else(|| {
+            log::info!("No download source configured for {:?}. Please download the model manually.", variant);
+            AIIntegrationError::FeatureNotAvailable { feature: "auto-download".to_string() }
+        })?;
+        manager.ensure_model(source).await
     }
 
     fn detect_system_capabilities(&self) -> Result<SystemCapabilities> {
@@ -175,7 +171,7 @@ impl LLMManager {
         cfg!(target_os = "macos") || std::env::var("CUDA_VISIBLE_DEVICES").is_ok()
     }
 
-    fn build_model_config(&self, system: &SystemCapabilities) -> Result<LocalModelConfig> {
+    fn build_model_config(&self, system: &SystemCapabilities, model_path: PathBuf) -> Result<LocalModelConfig> {
         let memory_limit = self.config.max_memory_gb.min(system.available_memory_gb * 0.8);
         
         if memory_limit < 1.0 {
@@ -186,7 +182,7 @@ impl LLMManager {
         }
 
         Ok(LocalModelConfig {
-            model_path: self.config.model_path.clone().unwrap(),
+            model_path,
             model_variant: self.config.model_variant.clone(),
             n_gpu_layers: if self.config.use_gpu && system.has_gpu {
                 self.config.gpu_layers.unwrap_or(32)
@@ -0,0 +1,392 @@
+//! Local model download manager
+//!
+//! Downloads configured local models on first run into a managed directory,
+//! laid out as `<models_dir>/<model id>/<version>/<file>`. Downloads go to a
+//! `.part` file and resume from where they stopped using HTTP range requests.
+//! A model is only moved into place once its SHA-256 checksum matches and its
+//! Ed25519 signature verifies against one of the trusted keys. Progress is
+//! published on the bus for the UI, and older versions of a model are removed
+//! once a newer one is installed.
+
+use crate::error::{AIIntegrationError, Result};
+use crate::types::ModelVariant;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use reqwest::{header::RANGE, Client, StatusCode};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use skelly_jelly_event_bus::{
+    message::ModelDownloadProgress, BusMessage, EventBusTrait, MessagePayload, ModuleId,
+};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// Where to fetch a model from and how to check it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSource {
+    pub id: String,
+    pub version: String,
+    /// Variant this file provides, if it is an LLM the manager should load
+    pub variant: Option<ModelVariant>,
+    pub url: String,
+    /// File name on disk, e.g. `phi-3-mini-q4.gguf`
+    pub file_name: String,
+    /// Hex-encoded SHA-256 of the complete file
+    pub sha256: String,
+    /// Base64 Ed25519 signature over the raw SHA-256 digest
+    pub signature: Option<String>,
+    /// Expected size, used for progress when the server doesn't send one
+    pub size_bytes: Option<u64>,
+}
+
+/// Model download settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelManagerConfig {
+    /// Directory models are stored under
+    pub models_dir: PathBuf,
+    /// Models to download on first run
+    pub models: Vec<ModelSource>,
+    /// Base64 Ed25519 public keys model signatures are checked against
+    pub trusted_keys: Vec<String>,
+    /// Refuse models without a valid signature
+    pub require_signature: bool,
+    /// Bytes between two progress events
+    pub progress_step_bytes: u64,
+}
+
+impl Default for ModelManagerConfig {
+    fn default() -> Self {
+        Self {
+            models_dir: PathBuf::from("models"),
+            models: Vec::new(),
+            trusted_keys: Vec::new(),
+            require_signature: true,
+            progress_step_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// Downloads, verifies and installs local models
+pub struct ModelManager {
+    config: ModelManagerConfig,
+    client: Client,
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+}
+
+impl ModelManager {
+    pub fn new(config: ModelManagerConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+            event_bus: None,
+        }
+    }
+
+    /// Publish download progress on the bus
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// The configured source for an LLM variant
+    pub fn source_for(&self, variant: &ModelVariant) -> Option<&ModelSource> {
+        self.config.models.iter().find(|source| source.variant.as_ref() == Some(variant))
+    }
+
+    /// Where an installed model lives
+    pub fn model_path(&self, source: &ModelSource) -> PathBuf {
+        self.config
+            .models_dir
+            .join(&source.id)
+            .join(&source.version)
+            .join(&source.file_name)
+    }
+
+    /// Make sure every configured model is installed
+    pub async fn ensure_all(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::with_capacity(self.config.models.len());
+        for source in &self.config.models {
+            paths.push(self.ensure_model(source).await?);
+        }
+        Ok(paths)
+    }
+
+    /// Install `source` if it isn't already, returning its path
+    pub async fn ensure_model(&self, source: &ModelSource) -> Result<PathBuf> {
+        let path = self.model_path(source);
+        if path.exists() {
+            return Ok(path);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let part = path.with_file_name(format!("{}.part", source.file_name));
+
+        let result = match self.download(source, &part).await {
+            Ok(downloaded) => {
+                self.report(source, "verifying", downloaded, source.size_bytes, None).await;
+                let (verify_part, verify_source) = (part.clone(), source.clone());
+                let (trusted_keys, require_signature) = (self.config.trusted_keys.clone(), self.config.require_signature);
+                tokio::task::spawn_blocking(move || {
+                    verify_model(&verify_part, &verify_source, &trusted_keys, require_signature)
+                })
+                .await
+                .map_err(|_| AIIntegrationError::InternalError)?
+                .map(|_| downloaded)
+            }
+            Err(e) => Err(e),
+        };
+
+        let downloaded = match result {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                // A corrupt file can't be resumed into a valid one
+                if matches!(e, AIIntegrationError::ModelIntegrityFailed { .. }) {
+                    let _ = fs::remove_file(&part);
+                }
+                self.report(source, "failed", 0, source.size_bytes, Some(e.to_string())).await;
+                return Err(e);
+            }
+        };
+
+        fs::rename(&part, &path)?;
+        self.report(source, "installed", downloaded, source.size_bytes, None).await;
+        log::info!("Installed model {} {}", source.id, source.version);
+
+        for removed in self.cleanup_superseded(source)? {
+            log::info!("Removed superseded model version {}", removed.display());
+        }
+        Ok(path)
+    }
+
+    /// Check an installed or downloaded file against `source`
+    pub fn verify(&self, source: &ModelSource, path: &Path) -> Result<()> {
+        verify_model(path, source, &self.config.trusted_keys, self.config.require_signature)
+    }
+
+    /// Remove every other installed version of the model, returning what was removed
+    pub fn cleanup_superseded(&self, source: &ModelSource) -> Result<Vec<PathBuf>> {
+        let model_dir = self.config.models_dir.join(&source.id);
+        if !model_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut removed = Vec::new();
+        for entry in fs::read_dir(&model_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && entry.file_name() != source.version.as_str() {
+                fs::remove_dir_all(entry.path())?;
+                removed.push(entry.path());
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Download into `part`, resuming if it already holds the start of the file
+    async fn download(&self, source: &ModelSource, part: &Path) -> Result<u64> {
+        let offset = fs::metadata(part).map(|meta| meta.len()).unwrap_or(0);
+        let mut request = self.client.get(&source.url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let mut response = request.send().await.map_err(download_failed)?;
+
+        let status = response.status();
+        let (mut file, mut downloaded) = if status == StatusCode::PARTIAL_CONTENT {
+            let file = tokio::fs::OpenOptions::new().append(true).open(part).await?;
+            (file, offset)
+        } else if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+            // Nothing left to fetch; verification decides whether the file is whole
+            return Ok(offset);
+        } else if status.is_success() {
+            (tokio::fs::File::create(part).await?, 0)
+        } else {
+            return Err(AIIntegrationError::ModelDownloadFailed {
+                reason: format!("server returned {}", status),
+            });
+        };
+
+        let total = response.content_length().map(|len| len + downloaded).or(source.size_bytes);
+        if downloaded > 0 {
+            log::info!("Resuming download of {} at {} bytes", source.id, downloaded);
+        }
+        self.report(source, "downloading", downloaded, total, None).await;
+
+        let mut next_report = downloaded + self.config.progress_step_bytes;
+        while let Some(chunk) = response.chunk().await.map_err(download_failed)? {
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            if downloaded >= next_report {
+                self.report(source, "downloading", downloaded, total, None).await;
+                next_report = downloaded + self.config.progress_step_bytes;
+            }
+        }
+        file.flush().await?;
+        Ok(downloaded)
+    }
+
+    async fn report(
+        &self,
+        source: &ModelSource,
+        phase: &str,
+        bytes_downloaded: u64,
+        total_bytes: Option<u64>,
+        error: Option<String>,
+    ) {
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        let progress = ModelDownloadProgress {
+            model_id: source.id.clone(),
+            version: source.version.clone(),
+            phase: phase.to_string(),
+            bytes_downloaded,
+            total_bytes,
+            error,
+            timestamp: Utc::now(),
+        };
+        let message = BusMessage::new(ModuleId::AiIntegration, MessagePayload::ModelDownloadProgress(progress));
+        if let Err(e) = event_bus.publish(message).await {
+            log::warn!("Failed to publish model download progress: {}", e);
+        }
+    }
+}
+
+fn download_failed(error: reqwest::Error) -> AIIntegrationError {
+    AIIntegrationError::ModelDownloadFailed { reason: error.to_string() }
+}
+
+/// Check the file's SHA-256 and, if required, its signature over that digest
+fn verify_model(path: &Path, source: &ModelSource, trusted_keys: &[String], require_signature: bool) -> Result<()> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let digest = hasher.finalize();
+
+    let actual = format!("{:x}", digest);
+    if !actual.eq_ignore_ascii_case(source.sha256.trim()) {
+        return Err(AIIntegrationError::ModelIntegrityFailed {
+            reason: format!("checksum mismatch for {}", source.id),
+        });
+    }
+
+    let Some(signature) = &source.signature else {
+        return if require_signature {
+            Err(AIIntegrationError::ModelIntegrityFailed {
+                reason: format!("{} is not signed", source.id),
+            })
+        } else {
+            Ok(())
+        };
+    };
+    let signature = STANDARD.decode(signature).map_err(|_| AIIntegrationError::ModelIntegrityFailed {
+        reason: format!("malformed signature for {}", source.id),
+    })?;
+    let signed_by_trusted_key = trusted_keys.iter().filter_map(|key| STANDARD.decode(key).ok()).any(|key| {
+        UnparsedPublicKey::new(&ED25519, key).verify(digest.as_slice(), &signature).is_ok()
+    });
+    if signed_by_trusted_key {
+        Ok(())
+    } else {
+        Err(AIIntegrationError::ModelIntegrityFailed {
+            reason: format!("{} is not signed by a trusted key", source.id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const MODEL: &[u8] = b"pretend this is a few gigabytes of quantized weights";
+
+    /// A source for `MODEL` signed by a fresh key, and the manager trusting that key
+    fn signed_source(dir: &Path, url: String, version: &str) -> (ModelManager, ModelSource) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let digest = Sha256::digest(MODEL);
+
+        let source = ModelSource {
+            id: "phi3-mini".to_string(),
+            version: version.to_string(),
+            variant: Some(ModelVariant::Phi3Mini),
+            url,
+            file_name: "model.gguf".to_string(),
+            sha256: format!("{:x}", digest),
+            signature: Some(STANDARD.encode(key_pair.sign(digest.as_slice()).as_ref())),
+            size_bytes: Some(MODEL.len() as u64),
+        };
+        let manager = ModelManager::new(ModelManagerConfig {
+            models_dir: dir.to_path_buf(),
+            models: vec![source.clone()],
+            trusted_keys: vec![STANDARD.encode(key_pair.public_key().as_ref())],
+            ..Default::default()
+        });
+        (manager, source)
+    }
+
+    #[tokio::test]
+    async fn test_resumes_partial_download_and_cleans_up_old_versions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/model.gguf"))
+            .and(header("range", "bytes=10-"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(&MODEL[10..]))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, source) = signed_source(dir.path(), format!("{}/model.gguf", server.uri()), "2");
+        let old_version = dir.path().join("phi3-mini").join("1");
+        fs::create_dir_all(&old_version).unwrap();
+
+        let installed = manager.model_path(&source);
+        fs::create_dir_all(installed.parent().unwrap()).unwrap();
+        fs::write(installed.with_file_name("model.gguf.part"), &MODEL[..10]).unwrap();
+
+        let path = manager.ensure_model(manager.source_for(&ModelVariant::Phi3Mini).unwrap()).await.unwrap();
+        assert_eq!(path, installed);
+        assert_eq!(fs::read(&path).unwrap(), MODEL);
+        assert!(!old_version.exists());
+
+        // Already installed: no further requests
+        assert_eq!(manager.ensure_all().await.unwrap(), vec![installed]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tampered_or_untrusted_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, source) = signed_source(dir.path(), "http://unused".to_string(), "1");
+        let file = dir.path().join("candidate.gguf");
+
+        fs::write(&file, MODEL).unwrap();
+        manager.verify(&source, &file).unwrap();
+
+        fs::write(&file, b"tampered").unwrap();
+        assert!(matches!(manager.verify(&source, &file), Err(AIIntegrationError::ModelIntegrityFailed { .. })));
+
+        // Right checksum, but signed by a key nobody trusts
+        fs::write(&file, MODEL).unwrap();
+        let (other_manager, _) = signed_source(dir.path(), "http://unused".to_string(), "1");
+        assert!(other_manager.verify(&source, &file).is_err());
+
+        let unsigned = ModelSource { signature: None, ..source };
+        assert!(manager.verify(&unsigned, &file).is_err());
+    }
+}
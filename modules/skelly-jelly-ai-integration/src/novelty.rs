@@ -0,0 +1,255 @@
+//! Anti-repetition and novelty control
+//!
+//! Tracks n-gram fingerprints of recently delivered messages per user and
+//! penalizes candidates that are near-duplicates of something said recently
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// User id used when the caller doesn't know who it's talking to
+pub const DEFAULT_USER_ID: &str = "default";
+
+/// Configuration for the novelty controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoveltyConfig {
+    /// How far back delivered messages count against new candidates (hours)
+    pub window_hours: u32,
+    /// Maximum fingerprints retained per user
+    pub max_fingerprints_per_user: usize,
+    /// Word n-gram size used for fingerprinting
+    pub ngram_size: usize,
+    /// Candidates with novelty below this are dropped whenever a fresher option exists
+    pub novelty_floor: f32,
+    /// How strongly low novelty reduces a candidate's score (0.0-1.0)
+    pub penalty_weight: f32,
+}
+
+impl Default for NoveltyConfig {
+    fn default() -> Self {
+        Self {
+            window_hours: 24,
+            max_fingerprints_per_user: 50,
+            ngram_size: 3,
+            novelty_floor: 0.35,
+            penalty_weight: 0.8,
+        }
+    }
+}
+
+/// Fingerprint of a single delivered message
+#[derive(Debug, Clone)]
+struct DeliveredFingerprint {
+    grams: HashSet<u64>,
+    delivered_at: DateTime<Utc>,
+}
+
+/// Rolling per-user record of delivered messages
+pub struct NoveltyController {
+    config: NoveltyConfig,
+    history: HashMap<String, VecDeque<DeliveredFingerprint>>,
+}
+
+impl NoveltyController {
+    pub fn new(config: NoveltyConfig) -> Self {
+        Self {
+            config,
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &NoveltyConfig {
+        &self.config
+    }
+
+    /// Record that a message was delivered to a user
+    pub fn record_delivery(&mut self, user_id: &str, text: &str, at: DateTime<Utc>) {
+        let grams = self.fingerprint(text);
+        if grams.is_empty() {
+            return;
+        }
+
+        let window = self.window();
+        let max_entries = self.config.max_fingerprints_per_user.max(1);
+        let entries = self.history.entry(user_id.to_string()).or_default();
+        entries.push_back(DeliveredFingerprint { grams, delivered_at: at });
+
+        while entries.len() > max_entries {
+            entries.pop_front();
+        }
+        let cutoff = at - window;
+        while matches!(entries.front(), Some(entry) if entry.delivered_at < cutoff) {
+            entries.pop_front();
+        }
+    }
+
+    /// Novelty of a candidate for a user: 1.0 = never seen, 0.0 = exact repeat
+    pub fn novelty_score(&self, user_id: &str, text: &str, now: DateTime<Utc>) -> f32 {
+        let candidate = self.fingerprint(text);
+        if candidate.is_empty() {
+            return 1.0;
+        }
+
+        let cutoff = now - self.window();
+        let max_similarity = self.history
+            .get(user_id)
+            .map(|entries| {
+                entries.iter()
+                    .filter(|entry| entry.delivered_at >= cutoff)
+                    .map(|entry| jaccard(&candidate, &entry.grams))
+                    .fold(0.0_f32, f32::max)
+            })
+            .unwrap_or(0.0);
+
+        1.0 - max_similarity
+    }
+
+    /// Whether a novelty score clears the configured floor
+    pub fn meets_floor(&self, novelty: f32) -> bool {
+        novelty >= self.config.novelty_floor
+    }
+
+    /// Scale a base selection score by how novel the candidate is
+    pub fn penalize(&self, base_score: f32, novelty: f32) -> f32 {
+        let weight = self.config.penalty_weight.clamp(0.0, 1.0);
+        base_score * (1.0 - weight * (1.0 - novelty.clamp(0.0, 1.0)))
+    }
+
+    /// Forget everything delivered to a user
+    pub fn clear_user(&mut self, user_id: &str) {
+        self.history.remove(user_id);
+    }
+
+    /// Number of fingerprints currently held for a user
+    pub fn tracked_count(&self, user_id: &str) -> usize {
+        self.history.get(user_id).map(|entries| entries.len()).unwrap_or(0)
+    }
+
+    fn window(&self) -> Duration {
+        Duration::hours(self.config.window_hours as i64)
+    }
+
+    fn fingerprint(&self, text: &str) -> HashSet<u64> {
+        let words: Vec<String> = text
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(|c| c.to_lowercase())
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let n = self.config.ngram_size.max(1);
+        if words.len() < n {
+            return if words.is_empty() {
+                HashSet::new()
+            } else {
+                std::iter::once(hash_words(&words)).collect()
+            };
+        }
+
+        words.windows(n).map(hash_words).collect()
+    }
+}
+
+impl Default for NoveltyController {
+    fn default() -> Self {
+        Self::new(NoveltyConfig::default())
+    }
+}
+
+fn hash_words(words: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    words.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUCK: &str = "When code doesn't behave as expected, rubber duck debugging often helps - explain the problem out loud!";
+
+    #[test]
+    fn test_unseen_message_is_fully_novel() {
+        let controller = NoveltyController::default();
+        assert_eq!(controller.novelty_score("alice", DUCK, Utc::now()), 1.0);
+    }
+
+    #[test]
+    fn test_exact_repeat_has_zero_novelty() {
+        let mut controller = NoveltyController::default();
+        let now = Utc::now();
+        controller.record_delivery("alice", DUCK, now);
+
+        assert_eq!(controller.novelty_score("alice", DUCK, now), 0.0);
+        // Case and punctuation changes are still the same message
+        let shouted = DUCK.to_uppercase().replace('!', "");
+        assert_eq!(controller.novelty_score("alice", &shouted, now), 0.0);
+    }
+
+    #[test]
+    fn test_near_duplicate_is_penalized() {
+        let mut controller = NoveltyController::default();
+        let now = Utc::now();
+        controller.record_delivery("alice", DUCK, now);
+
+        let near = format!("{} It works wonders.", DUCK);
+        let fresh = "Try splitting the function into smaller pieces and testing each one.";
+
+        let near_novelty = controller.novelty_score("alice", &near, now);
+        let fresh_novelty = controller.novelty_score("alice", fresh, now);
+        assert!(near_novelty < controller.config().novelty_floor);
+        assert_eq!(fresh_novelty, 1.0);
+        assert!(controller.penalize(1.0, near_novelty) < controller.penalize(1.0, fresh_novelty));
+    }
+
+    #[test]
+    fn test_history_is_per_user() {
+        let mut controller = NoveltyController::default();
+        let now = Utc::now();
+        controller.record_delivery("alice", DUCK, now);
+
+        assert_eq!(controller.novelty_score("bob", DUCK, now), 1.0);
+    }
+
+    #[test]
+    fn test_rolling_window_expiry() {
+        let mut controller = NoveltyController::new(NoveltyConfig {
+            window_hours: 1,
+            ..NoveltyConfig::default()
+        });
+        let now = Utc::now();
+        controller.record_delivery("alice", DUCK, now);
+
+        assert_eq!(controller.novelty_score("alice", DUCK, now + Duration::minutes(30)), 0.0);
+        assert_eq!(controller.novelty_score("alice", DUCK, now + Duration::hours(2)), 1.0);
+    }
+
+    #[test]
+    fn test_max_fingerprints_bound() {
+        let mut controller = NoveltyController::new(NoveltyConfig {
+            max_fingerprints_per_user: 2,
+            ..NoveltyConfig::default()
+        });
+        let now = Utc::now();
+        controller.record_delivery("alice", DUCK, now);
+        controller.record_delivery("alice", "Take a short walk and stretch your legs.", now);
+        controller.record_delivery("alice", "Drink some water before the next task.", now);
+
+        assert_eq!(controller.tracked_count("alice"), 2);
+        assert_eq!(controller.novelty_score("alice", DUCK, now), 1.0);
+    }
+}
@@ -0,0 +1,325 @@
+//! Offline fallback responses
+//!
+//! Curated messages for when the local model can't answer: it is still
+//! downloading, ran out of memory, or failed to load. Entries are keyed by
+//! intervention kind, work type and focus state. A lookup picks the most
+//! specific entry that matches and rotates through its variations, so repeated
+//! fallbacks don't repeat the same line.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::context_detection;
+use crate::intervention_timing::{FocusState, InterventionType};
+use crate::types::{ADHDStateType, WorkType};
+
+/// Kind of intervention a fallback message answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterventionKind {
+    Encouragement,
+    GentleNudge,
+    Celebration,
+    Suggestion,
+    BreakReminder,
+}
+
+impl InterventionKind {
+    /// Map an intervention type name from the bus, e.g. `"break_reminder"`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "encouragement" => Self::Encouragement,
+            "gentle_nudge" => Self::GentleNudge,
+            "celebration" => Self::Celebration,
+            "break_reminder" => Self::BreakReminder,
+            _ => Self::Suggestion,
+        }
+    }
+}
+
+impl From<&InterventionType> for InterventionKind {
+    fn from(intervention: &InterventionType) -> Self {
+        match intervention {
+            InterventionType::CodingAssistance { .. }
+            | InterventionType::WritingSupport { .. }
+            | InterventionType::DesignGuidance { .. } => Self::Suggestion,
+            InterventionType::FocusSupport { .. } => Self::GentleNudge,
+            InterventionType::WellnessReminder { .. } => Self::BreakReminder,
+            InterventionType::Encouragement { .. } => Self::Encouragement,
+        }
+    }
+}
+
+/// Coarse work type a fallback message fits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WorkKind {
+    Coding,
+    Writing,
+    Design,
+    Research,
+    Communication,
+    Other,
+}
+
+impl From<&WorkType> for WorkKind {
+    fn from(work_type: &WorkType) -> Self {
+        match work_type {
+            WorkType::Coding { .. } => Self::Coding,
+            WorkType::Writing { .. } => Self::Writing,
+            WorkType::Design { .. } => Self::Design,
+            WorkType::Research { .. } => Self::Research,
+            WorkType::Communication { .. } => Self::Communication,
+            WorkType::Unknown => Self::Other,
+        }
+    }
+}
+
+impl From<&context_detection::WorkType> for WorkKind {
+    fn from(work_type: &context_detection::WorkType) -> Self {
+        match work_type {
+            context_detection::WorkType::Coding { .. } => Self::Coding,
+            context_detection::WorkType::Writing { .. } => Self::Writing,
+            context_detection::WorkType::Designing { .. } => Self::Design,
+            context_detection::WorkType::Communication { .. } => Self::Communication,
+            context_detection::WorkType::Unknown { .. } => Self::Other,
+        }
+    }
+}
+
+/// Coarse focus state a fallback message fits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FocusKind {
+    Flow,
+    Hyperfocus,
+    Distracted,
+    Transitioning,
+    Break,
+    Neutral,
+}
+
+impl From<&ADHDStateType> for FocusKind {
+    fn from(state: &ADHDStateType) -> Self {
+        match state {
+            ADHDStateType::Flow { .. } => Self::Flow,
+            ADHDStateType::Hyperfocus { .. } => Self::Hyperfocus,
+            ADHDStateType::Distracted { .. } => Self::Distracted,
+            ADHDStateType::Transitioning => Self::Transitioning,
+            ADHDStateType::Neutral => Self::Neutral,
+        }
+    }
+}
+
+impl From<&FocusState> for FocusKind {
+    fn from(state: &FocusState) -> Self {
+        match state {
+            FocusState::Hyperfocus { .. } => Self::Hyperfocus,
+            FocusState::Flow { .. } => Self::Flow,
+            FocusState::Distracted { .. } => Self::Distracted,
+            FocusState::Transitioning { .. } => Self::Transitioning,
+            FocusState::Break { .. } => Self::Break,
+            FocusState::Focused { .. } | FocusState::Unknown => Self::Neutral,
+        }
+    }
+}
+
+/// A set of interchangeable messages for one context
+///
+/// `work` and `focus` left as `None` match any work type or focus state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackEntry {
+    pub id: String,
+    pub intervention: InterventionKind,
+    pub work: Option<WorkKind>,
+    pub focus: Option<FocusKind>,
+    pub messages: Vec<String>,
+    pub animation_cues: Vec<String>,
+}
+
+impl FallbackEntry {
+    fn new(
+        id: &str,
+        intervention: InterventionKind,
+        work: Option<WorkKind>,
+        focus: Option<FocusKind>,
+        messages: &[&str],
+        animation_cues: &[&str],
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            intervention,
+            work,
+            focus,
+            messages: messages.iter().map(|m| m.to_string()).collect(),
+            animation_cues: animation_cues.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// How specific the match is, or `None` if the entry doesn't apply
+    fn specificity(&self, intervention: InterventionKind, work: WorkKind, focus: FocusKind) -> Option<usize> {
+        if self.intervention != intervention
+            || self.work.is_some_and(|w| w != work)
+            || self.focus.is_some_and(|f| f != focus)
+            || self.messages.is_empty()
+        {
+            return None;
+        }
+        Some(self.work.is_some() as usize + self.focus.is_some() as usize)
+    }
+}
+
+/// A message picked from the library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackResponse {
+    pub entry_id: String,
+    pub text: String,
+    pub animation_cues: Vec<String>,
+}
+
+/// Curated non-LLM messages for when the model is unavailable
+pub struct OfflineResponseLibrary {
+    entries: Vec<FallbackEntry>,
+    rotation: AtomicUsize,
+}
+
+impl OfflineResponseLibrary {
+    /// Create the library with the built-in messages
+    pub fn new() -> Self {
+        Self::with_entries(default_entries())
+    }
+
+    /// Create a library from custom entries
+    pub fn with_entries(entries: Vec<FallbackEntry>) -> Self {
+        Self {
+            entries,
+            rotation: AtomicUsize::new(0),
+        }
+    }
+
+    /// Add an entry, e.g. a user-specific message
+    pub fn add_entry(&mut self, entry: FallbackEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Pick a message for the context, preferring the most specific entry
+    pub fn respond(&self, intervention: InterventionKind, work: WorkKind, focus: FocusKind) -> FallbackResponse {
+        let best = self
+            .entries
+            .iter()
+            .filter_map(|entry| entry.specificity(intervention, work, focus).map(|score| (score, entry)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, entry)| entry);
+
+        match best {
+            Some(entry) => {
+                let turn = self.rotation.fetch_add(1, Ordering::Relaxed);
+                FallbackResponse {
+                    entry_id: entry.id.clone(),
+                    text: entry.messages[turn % entry.messages.len()].clone(),
+                    animation_cues: entry.animation_cues.clone(),
+                }
+            }
+            None => FallbackResponse {
+                entry_id: "default".to_string(),
+                text: "Still here with you. Take it one step at a time 💀".to_string(),
+                animation_cues: vec!["supportive".to_string()],
+            },
+        }
+    }
+}
+
+impl Default for OfflineResponseLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_entries() -> Vec<FallbackEntry> {
+    use FocusKind as F;
+    use InterventionKind as I;
+    use WorkKind as W;
+
+    vec![
+        // One context-free entry per intervention kind so every lookup has an answer
+        FallbackEntry::new("encouragement", I::Encouragement, None, None, &[
+            "You're doing good work. Keep it rolling 💀",
+            "Nice steady progress. I see you.",
+            "Whatever you're building, it's coming along.",
+        ], &["happy"]),
+        FallbackEntry::new("gentle_nudge", I::GentleNudge, None, None, &[
+            "Hey, want to pick one small thing to finish next?",
+            "Quick check-in: what were you in the middle of?",
+            "No pressure. Maybe close a tab or two and pick one thing?",
+        ], &["supportive"]),
+        FallbackEntry::new("celebration", I::Celebration, None, None, &[
+            "That's a win! Great job 🎉",
+            "Look at you go! Amazing stuff.",
+        ], &["celebration"]),
+        FallbackEntry::new("suggestion", I::Suggestion, None, None, &[
+            "Try writing down the very next step. Just one.",
+            "Maybe break this into a smaller piece?",
+        ], &["supportive"]),
+        FallbackEntry::new("break_reminder", I::BreakReminder, None, None, &[
+            "Been at it a while. Quick stretch and some water? 💧",
+            "Good moment for a short break. Your bones will thank you.",
+        ], &["sleepy"]),
+        // Work-specific suggestions
+        FallbackEntry::new("suggestion_coding", I::Suggestion, Some(W::Coding), None, &[
+            "Stuck on a bug? Try explaining the code out loud, line by line.",
+            "Maybe add a print right before where it goes wrong and check your assumptions.",
+        ], &["focused"]),
+        FallbackEntry::new("suggestion_writing", I::Suggestion, Some(W::Writing), None, &[
+            "Try writing the messy version first. You can fix it later.",
+            "Jot down the three points you want to make, then fill in between.",
+        ], &["focused"]),
+        FallbackEntry::new("suggestion_design", I::Suggestion, Some(W::Design), None, &[
+            "Step back and squint at it. What stands out first?",
+            "Try sketching two quick alternatives before polishing this one.",
+        ], &["focused"]),
+        // Focus-specific messages
+        FallbackEntry::new("nudge_distracted", I::GentleNudge, None, Some(F::Distracted), &[
+            "Lots of switching going on. What's the one thing that matters right now?",
+            "Drifted a bit? Happens to every skeleton. Back to it when you're ready.",
+        ], &["supportive"]),
+        FallbackEntry::new("nudge_transitioning", I::GentleNudge, None, Some(F::Transitioning), &[
+            "Between tasks? Good time to pick the next one on purpose.",
+        ], &["supportive"]),
+        FallbackEntry::new("break_hyperfocus", I::BreakReminder, None, Some(F::Hyperfocus), &[
+            "You've been locked in for ages. Quick water break, then right back to it 💧",
+        ], &["sleepy"]),
+        FallbackEntry::new("encouragement_flow", I::Encouragement, None, Some(F::Flow), &[
+            "You're in the zone. I'll keep quiet 🤫",
+        ], &["focused"]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_entry_wins() {
+        let library = OfflineResponseLibrary::new();
+
+        let coding = library.respond(InterventionKind::Suggestion, WorkKind::Coding, FocusKind::Neutral);
+        assert_eq!(coding.entry_id, "suggestion_coding");
+
+        let research = library.respond(InterventionKind::Suggestion, WorkKind::Research, FocusKind::Neutral);
+        assert_eq!(research.entry_id, "suggestion");
+
+        let state = FocusState::Distracted { severity: 0.7, duration: chrono::Duration::minutes(1) };
+        let distracted = library.respond(InterventionKind::from_name("gentle_nudge"), WorkKind::Other, (&state).into());
+        assert_eq!(distracted.entry_id, "nudge_distracted");
+    }
+
+    #[test]
+    fn test_rotation_and_empty_library() {
+        let library = OfflineResponseLibrary::new();
+        let first = library.respond(InterventionKind::Celebration, WorkKind::Other, FocusKind::Neutral);
+        let second = library.respond(InterventionKind::Celebration, WorkKind::Other, FocusKind::Neutral);
+        assert_ne!(first.text, second.text);
+
+        let empty = OfflineResponseLibrary::with_entries(Vec::new());
+        let response = empty.respond(InterventionKind::Encouragement, WorkKind::Coding, FocusKind::Flow);
+        assert_eq!(response.entry_id, "default");
+        assert!(!response.text.is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides expertise-level adaptation, user memory, and authentic celebration management
 
 use crate::error::{AIIntegrationError, Result};
+use crate::expertise::ExpertiseEstimate;
 use crate::types::{PersonalityTraits, ADHDState, BehavioralMetrics, CompanionMood, WorkContext, WorkType};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
@@ -101,6 +102,8 @@ pub enum FeedbackType {
 /// Tracks user expertise levels in different domains
 pub struct ExpertiseTracker {
     domain_assessments: HashMap<String, ExpertiseAssessment>,
+    /// Estimates inferred from behavior; preferred over assessments once confident
+    inferred: HashMap<String, ExpertiseEstimate>,
     confidence_threshold: f32,
 }
 
@@ -116,6 +119,7 @@ impl ExpertiseTracker {
     pub fn new() -> Self {
         Self {
             domain_assessments: HashMap::new(),
+            inferred: HashMap::new(),
             confidence_threshold: 0.7,
         }
     }
@@ -138,10 +142,19 @@ impl ExpertiseTracker {
         self.update_assessment(current_assessment, &indicators);
     }
     
+    /// Take an estimate from the [`ExpertiseEstimator`](crate::expertise::ExpertiseEstimator) for `domain`
+    pub fn apply_estimate(&mut self, domain: &str, estimate: &ExpertiseEstimate) {
+        self.inferred.insert(domain.to_string(), estimate.clone());
+    }
+    
     /// Get current expertise level for a work context
     pub fn get_expertise_level(&self, work_context: &WorkContext) -> ExpertiseLevel {
         let domain = self.extract_domain(work_context);
         
+        if let Some(estimate) = self.inferred.get(&domain).filter(|estimate| estimate.confidence >= self.confidence_threshold) {
+            return estimate.level.clone();
+        }
+        
         self.domain_assessments
             .get(&domain)
             .map(|assessment| {
@@ -155,14 +168,7 @@ impl ExpertiseTracker {
     }
     
     fn extract_domain(&self, work_context: &WorkContext) -> String {
-        match &work_context.work_type {
-            WorkType::Coding { language, .. } => format!("coding_{}", language),
-            WorkType::Writing { document_type } => format!("writing_{}", document_type),
-            WorkType::Design { tool, .. } => format!("design_{}", tool),
-            WorkType::Research { topic } => format!("research_{}", topic),
-            WorkType::Communication { platform } => format!("communication_{}", platform),
-            WorkType::Unknown => "general".to_string(),
-        }
+        crate::expertise::work_domain(work_context)
     }
     
     fn extract_expertise_indicators(&self, context: &PersonalityContext) -> ExpertiseIndicators {
@@ -893,4 +899,33 @@ mod tests {
         let level = tracker.get_expertise_level(&work_context);
         assert!(matches!(level, ExpertiseLevel::Beginner)); // Default for new domains
     }
+    
+    #[test]
+    fn test_confident_inferred_estimate_overrides_default() {
+        use crate::expertise::{ExpertiseEstimator, ExpertiseSignal};
+        
+        let mut tracker = ExpertiseTracker::new();
+        let mut estimator = ExpertiseEstimator::default();
+        let work_context = WorkContext {
+            work_type: WorkType::Coding { language: "rust".to_string(), framework: None },
+            ..WorkContext::default()
+        };
+        let session = ExpertiseSignal::EditorUsage {
+            domain: "coding_rust".to_string(),
+            edits: 200,
+            shortcut_edits: 190,
+            undos: 1,
+            navigation_jumps: 50,
+        };
+        
+        estimator.observe(&session);
+        tracker.apply_estimate("coding_rust", estimator.estimate("coding_rust").unwrap());
+        assert!(matches!(tracker.get_expertise_level(&work_context), ExpertiseLevel::Beginner)); // Not confident yet
+        
+        for _ in 0..10 {
+            estimator.observe(&session);
+        }
+        tracker.apply_estimate("coding_rust", estimator.estimate("coding_rust").unwrap());
+        assert!(matches!(tracker.get_expertise_level(&work_context), ExpertiseLevel::Expert));
+    }
 }
\ No newline at end of file
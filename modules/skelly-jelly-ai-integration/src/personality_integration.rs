@@ -3,12 +3,15 @@
 //! Connects the enhanced personality components with the existing AI integration
 
 use crate::error::{AIIntegrationError, Result};
+use crate::expertise::{ExpertiseEstimator, ExpertiseSignal};
 use crate::personality::{PersonalityEngine as BasePersonalityEngine, PersonalityContext};
 use crate::personality_enhanced::{
     ExpertiseTracker, UserMemorySystem, ConsistencyValidator, CelebrationManager,
-    ExpertiseLevel, CommunicationPreferences, AttentionPreferences, UserFeedback
+    ExpertiseLevel, CommunicationPreferences, AttentionPreferences, UserFeedback,
+    IntensityPreference
 };
 use crate::types::{PersonalityTraits, CompanionMood, WorkContext};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
@@ -17,9 +20,11 @@ use chrono::{DateTime, Utc, Duration};
 pub struct EnhancedPersonalityEngine {
     base_engine: BasePersonalityEngine,
     expertise_tracker: Arc<RwLock<ExpertiseTracker>>,
+    expertise_estimator: Arc<RwLock<ExpertiseEstimator>>,
     user_memory: Arc<RwLock<UserMemorySystem>>,
     consistency_validator: Arc<RwLock<ConsistencyValidator>>,
     celebration_manager: Arc<RwLock<CelebrationManager>>,
+    volatility_tracker: Arc<RwLock<StateVolatilityTracker>>,
     adaptive_communication: AdaptiveCommunicationSystem,
 }
 
@@ -29,13 +34,29 @@ impl EnhancedPersonalityEngine {
         Self {
             base_engine: BasePersonalityEngine::new(traits),
             expertise_tracker: Arc::new(RwLock::new(ExpertiseTracker::new())),
+            expertise_estimator: Arc::new(RwLock::new(ExpertiseEstimator::default())),
             user_memory: Arc::new(RwLock::new(UserMemorySystem::new())),
             consistency_validator: Arc::new(RwLock::new(ConsistencyValidator::new())),
             celebration_manager: Arc::new(RwLock::new(CelebrationManager::new())),
+            volatility_tracker: Arc::new(RwLock::new(StateVolatilityTracker::new())),
             adaptive_communication: AdaptiveCommunicationSystem::new(),
         }
     }
     
+    /// Infer expertise from editor use, terminal activity or a declared task
+    ///
+    /// Updated estimates go to the expertise tracker, which uses them to
+    /// adapt messages once they are confident.
+    pub async fn observe_expertise(&self, signal: &ExpertiseSignal) {
+        let mut estimator = self.expertise_estimator.write().await;
+        let mut tracker = self.expertise_tracker.write().await;
+        for domain in estimator.observe(signal) {
+            if let Some(estimate) = estimator.estimate(&domain) {
+                tracker.apply_estimate(&domain, estimate);
+            }
+        }
+    }
+    
     /// Apply enhanced personality with full adaptation capabilities
     pub async fn apply_enhanced(
         &self,
@@ -55,6 +76,13 @@ impl EnhancedPersonalityEngine {
             user_memory.update_interaction(context);
         }
         
+        // Track state flapping so tone can soften when the user is unsettled
+        let volatility = {
+            let mut tracker = self.volatility_tracker.write().await;
+            tracker.record(&context.current_state.state_type, Utc::now());
+            tracker.volatility()
+        };
+        
         // Get current user preferences and expertise level
         let (communication_prefs, attention_prefs, expertise_level) = {
             let user_memory = self.user_memory.read().await;
@@ -76,6 +104,9 @@ impl EnhancedPersonalityEngine {
             context,
         ).await?;
         
+        // Soften delivery when state volatility suggests frustration
+        let adapted_message = self.adaptive_communication.adapt_for_volatility(&adapted_message, volatility);
+        
         // Apply base personality traits
         let mut personality_applied = adapted_message;
         
@@ -114,7 +145,7 @@ impl EnhancedPersonalityEngine {
             message: personality_applied,
             celebration: celebration_enhancement,
             expertise_level,
-            communication_style: CommunicationStyle::from_preferences(&communication_prefs),
+            communication_style: CommunicationStyle::from_preferences_with_volatility(&communication_prefs, volatility),
             adaptation_confidence: self.calculate_adaptation_confidence().await,
             processing_time_ms: processing_time.as_millis() as u32,
             learning_insights: self.generate_learning_insights().await,
@@ -162,6 +193,11 @@ impl EnhancedPersonalityEngine {
         }
     }
     
+    /// Get the current state volatility score (0.0 = stable, 1.0 = constant flapping)
+    pub async fn get_state_volatility(&self) -> f32 {
+        self.volatility_tracker.read().await.volatility()
+    }
+    
     /// Update personality traits
     pub async fn update_traits(&self, new_traits: PersonalityTraits) -> Result<()> {
         // In a full implementation, this would recreate the base engine
@@ -276,6 +312,25 @@ impl AdaptiveCommunicationSystem {
         Ok(adapted)
     }
     
+    /// Soften a message when the user's state has been flapping.
+    ///
+    /// Rapid flow↔distracted swings usually mean frustration, so high-volatility
+    /// messages drop exclamation marks and lead with a calm acknowledgement.
+    pub fn adapt_for_volatility(&self, message: &str, volatility: f32) -> String {
+        match VolatilityBand::from_score(volatility) {
+            VolatilityBand::Stable => message.to_string(),
+            VolatilityBand::Unsettled => calm_exclamations(message),
+            VolatilityBand::Turbulent => {
+                let calmed = calm_exclamations(message);
+                if calmed.starts_with(TURBULENT_PREFIX) {
+                    calmed
+                } else {
+                    format!("{}{}", TURBULENT_PREFIX, calmed)
+                }
+            }
+        }
+    }
+    
     fn adapt_formality(&self, message: &str, formality: &crate::personality_enhanced::FormalityLevel) -> String {
         match formality {
             crate::personality_enhanced::FormalityLevel::Casual => {
@@ -393,16 +448,174 @@ pub struct CommunicationStyle {
     pub formality: String,
     pub intensity: String,
     pub preferred_length: String,
+    pub tone: String,
+    pub volatility: f32,
 }
 
 impl CommunicationStyle {
     fn from_preferences(prefs: &CommunicationPreferences) -> Self {
+        Self::from_preferences_with_volatility(prefs, 0.0)
+    }
+    
+    /// Derive the style from preferences, dialing intensity down as state volatility rises
+    fn from_preferences_with_volatility(prefs: &CommunicationPreferences, volatility: f32) -> Self {
+        let volatility = volatility.clamp(0.0, 1.0);
+        let band = VolatilityBand::from_score(volatility);
+        
+        let intensity = match (band, &prefs.intensity_preference) {
+            (VolatilityBand::Stable, preferred) => preferred.clone(),
+            (VolatilityBand::Unsettled, IntensityPreference::Energetic) => IntensityPreference::Moderate,
+            (VolatilityBand::Unsettled, preferred) => preferred.clone(),
+            (VolatilityBand::Turbulent, _) => IntensityPreference::Subtle,
+        };
+        
+        let tone = match band {
+            VolatilityBand::Stable => "steady",
+            VolatilityBand::Unsettled => "gentle",
+            VolatilityBand::Turbulent => "reassuring",
+        };
+        
         Self {
             formality: format!("{:?}", prefs.formality_level),
-            intensity: format!("{:?}", prefs.intensity_preference),
+            intensity: format!("{:?}", intensity),
             preferred_length: "adaptive".to_string(),
+            tone: tone.to_string(),
+            volatility,
+        }
+    }
+}
+
+const TURBULENT_PREFIX: &str = "Rough patch, no stress. ";
+
+/// Turn sentence-ending exclamation marks into full stops
+///
+/// Only a run of `!` followed by whitespace or the end of the text counts, so
+/// code in a message (`a != b`, `!important`, `if !done`) is left alone.
+fn calm_exclamations(message: &str) -> String {
+    let mut calmed = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '!' {
+            calmed.push(c);
+            continue;
+        }
+        let mut run = 1;
+        while chars.next_if_eq(&'!').is_some() {
+            run += 1;
+        }
+        if chars.peek().is_none_or(|next| next.is_whitespace()) {
+            calmed.push('.');
+        } else {
+            calmed.extend(std::iter::repeat_n('!', run));
+        }
+    }
+    calmed
+}
+
+/// Coarse volatility bands used to pick a tone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VolatilityBand {
+    Stable,
+    Unsettled,
+    Turbulent,
+}
+
+impl VolatilityBand {
+    fn from_score(volatility: f32) -> Self {
+        if volatility >= 0.6 {
+            VolatilityBand::Turbulent
+        } else if volatility >= 0.3 {
+            VolatilityBand::Unsettled
+        } else {
+            VolatilityBand::Stable
+        }
+    }
+}
+
+/// Coarse state category used for volatility tracking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StateCategory {
+    Focused,
+    Distracted,
+    Other,
+}
+
+impl StateCategory {
+    fn from_state(state: &crate::types::ADHDStateType) -> Self {
+        match state {
+            crate::types::ADHDStateType::Flow { .. }
+            | crate::types::ADHDStateType::Hyperfocus { .. } => StateCategory::Focused,
+            crate::types::ADHDStateType::Distracted { .. } => StateCategory::Distracted,
+            crate::types::ADHDStateType::Transitioning
+            | crate::types::ADHDStateType::Neutral => StateCategory::Other,
+        }
+    }
+}
+
+/// Rolling window of observed states used to score volatility.
+///
+/// Volatility is the weighted share of transitions in the window, where
+/// focused↔distracted flips count double-weight versus other changes.
+#[derive(Debug, Clone)]
+pub struct StateVolatilityTracker {
+    observations: VecDeque<(StateCategory, DateTime<Utc>)>,
+    max_observations: usize,
+    window: Duration,
+}
+
+impl StateVolatilityTracker {
+    pub fn new() -> Self {
+        Self::with_window(12, Duration::minutes(15))
+    }
+    
+    pub fn with_window(max_observations: usize, window: Duration) -> Self {
+        Self {
+            observations: VecDeque::with_capacity(max_observations),
+            max_observations: max_observations.max(2),
+            window,
         }
     }
+    
+    /// Record an observed state at the given time
+    pub fn record(&mut self, state: &crate::types::ADHDStateType, at: DateTime<Utc>) {
+        self.observations.push_back((StateCategory::from_state(state), at));
+        
+        while self.observations.len() > self.max_observations {
+            self.observations.pop_front();
+        }
+        
+        let cutoff = at - self.window;
+        while matches!(self.observations.front(), Some((_, ts)) if *ts < cutoff) {
+            self.observations.pop_front();
+        }
+    }
+    
+    /// Volatility score in 0.0-1.0
+    pub fn volatility(&self) -> f32 {
+        if self.observations.len() < 2 {
+            return 0.0;
+        }
+        
+        let transitions = self.observations.len() - 1;
+        let weighted: f32 = self.observations
+            .iter()
+            .zip(self.observations.iter().skip(1))
+            .map(|((prev, _), (next, _))| match (prev, next) {
+                (a, b) if a == b => 0.0,
+                (StateCategory::Focused, StateCategory::Distracted)
+                | (StateCategory::Distracted, StateCategory::Focused) => 1.0,
+                _ => 0.5,
+            })
+            .sum();
+        
+        (weighted / transitions as f32).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for StateVolatilityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Learning insight about user preferences
@@ -505,5 +718,92 @@ mod tests {
         assert_eq!(style.formality, "Balanced");
         assert_eq!(style.intensity, "Moderate");
         assert_eq!(style.preferred_length, "adaptive");
+        assert_eq!(style.tone, "steady");
+    }
+    
+    #[test]
+    fn test_volatility_stable_states() {
+        let mut tracker = StateVolatilityTracker::new();
+        let now = Utc::now();
+        for i in 0..6 {
+            tracker.record(&ADHDStateType::Flow { depth: 0.7 }, now + Duration::seconds(i * 30));
+        }
+        
+        assert_eq!(tracker.volatility(), 0.0);
+    }
+    
+    #[test]
+    fn test_volatility_flow_distracted_flapping() {
+        let mut tracker = StateVolatilityTracker::new();
+        let now = Utc::now();
+        for i in 0..6 {
+            let state = if i % 2 == 0 {
+                ADHDStateType::Flow { depth: 0.7 }
+            } else {
+                ADHDStateType::Distracted { severity: 0.6 }
+            };
+            tracker.record(&state, now + Duration::seconds(i * 30));
+        }
+        
+        assert_eq!(tracker.volatility(), 1.0);
+    }
+    
+    #[test]
+    fn test_volatility_window_expiry() {
+        let mut tracker = StateVolatilityTracker::with_window(12, Duration::minutes(5));
+        let now = Utc::now();
+        tracker.record(&ADHDStateType::Flow { depth: 0.7 }, now);
+        tracker.record(&ADHDStateType::Distracted { severity: 0.6 }, now + Duration::seconds(30));
+        assert!(tracker.volatility() > 0.9);
+        
+        // Old flapping ages out of the window
+        tracker.record(&ADHDStateType::Neutral, now + Duration::minutes(20));
+        tracker.record(&ADHDStateType::Neutral, now + Duration::minutes(21));
+        assert_eq!(tracker.volatility(), 0.0);
+    }
+    
+    #[test]
+    fn test_communication_style_shifts_with_volatility() {
+        let mut prefs = CommunicationPreferences::default();
+        prefs.intensity_preference = IntensityPreference::Energetic;
+        
+        let stable = CommunicationStyle::from_preferences_with_volatility(&prefs, 0.1);
+        assert_eq!(stable.tone, "steady");
+        assert_eq!(stable.intensity, "Energetic");
+        
+        let unsettled = CommunicationStyle::from_preferences_with_volatility(&prefs, 0.4);
+        assert_eq!(unsettled.tone, "gentle");
+        assert_eq!(unsettled.intensity, "Moderate");
+        
+        let turbulent = CommunicationStyle::from_preferences_with_volatility(&prefs, 0.9);
+        assert_eq!(turbulent.tone, "reassuring");
+        assert_eq!(turbulent.intensity, "Subtle");
+    }
+    
+    #[test]
+    fn test_message_softened_under_volatility() {
+        let system = AdaptiveCommunicationSystem::new();
+        let message = "Great job! Keep going!";
+        
+        assert_eq!(system.adapt_for_volatility(message, 0.0), message);
+        assert_eq!(system.adapt_for_volatility(message, 0.4), "Great job. Keep going.");
+        
+        let turbulent = system.adapt_for_volatility(message, 0.9);
+        assert!(turbulent.starts_with(TURBULENT_PREFIX));
+        assert!(!turbulent.contains('!'));
+        
+        // Prefix is not stacked on repeated passes
+        assert_eq!(system.adapt_for_volatility(&turbulent, 0.9), turbulent);
+    }
+
+    #[test]
+    fn test_only_sentence_final_exclamations_calmed() {
+        let system = AdaptiveCommunicationSystem::new();
+        let message = "Nice!! Check `a != b` and `if !done`, then drop `!important`!";
+
+        assert_eq!(
+            system.adapt_for_volatility(message, 0.4),
+            "Nice. Check `a != b` and `if !done`, then drop `!important`."
+        );
     }
 }
\ No newline at end of file
@@ -6,7 +6,9 @@
 use crate::error::{AIIntegrationError, Result};
 use crate::types::{PrivacyAnalysis, SensitivePattern, SensitivePatternType};
 use regex::{Regex, RegexSet};
+use skelly_jelly_privacy_policy::{EntityKind, PrivacyPolicy, PrivacyPolicyConfig};
 use std::collections::HashMap;
+use std::sync::RwLock;
 
 /// Privacy guardian that protects user data
 pub struct PrivacyGuardian {
@@ -26,6 +28,11 @@ impl PrivacyGuardian {
         }
     }
 
+    /// Apply the privacy policy broadcast by the orchestrator
+    pub fn set_policy(&self, policy: PrivacyPolicyConfig) {
+        self.pii_detector.set_policy(policy);
+    }
+
     /// Analyze text for privacy issues
     pub fn analyze(&self, text: &str) -> Result<PrivacyAnalysis> {
         // Detect PII
@@ -107,42 +114,20 @@ impl PrivacyGuardian {
 }
 
 /// Detects personally identifiable information
+///
+/// Emails, phone numbers, SSNs, cards, IP addresses, credentials, names,
+/// organisations and places are found and masked by the shared privacy
+/// policy (with its NER model when one is configured); the basic name
+/// patterns here are a lower-confidence extra.
 pub struct PIIDetector {
-    email_regex: Regex,
-    phone_regex: Regex,
-    ssn_regex: Regex,
-    credit_card_regex: Regex,
-    ip_regex: Regex,
+    policy: RwLock<PrivacyPolicy>,
     name_patterns: RegexSet,
 }
 
 impl PIIDetector {
     pub fn new() -> Self {
         Self {
-            // Email pattern
-            email_regex: Regex::new(
-                r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b"
-            ).unwrap(),
-            
-            // Phone patterns (various formats)
-            phone_regex: Regex::new(
-                r"(?:\+?1[-.\s]?)?\(?([0-9]{3})\)?[-.\s]?([0-9]{3})[-.\s]?([0-9]{4})"
-            ).unwrap(),
-            
-            // SSN pattern
-            ssn_regex: Regex::new(
-                r"\b\d{3}-?\d{2}-?\d{4}\b"
-            ).unwrap(),
-            
-            // Credit card pattern (basic)
-            credit_card_regex: Regex::new(
-                r"\b(?:\d{4}[-\s]?){3}\d{4}\b"
-            ).unwrap(),
-            
-            // IP address pattern
-            ip_regex: Regex::new(
-                r"\b(?:[0-9]{1,3}\.){3}[0-9]{1,3}\b"
-            ).unwrap(),
+            policy: RwLock::new(PrivacyPolicy::default()),
             
             // Common name patterns (very basic)
             name_patterns: RegexSet::new(&[
@@ -152,68 +137,40 @@ impl PIIDetector {
         }
     }
 
+    /// Replace the privacy policy the detector applies
+    pub fn set_policy(&self, policy: PrivacyPolicyConfig) {
+        self.policy.write().unwrap().set_config(policy);
+    }
+
     pub fn detect(&self, text: &str) -> Result<Vec<SensitivePattern>> {
         let mut patterns = Vec::new();
         
-        // Detect emails
-        for mat in self.email_regex.find_iter(text) {
-            patterns.push(SensitivePattern {
-                pattern_type: SensitivePatternType::Email,
-                location: (mat.start(), mat.end()),
-                confidence: 0.95,
-                replacement: "[EMAIL]".to_string(),
-            });
-        }
-        
-        // Detect phone numbers
-        for mat in self.phone_regex.find_iter(text) {
+        // Entities covered by the shared policy, masked the way it says
+        let policy = self.policy.read().unwrap();
+        for entity in policy.detect(text) {
+            let pattern_type = match entity.kind {
+                EntityKind::Email => SensitivePatternType::Email,
+                EntityKind::Phone => SensitivePatternType::Phone,
+                EntityKind::Ssn => SensitivePatternType::SSN,
+                EntityKind::CreditCard => SensitivePatternType::CreditCard,
+                EntityKind::IpAddress => SensitivePatternType::IPAddress,
+                EntityKind::Credential => SensitivePatternType::Custom("credential".to_string()),
+                EntityKind::Person => SensitivePatternType::PersonalName,
+                EntityKind::Org => SensitivePatternType::Organization,
+                EntityKind::Location => SensitivePatternType::Custom("location".to_string()),
+            };
             patterns.push(SensitivePattern {
-                pattern_type: SensitivePatternType::Phone,
-                location: (mat.start(), mat.end()),
-                confidence: 0.9,
-                replacement: "[PHONE]".to_string(),
+                pattern_type,
+                location: (entity.start, entity.end),
+                confidence: entity.confidence,
+                replacement: policy.strategy(entity.kind).apply(
+                    entity.kind,
+                    entity.text(text),
+                    &policy.config().hash_salt,
+                ),
             });
         }
         
-        // Detect SSNs
-        for mat in self.ssn_regex.find_iter(text) {
-            // Additional validation to reduce false positives
-            let ssn_text = mat.as_str().replace('-', "");
-            if ssn_text.len() == 9 && self.is_valid_ssn(&ssn_text) {
-                patterns.push(SensitivePattern {
-                    pattern_type: SensitivePatternType::SSN,
-                    location: (mat.start(), mat.end()),
-                    confidence: 0.98,
-                    replacement: "[SSN]".to_string(),
-                });
-            }
-        }
-        
-        // Detect credit cards
-        for mat in self.credit_card_regex.find_iter(text) {
-            let card_text = mat.as_str().replace(&['-', ' '][..], "");
-            if self.is_valid_credit_card(&card_text) {
-                patterns.push(SensitivePattern {
-                    pattern_type: SensitivePatternType::CreditCard,
-                    location: (mat.start(), mat.end()),
-                    confidence: 0.9,
-                    replacement: "[CARD]".to_string(),
-                });
-            }
-        }
-        
-        // Detect IP addresses (less sensitive but still worth noting)
-        for mat in self.ip_regex.find_iter(text) {
-            if self.is_valid_ip(mat.as_str()) {
-                patterns.push(SensitivePattern {
-                    pattern_type: SensitivePatternType::IPAddress,
-                    location: (mat.start(), mat.end()),
-                    confidence: 0.8,
-                    replacement: "[IP]".to_string(),
-                });
-            }
-        }
-        
         // Detect potential names (lower confidence)
         let matches: Vec<_> = self.name_patterns.matches(text).into_iter().collect();
         for &pattern_idx in &matches {
@@ -232,45 +189,6 @@ impl PIIDetector {
         Ok(patterns)
     }
 
-    fn is_valid_ssn(&self, ssn: &str) -> bool {
-        // Basic SSN validation (not comprehensive)
-        ssn.len() == 9 && !ssn.starts_with("000") && !ssn[3..5].eq("00")
-    }
-
-    fn is_valid_credit_card(&self, card: &str) -> bool {
-        // Luhn algorithm check
-        if card.len() < 13 || card.len() > 19 {
-            return false;
-        }
-        
-        let mut sum = 0;
-        let mut alternate = false;
-        
-        for ch in card.chars().rev() {
-            if let Some(digit) = ch.to_digit(10) {
-                let mut n = digit;
-                if alternate {
-                    n *= 2;
-                    if n > 9 {
-                        n = n / 10 + n % 10;
-                    }
-                }
-                sum += n;
-                alternate = !alternate;
-            } else {
-                return false;
-            }
-        }
-        
-        sum % 10 == 0
-    }
-
-    fn is_valid_ip(&self, ip: &str) -> bool {
-        ip.split('.').all(|octet| {
-            octet.parse::<u8>().is_ok()
-        })
-    }
-
     fn get_name_regex(&self, pattern_idx: usize) -> Option<&Regex> {
         // This is a simplification - in practice, you'd need a more sophisticated approach
         None
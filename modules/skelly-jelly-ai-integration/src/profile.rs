@@ -0,0 +1,226 @@
+//! Personality profile export and import
+//!
+//! A profile bundles everything a user tunes about Skelly: the personality
+//! traits and the message personalization (humor, directness, blocked
+//! phrases, ...). It is exported as JSON signed with the install's Ed25519
+//! key, so a file that was edited or corrupted after export is refused on
+//! import. Profiles written by an older format version are migrated forward
+//! before they are applied.
+
+use crate::contextual_messaging::MessagePersonalization;
+use crate::error::{AIIntegrationError, Result};
+use crate::types::PersonalityTraits;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Format tag written into every exported profile
+pub const PROFILE_FORMAT: &str = "skelly-jelly/personality-profile";
+
+/// Current profile format version
+pub const PROFILE_VERSION: u32 = 1;
+
+/// Upgrades applied on import; step `i` turns a version `i + 1` profile into version `i + 2`
+const MIGRATIONS: &[fn(&mut Value)] = &[];
+
+/// Everything the user has tuned about Skelly's personality
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersonalityProfile {
+    pub traits: PersonalityTraits,
+    pub personalization: MessagePersonalization,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Profile file as written to disk
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedProfile {
+    format: String,
+    version: u32,
+    profile: Value,
+    /// Base64 Ed25519 public key of the exporting install
+    public_key: String,
+    /// Base64 signature over format, version and profile
+    signature: String,
+}
+
+/// A verified profile read from a file
+#[derive(Debug, Clone)]
+pub struct ProfileImport {
+    pub profile: PersonalityProfile,
+    /// Format version the file was written in, if it had to be migrated
+    pub migrated_from: Option<u32>,
+    /// Whether the file was exported by this install
+    pub signed_by_this_install: bool,
+}
+
+/// Signs exported profiles with a persistent Ed25519 key
+pub struct ProfileSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl ProfileSigner {
+    /// A fresh key that lives only as long as this signer
+    pub fn generate() -> Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| AIIntegrationError::InternalError)?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Load the key at `path`, creating it on first use
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if path.exists() {
+            return Self::from_pkcs8(&fs::read(path)?);
+        }
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| AIIntegrationError::InternalError)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, pkcs8.as_ref())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    fn from_pkcs8(pkcs8: &[u8]) -> Result<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8).map_err(|_| AIIntegrationError::InvalidProfile {
+            reason: "signing key is corrupt".to_string(),
+        })?;
+        Ok(Self { key_pair })
+    }
+
+    /// Base64 public key, as written into exported profiles
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Serialize and sign `profile` at the current format version
+    pub fn export(&self, profile: &PersonalityProfile) -> Result<String> {
+        let profile = serde_json::to_value(profile)?;
+        let signature = self.key_pair.sign(&signing_payload(PROFILE_VERSION, &profile)?);
+        let file = SignedProfile {
+            format: PROFILE_FORMAT.to_string(),
+            version: PROFILE_VERSION,
+            profile,
+            public_key: self.public_key(),
+            signature: STANDARD.encode(signature.as_ref()),
+        };
+        Ok(serde_json::to_string_pretty(&file)?)
+    }
+
+    /// Verify, migrate and parse an exported profile
+    pub fn import(&self, json: &str) -> Result<ProfileImport> {
+        let file: SignedProfile = serde_json::from_str(json).map_err(|e| invalid(format!("not a profile file: {}", e)))?;
+        if file.format != PROFILE_FORMAT {
+            return Err(invalid(format!("unexpected format '{}'", file.format)));
+        }
+
+        let public_key = STANDARD.decode(&file.public_key).map_err(|_| invalid("malformed public key"))?;
+        let signature = STANDARD.decode(&file.signature).map_err(|_| invalid("malformed signature"))?;
+        UnparsedPublicKey::new(&ED25519, &public_key)
+            .verify(&signing_payload(file.version, &file.profile)?, &signature)
+            .map_err(|_| invalid("signature does not match; the file was changed after export"))?;
+
+        let profile = migrate(file.version, file.profile)?;
+        Ok(ProfileImport {
+            profile: serde_json::from_value(profile).map_err(|e| invalid(format!("bad profile contents: {}", e)))?,
+            migrated_from: (file.version != PROFILE_VERSION).then_some(file.version),
+            signed_by_this_install: public_key == self.key_pair.public_key().as_ref(),
+        })
+    }
+}
+
+/// Bytes covered by the signature
+fn signing_payload(version: u32, profile: &Value) -> Result<Vec<u8>> {
+    let mut payload = format!("{}\n{}\n", PROFILE_FORMAT, version).into_bytes();
+    payload.extend(serde_json::to_vec(profile)?);
+    Ok(payload)
+}
+
+/// Bring a profile written at `version` up to [`PROFILE_VERSION`]
+fn migrate(version: u32, mut profile: Value) -> Result<Value> {
+    if version == 0 || version > PROFILE_VERSION {
+        return Err(invalid(format!(
+            "profile version {} is not supported (this build reads 1 to {})",
+            version, PROFILE_VERSION
+        )));
+    }
+    for step in &MIGRATIONS[(version - 1) as usize..] {
+        step(&mut profile);
+    }
+    Ok(profile)
+}
+
+fn invalid(reason: impl Into<String>) -> AIIntegrationError {
+    AIIntegrationError::InvalidProfile { reason: reason.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuned_profile() -> PersonalityProfile {
+        PersonalityProfile {
+            traits: PersonalityTraits { humor: 0.9, directness: 0.2, ..Default::default() },
+            personalization: MessagePersonalization {
+                humor_level: 0.8,
+                blocked_phrases: vec!["you got this".to_string()],
+                ..Default::default()
+            },
+            exported_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_tamper_detection() {
+        let signer = ProfileSigner::generate().unwrap();
+        let exported = signer.export(&tuned_profile()).unwrap();
+
+        let imported = signer.import(&exported).unwrap();
+        assert!(imported.signed_by_this_install);
+        assert_eq!(imported.migrated_from, None);
+        assert_eq!(imported.profile.traits.humor, 0.9);
+        assert_eq!(imported.profile.personalization.blocked_phrases, vec!["you got this"]);
+
+        // Another install verifies the file but knows it did not write it
+        let other = ProfileSigner::generate().unwrap();
+        assert!(!other.import(&exported).unwrap().signed_by_this_install);
+
+        let tampered = exported.replace("you got this", "keep going");
+        assert!(matches!(signer.import(&tampered), Err(AIIntegrationError::InvalidProfile { .. })));
+    }
+
+    #[test]
+    fn test_versions_and_missing_sections() {
+        let signer = ProfileSigner::generate().unwrap();
+        let sign = |version: u32, profile: Value| {
+            let signature = signer.key_pair.sign(&signing_payload(version, &profile).unwrap());
+            serde_json::to_string(&SignedProfile {
+                format: PROFILE_FORMAT.to_string(),
+                version,
+                profile,
+                public_key: signer.public_key(),
+                signature: STANDARD.encode(signature.as_ref()),
+            })
+            .unwrap()
+        };
+
+        // Sections the file lacks fall back to defaults
+        let partial = sign(PROFILE_VERSION, serde_json::json!({ "traits": { "cheerfulness": 0.1, "humor": 0.2, "supportiveness": 0.3, "casualness": 0.4, "pun_frequency": 0.0, "directness": 0.9 } }));
+        let imported = signer.import(&partial).unwrap();
+        assert_eq!(imported.profile.traits.directness, 0.9);
+        assert!(imported.profile.personalization.blocked_phrases.is_empty());
+
+        let newer = sign(PROFILE_VERSION + 1, serde_json::to_value(tuned_profile()).unwrap());
+        assert!(matches!(signer.import(&newer), Err(AIIntegrationError::InvalidProfile { .. })));
+    }
+}
@@ -0,0 +1,398 @@
+//! Rolling session memory for prompts
+//!
+//! Over a long work session, the full history of states and suggestions would
+//! overflow the local model's context. Recent events are kept verbatim. Older
+//! ones are folded into compact structured summaries: how long each state
+//! lasted, which suggestions were accepted, and which tasks were worked on.
+//! Rendering always fits a token budget, newest information first.
+//!
+//! A task the user declared themselves takes precedence over the task inferred
+//! from their work context until they clear it.
+
+use crate::types::{ADHDState, ADHDStateType};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, VecDeque};
+use uuid::Uuid;
+
+/// Rough token estimate, matching the context compressor: 1 token ≈ 4 characters
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// How much session history is kept before older parts are summarized
+#[derive(Debug, Clone)]
+pub struct SessionContextConfig {
+    /// Events kept verbatim before the oldest are folded into a summary
+    pub max_recent_events: usize,
+    /// Summaries kept before the two oldest are merged
+    pub max_summaries: usize,
+    /// Accepted suggestions quoted per summary
+    pub max_quoted_suggestions: usize,
+}
+
+impl Default for SessionContextConfig {
+    fn default() -> Self {
+        Self {
+            max_recent_events: 12,
+            max_summaries: 4,
+            max_quoted_suggestions: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SessionEvent {
+    State { state: &'static str, confidence: f32, at: DateTime<Utc> },
+    Suggestion { request_id: Uuid, text: String, accepted: Option<bool>, at: DateTime<Utc> },
+    Task { description: String, at: DateTime<Utc> },
+}
+
+impl SessionEvent {
+    fn at(&self) -> DateTime<Utc> {
+        match self {
+            Self::State { at, .. } | Self::Suggestion { at, .. } | Self::Task { at, .. } => *at,
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Self::State { state, confidence, at } => {
+                format!("{} state {} ({:.1})", at.format("%H:%M"), state, confidence)
+            }
+            Self::Suggestion { text, accepted, at, .. } => {
+                let outcome = match accepted {
+                    Some(true) => "accepted",
+                    Some(false) => "dismissed",
+                    None => "no response",
+                };
+                format!("{} suggested \"{}\" ({})", at.format("%H:%M"), truncate(text, 60), outcome)
+            }
+            Self::Task { description, at } => format!("{} task: {}", at.format("%H:%M"), truncate(description, 60)),
+        }
+    }
+}
+
+/// Compact record of an older stretch of the session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// State observations per state name
+    pub states: BTreeMap<&'static str, u32>,
+    pub suggestions_offered: u32,
+    pub suggestions_accepted: u32,
+    /// A few accepted suggestions, shortened
+    pub accepted_examples: Vec<String>,
+    /// Distinct tasks worked on, oldest first
+    pub tasks: Vec<String>,
+}
+
+impl SessionSummary {
+    fn from_events(events: &[SessionEvent], max_quoted: usize) -> Self {
+        let mut summary = Self {
+            start: events.first().map(SessionEvent::at).unwrap_or_else(Utc::now),
+            end: events.last().map(SessionEvent::at).unwrap_or_else(Utc::now),
+            states: BTreeMap::new(),
+            suggestions_offered: 0,
+            suggestions_accepted: 0,
+            accepted_examples: Vec::new(),
+            tasks: Vec::new(),
+        };
+        for event in events {
+            match event {
+                SessionEvent::State { state, .. } => *summary.states.entry(state).or_insert(0) += 1,
+                SessionEvent::Suggestion { text, accepted, .. } => {
+                    summary.suggestions_offered += 1;
+                    if *accepted == Some(true) {
+                        summary.suggestions_accepted += 1;
+                        if summary.accepted_examples.len() < max_quoted {
+                            summary.accepted_examples.push(truncate(text, 40));
+                        }
+                    }
+                }
+                SessionEvent::Task { description, .. } => summary.add_task(description),
+            }
+        }
+        summary
+    }
+
+    fn merge(mut self, later: Self, max_quoted: usize) -> Self {
+        self.end = later.end;
+        for (state, count) in later.states {
+            *self.states.entry(state).or_insert(0) += count;
+        }
+        self.suggestions_offered += later.suggestions_offered;
+        self.suggestions_accepted += later.suggestions_accepted;
+        // Keep the most recent accepted examples
+        self.accepted_examples.extend(later.accepted_examples);
+        let excess = self.accepted_examples.len().saturating_sub(max_quoted);
+        self.accepted_examples.drain(..excess);
+        for task in &later.tasks {
+            self.add_task(task);
+        }
+        self
+    }
+
+    fn add_task(&mut self, description: &str) {
+        let description = truncate(description, 40);
+        if !self.tasks.contains(&description) {
+            self.tasks.push(description);
+        }
+    }
+
+    /// One line, e.g. `09:00-09:40: Flow 6, Distracted 2; 3 suggestions, 2 accepted ("..."); tasks: Coding`
+    pub fn render(&self) -> String {
+        let states: Vec<String> = self.states.iter().map(|(state, count)| format!("{} {}", state, count)).collect();
+        let mut line = format!("{}-{}: {}", self.start.format("%H:%M"), self.end.format("%H:%M"), states.join(", "));
+        if self.suggestions_offered > 0 {
+            line.push_str(&format!(
+                "; {} suggestions, {} accepted",
+                self.suggestions_offered, self.suggestions_accepted
+            ));
+            if !self.accepted_examples.is_empty() {
+                let quoted: Vec<String> = self.accepted_examples.iter().map(|text| format!("\"{}\"", text)).collect();
+                line.push_str(&format!(" ({})", quoted.join(", ")));
+            }
+        }
+        if !self.tasks.is_empty() {
+            line.push_str(&format!("; tasks: {}", self.tasks.join(", ")));
+        }
+        line
+    }
+}
+
+/// Session history that stays within a token budget
+#[derive(Debug)]
+pub struct SessionContext {
+    config: SessionContextConfig,
+    recent: VecDeque<SessionEvent>,
+    summaries: VecDeque<SessionSummary>,
+    current_task: Option<String>,
+    /// Whether `current_task` was declared by the user rather than inferred
+    task_declared: bool,
+}
+
+impl SessionContext {
+    pub fn new(config: SessionContextConfig) -> Self {
+        Self {
+            config,
+            recent: VecDeque::new(),
+            summaries: VecDeque::new(),
+            current_task: None,
+            task_declared: false,
+        }
+    }
+
+    pub fn record_state(&mut self, state: &ADHDState) {
+        self.push(SessionEvent::State {
+            state: state_name(&state.state_type),
+            confidence: state.confidence,
+            at: Utc::now(),
+        });
+    }
+
+    /// Note the task being worked on; repeated reports of the same task are ignored
+    ///
+    /// Inferred tasks are ignored while the user has declared one.
+    pub fn set_task(&mut self, description: &str) {
+        if self.task_declared || self.current_task.as_deref() == Some(description) {
+            return;
+        }
+        self.current_task = Some(description.to_string());
+        self.push(SessionEvent::Task { description: description.to_string(), at: Utc::now() });
+    }
+
+    /// The user said what they are working on; `None` goes back to inferring it
+    pub fn declare_task(&mut self, description: Option<&str>) {
+        match description {
+            Some(description) => {
+                self.task_declared = false;
+                self.set_task(description);
+                self.task_declared = true;
+            }
+            None => {
+                self.task_declared = false;
+                self.current_task = None;
+            }
+        }
+    }
+
+    pub fn record_suggestion(&mut self, request_id: Uuid, text: &str) {
+        self.push(SessionEvent::Suggestion {
+            request_id,
+            text: text.to_string(),
+            accepted: None,
+            at: Utc::now(),
+        });
+    }
+
+    /// Record how the user responded to a suggestion still held verbatim
+    ///
+    /// Returns false if the suggestion has already been folded into a summary.
+    pub fn record_outcome(&mut self, request_id: Uuid, was_accepted: bool) -> bool {
+        for event in self.recent.iter_mut() {
+            if let SessionEvent::Suggestion { request_id: id, accepted, .. } = event {
+                if *id == request_id {
+                    *accepted = Some(was_accepted);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn summaries(&self) -> impl Iterator<Item = &SessionSummary> {
+        self.summaries.iter()
+    }
+
+    /// Render the session within `max_tokens`: current task, then recent
+    /// events newest first, then summaries newest first. Whatever doesn't fit
+    /// is left out, oldest first.
+    pub fn render(&self, max_tokens: usize) -> String {
+        let mut lines = Vec::new();
+        if let Some(task) = &self.current_task {
+            let source = if self.task_declared { " (as stated by the user)" } else { "" };
+            lines.push(format!("Current task{}: {}", source, truncate(task, 80)));
+        }
+        let recent: Vec<String> = self.recent.iter().rev().map(SessionEvent::render).collect();
+        if !recent.is_empty() {
+            lines.push(format!("Recent: {}", recent.join("; ")));
+        }
+        lines.extend(self.summaries.iter().rev().map(|summary| format!("Earlier {}", summary.render())));
+
+        let mut rendered = String::new();
+        for line in lines {
+            let separator = if rendered.is_empty() { "" } else { ". " };
+            let available = max_tokens.saturating_sub(estimate_tokens(&rendered) + estimate_tokens(separator));
+            if estimate_tokens(&line) <= available {
+                rendered.push_str(separator);
+                rendered.push_str(&line);
+            } else {
+                // Only a clipped part of this line fits; nothing older will either
+                let clipped = truncate(&line, (available * 4).saturating_sub(3));
+                if clipped.len() > 3 && estimate_tokens(&clipped) <= available {
+                    rendered.push_str(separator);
+                    rendered.push_str(&clipped);
+                }
+                break;
+            }
+        }
+        rendered
+    }
+
+    fn push(&mut self, event: SessionEvent) {
+        self.recent.push_back(event);
+        if self.recent.len() <= self.config.max_recent_events {
+            return;
+        }
+
+        // Fold the older half of the recent events into a summary
+        let fold = (self.recent.len() / 2).max(1);
+        let older: Vec<SessionEvent> = self.recent.drain(..fold).collect();
+        self.summaries.push_back(SessionSummary::from_events(&older, self.config.max_quoted_suggestions));
+
+        while self.summaries.len() > self.config.max_summaries.max(1) {
+            let oldest = self.summaries.pop_front().unwrap();
+            let next = self.summaries.pop_front().unwrap();
+            self.summaries.push_front(oldest.merge(next, self.config.max_quoted_suggestions));
+        }
+    }
+}
+
+impl Default for SessionContext {
+    fn default() -> Self {
+        Self::new(SessionContextConfig::default())
+    }
+}
+
+fn state_name(state: &ADHDStateType) -> &'static str {
+    match state {
+        ADHDStateType::Flow { .. } => "Flow",
+        ADHDStateType::Hyperfocus { .. } => "Hyperfocus",
+        ADHDStateType::Distracted { .. } => "Distracted",
+        ADHDStateType::Transitioning => "Transitioning",
+        ADHDStateType::Neutral => "Neutral",
+    }
+}
+
+/// Shorten to at most `max_chars` bytes on a character boundary, marking the cut with "..."
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut end = max_chars.saturating_sub(3);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn state(state_type: ADHDStateType) -> ADHDState {
+        ADHDState {
+            state_type,
+            confidence: 0.8,
+            depth: None,
+            duration: 60_000,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_older_events_fold_into_bounded_summaries() {
+        let mut session = SessionContext::default();
+        session.set_task("Debugging code");
+        let accepted = Uuid::new_v4();
+        session.record_suggestion(accepted, "Try rubber duck debugging");
+        assert!(session.record_outcome(accepted, true));
+
+        for i in 0..500 {
+            let state_type = if i % 3 == 0 { ADHDStateType::Distracted { severity: 0.5 } } else { ADHDStateType::Flow { depth: 0.7 } };
+            session.record_state(&state(state_type));
+        }
+
+        assert!(session.recent.len() <= 12);
+        assert!(session.summaries.len() <= 4);
+        let oldest = session.summaries().next().unwrap();
+        assert_eq!((oldest.suggestions_offered, oldest.suggestions_accepted), (1, 1));
+        assert_eq!(oldest.tasks, vec!["Debugging code".to_string()]);
+        let observed: u32 = session.summaries().flat_map(|s| s.states.values()).sum();
+        assert_eq!(observed as usize + session.recent.len(), 500);
+        assert!(!session.record_outcome(accepted, false));
+    }
+
+    #[test]
+    fn test_render_stays_within_budget_newest_first() {
+        let mut session = SessionContext::default();
+        for i in 0..200 {
+            session.set_task(&format!("Task number {}", i % 7));
+            session.record_suggestion(Uuid::new_v4(), "Break the problem into smaller pieces");
+            session.record_state(&state(ADHDStateType::Flow { depth: 0.9 }));
+        }
+
+        for budget in [0, 5, 20, 60, 150, 1000] {
+            let rendered = session.render(budget);
+            assert!(estimate_tokens(&rendered) <= budget, "{} tokens over budget {}", estimate_tokens(&rendered), budget);
+        }
+        assert!(session.render(20).starts_with("Current task: Task number"));
+        assert!(session.render(1000).contains("Earlier"));
+    }
+
+    #[test]
+    fn test_declared_task_wins_over_inferred() {
+        let mut session = SessionContext::default();
+        session.set_task("Editing spreadsheet");
+        session.declare_task(Some("Writing Q3 report"));
+        session.set_task("Browsing web");
+        assert!(session.render(100).starts_with("Current task (as stated by the user): Writing Q3 report"));
+
+        session.declare_task(None);
+        assert!(!session.render(100).contains("Current task"));
+        session.set_task("Browsing web");
+        assert!(session.render(100).starts_with("Current task: Browsing web"));
+    }
+}
@@ -3,6 +3,7 @@
 //! Generates helpful, personality-driven suggestions using LLM or templates.
 
 use crate::error::{AIIntegrationError, Result};
+use crate::guardrails::{ContentGuardrail, GuardrailConfig, GuardrailMetrics, GuardrailOutcome};
 use crate::llm::{LLMManager, GenerationResult};
 use crate::personality::{PersonalityEngine, PersonalityContext};
 use crate::types::{
@@ -19,6 +20,7 @@ pub struct SuggestionGenerator {
     llm_manager: Arc<LLMManager>,
     personality_engine: std::sync::Mutex<PersonalityEngine>,
     validator: SuggestionValidator,
+    guardrail: ContentGuardrail,
 }
 
 impl SuggestionGenerator {
@@ -31,9 +33,21 @@ impl SuggestionGenerator {
             llm_manager,
             personality_engine: std::sync::Mutex::new(personality_engine),
             validator: SuggestionValidator::new(),
+            guardrail: ContentGuardrail::default(),
         }
     }
 
+    /// Use `config` for the post-generation guardrail
+    pub fn with_guardrails(mut self, config: GuardrailConfig) -> Self {
+        self.guardrail = ContentGuardrail::new(config);
+        self
+    }
+
+    /// Guardrail trigger counts since startup
+    pub fn guardrail_metrics(&self) -> GuardrailMetrics {
+        self.guardrail.metrics()
+    }
+
     /// Generate a suggestion based on context
     pub async fn generate(
         &self,
@@ -47,7 +61,7 @@ impl SuggestionGenerator {
         let raw_suggestion = if use_template {
             self.generate_template_suggestion(&context)?
         } else {
-            self.generate_llm_suggestion(&context, allow_api).await?
+            self.generate_guarded_llm_suggestion(&context, allow_api).await?
         };
 
         // Apply personality modifications
@@ -100,12 +114,47 @@ impl SuggestionGenerator {
         })
     }
 
+    /// LLM suggestion that passed the guardrail, or a template if none did
+    async fn generate_guarded_llm_suggestion(
+        &self,
+        context: &LLMContext,
+        allow_api: bool,
+    ) -> Result<RawSuggestion> {
+        let first = self.generate_llm_suggestion(context, allow_api, None).await?;
+        let regenerated = std::sync::Mutex::new(None);
+        let outcome = self
+            .guardrail
+            .enforce(first.text.clone(), |corrections| {
+                let regenerated = &regenerated;
+                async move {
+                    let suggestion = self.generate_llm_suggestion(context, allow_api, Some(&corrections)).await?;
+                    let text = suggestion.text.clone();
+                    *regenerated.lock().unwrap() = Some(suggestion);
+                    Ok(text)
+                }
+            })
+            .await?;
+
+        match outcome {
+            GuardrailOutcome::Passed(_) => Ok(first),
+            GuardrailOutcome::Regenerated { .. } => Ok(regenerated.into_inner().unwrap().unwrap_or(first)),
+            GuardrailOutcome::Rejected(violations) => {
+                log::warn!("Generated suggestion rejected by guardrail ({:?}), using template", violations);
+                self.generate_template_suggestion(context)
+            }
+        }
+    }
+
     async fn generate_llm_suggestion(
         &self,
         context: &LLMContext,
         allow_api: bool,
+        corrections: Option<&str>,
     ) -> Result<RawSuggestion> {
-        let prompt = self.build_prompt(context)?;
+        let mut prompt = self.build_prompt(context)?;
+        if let Some(corrections) = corrections {
+            prompt.push_str(corrections);
+        }
         let params = GenerationParams {
             max_tokens: context.max_tokens.min(200), // Keep suggestions brief
             temperature: 0.7,
@@ -143,6 +192,12 @@ impl SuggestionGenerator {
             context.user_preferences.message_style
         ));
 
+        // What happened in similar situations before
+        if !context.past_situations.is_empty() {
+            prompt.push('\n');
+            prompt.push_str(&context.past_situations);
+        }
+
         // Add specific request
         prompt.push_str("\nProvide a helpful, brief suggestion (1-2 sentences max):\n");
 
@@ -555,6 +610,7 @@ mod tests {
             system_prompt: "test".to_string(),
             behavioral_context: "focused".to_string(),
             work_context: "coding".to_string(),
+            past_situations: String::new(),
             intervention_type: "encouragement".to_string(),
             user_preferences: UserPreferences {
                 intervention_frequency: InterventionFrequency::Moderate,
@@ -584,6 +640,7 @@ mod tests {
             system_prompt: "test".to_string(),
             behavioral_context: "test".to_string(),
             work_context: "test".to_string(),
+            past_situations: String::new(),
             intervention_type: "encouragement".to_string(),
             user_preferences: UserPreferences {
                 intervention_frequency: InterventionFrequency::Moderate,
@@ -619,6 +676,7 @@ mod tests {
             system_prompt: "test".to_string(),
             behavioral_context: "test".to_string(),
             work_context: "test".to_string(),
+            past_situations: String::new(),
             intervention_type: "celebration".to_string(),
             user_preferences: UserPreferences {
                 intervention_frequency: InterventionFrequency::Moderate,
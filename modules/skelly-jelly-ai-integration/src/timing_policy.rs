@@ -0,0 +1,348 @@
+//! Learned intervention timing
+//!
+//! An optional contextual bandit that replaces the timing engine's fixed
+//! delay and confidence thresholds. Contexts are (focus state, work type, time
+//! of day); actions are how soon to intervene. Each intervention is scored from
+//! the user's response and how their focus changed afterwards, and the policy
+//! learns which action pays off in which context.
+//!
+//! Exploration is epsilon-greedy and capped by a daily budget. The policy only
+//! runs after the timing engine's safety checks (hyperfocus, blocked hours,
+//! hourly cap and cooldown) have passed, so it can never exceed the user's limits.
+
+use crate::context_detection::WorkType;
+use crate::intervention_timing::{FocusState, UserResponse};
+use crate::offline_responses::{FocusKind, WorkKind};
+use crate::types::TimeOfDay;
+use chrono::{DateTime, Duration, Local, Timelike, Utc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+/// Settings for the learned timing policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimingPolicyConfig {
+    /// Chance of trying a random action instead of the best known one
+    pub exploration_rate: f32,
+    /// Exploratory decisions allowed in any rolling 24 hours
+    pub max_explorations_per_day: u32,
+    /// Expected reward (0-1) below which the policy holds off
+    pub min_expected_reward: f32,
+    /// How long after an answered intervention the focus change is measured
+    pub outcome_window_minutes: i64,
+    /// How long an unanswered intervention waits before counting as ignored
+    pub outcome_timeout_minutes: i64,
+}
+
+impl Default for TimingPolicyConfig {
+    fn default() -> Self {
+        Self {
+            exploration_rate: 0.1,
+            max_explorations_per_day: 5,
+            min_expected_reward: 0.4,
+            outcome_window_minutes: 5,
+            outcome_timeout_minutes: 30,
+        }
+    }
+}
+
+/// How soon to intervene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimingAction {
+    Now,
+    Soon,
+    Later,
+}
+
+impl TimingAction {
+    pub const ALL: [TimingAction; 3] = [TimingAction::Now, TimingAction::Soon, TimingAction::Later];
+
+    pub fn delay_seconds(&self) -> u64 {
+        match self {
+            TimingAction::Now => 0,
+            TimingAction::Soon => 120,
+            TimingAction::Later => 600,
+        }
+    }
+}
+
+/// The situation a timing decision is made in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PolicyContext {
+    pub focus: FocusKind,
+    pub work: WorkKind,
+    pub time_of_day: TimeOfDay,
+}
+
+impl PolicyContext {
+    pub fn new(state: &FocusState, work_type: &WorkType, now: DateTime<Utc>) -> Self {
+        Self {
+            focus: FocusKind::from(state),
+            work: WorkKind::from(work_type),
+            time_of_day: TimeOfDay::from_hour(now.with_timezone(&Local).hour()),
+        }
+    }
+}
+
+/// What the policy decided
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyChoice {
+    /// Identifies the decision when its outcome is reported
+    pub decision_id: Uuid,
+    /// `None` means hold off for now
+    pub action: Option<TimingAction>,
+    /// Whether the action was picked to explore rather than exploit
+    pub explored: bool,
+    /// Estimated reward (0-1) of the best action in this context
+    pub expected_reward: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ArmStats {
+    reward: f32,
+    trials: u32,
+}
+
+impl ArmStats {
+    /// Mean reward with one neutral pseudo-observation, so untried arms start at 0.5
+    fn mean(&self) -> f32 {
+        (self.reward + 0.5) / (self.trials as f32 + 1.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingOutcome {
+    context: PolicyContext,
+    action: TimingAction,
+    state_before: FocusKind,
+    decided_at: DateTime<Utc>,
+    response: Option<UserResponse>,
+}
+
+/// Contextual bandit over intervention timing
+#[derive(Debug)]
+pub struct TimingPolicy {
+    config: TimingPolicyConfig,
+    arms: HashMap<(PolicyContext, TimingAction), ArmStats>,
+    pending: HashMap<Uuid, PendingOutcome>,
+    explorations: VecDeque<DateTime<Utc>>,
+    rng: StdRng,
+}
+
+impl TimingPolicy {
+    pub fn new(config: TimingPolicyConfig) -> Self {
+        Self {
+            config,
+            arms: HashMap::new(),
+            pending: HashMap::new(),
+            explorations: VecDeque::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Use a fixed random seed, for reproducible exploration
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Pick how soon to intervene in `context`
+    ///
+    /// Pass `allow_exploration = false` in states where a bad guess is costly;
+    /// the policy then only acts on what it has learned.
+    pub fn choose(&mut self, context: PolicyContext, allow_exploration: bool, now: DateTime<Utc>) -> PolicyChoice {
+        let (best_action, expected_reward) = TimingAction::ALL
+            .iter()
+            .map(|action| (*action, self.expected_reward(context, *action)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((TimingAction::Later, 0.5));
+
+        let day_ago = now - Duration::hours(24);
+        while self.explorations.front().is_some_and(|at| *at <= day_ago) {
+            self.explorations.pop_front();
+        }
+        let explore = allow_exploration
+            && (self.explorations.len() as u32) < self.config.max_explorations_per_day
+            && self.rng.gen::<f32>() < self.config.exploration_rate;
+
+        let action = if explore {
+            self.explorations.push_back(now);
+            Some(TimingAction::ALL[self.rng.gen_range(0..TimingAction::ALL.len())])
+        } else if expected_reward >= self.config.min_expected_reward {
+            Some(best_action)
+        } else {
+            None
+        };
+
+        let decision_id = Uuid::new_v4();
+        if let Some(action) = action {
+            self.pending.insert(decision_id, PendingOutcome {
+                context,
+                action,
+                state_before: context.focus,
+                decided_at: now,
+                response: None,
+            });
+        }
+
+        PolicyChoice {
+            decision_id,
+            action,
+            explored: explore,
+            expected_reward,
+        }
+    }
+
+    /// Record how the user responded to an intervention the policy timed
+    ///
+    /// Returns false for unknown or already resolved decisions.
+    pub fn record_response(&mut self, decision_id: Uuid, response: UserResponse) -> bool {
+        match self.pending.get_mut(&decision_id) {
+            Some(pending) => {
+                pending.response = Some(response);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Learn from pending decisions whose outcome is now known, given the current state
+    pub fn observe_state(&mut self, state: &FocusState, now: DateTime<Utc>) {
+        let window = Duration::minutes(self.config.outcome_window_minutes);
+        let timeout = Duration::minutes(self.config.outcome_timeout_minutes);
+        let state_after = FocusKind::from(state);
+
+        let resolved: Vec<Uuid> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| {
+                let age = now - pending.decided_at;
+                (pending.response.is_some() && age >= window) || age >= timeout
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in resolved {
+            if let Some(pending) = self.pending.remove(&id) {
+                let response = pending.response.unwrap_or(UserResponse::Ignored);
+                let reward = outcome_reward(&response, pending.state_before, state_after);
+                let arm = self.arms.entry((pending.context, pending.action)).or_default();
+                arm.reward += reward;
+                arm.trials += 1;
+            }
+        }
+    }
+
+    /// Estimated reward (0-1) of `action` in `context`
+    pub fn expected_reward(&self, context: PolicyContext, action: TimingAction) -> f32 {
+        self.arms.get(&(context, action)).map(ArmStats::mean).unwrap_or(0.5)
+    }
+
+    /// Exploratory decisions made in the last 24 hours
+    pub fn explorations_today(&self) -> u32 {
+        self.explorations.len() as u32
+    }
+}
+
+impl Default for TimingPolicy {
+    fn default() -> Self {
+        Self::new(TimingPolicyConfig::default())
+    }
+}
+
+/// Blend the user's response with how their focus changed, in 0-1
+fn outcome_reward(response: &UserResponse, before: FocusKind, after: FocusKind) -> f32 {
+    let response_score = match response {
+        UserResponse::Helpful | UserResponse::ActionTaken => 1.0,
+        UserResponse::NotHelpful => 0.3,
+        UserResponse::Ignored => 0.2,
+        UserResponse::Dismissed => 0.0,
+    };
+    let focus_change = focus_score(after) - focus_score(before);
+    (0.7 * response_score + 0.3 * (0.5 + focus_change / 2.0)).clamp(0.0, 1.0)
+}
+
+fn focus_score(focus: FocusKind) -> f32 {
+    match focus {
+        FocusKind::Flow | FocusKind::Hyperfocus => 1.0,
+        FocusKind::Neutral => 0.7,
+        FocusKind::Transitioning | FocusKind::Break => 0.5,
+        FocusKind::Distracted => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distracted() -> FocusState {
+        FocusState::Distracted { severity: 0.7, duration: Duration::minutes(10) }
+    }
+
+    fn flow() -> FocusState {
+        FocusState::Flow { depth: 0.6, stability: 0.8 }
+    }
+
+    fn context(state: &FocusState) -> PolicyContext {
+        PolicyContext {
+            focus: FocusKind::from(state),
+            work: WorkKind::Coding,
+            time_of_day: TimeOfDay::Afternoon,
+        }
+    }
+
+    #[test]
+    fn test_policy_learns_from_outcomes() {
+        let mut policy = TimingPolicy::new(TimingPolicyConfig { exploration_rate: 0.0, ..Default::default() });
+        let mut now = Utc::now();
+
+        // Intervening while distracted keeps helping and the user gets back into flow
+        for _ in 0..10 {
+            let choice = policy.choose(context(&distracted()), true, now);
+            let action = choice.action.unwrap();
+            assert!(!choice.explored);
+            let response = if action == TimingAction::Now { UserResponse::Helpful } else { UserResponse::Dismissed };
+            assert!(policy.record_response(choice.decision_id, response));
+            now += Duration::minutes(6);
+            policy.observe_state(&flow(), now);
+        }
+        assert!(policy.expected_reward(context(&distracted()), TimingAction::Now) > 0.8);
+        assert_eq!(policy.choose(context(&distracted()), true, now).action, Some(TimingAction::Now));
+
+        // Interventions in flow that go unanswered and break focus teach the policy to hold off
+        for _ in 0..10 {
+            let choice = policy.choose(context(&flow()), true, now);
+            if choice.action.is_none() {
+                break;
+            }
+            now += Duration::minutes(31);
+            policy.observe_state(&distracted(), now);
+        }
+        assert_eq!(policy.choose(context(&flow()), true, now).action, None);
+    }
+
+    #[test]
+    fn test_exploration_budget_and_safety() {
+        let config = TimingPolicyConfig {
+            exploration_rate: 1.0,
+            max_explorations_per_day: 3,
+            min_expected_reward: 0.9,
+            ..Default::default()
+        };
+        let mut policy = TimingPolicy::new(config).with_seed(7);
+        let now = Utc::now();
+
+        // Protected states never explore; with nothing learned the policy holds off
+        let choice = policy.choose(context(&flow()), false, now);
+        assert!(!choice.explored && choice.action.is_none());
+
+        let explored = (0..10).filter(|_| policy.choose(context(&distracted()), true, now).explored).count();
+        assert_eq!(explored, 3);
+        assert_eq!(policy.explorations_today(), 3);
+
+        // The budget frees up a day later
+        assert!(policy.choose(context(&distracted()), true, now + Duration::hours(25)).explored);
+    }
+}
@@ -69,6 +69,7 @@ pub enum GenerationMethod {
     APIFallback { service: String },
     Template { template_id: String },
     Cached { cache_key: String },
+    OfflineFallback { entry_id: String, reason: String },
 }
 
 /// Privacy level of the response generation
@@ -163,7 +164,7 @@ pub enum UrgencyLevel {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TimeOfDay {
     Morning,
     Afternoon,
@@ -171,6 +172,18 @@ pub enum TimeOfDay {
     Night,
 }
 
+impl TimeOfDay {
+    /// Part of the day for a local hour (0-23)
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=11 => Self::Morning,
+            12..=16 => Self::Afternoon,
+            17..=21 => Self::Evening,
+            _ => Self::Night,
+        }
+    }
+}
+
 /// User preferences for AI behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
@@ -253,6 +266,8 @@ pub struct LLMContext {
     pub system_prompt: String,
     pub behavioral_context: String,
     pub work_context: String,
+    /// Similar past situations and user notes as few-shot examples, if any
+    pub past_situations: String,
     pub intervention_type: String,
     pub user_preferences: UserPreferences,
     pub max_tokens: usize,
@@ -307,7 +322,7 @@ pub struct LocalModelConfig {
     pub repeat_penalty: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ModelVariant {
     Mistral7B,
     Phi3Mini,
@@ -357,6 +372,8 @@ pub struct UsageStatistics {
     pub api_generations: u64,
     pub template_responses: u64,
     pub cached_responses: u64,
+    #[serde(default)]
+    pub fallback_responses: u64,
     pub average_response_time_ms: f64,
     pub total_tokens_used: u64,
     pub total_cost_usd: f32,
@@ -0,0 +1,217 @@
+//! Wellness Reminder Engine
+//!
+//! Decides when a hydration, posture, eye strain (20-20-20), movement or
+//! breathing reminder is due, based on:
+//! - Continuous screen time since the last break (idle gaps count as breaks)
+//! - Per-type reminder frequencies from user preferences
+//! - Time of day (reminders only during active hours)
+//! - Focus state (nothing is offered while the user is in flow)
+//!
+//! Due reminders are timed by the InterventionTimingEngine like any other
+//! intervention; see `ContextualInterventionSystem::check_wellness`.
+
+use crate::intervention_timing::{FocusState, WellnessType};
+use chrono::{DateTime, Duration, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How often one kind of reminder may be offered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WellnessFrequency {
+    pub reminder_type: WellnessType,
+    pub every_minutes: u32,
+    pub enabled: bool,
+}
+
+impl WellnessFrequency {
+    fn new(reminder_type: WellnessType, every_minutes: u32, enabled: bool) -> Self {
+        Self { reminder_type, every_minutes, enabled }
+    }
+
+    /// Whether a break resets this reminder's timer
+    fn reset_by_break(&self) -> bool {
+        // Stepping away rests eyes and back, but doesn't mean a drink was had
+        !matches!(self.reminder_type, WellnessType::Hydration)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WellnessConfig {
+    pub enabled: bool,
+    pub frequencies: Vec<WellnessFrequency>,
+    /// Inactivity at least this long counts as a break
+    pub break_after_idle_minutes: u32,
+    /// Local hours reminders may be offered in, (start_hour, end_hour) in 24h format
+    pub active_hours: (u32, u32),
+    /// Hold reminders while the user is in flow or hyperfocus
+    pub suppress_during_flow: bool,
+}
+
+impl Default for WellnessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            frequencies: vec![
+                WellnessFrequency::new(WellnessType::EyeBreak, 20, true), // 20-20-20 rule
+                WellnessFrequency::new(WellnessType::PostureCheck, 45, true),
+                WellnessFrequency::new(WellnessType::Hydration, 60, true),
+                WellnessFrequency::new(WellnessType::MovementBreak, 90, true),
+                WellnessFrequency::new(WellnessType::DeepBreathing, 120, false),
+            ],
+            break_after_idle_minutes: 5,
+            active_hours: (8, 22),
+            suppress_during_flow: true,
+        }
+    }
+}
+
+/// Tracks screen time and breaks, and picks the most overdue reminder
+pub struct WellnessEngine {
+    config: WellnessConfig,
+    /// Start of the current stretch of screen time
+    session_start: Option<DateTime<Utc>>,
+    first_activity: Option<DateTime<Utc>>,
+    last_activity: Option<DateTime<Utc>>,
+    last_break: Option<DateTime<Utc>>,
+    last_reminded: HashMap<WellnessType, DateTime<Utc>>,
+}
+
+impl WellnessEngine {
+    pub fn new(config: WellnessConfig) -> Self {
+        Self {
+            config,
+            session_start: None,
+            first_activity: None,
+            last_activity: None,
+            last_break: None,
+            last_reminded: HashMap::new(),
+        }
+    }
+
+    /// Record user input at `at`
+    pub fn record_activity(&mut self, at: DateTime<Utc>) {
+        match self.last_activity {
+            Some(last) if at - last >= self.break_threshold() => {
+                self.last_break = Some(at);
+                self.session_start = Some(at);
+            }
+            None => self.session_start = Some(at),
+            _ => {}
+        }
+        self.first_activity.get_or_insert(at);
+        self.last_activity = Some(self.last_activity.map_or(at, |last| last.max(at)));
+    }
+
+    /// Continuous screen time since the last break
+    pub fn screen_time(&self, now: DateTime<Utc>) -> Duration {
+        self.session_start.map_or_else(Duration::zero, |start| now - start)
+    }
+
+    pub fn last_break(&self) -> Option<DateTime<Utc>> {
+        self.last_break
+    }
+
+    /// Most overdue reminder, regardless of focus state
+    pub fn due(&self, now: DateTime<Utc>) -> Option<WellnessType> {
+        if !self.config.enabled || !self.in_active_hours(now) {
+            return None;
+        }
+        // Nobody to remind while the user is away
+        let last_activity = self.last_activity?;
+        if now - last_activity >= self.break_threshold() {
+            return None;
+        }
+
+        self.config.frequencies.iter()
+            .filter(|frequency| frequency.enabled && frequency.every_minutes > 0)
+            .filter_map(|frequency| {
+                let started = if frequency.reset_by_break() { self.session_start } else { self.first_activity }?;
+                let since = self.last_reminded.get(&frequency.reminder_type)
+                    .map_or(started, |&reminded| reminded.max(started));
+                let overdue = (now - since).num_seconds() as f64 / (frequency.every_minutes as f64 * 60.0);
+                (overdue >= 1.0).then(|| (frequency.reminder_type.clone(), overdue))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(reminder_type, _)| reminder_type)
+    }
+
+    /// Whether the focus state holds reminders back
+    pub fn suppressed_by(&self, focus_state: &FocusState) -> bool {
+        self.config.suppress_during_flow
+            && matches!(focus_state, FocusState::Flow { .. } | FocusState::Hyperfocus { .. })
+    }
+
+    /// Record that a reminder was shown
+    pub fn mark_reminded(&mut self, reminder_type: WellnessType, at: DateTime<Utc>) {
+        self.last_reminded.insert(reminder_type, at);
+    }
+
+    fn break_threshold(&self) -> Duration {
+        Duration::minutes(self.config.break_after_idle_minutes as i64)
+    }
+
+    fn in_active_hours(&self, now: DateTime<Utc>) -> bool {
+        let hour = now.with_timezone(&Local).hour();
+        let (start, end) = self.config.active_hours;
+        if start <= end {
+            hour >= start && hour < end
+        } else {
+            hour >= start || hour < end
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> WellnessEngine {
+        WellnessEngine::new(WellnessConfig { active_hours: (0, 24), ..Default::default() })
+    }
+
+    /// Activity every minute from `from` to `to` minutes after `start`
+    fn work(engine: &mut WellnessEngine, start: DateTime<Utc>, from: i64, to: i64) {
+        for minute in from..=to {
+            engine.record_activity(start + Duration::minutes(minute));
+        }
+    }
+
+    #[test]
+    fn test_reminders_follow_screen_time_and_breaks() {
+        let mut engine = engine();
+        let start = Utc::now();
+        let at = |minute: i64| start + Duration::minutes(minute);
+
+        work(&mut engine, start, 0, 19);
+        assert_eq!(engine.due(at(19)), None);
+        work(&mut engine, start, 20, 20);
+        assert_eq!(engine.due(at(20)), Some(WellnessType::EyeBreak));
+        engine.mark_reminded(WellnessType::EyeBreak, at(20));
+        assert_eq!(engine.due(at(20)), None);
+
+        // A ten minute break resets eye and posture timers but not hydration
+        work(&mut engine, start, 21, 40);
+        work(&mut engine, start, 50, 61);
+        assert_eq!(engine.last_break(), Some(at(50)));
+        assert_eq!(engine.screen_time(at(61)), Duration::minutes(11));
+        assert_eq!(engine.due(at(61)), Some(WellnessType::Hydration));
+
+        // Away from the keyboard: no reminders
+        assert_eq!(engine.due(at(70)), None);
+    }
+
+    #[test]
+    fn test_flow_suppression_and_disabled_types() {
+        let mut engine = WellnessEngine::new(WellnessConfig {
+            active_hours: (0, 24),
+            frequencies: vec![WellnessFrequency::new(WellnessType::PostureCheck, 30, false)],
+            ..Default::default()
+        });
+        let start = Utc::now();
+        work(&mut engine, start, 0, 120);
+        assert_eq!(engine.due(start + Duration::minutes(120)), None);
+
+        assert!(engine.suppressed_by(&FocusState::Flow { depth: 0.5, stability: 0.8 }));
+        assert!(!engine.suppressed_by(&FocusState::Focused { concentration: 0.5 }));
+    }
+}
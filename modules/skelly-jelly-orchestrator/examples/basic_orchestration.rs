@@ -31,14 +31,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         startup_timeout: Duration::from_secs(30),
         module_start_delay: Duration::from_millis(500),
         parallel_startup: false,
+        headless: false,
         health_check_interval: Duration::from_secs(10),
         health_check_timeout: Duration::from_secs(3),
         unhealthy_threshold: 2,
         auto_recovery: true,
         max_recovery_attempts: 3,
         recovery_backoff: Duration::from_secs(5),
+        restart_buffer_size: 1_000,
+        crash_loop_max_failures: 3,
+        crash_loop_window: Duration::from_secs(300),
         resource_check_interval: Duration::from_secs(5),
         throttle_threshold: 0.8,
+        user_profile: "default".to_string(),
+        privacy_policy: Default::default(),
     };
 
     // Create orchestrator
@@ -0,0 +1,551 @@
+//! Local HTTP admin and status API
+//!
+//! Opt-in, localhost-only server for inspecting and managing a running
+//! system without IPC plumbing. GET endpoints are read-only and open to local
+//! callers; POST endpoints change the system and require a bearer token.
+//!
+//! | Method | Path                         | Description                    |
+//! |--------|------------------------------|--------------------------------|
+//! | GET    | `/health`                    | System status summary          |
+//! | GET    | `/modules`                   | Lifecycle state of each module |
+//! | GET    | `/bus/metrics`               | Event bus metrics              |
+//! | GET    | `/graph?format=mermaid`      | Live map, DOT unless Mermaid   |
+//! | GET    | `/incidents?limit=N`         | Most recent system issues      |
+//! | POST   | `/modules/{module}/restart`  | Restart with traffic buffering |
+//! | POST   | `/modules/{module}/pause`    | Hold the module's bus traffic  |
+//! | POST   | `/modules/{module}/resume`   | Replay held traffic            |
+//! | POST   | `/profile`                   | Switch profile (`{"profile"}`) |
+//! | POST   | `/shutdown`                  | Stop gracefully, e.g. for a takeover |
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::lifecycle::ModuleState;
+use crate::orchestrator::{IssueSeverity, SystemStatus};
+use crate::profiles::{ProfileChange, SystemProfile};
+use crate::system_map::{GraphFormat, SystemMap};
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{BusMetrics, ModuleId, ReplaySummary};
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Configuration for the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminApiConfig {
+    /// Off unless explicitly enabled
+    pub enabled: bool,
+    /// Must be a loopback address; port 0 picks a free port
+    pub bind_address: SocketAddr,
+    /// Bearer token for POST endpoints; generated at startup if unset
+    pub auth_token: Option<String>,
+    /// Upper bound for `/incidents?limit=`
+    pub max_incidents: usize,
+}
+
+impl Default for AdminApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 7717)),
+            auth_token: None,
+            max_incidents: 100,
+        }
+    }
+}
+
+/// Health summary returned by `GET /health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSummary {
+    pub status: SystemStatus,
+    pub uptime_secs: u64,
+    pub unhealthy_modules: Vec<ModuleId>,
+    pub active_issues: usize,
+    pub profile: SystemProfile,
+}
+
+/// One module's lifecycle state as returned by `GET /modules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleStateView {
+    pub module: ModuleId,
+    pub state: String,
+    pub detail: Option<String>,
+    /// Seconds spent in the current state, where known
+    pub for_secs: Option<u64>,
+}
+
+impl ModuleStateView {
+    pub fn new(module: ModuleId, state: &ModuleState) -> Self {
+        let (name, detail, since) = match state {
+            ModuleState::NotStarted => ("not_started", None, None),
+            ModuleState::Starting { since } => ("starting", None, Some(*since)),
+            ModuleState::Running { since } => ("running", None, Some(*since)),
+            ModuleState::Stopping { since } => ("stopping", None, Some(*since)),
+            ModuleState::Stopped { reason } => ("stopped", Some(format!("{:?}", reason)), None),
+            ModuleState::Failed { error, attempts } => {
+                ("failed", Some(format!("{} (attempt {})", error, attempts)), None)
+            }
+        };
+
+        Self {
+            module,
+            state: name.to_string(),
+            detail,
+            for_secs: since.map(|since: Instant| since.elapsed().as_secs()),
+        }
+    }
+}
+
+/// A system issue as returned by `GET /incidents`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentView {
+    pub id: Uuid,
+    pub severity: IssueSeverity,
+    pub description: String,
+    pub affected_modules: Vec<ModuleId>,
+    pub age_secs: u64,
+    pub resolved: bool,
+}
+
+/// What the admin API needs from the running system
+#[async_trait]
+pub trait AdminBackend: Send + Sync {
+    async fn health_summary(&self) -> HealthSummary;
+    async fn module_states(&self) -> Vec<ModuleStateView>;
+    async fn bus_metrics(&self) -> OrchestratorResult<BusMetrics>;
+    async fn system_map(&self) -> SystemMap;
+    /// Most recent issues first
+    async fn recent_incidents(&self, limit: usize) -> Vec<IncidentView>;
+    async fn restart_module(&self, module: ModuleId) -> OrchestratorResult<()>;
+    async fn pause_module(&self, module: ModuleId) -> OrchestratorResult<()>;
+    async fn resume_module(&self, module: ModuleId) -> OrchestratorResult<ReplaySummary>;
+    async fn switch_profile(&self, profile: SystemProfile) -> OrchestratorResult<Option<ProfileChange>>;
+    /// Ask the process to shut down gracefully; returns before it has stopped
+    async fn request_shutdown(&self) -> OrchestratorResult<()>;
+}
+
+#[derive(Clone)]
+struct ApiState {
+    backend: Arc<dyn AdminBackend>,
+    token: Arc<str>,
+    max_incidents: usize,
+}
+
+/// Localhost HTTP server exposing the admin API
+pub struct AdminApiServer {
+    config: AdminApiConfig,
+    backend: Arc<dyn AdminBackend>,
+    token: Arc<str>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl AdminApiServer {
+    pub fn new(config: AdminApiConfig, backend: Arc<dyn AdminBackend>) -> Self {
+        let token: Arc<str> = match &config.auth_token {
+            Some(token) => token.as_str().into(),
+            None => generate_token().into(),
+        };
+
+        Self {
+            config,
+            backend,
+            token,
+            shutdown: Mutex::new(None),
+        }
+    }
+
+    /// Token POST requests must present as `Authorization: Bearer <token>`
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Bind and serve in the background, returning the bound address
+    pub async fn start(&self) -> OrchestratorResult<SocketAddr> {
+        let invalid = |reason: String| OrchestratorError::ConfigurationError {
+            module: ModuleId::Orchestrator,
+            reason,
+        };
+
+        if !self.config.enabled {
+            return Err(invalid("Admin API is disabled".to_string()));
+        }
+        if !self.config.bind_address.ip().is_loopback() {
+            return Err(invalid(format!(
+                "Admin API must bind to a loopback address, got {}",
+                self.config.bind_address
+            )));
+        }
+
+        let mut shutdown = self.shutdown.lock().await;
+        if shutdown.is_some() {
+            return Err(invalid("Admin API already running".to_string()));
+        }
+
+        let listener = tokio::net::TcpListener::bind(self.config.bind_address).await?;
+        let address = listener.local_addr()?;
+
+        let router = build_router(ApiState {
+            backend: Arc::clone(&self.backend),
+            token: Arc::clone(&self.token),
+            max_incidents: self.config.max_incidents,
+        });
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        *shutdown = Some(stop_tx);
+
+        tokio::spawn(async move {
+            let server = axum::serve(listener, router)
+                .with_graceful_shutdown(async { let _ = stop_rx.await; });
+            if let Err(e) = server.await {
+                error!("❌ Admin API server error: {}", e);
+            }
+        });
+
+        info!("🛠️  Admin API listening on http://{}", address);
+        Ok(address)
+    }
+
+    /// Stop serving
+    pub async fn stop(&self) {
+        if let Some(stop) = self.shutdown.lock().await.take() {
+            let _ = stop.send(());
+            info!("Admin API stopped");
+        }
+    }
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 24] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn build_router(state: ApiState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/modules", get(modules))
+        .route("/bus/metrics", get(bus_metrics))
+        .route("/graph", get(graph))
+        .route("/incidents", get(incidents))
+        .route("/modules/:module/restart", post(restart_module))
+        .route("/modules/:module/pause", post(pause_module))
+        .route("/modules/:module/resume", post(resume_module))
+        .route("/profile", post(switch_profile))
+        .route("/shutdown", post(shutdown))
+        .with_state(state)
+}
+
+/// Error body returned by every failing endpoint
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+}
+
+impl From<OrchestratorError> for ApiError {
+    fn from(error: OrchestratorError) -> Self {
+        let status = match &error {
+            OrchestratorError::ConfigurationError { .. }
+            | OrchestratorError::MissingDependency { .. } => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(serde_json::json!({ "error": self.message }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn authorize(state: &ApiState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    // Compare without short-circuiting so timing doesn't leak the token
+    let matches = presented.is_some_and(|presented| {
+        presented.len() == state.token.len()
+            && presented.bytes().zip(state.token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    });
+
+    if matches {
+        Ok(())
+    } else {
+        warn!("Rejected unauthenticated admin API request");
+        Err(ApiError::new(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token"))
+    }
+}
+
+fn parse_module(name: &str) -> Result<ModuleId, ApiError> {
+    name.parse().map_err(|e: String| ApiError::new(StatusCode::NOT_FOUND, e))
+}
+
+async fn health(State(state): State<ApiState>) -> Json<HealthSummary> {
+    Json(state.backend.health_summary().await)
+}
+
+async fn modules(State(state): State<ApiState>) -> Json<Vec<ModuleStateView>> {
+    Json(state.backend.module_states().await)
+}
+
+async fn bus_metrics(State(state): State<ApiState>) -> ApiResult<BusMetrics> {
+    Ok(Json(state.backend.bus_metrics().await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQuery {
+    format: Option<String>,
+}
+
+async fn graph(State(state): State<ApiState>, Query(query): Query<GraphQuery>) -> Result<String, ApiError> {
+    let format = match query.format {
+        Some(format) => format.parse().map_err(|e: String| ApiError::new(StatusCode::BAD_REQUEST, e))?,
+        None => GraphFormat::default(),
+    };
+    Ok(state.backend.system_map().await.render(format))
+}
+
+#[derive(Debug, Deserialize)]
+struct IncidentQuery {
+    limit: Option<usize>,
+}
+
+async fn incidents(State(state): State<ApiState>, Query(query): Query<IncidentQuery>) -> Json<Vec<IncidentView>> {
+    let limit = query.limit.unwrap_or(20).min(state.max_incidents);
+    Json(state.backend.recent_incidents(limit).await)
+}
+
+async fn restart_module(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(module): Path<String>,
+) -> ApiResult<serde_json::Value> {
+    authorize(&state, &headers)?;
+    let module = parse_module(&module)?;
+    state.backend.restart_module(module).await?;
+    Ok(Json(serde_json::json!({ "module": module, "restarted": true })))
+}
+
+async fn pause_module(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(module): Path<String>,
+) -> ApiResult<serde_json::Value> {
+    authorize(&state, &headers)?;
+    let module = parse_module(&module)?;
+    state.backend.pause_module(module).await?;
+    Ok(Json(serde_json::json!({ "module": module, "paused": true })))
+}
+
+async fn resume_module(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(module): Path<String>,
+) -> ApiResult<serde_json::Value> {
+    authorize(&state, &headers)?;
+    let module = parse_module(&module)?;
+    let replay = state.backend.resume_module(module).await?;
+    Ok(Json(serde_json::json!({
+        "module": module,
+        "replayed": replay.replayed,
+        "dropped": replay.dropped,
+        "failed": replay.failed,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileRequest {
+    profile: SystemProfile,
+}
+
+async fn switch_profile(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(request): Json<ProfileRequest>,
+) -> ApiResult<serde_json::Value> {
+    authorize(&state, &headers)?;
+    let change = state.backend.switch_profile(request.profile).await?;
+    Ok(Json(serde_json::json!({ "profile": request.profile, "change": change })))
+}
+
+async fn shutdown(State(state): State<ApiState>, headers: HeaderMap) -> ApiResult<serde_json::Value> {
+    authorize(&state, &headers)?;
+    info!("Shutdown requested over the admin API");
+    state.backend.request_shutdown().await?;
+    Ok(Json(serde_json::json!({ "shutting_down": true })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Default)]
+    struct MockBackend {
+        restarted: Mutex<Vec<ModuleId>>,
+        shutdown_requested: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl AdminBackend for MockBackend {
+        async fn health_summary(&self) -> HealthSummary {
+            HealthSummary {
+                status: SystemStatus::Healthy,
+                uptime_secs: 42,
+                unhealthy_modules: Vec::new(),
+                active_issues: 0,
+                profile: SystemProfile::Balanced,
+            }
+        }
+
+        async fn module_states(&self) -> Vec<ModuleStateView> {
+            vec![ModuleStateView::new(ModuleId::Storage, &ModuleState::NotStarted)]
+        }
+
+        async fn bus_metrics(&self) -> OrchestratorResult<BusMetrics> {
+            Err(OrchestratorError::SystemResource("no bus in tests".to_string()))
+        }
+
+        async fn system_map(&self) -> SystemMap {
+            SystemMap::new(Vec::new(), vec![(ModuleId::EventBus, ModuleId::Storage)])
+        }
+
+        async fn recent_incidents(&self, _limit: usize) -> Vec<IncidentView> {
+            Vec::new()
+        }
+
+        async fn restart_module(&self, module: ModuleId) -> OrchestratorResult<()> {
+            self.restarted.lock().await.push(module);
+            Ok(())
+        }
+
+        async fn pause_module(&self, _module: ModuleId) -> OrchestratorResult<()> {
+            Ok(())
+        }
+
+        async fn resume_module(&self, _module: ModuleId) -> OrchestratorResult<ReplaySummary> {
+            Ok(ReplaySummary::default())
+        }
+
+        async fn switch_profile(&self, _profile: SystemProfile) -> OrchestratorResult<Option<ProfileChange>> {
+            Ok(None)
+        }
+
+        async fn request_shutdown(&self) -> OrchestratorResult<()> {
+            *self.shutdown_requested.lock().await = true;
+            Ok(())
+        }
+    }
+
+    async fn start(backend: Arc<MockBackend>) -> (AdminApiServer, SocketAddr) {
+        let config = AdminApiConfig {
+            enabled: true,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 0)),
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let server = AdminApiServer::new(config, backend);
+        let address = server.start().await.unwrap();
+        (server, address)
+    }
+
+    /// Minimal HTTP/1.1 client returning the status code and body
+    async fn request(address: SocketAddr, method: &str, path: &str, headers: &[(&str, &str)]) -> (u16, String) {
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        let mut raw = format!("{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: 0\r\n", method, path);
+        for (name, value) in headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        raw.push_str("\r\n");
+        stream.write_all(raw.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or_default().to_string();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_read_only_endpoints_need_no_token() {
+        let (server, address) = start(Arc::new(MockBackend::default())).await;
+
+        let (status, body) = request(address, "GET", "/health", &[]).await;
+        assert_eq!(status, 200);
+        let summary: HashMap<String, serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(summary["uptime_secs"], 42);
+
+        let (status, body) = request(address, "GET", "/modules", &[]).await;
+        assert_eq!(status, 200);
+        assert!(body.contains("not_started"));
+
+        let (status, _) = request(address, "GET", "/bus/metrics", &[]).await;
+        assert_eq!(status, 500);
+
+        let (status, body) = request(address, "GET", "/graph?format=mermaid", &[]).await;
+        assert_eq!(status, 200);
+        assert!(body.contains("event_bus --> storage"));
+        let (status, _) = request(address, "GET", "/graph?format=svg", &[]).await;
+        assert_eq!(status, 400);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_mutating_endpoints_require_token() {
+        let backend = Arc::new(MockBackend::default());
+        let (server, address) = start(Arc::clone(&backend)).await;
+
+        let (status, _) = request(address, "POST", "/modules/storage/restart", &[]).await;
+        assert_eq!(status, 401);
+        let (status, _) = request(address, "POST", "/modules/storage/restart", &[("Authorization", "Bearer wrong")]).await;
+        assert_eq!(status, 401);
+
+        let (status, _) = request(address, "POST", "/modules/storage/restart", &[("Authorization", "Bearer secret")]).await;
+        assert_eq!(status, 200);
+        assert_eq!(*backend.restarted.lock().await, vec![ModuleId::Storage]);
+
+        let (status, _) = request(address, "POST", "/modules/toaster/restart", &[("Authorization", "Bearer secret")]).await;
+        assert_eq!(status, 404);
+
+        let (status, _) = request(address, "POST", "/shutdown", &[]).await;
+        assert_eq!(status, 401);
+        assert!(!*backend.shutdown_requested.lock().await);
+        let (status, _) = request(address, "POST", "/shutdown", &[("Authorization", "Bearer secret")]).await;
+        assert_eq!(status, 200);
+        assert!(*backend.shutdown_requested.lock().await);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_refuses_non_loopback_bind() {
+        let config = AdminApiConfig {
+            enabled: true,
+            bind_address: SocketAddr::from(([0, 0, 0, 0], 0)),
+            ..Default::default()
+        };
+        let server = AdminApiServer::new(config, Arc::new(MockBackend::default()));
+        assert!(matches!(server.start().await, Err(OrchestratorError::ConfigurationError { .. })));
+        assert_eq!(server.token().len(), 48);
+    }
+}
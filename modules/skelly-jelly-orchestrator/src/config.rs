@@ -1,10 +1,14 @@
 //! Configuration management for the orchestrator
 
+use crate::config_schema::{ConfigSchema, SchemaReport, SchemaViolation};
 use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::maintenance::MaintenanceConfig;
+use crate::sleep_wake::SleepWakeConfig;
 use dashmap::DashMap;
 use skelly_jelly_event_bus::{EventBusTrait, ModuleId, BusMessage, MessagePayload};
 use notify::RecommendedWatcher;
 use serde::{Deserialize, Serialize};
+use skelly_jelly_privacy_policy::PrivacyPolicyConfig;
 use std::{
     path::Path,
     sync::Arc,
@@ -19,7 +23,10 @@ pub struct OrchestratorConfig {
     /// Startup configuration
     pub startup_timeout: Duration,
     pub module_start_delay: Duration,
+    /// Start modules in the same dependency level concurrently
     pub parallel_startup: bool,
+    /// Run only capture, storage, and analysis: no intervention delivery or UI bridges
+    pub headless: bool,
     
     /// Health monitoring
     pub health_check_interval: Duration,
@@ -30,10 +37,30 @@ pub struct OrchestratorConfig {
     pub auto_recovery: bool,
     pub max_recovery_attempts: u32,
     pub recovery_backoff: Duration,
+    /// Messages held for a restarting module before the oldest are dropped
+    pub restart_buffer_size: usize,
+    /// Failures within `crash_loop_window` that put a module into quarantine
+    pub crash_loop_max_failures: u32,
+    pub crash_loop_window: Duration,
     
     /// Resource management
     pub resource_check_interval: Duration,
     pub throttle_threshold: f32,
+
+    /// User profile whose data the modules open at startup
+    pub user_profile: String,
+
+    /// Privacy policy broadcast to every module
+    #[serde(default)]
+    pub privacy_policy: PrivacyPolicyConfig,
+
+    /// Windows for compaction, retraining, backup, and DLQ cleanup
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// Checkpoint and pause before OS sleep, re-baseline after wake
+    #[serde(default)]
+    pub sleep_wake: SleepWakeConfig,
 }
 
 impl Default for OrchestratorConfig {
@@ -41,16 +68,74 @@ impl Default for OrchestratorConfig {
         Self {
             startup_timeout: Duration::from_secs(60),
             module_start_delay: Duration::from_secs(1),
-            parallel_startup: false,
+            parallel_startup: true,
+            headless: false,
             health_check_interval: Duration::from_secs(30),
             health_check_timeout: Duration::from_secs(5),
             unhealthy_threshold: 3,
             auto_recovery: true,
             max_recovery_attempts: 3,
             recovery_backoff: Duration::from_secs(10),
+            restart_buffer_size: 1_000,
+            crash_loop_max_failures: 3,
+            crash_loop_window: Duration::from_secs(300),
             resource_check_interval: Duration::from_secs(10),
             throttle_threshold: 0.9,
+            user_profile: crate::user_profiles::DEFAULT_USER_PROFILE.to_string(),
+            privacy_policy: PrivacyPolicyConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            sleep_wake: SleepWakeConfig::default(),
+        }
+    }
+}
+
+impl OrchestratorConfig {
+    /// Cross-field checks that serde alone can't express
+    pub fn validate(&self) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+
+        if self.health_check_timeout >= self.health_check_interval {
+            violations.push(SchemaViolation::Constraint {
+                path: "health_check_timeout".to_string(),
+                reason: "must be less than health_check_interval".to_string(),
+            });
+        }
+        if self.module_start_delay >= self.startup_timeout {
+            violations.push(SchemaViolation::Constraint {
+                path: "module_start_delay".to_string(),
+                reason: "must be less than startup_timeout".to_string(),
+            });
+        }
+        if self.crash_loop_max_failures == 0 {
+            violations.push(SchemaViolation::OutOfRange {
+                path: "crash_loop_max_failures".to_string(),
+                value: 0.0,
+                min: Some(1.0),
+                max: None,
+            });
+        }
+        if !(self.throttle_threshold > 0.0 && self.throttle_threshold <= 1.0) {
+            violations.push(SchemaViolation::OutOfRange {
+                path: "throttle_threshold".to_string(),
+                value: self.throttle_threshold as f64,
+                min: Some(0.0),
+                max: Some(1.0),
+            });
+        }
+        if crate::user_profiles::validate_user_profile(&self.user_profile).is_err() {
+            violations.push(SchemaViolation::Constraint {
+                path: "user_profile".to_string(),
+                reason: "must be lowercase letters, digits, '-' and '_'".to_string(),
+            });
+        }
+        if crate::privacy_policy::validate_privacy_policy(&self.privacy_policy).is_err() {
+            violations.push(SchemaViolation::Constraint {
+                path: "privacy_policy.entities".to_string(),
+                reason: "min_confidence must be between 0.0 and 1.0".to_string(),
+            });
         }
+
+        violations
     }
 }
 
@@ -80,6 +165,10 @@ impl ConfigStore {
         self.module_configs.insert(module_id, config);
     }
 
+    pub fn remove_module_config(&self, module_id: ModuleId) {
+        self.module_configs.remove(&module_id);
+    }
+
     pub fn get_module_config(&self, module_id: ModuleId) -> Option<serde_json::Value> {
         self.module_configs.get(&module_id).map(|entry| entry.clone())
     }
@@ -125,13 +214,16 @@ impl ConfigurationManager {
         module_id: ModuleId,
         config: serde_json::Value,
     ) -> OrchestratorResult<()> {
-        // Validate configuration (basic JSON validation for now)
-        if !config.is_object() {
-            return Err(OrchestratorError::ConfigurationError {
+        let report = self.validate_config(module_id, &config);
+        if !report.is_valid() {
+            return Err(OrchestratorError::InvalidConfig {
                 module: module_id,
-                reason: "Configuration must be a JSON object".to_string(),
+                violations: report.violations,
             });
         }
+        for warning in &report.warnings {
+            warn!("Config for {}: {}", module_id, warning);
+        }
 
         // Store new config
         {
@@ -158,6 +250,34 @@ impl ConfigurationManager {
         Ok(())
     }
 
+    /// Put back a module's config from before a failed reload; it was valid then, so it isn't checked again
+    pub async fn restore_config(
+        &self,
+        module_id: ModuleId,
+        previous: Option<serde_json::Value>,
+    ) -> OrchestratorResult<()> {
+        {
+            let store = self.config_store.read().await;
+            match &previous {
+                Some(config) => store.update_module_config(module_id, config.clone()),
+                None => store.remove_module_config(module_id),
+            }
+        }
+
+        let config_update = skelly_jelly_event_bus::message::ConfigUpdate {
+            config_key: format!("{}_config", module_id),
+            config_value: previous.unwrap_or(serde_json::Value::Null),
+            target_module: Some(module_id),
+        };
+        self.event_bus
+            .publish(BusMessage::new(ModuleId::Orchestrator, MessagePayload::ConfigUpdate(config_update)))
+            .await
+            .map_err(OrchestratorError::EventBus)?;
+
+        info!("Restored previous configuration for module: {}", module_id);
+        Ok(())
+    }
+
     /// Get configuration for a module
     pub async fn get_config(&self, module_id: ModuleId) -> Option<serde_json::Value> {
         let store = self.config_store.read().await;
@@ -192,10 +312,45 @@ impl ConfigurationManager {
         Ok(())
     }
 
-    /// Validate configuration for a module (basic implementation)
-    fn _validate_config(&self, _config: &serde_json::Value) -> OrchestratorResult<()> {
-        // Basic validation - in a real implementation, this would use
-        // JSON schema validation or custom validators per module
-        Ok(())
+    /// Dry run: check a module config against its schema without storing or publishing it
+    pub fn validate_config(&self, module_id: ModuleId, config: &serde_json::Value) -> SchemaReport {
+        ConfigSchema::for_module(module_id).validate(config)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_event_bus::create_event_bus;
+
+    #[tokio::test]
+    async fn test_invalid_config_is_not_stored() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let manager = ConfigurationManager::new(OrchestratorConfig::default(), bus);
+
+        let config = serde_json::json!({ "database_path": "./data/skelly.db", "retention_days": 0 });
+        let result = manager.update_config(ModuleId::Storage, config).await;
+
+        match result {
+            Err(OrchestratorError::InvalidConfig { module, violations }) => {
+                assert_eq!(module, ModuleId::Storage);
+                assert_eq!(violations[0].path(), "retention_days");
+            }
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+        assert!(manager.get_config(ModuleId::Storage).await.is_none());
+    }
+
+    #[test]
+    fn test_global_cross_field_checks() {
+        let config = OrchestratorConfig {
+            health_check_timeout: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let violations = config.validate();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path(), "health_check_timeout");
+        assert!(OrchestratorConfig::default().validate().is_empty());
+    }
+}
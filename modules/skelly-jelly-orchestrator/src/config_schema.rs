@@ -0,0 +1,454 @@
+//! Per-module configuration schemas
+//!
+//! Module configs travel as `serde_json::Value` blobs. Each module gets a
+//! schema of typed fields and cross-field constraints so a bad value is
+//! rejected with the exact path that caused it, before anything is stored
+//! or published.
+
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::ModuleId;
+use std::fmt;
+use thiserror::Error;
+
+/// A single way a config blob breaks its module's schema
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SchemaViolation {
+    #[error("{path}: required field is missing")]
+    MissingField { path: String },
+
+    #[error("{path}: expected {expected}, found {found}")]
+    WrongType {
+        path: String,
+        expected: FieldKind,
+        found: String,
+    },
+
+    #[error("{path}: {value} is outside {}", describe_range(*.min, *.max))]
+    OutOfRange {
+        path: String,
+        value: f64,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+
+    #[error("{path}: {reason}")]
+    Constraint { path: String, reason: String },
+}
+
+impl SchemaViolation {
+    /// Dotted path of the offending field, e.g. `intervention.flow_state_threshold`
+    pub fn path(&self) -> &str {
+        match self {
+            SchemaViolation::MissingField { path }
+            | SchemaViolation::WrongType { path, .. }
+            | SchemaViolation::OutOfRange { path, .. }
+            | SchemaViolation::Constraint { path, .. } => path,
+        }
+    }
+}
+
+fn describe_range(min: Option<f64>, max: Option<f64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}..={}", min, max),
+        (Some(min), None) => format!("{}..", min),
+        (None, Some(max)) => format!("..={}", max),
+        (None, None) => "..".to_string(),
+    }
+}
+
+/// JSON type a field must have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldKind {
+    Bool,
+    Integer,
+    Number,
+    String,
+    Array,
+    /// Nested table whose contents are not checked field by field
+    Object,
+}
+
+impl FieldKind {
+    fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            FieldKind::Bool => value.is_boolean(),
+            FieldKind::Integer => value.is_i64() || value.is_u64(),
+            FieldKind::Number => value.is_number(),
+            FieldKind::String => value.is_string(),
+            FieldKind::Array => value.is_array(),
+            FieldKind::Object => value.is_object(),
+        }
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldKind::Bool => "bool",
+            FieldKind::Integer => "integer",
+            FieldKind::Number => "number",
+            FieldKind::String => "string",
+            FieldKind::Array => "array",
+            FieldKind::Object => "object",
+        };
+        f.write_str(name)
+    }
+}
+
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// Expected type and bounds for one field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    pub path: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FieldSpec {
+    pub const fn new(path: &'static str, kind: FieldKind) -> Self {
+        Self { path, kind, required: false, min: None, max: None }
+    }
+
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub const fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    pub const fn at_least(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+}
+
+/// Cross-field rule: `lesser` must stay strictly below `greater`
+///
+/// Each side carries a scale so fields in different units compare correctly,
+/// e.g. a millisecond timeout against a retention period in days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldOrdering {
+    pub lesser: &'static str,
+    pub lesser_scale: f64,
+    pub greater: &'static str,
+    pub greater_scale: f64,
+}
+
+impl FieldOrdering {
+    pub const fn new(lesser: &'static str, greater: &'static str) -> Self {
+        Self { lesser, lesser_scale: 1.0, greater, greater_scale: 1.0 }
+    }
+
+    pub const fn scaled(mut self, lesser_scale: f64, greater_scale: f64) -> Self {
+        self.lesser_scale = lesser_scale;
+        self.greater_scale = greater_scale;
+        self
+    }
+}
+
+/// Outcome of validating a config blob
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaReport {
+    pub violations: Vec<SchemaViolation>,
+    /// Fields the schema does not know about; kept but flagged
+    pub warnings: Vec<String>,
+}
+
+impl SchemaReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Keys the profile manager merges into module configs
+const PROFILE_FIELD: FieldSpec = FieldSpec::new("profile", FieldKind::String);
+/// Key the power manager merges into module configs
+const POWER_STATE_FIELD: FieldSpec = FieldSpec::new("power_state", FieldKind::String);
+
+/// Schema for one module's config blob
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSchema {
+    pub fields: Vec<FieldSpec>,
+    pub orderings: Vec<FieldOrdering>,
+}
+
+impl ConfigSchema {
+    /// Built-in schema matching the sections of `config/default.toml`
+    pub fn for_module(module_id: ModuleId) -> Self {
+        use FieldKind::*;
+
+        match module_id {
+            ModuleId::EventBus => Self {
+                fields: vec![
+                    FieldSpec::new("max_queue_size", Integer).at_least(1.0),
+                    FieldSpec::new("message_timeout_ms", Integer).at_least(1.0),
+                    FieldSpec::new("enable_persistence", Bool),
+                    FieldSpec::new("persistence_path", String),
+                ],
+                orderings: Vec::new(),
+            },
+            ModuleId::Storage => Self {
+                fields: vec![
+                    FieldSpec::new("database_path", String).required(),
+                    FieldSpec::new("max_batch_size", Integer).at_least(1.0),
+                    FieldSpec::new("batch_timeout_ms", Integer).at_least(1.0),
+                    FieldSpec::new("retention_days", Integer).at_least(1.0),
+                    FieldSpec::new("enable_compression", Bool),
+                ],
+                orderings: vec![
+                    FieldOrdering::new("batch_timeout_ms", "retention_days").scaled(1.0, MS_PER_DAY),
+                ],
+            },
+            ModuleId::DataCapture => Self {
+                fields: vec![
+                    FieldSpec::new("screenshot_interval_ms", Integer).at_least(1.0),
+                    FieldSpec::new("enable_screenshots", Bool),
+                    FieldSpec::new("screenshot_quality", Integer).range(1.0, 100.0),
+                    FieldSpec::new("keystroke_buffer_size", Integer).at_least(1.0),
+                    FieldSpec::new("mouse_sample_rate_hz", Number).range(0.1, 1000.0),
+                    FieldSpec::new("window_tracking_enabled", Bool),
+                    FieldSpec::new("sampling_rate_hz", Number).range(0.1, 1000.0),
+                    FieldSpec::new("power_sampling_factor", Number).range(0.0, 1.0),
+                    PROFILE_FIELD,
+                    POWER_STATE_FIELD,
+                ],
+                orderings: Vec::new(),
+            },
+            ModuleId::AnalysisEngine => Self {
+                fields: vec![
+                    FieldSpec::new("model_path", String),
+                    FieldSpec::new("inference_threads", Integer).range(1.0, 64.0),
+                    FieldSpec::new("window_size_seconds", Integer).at_least(1.0),
+                    FieldSpec::new("window_overlap_seconds", Integer).at_least(0.0),
+                    FieldSpec::new("confidence_threshold", Number).range(0.0, 1.0),
+                    FieldSpec::new("enable_gpu", Bool),
+                    FieldSpec::new("analysis_interval_ms", Integer).at_least(100.0),
+                    FieldSpec::new("power_window_factor", Number).range(1.0, 10.0),
+                    FieldSpec::new("defer_training", Bool),
+                    PROFILE_FIELD,
+                    POWER_STATE_FIELD,
+                ],
+                orderings: vec![
+                    FieldOrdering::new("window_overlap_seconds", "window_size_seconds"),
+                ],
+            },
+            ModuleId::Gamification => Self {
+                fields: vec![
+                    FieldSpec::new("intervention", Object),
+                    FieldSpec::new("intervention.min_cooldown_minutes", Integer).at_least(0.0),
+                    FieldSpec::new("intervention.max_interventions_per_hour", Integer).range(0.0, 60.0),
+                    FieldSpec::new("intervention.flow_state_threshold", Number).range(0.0, 1.0),
+                    FieldSpec::new("rewards", Object),
+                    FieldSpec::new("progress", Object),
+                    FieldSpec::new("progress.session_timeout_minutes", Integer).at_least(1.0),
+                    FieldSpec::new("companion", Object),
+                    FieldSpec::new("companion.reaction_sensitivity", Number).range(0.0, 1.0),
+                    FieldSpec::new("messages", Object),
+                    FieldSpec::new("messages.max_length", Integer).at_least(1.0),
+                    FieldSpec::new("performance", Object),
+                ],
+                orderings: Vec::new(),
+            },
+            ModuleId::AiIntegration => Self {
+                fields: vec![
+                    FieldSpec::new("local_model", Object),
+                    FieldSpec::new("local_model.max_memory_gb", Number).range(0.5, 256.0),
+                    FieldSpec::new("local_model.context_length", Integer).range(128.0, 131_072.0),
+                    FieldSpec::new("local_model.batch_size", Integer).at_least(1.0),
+                    FieldSpec::new("local_model.temperature", Number).range(0.0, 2.0),
+                    FieldSpec::new("local_model.top_p", Number).range(0.0, 1.0),
+                    FieldSpec::new("api_config", Object),
+                    FieldSpec::new("api_config.max_monthly_cost", Number).at_least(0.0),
+                    FieldSpec::new("api_config.request_timeout_ms", Integer).at_least(1.0),
+                    FieldSpec::new("privacy", Object),
+                    FieldSpec::new("personality", Object),
+                    FieldSpec::new("personality.pun_frequency", Number).range(0.0, 1.0),
+                    FieldSpec::new("intervention_aggressiveness", Number).range(0.0, 1.0),
+                    PROFILE_FIELD,
+                ],
+                orderings: vec![
+                    FieldOrdering::new("local_model.batch_size", "local_model.context_length"),
+                ],
+            },
+            ModuleId::CuteFigurine => Self {
+                fields: vec![
+                    FieldSpec::new("window_width", Integer).range(32.0, 4096.0),
+                    FieldSpec::new("window_height", Integer).range(32.0, 4096.0),
+                    FieldSpec::new("position_x", Integer).at_least(-1.0),
+                    FieldSpec::new("position_y", Integer).at_least(-1.0),
+                    FieldSpec::new("enable_transparency", Bool),
+                    FieldSpec::new("always_on_top", Bool),
+                    FieldSpec::new("enable_click_through", Bool),
+                    FieldSpec::new("animation_fps", Integer).range(1.0, 240.0),
+                    FieldSpec::new("enable_webgl", Bool),
+                ],
+                orderings: Vec::new(),
+            },
+            ModuleId::Orchestrator => Self::default(),
+        }
+    }
+
+    /// Check a config blob against the schema without side effects
+    pub fn validate(&self, config: &serde_json::Value) -> SchemaReport {
+        let mut report = SchemaReport::default();
+
+        if !config.is_object() {
+            report.violations.push(SchemaViolation::WrongType {
+                path: "$".to_string(),
+                expected: FieldKind::Object,
+                found: json_type_name(config).to_string(),
+            });
+            return report;
+        }
+
+        for spec in &self.fields {
+            self.check_field(spec, config, &mut report);
+        }
+
+        for ordering in &self.orderings {
+            let lesser = lookup(config, ordering.lesser).and_then(|v| v.as_f64());
+            let greater = lookup(config, ordering.greater).and_then(|v| v.as_f64());
+            if let (Some(lesser), Some(greater)) = (lesser, greater) {
+                if lesser * ordering.lesser_scale >= greater * ordering.greater_scale {
+                    report.violations.push(SchemaViolation::Constraint {
+                        path: ordering.lesser.to_string(),
+                        reason: format!("must be less than {}", ordering.greater),
+                    });
+                }
+            }
+        }
+
+        if !self.fields.is_empty() {
+            self.collect_unknown(config, "", &mut report.warnings);
+        }
+
+        report
+    }
+
+    fn check_field(&self, spec: &FieldSpec, config: &serde_json::Value, report: &mut SchemaReport) {
+        let value = match lookup(config, spec.path) {
+            Some(value) => value,
+            None => {
+                if spec.required {
+                    report.violations.push(SchemaViolation::MissingField { path: spec.path.to_string() });
+                }
+                return;
+            }
+        };
+
+        if !spec.kind.matches(value) {
+            report.violations.push(SchemaViolation::WrongType {
+                path: spec.path.to_string(),
+                expected: spec.kind,
+                found: json_type_name(value).to_string(),
+            });
+            return;
+        }
+
+        if let Some(number) = value.as_f64() {
+            let below = spec.min.is_some_and(|min| number < min);
+            let above = spec.max.is_some_and(|max| number > max);
+            if below || above {
+                report.violations.push(SchemaViolation::OutOfRange {
+                    path: spec.path.to_string(),
+                    value: number,
+                    min: spec.min,
+                    max: spec.max,
+                });
+            }
+        }
+    }
+
+    fn collect_unknown(&self, value: &serde_json::Value, prefix: &str, warnings: &mut Vec<String>) {
+        let Some(object) = value.as_object() else { return };
+
+        for (key, child) in object {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+
+            if self.fields.iter().any(|spec| spec.path == path) {
+                continue;
+            }
+
+            let nested = format!("{}.", path);
+            if self.fields.iter().any(|spec| spec.path.starts_with(&nested)) {
+                self.collect_unknown(child, &path, warnings);
+            } else {
+                warnings.push(format!("{}: unknown field", path));
+            }
+        }
+    }
+}
+
+/// Resolve a dotted path inside a JSON object
+fn lookup<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').try_fold(value, |current, key| current.get(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_sections_are_valid() {
+        let storage = json!({
+            "database_path": "./data/skelly.db",
+            "max_batch_size": 1000,
+            "batch_timeout_ms": 30000,
+            "retention_days": 30,
+            "enable_compression": true,
+        });
+        let report = ConfigSchema::for_module(ModuleId::Storage).validate(&storage);
+        assert!(report.is_valid(), "{:?}", report.violations);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_violations_carry_paths() {
+        let gamification = json!({
+            "intervention": { "flow_state_threshold": 1.5, "max_interventions_per_hour": "lots" },
+        });
+        let report = ConfigSchema::for_module(ModuleId::Gamification).validate(&gamification);
+
+        let paths: Vec<&str> = report.violations.iter().map(|v| v.path()).collect();
+        assert!(paths.contains(&"intervention.flow_state_threshold"));
+        assert!(paths.contains(&"intervention.max_interventions_per_hour"));
+        assert!(report.violations.iter().any(|v| matches!(v, SchemaViolation::WrongType { expected: FieldKind::Integer, .. })));
+    }
+
+    #[test]
+    fn test_cross_field_and_missing_checks() {
+        let storage = json!({ "batch_timeout_ms": 2 * 86_400_000u64, "retention_days": 1 });
+        let report = ConfigSchema::for_module(ModuleId::Storage).validate(&storage);
+
+        assert!(report.violations.contains(&SchemaViolation::MissingField { path: "database_path".to_string() }));
+        assert!(report.violations.iter().any(|v| matches!(v, SchemaViolation::Constraint { path, .. } if path == "batch_timeout_ms")));
+    }
+
+    #[test]
+    fn test_unknown_fields_warn_only() {
+        let capture = json!({ "sampling_rate_hz": 20.0, "profile": "focus", "sampling_rate": 20 });
+        let report = ConfigSchema::for_module(ModuleId::DataCapture).validate(&capture);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings, vec!["sampling_rate: unknown field".to_string()]);
+    }
+}
@@ -0,0 +1,273 @@
+//! Startup configuration rendered from the machine's capabilities
+//!
+//! A module's sensible defaults depend on where it runs: the analysis engine
+//! can batch on a GPU but should keep its caches small on an 8GB laptop, and
+//! the AI integration should pick a model that fits in memory. A
+//! [`ConfigTemplate`] holds a module's base settings plus rules that override
+//! them when a [`TemplateCondition`] holds. When a module registers, the
+//! registry renders its template against the detected [`SystemCapabilities`]
+//! and the active [`SystemProfile`] and returns the fragment.
+//!
+//! Rules are applied in order, so later rules win. The profile preset is
+//! merged last, as [`ProfileManager`](crate::ProfileManager) does on a switch.
+
+use crate::profiles::{ProfilePreset, SystemProfile};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use skelly_jelly_event_bus::ModuleId;
+use std::collections::HashMap;
+
+/// What the machine offers, as far as module configuration cares
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemCapabilities {
+    /// `std::env::consts::OS`, e.g. `macos`, `linux`, `windows`
+    pub platform: String,
+    pub total_memory_mb: u64,
+    pub cpu_cores: usize,
+    /// A GPU usable for inference was found
+    pub has_gpu: bool,
+}
+
+impl SystemCapabilities {
+    /// Detect the capabilities of this machine
+    pub fn detect() -> Self {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+
+        Self {
+            platform: std::env::consts::OS.to_string(),
+            total_memory_mb: system.total_memory() / (1024 * 1024),
+            cpu_cores: std::thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1),
+            has_gpu: Self::detect_gpu(),
+        }
+    }
+
+    /// Apple silicon always has a Metal GPU; on Linux look for an NVIDIA or DRM render node
+    fn detect_gpu() -> bool {
+        if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+            return true;
+        }
+        if cfg!(target_os = "linux") {
+            return ["/dev/nvidia0", "/dev/dri/renderD128"]
+                .iter()
+                .any(|path| std::path::Path::new(path).exists());
+        }
+        false
+    }
+}
+
+/// When a template rule applies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateCondition {
+    /// At least this much RAM
+    MinMemoryMb(u64),
+    /// At most this much RAM
+    MaxMemoryMb(u64),
+    /// A GPU is (or isn't) present
+    Gpu(bool),
+    /// Running on this platform
+    Platform(String),
+    /// This profile is active
+    Profile(SystemProfile),
+}
+
+impl TemplateCondition {
+    pub fn matches(&self, capabilities: &SystemCapabilities, profile: SystemProfile) -> bool {
+        match self {
+            TemplateCondition::MinMemoryMb(mb) => capabilities.total_memory_mb >= *mb,
+            TemplateCondition::MaxMemoryMb(mb) => capabilities.total_memory_mb <= *mb,
+            TemplateCondition::Gpu(present) => capabilities.has_gpu == *present,
+            TemplateCondition::Platform(platform) => capabilities.platform == *platform,
+            TemplateCondition::Profile(active) => *active == profile,
+        }
+    }
+}
+
+/// Settings applied when every condition holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateRule {
+    pub when: Vec<TemplateCondition>,
+    pub set: Value,
+}
+
+/// A module's startup configuration before capabilities are known
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigTemplate {
+    pub module: ModuleId,
+    pub base: Value,
+    pub rules: Vec<TemplateRule>,
+}
+
+impl ConfigTemplate {
+    pub fn new(module: ModuleId, base: Value) -> Self {
+        Self { module, base, rules: Vec::new() }
+    }
+
+    /// Override settings when every condition in `when` holds
+    pub fn with_rule(mut self, when: Vec<TemplateCondition>, set: Value) -> Self {
+        self.rules.push(TemplateRule { when, set });
+        self
+    }
+
+    /// The module's configuration on a machine with `capabilities` under `profile`
+    pub fn render(&self, capabilities: &SystemCapabilities, profile: SystemProfile) -> Value {
+        let mut config = self.base.clone();
+        for rule in &self.rules {
+            if rule.when.iter().all(|condition| condition.matches(capabilities, profile)) {
+                merge(&mut config, &rule.set);
+            }
+        }
+
+        if let Some((_, preset)) = ProfilePreset::for_profile(profile)
+            .module_configs()
+            .into_iter()
+            .find(|(module_id, _)| *module_id == self.module)
+        {
+            merge(&mut config, &preset);
+            merge(&mut config, &json!({ "profile": profile }));
+        }
+        config
+    }
+}
+
+/// Deep-merge `overlay` into `target`; objects merge key by key, anything else is replaced
+pub fn merge(target: &mut Value, overlay: &Value) {
+    match (target.as_object_mut(), overlay.as_object()) {
+        (Some(target), Some(overlay)) => {
+            for (key, value) in overlay {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        _ => *target = overlay.clone(),
+    }
+}
+
+/// Startup templates by module
+#[derive(Debug, Clone, Default)]
+pub struct ConfigTemplates {
+    templates: HashMap<ModuleId, ConfigTemplate>,
+}
+
+impl ConfigTemplates {
+    /// No templates; modules register without a rendered config
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Templates for the modules whose defaults depend on the hardware
+    pub fn builtin() -> Self {
+        use TemplateCondition::*;
+
+        Self::empty()
+            .with_template(
+                ConfigTemplate::new(ModuleId::AnalysisEngine, json!({
+                    "use_gpu": false,
+                    "batch_size": 1,
+                    "feature_cache_size": 100,
+                    "max_concurrent_analyses": 3,
+                }))
+                .with_rule(vec![Gpu(true)], json!({ "use_gpu": true, "batch_size": 4 }))
+                .with_rule(vec![MaxMemoryMb(8 * 1024)], json!({
+                    "batch_size": 1,
+                    "feature_cache_size": 50,
+                    "max_concurrent_analyses": 1,
+                }))
+                .with_rule(vec![Profile(SystemProfile::LowPower)], json!({ "max_concurrent_analyses": 1 })),
+            )
+            .with_template(
+                ConfigTemplate::new(ModuleId::AiIntegration, json!({
+                    "local_model": {
+                        "model_variant": "Phi3Mini",
+                        "max_memory_gb": 4.0,
+                        "use_gpu": false,
+                        "context_length": 4096,
+                        "batch_size": 1,
+                    },
+                }))
+                .with_rule(vec![Gpu(true)], json!({ "local_model": { "use_gpu": true } }))
+                .with_rule(vec![MinMemoryMb(16 * 1024), Gpu(true)], json!({
+                    "local_model": { "model_variant": "Mistral7B", "max_memory_gb": 8.0 },
+                }))
+                .with_rule(vec![MaxMemoryMb(8 * 1024)], json!({
+                    "local_model": { "model_variant": "TinyLlama", "max_memory_gb": 2.0, "context_length": 2048 },
+                })),
+            )
+    }
+
+    /// Add or replace the template for its module
+    pub fn with_template(mut self, template: ConfigTemplate) -> Self {
+        self.templates.insert(template.module, template);
+        self
+    }
+
+    pub fn get(&self, module_id: ModuleId) -> Option<&ConfigTemplate> {
+        self.templates.get(&module_id)
+    }
+
+    /// Render `module_id`'s template, if it has one
+    pub fn render(&self, module_id: ModuleId, capabilities: &SystemCapabilities, profile: SystemProfile) -> Option<Value> {
+        self.get(module_id).map(|template| template.render(capabilities, profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(memory_gb: u64, has_gpu: bool) -> SystemCapabilities {
+        SystemCapabilities {
+            platform: "linux".to_string(),
+            total_memory_mb: memory_gb * 1024,
+            cpu_cores: 8,
+            has_gpu,
+        }
+    }
+
+    #[test]
+    fn test_small_machine_gets_smaller_model_settings() {
+        let templates = ConfigTemplates::builtin();
+
+        let small = templates.render(ModuleId::AnalysisEngine, &machine(8, false), SystemProfile::Balanced).unwrap();
+        assert_eq!(small["feature_cache_size"], 50);
+        assert_eq!(small["max_concurrent_analyses"], 1);
+        assert_eq!(small["use_gpu"], false);
+        assert_eq!(small["analysis_interval_ms"], 30_000);
+        assert_eq!(small["profile"], "balanced");
+
+        let large = templates.render(ModuleId::AnalysisEngine, &machine(32, true), SystemProfile::Balanced).unwrap();
+        assert_eq!(large["feature_cache_size"], 100);
+        assert_eq!(large["use_gpu"], true);
+        assert_eq!(large["batch_size"], 4);
+
+        let ai = templates.render(ModuleId::AiIntegration, &machine(8, false), SystemProfile::Balanced).unwrap();
+        assert_eq!(ai["local_model"]["model_variant"], "TinyLlama");
+        assert_eq!(ai["local_model"]["batch_size"], 1);
+        let ai = templates.render(ModuleId::AiIntegration, &machine(32, true), SystemProfile::Balanced).unwrap();
+        assert_eq!(ai["local_model"]["model_variant"], "Mistral7B");
+
+        assert!(templates.render(ModuleId::Storage, &machine(8, false), SystemProfile::Balanced).is_none());
+    }
+
+    #[test]
+    fn test_rules_match_platform_and_profile() {
+        let template = ConfigTemplate::new(ModuleId::DataCapture, json!({ "backend": "generic" }))
+            .with_rule(vec![TemplateCondition::Platform("macos".to_string())], json!({ "backend": "cg_event_tap" }))
+            .with_rule(vec![TemplateCondition::Profile(SystemProfile::Gaming)], json!({ "screenshots": false }));
+
+        let linux = template.render(&machine(16, false), SystemProfile::Gaming);
+        assert_eq!(linux["backend"], "generic");
+        assert_eq!(linux["screenshots"], false);
+        assert_eq!(linux["sampling_rate_hz"], 2.0);
+
+        let mac = SystemCapabilities { platform: "macos".to_string(), ..machine(16, false) };
+        let rendered = template.render(&mac, SystemProfile::Balanced);
+        assert_eq!(rendered["backend"], "cg_event_tap");
+        assert!(rendered.get("screenshots").is_none());
+    }
+}
@@ -0,0 +1,283 @@
+//! All-or-nothing hot reloads across module config sections
+//!
+//! A reload validates every changed section before touching any module, applies
+//! the changes in dependency order, and if a module rejects its section puts the
+//! already-updated modules back on their previous config, newest first. Every
+//! attempt ends with a `ConfigTransactionResult` on the bus.
+
+use crate::{
+    config::ConfigurationManager,
+    config_schema::ConfigSchema,
+    error::OrchestratorResult,
+    module_registry::ModuleRegistry,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use skelly_jelly_event_bus::{
+    message::ConfigTransactionResult, BusMessage, EventBusTrait, MessagePayload, ModuleId,
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Modules that can be configured from a section of the canonical config file
+const SECTION_MODULES: [ModuleId; 8] = [
+    ModuleId::Orchestrator,
+    ModuleId::EventBus,
+    ModuleId::Storage,
+    ModuleId::DataCapture,
+    ModuleId::AnalysisEngine,
+    ModuleId::Gamification,
+    ModuleId::AiIntegration,
+    ModuleId::CuteFigurine,
+];
+
+/// Hands a module its new config section during a transaction
+#[async_trait]
+pub trait ConfigApplier: Send + Sync {
+    /// Apply `config`; an error rejects the whole transaction
+    async fn apply(&self, module: ModuleId, config: &serde_json::Value) -> OrchestratorResult<()>;
+
+    /// Put back the config the module had before the transaction (`None` if it had none)
+    async fn restore(&self, module: ModuleId, previous: Option<&serde_json::Value>) -> OrchestratorResult<()>;
+}
+
+#[async_trait]
+impl ConfigApplier for ConfigurationManager {
+    async fn apply(&self, module: ModuleId, config: &serde_json::Value) -> OrchestratorResult<()> {
+        self.update_config(module, config.clone()).await
+    }
+
+    async fn restore(&self, module: ModuleId, previous: Option<&serde_json::Value>) -> OrchestratorResult<()> {
+        self.restore_config(module, previous.cloned()).await
+    }
+}
+
+/// The module a top-level section such as `[data_capture]` configures
+pub fn section_module(section: &str) -> Option<ModuleId> {
+    SECTION_MODULES
+        .into_iter()
+        .find(|module| module.to_string().replace('-', "_") == section)
+}
+
+/// Module sections of a parsed config document; other sections are left out
+pub fn module_sections(document: &serde_json::Value) -> HashMap<ModuleId, serde_json::Value> {
+    document
+        .as_object()
+        .map(|sections| {
+            sections
+                .iter()
+                .filter(|(_, value)| value.is_object())
+                .filter_map(|(name, value)| section_module(name).map(|module| (module, value.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Runs config transactions and remembers the last committed section per module
+pub struct ConfigTransactions {
+    applier: Arc<dyn ConfigApplier>,
+    event_bus: Arc<dyn EventBusTrait>,
+    registry: Arc<ModuleRegistry>,
+    committed: RwLock<HashMap<ModuleId, serde_json::Value>>,
+    /// One transaction at a time, so a rollback never interleaves with another reload
+    in_flight: Mutex<()>,
+}
+
+impl ConfigTransactions {
+    pub fn new(
+        applier: Arc<dyn ConfigApplier>,
+        event_bus: Arc<dyn EventBusTrait>,
+        registry: Arc<ModuleRegistry>,
+    ) -> Self {
+        Self {
+            applier,
+            event_bus,
+            registry,
+            committed: RwLock::new(HashMap::new()),
+            in_flight: Mutex::new(()),
+        }
+    }
+
+    /// Record sections the modules already run with, without applying them
+    pub async fn seed(&self, sections: HashMap<ModuleId, serde_json::Value>) {
+        self.committed.write().await.extend(sections);
+    }
+
+    /// Last committed section for a module
+    pub async fn committed(&self, module: ModuleId) -> Option<serde_json::Value> {
+        self.committed.read().await.get(&module).cloned()
+    }
+
+    /// Apply the sections that differ from the committed ones as a single transaction
+    pub async fn apply(&self, source: &str, sections: HashMap<ModuleId, serde_json::Value>) -> ConfigTransactionResult {
+        let _guard = self.in_flight.lock().await;
+        let previous = self.committed.read().await.clone();
+
+        let mut changed: Vec<(ModuleId, serde_json::Value)> = sections
+            .into_iter()
+            .filter(|(module, section)| previous.get(module) != Some(section))
+            .collect();
+
+        let mut result = ConfigTransactionResult {
+            transaction_id: Uuid::new_v4(),
+            source: source.to_string(),
+            committed: false,
+            affected_modules: Vec::new(),
+            applied_modules: Vec::new(),
+            rejected_by: None,
+            errors: Vec::new(),
+            timestamp: Utc::now(),
+        };
+
+        if changed.is_empty() {
+            result.committed = true;
+            return result;
+        }
+
+        // Dependencies first, so a module never sees settings its dependencies don't have yet
+        let levels = self.registry.compute_startup_levels().await.unwrap_or_default();
+        let rank = |module: &ModuleId| levels.iter().position(|level| level.contains(module)).unwrap_or(levels.len());
+        changed.sort_by_key(|(module, _)| rank(module));
+        result.affected_modules = changed.iter().map(|(module, _)| *module).collect();
+
+        for (module, section) in &changed {
+            let report = ConfigSchema::for_module(*module).validate(section);
+            result.errors.extend(report.violations.iter().map(|v| format!("{}: {}", module, v)));
+            for warning in &report.warnings {
+                warn!("Config for {}: {}", module, warning);
+            }
+        }
+
+        if result.errors.is_empty() {
+            for (module, section) in &changed {
+                if let Err(e) = self.applier.apply(*module, section).await {
+                    warn!("❌ {} rejected its new configuration: {}", module, e);
+                    result.rejected_by = Some(*module);
+                    result.errors.push(format!("{}: {}", module, e));
+                    break;
+                }
+                result.applied_modules.push(*module);
+            }
+
+            if result.rejected_by.is_none() {
+                self.committed.write().await.extend(changed);
+                result.committed = true;
+            } else {
+                for module in result.applied_modules.iter().rev() {
+                    if let Err(e) = self.applier.restore(*module, previous.get(module)).await {
+                        result.errors.push(format!("{}: rollback failed: {}", module, e));
+                    }
+                }
+            }
+        }
+
+        if result.committed {
+            info!("✅ Config transaction {} committed for {:?}", result.transaction_id, result.affected_modules);
+        } else {
+            warn!("🔄 Config transaction {} not committed: {:?}", result.transaction_id, result.errors);
+        }
+
+        let message = BusMessage::new(ModuleId::Orchestrator, MessagePayload::ConfigTransactionResult(result.clone()));
+        if let Err(e) = self.event_bus.publish(message).await {
+            warn!("Failed to publish config transaction result: {}", e);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::OrchestratorError;
+    use skelly_jelly_event_bus::create_event_bus;
+
+    /// Records applies and restores; rejects updates for one module
+    #[derive(Default)]
+    struct RecordingApplier {
+        reject: Option<ModuleId>,
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ConfigApplier for RecordingApplier {
+        async fn apply(&self, module: ModuleId, _config: &serde_json::Value) -> OrchestratorResult<()> {
+            self.calls.lock().unwrap().push(format!("apply {}", module));
+            if self.reject == Some(module) {
+                return Err(OrchestratorError::ConfigurationError { module, reason: "busy".to_string() });
+            }
+            Ok(())
+        }
+
+        async fn restore(&self, module: ModuleId, previous: Option<&serde_json::Value>) -> OrchestratorResult<()> {
+            let kind = if previous.is_some() { "previous" } else { "none" };
+            self.calls.lock().unwrap().push(format!("restore {} {}", module, kind));
+            Ok(())
+        }
+    }
+
+    async fn transactions(applier: Arc<RecordingApplier>) -> ConfigTransactions {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        ConfigTransactions::new(applier, bus, Arc::new(ModuleRegistry::new()))
+    }
+
+    fn document() -> serde_json::Value {
+        serde_json::json!({
+            "storage": { "database_path": "./data/skelly.db", "retention_days": 30 },
+            "analysis_engine": { "inference_threads": 4 },
+            "data_capture": { "screenshot_quality": 50 },
+            "demo": { "enable_synthetic_data": true },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rejection_rolls_back_in_reverse_dependency_order() {
+        let applier = Arc::new(RecordingApplier { reject: Some(ModuleId::AnalysisEngine), ..Default::default() });
+        let transactions = transactions(applier.clone()).await;
+        transactions
+            .seed(HashMap::from([(ModuleId::Storage, serde_json::json!({ "database_path": "./old.db" }))]))
+            .await;
+
+        let result = transactions.apply("config/default.toml", module_sections(&document())).await;
+
+        assert!(!result.committed);
+        assert_eq!(result.rejected_by, Some(ModuleId::AnalysisEngine));
+        assert_eq!(result.affected_modules.len(), 3);
+        assert_eq!(result.affected_modules[2], ModuleId::AnalysisEngine);
+        let calls = applier.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 5);
+        assert_eq!(calls[2], "apply analysis-engine");
+        // Whichever of Storage and Data Capture went second is restored first
+        let second = calls[1].trim_start_matches("apply ");
+        assert_eq!(calls[3], format!("restore {} {}", second, if second == "storage" { "previous" } else { "none" }));
+        assert_eq!(
+            transactions.committed(ModuleId::Storage).await,
+            Some(serde_json::json!({ "database_path": "./old.db" }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_section_applies_nothing_and_unchanged_sections_are_skipped() {
+        let applier = Arc::new(RecordingApplier::default());
+        let transactions = transactions(applier.clone()).await;
+
+        let mut invalid = document();
+        invalid["storage"]["retention_days"] = serde_json::json!(0);
+        let result = transactions.apply("config/default.toml", module_sections(&invalid)).await;
+        assert!(!result.committed);
+        assert!(result.errors.iter().any(|e| e.contains("retention_days")));
+        assert!(applier.calls.lock().unwrap().is_empty());
+
+        assert!(transactions.apply("config/default.toml", module_sections(&document())).await.committed);
+        applier.calls.lock().unwrap().clear();
+
+        let mut tweaked = document();
+        tweaked["data_capture"]["screenshot_quality"] = serde_json::json!(60);
+        let result = transactions.apply("config/default.toml", module_sections(&tweaked)).await;
+        assert!(result.committed);
+        assert_eq!(result.affected_modules, vec![ModuleId::DataCapture]);
+        assert_eq!(*applier.calls.lock().unwrap(), vec!["apply data-capture".to_string()]);
+    }
+}
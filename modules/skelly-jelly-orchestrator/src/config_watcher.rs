@@ -3,6 +3,8 @@
 use crate::{
     error::{OrchestratorError, OrchestratorResult},
     config::{ConfigurationManager, OrchestratorConfig},
+    config_transaction::{module_sections, ConfigTransactions},
+    module_registry::ModuleRegistry,
 };
 use notify::{RecommendedWatcher, Watcher, RecursiveMode, Event, EventKind};
 use skelly_jelly_event_bus::{EventBusTrait, ModuleId, BusMessage, MessagePayload, message::ConfigUpdate};
@@ -119,6 +121,9 @@ pub struct ConfigWatcher {
     
     /// Debounce tracking
     pending_changes: Arc<RwLock<HashMap<PathBuf, Instant>>>,
+
+    /// All-or-nothing application of changed module sections
+    transactions: Arc<ConfigTransactions>,
 }
 
 impl ConfigWatcher {
@@ -128,6 +133,12 @@ impl ConfigWatcher {
         event_bus: Arc<dyn EventBusTrait>,
         hot_reload_config: HotReloadConfig,
     ) -> Self {
+        let transactions = Arc::new(ConfigTransactions::new(
+            config_manager.clone(),
+            Arc::clone(&event_bus),
+            Arc::new(ModuleRegistry::new()),
+        ));
+
         Self {
             config_dir,
             config_manager,
@@ -139,9 +150,20 @@ impl ConfigWatcher {
             config_backups: Arc::new(RwLock::new(HashMap::new())),
             last_known_good: Arc::new(RwLock::new(HashMap::new())),
             pending_changes: Arc::new(RwLock::new(HashMap::new())),
+            transactions,
         }
     }
 
+    /// Use a different applier or dependency graph for reload transactions
+    pub fn with_transactions(mut self, transactions: Arc<ConfigTransactions>) -> Self {
+        self.transactions = transactions;
+        self
+    }
+
+    pub fn transactions(&self) -> Arc<ConfigTransactions> {
+        Arc::clone(&self.transactions)
+    }
+
     /// Start configuration watching and hot-reloading
     pub async fn start_watching(&mut self) -> OrchestratorResult<()> {
         if !self.hot_reload_config.enabled {
@@ -329,6 +351,14 @@ impl ConfigWatcher {
                 if let Err(e) = self.create_backup(&path).await {
                     warn!("⚠️  Failed to create initial backup for {:?}: {}", path, e);
                 }
+
+                // The modules already run with these sections, so later reloads diff against them
+                let file_type = Self::determine_file_type(&path, &self.config_dir)?;
+                if let Ok(content) = fs::read_to_string(&path).await {
+                    if let Some(sections) = Self::transactional_sections(&content, &file_type) {
+                        self.transactions.seed(sections).await;
+                    }
+                }
             }
         }
 
@@ -394,6 +424,7 @@ impl ConfigWatcher {
         let last_known_good = Arc::clone(&self.last_known_good);
         let pending_changes = Arc::clone(&self.pending_changes);
         let hot_reload_config = self.hot_reload_config.clone();
+        let transactions = Arc::clone(&self.transactions);
 
         let task = tokio::spawn(async move {
             info!("🔄 Starting configuration change processor");
@@ -429,6 +460,7 @@ impl ConfigWatcher {
                     &config_backups,
                     &last_known_good,
                     &hot_reload_config,
+                    &transactions,
                 ).await {
                     error!("❌ Failed to process config change for {:?}: {}", change.file_path, e);
                 }
@@ -454,6 +486,7 @@ impl ConfigWatcher {
         config_backups: &Arc<RwLock<HashMap<PathBuf, Vec<ConfigBackup>>>>,
         last_known_good: &Arc<RwLock<HashMap<ConfigFileType, ConfigBackup>>>,
         hot_reload_config: &HotReloadConfig,
+        transactions: &Arc<ConfigTransactions>,
     ) -> OrchestratorResult<()> {
         info!("🔄 Processing configuration change: {:?} -> {:?}", 
               change.change_type, change.file_path);
@@ -467,6 +500,7 @@ impl ConfigWatcher {
                     config_backups,
                     last_known_good,
                     hot_reload_config,
+                    transactions,
                 ).await
             }
             ConfigChangeType::Deleted => {
@@ -493,6 +527,7 @@ impl ConfigWatcher {
         config_backups: &Arc<RwLock<HashMap<PathBuf, Vec<ConfigBackup>>>>,
         last_known_good: &Arc<RwLock<HashMap<ConfigFileType, ConfigBackup>>>,
         hot_reload_config: &HotReloadConfig,
+        transactions: &Arc<ConfigTransactions>,
     ) -> OrchestratorResult<()> {
         // Read the new configuration
         let new_content = tokio::fs::read_to_string(&change.file_path).await
@@ -501,8 +536,37 @@ impl ConfigWatcher {
                 reason: format!("Failed to read config file: {}", e),
             })?;
 
+        // Module sections go through a transaction: every changed section or none of them
+        if let Some(sections) = Self::transactional_sections(&new_content, &change.file_type) {
+            let result = transactions.apply(&change.file_path.to_string_lossy(), sections).await;
+            if !result.committed {
+                if hot_reload_config.auto_rollback_on_error {
+                    warn!("🔄 Auto-rollback enabled, restoring last known good configuration");
+                    return Self::rollback_config(change, last_known_good).await;
+                }
+                return Err(OrchestratorError::ConfigurationError {
+                    module: result.rejected_by.unwrap_or(ModuleId::Orchestrator),
+                    reason: format!("Config transaction rolled back: {}", result.errors.join("; ")),
+                });
+            }
+
+            Self::create_config_backup(&change.file_path, &new_content, config_backups).await?;
+            {
+                let backup = ConfigBackup {
+                    file_path: change.file_path.clone(),
+                    content: new_content.clone(),
+                    timestamp: change.timestamp,
+                    checksum: Self::calculate_checksum(&new_content),
+                };
+                let mut last_good = last_known_good.write().await;
+                last_good.insert(change.file_type.clone(), backup);
+            }
+            Self::broadcast_config_change(change, event_bus).await?;
+            return Ok(());
+        }
+
         // Validate the configuration
-        let validation = Self::validate_config_content(&new_content, &change.file_type).await;
+        let validation = Self::validate_config_content(&new_content, &change.file_type, config_manager).await;
         
         if !validation.valid {
             error!("❌ Configuration validation failed for {:?}", change.file_path);
@@ -581,10 +645,11 @@ impl ConfigWatcher {
         Ok(())
     }
 
-    /// Validate configuration content
+    /// Validate configuration content without applying it
     async fn validate_config_content(
         content: &str,
         file_type: &ConfigFileType,
+        config_manager: &ConfigurationManager,
     ) -> ConfigValidation {
         let mut validation = ConfigValidation {
             valid: true,
@@ -610,17 +675,29 @@ impl ConfigWatcher {
             }
         }
 
-        // File-type specific validation
+        // File-type specific validation (dry run, nothing is applied here)
         match file_type {
             ConfigFileType::Global => {
                 // Validate global orchestrator configuration
-                if let Err(e) = toml::from_str::<OrchestratorConfig>(content) {
-                    validation.warnings.push(format!("Global config structure validation: {}", e));
+                match toml::from_str::<OrchestratorConfig>(content) {
+                    Ok(config) => {
+                        validation.errors.extend(config.validate().iter().map(|v| v.to_string()));
+                    }
+                    Err(e) => {
+                        validation.warnings.push(format!("Global config structure validation: {}", e));
+                    }
                 }
             }
             ConfigFileType::Module(module_id) => {
                 debug!("Validating module configuration for: {}", module_id);
-                // Module-specific validation would go here
+                match Self::parse_module_config(content) {
+                    Ok(config) => {
+                        let report = config_manager.validate_config(*module_id, &config);
+                        validation.errors.extend(report.violations.iter().map(|v| v.to_string()));
+                        validation.warnings.extend(report.warnings);
+                    }
+                    Err(e) => validation.errors.push(e.to_string()),
+                }
             }
             ConfigFileType::Environment => {
                 debug!("Validating environment configuration");
@@ -628,6 +705,7 @@ impl ConfigWatcher {
             }
         }
 
+        validation.valid = validation.errors.is_empty();
         validation
     }
 
@@ -685,6 +763,33 @@ impl ConfigWatcher {
     ) -> OrchestratorResult<()> {
         info!("🔧 Applying configuration changes for module: {}", module_id);
 
+        let config_value = Self::parse_module_config(content)?;
+
+        // Update configuration through the configuration manager
+        config_manager.update_config(module_id, config_value).await?;
+
+        info!("✅ Module configuration updated for: {}", module_id);
+        Ok(())
+    }
+
+    /// Module sections a file configures: the whole file for a module file, the
+    /// module tables of a global file; `None` when there are none or it doesn't parse
+    fn transactional_sections(
+        content: &str,
+        file_type: &ConfigFileType,
+    ) -> Option<HashMap<ModuleId, serde_json::Value>> {
+        let sections = match file_type {
+            ConfigFileType::Module(module_id) => {
+                HashMap::from([(*module_id, Self::parse_module_config(content).ok()?)])
+            }
+            ConfigFileType::Global => module_sections(&Self::parse_module_config(content).ok()?),
+            ConfigFileType::Environment => return None,
+        };
+        (!sections.is_empty()).then_some(sections)
+    }
+
+    /// Parse a module config file (JSON or TOML) into a JSON value
+    fn parse_module_config(content: &str) -> OrchestratorResult<serde_json::Value> {
         // Parse as generic JSON value for flexibility
         let config_value: serde_json::Value = if content.trim().starts_with('{') {
             serde_json::from_str(content)
@@ -707,11 +812,7 @@ impl ConfigWatcher {
                 })?
         };
 
-        // Update configuration through the configuration manager
-        config_manager.update_config(module_id, config_value).await?;
-
-        info!("✅ Module configuration updated for: {}", module_id);
-        Ok(())
+        Ok(config_value)
     }
 
     /// Apply environment-specific configuration changes
@@ -0,0 +1,172 @@
+//! Running as a background daemon
+//!
+//! Detaching re-launches the current executable without a terminal rather than
+//! forking, so it is safe to call after the async runtime has started. The
+//! detached process writes its logs through a size-rotated file; double starts
+//! are kept out by the instance lock.
+
+use crate::error::OrchestratorResult;
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tracing::info;
+
+/// Set in the environment of a process started by `detach`
+pub const DETACHED_ENV: &str = "SKELLY_JELLY_DETACHED";
+
+/// Whether this process is the detached copy started by `detach`
+pub fn is_detached() -> bool {
+    std::env::var_os(DETACHED_ENV).is_some()
+}
+
+/// Start the current executable again in the background with `args`; returns its pid
+pub fn detach(args: impl IntoIterator<Item = OsString>) -> OrchestratorResult<u32> {
+    let executable = std::env::current_exe()?;
+    let mut command = Command::new(executable);
+    command
+        .args(args)
+        .env(DETACHED_ENV, "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Leave the terminal's process group so Ctrl+C and hangups don't reach the daemon
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
+    let child = command.spawn()?;
+    info!("👻 Detached as pid {}", child.id());
+    Ok(child.id())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    // EPERM still means the process exists, just not one of ours
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+        || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        CloseHandle(handle);
+        true
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub(crate) fn process_alive(_pid: u32) -> bool {
+    // No way to tell; assume the recorded instance is alive rather than risk two
+    true
+}
+
+/// Log file that rolls over to `<name>.1`, `<name>.2`, ... once it reaches `max_bytes`
+#[derive(Debug)]
+pub struct RotatingFileWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFileWriter {
+    /// Append to `path`, keeping at most `keep` rotated files beside it
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self { path, max_bytes: max_bytes.max(1), keep, file, written })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated(self.keep));
+            for index in (1..self.keep).rev() {
+                let from = self.rotated(index);
+                if from.exists() {
+                    fs::rename(&from, self.rotated(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_writer_keeps_bounded_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skelly.log");
+        let mut writer = RotatingFileWriter::new(&path, 10, 2).unwrap();
+
+        for line in ["first-line\n", "second-line\n", "third-line\n", "fourth-line\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth-line\n");
+        assert_eq!(fs::read_to_string(dir.path().join("skelly.log.1")).unwrap(), "third-line\n");
+        assert_eq!(fs::read_to_string(dir.path().join("skelly.log.2")).unwrap(), "second-line\n");
+        assert!(!dir.path().join("skelly.log.3").exists());
+    }
+}
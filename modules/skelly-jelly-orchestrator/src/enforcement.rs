@@ -0,0 +1,690 @@
+//! OS-level enforcement of per-module resource ceilings
+//!
+//! `ResourceManager` only observes usage. The enforcer binds a module's
+//! process to an OS mechanism (cgroups v2 on Linux, Job Objects on Windows,
+//! task policy on macOS), publishes `ResourceViolation` events when usage
+//! breaks a ceiling, and escalates throttling while violations persist.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::resource::{ResourceLimits, ResourceUsage};
+use chrono::Utc;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{
+    message::ResourceViolation, BusMessage, EventBusTrait, MessagePayload, MessagePriority, ModuleId,
+};
+use std::{fmt, sync::Arc};
+use tracing::{info, warn};
+
+/// Escalating throttle applied on top of a module's configured ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ThrottleLevel {
+    /// Configured ceiling only
+    None,
+    /// Half the CPU ceiling
+    Reduced,
+    /// A quarter of the CPU ceiling
+    Restricted,
+    /// Process frozen until usage recovers
+    Suspended,
+}
+
+impl ThrottleLevel {
+    pub fn escalate(self) -> Self {
+        match self {
+            ThrottleLevel::None => ThrottleLevel::Reduced,
+            ThrottleLevel::Reduced => ThrottleLevel::Restricted,
+            ThrottleLevel::Restricted | ThrottleLevel::Suspended => ThrottleLevel::Suspended,
+        }
+    }
+
+    pub fn relax(self) -> Self {
+        match self {
+            ThrottleLevel::None | ThrottleLevel::Reduced => ThrottleLevel::None,
+            ThrottleLevel::Restricted => ThrottleLevel::Reduced,
+            ThrottleLevel::Suspended => ThrottleLevel::Restricted,
+        }
+    }
+
+    /// Fraction of the CPU ceiling still granted at this level
+    pub fn cpu_factor(self) -> f32 {
+        match self {
+            ThrottleLevel::None => 1.0,
+            ThrottleLevel::Reduced => 0.5,
+            ThrottleLevel::Restricted => 0.25,
+            ThrottleLevel::Suspended => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for ThrottleLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ThrottleLevel::None => "none",
+            ThrottleLevel::Reduced => "reduced",
+            ThrottleLevel::Restricted => "restricted",
+            ThrottleLevel::Suspended => "suspended",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Platform mechanism that applies ceilings to a module's process
+pub trait EnforcementBackend: Send + Sync {
+    /// Short name for logs and violation events
+    fn name(&self) -> &'static str;
+
+    /// Place the process under the module's ceilings
+    fn attach(&self, module_id: ModuleId, pid: u32, limits: &ResourceLimits) -> OrchestratorResult<()>;
+
+    /// Re-apply the ceilings scaled for a throttle level
+    fn apply_throttle(&self, module_id: ModuleId, limits: &ResourceLimits, level: ThrottleLevel) -> OrchestratorResult<()>;
+
+    /// Lift all ceilings for the module
+    fn detach(&self, module_id: ModuleId) -> OrchestratorResult<()>;
+}
+
+fn backend_error(module_id: ModuleId, backend: &str, reason: impl fmt::Display) -> OrchestratorError {
+    OrchestratorError::SystemResource(format!("{} enforcement for {}: {}", backend, module_id, reason))
+}
+
+/// Linux cgroups v2: one child cgroup per module under a delegated root
+#[cfg(target_os = "linux")]
+pub struct CgroupV2Backend {
+    root: std::path::PathBuf,
+    cpu_count: u32,
+    /// Set once the root cgroup exists with the cpu and memory controllers enabled
+    root_ready: std::sync::atomic::AtomicBool,
+}
+
+#[cfg(target_os = "linux")]
+impl CgroupV2Backend {
+    /// Scheduler period used for `cpu.max`, in microseconds
+    const CPU_PERIOD_US: u64 = 100_000;
+
+    /// Use `root` as the parent cgroup; it must be writable by the orchestrator.
+    /// Nothing is created until the first module is attached
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        let cpu_count = std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(1);
+        Self {
+            root: root.into(),
+            cpu_count,
+            root_ready: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Use the default delegated root if this host runs the unified hierarchy
+    /// with the cpu and memory controllers available
+    pub fn detect() -> Option<Self> {
+        let unified = std::path::Path::new("/sys/fs/cgroup");
+        let controllers = std::fs::read_to_string(unified.join("cgroup.controllers")).ok()?;
+        let available: Vec<&str> = controllers.split_whitespace().collect();
+        if !available.contains(&"cpu") || !available.contains(&"memory") {
+            return None;
+        }
+        Some(Self::new(unified.join("skelly-jelly")))
+    }
+
+    /// Create the root cgroup and delegate controllers to its children
+    fn ensure_root(&self, module_id: ModuleId) -> OrchestratorResult<()> {
+        use std::sync::atomic::Ordering;
+
+        if self.root_ready.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.root)
+            .and_then(|_| std::fs::write(self.root.join("cgroup.subtree_control"), "+cpu +memory"))
+            .map_err(|e| backend_error(module_id, self.name(), format!("preparing {}: {}", self.root.display(), e)))?;
+        self.root_ready.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    fn module_dir(&self, module_id: ModuleId) -> std::path::PathBuf {
+        self.root.join(module_id.to_string())
+    }
+
+    fn write(&self, module_id: ModuleId, file: &str, value: &str) -> OrchestratorResult<()> {
+        std::fs::write(self.module_dir(module_id).join(file), value)
+            .map_err(|e| backend_error(module_id, self.name(), format!("writing {}: {}", file, e)))
+    }
+
+    fn cpu_max(&self, limits: &ResourceLimits, level: ThrottleLevel) -> String {
+        // max_cpu_percent is a share of the whole machine, cpu.max is per period across all CPUs
+        let share = (limits.max_cpu_percent * level.cpu_factor()).max(0.0) as f64 / 100.0;
+        let quota = (share * Self::CPU_PERIOD_US as f64 * self.cpu_count as f64) as u64;
+        // The kernel rejects quotas under 1ms
+        format!("{} {}", quota.max(1_000), Self::CPU_PERIOD_US)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl EnforcementBackend for CgroupV2Backend {
+    fn name(&self) -> &'static str {
+        "cgroups-v2"
+    }
+
+    fn attach(&self, module_id: ModuleId, pid: u32, limits: &ResourceLimits) -> OrchestratorResult<()> {
+        self.ensure_root(module_id)?;
+        std::fs::create_dir_all(self.module_dir(module_id))
+            .map_err(|e| backend_error(module_id, self.name(), e))?;
+
+        let memory_bytes = limits.max_memory_mb as u64 * 1024 * 1024;
+        // memory.high starts reclaim before the hard ceiling triggers the OOM killer
+        self.write(module_id, "memory.high", &(memory_bytes / 10 * 9).to_string())?;
+        self.write(module_id, "memory.max", &memory_bytes.to_string())?;
+        self.write(module_id, "cpu.max", &self.cpu_max(limits, ThrottleLevel::None))?;
+        self.write(module_id, "cgroup.procs", &pid.to_string())
+    }
+
+    fn apply_throttle(&self, module_id: ModuleId, limits: &ResourceLimits, level: ThrottleLevel) -> OrchestratorResult<()> {
+        if level == ThrottleLevel::Suspended {
+            return self.write(module_id, "cgroup.freeze", "1");
+        }
+        self.write(module_id, "cgroup.freeze", "0")?;
+        self.write(module_id, "cpu.max", &self.cpu_max(limits, level))
+    }
+
+    fn detach(&self, module_id: ModuleId) -> OrchestratorResult<()> {
+        self.write(module_id, "cgroup.freeze", "0")?;
+        self.write(module_id, "cpu.max", "max")?;
+        self.write(module_id, "memory.high", "max")?;
+        self.write(module_id, "memory.max", "max")
+    }
+}
+
+/// Windows Job Objects: one job per module with CPU rate and memory caps
+#[cfg(windows)]
+pub struct JobObjectBackend {
+    // Raw HANDLEs stored as isize so the map stays Send + Sync
+    jobs: DashMap<ModuleId, isize>,
+}
+
+#[cfg(windows)]
+impl JobObjectBackend {
+    pub fn new() -> Self {
+        Self { jobs: DashMap::new() }
+    }
+
+    fn set_cpu_rate(&self, module_id: ModuleId, job: isize, percent: f32) -> OrchestratorResult<()> {
+        use windows_sys::Win32::System::JobObjects::*;
+
+        // CpuRate is in 1/100ths of a percent; 1 is the smallest cap Windows accepts
+        let mut info: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = unsafe { std::mem::zeroed() };
+        info.ControlFlags = JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+        info.Anonymous.CpuRate = ((percent * 100.0) as u32).clamp(1, 10_000);
+
+        let ok = unsafe {
+            SetInformationJobObject(
+                job as _,
+                JobObjectCpuRateControlInformation,
+                &info as *const _ as *const _,
+                std::mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+            )
+        };
+        if ok == 0 {
+            return Err(backend_error(module_id, self.name(), std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+impl Default for JobObjectBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(windows)]
+impl EnforcementBackend for JobObjectBackend {
+    fn name(&self) -> &'static str {
+        "job-objects"
+    }
+
+    fn attach(&self, module_id: ModuleId, pid: u32, limits: &ResourceLimits) -> OrchestratorResult<()> {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::JobObjects::*;
+        use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(backend_error(module_id, self.name(), std::io::Error::last_os_error()));
+            }
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            info.ProcessMemoryLimit = limits.max_memory_mb * 1024 * 1024;
+            let limited = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            let assigned = !process.is_null() && AssignProcessToJobObject(job, process) != 0;
+            if !process.is_null() {
+                CloseHandle(process);
+            }
+
+            if limited == 0 || !assigned {
+                let error = std::io::Error::last_os_error();
+                CloseHandle(job);
+                return Err(backend_error(module_id, self.name(), error));
+            }
+
+            self.jobs.insert(module_id, job as isize);
+            self.set_cpu_rate(module_id, job as isize, limits.max_cpu_percent)
+        }
+    }
+
+    fn apply_throttle(&self, module_id: ModuleId, limits: &ResourceLimits, level: ThrottleLevel) -> OrchestratorResult<()> {
+        let job = *self.jobs.get(&module_id)
+            .ok_or_else(|| backend_error(module_id, self.name(), "module is not attached"))?;
+        // Jobs can't be frozen; the minimum hard cap is the closest equivalent
+        self.set_cpu_rate(module_id, job, limits.max_cpu_percent * level.cpu_factor())
+    }
+
+    fn detach(&self, module_id: ModuleId) -> OrchestratorResult<()> {
+        if let Some((_, job)) = self.jobs.remove(&module_id) {
+            // Closing the last handle releases the limits; processes keep running
+            unsafe { windows_sys::Win32::Foundation::CloseHandle(job as _) };
+        }
+        Ok(())
+    }
+}
+
+/// macOS task policy: background QoS for throttling, SIGSTOP for suspension
+///
+/// macOS has no per-process hard memory ceiling, so memory is enforced
+/// through violation events and throttle escalation only.
+#[cfg(target_os = "macos")]
+pub struct TaskPolicyBackend {
+    pids: DashMap<ModuleId, u32>,
+}
+
+#[cfg(target_os = "macos")]
+impl TaskPolicyBackend {
+    pub fn new() -> Self {
+        Self { pids: DashMap::new() }
+    }
+
+    fn pid(&self, module_id: ModuleId) -> OrchestratorResult<u32> {
+        self.pids.get(&module_id)
+            .map(|pid| *pid)
+            .ok_or_else(|| backend_error(module_id, self.name(), "module is not attached"))
+    }
+
+    fn set_background(&self, module_id: ModuleId, pid: u32, background: bool) -> OrchestratorResult<()> {
+        let priority = if background { libc::PRIO_DARWIN_BG } else { 0 };
+        if unsafe { libc::setpriority(libc::PRIO_DARWIN_PROCESS, pid as libc::id_t, priority) } != 0 {
+            return Err(backend_error(module_id, self.name(), std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    fn signal(&self, module_id: ModuleId, pid: u32, signal: libc::c_int) -> OrchestratorResult<()> {
+        if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+            return Err(backend_error(module_id, self.name(), std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Default for TaskPolicyBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl EnforcementBackend for TaskPolicyBackend {
+    fn name(&self) -> &'static str {
+        "task-policy"
+    }
+
+    fn attach(&self, module_id: ModuleId, pid: u32, _limits: &ResourceLimits) -> OrchestratorResult<()> {
+        self.pids.insert(module_id, pid);
+        Ok(())
+    }
+
+    fn apply_throttle(&self, module_id: ModuleId, _limits: &ResourceLimits, level: ThrottleLevel) -> OrchestratorResult<()> {
+        let pid = self.pid(module_id)?;
+        match level {
+            ThrottleLevel::Suspended => self.signal(module_id, pid, libc::SIGSTOP),
+            level => {
+                self.signal(module_id, pid, libc::SIGCONT)?;
+                self.set_background(module_id, pid, level != ThrottleLevel::None)
+            }
+        }
+    }
+
+    fn detach(&self, module_id: ModuleId) -> OrchestratorResult<()> {
+        if let Some((_, pid)) = self.pids.remove(&module_id) {
+            self.signal(module_id, pid, libc::SIGCONT)?;
+            self.set_background(module_id, pid, false)?;
+        }
+        Ok(())
+    }
+}
+
+/// Best available backend for the current platform, if any
+pub fn platform_backend() -> Option<Arc<dyn EnforcementBackend>> {
+    #[cfg(target_os = "linux")]
+    {
+        CgroupV2Backend::detect().map(|backend| Arc::new(backend) as Arc<dyn EnforcementBackend>)
+    }
+    #[cfg(windows)]
+    {
+        Some(Arc::new(JobObjectBackend::new()))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Some(Arc::new(TaskPolicyBackend::new()))
+    }
+    #[cfg(not(any(target_os = "linux", windows, target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Escalation thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnforcementConfig {
+    /// Consecutive violating checks before the throttle tightens a step
+    pub escalate_after: u32,
+    /// Consecutive clean checks before the throttle loosens a step
+    pub relax_after: u32,
+}
+
+impl Default for EnforcementConfig {
+    fn default() -> Self {
+        Self {
+            escalate_after: 2,
+            relax_after: 3,
+        }
+    }
+}
+
+/// Per-module escalation state
+#[derive(Debug, Clone)]
+struct EnforcementState {
+    pid: Option<u32>,
+    limits: Option<ResourceLimits>,
+    level: ThrottleLevel,
+    violating_checks: u32,
+    clean_checks: u32,
+}
+
+impl EnforcementState {
+    fn new(pid: Option<u32>, limits: Option<ResourceLimits>) -> Self {
+        Self { pid, limits, level: ThrottleLevel::None, violating_checks: 0, clean_checks: 0 }
+    }
+}
+
+/// Applies ceilings through a backend and escalates throttling on repeated violations
+pub struct ResourceEnforcer {
+    backend: Option<Arc<dyn EnforcementBackend>>,
+    event_bus: Arc<dyn EventBusTrait>,
+    config: EnforcementConfig,
+    states: DashMap<ModuleId, EnforcementState>,
+}
+
+impl ResourceEnforcer {
+    /// Without a backend the enforcer still publishes violations but can't throttle
+    pub fn new(
+        backend: Option<Arc<dyn EnforcementBackend>>,
+        event_bus: Arc<dyn EventBusTrait>,
+        config: EnforcementConfig,
+    ) -> Self {
+        match &backend {
+            Some(backend) => info!("🛡️  Resource enforcement via {}", backend.name()),
+            None => warn!("⚠️  No OS enforcement backend available, resource limits are advisory"),
+        }
+
+        Self {
+            backend,
+            event_bus,
+            config,
+            states: DashMap::new(),
+        }
+    }
+
+    /// Name of the active backend, if any
+    pub fn backend_name(&self) -> Option<&'static str> {
+        self.backend.as_ref().map(|backend| backend.name())
+    }
+
+    /// Bind a module's process to its ceilings
+    pub fn attach(&self, module_id: ModuleId, pid: u32, limits: &ResourceLimits) -> OrchestratorResult<()> {
+        if let Some(backend) = &self.backend {
+            backend.attach(module_id, pid, limits)?;
+        }
+        self.states.insert(module_id, EnforcementState::new(Some(pid), Some(limits.clone())));
+        info!("Attached {} (pid {}) to resource enforcement", module_id, pid);
+        Ok(())
+    }
+
+    /// Release a module's process from enforcement
+    pub fn detach(&self, module_id: ModuleId) -> OrchestratorResult<()> {
+        if let Some((_, state)) = self.states.remove(&module_id) {
+            if let (Some(backend), Some(_)) = (&self.backend, state.pid) {
+                backend.detach(module_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a module's own process is under enforcement
+    pub fn is_attached(&self, module_id: ModuleId) -> bool {
+        self.states.get(&module_id).is_some_and(|state| state.pid.is_some())
+    }
+
+    /// Attached modules with their process id and the ceilings they were attached with
+    pub fn attached(&self) -> Vec<(ModuleId, u32, ResourceLimits)> {
+        self.states
+            .iter()
+            .filter_map(|entry| match (entry.pid, &entry.limits) {
+                (Some(pid), Some(limits)) => Some((*entry.key(), pid, limits.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Current throttle level for a module
+    pub fn throttle_level(&self, module_id: ModuleId) -> ThrottleLevel {
+        self.states.get(&module_id).map(|state| state.level).unwrap_or(ThrottleLevel::None)
+    }
+
+    /// Check one usage sample; publishes violations and moves the throttle level
+    pub async fn evaluate(
+        &self,
+        module_id: ModuleId,
+        usage: &ResourceUsage,
+        limits: &ResourceLimits,
+    ) -> OrchestratorResult<ThrottleLevel> {
+        let violations = Self::violations(usage, limits);
+
+        let (previous, level, pid) = {
+            let mut state = self.states.entry(module_id).or_insert_with(|| EnforcementState::new(None, None));
+            let previous = state.level;
+
+            if violations.is_empty() {
+                state.violating_checks = 0;
+                state.clean_checks += 1;
+                if state.level != ThrottleLevel::None && state.clean_checks >= self.config.relax_after {
+                    state.level = state.level.relax();
+                    state.clean_checks = 0;
+                }
+            } else {
+                state.clean_checks = 0;
+                state.violating_checks += 1;
+                if state.violating_checks >= self.config.escalate_after {
+                    state.level = state.level.escalate();
+                    state.violating_checks = 0;
+                }
+            }
+
+            (previous, state.level, state.pid)
+        };
+
+        for (resource, observed, limit) in &violations {
+            let violation = ResourceViolation {
+                module: module_id,
+                resource: resource.to_string(),
+                observed: *observed,
+                limit: *limit,
+                throttle_level: level.to_string(),
+                timestamp: Utc::now(),
+            };
+            let message = BusMessage::with_priority(
+                ModuleId::Orchestrator,
+                MessagePayload::ResourceViolation(violation),
+                MessagePriority::High,
+            );
+            self.event_bus.publish(message).await?;
+        }
+
+        if level != previous {
+            if level > previous {
+                warn!("🔻 Throttling {} {} → {}", module_id, previous, level);
+            } else {
+                info!("🔺 Easing throttle on {} {} → {}", module_id, previous, level);
+            }
+            if let (Some(backend), Some(_)) = (&self.backend, pid) {
+                backend.apply_throttle(module_id, limits, level)?;
+            }
+        }
+
+        Ok(level)
+    }
+
+    /// (resource, observed, limit) for every exceeded ceiling
+    fn violations(usage: &ResourceUsage, limits: &ResourceLimits) -> Vec<(&'static str, f64, f64)> {
+        let mut violations = Vec::new();
+        if usage.cpu_percent > limits.max_cpu_percent {
+            violations.push(("cpu_percent", usage.cpu_percent as f64, limits.max_cpu_percent as f64));
+        }
+        if usage.memory_mb > limits.max_memory_mb {
+            violations.push(("memory_mb", usage.memory_mb as f64, limits.max_memory_mb as f64));
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_event_bus::create_event_bus;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        calls: Mutex<Vec<(ModuleId, ThrottleLevel)>>,
+    }
+
+    impl EnforcementBackend for RecordingBackend {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn attach(&self, _module_id: ModuleId, _pid: u32, _limits: &ResourceLimits) -> OrchestratorResult<()> {
+            Ok(())
+        }
+
+        fn apply_throttle(&self, module_id: ModuleId, _limits: &ResourceLimits, level: ThrottleLevel) -> OrchestratorResult<()> {
+            self.calls.lock().unwrap().push((module_id, level));
+            Ok(())
+        }
+
+        fn detach(&self, _module_id: ModuleId) -> OrchestratorResult<()> {
+            Ok(())
+        }
+    }
+
+    fn usage(cpu_percent: f32, memory_mb: usize) -> ResourceUsage {
+        ResourceUsage {
+            cpu_percent,
+            memory_mb,
+            file_handles: 0,
+            threads: 1,
+            battery_impact: 0.0,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_escalates_and_relaxes() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let backend = Arc::new(RecordingBackend::default());
+        let enforcer = ResourceEnforcer::new(Some(backend.clone()), bus, EnforcementConfig::default());
+        let limits = ResourceLimits::new(10.0, 256);
+        enforcer.attach(ModuleId::AnalysisEngine, 4242, &limits).unwrap();
+
+        let hot = usage(40.0, 100);
+        assert_eq!(enforcer.evaluate(ModuleId::AnalysisEngine, &hot, &limits).await.unwrap(), ThrottleLevel::None);
+        assert_eq!(enforcer.evaluate(ModuleId::AnalysisEngine, &hot, &limits).await.unwrap(), ThrottleLevel::Reduced);
+        enforcer.evaluate(ModuleId::AnalysisEngine, &hot, &limits).await.unwrap();
+        assert_eq!(enforcer.evaluate(ModuleId::AnalysisEngine, &hot, &limits).await.unwrap(), ThrottleLevel::Restricted);
+
+        let calm = usage(5.0, 100);
+        for _ in 0..3 {
+            enforcer.evaluate(ModuleId::AnalysisEngine, &calm, &limits).await.unwrap();
+        }
+        assert_eq!(enforcer.throttle_level(ModuleId::AnalysisEngine), ThrottleLevel::Reduced);
+
+        let calls = backend.calls.lock().unwrap().clone();
+        assert_eq!(calls, vec![
+            (ModuleId::AnalysisEngine, ThrottleLevel::Reduced),
+            (ModuleId::AnalysisEngine, ThrottleLevel::Restricted),
+            (ModuleId::AnalysisEngine, ThrottleLevel::Reduced),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_violations_published_without_backend() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+
+        let enforcer = ResourceEnforcer::new(None, bus.clone(), EnforcementConfig::default());
+        let limits = ResourceLimits::new(10.0, 256);
+
+        enforcer.evaluate(ModuleId::Storage, &usage(5.0, 100), &limits).await.unwrap();
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 0);
+
+        // Over on both CPU and memory: one event per resource, level unchanged until escalate_after
+        let level = enforcer.evaluate(ModuleId::Storage, &usage(50.0, 512), &limits).await.unwrap();
+        assert_eq!(level, ThrottleLevel::None);
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 2);
+        assert_eq!(enforcer.backend_name(), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cgroup_backend_writes_ceilings() {
+        let parent = tempfile::tempdir().unwrap();
+        let root = parent.path().join("skelly-jelly");
+        let backend = CgroupV2Backend::new(&root);
+        let limits = ResourceLimits::new(25.0, 512);
+        // Constructing the backend touches nothing
+        assert!(!root.exists());
+
+        backend.attach(ModuleId::Storage, 1234, &limits).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("cgroup.subtree_control")).unwrap(), "+cpu +memory");
+        let dir = root.join("storage");
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).unwrap();
+        assert_eq!(read("memory.max"), (512u64 * 1024 * 1024).to_string());
+        assert_eq!(read("cgroup.procs"), "1234");
+        assert_eq!(read("cpu.max"), backend.cpu_max(&limits, ThrottleLevel::None));
+
+        backend.apply_throttle(ModuleId::Storage, &limits, ThrottleLevel::Suspended).unwrap();
+        assert_eq!(read("cgroup.freeze"), "1");
+
+        backend.detach(ModuleId::Storage).unwrap();
+        assert_eq!(read("cgroup.freeze"), "0");
+        assert_eq!(read("memory.max"), "max");
+    }
+}
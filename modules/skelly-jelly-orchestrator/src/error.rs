@@ -1,5 +1,6 @@
 //! Error types for the orchestrator module
 
+use crate::config_schema::SchemaViolation;
 use skelly_jelly_event_bus::{EventBusError, ModuleId};
 use thiserror::Error;
 
@@ -41,6 +42,12 @@ pub enum OrchestratorError {
         reason: String,
     },
 
+    #[error("Invalid configuration for {module}: {}", format_violations(.violations))]
+    InvalidConfig {
+        module: ModuleId,
+        violations: Vec<SchemaViolation>,
+    },
+
     #[error("Health check failed for {module}: {reason}")]
     HealthCheckFailed {
         module: ModuleId,
@@ -69,6 +76,47 @@ pub enum OrchestratorError {
         reason: String,
     },
 
+    #[error("Secret {name} unavailable: {reason}")]
+    SecretUnavailable {
+        name: String,
+        reason: String,
+    },
+
+    #[error(
+        "Another instance is already running ({}, lock {}); stop it or start with --takeover",
+        .pid.map_or_else(|| "pid unknown".to_string(), |pid| format!("pid {}", pid)),
+        .lock_file.display()
+    )]
+    InstanceRunning {
+        pid: Option<u32>,
+        lock_file: std::path::PathBuf,
+    },
+
+    #[error(
+        "Could not take over from the running instance ({}): {reason}",
+        .pid.map_or_else(|| "pid unknown".to_string(), |pid| format!("pid {}", pid))
+    )]
+    TakeoverFailed {
+        pid: Option<u32>,
+        reason: String,
+    },
+
+    #[error("Cannot install as a service: {reason}")]
+    ServiceUnsupported {
+        reason: String,
+    },
+
+    #[error("Setup cannot finish yet: {step} is unanswered")]
+    SetupIncomplete {
+        step: String,
+    },
+
+    #[error("Setup {step}: {reason}")]
+    SetupInvalid {
+        step: String,
+        reason: String,
+    },
+
     #[error("File system error: {0}")]
     FileSystem(#[from] std::io::Error),
 
@@ -80,4 +128,8 @@ pub enum OrchestratorError {
 
     #[error("Internal error: {0}")]
     Internal(#[from] anyhow::Error),
-}
\ No newline at end of file
+}
+
+fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("; ")
+}
@@ -1,8 +1,13 @@
 //! Event loss prevention system for maintaining <0.1% event loss rate
 
-use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::{
+    error::{OrchestratorError, OrchestratorResult},
+    sequencing::{DeliverySequencer, StreamStatistics},
+};
 use dashmap::DashMap;
-use skelly_jelly_event_bus::{ModuleId, MessageId, BusMessage};
+use skelly_jelly_event_bus::{
+    message::DeliveryAck, BusMessage, EventBusTrait, MessageId, MessagePayload, MessagePriority, ModuleId,
+};
 use anyhow;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -41,6 +46,12 @@ pub struct EventLossPreventionSystem {
     /// Graceful degradation manager
     degradation_manager: Arc<GracefulDegradationManager>,
     
+    /// Per-stream sequence numbers, acks, and gap detection
+    sequencer: Arc<DeliverySequencer>,
+    
+    /// Bus used to request retransmissions from Storage
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+    
     /// Background monitoring task
     monitoring_task: Option<JoinHandle<()>>,
     
@@ -57,6 +68,12 @@ pub struct EventLossPreventionConfig {
     pub critical_mark: f32,
     pub target_loss_rate: f32,
     pub emergency_threshold: f32,
+    /// How long a sequence gap may stay open before it is treated as loss (allows for reordering)
+    pub gap_grace_period: Duration,
+    /// How long to wait for a retransmission before requesting it again
+    pub retransmit_timeout: Duration,
+    /// Retransmission requests per gap before its events count as lost
+    pub max_retransmit_attempts: u32,
 }
 
 impl Default for EventLossPreventionConfig {
@@ -69,6 +86,9 @@ impl Default for EventLossPreventionConfig {
             critical_mark: QUEUE_CRITICAL_MARK,
             target_loss_rate: TARGET_EVENT_LOSS_RATE,
             emergency_threshold: 0.01, // 1% loss rate triggers emergency
+            gap_grace_period: Duration::from_millis(500),
+            retransmit_timeout: Duration::from_secs(2),
+            max_retransmit_attempts: 3,
         }
     }
 }
@@ -564,11 +584,23 @@ impl EventLossPreventionSystem {
             loss_tracker: Arc::new(EventLossTracker::new()),
             emergency_circuit_breaker: Arc::new(EmergencyCircuitBreaker::new(CircuitBreakerConfig::default())),
             degradation_manager: Arc::new(GracefulDegradationManager::new()),
+            sequencer: Arc::new(DeliverySequencer::new(
+                config.gap_grace_period,
+                config.retransmit_timeout,
+                config.max_retransmit_attempts,
+            )),
+            event_bus: None,
             monitoring_task: None,
             config,
         }
     }
 
+    /// Publish retransmission requests for detected gaps on this bus
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Start the event loss prevention system
     pub async fn start(&mut self) -> OrchestratorResult<()> {
         if !self.config.enabled {
@@ -582,6 +614,8 @@ impl EventLossPreventionSystem {
         let queue_monitors = Arc::clone(&self.queue_monitors);
         let loss_tracker = Arc::clone(&self.loss_tracker);
         let degradation_manager = Arc::clone(&self.degradation_manager);
+        let sequencer = Arc::clone(&self.sequencer);
+        let event_bus = self.event_bus.clone();
         let monitoring_interval = self.config.monitoring_interval;
         let target_loss_rate = self.config.target_loss_rate;
 
@@ -621,6 +655,24 @@ impl EventLossPreventionSystem {
                 if avg_pressure > 0.7 {
                     warn!("Average queue pressure ({:.1}%) is high", avg_pressure * 100.0);
                 }
+                
+                // Ask Storage to resend sequence gaps that outlived the grace period
+                for request in sequencer.detect_gaps() {
+                    debug!(
+                        "Requesting retransmission of {}..={} from {} to {} (attempt {})",
+                        request.from_sequence, request.to_sequence, request.producer, request.consumer, request.attempt
+                    );
+                    if let Some(event_bus) = &event_bus {
+                        let message = BusMessage::with_priority(
+                            ModuleId::Orchestrator,
+                            MessagePayload::RetransmitRequest(request),
+                            MessagePriority::High,
+                        );
+                        if let Err(e) = event_bus.publish(message).await {
+                            warn!("Failed to publish retransmission request: {}", e);
+                        }
+                    }
+                }
             }
         });
 
@@ -679,6 +731,17 @@ impl EventLossPreventionSystem {
         }
     }
 
+    /// Stamp a message with the next sequence number on its stream to `consumer`
+    pub fn stamp_sequence(&self, message: BusMessage, consumer: ModuleId) -> BusMessage {
+        self.sequencer.stamp(message, consumer)
+    }
+
+    /// Record a consumer's acknowledgement of a range of sequence numbers
+    pub fn acknowledge(&self, ack: &DeliveryAck) {
+        self.sequencer
+            .acknowledge(ack.producer, ack.consumer, ack.from_sequence..=ack.to_sequence);
+    }
+
     /// Get current event loss statistics
     pub async fn get_loss_statistics(&self) -> EventLossStatistics {
         let overall_loss_rate = self.loss_tracker.calculate_loss_rate().await;
@@ -709,6 +772,7 @@ impl EventLossPreventionSystem {
             dropped_events,
             meets_target: overall_loss_rate < self.config.target_loss_rate,
             module_statistics: module_stats,
+            stream_statistics: self.sequencer.statistics(),
             degradation_level,
             circuit_breaker_state,
             last_updated: Utc::now(),
@@ -724,6 +788,8 @@ pub struct EventLossStatistics {
     pub dropped_events: u64,
     pub meets_target: bool,
     pub module_statistics: HashMap<ModuleId, ModuleStatistics>,
+    /// Sequenced delivery per producer → consumer pair
+    pub stream_statistics: Vec<StreamStatistics>,
     pub degradation_level: DegradationLevel,
     pub circuit_breaker_state: CircuitBreakerState,
     pub last_updated: DateTime<Utc>,
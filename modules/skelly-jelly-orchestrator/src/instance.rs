@@ -0,0 +1,308 @@
+//! Single-instance guard and takeover
+//!
+//! Two instances capturing into the same data directory corrupt each other's
+//! storage, so `run` holds an OS file lock on `<data-dir>/skelly-jelly.lock`
+//! for as long as it runs. The kernel drops the lock when the process dies,
+//! so a crash never leaves a stale lock behind. Where the file system can't
+//! lock, the recorded pid is checked for liveness instead.
+//!
+//! The holder's pid and admin API address and token sit in a JSON file
+//! beside the lock (Windows locks are mandatory, so others couldn't read the
+//! lock file itself). A new instance started with `--takeover` uses them to
+//! ask the old one to shut down over `POST /shutdown`, then waits for the
+//! lock to come free.
+//!
+//! A pidfile for service managers is written only once the lock is held, so it
+//! never decides who runs and a takeover simply overwrites the old one.
+
+use crate::daemon::process_alive;
+use crate::error::{OrchestratorError, OrchestratorResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tracing::{info, warn};
+
+/// How often a takeover checks whether the old instance has let go
+const TAKEOVER_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What a running instance publishes about itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    /// Admin API of the instance, if it serves one
+    pub admin_address: Option<SocketAddr>,
+    /// Bearer token for the admin API's POST endpoints
+    pub admin_token: Option<String>,
+}
+
+/// Exclusive lock on a data directory, released when dropped
+#[derive(Debug)]
+pub struct InstanceLock {
+    /// Held open for the lock; closing it releases the lock
+    _file: File,
+    path: PathBuf,
+    info: InstanceInfo,
+    /// Pidfile written for service managers, removed with the lock
+    pidfile: Option<PathBuf>,
+}
+
+impl InstanceLock {
+    /// Lock `path`, failing with `InstanceRunning` if a live instance holds it
+    pub fn acquire(path: impl Into<PathBuf>) -> OrchestratorResult<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path)?;
+        let previous = Self::holder(&path);
+
+        match file.try_lock() {
+            Ok(()) => {
+                if let Some(previous) = previous.filter(|previous| previous.pid != std::process::id()) {
+                    warn!("Instance pid {} exited without releasing {}", previous.pid, path.display());
+                }
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(OrchestratorError::InstanceRunning {
+                    pid: previous.map(|previous| previous.pid),
+                    lock_file: path,
+                });
+            }
+            Err(TryLockError::Error(e)) if e.kind() == io::ErrorKind::Unsupported => {
+                // No locking on this file system (e.g. some network mounts); fall back to the pid
+                warn!("File locking unsupported for {}, checking the recorded pid instead", path.display());
+                if let Some(previous) = previous {
+                    if previous.pid != std::process::id() && process_alive(previous.pid) {
+                        return Err(OrchestratorError::InstanceRunning { pid: Some(previous.pid), lock_file: path });
+                    }
+                }
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        let lock = Self {
+            _file: file,
+            path,
+            info: InstanceInfo {
+                pid: std::process::id(),
+                started_at: Utc::now(),
+                admin_address: None,
+                admin_token: None,
+            },
+            pidfile: None,
+        };
+        lock.publish()?;
+        Ok(lock)
+    }
+
+    /// Lock `path`, asking the instance holding it to shut down first
+    ///
+    /// Fails with `TakeoverFailed` if the holder has no admin API, refuses the
+    /// request, or still holds the lock after `timeout`.
+    pub async fn takeover(path: impl Into<PathBuf>, timeout: Duration) -> OrchestratorResult<Self> {
+        let path = path.into();
+        let pid = match Self::acquire(&path) {
+            Err(OrchestratorError::InstanceRunning { pid, .. }) => pid,
+            acquired => return acquired,
+        };
+        let failed = |reason: String| OrchestratorError::TakeoverFailed { pid, reason };
+
+        let holder = Self::holder(&path).ok_or_else(|| failed("it hasn't published its details yet".to_string()))?;
+        let (Some(address), Some(token)) = (holder.admin_address, holder.admin_token) else {
+            return Err(failed("it runs without the admin API; stop it manually".to_string()));
+        };
+
+        info!("🔁 Asking instance pid {} to shut down", holder.pid);
+        let status = request_shutdown(address, &token)
+            .await
+            .map_err(|e| failed(format!("admin API at {} unreachable: {}", address, e)))?;
+        if status != 200 {
+            return Err(failed(format!("admin API answered {}", status)));
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            tokio::time::sleep(TAKEOVER_POLL_INTERVAL).await;
+            match Self::acquire(&path) {
+                Err(OrchestratorError::InstanceRunning { .. }) if Instant::now() < deadline => continue,
+                Err(OrchestratorError::InstanceRunning { .. }) => {
+                    return Err(failed(format!("still running after {:?}", timeout)));
+                }
+                acquired => {
+                    info!("Took over from instance pid {}", holder.pid);
+                    return acquired;
+                }
+            }
+        }
+    }
+
+    /// Details published by whoever holds or last held the lock at `path`
+    pub fn holder(path: &Path) -> Option<InstanceInfo> {
+        serde_json::from_str(&fs::read_to_string(Self::info_path(path)).ok()?).ok()
+    }
+
+    /// Publish the admin API so a later `--takeover` can reach this instance
+    pub fn advertise_admin_api(&mut self, address: SocketAddr, token: &str) -> OrchestratorResult<()> {
+        self.info.admin_address = Some(address);
+        self.info.admin_token = Some(token.to_string());
+        self.publish()
+    }
+
+    /// Record our pid in `path` for service managers; the lock, not the pidfile, keeps instances apart
+    pub fn write_pidfile(&mut self, path: impl Into<PathBuf>) -> OrchestratorResult<()> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, format!("{}\n", self.info.pid))?;
+        self.pidfile = Some(path);
+        Ok(())
+    }
+
+    /// The pid recorded in a pidfile, if it holds one
+    pub fn read_pidfile(path: &Path) -> Option<u32> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    pub fn info(&self) -> &InstanceInfo {
+        &self.info
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn info_path(path: &Path) -> PathBuf {
+        path.with_extension("json")
+    }
+
+    fn publish(&self) -> OrchestratorResult<()> {
+        let mut options = OpenOptions::new();
+        options.create(true).truncate(true).write(true);
+        // Holds the admin token, so only the user may read it
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(Self::info_path(&self.path))?;
+        file.write_all(serde_json::to_string_pretty(&self.info)?.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if Self::holder(&self.path).is_some_and(|holder| holder.pid == self.info.pid) {
+            let _ = fs::remove_file(Self::info_path(&self.path));
+        }
+        // A takeover may already have written its own pid there
+        if let Some(pidfile) = self.pidfile.as_ref().filter(|pidfile| Self::read_pidfile(pidfile) == Some(self.info.pid)) {
+            let _ = fs::remove_file(pidfile);
+        }
+    }
+}
+
+/// POST `/shutdown` to an admin API, returning the HTTP status
+async fn request_shutdown(address: SocketAddr, token: &str) -> io::Result<u16> {
+    let mut stream = TcpStream::connect(address).await?;
+    let request = format!(
+        "POST /shutdown HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        token
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_instance_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skelly-jelly.lock");
+
+        let first = InstanceLock::acquire(&path).unwrap();
+        match InstanceLock::acquire(&path) {
+            Err(OrchestratorError::InstanceRunning { pid, lock_file }) => {
+                assert_eq!(pid, Some(std::process::id()));
+                assert_eq!(lock_file, path);
+            }
+            other => panic!("expected InstanceRunning, got {:?}", other),
+        }
+
+        drop(first);
+        assert!(InstanceLock::holder(&path).is_none());
+        let second = InstanceLock::acquire(&path).unwrap();
+        assert_eq!(second.info().pid, std::process::id());
+    }
+
+    #[test]
+    fn test_pidfile_written_after_lock_and_removed_with_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skelly-jelly.lock");
+        let pidfile = dir.path().join("run").join("skelly-jelly.pid");
+        // Left behind by an instance that was killed; the lock, not this, decides
+        fs::create_dir_all(pidfile.parent().unwrap()).unwrap();
+        fs::write(&pidfile, "4000000000\n").unwrap();
+
+        let mut lock = InstanceLock::acquire(&path).unwrap();
+        lock.write_pidfile(&pidfile).unwrap();
+        assert_eq!(InstanceLock::read_pidfile(&pidfile), Some(std::process::id()));
+
+        drop(lock);
+        assert!(!pidfile.exists());
+    }
+
+    #[tokio::test]
+    async fn test_takeover_asks_holder_to_shut_down() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skelly-jelly.lock");
+        let mut old = InstanceLock::acquire(&path).unwrap();
+
+        // Without an admin API there is nobody to ask
+        let refused = InstanceLock::takeover(&path, Duration::from_secs(1)).await;
+        assert!(matches!(refused, Err(OrchestratorError::TakeoverFailed { .. })));
+
+        // Stand-in admin API that lets go of the lock once asked
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        old.advertise_admin_api(listener.local_addr().unwrap(), "secret").unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let read = stream.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").await.unwrap();
+            drop(stream);
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            drop(old);
+            request
+        });
+
+        let lock = InstanceLock::takeover(&path, Duration::from_secs(5)).await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /shutdown"));
+        assert!(request.contains("Authorization: Bearer secret"));
+        assert_eq!(InstanceLock::holder(&path), Some(lock.info().clone()));
+    }
+}
@@ -5,6 +5,8 @@
 //! and resource coordination.
 
 pub mod config;
+pub mod config_schema;
+pub mod config_templates;
 pub mod error;
 pub mod health;
 pub mod lifecycle;
@@ -12,29 +14,73 @@ pub mod module_registry;
 pub mod orchestrator;
 pub mod recovery;
 pub mod resource;
+pub mod enforcement;
 pub mod startup;
 pub mod enhanced_health;
 pub mod config_watcher;
+pub mod config_transaction;
 pub mod performance_telemetry;
+pub mod readiness;
+pub mod profiles;
+pub mod power;
+pub mod sleep_wake;
+pub mod maintenance;
+pub mod safe_mode;
+pub mod secrets;
+pub mod admin_api;
+pub mod daemon;
+pub mod instance;
+pub mod service;
+pub mod setup_wizard;
 pub mod event_loss_prevention;
+pub mod sequencing;
+pub mod user_profiles;
+pub mod privacy_policy;
+pub mod system_map;
+pub mod validation;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
 
 #[cfg(test)]
 pub mod resource_management_integration_test;
 
 // Re-export public API
 pub use config::{ConfigurationManager, OrchestratorConfig};
+pub use config_schema::{ConfigSchema, FieldKind, FieldSpec, FieldOrdering, SchemaReport, SchemaViolation};
+pub use config_templates::{ConfigTemplate, ConfigTemplates, SystemCapabilities, TemplateCondition, TemplateRule};
 pub use error::{OrchestratorError, OrchestratorResult};
 pub use health::{HealthMonitor, HealthReport, HealthStatus, HealthMetrics};
 pub use lifecycle::{LifecycleController, ModuleState, StopReason};
-pub use module_registry::{ModuleRegistry, ModuleDescriptor, DependencyGraph};
+pub use module_registry::{ModuleRegistry, ModuleDescriptor, DependencyGraph, CriticalPath};
 pub use orchestrator::{Orchestrator, OrchestratorImpl, SystemHealth, SystemStatus};
 pub use recovery::{RecoveryManager, RecoveryStrategy};
+pub use enforcement::{ResourceEnforcer, EnforcementBackend, EnforcementConfig, ThrottleLevel};
 pub use resource::{ResourceManager, ResourceLimits, ResourceAllocations, SystemResources, PerformanceStats, BatteryOptimization};
-pub use performance_telemetry::{PerformanceTelemetrySystem, TelemetryConfig, DashboardData, PerformanceTrends};
+pub use performance_telemetry::{PerformanceTelemetrySystem, TelemetryConfig, DashboardData, PerformanceTrends, LeakDetector};
 pub use event_loss_prevention::{EventLossPreventionSystem, EventLossPreventionConfig, EventLossStatistics};
-pub use startup::{StartupSequencer, StartupMetrics, StartupPhase, StartupBottleneck};
+pub use sequencing::{DeliverySequencer, StreamStatistics};
+pub use startup::{StartupSequencer, StartupMetrics, StartupPhase, StartupBottleneck, StartupLevelTiming};
+pub use readiness::ReadinessGate;
+pub use safe_mode::{CrashLoopDetector, QuarantineRecord};
+pub use secrets::{SecretsBroker, SecretsConfig, SecretStore, KeychainStore, MemorySecretStore, SecretReceiver, SecretValue};
+pub use daemon::RotatingFileWriter;
+pub use instance::{InstanceInfo, InstanceLock};
+pub use service::ServiceSpec;
+pub use setup_wizard::{SetupWizard, SetupStep, SetupAnswer, SetupAnswers, PrivacyChoice};
+pub use system_map::{SystemMap, ModuleNode, GraphFormat};
+pub use validation::{CheckCategory, CheckResult, CheckStatus, ConfigProbe, ModelFile, ModelFileProbe, PortProbe, ReadinessProbe, ReadinessReport};
+#[cfg(feature = "prometheus")]
+pub use prometheus::{MetricsExporter, PrometheusConfig, PrometheusServer, EXPORTED_MESSAGE_TYPES};
+pub use admin_api::{AdminApiServer, AdminApiConfig, AdminBackend, HealthSummary, ModuleStateView, IncidentView};
+pub use maintenance::{MaintenanceScheduler, MaintenanceConfig, MaintenanceWindow};
+pub use power::{PowerManager, PowerPolicy, PowerSample, PowerState, ThermalPressure};
+pub use sleep_wake::{ClockJumpDetector, SleepWakeConfig, SleepWakeCoordinator, SYSTEM_POWER_KEY};
+pub use profiles::{ProfileManager, ProfileConfig, ProfilePreset, ProfileChange, ProfileTrigger, SystemProfile};
+pub use user_profiles::{USER_PROFILE_KEY, DEFAULT_USER_PROFILE, PROFILE_SCOPED_MODULES};
+pub use skelly_jelly_privacy_policy::{PrivacyPolicyConfig, PRIVACY_POLICY_KEY};
 pub use enhanced_health::{EnhancedHealthMonitor, EnhancedHealthReport, EnhancedHealthStatus, EnhancedHealthMetrics, HealthConfig};
 pub use config_watcher::{ConfigWatcher, ConfigChange, HotReloadConfig, ConfigValidation};
+pub use config_transaction::{ConfigApplier, ConfigTransactions};
 
 use async_trait::async_trait;
 use skelly_jelly_event_bus::{EventBusTrait, ModuleId};
@@ -63,6 +109,9 @@ pub trait OrchestratorTrait: Send + Sync {
     
     /// Get module state
     async fn get_module_state(&self, module_id: ModuleId) -> Option<ModuleState>;
+
+    /// Dry run: check configs and registered readiness probes without starting anything
+    async fn validate(&self) -> validation::ReadinessReport;
 }
 
 /// Create a new orchestrator instance
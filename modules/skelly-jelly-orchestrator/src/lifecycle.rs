@@ -3,6 +3,7 @@
 use crate::config::ConfigurationManager;
 use crate::error::{OrchestratorError, OrchestratorResult};
 use crate::module_registry::ModuleRegistry;
+use crate::readiness::ReadinessGate;
 use skelly_jelly_event_bus::{EventBusTrait, ModuleId, BusMessage, MessagePayload};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -37,6 +38,7 @@ pub struct LifecycleController {
     registry: Arc<ModuleRegistry>,
     event_bus: Arc<dyn EventBusTrait>,
     config_manager: Arc<ConfigurationManager>,
+    readiness: Arc<ReadinessGate>,
 }
 
 impl LifecycleController {
@@ -49,9 +51,15 @@ impl LifecycleController {
             registry,
             event_bus,
             config_manager,
+            readiness: Arc::new(ReadinessGate::new()),
         }
     }
 
+    /// Readiness gate opened by `ModuleReady` messages
+    pub fn readiness_gate(&self) -> Arc<ReadinessGate> {
+        Arc::clone(&self.readiness)
+    }
+
     /// Start the entire system
     pub async fn start_system(&self) -> OrchestratorResult<()> {
         info!("Starting system...");
@@ -143,7 +151,9 @@ impl LifecycleController {
             }
         }
 
-        // Set module state to starting
+        // Set module state to starting. The readiness gate stays as it is: a
+        // module that announced itself before we got to it is already up, and
+        // stopping a module closes its gate
         self.registry.set_module_state(
             module_id,
             ModuleState::Starting { since: Instant::now() },
@@ -168,14 +178,9 @@ impl LifecycleController {
                     module_id,
                     ModuleState::Running { since: Instant::now() },
                 );
-                
-                // Publish module ready event
-                let message = BusMessage::new(
-                    ModuleId::Orchestrator,
-                    MessagePayload::ModuleReady(module_id),
-                );
-                let _ = self.event_bus.publish(message).await;
 
+                // Readiness is the module's own `ModuleReady`, which reaches the
+                // gate through the orchestrator's subscription
                 info!("Module {} started successfully", module_id);
                 Ok(())
             }
@@ -206,8 +211,12 @@ impl LifecycleController {
         }
     }
 
-    /// Stop a specific module
+    /// Stop a specific module, stopping its dependents first
     pub async fn stop_module(&self, module_id: ModuleId, timeout_duration: Duration) -> OrchestratorResult<()> {
+        self.stop_module_with(module_id, timeout_duration, true).await
+    }
+
+    pub(crate) async fn stop_module_with(&self, module_id: ModuleId, timeout_duration: Duration, stop_dependents: bool) -> OrchestratorResult<()> {
         info!("Stopping module: {}", module_id);
 
         // Check if module is already stopped
@@ -232,7 +241,11 @@ impl LifecycleController {
         );
 
         // Stop dependent modules first
-        let dependents = self.registry.get_dependents(module_id).await;
+        let dependents = if stop_dependents {
+            self.registry.get_dependents(module_id).await
+        } else {
+            Vec::new()
+        };
         for dependent in dependents {
             if let Some(state) = self.registry.get_module_state(dependent) {
                 match state {
@@ -250,6 +263,7 @@ impl LifecycleController {
 
         match result {
             Ok(Ok(())) => {
+                self.readiness.reset(module_id);
                 self.registry.set_module_state(
                     module_id,
                     ModuleState::Stopped { reason: StopReason::Requested },
@@ -284,7 +298,12 @@ impl LifecycleController {
         }
     }
 
-    /// Restart a specific module
+    /// Restart a specific module without losing its traffic.
+    ///
+    /// Messages for the module are buffered on the bus while it is down and
+    /// replayed to its new subscriptions once it signals ready again.
+    /// Dependents keep running. Delivery is resumed whether or not the restart
+    /// succeeds; whatever the module can't take then is dead-lettered.
     pub async fn restart_module(&self, module_id: ModuleId) -> OrchestratorResult<()> {
         info!("Restarting module: {}", module_id);
 
@@ -295,16 +314,32 @@ impl LifecycleController {
                 reason: "Module not registered".to_string(),
             })?;
 
-        // Stop the module first
-        self.stop_module(module_id, descriptor.shutdown_timeout).await?;
+        let buffer_size = self.config_manager.get_global_config().await.restart_buffer_size;
+        self.event_bus.pause_delivery(module_id, buffer_size).await?;
 
-        // Wait a moment before restart
-        tokio::time::sleep(Duration::from_millis(500)).await;
+        let restarted = async {
+            self.stop_module_with(module_id, descriptor.shutdown_timeout, false).await?;
 
-        // Start the module
-        self.start_module(module_id).await?;
+            // Wait a moment before restart
+            tokio::time::sleep(Duration::from_millis(500)).await;
 
-        info!("Module {} restarted successfully", module_id);
+            // Start the module and wait for it to report ready before replaying
+            self.start_module(module_id).await?;
+            self.readiness.wait_ready(module_id, descriptor.startup_timeout).await
+        }
+        .await;
+
+        // A failed restart must not leave the module's traffic held forever
+        let replay = self.event_bus.resume_delivery(module_id).await;
+        if let Err(e) = restarted {
+            warn!("Restart of {} failed, released its buffered messages: {}", module_id, e);
+            return Err(e);
+        }
+        let replay = replay?;
+        info!(
+            "Module {} restarted successfully, replayed {} buffered messages ({} dropped)",
+            module_id, replay.replayed, replay.dropped
+        );
         Ok(())
     }
 
@@ -312,8 +347,10 @@ impl LifecycleController {
     async fn start_module_impl(&self, module_id: ModuleId) -> OrchestratorResult<()> {
         match module_id {
             ModuleId::EventBus => {
-                // Event bus should already be running since we need it for communication
+                // Event bus should already be running since we need it for communication,
+                // and it can't announce itself over itself
                 debug!("Event bus module start - assuming already running");
+                self.readiness.mark_ready(module_id);
                 Ok(())
             }
             ModuleId::Storage => {
@@ -390,8 +427,8 @@ impl LifecycleController {
         // 1. Load module configuration
         // 2. Spawn the module's main task
         // 3. Store the task handle
-        // 4. Wait for module to signal ready
-        
+        //
+        // The host runs the modules; each announces its own `ModuleReady`
         Ok(())
     }
 }
\ No newline at end of file
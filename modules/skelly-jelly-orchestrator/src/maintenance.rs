@@ -0,0 +1,337 @@
+//! Scheduled maintenance windows
+//!
+//! Runs housekeeping (storage compaction, model retraining, backup, dead
+//! letter cleanup) during user-defined windows such as 3–5am while charging.
+//! Tasks are handed to their modules one at a time over the bus; if the user
+//! comes back, leaves the window, or unplugs, the running task is deferred and
+//! asked for again once the machine is idle inside a window.
+
+use crate::error::OrchestratorResult;
+use crate::power::PowerManager;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{
+    message::{MaintenanceAction, MaintenanceReport, MaintenanceRequest, MaintenanceTask},
+    BusMessage, EventBusTrait, MessagePayload, MessagePriority, ModuleId,
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Raw event types that mean someone is at the keyboard
+const USER_INPUT_EVENTS: [&str; 4] = ["keystroke", "mouse_move", "mouse_click", "window_focus"];
+
+/// Daily time range in which maintenance may run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: NaiveTime,
+    /// May be earlier than `start` for windows spanning midnight
+    pub end: NaiveTime,
+    /// Only run while on mains power
+    #[serde(default)]
+    pub require_charging: bool,
+    /// Run in this order, each at most once per window
+    pub tasks: Vec<MaintenanceTask>,
+}
+
+impl MaintenanceWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Day the window occurrence containing `now` opened on
+    fn opened_on(&self, now: NaiveDateTime) -> NaiveDate {
+        if self.start > self.end && now.time() < self.end {
+            now.date().pred_opt().unwrap_or(now.date())
+        } else {
+            now.date()
+        }
+    }
+
+    fn closes_at(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let opened = self.opened_on(now);
+        let close_day = if self.start > self.end { opened.succ_opt().unwrap_or(opened) } else { opened };
+        close_day.and_time(self.end)
+    }
+}
+
+/// Maintenance scheduling settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+    pub windows: Vec<MaintenanceWindow>,
+    /// Quiet time required before a task is started
+    pub idle_before_start: Duration,
+    pub check_interval: Duration,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            windows: vec![MaintenanceWindow {
+                start: NaiveTime::from_hms_opt(3, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(5, 0, 0).unwrap(),
+                require_charging: true,
+                tasks: MaintenanceTask::ALL.to_vec(),
+            }],
+            idle_before_start: Duration::from_secs(15 * 60),
+            check_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RunningTask {
+    run_id: Uuid,
+    task: MaintenanceTask,
+    window: usize,
+    opened_on: NaiveDate,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    last_activity: Option<NaiveDateTime>,
+    running: Option<RunningTask>,
+    /// Window occurrence each task last finished in
+    finished: HashMap<MaintenanceTask, NaiveDate>,
+}
+
+/// Hands maintenance tasks to modules inside maintenance windows
+pub struct MaintenanceScheduler {
+    config: MaintenanceConfig,
+    event_bus: Arc<dyn EventBusTrait>,
+    state: RwLock<SchedulerState>,
+    scheduler_task: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl MaintenanceScheduler {
+    pub fn new(config: MaintenanceConfig, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self {
+            config,
+            event_bus,
+            state: RwLock::new(SchedulerState::default()),
+            scheduler_task: RwLock::new(None),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Task currently handed to a module, if any
+    pub async fn running_task(&self) -> Option<MaintenanceTask> {
+        self.state.read().await.running.as_ref().map(|running| running.task)
+    }
+
+    /// Check the windows and start, keep, or defer work; returns a task if one was started
+    pub async fn tick(&self, now: NaiveDateTime, charging: bool) -> OrchestratorResult<Option<MaintenanceTask>> {
+        let mut state = self.state.write().await;
+
+        if let Some(running) = state.running.clone() {
+            let window = &self.config.windows[running.window];
+            if !window.contains(now.time()) || (window.require_charging && !charging) {
+                state.running = None;
+                drop(state);
+                self.request(&running, MaintenanceAction::Defer, window.closes_at(now)).await?;
+                info!("🧹 Deferred {}: window closed or power unplugged", running.task);
+            }
+            return Ok(None);
+        }
+
+        if !self.config.enabled || self.user_active(&state, now) {
+            return Ok(None);
+        }
+
+        let next = self.config.windows.iter().enumerate()
+            .filter(|(_, window)| window.contains(now.time()) && (charging || !window.require_charging))
+            .find_map(|(index, window)| {
+                let opened_on = window.opened_on(now);
+                window.tasks.iter()
+                    .find(|task| state.finished.get(task) != Some(&opened_on))
+                    .map(|&task| RunningTask { run_id: Uuid::new_v4(), task, window: index, opened_on })
+            });
+        let Some(running) = next else {
+            return Ok(None);
+        };
+
+        state.running = Some(running.clone());
+        drop(state);
+        let deadline = self.config.windows[running.window].closes_at(now);
+        self.request(&running, MaintenanceAction::Start, deadline).await?;
+        info!("🧹 Started {} on {}", running.task, running.task.module());
+        Ok(Some(running.task))
+    }
+
+    /// Note user input; a running task is deferred straight away
+    pub async fn record_activity(&self, at: NaiveDateTime) -> OrchestratorResult<()> {
+        let running = {
+            let mut state = self.state.write().await;
+            state.last_activity = Some(state.last_activity.map_or(at, |last| last.max(at)));
+            state.running.take()
+        };
+
+        if let Some(running) = running {
+            let deadline = self.config.windows[running.window].closes_at(at);
+            self.request(&running, MaintenanceAction::Defer, deadline).await?;
+            info!("🧹 Deferred {}: user is active", running.task);
+        }
+        Ok(())
+    }
+
+    /// Track user input and completion reports
+    pub async fn handle_message(&self, message: &BusMessage) -> OrchestratorResult<()> {
+        match &message.payload {
+            MessagePayload::RawEvent(event) if USER_INPUT_EVENTS.contains(&event.event_type.as_str()) => {
+                self.record_activity(event.timestamp.with_timezone(&Local).naive_local()).await
+            }
+            MessagePayload::MaintenanceReport(report) => {
+                self.complete(report).await;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Tick every `check_interval`, reading the charging state from the power manager
+    pub async fn start(self: &Arc<Self>, power: Arc<PowerManager>) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let scheduler = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(scheduler.config.check_interval);
+            loop {
+                ticker.tick().await;
+                let charging = power.last_sample().await.charging;
+                if let Err(e) = scheduler.tick(Local::now().naive_local(), charging).await {
+                    warn!("Maintenance scheduling failed: {}", e);
+                }
+            }
+        });
+        *self.scheduler_task.write().await = Some(task);
+    }
+
+    pub async fn stop(&self) {
+        if let Some(task) = self.scheduler_task.write().await.take() {
+            task.abort();
+        }
+    }
+
+    fn user_active(&self, state: &SchedulerState, now: NaiveDateTime) -> bool {
+        let idle = chrono::Duration::from_std(self.config.idle_before_start).unwrap_or_else(|_| chrono::Duration::zero());
+        state.last_activity.is_some_and(|last| now - last < idle)
+    }
+
+    async fn complete(&self, report: &MaintenanceReport) {
+        let mut state = self.state.write().await;
+        let Some(running) = state.running.take_if(|running| running.run_id == report.run_id) else {
+            return;
+        };
+
+        // Failed tasks also count as done so a broken job isn't retried all night
+        state.finished.insert(running.task, running.opened_on);
+        if report.success {
+            info!("🧹 Finished {}", running.task);
+        } else {
+            warn!("Maintenance task {} failed: {}", running.task, report.details.as_deref().unwrap_or("no details"));
+        }
+    }
+
+    async fn request(&self, running: &RunningTask, action: MaintenanceAction, deadline: NaiveDateTime) -> OrchestratorResult<()> {
+        let deadline = Local.from_local_datetime(&deadline)
+            .earliest()
+            .map(|deadline| deadline.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+        let request = MaintenanceRequest {
+            run_id: running.run_id,
+            task: running.task,
+            action,
+            deadline,
+            timestamp: Utc::now(),
+        };
+        let message = BusMessage::with_priority(
+            ModuleId::Orchestrator,
+            MessagePayload::MaintenanceRequest(request),
+            MessagePriority::Low,
+        );
+        self.event_bus.publish(message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_event_bus::create_event_bus;
+
+    /// A time during the night of March 1st to 2nd
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        let day = if hour < 12 { 2 } else { 1 };
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    async fn scheduler(tasks: Vec<MaintenanceTask>) -> (MaintenanceScheduler, Arc<dyn EventBusTrait>) {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let config = MaintenanceConfig {
+            enabled: true,
+            windows: vec![MaintenanceWindow {
+                start: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+                require_charging: true,
+                tasks,
+            }],
+            ..Default::default()
+        };
+        (MaintenanceScheduler::new(config, bus.clone()), bus)
+    }
+
+    fn report(run_id: Uuid, task: MaintenanceTask) -> BusMessage {
+        BusMessage::new(ModuleId::Storage, MessagePayload::MaintenanceReport(MaintenanceReport {
+            run_id,
+            task,
+            success: true,
+            details: None,
+            timestamp: Utc::now(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_runs_tasks_in_order_inside_window() {
+        let (scheduler, bus) = scheduler(vec![MaintenanceTask::StorageCompaction, MaintenanceTask::Backup]).await;
+
+        assert_eq!(scheduler.tick(at(22, 30), true).await.unwrap(), None);
+        assert_eq!(scheduler.tick(at(23, 30), false).await.unwrap(), None);
+        assert_eq!(scheduler.tick(at(23, 30), true).await.unwrap(), Some(MaintenanceTask::StorageCompaction));
+        // One task at a time
+        assert_eq!(scheduler.tick(at(23, 31), true).await.unwrap(), None);
+
+        let run_id = scheduler.state.read().await.running.as_ref().unwrap().run_id;
+        scheduler.handle_message(&report(run_id, MaintenanceTask::StorageCompaction)).await.unwrap();
+        // Past midnight is still the same window occurrence, so compaction isn't repeated
+        assert_eq!(scheduler.tick(at(0, 30), true).await.unwrap(), Some(MaintenanceTask::Backup));
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 2);
+    }
+
+    #[tokio::test]
+    async fn test_user_activity_defers_running_task() {
+        let (scheduler, bus) = scheduler(vec![MaintenanceTask::ModelRetraining]).await;
+        scheduler.tick(at(23, 0), true).await.unwrap();
+        assert_eq!(scheduler.running_task().await, Some(MaintenanceTask::ModelRetraining));
+
+        scheduler.record_activity(at(23, 5)).await.unwrap();
+        assert_eq!(scheduler.running_task().await, None);
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 2);
+
+        // Waits out the idle period, then asks again
+        assert_eq!(scheduler.tick(at(23, 10), true).await.unwrap(), None);
+        assert_eq!(scheduler.tick(at(23, 21), true).await.unwrap(), Some(MaintenanceTask::ModelRetraining));
+    }
+}
@@ -1,11 +1,13 @@
 //! Module registry and dependency graph management
 
+use crate::config_templates::{ConfigTemplates, SystemCapabilities};
 use crate::error::{OrchestratorError, OrchestratorResult};
 use crate::lifecycle::ModuleState;
+use crate::profiles::SystemProfile;
 use dashmap::DashMap;
 use skelly_jelly_event_bus::ModuleId;
-use petgraph::{Graph, Direction};
-use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::Direction;
+use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
 use semver::Version;
@@ -60,16 +62,25 @@ impl ModuleDescriptor {
     }
 }
 
+/// Longest chain of dependent modules, which bounds how fast startup can be
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CriticalPath {
+    /// Modules on the path, dependencies first
+    pub modules: Vec<ModuleId>,
+    /// Sum of the startup times along the path
+    pub duration: Duration,
+}
+
 /// Dependency graph for managing module startup order
 pub struct DependencyGraph {
-    graph: UnGraph<ModuleId, ()>,
+    graph: DiGraph<ModuleId, ()>,
     node_indices: HashMap<ModuleId, NodeIndex>,
 }
 
 impl DependencyGraph {
     pub fn new() -> Self {
         Self {
-            graph: Graph::new_undirected(),
+            graph: DiGraph::new(),
             node_indices: HashMap::new(),
         }
     }
@@ -91,7 +102,7 @@ impl DependencyGraph {
         let dependency_index = self.node_indices[&dependency];
 
         // Add edge from dependency to dependent (dependency must start first)
-        self.graph.add_edge(dependency_index, dependent_index, ());
+        self.graph.update_edge(dependency_index, dependent_index, ());
     }
 
     /// Compute startup order using topological sort
@@ -116,6 +127,90 @@ impl DependencyGraph {
         Ok(result.into_iter().map(|idx| self.graph[idx]).collect())
     }
 
+    /// Group modules into startup levels; modules in the same level don't depend on each other
+    pub fn compute_startup_levels(&self) -> OrchestratorResult<Vec<Vec<ModuleId>>> {
+        let mut in_degree: HashMap<NodeIndex, usize> = self.graph
+            .node_indices()
+            .map(|node| (node, self.graph.edges_directed(node, Direction::Incoming).count()))
+            .collect();
+
+        let mut current: Vec<NodeIndex> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect();
+        let mut levels = Vec::new();
+        let mut placed = 0;
+
+        while !current.is_empty() {
+            // Keep registration order within a level so startup logs are stable
+            current.sort();
+
+            let mut next = Vec::new();
+            for &node in &current {
+                for edge in self.graph.edges_directed(node, Direction::Outgoing) {
+                    let degree = in_degree.get_mut(&edge.target()).expect("every node has an in-degree");
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next.push(edge.target());
+                    }
+                }
+            }
+
+            placed += current.len();
+            levels.push(current.iter().map(|&node| self.graph[node]).collect());
+            current = next;
+        }
+
+        if placed < self.graph.node_count() {
+            let cycle = in_degree
+                .into_iter()
+                .filter(|(_, degree)| *degree > 0)
+                .map(|(node, _)| self.graph[node])
+                .collect();
+            return Err(OrchestratorError::DependencyCycle { cycle });
+        }
+
+        Ok(levels)
+    }
+
+    /// Find the slowest dependency chain given how long each module took to start
+    pub fn critical_path(&self, durations: &HashMap<ModuleId, Duration>) -> OrchestratorResult<CriticalPath> {
+        // Finish time of each module if it started as soon as its dependencies were ready
+        let mut finish: HashMap<ModuleId, Duration> = HashMap::new();
+        let mut previous: HashMap<ModuleId, ModuleId> = HashMap::new();
+
+        for level in self.compute_startup_levels()? {
+            for module_id in level {
+                let slowest_dependency = self.get_dependencies(module_id)
+                    .into_iter()
+                    .max_by_key(|dependency| finish[dependency]);
+
+                let start = match slowest_dependency {
+                    Some(dependency) => {
+                        previous.insert(module_id, dependency);
+                        finish[&dependency]
+                    }
+                    None => Duration::ZERO,
+                };
+                let own = durations.get(&module_id).copied().unwrap_or(Duration::ZERO);
+                finish.insert(module_id, start + own);
+            }
+        }
+
+        let Some((&end, &duration)) = finish.iter().max_by_key(|(_, &duration)| duration) else {
+            return Ok(CriticalPath::default());
+        };
+
+        let mut modules = vec![end];
+        while let Some(&dependency) = previous.get(modules.last().expect("path is never empty")) {
+            modules.push(dependency);
+        }
+        modules.reverse();
+
+        Ok(CriticalPath { modules, duration })
+    }
+
     /// Recursive helper for topological sort with cycle detection
     fn topological_sort_visit(
         &self,
@@ -175,6 +270,14 @@ impl DependencyGraph {
             Vec::new()
         }
     }
+
+    /// Every dependency relationship as `(dependency, dependent)`
+    pub fn edges(&self) -> Vec<(ModuleId, ModuleId)> {
+        self.graph
+            .edge_references()
+            .map(|edge| (self.graph[edge.source()], self.graph[edge.target()]))
+            .collect()
+    }
 }
 
 /// Handle for controlling a module
@@ -228,24 +331,71 @@ pub struct ModuleRegistry {
     
     /// Module handles for lifecycle control
     module_handles: DashMap<ModuleId, ModuleHandle>,
+
+    /// Startup config templates, rendered against `capabilities` on registration
+    templates: ConfigTemplates,
+
+    /// What this machine offers
+    capabilities: SystemCapabilities,
 }
 
+/// Modules left out in headless mode
+pub const HEADLESS_EXCLUDED_MODULES: [ModuleId; 3] =
+    [ModuleId::Gamification, ModuleId::AiIntegration, ModuleId::CuteFigurine];
+
 impl ModuleRegistry {
     pub fn new() -> Self {
+        Self::with_default_modules(&[])
+    }
+
+    /// Registry for headless mode: the default modules minus intervention delivery and UI
+    pub fn headless() -> Self {
+        Self::with_default_modules(&HEADLESS_EXCLUDED_MODULES)
+    }
+
+    fn with_default_modules(excluded: &[ModuleId]) -> Self {
         let mut registry = Self {
             modules: DashMap::new(),
             dependency_graph: Arc::new(tokio::sync::RwLock::new(DependencyGraph::new())),
             module_states: Arc::new(DashMap::new()),
             module_handles: DashMap::new(),
+            templates: ConfigTemplates::builtin(),
+            capabilities: SystemCapabilities::detect(),
         };
 
         // Register default modules with their dependencies
-        registry.register_default_modules();
+        registry.register_default_modules(excluded);
         registry
     }
 
+    /// Render startup configs from `templates` as if running on `capabilities`
+    pub fn with_config_templates(mut self, templates: ConfigTemplates, capabilities: SystemCapabilities) -> Self {
+        self.templates = templates;
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Capabilities startup configs are rendered against
+    pub fn capabilities(&self) -> &SystemCapabilities {
+        &self.capabilities
+    }
+
+    /// Startup config fragment for a module on this machine under `profile`
+    ///
+    /// `None` if the module has no template.
+    pub fn startup_config(&self, module_id: ModuleId, profile: SystemProfile) -> Option<serde_json::Value> {
+        self.templates.render(module_id, &self.capabilities, profile)
+    }
+
     /// Register a module with the registry
-    pub async fn register_module(&self, descriptor: ModuleDescriptor) -> OrchestratorResult<()> {
+    ///
+    /// Returns the module's startup config fragment rendered for this
+    /// machine and `profile`, if it has a template.
+    pub async fn register_module(
+        &self,
+        descriptor: ModuleDescriptor,
+        profile: SystemProfile,
+    ) -> OrchestratorResult<Option<serde_json::Value>> {
         let module_id = descriptor.id;
         
         // Check if all dependencies are available
@@ -270,7 +420,7 @@ impl ModuleRegistry {
         self.module_handles.insert(module_id, ModuleHandle::new(module_id));
 
         info!("Registered module: {}", module_id);
-        Ok(())
+        Ok(self.startup_config(module_id, profile))
     }
 
     /// Get module descriptor
@@ -310,6 +460,18 @@ impl ModuleRegistry {
         graph.compute_startup_order()
     }
 
+    /// Compute startup levels for parallel startup
+    pub async fn compute_startup_levels(&self) -> OrchestratorResult<Vec<Vec<ModuleId>>> {
+        let graph = self.dependency_graph.read().await;
+        graph.compute_startup_levels()
+    }
+
+    /// Compute the critical startup path from measured module startup times
+    pub async fn critical_path(&self, durations: &HashMap<ModuleId, Duration>) -> OrchestratorResult<CriticalPath> {
+        let graph = self.dependency_graph.read().await;
+        graph.critical_path(durations)
+    }
+
     /// Get dependencies of a module
     pub async fn get_dependencies(&self, module_id: ModuleId) -> Vec<ModuleId> {
         let graph = self.dependency_graph.read().await;
@@ -322,11 +484,33 @@ impl ModuleRegistry {
         graph.get_dependents(module_id)
     }
 
+    /// Everything that depends on a module, directly or through other modules
+    pub async fn get_transitive_dependents(&self, module_id: ModuleId) -> Vec<ModuleId> {
+        let graph = self.dependency_graph.read().await;
+        let mut dependents = Vec::new();
+        let mut pending = vec![module_id];
+        while let Some(current) = pending.pop() {
+            for dependent in graph.get_dependents(current) {
+                if !dependents.contains(&dependent) {
+                    dependents.push(dependent);
+                    pending.push(dependent);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Every dependency relationship as `(dependency, dependent)`
+    pub async fn dependency_edges(&self) -> Vec<(ModuleId, ModuleId)> {
+        let graph = self.dependency_graph.read().await;
+        graph.edges()
+    }
+
     /// Register default system modules
-    fn register_default_modules(&mut self) {
-        // Note: This runs synchronously during construction
-        // We'll register modules without updating the dependency graph here
-        // The actual dependency setup will happen when modules are properly registered
+    fn register_default_modules(&mut self, excluded: &[ModuleId]) {
+        // This runs synchronously during construction, so build the graph
+        // directly instead of going through the async lock
+        let mut graph = DependencyGraph::new();
 
         let modules = vec![
             (ModuleId::Orchestrator, "orchestrator", vec![]),
@@ -340,6 +524,14 @@ impl ModuleRegistry {
         ];
 
         for (id, name, dependencies) in modules {
+            if excluded.contains(&id) {
+                continue;
+            }
+            graph.add_module(id);
+            for &dependency in &dependencies {
+                graph.add_dependency(id, dependency);
+            }
+
             let descriptor = ModuleDescriptor::new(id, name.to_string())
                 .with_dependencies(dependencies);
             
@@ -347,5 +539,105 @@ impl ModuleRegistry {
             self.module_states.insert(id, ModuleState::NotStarted);
             self.module_handles.insert(id, ModuleHandle::new(id));
         }
+
+        self.dependency_graph = Arc::new(tokio::sync::RwLock::new(graph));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_levels_group_independent_modules() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency(ModuleId::Storage, ModuleId::EventBus);
+        graph.add_dependency(ModuleId::DataCapture, ModuleId::EventBus);
+        graph.add_dependency(ModuleId::AnalysisEngine, ModuleId::Storage);
+
+        let levels = graph.compute_startup_levels().unwrap();
+        assert_eq!(levels, vec![
+            vec![ModuleId::EventBus],
+            vec![ModuleId::Storage, ModuleId::DataCapture],
+            vec![ModuleId::AnalysisEngine],
+        ]);
+    }
+
+    #[test]
+    fn test_startup_levels_detect_cycle() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency(ModuleId::Storage, ModuleId::AnalysisEngine);
+        graph.add_dependency(ModuleId::AnalysisEngine, ModuleId::Storage);
+
+        assert!(matches!(
+            graph.compute_startup_levels(),
+            Err(OrchestratorError::DependencyCycle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_critical_path_follows_slowest_chain() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency(ModuleId::Storage, ModuleId::EventBus);
+        graph.add_dependency(ModuleId::DataCapture, ModuleId::EventBus);
+        graph.add_dependency(ModuleId::AnalysisEngine, ModuleId::Storage);
+
+        let durations = HashMap::from([
+            (ModuleId::EventBus, Duration::from_millis(10)),
+            (ModuleId::Storage, Duration::from_millis(100)),
+            (ModuleId::DataCapture, Duration::from_millis(300)),
+            (ModuleId::AnalysisEngine, Duration::from_millis(50)),
+        ]);
+
+        let path = graph.critical_path(&durations).unwrap();
+        assert_eq!(path.modules, vec![ModuleId::EventBus, ModuleId::DataCapture]);
+        assert_eq!(path.duration, Duration::from_millis(310));
+    }
+
+    #[tokio::test]
+    async fn test_default_modules_populate_graph() {
+        let registry = ModuleRegistry::new();
+        assert_eq!(
+            registry.get_dependencies(ModuleId::AnalysisEngine).await.len(),
+            2
+        );
+
+        let levels = registry.compute_startup_levels().await.unwrap();
+        assert_eq!(levels.last().unwrap(), &vec![ModuleId::CuteFigurine]);
+    }
+
+    #[tokio::test]
+    async fn test_headless_registry_stops_at_analysis() {
+        let registry = ModuleRegistry::headless();
+        assert!(registry.get_module(ModuleId::AiIntegration).is_none());
+
+        let levels = registry.compute_startup_levels().await.unwrap();
+        assert_eq!(levels.last().unwrap(), &vec![ModuleId::AnalysisEngine]);
+    }
+
+    #[tokio::test]
+    async fn test_registration_returns_config_for_this_machine() {
+        let laptop = SystemCapabilities {
+            platform: "macos".to_string(),
+            total_memory_mb: 8 * 1024,
+            cpu_cores: 8,
+            has_gpu: false,
+        };
+        let registry = ModuleRegistry::headless().with_config_templates(ConfigTemplates::builtin(), laptop);
+
+        let config = registry
+            .register_module(ModuleDescriptor::new(ModuleId::AnalysisEngine, "analysis-engine".to_string()), SystemProfile::LowPower)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(config["max_concurrent_analyses"], 1);
+        assert_eq!(config["feature_cache_size"], 50);
+        assert_eq!(config["profile"], "low_power");
+
+        let storage = registry
+            .register_module(ModuleDescriptor::new(ModuleId::Storage, "storage".to_string()), SystemProfile::LowPower)
+            .await
+            .unwrap();
+        assert!(storage.is_none());
     }
 }
\ No newline at end of file
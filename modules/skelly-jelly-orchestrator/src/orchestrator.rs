@@ -1,20 +1,38 @@
 //! Main orchestrator implementation
 
 use crate::{
+    admin_api::{AdminBackend, HealthSummary, IncidentView, ModuleStateView},
     config::{ConfigurationManager, OrchestratorConfig},
+    config_schema::SchemaReport,
+    config_templates,
+    enforcement::{self, EnforcementConfig, ResourceEnforcer},
     error::{OrchestratorError, OrchestratorResult},
     health::{HealthMonitor, HealthReport, HealthStatus},
-    lifecycle::{LifecycleController, ModuleState},
-    module_registry::{ModuleRegistry, ModuleDescriptor},
+    lifecycle::{LifecycleController, ModuleState, StopReason},
+    maintenance::MaintenanceScheduler,
+    module_registry::{ModuleRegistry, ModuleDescriptor, HEADLESS_EXCLUDED_MODULES},
     recovery::{RecoveryManager, ModuleFailure, FailureType},
-    resource::{ResourceManager, SystemResources, PerformanceStats},
+    resource::{ResourceManager, ResourceLimits, ThrottleAction, SystemResources, PerformanceStats, BatteryOptimization},
+    module_registry::CriticalPath,
+    profiles::{ProfileChange, ProfileConfig, ProfileManager, SystemProfile},
+    power::{PowerManager, PowerState},
+    safe_mode::{CrashLoopDetector, QuarantineRecord},
+    sleep_wake::SleepWakeCoordinator,
+    secrets::{KeychainStore, SecretReceiver, SecretStore, SecretsBroker, SecretsConfig, KEYCHAIN_SERVICE},
     startup::{StartupSequencer, StartupMetrics},
+    system_map::{GraphFormat, ModuleNode, SystemMap},
     performance_telemetry::{PerformanceTelemetrySystem, TelemetryConfig},
     event_loss_prevention::{EventLossPreventionSystem, EventLossPreventionConfig},
+    user_profiles::{self, PROFILE_SCOPED_MODULES, USER_PROFILE_KEY},
+    privacy_policy,
+    validation::{self, ReadinessProbe, ReadinessReport},
     OrchestratorTrait,
 };
 use async_trait::async_trait;
-use skelly_jelly_event_bus::{EventBusTrait, ModuleId, BusMessage, MessagePayload, message::ErrorReport};
+use futures::{Stream, StreamExt};
+use skelly_jelly_event_bus::{
+    EventBusTrait, ModuleId, BusMessage, MessageFilter, MessagePayload, MessageType, message::ErrorReport,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -22,9 +40,10 @@ use std::{
     time::{Duration, Instant},
 };
 use chrono::{DateTime, Utc};
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tracing::{info, warn, error, debug};
 use uuid::Uuid;
+use skelly_jelly_privacy_policy::{PrivacyPolicyConfig, PRIVACY_POLICY_KEY};
 
 /// System-wide health status
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +64,8 @@ pub struct SystemHealth {
     pub module_health: HashMap<ModuleId, HealthReport>,
     pub resource_usage: SystemResources,
     pub active_issues: Vec<SystemIssue>,
+    /// Modules in safe mode after crash-looping
+    pub quarantined_modules: Vec<QuarantineRecord>,
     pub last_updated: Instant,
 }
 
@@ -57,6 +78,8 @@ pub struct SystemIssue {
     pub affected_modules: Vec<ModuleId>,
     pub timestamp: Instant,
     pub resolved: bool,
+    /// Suggested operator actions, most specific first
+    pub remediation: Vec<String>,
 }
 
 /// Issue severity levels
@@ -108,17 +131,61 @@ pub struct OrchestratorImpl {
     
     /// Event loss prevention system
     loss_prevention_system: Arc<RwLock<EventLossPreventionSystem>>,
+    
+    /// System-wide profile switching
+    profile_manager: Arc<ProfileManager>,
+    
+    /// Battery and thermal awareness
+    power_manager: Arc<PowerManager>,
+    
+    /// Housekeeping during maintenance windows
+    maintenance: Arc<MaintenanceScheduler>,
+    
+    /// OS sleep, wake and shutdown handling
+    sleep_wake: Arc<SleepWakeCoordinator>,
+    
+    /// Crash-loop detection and module quarantine
+    crash_loop: Arc<CrashLoopDetector>,
+    
+    /// Keychain secrets sealed for modules at registration
+    secrets: Arc<SecretsBroker>,
+
+    /// User profile whose data the modules have open
+    user_profile: Arc<RwLock<String>>,
+
+    /// Signalled when something asks the process to exit, e.g. a takeover
+    shutdown_request: Arc<Notify>,
+
+    /// Extra checks run by `validate`
+    readiness_probes: Arc<RwLock<Vec<Arc<dyn ReadinessProbe>>>>,
 }
 
 impl OrchestratorImpl {
+    /// Create an orchestrator that reads module secrets from the OS keychain
     pub async fn new(
         config: OrchestratorConfig,
         event_bus: Arc<dyn EventBusTrait>,
+    ) -> OrchestratorResult<Self> {
+        Self::with_secret_store(config, event_bus, Arc::new(KeychainStore::new(KEYCHAIN_SERVICE))).await
+    }
+
+    /// Create an orchestrator that reads module secrets from `secret_store`
+    pub async fn with_secret_store(
+        config: OrchestratorConfig,
+        event_bus: Arc<dyn EventBusTrait>,
+        secret_store: Arc<dyn SecretStore>,
     ) -> OrchestratorResult<Self> {
         info!("Initializing orchestrator");
+        user_profiles::validate_user_profile(&config.user_profile)?;
+        privacy_policy::validate_privacy_policy(&config.privacy_policy)?;
 
         // Create core components
-        let registry = Arc::new(ModuleRegistry::new());
+        let registry = if config.headless {
+            info!("🕶️ Headless mode: skipping {:?}", HEADLESS_EXCLUDED_MODULES);
+            Arc::new(ModuleRegistry::headless())
+        } else {
+            Arc::new(ModuleRegistry::new())
+        };
         let config_manager = Arc::new(ConfigurationManager::new(config.clone(), Arc::clone(&event_bus)));
         
         let lifecycle_controller = Arc::new(LifecycleController::new(
@@ -135,21 +202,65 @@ impl OrchestratorImpl {
             config.unhealthy_threshold,
         )));
         
-        let resource_manager = Arc::new(RwLock::new(ResourceManager::new(
+        let mut resource_manager = ResourceManager::new(
             Arc::clone(&registry),
             config.resource_check_interval,
             config.throttle_threshold,
+        );
+        resource_manager.set_enforcer(Arc::new(ResourceEnforcer::new(
+            enforcement::platform_backend(),
+            Arc::clone(&event_bus),
+            EnforcementConfig::default(),
         )));
+        let resource_manager = Arc::new(RwLock::new(resource_manager));
         
         let recovery_manager = Arc::new(RecoveryManager::new(Arc::clone(&lifecycle_controller)));
         
         // Create performance telemetry system
         let telemetry_config = TelemetryConfig::default();
-        let telemetry_system = Arc::new(RwLock::new(PerformanceTelemetrySystem::new(telemetry_config)));
+        let telemetry_system = Arc::new(RwLock::new(
+            PerformanceTelemetrySystem::new(telemetry_config)
+                .with_event_bus(Arc::clone(&event_bus))
+                .with_lifecycle(Arc::clone(&lifecycle_controller)),
+        ));
         
         // Create event loss prevention system
         let loss_prevention_config = EventLossPreventionConfig::default();
-        let loss_prevention_system = Arc::new(RwLock::new(EventLossPreventionSystem::new(loss_prevention_config)));
+        let loss_prevention_system = Arc::new(RwLock::new(
+            EventLossPreventionSystem::new(loss_prevention_config).with_event_bus(Arc::clone(&event_bus)),
+        ));
+        
+        // Create profile manager
+        let profile_manager = Arc::new(ProfileManager::new(ProfileConfig::default(), Arc::clone(&config_manager)));
+        
+        // Create power manager
+        let power_manager = Arc::new(PowerManager::new(
+            BatteryOptimization::default(),
+            Arc::clone(&config_manager),
+            Arc::clone(&event_bus),
+        ));
+
+        let maintenance = Arc::new(MaintenanceScheduler::new(config.maintenance.clone(), Arc::clone(&event_bus)));
+
+        let sleep_wake = Arc::new(SleepWakeCoordinator::new(
+            config.sleep_wake.clone(),
+            Arc::clone(&registry),
+            Arc::clone(&lifecycle_controller),
+            Arc::clone(&event_bus),
+        ));
+
+        let crash_loop = Arc::new(CrashLoopDetector::new(
+            config.crash_loop_max_failures,
+            config.crash_loop_window,
+        ));
+
+        // Seal secrets for the modules registered by default
+        let secrets = Arc::new(SecretsBroker::new(secret_store, SecretsConfig::default()));
+        for descriptor in registry.get_all_modules() {
+            if let Err(e) = secrets.provision(descriptor.id).await {
+                warn!("Could not provision secrets for {}: {}", descriptor.id, e);
+            }
+        }
 
         let orchestrator = Self {
             config_manager,
@@ -165,41 +276,54 @@ impl OrchestratorImpl {
             startup_sequencer: Arc::new(RwLock::new(None)),
             telemetry_system,
             loss_prevention_system,
+            profile_manager,
+            power_manager,
+            maintenance,
+            sleep_wake,
+            crash_loop,
+            secrets,
+            user_profile: Arc::new(RwLock::new(config.user_profile.clone())),
+            shutdown_request: Arc::new(Notify::new()),
+            readiness_probes: Arc::new(RwLock::new(Vec::new())),
         };
 
-        // Subscribe to system events
-        orchestrator.setup_event_subscriptions().await?;
-
         info!("Orchestrator initialized successfully");
         Ok(orchestrator)
     }
 
-    /// Setup event subscriptions for system monitoring
-    async fn setup_event_subscriptions(&self) -> OrchestratorResult<()> {
-        use skelly_jelly_event_bus::{MessageFilter, DeliveryMode, MessageType};
-
-        debug!("Setting up orchestrator event subscriptions");
+    /// Messages the orchestrator acts on
+    ///
+    /// The host subscribes with this filter and hands the stream to
+    /// [`consume`](Self::consume): error reports, modules' own readiness
+    /// signals, delivery acks, config updates addressed to the orchestrator
+    /// and, while maintenance windows are enabled, maintenance reports and
+    /// user input.
+    pub fn subscription_filter(&self) -> MessageFilter {
+        let mut types = vec![
+            MessageType::Error,
+            MessageType::ModuleReady,
+            MessageType::DeliveryAck,
+            MessageType::ConfigUpdate,
+        ];
+        if self.maintenance.is_enabled() {
+            types.extend([MessageType::MaintenanceReport, MessageType::RawEvent]);
+        }
 
-        // Subscribe to error reports
-        let error_filter = MessageFilter::types(vec![MessageType::Error]);
-        
-        self.event_bus.subscribe(
-            ModuleId::Orchestrator,
-            error_filter,
-            DeliveryMode::Reliable { timeout: Duration::from_secs(5) },
-        ).await?;
-
-        // Subscribe to module ready events
-        let ready_filter = MessageFilter::types(vec![MessageType::ModuleReady]);
-        
-        self.event_bus.subscribe(
-            ModuleId::Orchestrator,
-            ready_filter,
-            DeliveryMode::Reliable { timeout: Duration::from_secs(5) },
-        ).await?;
+        MessageFilter::types(types).with_predicate(|message| match &message.payload {
+            MessagePayload::ConfigUpdate(update) => update.target_module == Some(ModuleId::Orchestrator),
+            _ => true,
+        })
+    }
 
-        debug!("Event subscriptions setup complete");
-        Ok(())
+    /// Handle messages from the orchestrator's subscription until the stream ends
+    pub async fn consume(&self, mut messages: impl Stream<Item = BusMessage> + Unpin) {
+        while let Some(message) = messages.next().await {
+            let message_type = message.message_type();
+            if let Err(e) = self.handle_bus_message(message).await {
+                warn!("Failed to handle {:?} message: {}", message_type, e);
+            }
+        }
+        debug!("Orchestrator subscription ended");
     }
 
     /// Handle incoming error reports
@@ -242,6 +366,7 @@ impl OrchestratorImpl {
             affected_modules: vec![error_report.module],
             timestamp: Instant::now(),
             resolved: false,
+            remediation: Vec::new(),
         };
 
         {
@@ -249,6 +374,17 @@ impl OrchestratorImpl {
             issues.push(issue);
         }
 
+        // A crash-looping module stays down until an operator restarts it
+        if self.crash_loop.is_quarantined(error_report.module) {
+            debug!("Module {} is quarantined, not recovering", error_report.module);
+            return Ok(());
+        }
+        if let Some(mut record) = self.crash_loop.record_failure(error_report.module, &error_report.message) {
+            record.held_back = self.hold_back_dependents(record.module).await;
+            self.record_quarantine_issue(&record).await;
+            return Ok(());
+        }
+
         // Trigger recovery if auto-recovery is enabled
         let global_config = self.config_manager.get_global_config().await;
         if global_config.auto_recovery {
@@ -260,6 +396,53 @@ impl OrchestratorImpl {
         Ok(())
     }
 
+    /// Stop everything that transitively depends on a module quarantined at
+    /// runtime and keep it down until the module leaves quarantine
+    async fn hold_back_dependents(&self, module_id: ModuleId) -> Vec<ModuleId> {
+        let held_back = self.registry.get_transitive_dependents(module_id).await;
+
+        // Dependents of dependents first, so nothing outlives what it needs
+        let order = self.registry.compute_startup_order().await.unwrap_or_default();
+        for &dependent in order.iter().rev().filter(|dependent| held_back.contains(dependent)) {
+            if let Some(descriptor) = self.registry.get_module(dependent) {
+                if let Err(e) = self.lifecycle_controller.stop_module_with(dependent, descriptor.shutdown_timeout, false).await {
+                    warn!("Failed to stop {} while {} is quarantined: {}", dependent, module_id, e);
+                }
+            }
+            self.registry.set_module_state(
+                dependent,
+                ModuleState::Stopped { reason: StopReason::Dependency(module_id) },
+            );
+        }
+        if !held_back.is_empty() {
+            warn!("🛟 Holding back {:?} until {} leaves quarantine", held_back, module_id);
+        }
+        self.crash_loop.hold_back(module_id, held_back.clone());
+        held_back
+    }
+
+    /// Surface a quarantined module as a system issue with its remediation hints
+    async fn record_quarantine_issue(&self, record: &QuarantineRecord) {
+        let mut affected_modules = vec![record.module];
+        affected_modules.extend(record.held_back.iter().copied());
+
+        let issue = SystemIssue {
+            id: Uuid::new_v4(),
+            severity: IssueSeverity::High,
+            description: format!(
+                "{} quarantined after {} failures: {}",
+                record.module, record.failures, record.last_error
+            ),
+            affected_modules,
+            timestamp: Instant::now(),
+            resolved: false,
+            remediation: record.remediation.clone(),
+        };
+
+        let mut issues = self.active_issues.write().await;
+        issues.push(issue);
+    }
+
     /// Map failure to issue severity
     fn map_failure_to_severity(&self, failure: &ModuleFailure) -> IssueSeverity {
         match failure.failure_type {
@@ -290,6 +473,12 @@ impl OrchestratorImpl {
             resource_manager.start_monitoring().await?;
         }
 
+        // Start battery and thermal monitoring
+        let interval = self.config_manager.get_global_config().await.resource_check_interval;
+        self.power_manager.start_monitoring(interval).await;
+        self.maintenance.start(Arc::clone(&self.power_manager)).await;
+        self.sleep_wake.start_monitoring().await;
+
         info!("Monitoring services started");
         Ok(())
     }
@@ -310,6 +499,10 @@ impl OrchestratorImpl {
             resource_manager.stop_monitoring().await;
         }
 
+        self.power_manager.stop_monitoring().await;
+        self.maintenance.stop().await;
+        self.sleep_wake.stop_monitoring().await;
+
         info!("Monitoring services stopped");
         Ok(())
     }
@@ -319,20 +512,25 @@ impl OrchestratorImpl {
         let health_monitor = self.health_monitor.read().await;
         let health_reports = health_monitor.get_all_health_reports();
         
-        let unhealthy_modules: Vec<ModuleId> = health_reports
-            .iter()
+        // Quarantined modules are reported through safe mode instead
+        let reports = || health_reports.iter().filter(|report| !self.crash_loop.is_sidelined(report.module_id));
+
+        let unhealthy_modules: Vec<ModuleId> = reports()
             .filter(|report| matches!(report.status, HealthStatus::Unhealthy { .. }))
             .map(|report| report.module_id)
             .collect();
 
-        let degraded_modules: Vec<ModuleId> = health_reports
-            .iter()
+        let degraded_modules: Vec<ModuleId> = reports()
             .filter(|report| matches!(report.status, HealthStatus::Degraded { .. }))
             .map(|report| report.module_id)
             .collect();
 
+        let quarantined = self.crash_loop.quarantined();
+
         let status = if !unhealthy_modules.is_empty() {
             SystemStatus::Critical { failing_modules: unhealthy_modules }
+        } else if !quarantined.is_empty() {
+            SystemStatus::Degraded { reason: safe_mode_reason(&quarantined) }
         } else if !degraded_modules.is_empty() {
             SystemStatus::Degraded {
                 reason: format!("Modules in degraded state: {:?}", degraded_modules),
@@ -390,6 +588,19 @@ impl OrchestratorTrait for OrchestratorImpl {
             info!("Event loss prevention system started with queue monitors");
         }
 
+        // Tell modules which user profile to open and which privacy policy
+        // to apply before any of them start
+        self.announce_user_profile().await?;
+        self.set_privacy_policy(self.privacy_policy().await).await?;
+
+        // Fit the default modules' configs to this machine before they start
+        let profile = self.profile_manager.active_profile().await;
+        for descriptor in self.registry.get_all_modules() {
+            if let Some(fragment) = self.registry.startup_config(descriptor.id, profile) {
+                self.apply_startup_config(descriptor.id, fragment).await;
+            }
+        }
+
         // Initialize the startup sequencer
         {
             let mut sequencer_lock = self.startup_sequencer.write().await;
@@ -399,7 +610,7 @@ impl OrchestratorTrait for OrchestratorImpl {
                 Arc::clone(&self.health_monitor),
                 Arc::clone(&self.config_manager),
                 Arc::clone(&self.event_bus),
-            ));
+            ).with_crash_loop_detector(Arc::clone(&self.crash_loop)));
         }
 
         // Execute coordinated startup sequence
@@ -415,6 +626,13 @@ impl OrchestratorTrait for OrchestratorImpl {
             }
         };
 
+        // Surface anything that crash-looped during startup
+        for record in self.crash_loop.quarantined() {
+            if startup_metrics.quarantined_modules.contains(&record.module) {
+                self.record_quarantine_issue(&record).await;
+            }
+        }
+
         // Update system status
         self.update_system_status().await;
 
@@ -448,6 +666,11 @@ impl OrchestratorTrait for OrchestratorImpl {
             *status = SystemStatus::Stopping;
         }
 
+        // Have storage checkpoint while it is still running
+        if let Err(e) = self.sleep_wake.prepare_for_shutdown().await {
+            warn!("Could not announce shutdown: {}", e);
+        }
+
         // Stop all modules
         if let Err(e) = self.lifecycle_controller.stop_system(timeout).await {
             warn!("Error during system shutdown: {}", e);
@@ -515,6 +738,7 @@ impl OrchestratorTrait for OrchestratorImpl {
             module_health,
             resource_usage,
             active_issues,
+            quarantined_modules: self.crash_loop.quarantined(),
             last_updated: Instant::now(),
         }
     }
@@ -525,30 +749,310 @@ impl OrchestratorTrait for OrchestratorImpl {
         self.config_manager.update_config(module_id, config).await
     }
 
-    /// Restart a specific module
+    /// Restart a specific module, releasing it from quarantine if needed
     async fn restart_module(&self, module_id: ModuleId) -> OrchestratorResult<()> {
         info!("Restarting module: {}", module_id);
-        self.lifecycle_controller.restart_module(module_id).await
+        match self.crash_loop.release(module_id) {
+            Some(record) => self.leave_safe_mode(record).await,
+            None => self.lifecycle_controller.restart_module(module_id).await,
+        }
     }
 
     /// Register a new module
     async fn register_module(&self, descriptor: ModuleDescriptor) -> OrchestratorResult<()> {
         info!("Registering module: {}", descriptor.id);
-        self.registry.register_module(descriptor).await
+        let module_id = descriptor.id;
+        let profile = self.profile_manager.active_profile().await;
+        if let Some(fragment) = self.registry.register_module(descriptor, profile).await? {
+            self.apply_startup_config(module_id, fragment).await;
+        }
+
+        if let Err(e) = self.secrets.provision(module_id).await {
+            warn!("Could not provision secrets for {}: {}", module_id, e);
+        }
+        Ok(())
     }
 
     /// Get module state
     async fn get_module_state(&self, module_id: ModuleId) -> Option<ModuleState> {
         self.registry.get_module_state(module_id)
     }
+
+    /// Validate the global and stored module configs, then run the readiness probes
+    async fn validate(&self) -> ReadinessReport {
+        let global = self.config_manager.get_global_config().await;
+        let mut checks = vec![validation::config_check(
+            "orchestrator",
+            &SchemaReport { violations: global.validate(), warnings: Vec::new() },
+        )];
+
+        let mut modules: Vec<ModuleId> = self.registry.get_all_modules().into_iter().map(|descriptor| descriptor.id).collect();
+        modules.sort_by_key(|module_id| module_id.to_string());
+        for module_id in modules {
+            if let Some(config) = self.config_manager.get_config(module_id).await {
+                let report = self.config_manager.validate_config(module_id, &config);
+                checks.push(validation::config_check(&module_id.to_string(), &report));
+            }
+        }
+
+        let probes = self.readiness_probes.read().await.clone();
+        for probe in probes {
+            checks.extend(probe.check().await);
+        }
+        ReadinessReport::new(checks)
+    }
 }
 
 impl OrchestratorImpl {
+    /// Run `probe` as part of every `validate`
+    pub async fn add_readiness_probe(&self, probe: Arc<dyn ReadinessProbe>) {
+        self.readiness_probes.write().await.push(probe);
+    }
+
     /// Get startup metrics (if available)
     pub async fn get_startup_metrics(&self) -> Option<StartupMetrics> {
         let sequencer_lock = self.startup_sequencer.read().await;
         sequencer_lock.as_ref().map(|sequencer| sequencer.get_metrics().clone())
     }
+
+    /// Get the critical startup path from the last startup (if available)
+    pub async fn get_critical_path(&self) -> Option<CriticalPath> {
+        let sequencer_lock = self.startup_sequencer.read().await;
+        sequencer_lock.as_ref().map(|sequencer| sequencer.get_critical_path().clone())
+    }
+
+    /// Dependency graph annotated with each module's state, health, startup time and bus traffic
+    pub async fn system_map(&self) -> SystemMap {
+        let health: HashMap<ModuleId, HealthStatus> = self.health_monitor.read().await
+            .get_all_health_reports()
+            .into_iter()
+            .map(|report| (report.module_id, report.status))
+            .collect();
+        let startup_times = self.get_startup_metrics().await
+            .map(|metrics| metrics.module_startup_times)
+            .unwrap_or_default();
+        let bus_stats = match self.event_bus.metrics().await {
+            Ok(metrics) => metrics.module_stats,
+            Err(e) => {
+                warn!("System map drawn without message volumes: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let modules = self.registry
+            .get_all_modules()
+            .into_iter()
+            .map(|descriptor| {
+                let module = descriptor.id;
+                let state = self.registry.get_module_state(module).unwrap_or(ModuleState::NotStarted);
+                let traffic = bus_stats.get(&module);
+                ModuleNode {
+                    module,
+                    state: ModuleStateView::new(module, &state).state,
+                    health: health.get(&module).cloned().unwrap_or(HealthStatus::Unknown),
+                    startup_time: startup_times.get(&module).copied(),
+                    messages_published: traffic.map_or(0, |stats| stats.messages_published),
+                    messages_received: traffic.map_or(0, |stats| stats.messages_received),
+                }
+            })
+            .collect();
+
+        SystemMap::new(modules, self.registry.dependency_edges().await)
+    }
+
+    /// Render the live system map as DOT or Mermaid
+    pub async fn export_dependency_graph(&self, format: GraphFormat) -> String {
+        self.system_map().await.render(format)
+    }
+
+    /// Switch the system profile on the user's behalf
+    pub async fn switch_profile(&self, profile: SystemProfile) -> OrchestratorResult<Option<ProfileChange>> {
+        self.profile_manager.select_profile(profile).await
+    }
+
+    /// Get the currently applied system profile
+    pub async fn active_profile(&self) -> SystemProfile {
+        self.profile_manager.active_profile().await
+    }
+
+    /// Switch to another user profile
+    ///
+    /// Modules that keep per-profile data are restarted so they reopen their
+    /// state for the new profile; messages sent to them meanwhile are held
+    /// and replayed as for any restart.
+    pub async fn switch_user_profile(&self, name: &str) -> OrchestratorResult<()> {
+        user_profiles::validate_user_profile(name)?;
+        {
+            let mut active = self.user_profile.write().await;
+            if *active == name {
+                return Ok(());
+            }
+            info!("👤 Switching user profile from {} to {}", *active, name);
+            *active = name.to_string();
+        }
+
+        self.announce_user_profile().await?;
+        for module_id in PROFILE_SCOPED_MODULES {
+            if matches!(self.registry.get_module_state(*module_id), Some(ModuleState::Running { .. })) {
+                self.lifecycle_controller.restart_module(*module_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the active user profile
+    pub async fn active_user_profile(&self) -> String {
+        self.user_profile.read().await.clone()
+    }
+
+    /// Apply a rendered startup config under whatever was configured explicitly
+    ///
+    /// Settings already in the module's config win over the template, so a
+    /// user's choice isn't replaced by the hardware default.
+    async fn apply_startup_config(&self, module_id: ModuleId, fragment: serde_json::Value) {
+        let mut config = fragment;
+        if let Some(existing) = self.config_manager.get_config(module_id).await {
+            config_templates::merge(&mut config, &existing);
+        }
+        debug!("Startup config for {}: {}", module_id, config);
+        if let Err(e) = self.config_manager.update_config(module_id, config).await {
+            warn!("Could not apply startup config for {}: {}", module_id, e);
+        }
+    }
+
+    /// Record the active user profile and broadcast it to the modules
+    async fn announce_user_profile(&self) -> OrchestratorResult<()> {
+        let profile = self.user_profile.read().await.clone();
+        self.config_manager
+            .set_override(USER_PROFILE_KEY.to_string(), serde_json::json!(profile))
+            .await;
+        self.event_bus
+            .publish(user_profiles::user_profile_announcement(&profile))
+            .await
+            .map_err(OrchestratorError::EventBus)?;
+        Ok(())
+    }
+
+    /// Replace the privacy policy and broadcast it to the modules
+    pub async fn set_privacy_policy(&self, policy: PrivacyPolicyConfig) -> OrchestratorResult<()> {
+        privacy_policy::validate_privacy_policy(&policy)?;
+        self.config_manager
+            .set_override(PRIVACY_POLICY_KEY.to_string(), serde_json::json!(policy))
+            .await;
+        self.event_bus
+            .publish(privacy_policy::privacy_policy_announcement(&policy))
+            .await
+            .map_err(OrchestratorError::EventBus)?;
+        Ok(())
+    }
+
+    /// The privacy policy every module applies
+    pub async fn privacy_policy(&self) -> PrivacyPolicyConfig {
+        match self.config_manager.get_override(PRIVACY_POLICY_KEY).await {
+            Some(value) => serde_json::from_value(value).unwrap_or_default(),
+            None => self.config_manager.get_global_config().await.privacy_policy,
+        }
+    }
+
+    /// Get the current battery/thermal power state
+    pub async fn power_state(&self) -> PowerState {
+        self.power_manager.power_state().await
+    }
+
+    /// Prepare modules for OS sleep, e.g. from a platform power notification
+    pub async fn prepare_for_sleep(&self) -> OrchestratorResult<bool> {
+        self.sleep_wake.prepare_for_sleep().await
+    }
+
+    /// Bring modules back after OS wake
+    pub async fn handle_wake(&self) -> OrchestratorResult<bool> {
+        self.sleep_wake.handle_wake(None).await
+    }
+
+    /// Ask whoever awaits `shutdown_requested` to stop the system
+    pub fn request_shutdown(&self) {
+        self.shutdown_request.notify_one();
+    }
+
+    /// Resolves once `request_shutdown` has been called, even if that was earlier
+    pub async fn shutdown_requested(&self) {
+        self.shutdown_request.notified().await;
+    }
+
+    /// Hand a module the secrets sealed for it at registration; each channel can be taken once
+    pub fn take_secrets(&self, module_id: ModuleId) -> Option<SecretReceiver> {
+        self.secrets.take_receiver(module_id)
+    }
+
+    /// Modules currently quarantined after crash-looping
+    pub fn quarantined_modules(&self) -> Vec<QuarantineRecord> {
+        self.crash_loop.quarantined()
+    }
+
+    /// Start a released module and everything it held back, then resolve its issues
+    async fn leave_safe_mode(&self, record: QuarantineRecord) -> OrchestratorResult<()> {
+        self.lifecycle_controller.start_module(record.module).await?;
+
+        for module_id in self.registry.compute_startup_order().await? {
+            if record.held_back.contains(&module_id) && !self.crash_loop.is_sidelined(module_id) {
+                self.lifecycle_controller.start_module(module_id).await?;
+            }
+        }
+
+        let mut issues = self.active_issues.write().await;
+        for issue in issues.iter_mut().filter(|issue| issue.affected_modules.contains(&record.module)) {
+            issue.resolved = true;
+        }
+        info!("✅ {} left safe mode", record.module);
+        Ok(())
+    }
+
+    /// Put a module running as its own process under OS-level resource limits
+    pub async fn attach_module_process(&self, module_id: ModuleId, pid: u32) -> OrchestratorResult<()> {
+        let resource_manager = self.resource_manager.read().await;
+        resource_manager.attach_module_process(module_id, pid)
+    }
+
+    /// Set the resource ceilings a module is held to
+    pub async fn set_resource_limits(&self, module_id: ModuleId, limits: ResourceLimits) {
+        self.resource_manager.read().await.set_resource_limits(module_id, limits);
+    }
+
+    /// Check every module's latest usage against its limits: attached processes
+    /// through the OS-level enforcer, in-process modules through the throttle
+    pub async fn enforce_resource_limits(&self) -> OrchestratorResult<()> {
+        self.resource_manager.read().await.enforce_limits().await
+    }
+
+    /// Throttle currently applied to a module running in this process
+    pub async fn current_throttle(&self, module_id: ModuleId) -> Option<ThrottleAction> {
+        self.resource_manager.read().await.current_throttle(module_id)
+    }
+
+    /// Route a message received on the orchestrator's subscriptions
+    pub async fn handle_bus_message(&self, message: BusMessage) -> OrchestratorResult<()> {
+        match message.payload {
+            MessagePayload::ModuleReady(_) => {
+                self.lifecycle_controller.readiness_gate().observe(&message);
+                Ok(())
+            }
+            MessagePayload::ConfigUpdate(_) => {
+                self.profile_manager.handle_message(&message).await?;
+                self.power_manager.handle_message(&message).await?;
+                self.sleep_wake.handle_message(&message).await?;
+                Ok(())
+            }
+            MessagePayload::DeliveryAck(ack) => {
+                self.loss_prevention_system.read().await.acknowledge(&ack);
+                Ok(())
+            }
+            MessagePayload::RawEvent(_) | MessagePayload::MaintenanceReport(_) => {
+                self.maintenance.handle_message(&message).await
+            }
+            MessagePayload::Error(error_report) => self.handle_error_report(error_report).await,
+            _ => Ok(()),
+        }
+    }
     
     /// Get performance statistics
     pub async fn get_performance_stats(&self) -> OrchestratorResult<PerformanceStats> {
@@ -562,6 +1066,16 @@ impl OrchestratorImpl {
         telemetry.get_dashboard_data().await
     }
     
+    /// Get the slowest event bus flows matching `query`
+    pub async fn get_slowest_flows(
+        &self,
+        query: skelly_jelly_event_bus::FlowQuery,
+        k: usize,
+    ) -> OrchestratorResult<Vec<skelly_jelly_event_bus::FlowMetrics>> {
+        let telemetry = self.telemetry_system.read().await;
+        telemetry.slowest_flows(query, k).await
+    }
+    
     /// Get performance trends over time
     pub async fn get_performance_trends(&self, duration: Duration) -> OrchestratorResult<crate::performance_telemetry::PerformanceTrends> {
         let telemetry = self.telemetry_system.read().await;
@@ -586,6 +1100,12 @@ impl OrchestratorImpl {
         loss_prevention.can_enqueue(module_id).await
     }
     
+    /// Stamp a message with the next sequence number on its stream to `consumer`
+    pub async fn stamp_sequence(&self, message: BusMessage, consumer: ModuleId) -> BusMessage {
+        let loss_prevention = self.loss_prevention_system.read().await;
+        loss_prevention.stamp_sequence(message, consumer)
+    }
+    
     /// Record successful event dequeue
     pub fn record_event_dequeue(&self, module_id: ModuleId) {
         if let Ok(loss_prevention) = self.loss_prevention_system.try_read() {
@@ -593,12 +1113,19 @@ impl OrchestratorImpl {
         }
     }
     
-    /// Record resource usage for telemetry
+    /// Record resource usage for telemetry and limit enforcement
     pub async fn record_resource_usage(&self, module_id: ModuleId, usage: crate::resource::ResourceUsage) -> OrchestratorResult<()> {
+        self.resource_manager.read().await.record_usage(module_id, usage.clone());
         let telemetry = self.telemetry_system.read().await;
         telemetry.record_resource_usage(module_id, usage).await
     }
     
+    /// Record a module's processing latency for telemetry
+    pub async fn record_latency(&self, module_id: ModuleId, latency: Duration) -> OrchestratorResult<()> {
+        let telemetry = self.telemetry_system.read().await;
+        telemetry.record_latency(module_id, latency).await
+    }
+    
     /// Record system resources for telemetry
     pub async fn record_system_resources(&self, resources: SystemResources) -> OrchestratorResult<()> {
         let telemetry = self.telemetry_system.read().await;
@@ -606,5 +1133,107 @@ impl OrchestratorImpl {
     }
 }
 
+#[async_trait]
+impl AdminBackend for OrchestratorImpl {
+    async fn health_summary(&self) -> HealthSummary {
+        let health = self.get_system_health().await;
+        let mut unhealthy_modules: Vec<ModuleId> = health.module_health
+            .values()
+            .filter(|report| matches!(report.status, HealthStatus::Unhealthy { .. }))
+            .map(|report| report.module_id)
+            .collect();
+        unhealthy_modules.sort_by_key(|module| module.to_string());
+
+        HealthSummary {
+            status: health.status,
+            uptime_secs: health.uptime.as_secs(),
+            unhealthy_modules,
+            active_issues: health.active_issues.iter().filter(|issue| !issue.resolved).count(),
+            profile: self.active_profile().await,
+        }
+    }
+
+    async fn module_states(&self) -> Vec<ModuleStateView> {
+        let mut states: Vec<ModuleStateView> = self.registry
+            .get_all_modules()
+            .into_iter()
+            .filter_map(|descriptor| {
+                self.registry.get_module_state(descriptor.id)
+                    .map(|state| ModuleStateView::new(descriptor.id, &state))
+            })
+            .collect();
+        states.sort_by_key(|view| view.module.to_string());
+        states
+    }
+
+    async fn bus_metrics(&self) -> OrchestratorResult<skelly_jelly_event_bus::BusMetrics> {
+        Ok(self.event_bus.metrics().await?)
+    }
+
+    async fn system_map(&self) -> SystemMap {
+        OrchestratorImpl::system_map(self).await
+    }
+
+    async fn recent_incidents(&self, limit: usize) -> Vec<IncidentView> {
+        let issues = self.active_issues.read().await;
+        let mut incidents: Vec<&SystemIssue> = issues.iter().collect();
+        incidents.sort_by_key(|issue| std::cmp::Reverse(issue.timestamp));
+
+        incidents
+            .into_iter()
+            .take(limit)
+            .map(|issue| IncidentView {
+                id: issue.id,
+                severity: issue.severity,
+                description: issue.description.clone(),
+                affected_modules: issue.affected_modules.clone(),
+                age_secs: issue.timestamp.elapsed().as_secs(),
+                resolved: issue.resolved,
+            })
+            .collect()
+    }
+
+    async fn restart_module(&self, module: ModuleId) -> OrchestratorResult<()> {
+        OrchestratorTrait::restart_module(self, module).await
+    }
+
+    async fn pause_module(&self, module: ModuleId) -> OrchestratorResult<()> {
+        let buffer_size = self.config_manager.get_global_config().await.restart_buffer_size;
+        self.event_bus.pause_delivery(module, buffer_size).await?;
+        info!("⏸️  Paused bus delivery to {}", module);
+        Ok(())
+    }
+
+    async fn resume_module(&self, module: ModuleId) -> OrchestratorResult<skelly_jelly_event_bus::ReplaySummary> {
+        Ok(self.event_bus.resume_delivery(module).await?)
+    }
+
+    async fn switch_profile(&self, profile: SystemProfile) -> OrchestratorResult<Option<ProfileChange>> {
+        OrchestratorImpl::switch_profile(self, profile).await
+    }
+
+    async fn request_shutdown(&self) -> OrchestratorResult<()> {
+        OrchestratorImpl::request_shutdown(self);
+        Ok(())
+    }
+}
+
+/// Status reason listing quarantined modules and what they hold back
+fn safe_mode_reason(quarantined: &[QuarantineRecord]) -> String {
+    let modules = quarantined
+        .iter()
+        .map(|record| {
+            if record.held_back.is_empty() {
+                record.module.to_string()
+            } else {
+                let held_back: Vec<String> = record.held_back.iter().map(|m| m.to_string()).collect();
+                format!("{} (holding back {})", record.module, held_back.join(", "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("Safe mode: quarantined {}", modules)
+}
+
 /// Orchestrator type alias for convenience
 pub type Orchestrator = Arc<dyn OrchestratorTrait>;
\ No newline at end of file
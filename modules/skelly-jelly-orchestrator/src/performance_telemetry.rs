@@ -1,9 +1,13 @@
 //! Performance telemetry system for real-time monitoring and regression detection
 
 use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::lifecycle::LifecycleController;
 use crate::resource::{ResourceUsage, SystemResources};
 use dashmap::DashMap;
-use skelly_jelly_event_bus::ModuleId;
+use skelly_jelly_event_bus::{
+    message::{MemoryLeakSuspected, PerformanceRegression, TelemetryBatch, TelemetrySample},
+    BusMessage, EventBusTrait, FlowMetrics, FlowQuery, MessagePayload, MessagePriority, ModuleId,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, VecDeque},
@@ -53,6 +57,15 @@ const METRICS_AGGREGATION_PERIOD: Duration = Duration::from_secs(60);
 const METRICS_RETENTION_PERIOD: Duration = Duration::from_secs(3600); // 1 hour
 const REGRESSION_DETECTION_SAMPLES: usize = 10;
 
+/// Metric names used for persisted telemetry samples
+pub const METRIC_CPU_PERCENT: &str = "cpu_percent";
+pub const METRIC_MEMORY_MB: &str = "memory_mb";
+pub const METRIC_LATENCY_P95_MS: &str = "latency_p95_ms";
+pub const METRIC_SYSTEM_CPU_PERCENT: &str = "system_cpu_percent";
+
+/// Metrics compared against their rolling baseline
+const REGRESSION_METRICS: [&str; 3] = [METRIC_CPU_PERCENT, METRIC_LATENCY_P95_MS, METRIC_SYSTEM_CPU_PERCENT];
+
 /// Performance telemetry system
 pub struct PerformanceTelemetrySystem {
     /// Metrics storage
@@ -64,6 +77,12 @@ pub struct PerformanceTelemetrySystem {
     /// Regression detector
     regression_detector: Arc<RegressionDetector>,
     
+    /// Long-horizon memory trend tracking
+    leak_detector: Arc<LeakDetector>,
+    
+    /// Restarts leaking modules when `leak_restart_delay` is set
+    lifecycle: Option<Arc<LifecycleController>>,
+    
     /// Alert system
     alert_system: Arc<AlertSystem>,
     
@@ -71,6 +90,9 @@ pub struct PerformanceTelemetrySystem {
     aggregation_task: Option<JoinHandle<()>>,
     cleanup_task: Option<JoinHandle<()>>,
     
+    /// Where aggregated samples and regressions are published; storage persists them
+    event_bus: Option<Arc<dyn EventBusTrait>>,
+    
     /// Configuration
     config: TelemetryConfig,
 }
@@ -82,6 +104,21 @@ pub struct TelemetryConfig {
     pub aggregation_interval: Duration,
     pub retention_period: Duration,
     pub regression_threshold: f32,
+    /// A metric above `baseline * regression_factor` counts as regressed
+    pub regression_factor: f32,
+    /// How long a metric must stay regressed before `PerformanceRegression` is raised
+    pub regression_sustain: Duration,
+    /// History the rolling baselines are computed over
+    pub baseline_window: Duration,
+    /// Memory history a leak trend is fitted over
+    pub leak_window: Duration,
+    /// Growth has to be visible for this long before it counts as a leak
+    pub leak_min_span: Duration,
+    pub leak_min_growth_mb_per_hour: f64,
+    /// Minimum fit of the trend line; noisy memory use isn't a leak
+    pub leak_min_r_squared: f64,
+    /// Restart a leaking module this long after the leak is reported; never if unset
+    pub leak_restart_delay: Option<Duration>,
     pub alert_thresholds: AlertThresholds,
 }
 
@@ -93,6 +130,14 @@ impl Default for TelemetryConfig {
             aggregation_interval: METRICS_AGGREGATION_PERIOD,
             retention_period: METRICS_RETENTION_PERIOD,
             regression_threshold: 0.2, // 20% degradation
+            regression_factor: 1.5,
+            regression_sustain: Duration::from_secs(300),
+            baseline_window: Duration::from_secs(24 * 3600),
+            leak_window: Duration::from_secs(6 * 3600),
+            leak_min_span: Duration::from_secs(2 * 3600),
+            leak_min_growth_mb_per_hour: 5.0,
+            leak_min_r_squared: 0.8,
+            leak_restart_delay: None,
             alert_thresholds: AlertThresholds::default(),
         }
     }
@@ -123,6 +168,9 @@ pub struct MetricsStore {
     /// Per-module resource metrics
     module_metrics: HashMap<ModuleId, VecDeque<TimestampedResourceUsage>>,
     
+    /// Per-module processing latencies
+    latency_samples: HashMap<ModuleId, VecDeque<TimestampedLatency>>,
+    
     /// System-wide metrics
     system_metrics: VecDeque<TimestampedSystemResources>,
     
@@ -139,6 +187,12 @@ pub struct TimestampedResourceUsage {
     pub timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampedLatency {
+    pub latency_ms: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimestampedSystemResources {
     pub resources: SystemResources,
@@ -165,6 +219,7 @@ pub struct AlertEvent {
 pub enum AlertType {
     CpuThresholdExceeded,
     MemoryThresholdExceeded,
+    MemoryLeakSuspected,
     EventLossDetected,
     BatteryDrainHigh,
     PerformanceRegression,
@@ -184,26 +239,49 @@ impl PerformanceTelemetrySystem {
     pub fn new(config: TelemetryConfig) -> Self {
         let metrics_store = Arc::new(RwLock::new(MetricsStore {
             module_metrics: HashMap::new(),
+            latency_samples: HashMap::new(),
             system_metrics: VecDeque::new(),
             performance_stats: VecDeque::new(),
             alert_history: VecDeque::new(),
         }));
 
         let aggregator = Arc::new(MetricsAggregator::new());
-        let regression_detector = Arc::new(RegressionDetector::new(config.regression_threshold));
+        let regression_detector = Arc::new(
+            RegressionDetector::new(config.regression_threshold).with_rolling_baselines(
+                config.regression_factor,
+                config.regression_sustain,
+                config.baseline_window,
+            ),
+        );
         let alert_system = Arc::new(AlertSystem::new(config.alert_thresholds.clone()));
+        let leak_detector = Arc::new(LeakDetector::new(&config));
 
         Self {
             metrics_store,
             aggregator,
             regression_detector,
+            leak_detector,
+            lifecycle: None,
             alert_system,
             aggregation_task: None,
             cleanup_task: None,
+            event_bus: None,
             config,
         }
     }
 
+    /// Publish aggregated samples for storage and raise regressions on the bus
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusTrait>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Let `leak_restart_delay` restart leaking modules
+    pub fn with_lifecycle(mut self, lifecycle: Arc<LifecycleController>) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
     /// Start the telemetry system
     pub async fn start(&mut self) -> OrchestratorResult<()> {
         if !self.config.enabled {
@@ -217,7 +295,10 @@ impl PerformanceTelemetrySystem {
         let metrics_store = Arc::clone(&self.metrics_store);
         let aggregator = Arc::clone(&self.aggregator);
         let regression_detector = Arc::clone(&self.regression_detector);
+        let leak_detector = Arc::clone(&self.leak_detector);
+        let leak_restart = self.lifecycle.clone().zip(self.config.leak_restart_delay);
         let alert_system = Arc::clone(&self.alert_system);
+        let event_bus = self.event_bus.clone();
         let aggregation_interval = self.config.aggregation_interval;
 
         let aggregation_task = tokio::spawn(async move {
@@ -230,7 +311,11 @@ impl PerformanceTelemetrySystem {
                     &metrics_store,
                     &aggregator,
                     &regression_detector,
+                    &leak_detector,
+                    leak_restart.as_ref(),
                     &alert_system,
+                    event_bus.as_ref(),
+                    aggregation_interval,
                 ).await {
                     error!("Failed to run metrics aggregation: {}", e);
                 }
@@ -303,6 +388,25 @@ impl PerformanceTelemetrySystem {
         Ok(())
     }
 
+    /// Record how long a module took to process one unit of work
+    pub async fn record_latency(&self, module_id: ModuleId, latency: Duration) -> OrchestratorResult<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let mut store = self.metrics_store.write().await;
+        let samples = store.latency_samples.entry(module_id).or_default();
+        samples.push_back(TimestampedLatency {
+            latency_ms: latency.as_secs_f64() * 1000.0,
+            timestamp: Utc::now(),
+        });
+        if samples.len() > 1000 {
+            samples.pop_front();
+        }
+
+        Ok(())
+    }
+
     /// Record system resources
     pub async fn record_system_resources(&self, resources: SystemResources) -> OrchestratorResult<()> {
         if !self.config.enabled {
@@ -383,15 +487,33 @@ impl PerformanceTelemetrySystem {
             .cloned()
             .collect();
 
+        // A bus that can't report metrics shouldn't take the dashboard down with it
+        let slowest_flows = match self.slowest_flows(FlowQuery::any(), 10).await {
+            Ok(flows) => flows,
+            Err(e) => {
+                warn!("Failed to read event bus flow metrics: {}", e);
+                Vec::new()
+            }
+        };
+
         Ok(DashboardData {
             module_summaries,
             system_resources: latest_system,
             performance_stats: latest_performance,
             recent_alerts,
+            slowest_flows,
             last_updated: Instant::now(),
         })
     }
 
+    /// The `k` slowest event bus flows matching `query`, by p99 delivery plus handler time
+    pub async fn slowest_flows(&self, query: FlowQuery, k: usize) -> OrchestratorResult<Vec<FlowMetrics>> {
+        let Some(event_bus) = &self.event_bus else {
+            return Ok(Vec::new());
+        };
+        Ok(event_bus.metrics().await?.slowest_flows(query, k))
+    }
+
     /// Get performance trends
     pub async fn get_performance_trends(&self, duration: Duration) -> OrchestratorResult<PerformanceTrends> {
         let store = self.metrics_store.read().await;
@@ -432,24 +554,123 @@ impl PerformanceTelemetrySystem {
         metrics_store: &Arc<RwLock<MetricsStore>>,
         _aggregator: &Arc<MetricsAggregator>,
         regression_detector: &Arc<RegressionDetector>,
+        leak_detector: &Arc<LeakDetector>,
+        leak_restart: Option<&(Arc<LifecycleController>, Duration)>,
         alert_system: &Arc<AlertSystem>,
+        event_bus: Option<&Arc<dyn EventBusTrait>>,
+        aggregation_interval: Duration,
     ) -> OrchestratorResult<()> {
         debug!("Running metrics aggregation");
 
-        let store = metrics_store.read().await;
-        
-        // Check for performance regressions
-        if let Some(latest_stats) = store.performance_stats.back() {
-            regression_detector.check_regression(&latest_stats.stats).await;
+        let now = Utc::now();
+        let since = now - chrono::Duration::from_std(aggregation_interval).unwrap_or_default();
+
+        let samples = {
+            let store = metrics_store.read().await;
+            
+            // Check for performance regressions
+            if let Some(latest_stats) = store.performance_stats.back() {
+                regression_detector.check_regression(&latest_stats.stats).await;
+            }
+
+            // Update alert system with current state
+            alert_system.process_aggregated_metrics(&store).await;
+
+            store.aggregate_samples(since, now)
+        };
+
+        let mut regressions = Vec::new();
+        let mut leaks = Vec::new();
+        for sample in &samples {
+            if let Some(regression) = regression_detector.observe(sample).await {
+                regressions.push(regression);
+            }
+            if let Some(mut leak) = leak_detector.observe(sample).await {
+                if let Some((lifecycle, delay)) = leak_restart {
+                    leak.restart_at = Some(now + chrono::Duration::from_std(*delay).unwrap_or_default());
+                    Self::schedule_restart(Arc::clone(lifecycle), leak.module, *delay);
+                }
+                leaks.push(leak);
+            }
+        }
+
+        if !regressions.is_empty() || !leaks.is_empty() {
+            let mut store = metrics_store.write().await;
+            for regression in &regressions {
+                let module = regression.module.map(|m| m.to_string()).unwrap_or_else(|| "system".to_string());
+                let alert = AlertEvent {
+                    alert_type: AlertType::PerformanceRegression,
+                    severity: AlertSeverity::Error,
+                    message: format!(
+                        "{} {} at {:.2} is {:.1}x its baseline of {:.2} for {}s",
+                        module, regression.metric, regression.observed,
+                        regression.observed / regression.baseline, regression.baseline, regression.sustained_secs
+                    ),
+                    module_id: regression.module,
+                    timestamp: regression.timestamp,
+                    resolved: false,
+                };
+                warn!("📉 {}", alert.message);
+                store.alert_history.push_back(alert);
+            }
+            for leak in &leaks {
+                let alert = AlertEvent {
+                    alert_type: AlertType::MemoryLeakSuspected,
+                    severity: AlertSeverity::Error,
+                    message: format!(
+                        "{} memory grew {:.1}MB/h over {} samples (r² {:.2})",
+                        leak.module, leak.growth_mb_per_hour, leak.samples.len(), leak.r_squared
+                    ),
+                    module_id: Some(leak.module),
+                    timestamp: leak.timestamp,
+                    resolved: false,
+                };
+                warn!("💧 {}", alert.message);
+                store.alert_history.push_back(alert);
+            }
         }
 
-        // Update alert system with current state
-        alert_system.process_aggregated_metrics(&*store).await;
+        if let Some(event_bus) = event_bus {
+            if !samples.is_empty() {
+                let message = BusMessage::new(
+                    ModuleId::Orchestrator,
+                    MessagePayload::TelemetryBatch(TelemetryBatch { samples }),
+                );
+                event_bus.publish(message).await?;
+            }
+            for regression in regressions {
+                let message = BusMessage::with_priority(
+                    ModuleId::Orchestrator,
+                    MessagePayload::PerformanceRegression(regression),
+                    MessagePriority::High,
+                );
+                event_bus.publish(message).await?;
+            }
+            for leak in leaks {
+                let message = BusMessage::with_priority(
+                    ModuleId::Orchestrator,
+                    MessagePayload::MemoryLeakSuspected(leak),
+                    MessagePriority::High,
+                );
+                event_bus.publish(message).await?;
+            }
+        }
 
         debug!("Metrics aggregation completed");
         Ok(())
     }
 
+    /// Restart a leaking module once `delay` has passed
+    fn schedule_restart(lifecycle: Arc<LifecycleController>, module: ModuleId, delay: Duration) {
+        info!("Restarting {} in {}s to reclaim leaked memory", module, delay.as_secs());
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if let Err(e) = lifecycle.restart_module(module).await {
+                error!("Scheduled restart of leaking {} failed: {}", module, e);
+            }
+        });
+    }
+
     /// Clean up old metrics
     async fn cleanup_old_metrics(
         metrics_store: &Arc<RwLock<MetricsStore>>,
@@ -464,6 +685,9 @@ impl PerformanceTelemetrySystem {
         for metrics in store.module_metrics.values_mut() {
             metrics.retain(|ts_usage| ts_usage.timestamp >= cutoff_time);
         }
+        for samples in store.latency_samples.values_mut() {
+            samples.retain(|sample| sample.timestamp >= cutoff_time);
+        }
 
         // Clean up system metrics
         store.system_metrics.retain(|ts_resources| ts_resources.timestamp >= cutoff_time);
@@ -479,6 +703,63 @@ impl PerformanceTelemetrySystem {
     }
 }
 
+impl MetricsStore {
+    /// Summarise one aggregation interval into samples worth persisting
+    fn aggregate_samples(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> Vec<TelemetrySample> {
+        let mut samples = Vec::new();
+        let mut push = |module: Option<ModuleId>, metric: &str, value: f64| {
+            samples.push(TelemetrySample { module, metric: metric.to_string(), value, timestamp: now });
+        };
+
+        for (&module_id, metrics) in &self.module_metrics {
+            let recent: Vec<&ResourceUsage> = metrics.iter()
+                .filter(|ts_usage| ts_usage.timestamp >= since)
+                .map(|ts_usage| &ts_usage.usage)
+                .collect();
+            if let Some(cpu) = mean(recent.iter().map(|usage| usage.cpu_percent as f64)) {
+                push(Some(module_id), METRIC_CPU_PERCENT, cpu);
+            }
+            if let Some(memory) = mean(recent.iter().map(|usage| usage.memory_mb as f64)) {
+                push(Some(module_id), METRIC_MEMORY_MB, memory);
+            }
+        }
+
+        for (&module_id, latencies) in &self.latency_samples {
+            let mut recent: Vec<f64> = latencies.iter()
+                .filter(|sample| sample.timestamp >= since)
+                .map(|sample| sample.latency_ms)
+                .collect();
+            if let Some(p95) = percentile(&mut recent, 0.95) {
+                push(Some(module_id), METRIC_LATENCY_P95_MS, p95);
+            }
+        }
+
+        let system_cpu = mean(self.system_metrics.iter()
+            .filter(|ts_resources| ts_resources.timestamp >= since)
+            .map(|ts_resources| ts_resources.resources.total_cpu_usage as f64));
+        if let Some(cpu) = system_cpu {
+            push(None, METRIC_SYSTEM_CPU_PERCENT, cpu);
+        }
+
+        samples
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Nearest-rank percentile
+fn percentile(values: &mut [f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((p * values.len() as f64).ceil() as usize).clamp(1, values.len());
+    Some(values[rank - 1])
+}
+
 /// Dashboard data structure
 #[derive(Debug, Clone)]
 pub struct DashboardData {
@@ -486,6 +767,8 @@ pub struct DashboardData {
     pub system_resources: Option<SystemResources>,
     pub performance_stats: Option<PerformanceStats>,
     pub recent_alerts: Vec<AlertEvent>,
+    /// Slowest publisher → subscriber flows on the event bus
+    pub slowest_flows: Vec<FlowMetrics>,
     pub last_updated: Instant,
 }
 
@@ -514,6 +797,31 @@ impl MetricsAggregator {
 pub struct RegressionDetector {
     regression_threshold: f32,
     baseline_metrics: Arc<RwLock<Option<PerformanceBaseline>>>,
+    regression_factor: f64,
+    regression_sustain: Duration,
+    baseline_window: Duration,
+    rolling_baselines: Arc<RwLock<HashMap<MetricKey, RollingBaseline>>>,
+}
+
+/// A metric name, per module or system-wide
+type MetricKey = (Option<ModuleId>, String);
+
+/// Rolling baseline for one metric, built from per-interval aggregates
+#[derive(Debug, Default)]
+struct RollingBaseline {
+    history: VecDeque<(DateTime<Utc>, f64)>,
+    breach_since: Option<DateTime<Utc>>,
+    reported: bool,
+}
+
+impl RollingBaseline {
+    /// Mean of the healthy history, once there is enough of it to trust
+    fn value(&self) -> Option<f64> {
+        if self.history.len() < REGRESSION_DETECTION_SAMPLES {
+            return None;
+        }
+        mean(self.history.iter().map(|&(_, value)| value))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -526,10 +834,68 @@ pub struct PerformanceBaseline {
 
 impl RegressionDetector {
     pub fn new(threshold: f32) -> Self {
+        let defaults = TelemetryConfig::default();
         Self {
             regression_threshold: threshold,
             baseline_metrics: Arc::new(RwLock::new(None)),
+            regression_factor: defaults.regression_factor as f64,
+            regression_sustain: defaults.regression_sustain,
+            baseline_window: defaults.baseline_window,
+            rolling_baselines: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_rolling_baselines(mut self, factor: f32, sustain: Duration, window: Duration) -> Self {
+        self.regression_factor = factor as f64;
+        self.regression_sustain = sustain;
+        self.baseline_window = window;
+        self
+    }
+
+    /// Compare an aggregated sample with its rolling baseline. Returns a regression
+    /// once the sample has stayed above `baseline * factor` for the sustain period;
+    /// each regression is reported once until the metric recovers
+    pub async fn observe(&self, sample: &TelemetrySample) -> Option<PerformanceRegression> {
+        if !REGRESSION_METRICS.contains(&sample.metric.as_str()) {
+            return None;
+        }
+
+        let now = sample.timestamp;
+        let window = chrono::Duration::from_std(self.baseline_window).unwrap_or_default();
+        let mut baselines = self.rolling_baselines.write().await;
+        let tracker = baselines.entry((sample.module, sample.metric.clone())).or_default();
+        tracker.history.retain(|&(timestamp, _)| now - timestamp <= window);
+
+        let baseline = match tracker.value() {
+            Some(baseline) if baseline > 0.0 && sample.value > baseline * self.regression_factor => baseline,
+            _ => {
+                if tracker.reported {
+                    info!("📈 {} recovered to {:.2}", sample.metric, sample.value);
+                }
+                tracker.breach_since = None;
+                tracker.reported = false;
+                // Only healthy intervals feed the baseline, so a regression can't become the new normal
+                tracker.history.push_back((now, sample.value));
+                return None;
+            }
+        };
+
+        let breach_since = *tracker.breach_since.get_or_insert(now);
+        let sustained = (now - breach_since).to_std().unwrap_or_default();
+        if tracker.reported || sustained < self.regression_sustain {
+            return None;
         }
+
+        tracker.reported = true;
+        Some(PerformanceRegression {
+            module: sample.module,
+            metric: sample.metric.clone(),
+            baseline,
+            observed: sample.value,
+            factor: self.regression_factor,
+            sustained_secs: sustained.as_secs(),
+            timestamp: now,
+        })
     }
 
     pub async fn check_regression(&self, current_stats: &PerformanceStats) {
@@ -567,6 +933,95 @@ impl RegressionDetector {
     }
 }
 
+/// Fits a line to each module's memory over hours and flags steady growth
+pub struct LeakDetector {
+    window: Duration,
+    min_span: Duration,
+    min_growth_mb_per_hour: f64,
+    min_r_squared: f64,
+    trends: Arc<RwLock<HashMap<ModuleId, MemoryTrend>>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryTrend {
+    samples: VecDeque<(DateTime<Utc>, f64)>,
+    reported: bool,
+}
+
+impl LeakDetector {
+    pub fn new(config: &TelemetryConfig) -> Self {
+        Self {
+            window: config.leak_window,
+            min_span: config.leak_min_span,
+            min_growth_mb_per_hour: config.leak_min_growth_mb_per_hour,
+            min_r_squared: config.leak_min_r_squared,
+            trends: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Add an aggregated memory sample. Returns a suspected leak once growth over
+    /// at least `min_span` is steep and steady enough; reported once until it stops
+    pub async fn observe(&self, sample: &TelemetrySample) -> Option<MemoryLeakSuspected> {
+        let module = sample.module.filter(|_| sample.metric == METRIC_MEMORY_MB)?;
+
+        let now = sample.timestamp;
+        let window = chrono::Duration::from_std(self.window).unwrap_or_default();
+        let mut trends = self.trends.write().await;
+        let trend = trends.entry(module).or_default();
+        trend.samples.push_back((now, sample.value));
+        trend.samples.retain(|&(timestamp, _)| now - timestamp <= window);
+
+        let oldest = trend.samples.front()?.0;
+        let span = (now - oldest).to_std().unwrap_or_default();
+        if span < self.min_span || trend.samples.len() < REGRESSION_DETECTION_SAMPLES {
+            return None;
+        }
+
+        let points: Vec<(f64, f64)> = trend.samples.iter()
+            .map(|&(timestamp, value)| ((timestamp - oldest).num_seconds() as f64 / 3600.0, value))
+            .collect();
+        let (slope, r_squared) = linear_fit(&points)?;
+        if slope < self.min_growth_mb_per_hour || r_squared < self.min_r_squared {
+            if trend.reported {
+                info!("📈 {} memory growth has stopped", module);
+            }
+            trend.reported = false;
+            return None;
+        }
+        if trend.reported {
+            return None;
+        }
+
+        trend.reported = true;
+        Some(MemoryLeakSuspected {
+            module,
+            growth_mb_per_hour: slope,
+            r_squared,
+            samples: trend.samples.iter().copied().collect(),
+            restart_at: None,
+            timestamp: now,
+        })
+    }
+}
+
+/// Least-squares slope and coefficient of determination
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    let mean_x = mean(points.iter().map(|&(x, _)| x))?;
+    let mean_y = mean(points.iter().map(|&(_, y)| y))?;
+    let sxx: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy: f64 = points.iter().map(|&(_, y)| (y - mean_y).powi(2)).sum();
+    if n < 2.0 || sxx == 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    // A perfectly flat series fits perfectly but isn't growing
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+    Some((slope, r_squared))
+}
+
 /// Alert system for proactive monitoring
 pub struct AlertSystem {
     thresholds: AlertThresholds,
@@ -658,4 +1113,104 @@ impl AlertSystem {
             AlertSeverity::Critical => error!("CRITICAL: {}", alert.message),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(value: f64, timestamp: DateTime<Utc>) -> TelemetrySample {
+        TelemetrySample {
+            module: Some(ModuleId::AnalysisEngine),
+            metric: METRIC_LATENCY_P95_MS.to_string(),
+            value,
+            timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regression_requires_sustained_breach() {
+        let detector = RegressionDetector::new(0.2).with_rolling_baselines(
+            1.5,
+            Duration::from_secs(120),
+            Duration::from_secs(3600),
+        );
+        let start = Utc::now();
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+
+        for minute in 0..REGRESSION_DETECTION_SAMPLES as i64 {
+            assert!(detector.observe(&sample(10.0, at(minute))).await.is_none());
+        }
+
+        // A single spike is not a regression
+        assert!(detector.observe(&sample(40.0, at(10))).await.is_none());
+        assert!(detector.observe(&sample(10.0, at(11))).await.is_none());
+
+        assert!(detector.observe(&sample(20.0, at(12))).await.is_none());
+        assert!(detector.observe(&sample(20.0, at(13))).await.is_none());
+        let regression = detector.observe(&sample(20.0, at(14))).await.expect("sustained for two minutes");
+        assert_eq!(regression.baseline, 10.0);
+        assert_eq!(regression.sustained_secs, 120);
+
+        // Reported once per breach
+        assert!(detector.observe(&sample(20.0, at(15))).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_steady_memory_growth_is_a_suspected_leak() {
+        let detector = LeakDetector::new(&TelemetryConfig::default());
+        let start = Utc::now();
+        let memory = |minutes: i64, mb: f64| TelemetrySample {
+            module: Some(ModuleId::Storage),
+            metric: METRIC_MEMORY_MB.to_string(),
+            value: mb,
+            timestamp: start + chrono::Duration::minutes(minutes),
+        };
+
+        // Noisy but flat for three hours
+        for minute in (0..180).step_by(10) {
+            let jitter = if minute % 20 == 0 { 8.0 } else { -8.0 };
+            assert!(detector.observe(&memory(minute, 100.0 + jitter)).await.is_none());
+        }
+
+        // Then 12MB/h of steady growth; it takes a while to dominate the fit
+        let mut leak = None;
+        for minute in (180..600).step_by(10) {
+            let grown = 100.0 + 12.0 * (minute - 180) as f64 / 60.0;
+            if let Some(found) = detector.observe(&memory(minute, grown)).await {
+                assert!(leak.is_none(), "reported once");
+                leak = Some(found);
+            }
+        }
+
+        let leak = leak.expect("leak detected");
+        assert_eq!(leak.module, ModuleId::Storage);
+        assert!(leak.growth_mb_per_hour >= 5.0 && leak.r_squared >= 0.8);
+        assert!(leak.samples.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_linear_fit() {
+        let (slope, r_squared) = linear_fit(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]).unwrap();
+        assert!((slope - 2.0).abs() < 1e-9);
+        assert!((r_squared - 1.0).abs() < 1e-9);
+        assert!(linear_fit(&[(1.0, 1.0)]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregation_computes_p95_latency() {
+        let telemetry = PerformanceTelemetrySystem::new(TelemetryConfig::default());
+        for ms in 1..=100 {
+            telemetry.record_latency(ModuleId::Storage, Duration::from_millis(ms)).await.unwrap();
+        }
+
+        let now = Utc::now();
+        let store = telemetry.metrics_store.read().await;
+        let samples = store.aggregate_samples(now - chrono::Duration::minutes(1), now);
+        let p95 = samples.iter()
+            .find(|sample| sample.metric == METRIC_LATENCY_P95_MS)
+            .expect("latency sample");
+        assert_eq!(p95.module, Some(ModuleId::Storage));
+        assert!((p95.value - 95.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,508 @@
+//! Battery and thermal awareness
+//!
+//! Watches battery level, charging state, and thermal pressure, and moves the
+//! system between power states with hysteresis so a battery hovering around a
+//! threshold doesn't make modules flap. Each state maps to a policy that slows
+//! capture sampling, lengthens analysis windows, and defers model training.
+
+use crate::config::ConfigurationManager;
+use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::profiles::BATTERY_STATE_KEY;
+use crate::resource::BatteryOptimization;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{
+    message::PowerStateChange, BusMessage, EventBusTrait, MessagePayload, MessagePriority, ModuleId,
+};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{debug, info, warn};
+
+/// Config key for thermal reports, e.g. `{"pressure": "serious"}`
+pub const THERMAL_STATE_KEY: &str = "thermal_state";
+
+/// OS-reported thermal pressure, mildest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThermalPressure {
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+impl ThermalPressure {
+    /// Classify the hottest thermal zone temperature
+    pub fn from_celsius(celsius: f32) -> Self {
+        match celsius {
+            c if c >= 90.0 => ThermalPressure::Critical,
+            c if c >= 80.0 => ThermalPressure::Serious,
+            c if c >= 70.0 => ThermalPressure::Fair,
+            _ => ThermalPressure::Nominal,
+        }
+    }
+}
+
+impl fmt::Display for ThermalPressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ThermalPressure::Nominal => "nominal",
+            ThermalPressure::Fair => "fair",
+            ThermalPressure::Serious => "serious",
+            ThermalPressure::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One reading of the machine's power situation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerSample {
+    /// None on machines without a battery
+    pub battery_percent: Option<f32>,
+    pub charging: bool,
+    pub thermal_pressure: ThermalPressure,
+}
+
+impl Default for PowerSample {
+    fn default() -> Self {
+        Self {
+            battery_percent: None,
+            charging: true,
+            thermal_pressure: ThermalPressure::Nominal,
+        }
+    }
+}
+
+/// Power-saving tier the system is running in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerState {
+    Normal,
+    Conserve,
+    Critical,
+}
+
+impl fmt::Display for PowerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PowerState::Normal => "normal",
+            PowerState::Conserve => "conserve",
+            PowerState::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+impl PowerState {
+    /// State a sample calls for, given the current state
+    ///
+    /// Leaving a state takes `hysteresis_percent` more battery than entering
+    /// it, or thermal pressure one level below the one that triggered it.
+    pub fn next(self, sample: &PowerSample, settings: &BatteryOptimization) -> Self {
+        if !settings.enabled {
+            return PowerState::Normal;
+        }
+
+        let margin = |level: PowerState| if self >= level { settings.hysteresis_percent } else { 0.0 };
+        let battery_below = |threshold: f32| {
+            !sample.charging && sample.battery_percent.is_some_and(|percent| percent < threshold)
+        };
+        let thermal_at = |level: PowerState, trigger: ThermalPressure, hold: ThermalPressure| {
+            sample.thermal_pressure >= trigger || (self >= level && sample.thermal_pressure >= hold)
+        };
+
+        if battery_below(settings.critical_threshold + margin(PowerState::Critical))
+            || thermal_at(PowerState::Critical, ThermalPressure::Critical, ThermalPressure::Serious)
+        {
+            PowerState::Critical
+        } else if battery_below(settings.power_save_threshold + margin(PowerState::Conserve))
+            || thermal_at(PowerState::Conserve, ThermalPressure::Serious, ThermalPressure::Fair)
+        {
+            PowerState::Conserve
+        } else {
+            PowerState::Normal
+        }
+    }
+}
+
+/// Module adjustments applied in a power state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerPolicy {
+    /// Multiplier on data capture sampling rates
+    pub capture_sampling_factor: f32,
+    /// Multiplier on analysis window length
+    pub analysis_window_factor: f32,
+    /// Postpone model training until power recovers
+    pub defer_training: bool,
+}
+
+impl PowerPolicy {
+    pub fn for_state(state: PowerState, settings: &BatteryOptimization) -> Self {
+        match state {
+            PowerState::Normal => Self {
+                capture_sampling_factor: 1.0,
+                analysis_window_factor: 1.0,
+                defer_training: false,
+            },
+            PowerState::Conserve => Self {
+                capture_sampling_factor: settings.cpu_throttle_factor,
+                analysis_window_factor: 2.0,
+                defer_training: true,
+            },
+            PowerState::Critical => Self {
+                capture_sampling_factor: settings.cpu_throttle_factor / 2.0,
+                analysis_window_factor: 4.0,
+                defer_training: true,
+            },
+        }
+    }
+
+    /// Per-module config fragments for this policy
+    pub fn module_configs(&self, state: PowerState) -> Vec<(ModuleId, serde_json::Value)> {
+        vec![
+            (ModuleId::DataCapture, serde_json::json!({
+                "power_sampling_factor": self.capture_sampling_factor,
+                "power_state": state,
+            })),
+            (ModuleId::AnalysisEngine, serde_json::json!({
+                "power_window_factor": self.analysis_window_factor,
+                "defer_training": self.defer_training,
+                "power_state": state,
+            })),
+        ]
+    }
+}
+
+/// Reads battery and thermal state from sysfs
+#[cfg(target_os = "linux")]
+pub struct SysfsPowerSource {
+    power_supply: std::path::PathBuf,
+    thermal: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl SysfsPowerSource {
+    pub fn new() -> Self {
+        Self::with_root("/sys/class")
+    }
+
+    /// Read from an alternate `/sys/class` tree
+    pub fn with_root(root: impl Into<std::path::PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            power_supply: root.join("power_supply"),
+            thermal: root.join("thermal"),
+        }
+    }
+
+    pub fn sample(&self) -> PowerSample {
+        let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+        let mut sample = PowerSample::default();
+        let mut battery_charging = false;
+        let mut mains_online = false;
+
+        for entry in std::fs::read_dir(&self.power_supply).into_iter().flatten().flatten() {
+            let path = entry.path();
+            match read(path.join("type")).as_deref() {
+                Some("Battery") => {
+                    sample.battery_percent = read(path.join("capacity")).and_then(|c| c.parse().ok());
+                    battery_charging = matches!(read(path.join("status")).as_deref(), Some("Charging") | Some("Full"));
+                }
+                Some("Mains") => mains_online |= read(path.join("online")).as_deref() == Some("1"),
+                _ => {}
+            }
+        }
+        // Without a battery the machine is on mains power by definition
+        sample.charging = sample.battery_percent.is_none() || battery_charging || mains_online;
+
+        let hottest = std::fs::read_dir(&self.thermal)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("thermal_zone"))
+            .filter_map(|entry| read(entry.path().join("temp")).and_then(|t| t.parse::<f32>().ok()))
+            .fold(None, |max: Option<f32>, millidegrees| Some(max.map_or(millidegrees, |m| m.max(millidegrees))));
+        if let Some(millidegrees) = hottest {
+            sample.thermal_pressure = ThermalPressure::from_celsius(millidegrees / 1000.0);
+        }
+
+        sample
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Default for SysfsPowerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Current state plus the sample that produced it
+#[derive(Debug, Clone)]
+struct PowerTracker {
+    state: PowerState,
+    sample: PowerSample,
+    entered_at: Instant,
+}
+
+/// Drives power states from samples and pushes policy changes to modules
+pub struct PowerManager {
+    settings: BatteryOptimization,
+    config_manager: Arc<ConfigurationManager>,
+    event_bus: Arc<dyn EventBusTrait>,
+    tracker: RwLock<PowerTracker>,
+    monitor_task: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl PowerManager {
+    pub fn new(
+        settings: BatteryOptimization,
+        config_manager: Arc<ConfigurationManager>,
+        event_bus: Arc<dyn EventBusTrait>,
+    ) -> Self {
+        Self {
+            settings,
+            config_manager,
+            event_bus,
+            tracker: RwLock::new(PowerTracker {
+                state: PowerState::Normal,
+                sample: PowerSample::default(),
+                entered_at: Instant::now(),
+            }),
+            monitor_task: RwLock::new(None),
+        }
+    }
+
+    /// Current power state
+    pub async fn power_state(&self) -> PowerState {
+        self.tracker.read().await.state
+    }
+
+    /// Most recent sample
+    pub async fn last_sample(&self) -> PowerSample {
+        self.tracker.read().await.sample.clone()
+    }
+
+    /// Feed a sample; returns the new state if it changed
+    pub async fn report(&self, sample: PowerSample) -> OrchestratorResult<Option<PowerState>> {
+        let (from, to) = {
+            let mut tracker = self.tracker.write().await;
+            let from = tracker.state;
+            let mut to = from.next(&sample, &self.settings);
+
+            // Relaxing waits out the dwell time; tightening is immediate
+            if to < from && tracker.entered_at.elapsed() < self.settings.min_dwell {
+                debug!("Holding power state {} for dwell time", from);
+                to = from;
+            }
+
+            tracker.sample = sample.clone();
+            if to == from {
+                return Ok(None);
+            }
+            tracker.state = to;
+            tracker.entered_at = Instant::now();
+            (from, to)
+        };
+
+        self.apply(to).await?;
+        self.publish_change(from, to, &sample).await?;
+
+        info!("🔋 Power state {} → {} (battery {:?}, charging {}, thermal {})",
+              from, to, sample.battery_percent, sample.charging, sample.thermal_pressure);
+        Ok(Some(to))
+    }
+
+    /// Handle battery and thermal reports sent as config updates
+    pub async fn handle_message(&self, message: &BusMessage) -> OrchestratorResult<Option<PowerState>> {
+        let update = match &message.payload {
+            MessagePayload::ConfigUpdate(update)
+                if matches!(update.target_module, None | Some(ModuleId::Orchestrator)) => update,
+            _ => return Ok(None),
+        };
+
+        let mut sample = self.last_sample().await;
+        match update.config_key.as_str() {
+            BATTERY_STATE_KEY => {
+                sample.battery_percent = update.config_value.get("percent")
+                    .and_then(|v| v.as_f64())
+                    .map(|v| v as f32);
+                sample.charging = update.config_value.get("charging")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+            }
+            THERMAL_STATE_KEY => {
+                let pressure = update.config_value.get("pressure").cloned().unwrap_or_default();
+                sample.thermal_pressure = serde_json::from_value(pressure)
+                    .map_err(|e| OrchestratorError::ConfigurationError {
+                        module: ModuleId::Orchestrator,
+                        reason: format!("Invalid thermal pressure: {}", e),
+                    })?;
+            }
+            _ => return Ok(None),
+        }
+
+        self.report(sample).await
+    }
+
+    /// Poll sysfs for battery and thermal state (Linux only; other platforms report over the bus)
+    pub async fn start_monitoring(self: &Arc<Self>, interval: Duration) {
+        #[cfg(target_os = "linux")]
+        {
+            let manager = Arc::clone(self);
+            let source = SysfsPowerSource::new();
+            let task = tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = manager.report(source.sample()).await {
+                        warn!("Failed to apply power sample: {}", e);
+                    }
+                }
+            });
+            *self.monitor_task.write().await = Some(task);
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            debug!("No power source polling on this platform (interval {:?})", interval);
+        }
+    }
+
+    pub async fn stop_monitoring(&self) {
+        if let Some(task) = self.monitor_task.write().await.take() {
+            task.abort();
+        }
+    }
+
+    /// Merge the state's policy into each affected module's config
+    async fn apply(&self, state: PowerState) -> OrchestratorResult<()> {
+        let policy = PowerPolicy::for_state(state, &self.settings);
+        for (module_id, fragment) in policy.module_configs(state) {
+            let mut config = self.config_manager.get_config(module_id).await
+                .filter(|existing| existing.is_object())
+                .unwrap_or_else(|| serde_json::json!({}));
+
+            if let (Some(target), Some(values)) = (config.as_object_mut(), fragment.as_object()) {
+                for (key, value) in values {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+
+            self.config_manager.update_config(module_id, config).await?;
+        }
+        Ok(())
+    }
+
+    async fn publish_change(&self, from: PowerState, to: PowerState, sample: &PowerSample) -> OrchestratorResult<()> {
+        let change = PowerStateChange {
+            from: from.to_string(),
+            to: to.to_string(),
+            battery_percent: sample.battery_percent,
+            charging: sample.charging,
+            thermal_pressure: sample.thermal_pressure.to_string(),
+            timestamp: Utc::now(),
+        };
+        let message = BusMessage::with_priority(
+            ModuleId::Orchestrator,
+            MessagePayload::PowerStateChanged(change),
+            MessagePriority::High,
+        );
+        self.event_bus.publish(message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrchestratorConfig;
+    use skelly_jelly_event_bus::create_event_bus;
+
+    fn battery(percent: f32) -> PowerSample {
+        PowerSample {
+            battery_percent: Some(percent),
+            charging: false,
+            thermal_pressure: ThermalPressure::Nominal,
+        }
+    }
+
+    #[test]
+    fn test_battery_hysteresis() {
+        let settings = BatteryOptimization::default();
+
+        let state = PowerState::Normal.next(&battery(19.0), &settings);
+        assert_eq!(state, PowerState::Conserve);
+        // Above the threshold but inside the hysteresis band: stay
+        assert_eq!(state.next(&battery(22.0), &settings), PowerState::Conserve);
+        assert_eq!(state.next(&battery(26.0), &settings), PowerState::Normal);
+        // Plugging in clears it immediately
+        let plugged = PowerSample { charging: true, ..battery(5.0) };
+        assert_eq!(PowerState::Critical.next(&plugged, &settings), PowerState::Normal);
+    }
+
+    #[test]
+    fn test_thermal_hysteresis() {
+        let settings = BatteryOptimization::default();
+        let thermal = |pressure| PowerSample { thermal_pressure: pressure, ..PowerSample::default() };
+
+        assert_eq!(PowerState::Normal.next(&thermal(ThermalPressure::Fair), &settings), PowerState::Normal);
+        assert_eq!(PowerState::Normal.next(&thermal(ThermalPressure::Critical), &settings), PowerState::Critical);
+        assert_eq!(PowerState::Critical.next(&thermal(ThermalPressure::Serious), &settings), PowerState::Critical);
+        assert_eq!(PowerState::Critical.next(&thermal(ThermalPressure::Fair), &settings), PowerState::Conserve);
+    }
+
+    #[tokio::test]
+    async fn test_report_applies_policy_and_publishes() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let config_manager = Arc::new(ConfigurationManager::new(OrchestratorConfig::default(), bus.clone()));
+        let settings = BatteryOptimization { min_dwell: Duration::ZERO, ..Default::default() };
+        let power = PowerManager::new(settings, Arc::clone(&config_manager), bus.clone());
+
+        assert_eq!(power.report(battery(50.0)).await.unwrap(), None);
+        assert_eq!(power.report(battery(8.0)).await.unwrap(), Some(PowerState::Critical));
+
+        let analysis = config_manager.get_config(ModuleId::AnalysisEngine).await.unwrap();
+        assert_eq!(analysis["defer_training"], true);
+        assert_eq!(analysis["power_window_factor"], 4.0);
+        assert_eq!(analysis["power_state"], "critical");
+        // Two config updates plus the PowerStateChanged event
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 3);
+    }
+
+    #[tokio::test]
+    async fn test_dwell_time_delays_relaxing() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let config_manager = Arc::new(ConfigurationManager::new(OrchestratorConfig::default(), bus.clone()));
+        let power = PowerManager::new(BatteryOptimization::default(), config_manager, bus);
+
+        power.report(battery(15.0)).await.unwrap();
+        assert_eq!(power.report(battery(90.0)).await.unwrap(), None);
+        assert_eq!(power.power_state().await, PowerState::Conserve);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sysfs_source() {
+        let root = tempfile::tempdir().unwrap();
+        let bat = root.path().join("power_supply/BAT0");
+        let zone = root.path().join("thermal/thermal_zone0");
+        std::fs::create_dir_all(&bat).unwrap();
+        std::fs::create_dir_all(&zone).unwrap();
+        std::fs::write(bat.join("type"), "Battery\n").unwrap();
+        std::fs::write(bat.join("capacity"), "42\n").unwrap();
+        std::fs::write(bat.join("status"), "Discharging\n").unwrap();
+        std::fs::write(zone.join("temp"), "85000\n").unwrap();
+
+        let sample = SysfsPowerSource::with_root(root.path()).sample();
+        assert_eq!(sample.battery_percent, Some(42.0));
+        assert!(!sample.charging);
+        assert_eq!(sample.thermal_pressure, ThermalPressure::Serious);
+    }
+}
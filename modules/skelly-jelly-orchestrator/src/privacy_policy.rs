@@ -0,0 +1,74 @@
+//! Shared privacy policy distribution
+//!
+//! The orchestrator owns the one privacy policy all modules apply: which
+//! entities are detected, how each is masked, and which apps and window titles
+//! are sensitive. It is broadcast before modules start and again whenever it
+//! changes, so no module keeps its own copy of these rules.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use skelly_jelly_event_bus::{message::ConfigUpdate, BusMessage, MessagePayload, ModuleId};
+use skelly_jelly_privacy_policy::{PrivacyPolicyConfig, PRIVACY_POLICY_KEY};
+
+/// Check that every entity rule's confidence threshold is within 0.0 - 1.0
+pub fn validate_privacy_policy(policy: &PrivacyPolicyConfig) -> OrchestratorResult<()> {
+    match policy
+        .entities
+        .iter()
+        .find(|(_, rule)| !(0.0..=1.0).contains(&rule.min_confidence))
+    {
+        Some((kind, rule)) => Err(OrchestratorError::ConfigurationError {
+            module: ModuleId::Orchestrator,
+            reason: format!(
+                "Privacy policy rule for {:?} has min_confidence {} outside 0.0 - 1.0",
+                kind, rule.min_confidence
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Broadcast telling modules which privacy policy to apply
+pub fn privacy_policy_announcement(policy: &PrivacyPolicyConfig) -> BusMessage {
+    BusMessage::new(
+        ModuleId::Orchestrator,
+        MessagePayload::ConfigUpdate(ConfigUpdate {
+            config_key: PRIVACY_POLICY_KEY.to_string(),
+            config_value: serde_json::json!(policy),
+            target_module: None,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skelly_jelly_privacy_policy::{EntityKind, EntityRule, MaskingStrategy};
+
+    #[test]
+    fn test_announcement_round_trips() {
+        let mut policy = PrivacyPolicyConfig::default();
+        policy
+            .entities
+            .insert(EntityKind::Email, EntityRule::new(MaskingStrategy::Hash, 0.7));
+
+        match privacy_policy_announcement(&policy).payload {
+            MessagePayload::ConfigUpdate(update) => {
+                assert_eq!(update.config_key, PRIVACY_POLICY_KEY);
+                assert!(update.target_module.is_none());
+                let received: PrivacyPolicyConfig = serde_json::from_value(update.config_value).unwrap();
+                assert_eq!(received, policy);
+            }
+            other => panic!("expected ConfigUpdate, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_thresholds() {
+        let mut policy = PrivacyPolicyConfig::default();
+        assert!(validate_privacy_policy(&policy).is_ok());
+        policy
+            .entities
+            .insert(EntityKind::Phone, EntityRule::new(MaskingStrategy::Redact, 1.5));
+        assert!(validate_privacy_policy(&policy).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+//! System-wide profiles that retune several modules at once
+//!
+//! A profile is a coordinated preset: how often data capture samples, how
+//! often the analysis engine classifies, and how readily the AI intervenes.
+//! Profiles switch on user command or automatically when a fullscreen game
+//! starts or the battery runs low.
+
+use crate::config::ConfigurationManager;
+use crate::error::{OrchestratorError, OrchestratorResult};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{BusMessage, MessagePayload, ModuleId};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+/// Config key for switching profiles over the bus, e.g. `{"profile": "focus"}`
+pub const PROFILE_SWITCH_KEY: &str = "system_profile";
+/// Config key for battery reports, e.g. `{"percent": 18.0, "charging": false}`
+pub const BATTERY_STATE_KEY: &str = "battery_state";
+/// Config key for fullscreen reports, e.g. `{"fullscreen_game": true, "app": "steam"}`
+pub const FULLSCREEN_STATE_KEY: &str = "fullscreen_state";
+
+/// Named system profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemProfile {
+    /// Everyday defaults
+    Balanced,
+    /// Closer tracking and more active coaching during deep work
+    Focus,
+    /// Stay out of the way while a fullscreen game runs
+    Gaming,
+    /// Sample and analyze as little as possible to save battery
+    LowPower,
+}
+
+impl std::fmt::Display for SystemProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SystemProfile::Balanced => "balanced",
+            SystemProfile::Focus => "focus",
+            SystemProfile::Gaming => "gaming",
+            SystemProfile::LowPower => "low_power",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Module settings a profile applies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfilePreset {
+    /// Data capture sampling rate
+    pub capture_sampling_hz: f32,
+    /// Time between analysis engine classifications
+    pub analysis_interval: Duration,
+    /// How readily the AI offers interventions, 0.0 (never) to 1.0 (eager)
+    pub intervention_aggressiveness: f32,
+}
+
+impl ProfilePreset {
+    /// Built-in preset for a profile
+    pub fn for_profile(profile: SystemProfile) -> Self {
+        match profile {
+            SystemProfile::Balanced => Self {
+                capture_sampling_hz: 10.0,
+                analysis_interval: Duration::from_secs(30),
+                intervention_aggressiveness: 0.5,
+            },
+            SystemProfile::Focus => Self {
+                capture_sampling_hz: 20.0,
+                analysis_interval: Duration::from_secs(15),
+                intervention_aggressiveness: 0.7,
+            },
+            SystemProfile::Gaming => Self {
+                capture_sampling_hz: 2.0,
+                analysis_interval: Duration::from_secs(120),
+                intervention_aggressiveness: 0.0,
+            },
+            SystemProfile::LowPower => Self {
+                capture_sampling_hz: 1.0,
+                analysis_interval: Duration::from_secs(90),
+                intervention_aggressiveness: 0.3,
+            },
+        }
+    }
+
+    /// Per-module config fragments for this preset
+    pub fn module_configs(&self) -> Vec<(ModuleId, serde_json::Value)> {
+        vec![
+            (ModuleId::DataCapture, serde_json::json!({
+                "sampling_rate_hz": self.capture_sampling_hz,
+            })),
+            (ModuleId::AnalysisEngine, serde_json::json!({
+                "analysis_interval_ms": self.analysis_interval.as_millis() as u64,
+            })),
+            (ModuleId::AiIntegration, serde_json::json!({
+                "intervention_aggressiveness": self.intervention_aggressiveness,
+            })),
+        ]
+    }
+}
+
+/// What caused a profile switch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProfileTrigger {
+    UserCommand,
+    FullscreenGame { app: Option<String> },
+    LowBattery { percent: f32 },
+    ConditionCleared,
+}
+
+/// A profile switch that was applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileChange {
+    pub from: SystemProfile,
+    pub to: SystemProfile,
+    pub trigger: ProfileTrigger,
+}
+
+/// Settings for automatic profile switching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Switch automatically on battery and fullscreen reports
+    pub auto_switch: bool,
+    /// Battery percentage below which low-power kicks in while discharging
+    pub low_battery_threshold: f32,
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            auto_switch: true,
+            low_battery_threshold: 20.0,
+        }
+    }
+}
+
+/// Signals and choices that decide the active profile
+#[derive(Debug, Clone)]
+struct ProfileInputs {
+    /// Profile the user picked; automatic switches return here when they clear
+    selected: SystemProfile,
+    active: SystemProfile,
+    battery_percent: Option<f32>,
+    charging: bool,
+    fullscreen_game: Option<Option<String>>,
+}
+
+/// Chooses and applies system profiles
+pub struct ProfileManager {
+    config: ProfileConfig,
+    config_manager: Arc<ConfigurationManager>,
+    inputs: RwLock<ProfileInputs>,
+}
+
+impl ProfileManager {
+    pub fn new(config: ProfileConfig, config_manager: Arc<ConfigurationManager>) -> Self {
+        Self {
+            config,
+            config_manager,
+            inputs: RwLock::new(ProfileInputs {
+                selected: SystemProfile::Balanced,
+                active: SystemProfile::Balanced,
+                battery_percent: None,
+                charging: true,
+                fullscreen_game: None,
+            }),
+        }
+    }
+
+    /// Currently applied profile
+    pub async fn active_profile(&self) -> SystemProfile {
+        self.inputs.read().await.active
+    }
+
+    /// Profile the user last picked
+    pub async fn selected_profile(&self) -> SystemProfile {
+        self.inputs.read().await.selected
+    }
+
+    /// Switch profile on the user's behalf; automatic overrides still win while their condition holds
+    pub async fn select_profile(&self, profile: SystemProfile) -> OrchestratorResult<Option<ProfileChange>> {
+        self.inputs.write().await.selected = profile;
+        self.reevaluate(ProfileTrigger::UserCommand).await
+    }
+
+    /// Report battery state
+    pub async fn report_battery(&self, percent: f32, charging: bool) -> OrchestratorResult<Option<ProfileChange>> {
+        {
+            let mut inputs = self.inputs.write().await;
+            inputs.battery_percent = Some(percent);
+            inputs.charging = charging;
+        }
+        self.reevaluate(ProfileTrigger::LowBattery { percent }).await
+    }
+
+    /// Report whether a fullscreen game is in the foreground
+    pub async fn report_fullscreen_game(&self, app: Option<String>, active: bool) -> OrchestratorResult<Option<ProfileChange>> {
+        {
+            let mut inputs = self.inputs.write().await;
+            inputs.fullscreen_game = active.then(|| app.clone());
+        }
+        self.reevaluate(ProfileTrigger::FullscreenGame { app }).await
+    }
+
+    /// Handle a profile command or power/display report from the bus
+    pub async fn handle_message(&self, message: &BusMessage) -> OrchestratorResult<Option<ProfileChange>> {
+        let MessagePayload::ConfigUpdate(update) = &message.payload else {
+            return Ok(None);
+        };
+        if update.target_module.is_some_and(|target| target != ModuleId::Orchestrator) {
+            return Ok(None);
+        }
+
+        let value = &update.config_value;
+        let invalid = |reason: &str| OrchestratorError::ConfigurationError {
+            module: message.source,
+            reason: format!("Invalid {} update: {}", update.config_key, reason),
+        };
+
+        match update.config_key.as_str() {
+            PROFILE_SWITCH_KEY => {
+                let profile = value.get("profile")
+                    .cloned()
+                    .ok_or_else(|| invalid("missing profile"))
+                    .and_then(|p| serde_json::from_value(p).map_err(|e| invalid(&e.to_string())))?;
+                self.select_profile(profile).await
+            }
+            BATTERY_STATE_KEY => {
+                let percent = value.get("percent")
+                    .and_then(|p| p.as_f64())
+                    .ok_or_else(|| invalid("missing percent"))?;
+                let charging = value.get("charging").and_then(|c| c.as_bool()).unwrap_or(false);
+                self.report_battery(percent as f32, charging).await
+            }
+            FULLSCREEN_STATE_KEY => {
+                let active = value.get("fullscreen_game").and_then(|g| g.as_bool()).unwrap_or(false);
+                let app = value.get("app").and_then(|a| a.as_str()).map(str::to_string);
+                self.report_fullscreen_game(app, active).await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Pick the profile the current inputs call for
+    fn choose(&self, inputs: &ProfileInputs) -> (SystemProfile, Option<ProfileTrigger>) {
+        if self.config.auto_switch {
+            if let Some(app) = &inputs.fullscreen_game {
+                return (SystemProfile::Gaming, Some(ProfileTrigger::FullscreenGame { app: app.clone() }));
+            }
+            if let Some(percent) = inputs.battery_percent {
+                if !inputs.charging && percent < self.config.low_battery_threshold {
+                    return (SystemProfile::LowPower, Some(ProfileTrigger::LowBattery { percent }));
+                }
+            }
+        }
+        (inputs.selected, None)
+    }
+
+    async fn reevaluate(&self, reported: ProfileTrigger) -> OrchestratorResult<Option<ProfileChange>> {
+        let mut inputs = self.inputs.write().await;
+        let (target, automatic) = self.choose(&inputs);
+
+        if target == inputs.active {
+            debug!("Profile stays {} after {:?}", target, reported);
+            return Ok(None);
+        }
+
+        let trigger = match (automatic, reported) {
+            (Some(trigger), _) => trigger,
+            (None, ProfileTrigger::UserCommand) => ProfileTrigger::UserCommand,
+            (None, _) => ProfileTrigger::ConditionCleared,
+        };
+
+        self.apply(target).await?;
+
+        let change = ProfileChange { from: inputs.active, to: target, trigger };
+        inputs.active = target;
+        info!("🎛️  Switched profile {} → {} ({:?})", change.from, change.to, change.trigger);
+        Ok(Some(change))
+    }
+
+    /// Merge the preset into each module's config and push it out
+    ///
+    /// If a module rejects its update, the modules already updated get their
+    /// previous config back, newest first, so a switch applies fully or not at all.
+    async fn apply(&self, profile: SystemProfile) -> OrchestratorResult<()> {
+        let mut applied: Vec<(ModuleId, Option<serde_json::Value>)> = Vec::new();
+
+        for (module_id, fragment) in ProfilePreset::for_profile(profile).module_configs() {
+            let previous = self.config_manager.get_config(module_id).await;
+            let mut config = previous.clone()
+                .filter(|existing| existing.is_object())
+                .unwrap_or_else(|| serde_json::json!({}));
+
+            if let (Some(target), Some(values)) = (config.as_object_mut(), fragment.as_object()) {
+                for (key, value) in values {
+                    target.insert(key.clone(), value.clone());
+                }
+                target.insert("profile".to_string(), serde_json::json!(profile));
+            }
+
+            if let Err(e) = self.config_manager.update_config(module_id, config).await {
+                warn!("❌ {} rejected the {} profile, rolling back: {}", module_id, profile, e);
+                for (applied_module, previous) in applied.into_iter().rev() {
+                    if let Err(rollback) = self.config_manager.restore_config(applied_module, previous).await {
+                        warn!("Failed to restore {} after profile rollback: {}", applied_module, rollback);
+                    }
+                }
+                return Err(e);
+            }
+            applied.push((module_id, previous));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OrchestratorConfig;
+    use skelly_jelly_event_bus::{create_event_bus, message::ConfigUpdate};
+
+    async fn manager() -> (ProfileManager, Arc<ConfigurationManager>) {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let config_manager = Arc::new(ConfigurationManager::new(OrchestratorConfig::default(), bus));
+        (ProfileManager::new(ProfileConfig::default(), Arc::clone(&config_manager)), config_manager)
+    }
+
+    fn command(key: &str, value: serde_json::Value) -> BusMessage {
+        BusMessage::new(
+            ModuleId::CuteFigurine,
+            MessagePayload::ConfigUpdate(ConfigUpdate {
+                config_key: key.to_string(),
+                config_value: value,
+                target_module: Some(ModuleId::Orchestrator),
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_user_command_applies_preset() {
+        let (profiles, config_manager) = manager().await;
+
+        let change = profiles
+            .handle_message(&command(PROFILE_SWITCH_KEY, serde_json::json!({"profile": "focus"})))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.to, SystemProfile::Focus);
+        assert_eq!(change.trigger, ProfileTrigger::UserCommand);
+
+        let capture = config_manager.get_config(ModuleId::DataCapture).await.unwrap();
+        assert_eq!(capture["sampling_rate_hz"], 20.0);
+        assert_eq!(capture["profile"], "focus");
+    }
+
+    #[tokio::test]
+    async fn test_game_overrides_and_restores_selection() {
+        let (profiles, _) = manager().await;
+        profiles.select_profile(SystemProfile::Focus).await.unwrap();
+
+        let change = profiles.report_fullscreen_game(Some("game".to_string()), true).await.unwrap().unwrap();
+        assert_eq!(change.to, SystemProfile::Gaming);
+
+        // A user command while gaming is remembered but doesn't interrupt the game
+        assert!(profiles.select_profile(SystemProfile::Balanced).await.unwrap().is_none());
+
+        let change = profiles.report_fullscreen_game(None, false).await.unwrap().unwrap();
+        assert_eq!(change.to, SystemProfile::Balanced);
+        assert_eq!(change.trigger, ProfileTrigger::ConditionCleared);
+    }
+
+    #[tokio::test]
+    async fn test_low_battery_only_while_discharging() {
+        let (profiles, _) = manager().await;
+
+        assert!(profiles.report_battery(10.0, true).await.unwrap().is_none());
+
+        let change = profiles
+            .handle_message(&command(BATTERY_STATE_KEY, serde_json::json!({"percent": 10.0, "charging": false})))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.to, SystemProfile::LowPower);
+        assert_eq!(profiles.active_profile().await, SystemProfile::LowPower);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_switch_restores_earlier_modules() {
+        let (profiles, config_manager) = manager().await;
+        let capture = serde_json::json!({"sampling_rate_hz": 5.0});
+        config_manager.update_config(ModuleId::DataCapture, capture.clone()).await.unwrap();
+        // Kept through the merge, so the AI's update fails after capture and analysis were updated
+        config_manager
+            .restore_config(ModuleId::AiIntegration, Some(serde_json::json!({"local_model": {"temperature": 5.0}})))
+            .await
+            .unwrap();
+
+        let result = profiles.select_profile(SystemProfile::Focus).await;
+        assert!(matches!(result, Err(OrchestratorError::InvalidConfig { module: ModuleId::AiIntegration, .. })));
+        assert_eq!(profiles.active_profile().await, SystemProfile::Balanced);
+        assert_eq!(config_manager.get_config(ModuleId::DataCapture).await, Some(capture));
+        assert!(config_manager.get_config(ModuleId::AnalysisEngine).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_profile_rejected() {
+        let (profiles, _) = manager().await;
+        let result = profiles
+            .handle_message(&command(PROFILE_SWITCH_KEY, serde_json::json!({"profile": "turbo"})))
+            .await;
+        assert!(matches!(result, Err(OrchestratorError::ConfigurationError { .. })));
+    }
+}
@@ -0,0 +1,430 @@
+//! Prometheus text exposition (`prometheus` feature)
+//!
+//! Opt-in, localhost-only `GET /metrics` endpoint for scraping Skelly from an
+//! existing Prometheus/Grafana stack. [`MetricsExporter`] keeps the latest
+//! values from bus traffic (per-module telemetry, storage status, captured
+//! events, completed analyses) and reads the bus's own counters on each
+//! scrape.
+//!
+//! Names follow Prometheus conventions: a `skelly_` prefix and the subsystem
+//! (`bus`, `storage`, `capture`, `inference`, `module`, `system`), `_total`
+//! on counters, and base units (`_bytes`, `_seconds`, `_ratio`). Per-module
+//! series carry a `module` label with the module's name, e.g.
+//! `skelly_module_memory_bytes{module="analysis-engine"}`.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::performance_telemetry::{METRIC_CPU_PERCENT, METRIC_LATENCY_P95_MS, METRIC_MEMORY_MB, METRIC_SYSTEM_CPU_PERCENT};
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use futures::{Stream, StreamExt};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{BusMessage, BusMetrics, EventBusTrait, MessagePayload, MessageType, ModuleId};
+use std::{collections::BTreeMap, fmt::Write, net::SocketAddr, sync::Arc};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{debug, error, info};
+
+/// Content type of the text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Messages the exporter should be subscribed to
+pub const EXPORTED_MESSAGE_TYPES: [MessageType; 5] = [
+    MessageType::TelemetryBatch,
+    MessageType::StorageStatus,
+    MessageType::RawEvent,
+    MessageType::EventBatch,
+    MessageType::AnalysisComplete,
+];
+
+/// Configuration for the `/metrics` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrometheusConfig {
+    /// Off unless explicitly enabled
+    pub enabled: bool,
+    /// Must be a loopback address; port 0 picks a free port
+    pub bind_address: SocketAddr,
+}
+
+impl Default for PrometheusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 9717)),
+        }
+    }
+}
+
+/// Latest values seen on the bus
+#[derive(Debug, Default)]
+struct Collected {
+    /// Gauges from telemetry samples by (metric name, module label)
+    telemetry: BTreeMap<(String, String), f64>,
+    storage_events: Option<u64>,
+    storage_size_bytes: Option<u64>,
+    /// Captured events by event type
+    capture_events: BTreeMap<String, u64>,
+    /// Analyzed windows by classified state
+    inference_windows: BTreeMap<String, u64>,
+    inference_confidence: Option<f64>,
+}
+
+/// Aggregates bus, storage, capture and inference metrics for scraping
+pub struct MetricsExporter {
+    event_bus: Arc<dyn EventBusTrait>,
+    collected: RwLock<Collected>,
+}
+
+impl MetricsExporter {
+    pub fn new(event_bus: Arc<dyn EventBusTrait>) -> Self {
+        Self { event_bus, collected: RwLock::new(Collected::default()) }
+    }
+
+    /// Fold one bus message into the collected metrics
+    pub fn observe(&self, message: &BusMessage) {
+        let mut collected = self.collected.write();
+        match &message.payload {
+            MessagePayload::TelemetryBatch(batch) => {
+                for sample in &batch.samples {
+                    let (name, value) = telemetry_series(&sample.metric, sample.value, sample.module.is_some());
+                    let module = sample.module.map(|module| module.to_string()).unwrap_or_default();
+                    collected.telemetry.insert((name, module), value);
+                }
+            }
+            MessagePayload::StorageStatus(status) => {
+                collected.storage_events = Some(status.total_events);
+                collected.storage_size_bytes = Some(status.storage_size_bytes);
+            }
+            MessagePayload::RawEvent(event) => {
+                *collected.capture_events.entry(event.event_type.clone()).or_insert(0) += 1;
+            }
+            MessagePayload::EventBatch(batch) => {
+                for event in &batch.events {
+                    *collected.capture_events.entry(event.event_type.clone()).or_insert(0) += 1;
+                }
+            }
+            MessagePayload::AnalysisComplete(window) => {
+                *collected.inference_windows.entry(window.state.clone()).or_insert(0) += 1;
+                collected.inference_confidence = Some(window.confidence);
+            }
+            _ => {}
+        }
+    }
+
+    /// Observe messages until the stream ends
+    pub async fn consume(&self, mut messages: impl Stream<Item = BusMessage> + Unpin) {
+        while let Some(message) = messages.next().await {
+            self.observe(&message);
+        }
+    }
+
+    /// Everything collected, in the text exposition format
+    pub async fn render(&self) -> String {
+        let mut out = Exposition::default();
+
+        match self.event_bus.metrics().await {
+            Ok(metrics) => render_bus(&mut out, &metrics),
+            Err(e) => debug!("Leaving bus metrics out of the scrape: {}", e),
+        }
+
+        let collected = self.collected.read();
+
+        let mut family = String::new();
+        for ((name, module), value) in &collected.telemetry {
+            if *name != family {
+                out.family(name, "gauge", "Latest aggregated telemetry sample");
+                family.clone_from(name);
+            }
+            if module.is_empty() {
+                out.sample(name, &[], *value);
+            } else {
+                out.sample(name, &[("module", module)], *value);
+            }
+        }
+
+        if let Some(events) = collected.storage_events {
+            out.family("skelly_storage_events_total", "counter", "Events written to storage");
+            out.sample("skelly_storage_events_total", &[], events as f64);
+        }
+        if let Some(bytes) = collected.storage_size_bytes {
+            out.family("skelly_storage_size_bytes", "gauge", "Size of the storage database");
+            out.sample("skelly_storage_size_bytes", &[], bytes as f64);
+        }
+
+        if !collected.capture_events.is_empty() {
+            out.family("skelly_capture_events_total", "counter", "Events captured, by event type");
+            for (event_type, count) in &collected.capture_events {
+                out.sample("skelly_capture_events_total", &[("event_type", event_type)], *count as f64);
+            }
+        }
+
+        if !collected.inference_windows.is_empty() {
+            out.family("skelly_inference_windows_total", "counter", "Windows classified by the analysis engine, by state");
+            for (state, count) in &collected.inference_windows {
+                out.sample("skelly_inference_windows_total", &[("state", state)], *count as f64);
+            }
+        }
+        if let Some(confidence) = collected.inference_confidence {
+            out.family("skelly_inference_confidence_ratio", "gauge", "Confidence of the latest classification");
+            out.sample("skelly_inference_confidence_ratio", &[], confidence);
+        }
+
+        out.text
+    }
+}
+
+/// Series name and base-unit value for a telemetry sample
+fn telemetry_series(metric: &str, value: f64, per_module: bool) -> (String, f64) {
+    let scope = if per_module { "module" } else { "system" };
+    match metric {
+        METRIC_CPU_PERCENT | METRIC_SYSTEM_CPU_PERCENT => (format!("skelly_{}_cpu_ratio", scope), value / 100.0),
+        METRIC_MEMORY_MB => (format!("skelly_{}_memory_bytes", scope), value * 1024.0 * 1024.0),
+        METRIC_LATENCY_P95_MS => (format!("skelly_{}_latency_p95_seconds", scope), value / 1000.0),
+        other => {
+            let name: String = other
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+                .collect();
+            (format!("skelly_{}_{}", scope, name), value)
+        }
+    }
+}
+
+fn render_bus(out: &mut Exposition, metrics: &BusMetrics) {
+    for (name, help, value) in [
+        ("skelly_bus_messages_published_total", "Messages published on the event bus", metrics.messages_published),
+        ("skelly_bus_messages_delivered_total", "Messages delivered to subscribers", metrics.messages_delivered),
+        ("skelly_bus_messages_failed_total", "Deliveries that failed", metrics.messages_failed),
+    ] {
+        out.family(name, "counter", help);
+        out.sample(name, &[], value as f64);
+    }
+
+    out.family("skelly_bus_queue_depth", "gauge", "Messages waiting for delivery");
+    out.sample("skelly_bus_queue_depth", &[], metrics.current_queue_depth as f64);
+
+    let latency = &metrics.delivery_latency;
+    out.family("skelly_bus_delivery_latency_seconds", "summary", "Delivery latency");
+    for (quantile, ms) in [("0.5", latency.p50_ms), ("0.95", latency.p95_ms), ("0.99", latency.p99_ms)] {
+        out.sample("skelly_bus_delivery_latency_seconds", &[("quantile", quantile)], ms / 1000.0);
+    }
+
+    let mut modules: Vec<(String, u64, u64)> = metrics
+        .module_stats
+        .iter()
+        .map(|(module, stats)| (module.to_string(), stats.messages_published, stats.messages_received))
+        .collect();
+    modules.sort();
+    out.family("skelly_bus_module_messages_published_total", "counter", "Messages published, by module");
+    for (module, published, _) in &modules {
+        out.sample("skelly_bus_module_messages_published_total", &[("module", module)], *published as f64);
+    }
+    out.family("skelly_bus_module_messages_received_total", "counter", "Messages received, by module");
+    for (module, _, received) in &modules {
+        out.sample("skelly_bus_module_messages_received_total", &[("module", module)], *received as f64);
+    }
+}
+
+/// Text exposition format writer
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| {
+                    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+                    format!("{}=\"{}\"", key, escaped)
+                })
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value);
+    }
+}
+
+/// Serves the exporter's metrics at `/metrics`
+pub struct PrometheusServer {
+    config: PrometheusConfig,
+    exporter: Arc<MetricsExporter>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl PrometheusServer {
+    pub fn new(config: PrometheusConfig, exporter: Arc<MetricsExporter>) -> Self {
+        Self { config, exporter, shutdown: Mutex::new(None) }
+    }
+
+    /// Bind and serve in the background, returning the bound address
+    pub async fn start(&self) -> OrchestratorResult<SocketAddr> {
+        let invalid = |reason: String| OrchestratorError::ConfigurationError {
+            module: ModuleId::Orchestrator,
+            reason,
+        };
+
+        if !self.config.enabled {
+            return Err(invalid("Metrics endpoint is disabled".to_string()));
+        }
+        if !self.config.bind_address.ip().is_loopback() {
+            return Err(invalid(format!(
+                "Metrics endpoint must bind to a loopback address, got {}",
+                self.config.bind_address
+            )));
+        }
+
+        let mut shutdown = self.shutdown.lock().await;
+        if shutdown.is_some() {
+            return Err(invalid("Metrics endpoint already running".to_string()));
+        }
+
+        let listener = tokio::net::TcpListener::bind(self.config.bind_address).await?;
+        let address = listener.local_addr()?;
+
+        let router = Router::new()
+            .route("/metrics", get(metrics))
+            .with_state(Arc::clone(&self.exporter));
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        *shutdown = Some(stop_tx);
+
+        tokio::spawn(async move {
+            let server = axum::serve(listener, router)
+                .with_graceful_shutdown(async { let _ = stop_rx.await; });
+            if let Err(e) = server.await {
+                error!("❌ Metrics endpoint error: {}", e);
+            }
+        });
+
+        info!("📈 Prometheus metrics on http://{}/metrics", address);
+        Ok(address)
+    }
+
+    /// Stop serving
+    pub async fn stop(&self) {
+        if let Some(stop) = self.shutdown.lock().await.take() {
+            let _ = stop.send(());
+            info!("Metrics endpoint stopped");
+        }
+    }
+}
+
+async fn metrics(State(exporter): State<Arc<MetricsExporter>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], exporter.render().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use skelly_jelly_event_bus::{
+        create_event_bus,
+        message::{AnalysisWindow, RawEvent, StorageMetrics, TelemetryBatch, TelemetrySample},
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use uuid::Uuid;
+
+    async fn exporter() -> Arc<MetricsExporter> {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        Arc::new(MetricsExporter::new(bus))
+    }
+
+    fn message(source: ModuleId, payload: MessagePayload) -> BusMessage {
+        BusMessage::new(source, payload)
+    }
+
+    #[tokio::test]
+    async fn test_renders_metrics_from_bus_traffic() {
+        let exporter = exporter().await;
+        let sample = |module: Option<ModuleId>, metric: &str, value| TelemetrySample {
+            module,
+            metric: metric.to_string(),
+            value,
+            timestamp: Utc::now(),
+        };
+
+        exporter.observe(&message(ModuleId::Orchestrator, MessagePayload::TelemetryBatch(TelemetryBatch {
+            samples: vec![
+                sample(Some(ModuleId::AnalysisEngine), METRIC_MEMORY_MB, 2.0),
+                sample(Some(ModuleId::AnalysisEngine), METRIC_LATENCY_P95_MS, 250.0),
+                sample(None, METRIC_SYSTEM_CPU_PERCENT, 12.5),
+            ],
+        })));
+        exporter.observe(&message(ModuleId::Storage, MessagePayload::StorageStatus(StorageMetrics {
+            total_events: 1200,
+            storage_size_bytes: 4096,
+            last_batch_time: Utc::now(),
+        })));
+        for _ in 0..2 {
+            exporter.observe(&message(ModuleId::DataCapture, MessagePayload::RawEvent(RawEvent {
+                event_type: "keystroke".to_string(),
+                data: serde_json::json!({}),
+                window_title: None,
+                timestamp: Utc::now(),
+            })));
+        }
+        exporter.observe(&message(ModuleId::AnalysisEngine, MessagePayload::AnalysisComplete(AnalysisWindow {
+            window_id: Uuid::new_v4(),
+            state: "flow".to_string(),
+            confidence: 0.9,
+            start_time: Utc::now(),
+            end_time: Utc::now(),
+        })));
+
+        let text = exporter.render().await;
+        assert!(text.contains("# TYPE skelly_bus_messages_published_total counter\n"));
+        assert!(text.contains("skelly_module_memory_bytes{module=\"analysis-engine\"} 2097152\n"));
+        assert!(text.contains("skelly_module_latency_p95_seconds{module=\"analysis-engine\"} 0.25\n"));
+        assert!(text.contains("skelly_system_cpu_ratio 0.125\n"));
+        assert!(text.contains("skelly_storage_events_total 1200\n"));
+        assert!(text.contains("skelly_capture_events_total{event_type=\"keystroke\"} 2\n"));
+        assert!(text.contains("skelly_inference_windows_total{state=\"flow\"} 1\n"));
+
+        // Every sample belongs to a declared family
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {} ", name)), "{} has no TYPE", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics_on_loopback_only() {
+        let exporter = exporter().await;
+
+        let refused = PrometheusServer::new(
+            PrometheusConfig { enabled: true, bind_address: SocketAddr::from(([0, 0, 0, 0], 0)) },
+            Arc::clone(&exporter),
+        );
+        assert!(matches!(refused.start().await, Err(OrchestratorError::ConfigurationError { .. })));
+        let disabled = PrometheusServer::new(PrometheusConfig::default(), Arc::clone(&exporter));
+        assert!(disabled.start().await.is_err());
+
+        let server = PrometheusServer::new(
+            PrometheusConfig { enabled: true, bind_address: SocketAddr::from(([127, 0, 0, 1], 0)) },
+            exporter,
+        );
+        let address = server.start().await.unwrap();
+
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains(PROMETHEUS_CONTENT_TYPE));
+        assert!(response.contains("skelly_bus_queue_depth"));
+
+        server.stop().await;
+    }
+}
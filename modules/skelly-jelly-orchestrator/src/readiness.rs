@@ -0,0 +1,127 @@
+//! Readiness gates driven by `ModuleReady` bus messages
+//!
+//! A module counts as started only once a `ModuleReady` message for it has
+//! been observed, not merely when its start call returns. Only the module
+//! itself can open its gate: a `ModuleReady` published by anyone else, such
+//! as the orchestrator announcing a start, is ignored. An announcement that
+//! arrives before the orchestrator starts the module still counts; stopping
+//! the module closes its gate again.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use skelly_jelly_event_bus::{BusMessage, MessagePayload, ModuleId};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use tracing::debug;
+
+/// Tracks which modules have signalled readiness and lets callers wait on them
+pub struct ReadinessGate {
+    ready: watch::Sender<HashSet<ModuleId>>,
+}
+
+impl ReadinessGate {
+    pub fn new() -> Self {
+        let (ready, _) = watch::channel(HashSet::new());
+        Self { ready }
+    }
+
+    /// Feed a bus message to the gate; returns true if it opened a gate
+    pub fn observe(&self, message: &BusMessage) -> bool {
+        match &message.payload {
+            MessagePayload::ModuleReady(module_id) if *module_id == message.source => {
+                debug!("Readiness signal from {}", module_id);
+                self.mark_ready(*module_id);
+                true
+            }
+            MessagePayload::ModuleReady(module_id) => {
+                debug!("Ignoring readiness signal for {} sent by {}", module_id, message.source);
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Open the gate for a module
+    pub fn mark_ready(&self, module_id: ModuleId) {
+        self.ready.send_modify(|ready| {
+            ready.insert(module_id);
+        });
+    }
+
+    /// Close the gate for a module, e.g. before it is (re)started
+    pub fn reset(&self, module_id: ModuleId) {
+        self.ready.send_if_modified(|ready| ready.remove(&module_id));
+    }
+
+    /// Whether a module has signalled readiness
+    pub fn is_ready(&self, module_id: ModuleId) -> bool {
+        self.ready.borrow().contains(&module_id)
+    }
+
+    /// Wait until a module signals readiness, returning how long the wait took
+    pub async fn wait_ready(&self, module_id: ModuleId, timeout_duration: Duration) -> OrchestratorResult<Duration> {
+        let wait_start = Instant::now();
+        let mut receiver = self.ready.subscribe();
+
+        let signalled = async {
+            receiver.wait_for(|ready| ready.contains(&module_id)).await.map(|_| ())
+        };
+
+        match tokio::time::timeout(timeout_duration, signalled).await {
+            Ok(Ok(())) => Ok(wait_start.elapsed()),
+            Ok(Err(_)) => Err(OrchestratorError::Internal(anyhow::anyhow!("Readiness gate closed"))),
+            Err(_) => Err(OrchestratorError::ModuleStartupFailed {
+                module: module_id,
+                reason: format!("No readiness signal within {:?}", timeout_duration),
+            }),
+        }
+    }
+}
+
+impl Default for ReadinessGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_gate_opens_on_module_ready() {
+        let gate = Arc::new(ReadinessGate::new());
+        let waiter = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move { gate.wait_ready(ModuleId::Storage, Duration::from_secs(1)).await })
+        };
+
+        let message = BusMessage::new(ModuleId::Storage, MessagePayload::ModuleReady(ModuleId::Storage));
+        assert!(gate.observe(&message));
+
+        assert!(waiter.await.unwrap().is_ok());
+        assert!(gate.is_ready(ModuleId::Storage));
+    }
+
+    #[test]
+    fn test_gate_ignores_signals_sent_on_a_modules_behalf() {
+        let gate = ReadinessGate::new();
+        let announced = BusMessage::new(ModuleId::Orchestrator, MessagePayload::ModuleReady(ModuleId::Storage));
+
+        assert!(!gate.observe(&announced));
+        assert!(!gate.is_ready(ModuleId::Storage));
+    }
+
+    #[tokio::test]
+    async fn test_gate_times_out_without_signal() {
+        let gate = ReadinessGate::new();
+        gate.mark_ready(ModuleId::Storage);
+        gate.reset(ModuleId::Storage);
+
+        let result = gate.wait_ready(ModuleId::Storage, Duration::from_millis(20)).await;
+        assert!(matches!(result, Err(OrchestratorError::ModuleStartupFailed { module: ModuleId::Storage, .. })));
+    }
+}
@@ -1,5 +1,6 @@
 //! Resource management and monitoring
 
+use crate::enforcement::ResourceEnforcer;
 use crate::error::{OrchestratorError, OrchestratorResult};
 use crate::module_registry::ModuleRegistry;
 use dashmap::DashMap;
@@ -29,6 +30,11 @@ use parking_lot::RwLock as ParkingLotRwLock;
 pub struct BatteryOptimization {
     pub enabled: bool,
     pub power_save_threshold: f32, // Battery percentage
+    pub critical_threshold: f32,   // Battery percentage
+    /// Extra battery percentage needed before leaving a power-saving state
+    pub hysteresis_percent: f32,
+    /// Minimum time in a power-saving state before relaxing it
+    pub min_dwell: Duration,
     pub cpu_throttle_factor: f32,
     pub background_task_delay: Duration,
     pub reduced_monitoring_interval: Duration,
@@ -39,6 +45,9 @@ impl Default for BatteryOptimization {
         Self {
             enabled: true,
             power_save_threshold: 20.0, // Below 20% battery
+            critical_threshold: 10.0,   // Below 10% battery
+            hysteresis_percent: 5.0,
+            min_dwell: Duration::from_secs(60),
             cpu_throttle_factor: 0.5,   // Reduce CPU usage by 50%
             background_task_delay: Duration::from_millis(500),
             reduced_monitoring_interval: Duration::from_secs(5),
@@ -114,6 +123,21 @@ pub struct ResourceUsage {
 }
 
 impl ResourceUsage {
+    /// Sample a live process; CPU is normalised to a share of the whole machine
+    pub fn from_process(process: &Process, cpu_count: usize) -> Self {
+        let cpu_percent = process.cpu_usage() / cpu_count.max(1) as f32;
+        let memory_mb = (process.memory() / 1024 / 1024) as usize;
+
+        Self {
+            cpu_percent,
+            memory_mb,
+            file_handles: 0,
+            threads: 0,
+            battery_impact: cpu_percent / 100.0 * 0.3 + (memory_mb as f32 / 1024.0) * 0.1,
+            timestamp: Utc::now(),
+        }
+    }
+
     pub fn exceeds(&self, limits: &ResourceLimits) -> bool {
         self.cpu_percent > limits.max_cpu_percent ||
         self.memory_mb > limits.max_memory_mb ||
@@ -357,6 +381,9 @@ pub struct ResourceManager {
     /// Current resource usage per module
     current_usage: DashMap<ModuleId, ResourceUsage>,
     
+    /// OS-level enforcement of limits for modules running as their own process
+    enforcer: Option<Arc<ResourceEnforcer>>,
+    
     /// System resource monitoring task
     monitor_task: Option<JoinHandle<()>>,
     
@@ -381,6 +408,7 @@ impl ResourceManager {
             resource_limits: DashMap::new(),
             throttle_controller: ThrottleController::new(),
             current_usage: DashMap::new(),
+            enforcer: None,
             monitor_task: None,
             check_interval,
             throttle_threshold,
@@ -399,6 +427,7 @@ impl ResourceManager {
         let allocations = Arc::clone(&self.allocations);
         let registry = Arc::clone(&self.registry);
         let current_usage = self.current_usage.clone();
+        let enforcer = self.enforcer.clone();
         let check_interval = self.check_interval;
 
         let monitor_task = tokio::spawn(async move {
@@ -421,6 +450,11 @@ impl ResourceManager {
                 ).await {
                     error!("Failed to update resource allocations: {}", e);
                 }
+
+                // Attached modules are sampled from their real process and enforced
+                if let Some(enforcer) = &enforcer {
+                    Self::enforce_attached(enforcer, &system_monitor, &current_usage).await;
+                }
             }
         });
 
@@ -454,18 +488,52 @@ impl ResourceManager {
             .unwrap_or_default()
     }
 
+    /// Enforce limits at the OS level through the given enforcer
+    pub fn set_enforcer(&mut self, enforcer: Arc<ResourceEnforcer>) {
+        self.enforcer = Some(enforcer);
+    }
+
+    /// Place a module's process under its resource limits
+    pub fn attach_module_process(&self, module_id: ModuleId, pid: u32) -> OrchestratorResult<()> {
+        let enforcer = self.enforcer.as_ref().ok_or_else(|| {
+            OrchestratorError::SystemResource("No resource enforcer configured".to_string())
+        })?;
+        enforcer.attach(module_id, pid, &self.get_resource_limits(module_id))
+    }
+
+    /// Record a usage sample reported by a module running in this process
+    pub fn record_usage(&self, module_id: ModuleId, usage: ResourceUsage) {
+        self.current_usage.insert(module_id, usage);
+    }
+
+    /// Throttle currently applied to a module running in this process
+    pub fn current_throttle(&self, module_id: ModuleId) -> Option<ThrottleAction> {
+        self.throttle_controller.get_current_throttle(module_id)
+    }
+
     /// Enforce resource limits across all modules
+    ///
+    /// Modules attached with their own process go through the OS-level
+    /// enforcer; the rest run in this process and are throttled in-process.
     pub async fn enforce_limits(&self) -> OrchestratorResult<()> {
         debug!("Enforcing resource limits");
 
-        for entry in self.current_usage.iter() {
-            let module_id = *entry.key();
-            let usage = entry.value();
+        let samples: Vec<(ModuleId, ResourceUsage)> = self.current_usage
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+
+        for (module_id, usage) in samples {
+            if let Some(enforcer) = self.enforcer.as_ref().filter(|enforcer| enforcer.is_attached(module_id)) {
+                let limits = self.get_resource_limits(module_id);
+                enforcer.evaluate(module_id, &usage, &limits).await?;
+                continue;
+            }
 
             if let Some(limits) = self.resource_limits.get(&module_id) {
                 if usage.exceeds(&limits) {
                     self.throttle_controller
-                        .throttle(module_id, usage, &limits)
+                        .throttle(module_id, &usage, &limits)
                         .await?;
                 }
             }
@@ -531,6 +599,32 @@ impl ResourceManager {
         }
     }
 
+    /// Sample attached processes and feed them to the enforcer (called by monitoring task)
+    async fn enforce_attached(
+        enforcer: &Arc<ResourceEnforcer>,
+        system_monitor: &Arc<tokio::sync::Mutex<System>>,
+        current_usage: &DashMap<ModuleId, ResourceUsage>,
+    ) {
+        let samples: Vec<(ModuleId, ResourceUsage, ResourceLimits)> = {
+            let system = system_monitor.lock().await;
+            let cpu_count = system.cpus().len();
+            enforcer.attached()
+                .into_iter()
+                .filter_map(|(module_id, pid, limits)| {
+                    system.process(Pid::from_u32(pid))
+                        .map(|process| (module_id, ResourceUsage::from_process(process, cpu_count), limits))
+                })
+                .collect()
+        };
+
+        for (module_id, usage, limits) in samples {
+            current_usage.insert(module_id, usage.clone());
+            if let Err(e) = enforcer.evaluate(module_id, &usage, &limits).await {
+                warn!("Resource enforcement failed for {}: {}", module_id, e);
+            }
+        }
+    }
+
     /// Update resource allocations (called by monitoring task)
     async fn update_resource_allocations(
         system_monitor: &Arc<tokio::sync::Mutex<System>>,
@@ -0,0 +1,195 @@
+//! Crash-loop detection and quarantine (safe mode)
+//!
+//! A module that fails `max_failures` times within `window` is quarantined: it is
+//! not retried, modules depending on it are held back, and the rest of the system
+//! runs degraded until an operator restarts it.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use skelly_jelly_event_bus::ModuleId;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use tracing::{error, info};
+
+/// A module taken out of rotation after crash-looping
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantineRecord {
+    pub module: ModuleId,
+    /// Failures counted inside the detection window when the module was quarantined
+    pub failures: u32,
+    pub last_error: String,
+    /// Modules not started because they depend on this one
+    pub held_back: Vec<ModuleId>,
+    /// What an operator can do to get the module running again
+    pub remediation: Vec<String>,
+    #[serde(skip)]
+    pub since: Instant,
+}
+
+/// Counts module failures and quarantines modules that fail too often
+#[derive(Debug)]
+pub struct CrashLoopDetector {
+    max_failures: u32,
+    window: Duration,
+    failures: DashMap<ModuleId, VecDeque<Instant>>,
+    quarantined: DashMap<ModuleId, QuarantineRecord>,
+}
+
+impl CrashLoopDetector {
+    pub fn new(max_failures: u32, window: Duration) -> Self {
+        Self {
+            max_failures: max_failures.max(1),
+            window,
+            failures: DashMap::new(),
+            quarantined: DashMap::new(),
+        }
+    }
+
+    /// Record a failure; returns the record if this failure put the module into quarantine
+    pub fn record_failure(&self, module: ModuleId, error: &str) -> Option<QuarantineRecord> {
+        self.record_failure_at(module, error, Instant::now())
+    }
+
+    fn record_failure_at(&self, module: ModuleId, error: &str, now: Instant) -> Option<QuarantineRecord> {
+        if self.quarantined.contains_key(&module) {
+            return None;
+        }
+
+        let failures = {
+            let mut history = self.failures.entry(module).or_default();
+            history.push_back(now);
+            while history.front().is_some_and(|&t| now.duration_since(t) > self.window) {
+                history.pop_front();
+            }
+            history.len() as u32
+        };
+
+        if failures < self.max_failures {
+            return None;
+        }
+
+        let record = QuarantineRecord {
+            module,
+            failures,
+            last_error: error.to_string(),
+            held_back: Vec::new(),
+            remediation: remediation_hints(module, error),
+            since: now,
+        };
+        error!(
+            "🛑 Module {} failed {} times in {:?}, quarantined: {}",
+            module, failures, self.window, error
+        );
+        self.quarantined.insert(module, record.clone());
+        Some(record)
+    }
+
+    /// Note which modules were held back because they depend on a quarantined module
+    pub fn hold_back(&self, module: ModuleId, dependents: Vec<ModuleId>) {
+        if let Some(mut record) = self.quarantined.get_mut(&module) {
+            for dependent in dependents {
+                if !record.held_back.contains(&dependent) {
+                    record.held_back.push(dependent);
+                }
+            }
+        }
+    }
+
+    pub fn is_quarantined(&self, module: ModuleId) -> bool {
+        self.quarantined.contains_key(&module)
+    }
+
+    /// Whether the module is quarantined or held back by a quarantined dependency
+    pub fn is_sidelined(&self, module: ModuleId) -> bool {
+        self.quarantined
+            .iter()
+            .any(|record| record.module == module || record.held_back.contains(&module))
+    }
+
+    pub fn quarantined(&self) -> Vec<QuarantineRecord> {
+        let mut records: Vec<QuarantineRecord> = self.quarantined.iter().map(|r| r.clone()).collect();
+        records.sort_by_key(|record| record.since);
+        records
+    }
+
+    /// Lift the quarantine and forget the failure history
+    pub fn release(&self, module: ModuleId) -> Option<QuarantineRecord> {
+        self.failures.remove(&module);
+        let released = self.quarantined.remove(&module).map(|(_, record)| record);
+        if released.is_some() {
+            info!("🔓 Released {} from quarantine", module);
+        }
+        released
+    }
+}
+
+/// Suggested fixes for a crash-looping module, most specific first
+fn remediation_hints(module: ModuleId, error: &str) -> Vec<String> {
+    let lowered = error.to_lowercase();
+    let mut hints = Vec::new();
+
+    if lowered.contains("timeout") || lowered.contains("timed out") {
+        hints.push(format!(
+            "{} did not become ready in time; raise its startup_timeout or check what blocks its ModuleReady signal",
+            module
+        ));
+    }
+    if lowered.contains("config") {
+        hints.push(format!("Check config/{}.toml for invalid values", module));
+    }
+    if lowered.contains("requires") || lowered.contains("dependency") {
+        hints.push(format!("A dependency of {} is not running; fix that module first", module));
+    }
+    if lowered.contains("resource") || lowered.contains("memory") {
+        hints.push(format!("{} is hitting its resource limits; raise them or free system resources", module));
+    }
+
+    hints.push(format!("Check the {} logs for the failure: {}", module, error));
+    hints.push(format!(
+        "Once fixed, restart {} (restart_module or POST /modules/{}/restart) to leave safe mode",
+        module, module
+    ));
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarantines_after_repeated_failures_in_window() {
+        let detector = CrashLoopDetector::new(3, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(detector.record_failure_at(ModuleId::Storage, "crash", start).is_none());
+        // Outside the window, so the first failure no longer counts
+        let later = start + Duration::from_secs(90);
+        assert!(detector.record_failure_at(ModuleId::Storage, "crash", later).is_none());
+        assert!(detector.record_failure_at(ModuleId::Storage, "crash", later).is_none());
+        assert!(!detector.is_quarantined(ModuleId::Storage));
+
+        let record = detector
+            .record_failure_at(ModuleId::Storage, "startup timeout", later + Duration::from_secs(1))
+            .expect("third failure in the window quarantines");
+        assert_eq!(record.failures, 3);
+        assert!(record.remediation[0].contains("startup_timeout"));
+        assert!(detector.is_quarantined(ModuleId::Storage));
+    }
+
+    #[test]
+    fn test_hold_back_and_release() {
+        let detector = CrashLoopDetector::new(1, Duration::from_secs(60));
+        detector.record_failure(ModuleId::Storage, "crash");
+        detector.hold_back(ModuleId::Storage, vec![ModuleId::AnalysisEngine]);
+
+        assert!(detector.is_sidelined(ModuleId::AnalysisEngine));
+        assert!(!detector.is_sidelined(ModuleId::DataCapture));
+        assert_eq!(detector.quarantined()[0].held_back, vec![ModuleId::AnalysisEngine]);
+
+        assert!(detector.release(ModuleId::Storage).is_some());
+        assert!(!detector.is_sidelined(ModuleId::AnalysisEngine));
+        assert!(detector.release(ModuleId::Storage).is_none());
+    }
+}
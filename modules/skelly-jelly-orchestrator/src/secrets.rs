@@ -0,0 +1,379 @@
+//! Secret distribution to modules
+//!
+//! Secrets such as the AI fallback API keys are read from the OS keychain and
+//! handed to a module when it is registered. Each module gets its own channel
+//! whose contents are sealed with a per-module key, so secrets never sit in
+//! config files or travel over the event bus in plaintext.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use dashmap::DashMap;
+use skelly_jelly_event_bus::ModuleId;
+use std::{collections::HashMap, fmt, sync::Arc};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Keychain service name the orchestrator looks secrets up under
+pub const KEYCHAIN_SERVICE: &str = "skelly-jelly";
+
+/// A secret that is wiped from memory on drop and never printed
+pub struct SecretValue(Zeroizing<String>);
+
+impl SecretValue {
+    pub fn new(value: String) -> Self {
+        Self(Zeroizing::new(value))
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretValue(***)")
+    }
+}
+
+/// Where secrets are read from
+pub trait SecretStore: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `Ok(None)` when the store works but holds no such secret
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>>;
+}
+
+/// The platform keychain: Keychain Services on macOS, the Secret Service
+/// (`secret-tool`) on Linux, and Credential Manager on Windows
+pub struct KeychainStore {
+    service: String,
+}
+
+impl KeychainStore {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self { service: service.into() }
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn lookup_with(&self, key: &str, program: &str, args: &[&str]) -> OrchestratorResult<Option<SecretValue>> {
+        let output = std::process::Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|e| OrchestratorError::SecretUnavailable {
+                name: key.to_string(),
+                reason: format!("{} not available: {}", program, e),
+            })?;
+
+        // Both tools exit non-zero when the item doesn't exist
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let mut stdout = Zeroizing::new(output.stdout);
+        while stdout.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+            stdout.pop();
+        }
+        let value = String::from_utf8(stdout.to_vec()).map_err(|_| OrchestratorError::SecretUnavailable {
+            name: key.to_string(),
+            reason: "keychain item is not valid UTF-8".to_string(),
+        })?;
+        Ok(Some(SecretValue::new(value)))
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>> {
+        self.lookup_with(key, "security", &["find-generic-password", "-s", &self.service, "-a", key, "-w"])
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>> {
+        self.lookup_with(key, "secret-tool", &["lookup", "service", &self.service, "account", key])
+    }
+
+    #[cfg(windows)]
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>> {
+        use windows_sys::Win32::{
+            Foundation::{GetLastError, ERROR_NOT_FOUND},
+            Security::Credentials::{CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC},
+        };
+
+        let target: Vec<u16> = format!("{}/{}", self.service, key)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+
+        // SAFETY: `target` is NUL-terminated and outlives the call; the credential
+        // buffer is only read while valid and released with CredFree
+        unsafe {
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                let code = GetLastError();
+                if code == ERROR_NOT_FOUND {
+                    return Ok(None);
+                }
+                return Err(OrchestratorError::SecretUnavailable {
+                    name: key.to_string(),
+                    reason: format!("CredReadW failed with error {}", code),
+                });
+            }
+
+            let blob = std::slice::from_raw_parts(
+                (*credential).CredentialBlob,
+                (*credential).CredentialBlobSize as usize,
+            );
+            let value = Zeroizing::new(blob.to_vec());
+            CredFree(credential as *const _);
+
+            String::from_utf8(value.to_vec())
+                .map(|value| Some(SecretValue::new(value)))
+                .map_err(|_| OrchestratorError::SecretUnavailable {
+                    name: key.to_string(),
+                    reason: "credential is not valid UTF-8".to_string(),
+                })
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>> {
+        Err(OrchestratorError::SecretUnavailable {
+            name: key.to_string(),
+            reason: format!("no keychain backend for service {} on this platform", self.service),
+        })
+    }
+}
+
+/// In-memory secrets, for tests and embedders that manage secrets themselves
+#[derive(Default)]
+pub struct MemorySecretStore {
+    secrets: DashMap<String, Zeroizing<String>>,
+}
+
+impl MemorySecretStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, key: impl Into<String>, value: impl Into<String>) {
+        self.secrets.insert(key.into(), Zeroizing::new(value.into()));
+    }
+}
+
+impl SecretStore for MemorySecretStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn get(&self, key: &str) -> OrchestratorResult<Option<SecretValue>> {
+        Ok(self.secrets.get(key).map(|value| SecretValue::new(value.as_str().to_string())))
+    }
+}
+
+/// Which module may receive which secrets
+#[derive(Debug, Clone)]
+pub struct SecretsConfig {
+    pub grants: HashMap<ModuleId, Vec<String>>,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        let mut grants = HashMap::new();
+        // Named after the AI integration's api_config fields
+        grants.insert(
+            ModuleId::AiIntegration,
+            vec!["openai_key".to_string(), "anthropic_key".to_string()],
+        );
+        Self { grants }
+    }
+}
+
+/// One secret sealed for a single module
+#[derive(Clone)]
+pub struct SealedSecret {
+    pub name: String,
+    nonce: Nonce,
+    ciphertext: Vec<u8>,
+}
+
+impl fmt::Debug for SealedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SealedSecret")
+            .field("name", &self.name)
+            .field("len", &self.ciphertext.len())
+            .finish()
+    }
+}
+
+/// A module's end of its secrets channel; only it holds the key to open them
+pub struct SecretReceiver {
+    module: ModuleId,
+    cipher: ChaCha20Poly1305,
+    receiver: mpsc::UnboundedReceiver<SealedSecret>,
+}
+
+impl SecretReceiver {
+    pub fn module(&self) -> ModuleId {
+        self.module
+    }
+
+    /// Next delivered secret, or `None` once every granted secret has been read
+    pub fn try_recv(&mut self) -> Option<OrchestratorResult<(String, SecretValue)>> {
+        let sealed = self.receiver.try_recv().ok()?;
+        let aad = self.module.to_string();
+        let opened = self.cipher
+            .decrypt(&sealed.nonce, Payload { msg: &sealed.ciphertext, aad: aad.as_bytes() })
+            .map_err(|_| OrchestratorError::SecretUnavailable {
+                name: sealed.name.clone(),
+                reason: format!("could not be opened by {}", self.module),
+            })
+            .and_then(|plaintext| {
+                let plaintext = Zeroizing::new(plaintext);
+                String::from_utf8(plaintext.to_vec()).map_err(|_| OrchestratorError::SecretUnavailable {
+                    name: sealed.name.clone(),
+                    reason: "not valid UTF-8".to_string(),
+                })
+            });
+        Some(opened.map(|value| (sealed.name, SecretValue::new(value))))
+    }
+
+    /// Open every pending secret
+    pub fn drain(&mut self) -> OrchestratorResult<Vec<(String, SecretValue)>> {
+        std::iter::from_fn(|| self.try_recv()).collect()
+    }
+}
+
+/// Fetches granted secrets and seals them into per-module channels at registration
+pub struct SecretsBroker {
+    store: Arc<dyn SecretStore>,
+    config: SecretsConfig,
+    pending: DashMap<ModuleId, SecretReceiver>,
+}
+
+impl SecretsBroker {
+    pub fn new(store: Arc<dyn SecretStore>, config: SecretsConfig) -> Self {
+        Self {
+            store,
+            config,
+            pending: DashMap::new(),
+        }
+    }
+
+    /// Seal the module's granted secrets into a fresh channel, replacing any
+    /// undelivered one. Returns how many secrets were found
+    pub async fn provision(&self, module: ModuleId) -> OrchestratorResult<usize> {
+        let Some(names) = self.config.grants.get(&module).filter(|names| !names.is_empty()) else {
+            return Ok(0);
+        };
+
+        // Keychain lookups shell out or call into the OS, so keep them off the runtime
+        let store = Arc::clone(&self.store);
+        let lookup = names.clone();
+        let fetched = tokio::task::spawn_blocking(move || {
+            lookup
+                .iter()
+                .map(|name| store.get(name))
+                .collect::<OrchestratorResult<Vec<_>>>()
+        })
+        .await
+        .map_err(|e| OrchestratorError::SecretUnavailable {
+            name: module.to_string(),
+            reason: format!("keychain lookup task failed: {}", e),
+        })??;
+
+        let mut key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let cipher = ChaCha20Poly1305::new(&key);
+        key.as_mut_slice().zeroize();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let aad = module.to_string();
+        let mut delivered = 0;
+
+        for (name, secret) in names.iter().zip(fetched) {
+            let Some(secret) = secret else {
+                debug!("Secret {} for {} not found in {}", name, module, self.store.name());
+                continue;
+            };
+
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let ciphertext = cipher
+                .encrypt(&nonce, Payload { msg: secret.expose().as_bytes(), aad: aad.as_bytes() })
+                .map_err(|_| OrchestratorError::SecretUnavailable {
+                    name: name.clone(),
+                    reason: "encryption failed".to_string(),
+                })?;
+            // The receiver is still in hand, so the send can't fail
+            let _ = sender.send(SealedSecret { name: name.clone(), nonce, ciphertext });
+            delivered += 1;
+        }
+
+        if delivered < names.len() {
+            warn!(
+                "🔑 {} of {} secrets for {} missing from the {}",
+                names.len() - delivered, names.len(), module, self.store.name()
+            );
+        }
+        info!("🔑 Sealed {} secrets for {}", delivered, module);
+        self.pending.insert(module, SecretReceiver { module, cipher, receiver });
+        Ok(delivered)
+    }
+
+    /// Hand a module its secrets channel; each channel can be taken once
+    pub fn take_receiver(&self, module: ModuleId) -> Option<SecretReceiver> {
+        self.pending.remove(&module).map(|(_, receiver)| receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broker_with(store: MemorySecretStore) -> SecretsBroker {
+        SecretsBroker::new(Arc::new(store), SecretsConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_granted_secrets_reach_the_module() {
+        let store = MemorySecretStore::new();
+        store.insert("openai_key", "sk-test");
+        let broker = broker_with(store);
+
+        assert_eq!(broker.provision(ModuleId::AiIntegration).await.unwrap(), 1);
+        assert_eq!(broker.provision(ModuleId::Storage).await.unwrap(), 0);
+        assert!(broker.take_receiver(ModuleId::Storage).is_none());
+
+        let mut receiver = broker.take_receiver(ModuleId::AiIntegration).unwrap();
+        let secrets = receiver.drain().unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].0, "openai_key");
+        assert_eq!(secrets[0].1.expose(), "sk-test");
+        assert!(broker.take_receiver(ModuleId::AiIntegration).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sealed_secrets_are_bound_to_their_module() {
+        let store = MemorySecretStore::new();
+        store.insert("openai_key", "sk-test");
+        let broker = broker_with(store);
+        broker.provision(ModuleId::AiIntegration).await.unwrap();
+        let mut receiver = broker.take_receiver(ModuleId::AiIntegration).unwrap();
+
+        let sealed = receiver.receiver.try_recv().unwrap();
+        assert!(!sealed.ciphertext.windows(7).any(|w| w == b"sk-test"));
+        assert!(!format!("{:?}", sealed).contains("sk-test"));
+
+        // Replaying the sealed secret under another module's identity fails authentication
+        let (sender, replay) = mpsc::unbounded_channel();
+        sender.send(sealed).unwrap();
+        let mut impostor = SecretReceiver { module: ModuleId::Storage, cipher: receiver.cipher.clone(), receiver: replay };
+        assert!(impostor.try_recv().unwrap().is_err());
+    }
+}
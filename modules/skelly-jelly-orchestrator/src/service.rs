@@ -0,0 +1,203 @@
+//! OS service integration: launchd agents on macOS, systemd user units on Linux
+//!
+//! The service manager supervises the process, so the generated definitions run
+//! the binary in the foreground rather than asking it to detach.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// launchd label and systemd unit name
+pub const SERVICE_LABEL: &str = "com.skelly-jelly.agent";
+const SYSTEMD_UNIT_NAME: &str = "skelly-jelly.service";
+
+/// What the service manager should run
+#[derive(Debug, Clone)]
+pub struct ServiceSpec {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub working_dir: PathBuf,
+    /// Where launchd sends stdout/stderr; systemd uses the journal
+    pub log_path: PathBuf,
+}
+
+impl ServiceSpec {
+    /// Run the current executable with `args` from `working_dir`
+    pub fn current_exe(args: Vec<String>, working_dir: impl Into<PathBuf>) -> OrchestratorResult<Self> {
+        let working_dir = working_dir.into();
+        Ok(Self {
+            program: std::env::current_exe()?,
+            args,
+            log_path: working_dir.join("data").join("logs").join("service.log"),
+            working_dir,
+        })
+    }
+
+    /// launchd agent property list
+    pub fn launchd_plist(&self) -> String {
+        let arguments: String = std::iter::once(self.program.display().to_string())
+            .chain(self.args.iter().cloned())
+            .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+            .collect();
+        let log_path = xml_escape(&self.log_path.display().to_string());
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>WorkingDirectory</key>
+    <string>{working_dir}</string>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ProcessType</key>
+    <string>Background</string>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+</dict>
+</plist>
+"#,
+            label = SERVICE_LABEL,
+            arguments = arguments,
+            working_dir = xml_escape(&self.working_dir.display().to_string()),
+            log_path = log_path,
+        )
+    }
+
+    /// systemd user unit
+    pub fn systemd_unit(&self) -> String {
+        let exec_start: Vec<String> = std::iter::once(self.program.display().to_string())
+            .chain(self.args.iter().cloned())
+            .map(|arg| systemd_quote(&arg))
+            .collect();
+
+        format!(
+            "[Unit]\n\
+             Description=Skelly-Jelly ADHD companion\n\
+             After=graphical-session.target\n\
+             \n\
+             [Service]\n\
+             Type=simple\n\
+             ExecStart={}\n\
+             WorkingDirectory={}\n\
+             Restart=on-failure\n\
+             RestartSec=10\n\
+             \n\
+             [Install]\n\
+             WantedBy=default.target\n",
+            exec_start.join(" "),
+            systemd_quote(&self.working_dir.display().to_string()),
+        )
+    }
+
+    /// Where this platform's service manager looks for the definition
+    pub fn install_path() -> OrchestratorResult<PathBuf> {
+        let home = std::env::var_os("HOME").map(PathBuf::from).ok_or_else(|| unsupported("HOME is not set"))?;
+        if cfg!(target_os = "macos") {
+            Ok(home.join("Library/LaunchAgents").join(format!("{}.plist", SERVICE_LABEL)))
+        } else if cfg!(target_os = "linux") {
+            let config = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).unwrap_or_else(|| home.join(".config"));
+            Ok(config.join("systemd/user").join(SYSTEMD_UNIT_NAME))
+        } else {
+            Err(unsupported("no launchd or systemd on this platform"))
+        }
+    }
+
+    /// The definition for this platform's service manager
+    pub fn render(&self) -> OrchestratorResult<String> {
+        if cfg!(target_os = "macos") {
+            Ok(self.launchd_plist())
+        } else if cfg!(target_os = "linux") {
+            Ok(self.systemd_unit())
+        } else {
+            Err(unsupported("no launchd or systemd on this platform"))
+        }
+    }
+
+    /// Write the definition to `install_path`; returns the path written
+    pub fn install(&self) -> OrchestratorResult<PathBuf> {
+        let path = Self::install_path()?;
+        self.install_to(&path)?;
+        Ok(path)
+    }
+
+    fn install_to(&self, path: &Path) -> OrchestratorResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.render()?)?;
+        info!("📝 Wrote service definition to {}", path.display());
+        Ok(())
+    }
+}
+
+fn unsupported(reason: &str) -> OrchestratorError {
+    OrchestratorError::ServiceUnsupported { reason: reason.to_string() }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Quote an ExecStart word when it has characters systemd would split or expand
+fn systemd_quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "/._-=:".contains(c)) {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "$$"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ServiceSpec {
+        ServiceSpec {
+            program: PathBuf::from("/opt/skelly jelly/skelly-jelly-full"),
+            args: vec!["run".to_string(), "--headless".to_string()],
+            working_dir: PathBuf::from("/home/me/skelly"),
+            log_path: PathBuf::from("/home/me/skelly/data/logs/service.log"),
+        }
+    }
+
+    #[test]
+    fn test_launchd_plist_lists_program_arguments() {
+        let plist = spec().launchd_plist();
+        assert!(plist.contains("<string>com.skelly-jelly.agent</string>"));
+        assert!(plist.contains(
+            "        <string>/opt/skelly jelly/skelly-jelly-full</string>\n        <string>run</string>\n        <string>--headless</string>\n"
+        ));
+        assert!(plist.contains("<key>StandardErrorPath</key>\n    <string>/home/me/skelly/data/logs/service.log</string>"));
+    }
+
+    #[test]
+    fn test_systemd_unit_quotes_exec_start() {
+        let unit = spec().systemd_unit();
+        assert!(unit.contains("ExecStart=\"/opt/skelly jelly/skelly-jelly-full\" run --headless\n"));
+        assert!(unit.contains("WorkingDirectory=/home/me/skelly\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+    }
+}
@@ -0,0 +1,372 @@
+//! First-run setup: permissions, privacy level, sensitive apps, storage location,
+//! and model download
+//!
+//! Answers are saved to `<data-dir>/setup_state.json` after every step, so an
+//! interrupted setup picks up where it stopped. `finish` writes them into the
+//! canonical TOML config with `toml_edit`, keeping the file's comments and layout.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{Array, DocumentMut, Item, TableLike, Value};
+use tracing::info;
+
+/// File under the data directory holding setup progress
+pub const SETUP_STATE_FILE: &str = "setup_state.json";
+
+/// Steps in the order they are asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetupStep {
+    Permissions,
+    PrivacyLevel,
+    SensitiveApps,
+    StorageLocation,
+    ModelDownload,
+    Complete,
+}
+
+impl fmt::Display for SetupStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SetupStep::Permissions => "permissions",
+            SetupStep::PrivacyLevel => "privacy level",
+            SetupStep::SensitiveApps => "sensitive apps",
+            SetupStep::StorageLocation => "storage location",
+            SetupStep::ModelDownload => "model download",
+            SetupStep::Complete => "complete",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How the AI integration may use remote APIs; mirrors its `UserPrivacyLevel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivacyChoice {
+    /// Never leave the device
+    LocalOnly,
+    /// Remote APIs with personal details stripped from prompts
+    SanitizedAPI,
+    /// Ask before each remote request
+    ConsentBased,
+}
+
+impl PrivacyChoice {
+    fn config_value(self) -> &'static str {
+        match self {
+            PrivacyChoice::LocalOnly => "LocalOnly",
+            PrivacyChoice::SanitizedAPI => "SanitizedAPI",
+            PrivacyChoice::ConsentBased => "ConsentBased",
+        }
+    }
+}
+
+/// One step's answer
+#[derive(Debug, Clone)]
+pub enum SetupAnswer {
+    /// Whether capture permissions were granted; setup continues either way
+    Permissions { granted: bool },
+    PrivacyLevel(PrivacyChoice),
+    SensitiveApps(Vec<String>),
+    /// Directory for the event database
+    StorageLocation(PathBuf),
+    /// Download the local model on first start
+    ModelDownload { download: bool },
+}
+
+impl SetupAnswer {
+    pub fn step(&self) -> SetupStep {
+        match self {
+            SetupAnswer::Permissions { .. } => SetupStep::Permissions,
+            SetupAnswer::PrivacyLevel(_) => SetupStep::PrivacyLevel,
+            SetupAnswer::SensitiveApps(_) => SetupStep::SensitiveApps,
+            SetupAnswer::StorageLocation(_) => SetupStep::StorageLocation,
+            SetupAnswer::ModelDownload { .. } => SetupStep::ModelDownload,
+        }
+    }
+}
+
+/// Everything answered so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetupAnswers {
+    pub permissions_granted: Option<bool>,
+    pub privacy_level: Option<PrivacyChoice>,
+    pub sensitive_apps: Option<Vec<String>>,
+    pub storage_location: Option<PathBuf>,
+    pub download_model: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SetupState {
+    answers: SetupAnswers,
+    started_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl SetupState {
+    fn new() -> Self {
+        let now = Utc::now();
+        Self { answers: SetupAnswers::default(), started_at: now, updated_at: now, completed_at: None }
+    }
+}
+
+/// Resumable first-run setup
+#[derive(Debug)]
+pub struct SetupWizard {
+    config_path: PathBuf,
+    state_path: PathBuf,
+    state: SetupState,
+    resumed: bool,
+}
+
+impl SetupWizard {
+    /// Load saved progress from `data_dir`, or start fresh
+    pub fn open(config_path: impl Into<PathBuf>, data_dir: &Path) -> OrchestratorResult<Self> {
+        let state_path = data_dir.join(SETUP_STATE_FILE);
+        let (state, resumed) = match fs::read_to_string(&state_path) {
+            Ok(contents) => (serde_json::from_str(&contents)?, true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (SetupState::new(), false),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { config_path: config_path.into(), state_path, state, resumed })
+    }
+
+    /// Whether setup has been finished for `data_dir`
+    pub fn is_complete_in(data_dir: &Path) -> bool {
+        fs::read_to_string(data_dir.join(SETUP_STATE_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<SetupState>(&contents).ok())
+            .is_some_and(|state| state.completed_at.is_some())
+    }
+
+    /// Whether earlier progress was loaded
+    pub fn is_resumed(&self) -> bool {
+        self.resumed
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.state.completed_at.is_some()
+    }
+
+    pub fn answers(&self) -> &SetupAnswers {
+        &self.state.answers
+    }
+
+    /// First unanswered step
+    pub fn current_step(&self) -> SetupStep {
+        let answers = &self.state.answers;
+        if answers.permissions_granted.is_none() {
+            SetupStep::Permissions
+        } else if answers.privacy_level.is_none() {
+            SetupStep::PrivacyLevel
+        } else if answers.sensitive_apps.is_none() {
+            SetupStep::SensitiveApps
+        } else if answers.storage_location.is_none() {
+            SetupStep::StorageLocation
+        } else if answers.download_model.is_none() {
+            SetupStep::ModelDownload
+        } else {
+            SetupStep::Complete
+        }
+    }
+
+    /// Record an answer (any step may be revisited) and save progress; returns the next step
+    pub fn submit(&mut self, answer: SetupAnswer) -> OrchestratorResult<SetupStep> {
+        let step = answer.step();
+        let answers = &mut self.state.answers;
+        match answer {
+            SetupAnswer::Permissions { granted } => answers.permissions_granted = Some(granted),
+            SetupAnswer::PrivacyLevel(level) => answers.privacy_level = Some(level),
+            SetupAnswer::SensitiveApps(apps) => {
+                let mut cleaned: Vec<String> = Vec::new();
+                for app in apps.iter().map(|app| app.trim()).filter(|app| !app.is_empty()) {
+                    if !cleaned.iter().any(|existing| existing.eq_ignore_ascii_case(app)) {
+                        cleaned.push(app.to_string());
+                    }
+                }
+                answers.sensitive_apps = Some(cleaned);
+            }
+            SetupAnswer::StorageLocation(directory) => {
+                if directory.as_os_str().is_empty() {
+                    return Err(invalid(step, "no directory given"));
+                }
+                fs::create_dir_all(&directory)
+                    .map_err(|e| invalid(step, &format!("cannot create {}: {}", directory.display(), e)))?;
+                answers.storage_location = Some(directory);
+            }
+            SetupAnswer::ModelDownload { download } => answers.download_model = Some(download),
+        }
+
+        // Changing an answer after finishing means the config needs writing again
+        self.state.completed_at = None;
+        self.save()?;
+        Ok(self.current_step())
+    }
+
+    /// Write the answers into the config file and mark setup complete
+    pub fn finish(&mut self) -> OrchestratorResult<()> {
+        let step = self.current_step();
+        if step != SetupStep::Complete {
+            return Err(OrchestratorError::SetupIncomplete { step: step.to_string() });
+        }
+
+        let mut document = match fs::read_to_string(&self.config_path) {
+            Ok(contents) => contents.parse::<DocumentMut>().map_err(|e| {
+                invalid(SetupStep::Complete, &format!("cannot parse {}: {}", self.config_path.display(), e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+            Err(e) => return Err(e.into()),
+        };
+        apply_answers(&mut document, &self.state.answers)?;
+        write_atomically(&self.config_path, document.to_string().as_bytes())?;
+
+        self.state.completed_at = Some(Utc::now());
+        self.save()?;
+        info!("✨ First-run setup written to {}", self.config_path.display());
+        Ok(())
+    }
+
+    /// Forget saved progress and start over
+    pub fn reset(&mut self) -> OrchestratorResult<()> {
+        self.state = SetupState::new();
+        self.resumed = false;
+        match fs::remove_file(&self.state_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn save(&mut self) -> OrchestratorResult<()> {
+        self.state.updated_at = Utc::now();
+        write_atomically(&self.state_path, &serde_json::to_vec_pretty(&self.state)?)
+    }
+}
+
+/// Set the config keys the modules read for each answer
+fn apply_answers(document: &mut DocumentMut, answers: &SetupAnswers) -> OrchestratorResult<()> {
+    if let Some(level) = answers.privacy_level {
+        set_value(document, &["ai_integration", "privacy", "default_privacy_level"], level.config_value().into())?;
+        set_value(
+            document,
+            &["ai_integration", "privacy", "allow_api_fallback"],
+            (level != PrivacyChoice::LocalOnly).into(),
+        )?;
+    }
+    if let Some(apps) = &answers.sensitive_apps {
+        set_value(document, &["data_capture", "privacy", "sensitive_app_list"], Value::Array(apps.iter().collect::<Array>()))?;
+    }
+    if let Some(directory) = &answers.storage_location {
+        let database = directory.join("events.db").display().to_string();
+        set_value(document, &["storage", "database", "path"], database.into())?;
+    }
+    if let Some(download) = answers.download_model {
+        set_value(document, &["ai_integration", "local_model", "auto_download"], download.into())?;
+    }
+    Ok(())
+}
+
+/// Replace or insert `path`, creating tables on the way and keeping any trailing comment
+fn set_value(document: &mut DocumentMut, path: &[&str], value: Value) -> OrchestratorResult<()> {
+    let (key, tables) = path.split_last().expect("config path is never empty");
+    let mut table: &mut dyn TableLike = document.as_table_mut();
+    for name in tables {
+        table = table
+            .entry(name)
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| invalid(SetupStep::Complete, &format!("`{}` in the config is not a table", name)))?;
+    }
+
+    match table.get_mut(key) {
+        Some(Item::Value(existing)) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(key, Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+/// Write through a temporary file so an interruption never leaves half a file
+fn write_atomically(path: &Path, contents: &[u8]) -> OrchestratorResult<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+fn invalid(step: SetupStep, reason: &str) -> OrchestratorError {
+    OrchestratorError::SetupInvalid { step: step.to_string(), reason: reason.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_resumes_after_interruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        let mut wizard = SetupWizard::open(&config_path, dir.path()).unwrap();
+        assert!(!wizard.is_resumed());
+        assert_eq!(wizard.submit(SetupAnswer::Permissions { granted: true }).unwrap(), SetupStep::PrivacyLevel);
+        assert_eq!(
+            wizard.submit(SetupAnswer::PrivacyLevel(PrivacyChoice::SanitizedAPI)).unwrap(),
+            SetupStep::SensitiveApps
+        );
+        drop(wizard);
+
+        let wizard = SetupWizard::open(&config_path, dir.path()).unwrap();
+        assert!(wizard.is_resumed());
+        assert_eq!(wizard.current_step(), SetupStep::SensitiveApps);
+        assert_eq!(wizard.answers().privacy_level, Some(PrivacyChoice::SanitizedAPI));
+        assert!(!SetupWizard::is_complete_in(dir.path()));
+    }
+
+    #[test]
+    fn test_finish_writes_config_and_keeps_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "# Skelly-Jelly\n[ai_integration.privacy]\ndefault_privacy_level = \"LocalOnly\"  # most private\n",
+        )
+        .unwrap();
+
+        let mut wizard = SetupWizard::open(&config_path, dir.path()).unwrap();
+        assert!(matches!(wizard.finish(), Err(OrchestratorError::SetupIncomplete { .. })));
+
+        wizard.submit(SetupAnswer::Permissions { granted: false }).unwrap();
+        wizard.submit(SetupAnswer::PrivacyLevel(PrivacyChoice::ConsentBased)).unwrap();
+        wizard
+            .submit(SetupAnswer::SensitiveApps(vec!["1Password".into(), " ".into(), "1password".into(), "Signal".into()]))
+            .unwrap();
+        wizard.submit(SetupAnswer::StorageLocation(dir.path().join("store"))).unwrap();
+        wizard.submit(SetupAnswer::ModelDownload { download: true }).unwrap();
+        wizard.finish().unwrap();
+
+        let written = fs::read_to_string(&config_path).unwrap();
+        assert!(written.starts_with("# Skelly-Jelly\n"));
+        assert!(written.contains("default_privacy_level = \"ConsentBased\"  # most private\n"));
+
+        let config: toml::Value = toml::from_str(&written).unwrap();
+        assert_eq!(config["ai_integration"]["privacy"]["allow_api_fallback"].as_bool(), Some(true));
+        assert_eq!(config["ai_integration"]["local_model"]["auto_download"].as_bool(), Some(true));
+        let apps = config["data_capture"]["privacy"]["sensitive_app_list"].as_array().unwrap();
+        assert_eq!(apps.len(), 2);
+        assert!(config["storage"]["database"]["path"].as_str().unwrap().ends_with("events.db"));
+        assert!(SetupWizard::is_complete_in(dir.path()));
+    }
+}
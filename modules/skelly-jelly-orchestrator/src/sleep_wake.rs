@@ -0,0 +1,298 @@
+//! OS sleep, wake and shutdown handling
+//!
+//! Before the machine suspends, capture is paused and a `SystemPower` sleep
+//! event tells storage to checkpoint. After it resumes, a wake event tells
+//! the analysis engine to start a fresh window instead of closing one that
+//! spans the whole suspend, and capture is started again with fresh monitors.
+//! OS shutdown gets the same checkpoint before modules are stopped.
+//!
+//! Platform helpers announce transitions as config updates under
+//! [`SYSTEM_POWER_KEY`], e.g. `{"event": "sleep"}`. A suspend nobody
+//! announced is caught after the fact: the wall clock keeps running while the
+//! machine sleeps but the monotonic clock doesn't, so a jump between them
+//! means the machine was asleep.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use crate::lifecycle::{LifecycleController, ModuleState};
+use crate::module_registry::ModuleRegistry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::{
+    message::{SystemPowerEvent, SystemPowerTransition},
+    BusMessage, EventBusTrait, MessagePayload, MessagePriority, ModuleId,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{debug, info, warn};
+
+/// Config key for sleep/wake/shutdown reports, e.g. `{"event": "sleep"}`
+pub const SYSTEM_POWER_KEY: &str = "system_power";
+
+/// Sleep and wake handling settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepWakeConfig {
+    pub enabled: bool,
+    /// How often the clocks are compared to catch an unannounced suspend
+    pub check_interval: Duration,
+    /// Wall-clock time beyond monotonic time that counts as a suspend
+    pub sleep_gap_threshold: Duration,
+    /// Time allowed for capture to stop before sleep
+    pub pause_timeout: Duration,
+}
+
+impl Default for SleepWakeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval: Duration::from_secs(5),
+            sleep_gap_threshold: Duration::from_secs(30),
+            pause_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Spots a suspend from the wall clock running ahead of the monotonic clock
+#[derive(Debug, Clone)]
+pub struct ClockJumpDetector {
+    threshold: Duration,
+    last: Option<(DateTime<Utc>, Instant)>,
+}
+
+impl ClockJumpDetector {
+    pub fn new(threshold: Duration) -> Self {
+        Self { threshold, last: None }
+    }
+
+    /// Record a reading of both clocks; returns how long the machine slept since the last one
+    pub fn observe(&mut self, wall: DateTime<Utc>, monotonic: Instant) -> Option<Duration> {
+        let previous = self.last.replace((wall, monotonic))?;
+        let wall_elapsed = (wall - previous.0).to_std().ok()?;
+        let gap = wall_elapsed.saturating_sub(monotonic.duration_since(previous.1));
+        (gap >= self.threshold).then_some(gap)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SleepState {
+    /// Set between a sleep and the matching wake
+    asleep_since: Option<DateTime<Utc>>,
+    /// Capture was running and stopped for the sleep
+    capture_paused: bool,
+}
+
+/// Coordinates modules around OS power transitions
+pub struct SleepWakeCoordinator {
+    config: SleepWakeConfig,
+    registry: Arc<ModuleRegistry>,
+    lifecycle: Arc<LifecycleController>,
+    event_bus: Arc<dyn EventBusTrait>,
+    state: RwLock<SleepState>,
+    monitor_task: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl SleepWakeCoordinator {
+    pub fn new(
+        config: SleepWakeConfig,
+        registry: Arc<ModuleRegistry>,
+        lifecycle: Arc<LifecycleController>,
+        event_bus: Arc<dyn EventBusTrait>,
+    ) -> Self {
+        Self {
+            config,
+            registry,
+            lifecycle,
+            event_bus,
+            state: RwLock::new(SleepState::default()),
+            monitor_task: RwLock::new(None),
+        }
+    }
+
+    /// Whether a sleep has been handled without a wake yet
+    pub async fn is_asleep(&self) -> bool {
+        self.state.read().await.asleep_since.is_some()
+    }
+
+    /// Pause capture and have storage checkpoint; returns false if already prepared
+    pub async fn prepare_for_sleep(&self) -> OrchestratorResult<bool> {
+        let mut state = self.state.write().await;
+        if state.asleep_since.is_some() {
+            return Ok(false);
+        }
+        info!("💤 System going to sleep");
+
+        // Stop capture first so the checkpoint includes its last events
+        if matches!(self.registry.get_module_state(ModuleId::DataCapture), Some(ModuleState::Running { .. })) {
+            match self.lifecycle.stop_module_with(ModuleId::DataCapture, self.config.pause_timeout, false).await {
+                Ok(()) => state.capture_paused = true,
+                Err(e) => warn!("Could not pause capture before sleep: {}", e),
+            }
+        }
+        state.asleep_since = Some(Utc::now());
+        drop(state);
+
+        self.publish(SystemPowerTransition::Sleep, None).await?;
+        Ok(true)
+    }
+
+    /// Re-baseline analysis and restart capture; returns false if nothing was asleep
+    ///
+    /// `slept_for` comes from the clock check when the sleep wasn't announced;
+    /// otherwise it is measured from the sleep event.
+    pub async fn handle_wake(&self, slept_for: Option<Duration>) -> OrchestratorResult<bool> {
+        let (asleep_since, capture_paused) = {
+            let mut state = self.state.write().await;
+            let asleep_since = state.asleep_since.take();
+            (asleep_since, std::mem::take(&mut state.capture_paused))
+        };
+        if asleep_since.is_none() && slept_for.is_none() {
+            return Ok(false);
+        }
+        let slept_for = slept_for.or_else(|| asleep_since.and_then(|since| (Utc::now() - since).to_std().ok()));
+        info!("☀️ System woke after {:?}", slept_for);
+
+        // Analysis drops its open window before capture sends anything new
+        self.publish(SystemPowerTransition::Wake, slept_for).await?;
+
+        if capture_paused {
+            self.lifecycle.start_module(ModuleId::DataCapture).await?;
+        } else if matches!(self.registry.get_module_state(ModuleId::DataCapture), Some(ModuleState::Running { .. })) {
+            // Slept without warning: the monitors' OS hooks may not have survived
+            self.lifecycle.restart_module(ModuleId::DataCapture).await?;
+        }
+        Ok(true)
+    }
+
+    /// Have storage checkpoint before the OS shuts down
+    pub async fn prepare_for_shutdown(&self) -> OrchestratorResult<()> {
+        info!("🔌 Preparing for OS shutdown");
+        self.publish(SystemPowerTransition::Shutdown, None).await
+    }
+
+    /// Handle sleep/wake/shutdown reports sent as config updates
+    pub async fn handle_message(&self, message: &BusMessage) -> OrchestratorResult<()> {
+        let update = match &message.payload {
+            MessagePayload::ConfigUpdate(update)
+                if update.config_key == SYSTEM_POWER_KEY
+                    && matches!(update.target_module, None | Some(ModuleId::Orchestrator)) => update,
+            _ => return Ok(()),
+        };
+
+        let event = update.config_value.get("event").cloned().unwrap_or_default();
+        let transition: SystemPowerTransition = serde_json::from_value(event)
+            .map_err(|e| OrchestratorError::ConfigurationError {
+                module: ModuleId::Orchestrator,
+                reason: format!("Invalid system power event: {}", e),
+            })?;
+        match transition {
+            SystemPowerTransition::Sleep => self.prepare_for_sleep().await.map(drop),
+            SystemPowerTransition::Wake => self.handle_wake(None).await.map(drop),
+            SystemPowerTransition::Shutdown => self.prepare_for_shutdown().await,
+        }
+    }
+
+    /// Compare clocks periodically to catch suspends nobody announced
+    pub async fn start_monitoring(self: &Arc<Self>) {
+        if !self.config.enabled {
+            return;
+        }
+        let coordinator = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            let mut detector = ClockJumpDetector::new(coordinator.config.sleep_gap_threshold);
+            let mut ticker = tokio::time::interval(coordinator.config.check_interval);
+            loop {
+                ticker.tick().await;
+                if let Some(slept_for) = detector.observe(Utc::now(), Instant::now()) {
+                    debug!("Wall clock jumped {:?} past the monotonic clock", slept_for);
+                    if let Err(e) = coordinator.handle_wake(Some(slept_for)).await {
+                        warn!("Failed to handle wake: {}", e);
+                    }
+                }
+            }
+        });
+        *self.monitor_task.write().await = Some(task);
+    }
+
+    pub async fn stop_monitoring(&self) {
+        if let Some(task) = self.monitor_task.write().await.take() {
+            task.abort();
+        }
+    }
+
+    async fn publish(&self, transition: SystemPowerTransition, slept_for: Option<Duration>) -> OrchestratorResult<()> {
+        let message = BusMessage::with_priority(
+            ModuleId::Orchestrator,
+            MessagePayload::SystemPower(SystemPowerEvent {
+                transition,
+                slept_for,
+                timestamp: Utc::now(),
+            }),
+            MessagePriority::Critical,
+        );
+        self.event_bus.publish(message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigurationManager, OrchestratorConfig};
+    use skelly_jelly_event_bus::{create_event_bus, message::ConfigUpdate};
+
+    #[test]
+    fn test_clock_jump_detector() {
+        let mut detector = ClockJumpDetector::new(Duration::from_secs(30));
+        let (wall, monotonic) = (Utc::now(), Instant::now());
+        let at = |wall_secs: i64, monotonic_secs: u64| {
+            (wall + chrono::Duration::seconds(wall_secs), monotonic + Duration::from_secs(monotonic_secs))
+        };
+
+        assert_eq!(detector.observe(wall, monotonic), None);
+        // Regular ticks, and NTP nudging the wall clock a little
+        let (w, m) = at(5, 5);
+        assert_eq!(detector.observe(w, m), None);
+        let (w, m) = at(12, 10);
+        assert_eq!(detector.observe(w, m), None);
+        // Ten minutes pass on the wall clock while the monotonic clock was stopped
+        let (w, m) = at(617, 15);
+        assert_eq!(detector.observe(w, m), Some(Duration::from_secs(600)));
+        // A wall clock set backwards is not a sleep
+        let (w, m) = at(300, 20);
+        assert_eq!(detector.observe(w, m), None);
+    }
+
+    #[tokio::test]
+    async fn test_sleep_then_wake() {
+        let bus = create_event_bus().unwrap();
+        bus.start().await.unwrap();
+        let registry = Arc::new(ModuleRegistry::new());
+        let config_manager = Arc::new(ConfigurationManager::new(OrchestratorConfig::default(), bus.clone()));
+        let lifecycle = Arc::new(LifecycleController::new(Arc::clone(&registry), bus.clone(), config_manager));
+        let coordinator = SleepWakeCoordinator::new(SleepWakeConfig::default(), registry, lifecycle, bus.clone());
+
+        // A wake with nothing asleep and no measured gap is ignored
+        assert!(!coordinator.handle_wake(None).await.unwrap());
+
+        let report = |event: &str| {
+            BusMessage::new(ModuleId::Orchestrator, MessagePayload::ConfigUpdate(ConfigUpdate {
+                config_key: SYSTEM_POWER_KEY.to_string(),
+                config_value: serde_json::json!({ "event": event }),
+                target_module: Some(ModuleId::Orchestrator),
+            }))
+        };
+        coordinator.handle_message(&report("sleep")).await.unwrap();
+        assert!(coordinator.is_asleep().await);
+        // Helpers may announce the same sleep twice
+        assert!(!coordinator.prepare_for_sleep().await.unwrap());
+
+        coordinator.handle_message(&report("wake")).await.unwrap();
+        assert!(!coordinator.is_asleep().await);
+        assert!(coordinator.handle_message(&report("hibernate")).await.is_err());
+
+        // One sleep and one wake event
+        assert_eq!(bus.metrics().await.unwrap().messages_published, 2);
+    }
+}
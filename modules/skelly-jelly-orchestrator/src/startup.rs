@@ -2,10 +2,12 @@
 
 use crate::{
     error::{OrchestratorError, OrchestratorResult},
-    lifecycle::LifecycleController,
+    lifecycle::{LifecycleController, ModuleState, StopReason},
     health::HealthMonitor,
-    module_registry::ModuleRegistry,
-    config::ConfigurationManager,
+    module_registry::{CriticalPath, ModuleRegistry},
+    config::{ConfigurationManager, OrchestratorConfig},
+    readiness::ReadinessGate,
+    safe_mode::CrashLoopDetector,
 };
 use skelly_jelly_event_bus::{EventBusTrait, ModuleId, BusMessage, MessagePayload};
 use serde::{Deserialize, Serialize};
@@ -14,7 +16,7 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::time::timeout;
+use tokio::sync::Semaphore;
 use tracing::{info, warn, error, debug};
 
 /// Startup phase tracking
@@ -50,6 +52,23 @@ pub struct StartupMetrics {
     pub health_validation_time: Duration,
     pub target_met: bool,
     pub bottlenecks: Vec<StartupBottleneck>,
+    /// Wall-clock time of each dependency level, in startup order
+    pub level_timings: Vec<StartupLevelTiming>,
+    /// Time between a module's start call returning and its readiness signal
+    pub readiness_wait_times: HashMap<ModuleId, Duration>,
+    /// Slowest dependency chain; shortening it is the only way to start faster
+    pub critical_path: CriticalPath,
+    /// Modules that crash-looped and were left out of this startup
+    pub quarantined_modules: Vec<ModuleId>,
+}
+
+/// Timing for one dependency level of the startup
+#[derive(Debug, Clone)]
+pub struct StartupLevelTiming {
+    pub level: usize,
+    pub phase: StartupPhase,
+    pub modules: Vec<ModuleId>,
+    pub duration: Duration,
 }
 
 /// Identified startup bottlenecks
@@ -73,6 +92,8 @@ pub enum BottleneckImpact {
 #[derive(Debug, Clone)]
 pub struct StartupGroup {
     pub phase: StartupPhase,
+    /// Dependency level; every module in a group only depends on lower levels
+    pub level: usize,
     pub modules: Vec<ModuleId>,
     pub dependencies_satisfied: bool,
     pub max_parallel: usize,
@@ -86,6 +107,8 @@ pub struct StartupSequencer {
     health_monitor: Arc<tokio::sync::RwLock<HealthMonitor>>,
     config_manager: Arc<ConfigurationManager>,
     event_bus: Arc<dyn EventBusTrait>,
+    readiness: Arc<ReadinessGate>,
+    crash_loop: Arc<CrashLoopDetector>,
     
     /// Performance targets
     total_startup_target: Duration,
@@ -108,12 +131,20 @@ impl StartupSequencer {
         config_manager: Arc<ConfigurationManager>,
         event_bus: Arc<dyn EventBusTrait>,
     ) -> Self {
+        let readiness = lifecycle_controller.readiness_gate();
+        let defaults = OrchestratorConfig::default();
+
         Self {
             registry,
             lifecycle_controller,
             health_monitor,
             config_manager,
             event_bus,
+            readiness,
+            crash_loop: Arc::new(CrashLoopDetector::new(
+                defaults.crash_loop_max_failures,
+                defaults.crash_loop_window,
+            )),
             total_startup_target: Duration::from_secs(10), // Target: <10 seconds
             health_check_target: Duration::from_secs(2),
             current_phase: StartupPhase::Initializing,
@@ -127,10 +158,20 @@ impl StartupSequencer {
                 health_validation_time: Duration::ZERO,
                 target_met: false,
                 bottlenecks: Vec::new(),
+                level_timings: Vec::new(),
+                readiness_wait_times: HashMap::new(),
+                critical_path: CriticalPath::default(),
+                quarantined_modules: Vec::new(),
             },
         }
     }
 
+    /// Share the orchestrator's crash-loop detector so quarantines outlive this startup
+    pub fn with_crash_loop_detector(mut self, crash_loop: Arc<CrashLoopDetector>) -> Self {
+        self.crash_loop = crash_loop;
+        self
+    }
+
     /// Execute coordinated system startup with performance monitoring
     pub async fn startup_system(&mut self) -> OrchestratorResult<StartupMetrics> {
         info!("🚀 Starting coordinated system startup sequence");
@@ -144,17 +185,12 @@ impl StartupSequencer {
         let startup_order = self.compute_optimized_startup_order().await?;
         info!("📋 Computed startup order: {:?}", startup_order);
 
-        // Phase 2: Start core infrastructure (EventBus first)
-        self.advance_phase(StartupPhase::StartingCore).await?;
-        self.start_core_modules(&startup_order).await?;
-
-        // Phase 3: Start service modules with parallelization
-        self.advance_phase(StartupPhase::StartingServices).await?;
-        self.start_service_modules_parallel(&startup_order).await?;
-
-        // Phase 4: Start UI modules
-        self.advance_phase(StartupPhase::StartingUI).await?;
-        self.start_ui_modules(&startup_order).await?;
+        // Phases 2-4: Core infrastructure, services, then UI, one dependency level at a time
+        for phase in [StartupPhase::StartingCore, StartupPhase::StartingServices, StartupPhase::StartingUI] {
+            self.advance_phase(phase).await?;
+            self.start_phase_groups(phase, &startup_order).await?;
+        }
+        self.analyze_critical_path().await?;
 
         // Phase 5: System validation and health checks
         self.advance_phase(StartupPhase::ValidatingSystem).await?;
@@ -165,6 +201,10 @@ impl StartupSequencer {
         
         // Calculate final metrics
         self.finalize_metrics(startup_start);
+
+        if !self.metrics.quarantined_modules.is_empty() {
+            warn!("🛟 Started in safe mode without {:?}", self.metrics.quarantined_modules);
+        }
         
         let total_time = startup_start.elapsed();
         if total_time <= self.total_startup_target {
@@ -183,208 +223,161 @@ impl StartupSequencer {
         Ok(self.metrics.clone())
     }
 
-    /// Compute optimized startup order with parallel groups
+    /// Compute startup groups from the dependency graph's levels
     async fn compute_optimized_startup_order(&mut self) -> OrchestratorResult<Vec<StartupGroup>> {
         let dependency_start = Instant::now();
         
-        // Get basic dependency order
-        let _basic_order = self.registry.compute_startup_order().await?;
-        
-        // Group modules by startup phase and dependencies
-        let groups = vec![
-            StartupGroup {
-                phase: StartupPhase::StartingCore,
-                modules: vec![ModuleId::EventBus, ModuleId::Orchestrator],
-                dependencies_satisfied: true,
-                max_parallel: 1, // Core modules start sequentially
-                timeout: Duration::from_secs(5),
-            },
-            StartupGroup {
-                phase: StartupPhase::StartingServices,
-                modules: vec![
-                    ModuleId::Storage,
-                    ModuleId::DataCapture,
-                    ModuleId::AnalysisEngine,
-                ],
-                dependencies_satisfied: false,
-                max_parallel: 2, // Can start some in parallel
-                timeout: Duration::from_secs(15),
-            },
-            StartupGroup {
-                phase: StartupPhase::StartingUI,
-                modules: vec![
-                    ModuleId::Gamification,
-                    ModuleId::AiIntegration,
-                    ModuleId::CuteFigurine,
-                ],
-                dependencies_satisfied: false,
-                max_parallel: 3, // UI modules can start in parallel
-                timeout: Duration::from_secs(10),
-            },
-        ];
-
-        self.metrics.dependency_resolution_time = dependency_start.elapsed();
-        debug!("🔗 Dependency resolution completed in {:?}", self.metrics.dependency_resolution_time);
-        
-        Ok(groups)
-    }
-
-    /// Start core infrastructure modules sequentially
-    async fn start_core_modules(&mut self, groups: &[StartupGroup]) -> OrchestratorResult<()> {
-        let core_group = groups.iter()
-            .find(|g| matches!(g.phase, StartupPhase::StartingCore))
-            .ok_or_else(|| OrchestratorError::ConfigurationError {
-                module: ModuleId::Orchestrator,
-                reason: "Core startup group not found".to_string(),
-            })?;
-
-        info!("🏗️  Starting core modules: {:?}", core_group.modules);
-
-        for &module_id in &core_group.modules {
-            if module_id == ModuleId::Orchestrator {
-                continue; // Already running
-            }
-
-            let module_start = Instant::now();
-            
-            // Start module with timeout
-            match timeout(core_group.timeout, self.lifecycle_controller.start_module(module_id)).await {
-                Ok(Ok(())) => {
-                    let duration = module_start.elapsed();
-                    self.metrics.module_startup_times.insert(module_id, duration);
-                    info!("✅ Core module {} started in {:?}", module_id, duration);
-                    
-                    // Brief delay between core modules for stability
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
-                Ok(Err(e)) => {
-                    error!("❌ Failed to start core module {}: {}", module_id, e);
-                    return Err(e);
-                }
-                Err(_) => {
-                    let error = OrchestratorError::ModuleStartupFailed {
-                        module: module_id,
-                        reason: format!("Timeout after {:?}", core_group.timeout),
-                    };
-                    error!("❌ Core module {} startup timeout", module_id);
-                    return Err(error);
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Start service modules with controlled parallelization
-    async fn start_service_modules_parallel(&mut self, groups: &[StartupGroup]) -> OrchestratorResult<()> {
-        let service_group = groups.iter()
-            .find(|g| matches!(g.phase, StartupPhase::StartingServices))
-            .ok_or_else(|| OrchestratorError::ConfigurationError {
-                module: ModuleId::Orchestrator,
-                reason: "Service startup group not found".to_string(),
-            })?;
-
-        info!("⚙️  Starting service modules with parallelization: {:?}", service_group.modules);
-
-        // Start modules in parallel batches
-        let mut remaining_modules = service_group.modules.clone();
-        
-        while !remaining_modules.is_empty() {
-            // Take up to max_parallel modules that have dependencies satisfied
-            let mut current_batch = Vec::new();
-            let mut i = 0;
-            
-            while i < remaining_modules.len() && current_batch.len() < service_group.max_parallel {
-                let module_id = remaining_modules[i];
-                
-                // Check if dependencies are satisfied
-                if self.are_dependencies_satisfied(module_id).await {
-                    current_batch.push(module_id);
-                    remaining_modules.remove(i);
-                } else {
-                    i += 1;
+        let levels = self.registry.compute_startup_levels().await?;
+        let parallel = self.config_manager.get_global_config().await.parallel_startup;
+
+        // A module never starts in an earlier phase than any of its dependencies
+        let mut module_phases: HashMap<ModuleId, StartupPhase> = HashMap::new();
+        let mut groups = Vec::new();
+
+        for (level, modules) in levels.into_iter().enumerate() {
+            let mut by_phase: Vec<(StartupPhase, Vec<ModuleId>)> = Vec::new();
+
+            for module_id in modules {
+                let mut phase = Self::default_phase(module_id);
+                for dependency in self.registry.get_dependencies(module_id).await {
+                    if let Some(&dependency_phase) = module_phases.get(&dependency) {
+                        phase = Self::later_phase(phase, dependency_phase);
+                    }
                 }
-            }
+                module_phases.insert(module_id, phase);
 
-            if current_batch.is_empty() {
-                // Dependency deadlock - try to start one module anyway
-                if let Some(module_id) = remaining_modules.first() {
-                    warn!("⚠️  Potential dependency deadlock, force-starting {}", module_id);
-                    current_batch.push(*module_id);
-                    remaining_modules.remove(0);
+                match by_phase.iter_mut().find(|(p, _)| *p == phase) {
+                    Some((_, group)) => group.push(module_id),
+                    None => by_phase.push((phase, vec![module_id])),
                 }
             }
 
-            // Start current batch in parallel
-            if !current_batch.is_empty() {
-                self.start_module_batch(current_batch, service_group.timeout).await?;
-                
-                // Brief pause between batches
-                tokio::time::sleep(Duration::from_millis(200)).await;
+            for (phase, modules) in by_phase {
+                let timeout = modules.iter()
+                    .filter_map(|&m| self.registry.get_module(m))
+                    .map(|descriptor| descriptor.startup_timeout)
+                    .max()
+                    .unwrap_or(Duration::from_secs(30));
+
+                groups.push(StartupGroup {
+                    phase,
+                    level,
+                    max_parallel: if parallel { modules.len() } else { 1 },
+                    modules,
+                    dependencies_satisfied: level == 0,
+                    timeout,
+                });
             }
         }
 
-        Ok(())
+        self.metrics.dependency_resolution_time = dependency_start.elapsed();
+        debug!("🔗 Dependency resolution completed in {:?}", self.metrics.dependency_resolution_time);
+        
+        Ok(groups)
     }
 
-    /// Start UI modules in parallel
-    async fn start_ui_modules(&mut self, groups: &[StartupGroup]) -> OrchestratorResult<()> {
-        let ui_group = groups.iter()
-            .find(|g| matches!(g.phase, StartupPhase::StartingUI))
-            .ok_or_else(|| OrchestratorError::ConfigurationError {
-                module: ModuleId::Orchestrator,
-                reason: "UI startup group not found".to_string(),
-            })?;
+    /// Phase a module starts in when its dependencies don't push it later
+    fn default_phase(module_id: ModuleId) -> StartupPhase {
+        match module_id {
+            ModuleId::EventBus | ModuleId::Orchestrator => StartupPhase::StartingCore,
+            ModuleId::Storage | ModuleId::DataCapture | ModuleId::AnalysisEngine => StartupPhase::StartingServices,
+            ModuleId::Gamification | ModuleId::AiIntegration | ModuleId::CuteFigurine => StartupPhase::StartingUI,
+        }
+    }
 
-        info!("🎨 Starting UI modules: {:?}", ui_group.modules);
+    fn later_phase(a: StartupPhase, b: StartupPhase) -> StartupPhase {
+        let rank = |phase: StartupPhase| match phase {
+            StartupPhase::StartingCore => 0,
+            StartupPhase::StartingServices => 1,
+            _ => 2,
+        };
+        if rank(b) > rank(a) { b } else { a }
+    }
 
-        // UI modules can typically start in parallel
-        self.start_module_batch(ui_group.modules.clone(), ui_group.timeout).await?;
+    /// Start every group belonging to a phase, level by level
+    async fn start_phase_groups(&mut self, phase: StartupPhase, groups: &[StartupGroup]) -> OrchestratorResult<()> {
+        for group in groups.iter().filter(|g| g.phase == phase) {
+            info!("📦 Starting level {} ({:?}): {:?}", group.level, phase, group.modules);
+            let level_start = Instant::now();
+
+            self.start_module_batch(group).await?;
+
+            let duration = level_start.elapsed();
+            debug!("📊 Level {} ready in {:?}", group.level, duration);
+            self.metrics.level_timings.push(StartupLevelTiming {
+                level: group.level,
+                phase,
+                modules: group.modules.clone(),
+                duration,
+            });
+        }
 
         Ok(())
     }
 
-    /// Start a batch of modules in parallel
-    async fn start_module_batch(&mut self, modules: Vec<ModuleId>, timeout_duration: Duration) -> OrchestratorResult<()> {
-        let batch_start = Instant::now();
-        
-        // Create futures for all modules in the batch
+    /// Start a group of independent modules concurrently, each gated on its readiness signal.
+    /// A module that keeps failing is retried until the crash-loop detector quarantines it
+    async fn start_module_batch(&mut self, group: &StartupGroup) -> OrchestratorResult<()> {
+        let permits = Arc::new(Semaphore::new(group.max_parallel.max(1)));
+        let retry_delay = self.config_manager.get_global_config().await.module_start_delay;
         let mut tasks = Vec::new();
         
-        for module_id in &modules {
+        for &module_id in &group.modules {
+            if module_id == ModuleId::Orchestrator {
+                // Already running
+                self.readiness.mark_ready(module_id);
+                continue;
+            }
+            if self.crash_loop.is_sidelined(module_id) {
+                warn!("🛟 Skipping {}: quarantined or waiting on a quarantined dependency", module_id);
+                continue;
+            }
+
             let lifecycle_controller = Arc::clone(&self.lifecycle_controller);
-            let module_id = *module_id;
+            let readiness = Arc::clone(&self.readiness);
+            let crash_loop = Arc::clone(&self.crash_loop);
+            let permits = Arc::clone(&permits);
+            let timeout_duration = self.registry.get_module(module_id)
+                .map(|descriptor| descriptor.startup_timeout)
+                .unwrap_or(group.timeout);
             
             let task = tokio::spawn(async move {
+                let _permit = permits.acquire_owned().await;
                 let start_time = Instant::now();
-                let result = lifecycle_controller.start_module(module_id).await;
-                (module_id, result, start_time.elapsed())
+                loop {
+                    let result = match lifecycle_controller.start_module(module_id).await {
+                        Ok(()) => readiness.wait_ready(module_id, timeout_duration).await,
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Err(e) if crash_loop.record_failure(module_id, &e.to_string()).is_none()
+                            && !crash_loop.is_quarantined(module_id) => {
+                            warn!("🔁 Module {} failed to start, retrying: {}", module_id, e);
+                            tokio::time::sleep(retry_delay).await;
+                        }
+                        result => return (module_id, result, start_time.elapsed()),
+                    }
+                }
             });
             
             tasks.push(task);
         }
 
-        // Wait for all tasks to complete or timeout
-        let results = match timeout(timeout_duration, futures::future::join_all(tasks)).await {
-            Ok(results) => results,
-            Err(_) => {
-                error!("❌ Module batch startup timeout after {:?}", timeout_duration);
-                return Err(OrchestratorError::ModuleStartupFailed {
-                    module: ModuleId::Orchestrator,
-                    reason: format!("Batch timeout after {:?}", timeout_duration),
-                });
-            }
-        };
-
-        // Process results
-        for result in results {
+        // Every module has its own start and readiness timeout, so just wait for them all
+        for result in futures::future::join_all(tasks).await {
             match result {
-                Ok((module_id, Ok(()), duration)) => {
+                Ok((module_id, Ok(readiness_wait), duration)) => {
                     self.metrics.module_startup_times.insert(module_id, duration);
-                    info!("✅ Module {} started in {:?}", module_id, duration);
+                    self.metrics.readiness_wait_times.insert(module_id, readiness_wait);
+                    info!("✅ Module {} ready in {:?}", module_id, duration);
                 }
                 Ok((module_id, Err(e), duration)) => {
                     error!("❌ Module {} failed to start after {:?}: {}", module_id, duration, e);
+                    // A start call can return before the module ever announced itself
+                    self.registry.set_module_state(
+                        module_id,
+                        ModuleState::Failed { error: e.to_string(), attempts: 1 },
+                    );
                     
                     // Record as bottleneck
                     self.metrics.bottlenecks.push(StartupBottleneck {
@@ -394,7 +387,11 @@ impl StartupSequencer {
                         impact: BottleneckImpact::High,
                     });
                     
-                    return Err(e);
+                    if !self.crash_loop.is_quarantined(module_id) {
+                        return Err(e);
+                    }
+                    self.quarantine_dependents(module_id).await;
+                    self.metrics.quarantined_modules.push(module_id);
                 }
                 Err(e) => {
                     error!("❌ Task execution error: {}", e);
@@ -406,25 +403,37 @@ impl StartupSequencer {
             }
         }
 
-        debug!("📦 Batch of {} modules started in {:?}", modules.len(), batch_start.elapsed());
         Ok(())
     }
 
-    /// Check if all dependencies for a module are satisfied
-    async fn are_dependencies_satisfied(&self, module_id: ModuleId) -> bool {
-        let dependencies = self.registry.get_dependencies(module_id).await;
-        
-        for dependency in dependencies {
-            if let Some(state) = self.registry.get_module_state(dependency) {
-                if !matches!(state, crate::lifecycle::ModuleState::Running { .. }) {
-                    return false;
-                }
-            } else {
-                return false;
-            }
+    /// Hold back everything that transitively depends on a quarantined module
+    async fn quarantine_dependents(&self, module_id: ModuleId) {
+        let held_back = self.registry.get_transitive_dependents(module_id).await;
+
+        for &dependent in &held_back {
+            self.registry.set_module_state(
+                dependent,
+                ModuleState::Stopped { reason: StopReason::Dependency(module_id) },
+            );
         }
-        
-        true
+        if !held_back.is_empty() {
+            warn!("🛟 Holding back {:?} until {} leaves quarantine", held_back, module_id);
+        }
+        self.crash_loop.hold_back(module_id, held_back);
+    }
+
+    /// Work out which dependency chain bounded the startup time
+    async fn analyze_critical_path(&mut self) -> OrchestratorResult<()> {
+        let critical_path = self.registry.critical_path(&self.metrics.module_startup_times).await?;
+
+        info!(
+            "🧭 Critical startup path ({:?}): {}",
+            critical_path.duration,
+            critical_path.modules.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(" → "),
+        );
+
+        self.metrics.critical_path = critical_path;
+        Ok(())
     }
 
     /// Validate system health after startup
@@ -442,7 +451,8 @@ impl StartupSequencer {
         let mut unhealthy_modules = Vec::new();
         let mut degraded_modules = Vec::new();
 
-        for report in &health_reports {
+        // Quarantined modules are already accounted for by safe mode
+        for report in health_reports.iter().filter(|r| !self.crash_loop.is_sidelined(r.module_id)) {
             match &report.status {
                 crate::health::HealthStatus::Unhealthy { reason } => {
                     unhealthy_modules.push((report.module_id, reason.clone()));
@@ -568,6 +578,15 @@ impl StartupSequencer {
             "target_met": self.metrics.target_met,
             "modules_started": self.metrics.module_startup_times.len(),
             "bottlenecks_count": self.metrics.bottlenecks.len(),
+            "safe_mode": !self.metrics.quarantined_modules.is_empty(),
+            "quarantined_modules": self.metrics.quarantined_modules,
+            "critical_path": self.metrics.critical_path.modules,
+            "critical_path_ms": self.metrics.critical_path.duration.as_millis(),
+            "levels": self.metrics.level_timings.iter().map(|timing| serde_json::json!({
+                "level": timing.level,
+                "modules": timing.modules,
+                "duration_ms": timing.duration.as_millis(),
+            })).collect::<Vec<_>>(),
         });
 
         let config_update = skelly_jelly_event_bus::message::ConfigUpdate {
@@ -592,6 +611,11 @@ impl StartupSequencer {
         &self.metrics
     }
 
+    /// Get the critical startup path from the last run
+    pub fn get_critical_path(&self) -> &CriticalPath {
+        &self.metrics.critical_path
+    }
+
     /// Get current startup phase
     pub fn get_current_phase(&self) -> StartupPhase {
         self.current_phase
@@ -0,0 +1,186 @@
+//! Live system map export
+//!
+//! Renders the module dependency graph as Graphviz DOT or Mermaid, with each
+//! module annotated by its lifecycle state, health, startup time and bus
+//! traffic, so the running system can be drawn with stock tooling.
+
+use crate::health::HealthStatus;
+use serde::{Deserialize, Serialize};
+use skelly_jelly_event_bus::ModuleId;
+use std::{fmt::Write, str::FromStr, time::Duration};
+
+/// Output format of an exported graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            other => Err(format!("Unknown graph format: {}", other)),
+        }
+    }
+}
+
+/// One module on the map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleNode {
+    pub module: ModuleId,
+    /// Lifecycle state, as named by `GET /modules`
+    pub state: String,
+    pub health: HealthStatus,
+    /// How long the module took to start, if it has started
+    pub startup_time: Option<Duration>,
+    pub messages_published: u64,
+    pub messages_received: u64,
+}
+
+impl ModuleNode {
+    fn health_label(&self) -> &'static str {
+        match self.health {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded { .. } => "degraded",
+            HealthStatus::Unhealthy { .. } => "unhealthy",
+            HealthStatus::Unknown => "unknown",
+        }
+    }
+
+    fn fill_color(&self) -> &'static str {
+        match self.health {
+            HealthStatus::Healthy => "#c8e6c9",
+            HealthStatus::Degraded { .. } => "#fff3c4",
+            HealthStatus::Unhealthy { .. } => "#ffcdd2",
+            HealthStatus::Unknown => "#e0e0e0",
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            self.module.to_string(),
+            format!("{} · {}", self.state, self.health_label()),
+        ];
+        if let Some(startup_time) = self.startup_time {
+            lines.push(format!("startup {}ms", startup_time.as_millis()));
+        }
+        lines.push(format!("{} out / {} in", self.messages_published, self.messages_received));
+        lines
+    }
+}
+
+/// Module dependency graph with live annotations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemMap {
+    pub modules: Vec<ModuleNode>,
+    /// `(dependency, dependent)` pairs; the dependency starts first
+    pub dependencies: Vec<(ModuleId, ModuleId)>,
+}
+
+impl SystemMap {
+    pub fn new(mut modules: Vec<ModuleNode>, mut dependencies: Vec<(ModuleId, ModuleId)>) -> Self {
+        modules.sort_by_key(|node| node.module.to_string());
+        dependencies.sort_by_key(|(from, to)| (from.to_string(), to.to_string()));
+        Self { modules, dependencies }
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Graphviz DOT; render with `dot -Tsvg`
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph skelly_jelly {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+        for node in &self.modules {
+            let _ = writeln!(
+                out,
+                "    \"{}\" [label=\"{}\", fillcolor=\"{}\"];",
+                node.module,
+                node.lines().join("\\n"),
+                node.fill_color()
+            );
+        }
+        for (dependency, dependent) in &self.dependencies {
+            let _ = writeln!(out, "    \"{}\" -> \"{}\";", dependency, dependent);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart; paste into any Markdown renderer that supports it
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+        for node in &self.modules {
+            let _ = writeln!(out, "    {}[\"{}\"]", mermaid_id(node.module), node.lines().join("<br/>"));
+        }
+        for (dependency, dependent) in &self.dependencies {
+            let _ = writeln!(out, "    {} --> {}", mermaid_id(*dependency), mermaid_id(*dependent));
+        }
+        for node in &self.modules {
+            let _ = writeln!(out, "    style {} fill:{}", mermaid_id(node.module), node.fill_color());
+        }
+        out
+    }
+}
+
+/// Mermaid ids can't contain dashes
+fn mermaid_id(module: ModuleId) -> String {
+    module.to_string().replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> SystemMap {
+        let node = |module, health| ModuleNode {
+            module,
+            state: "running".to_string(),
+            health,
+            startup_time: Some(Duration::from_millis(120)),
+            messages_published: 40,
+            messages_received: 7,
+        };
+        SystemMap::new(
+            vec![
+                node(ModuleId::Storage, HealthStatus::Unhealthy { reason: "disk full".to_string() }),
+                node(ModuleId::DataCapture, HealthStatus::Healthy),
+            ],
+            vec![(ModuleId::EventBus, ModuleId::Storage), (ModuleId::Storage, ModuleId::DataCapture)],
+        )
+    }
+
+    #[test]
+    fn test_dot_export_annotates_modules() {
+        let dot = map().render(GraphFormat::Dot);
+
+        assert!(dot.starts_with("digraph skelly_jelly {"));
+        assert!(dot.contains(
+            "\"storage\" [label=\"storage\\nrunning · unhealthy\\nstartup 120ms\\n40 out / 7 in\", fillcolor=\"#ffcdd2\"];"
+        ));
+        assert!(dot.contains("\"storage\" -> \"data-capture\";"));
+        assert!(dot.find("\"data-capture\" [").unwrap() < dot.find("\"storage\" [").unwrap());
+    }
+
+    #[test]
+    fn test_mermaid_export_uses_safe_ids() {
+        let mermaid = map().render("mermaid".parse().unwrap());
+
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("    data_capture[\"data-capture<br/>running · healthy<br/>startup 120ms<br/>40 out / 7 in\"]"));
+        assert!(mermaid.contains("    event_bus --> storage"));
+        assert!(mermaid.contains("    style storage fill:#ffcdd2"));
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+}
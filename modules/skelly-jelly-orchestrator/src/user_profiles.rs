@@ -0,0 +1,78 @@
+//! User profile selection
+//!
+//! A user profile keeps one person's data apart from another's on a shared
+//! machine: storage opens a separate database, encryption key and config for
+//! each profile. The orchestrator announces the profile before modules start
+//! and, when it changes at runtime, restarts the modules that keep
+//! per-profile state so they come back up on the new profile.
+
+use crate::error::{OrchestratorError, OrchestratorResult};
+use skelly_jelly_event_bus::{message::ConfigUpdate, BusMessage, MessagePayload, ModuleId};
+
+/// Config key announcing the active user profile, e.g. `{"profile": "work"}`
+pub const USER_PROFILE_KEY: &str = "user_profile";
+
+/// Profile used when none is configured
+pub const DEFAULT_USER_PROFILE: &str = "default";
+
+/// Modules that keep per-profile data and are restarted on a switch
+pub const PROFILE_SCOPED_MODULES: &[ModuleId] = &[ModuleId::Storage];
+
+/// Check that a profile name can be used as a directory name by the modules
+pub fn validate_user_profile(name: &str) -> OrchestratorResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(OrchestratorError::ConfigurationError {
+            module: ModuleId::Orchestrator,
+            reason: format!(
+                "Invalid user profile {:?}: use lowercase letters, digits, '-' and '_'",
+                name
+            ),
+        })
+    }
+}
+
+/// Broadcast telling modules which user profile to open
+pub fn user_profile_announcement(name: &str) -> BusMessage {
+    BusMessage::new(
+        ModuleId::Orchestrator,
+        MessagePayload::ConfigUpdate(ConfigUpdate {
+            config_key: USER_PROFILE_KEY.to_string(),
+            config_value: serde_json::json!({ "profile": name }),
+            target_module: None,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names() {
+        assert!(validate_user_profile(DEFAULT_USER_PROFILE).is_ok());
+        assert!(validate_user_profile("shared-user_2").is_ok());
+        assert!(validate_user_profile("").is_err());
+        assert!(validate_user_profile("Work").is_err());
+        assert!(validate_user_profile("../work").is_err());
+    }
+
+    #[test]
+    fn test_announcement_is_broadcast() {
+        let message = user_profile_announcement("work");
+        match message.payload {
+            MessagePayload::ConfigUpdate(update) => {
+                assert_eq!(update.config_key, USER_PROFILE_KEY);
+                assert_eq!(update.config_value["profile"], "work");
+                assert!(update.target_module.is_none());
+            }
+            other => panic!("expected ConfigUpdate, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Dry-run system validation
+//!
+//! [`OrchestratorTrait::validate`](crate::OrchestratorTrait::validate) checks
+//! what a start would need without starting any module or monitor, and
+//! returns a [`ReadinessReport`] the doctor command and the first-run wizard
+//! can show. The orchestrator validates its own config and every module
+//! config it holds; checks that need other crates (capture permissions,
+//! opening the database) are [`ReadinessProbe`]s the binary registers with
+//! [`OrchestratorImpl::add_readiness_probe`](crate::OrchestratorImpl::add_readiness_probe).
+//!
+//! A [`CheckStatus::Fail`] means the system would not come up; a
+//! [`CheckStatus::Warn`] means it would, with something degraded.
+
+use crate::config_schema::{ConfigSchema, SchemaReport};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use skelly_jelly_event_bus::ModuleId;
+use std::{fmt::Write, io::Read, net::SocketAddr, path::PathBuf};
+
+/// What a check looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckCategory {
+    Permissions,
+    Config,
+    Models,
+    Database,
+    Ports,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub category: CheckCategory,
+    /// What was checked, e.g. `storage config` or `admin API port`
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a failure or warning
+    pub remedy: Option<String>,
+}
+
+impl CheckResult {
+    fn new(category: CheckCategory, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { category, name: name.into(), status, detail: detail.into(), remedy: None }
+    }
+
+    pub fn pass(category: CheckCategory, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(category, name, CheckStatus::Pass, detail)
+    }
+
+    pub fn warn(category: CheckCategory, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(category, name, CheckStatus::Warn, detail)
+    }
+
+    pub fn fail(category: CheckCategory, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(category, name, CheckStatus::Fail, detail)
+    }
+
+    pub fn with_remedy(mut self, remedy: impl Into<String>) -> Self {
+        self.remedy = Some(remedy.into());
+        self
+    }
+}
+
+/// Everything a dry run checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub checks: Vec<CheckResult>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ReadinessReport {
+    pub fn new(checks: Vec<CheckResult>) -> Self {
+        Self { checks, checked_at: Utc::now() }
+    }
+
+    /// Nothing failed; warnings don't stop a start
+    pub fn is_ready(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|check| check.status == CheckStatus::Fail)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|check| check.status == CheckStatus::Warn)
+    }
+
+    pub fn in_category(&self, category: CheckCategory) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(move |check| check.category == category)
+    }
+}
+
+/// A check run as part of [`validate`](crate::OrchestratorTrait::validate)
+///
+/// Probes must not change anything: they may open and close a file, socket
+/// or database, but leave it as they found it.
+#[async_trait]
+pub trait ReadinessProbe: Send + Sync {
+    async fn check(&self) -> Vec<CheckResult>;
+}
+
+/// Result of checking a config blob against its schema
+pub fn config_check(subject: &str, report: &SchemaReport) -> CheckResult {
+    let name = format!("{} config", subject);
+    if !report.is_valid() {
+        let violations: Vec<String> = report.violations.iter().map(|violation| violation.to_string()).collect();
+        return CheckResult::fail(CheckCategory::Config, name, violations.join("; "))
+            .with_remedy(format!("Fix the [{}] section of the config file", subject.replace('-', "_")));
+    }
+    if !report.warnings.is_empty() {
+        return CheckResult::warn(CheckCategory::Config, name, format!("unknown fields: {}", report.warnings.join(", ")));
+    }
+    CheckResult::pass(CheckCategory::Config, name, "matches the schema")
+}
+
+/// Checks module configs that aren't stored yet, e.g. straight from the config file
+#[derive(Debug, Clone, Default)]
+pub struct ConfigProbe {
+    configs: Vec<(ModuleId, serde_json::Value)>,
+}
+
+impl ConfigProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_module(mut self, module_id: ModuleId, config: serde_json::Value) -> Self {
+        self.configs.push((module_id, config));
+        self
+    }
+}
+
+#[async_trait]
+impl ReadinessProbe for ConfigProbe {
+    async fn check(&self) -> Vec<CheckResult> {
+        self.configs
+            .iter()
+            .map(|(module_id, config)| {
+                config_check(&module_id.to_string(), &ConfigSchema::for_module(*module_id).validate(config))
+            })
+            .collect()
+    }
+}
+
+/// Checks that a port a server will listen on is free
+#[derive(Debug, Clone)]
+pub struct PortProbe {
+    name: String,
+    address: SocketAddr,
+}
+
+impl PortProbe {
+    pub fn new(name: impl Into<String>, address: SocketAddr) -> Self {
+        Self { name: name.into(), address }
+    }
+}
+
+#[async_trait]
+impl ReadinessProbe for PortProbe {
+    async fn check(&self) -> Vec<CheckResult> {
+        let name = format!("{} port", self.name);
+        // Bound and dropped straight away; port 0 is always free
+        let result = match tokio::net::TcpListener::bind(self.address).await {
+            Ok(_) => CheckResult::pass(CheckCategory::Ports, name, format!("{} is free", self.address)),
+            Err(e) => CheckResult::fail(CheckCategory::Ports, name, format!("cannot bind {}: {}", self.address, e))
+                .with_remedy(format!(
+                    "Stop whatever listens on port {} or change the {} bind address",
+                    self.address.port(),
+                    self.name
+                )),
+        };
+        vec![result]
+    }
+}
+
+/// A model file a module loads at startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelFile {
+    pub name: String,
+    pub path: PathBuf,
+    /// Expected SHA-256, lowercase hex; not verified if unset
+    pub sha256: Option<String>,
+    /// A missing required file fails the check, a missing optional one warns
+    pub required: bool,
+}
+
+impl ModelFile {
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self { name: name.into(), path: path.into(), sha256: None, required: true }
+    }
+
+    pub fn with_sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into().to_lowercase());
+        self
+    }
+
+    /// The module runs without it, e.g. falling back to rules or a cloud API
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+}
+
+/// Checks that model files exist and match their checksums
+#[derive(Debug, Clone, Default)]
+pub struct ModelFileProbe {
+    files: Vec<ModelFile>,
+}
+
+impl ModelFileProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, file: ModelFile) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    async fn check_file(file: &ModelFile) -> CheckResult {
+        let name = format!("{} model", file.name);
+        if !file.path.exists() {
+            let detail = format!("{} not found", file.path.display());
+            return if file.required {
+                CheckResult::fail(CheckCategory::Models, name, detail)
+                    .with_remedy("Download the model or point the config at it")
+            } else {
+                CheckResult::warn(CheckCategory::Models, name, detail)
+            };
+        }
+
+        let Some(expected) = &file.sha256 else {
+            return CheckResult::pass(CheckCategory::Models, name, format!("{} present", file.path.display()));
+        };
+        let path = file.path.clone();
+        match tokio::task::spawn_blocking(move || sha256_file(&path)).await {
+            Ok(Ok(actual)) if actual == *expected => {
+                CheckResult::pass(CheckCategory::Models, name, format!("{} present, checksum matches", file.path.display()))
+            }
+            Ok(Ok(actual)) => CheckResult::fail(
+                CheckCategory::Models,
+                name,
+                format!("{} checksum is {}, expected {}", file.path.display(), actual, expected),
+            )
+            .with_remedy("Delete the file and download it again"),
+            Ok(Err(e)) => CheckResult::fail(CheckCategory::Models, name, format!("cannot read {}: {}", file.path.display(), e)),
+            Err(e) => CheckResult::fail(CheckCategory::Models, name, format!("checksum task failed: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl ReadinessProbe for ModelFileProbe {
+    async fn check(&self) -> Vec<CheckResult> {
+        let mut results = Vec::with_capacity(self.files.len());
+        for file in &self.files {
+            results.push(Self::check_file(file).await);
+        }
+        results
+    }
+}
+
+fn sha256_file(path: &std::path::Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let mut hex = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    Ok(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_model_files_checked_for_presence_and_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("model.onnx");
+        std::fs::write(&model, b"abc").unwrap();
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let probe = ModelFileProbe::new()
+            .with_file(ModelFile::new("good", &model).with_sha256(abc.to_uppercase()))
+            .with_file(ModelFile::new("corrupt", &model).with_sha256("00".repeat(32)))
+            .with_file(ModelFile::new("missing", dir.path().join("absent.onnx")))
+            .with_file(ModelFile::new("fallback", dir.path().join("absent.gguf")).optional());
+        let report = ReadinessReport::new(probe.check().await);
+
+        let statuses: Vec<CheckStatus> = report.checks.iter().map(|check| check.status).collect();
+        assert_eq!(statuses, vec![CheckStatus::Pass, CheckStatus::Fail, CheckStatus::Fail, CheckStatus::Warn]);
+        assert!(report.checks[1].detail.contains(abc));
+        assert!(report.checks[2].remedy.is_some());
+        assert!(!report.is_ready());
+        assert_eq!(report.warnings().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ports_and_configs_reported() {
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut checks = PortProbe::new("admin API", taken.local_addr().unwrap()).check().await;
+        checks.extend(PortProbe::new("metrics", SocketAddr::from(([127, 0, 0, 1], 0))).check().await);
+        checks.extend(
+            ConfigProbe::new()
+                .with_module(ModuleId::Storage, serde_json::json!({ "database_path": "./data/skelly.db" }))
+                .with_module(ModuleId::Storage, serde_json::json!({ "database_path": "x", "retention_days": 0 }))
+                .check()
+                .await,
+        );
+        let report = ReadinessReport::new(checks);
+
+        let statuses: Vec<CheckStatus> = report.checks.iter().map(|check| check.status).collect();
+        assert_eq!(statuses, vec![CheckStatus::Fail, CheckStatus::Pass, CheckStatus::Pass, CheckStatus::Fail]);
+        assert_eq!(report.in_category(CheckCategory::Ports).count(), 2);
+        assert!(report.checks[3].detail.contains("retention_days"));
+        assert_eq!(report.checks[3].remedy.as_deref(), Some("Fix the [storage] section of the config file"));
+        assert_eq!(serde_json::to_value(&report).unwrap()["checks"][0]["category"], "ports");
+    }
+}
@@ -1,13 +1,29 @@
 //! Comprehensive integration tests for the orchestration system
 
-use skelly_jelly_event_bus::{create_event_bus_with_config, create_event_bus, EventBusConfig, ModuleId};
+use skelly_jelly_event_bus::{
+    create_event_bus_with_config, create_event_bus, BusMessage, DeliveryMode, EventBusConfig, EventBusImpl,
+    EventBusTrait, MessageFilter, MessagePayload, ModuleId, message::ErrorReport,
+};
+use futures::StreamExt;
 use skelly_jelly_orchestrator::{
-    create_orchestrator, OrchestratorConfig, StartupSequencer, EnhancedHealthMonitor,
-    ConfigWatcher, HotReloadConfig, HealthConfig,
+    resource::{ResourceUsage, ThrottleAction},
+    MemorySecretStore, ModuleDescriptor, ModuleState, ResourceLimits, OrchestratorConfig, OrchestratorImpl, OrchestratorResult, OrchestratorTrait, StartupSequencer, EnhancedHealthMonitor,
+    ConfigWatcher, HotReloadConfig, HealthConfig, MaintenanceConfig,
+    SleepWakeConfig,
 };
 use std::{sync::Arc, time::Duration};
 use tokio_test;
 
+/// Build an orchestrator whose secrets come from memory rather than the host keychain
+async fn create_orchestrator(
+    config: OrchestratorConfig,
+    event_bus: Arc<dyn EventBusTrait>,
+) -> OrchestratorResult<Arc<dyn OrchestratorTrait>> {
+    let orchestrator =
+        OrchestratorImpl::with_secret_store(config, event_bus, Arc::new(MemorySecretStore::new())).await?;
+    Ok(Arc::new(orchestrator))
+}
+
 /// Test orchestrator system startup with dependency ordering
 #[tokio::test]
 async fn test_orchestrated_startup_sequence() {
@@ -23,14 +39,22 @@ async fn test_orchestrated_startup_sequence() {
         startup_timeout: Duration::from_secs(30),
         module_start_delay: Duration::from_millis(100),
         parallel_startup: false,
+        headless: false,
         health_check_interval: Duration::from_secs(5),
         health_check_timeout: Duration::from_secs(2),
         unhealthy_threshold: 2,
         auto_recovery: true,
         max_recovery_attempts: 2,
         recovery_backoff: Duration::from_secs(1),
+        restart_buffer_size: 1_000,
+        crash_loop_max_failures: 3,
+        crash_loop_window: Duration::from_secs(300),
         resource_check_interval: Duration::from_secs(5),
         throttle_threshold: 0.9,
+        user_profile: "default".to_string(),
+        privacy_policy: Default::default(),
+        maintenance: MaintenanceConfig::default(),
+        sleep_wake: SleepWakeConfig::default(),
     };
 
     let orchestrator = create_orchestrator(config, event_bus.clone()).await
@@ -54,6 +78,248 @@ async fn test_orchestrated_startup_sequence() {
     println!("✅ Orchestrated startup sequence test completed successfully");
 }
 
+/// Orchestrator on a started bus, consuming its own subscription the way the binary does
+async fn subscribed_orchestrator(config: OrchestratorConfig) -> (Arc<EventBusImpl>, Arc<OrchestratorImpl>) {
+    let event_bus = create_event_bus().expect("Failed to create event bus");
+    event_bus.start().await.expect("Failed to start event bus");
+
+    let orchestrator = Arc::new(
+        OrchestratorImpl::with_secret_store(config, event_bus.clone(), Arc::new(MemorySecretStore::new()))
+            .await
+            .expect("Failed to create orchestrator"),
+    );
+    let (_, messages) = event_bus
+        .subscribe_stream(
+            ModuleId::Orchestrator,
+            orchestrator.subscription_filter(),
+            DeliveryMode::Reliable { timeout: Duration::from_secs(5) },
+        )
+        .expect("Failed to subscribe the orchestrator");
+    let consumer = Arc::clone(&orchestrator);
+    tokio::spawn(async move { consumer.consume(messages).await });
+    (event_bus, orchestrator)
+}
+
+/// Publish each module's `ModuleReady` under its own identity, as the binary does once it is up
+async fn announce(event_bus: &EventBusImpl, modules: &[ModuleId]) {
+    for &module_id in modules {
+        event_bus
+            .publish(BusMessage::new(module_id, MessagePayload::ModuleReady(module_id)))
+            .await
+            .expect("Failed to announce module");
+    }
+}
+
+/// Test that startup completes on the modules' own readiness signals, delivered
+/// through the orchestrator's bus subscription
+#[tokio::test]
+async fn test_startup_gated_on_module_ready_signals() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (event_bus, orchestrator) =
+        subscribed_orchestrator(OrchestratorConfig { parallel_startup: true, ..Default::default() }).await;
+    announce(&event_bus, &[
+        ModuleId::Storage,
+        ModuleId::DataCapture,
+        ModuleId::AnalysisEngine,
+        ModuleId::Gamification,
+        ModuleId::AiIntegration,
+        ModuleId::CuteFigurine,
+    ]).await;
+
+    tokio::time::timeout(Duration::from_secs(30), orchestrator.start_system())
+        .await
+        .expect("Startup should not wait out the readiness timeout")
+        .expect("System startup should succeed");
+    assert!(orchestrator.quarantined_modules().is_empty());
+    assert!(matches!(
+        orchestrator.get_module_state(ModuleId::CuteFigurine).await,
+        Some(ModuleState::Running { .. })
+    ));
+
+    orchestrator.stop_system(Duration::from_secs(5)).await
+        .expect("System shutdown should succeed");
+}
+
+/// Test that a module which never announces itself is not counted as started
+#[tokio::test]
+async fn test_silent_module_is_quarantined_with_its_dependents() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (event_bus, orchestrator) = subscribed_orchestrator(OrchestratorConfig {
+        crash_loop_max_failures: 1,
+        ..Default::default()
+    }).await;
+    orchestrator
+        .register_module(
+            ModuleDescriptor::new(ModuleId::AnalysisEngine, "analysis-engine".to_string())
+                .with_dependencies(vec![ModuleId::EventBus, ModuleId::Storage])
+                .with_timeouts(Duration::from_millis(500), Duration::from_secs(1)),
+        )
+        .await
+        .expect("Failed to register analysis engine");
+    // Everyone but the analysis engine comes up
+    announce(&event_bus, &[
+        ModuleId::Storage,
+        ModuleId::DataCapture,
+        ModuleId::Gamification,
+        ModuleId::AiIntegration,
+        ModuleId::CuteFigurine,
+    ]).await;
+
+    tokio::time::timeout(Duration::from_secs(30), orchestrator.start_system())
+        .await
+        .expect("Startup should give up on the silent module")
+        .expect("Startup should carry on without the silent module");
+
+    let quarantined = orchestrator.quarantined_modules();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].module, ModuleId::AnalysisEngine);
+    let mut held_back = quarantined[0].held_back.clone();
+    held_back.sort_by_key(|module_id| module_id.to_string());
+    assert_eq!(held_back, vec![ModuleId::AiIntegration, ModuleId::CuteFigurine, ModuleId::Gamification]);
+    assert!(matches!(
+        orchestrator.get_module_state(ModuleId::AnalysisEngine).await,
+        Some(ModuleState::Failed { .. })
+    ));
+    assert!(matches!(
+        orchestrator.get_module_state(ModuleId::Storage).await,
+        Some(ModuleState::Running { .. })
+    ));
+
+    orchestrator.stop_system(Duration::from_secs(5)).await
+        .expect("System shutdown should succeed");
+}
+
+/// Test that a module quarantined at runtime holds back its dependents, as at startup
+#[tokio::test]
+async fn test_runtime_quarantine_holds_back_dependents() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (event_bus, orchestrator) = subscribed_orchestrator(OrchestratorConfig {
+        crash_loop_max_failures: 1,
+        auto_recovery: false,
+        ..Default::default()
+    }).await;
+    announce(&event_bus, &[
+        ModuleId::Storage,
+        ModuleId::DataCapture,
+        ModuleId::AnalysisEngine,
+        ModuleId::Gamification,
+        ModuleId::AiIntegration,
+        ModuleId::CuteFigurine,
+    ]).await;
+    tokio::time::timeout(Duration::from_secs(30), orchestrator.start_system())
+        .await
+        .expect("Startup should not wait out the readiness timeout")
+        .expect("System startup should succeed");
+
+    event_bus
+        .publish(BusMessage::new(
+            ModuleId::AnalysisEngine,
+            MessagePayload::Error(ErrorReport {
+                error_id: uuid::Uuid::new_v4(),
+                error_type: "crash".to_string(),
+                message: "model panicked".to_string(),
+                module: ModuleId::AnalysisEngine,
+                timestamp: chrono::Utc::now(),
+                context: None,
+            }),
+        ))
+        .await
+        .expect("Failed to report the crash");
+
+    let quarantined = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            // Dependents are stopped before they are recorded as held back
+            let quarantined = orchestrator.quarantined_modules();
+            if quarantined.first().is_some_and(|record| !record.held_back.is_empty()) {
+                return quarantined;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("The crash should quarantine the analysis engine and hold back its dependents");
+
+    assert_eq!(quarantined[0].module, ModuleId::AnalysisEngine);
+    let mut held_back = quarantined[0].held_back.clone();
+    held_back.sort_by_key(|module_id| module_id.to_string());
+    assert_eq!(held_back, vec![ModuleId::AiIntegration, ModuleId::CuteFigurine, ModuleId::Gamification]);
+    assert!(matches!(
+        orchestrator.get_module_state(ModuleId::Gamification).await,
+        Some(ModuleState::Stopped { .. })
+    ));
+    assert!(matches!(
+        orchestrator.get_module_state(ModuleId::Storage).await,
+        Some(ModuleState::Running { .. })
+    ));
+
+    orchestrator.stop_system(Duration::from_secs(5)).await
+        .expect("System shutdown should succeed");
+}
+
+/// Test that a restart which fails still hands the module's traffic back
+#[tokio::test]
+async fn test_failed_restart_resumes_delivery() {
+    let (event_bus, orchestrator) = subscribed_orchestrator(OrchestratorConfig::default()).await;
+    let (_, mut messages) = event_bus
+        .subscribe_stream(
+            ModuleId::Gamification,
+            MessageFilter::sources(vec![ModuleId::AnalysisEngine]),
+            DeliveryMode::BestEffort,
+        )
+        .expect("Failed to subscribe gamification");
+
+    // Nothing it depends on is running, so it can't come back up
+    let restarted = orchestrator.restart_module(ModuleId::Gamification).await;
+    assert!(restarted.is_err(), "Restart should fail without its dependencies");
+
+    event_bus
+        .publish(BusMessage::new(ModuleId::AnalysisEngine, MessagePayload::ModuleReady(ModuleId::AnalysisEngine)))
+        .await
+        .expect("Failed to publish");
+    let delivered = tokio::time::timeout(Duration::from_secs(2), messages.next())
+        .await
+        .expect("Delivery should not stay paused after a failed restart");
+    assert!(delivered.is_some());
+}
+
+/// Test that a module without a process of its own is still throttled in-process
+/// while the orchestrator's OS-level enforcer is installed
+#[tokio::test]
+async fn test_in_process_module_throttled_alongside_enforcer() {
+    let event_bus = create_event_bus().expect("Failed to create event bus");
+    event_bus.start().await.expect("Failed to start event bus");
+    let orchestrator = OrchestratorImpl::with_secret_store(
+        OrchestratorConfig::default(),
+        event_bus.clone(),
+        Arc::new(MemorySecretStore::new()),
+    )
+    .await
+    .expect("Failed to create orchestrator");
+
+    orchestrator.set_resource_limits(ModuleId::Storage, ResourceLimits::new(20.0, 512)).await;
+    orchestrator
+        .record_resource_usage(ModuleId::Storage, ResourceUsage {
+            cpu_percent: 60.0,
+            memory_mb: 2048,
+            file_handles: 10,
+            threads: 2,
+            battery_impact: 0.5,
+            timestamp: chrono::Utc::now(),
+        })
+        .await
+        .expect("Failed to record usage");
+    orchestrator.enforce_resource_limits().await.expect("Enforcement should succeed");
+
+    assert!(matches!(
+        orchestrator.current_throttle(ModuleId::Storage).await,
+        Some(ThrottleAction::PauseProcessing { .. })
+    ));
+    assert!(orchestrator.current_throttle(ModuleId::DataCapture).await.is_none());
+}
+
 /// Test startup performance metrics and bottleneck detection
 #[tokio::test]
 async fn test_startup_performance_metrics() {
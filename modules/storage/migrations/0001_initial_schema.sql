@@ -0,0 +1,53 @@
+-- Raw events, screenshot metadata and aggregation tables
+
+CREATE TABLE IF NOT EXISTS events (
+    timestamp INTEGER NOT NULL,
+    session_id BLOB NOT NULL,
+    event_type INTEGER NOT NULL,
+    data BLOB NOT NULL,
+    PRIMARY KEY (timestamp, session_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_events_session
+ON events(session_id, timestamp);
+
+CREATE TABLE IF NOT EXISTS screenshot_metadata (
+    screenshot_id BLOB PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    window_title TEXT,
+    app_name TEXT,
+    text_density REAL,
+    ui_element_count INTEGER,
+    dominant_colors TEXT,
+    privacy_masked INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_screenshots_timestamp
+ON screenshot_metadata(timestamp);
+
+CREATE TABLE IF NOT EXISTS event_aggregates_minute (
+    timestamp INTEGER PRIMARY KEY,
+    keystroke_count INTEGER,
+    mouse_clicks INTEGER,
+    window_switches INTEGER,
+    active_time_ms INTEGER,
+    screenshot_count INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS event_aggregates_hour (
+    timestamp INTEGER PRIMARY KEY,
+    keystroke_count INTEGER,
+    mouse_clicks INTEGER,
+    window_switches INTEGER,
+    active_time_ms INTEGER,
+    screenshot_count INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS event_aggregates_day (
+    timestamp INTEGER PRIMARY KEY,
+    keystroke_count INTEGER,
+    mouse_clicks INTEGER,
+    window_switches INTEGER,
+    active_time_ms INTEGER,
+    screenshot_count INTEGER
+);
@@ -0,0 +1,11 @@
+-- Orchestrator performance telemetry
+
+CREATE TABLE IF NOT EXISTS telemetry_samples (
+    timestamp INTEGER NOT NULL,
+    module TEXT,
+    metric TEXT NOT NULL,
+    value REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_telemetry_metric
+ON telemetry_samples(metric, module, timestamp);
@@ -0,0 +1,31 @@
+-- Dashboard aggregates, maintained on ingest
+
+CREATE TABLE IF NOT EXISTS mv_hourly_event_counts (
+    hour_ts INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (hour_ts, event_type)
+) WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS mv_daily_state_distribution (
+    day_ts INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    entries INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day_ts, state)
+) WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS mv_daily_app_focus (
+    day_ts INTEGER NOT NULL,
+    app_name TEXT NOT NULL,
+    focus_ms INTEGER NOT NULL DEFAULT 0,
+    sessions INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day_ts, app_name)
+) WITHOUT ROWID;
+
+-- Last published state, so the next change can credit its duration
+CREATE TABLE IF NOT EXISTS mv_current_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    state TEXT NOT NULL,
+    since INTEGER NOT NULL
+);
@@ -0,0 +1,23 @@
+-- Event bus audit records, kept until they expire
+
+CREATE TABLE IF NOT EXISTS bus_audit (
+    message_id TEXT NOT NULL,
+    message_type TEXT NOT NULL,
+    source TEXT NOT NULL,
+    priority TEXT NOT NULL,
+    correlation_id TEXT,
+    published_at INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    delivered_to TEXT NOT NULL,
+    failed TEXT NOT NULL,
+    detail TEXT,
+    payload TEXT,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bus_audit_published
+ON bus_audit(published_at);
+
+CREATE INDEX IF NOT EXISTS idx_bus_audit_expires
+ON bus_audit(expires_at);
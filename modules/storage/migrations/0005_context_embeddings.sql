@@ -0,0 +1,15 @@
+-- AI context memory: past intervention situations and user notes with their embeddings
+
+CREATE TABLE IF NOT EXISTS context_embeddings (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    text TEXT NOT NULL,
+    response TEXT,
+    accepted INTEGER,
+    embedding BLOB NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    last_used INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_context_embeddings_last_used
+ON context_embeddings(last_used);
@@ -0,0 +1,10 @@
+-- Analysis engine progress: events before the offset are in analyzed windows
+
+CREATE TABLE IF NOT EXISTS analysis_checkpoints (
+    consumer TEXT PRIMARY KEY,
+    offset_ts INTEGER NOT NULL,
+    last_window_id TEXT,
+    last_window_end INTEGER,
+    windows_analyzed INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
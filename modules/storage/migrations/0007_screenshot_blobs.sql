@@ -0,0 +1,19 @@
+-- Screenshot images moved to a content-addressed file store: the database
+-- keeps each screenshot's content hash and a reference count per blob
+
+ALTER TABLE screenshot_metadata ADD COLUMN content_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_screenshots_content_hash
+ON screenshot_metadata(content_hash);
+
+CREATE TABLE IF NOT EXISTS screenshot_blobs (
+    content_hash TEXT PRIMARY KEY,
+    size_bytes INTEGER NOT NULL,
+    ref_count INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    -- When ref_count last dropped to zero; garbage collection waits a grace period after it
+    unreferenced_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_screenshot_blobs_unreferenced
+ON screenshot_blobs(unreferenced_at) WHERE ref_count = 0;
@@ -0,0 +1,13 @@
+-- Sequenced bus messages kept until their consumer acknowledges them, so a gap can be resent
+
+CREATE TABLE IF NOT EXISTS sequenced_messages (
+    producer TEXT NOT NULL,
+    consumer TEXT NOT NULL,
+    sequence INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    stored_at INTEGER NOT NULL,
+    PRIMARY KEY (producer, consumer, sequence)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sequenced_messages_stored
+ON sequenced_messages(stored_at);
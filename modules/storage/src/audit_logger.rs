@@ -162,6 +162,18 @@ pub enum DataSensitivity {
     TopSecret,
 }
 
+impl From<skelly_jelly_privacy_policy::SensitivityLevel> for DataSensitivity {
+    fn from(level: skelly_jelly_privacy_policy::SensitivityLevel) -> Self {
+        use skelly_jelly_privacy_policy::SensitivityLevel;
+        match level {
+            SensitivityLevel::Public => Self::Public,
+            SensitivityLevel::Internal => Self::Internal,
+            SensitivityLevel::Confidential => Self::Confidential,
+            SensitivityLevel::Restricted => Self::Restricted,
+        }
+    }
+}
+
 /// Audit configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditConfig {
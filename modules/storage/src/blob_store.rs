@@ -0,0 +1,386 @@
+//! Content-addressed screenshot blobs
+//!
+//! Screenshot images are kept out of the database, in files under
+//! `screenshot.blob_dir` named by a hash of their contents. Identical
+//! captures, such as an unchanged screen, are stored once. The hash is keyed
+//! with the profile's storage key, so a file name reveals nothing about the
+//! image to someone without the key. Each file holds the image encrypted
+//! with AES-256-GCM under the same key.
+//!
+//! The database keeps the metadata, each screenshot's content hash and a
+//! reference count per blob. A blob whose count drops to zero is kept for
+//! `blob_gc_grace_seconds`, then [`ScreenshotBlobStore::collect_garbage`]
+//! securely deletes it. It also deletes files the database has no record of,
+//! left by a crash between writing a file and recording it.
+//!
+//! Before this store, screenshots were kept one `<id>.bin` file per
+//! screenshot. [`ScreenshotBlobStore::migrate_legacy`] moves such a
+//! directory into the store.
+
+use crate::{
+    database::TimeSeriesDatabase,
+    error::{Result, StorageError},
+    screenshot_manager::{ScreenshotManager, SecureDeletionConfig},
+    types::ScreenshotId,
+};
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+const NONCE_LEN: usize = 12;
+const HASH_LEN: usize = 64;
+
+/// Outcome of a garbage collection run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobGcReport {
+    /// Blobs deleted after their grace period
+    pub blobs_deleted: u64,
+    /// Files deleted that the database had no record of
+    pub orphans_deleted: u64,
+    /// Disk space released
+    pub bytes_freed: u64,
+}
+
+/// Outcome of moving the old one-file-per-screenshot layout into the store
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacyMigrationReport {
+    /// Screenshots moved into the store
+    pub migrated: u64,
+    /// Of those, screenshots whose image was already stored
+    pub deduplicated: u64,
+    /// Files left alone because their name isn't a screenshot id
+    pub skipped: u64,
+}
+
+/// Encrypted, reference-counted screenshot images named by content
+pub struct ScreenshotBlobStore {
+    root: PathBuf,
+    key: [u8; 32],
+    gc_grace: Duration,
+    /// Shared by writers and held exclusively by garbage collection, so a
+    /// blob can't be collected between being written and being referenced
+    gc_lock: RwLock<()>,
+}
+
+impl ScreenshotBlobStore {
+    /// Open the store at `root` with a 32-byte profile key, creating it if needed
+    pub async fn open(root: impl Into<PathBuf>, key: &[u8], gc_grace: std::time::Duration) -> Result<Self> {
+        let key: [u8; 32] = key.try_into().map_err(|_| {
+            StorageError::InvalidState(format!("blob store key is {} bytes, expected 32", key.len()))
+        })?;
+        let root = root.into();
+        tokio::fs::create_dir_all(root.join("tmp")).await?;
+
+        Ok(Self {
+            root,
+            key,
+            gc_grace: Duration::from_std(gc_grace).unwrap_or(Duration::MAX),
+            gc_lock: RwLock::new(()),
+        })
+    }
+
+    /// Directory holding the blobs
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Name of the blob that holds `data`
+    pub fn content_hash(&self, data: &[u8]) -> String {
+        Sha256::new()
+            .chain_update(self.key)
+            .chain_update(data)
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Store screenshot `id`'s image and reference it from the database
+    ///
+    /// Returns the content hash. The image is written only if no screenshot
+    /// with the same contents is stored yet.
+    pub async fn put(
+        &self,
+        database: &TimeSeriesDatabase,
+        id: &ScreenshotId,
+        data: &[u8],
+        timestamp: DateTime<Utc>,
+    ) -> Result<String> {
+        let _writing = self.gc_lock.read().await;
+        let (content_hash, size_bytes, _) = self.write_blob(data).await?;
+        let references = database.link_screenshot_blob(id, &content_hash, size_bytes, timestamp).await?;
+        debug!("Screenshot {} stored as blob {} ({} references)", id, content_hash, references);
+        Ok(content_hash)
+    }
+
+    /// Image of the blob `content_hash`, decrypted and checked
+    pub async fn get(&self, content_hash: &str) -> Result<Vec<u8>> {
+        let sealed = tokio::fs::read(self.blob_path(content_hash)?).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StorageError::NotFound(format!("Screenshot blob {content_hash}")),
+            _ => e.into(),
+        })?;
+        if sealed.len() < NONCE_LEN {
+            return Err(StorageError::ScreenshotStorage(format!("blob {content_hash} is truncated")));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let data = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: content_hash.as_bytes() })
+            .map_err(|_| StorageError::ScreenshotStorage(format!("blob {content_hash} failed to decrypt")))?;
+        if self.content_hash(&data) != content_hash {
+            return Err(StorageError::ScreenshotStorage(format!("blob {content_hash} does not match its hash")));
+        }
+        Ok(data)
+    }
+
+    /// Image of screenshot `id`, if it has one in the store
+    pub async fn get_screenshot(&self, database: &TimeSeriesDatabase, id: &ScreenshotId) -> Result<Option<Vec<u8>>> {
+        match database.screenshot_content_hash(id).await? {
+            Some(content_hash) => self.get(&content_hash).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Drop screenshot `id`'s reference; the blob goes once nothing references it
+    pub async fn release(&self, database: &TimeSeriesDatabase, id: &ScreenshotId) -> Result<Option<String>> {
+        database.unlink_screenshot_blob(id).await
+    }
+
+    /// Delete blobs unreferenced for the grace period, and files with no record
+    pub async fn collect_garbage(&self, database: &TimeSeriesDatabase, now: DateTime<Utc>) -> Result<BlobGcReport> {
+        let _collecting = self.gc_lock.write().await;
+        let mut report = BlobGcReport::default();
+
+        let cutoff = now.checked_sub_signed(self.gc_grace).unwrap_or(DateTime::<Utc>::MIN_UTC);
+        for content_hash in database.unreferenced_screenshot_blobs(cutoff).await? {
+            // Referenced again since the query: keep it
+            if !database.forget_screenshot_blob(&content_hash).await? {
+                continue;
+            }
+            report.bytes_freed += Self::delete_file(&self.blob_path(&content_hash)?).await?;
+            report.blobs_deleted += 1;
+        }
+
+        // No writer is between writing a file and recording it while the lock is held
+        let known = database.screenshot_blob_hashes().await?;
+        for (content_hash, path) in self.stored_blobs().await? {
+            if !known.contains(&content_hash) {
+                report.bytes_freed += Self::delete_file(&path).await?;
+                report.orphans_deleted += 1;
+            }
+        }
+        let mut partial = tokio::fs::read_dir(self.root.join("tmp")).await?;
+        while let Some(entry) = partial.next_entry().await? {
+            report.bytes_freed += Self::delete_file(&entry.path()).await?;
+        }
+
+        if report.blobs_deleted + report.orphans_deleted > 0 {
+            info!(
+                "Screenshot blob GC deleted {} blobs and {} orphans, freeing {} bytes",
+                report.blobs_deleted, report.orphans_deleted, report.bytes_freed
+            );
+        }
+        Ok(report)
+    }
+
+    /// Move `<id>.bin` screenshot files from `legacy_dir` into the store
+    ///
+    /// Each file is stored, referenced from its screenshot's metadata and
+    /// then securely deleted, so an interrupted migration can be rerun.
+    pub async fn migrate_legacy(&self, database: &TimeSeriesDatabase, legacy_dir: &Path) -> Result<LegacyMigrationReport> {
+        let mut report = LegacyMigrationReport::default();
+        let mut entries = match tokio::fs::read_dir(legacy_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("bin") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).filter(|stem| Uuid::parse_str(stem).is_ok()) else {
+                warn!("Leaving {:?} in place: not named by a screenshot id", path);
+                report.skipped += 1;
+                continue;
+            };
+
+            let data = tokio::fs::read(&path).await?;
+            let taken_at = metadata.modified().map_or_else(|_| Utc::now(), DateTime::<Utc>::from);
+            let writing = self.gc_lock.read().await;
+            let (content_hash, size_bytes, written) = self.write_blob(&data).await?;
+            database.link_screenshot_blob(&ScreenshotId::from(id), &content_hash, size_bytes, taken_at).await?;
+            drop(writing);
+
+            Self::delete_file(&path).await?;
+            report.migrated += 1;
+            if !written {
+                report.deduplicated += 1;
+            }
+        }
+
+        info!(
+            "Migrated {} legacy screenshots into the blob store ({} deduplicated, {} skipped)",
+            report.migrated, report.deduplicated, report.skipped
+        );
+        Ok(report)
+    }
+
+    /// Write `data` unless its blob exists; returns (hash, file size, whether it was written)
+    async fn write_blob(&self, data: &[u8]) -> Result<(String, u64, bool)> {
+        let content_hash = self.content_hash(data);
+        let path = self.blob_path(&content_hash)?;
+        if let Ok(existing) = tokio::fs::metadata(&path).await {
+            return Ok((content_hash, existing.len(), false));
+        }
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: data, aad: content_hash.as_bytes() })
+            .map_err(|e| StorageError::ScreenshotStorage(format!("encryption failed: {e}")))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+
+        // Write aside and rename, so a blob file is never seen half written
+        let partial = self.root.join("tmp").join(format!("{}.partial", Uuid::new_v4()));
+        tokio::fs::write(&partial, &sealed).await?;
+        if let Some(shard) = path.parent() {
+            tokio::fs::create_dir_all(shard).await?;
+        }
+        tokio::fs::rename(&partial, &path).await?;
+        Ok((content_hash, sealed.len() as u64, true))
+    }
+
+    /// `<root>/<first two hex digits>/<hash>`
+    fn blob_path(&self, content_hash: &str) -> Result<PathBuf> {
+        if !is_content_hash(content_hash) {
+            return Err(StorageError::ScreenshotStorage(format!("invalid blob hash {content_hash:?}")));
+        }
+        Ok(self.root.join(&content_hash[..2]).join(content_hash))
+    }
+
+    /// Every blob file in the store
+    async fn stored_blobs(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut blobs = Vec::new();
+        let mut shards = tokio::fs::read_dir(&self.root).await?;
+        while let Some(shard) = shards.next_entry().await? {
+            let name = shard.file_name();
+            let is_shard = name.len() == 2 && name.to_str().is_some_and(|name| name.bytes().all(|b| b.is_ascii_hexdigit()));
+            if !is_shard || !shard.file_type().await?.is_dir() {
+                continue;
+            }
+            let mut files = tokio::fs::read_dir(shard.path()).await?;
+            while let Some(file) = files.next_entry().await? {
+                if let Some(content_hash) = file.file_name().to_str().filter(|name| is_content_hash(name)) {
+                    blobs.push((content_hash.to_string(), file.path()));
+                }
+            }
+        }
+        Ok(blobs)
+    }
+
+    /// Securely delete `path`, returning its size
+    async fn delete_file(path: &Path) -> Result<u64> {
+        let size = tokio::fs::metadata(path).await.map_or(0, |metadata| metadata.len());
+        ScreenshotManager::secure_delete_file(path, &SecureDeletionConfig::default()).await?;
+        Ok(size)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.key.into())
+    }
+}
+
+fn is_content_hash(name: &str) -> bool {
+    name.len() == HASH_LEN && name.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::DatabaseConfig, types::ScreenshotMetadata};
+    use tempfile::TempDir;
+
+    async fn setup() -> (ScreenshotBlobStore, TimeSeriesDatabase, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DatabaseConfig { path: temp_dir.path().join("test.db"), ..DatabaseConfig::default() };
+        let database = TimeSeriesDatabase::new(config).await.unwrap();
+        let store = ScreenshotBlobStore::open(temp_dir.path().join("blobs"), &[7u8; 32], std::time::Duration::from_secs(60))
+            .await
+            .unwrap();
+        (store, database, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_identical_images_share_an_encrypted_blob_until_released() {
+        let (store, database, _temp_dir) = setup().await;
+        let image = b"png bytes of an unchanged screen".to_vec();
+        let (first, second) = (ScreenshotId::new(), ScreenshotId::new());
+
+        database.store_screenshot_metadata(&first, &ScreenshotMetadata::default()).await.unwrap();
+        let content_hash = store.put(&database, &first, &image, Utc::now()).await.unwrap();
+        assert_eq!(store.put(&database, &second, &image, Utc::now()).await.unwrap(), content_hash);
+        // Storing the same screenshot again adds no reference
+        store.put(&database, &first, &image, Utc::now()).await.unwrap();
+        assert_eq!(store.stored_blobs().await.unwrap().len(), 1);
+
+        let on_disk = tokio::fs::read(store.blob_path(&content_hash).unwrap()).await.unwrap();
+        assert!(!on_disk.windows(image.len()).any(|window| window == image.as_slice()));
+        assert_eq!(store.get_screenshot(&database, &second).await.unwrap(), Some(image.clone()));
+
+        // Still referenced by the second screenshot
+        store.release(&database, &first).await.unwrap();
+        let later = Utc::now() + Duration::hours(1);
+        assert_eq!(store.collect_garbage(&database, later).await.unwrap().blobs_deleted, 0);
+
+        store.release(&database, &second).await.unwrap();
+        // Within the grace period the blob is kept
+        assert_eq!(store.collect_garbage(&database, Utc::now()).await.unwrap().blobs_deleted, 0);
+        let report = store.collect_garbage(&database, later).await.unwrap();
+        assert_eq!(report.blobs_deleted, 1);
+        assert!(report.bytes_freed > image.len() as u64);
+        assert!(matches!(store.get(&content_hash).await, Err(StorageError::NotFound(_))));
+        assert_eq!(store.get_screenshot(&database, &first).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_files_migrate_and_orphans_are_collected() {
+        let (store, database, temp_dir) = setup().await;
+        let legacy_dir = temp_dir.path().join("screenshots");
+        tokio::fs::create_dir_all(&legacy_dir).await.unwrap();
+
+        let ids: Vec<ScreenshotId> = (0..3).map(|_| ScreenshotId::new()).collect();
+        for (id, image) in ids.iter().zip([&b"one"[..], b"two", b"two"]) {
+            tokio::fs::write(legacy_dir.join(format!("{id}.bin")), image).await.unwrap();
+        }
+        tokio::fs::write(legacy_dir.join("notes.bin"), b"not a screenshot").await.unwrap();
+
+        let report = store.migrate_legacy(&database, &legacy_dir).await.unwrap();
+        assert_eq!(report, LegacyMigrationReport { migrated: 3, deduplicated: 1, skipped: 1 });
+        assert!(!legacy_dir.join(format!("{}.bin", ids[0])).exists());
+        assert_eq!(store.get_screenshot(&database, &ids[2]).await.unwrap(), Some(b"two".to_vec()));
+        // Nothing left to move on a second run
+        assert_eq!(store.migrate_legacy(&database, &legacy_dir).await.unwrap().migrated, 0);
+
+        // A blob written before a crash, never recorded
+        store.write_blob(b"orphan").await.unwrap();
+        let report = store.collect_garbage(&database, Utc::now()).await.unwrap();
+        assert_eq!((report.blobs_deleted, report.orphans_deleted), (0, 1));
+        assert_eq!(store.stored_blobs().await.unwrap().len(), 2);
+        assert!(store.blob_path("../../etc/passwd").is_err());
+    }
+}
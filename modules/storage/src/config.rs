@@ -1,5 +1,6 @@
 //! Configuration for the Storage module
 
+use crate::migrations::MigrationMode;
 use config::{Config, ConfigError, Environment, File};
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
@@ -32,6 +33,14 @@ pub struct StorageConfig {
     /// Development mode settings
     #[serde(default)]
     pub dev_mode: DevModeConfig,
+
+    /// In-memory cache of recent events
+    #[serde(default)]
+    pub hot_cache: HotCacheConfig,
+
+    /// User profile selection
+    #[serde(default)]
+    pub profile: ProfileConfig,
 }
 
 /// Batching configuration
@@ -68,6 +77,14 @@ pub struct ScreenshotConfig {
     /// Temporary directory path
     #[serde(default = "default_temp_dir")]
     pub temp_dir: PathBuf,
+
+    /// Content-addressed store for screenshot images
+    #[serde(default = "default_blob_dir")]
+    pub blob_dir: PathBuf,
+
+    /// How long an unreferenced blob is kept before garbage collection deletes it
+    #[serde(default = "default_blob_gc_grace_seconds")]
+    pub blob_gc_grace_seconds: u64,
 }
 
 /// Database configuration
@@ -77,10 +94,14 @@ pub struct DatabaseConfig {
     #[serde(default = "default_database_path")]
     pub path: PathBuf,
 
-    /// Connection pool size
+    /// Connection pool size for writes
     #[serde(default = "default_pool_size")]
     pub pool_size: u32,
 
+    /// Read-only connections for queries and exports; used only with WAL
+    #[serde(default = "default_read_pool_size")]
+    pub read_pool_size: u32,
+
     /// Write buffer size in MB
     #[serde(default = "default_write_buffer_size_mb")]
     pub write_buffer_size_mb: usize,
@@ -96,6 +117,10 @@ pub struct DatabaseConfig {
     /// Synchronous mode
     #[serde(default = "default_synchronous_mode")]
     pub synchronous_mode: String,
+
+    /// Apply pending schema migrations at startup, or only report them
+    #[serde(default)]
+    pub migration_mode: MigrationMode,
 }
 
 /// Performance configuration
@@ -158,6 +183,34 @@ pub struct DevModeConfig {
     pub debug_endpoints: bool,
 }
 
+/// Hot cache configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotCacheConfig {
+    /// Keep recent events in memory
+    #[serde(default = "default_hot_cache_enabled")]
+    pub enabled: bool,
+
+    /// How many minutes of events to keep
+    #[serde(default = "default_hot_cache_window_minutes")]
+    pub window_minutes: u64,
+
+    /// Maximum events held, regardless of the window
+    #[serde(default = "default_hot_cache_max_events")]
+    pub max_events: usize,
+}
+
+/// User profile configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Active profile
+    #[serde(default = "default_profile_name")]
+    pub name: String,
+
+    /// Data directory; named profiles live in its `profiles/` subdirectory
+    #[serde(default = "default_data_dir")]
+    pub base_dir: PathBuf,
+}
+
 // Default value functions
 fn default_batch_window_seconds() -> u64 { 30 }
 fn default_max_events_per_batch() -> usize { 10_000 }
@@ -177,7 +230,15 @@ fn default_database_path() -> PathBuf {
         .join(".skelly-jelly")
         .join("events.db")
 }
+fn default_blob_dir() -> PathBuf {
+    home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".skelly-jelly")
+        .join("blobs")
+}
+fn default_blob_gc_grace_seconds() -> u64 { 3600 }
 fn default_pool_size() -> u32 { 4 }
+fn default_read_pool_size() -> u32 { 4 }
 fn default_write_buffer_size_mb() -> usize { 10 }
 fn default_compaction_interval_hours() -> u64 { 24 }
 fn default_wal_enabled() -> bool { true }
@@ -191,6 +252,15 @@ fn default_raw_events_days() -> u32 { 7 }
 fn default_hourly_aggregates_days() -> u32 { 30 }
 fn default_daily_summaries_days() -> u32 { 365 }
 fn default_dev_screenshot_count() -> usize { 5 }
+fn default_profile_name() -> String { crate::profiles::DEFAULT_PROFILE.to_string() }
+fn default_data_dir() -> PathBuf {
+    home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".skelly-jelly")
+}
+fn default_hot_cache_enabled() -> bool { true }
+fn default_hot_cache_window_minutes() -> u64 { 10 }
+fn default_hot_cache_max_events() -> usize { 50_000 }
 
 // Default implementations
 impl Default for BatchingConfig {
@@ -210,6 +280,8 @@ impl Default for ScreenshotConfig {
             retention_seconds: default_retention_seconds(),
             memory_cache_size: default_memory_cache_size(),
             temp_dir: default_temp_dir(),
+            blob_dir: default_blob_dir(),
+            blob_gc_grace_seconds: default_blob_gc_grace_seconds(),
         }
     }
 }
@@ -219,10 +291,12 @@ impl Default for DatabaseConfig {
         Self {
             path: default_database_path(),
             pool_size: default_pool_size(),
+            read_pool_size: default_read_pool_size(),
             write_buffer_size_mb: default_write_buffer_size_mb(),
             compaction_interval_hours: default_compaction_interval_hours(),
             wal_enabled: default_wal_enabled(),
             synchronous_mode: default_synchronous_mode(),
+            migration_mode: MigrationMode::default(),
         }
     }
 }
@@ -260,6 +334,25 @@ impl Default for DevModeConfig {
     }
 }
 
+impl Default for HotCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_hot_cache_enabled(),
+            window_minutes: default_hot_cache_window_minutes(),
+            max_events: default_hot_cache_max_events(),
+        }
+    }
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            name: default_profile_name(),
+            base_dir: default_data_dir(),
+        }
+    }
+}
+
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
@@ -269,6 +362,8 @@ impl Default for StorageConfig {
             performance: PerformanceConfig::default(),
             retention: RetentionConfig::default(),
             dev_mode: DevModeConfig::default(),
+            hot_cache: HotCacheConfig::default(),
+            profile: ProfileConfig::default(),
         }
     }
 }
@@ -1,21 +1,34 @@
 //! Database layer for event storage
 
-use crate::{config::DatabaseConfig, error::Result, types::*};
+use crate::{config::DatabaseConfig, error::Result, migrations::Migrator, types::*, views};
 use chrono::{DateTime, Utc};
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
-    ConnectOptions, Row, SqlitePool,
+    ConnectOptions, Row, Sqlite, SqlitePool, Transaction,
 };
+use std::collections::HashSet;
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
-use tracing::info;
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Called before each event write in chaos tests; `Some(reason)` fails the write
+#[cfg(feature = "chaos")]
+pub type WriteFaultHook = std::sync::Arc<dyn Fn() -> Option<String> + Send + Sync>;
+
 /// Time-series optimized database for event storage
+///
+/// Writes go through `pool`. With WAL enabled, queries use a separate pool of
+/// read-only connections, so a long read such as a full export never holds a
+/// lock that ingest has to wait for.
 pub struct TimeSeriesDatabase {
     pool: SqlitePool,
+    read_pool: SqlitePool,
     config: DatabaseConfig,
+    #[cfg(feature = "chaos")]
+    write_fault: Option<WriteFaultHook>,
 }
 
 impl TimeSeriesDatabase {
@@ -60,7 +73,31 @@ impl TimeSeriesDatabase {
 
         info!("Database connection pool established with {} connections", config.pool_size);
 
-        let db = Self { pool, config };
+        // Readers only stay out of the writer's way in WAL mode. Connections
+        // open lazily, after migrations have created the file.
+        let read_pool = if config.wal_enabled {
+            let read_options = SqliteConnectOptions::new()
+                .filename(&db_path)
+                .read_only(true)
+                .busy_timeout(Duration::from_secs(5))
+                .log_statements(tracing::log::LevelFilter::Debug);
+            SqlitePoolOptions::new()
+                .max_connections(config.read_pool_size.max(1))
+                .acquire_timeout(Duration::from_secs(3))
+                .idle_timeout(Duration::from_secs(600))
+                .connect_lazy_with(read_options)
+        } else {
+            warn!("WAL disabled: reads share the write pool and long exports will block ingest");
+            pool.clone()
+        };
+
+        let db = Self {
+            pool,
+            read_pool,
+            config,
+            #[cfg(feature = "chaos")]
+            write_fault: None,
+        };
         
         // Run migrations
         db.migrate().await?;
@@ -68,127 +105,44 @@ impl TimeSeriesDatabase {
         Ok(db)
     }
 
-    /// Run database migrations
+    /// Run pending schema migrations, or report them in dry-run mode
     async fn migrate(&self) -> Result<()> {
-        info!("Running database migrations...");
-        
-        // Create tables
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS events (
-                timestamp INTEGER NOT NULL,
-                session_id BLOB NOT NULL,
-                event_type INTEGER NOT NULL,
-                data BLOB NOT NULL,
-                PRIMARY KEY (timestamp, session_id)
-            ) WITHOUT ROWID;
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create indexes
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_events_session 
-            ON events(session_id, timestamp);
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Screenshot metadata table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS screenshot_metadata (
-                screenshot_id BLOB PRIMARY KEY,
-                timestamp INTEGER NOT NULL,
-                window_title TEXT,
-                app_name TEXT,
-                text_density REAL,
-                ui_element_count INTEGER,
-                dominant_colors TEXT,
-                privacy_masked INTEGER
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_screenshots_timestamp 
-            ON screenshot_metadata(timestamp);
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Aggregation tables
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS event_aggregates_minute (
-                timestamp INTEGER PRIMARY KEY,
-                keystroke_count INTEGER,
-                mouse_clicks INTEGER,
-                window_switches INTEGER,
-                active_time_ms INTEGER,
-                screenshot_count INTEGER
+        let report = Migrator::embedded().run(&self.pool, self.config.migration_mode).await?;
+        if !report.dry_run {
+            info!(
+                "Database schema at version {} ({} migration(s) applied)",
+                report.to_version,
+                report.pending.len()
             );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS event_aggregates_hour (
-                timestamp INTEGER PRIMARY KEY,
-                keystroke_count INTEGER,
-                mouse_clicks INTEGER,
-                window_switches INTEGER,
-                active_time_ms INTEGER,
-                screenshot_count INTEGER
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        }
+        Ok(())
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS event_aggregates_day (
-                timestamp INTEGER PRIMARY KEY,
-                keystroke_count INTEGER,
-                mouse_clicks INTEGER,
-                window_switches INTEGER,
-                active_time_ms INTEGER,
-                screenshot_count INTEGER
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Fail event writes when `hook` says so (`chaos` feature)
+    #[cfg(feature = "chaos")]
+    pub fn set_write_fault_hook(&mut self, hook: WriteFaultHook) {
+        self.write_fault = Some(hook);
+    }
 
-        info!("Database migrations completed");
+    #[cfg(feature = "chaos")]
+    fn check_write_fault(&self) -> Result<()> {
+        if let Some(reason) = self.write_fault.as_ref().and_then(|hook| hook()) {
+            return Err(crate::error::StorageError::Other(reason));
+        }
         Ok(())
     }
 
-    /// Store a raw event
+    /// Store a raw event and update the materialized aggregates
     pub async fn store_event(&self, session_id: &Uuid, event: &RawEvent) -> Result<()> {
+        #[cfg(feature = "chaos")]
+        self.check_write_fault()?;
         let timestamp = event.timestamp().timestamp_millis();
-        let event_type = match event {
-            RawEvent::Keystroke(_) => 1,
-            RawEvent::MouseMove(_) => 2,
-            RawEvent::MouseClick(_) => 3,
-            RawEvent::WindowFocus(_) => 4,
-            RawEvent::Screenshot(_) => 5,
-            RawEvent::ProcessStart(_) => 6,
-            RawEvent::ResourceUsage(_) => 7,
-        };
+        let event_type = event_type_code(event.event_type());
         
         let data = bincode::serialize(event)?;
         
+        let mut tx = self.pool.begin().await?;
+        views::apply_event(&mut tx, session_id, event).await?;
         sqlx::query(
             r#"
             INSERT INTO events (timestamp, session_id, event_type, data)
@@ -199,30 +153,26 @@ impl TimeSeriesDatabase {
         .bind(&session_id.as_bytes()[..])
         .bind(event_type)
         .bind(&data)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
         
+        tx.commit().await?;
         Ok(())
     }
 
     /// Store multiple events in a batch
     pub async fn store_events_batch(&self, session_id: &Uuid, events: &[RawEvent]) -> Result<()> {
+        #[cfg(feature = "chaos")]
+        self.check_write_fault()?;
         let mut tx = self.pool.begin().await?;
         
         for event in events {
             let timestamp = event.timestamp().timestamp_millis();
-            let event_type = match event {
-                RawEvent::Keystroke(_) => 1,
-                RawEvent::MouseMove(_) => 2,
-                RawEvent::MouseClick(_) => 3,
-                RawEvent::WindowFocus(_) => 4,
-                RawEvent::Screenshot(_) => 5,
-                RawEvent::ProcessStart(_) => 6,
-                RawEvent::ResourceUsage(_) => 7,
-            };
+            let event_type = event_type_code(event.event_type());
             
             let data = bincode::serialize(event)?;
             
+            views::apply_event(&mut tx, session_id, event).await?;
             sqlx::query(
                 r#"
                 INSERT INTO events (timestamp, session_id, event_type, data)
@@ -241,7 +191,9 @@ impl TimeSeriesDatabase {
         Ok(())
     }
 
-    /// Store screenshot metadata
+    /// Store screenshot metadata, replacing any stored for `id`
+    ///
+    /// The screenshot's content hash, if already linked, is kept.
     pub async fn store_screenshot_metadata(
         &self,
         id: &ScreenshotId,
@@ -257,6 +209,14 @@ impl TimeSeriesDatabase {
                 text_density, ui_element_count, dominant_colors, privacy_masked
             )
             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(screenshot_id) DO UPDATE SET
+                timestamp = excluded.timestamp,
+                window_title = excluded.window_title,
+                app_name = excluded.app_name,
+                text_density = excluded.text_density,
+                ui_element_count = excluded.ui_element_count,
+                dominant_colors = excluded.dominant_colors,
+                privacy_masked = excluded.privacy_masked
             "#,
         )
         .bind(&id.as_bytes()[..])
@@ -273,6 +233,140 @@ impl TimeSeriesDatabase {
         Ok(())
     }
 
+    /// Point screenshot `id` at the blob `content_hash` and count the reference
+    ///
+    /// Creates a bare metadata row if `id` has none yet. Relinking to the
+    /// same blob changes nothing; linking to another blob releases the old
+    /// one. Returns the blob's reference count.
+    pub async fn link_screenshot_blob(
+        &self,
+        id: &ScreenshotId,
+        content_hash: &str,
+        size_bytes: u64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<u32> {
+        let mut tx = self.pool.begin().await?;
+        let previous = Self::screenshot_hash_in(&mut tx, id).await?;
+
+        if previous.as_deref() != Some(content_hash) {
+            sqlx::query(
+                r#"
+                INSERT INTO screenshot_metadata (screenshot_id, timestamp, content_hash)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(screenshot_id) DO UPDATE SET content_hash = excluded.content_hash
+                "#,
+            )
+            .bind(&id.as_bytes()[..])
+            .bind(timestamp.timestamp_millis())
+            .bind(content_hash)
+            .execute(&mut *tx)
+            .await?;
+
+            if let Some(previous) = &previous {
+                Self::release_blob_in(&mut tx, previous).await?;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO screenshot_blobs (content_hash, size_bytes, ref_count, created_at, unreferenced_at)
+                VALUES (?1, ?2, 1, ?3, NULL)
+                ON CONFLICT(content_hash) DO UPDATE SET ref_count = ref_count + 1, unreferenced_at = NULL
+                "#,
+            )
+            .bind(content_hash)
+            .bind(i64::try_from(size_bytes).unwrap_or(i64::MAX))
+            .bind(Utc::now().timestamp_millis())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let ref_count: i64 = sqlx::query("SELECT ref_count FROM screenshot_blobs WHERE content_hash = ?1")
+            .bind(content_hash)
+            .fetch_one(&mut *tx)
+            .await?
+            .get("ref_count");
+        tx.commit().await?;
+        Ok(u32::try_from(ref_count).unwrap_or_default())
+    }
+
+    /// Drop screenshot `id`'s reference to its blob, returning the blob's hash
+    pub async fn unlink_screenshot_blob(&self, id: &ScreenshotId) -> Result<Option<String>> {
+        let mut tx = self.pool.begin().await?;
+        let content_hash = Self::screenshot_hash_in(&mut tx, id).await?;
+
+        if let Some(content_hash) = &content_hash {
+            sqlx::query("UPDATE screenshot_metadata SET content_hash = NULL WHERE screenshot_id = ?1")
+                .bind(&id.as_bytes()[..])
+                .execute(&mut *tx)
+                .await?;
+            Self::release_blob_in(&mut tx, content_hash).await?;
+        }
+
+        tx.commit().await?;
+        Ok(content_hash)
+    }
+
+    /// Hash of the blob holding screenshot `id`'s image
+    pub async fn screenshot_content_hash(&self, id: &ScreenshotId) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT content_hash FROM screenshot_metadata WHERE screenshot_id = ?1")
+            .bind(&id.as_bytes()[..])
+            .fetch_optional(&self.read_pool)
+            .await?;
+        Ok(row.and_then(|row| row.get("content_hash")))
+    }
+
+    /// Blobs nothing has referenced since before `before`
+    pub async fn unreferenced_screenshot_blobs(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT content_hash FROM screenshot_blobs WHERE ref_count = 0 AND unreferenced_at <= ?1",
+        )
+        .bind(before.timestamp_millis())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(|row| row.get("content_hash")).collect())
+    }
+
+    /// Remove an unreferenced blob's record; `false` if it is referenced again
+    pub async fn forget_screenshot_blob(&self, content_hash: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM screenshot_blobs WHERE content_hash = ?1 AND ref_count = 0")
+            .bind(content_hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every blob the database has a record of
+    pub async fn screenshot_blob_hashes(&self) -> Result<HashSet<String>> {
+        let rows = sqlx::query("SELECT content_hash FROM screenshot_blobs")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("content_hash")).collect())
+    }
+
+    async fn screenshot_hash_in(tx: &mut Transaction<'_, Sqlite>, id: &ScreenshotId) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT content_hash FROM screenshot_metadata WHERE screenshot_id = ?1")
+            .bind(&id.as_bytes()[..])
+            .fetch_optional(&mut **tx)
+            .await?;
+        Ok(row.and_then(|row| row.get("content_hash")))
+    }
+
+    async fn release_blob_in(tx: &mut Transaction<'_, Sqlite>, content_hash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE screenshot_blobs SET
+                ref_count = MAX(ref_count - 1, 0),
+                unreferenced_at = CASE WHEN ref_count <= 1 THEN ?2 ELSE unreferenced_at END
+            WHERE content_hash = ?1
+            "#,
+        )
+        .bind(content_hash)
+        .bind(Utc::now().timestamp_millis())
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     /// Get events for a time range
     pub async fn get_events(
         &self,
@@ -293,7 +387,7 @@ impl TimeSeriesDatabase {
         .bind(&session_id.as_bytes()[..])
         .bind(start_ts)
         .bind(end_ts)
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await?;
         
         let mut events = Vec::with_capacity(rows.len());
@@ -306,6 +400,30 @@ impl TimeSeriesDatabase {
         Ok(events)
     }
 
+    /// Get the most recent event of one type, e.g. `"window_focus"`
+    pub async fn get_latest_event(&self, session_id: &Uuid, event_type: &str) -> Result<Option<RawEvent>> {
+        let row = sqlx::query(
+            r#"
+            SELECT data FROM events
+            WHERE session_id = ?1 AND event_type = ?2
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&session_id.as_bytes()[..])
+        .bind(event_type_code(event_type))
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let data: Vec<u8> = row.get("data");
+                Ok(Some(bincode::deserialize(&data)?))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Delete old events based on retention policy
     pub async fn cleanup_old_events(&self, retention_days: u32) -> Result<u64> {
         let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
@@ -328,6 +446,366 @@ impl TimeSeriesDatabase {
         Ok(deleted)
     }
 
+    /// Store a batch of telemetry samples
+    pub async fn store_telemetry_samples(&self, samples: &[TelemetrySample]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for sample in samples {
+            sqlx::query(
+                r#"
+                INSERT INTO telemetry_samples (timestamp, module, metric, value)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )
+            .bind(sample.timestamp.timestamp_millis())
+            .bind(&sample.module)
+            .bind(&sample.metric)
+            .bind(sample.value)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get telemetry samples for one metric since a point in time, oldest first
+    pub async fn get_telemetry_samples(
+        &self,
+        metric: &str,
+        module: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<TelemetrySample>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT timestamp, module, metric, value FROM telemetry_samples
+            WHERE metric = ?1 AND module IS ?2 AND timestamp >= ?3
+            ORDER BY timestamp
+            "#,
+        )
+        .bind(metric)
+        .bind(module)
+        .bind(since.timestamp_millis())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TelemetrySample {
+                module: row.get("module"),
+                metric: row.get("metric"),
+                value: row.get("value"),
+                timestamp: DateTime::from_timestamp_millis(row.get("timestamp")).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Delete telemetry older than the retention period
+    pub async fn cleanup_old_telemetry(&self, retention_days: u32) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+
+        let result = sqlx::query("DELETE FROM telemetry_samples WHERE timestamp < ?1")
+            .bind(cutoff.timestamp_millis())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Store a batch of event bus audit records
+    pub async fn store_bus_audit(&self, records: &[BusAuditRecord]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for record in records {
+            sqlx::query(
+                r#"
+                INSERT INTO bus_audit (
+                    message_id, message_type, source, priority, correlation_id, published_at,
+                    recorded_at, outcome, delivered_to, failed, detail, payload, expires_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                "#,
+            )
+            .bind(record.message_id.to_string())
+            .bind(&record.message_type)
+            .bind(&record.source)
+            .bind(&record.priority)
+            .bind(record.correlation_id.map(|id| id.to_string()))
+            .bind(record.published_at.timestamp_millis())
+            .bind(record.recorded_at.timestamp_millis())
+            .bind(&record.outcome)
+            .bind(serde_json::to_string(&record.delivered_to)?)
+            .bind(serde_json::to_string(&record.failed)?)
+            .bind(&record.detail)
+            .bind(record.payload.as_ref().map(serde_json::Value::to_string))
+            .bind(record.expires_at.timestamp_millis())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Unexpired bus audit records for messages published in `[start, end)`, oldest first
+    pub async fn get_bus_audit(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        message_type: Option<&str>,
+    ) -> Result<Vec<BusAuditRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM bus_audit
+            WHERE published_at >= ?1 AND published_at < ?2 AND expires_at > ?3
+              AND (?4 IS NULL OR message_type = ?4)
+            ORDER BY published_at, recorded_at
+            "#,
+        )
+        .bind(start.timestamp_millis())
+        .bind(end.timestamp_millis())
+        .bind(Utc::now().timestamp_millis())
+        .bind(message_type)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let parse_id = |id: Option<String>| id.and_then(|id| Uuid::parse_str(&id).ok());
+        let millis = |column: &str, row: &sqlx::sqlite::SqliteRow| {
+            DateTime::from_timestamp_millis(row.get(column)).unwrap_or_default()
+        };
+        rows.iter()
+            .map(|row| {
+                Ok(BusAuditRecord {
+                    message_id: parse_id(row.get("message_id")).unwrap_or_default(),
+                    message_type: row.get("message_type"),
+                    source: row.get("source"),
+                    priority: row.get("priority"),
+                    correlation_id: parse_id(row.get("correlation_id")),
+                    published_at: millis("published_at", row),
+                    recorded_at: millis("recorded_at", row),
+                    outcome: row.get("outcome"),
+                    delivered_to: serde_json::from_str(row.get("delivered_to"))?,
+                    failed: serde_json::from_str(row.get("failed"))?,
+                    detail: row.get("detail"),
+                    payload: row
+                        .get::<Option<String>, _>("payload")
+                        .map(|payload| serde_json::from_str(&payload))
+                        .transpose()?,
+                    expires_at: millis("expires_at", row),
+                })
+            })
+            .collect()
+    }
+
+    /// Delete bus audit records whose expiry has passed
+    pub async fn cleanup_expired_bus_audit(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM bus_audit WHERE expires_at <= ?1")
+            .bind(now.timestamp_millis())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Keep a sequenced message until it is acknowledged; storing it again replaces it
+    pub async fn store_sequenced_message(&self, message: &SequencedMessage) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO sequenced_messages (producer, consumer, sequence, message, stored_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&message.producer)
+        .bind(&message.consumer)
+        .bind(i64::try_from(message.sequence).unwrap_or(i64::MAX))
+        .bind(message.message.to_string())
+        .bind(message.stored_at.timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Kept messages in `range`, lowest sequence number first
+    pub async fn get_sequenced_messages(&self, range: &SequenceRange) -> Result<Vec<SequencedMessage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM sequenced_messages
+            WHERE producer = ?1 AND consumer = ?2 AND sequence >= ?3 AND sequence <= ?4
+            ORDER BY sequence
+            "#,
+        )
+        .bind(&range.producer)
+        .bind(&range.consumer)
+        .bind(i64::try_from(range.from_sequence).unwrap_or(i64::MAX))
+        .bind(i64::try_from(range.to_sequence).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(SequencedMessage {
+                    producer: row.get("producer"),
+                    consumer: row.get("consumer"),
+                    sequence: u64::try_from(row.get::<i64, _>("sequence")).unwrap_or_default(),
+                    message: serde_json::from_str(row.get("message"))?,
+                    stored_at: DateTime::from_timestamp_millis(row.get("stored_at")).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Stop keeping the messages in an acknowledged range
+    pub async fn delete_sequenced_messages(&self, range: &SequenceRange) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM sequenced_messages WHERE producer = ?1 AND consumer = ?2 AND sequence >= ?3 AND sequence <= ?4",
+        )
+        .bind(&range.producer)
+        .bind(&range.consumer)
+        .bind(i64::try_from(range.from_sequence).unwrap_or(i64::MAX))
+        .bind(i64::try_from(range.to_sequence).unwrap_or(i64::MAX))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete kept messages stored before `before`, acknowledged or not
+    pub async fn cleanup_old_sequenced_messages(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM sequenced_messages WHERE stored_at < ?1")
+            .bind(before.timestamp_millis())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Insert or replace remembered AI contexts
+    pub async fn upsert_context_embeddings(&self, entries: &[ContextEmbedding]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for entry in entries {
+            let embedding: Vec<u8> = entry.embedding.iter().flat_map(|value| value.to_le_bytes()).collect();
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO context_embeddings (
+                    id, kind, text, response, accepted, embedding, recorded_at, last_used
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+            )
+            .bind(entry.id.to_string())
+            .bind(&entry.kind)
+            .bind(&entry.text)
+            .bind(&entry.response)
+            .bind(entry.accepted)
+            .bind(embedding)
+            .bind(entry.recorded_at.timestamp_millis())
+            .bind(entry.last_used.timestamp_millis())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The `limit` most recently used AI contexts, least recently used first
+    pub async fn get_context_embeddings(&self, limit: usize) -> Result<Vec<ContextEmbedding>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM (
+                SELECT * FROM context_embeddings ORDER BY last_used DESC LIMIT ?1
+            )
+            ORDER BY last_used
+            "#,
+        )
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let millis = |column: &str, row: &sqlx::sqlite::SqliteRow| {
+            DateTime::from_timestamp_millis(row.get(column)).unwrap_or_default()
+        };
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let id = Uuid::parse_str(row.get("id")).ok()?;
+                let embedding: Vec<u8> = row.get("embedding");
+                Some(ContextEmbedding {
+                    id,
+                    kind: row.get("kind"),
+                    text: row.get("text"),
+                    response: row.get("response"),
+                    accepted: row.get("accepted"),
+                    embedding: embedding
+                        .chunks_exact(4)
+                        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                        .collect(),
+                    recorded_at: millis("recorded_at", row),
+                    last_used: millis("last_used", row),
+                })
+            })
+            .collect())
+    }
+
+    /// Forget AI contexts, e.g. those evicted from the in-memory index
+    pub async fn delete_context_embeddings(&self, ids: &[Uuid]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+
+        for id in ids {
+            deleted += sqlx::query("DELETE FROM context_embeddings WHERE id = ?1")
+                .bind(id.to_string())
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Record a consumer's progress, replacing its previous checkpoint
+    pub async fn save_analysis_checkpoint(&self, checkpoint: &AnalysisCheckpoint) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO analysis_checkpoints (
+                consumer, offset_ts, last_window_id, last_window_end, windows_analyzed, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&checkpoint.consumer)
+        .bind(checkpoint.offset.timestamp_millis())
+        .bind(checkpoint.last_window_id.map(|id| id.to_string()))
+        .bind(checkpoint.last_window_end.map(|end| end.timestamp_millis()))
+        .bind(i64::try_from(checkpoint.windows_analyzed).unwrap_or(i64::MAX))
+        .bind(checkpoint.updated_at.timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// A consumer's last checkpoint, `None` if it never saved one
+    pub async fn get_analysis_checkpoint(&self, consumer: &str) -> Result<Option<AnalysisCheckpoint>> {
+        let row = sqlx::query("SELECT * FROM analysis_checkpoints WHERE consumer = ?1")
+            .bind(consumer)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let millis = |millis: i64| DateTime::from_timestamp_millis(millis).unwrap_or_default();
+        Ok(row.map(|row| AnalysisCheckpoint {
+            consumer: row.get("consumer"),
+            offset: millis(row.get("offset_ts")),
+            last_window_id: row
+                .get::<Option<String>, _>("last_window_id")
+                .and_then(|id| Uuid::parse_str(&id).ok()),
+            last_window_end: row.get::<Option<i64>, _>("last_window_end").map(millis),
+            windows_analyzed: u64::try_from(row.get::<i64, _>("windows_analyzed")).unwrap_or_default(),
+            updated_at: millis(row.get("updated_at")),
+        }))
+    }
+
     /// Get database size in bytes
     pub async fn get_size(&self) -> Result<u64> {
         let row = sqlx::query(
@@ -342,6 +820,18 @@ impl TimeSeriesDatabase {
         Ok(size as u64)
     }
 
+    /// Copy the WAL into the main database file and truncate it
+    ///
+    /// Run before the machine sleeps or shuts down, so a lost WAL can't take
+    /// recent events with it. Pages still needed by an open snapshot stay in
+    /// the WAL.
+    pub async fn checkpoint(&self) -> Result<()> {
+        if self.config.wal_enabled {
+            sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
     /// Vacuum the database
     pub async fn vacuum(&self) -> Result<()> {
         info!("Running database vacuum...");
@@ -350,18 +840,127 @@ impl TimeSeriesDatabase {
         Ok(())
     }
 
+    /// Start a snapshot-consistent read, e.g. for an export
+    ///
+    /// The snapshot sees every transaction committed before this call and
+    /// nothing after, however long it stays open. Ingest carries on meanwhile,
+    /// though the WAL can't be checkpointed past the snapshot until it ends.
+    pub async fn snapshot(&self) -> Result<ReadSnapshot> {
+        let mut tx = self.read_pool.begin().await?;
+        // A deferred transaction takes its snapshot at the first read
+        sqlx::query("SELECT COUNT(*) FROM sqlite_master").fetch_one(&mut *tx).await?;
+        Ok(ReadSnapshot { tx })
+    }
+
     /// Get connection pool for direct access
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
 
+    /// Get the read-only pool; the write pool when WAL is disabled
+    pub fn read_pool(&self) -> &SqlitePool {
+        &self.read_pool
+    }
+
     /// Close the database
     pub async fn close(self) -> Result<()> {
+        self.read_pool.close().await;
         self.pool.close().await;
         Ok(())
     }
 }
 
+/// A read transaction pinned to one point in time
+///
+/// Created by [`TimeSeriesDatabase::snapshot`]. Dropping it ends the
+/// transaction.
+pub struct ReadSnapshot {
+    tx: Transaction<'static, Sqlite>,
+}
+
+impl ReadSnapshot {
+    /// Number of stored events across all sessions
+    pub async fn count_events(&mut self) -> Result<u64> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM events")
+            .fetch_one(&mut *self.tx)
+            .await?;
+        Ok(row.get::<i64, _>("count") as u64)
+    }
+
+    /// Events of one session in a time range
+    pub async fn get_events(
+        &mut self,
+        session_id: &Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<RawEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT data FROM events
+            WHERE session_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+            ORDER BY timestamp
+            "#,
+        )
+        .bind(&session_id.as_bytes()[..])
+        .bind(start.timestamp_millis())
+        .bind(end.timestamp_millis())
+        .fetch_all(&mut *self.tx)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(bincode::deserialize(&row.get::<Vec<u8>, _>("data"))?))
+            .collect()
+    }
+
+    /// Stream every event in `[start, end]`, all sessions, oldest first
+    ///
+    /// Rows are decoded one at a time, so memory stays flat however large the
+    /// export. Returns the number of events passed to `sink`.
+    pub async fn export_events<F>(&mut self, start: DateTime<Utc>, end: DateTime<Utc>, mut sink: F) -> Result<u64>
+    where
+        F: FnMut(RawEvent) -> Result<()>,
+    {
+        let mut rows = sqlx::query(
+            r#"
+            SELECT data FROM events
+            WHERE timestamp >= ?1 AND timestamp <= ?2
+            ORDER BY timestamp
+            "#,
+        )
+        .bind(start.timestamp_millis())
+        .bind(end.timestamp_millis())
+        .fetch(&mut *self.tx);
+
+        let mut exported = 0;
+        while let Some(row) = rows.next().await {
+            sink(bincode::deserialize(&row?.get::<Vec<u8>, _>("data"))?)?;
+            exported += 1;
+        }
+        Ok(exported)
+    }
+
+    /// End the snapshot
+    pub async fn finish(self) -> Result<()> {
+        self.tx.rollback().await?;
+        Ok(())
+    }
+}
+
+/// Code stored in the `event_type` column for a `RawEvent::event_type` name
+fn event_type_code(event_type: &str) -> i32 {
+    match event_type {
+        "keystroke" => 1,
+        "mouse_move" => 2,
+        "mouse_click" => 3,
+        "window_focus" => 4,
+        "screenshot" => 5,
+        "process_start" => 6,
+        "resource_usage" => 7,
+        "mouse_trajectory" => 8,
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -449,4 +1048,268 @@ mod tests {
         
         assert_eq!(stored_events.len(), 2);
     }
+
+    fn keystrokes(start: DateTime<Utc>, count: usize) -> Vec<RawEvent> {
+        (0..count)
+            .map(|i| {
+                RawEvent::Keystroke(KeystrokeEvent {
+                    timestamp: start + chrono::Duration::milliseconds(i as i64),
+                    key_code: 65,
+                    modifiers: KeyModifiers::default(),
+                    inter_key_interval_ms: None,
+                })
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_ignores_later_writes() {
+        let (db, _temp_dir) = create_test_db().await;
+        let session_id = Uuid::new_v4();
+        let start = Utc::now() - chrono::Duration::minutes(1);
+        db.store_events_batch(&session_id, &keystrokes(start, 10)).await.unwrap();
+
+        let mut snapshot = db.snapshot().await.unwrap();
+        db.store_events_batch(&session_id, &keystrokes(start + chrono::Duration::seconds(1), 5)).await.unwrap();
+
+        let end = Utc::now();
+        assert_eq!(snapshot.count_events().await.unwrap(), 10);
+        assert_eq!(snapshot.get_events(&session_id, start, end).await.unwrap().len(), 10);
+        assert_eq!(snapshot.export_events(start, end, |_| Ok(())).await.unwrap(), 10);
+        snapshot.finish().await.unwrap();
+
+        assert_eq!(db.get_events(&session_id, start, end).await.unwrap().len(), 15);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_ingest_during_full_export() {
+        let (db, _temp_dir) = create_test_db().await;
+        let session_id = Uuid::new_v4();
+        let start = Utc::now() - chrono::Duration::hours(1);
+        db.store_events_batch(&session_id, &keystrokes(start, 20_000)).await.unwrap();
+
+        let ingest = |offset: i64| {
+            let db = &db;
+            async move {
+                let began = std::time::Instant::now();
+                for event in keystrokes(start + chrono::Duration::minutes(offset), 200) {
+                    db.store_event(&session_id, &event).await.unwrap();
+                }
+                began.elapsed()
+            }
+        };
+        let baseline = ingest(10).await;
+
+        // Export everything while single-event ingest runs alongside
+        let mut snapshot = db.snapshot().await.unwrap();
+        let export = async {
+            let mut exported = 0;
+            let count = snapshot
+                .export_events(start, Utc::now(), |_| {
+                    exported += 1;
+                    Ok(())
+                })
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await; // still holding the snapshot
+            (count, exported)
+        };
+        let ((count, exported), during) = tokio::join!(export, ingest(20));
+        snapshot.finish().await.unwrap();
+
+        assert_eq!((count, exported), (20_200, 20_200));
+        // A blocked writer would wait out the 5 s busy timeout and then fail
+        assert!(during < baseline * 4 + Duration::from_millis(500), "baseline {baseline:?}, during export {during:?}");
+        let all = db.get_events(&session_id, start, Utc::now()).await.unwrap();
+        assert_eq!(all.len(), 20_400);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_truncates_wal() {
+        let (db, temp_dir) = create_test_db().await;
+        db.store_events_batch(&Uuid::new_v4(), &keystrokes(Utc::now(), 100)).await.unwrap();
+
+        let wal = temp_dir.path().join("test.db-wal");
+        assert!(std::fs::metadata(&wal).unwrap().len() > 0);
+        db.checkpoint().await.unwrap();
+        assert_eq!(std::fs::metadata(&wal).unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_storage() {
+        let (db, _temp_dir) = create_test_db().await;
+        let now = Utc::now();
+
+        let sample = |module: Option<&str>, value| TelemetrySample {
+            module: module.map(str::to_string),
+            metric: "cpu_percent".to_string(),
+            value,
+            timestamp: now,
+        };
+        db.store_telemetry_samples(&[sample(Some("storage"), 1.5), sample(None, 12.0)])
+            .await
+            .unwrap();
+
+        let since = now - chrono::Duration::minutes(1);
+        let module_samples = db.get_telemetry_samples("cpu_percent", Some("storage"), since).await.unwrap();
+        assert_eq!(module_samples.len(), 1);
+        assert_eq!(module_samples[0].value, 1.5);
+
+        let system_samples = db.get_telemetry_samples("cpu_percent", None, since).await.unwrap();
+        assert_eq!(system_samples.len(), 1);
+        assert_eq!(system_samples[0].value, 12.0);
+    }
+
+    #[tokio::test]
+    async fn test_bus_audit_expires() {
+        let (db, _temp_dir) = create_test_db().await;
+        // Stored at millisecond precision
+        let now = DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap();
+
+        let record = |message_type: &str, expires_in: i64| BusAuditRecord {
+            message_id: Uuid::new_v4(),
+            message_type: message_type.to_string(),
+            source: "analysis_engine".to_string(),
+            priority: "Normal".to_string(),
+            correlation_id: None,
+            published_at: now - chrono::Duration::seconds(5),
+            recorded_at: now,
+            outcome: "delivered".to_string(),
+            delivered_to: vec!["ai_integration".to_string()],
+            failed: Vec::new(),
+            detail: None,
+            payload: Some(serde_json::json!({ "state": "distracted" })),
+            expires_at: now + chrono::Duration::seconds(expires_in),
+        };
+        let kept = record("StateChange", 3600);
+        db.store_bus_audit(&[kept.clone(), record("StateChange", -1), record("RawEvent", 3600)])
+            .await
+            .unwrap();
+
+        let range = (now - chrono::Duration::minutes(1), now + chrono::Duration::minutes(1));
+        let audit = db.get_bus_audit(range.0, range.1, Some("StateChange")).await.unwrap();
+        assert_eq!(audit, vec![kept]);
+        assert_eq!(db.get_bus_audit(range.0, range.1, None).await.unwrap().len(), 2);
+
+        assert_eq!(db.cleanup_expired_bus_audit(now).await.unwrap(), 1);
+        assert_eq!(db.cleanup_expired_bus_audit(now + chrono::Duration::hours(2)).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_messages_until_acknowledged() {
+        let (db, _temp_dir) = create_test_db().await;
+        // Stored at millisecond precision
+        let now = DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap();
+
+        let message = |consumer: &str, sequence| SequencedMessage {
+            producer: "data_capture".to_string(),
+            consumer: consumer.to_string(),
+            sequence,
+            message: serde_json::json!({ "sequence": sequence }),
+            stored_at: now,
+        };
+        for sequence in 1..=5 {
+            db.store_sequenced_message(&message("analysis_engine", sequence)).await.unwrap();
+        }
+        db.store_sequenced_message(&message("storage", 2)).await.unwrap();
+
+        let range = |from_sequence, to_sequence| SequenceRange {
+            producer: "data_capture".to_string(),
+            consumer: "analysis_engine".to_string(),
+            from_sequence,
+            to_sequence,
+        };
+        assert_eq!(db.get_sequenced_messages(&range(2, 3)).await.unwrap(), vec![
+            message("analysis_engine", 2),
+            message("analysis_engine", 3),
+        ]);
+
+        assert_eq!(db.delete_sequenced_messages(&range(1, 3)).await.unwrap(), 3);
+        let left = db.get_sequenced_messages(&range(1, 10)).await.unwrap();
+        assert_eq!(left.iter().map(|m| m.sequence).collect::<Vec<_>>(), vec![4, 5]);
+
+        assert_eq!(db.cleanup_old_sequenced_messages(now).await.unwrap(), 0);
+        assert_eq!(db.cleanup_old_sequenced_messages(now + chrono::Duration::seconds(1)).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_context_embeddings_round_trip() {
+        let (db, _temp_dir) = create_test_db().await;
+        // Stored at millisecond precision
+        let now = DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap();
+
+        let entry = |text: &str, used_ago: i64| ContextEmbedding {
+            id: Uuid::new_v4(),
+            kind: "intervention".to_string(),
+            text: text.to_string(),
+            response: Some("Try a short break".to_string()),
+            accepted: None,
+            embedding: vec![0.5, -0.25, 1.0],
+            recorded_at: now - chrono::Duration::hours(1),
+            last_used: now - chrono::Duration::minutes(used_ago),
+        };
+        let oldest = entry("stuck on a borrow error", 30);
+        let mut newest = entry("drifting between tabs", 1);
+        let middle = entry("long meeting notes", 10);
+        db.upsert_context_embeddings(&[oldest.clone(), newest.clone(), middle.clone()]).await.unwrap();
+
+        // The user's verdict arrives later and replaces the row
+        newest.accepted = Some(true);
+        db.upsert_context_embeddings(&[newest.clone()]).await.unwrap();
+
+        assert_eq!(db.get_context_embeddings(10).await.unwrap(), vec![oldest.clone(), middle.clone(), newest.clone()]);
+        assert_eq!(db.get_context_embeddings(2).await.unwrap(), vec![middle, newest]);
+
+        assert_eq!(db.delete_context_embeddings(&[oldest.id, Uuid::new_v4()]).await.unwrap(), 1);
+        assert_eq!(db.get_context_embeddings(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_analysis_checkpoint_is_replaced() {
+        let (db, _temp_dir) = create_test_db().await;
+        let now = DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap();
+        assert_eq!(db.get_analysis_checkpoint("analysis_engine").await.unwrap(), None);
+
+        let mut checkpoint = AnalysisCheckpoint {
+            consumer: "analysis_engine".to_string(),
+            offset: now - chrono::Duration::seconds(25),
+            last_window_id: None,
+            last_window_end: None,
+            windows_analyzed: 0,
+            updated_at: now,
+        };
+        db.save_analysis_checkpoint(&checkpoint).await.unwrap();
+        checkpoint.offset = now;
+        checkpoint.last_window_id = Some(Uuid::new_v4());
+        checkpoint.last_window_end = Some(now - chrono::Duration::seconds(5));
+        checkpoint.windows_analyzed = 1;
+        db.save_analysis_checkpoint(&checkpoint).await.unwrap();
+
+        assert_eq!(db.get_analysis_checkpoint("analysis_engine").await.unwrap(), Some(checkpoint));
+        assert_eq!(db.get_analysis_checkpoint("other").await.unwrap(), None);
+    }
+
+    #[cfg(feature = "chaos")]
+    #[tokio::test]
+    async fn test_write_fault_hook_fails_scripted_writes() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let (mut db, _temp_dir) = create_test_db().await;
+        let writes = std::sync::Arc::new(AtomicU32::new(0));
+        let counter = writes.clone();
+        db.set_write_fault_hook(std::sync::Arc::new(move || {
+            (counter.fetch_add(1, Ordering::SeqCst) == 0).then(|| "injected write failure".to_string())
+        }));
+
+        let session_id = Uuid::new_v4();
+        let event = RawEvent::Keystroke(KeystrokeEvent {
+            timestamp: Utc::now(),
+            key_code: 65,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: Some(100),
+        });
+        assert!(matches!(db.store_event(&session_id, &event).await, Err(crate::error::StorageError::Other(_))));
+        db.store_event(&session_id, &event).await.unwrap();
+        assert_eq!(writes.load(Ordering::SeqCst), 2);
+    }
 }
\ No newline at end of file
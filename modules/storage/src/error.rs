@@ -12,6 +12,23 @@ pub enum StorageError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
+    /// Database was written by a newer build
+    #[error("Database schema version {found} is newer than the supported version {supported}")]
+    SchemaTooNew {
+        /// Schema version found in the database
+        found: u32,
+        /// Newest version this binary can migrate to
+        supported: u32,
+    },
+
+    /// Schema migration could not be applied
+    #[error("Migration error: {0}")]
+    Migration(String),
+
+    /// Profile name that can't be used as a directory name
+    #[error("Invalid profile name: {0:?}")]
+    InvalidProfile(String),
+
     /// Screenshot storage operation failed
     #[error("Screenshot storage error: {0}")]
     ScreenshotStorage(String),
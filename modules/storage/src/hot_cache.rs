@@ -0,0 +1,193 @@
+//! In-memory cache of recent events
+//!
+//! The analysis engine mostly asks for the last few minutes of events. The
+//! hot cache keeps that window in memory so those queries don't touch the
+//! database. Events are written through as they are stored, and a query that
+//! reaches further back than the cache fills it from the database on the way
+//! out. The latest event of each type (focused window, resource usage, ...)
+//! is kept as well, whatever its age.
+
+use crate::{config::HotCacheConfig, types::RawEvent};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+
+/// Bounded cache of the most recent events of one session
+pub(crate) struct HotCache {
+    window: Duration,
+    max_events: usize,
+    state: RwLock<CacheState>,
+}
+
+struct CacheState {
+    /// Oldest first
+    events: VecDeque<RawEvent>,
+    /// Every stored event at or after this time is in `events`
+    complete_from: DateTime<Utc>,
+    latest: HashMap<&'static str, RawEvent>,
+}
+
+impl HotCache {
+    /// Create an empty cache; events stored from `now` on are complete
+    pub(crate) fn new(config: &HotCacheConfig, now: DateTime<Utc>) -> Self {
+        Self {
+            window: Duration::minutes(i64::try_from(config.window_minutes).unwrap_or(i64::MAX / 60_000)),
+            max_events: config.max_events,
+            state: RwLock::new(CacheState {
+                events: VecDeque::new(),
+                complete_from: now,
+                latest: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Add an event that was just stored
+    pub(crate) fn insert(&self, event: &RawEvent, now: DateTime<Utc>) {
+        let mut state = self.state.write();
+        state.update_latest(event);
+
+        let timestamp = event.timestamp();
+        if timestamp >= state.complete_from {
+            let position = state.events.partition_point(|e| e.timestamp() <= timestamp);
+            state.events.insert(position, event.clone());
+        }
+        self.evict(&mut state, now);
+    }
+
+    /// Events in `[start, end]`, or `None` if the cache doesn't cover `start`
+    pub(crate) fn get(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Vec<RawEvent>> {
+        let state = self.state.read();
+        if start < state.complete_from {
+            return None;
+        }
+        Some(
+            state
+                .events
+                .iter()
+                .skip_while(|e| e.timestamp() < start)
+                .take_while(|e| e.timestamp() <= end)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Extend the cache back to `start` with events read from the database
+    ///
+    /// Only takes effect when `[start, end]` reaches the cached range and
+    /// `start` is inside the window.
+    pub(crate) fn fill(&self, start: DateTime<Utc>, end: DateTime<Utc>, events: &[RawEvent], now: DateTime<Utc>) {
+        let mut state = self.state.write();
+        if start >= state.complete_from || end < state.complete_from || start < now - self.window {
+            return;
+        }
+
+        let complete_from = state.complete_from;
+        for event in events.iter().rev().filter(|e| e.timestamp() < complete_from) {
+            state.events.push_front(event.clone());
+        }
+        state.complete_from = start;
+        self.evict(&mut state, now);
+    }
+
+    /// The latest cached event of a type
+    pub(crate) fn latest(&self, event_type: &str) -> Option<RawEvent> {
+        self.state.read().latest.get(event_type).cloned()
+    }
+
+    /// Remember a latest event read from the database, unless a newer one is cached
+    pub(crate) fn fill_latest(&self, event: &RawEvent) {
+        self.state.write().update_latest(event);
+    }
+
+    fn evict(&self, state: &mut CacheState, now: DateTime<Utc>) {
+        state.complete_from = state.complete_from.max(now - self.window);
+        while state.events.len() > self.max_events {
+            if let Some(oldest) = state.events.pop_front() {
+                state.complete_from = state
+                    .complete_from
+                    .max(oldest.timestamp() + Duration::milliseconds(1));
+            }
+        }
+        let complete_from = state.complete_from;
+        while state.events.front().is_some_and(|e| e.timestamp() < complete_from) {
+            state.events.pop_front();
+        }
+    }
+}
+
+impl CacheState {
+    fn update_latest(&mut self, event: &RawEvent) {
+        let newer = self
+            .latest
+            .get(event.event_type())
+            .is_none_or(|current| current.timestamp() <= event.timestamp());
+        if newer {
+            self.latest.insert(event.event_type(), event.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KeyModifiers, KeystrokeEvent};
+
+    fn keystroke(timestamp: DateTime<Utc>, key_code: u32) -> RawEvent {
+        RawEvent::Keystroke(KeystrokeEvent {
+            timestamp,
+            key_code,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: None,
+        })
+    }
+
+    fn config(window_minutes: u64, max_events: usize) -> HotCacheConfig {
+        HotCacheConfig { enabled: true, window_minutes, max_events }
+    }
+
+    #[test]
+    fn test_window_and_size_bounds() {
+        let start = Utc::now();
+        let cache = HotCache::new(&config(5, 3), start);
+
+        for i in 0..4 {
+            let at = start + Duration::seconds(i);
+            cache.insert(&keystroke(at, i as u32), at);
+        }
+        // The oldest event was dropped, so queries reaching it are misses
+        assert!(cache.get(start, start + Duration::minutes(1)).is_none());
+        let recent = cache.get(start + Duration::seconds(1), start + Duration::minutes(1)).unwrap();
+        assert_eq!(recent.len(), 3);
+
+        // Moving past the window empties the cache but keeps the latest event
+        let later = start + Duration::minutes(10);
+        cache.insert(&keystroke(later, 99), later);
+        assert!(cache.get(later - Duration::minutes(6), later).is_none());
+        assert_eq!(cache.get(later - Duration::minutes(5), later).unwrap().len(), 1);
+        assert!(matches!(cache.latest("keystroke"), Some(RawEvent::Keystroke(e)) if e.key_code == 99));
+    }
+
+    #[test]
+    fn test_fill_extends_cached_range() {
+        let now = Utc::now();
+        let cache = HotCache::new(&config(5, 100), now);
+        cache.insert(&keystroke(now, 2), now);
+
+        let start = now - Duration::minutes(2);
+        let from_db = vec![keystroke(start, 0), keystroke(now - Duration::minutes(1), 1), keystroke(now, 2)];
+
+        // A range that doesn't reach the cached events can't be merged
+        cache.fill(start, now - Duration::seconds(30), &from_db[..2], now);
+        assert!(cache.get(start, now).is_none());
+
+        cache.fill(start, now, &from_db, now);
+        let events = cache.get(start, now).unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.windows(2).all(|w| w[0].timestamp() <= w[1].timestamp()));
+
+        // Ranges older than the window are never cached
+        let old = now - Duration::minutes(30);
+        cache.fill(old, now, &[keystroke(old, 7)], now);
+        assert!(cache.get(old, now).is_none());
+    }
+}
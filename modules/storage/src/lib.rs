@@ -9,31 +9,45 @@
 #![allow(clippy::module_name_repetitions)]
 
 pub mod audit_logger;
+pub mod blob_store;
 pub mod config;
 pub mod database;
 pub mod encryption;
 pub mod error;
 pub mod metrics;
+pub mod migrations;
 pub mod types;
+pub mod views;
 
 mod batch_manager;
 mod event_receiver;
+mod hot_cache;
 pub mod privacy_api;
+pub mod profiles;
 mod screenshot_manager;
 mod storage_module;
 
 pub use audit_logger::{PrivacyAuditLogger, AuditConfig, AuditCategory, AuditOutcome, PrivacyLevel, DataSensitivity};
-pub use config::StorageConfig;
+pub use blob_store::{BlobGcReport, LegacyMigrationReport, ScreenshotBlobStore};
+pub use database::{ReadSnapshot, TimeSeriesDatabase};
+#[cfg(feature = "chaos")]
+pub use database::WriteFaultHook;
+pub use config::{HotCacheConfig, ProfileConfig, StorageConfig};
 pub use error::{Result, StorageError};
 pub use metrics::PerformanceMetrics;
+pub use migrations::{MigrationMode, MigrationReport, Migrator};
+pub use profiles::{StorageProfile, DEFAULT_PROFILE};
 pub use storage_module::StorageModule;
 
 // Re-export commonly used types
 pub use types::{
     BusMessage, EventBatch, RawEvent, ScreenshotEvent, ScreenshotId, ScreenshotMetadata,
-    KeystrokeEvent, MouseMoveEvent, MouseClickEvent, WindowFocusEvent, ProcessEvent, ResourceEvent,
+    KeystrokeEvent, MouseMoveEvent, MouseTrajectoryEvent, MouseClickEvent, WindowFocusEvent, ProcessEvent, ResourceEvent,
     ImageFormat, ScreenRegion, KeyModifiers, MouseButton, ClickType, ProcessEventType,
+    StateClassification, TelemetrySample, BusAuditRecord, ContextEmbedding, AnalysisCheckpoint,
+    SequencedMessage, SequenceRange,
 };
+pub use views::{AppFocusTime, HourlyEventCount, StateShare};
 
 /// Module version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -35,6 +35,10 @@ pub struct PerformanceMetrics {
     pub db_write_batch_size: Arc<RwLock<RollingAverage>>,
     pub db_size_bytes: Arc<AtomicU64>,
 
+    // Hot cache metrics
+    pub cache_hits: Arc<AtomicU64>,
+    pub cache_misses: Arc<AtomicU64>,
+
     // Resource usage
     pub memory_usage_bytes: Arc<AtomicU64>,
     pub cpu_usage_percent: Arc<RwLock<ExponentialMovingAverage>>,
@@ -150,6 +154,10 @@ impl PerformanceMetrics {
             db_write_batch_size: Arc::new(RwLock::new(RollingAverage::new(100))),
             db_size_bytes: Arc::new(AtomicU64::new(0)),
 
+            // Hot cache metrics
+            cache_hits: Arc::new(AtomicU64::new(0)),
+            cache_misses: Arc::new(AtomicU64::new(0)),
+
             // Resource usage
             memory_usage_bytes: Arc::new(AtomicU64::new(0)),
             cpu_usage_percent: Arc::new(RwLock::new(ExponentialMovingAverage::new(0.1))),
@@ -253,6 +261,27 @@ impl PerformanceMetrics {
             .set(bytes as f64);
     }
 
+    /// Record a query answered from the hot cache
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a query that had to go to the database
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Share of hot cache lookups that were hits, 0.0 before any lookup
+    pub fn cache_hit_rate(&self) -> f64 {
+        let hits = self.cache_hits.load(Ordering::Relaxed);
+        let total = hits + self.cache_misses.load(Ordering::Relaxed);
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+
     /// Update memory usage
     pub fn update_memory_usage(&self, bytes: u64) {
         self.memory_usage_bytes.store(bytes, Ordering::Relaxed);
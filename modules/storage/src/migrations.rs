@@ -0,0 +1,307 @@
+//! Versioned schema migrations
+//!
+//! Migration scripts live in `migrations/` and are embedded in the binary.
+//! They run in version order when the database is opened, each in its own
+//! transaction, and are recorded in `schema_migrations` with a checksum of
+//! the script. Migrations are forward-only: an applied script must never
+//! change, and a database that has migrations this binary doesn't know about
+//! is refused rather than opened.
+
+use crate::error::{Result, StorageError};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tracing::info;
+
+/// One embedded schema change
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Position in the sequence, starting at 1
+    pub version: u32,
+    /// Short description, taken from the file name
+    pub name: &'static str,
+    /// SQL to run; may hold several statements
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// SHA-256 of the script, hex encoded
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.sql.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+/// Migrations shipped with this binary, in version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../migrations/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "telemetry_samples",
+        sql: include_str!("../migrations/0002_telemetry_samples.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "materialized_views",
+        sql: include_str!("../migrations/0003_materialized_views.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "bus_audit",
+        sql: include_str!("../migrations/0004_bus_audit.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "context_embeddings",
+        sql: include_str!("../migrations/0005_context_embeddings.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "analysis_checkpoints",
+        sql: include_str!("../migrations/0006_analysis_checkpoints.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "screenshot_blobs",
+        sql: include_str!("../migrations/0007_screenshot_blobs.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "sequenced_messages",
+        sql: include_str!("../migrations/0008_sequenced_messages.sql"),
+    },
+];
+
+/// Whether pending migrations are applied or only reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationMode {
+    /// Apply pending migrations
+    #[default]
+    Apply,
+    /// Check the database and list pending migrations without changing anything
+    DryRun,
+}
+
+/// Outcome of a migration run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Schema version before the run
+    pub from_version: u32,
+    /// Schema version after the run; unchanged in a dry run
+    pub to_version: u32,
+    /// Versions applied, or that would be applied in a dry run
+    pub pending: Vec<u32>,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+}
+
+/// Runs embedded migrations against a database
+pub struct Migrator {
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    /// Create a migrator for a custom migration list, in version order
+    pub fn new(migrations: &'static [Migration]) -> Self {
+        Self { migrations }
+    }
+
+    /// Create a migrator for the migrations shipped with this binary
+    pub fn embedded() -> Self {
+        Self::new(MIGRATIONS)
+    }
+
+    /// Newest schema version this binary supports
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.iter().map(|m| m.version).max().unwrap_or(0)
+    }
+
+    /// Bring the database up to the latest version, or report what that would do
+    pub async fn run(&self, pool: &SqlitePool, mode: MigrationMode) -> Result<MigrationReport> {
+        let applied = Self::applied(pool).await?;
+        let from_version = applied.keys().copied().max().unwrap_or(0);
+        let supported = self.latest_version();
+        if from_version > supported {
+            return Err(StorageError::SchemaTooNew { found: from_version, supported });
+        }
+
+        for (version, checksum) in &applied {
+            match self.migrations.iter().find(|m| m.version == *version) {
+                Some(migration) if migration.checksum() == *checksum => {}
+                Some(migration) => {
+                    return Err(StorageError::Migration(format!(
+                        "migration {version} ({}) was changed after it was applied",
+                        migration.name
+                    )));
+                }
+                None => {
+                    return Err(StorageError::Migration(format!(
+                        "database has migration {version}, which this binary doesn't ship"
+                    )));
+                }
+            }
+        }
+
+        let pending: Vec<&Migration> = self
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains_key(&m.version))
+            .collect();
+        if let Some(skipped) = pending.iter().find(|m| m.version < from_version) {
+            return Err(StorageError::Migration(format!(
+                "migration {} ({}) is older than the applied schema version {from_version}",
+                skipped.version, skipped.name
+            )));
+        }
+
+        let mut report = MigrationReport {
+            from_version,
+            to_version: from_version,
+            pending: pending.iter().map(|m| m.version).collect(),
+            dry_run: mode == MigrationMode::DryRun,
+        };
+        if report.dry_run {
+            info!(
+                "Dry run: schema at version {}, {} migration(s) pending: {:?}",
+                from_version,
+                pending.len(),
+                report.pending
+            );
+            return Ok(report);
+        }
+
+        if !pending.is_empty() {
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS schema_migrations (
+                    version INTEGER PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    applied_at INTEGER NOT NULL
+                );
+                "#,
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        for migration in pending {
+            info!("Applying migration {} ({})", migration.version, migration.name);
+            let mut tx = pool.begin().await?;
+            sqlx::query(migration.sql).execute(&mut *tx).await?;
+            sqlx::query(
+                r#"
+                INSERT INTO schema_migrations (version, name, checksum, applied_at)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .bind(Utc::now().timestamp_millis())
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            report.to_version = migration.version;
+        }
+
+        Ok(report)
+    }
+
+    /// Applied versions and their checksums; empty if nothing was ever migrated
+    async fn applied(pool: &SqlitePool) -> Result<HashMap<u32, String>> {
+        let exists = sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'")
+            .fetch_optional(pool)
+            .await?
+            .is_some();
+        if !exists {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query("SELECT version, checksum FROM schema_migrations")
+            .fetch_all(pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get::<u32, _>("version"), row.get::<String, _>("checksum")))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn has_table(pool: &SqlitePool, name: &str) -> bool {
+        sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_then_apply() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::embedded();
+
+        let dry = migrator.run(&pool, MigrationMode::DryRun).await.unwrap();
+        assert_eq!(dry.pending, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(dry.to_version, 0);
+        assert!(!has_table(&pool, "events").await);
+        assert!(!has_table(&pool, "schema_migrations").await);
+
+        let applied = migrator.run(&pool, MigrationMode::Apply).await.unwrap();
+        assert_eq!((applied.from_version, applied.to_version), (0, 8));
+        assert!(has_table(&pool, "events").await);
+        assert!(has_table(&pool, "telemetry_samples").await);
+        assert!(has_table(&pool, "mv_hourly_event_counts").await);
+        assert!(has_table(&pool, "bus_audit").await);
+        assert!(has_table(&pool, "context_embeddings").await);
+        assert!(has_table(&pool, "screenshot_blobs").await);
+        assert!(has_table(&pool, "sequenced_messages").await);
+
+        // Nothing left to do on the next start
+        let again = migrator.run(&pool, MigrationMode::Apply).await.unwrap();
+        assert!(again.pending.is_empty());
+        assert_eq!(again.from_version, 8);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_newer_or_changed_schema() {
+        static CHANGED: &[Migration] = &[Migration {
+            version: 1,
+            name: "initial_schema",
+            sql: "CREATE TABLE IF NOT EXISTS events (timestamp INTEGER);",
+        }];
+
+        let pool = memory_pool().await;
+        Migrator::embedded().run(&pool, MigrationMode::Apply).await.unwrap();
+
+        let changed = Migrator::new(CHANGED).run(&pool, MigrationMode::DryRun).await;
+        assert!(matches!(changed, Err(StorageError::SchemaTooNew { found: 7, supported: 1 })));
+
+        sqlx::query("DELETE FROM schema_migrations WHERE version >= 2").execute(&pool).await.unwrap();
+        let changed = Migrator::new(CHANGED).run(&pool, MigrationMode::Apply).await;
+        assert!(matches!(changed, Err(StorageError::Migration(_))));
+    }
+}
@@ -2,6 +2,9 @@
 //! 
 //! Provides endpoints for data export, deletion, and audit log access
 //! with complete user control over their personal information.
+//!
+//! Also re-exports the shared privacy policy, so consumers of storage detect,
+//! mask and classify sensitive data with the same rules as every other module.
 
 use crate::{
     audit_logger::{
@@ -21,6 +24,11 @@ use std::{
 use tokio::fs;
 use uuid::Uuid;
 
+pub use skelly_jelly_privacy_policy::{
+    Entity, EntityDetector, EntityKind, EntityRule, MaskingStrategy, PrivacyPolicy, PrivacyPolicyConfig,
+    SensitivityLevel, PRIVACY_POLICY_KEY,
+};
+
 /// Privacy API service for user data control
 pub struct PrivacyApiService {
     screenshot_manager: ScreenshotManager,
@@ -0,0 +1,229 @@
+//! User profiles
+//!
+//! Each user profile, say the laptop's owner and a second person sharing the
+//! machine, keeps its own database, screenshot directory, encryption key and
+//! config file. Named profiles live under `<base_dir>/profiles/<name>/`; the
+//! default profile keeps the configured paths so existing installs find their
+//! data where it always was. Nothing is shared between profiles, so switching
+//! profile means reopening storage.
+
+use crate::{
+    config::StorageConfig,
+    error::{Result, StorageError},
+};
+use config::{Config, File};
+use rand::RngCore;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Profile used when none is selected
+pub const DEFAULT_PROFILE: &str = "default";
+
+const KEY_LEN: usize = 32;
+
+/// Where one user profile keeps its data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageProfile {
+    name: String,
+    root: PathBuf,
+}
+
+impl StorageProfile {
+    /// Look up a profile under `base_dir`; names are lowercase letters, digits, `-` and `_`
+    pub fn new(base_dir: impl AsRef<Path>, name: &str) -> Result<Self> {
+        validate_name(name)?;
+        let base_dir = base_dir.as_ref();
+        let root = if name == DEFAULT_PROFILE {
+            base_dir.to_path_buf()
+        } else {
+            base_dir.join("profiles").join(name)
+        };
+        Ok(Self { name: name.to_string(), root })
+    }
+
+    /// Profile name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this is the default profile
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Directory holding the profile's data
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Event database of a named profile
+    pub fn database_path(&self) -> PathBuf {
+        self.root.join("events.db")
+    }
+
+    /// Screenshot spill directory of a named profile
+    pub fn temp_dir(&self) -> PathBuf {
+        self.root.join("tmp")
+    }
+
+    /// Screenshot blob store of a named profile
+    pub fn blob_dir(&self) -> PathBuf {
+        self.root.join("blobs")
+    }
+
+    /// Settings that apply only to this profile
+    pub fn config_path(&self) -> PathBuf {
+        self.root.join("storage.toml")
+    }
+
+    /// The profile's storage encryption key
+    pub fn key_path(&self) -> PathBuf {
+        self.root.join("keys").join("storage.key")
+    }
+
+    /// Storage settings for this profile
+    ///
+    /// A named profile gets `base` overlaid with its own `storage.toml`, and
+    /// its database and screenshot paths always point into the profile
+    /// directory. The default profile uses `base` unchanged.
+    pub fn configure(&self, base: &StorageConfig) -> Result<StorageConfig> {
+        let mut config = base.clone();
+        config.profile.name.clone_from(&self.name);
+        if self.is_default() {
+            return Ok(config);
+        }
+
+        fs::create_dir_all(&self.root)?;
+        let overlay = self.config_path();
+        if overlay.exists() {
+            config = Config::builder()
+                .add_source(Config::try_from(&config)?)
+                .add_source(File::from(overlay))
+                .build()?
+                .try_deserialize()?;
+            config.profile.name.clone_from(&self.name);
+        }
+        config.database.path = self.database_path();
+        config.screenshot.temp_dir = self.temp_dir();
+        config.screenshot.blob_dir = self.blob_dir();
+        Ok(config)
+    }
+
+    /// Read the profile's encryption key, creating it on first use
+    pub fn load_or_create_key(&self) -> Result<Vec<u8>> {
+        let path = self.key_path();
+        if path.exists() {
+            let key = fs::read(&path)?;
+            if key.len() != KEY_LEN {
+                return Err(StorageError::InvalidState(format!(
+                    "key for profile '{}' is {} bytes, expected {KEY_LEN}",
+                    self.name,
+                    key.len()
+                )));
+            }
+            return Ok(key);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut key = vec![0u8; KEY_LEN];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(&path)?.write_all(&key)?;
+        Ok(key)
+    }
+}
+
+/// Profiles that exist under `base_dir`, default first
+pub fn list_profiles(base_dir: impl AsRef<Path>) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let profiles_dir = base_dir.as_ref().join("profiles");
+    if profiles_dir.is_dir() {
+        for entry in fs::read_dir(profiles_dir)? {
+            let entry = entry?;
+            if let Some(name) = entry.file_name().to_str() {
+                if entry.file_type()?.is_dir() && name != DEFAULT_PROFILE && validate_name(name).is_ok() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(names)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidProfile(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_profiles_are_isolated() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = StorageConfig::default();
+
+        let work = StorageProfile::new(temp_dir.path(), "work").unwrap();
+        let shared = StorageProfile::new(temp_dir.path(), "shared-user").unwrap();
+        let work_config = work.configure(&base).unwrap();
+        let shared_config = shared.configure(&base).unwrap();
+        assert_ne!(work_config.database.path, shared_config.database.path);
+        assert_ne!(work_config.screenshot.temp_dir, shared_config.screenshot.temp_dir);
+        assert!(work_config.screenshot.blob_dir.starts_with(work.root()));
+        assert!(work_config.database.path.starts_with(work.root()));
+
+        // Keys differ per profile and survive a reload
+        let work_key = work.load_or_create_key().unwrap();
+        assert_ne!(work_key, shared.load_or_create_key().unwrap());
+        assert_eq!(work_key, work.load_or_create_key().unwrap());
+
+        // The default profile keeps the configured paths
+        let default = StorageProfile::new(temp_dir.path(), DEFAULT_PROFILE).unwrap();
+        assert_eq!(default.configure(&base).unwrap().database.path, base.database.path);
+
+        assert!(StorageProfile::new(temp_dir.path(), "../escape").is_err());
+        assert!(StorageProfile::new(temp_dir.path(), "").is_err());
+        assert_eq!(list_profiles(temp_dir.path()).unwrap(), vec!["default", "shared-user", "work"]);
+    }
+
+    #[test]
+    fn test_profile_config_overlay() {
+        let temp_dir = TempDir::new().unwrap();
+        let profile = StorageProfile::new(temp_dir.path(), "work").unwrap();
+        fs::create_dir_all(profile.root()).unwrap();
+        fs::write(
+            profile.config_path(),
+            "[batching]\nwindow_seconds = 60\n\n[database]\npath = \"/tmp/elsewhere.db\"\n",
+        )
+        .unwrap();
+
+        let config = profile.configure(&StorageConfig::default()).unwrap();
+        assert_eq!(config.batching.window_seconds, 60);
+        assert_eq!(config.screenshot.memory_threshold_mb, 5);
+        // A profile can't point its database outside its own directory
+        assert_eq!(config.database.path, profile.database_path());
+        assert_eq!(config.profile.name, "work");
+    }
+}
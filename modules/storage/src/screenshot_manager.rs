@@ -55,7 +55,7 @@ pub struct ScreenshotManager {
 
 /// Configuration for secure deletion
 #[derive(Debug, Clone)]
-struct SecureDeletionConfig {
+pub(crate) struct SecureDeletionConfig {
     /// Number of overwrite passes (default: 3 passes)
     overwrite_passes: u32,
     /// Patterns for overwriting data
@@ -334,7 +334,7 @@ impl ScreenshotManager {
     }
     
     /// Securely delete a file with multiple overwrite passes
-    async fn secure_delete_file(file_path: &Path, config: &SecureDeletionConfig) -> Result<()> {
+    pub(crate) async fn secure_delete_file(file_path: &Path, config: &SecureDeletionConfig) -> Result<()> {
         if !file_path.exists() {
             return Ok(()); // Already deleted
         }
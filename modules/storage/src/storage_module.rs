@@ -1,26 +1,47 @@
 //! Main storage module implementation
 
 use crate::{
+    blob_store::{LegacyMigrationReport, ScreenshotBlobStore},
     config::StorageConfig,
     database::TimeSeriesDatabase,
     error::{Result, StorageError},
+    hot_cache::HotCache,
     metrics::PerformanceMetrics,
+    profiles::StorageProfile,
     types::*,
 };
+use chrono::{DateTime, Utc};
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, OnceCell};
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// How long an unacknowledged sequenced message is kept for retransmission
+const SEQUENCED_MESSAGE_RETENTION: chrono::Duration = chrono::Duration::hours(1);
+
 /// Main storage module that coordinates all storage operations
 pub struct StorageModule {
+    /// Configuration as given, before profile paths were applied
+    base_config: StorageConfig,
     config: StorageConfig,
+    profile: StorageProfile,
     database: Arc<TimeSeriesDatabase>,
     metrics: Arc<PerformanceMetrics>,
+    hot_cache: Option<HotCache>,
+    /// Screenshot image store, opened on first use
+    screenshots: Arc<OnceCell<ScreenshotBlobStore>>,
+    /// Inbound messages; the event bus bridge forwards into a clone of this
+    event_sender: mpsc::Sender<BusMessage>,
     event_receiver: mpsc::Receiver<BusMessage>,
+    /// Kept messages going back out for a `RetransmitRequest`
+    retransmit_sender: mpsc::Sender<SequencedMessage>,
+    retransmit_receiver: Option<mpsc::Receiver<SequencedMessage>>,
     batch_sender: mpsc::Sender<BusMessage>,
     session_id: Uuid,
     shutdown_signal: Arc<Mutex<bool>>,
+    background_tasks: Vec<JoinHandle<()>>,
 }
 
 impl StorageModule {
@@ -28,31 +49,49 @@ impl StorageModule {
     pub async fn new(config: StorageConfig) -> Result<Self> {
         info!("Initializing Storage Module v{}", crate::VERSION);
 
+        // Resolve the user profile's paths and settings
+        let profile = StorageProfile::new(&config.profile.base_dir, &config.profile.name)?;
+        let base_config = config;
+        let config = profile.configure(&base_config)?;
+
         // Create database
         let database = Arc::new(TimeSeriesDatabase::new(config.database.clone()).await?);
 
         // Create metrics
         let metrics = Arc::new(PerformanceMetrics::new());
 
-        // Create channels (these would normally connect to the Event Bus)
+        // Inbound messages arrive through `sender()`
         let (event_sender, event_receiver) = mpsc::channel(config.performance.channel_capacity);
+        let (retransmit_sender, retransmit_receiver) = mpsc::channel(config.performance.channel_capacity);
         let (batch_sender, batch_receiver) = mpsc::channel(100);
 
-        // For now, drop the receivers we don't use
-        drop(event_sender);
+        // For now, drop the receiver we don't use
         drop(batch_receiver);
 
+        let hot_cache = config
+            .hot_cache
+            .enabled
+            .then(|| HotCache::new(&config.hot_cache, Utc::now()));
+
         let session_id = Uuid::new_v4();
-        info!("Storage Module initialized with session {}", session_id);
+        info!("Storage Module initialized with session {} for profile {}", session_id, profile.name());
 
         Ok(Self {
+            base_config,
             config,
+            profile,
             database,
             metrics,
+            hot_cache,
+            screenshots: Arc::new(OnceCell::new()),
+            event_sender,
             event_receiver,
+            retransmit_sender,
+            retransmit_receiver: Some(retransmit_receiver),
             batch_sender,
             session_id,
             shutdown_signal: Arc::new(Mutex::new(false)),
+            background_tasks: Vec::new(),
         })
     }
 
@@ -61,8 +100,7 @@ impl StorageModule {
         info!("Storage Module starting...");
 
         // Spawn background tasks
-        let metrics_handle = self.spawn_metrics_collector();
-        let cleanup_handle = self.spawn_cleanup_task();
+        self.spawn_background_tasks();
 
         // Main event processing loop
         loop {
@@ -87,9 +125,10 @@ impl StorageModule {
             }
         }
 
-        // Wait for background tasks
-        metrics_handle.abort();
-        cleanup_handle.abort();
+        // Stop background tasks
+        for task in self.background_tasks.drain(..) {
+            task.abort();
+        }
 
         info!("Storage Module stopped");
         Ok(())
@@ -101,6 +140,31 @@ impl StorageModule {
             BusMessage::RawEvent(event) => {
                 self.handle_raw_event(event).await?;
             }
+            BusMessage::StateChange(state) => {
+                self.database.record_state(&state).await?;
+            }
+            BusMessage::TelemetryBatch(samples) => {
+                self.database.store_telemetry_samples(&samples).await?;
+            }
+            BusMessage::BusAuditBatch(records) => {
+                self.database.store_bus_audit(&records).await?;
+            }
+            BusMessage::Sequenced(message) => {
+                self.database.store_sequenced_message(&message).await?;
+            }
+            BusMessage::DeliveryAck(range) => {
+                self.database.delete_sequenced_messages(&range).await?;
+            }
+            BusMessage::RetransmitRequest(range) => {
+                self.retransmit(&range).await?;
+            }
+            BusMessage::ProfileSwitch(name) => {
+                self.switch_profile(&name).await?;
+            }
+            BusMessage::Checkpoint(reason) => {
+                info!("Checkpointing database: {}", reason);
+                self.database.checkpoint().await?;
+            }
             BusMessage::Shutdown(reason) => {
                 info!("Shutdown requested: {}", reason);
                 *self.shutdown_signal.lock().await = true;
@@ -113,6 +177,22 @@ impl StorageModule {
         Ok(())
     }
 
+    /// Send the kept messages in `range` back out; ones already acknowledged are gone
+    async fn retransmit(&self, range: &SequenceRange) -> Result<()> {
+        let messages = self.database.get_sequenced_messages(range).await?;
+        info!(
+            "Retransmitting {} of {}..={} from {} to {}",
+            messages.len(), range.from_sequence, range.to_sequence, range.producer, range.consumer
+        );
+        for message in messages {
+            if let Err(e) = self.retransmit_sender.try_send(message) {
+                warn!("Retransmission of {} to {} dropped: {}", range.producer, range.consumer, e);
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// Handle a raw event
     async fn handle_raw_event(&self, event: RawEvent) -> Result<()> {
         let start = std::time::Instant::now();
@@ -123,6 +203,9 @@ impl StorageModule {
 
         // Store in database
         self.database.store_event(&self.session_id, &event).await?;
+        if let Some(cache) = &self.hot_cache {
+            cache.insert(&event, Utc::now());
+        }
 
         // Record processing time
         self.metrics.record_event_latency(event_type, start.elapsed());
@@ -130,6 +213,136 @@ impl StorageModule {
         Ok(())
     }
 
+    /// Events of this session in `[start, end]`, oldest first
+    ///
+    /// Served from the hot cache when it covers `start`; otherwise read from
+    /// the database, and the result extends the cache if it is recent enough.
+    pub async fn recent_events(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<RawEvent>> {
+        let Some(cache) = &self.hot_cache else {
+            return self.database.get_events(&self.session_id, start, end).await;
+        };
+
+        if let Some(events) = cache.get(start, end) {
+            self.metrics.record_cache_hit();
+            return Ok(events);
+        }
+
+        self.metrics.record_cache_miss();
+        let events = self.database.get_events(&self.session_id, start, end).await?;
+        cache.fill(start, end, &events, Utc::now());
+        Ok(events)
+    }
+
+    /// The most recent event of one type in this session, e.g. `"window_focus"`
+    pub async fn latest_event(&self, event_type: &str) -> Result<Option<RawEvent>> {
+        let Some(cache) = &self.hot_cache else {
+            return self.database.get_latest_event(&self.session_id, event_type).await;
+        };
+
+        if let Some(event) = cache.latest(event_type) {
+            self.metrics.record_cache_hit();
+            return Ok(Some(event));
+        }
+
+        self.metrics.record_cache_miss();
+        let event = self.database.get_latest_event(&self.session_id, event_type).await?;
+        if let Some(event) = &event {
+            cache.fill_latest(event);
+        }
+        Ok(event)
+    }
+
+    /// Reopen storage for another user profile
+    ///
+    /// The new profile's database is opened first, so a failure leaves the
+    /// current profile in place. Events stored afterwards go to the new
+    /// profile under a new session, and the hot cache starts empty.
+    pub async fn switch_profile(&mut self, name: &str) -> Result<()> {
+        if name == self.profile.name() {
+            return Ok(());
+        }
+
+        let mut requested = self.base_config.clone();
+        requested.profile.name = name.to_string();
+        let profile = StorageProfile::new(&requested.profile.base_dir, name)?;
+        let config = profile.configure(&requested)?;
+        let database = Arc::new(TimeSeriesDatabase::new(config.database.clone()).await?);
+
+        info!("Switching storage from profile {} to {}", self.profile.name(), name);
+        let was_running = !self.background_tasks.is_empty();
+        for task in self.background_tasks.drain(..) {
+            task.abort();
+        }
+        self.database.pool().close().await;
+
+        self.hot_cache = config
+            .hot_cache
+            .enabled
+            .then(|| HotCache::new(&config.hot_cache, Utc::now()));
+        self.database = database;
+        self.screenshots = Arc::new(OnceCell::new());
+        self.config = config;
+        self.profile = profile;
+        self.session_id = Uuid::new_v4();
+
+        if was_running {
+            self.spawn_background_tasks();
+        }
+        Ok(())
+    }
+
+    /// The profile's screenshot blob store, opened with its storage key
+    pub async fn screenshot_store(&self) -> Result<&ScreenshotBlobStore> {
+        self.screenshots
+            .get_or_try_init(|| async {
+                let key = self.profile.load_or_create_key()?;
+                ScreenshotBlobStore::open(
+                    &self.config.screenshot.blob_dir,
+                    &key,
+                    std::time::Duration::from_secs(self.config.screenshot.blob_gc_grace_seconds),
+                )
+                .await
+            })
+            .await
+    }
+
+    /// Keep a screenshot: metadata in the database, image in the blob store
+    ///
+    /// Returns the image's content hash.
+    pub async fn store_screenshot(&self, screenshot: &ScreenshotEvent) -> Result<String> {
+        self.database
+            .store_screenshot_metadata(&screenshot.screenshot_id, &screenshot.metadata)
+            .await?;
+        self.screenshot_store()
+            .await?
+            .put(&self.database, &screenshot.screenshot_id, &screenshot.data, screenshot.timestamp)
+            .await
+    }
+
+    /// Move `<id>.bin` screenshot files from `legacy_dir` into the blob store
+    pub async fn migrate_legacy_screenshots(&self, legacy_dir: &Path) -> Result<LegacyMigrationReport> {
+        self.screenshot_store().await?.migrate_legacy(&self.database, legacy_dir).await
+    }
+
+    /// Channel for messages to handle while `run` is going
+    pub fn sender(&self) -> mpsc::Sender<BusMessage> {
+        self.event_sender.clone()
+    }
+
+    /// Messages resent for `RetransmitRequest`s, for the caller to publish; only handed out once
+    pub fn take_retransmissions(&mut self) -> Option<mpsc::Receiver<SequencedMessage>> {
+        self.retransmit_receiver.take()
+    }
+
+    /// The active user profile
+    pub fn profile(&self) -> &StorageProfile {
+        &self.profile
+    }
+
+    fn spawn_background_tasks(&mut self) {
+        self.background_tasks = vec![self.spawn_metrics_collector(), self.spawn_cleanup_task()];
+    }
+
     /// Spawn metrics collection task
     fn spawn_metrics_collector(&self) -> tokio::task::JoinHandle<()> {
         let metrics = Arc::clone(&self.metrics);
@@ -152,10 +365,11 @@ impl StorageModule {
                 
                 // Log current metrics
                 info!(
-                    "Metrics: {} events/sec, {:.1} MB memory, {:.1}% CPU",
+                    "Metrics: {} events/sec, {:.1} MB memory, {:.1}% CPU, {:.0}% cache hits",
                     metrics.events_per_second() as u64,
                     metrics.memory_usage_mb(),
-                    metrics.avg_cpu_usage()
+                    metrics.avg_cpu_usage(),
+                    metrics.cache_hit_rate() * 100.0
                 );
             }
         })
@@ -164,7 +378,10 @@ impl StorageModule {
     /// Spawn cleanup task for old data
     fn spawn_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
         let database = Arc::clone(&self.database);
+        let screenshots = Arc::clone(&self.screenshots);
         let retention_days = self.config.retention.raw_events_days;
+        let telemetry_retention_days = self.config.retention.hourly_aggregates_days;
+        let daily_retention_days = self.config.retention.daily_summaries_days;
         
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(24 * 60 * 60));
@@ -183,6 +400,35 @@ impl StorageModule {
                     }
                 }
                 
+                if let Err(e) = database.cleanup_old_telemetry(telemetry_retention_days).await {
+                    error!("Failed to cleanup old telemetry: {}", e);
+                }
+
+                if let Err(e) = database
+                    .cleanup_old_aggregates(telemetry_retention_days, daily_retention_days)
+                    .await
+                {
+                    error!("Failed to cleanup old aggregates: {}", e);
+                }
+
+                match database.cleanup_old_sequenced_messages(Utc::now() - SEQUENCED_MESSAGE_RETENTION).await {
+                    Ok(deleted) if deleted > 0 => warn!("Removed {} sequenced messages that were never acknowledged", deleted),
+                    Ok(_) => {}
+                    Err(e) => error!("Failed to cleanup sequenced messages: {}", e),
+                }
+
+                match database.cleanup_expired_bus_audit(Utc::now()).await {
+                    Ok(deleted) if deleted > 0 => info!("Removed {} expired bus audit records", deleted),
+                    Ok(_) => {}
+                    Err(e) => error!("Failed to cleanup bus audit records: {}", e),
+                }
+
+                if let Some(store) = screenshots.get() {
+                    if let Err(e) = store.collect_garbage(&database, Utc::now()).await {
+                        error!("Failed to collect screenshot blobs: {}", e);
+                    }
+                }
+
                 // Vacuum database
                 if let Err(e) = database.vacuum().await {
                     error!("Failed to vacuum database: {}", e);
@@ -195,6 +441,9 @@ impl StorageModule {
     pub async fn shutdown(&mut self) -> Result<()> {
         info!("Storage Module shutting down...");
         *self.shutdown_signal.lock().await = true;
+
+        // Credit the current state up to now so downtime isn't counted on restart
+        self.database.end_state(Utc::now()).await?;
         
         // Close database
         if let Ok(db) = Arc::try_unwrap(self.database.clone()) {
@@ -210,7 +459,7 @@ impl StorageModule {
     }
 
     /// Get database handle
-    pub fn database(&self) -> &TimeSeriesDatabase {
+    pub fn database(&self) -> &Arc<TimeSeriesDatabase> {
         &self.database
     }
 }
@@ -225,6 +474,8 @@ mod tests {
         let mut config = StorageConfig::default();
         config.database.path = temp_dir.path().join("test.db");
         config.database.pool_size = 1;
+        config.profile.base_dir = temp_dir.path().to_path_buf();
+        config.screenshot.blob_dir = temp_dir.path().join("blobs");
         
         let module = StorageModule::new(config).await.unwrap();
         (module, temp_dir)
@@ -235,6 +486,95 @@ mod tests {
         let (_module, _temp_dir) = create_test_module().await;
     }
 
+    #[tokio::test]
+    async fn test_recent_events_read_through() {
+        let (module, _temp_dir) = create_test_module().await;
+        let now = Utc::now();
+
+        // Stored before the cache covers it, so the first query goes to the database
+        let earlier = RawEvent::Keystroke(KeystrokeEvent {
+            timestamp: now - chrono::Duration::minutes(2),
+            key_code: 65,
+            modifiers: KeyModifiers::default(),
+            inter_key_interval_ms: None,
+        });
+        module.database.store_event(&module.session_id, &earlier).await.unwrap();
+        let focus = RawEvent::WindowFocus(WindowFocusEvent {
+            timestamp: Utc::now(),
+            window_title: "editor".to_string(),
+            app_name: "code".to_string(),
+            process_id: 1,
+            duration_ms: None,
+        });
+        module.handle_raw_event(focus).await.unwrap();
+
+        let start = now - chrono::Duration::minutes(5);
+        let end = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(module.recent_events(start, end).await.unwrap().len(), 2);
+        assert_eq!(module.recent_events(start, end).await.unwrap().len(), 2);
+        assert!(module.latest_event("window_focus").await.unwrap().is_some());
+
+        assert_eq!(module.metrics().cache_misses.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert!((module.metrics().cache_hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_switch_profile() {
+        let (mut module, _temp_dir) = create_test_module().await;
+        let event = |key_code| {
+            RawEvent::Keystroke(KeystrokeEvent {
+                timestamp: Utc::now(),
+                key_code,
+                modifiers: KeyModifiers::default(),
+                inter_key_interval_ms: None,
+            })
+        };
+        let everything = |module: &StorageModule| {
+            let session_id = module.session_id;
+            let database = Arc::clone(&module.database);
+            async move {
+                let start = Utc::now() - chrono::Duration::hours(1);
+                database.get_events(&session_id, start, Utc::now()).await.unwrap().len()
+            }
+        };
+
+        module.handle_raw_event(event(1)).await.unwrap();
+        let default_session = module.session_id;
+
+        module.handle_message(BusMessage::ProfileSwitch("guest".to_string())).await.unwrap();
+        assert_eq!(module.profile().name(), "guest");
+        assert!(module.config.database.path.starts_with(module.profile().root()));
+        assert_eq!(everything(&module).await, 0);
+        module.handle_raw_event(event(2)).await.unwrap();
+        assert_eq!(module.recent_events(Utc::now() - chrono::Duration::minutes(1), Utc::now()).await.unwrap().len(), 1);
+
+        assert!(module.switch_profile("Not Valid").await.is_err());
+        assert_eq!(module.profile().name(), "guest");
+
+        module.switch_profile(crate::DEFAULT_PROFILE).await.unwrap();
+        module.session_id = default_session;
+        assert_eq!(everything(&module).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_screenshots_go_to_the_profile_blob_store() {
+        let (module, temp_dir) = create_test_module().await;
+        let screenshot = ScreenshotEvent {
+            timestamp: Utc::now(),
+            screenshot_id: ScreenshotId::new(),
+            data: vec![9; 64],
+            metadata: ScreenshotMetadata::default(),
+        };
+
+        let content_hash = module.store_screenshot(&screenshot).await.unwrap();
+        let store = module.screenshot_store().await.unwrap();
+        assert_eq!(store.root(), module.config.screenshot.blob_dir);
+        assert_eq!(store.get(&content_hash).await.unwrap(), screenshot.data);
+        // Storing it again only refreshes the metadata
+        assert_eq!(module.store_screenshot(&screenshot).await.unwrap(), content_hash);
+        assert!(temp_dir.path().join("keys").join("storage.key").exists());
+    }
+
     #[tokio::test]
     async fn test_shutdown() {
         let (mut module, _temp_dir) = create_test_module().await;
@@ -66,6 +66,7 @@ pub struct ScreenRegion {
 pub enum RawEvent {
     Keystroke(KeystrokeEvent),
     MouseMove(MouseMoveEvent),
+    MouseTrajectory(MouseTrajectoryEvent),
     MouseClick(MouseClickEvent),
     WindowFocus(WindowFocusEvent),
     Screenshot(ScreenshotEvent),
@@ -100,6 +101,29 @@ pub struct MouseMoveEvent {
     pub velocity: f32, // pixels per second
 }
 
+/// Summary of the mouse moves in one short window, sent instead of the raw points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MouseTrajectoryEvent {
+    /// Time of the first move in the window
+    pub timestamp: DateTime<Utc>,
+    /// First to last move
+    pub duration_ms: u32,
+    pub sample_count: u32,
+    pub start_x: i32,
+    pub start_y: i32,
+    pub end_x: i32,
+    pub end_y: i32,
+    /// Path length, including the step from the previous window's last point
+    pub distance_px: f32,
+    pub mean_velocity: f32,
+    pub max_velocity: f32,
+    pub velocity_std: f32,
+    /// Turns sharper than 45 degrees
+    pub direction_changes: u32,
+    /// Time within the window spent in pauses between moves
+    pub idle_ms: u32,
+}
+
 /// Mouse click event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MouseClickEvent {
@@ -243,16 +267,126 @@ pub enum BusMessage {
     StateChange(StateClassification),
     InterventionRequest(InterventionRequest),
     AnimationCommand(AnimationCommand),
+    TelemetryBatch(Vec<TelemetrySample>),
+    ProfileSwitch(String),
+    /// Flush the WAL to the database file, e.g. before system sleep; carries the reason
+    Checkpoint(String),
+    BusAuditBatch(Vec<BusAuditRecord>),
+    /// Keep a sequenced message until its consumer acknowledges it
+    Sequenced(SequencedMessage),
+    /// The consumer has these; stop keeping them
+    DeliveryAck(SequenceRange),
+    /// Resend kept messages the consumer never acknowledged
+    RetransmitRequest(SequenceRange),
     Shutdown(String),
 }
 
+/// One aggregated orchestrator telemetry value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    /// Module the value belongs to; `None` for system-wide metrics
+    pub module: Option<String>,
+    /// Metric name, e.g. `latency_p95_ms`
+    pub metric: String,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A published state change from the analysis engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateClassification {
+    /// State name, e.g. `flow` or `distracted`
+    pub state: String,
+    /// Classifier confidence, 0.0 to 1.0
+    pub confidence: f32,
+    /// When the state began
+    pub timestamp: DateTime<Utc>,
+}
+
+/// One message recorded by an event bus audit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusAuditRecord {
+    pub message_id: Uuid,
+    /// Message type, e.g. `StateChange`
+    pub message_type: String,
+    /// Publishing module
+    pub source: String,
+    pub priority: String,
+    pub correlation_id: Option<Uuid>,
+    /// When the publisher created the message
+    pub published_at: DateTime<Utc>,
+    /// When the bus knew what happened to it
+    pub recorded_at: DateTime<Utc>,
+    /// `delivered`, `no_subscribers`, `direct` or `rejected`
+    pub outcome: String,
+    /// Modules the message reached
+    pub delivered_to: Vec<String>,
+    /// Subscribers the message did not reach
+    pub failed: Vec<String>,
+    /// Rejection reason or other note on the outcome
+    pub detail: Option<String>,
+    /// The payload as JSON, if the audit kept payloads
+    pub payload: Option<serde_json::Value>,
+    /// When the record may be deleted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A sequenced event bus message, kept for retransmission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequencedMessage {
+    /// Publishing module
+    pub producer: String,
+    /// Module the sequence number was stamped for
+    pub consumer: String,
+    pub sequence: u64,
+    /// The message as the event bus serializes it
+    pub message: serde_json::Value,
+    pub stored_at: DateTime<Utc>,
+}
+
+/// An inclusive range of sequence numbers on one producer → consumer stream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceRange {
+    pub producer: String,
+    pub consumer: String,
+    pub from_sequence: u64,
+    pub to_sequence: u64,
+}
+
+/// A past situation or user note remembered by the AI for context retrieval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextEmbedding {
+    pub id: Uuid,
+    /// `intervention` or `note`
+    pub kind: String,
+    /// The situation or note as embedded
+    pub text: String,
+    /// What the companion said, for interventions
+    pub response: Option<String>,
+    /// Whether the user acted on the response, once known
+    pub accepted: Option<bool>,
+    pub embedding: Vec<f32>,
+    pub recorded_at: DateTime<Utc>,
+    /// When the entry was last stored or retrieved; the least recently used go first
+    pub last_used: DateTime<Utc>,
+}
+
+/// How far a consumer, e.g. the analysis engine, has processed the event stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisCheckpoint {
+    pub consumer: String,
+    /// Every event before this is in an analyzed window; replay starts here
+    pub offset: DateTime<Utc>,
+    pub last_window_id: Option<Uuid>,
+    pub last_window_end: Option<DateTime<Utc>>,
+    pub windows_analyzed: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
 // Placeholder types for other modules
 #[derive(Debug, Clone)]
 pub struct AnalysisWindow;
 
-#[derive(Debug, Clone)]
-pub struct StateClassification;
-
 #[derive(Debug, Clone)]
 pub struct InterventionRequest;
 
@@ -271,6 +405,7 @@ impl RawEvent {
         match self {
             Self::Keystroke(e) => e.timestamp,
             Self::MouseMove(e) => e.timestamp,
+            Self::MouseTrajectory(e) => e.timestamp,
             Self::MouseClick(e) => e.timestamp,
             Self::WindowFocus(e) => e.timestamp,
             Self::Screenshot(e) => e.timestamp,
@@ -284,6 +419,7 @@ impl RawEvent {
         match self {
             Self::Keystroke(_) => "keystroke",
             Self::MouseMove(_) => "mouse_move",
+            Self::MouseTrajectory(_) => "mouse_trajectory",
             Self::MouseClick(_) => "mouse_click",
             Self::WindowFocus(_) => "window_focus",
             Self::Screenshot(_) => "screenshot",
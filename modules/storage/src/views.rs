@@ -0,0 +1,424 @@
+//! Materialized aggregates for dashboard queries
+//!
+//! Three summaries are kept up to date as data arrives, so dashboards and
+//! reports read a few rows instead of decoding the raw event table:
+//!
+//! - event counts per hour and event type, updated with every stored event
+//! - time per analysis state per day, updated on each `StateChange`
+//! - focus time per application per day, updated on each window switch
+//!
+//! Days and hours are UTC buckets. Spans that cross midnight are split
+//! between the days they cover.
+
+use crate::{
+    database::TimeSeriesDatabase,
+    error::Result,
+    types::{RawEvent, StateClassification},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+use uuid::Uuid;
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Events of one type stored during one hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlyEventCount {
+    /// Start of the hour
+    pub hour: DateTime<Utc>,
+    /// `RawEvent::event_type` name, e.g. `keystroke`
+    pub event_type: String,
+    /// Events stored
+    pub count: u64,
+}
+
+/// Time spent in one state during one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateShare {
+    /// Start of the day
+    pub day: DateTime<Utc>,
+    /// State name as published
+    pub state: String,
+    /// Time spent in the state
+    pub duration_ms: u64,
+    /// Times the state was entered that day
+    pub entries: u64,
+    /// Fraction of the day's classified time, 0.0 to 1.0
+    pub share: f64,
+}
+
+/// Time one application had focus during one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppFocusTime {
+    /// Start of the day
+    pub day: DateTime<Utc>,
+    /// Application name from the focus event
+    pub app_name: String,
+    /// Time with focus
+    pub focus_ms: u64,
+    /// Times the application was switched to that day
+    pub sessions: u64,
+}
+
+/// Update the aggregates for an event about to be stored
+///
+/// Must run before the event itself is inserted, in the same transaction,
+/// so the previous window focus can still be looked up.
+pub(crate) async fn apply_event(conn: &mut SqliteConnection, session_id: &Uuid, event: &RawEvent) -> Result<()> {
+    let timestamp = event.timestamp().timestamp_millis();
+    sqlx::query(
+        r#"
+        INSERT INTO mv_hourly_event_counts (hour_ts, event_type, count) VALUES (?1, ?2, 1)
+        ON CONFLICT (hour_ts, event_type) DO UPDATE SET count = count + 1
+        "#,
+    )
+    .bind(floor(timestamp, HOUR_MS))
+    .bind(event.event_type())
+    .execute(&mut *conn)
+    .await?;
+
+    let RawEvent::WindowFocus(focus) = event else {
+        return Ok(());
+    };
+
+    let previous = sqlx::query(
+        r#"
+        SELECT data FROM events
+        WHERE session_id = ?1 AND event_type = 4 AND timestamp <= ?2
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&session_id.as_bytes()[..])
+    .bind(timestamp)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(row) = previous {
+        if let RawEvent::WindowFocus(previous) = bincode::deserialize(&row.get::<Vec<u8>, _>("data"))? {
+            // Prefer the capture layer's measurement; fall back to the gap between switches
+            let start = previous.timestamp.timestamp_millis();
+            let end = focus.duration_ms.map_or(timestamp, |ms| start + i64::from(ms));
+            add_app_focus(conn, &previous.app_name, start, end, 0).await?;
+        }
+    }
+    add_app_focus(conn, &focus.app_name, timestamp, timestamp, 1).await
+}
+
+impl TimeSeriesDatabase {
+    /// Record a published state change, crediting the previous state with the time since it began
+    pub async fn record_state(&self, state: &StateClassification) -> Result<()> {
+        let timestamp = state.timestamp.timestamp_millis();
+        let mut tx = self.pool().begin().await?;
+
+        close_current_state(&mut tx, timestamp).await?;
+        add_state_time(&mut tx, &state.state, timestamp, timestamp, 1).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO mv_current_state (id, state, since) VALUES (1, ?1, ?2)
+            ON CONFLICT (id) DO UPDATE SET state = excluded.state, since = excluded.since
+            "#,
+        )
+        .bind(&state.state)
+        .bind(timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Credit the current state up to `at` and stop tracking it, e.g. on shutdown
+    pub async fn end_state(&self, at: DateTime<Utc>) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+        close_current_state(&mut tx, at.timestamp_millis()).await?;
+        sqlx::query("DELETE FROM mv_current_state").execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Event counts per hour and type for the hours overlapping `[start, end]`
+    pub async fn hourly_event_counts(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<HourlyEventCount>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT hour_ts, event_type, count FROM mv_hourly_event_counts
+            WHERE hour_ts >= ?1 AND hour_ts <= ?2
+            ORDER BY hour_ts, event_type
+            "#,
+        )
+        .bind(floor(start.timestamp_millis(), HOUR_MS))
+        .bind(end.timestamp_millis())
+        .fetch_all(self.read_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| HourlyEventCount {
+                hour: from_millis(row.get("hour_ts")),
+                event_type: row.get("event_type"),
+                count: row.get::<i64, _>("count") as u64,
+            })
+            .collect())
+    }
+
+    /// Time per state for the days overlapping `[start, end]`, longest first within each day
+    pub async fn daily_state_distribution(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<StateShare>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day_ts, state, duration_ms, entries,
+                   SUM(duration_ms) OVER (PARTITION BY day_ts) AS day_total
+            FROM mv_daily_state_distribution
+            WHERE day_ts >= ?1 AND day_ts <= ?2
+            ORDER BY day_ts, duration_ms DESC, state
+            "#,
+        )
+        .bind(floor(start.timestamp_millis(), DAY_MS))
+        .bind(end.timestamp_millis())
+        .fetch_all(self.read_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let duration_ms: i64 = row.get("duration_ms");
+                let day_total: i64 = row.get("day_total");
+                StateShare {
+                    day: from_millis(row.get("day_ts")),
+                    state: row.get("state"),
+                    duration_ms: duration_ms as u64,
+                    entries: row.get::<i64, _>("entries") as u64,
+                    share: if day_total > 0 { duration_ms as f64 / day_total as f64 } else { 0.0 },
+                }
+            })
+            .collect())
+    }
+
+    /// Focus time per application for the days overlapping `[start, end]`, longest first within each day
+    pub async fn app_focus_time(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<AppFocusTime>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day_ts, app_name, focus_ms, sessions FROM mv_daily_app_focus
+            WHERE day_ts >= ?1 AND day_ts <= ?2
+            ORDER BY day_ts, focus_ms DESC, app_name
+            "#,
+        )
+        .bind(floor(start.timestamp_millis(), DAY_MS))
+        .bind(end.timestamp_millis())
+        .fetch_all(self.read_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AppFocusTime {
+                day: from_millis(row.get("day_ts")),
+                app_name: row.get("app_name"),
+                focus_ms: row.get::<i64, _>("focus_ms") as u64,
+                sessions: row.get::<i64, _>("sessions") as u64,
+            })
+            .collect())
+    }
+
+    /// Delete hourly aggregates older than `hourly_days` and daily ones older than `daily_days`
+    pub async fn cleanup_old_aggregates(&self, hourly_days: u32, daily_days: u32) -> Result<u64> {
+        let cutoff = |days: u32| (Utc::now() - chrono::Duration::days(i64::from(days))).timestamp_millis();
+        let mut tx = self.pool().begin().await?;
+
+        let mut deleted = sqlx::query("DELETE FROM mv_hourly_event_counts WHERE hour_ts < ?1")
+            .bind(cutoff(hourly_days))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        for table in ["mv_daily_state_distribution", "mv_daily_app_focus"] {
+            deleted += sqlx::query(&format!("DELETE FROM {table} WHERE day_ts < ?1"))
+                .bind(floor(cutoff(daily_days), DAY_MS))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+}
+
+/// Credit the state in `mv_current_state`, if any, with the time up to `until`
+async fn close_current_state(conn: &mut SqliteConnection, until: i64) -> Result<()> {
+    let current = sqlx::query("SELECT state, since FROM mv_current_state WHERE id = 1")
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(row) = current {
+        let state: String = row.get("state");
+        add_state_time(conn, &state, row.get("since"), until, 0).await?;
+    }
+    Ok(())
+}
+
+async fn add_state_time(conn: &mut SqliteConnection, state: &str, start: i64, end: i64, entries: i64) -> Result<()> {
+    for (day_ts, ms, entries) in day_spans(start, end, entries) {
+        sqlx::query(
+            r#"
+            INSERT INTO mv_daily_state_distribution (day_ts, state, duration_ms, entries) VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (day_ts, state) DO UPDATE SET
+                duration_ms = duration_ms + excluded.duration_ms,
+                entries = entries + excluded.entries
+            "#,
+        )
+        .bind(day_ts)
+        .bind(state)
+        .bind(ms)
+        .bind(entries)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn add_app_focus(conn: &mut SqliteConnection, app_name: &str, start: i64, end: i64, sessions: i64) -> Result<()> {
+    for (day_ts, ms, sessions) in day_spans(start, end, sessions) {
+        sqlx::query(
+            r#"
+            INSERT INTO mv_daily_app_focus (day_ts, app_name, focus_ms, sessions) VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (day_ts, app_name) DO UPDATE SET
+                focus_ms = focus_ms + excluded.focus_ms,
+                sessions = sessions + excluded.sessions
+            "#,
+        )
+        .bind(day_ts)
+        .bind(app_name)
+        .bind(ms)
+        .bind(sessions)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Split `[start, end)` into `(day_ts, ms, count)` per UTC day; `count` goes to the first day
+fn day_spans(start: i64, end: i64, count: i64) -> Vec<(i64, i64, i64)> {
+    let end = end.max(start);
+    let mut spans = Vec::new();
+    let mut day = floor(start, DAY_MS);
+    let mut from = start;
+    loop {
+        let until = end.min(day + DAY_MS);
+        spans.push((day, until - from, if spans.is_empty() { count } else { 0 }));
+        if until >= end {
+            return spans;
+        }
+        day += DAY_MS;
+        from = until;
+    }
+}
+
+fn floor(timestamp_ms: i64, bucket_ms: i64) -> i64 {
+    timestamp_ms - timestamp_ms.rem_euclid(bucket_ms)
+}
+
+fn from_millis(timestamp_ms: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::DatabaseConfig, types::*};
+    use chrono::{Duration, TimeZone};
+    use tempfile::TempDir;
+
+    async fn create_test_db() -> (TimeSeriesDatabase, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = DatabaseConfig::default();
+        config.path = temp_dir.path().join("test.db");
+        config.pool_size = 1;
+        (TimeSeriesDatabase::new(config).await.unwrap(), temp_dir)
+    }
+
+    fn focus(timestamp: DateTime<Utc>, app_name: &str) -> RawEvent {
+        RawEvent::WindowFocus(WindowFocusEvent {
+            timestamp,
+            window_title: String::new(),
+            app_name: app_name.to_string(),
+            process_id: 1,
+            duration_ms: None,
+        })
+    }
+
+    fn state(timestamp: DateTime<Utc>, state: &str) -> StateClassification {
+        StateClassification { state: state.to_string(), confidence: 0.9, timestamp }
+    }
+
+    #[tokio::test]
+    async fn test_aggregates_follow_ingest() {
+        let (db, _temp_dir) = create_test_db().await;
+        let session_id = Uuid::new_v4();
+        let nine = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+
+        let keystroke = |timestamp| {
+            RawEvent::Keystroke(KeystrokeEvent {
+                timestamp,
+                key_code: 65,
+                modifiers: KeyModifiers::default(),
+                inter_key_interval_ms: None,
+            })
+        };
+        db.store_event(&session_id, &focus(nine, "Editor")).await.unwrap();
+        db.store_events_batch(
+            &session_id,
+            &[
+                keystroke(nine + Duration::minutes(5)),
+                keystroke(nine + Duration::minutes(50)),
+                focus(nine + Duration::minutes(40), "Browser"),
+                keystroke(nine + Duration::minutes(70)),
+            ],
+        )
+        .await
+        .unwrap();
+        db.store_event(&session_id, &focus(nine + Duration::minutes(55), "Editor")).await.unwrap();
+
+        let counts = db.hourly_event_counts(nine, nine + Duration::hours(2)).await.unwrap();
+        let count = |hour: i64, event_type: &str| {
+            counts
+                .iter()
+                .find(|c| c.hour == nine + Duration::hours(hour) && c.event_type == event_type)
+                .map_or(0, |c| c.count)
+        };
+        assert_eq!((count(0, "keystroke"), count(0, "window_focus"), count(1, "keystroke")), (2, 3, 1));
+
+        let apps = db.app_focus_time(nine, nine).await.unwrap();
+        let summary: Vec<_> = apps.iter().map(|a| (a.app_name.as_str(), a.focus_ms, a.sessions)).collect();
+        assert_eq!(summary, vec![("Editor", 40 * 60_000, 2), ("Browser", 15 * 60_000, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_state_distribution_splits_days() {
+        let (db, _temp_dir) = create_test_db().await;
+        let evening = Utc.with_ymd_and_hms(2024, 3, 4, 22, 0, 0).unwrap();
+
+        db.record_state(&state(evening, "flow")).await.unwrap();
+        db.record_state(&state(evening + Duration::hours(1), "distracted")).await.unwrap();
+        db.record_state(&state(evening + Duration::hours(3), "flow")).await.unwrap();
+        db.end_state(evening + Duration::hours(4)).await.unwrap();
+
+        let shares = db.daily_state_distribution(evening, evening + Duration::hours(4)).await.unwrap();
+        let summary: Vec<_> = shares
+            .iter()
+            .map(|s| (s.day.format("%d").to_string(), s.state.as_str(), s.duration_ms / 60_000, s.entries))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("04".to_string(), "distracted", 60, 1),
+                ("04".to_string(), "flow", 60, 1),
+                ("05".to_string(), "distracted", 60, 0),
+                ("05".to_string(), "flow", 60, 1),
+            ]
+        );
+        assert!(shares.iter().all(|s| (s.share - 0.5).abs() < 1e-9));
+
+        // A restart doesn't credit the downtime to the last state
+        db.record_state(&state(evening + Duration::hours(10), "flow")).await.unwrap();
+        let day_two = db.daily_state_distribution(evening + Duration::hours(10), evening + Duration::hours(10)).await.unwrap();
+        assert_eq!(day_two.iter().find(|s| s.state == "flow").unwrap().duration_ms, 60 * 60_000);
+    }
+}
@@ -1,344 +1,1095 @@
 //! Skelly-Jelly: Your ADHD companion with a melty skeleton friend
-//! 
-//! This is the main entry point that orchestrates all modules for real-time
-//! ADHD state detection and supportive interventions.
-
-use anyhow::{Context, Result};
-use std::{sync::Arc, time::Duration};
-use tokio::{signal, time::sleep};
-use tracing::{info, warn, error, debug};
+//!
+//! This is the main entry point. `run` brings the system up through the
+//! orchestrator, in the foreground or as a daemon; `setup`, `status`,
+//! `doctor`, `export`, and `service` are one-shot commands.
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{net::SocketAddr, path::{Path, PathBuf}, process::Stdio, sync::{Arc, Mutex}, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    process::{Child, ChildStdin, Command},
+    signal,
+    sync::{mpsc, oneshot},
+};
+use tracing::{debug, info, warn, error};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use serde::Deserialize;
-
-// Import all modules
-use skelly_jelly_event_bus::{EventBus, Event};
-use orchestrator::{Orchestrator, ModuleConfig};
-use skelly_jelly_data_capture::{DataCapture, BehaviorEvent};
-use skelly_jelly_storage::{Storage, StorageConfig};
-use skelly_jelly_analysis_engine::{AnalysisEngine, AdhdState};
-use ai_integration::{AiIntegration, InterventionRequest};
-
-#[derive(Debug, Deserialize)]
-struct Config {
-    event_bus: EventBusConfig,
-    orchestrator: OrchestratorConfig,
-    storage: StorageConfig,
-    data_capture: DataCaptureConfig,
-    analysis_engine: AnalysisConfig,
-    ai_integration: AiConfig,
+
+use skelly_jelly_event_bus::{
+    bridge_to_storage, create_error_logger, create_event_bus, create_event_bus_with_config, create_retry_executor,
+    encode_captured_event, publish_sequenced,
+    recovery::{DefaultRecoveryExecutor, RecoveryConfig},
+    BusMessage, CircuitBreakerRegistry, DeliveryMode, EventBusConfig, EventBusImpl, EventBusTrait, MessagePayload,
+    ModuleId, RecoverySystem,
+};
+use skelly_jelly_orchestrator::{
+    daemon, AdminApiConfig, AdminApiServer, CheckCategory, CheckResult, CheckStatus, ConfigProbe, HealthSummary,
+    InstanceLock, KeychainStore, ModelFile, ModelFileProbe, ModuleStateView, OrchestratorConfig, OrchestratorImpl,
+    OrchestratorTrait, PortProbe, PrivacyChoice, ReadinessProbe, ReadinessReport, RotatingFileWriter,
+    SecretStore, SecretsConfig, ServiceSpec, SetupAnswer, SetupStep, SetupWizard, secrets::KEYCHAIN_SERVICE,
+};
+use skelly_jelly_data_capture::{platform::permissions, DataCaptureConfig, DataCaptureModule};
+use skelly_jelly_storage::{
+    privacy_api::{DateRange, ExportFormat, ExportOptions, PrivacyApiService},
+    AuditConfig, BusMessage as StorageMessage, PrivacyAuditLogger, RawEvent as CapturedEvent, StorageConfig,
+    StorageModule,
+};
+use skelly_jelly_analysis_engine::{
+    create_analysis_engine, AnalysisEngineConfig, CaptureFeed, CheckpointStore, StorageCheckpointStore,
+};
+use skelly_jelly_ai_integration::{AIIntegrationConfig, AIIntegrationImpl};
+use skelly_jelly_figurine_protocol::{AnimationTranslator, FigurineConsumer};
+
+#[derive(Parser)]
+#[command(name = "skelly-jelly")]
+#[command(version)]
+#[command(about = "Your ADHD companion with a melty skeleton friend", long_about = None)]
+struct Cli {
+    /// Configuration file path
+    #[arg(short, long, default_value = "config/default.toml")]
+    config: PathBuf,
+
+    /// Data directory
+    #[arg(long, default_value = "./data")]
+    data_dir: PathBuf,
+
+    /// Enable debug logging
+    #[arg(short, long)]
+    debug: bool,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Start all modules and run until Ctrl+C
+    Run(RunArgs),
+    /// Walk through first-run setup; resumes where an interrupted setup stopped
+    Setup {
+        /// Discard saved progress and start over
+        #[arg(long)]
+        reset: bool,
+    },
+    /// Show health and module states of a running instance
+    Status {
+        /// Admin API address of the running instance
+        #[arg(long, default_value = "127.0.0.1:7717")]
+        address: SocketAddr,
+    },
+    /// Check permissions, configuration, and dependencies
+    Doctor,
+    /// Export your data to a file
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormatArg::Json)]
+        format: ExportFormatArg,
+        #[arg(long, value_enum, default_value_t = DateRangeArg::All)]
+        range: DateRangeArg,
+        /// Leave screenshots out of the export
+        #[arg(long)]
+        no_screenshots: bool,
+        /// Strip identifying details
+        #[arg(long)]
+        anonymize: bool,
+        /// Copy the export here instead of leaving it under <data-dir>/exports
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Generate a launchd agent (macOS) or systemd user unit (Linux)
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-struct EventBusConfig {
-    max_queue_size: usize,
-    message_timeout_ms: u64,
+#[derive(Args, Default)]
+struct RunArgs {
+    /// Don't serve the localhost admin API (`status` needs it)
+    #[arg(long)]
+    no_admin_api: bool,
+    /// Capture, store, and analyze only: no AI interventions or UI bridges
+    #[arg(long)]
+    headless: bool,
+    /// Detach from the terminal and keep running in the background
+    #[arg(long)]
+    daemon: bool,
+    /// Record our pid here once the instance lock is held [default with --daemon: <data-dir>/skelly-jelly.pid]
+    #[arg(long)]
+    pidfile: Option<PathBuf>,
+    /// Log to this file, rotated every 10 MB [default with --daemon: <data-dir>/logs/skelly-jelly.log]
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+    /// Ask an instance already running on this data directory to shut down, then start
+    #[arg(long)]
+    takeover: bool,
 }
 
-#[derive(Debug, Deserialize)]
-struct OrchestratorConfig {
-    health_check_interval_ms: u64,
-    startup_timeout_ms: u64,
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Print the service definition
+    Print {
+        #[arg(long)]
+        headless: bool,
+    },
+    /// Write the service definition where the service manager picks it up
+    Install {
+        #[arg(long)]
+        headless: bool,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-struct DataCaptureConfig {
-    sample_rate_hz: f64,
-    window_size_seconds: u32,
+const LOG_ROTATE_BYTES: u64 = 10 * 1024 * 1024;
+const TAKEOVER_TIMEOUT: Duration = Duration::from_secs(45);
+const LOG_FILES_KEPT: usize = 5;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormatArg {
+    Json,
+    Csv,
+    Xml,
 }
 
-#[derive(Debug, Deserialize)]
-struct AnalysisConfig {
-    model_path: String,
-    confidence_threshold: f64,
+#[derive(Clone, Copy, ValueEnum)]
+enum DateRangeArg {
+    Today,
+    Week,
+    Month,
+    All,
+}
+
+/// Per-module settings from the config file; sections that don't match a
+/// module's config fall back to its defaults
+struct SystemConfig {
+    orchestrator: OrchestratorConfig,
+    admin_api: AdminApiConfig,
+    #[cfg(feature = "prometheus")]
+    metrics: skelly_jelly_orchestrator::PrometheusConfig,
+    storage: StorageConfig,
+    data_capture: DataCaptureConfig,
+    analysis_engine: AnalysisEngineConfig,
+    ai_integration: AIIntegrationConfig,
+    gamification: GamificationConfig,
 }
 
-#[derive(Debug, Deserialize)]
-struct AiConfig {
-    privacy_level: String,
-    use_local_model: bool,
+/// How to launch the gamification module, which runs as its own Node process
+#[derive(Deserialize)]
+#[serde(default)]
+struct GamificationConfig {
+    node: PathBuf,
+    module_dir: PathBuf,
+    /// How long to wait for its "ready" line
+    startup_timeout_secs: u64,
 }
 
-impl Default for Config {
+impl Default for GamificationConfig {
     fn default() -> Self {
         Self {
-            event_bus: EventBusConfig {
-                max_queue_size: 10000,
-                message_timeout_ms: 5000,
-            },
-            orchestrator: OrchestratorConfig {
-                health_check_interval_ms: 30000,
-                startup_timeout_ms: 60000,
-            },
-            storage: StorageConfig {
-                database_path: "./data/skelly.db".to_string(),
-                max_batch_size: 1000,
-                batch_timeout_ms: 30000,
-                retention_days: 30,
-                enable_compression: true,
-            },
-            data_capture: DataCaptureConfig {
-                sample_rate_hz: 10.0,
-                window_size_seconds: 30,
-            },
-            analysis_engine: AnalysisConfig {
-                model_path: "./models/adhd_classifier.onnx".to_string(),
-                confidence_threshold: 0.7,
-            },
-            ai_integration: AiConfig {
-                privacy_level: "LocalOnly".to_string(),
-                use_local_model: true,
-            },
+            node: PathBuf::from("node"),
+            module_dir: PathBuf::from("modules/gamification"),
+            startup_timeout_secs: 20,
         }
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,skelly_jelly=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let cli = Cli::parse();
+
+    let mut log_file = None;
+    if let Some(Commands::Run(args)) = &cli.command {
+        if args.daemon && !daemon::is_detached() {
+            let pid = daemon::detach(std::env::args_os().skip(1))?;
+            println!("🦴 Skelly-Jelly is running in the background (pid {})", pid);
+            return Ok(());
+        }
+        log_file = args
+            .log_file
+            .clone()
+            .or_else(|| args.daemon.then(|| cli.data_dir.join("logs").join("skelly-jelly.log")));
+    }
+    init_logging(cli.debug, log_file.as_deref())?;
+
+    match cli.command {
+        Some(Commands::Run(ref args)) => run(&cli.config, &cli.data_dir, args).await,
+        Some(Commands::Setup { reset }) => setup(&cli.config, &cli.data_dir, reset).await,
+        Some(Commands::Status { address }) => status(address).await,
+        Some(Commands::Doctor) => doctor(&cli.config, &cli.data_dir).await,
+        Some(Commands::Export { format, range, no_screenshots, anonymize, ref output }) => {
+            let options = ExportOptions {
+                format: match format {
+                    ExportFormatArg::Json => ExportFormat::Json,
+                    ExportFormatArg::Csv => ExportFormat::Csv,
+                    ExportFormatArg::Xml => ExportFormat::Xml,
+                },
+                date_range: match range {
+                    DateRangeArg::Today => DateRange::Today,
+                    DateRangeArg::Week => DateRange::Week,
+                    DateRangeArg::Month => DateRange::Month,
+                    DateRangeArg::All => DateRange::All,
+                },
+                include_screenshots: !no_screenshots,
+                include_behavioral_data: true,
+                include_audit_log: true,
+                anonymize,
+            };
+            export(&cli.data_dir, options, output.as_deref()).await
+        }
+        Some(Commands::Service { ref action }) => service(&cli.config, &cli.data_dir, action),
+        None => run(&cli.config, &cli.data_dir, &RunArgs::default()).await,
+    }
+}
 
+async fn run(config_path: &Path, data_dir: &Path, args: &RunArgs) -> Result<()> {
     info!("🦴 Skelly-Jelly Starting!");
     info!("Your melty skeleton companion is awakening...");
-    
-    // Load configuration
-    let config = load_config().await?;
-    
-    // Initialize the system
-    let system = SkellyJellySystem::new(config).await?;
-    
-    // Start all modules
-    system.start().await?;
-    
-    // Wait for shutdown signal
+
+    // Held until exit: a second instance on the same data would corrupt capture and storage
+    let lock_path = data_dir.join("skelly-jelly.lock");
+    let mut instance_lock = if args.takeover {
+        InstanceLock::takeover(lock_path, TAKEOVER_TIMEOUT).await?
+    } else {
+        InstanceLock::acquire(lock_path)?
+    };
+    // Only written once the lock is ours, so a takeover replaces the old daemon's pidfile
+    let pidfile = args
+        .pidfile
+        .clone()
+        .or_else(|| args.daemon.then(|| data_dir.join("skelly-jelly.pid")));
+    if let Some(pidfile) = pidfile {
+        instance_lock.write_pidfile(pidfile)?;
+    }
+
+    if !SetupWizard::is_complete_in(data_dir) {
+        info!("First-run setup hasn't been finished; run `skelly-jelly setup` to choose privacy and storage settings");
+    }
+
+    let mut config = load_config(config_path)?;
+    config.orchestrator.headless |= args.headless;
+    let headless = config.orchestrator.headless;
+
+    // Dead letters survive a restart in the data dir rather than wherever we were launched from
+    let event_bus = create_event_bus_with_config(EventBusConfig {
+        dead_letter_path: Some(data_dir.join("dead_letter_queue.json")),
+        ..Default::default()
+    })
+    .context("Failed to create event bus")?;
+    event_bus.start().await.context("Failed to start event bus")?;
+    let bus: Arc<dyn EventBusTrait> = event_bus.clone();
+    info!("✅ Event Bus ready");
+
+    // Created before the other modules so their secrets are ready to collect
+    let orchestrator = Arc::new(
+        OrchestratorImpl::new(config.orchestrator.clone(), Arc::clone(&bus))
+            .await
+            .context("Failed to create orchestrator")?,
+    );
+
+    // Readiness signals, error reports, acks and config commands reach the orchestrator here
+    let (_, orchestrator_messages) = event_bus
+        .subscribe_stream(
+            ModuleId::Orchestrator,
+            orchestrator.subscription_filter(),
+            DeliveryMode::Reliable { timeout: Duration::from_secs(5) },
+        )
+        .context("Failed to subscribe the orchestrator")?;
+    let consumer = Arc::clone(&orchestrator);
+    tokio::spawn(async move { consumer.consume(orchestrator_messages).await });
+
+    let admin = if !args.no_admin_api {
+        let server = AdminApiServer::new(
+            AdminApiConfig { enabled: true, ..config.admin_api.clone() },
+            orchestrator.clone(),
+        );
+        let address = server.start().await.context("Failed to start admin API")?;
+        // The token only goes to the user-readable instance file, never the logs
+        instance_lock.advertise_admin_api(address, server.token())?;
+        info!("🔧 Admin API on http://{}", address);
+        Some(server)
+    } else {
+        None
+    };
+
+    #[cfg(feature = "prometheus")]
+    let metrics_server = if config.metrics.enabled {
+        use skelly_jelly_event_bus::MessageFilter;
+        use skelly_jelly_orchestrator::{MetricsExporter, PrometheusServer, EXPORTED_MESSAGE_TYPES};
+
+        let exporter = Arc::new(MetricsExporter::new(Arc::clone(&bus)));
+        let (_, messages) = event_bus
+            .subscribe_stream(
+                ModuleId::Orchestrator,
+                MessageFilter::types(EXPORTED_MESSAGE_TYPES.to_vec()),
+                DeliveryMode::BestEffort,
+            )
+            .context("Failed to subscribe the metrics exporter")?;
+        let feed = Arc::clone(&exporter);
+        tokio::spawn(async move { feed.consume(messages).await });
+
+        let server = PrometheusServer::new(config.metrics.clone(), exporter);
+        server.start().await.context("Failed to start metrics endpoint")?;
+        Some(server)
+    } else {
+        None
+    };
+
+    // Storage opens the orchestrator's profile and follows later switches over the bridge
+    config.storage.profile.name = config.orchestrator.user_profile.clone();
+    let mut storage = StorageModule::new(config.storage.clone())
+        .await
+        .context("Failed to initialize storage")?;
+    let storage_bridge = bridge_to_storage(&event_bus, &mut storage).context("Failed to subscribe storage")?;
+    let storage_inbox = storage.sender();
+    let checkpoints: Arc<dyn CheckpointStore> = Arc::new(StorageCheckpointStore::new(Arc::clone(storage.database())));
+    let storage_task = tokio::spawn(async move {
+        if let Err(e) = storage.run().await {
+            error!("Storage stopped: {}", e);
+        }
+    });
+    announce_ready(bus.as_ref(), ModuleId::Storage).await?;
+    info!("✅ Storage ready");
+
+    let data_capture = match DataCaptureModule::new(
+        config.data_capture.clone(),
+        Arc::new(skelly_jelly_data_capture::EventBus),
+    ).await {
+        Ok(mut module) => match module.start().await {
+            Ok(()) => {
+                announce_ready(bus.as_ref(), ModuleId::DataCapture).await?;
+                info!("✅ Data Capture monitoring");
+                Some(module)
+            }
+            Err(e) => {
+                warn!("Data Capture not started, run `skelly-jelly doctor` for details: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("Data Capture not started, run `skelly-jelly doctor` for details: {}", e);
+            None
+        }
+    };
+
+    let recovery = create_recovery_system(&event_bus)?;
+    let analysis_engine = create_analysis_engine(
+        config.analysis_engine.clone(),
+        Arc::clone(&bus),
+        Some(checkpoints),
+        Some(Arc::clone(&recovery)),
+    )
+    .await
+    .context("Failed to create analysis engine")?;
+    // Rebuild the window that was open at the last shutdown before new events arrive
+    match analysis_engine.resume_from_checkpoint().await {
+        Ok(0) => {}
+        Ok(replayed) => info!("Analysis resumed from its checkpoint, {} events replayed", replayed),
+        Err(e) => warn!("Analysis starts without its checkpoint: {}", e),
+    }
+    let (_, captured_events) = event_bus
+        .subscribe_stream(
+            ModuleId::AnalysisEngine,
+            CaptureFeed::subscription_filter(),
+            DeliveryMode::Reliable { timeout: Duration::from_secs(5) },
+        )
+        .context("Failed to subscribe the analysis engine")?;
+    let feed = CaptureFeed::new(Arc::clone(&analysis_engine), Arc::clone(&bus)).with_recovery_system(recovery);
+    tokio::spawn(async move { feed.consume(captured_events).await });
+    announce_ready(bus.as_ref(), ModuleId::AnalysisEngine).await?;
+    info!("✅ Analysis Engine ready");
+
+    // Captured events go to analysis sequence-numbered; storage keeps a copy until they're acknowledged
+    let (stop_capture, mut capture_stopping) = oneshot::channel::<()>();
+    let capture_task = data_capture.map(|mut module| {
+        let (orchestrator, bus, storage_inbox) = (Arc::clone(&orchestrator), Arc::clone(&bus), storage_inbox.clone());
+        tokio::spawn(async move {
+            loop {
+                let captured = tokio::select! {
+                    captured = module.next_event() => captured,
+                    _ = &mut capture_stopping => break,
+                };
+                let Some(captured) = captured else { break };
+                if let Err(e) = publish_captured(&orchestrator, bus.as_ref(), &storage_inbox, &captured.event).await {
+                    warn!("Captured event not published: {}", e);
+                }
+            }
+            module
+        })
+    });
+
+    // Gamification runs as its own Node process; headless mode leaves it out
+    let gamification = if headless {
+        None
+    } else {
+        match GamificationProcess::start(&config.gamification).await {
+            Ok(process) => {
+                // Its own process, so its limits are enforced by the OS rather than in-process
+                if let Some(pid) = process.pid() {
+                    if let Err(e) = orchestrator.attach_module_process(ModuleId::Gamification, pid).await {
+                        warn!("Gamification runs without resource enforcement: {}", e);
+                    }
+                }
+                announce_ready(bus.as_ref(), ModuleId::Gamification).await?;
+                info!("✅ Gamification ready");
+                Some(process)
+            }
+            Err(e) => {
+                warn!("Gamification not started, run `skelly-jelly doctor` for details: {:#}", e);
+                None
+            }
+        }
+    };
+
+    let _ai_integration = if headless {
+        info!("🕶️ Headless: AI interventions and UI bridges stay off");
+        None
+    } else {
+        if let Some(mut receiver) = orchestrator.take_secrets(ModuleId::AiIntegration) {
+            for (name, value) in receiver.drain()? {
+                match name.as_str() {
+                    "openai_key" => config.ai_integration.api_config.openai_key = Some(value.expose().to_string()),
+                    "anthropic_key" => config.ai_integration.api_config.anthropic_key = Some(value.expose().to_string()),
+                    _ => {}
+                }
+            }
+        }
+        let mut ai_integration = AIIntegrationImpl::new(config.ai_integration.clone());
+        ai_integration.initialize().await.context("Failed to initialize AI integration")?;
+        announce_ready(bus.as_ref(), ModuleId::AiIntegration).await?;
+        info!("✅ AI Integration ready");
+        Some(ai_integration)
+    };
+
+    // Turns state changes, interventions and rewards into animation commands for the figurine
+    let figurine = if headless {
+        None
+    } else {
+        let consumer = Arc::new(FigurineConsumer::new(Arc::clone(&bus), AnimationTranslator::default()));
+        consumer.start(&event_bus).await.context("Failed to start figurine consumer")?;
+        announce_ready(bus.as_ref(), ModuleId::CuteFigurine).await?;
+        info!("✅ Figurine animations ready");
+        Some(consumer)
+    };
+
+    // Startup waits on the announcements above; a module that never sent one is quarantined
+    orchestrator.start_system().await.context("Failed to start system")?;
     info!("✨ System ready! Press Ctrl+C to stop.");
-    signal::ctrl_c().await?;
-    
-    // Graceful shutdown
+
+    wait_for_shutdown(&orchestrator).await;
+
     info!("🛑 Shutting down gracefully...");
-    system.shutdown().await?;
-    
+    let shutdown_timeout = config.orchestrator.startup_timeout.min(Duration::from_secs(30));
+    if let Err(e) = orchestrator.stop_system(shutdown_timeout).await {
+        warn!("Orchestrator did not stop cleanly: {}", e);
+    }
+    let _ = stop_capture.send(());
+    if let Some(task) = capture_task {
+        match task.await {
+            Ok(mut module) => {
+                if let Err(e) = module.stop().await {
+                    warn!("Data Capture did not stop cleanly: {}", e);
+                }
+            }
+            Err(e) => warn!("Data Capture task failed: {}", e),
+        }
+    }
+    if let Some(process) = gamification {
+        process.stop(shutdown_timeout).await;
+    }
+    if let Some(consumer) = figurine {
+        if let Err(e) = consumer.stop().await {
+            warn!("Figurine consumer did not stop cleanly: {}", e);
+        }
+    }
+    storage_task.abort();
+    storage_bridge.abort();
+    if let Some(server) = admin {
+        server.stop().await;
+    }
+    #[cfg(feature = "prometheus")]
+    if let Some(server) = metrics_server {
+        server.stop().await;
+    }
+    let drain = event_bus.shutdown().await?;
+    if drain.dead_lettered > 0 {
+        warn!("{} bus messages were undelivered at shutdown and kept as dead letters", drain.dead_lettered);
+    }
+
     info!("👋 Your skeleton friend will miss you!");
     Ok(())
 }
 
-struct SkellyJellySystem {
-    event_bus: Arc<EventBus>,
-    orchestrator: Arc<Orchestrator>,
-    data_capture: Arc<DataCapture>,
-    storage: Arc<Storage>,
-    analysis_engine: Arc<AnalysisEngine>,
-    ai_integration: Arc<AiIntegration>,
+async fn status(address: SocketAddr) -> Result<()> {
+    let health: HealthSummary = admin_get(address, "/health").await?;
+    let modules: Vec<ModuleStateView> = admin_get(address, "/modules").await?;
+
+    println!("🦴 Skelly-Jelly status");
+    println!("  System: {:?}", health.status);
+    println!("  Uptime: {}s", health.uptime_secs);
+    println!("  Profile: {:?}", health.profile);
+    println!("  Active issues: {}", health.active_issues);
+    println!();
+    for module in modules {
+        let emoji = match module.state.as_str() {
+            "running" => "✅",
+            "failed" => "❌",
+            "starting" | "stopping" => "⏳",
+            _ => "⚪",
+        };
+        let unhealthy = if health.unhealthy_modules.contains(&module.module) { " (unhealthy)" } else { "" };
+        print!("{} {}: {}{}", emoji, module.module, module.state, unhealthy);
+        if let Some(detail) = module.detail {
+            print!(" - {}", detail);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// GET a JSON document from a running instance's admin API
+async fn admin_get<T: DeserializeOwned>(address: SocketAddr, path: &str) -> Result<T> {
+    let mut stream = TcpStream::connect(address)
+        .await
+        .with_context(|| format!("No Skelly-Jelly admin API at {} (is `skelly-jelly run` up?)", address))?;
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    let (head, body) = response.split_once("\r\n\r\n").context("Malformed admin API response")?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        bail!("GET {} returned {}: {}", path, status, body);
+    }
+    serde_json::from_str(body).with_context(|| format!("Unexpected response from GET {}", path))
 }
 
-impl SkellyJellySystem {
-    async fn new(config: Config) -> Result<Self> {
-        info!("🔧 Initializing Skelly-Jelly modules...");
-        
-        // Create event bus first - all modules need it
-        let event_bus = Arc::new(
-            EventBus::new(config.event_bus.max_queue_size)
-                .context("Failed to create event bus")?
-        );
-        
-        // Initialize storage
-        let storage = Arc::new(
-            Storage::new(config.storage).await
-                .context("Failed to initialize storage")?
-        );
-        
-        // Initialize data capture
-        let data_capture = Arc::new(
-            DataCapture::new(
-                config.data_capture.sample_rate_hz,
-                config.data_capture.window_size_seconds,
-                event_bus.clone()
-            ).await?
-        );
-        
-        // Initialize analysis engine
-        let analysis_engine = Arc::new(
-            AnalysisEngine::new(
-                &config.analysis_engine.model_path,
-                config.analysis_engine.confidence_threshold,
-                event_bus.clone()
-            ).await?
-        );
-        
-        // Initialize AI integration
-        let ai_integration = Arc::new(
-            AiIntegration::new(
-                config.ai_integration.use_local_model,
-                &config.ai_integration.privacy_level,
-                event_bus.clone()
-            ).await?
-        );
-        
-        // Initialize orchestrator last
-        let orchestrator = Arc::new(
-            Orchestrator::new(
-                Duration::from_millis(config.orchestrator.health_check_interval_ms),
-                event_bus.clone()
-            ).await?
-        );
-        
-        Ok(Self {
-            event_bus,
-            orchestrator,
-            data_capture,
-            storage,
-            analysis_engine,
-            ai_integration,
-        })
+async fn setup(config_path: &Path, data_dir: &Path, reset: bool) -> Result<()> {
+    let mut wizard = SetupWizard::open(config_path, data_dir)?;
+    if reset {
+        wizard.reset()?;
     }
-    
-    async fn start(&self) -> Result<()> {
-        info!("🚀 Starting all modules...");
-        
-        // Start modules in dependency order
-        self.event_bus.start().await?;
-        info!("✅ Event Bus ready");
-        
-        self.storage.start().await?;
-        info!("✅ Storage ready");
-        
-        self.data_capture.start().await?;
-        info!("✅ Data Capture monitoring");
-        
-        self.analysis_engine.start().await?;
-        info!("✅ Analysis Engine ready");
-        
-        self.ai_integration.start().await?;
-        info!("✅ AI Integration ready");
-        
-        // Start main event processing loop
-        let event_bus = self.event_bus.clone();
-        let storage = self.storage.clone();
-        let analysis_engine = self.analysis_engine.clone();
-        let ai_integration = self.ai_integration.clone();
-        
-        tokio::spawn(async move {
-            Self::process_events(event_bus, storage, analysis_engine, ai_integration).await
-        });
-        
-        // Start orchestrator last (it monitors everything)
-        self.orchestrator.start().await?;
-        info!("✅ Orchestrator monitoring");
-        
-        info!("🎉 All modules started successfully!");
-        Ok(())
+    println!("🦴 Skelly-Jelly setup");
+    if wizard.is_complete() {
+        println!("Setup is already finished; use --reset to go through it again.");
+        return Ok(());
     }
-    
-    async fn process_events(
-        event_bus: Arc<EventBus>,
-        storage: Arc<Storage>,
-        analysis_engine: Arc<AnalysisEngine>,
-        ai_integration: Arc<AiIntegration>,
-    ) {
-        info!("🔄 Event processing loop started");
-        
-        loop {
-            match event_bus.receive().await {
-                Ok(event) => {
-                    debug!("📨 Processing event: {:?}", event.event_type);
-                    
-                    match &event {
-                        Event::BehaviorCaptured(behavior) => {
-                            // Store behavior data
-                            if let Err(e) = storage.store_behavior(behavior).await {
-                                error!("Failed to store behavior: {}", e);
-                            }
-                            
-                            // Trigger analysis
-                            if let Err(e) = analysis_engine.analyze_behavior(behavior).await {
-                                error!("Failed to analyze behavior: {}", e);
-                            }
-                        },
-                        
-                        Event::StateDetected(state) => {
-                            info!("🧠 ADHD State: {:?} (confidence: {:.2})", 
-                                  state.state, state.confidence);
-                            
-                            // Store state
-                            if let Err(e) = storage.store_state(state).await {
-                                error!("Failed to store state: {}", e);
-                            }
-                            
-                            // Check if intervention needed
-                            if state.state == skelly_jelly_analysis_engine::AdhdState::Distracted && 
-                               state.confidence > 0.75 {
-                                let request = InterventionRequest {
-                                    state: state.clone(),
-                                    context: "User seems distracted".to_string(),
-                                };
-                                
-                                if let Err(e) = ai_integration.generate_intervention(&request).await {
-                                    error!("Failed to generate intervention: {}", e);
-                                }
-                            }
-                        },
-                        
-                        Event::InterventionGenerated(intervention) => {
-                            info!("💬 Intervention: {}", intervention.message);
-                            info!("💀 Skeleton: {}", intervention.animation);
-                            
-                            // Store intervention
-                            if let Err(e) = storage.store_intervention(intervention).await {
-                                error!("Failed to store intervention: {}", e);
+    if wizard.is_resumed() {
+        println!("Picking up at {}.", wizard.current_step());
+    }
+    let defaults = load_config(config_path)?;
+
+    loop {
+        let answer = match wizard.current_step() {
+            SetupStep::Permissions => {
+                println!("\n1/5 Permissions");
+                loop {
+                    match permissions::check_permissions().await {
+                        Ok(()) => {
+                            println!("✅ Capture permissions granted");
+                            break SetupAnswer::Permissions { granted: true };
+                        }
+                        Err(e) => {
+                            println!("❌ {}", e);
+                            println!("   macOS: System Settings → Privacy & Security → Accessibility and Screen Recording");
+                            if prompt("Grant access, then press Enter to check again (or type 'skip')", "")? == "skip" {
+                                break SetupAnswer::Permissions { granted: false };
                             }
-                            
-                            // TODO: Send to TypeScript modules (Gamification, Cute Figurine)
-                            // This will be implemented when we set up the TypeScript bridge
-                        },
-                        
-                        _ => {
-                            debug!("Unhandled event type");
                         }
                     }
-                },
-                Err(e) => {
-                    error!("Event processing error: {}", e);
-                    sleep(Duration::from_millis(100)).await;
                 }
             }
+            SetupStep::PrivacyLevel => {
+                println!("\n2/5 Privacy");
+                println!("  1) Local only: nothing leaves this device");
+                println!("  2) Sanitized API: remote AI with personal details removed");
+                println!("  3) Ask each time before using remote AI");
+                match prompt("Choose", "1")?.as_str() {
+                    "1" => SetupAnswer::PrivacyLevel(PrivacyChoice::LocalOnly),
+                    "2" => SetupAnswer::PrivacyLevel(PrivacyChoice::SanitizedAPI),
+                    "3" => SetupAnswer::PrivacyLevel(PrivacyChoice::ConsentBased),
+                    other => {
+                        println!("'{}' isn't one of the options", other);
+                        continue;
+                    }
+                }
+            }
+            SetupStep::SensitiveApps => {
+                println!("\n3/5 Sensitive apps (never screenshotted or read)");
+                let current = defaults.data_capture.privacy.sensitive_app_list.join(", ");
+                let apps = prompt("Comma-separated list", &current)?;
+                SetupAnswer::SensitiveApps(apps.split(',').map(str::to_string).collect())
+            }
+            SetupStep::StorageLocation => {
+                println!("\n4/5 Storage");
+                let location = prompt("Directory for your data", &data_dir.display().to_string())?;
+                SetupAnswer::StorageLocation(PathBuf::from(location))
+            }
+            SetupStep::ModelDownload => {
+                println!("\n5/5 Local AI model");
+                let download = prompt("Download the local language model on first start? [y/N]", "n")?;
+                SetupAnswer::ModelDownload { download: download.eq_ignore_ascii_case("y") }
+            }
+            SetupStep::Complete => break,
+        };
+        if let Err(e) = wizard.submit(answer) {
+            println!("❌ {}", e);
+        }
+    }
+
+    wizard.finish()?;
+    println!("\n✨ Saved to {}.", config_path.display());
+
+    // Only what needs attention; `doctor` shows every check
+    let report = dry_run(config_path, &load_config(config_path)?).await?;
+    for result in report.failures().chain(report.warnings()) {
+        print_check(result);
+    }
+    if report.is_ready() {
+        println!("Start with `skelly-jelly run`.");
+    } else {
+        println!("Fix the problems above, then start with `skelly-jelly run`.");
+    }
+    Ok(())
+}
+
+/// Ask on stdin; an empty answer takes `default`
+fn prompt(question: &str, default: &str) -> Result<String> {
+    use std::io::Write;
+
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} [{}]: ", question, default);
+    }
+    std::io::stdout().flush()?;
+
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        bail!("Setup interrupted; run `skelly-jelly setup` again to continue where you left off");
+    }
+    let answer = line.trim();
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+async fn doctor(config_path: &Path, data_dir: &Path) -> Result<()> {
+    println!("🩺 Skelly-Jelly doctor");
+    let mut problems = 0;
+    let mut check = |ok: bool, label: &str, detail: String| {
+        println!("{} {}: {}", if ok { "✅" } else { "❌" }, label, detail);
+        if !ok {
+            problems += 1;
+        }
+    };
+
+    let config = match load_config(config_path) {
+        Ok(config) => {
+            check(true, "Config", format!("{} loaded", config_path.display()));
+            config
+        }
+        Err(e) => {
+            // Nothing else can be checked without a config
+            check(false, "Config", format!("{:#}", e));
+            println!();
+            bail!("{} problem(s) found", problems)
+        }
+    };
+
+    let probe = data_dir.join(".doctor-probe");
+    let writable = std::fs::create_dir_all(data_dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    check(
+        writable.is_ok(),
+        "Data directory",
+        match writable {
+            Ok(()) => format!("{} is writable", data_dir.display()),
+            Err(e) => format!("{} is not writable: {}", data_dir.display(), e),
+        },
+    );
+
+    match dry_run(config_path, &config).await {
+        Ok(report) => {
+            // Warnings don't stop the system from running, so they are not counted
+            for result in &report.checks {
+                print_check(result);
+                if result.status == CheckStatus::Fail {
+                    problems += 1;
+                }
+            }
+        }
+        Err(e) => check(false, "Validation", format!("{:#}", e)),
+    }
+
+    let keychain = KeychainStore::new(KEYCHAIN_SERVICE);
+    let wanted = SecretsConfig::default().grants.remove(&ModuleId::AiIntegration).unwrap_or_default();
+    for key in wanted {
+        match keychain.get(&key) {
+            Ok(Some(_)) => println!("✅ Keychain: {} present", key),
+            Ok(None) => println!("⚠️  Keychain: {} not set (AI stays local-only)", key),
+            Err(e) => println!("⚠️  Keychain: {} unavailable: {}", keychain.name(), e),
         }
     }
-    
-    async fn shutdown(&self) -> Result<()> {
-        info!("🛑 Shutting down modules...");
-        
-        // Shutdown in reverse order
-        self.orchestrator.shutdown().await?;
-        self.ai_integration.shutdown().await?;
-        self.analysis_engine.shutdown().await?;
-        self.data_capture.shutdown().await?;
-        self.storage.shutdown().await?;
-        self.event_bus.shutdown().await?;
-        
-        info!("✅ Clean shutdown complete");
+
+    println!();
+    if problems == 0 {
+        println!("✨ Everything looks good!");
         Ok(())
+    } else {
+        bail!("{} problem(s) found", problems)
     }
 }
 
-async fn load_config() -> Result<Config> {
-    // Try to load from config file, fallback to defaults
-    let config_path = std::env::var("SKELLY_CONFIG")
-        .unwrap_or_else(|_| "./config/default.toml".to_string());
-    
-    match tokio::fs::read_to_string(&config_path).await {
-        Ok(content) => {
-            info!("📄 Loading config from {}", config_path);
-            toml::from_str(&content)
-                .with_context(|| format!("Failed to parse config file: {}", config_path))
-        },
-        Err(_) => {
-            warn!("⚠️  Config file not found, using defaults");
-            Ok(Config::default())
+/// Validate everything `run` would need, without starting any module
+async fn dry_run(config_path: &Path, config: &SystemConfig) -> Result<ReadinessReport> {
+    let event_bus = create_event_bus().context("Failed to create event bus")?;
+    let orchestrator = OrchestratorImpl::new(config.orchestrator.clone(), event_bus)
+        .await
+        .context("Failed to create orchestrator")?;
+
+    let mut sections = ConfigProbe::new();
+    for (module_id, section) in raw_sections(config_path)? {
+        sections = sections.with_module(module_id, section);
+    }
+    orchestrator.add_readiness_probe(Arc::new(sections)).await;
+    orchestrator.add_readiness_probe(Arc::new(CapturePermissionsProbe)).await;
+    let mut storage = config.storage.clone();
+    storage.profile.name = config.orchestrator.user_profile.clone();
+    orchestrator.add_readiness_probe(Arc::new(StorageProbe(storage))).await;
+
+    let local_model = &config.ai_integration.local_model;
+    let mut models = ModelFileProbe::new()
+        .with_file(ModelFile::new("analysis", &config.analysis_engine.model_path).optional());
+    for source in &local_model.downloads.models {
+        let path = local_model.downloads.models_dir.join(&source.id).join(&source.version).join(&source.file_name);
+        let file = ModelFile::new(&source.id, path).with_sha256(&source.sha256);
+        // Downloaded on first start if missing
+        models = models.with_file(if local_model.auto_download { file.optional() } else { file });
+    }
+    orchestrator.add_readiness_probe(Arc::new(models)).await;
+
+    if config.admin_api.enabled {
+        orchestrator.add_readiness_probe(Arc::new(PortProbe::new("admin API", config.admin_api.bind_address))).await;
+    }
+    #[cfg(feature = "prometheus")]
+    if config.metrics.enabled {
+        orchestrator.add_readiness_probe(Arc::new(PortProbe::new("metrics", config.metrics.bind_address))).await;
+    }
+
+    Ok(orchestrator.validate().await)
+}
+
+fn print_check(result: &CheckResult) {
+    let icon = match result.status {
+        CheckStatus::Pass => "✅",
+        CheckStatus::Warn => "⚠️ ",
+        CheckStatus::Fail => "❌",
+    };
+    println!("{} {}: {}", icon, result.name, result.detail);
+    if let Some(remedy) = result.remedy.as_deref().filter(|_| result.status != CheckStatus::Pass) {
+        println!("   {}", remedy);
+    }
+}
+
+/// Module sections as written in the config file, before defaults fill them in
+fn raw_sections(path: &Path) -> Result<Vec<(ModuleId, serde_json::Value)>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let root: toml::Value = toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let mut sections = Vec::new();
+    for (name, module_id) in [
+        ("event_bus", ModuleId::EventBus),
+        ("storage", ModuleId::Storage),
+        ("data_capture", ModuleId::DataCapture),
+        ("analysis_engine", ModuleId::AnalysisEngine),
+        ("ai_integration", ModuleId::AiIntegration),
+    ] {
+        if let Some(section) = root.get(name) {
+            sections.push((module_id, serde_json::to_value(section)?));
+        }
+    }
+    Ok(sections)
+}
+
+/// Capture needs OS permissions and, on Linux, a display to track windows on
+struct CapturePermissionsProbe;
+
+#[async_trait::async_trait]
+impl ReadinessProbe for CapturePermissionsProbe {
+    async fn check(&self) -> Vec<CheckResult> {
+        let mut results = vec![match permissions::check_permissions().await {
+            Ok(()) => CheckResult::pass(CheckCategory::Permissions, "Capture permissions", "granted"),
+            Err(e) => CheckResult::fail(CheckCategory::Permissions, "Capture permissions", e.to_string())
+                .with_remedy("macOS: System Settings → Privacy & Security → Accessibility and Screen Recording"),
+        }];
+        if cfg!(target_os = "linux") {
+            let has_display = std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some();
+            results.push(if has_display {
+                CheckResult::pass(CheckCategory::Permissions, "Display", "available for window tracking")
+            } else {
+                CheckResult::fail(CheckCategory::Permissions, "Display", "window tracking needs DISPLAY or WAYLAND_DISPLAY")
+            });
+        }
+        results
+    }
+}
+
+/// The storage database opens; it is closed again straight away
+struct StorageProbe(StorageConfig);
+
+#[async_trait::async_trait]
+impl ReadinessProbe for StorageProbe {
+    async fn check(&self) -> Vec<CheckResult> {
+        let result = match StorageModule::new(self.0.clone()).await {
+            Ok(mut storage) => {
+                let _ = storage.shutdown().await;
+                CheckResult::pass(CheckCategory::Database, "Storage", "database opens")
+            }
+            Err(e) => CheckResult::fail(CheckCategory::Database, "Storage", format!("database does not open: {}", e))
+                .with_remedy("Check that the database path is writable and not open in another instance"),
+        };
+        vec![result]
+    }
+}
+
+async fn export(data_dir: &Path, options: ExportOptions, output: Option<&Path>) -> Result<()> {
+    let audit_logger = Arc::new(PrivacyAuditLogger::new(AuditConfig::default()));
+    let mut privacy = PrivacyApiService::new(data_dir.to_path_buf(), audit_logger);
+    let result = privacy.export_data(options).await.context("Export failed")?;
+
+    let path = match output {
+        Some(output) => {
+            std::fs::copy(&result.file_path, output)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            std::fs::remove_file(&result.file_path)?;
+            output.to_path_buf()
+        }
+        None => result.file_path,
+    };
+    println!("📦 Exported {} items ({} bytes) to {}", result.items_exported, result.file_size, path.display());
+    Ok(())
+}
+
+fn service(config_path: &Path, data_dir: &Path, action: &ServiceAction) -> Result<()> {
+    let working_dir = std::env::current_dir()?;
+    let (ServiceAction::Print { headless } | ServiceAction::Install { headless }) = action;
+
+    // The service manager supervises the process, so it runs in the foreground
+    let mut args = vec![
+        "--config".to_string(),
+        working_dir.join(config_path).display().to_string(),
+        "--data-dir".to_string(),
+        working_dir.join(data_dir).display().to_string(),
+        "run".to_string(),
+    ];
+    if *headless {
+        args.push("--headless".to_string());
+    }
+    let mut spec = ServiceSpec::current_exe(args, &working_dir)?;
+    spec.log_path = working_dir.join(data_dir).join("logs").join("service.log");
+
+    match action {
+        ServiceAction::Print { .. } => print!("{}", spec.render()?),
+        ServiceAction::Install { .. } => {
+            let path = spec.install()?;
+            println!("📝 Wrote {}", path.display());
+            if cfg!(target_os = "macos") {
+                println!("Load it with: launchctl load -w {}", path.display());
+            } else {
+                println!("Enable it with: systemctl --user daemon-reload && systemctl --user enable --now skelly-jelly");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn load_config(path: &Path) -> Result<SystemConfig> {
+    let root = if path.exists() {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str::<toml::Value>(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?
+    } else {
+        info!("No config at {}, using defaults", path.display());
+        toml::Value::Table(Default::default())
+    };
+
+    Ok(SystemConfig {
+        orchestrator: section(&root, "orchestrator"),
+        admin_api: section(&root, "admin_api"),
+        #[cfg(feature = "prometheus")]
+        metrics: section(&root, "metrics"),
+        storage: section(&root, "storage"),
+        data_capture: section(&root, "data_capture"),
+        analysis_engine: section(&root, "analysis_engine"),
+        ai_integration: section(&root, "ai_integration"),
+        gamification: section(&root, "gamification"),
+    })
+}
+
+fn section<T: DeserializeOwned + Default>(root: &toml::Value, name: &str) -> T {
+    match root.get(name) {
+        Some(value) => value.clone().try_into().unwrap_or_else(|e| {
+            warn!("Using defaults for [{}]: {}", name, e);
+            T::default()
+        }),
+        None => T::default(),
+    }
+}
+
+fn init_logging(debug: bool, log_file: Option<&Path>) -> Result<()> {
+    let filter = if debug { "debug,hyper=info,reqwest=info" } else { "info,skelly_jelly=debug" };
+
+    let (stdout_layer, file_layer) = match log_file {
+        Some(path) => {
+            let writer = RotatingFileWriter::new(path, LOG_ROTATE_BYTES, LOG_FILES_KEPT)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(writer));
+            (None, Some(layer))
+        }
+        None => (Some(tracing_subscriber::fmt::layer()), None),
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| filter.into()),
+        )
+        .with(stdout_layer)
+        .with(file_layer)
+        .init();
+    Ok(())
+}
+
+/// Publish a captured event to the analysis engine under the next sequence number
+async fn publish_captured(
+    orchestrator: &OrchestratorImpl,
+    bus: &dyn EventBusTrait,
+    storage: &mpsc::Sender<StorageMessage>,
+    event: &CapturedEvent,
+) -> Result<()> {
+    let message = BusMessage::new(ModuleId::DataCapture, MessagePayload::RawEvent(encode_captured_event(event)?));
+    let message = orchestrator.stamp_sequence(message, ModuleId::AnalysisEngine).await;
+    publish_sequenced(bus, storage, message, ModuleId::AnalysisEngine).await?;
+    Ok(())
+}
+
+/// Recovery system modules report failures to; it acts on their recovery hints and dead-letters on the bus
+fn create_recovery_system(event_bus: &EventBusImpl) -> Result<Arc<RecoverySystem>> {
+    let circuit_breakers = Arc::new(CircuitBreakerRegistry::new());
+    let retry_executor = Arc::new(
+        create_retry_executor().map_err(|e| anyhow::anyhow!("Failed to create retry executor: {:?}", e))?,
+    );
+    let recovery = Arc::new(RecoverySystem::new(
+        RecoveryConfig::default(),
+        Arc::clone(&circuit_breakers),
+        Arc::clone(&retry_executor),
+        Arc::clone(event_bus.dead_letters()),
+        Arc::new(create_error_logger()),
+    ));
+    recovery.register_executor(Arc::new(DefaultRecoveryExecutor::new(circuit_breakers, retry_executor)));
+    Ok(recovery)
+}
+
+/// Tell the orchestrator a module is up; only the module's own identity opens its readiness gate
+async fn announce_ready(bus: &dyn EventBusTrait, module_id: ModuleId) -> Result<()> {
+    bus.publish(BusMessage::new(module_id, MessagePayload::ModuleReady(module_id)))
+        .await
+        .with_context(|| format!("Failed to announce {}", module_id))?;
+    Ok(())
+}
+
+/// Printed by the gamification IPC server once it takes requests
+const GAMIFICATION_READY_LINE: &str = "Gamification IPC Server ready";
+
+/// The gamification module's Node process; closing its stdin asks it to shut down
+struct GamificationProcess {
+    child: Child,
+    stdin: Option<ChildStdin>,
+}
+
+impl GamificationProcess {
+    /// Launch the IPC server and wait until it reports ready
+    async fn start(config: &GamificationConfig) -> Result<Self> {
+        let mut child = Command::new(&config.node)
+            .arg(Path::new("dist").join("ipc_server.js"))
+            .current_dir(&config.module_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to launch {} in {}", config.node.display(), config.module_dir.display()))?;
+        let stdin = child.stdin.take();
+        let mut lines = BufReader::new(child.stdout.take().context("Gamification stdout not captured")?).lines();
+
+        let startup_timeout = Duration::from_secs(config.startup_timeout_secs);
+        let ready = tokio::time::timeout(startup_timeout, async {
+            while let Some(line) = lines.next_line().await? {
+                debug!("gamification: {}", line);
+                if line.contains(GAMIFICATION_READY_LINE) {
+                    return Ok(true);
+                }
+            }
+            Ok::<_, std::io::Error>(false)
+        })
+        .await;
+        match ready {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => bail!("Gamification exited before it was ready"),
+            Ok(Err(e)) => return Err(e).context("Failed to read gamification output"),
+            Err(_) => bail!("Gamification not ready within {:?}", startup_timeout),
+        }
+
+        // Keep draining its output so the pipe never fills up
+        tokio::spawn(async move {
+            while let Ok(Some(line)) = lines.next_line().await {
+                debug!("gamification: {}", line);
+            }
+        });
+        Ok(Self { child, stdin })
+    }
+
+    fn pid(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Close its stdin and give it `timeout` to exit before killing it
+    async fn stop(mut self, timeout: Duration) {
+        drop(self.stdin.take());
+        if tokio::time::timeout(timeout, self.child.wait()).await.is_err() {
+            warn!("Gamification did not exit within {:?}, killing it", timeout);
+            let _ = self.child.kill().await;
         }
     }
-}
\ No newline at end of file
+}
+
+async fn wait_for_shutdown(orchestrator: &OrchestratorImpl) {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+        _ = orchestrator.shutdown_requested() => info!("Shutdown requested over the admin API"),
+    }
+}